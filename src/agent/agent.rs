@@ -11,6 +11,7 @@ use crate::runtime;
 use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool, ToolSpec};
 use anyhow::Result;
+use futures_util::StreamExt;
 use std::collections::HashMap;
 use std::io::Write as IoWrite;
 use std::sync::Arc;
@@ -433,11 +434,28 @@ impl Agent {
             return results;
         }
 
-        let futs: Vec<_> = calls
-            .iter()
-            .map(|call| self.execute_tool_call(call))
-            .collect();
-        futures_util::future::join_all(futs).await
+        let max_parallel = match self.config.max_parallel_tools {
+            0 => super::tool_batches::DEFAULT_MAX_PARALLEL_TOOLS,
+            n => n,
+        };
+        let batches = super::tool_batches::plan_batches(
+            calls
+                .iter()
+                .map(|call| (call.name.as_str(), &call.arguments)),
+        );
+        let mut results = Vec::with_capacity(calls.len());
+        for batch in batches {
+            let batch_results: Vec<ToolExecutionResult> = futures_util::stream::iter(
+                batch
+                    .into_iter()
+                    .map(|idx| self.execute_tool_call(&calls[idx])),
+            )
+            .buffered(max_parallel)
+            .collect()
+            .await;
+            results.extend(batch_results);
+        }
+        results
     }
 
     fn classify_model(&self, user_message: &str) -> String {
@@ -573,10 +591,12 @@ impl Agent {
         println!("Type /quit to exit.\n");
 
         let (tx, mut rx) = tokio::sync::mpsc::channel(32);
-        let cli = crate::channels::CliChannel::new();
+        let cli = crate::channels::CliChannel::new()
+            .with_history_file(self.workspace_dir.join("state").join("cli_history"));
 
+        let listener = cli.clone();
         let listen_handle = tokio::spawn(async move {
-            let _ = crate::channels::Channel::listen(&cli, tx).await;
+            let _ = crate::channels::Channel::listen(&listener, tx).await;
         });
 
         while let Some(msg) = rx.recv().await {
@@ -587,7 +607,10 @@ impl Agent {
                     continue;
                 }
             };
-            println!("\n{response}\n");
+            let reply = crate::channels::SendMessage::new(format!("\n{response}\n"), "user");
+            if let Err(e) = crate::channels::Channel::send(&cli, &reply).await {
+                eprintln!("\nError sending CLI response: {e}\n");
+            }
         }
 
         listen_handle.abort();
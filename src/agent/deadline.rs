@@ -0,0 +1,127 @@
+//! Per-request time budgets.
+//!
+//! A [`Deadline`] is created when a message is received and handed to every
+//! later stage (context assembly, provider calls, tool execution, delivery).
+//! Each stage derives its own timeout from the time left, keeping back a
+//! reserve for the stages after it, so a slow stage shortens the rest of the
+//! turn instead of pushing the reply past the budget. When the tool loop runs
+//! low it stops calling tools and asks the model for a best-effort answer
+//! from what it has so far.
+
+use std::time::{Duration, Instant};
+
+/// Share of the budget kept back for the final best-effort answer.
+const WRAP_UP_SHARE: u32 = 6;
+const MIN_WRAP_UP_RESERVE: Duration = Duration::from_secs(2);
+const MAX_WRAP_UP_RESERVE: Duration = Duration::from_secs(30);
+/// Share of the budget kept back for sending the reply.
+const DELIVERY_SHARE: u32 = 20;
+const MIN_DELIVERY_RESERVE: Duration = Duration::from_secs(1);
+const MAX_DELIVERY_RESERVE: Duration = Duration::from_secs(10);
+
+/// Point in time by which a request should be answered.
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+    expires_at: Instant,
+    budget: Duration,
+}
+
+impl Deadline {
+    /// Deadline `budget` from now.
+    pub fn after(budget: Duration) -> Self {
+        Self {
+            expires_at: Instant::now() + budget,
+            budget,
+        }
+    }
+
+    /// Total budget the deadline was created with.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Timeout for a stage that must leave `reserve` for the stages after it.
+    /// `None` when nothing is left beyond the reserve.
+    pub fn stage_timeout(&self, reserve: Duration) -> Option<Duration> {
+        let left = self.remaining().saturating_sub(reserve);
+        (!left.is_zero()).then_some(left)
+    }
+
+    /// Time kept back for a final answer without tools.
+    pub fn wrap_up_reserve(&self) -> Duration {
+        (self.budget / WRAP_UP_SHARE).clamp(MIN_WRAP_UP_RESERVE, MAX_WRAP_UP_RESERVE)
+    }
+
+    /// Time kept back for delivering the reply.
+    pub fn delivery_reserve(&self) -> Duration {
+        (self.budget / DELIVERY_SHARE).clamp(MIN_DELIVERY_RESERVE, MAX_DELIVERY_RESERVE)
+    }
+
+    /// Timeout for sending the reply. Never shorter than the delivery
+    /// reserve: an answer that is slightly late beats one that is dropped.
+    pub fn delivery_timeout(&self) -> Duration {
+        self.remaining().max(self.delivery_reserve())
+    }
+}
+
+/// The request deadline passed before the model produced any answer.
+#[derive(Debug)]
+pub struct DeadlineExceeded;
+
+impl std::fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("request deadline exceeded before the model replied")
+    }
+}
+
+impl std::error::Error for DeadlineExceeded {}
+
+pub fn is_deadline_exceeded(err: &anyhow::Error) -> bool {
+    err.chain().any(|source| source.is::<DeadlineExceeded>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserves_scale_with_budget_and_are_clamped() {
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert_eq!(deadline.wrap_up_reserve(), Duration::from_secs(10));
+        assert_eq!(deadline.delivery_reserve(), Duration::from_secs(3));
+
+        let short = Deadline::after(Duration::from_secs(6));
+        assert_eq!(short.wrap_up_reserve(), MIN_WRAP_UP_RESERVE);
+        assert_eq!(short.delivery_reserve(), MIN_DELIVERY_RESERVE);
+
+        let long = Deadline::after(Duration::from_secs(3600));
+        assert_eq!(long.wrap_up_reserve(), MAX_WRAP_UP_RESERVE);
+        assert_eq!(long.delivery_reserve(), MAX_DELIVERY_RESERVE);
+    }
+
+    #[test]
+    fn stage_timeout_leaves_reserve() {
+        let deadline = Deadline::after(Duration::from_secs(60));
+        let stage = deadline.stage_timeout(Duration::from_secs(10)).unwrap();
+        assert!(stage <= Duration::from_secs(50));
+        assert!(stage > Duration::from_secs(49));
+        assert!(deadline.stage_timeout(Duration::from_secs(61)).is_none());
+    }
+
+    #[test]
+    fn expired_deadline_still_allows_delivery() {
+        let deadline = Deadline::after(Duration::ZERO);
+        assert!(deadline.is_expired());
+        assert!(deadline.stage_timeout(Duration::ZERO).is_none());
+        assert_eq!(deadline.delivery_timeout(), MIN_DELIVERY_RESERVE);
+        assert!(is_deadline_exceeded(&anyhow::Error::new(DeadlineExceeded)));
+    }
+}
@@ -43,7 +43,9 @@ impl XmlToolDispatcher {
 
             if let Some(end) = remaining[start..].find("</tool_call>") {
                 let inner = &remaining[start + 11..start + end];
-                match serde_json::from_str::<Value>(inner.trim()) {
+                let parsed = serde_json::from_str::<Value>(inner.trim())
+                    .or_else(|e| super::json_repair::parse_lenient(inner).ok_or(e));
+                match parsed {
                     Ok(parsed) => {
                         let name = parsed
                             .get("name")
@@ -259,6 +261,24 @@ mod tests {
         assert_eq!(calls[0].name, "shell");
     }
 
+    #[test]
+    fn xml_dispatcher_repairs_sloppy_json() {
+        let response = ChatResponse {
+            text: Some(
+                "<tool_call>\n```json\n{name: 'file_read', arguments: {path: 'a.txt',},}\n```\n</tool_call>"
+                    .into(),
+            ),
+            tool_calls: vec![],
+            usage: None,
+            reasoning_content: None,
+        };
+        let dispatcher = XmlToolDispatcher;
+        let (_, calls) = dispatcher.parse_response(&response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_read");
+        assert_eq!(calls[0].arguments["path"], "a.txt");
+    }
+
     #[test]
     fn native_dispatcher_roundtrip() {
         let response = ChatResponse {
@@ -0,0 +1,215 @@
+//! Lenient JSON parsing for prompt-guided tool calls.
+//!
+//! Models that emit tool calls as text often produce almost-JSON: trailing
+//! commas, unquoted or single-quoted keys, Python literals, or an object that
+//! was cut off before its closing braces. [`parse_lenient`] first tries strict
+//! parsing and only then rewrites the candidate into valid JSON.
+//!
+//! Like `extract_json_values`, this must only be applied to text the model
+//! explicitly marked as a tool call (tag or fence body), never to arbitrary
+//! content.
+
+use serde_json::Value;
+
+/// Parse the first JSON object or array in `input`, repairing it if needed.
+pub(crate) fn parse_lenient(input: &str) -> Option<Value> {
+    parse_lenient_with_end(input).map(|(value, _)| value)
+}
+
+/// Like [`parse_lenient`], also returning the byte offset in `input` just
+/// past the consumed value.
+pub(crate) fn parse_lenient_with_end(input: &str) -> Option<(Value, usize)> {
+    for (start, ch) in input.char_indices() {
+        if ch != '{' && ch != '[' {
+            continue;
+        }
+        let slice = &input[start..];
+
+        let mut stream = serde_json::Deserializer::from_str(slice).into_iter::<Value>();
+        if let Some(Ok(value)) = stream.next() {
+            return Some((value, start + stream.byte_offset()));
+        }
+
+        let (repaired, consumed) = repair_json(slice);
+        if let Ok(value) = serde_json::from_str::<Value>(&repaired) {
+            return Some((value, start + consumed));
+        }
+    }
+    None
+}
+
+/// Rewrite the JSON-ish value at the start of `input` into strict JSON.
+///
+/// Stops after the outermost bracket closes, at a code fence, or at the end
+/// of input (closing anything still open). Returns the rewritten text and
+/// the number of input bytes consumed.
+fn repair_json(input: &str) -> (String, usize) {
+    let mut out = String::with_capacity(input.len() + 16);
+    let mut stack: Vec<char> = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    let mut consumed = input.len();
+
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '"' | '\'' => {
+                let (literal, end) = read_string(&input[idx..], ch);
+                out.push_str(&literal);
+                while chars.peek().is_some_and(|(next, _)| *next < idx + end) {
+                    chars.next();
+                }
+            }
+            '{' | '[' => {
+                stack.push(if ch == '{' { '}' } else { ']' });
+                out.push(ch);
+            }
+            '}' | ']' => {
+                // Drop a trailing comma before the closer.
+                let kept = out.trim_end().len();
+                if out[..kept].ends_with(',') {
+                    out.truncate(kept - 1);
+                }
+                if stack.pop().is_none() {
+                    consumed = idx;
+                    break;
+                }
+                out.push(ch);
+                if stack.is_empty() {
+                    consumed = idx + ch.len_utf8();
+                    break;
+                }
+            }
+            '`' => {
+                consumed = idx;
+                break;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' || c == '$' => {
+                let end = input[idx..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '-')))
+                    .map_or(input.len(), |len| idx + len);
+                let word = &input[idx..end];
+                let is_key = input[end..].trim_start().starts_with(':');
+                if is_key {
+                    out.push('"');
+                    out.push_str(word);
+                    out.push('"');
+                } else {
+                    out.push_str(match word {
+                        "True" => "true",
+                        "False" => "false",
+                        "None" => "null",
+                        other => other,
+                    });
+                }
+                while chars.peek().is_some_and(|(next, _)| *next < end) {
+                    chars.next();
+                }
+            }
+            _ => out.push(ch),
+        }
+    }
+
+    // Truncated output: close whatever is still open.
+    let kept = out.trim_end().len();
+    if !stack.is_empty() && out[..kept].ends_with(',') {
+        out.truncate(kept - 1);
+    }
+    while let Some(closer) = stack.pop() {
+        out.push(closer);
+    }
+    (out, consumed)
+}
+
+/// Read a string literal quoted with `quote` from the start of `input` and
+/// re-encode it with double quotes. An unterminated string runs to the end.
+fn read_string(input: &str, quote: char) -> (String, usize) {
+    let mut literal = String::from('"');
+    let mut chars = input.char_indices().skip(1);
+
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some((_, '\'')) => literal.push('\''),
+                Some((_, escaped)) => {
+                    literal.push('\\');
+                    literal.push(escaped);
+                }
+                None => {}
+            },
+            c if c == quote => {
+                literal.push('"');
+                return (literal, idx + c.len_utf8());
+            }
+            '"' => literal.push_str("\\\""),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c => literal.push(c),
+        }
+    }
+
+    literal.push('"');
+    (literal, input.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strict_json_passes_through() {
+        let value = parse_lenient(r#"{"name": "shell", "arguments": {"command": "ls"}}"#);
+        assert_eq!(
+            value,
+            Some(json!({"name": "shell", "arguments": {"command": "ls"}}))
+        );
+    }
+
+    #[test]
+    fn repairs_trailing_commas_and_unquoted_keys() {
+        let value = parse_lenient("{name: 'shell', arguments: {command: 'ls -la',},}");
+        assert_eq!(
+            value,
+            Some(json!({"name": "shell", "arguments": {"command": "ls -la"}}))
+        );
+    }
+
+    #[test]
+    fn repairs_single_quotes_and_python_literals() {
+        let value = parse_lenient(
+            r#"{'name': 'file_write', 'arguments': {'path': 'a.txt', 'content': 'say "hi"\nit\'s', 'append': False}}"#,
+        );
+        assert_eq!(
+            value,
+            Some(json!({
+                "name": "file_write",
+                "arguments": {"path": "a.txt", "content": "say \"hi\"\nit's", "append": false}
+            }))
+        );
+    }
+
+    #[test]
+    fn closes_truncated_object_and_stops_at_fence() {
+        let (value, end) = parse_lenient_with_end(
+            "```json\n{\"name\": \"shell\", \"arguments\": {\"command\": \"date\"\n```",
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({"name": "shell", "arguments": {"command": "date"}})
+        );
+        assert_eq!(
+            end,
+            "```json\n{\"name\": \"shell\", \"arguments\": {\"command\": \"date\"\n".len()
+        );
+    }
+
+    #[test]
+    fn skips_prose_brackets_before_the_payload() {
+        let value = parse_lenient("[note] calling now {name: \"memory_recall\", arguments: {}}");
+        assert_eq!(
+            value,
+            Some(json!({"name": "memory_recall", "arguments": {}}))
+        );
+    }
+}
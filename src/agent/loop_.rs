@@ -1,3 +1,5 @@
+use super::deadline::{self, Deadline, DeadlineExceeded};
+use super::json_repair;
 use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
@@ -11,6 +13,7 @@ use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
+use futures_util::StreamExt;
 use regex::{Regex, RegexSet};
 use std::collections::HashSet;
 use std::fmt::Write;
@@ -18,6 +21,7 @@ use std::io::Write as _;
 use std::sync::{Arc, LazyLock};
 use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 use uuid::Uuid;
 
 /// Minimum characters per chunk when relaying LLM text to a streaming draft.
@@ -27,6 +31,10 @@ const STREAM_CHUNK_MIN_CHARS: usize = 80;
 /// Used as a safe fallback when `max_tool_iterations` is unset or configured as zero.
 const DEFAULT_MAX_TOOL_ITERATIONS: usize = 10;
 
+/// How many times per turn a malformed tool call is sent back to the model
+/// with format instructions before its reply is accepted as the answer.
+const MAX_TOOL_CALL_PARSE_RETRIES: usize = 2;
+
 /// Minimum user-message length (in chars) for auto-save to memory.
 /// Matches the channel-side constant in `channels/mod.rs`.
 const AUTOSAVE_MIN_MESSAGE_CHARS: usize = 20;
@@ -106,6 +114,24 @@ pub(crate) const PROGRESS_MIN_INTERVAL_MS: u64 = 500;
 /// Used before streaming the final answer so progress lines are replaced by the clean response.
 pub(crate) const DRAFT_CLEAR_SENTINEL: &str = "\x00CLEAR\x00";
 
+/// Appended when the request deadline is close, asking for a final answer.
+const DEADLINE_WRAP_UP_PROMPT: &str = "[Time budget nearly exhausted] Do not call any more tools. Answer now using the results you already have, and say briefly what is still unfinished.";
+
+/// Sent back when a reply looked like a tool call that could not be parsed.
+fn tool_call_parse_retry_prompt(issue: &str) -> String {
+    format!(
+        "[Tool call parse error] Your last reply looked like a tool call, but it could not be \
+         parsed ({issue}). No tool was run.\n\
+         Re-send the call as one JSON object with double-quoted keys and strings and no \
+         trailing commas, wrapped in tags exactly like this:\n\
+         <tool_call>\n{{\"name\": \"tool_name\", \"arguments\": {{\"param\": \"value\"}}}}\n</tool_call>\n\
+         If you did not mean to call a tool, reply with plain text only."
+    )
+}
+
+/// Sent when not even the wrap-up answer fits in the remaining time.
+const DEADLINE_FALLBACK_REPLY: &str = "⏱️ I ran out of time before finishing this request. Please ask again, or narrow it down so it fits in one reply.";
+
 /// Extract a short hint from tool call arguments for progress display.
 fn truncate_tool_args_for_progress(name: &str, args: &serde_json::Value, max_len: usize) -> String {
     let hint = match name {
@@ -319,7 +345,9 @@ fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool>
 fn parse_arguments_value(raw: Option<&serde_json::Value>) -> serde_json::Value {
     match raw {
         Some(serde_json::Value::String(s)) => serde_json::from_str::<serde_json::Value>(s)
-            .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new())),
+            .ok()
+            .or_else(|| json_repair::parse_lenient(s))
+            .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::new())),
         Some(value) => value.clone(),
         None => serde_json::Value::Object(serde_json::Map::new()),
     }
@@ -782,6 +810,25 @@ fn extract_json_values(input: &str) -> Vec<serde_json::Value> {
     values
 }
 
+/// Parse the JSON tool calls in a tag or fence body.
+///
+/// Strict JSON is tried first; if it yields no tool call, the body is
+/// repaired (trailing commas, unquoted keys, single quotes, missing closers)
+/// and parsed again. Same trust requirement as [`extract_json_values`].
+fn parse_json_tool_call_body(inner: &str) -> Vec<ParsedToolCall> {
+    let calls: Vec<ParsedToolCall> = extract_json_values(inner)
+        .iter()
+        .flat_map(parse_tool_calls_from_json_value)
+        .collect();
+    if !calls.is_empty() {
+        return calls;
+    }
+
+    json_repair::parse_lenient(inner)
+        .map(|value| parse_tool_calls_from_json_value(&value))
+        .unwrap_or_default()
+}
+
 /// Find the end position of a JSON object by tracking balanced braces.
 fn find_json_end(input: &str) -> Option<usize> {
     let trimmed = input.trim_start();
@@ -1355,13 +1402,10 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
             let mut parsed_any = false;
 
             // Try JSON format first
-            let json_values = extract_json_values(inner);
-            for value in json_values {
-                let parsed_calls = parse_tool_calls_from_json_value(&value);
-                if !parsed_calls.is_empty() {
-                    parsed_any = true;
-                    calls.extend(parsed_calls);
-                }
+            let parsed_calls = parse_json_tool_call_body(inner);
+            if !parsed_calls.is_empty() {
+                parsed_any = true;
+                calls.extend(parsed_calls);
             }
 
             // If JSON parsing failed, try XML format (DeepSeek/GLM style)
@@ -1397,13 +1441,10 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
                 let mut parsed_any = false;
 
                 // Try JSON
-                let json_values = extract_json_values(inner);
-                for value in json_values {
-                    let parsed_calls = parse_tool_calls_from_json_value(&value);
-                    if !parsed_calls.is_empty() {
-                        parsed_any = true;
-                        calls.extend(parsed_calls);
-                    }
+                let parsed_calls = parse_json_tool_call_body(inner);
+                if !parsed_calls.is_empty() {
+                    parsed_any = true;
+                    calls.extend(parsed_calls);
                 }
 
                 // Try XML
@@ -1456,6 +1497,16 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
                 }
             }
 
+            // Truncated or sloppy JSON (missing closers, trailing commas, ...).
+            if let Some((value, consumed_end)) = json_repair::parse_lenient_with_end(after_open) {
+                let parsed_calls = parse_tool_calls_from_json_value(&value);
+                if !parsed_calls.is_empty() {
+                    calls.extend(parsed_calls);
+                    remaining = strip_leading_close_tags(&after_open[consumed_end..]);
+                    continue;
+                }
+            }
+
             // Last resort: try GLM shortened body on everything after the open tag.
             // The model may have emitted `<tool_call>shell>ls` with no close tag at all.
             let glm_input = after_open.trim();
@@ -1473,10 +1524,11 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
     // If XML tags found nothing, try markdown code blocks with tool_call language.
     // Models behind OpenRouter sometimes output ```tool_call ... ``` or hybrid
     // ```tool_call ... </tool_call> instead of structured API calls or XML tags.
+    // A `json` tag on either side of the language (```json tool_call) is accepted.
     if calls.is_empty() {
         static MD_TOOL_CALL_RE: LazyLock<Regex> = LazyLock::new(|| {
             Regex::new(
-                r"(?s)```(?:tool[_-]?call|invoke)\s*\n(.*?)(?:```|</tool[_-]?call>|</toolcall>|</invoke>|</minimax:toolcall>)",
+                r"(?s)```(?:json[ \t]+)?(?:tool[_-]?call|invoke)(?:[ \t]+json)?\s*\n(.*?)(?:```|</tool[_-]?call>|</toolcall>|</invoke>|</minimax:toolcall>)",
             )
             .unwrap()
         });
@@ -1489,12 +1541,7 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
             if !before.trim().is_empty() {
                 md_text_parts.push(before.trim().to_string());
             }
-            let inner = &cap[1];
-            let json_values = extract_json_values(inner);
-            for value in json_values {
-                let parsed_calls = parse_tool_calls_from_json_value(&value);
-                calls.extend(parsed_calls);
-            }
+            calls.extend(parse_json_tool_call_body(&cap[1]));
             last_end = full_match.end();
         }
 
@@ -1710,6 +1757,7 @@ fn detect_tool_call_parse_issue(response: &str, parsed_calls: &[ParsedToolCall])
         || trimmed.contains("```tool_call")
         || trimmed.contains("```toolcall")
         || trimmed.contains("```tool-call")
+        || trimmed.contains("```json tool_call")
         || trimmed.contains("```tool file_")
         || trimmed.contains("```tool shell")
         || trimmed.contains("```tool web_")
@@ -1891,6 +1939,8 @@ pub(crate) async fn agent_turn(
         None,
         None,
         &[],
+        None,
+        0,
     )
     .await
 }
@@ -1901,6 +1951,7 @@ async fn execute_one_tool(
     tools_registry: &[Box<dyn Tool>],
     observer: &dyn Observer,
     cancellation_token: Option<&CancellationToken>,
+    tool_timeout: Option<Duration>,
 ) -> Result<ToolExecutionOutcome> {
     observer.record_event(&ObserverEvent::ToolCallStart {
         tool: call_name.to_string(),
@@ -1923,7 +1974,20 @@ async fn execute_one_tool(
         });
     };
 
-    let tool_future = tool.execute(call_arguments);
+    let tool_future = async {
+        match tool_timeout {
+            Some(limit) => tokio::time::timeout(limit, tool.execute(call_arguments))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(anyhow::anyhow!(
+                        "timed out after {}s (request time budget exhausted)",
+                        limit.as_secs()
+                    ))
+                }),
+            None => tool.execute(call_arguments).await,
+        }
+    }
+    .instrument(tracing::info_span!("tool_call", tool = call_name));
     let tool_result = if let Some(token) = cancellation_token {
         tokio::select! {
             () = token.cancelled() => return Err(ToolLoopCancelled.into()),
@@ -2002,27 +2066,45 @@ fn should_execute_tools_in_parallel(
     true
 }
 
+/// Run independent calls concurrently (at most `max_parallel` at once) while
+/// dependent ones wait for the batch before them; outcomes keep call order.
 async fn execute_tools_parallel(
     tool_calls: &[ParsedToolCall],
     tools_registry: &[Box<dyn Tool>],
     observer: &dyn Observer,
     cancellation_token: Option<&CancellationToken>,
+    tool_timeout: Option<Duration>,
+    max_parallel: usize,
 ) -> Result<Vec<ToolExecutionOutcome>> {
-    let futures: Vec<_> = tool_calls
-        .iter()
-        .map(|call| {
-            execute_one_tool(
-                &call.name,
-                call.arguments.clone(),
-                tools_registry,
-                observer,
-                cancellation_token,
-            )
-        })
-        .collect();
+    let batches = super::tool_batches::plan_batches(
+        tool_calls
+            .iter()
+            .map(|call| (call.name.as_str(), &call.arguments)),
+    );
+    let mut outcomes = Vec::with_capacity(tool_calls.len());
 
-    let results = futures_util::future::join_all(futures).await;
-    results.into_iter().collect()
+    for batch in batches {
+        let results: Vec<Result<ToolExecutionOutcome>> =
+            futures_util::stream::iter(batch.into_iter().map(|idx| {
+                let call = &tool_calls[idx];
+                execute_one_tool(
+                    &call.name,
+                    call.arguments.clone(),
+                    tools_registry,
+                    observer,
+                    cancellation_token,
+                    tool_timeout,
+                )
+            }))
+            .buffered(max_parallel.max(1))
+            .collect()
+            .await;
+        for result in results {
+            outcomes.push(result?);
+        }
+    }
+
+    Ok(outcomes)
 }
 
 async fn execute_tools_sequential(
@@ -2030,6 +2112,7 @@ async fn execute_tools_sequential(
     tools_registry: &[Box<dyn Tool>],
     observer: &dyn Observer,
     cancellation_token: Option<&CancellationToken>,
+    tool_timeout: Option<Duration>,
 ) -> Result<Vec<ToolExecutionOutcome>> {
     let mut outcomes = Vec::with_capacity(tool_calls.len());
 
@@ -2041,6 +2124,7 @@ async fn execute_tools_sequential(
                 tools_registry,
                 observer,
                 cancellation_token,
+                tool_timeout,
             )
             .await?,
         );
@@ -2081,12 +2165,19 @@ pub(crate) async fn run_tool_call_loop(
     on_delta: Option<tokio::sync::mpsc::Sender<String>>,
     hooks: Option<&crate::hooks::HookRunner>,
     excluded_tools: &[String],
+    deadline: Option<Deadline>,
+    max_parallel_tools: usize,
 ) -> Result<String> {
     let max_iterations = if max_tool_iterations == 0 {
         DEFAULT_MAX_TOOL_ITERATIONS
     } else {
         max_tool_iterations
     };
+    let max_parallel_tools = if max_parallel_tools == 0 {
+        super::tool_batches::DEFAULT_MAX_PARALLEL_TOOLS
+    } else {
+        max_parallel_tools
+    };
 
     let tool_specs: Vec<crate::tools::ToolSpec> = tools_registry
         .iter()
@@ -2096,6 +2187,7 @@ pub(crate) async fn run_tool_call_loop(
     let use_native_tools = provider.supports_native_tools() && !tool_specs.is_empty();
     let turn_id = Uuid::new_v4().to_string();
     let mut seen_tool_signatures: HashSet<(String, String)> = HashSet::new();
+    let mut parse_retries = 0;
 
     for iteration in 0..max_iterations {
         if cancellation_token
@@ -2105,7 +2197,32 @@ pub(crate) async fn run_tool_call_loop(
             return Err(ToolLoopCancelled.into());
         }
 
-        let image_marker_count = multimodal::count_image_markers(history);
+        // Out of time for another tool round: answer with what we have.
+        if let Some(deadline) =
+            deadline.filter(|d| iteration > 0 && d.stage_timeout(d.wrap_up_reserve()).is_none())
+        {
+            return Ok(wrap_up_on_deadline(
+                provider,
+                history,
+                model,
+                temperature,
+                multimodal_config,
+                deadline,
+                on_delta.as_ref(),
+            )
+            .await);
+        }
+
+        let mut image_marker_count = multimodal::count_image_markers(history);
+        // Without vision, let the OCR tool turn images into text when it is available.
+        if image_marker_count > 0 && !provider.supports_vision() {
+            if let Some(ocr) = find_tool(tools_registry, tools::ocr::OCR_TOOL_NAME)
+                .filter(|tool| !excluded_tools.iter().any(|ex| ex == tool.name()))
+            {
+                tools::ocr::transcribe_image_markers(history, ocr, multimodal_config).await;
+                image_marker_count = multimodal::count_image_markers(history);
+            }
+        }
         if image_marker_count > 0 && !provider.supports_vision() {
             return Err(ProviderCapabilityError {
                 provider: provider_name.to_string(),
@@ -2171,14 +2288,39 @@ pub(crate) async fn run_tool_call_loop(
             None
         };
 
-        let chat_future = provider.chat(
-            ChatRequest {
-                messages: &prepared_messages.messages,
-                tools: request_tools,
-            },
-            model,
-            temperature,
-        );
+        // The first call only needs to leave time for delivery; later calls
+        // also keep back time for a wrap-up answer.
+        let chat_timeout = deadline.map(|d| {
+            let reserve = if iteration == 0 {
+                d.delivery_reserve()
+            } else {
+                d.wrap_up_reserve()
+            };
+            d.stage_timeout(reserve).unwrap_or(Duration::ZERO)
+        });
+        let chat_future = async {
+            let request = provider
+                .chat(
+                    ChatRequest {
+                        messages: &prepared_messages.messages,
+                        tools: request_tools,
+                    },
+                    model,
+                    temperature,
+                )
+                .instrument(tracing::info_span!(
+                    "provider_chat",
+                    provider = provider_name,
+                    model,
+                    iteration
+                ));
+            match chat_timeout {
+                Some(limit) => tokio::time::timeout(limit, request)
+                    .await
+                    .unwrap_or_else(|_| Err(DeadlineExceeded.into())),
+                None => request.await,
+            }
+        };
 
         let chat_result = if let Some(token) = cancellation_token.as_ref() {
             tokio::select! {
@@ -2189,6 +2331,7 @@ pub(crate) async fn run_tool_call_loop(
             chat_future.await
         };
 
+        let parse_issue;
         let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
             match chat_result {
                 Ok(resp) => {
@@ -2224,8 +2367,8 @@ pub(crate) async fn run_tool_call_loop(
                         calls = fallback_calls;
                     }
 
-                    if let Some(parse_issue) = detect_tool_call_parse_issue(&response_text, &calls)
-                    {
+                    parse_issue = detect_tool_call_parse_issue(&response_text, &calls);
+                    if let Some(parse_issue) = parse_issue.as_deref() {
                         runtime_trace::record_event(
                             "tool_call_parse_issue",
                             Some(channel_name),
@@ -2233,7 +2376,7 @@ pub(crate) async fn run_tool_call_loop(
                             Some(model),
                             Some(&turn_id),
                             Some(false),
-                            Some(&parse_issue),
+                            Some(parse_issue),
                             serde_json::json!({
                                 "iteration": iteration + 1,
                                 "response_excerpt": truncate_with_ellipsis(
@@ -2318,6 +2461,20 @@ pub(crate) async fn run_tool_call_loop(
                             "duration_ms": llm_started_at.elapsed().as_millis(),
                         }),
                     );
+                    if let Some(deadline) =
+                        deadline.filter(|_| iteration > 0 && deadline::is_deadline_exceeded(&e))
+                    {
+                        return Ok(wrap_up_on_deadline(
+                            provider,
+                            history,
+                            model,
+                            temperature,
+                            multimodal_config,
+                            deadline,
+                            on_delta.as_ref(),
+                        )
+                        .await);
+                    }
                     return Err(e);
                 }
             };
@@ -2341,6 +2498,32 @@ pub(crate) async fn run_tool_call_loop(
             }
         }
 
+        // A garbled tool call is not a final answer: show the model what went
+        // wrong and let it try again while there are iterations left.
+        if let Some(issue) = parse_issue.filter(|_| {
+            tool_calls.is_empty()
+                && parse_retries < MAX_TOOL_CALL_PARSE_RETRIES
+                && iteration + 1 < max_iterations
+        }) {
+            parse_retries += 1;
+            runtime_trace::record_event(
+                "tool_call_parse_retry",
+                Some(channel_name),
+                Some(provider_name),
+                Some(model),
+                Some(&turn_id),
+                Some(false),
+                Some(&issue),
+                serde_json::json!({
+                    "iteration": iteration + 1,
+                    "retry": parse_retries,
+                }),
+            );
+            history.push(ChatMessage::assistant(response_text));
+            history.push(ChatMessage::user(tool_call_parse_retry_prompt(&issue)));
+            continue;
+        }
+
         if tool_calls.is_empty() {
             runtime_trace::record_event(
                 "turn_final_response",
@@ -2566,23 +2749,31 @@ pub(crate) async fn run_tool_call_loop(
             });
         }
 
-        let executed_outcomes = if allow_parallel_execution && executable_calls.len() > 1 {
-            execute_tools_parallel(
-                &executable_calls,
-                tools_registry,
-                observer,
-                cancellation_token.as_ref(),
-            )
-            .await?
-        } else {
-            execute_tools_sequential(
-                &executable_calls,
-                tools_registry,
-                observer,
-                cancellation_token.as_ref(),
-            )
-            .await?
-        };
+        let tool_timeout = deadline.map(|d| {
+            d.stage_timeout(d.wrap_up_reserve())
+                .unwrap_or(Duration::ZERO)
+        });
+        let executed_outcomes =
+            if allow_parallel_execution && max_parallel_tools > 1 && executable_calls.len() > 1 {
+                execute_tools_parallel(
+                    &executable_calls,
+                    tools_registry,
+                    observer,
+                    cancellation_token.as_ref(),
+                    tool_timeout,
+                    max_parallel_tools,
+                )
+                .await?
+            } else {
+                execute_tools_sequential(
+                    &executable_calls,
+                    tools_registry,
+                    observer,
+                    cancellation_token.as_ref(),
+                    tool_timeout,
+                )
+                .await?
+            };
 
         for ((idx, call), outcome) in executable_indices
             .iter()
@@ -2693,6 +2884,65 @@ pub(crate) async fn run_tool_call_loop(
     anyhow::bail!("Agent exceeded maximum tool iterations ({max_iterations})")
 }
 
+/// Ask for a final answer without tools once the request deadline is close.
+/// Falls back to a fixed notice when even that does not fit in the time left.
+async fn wrap_up_on_deadline(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    model: &str,
+    temperature: f64,
+    multimodal_config: &crate::config::MultimodalConfig,
+    deadline: Deadline,
+    on_delta: Option<&tokio::sync::mpsc::Sender<String>>,
+) -> String {
+    tracing::info!(
+        remaining_ms = deadline.remaining().as_millis(),
+        "Request deadline is close; asking for a final answer without tools"
+    );
+    history.push(ChatMessage::user(DEADLINE_WRAP_UP_PROMPT));
+    let answer = match deadline.stage_timeout(deadline.delivery_reserve()) {
+        Some(limit) => {
+            let attempt = async {
+                let prepared =
+                    multimodal::prepare_messages_for_provider(history, multimodal_config).await?;
+                let response = provider
+                    .chat(
+                        ChatRequest {
+                            messages: &prepared.messages,
+                            tools: None,
+                        },
+                        model,
+                        temperature,
+                    )
+                    .await?;
+                let text = response.text_or_empty().to_string();
+                let (parsed_text, calls) = parse_tool_calls(&text);
+                anyhow::Ok(if calls.is_empty() { text } else { parsed_text })
+            };
+            match tokio::time::timeout(limit, attempt).await {
+                Ok(Ok(text)) => Some(text),
+                Ok(Err(e)) => {
+                    tracing::warn!("Wrap-up answer failed: {e}");
+                    None
+                }
+                Err(_) => None,
+            }
+        }
+        None => None,
+    };
+    let answer = answer
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .unwrap_or_else(|| DEADLINE_FALLBACK_REPLY.to_string());
+
+    if let Some(tx) = on_delta {
+        let _ = tx.send(DRAFT_CLEAR_SENTINEL.to_string()).await;
+        let _ = tx.send(answer.clone()).await;
+    }
+    history.push(ChatMessage::assistant(answer.clone()));
+    answer
+}
+
 /// Build the tool instruction block for the system prompt so the LLM knows
 /// how to invoke tools.
 pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> String {
@@ -2798,6 +3048,18 @@ pub async fn run(
         tools_registry.extend(peripheral_tools);
     }
 
+    let mcp_tools = tools::untrusted::guard_tool_outputs(
+        tools::output_limit::limit_tool_outputs(
+            tools::mcp::create_mcp_tools(&config.mcp, Arc::clone(&security)).await,
+            &config,
+        ),
+        &config,
+    );
+    if !mcp_tools.is_empty() {
+        tracing::info!(count = mcp_tools.len(), "MCP tools added");
+        tools_registry.extend(mcp_tools);
+    }
+
     // ── Resolve provider ─────────────────────────────────────────
     let provider_name = provider_override
         .as_deref()
@@ -2815,6 +3077,7 @@ pub async fn run(
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
         reasoning_enabled: config.runtime.reasoning_enabled,
+        redaction: config.security.redaction.clone(),
     };
 
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
@@ -2898,14 +3161,108 @@ pub async fn run(
         "Force-run a cron job immediately and record a run history entry.",
     ));
     tool_descs.push(("cron_runs", "Show recent run history for a cron job."));
+    tool_descs.push((
+        "set_reminder",
+        "Remind the user later in this chat: once (at/in) or repeating (cron). Pass channel and to from the channel context.",
+    ));
+    tool_descs.push((
+        "list_reminders",
+        "List pending reminders for this chat with their ids.",
+    ));
+    tool_descs.push((
+        "cancel_reminder",
+        "Cancel a pending reminder by reminder_id.",
+    ));
     tool_descs.push((
         "screenshot",
         "Capture a screenshot of the current screen. Returns file path and base64-encoded PNG. Use when: visual verification, UI inspection, debugging displays.",
     ));
+    tool_descs.push((
+        "record_screen",
+        "Record the screen for up to 60 seconds to an MP4 returned as a [VIDEO:path] marker. Use when: the user asks to show what happens over time (\"show me what happens when…\"), e.g. an animation, a loading sequence or a UI flow.",
+    ));
     tool_descs.push((
         "image_info",
         "Read image file metadata (format, dimensions, size) and optionally base64-encode it. Use when: inspecting images, preparing visual data for analysis.",
     ));
+    tool_descs.push((
+        "extract_document",
+        "Extract the text of a PDF, DOCX or XLSX file as markdown. Use when: the user sends a [DOCUMENT:path] attachment and asks about its contents. Pass the marker or path.",
+    ));
+    tool_descs.push((
+        "transcribe_media",
+        "Transcribe speech in an audio or video file, with [HH:MM:SS] offsets. Use when: the user asks to summarize or quote an [AUDIO:path], [VOICE:path] or [VIDEO:path] attachment such as a meeting recording. Pass the marker or path.",
+    ));
+    if config.multimodal.ocr.enabled {
+        tool_descs.push((
+            "ocr",
+            "Extract text from an image (screenshot, photo of a document). Use when: reading text in an image the model cannot see directly.",
+        ));
+    }
+    if config.image_generation.enabled {
+        tool_descs.push((
+            "generate_image",
+            "Generate an image from a text prompt. Returns an [IMAGE:path] marker; include it in your reply to send the picture. Use when: the user asks you to draw, render or create a picture.",
+        ));
+    }
+    if config.run_code.enabled {
+        tool_descs.push((
+            "run_code",
+            "Run a Python or SQL snippet in an isolated sandbox. Returns stdout, saves produced files to the workspace, and turns plots into [IMAGE:path] markers. Use when: calculations, data analysis, charts, or querying a SQLite file.",
+        ));
+    }
+    if config.sql_query.enabled {
+        tool_descs.push((
+            "sql_query",
+            "Run a read-only SQL query against a configured database and get a result table. Use when: answering questions from business or ops data (counts, recent records, trends).",
+        ));
+    }
+    if config.git.enabled {
+        tool_descs.push((
+            "git",
+            "Inspect the configured repository read-only: status, diff, log, blame, grep. Use when: questions about the codebase, recent changes, or who changed a line.",
+        ));
+    }
+    if config.calendar.enabled {
+        tool_descs.push((
+            "calendar",
+            "List, create, update or delete calendar events. Use when: the user asks what is on their schedule or to book, move or cancel a meeting. Resolve relative dates like 'tomorrow at 3' from the current date first.",
+        ));
+    }
+    if config.send_email.enabled {
+        tool_descs.push((
+            "send_email",
+            "Email a message with workspace files attached. Use when: the user asks to mail a report or file to someone. Don't use when: replying in the current chat is enough.",
+        ));
+    }
+    if config.home_assistant.enabled {
+        tool_descs.push((
+            "home_assistant",
+            "Read and control the smart home through Home Assistant. Use when: the user asks about a device's state (\"is the garage door open?\") or to switch something (\"turn off the lights\"). Find entity IDs with action 'states' and a search term first.",
+        ));
+    }
+    if config.knowledge.enabled {
+        tool_descs.push((
+            "search_knowledge",
+            "Search the user's own documents (notes, manuals, policies) for relevant passages. Use when: a question may be answered by the user's files rather than general knowledge (\"what does our refund policy say?\"). Cite the source file in the answer.",
+        ));
+    }
+    if config.desktop.enabled {
+        tool_descs.push((
+            "clipboard_read",
+            "Read the text on the local clipboard. Use when: the user refers to something they copied.",
+        ));
+        tool_descs.push((
+            "clipboard_write",
+            "Copy text to the local clipboard. Use when: the user asks to copy a result so they can paste it elsewhere.",
+        ));
+        if config.desktop.allow_input {
+            tool_descs.push((
+                "desktop_input",
+                "Move the mouse, click, type text or press keys on the local machine. Use when: explicitly asked to operate a desktop app. Take a screenshot first to find coordinates and after to check the result.",
+            ));
+        }
+    }
     if config.browser.enabled {
         tool_descs.push((
             "browser_open",
@@ -3044,6 +3401,8 @@ pub async fn run(
             None,
             None,
             &[],
+            None,
+            config.agent.max_parallel_tools,
         )
         .await?;
         final_output = response.clone();
@@ -3052,25 +3411,13 @@ pub async fn run(
     } else {
         println!("🦀 ZeroClaw Interactive Mode");
         println!("Type /help for commands.\n");
-        let cli = crate::channels::CliChannel::new();
+        let cli = crate::channels::CliChannel::new()
+            .with_history_file(config.workspace_dir.join("state").join("cli_history"));
 
         // Persistent conversation history across turns
         let mut history = vec![ChatMessage::system(&system_prompt)];
 
-        loop {
-            print!("> ");
-            let _ = std::io::stdout().flush();
-
-            let mut input = String::new();
-            match std::io::stdin().read_line(&mut input) {
-                Ok(0) => break,
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("\nError reading input: {e}\n");
-                    break;
-                }
-            }
-
+        while let Some(input) = cli.read_message("You") {
             let user_input = input.trim().to_string();
             if user_input.is_empty() {
                 continue;
@@ -3166,6 +3513,8 @@ pub async fn run(
                 None,
                 None,
                 &[],
+                None,
+                config.agent.max_parallel_tools,
             )
             .await
             {
@@ -3261,6 +3610,13 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
     let peripheral_tools: Vec<Box<dyn Tool>> =
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
     tools_registry.extend(peripheral_tools);
+    tools_registry.extend(tools::untrusted::guard_tool_outputs(
+        tools::output_limit::limit_tool_outputs(
+            tools::mcp::create_mcp_tools(&config.mcp, Arc::clone(&security)).await,
+            &config,
+        ),
+        &config,
+    ));
 
     let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
     let model_name = config
@@ -3273,6 +3629,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
         reasoning_enabled: config.runtime.reasoning_enabled,
+        redaction: config.security.redaction.clone(),
     };
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
         provider_name,
@@ -3312,8 +3669,63 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
             "Configure default model, scenario routing, and delegate agents.",
         ),
         ("screenshot", "Capture a screenshot."),
+        ("record_screen", "Record the screen to a short video."),
         ("image_info", "Read image metadata."),
+        (
+            "extract_document",
+            "Extract text from a PDF, DOCX or XLSX file.",
+        ),
+        ("transcribe_media", "Transcribe an audio or video file."),
     ];
+    if config.multimodal.ocr.enabled {
+        tool_descs.push(("ocr", "Extract text from an image."));
+    }
+    if config.image_generation.enabled {
+        tool_descs.push(("generate_image", "Generate an image from a text prompt."));
+    }
+    if config.run_code.enabled {
+        tool_descs.push(("run_code", "Run Python or SQL in a sandbox."));
+    }
+    if config.sql_query.enabled {
+        tool_descs.push((
+            "sql_query",
+            "Run a read-only SQL query on a configured database.",
+        ));
+    }
+    if config.git.enabled {
+        tool_descs.push(("git", "Read-only git status, diff, log, blame, grep."));
+    }
+    if config.calendar.enabled {
+        tool_descs.push((
+            "calendar",
+            "List, create, update or delete calendar events.",
+        ));
+    }
+    if config.send_email.enabled {
+        tool_descs.push((
+            "send_email",
+            "Email a message with workspace file attachments.",
+        ));
+    }
+    if config.home_assistant.enabled {
+        tool_descs.push((
+            "home_assistant",
+            "Read device states and call services in Home Assistant.",
+        ));
+    }
+    if config.knowledge.enabled {
+        tool_descs.push(("search_knowledge", "Search the user's documents."));
+    }
+    if config.desktop.enabled {
+        tool_descs.push(("clipboard_read", "Read the local clipboard."));
+        tool_descs.push(("clipboard_write", "Copy text to the local clipboard."));
+        if config.desktop.allow_input {
+            tool_descs.push((
+                "desktop_input",
+                "Move the mouse, click, type or press keys.",
+            ));
+        }
+    }
     if config.browser.enabled {
         tool_descs.push(("browser_open", "Open approved URLs in browser."));
     }
@@ -3710,6 +4122,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            0,
         )
         .await
         .expect_err("provider without vision support should fail");
@@ -3719,6 +4133,53 @@ mod tests {
         assert_eq!(calls.load(Ordering::SeqCst), 0);
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_transcribes_images_with_ocr_tool_for_non_vision_provider() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = NonVisionProvider {
+            calls: Arc::clone(&calls),
+        };
+        let ocr_runs = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> =
+            vec![Box::new(CountingTool::new("ocr", Arc::clone(&ocr_runs)))];
+
+        let mut history = vec![ChatMessage::user(
+            "please inspect [IMAGE:data:image/png;base64,iVBORw0KGgo=]".to_string(),
+        )];
+        let observer = NoopObserver;
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &observer,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "cli",
+            &crate::config::MultimodalConfig::default(),
+            3,
+            None,
+            None,
+            None,
+            &[],
+            None,
+            0,
+        )
+        .await
+        .expect("OCR should stand in for vision");
+
+        assert_eq!(result, "ok");
+        assert_eq!(ocr_runs.load(Ordering::SeqCst), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            history[0].content,
+            "please inspect\n\n[Image inline image — text extracted by OCR]\ncounted:"
+        );
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_rejects_oversized_image_payload() {
         let calls = Arc::new(AtomicUsize::new(0));
@@ -3737,6 +4198,7 @@ mod tests {
             max_images: 4,
             max_image_size_mb: 1,
             allow_remote_fetch: false,
+            ..Default::default()
         };
 
         let err = run_tool_call_loop(
@@ -3756,6 +4218,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            0,
         )
         .await
         .expect_err("oversized payload must fail");
@@ -3796,6 +4260,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            0,
         )
         .await
         .expect("valid multimodal payload should pass");
@@ -3922,6 +4388,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            0,
         )
         .await
         .expect("parallel execution should complete");
@@ -3950,6 +4418,164 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_reprompts_after_unparseable_tool_call() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>{"name": "delay_a", "arguments": }</tool_call>"#,
+            r#"<tool_call>{"name": "delay_a", "arguments": {"value": "A"},}</tool_call>"#,
+            "done",
+        ]);
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(DelayTool::new(
+            "delay_a",
+            1,
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicUsize::new(0)),
+        ))];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run tool calls"),
+        ];
+        let observer = NoopObserver;
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &observer,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "telegram",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            None,
+            None,
+            &[],
+            None,
+            0,
+        )
+        .await
+        .expect("loop should recover from a malformed tool call");
+
+        assert_eq!(result, "done");
+        let retry_idx = history
+            .iter()
+            .position(|msg| {
+                msg.role == "user" && msg.content.starts_with("[Tool call parse error]")
+            })
+            .expect("parse error prompt should be in history");
+        assert!(history[retry_idx].content.contains("<tool_call>"));
+        assert!(history[retry_idx + 1..]
+            .iter()
+            .any(|msg| msg.role == "user" && msg.content.starts_with("[Tool results]")));
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_stops_reprompting_after_retry_limit() {
+        let malformed = r#"<tool_call>{"name": "delay_a", "arguments": }</tool_call>"#;
+        let provider = ScriptedProvider::from_text_responses(vec![malformed; 4]);
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run tool calls"),
+        ];
+        let observer = NoopObserver;
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &[],
+            &observer,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "telegram",
+            &crate::config::MultimodalConfig::default(),
+            10,
+            None,
+            None,
+            None,
+            &[],
+            None,
+            0,
+        )
+        .await
+        .expect("loop should end with the unparsed reply");
+
+        assert_eq!(result, malformed);
+        let retries = history
+            .iter()
+            .filter(|msg| msg.content.starts_with("[Tool call parse error]"))
+            .count();
+        assert_eq!(retries, MAX_TOOL_CALL_PARSE_RETRIES);
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_caps_parallel_tool_calls() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"delay_a","arguments":{"value":"A"}}
+</tool_call>
+<tool_call>
+{"name":"delay_b","arguments":{"value":"B"}}
+</tool_call>
+<tool_call>
+{"name":"delay_c","arguments":{"value":"C"}}
+</tool_call>"#,
+            "done",
+        ]);
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = ["delay_a", "delay_b", "delay_c"]
+            .into_iter()
+            .map(|name| {
+                Box::new(DelayTool::new(
+                    name,
+                    100,
+                    Arc::clone(&active),
+                    Arc::clone(&max_active),
+                )) as Box<dyn Tool>
+            })
+            .collect();
+
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run tool calls"),
+        ];
+        let observer = NoopObserver;
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &observer,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "telegram",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            None,
+            None,
+            &[],
+            None,
+            2,
+        )
+        .await
+        .expect("capped parallel execution should complete");
+
+        assert_eq!(result, "done");
+        assert_eq!(max_active.load(Ordering::SeqCst), 2);
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_deduplicates_repeated_tool_calls() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -3991,6 +4617,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            0,
         )
         .await
         .expect("loop should finish after deduplicating repeated calls");
@@ -4010,6 +4638,61 @@ mod tests {
         assert!(tool_results.content.contains("Skipped duplicate tool call"));
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_wraps_up_when_deadline_runs_out() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"slow_tool","arguments":{"value":"A"}}
+</tool_call>"#,
+            "Partial answer: the lookup did not finish in time.",
+        ]);
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(DelayTool::new(
+            "slow_tool",
+            10_000,
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicUsize::new(0)),
+        ))];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("look it up"),
+        ];
+        let observer = NoopObserver;
+
+        // 3s budget keeps back 2s for the wrap-up, so the tool gets ~1s.
+        let started = Instant::now();
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &observer,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "telegram",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            None,
+            None,
+            &[],
+            Some(Deadline::after(Duration::from_secs(3))),
+            0,
+        )
+        .await
+        .expect("deadline should produce a partial answer, not an error");
+
+        assert_eq!(result, "Partial answer: the lookup did not finish in time.");
+        assert!(started.elapsed() < Duration::from_secs(3));
+        assert!(history
+            .iter()
+            .any(|msg| msg.content.contains("request time budget exhausted")));
+        assert!(history
+            .iter()
+            .any(|msg| msg.role == "user" && msg.content == DEADLINE_WRAP_UP_PROMPT));
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_native_mode_preserves_fallback_tool_call_ids() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -4047,6 +4730,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            0,
         )
         .await
         .expect("native fallback id flow should complete");
@@ -5124,6 +5809,53 @@ browser_open/url>https://example.com"#;
         assert_eq!(text, "Done");
     }
 
+    #[test]
+    fn parse_tool_calls_repairs_almost_json_in_tag() {
+        let response =
+            "<tool_call>\n{name: 'shell', arguments: {command: 'ls -la',},}\n</tool_call>";
+        let (_, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments["command"], "ls -la");
+    }
+
+    #[test]
+    fn parse_tool_calls_accepts_fenced_json_inside_tag() {
+        let response = "Reading it now.\n<tool_call>\n```json\n{\"name\": \"file_read\", \"arguments\": {\"path\": \"a.txt\",}}\n```\n</tool_call>";
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Reading it now.");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_read");
+        assert_eq!(calls[0].arguments["path"], "a.txt");
+    }
+
+    #[test]
+    fn parse_tool_calls_accepts_json_tool_call_fence() {
+        let response = "```json tool_call\n{\"name\": \"memory_recall\", \"arguments\": {\"query\": \"tea\"}}\n```\nDone";
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "memory_recall");
+        assert_eq!(calls[0].arguments["query"], "tea");
+        assert_eq!(text, "Done");
+    }
+
+    #[test]
+    fn parse_tool_calls_closes_truncated_json_in_unclosed_tag() {
+        let response = "<tool_call>{\"name\":\"shell\",\"arguments\":{\"command\":\"date\"";
+        let (_, calls) = parse_tool_calls(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments["command"], "date");
+    }
+
+    #[test]
+    fn parse_arguments_value_repairs_string_arguments() {
+        let raw = serde_json::json!("{path: 'notes.md',}");
+        assert_eq!(
+            parse_arguments_value(Some(&raw)),
+            serde_json::json!({"path": "notes.md"})
+        );
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // TG4 (inline): parse_tool_calls robustness — malformed/edge-case inputs
     // Prevents: Pattern 4 issues #746, #418, #777, #848
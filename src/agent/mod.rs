@@ -1,10 +1,13 @@
 #[allow(clippy::module_inception)]
 pub mod agent;
 pub mod classifier;
+pub mod deadline;
 pub mod dispatcher;
+pub mod json_repair;
 pub mod loop_;
 pub mod memory_loader;
 pub mod prompt;
+pub mod tool_batches;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,135 @@
+//! Dependency ordering for tool calls emitted in one model turn.
+//!
+//! Calls are split into consecutive batches: everything inside a batch is
+//! independent and may run concurrently, while a call that depends on an
+//! earlier one in the same turn (a read after a write to the same file, two
+//! shell commands, ...) starts a new batch. Running batches in order keeps
+//! results in the order the model asked for them.
+
+use serde_json::Value;
+
+/// Default cap on calls from one turn that run at the same time.
+/// Used when `max_parallel_tools` is configured as zero.
+pub const DEFAULT_MAX_PARALLEL_TOOLS: usize = 4;
+
+/// What a tool call touches, as far as ordering is concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Access {
+    /// No shared state; never waits for other calls.
+    Independent,
+    Read(Resource),
+    Write(Resource),
+    /// Shared, unknowable state (shell, browser session, git): runs alone.
+    Exclusive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Resource {
+    Path(String),
+    Memory,
+}
+
+fn path_arg(args: &Value) -> Option<String> {
+    args.get("path")
+        .and_then(Value::as_str)
+        .map(|path| path.trim().trim_start_matches("./").to_string())
+        .filter(|path| !path.is_empty())
+}
+
+fn classify(tool: &str, args: &Value) -> Access {
+    match tool {
+        "shell" | "browser" | "git_operations" | "delegate" | "run_code" => Access::Exclusive,
+        "file_write" | "file_edit" => path_arg(args)
+            .map(|path| Access::Write(Resource::Path(path)))
+            .unwrap_or(Access::Exclusive),
+        "file_read" | "pdf_read" | "image_info" | "ocr" => path_arg(args)
+            .map(|path| Access::Read(Resource::Path(path)))
+            .unwrap_or(Access::Independent),
+        "memory_store" | "memory_forget" => Access::Write(Resource::Memory),
+        "memory_recall" => Access::Read(Resource::Memory),
+        _ => Access::Independent,
+    }
+}
+
+fn conflicts(a: &Access, b: &Access) -> bool {
+    match (a, b) {
+        (Access::Exclusive, _) | (_, Access::Exclusive) => true,
+        (Access::Write(x), Access::Write(y) | Access::Read(y))
+        | (Access::Read(x), Access::Write(y)) => x == y,
+        _ => false,
+    }
+}
+
+/// Group `(tool name, arguments)` calls into ordered batches of indices.
+///
+/// Batches are contiguous, so flattening them gives `0..calls.len()`.
+pub fn plan_batches<'a>(calls: impl IntoIterator<Item = (&'a str, &'a Value)>) -> Vec<Vec<usize>> {
+    let mut batches: Vec<Vec<usize>> = Vec::new();
+    let mut current: Vec<(usize, Access)> = Vec::new();
+
+    for (idx, (tool, args)) in calls.into_iter().enumerate() {
+        let access = classify(tool, args);
+        if current
+            .iter()
+            .any(|(_, earlier)| conflicts(earlier, &access))
+        {
+            batches.push(current.drain(..).map(|(i, _)| i).collect());
+        }
+        current.push((idx, access));
+    }
+    if !current.is_empty() {
+        batches.push(current.into_iter().map(|(i, _)| i).collect());
+    }
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn plan(calls: &[(&str, Value)]) -> Vec<Vec<usize>> {
+        plan_batches(calls.iter().map(|(name, args)| (*name, args)))
+    }
+
+    #[test]
+    fn independent_calls_share_one_batch() {
+        let calls = [
+            ("web_search", json!({"query": "a"})),
+            ("file_read", json!({"path": "a.txt"})),
+            ("file_read", json!({"path": "b.txt"})),
+            ("http_request", json!({"url": "https://example.com"})),
+        ];
+        assert_eq!(plan(&calls), vec![vec![0, 1, 2, 3]]);
+    }
+
+    #[test]
+    fn read_after_write_to_same_path_waits() {
+        let calls = [
+            ("file_write", json!({"path": "./a.txt", "content": "x"})),
+            ("file_read", json!({"path": "b.txt"})),
+            ("file_read", json!({"path": "a.txt"})),
+        ];
+        assert_eq!(plan(&calls), vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn exclusive_tools_run_alone_in_order() {
+        let calls = [
+            ("file_read", json!({"path": "a.txt"})),
+            ("shell", json!({"command": "cargo build"})),
+            ("shell", json!({"command": "cargo test"})),
+            ("memory_store", json!({"key": "k", "content": "v"})),
+            ("memory_recall", json!({"query": "k"})),
+        ];
+        assert_eq!(
+            plan(&calls),
+            vec![vec![0], vec![1], vec![2], vec![3], vec![4]]
+        );
+    }
+
+    #[test]
+    fn empty_input_has_no_batches() {
+        assert!(plan(&[]).is_empty());
+    }
+}
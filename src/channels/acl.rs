@@ -0,0 +1,287 @@
+//! Access control for inbound channel messages.
+//!
+//! Every channel already filters senders with its own `allowed_users`; the
+//! lists in `[channels_config.acl]` apply across all channels on top of
+//! that, so a bot invited to a public group can be limited to known users
+//! and chats (or keep known abusers out) in one place. Messages that fail
+//! the check are dropped before they reach the agent. User entries follow
+//! the person: an account linked in `[[channels_config.identities]]` stands
+//! for all of that person's accounts.
+
+use super::identity::IdentityMap;
+use super::traits::ChannelMessage;
+use crate::config::ChannelAclConfig;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Why a message was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AclDenial {
+    DeniedUser,
+    UserNotAllowed,
+    DeniedChat,
+    ChatNotAllowed,
+}
+
+impl fmt::Display for AclDenial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::DeniedUser => "sender is in deny_users",
+            Self::UserNotAllowed => "sender is not in allow_users",
+            Self::DeniedChat => "chat is in deny_chats",
+            Self::ChatNotAllowed => "chat is not in allow_chats",
+        })
+    }
+}
+
+/// Compiled `[channels_config.acl]` lists.
+pub struct ChannelAcl {
+    allow_users: Vec<String>,
+    deny_users: Vec<String>,
+    allow_chats: Vec<String>,
+    deny_chats: Vec<String>,
+}
+
+impl ChannelAcl {
+    /// Build the ACL, expanding `role:<name>` and identity entries. Returns
+    /// `None` when no list is configured.
+    pub fn from_config(
+        config: &ChannelAclConfig,
+        identities: Option<&IdentityMap>,
+    ) -> Option<Self> {
+        if !config.is_active() {
+            return None;
+        }
+        let users = |entries: &[String]| {
+            expand_identities(expand_roles(entries, &config.roles), identities)
+        };
+        Some(Self {
+            allow_users: users(&config.allow_users),
+            deny_users: users(&config.deny_users),
+            allow_chats: normalize(&config.allow_chats),
+            deny_chats: normalize(&config.deny_chats),
+        })
+    }
+
+    /// `None` when `msg` may be answered, otherwise why it may not.
+    pub fn check(&self, msg: &ChannelMessage) -> Option<AclDenial> {
+        let user_matches = |entry: &String| user_entry_matches(entry, &msg.channel, &msg.sender);
+        let chat_matches =
+            |entry: &String| chat_entry_matches(entry, &msg.channel, &msg.reply_target);
+
+        if self.deny_users.iter().any(user_matches) {
+            return Some(AclDenial::DeniedUser);
+        }
+        if self.deny_chats.iter().any(chat_matches) {
+            return Some(AclDenial::DeniedChat);
+        }
+        if !self.allow_users.is_empty() && !self.allow_users.iter().any(user_matches) {
+            return Some(AclDenial::UserNotAllowed);
+        }
+        if !self.allow_chats.is_empty() && !self.allow_chats.iter().any(chat_matches) {
+            return Some(AclDenial::ChatNotAllowed);
+        }
+        None
+    }
+}
+
+fn normalize(entries: &[String]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+/// Replace `role:<name>` with the role's members. Unknown roles match
+/// nobody and are logged.
+fn expand_roles(entries: &[String], roles: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let mut expanded = Vec::new();
+    for entry in normalize(entries) {
+        match entry.strip_prefix("role:") {
+            Some(role) => match roles.get(role.trim()) {
+                Some(members) => expanded.extend(normalize(members)),
+                None => tracing::warn!("channels_config.acl: unknown role `{role}`"),
+            },
+            None => expanded.push(entry),
+        }
+    }
+    expanded
+}
+
+/// Replace `identity:<name>` with the identity's accounts, and add the other
+/// accounts of each linked `"<channel>:<user>"` entry.
+fn expand_identities(entries: Vec<String>, identities: Option<&IdentityMap>) -> Vec<String> {
+    let mut expanded = Vec::new();
+    for entry in entries {
+        let linked = match entry.strip_prefix("identity:") {
+            Some(name) => {
+                let accounts = identities.map_or(&[][..], |map| map.accounts_named(name.trim()));
+                if accounts.is_empty() {
+                    tracing::warn!("channels_config.acl: unknown identity `{name}`");
+                }
+                accounts.to_vec()
+            }
+            None => {
+                let account = entry.split_once(':');
+                let linked = identities
+                    .zip(account)
+                    .map(|(map, (channel, user))| map.accounts_of(channel, user))
+                    .unwrap_or_default();
+                expanded.push(entry);
+                linked
+            }
+        };
+        for (channel, user) in linked {
+            let account = format!("{channel}:{user}");
+            if !expanded.contains(&account) {
+                expanded.push(account);
+            }
+        }
+    }
+    expanded
+}
+
+/// `"<user>"`, `"<channel>:<user>"` or `"<channel>:*"`. User IDs may contain
+/// `:` themselves (Matrix), so both readings are tried.
+fn user_entry_matches(entry: &str, channel: &str, sender: &str) -> bool {
+    if entry == sender {
+        return true;
+    }
+    entry
+        .strip_prefix(channel)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .is_some_and(|user| user == "*" || user == sender)
+}
+
+/// `"<reply_target>"` or `"<channel>:<reply_target>"`; a chat entry also
+/// covers its threads and topics (`<chat>:<thread>` reply targets).
+fn chat_entry_matches(entry: &str, channel: &str, reply_target: &str) -> bool {
+    let in_chat = |chat: &str| {
+        reply_target == chat
+            || reply_target
+                .strip_prefix(chat)
+                .is_some_and(|rest| rest.starts_with(':'))
+    };
+    in_chat(entry)
+        || entry
+            .strip_prefix(channel)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .is_some_and(in_chat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(channel: &str, sender: &str, reply_target: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "1".into(),
+            sender: sender.into(),
+            reply_target: reply_target.into(),
+            content: "hi".into(),
+            channel: channel.into(),
+            timestamp: 0,
+            thread_ts: None,
+        }
+    }
+
+    #[test]
+    fn empty_config_builds_no_acl() {
+        assert!(ChannelAcl::from_config(&ChannelAclConfig::default(), None).is_none());
+    }
+
+    #[test]
+    fn deny_users_wins_over_allow_users() {
+        let acl = ChannelAcl::from_config(
+            &ChannelAclConfig {
+                allow_users: vec!["telegram:*".into()],
+                deny_users: vec!["telegram:mallory".into()],
+                ..ChannelAclConfig::default()
+            },
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(acl.check(&message("telegram", "alice", "1")), None);
+        assert_eq!(
+            acl.check(&message("telegram", "mallory", "1")),
+            Some(AclDenial::DeniedUser)
+        );
+        assert_eq!(
+            acl.check(&message("discord", "alice", "1")),
+            Some(AclDenial::UserNotAllowed)
+        );
+    }
+
+    #[test]
+    fn roles_expand_to_their_members() {
+        let acl = ChannelAclConfig {
+            allow_users: vec!["role:admins".into(), "role:missing".into()],
+            roles: HashMap::from([(
+                "admins".to_string(),
+                vec!["@alice:example.org".to_string(), "discord:42".to_string()],
+            )]),
+            ..ChannelAclConfig::default()
+        };
+        let acl = ChannelAcl::from_config(&acl, None).unwrap();
+
+        assert_eq!(
+            acl.check(&message("matrix", "@alice:example.org", "!room")),
+            None
+        );
+        assert_eq!(acl.check(&message("discord", "42", "c")), None);
+        assert_eq!(
+            acl.check(&message("telegram", "42", "c")),
+            Some(AclDenial::UserNotAllowed)
+        );
+    }
+
+    #[test]
+    fn chat_entries_cover_threads_in_that_chat() {
+        let acl = ChannelAcl::from_config(
+            &ChannelAclConfig {
+                allow_chats: vec!["telegram:-100123".into()],
+                deny_chats: vec!["C999".into()],
+                ..ChannelAclConfig::default()
+            },
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(acl.check(&message("telegram", "a", "-100123")), None);
+        assert_eq!(acl.check(&message("telegram", "a", "-100123:77")), None);
+        assert_eq!(
+            acl.check(&message("telegram", "a", "-1001234")),
+            Some(AclDenial::ChatNotAllowed)
+        );
+        assert_eq!(
+            acl.check(&message("slack", "a", "C999")),
+            Some(AclDenial::DeniedChat)
+        );
+    }
+
+    #[test]
+    fn user_entries_follow_linked_identities() {
+        let identities = IdentityMap::from_config(&[crate::config::UserIdentityConfig {
+            name: "mallory".into(),
+            accounts: vec!["telegram:666".into(), "slack:U666".into()],
+        }])
+        .unwrap();
+        let acl = ChannelAcl::from_config(
+            &ChannelAclConfig {
+                deny_users: vec!["telegram:666".into()],
+                allow_users: vec!["identity:mallory".into(), "discord:42".into()],
+                ..ChannelAclConfig::default()
+            },
+            Some(&identities),
+        )
+        .unwrap();
+
+        assert_eq!(
+            acl.check(&message("slack", "U666", "C1")),
+            Some(AclDenial::DeniedUser)
+        );
+        assert_eq!(acl.check(&message("discord", "42", "c")), None);
+    }
+}
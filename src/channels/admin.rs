@@ -0,0 +1,265 @@
+//! Runtime control of a running daemon for the gateway admin API.
+//!
+//! `start_channels` registers its runtime here so `/api/admin/*` can list,
+//! export and flush conversations and re-apply the config file without a
+//! restart.
+//! Paused channels are kept here too; both the channel dispatcher and the
+//! gateway's webhook handlers drop messages for them until they are
+//! enabled again. Nothing here survives a restart.
+
+use super::export::{ConversationExport, ExportFormat};
+use super::{runtime_config_path, runtime_config_store, ChannelRuntimeContext};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// One conversation held in memory by the channel runtime.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionInfo {
+    /// History key, as accepted by [`flush_session`].
+    pub key: String,
+    pub channel: String,
+    pub messages: usize,
+    pub chars: usize,
+    /// Provider chosen with `/models` for this conversation, if any.
+    pub provider: Option<String>,
+    /// Model chosen with `/model` for this conversation, if any.
+    pub model: Option<String>,
+}
+
+fn runtime_slot() -> &'static Mutex<Option<Arc<ChannelRuntimeContext>>> {
+    static RUNTIME: OnceLock<Mutex<Option<Arc<ChannelRuntimeContext>>>> = OnceLock::new();
+    RUNTIME.get_or_init(|| Mutex::new(None))
+}
+
+fn disabled_channels() -> &'static Mutex<BTreeSet<String>> {
+    static DISABLED: OnceLock<Mutex<BTreeSet<String>>> = OnceLock::new();
+    DISABLED.get_or_init(|| Mutex::new(BTreeSet::new()))
+}
+
+fn runtime() -> Option<Arc<ChannelRuntimeContext>> {
+    runtime_slot()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+pub(super) fn register(ctx: &Arc<ChannelRuntimeContext>) {
+    *runtime_slot().lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::clone(ctx));
+}
+
+/// Names of the channels the runtime listens on, sorted; `None` when it is
+/// not running.
+pub fn channel_names() -> Option<Vec<String>> {
+    let ctx = runtime()?;
+    let mut names: Vec<String> = ctx.channels_by_name.keys().cloned().collect();
+    names.sort();
+    Some(names)
+}
+
+/// Conversations with history, sorted by key; `None` when the channel
+/// runtime is not running.
+pub fn sessions() -> Option<Vec<SessionInfo>> {
+    let ctx = runtime()?;
+    let histories = ctx
+        .conversation_histories
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let routes = ctx
+        .route_overrides
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let routes: HashMap<&str, (&str, &str)> = routes
+        .iter()
+        .map(|(key, route)| {
+            (
+                key.as_str(),
+                (route.provider.as_str(), route.model.as_str()),
+            )
+        })
+        .collect();
+    let channels: Vec<&str> = ctx.channels_by_name.keys().map(String::as_str).collect();
+    Some(session_infos(
+        histories
+            .iter()
+            .map(|(key, turns)| (key.as_str(), turns.iter().map(|t| t.content.as_str()))),
+        &routes,
+        &channels,
+    ))
+}
+
+fn session_infos<'a, I>(
+    histories: impl Iterator<Item = (&'a str, I)>,
+    routes: &HashMap<&str, (&str, &str)>,
+    channels: &[&str],
+) -> Vec<SessionInfo>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut sessions: Vec<SessionInfo> = histories
+        .map(|(key, turns)| {
+            let (messages, chars) = turns.fold((0, 0), |(messages, chars), content| {
+                (messages + 1, chars + content.chars().count())
+            });
+            let route = routes.get(key);
+            SessionInfo {
+                key: key.to_string(),
+                channel: channel_of(key, channels).to_string(),
+                messages,
+                chars,
+                provider: route.map(|(provider, _)| (*provider).to_string()),
+                model: route.map(|(_, model)| (*model).to_string()),
+            }
+        })
+        .collect();
+    sessions.sort_by(|a, b| a.key.cmp(&b.key));
+    sessions
+}
+
+/// Channel a history key (`<channel>_[<thread>_]<sender>`) belongs to.
+/// Channel names may contain `_` themselves, so the longest match wins.
+fn channel_of<'a>(key: &'a str, channels: &[&'a str]) -> &'a str {
+    channels
+        .iter()
+        .filter(|name| {
+            key.strip_prefix(**name)
+                .is_some_and(|rest| rest.starts_with('_'))
+        })
+        .max_by_key(|name| name.len())
+        .copied()
+        .unwrap_or_else(|| key.split('_').next().unwrap_or(key))
+}
+
+/// Drop the history and model choice of conversation `key`, like `/new`.
+/// Returns whether it had any; `None` when the runtime is not running.
+pub fn flush_session(key: &str) -> Option<bool> {
+    let ctx = runtime()?;
+    let had_history = ctx
+        .conversation_histories
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(key)
+        .is_some();
+    let had_route = ctx
+        .route_overrides
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(key)
+        .is_some();
+    Some(had_history || had_route)
+}
+
+/// Render conversation `key` like `/export` does. An error when it has no
+/// history; `None` when the runtime is not running.
+pub fn export_session(key: &str, format: ExportFormat) -> Option<Result<String>> {
+    let ctx = runtime()?;
+    let history = ctx
+        .conversation_histories
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(key)
+        .cloned();
+    let Some(history) = history else {
+        return Some(Err(anyhow::anyhow!("Unknown session")));
+    };
+    let channels: Vec<&str> = ctx.channels_by_name.keys().map(String::as_str).collect();
+    let export = ConversationExport::new(
+        key,
+        channel_of(key, &channels),
+        &history,
+        &ctx.workspace_dir,
+    );
+    Some(Ok(export.render(format)))
+}
+
+/// Re-read the config file now instead of on the next message, even when
+/// it looks unchanged, and switch to its default provider and model.
+/// `None` when the runtime is not running.
+pub async fn reload_config() -> Option<Result<()>> {
+    let ctx = runtime()?;
+    if let Some(config_path) = runtime_config_path(&ctx) {
+        if let Some(state) = runtime_config_store()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&config_path)
+        {
+            state.last_applied_stamp = None;
+        }
+    }
+    Some(super::maybe_apply_runtime_config_update(&ctx).await)
+}
+
+/// Pause or resume answering messages on `channel`. Returns whether the
+/// state changed.
+pub fn set_channel_enabled(channel: &str, enabled: bool) -> bool {
+    let mut disabled = disabled_channels()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if enabled {
+        disabled.remove(channel)
+    } else {
+        disabled.insert(channel.to_string())
+    }
+}
+
+/// Whether messages on `channel` are currently dropped.
+pub fn is_channel_disabled(channel: &str) -> bool {
+    disabled_channels()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains(channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_infos_count_history_and_attach_routes() {
+        let histories = [
+            ("whatsapp_web_1555", vec!["hi", "hello there"]),
+            ("telegram_99_alice", vec!["ping"]),
+        ];
+        let routes = HashMap::from([("telegram_99_alice", ("openrouter", "gpt-4o"))]);
+        let sessions = session_infos(
+            histories
+                .iter()
+                .map(|(key, turns)| (*key, turns.iter().copied())),
+            &routes,
+            &["telegram", "whatsapp", "whatsapp_web"],
+        );
+
+        assert_eq!(
+            sessions,
+            vec![
+                SessionInfo {
+                    key: "telegram_99_alice".into(),
+                    channel: "telegram".into(),
+                    messages: 1,
+                    chars: 4,
+                    provider: Some("openrouter".into()),
+                    model: Some("gpt-4o".into()),
+                },
+                SessionInfo {
+                    key: "whatsapp_web_1555".into(),
+                    channel: "whatsapp_web".into(),
+                    messages: 2,
+                    chars: 13,
+                    provider: None,
+                    model: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_channels_toggle() {
+        assert!(!is_channel_disabled("admin-test"));
+        assert!(set_channel_enabled("admin-test", false));
+        assert!(!set_channel_enabled("admin-test", false));
+        assert!(is_channel_disabled("admin-test"));
+        assert!(set_channel_enabled("admin-test", true));
+        assert!(!is_channel_disabled("admin-test"));
+    }
+}
@@ -0,0 +1,188 @@
+//! Generated alt text for outbound images.
+//!
+//! Before a reply is delivered to a channel that can carry alt text, every
+//! `[IMAGE:target]` marker without a description is shown to a vision model
+//! and rewritten as `[IMAGE:target|description]`. The channel then attaches
+//! the description as the image's alt text. Failures leave the marker as is.
+
+use crate::config::{AltTextConfig, MultimodalConfig};
+use crate::providers::{ChatMessage, Provider};
+use std::fmt::Write;
+
+const ALT_TEXT_PROMPT: &str = "You write alt text for images. Describe the image in one short, factual sentence for someone who cannot see it. For charts and screenshots, state what is shown and the key takeaway. Reply with the description only.";
+
+/// Channels whose `send` attaches alt text from `[IMAGE:target|alt]` markers.
+pub fn supports_alt_text(channel_name: &str) -> bool {
+    matches!(channel_name, "mastodon" | "matrix" | "slack")
+}
+
+/// Byte range and target of each image marker that has no alt text yet.
+fn undescribed_images(content: &str) -> Vec<(std::ops::Range<usize>, String)> {
+    let mut found = Vec::new();
+    let mut cursor = 0;
+    while let Some(open_rel) = content[cursor..].find('[') {
+        let open = cursor + open_rel;
+        let Some(close_rel) = content[open..].find(']') else {
+            break;
+        };
+        let close = open + close_rel;
+        let marker = &content[open + 1..close];
+        if let Some((kind, target)) = marker.split_once(':') {
+            let target = target.trim();
+            if matches!(kind.trim().to_ascii_uppercase().as_str(), "IMAGE" | "PHOTO")
+                && !target.is_empty()
+                && !target.contains('|')
+            {
+                found.push((open..close + 1, target.to_string()));
+            }
+        }
+        cursor = close + 1;
+    }
+    found
+}
+
+/// Collapse a model reply into a single-line description that fits a marker.
+fn clean_description(raw: &str, max_chars: usize) -> Option<String> {
+    let single_line = raw
+        .replace(['[', ']', '|'], "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let trimmed = single_line.trim().trim_matches('"').trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some(crate::util::truncate_with_ellipsis(
+        trimmed,
+        max_chars.max(1),
+    ))
+}
+
+async fn describe_image(
+    provider: &dyn Provider,
+    model: &str,
+    target: &str,
+    multimodal: &MultimodalConfig,
+) -> anyhow::Result<String> {
+    let messages = vec![
+        ChatMessage::system(ALT_TEXT_PROMPT),
+        ChatMessage::user(format!("[IMAGE:{target}]")),
+    ];
+    let prepared = crate::multimodal::prepare_messages_for_provider(&messages, multimodal).await?;
+    provider
+        .chat_with_history(&prepared.messages, model, 0.2)
+        .await
+}
+
+/// Add generated alt text to the image markers in `content`.
+pub async fn add_alt_text(
+    provider: &dyn Provider,
+    model: &str,
+    content: &str,
+    multimodal: &MultimodalConfig,
+) -> String {
+    let config: &AltTextConfig = &multimodal.alt_text;
+    let images = undescribed_images(content);
+    if images.is_empty() {
+        return content.to_string();
+    }
+    if !provider.supports_vision() {
+        tracing::debug!("Skipping alt text: provider has no vision support");
+        return content.to_string();
+    }
+
+    let mut out = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for (range, target) in images {
+        out.push_str(&content[cursor..range.start]);
+        let description = match describe_image(provider, model, &target, multimodal).await {
+            Ok(raw) => clean_description(&raw, config.max_chars),
+            Err(e) => {
+                tracing::warn!("Failed to generate alt text for {target}: {e}");
+                None
+            }
+        };
+        match description {
+            Some(alt) => {
+                let _ = write!(out, "[IMAGE:{target}|{alt}]");
+            }
+            None => out.push_str(&content[range.clone()]),
+        }
+        cursor = range.end;
+    }
+    out.push_str(&content[cursor..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::ProviderCapabilities;
+    use std::sync::Mutex;
+
+    struct VisionProvider {
+        seen: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for VisionProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                vision: true,
+                ..ProviderCapabilities::default()
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.seen.lock().unwrap().push(message.to_string());
+            Ok("  A bar chart [of] monthly\nsales. ".to_string())
+        }
+    }
+
+    #[test]
+    fn finds_only_undescribed_images() {
+        let content = "See [IMAGE:/tmp/a.png] and [PHOTO:/tmp/b.jpg|done] [DOCUMENT:/tmp/c.pdf]";
+        let found = undescribed_images(content);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1, "/tmp/a.png");
+        assert_eq!(&content[found[0].0.clone()], "[IMAGE:/tmp/a.png]");
+    }
+
+    #[test]
+    fn clean_description_strips_marker_syntax() {
+        assert_eq!(
+            clean_description("\"A [red] | car\"\n", 200).as_deref(),
+            Some("A red car")
+        );
+        assert_eq!(clean_description("  ", 200), None);
+        assert_eq!(clean_description("abcdefgh", 3).as_deref(), Some("abc..."));
+    }
+
+    #[tokio::test]
+    async fn add_alt_text_rewrites_image_markers() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let image = tmp.path().join("chart.png");
+        std::fs::write(&image, [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]).unwrap();
+        let provider = VisionProvider {
+            seen: Mutex::new(Vec::new()),
+        };
+        let content = format!("Here you go [IMAGE:{}]", image.display());
+
+        let out = add_alt_text(&provider, "m", &content, &MultimodalConfig::default()).await;
+
+        assert_eq!(
+            out,
+            format!(
+                "Here you go [IMAGE:{}|A bar chart of monthly sales.]",
+                image.display()
+            )
+        );
+        assert!(provider.seen.lock().unwrap()[0].contains("data:image/png;base64,"));
+    }
+}
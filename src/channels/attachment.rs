@@ -10,6 +10,10 @@ pub enum AttachmentKind {
     Video,
     Audio,
     Voice,
+    /// A sticker (`.webp`, or `.tgs`/`.webm` on Telegram).
+    Sticker,
+    /// An animated GIF or short looping video.
+    Animation,
 }
 
 impl AttachmentKind {
@@ -20,6 +24,8 @@ impl AttachmentKind {
             "VIDEO" => Some(Self::Video),
             "AUDIO" => Some(Self::Audio),
             "VOICE" => Some(Self::Voice),
+            "STICKER" => Some(Self::Sticker),
+            "ANIMATION" | "GIF" => Some(Self::Animation),
             _ => None,
         }
     }
@@ -31,20 +37,34 @@ impl AttachmentKind {
             Self::Video => "VIDEO",
             Self::Audio => "AUDIO",
             Self::Voice => "VOICE",
+            Self::Sticker => "STICKER",
+            Self::Animation => "ANIMATION",
         }
     }
+
+    /// Whether the attachment is shown as a picture. Channels without native
+    /// sticker or animation messages send those as images.
+    pub fn is_image_like(&self) -> bool {
+        matches!(self, Self::Image | Self::Sticker | Self::Animation)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Attachment {
     pub kind: AttachmentKind,
     pub target: String,
+    /// Alt text from a `[IMAGE:target|alt text]` marker.
+    pub alt: Option<String>,
+    /// Text from the marker's line, set by [`parse_captioned_attachments`].
+    pub caption: Option<String>,
 }
 
 /// Parse attachment markers from message content.
 /// Returns (cleaned_text, attachments).
 ///
-/// Recognizes patterns: [IMAGE:path], [DOCUMENT:url], [VIDEO:path], [AUDIO:path], [VOICE:path]
+/// Recognizes patterns: [IMAGE:path], [DOCUMENT:url], [VIDEO:path], [AUDIO:path], [VOICE:path],
+/// [STICKER:path], [ANIMATION:url]
+/// An optional `|alt text` suffix on the target is returned as `alt`.
 pub fn parse_attachment_markers(message: &str) -> (String, Vec<Attachment>) {
     let mut cleaned = String::with_capacity(message.len());
     let mut attachments = Vec::new();
@@ -69,6 +89,10 @@ pub fn parse_attachment_markers(message: &str) -> (String, Vec<Attachment>) {
 
         let parsed = marker.split_once(':').and_then(|(kind, target)| {
             let kind = AttachmentKind::from_marker(kind)?;
+            let (target, alt) = match target.split_once('|') {
+                Some((target, alt)) => (target, Some(alt.trim()).filter(|a| !a.is_empty())),
+                None => (target, None),
+            };
             let target = target.trim();
             if target.is_empty() {
                 return None;
@@ -76,6 +100,8 @@ pub fn parse_attachment_markers(message: &str) -> (String, Vec<Attachment>) {
             Some(Attachment {
                 kind,
                 target: target.to_string(),
+                alt: alt.map(str::to_string),
+                caption: None,
             })
         });
 
@@ -91,11 +117,170 @@ pub fn parse_attachment_markers(message: &str) -> (String, Vec<Attachment>) {
     (cleaned.trim().to_string(), attachments)
 }
 
+/// Parse attachment markers and keep the text next to each marker as its
+/// caption, so channels can send "Check this report [IMAGE:/tmp/r.png]" as one
+/// captioned photo instead of a stray "Check this report" message.
+///
+/// The rest of a marker's line (other markers removed) becomes the caption of
+/// the first attachment on that line and is dropped from the returned text.
+/// Lines longer than `max_caption_chars` stay in the text instead.
+pub fn parse_captioned_attachments(
+    message: &str,
+    max_caption_chars: usize,
+) -> (String, Vec<Attachment>) {
+    let mut remaining = Vec::new();
+    let mut attachments = Vec::new();
+
+    for line in message.lines() {
+        let (line_text, mut line_attachments) = parse_attachment_markers(line);
+        if line_attachments.is_empty() {
+            remaining.push(line.to_string());
+            continue;
+        }
+
+        let caption = line_text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if caption.chars().count() > max_caption_chars {
+            remaining.push(caption);
+        } else if !caption.is_empty() {
+            line_attachments[0].caption = Some(caption);
+        }
+        attachments.extend(line_attachments);
+    }
+
+    (remaining.join("\n").trim().to_string(), attachments)
+}
+
 /// Check if a target is a local file path (vs URL).
 pub fn is_local_path(target: &str) -> bool {
     !target.starts_with("http://") && !target.starts_with("https://")
 }
 
+/// A remote attachment downloaded so it can be uploaded as a file.
+#[derive(Debug, Clone)]
+pub struct RemoteFile {
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+/// Whether a response `content_type` fits an attachment of `kind`. Generic
+/// binary types are accepted for every kind; HTML never is, since it is
+/// usually a login or error page served instead of the file.
+pub fn content_type_matches(kind: &AttachmentKind, content_type: Option<&str>) -> bool {
+    let Some(essence) = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|ct| ct.trim().to_ascii_lowercase())
+        .filter(|ct| !ct.is_empty())
+    else {
+        return true;
+    };
+    if essence == "application/octet-stream" || essence == "binary/octet-stream" {
+        return true;
+    }
+    if essence == "text/html" || essence == "application/xhtml+xml" {
+        return false;
+    }
+    let (top, sub) = essence.split_once('/').unwrap_or((essence.as_str(), ""));
+    match kind {
+        AttachmentKind::Image => top == "image",
+        AttachmentKind::Sticker => {
+            top == "image" || essence == "video/webm" || sub == "x-tgsticker"
+        }
+        AttachmentKind::Animation => essence == "image/gif" || top == "video",
+        AttachmentKind::Video => top == "video",
+        AttachmentKind::Audio | AttachmentKind::Voice => top == "audio" || sub == "ogg",
+        AttachmentKind::Document => true,
+    }
+}
+
+/// Download a remote attachment for channels that can only upload files.
+/// Fails when the server does not answer with a file of the expected type or
+/// the body exceeds `max_bytes`.
+pub async fn download_remote(
+    client: &reqwest::Client,
+    url: &str,
+    kind: &AttachmentKind,
+    max_bytes: u64,
+) -> anyhow::Result<RemoteFile> {
+    let mut response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        anyhow::bail!("Download of {url} failed with status {}", response.status());
+    }
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        anyhow::bail!("{url} is larger than {max_bytes} bytes");
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    if !content_type_matches(kind, content_type.as_deref()) {
+        anyhow::bail!(
+            "{url} returned {} instead of a {} attachment",
+            content_type.as_deref().unwrap_or_default(),
+            kind.marker_name().to_ascii_lowercase()
+        );
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > max_bytes {
+            anyhow::bail!("{url} is larger than {max_bytes} bytes");
+        }
+    }
+    Ok(RemoteFile {
+        file_name: file_name_for_url(url, content_type.as_deref()),
+        content_type,
+        bytes,
+    })
+}
+
+/// Keep a downloaded file name safe to store on disk.
+pub(crate) fn sanitize_file_name(raw: &str) -> String {
+    let name: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('.').to_string();
+    if name.is_empty() {
+        "file".to_string()
+    } else {
+        name
+    }
+}
+
+/// Derive a file name for a downloaded URL, adding an extension from the
+/// response content type when the URL has none.
+pub fn file_name_for_url(url: &str, content_type: Option<&str>) -> String {
+    let last_segment = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .and_then(|mut segments| segments.next_back().map(str::to_string))
+        })
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "file".to_string());
+    let mut name = sanitize_file_name(&last_segment);
+    if std::path::Path::new(&name).extension().is_none() {
+        let extension = content_type
+            .and_then(|ct| ct.split(';').next())
+            .and_then(|ct| mime_guess::get_mime_extensions_str(ct.trim()))
+            .and_then(|exts| exts.first());
+        if let Some(ext) = extension {
+            name.push('.');
+            name.push_str(ext);
+        }
+    }
+    name
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,6 +305,34 @@ mod tests {
         assert_eq!(attachments[1].kind, AttachmentKind::Document);
     }
 
+    #[test]
+    fn parse_alt_text_suffix() {
+        let (text, attachments) =
+            parse_attachment_markers("Chart [IMAGE:/tmp/c.png|Bar chart of monthly sales]");
+        assert_eq!(text, "Chart");
+        assert_eq!(attachments[0].target, "/tmp/c.png");
+        assert_eq!(
+            attachments[0].alt.as_deref(),
+            Some("Bar chart of monthly sales")
+        );
+
+        let (_, attachments) = parse_attachment_markers("[IMAGE:/tmp/c.png|  ]");
+        assert_eq!(attachments[0].alt, None);
+    }
+
+    #[test]
+    fn parse_sticker_and_gif_markers() {
+        let (text, attachments) = parse_attachment_markers(
+            "Yay [GIF:https://example.com/party.gif] [STICKER:/tmp/s.webp]",
+        );
+        assert_eq!(text, "Yay");
+        assert_eq!(attachments[0].kind, AttachmentKind::Animation);
+        assert_eq!(attachments[1].kind, AttachmentKind::Sticker);
+        assert_eq!(attachments[0].kind.marker_name(), "ANIMATION");
+        assert!(attachments.iter().all(|a| a.kind.is_image_like()));
+        assert!(!AttachmentKind::Video.is_image_like());
+    }
+
     #[test]
     fn parse_preserves_non_markers() {
         let (text, attachments) = parse_attachment_markers("Hello [world] and [not:a:marker]");
@@ -127,6 +340,33 @@ mod tests {
         assert_eq!(attachments.len(), 0);
     }
 
+    #[test]
+    fn parse_captioned_keeps_line_text_with_the_attachment() {
+        let (text, attachments) = parse_captioned_attachments(
+            "Here you go.\nCheck this report [IMAGE:/tmp/r.png] and the summary\n[DOCUMENT:/tmp/s.pdf]",
+            1024,
+        );
+        assert_eq!(text, "Here you go.");
+        assert_eq!(
+            attachments[0].caption.as_deref(),
+            Some("Check this report and the summary")
+        );
+        assert_eq!(attachments[1].caption, None);
+    }
+
+    #[test]
+    fn parse_captioned_leaves_long_lines_in_text() {
+        let (text, attachments) =
+            parse_captioned_attachments("A rather long sentence [IMAGE:/tmp/a.png]", 10);
+        assert_eq!(text, "A rather long sentence");
+        assert_eq!(attachments[0].caption, None);
+
+        let (_, attachments) =
+            parse_captioned_attachments("Both [IMAGE:/tmp/a.png] [IMAGE:/tmp/b.png]", 1024);
+        assert_eq!(attachments[0].caption.as_deref(), Some("Both"));
+        assert_eq!(attachments[1].caption, None);
+    }
+
     #[test]
     fn is_local_path_detection() {
         assert!(is_local_path("/tmp/file.png"));
@@ -135,4 +375,42 @@ mod tests {
         assert!(!is_local_path("http://example.com/file.png"));
         assert!(!is_local_path("https://example.com/file.png"));
     }
+
+    #[test]
+    fn file_name_for_url_sanitizes_and_adds_extension() {
+        assert_eq!(
+            file_name_for_url("https://cdn.example.com/x/Q3 report.pdf?sig=1", None),
+            "Q3_20report.pdf"
+        );
+        assert_eq!(
+            file_name_for_url("https://example.com/download", Some("application/pdf")),
+            "download.pdf"
+        );
+        assert_eq!(file_name_for_url("https://example.com/", None), "file");
+    }
+
+    #[test]
+    fn content_type_must_fit_the_attachment_kind() {
+        assert!(content_type_matches(
+            &AttachmentKind::Image,
+            Some("image/png")
+        ));
+        assert!(content_type_matches(
+            &AttachmentKind::Voice,
+            Some("audio/ogg; codecs=opus")
+        ));
+        assert!(content_type_matches(
+            &AttachmentKind::Video,
+            Some("application/octet-stream")
+        ));
+        assert!(content_type_matches(&AttachmentKind::Document, None));
+        assert!(!content_type_matches(
+            &AttachmentKind::Image,
+            Some("video/mp4")
+        ));
+        assert!(!content_type_matches(
+            &AttachmentKind::Document,
+            Some("text/html; charset=utf-8")
+        ));
+    }
 }
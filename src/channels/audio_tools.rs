@@ -0,0 +1,150 @@
+//! Local audio tooling shared by voice transcription and speech synthesis:
+//! temporary files and bounded runs of helper binaries (ffmpeg, whisper.cpp,
+//! Piper, edge-tts).
+
+use anyhow::{bail, Context, Result};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Upper bound for a single helper binary run.
+const LOCAL_TOOL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Run a local helper binary and return its stdout, failing on a non-zero
+/// exit status or when it runs longer than [`LOCAL_TOOL_TIMEOUT`].
+pub async fn run_local_tool(command: tokio::process::Command, name: &str) -> Result<Vec<u8>> {
+    run_local_tool_with_input(command, name, None).await
+}
+
+/// Like [`run_local_tool`], feeding `input` to the binary's stdin.
+pub async fn run_local_tool_with_input(
+    mut command: tokio::process::Command,
+    name: &str,
+    input: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    let stdin = if input.is_some() {
+        std::process::Stdio::piped()
+    } else {
+        std::process::Stdio::null()
+    };
+    command
+        .stdin(stdin)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+
+    let run = async {
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to run {name}"))?;
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            tokio::io::AsyncWriteExt::write_all(&mut stdin, input)
+                .await
+                .with_context(|| format!("Failed to write input to {name}"))?;
+        }
+        child
+            .wait_with_output()
+            .await
+            .with_context(|| format!("Failed to run {name}"))
+    };
+    let output = tokio::time::timeout(LOCAL_TOOL_TIMEOUT, run)
+        .await
+        .with_context(|| format!("{name} timed out after {}s", LOCAL_TOOL_TIMEOUT.as_secs()))??;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{name} exited with {}: {}", output.status, stderr.trim());
+    }
+    Ok(output.stdout)
+}
+
+/// Re-encode audio or video with ffmpeg. `codec_args` go between the input
+/// and the output file, e.g. `["-c:a", "libopus"]`.
+pub async fn ffmpeg_convert(
+    audio_data: &[u8],
+    input_extension: &str,
+    output_extension: &str,
+    codec_args: &[&str],
+) -> Result<Vec<u8>> {
+    let input_extension = if input_extension.is_empty() {
+        "bin"
+    } else {
+        input_extension
+    };
+    let input = TempAudioFile::write(input_extension, audio_data).await?;
+    let output = TempAudioFile::reserve(output_extension);
+
+    let mut command = tokio::process::Command::new("ffmpeg");
+    command
+        .args(["-nostdin", "-loglevel", "error", "-y", "-i"])
+        .arg(&input.path)
+        .args(codec_args)
+        .arg(&output.path);
+    run_local_tool(command, "ffmpeg").await?;
+
+    tokio::fs::read(&output.path)
+        .await
+        .context("Failed to read ffmpeg output")
+}
+
+/// Temporary audio file, removed when dropped.
+pub struct TempAudioFile {
+    pub path: PathBuf,
+}
+
+impl TempAudioFile {
+    /// Unique path in the system temp directory; nothing is created yet.
+    pub fn reserve(extension: &str) -> Self {
+        let name = format!("zeroclaw-voice-{}.{extension}", uuid::Uuid::new_v4());
+        Self {
+            path: std::env::temp_dir().join(name),
+        }
+    }
+
+    pub async fn write(extension: &str, data: &[u8]) -> Result<Self> {
+        let file = Self::reserve(extension);
+        tokio::fs::write(&file.path, data)
+            .await
+            .context("Failed to write temporary audio file")?;
+        Ok(file)
+    }
+}
+
+impl Drop for TempAudioFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn temp_audio_file_is_removed_on_drop() {
+        let file = TempAudioFile::write("ogg", b"data").await.unwrap();
+        let path = file.path.clone();
+        assert!(path.exists());
+        assert!(path.to_string_lossy().ends_with(".ogg"));
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn run_local_tool_reports_missing_binary() {
+        let command = tokio::process::Command::new("zeroclaw-no-such-binary");
+        let err = run_local_tool(command, "zeroclaw-no-such-binary")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to run"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_local_tool_feeds_stdin() {
+        let command = tokio::process::Command::new("cat");
+        let out = run_local_tool_with_input(command, "cat", Some(b"hello"))
+            .await
+            .unwrap();
+        assert_eq!(out, b"hello");
+    }
+}
@@ -0,0 +1,199 @@
+//! Relaying messages between chats.
+//!
+//! Each `[[channels_config.bridges]]` entry copies inbound messages from a
+//! source channel or chat to a recipient on another channel, attributed to
+//! the original sender. With `transform` set, the text is first rewritten
+//! by the provider (translation, summarization). Bridged messages are not
+//! answered by the agent unless the bridge sets `answer = true`. The bot
+//! ignores its own messages, so two opposite bridges do not loop.
+
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::BridgeConfig;
+use crate::providers::Provider;
+
+const TRANSFORM_PROMPT: &str = "You relay chat messages from one room to another. Apply the instructions below to the message you are given and reply with the resulting message only, without commentary.";
+
+/// A validated bridge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bridge {
+    channel: String,
+    chat: Option<String>,
+    pub to_channel: String,
+    pub to: String,
+    transform: Option<String>,
+    pub answer: bool,
+}
+
+impl Bridge {
+    fn from_config(config: &BridgeConfig) -> Option<Self> {
+        let (channel, chat) = match config.from.trim().split_once(':') {
+            Some((channel, chat)) => (channel.trim(), Some(chat.trim().to_string())),
+            None => (config.from.trim(), None),
+        };
+        let to_channel = config.to_channel.trim();
+        let to = config.to.trim();
+        if channel.is_empty() || to_channel.is_empty() || to.is_empty() {
+            return None;
+        }
+        Some(Self {
+            channel: channel.to_string(),
+            chat: chat.filter(|chat| !chat.is_empty()),
+            to_channel: to_channel.to_string(),
+            to: to.to_string(),
+            transform: config
+                .transform
+                .as_deref()
+                .map(str::trim)
+                .filter(|transform| !transform.is_empty())
+                .map(str::to_string),
+            answer: config.answer,
+        })
+    }
+
+    /// Whether `msg` comes from this bridge's source.
+    pub fn matches(&self, msg: &ChannelMessage) -> bool {
+        if msg.channel != self.channel {
+            return false;
+        }
+        self.chat.as_deref().is_none_or(|chat| {
+            msg.reply_target == chat
+                || msg
+                    .reply_target
+                    .strip_prefix(chat)
+                    .is_some_and(|rest| rest.starts_with(':'))
+        })
+    }
+}
+
+/// The relayed text: the source channel and sender, then the content.
+fn relayed_text(msg: &ChannelMessage, content: &str) -> String {
+    format!("[{}] {}: {}", msg.channel, msg.sender, content.trim())
+}
+
+/// All configured bridges.
+pub struct ChannelBridges {
+    bridges: Vec<Bridge>,
+}
+
+impl ChannelBridges {
+    /// Build the bridges, skipping incomplete entries. Returns `None` when no
+    /// bridge is configured.
+    pub fn from_config(configs: &[BridgeConfig]) -> Option<Self> {
+        let bridges: Vec<Bridge> = configs
+            .iter()
+            .filter_map(|config| {
+                let bridge = Bridge::from_config(config);
+                if bridge.is_none() {
+                    tracing::warn!(
+                        "channels_config.bridges: ignoring incomplete bridge from `{}`",
+                        config.from
+                    );
+                }
+                bridge
+            })
+            .collect();
+        (!bridges.is_empty()).then_some(Self { bridges })
+    }
+
+    /// Bridges whose source is the chat `msg` came from.
+    pub fn matching<'a>(&'a self, msg: &'a ChannelMessage) -> impl Iterator<Item = &'a Bridge> {
+        self.bridges
+            .iter()
+            .filter(move |bridge| bridge.matches(msg))
+    }
+}
+
+/// Relay `msg` over `bridge` to `target`. A failed transform falls back to
+/// the original text so a provider outage does not drop messages.
+pub async fn relay(
+    bridge: &Bridge,
+    msg: &ChannelMessage,
+    target: &dyn Channel,
+    provider: &dyn Provider,
+    model: &str,
+) -> anyhow::Result<()> {
+    let content = match bridge.transform.as_deref() {
+        Some(instructions) => {
+            let system = format!("{TRANSFORM_PROMPT}\n\nInstructions: {instructions}");
+            match provider
+                .chat_with_system(Some(&system), &msg.content, model, 0.2)
+                .await
+            {
+                Ok(transformed) if !transformed.trim().is_empty() => transformed,
+                Ok(_) => msg.content.clone(),
+                Err(e) => {
+                    tracing::warn!(
+                        "Bridge transform failed for {} -> {}: {e}",
+                        msg.channel,
+                        bridge.to_channel
+                    );
+                    msg.content.clone()
+                }
+            }
+        }
+        None => msg.content.clone(),
+    };
+
+    target
+        .send(&SendMessage::new(relayed_text(msg, &content), &bridge.to))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(channel: &str, reply_target: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            reply_target: reply_target.into(),
+            content: " hello there ".into(),
+            channel: channel.into(),
+            timestamp: 0,
+            thread_ts: None,
+        }
+    }
+
+    fn bridge(from: &str) -> BridgeConfig {
+        BridgeConfig {
+            from: from.into(),
+            to_channel: "discord".into(),
+            to: "123".into(),
+            transform: Some("  ".into()),
+            answer: false,
+        }
+    }
+
+    #[test]
+    fn incomplete_bridges_are_skipped() {
+        assert!(ChannelBridges::from_config(&[]).is_none());
+        let mut missing_target = bridge("telegram");
+        missing_target.to = " ".into();
+        assert!(ChannelBridges::from_config(&[missing_target]).is_none());
+
+        let bridges = ChannelBridges::from_config(&[bridge("telegram:-100")]).unwrap();
+        assert_eq!(bridges.bridges[0].transform, None);
+    }
+
+    #[test]
+    fn source_matches_channel_and_chat_with_threads() {
+        let bridges =
+            ChannelBridges::from_config(&[bridge("telegram:-100"), bridge("slack")]).unwrap();
+
+        assert_eq!(bridges.matching(&message("telegram", "-100")).count(), 1);
+        assert_eq!(bridges.matching(&message("telegram", "-100:7")).count(), 1);
+        assert_eq!(bridges.matching(&message("telegram", "-1001")).count(), 0);
+        assert_eq!(bridges.matching(&message("slack", "C1")).count(), 1);
+        assert_eq!(bridges.matching(&message("discord", "-100")).count(), 0);
+    }
+
+    #[test]
+    fn relayed_text_names_sender_and_channel() {
+        let msg = message("telegram", "-100");
+        assert_eq!(
+            relayed_text(&msg, &msg.content),
+            "[telegram] alice: hello there"
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! `[BUTTONS:...]` reply markers.
+//!
+//! The agent offers quick choices by adding `[BUTTONS:Yes|No|Cancel]`
+//! markers to a reply. Channels with tappable buttons (Telegram inline
+//! keyboards, Lark/Feishu cards, DingTalk ActionCards) render each marker as
+//! one row and send the tapped label back as the user's next message.
+
+/// Extract `[BUTTONS:Yes|No|Cancel]` markers from a reply.
+///
+/// Each marker becomes one keyboard row; labels are split on `|` and blank
+/// labels are dropped. Markers without any label are left in the text.
+pub fn parse_button_markers(message: &str) -> (String, Vec<Vec<String>>) {
+    let mut cleaned = String::with_capacity(message.len());
+    let mut rows = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(open_rel) = message[cursor..].find('[') {
+        let open = cursor + open_rel;
+        let Some(close_rel) = message[open..].find(']') else {
+            break;
+        };
+        let close = open + close_rel;
+        cleaned.push_str(&message[cursor..open]);
+
+        let row = message[open + 1..close]
+            .split_once(':')
+            .filter(|(kind, _)| kind.trim().eq_ignore_ascii_case("BUTTONS"))
+            .map(|(_, labels)| {
+                labels
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|label| !label.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|row| !row.is_empty());
+
+        match row {
+            Some(row) => rows.push(row),
+            None => cleaned.push_str(&message[open..=close]),
+        }
+        cursor = close + 1;
+    }
+    cleaned.push_str(&message[cursor..]);
+
+    if rows.is_empty() {
+        return (message.to_string(), rows);
+    }
+    (cleaned.trim().to_string(), rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_anywhere_in_the_text_become_rows() {
+        let (cleaned, rows) = parse_button_markers("Pick one [BUTTONS:A|B] then wait.");
+        assert_eq!(cleaned, "Pick one  then wait.");
+        assert_eq!(rows, vec![vec!["A", "B"]]);
+
+        let (cleaned, rows) = parse_button_markers("[buttons: Go ]");
+        assert_eq!(cleaned, "");
+        assert_eq!(rows, vec![vec!["Go"]]);
+    }
+}
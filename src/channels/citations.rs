@@ -0,0 +1,384 @@
+//! Citation mode post-processing for channel replies.
+//!
+//! With `[agent.citations].enabled`, the agent tags the sources behind an
+//! answer with inline markers: `[CITE:<url-or-path>]`, `[QUERY:<query used>]`
+//! and optionally `[CONFIDENCE:high|medium|low]`. Before delivery the markers
+//! are collected into a compact footer formatted for the target channel, and
+//! cited local paths and attachment markers are checked so a reply never
+//! points at a file that does not exist. A persona's `citations` setting
+//! turns the mode on or off while that persona is active.
+
+use super::attachment::{is_local_path, AttachmentKind};
+use super::persona::ActivePersona;
+use crate::config::CitationConfig;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// System prompt addendum injected when citation mode is enabled.
+pub const CITATION_INSTRUCTIONS: &str = "Citation mode is on. When your answer relies on tool lookups:\n\
+     - Cite each source with a standalone marker: [CITE:<url-or-file-path>]\n\
+     - Cite searches or database queries you ran with [QUERY:<query text>]\n\
+     - If you are not fully certain, add [CONFIDENCE:high], [CONFIDENCE:medium], or [CONFIDENCE:low]\n\
+     - Only cite URLs and files you actually read. Markers are turned into a sources footer.";
+
+/// Whether citation mode is on for a turn: the active persona's `citations`
+/// setting wins over `[agent.citations].enabled`.
+pub fn citation_mode_enabled(config: &CitationConfig, persona: Option<&ActivePersona>) -> bool {
+    persona
+        .and_then(|persona| persona.profile.citations)
+        .unwrap_or(config.enabled)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Citation {
+    Source(String),
+    Query(String),
+    /// Fallback when a tool-backed reply cited nothing.
+    Tools(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+impl Confidence {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "high" => Some(Self::High),
+            "medium" | "med" => Some(Self::Medium),
+            "low" => Some(Self::Low),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+        }
+    }
+}
+
+/// Result of scanning a reply for citation and attachment markers.
+#[derive(Debug, Default)]
+struct ScannedReply {
+    text: String,
+    citations: Vec<Citation>,
+    confidence: Option<Confidence>,
+    missing: Vec<String>,
+}
+
+fn resolve_local_path(target: &str, workspace_dir: &Path) -> PathBuf {
+    let target = target.strip_prefix("file://").unwrap_or(target);
+    let expanded = PathBuf::from(shellexpand::tilde(target).into_owned());
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        workspace_dir.join(expanded)
+    }
+}
+
+fn local_target_exists(target: &str, workspace_dir: &Path) -> bool {
+    resolve_local_path(target, workspace_dir).exists()
+}
+
+fn push_unique(citations: &mut Vec<Citation>, citation: Citation) {
+    if !citations.contains(&citation) {
+        citations.push(citation);
+    }
+}
+
+/// Pull citation markers out of `response` and, when `verify_paths` is set,
+/// drop attachment markers whose local file is missing. Unrelated bracketed
+/// text is preserved verbatim.
+fn scan_reply(response: &str, workspace_dir: &Path, verify_paths: bool) -> ScannedReply {
+    let mut scanned = ScannedReply::default();
+    let mut cursor = 0;
+
+    while cursor < response.len() {
+        let Some(open_rel) = response[cursor..].find('[') else {
+            scanned.text.push_str(&response[cursor..]);
+            break;
+        };
+        let open = cursor + open_rel;
+        scanned.text.push_str(&response[cursor..open]);
+
+        let Some(close_rel) = response[open..].find(']') else {
+            scanned.text.push_str(&response[open..]);
+            break;
+        };
+        let close = open + close_rel;
+        let marker = &response[open + 1..close];
+        cursor = close + 1;
+
+        let Some((kind, value)) = marker.split_once(':') else {
+            scanned.text.push_str(&response[open..=close]);
+            continue;
+        };
+        let value = value.trim();
+
+        match kind.trim().to_ascii_uppercase().as_str() {
+            "CITE" | "SOURCE" if !value.is_empty() => {
+                if verify_paths
+                    && is_local_path(value)
+                    && !local_target_exists(value, workspace_dir)
+                {
+                    scanned.missing.push(value.to_string());
+                } else {
+                    push_unique(&mut scanned.citations, Citation::Source(value.to_string()));
+                }
+            }
+            "QUERY" if !value.is_empty() => {
+                push_unique(&mut scanned.citations, Citation::Query(value.to_string()));
+            }
+            "CONFIDENCE" if Confidence::parse(value).is_some() => {
+                scanned.confidence = Confidence::parse(value);
+            }
+            other
+                if verify_paths
+                    && AttachmentKind::from_marker(other).is_some()
+                    && !value.is_empty()
+                    && is_local_path(value)
+                    && !local_target_exists(value, workspace_dir) =>
+            {
+                scanned.missing.push(value.to_string());
+            }
+            _ => scanned.text.push_str(&response[open..=close]),
+        }
+    }
+
+    scanned.text = scanned.text.trim().to_string();
+    scanned
+}
+
+/// Channels whose renderer understands `**bold**` and `` `code` ``.
+fn supports_markdown(channel: &str) -> bool {
+    matches!(
+        channel,
+        "telegram"
+            | "discord"
+            | "mattermost"
+            | "matrix"
+            | "lark"
+            | "dingtalk"
+            | "qq"
+            | "nextcloud_talk"
+            | "zulip"
+    )
+}
+
+fn render_footer(
+    channel: &str,
+    citations: &[Citation],
+    confidence: Option<Confidence>,
+    missing: &[String],
+) -> String {
+    let markdown = supports_markdown(channel);
+    let slack = channel == "slack";
+    let code = |value: &str| {
+        if markdown || slack {
+            format!("`{value}`")
+        } else {
+            value.to_string()
+        }
+    };
+    let (header, bullet) = if markdown {
+        ("**Sources**", "•")
+    } else if slack {
+        ("*Sources*", "•")
+    } else {
+        ("Sources:", "-")
+    };
+
+    let mut footer = String::new();
+    if !citations.is_empty() {
+        footer.push_str(header);
+        for citation in citations {
+            let line = match citation {
+                Citation::Source(target) if is_local_path(target) => code(target),
+                Citation::Source(target) => target.clone(),
+                Citation::Query(query) => format!("query: {}", code(query)),
+                Citation::Tools(names) => format!("tools: {}", names.join(", ")),
+            };
+            let _ = write!(footer, "\n{bullet} {line}");
+        }
+    }
+
+    if let Some(confidence) = confidence {
+        if !footer.is_empty() {
+            footer.push('\n');
+        }
+        let _ = write!(footer, "Confidence: {}", confidence.label());
+    }
+
+    for path in missing {
+        if !footer.is_empty() {
+            footer.push('\n');
+        }
+        let _ = write!(footer, "⚠️ Omitted missing file: {}", code(path));
+    }
+
+    footer
+}
+
+/// Apply citation mode to a reply before delivery.
+///
+/// `tools_used` lists the tools invoked while producing the reply; when the
+/// model cited nothing for a tool-backed answer, the tool names are listed so
+/// the reader still sees where the answer came from.
+pub fn apply_citation_mode(
+    channel: &str,
+    response: &str,
+    tools_used: &[String],
+    workspace_dir: &Path,
+    verify_paths: bool,
+) -> String {
+    let mut scanned = scan_reply(response, workspace_dir, verify_paths);
+    if scanned.citations.is_empty() && !tools_used.is_empty() {
+        scanned.citations.push(Citation::Tools(tools_used.to_vec()));
+    }
+
+    let footer = render_footer(
+        channel,
+        &scanned.citations,
+        scanned.confidence,
+        &scanned.missing,
+    );
+    if footer.is_empty() {
+        return scanned.text;
+    }
+    if scanned.text.is_empty() {
+        return footer;
+    }
+    format!("{}\n\n{footer}", scanned.text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn collects_citations_into_channel_footer() {
+        let tmp = TempDir::new().unwrap();
+        let reply = apply_citation_mode(
+            "telegram",
+            "Rust 1.87 is current. [CITE:https://blog.rust-lang.org] [QUERY:latest rust release]",
+            &["web_search".into()],
+            tmp.path(),
+            true,
+        );
+        assert_eq!(
+            reply,
+            "Rust 1.87 is current.\n\n**Sources**\n• https://blog.rust-lang.org\n• query: `latest rust release`"
+        );
+    }
+
+    #[test]
+    fn plain_channels_use_plain_footer() {
+        let tmp = TempDir::new().unwrap();
+        let reply = apply_citation_mode(
+            "email",
+            "Done. [CITE:https://example.com] [CONFIDENCE:low]",
+            &[],
+            tmp.path(),
+            true,
+        );
+        assert!(reply.ends_with("Sources:\n- https://example.com\nConfidence: low"));
+    }
+
+    #[test]
+    fn missing_local_citation_is_dropped_and_reported() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("notes.md"), "x").unwrap();
+        let reply = apply_citation_mode(
+            "slack",
+            "See notes. [CITE:notes.md] [CITE:/nonexistent/plan.md]",
+            &[],
+            tmp.path(),
+            true,
+        );
+        assert!(reply.contains("*Sources*\n• `notes.md`"));
+        assert!(reply.contains("⚠️ Omitted missing file: `/nonexistent/plan.md`"));
+        assert!(!reply.contains("• `/nonexistent/plan.md`"));
+    }
+
+    #[test]
+    fn missing_attachment_marker_is_removed_but_existing_kept() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("chart.png"), "png").unwrap();
+        let reply = apply_citation_mode(
+            "discord",
+            "Charts: [IMAGE:chart.png] [IMAGE:/nonexistent/gone.png]",
+            &[],
+            tmp.path(),
+            true,
+        );
+        assert!(reply.contains("[IMAGE:chart.png]"));
+        assert!(!reply.contains("[IMAGE:/nonexistent/gone.png]"));
+        assert!(reply.contains("Omitted missing file: `/nonexistent/gone.png`"));
+    }
+
+    #[test]
+    fn verification_can_be_disabled() {
+        let tmp = TempDir::new().unwrap();
+        let reply = apply_citation_mode(
+            "cli",
+            "[IMAGE:/nonexistent/gone.png] [CITE:/nonexistent/a.md]",
+            &[],
+            tmp.path(),
+            false,
+        );
+        assert!(reply.contains("[IMAGE:/nonexistent/gone.png]"));
+        assert!(reply.contains("- /nonexistent/a.md"));
+    }
+
+    #[test]
+    fn tool_backed_reply_without_citations_lists_tools() {
+        let tmp = TempDir::new().unwrap();
+        let reply = apply_citation_mode(
+            "cli",
+            "It is sunny.",
+            &["weather".into(), "http_request".into()],
+            tmp.path(),
+            true,
+        );
+        assert_eq!(
+            reply,
+            "It is sunny.\n\nSources:\n- tools: weather, http_request"
+        );
+    }
+
+    #[test]
+    fn reply_without_tools_or_markers_is_unchanged() {
+        let tmp = TempDir::new().unwrap();
+        let reply = apply_citation_mode("cli", "Hello [world]", &[], tmp.path(), true);
+        assert_eq!(reply, "Hello [world]");
+    }
+
+    #[test]
+    fn active_persona_overrides_citation_mode() {
+        let persona = |citations| ActivePersona {
+            name: "researcher".into(),
+            profile: crate::config::PersonaConfig {
+                citations,
+                ..crate::config::PersonaConfig::default()
+            },
+        };
+        let off = CitationConfig::default();
+        let on = CitationConfig {
+            enabled: true,
+            ..CitationConfig::default()
+        };
+
+        assert!(!citation_mode_enabled(&off, None));
+        assert!(citation_mode_enabled(&on, None));
+        assert!(citation_mode_enabled(&off, Some(&persona(Some(true)))));
+        assert!(!citation_mode_enabled(&on, Some(&persona(Some(false)))));
+        assert!(citation_mode_enabled(&on, Some(&persona(None))));
+    }
+}
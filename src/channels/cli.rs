@@ -1,14 +1,300 @@
+//! CLI channel: an interactive terminal REPL.
+//!
+//! On a terminal, input has line editing with history (↑/↓, kept across
+//! sessions with [`CliChannel::with_history_file`]) and multi-line messages:
+//! end a line with `\` to continue it, or put `"""` on its own line to open
+//! and close a block. Replies are rendered from Markdown to ANSI styles, and
+//! `[IMAGE:...]` files are shown inline on terminals that speak the iTerm2 or
+//! kitty graphics protocol; other attachments are printed as their path.
+//! Piped input and output stay plain text.
+
+use super::attachment::{is_local_path, parse_attachment_markers, Attachment, AttachmentKind};
+use super::markdown::{self, Dialect};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
-use tokio::io::{self, AsyncBufReadExt, BufReader};
+use base64::Engine as _;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Input lines remembered in the history.
+const MAX_HISTORY_ENTRIES: usize = 1_000;
+/// Larger images are printed as a path instead of inline.
+const MAX_INLINE_IMAGE_BYTES: u64 = 10 * 1024 * 1024;
+/// Kitty graphics payloads are sent in chunks of at most this many bytes.
+const KITTY_CHUNK_LEN: usize = 4096;
+/// A line with only this opens or closes a multi-line block.
+const BLOCK_DELIMITER: &str = "\"\"\"";
+
+/// Terminal graphics protocol for inline images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Graphics {
+    /// iTerm2 inline images (also WezTerm).
+    Iterm2,
+    /// kitty graphics protocol (also Ghostty).
+    Kitty,
+}
+
+/// Graphics protocol of the terminal described by the environment `var`.
+fn detect_graphics(var: impl Fn(&str) -> Option<String>) -> Option<Graphics> {
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+    if term_program == "iTerm.app"
+        || term_program == "WezTerm"
+        || var("LC_TERMINAL").is_some_and(|t| t == "iTerm2")
+    {
+        return Some(Graphics::Iterm2);
+    }
+    if term_program == "ghostty"
+        || var("KITTY_WINDOW_ID").is_some()
+        || var("TERM").is_some_and(|t| t.contains("kitty"))
+    {
+        return Some(Graphics::Kitty);
+    }
+    None
+}
+
+fn iterm2_image(name: &str, bytes: &[u8]) -> String {
+    let engine = base64::engine::general_purpose::STANDARD;
+    format!(
+        "\x1b]1337;File=name={};size={};inline=1:{}\x07",
+        engine.encode(name),
+        bytes.len(),
+        engine.encode(bytes)
+    )
+}
+
+fn kitty_image(png: &[u8]) -> String {
+    let payload = base64::engine::general_purpose::STANDARD.encode(png);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK_LEN).collect();
+    let mut out = String::with_capacity(payload.len() + chunks.len() * 16);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let control = if i == 0 {
+            format!("a=T,f=100,m={more}")
+        } else {
+            format!("m={more}")
+        };
+        let _ = write!(
+            out,
+            "\x1b_G{control};{}\x1b\\",
+            String::from_utf8_lossy(chunk)
+        );
+    }
+    out
+}
+
+/// Escape sequence showing the image at `target`, or `None` when it is not
+/// a readable local image of acceptable size.
+fn inline_image(target: &str, graphics: Graphics) -> Option<String> {
+    if !is_local_path(target) {
+        return None;
+    }
+    let path = Path::new(target);
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_INLINE_IMAGE_BYTES {
+        return None;
+    }
+    let bytes = std::fs::read(path).ok()?;
+    match graphics {
+        Graphics::Iterm2 => {
+            let name = path.file_name()?.to_string_lossy();
+            Some(iterm2_image(&name, &bytes))
+        }
+        // kitty only decodes PNG itself.
+        Graphics::Kitty => {
+            let image = image::load_from_memory(&bytes).ok()?;
+            let mut png = std::io::Cursor::new(Vec::new());
+            image.write_to(&mut png, image::ImageFormat::Png).ok()?;
+            Some(kitty_image(&png.into_inner()))
+        }
+    }
+}
+
+fn render_attachment(attachment: &Attachment, graphics: Option<Graphics>) -> String {
+    let target = attachment.target.trim();
+    if attachment.kind == AttachmentKind::Image {
+        if let Some(image) = graphics.and_then(|graphics| inline_image(target, graphics)) {
+            return image;
+        }
+    }
+    match &attachment.alt {
+        Some(alt) => format!("📎 {}: {target} ({alt})", attachment.kind.marker_name()),
+        None => format!("📎 {}: {target}", attachment.kind.marker_name()),
+    }
+}
+
+/// A reply as shown on a terminal: ANSI-styled text, then its attachments.
+/// Blank lines around the reply are kept.
+fn render_reply(content: &str, graphics: Option<Graphics>) -> String {
+    let leading = &content[..content.len() - content.trim_start_matches('\n').len()];
+    let trailing = &content[content.trim_end_matches('\n').len()..];
+    let (text, attachments) = parse_attachment_markers(content);
+
+    let mut out = markdown::render(&text, Dialect::Ansi);
+    for attachment in &attachments {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&render_attachment(attachment, graphics));
+    }
+    format!("{leading}{out}{trailing}")
+}
+
+/// Input history, most recent first.
+struct InputHistory {
+    entries: VecDeque<String>,
+    path: Option<PathBuf>,
+}
+
+impl InputHistory {
+    fn load(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_deref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|text| {
+                text.lines()
+                    .rev()
+                    .filter(|line| !line.trim().is_empty())
+                    .take(MAX_HISTORY_ENTRIES)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { entries, path }
+    }
+
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut text = String::new();
+        for entry in self.entries.iter().rev() {
+            text.push_str(entry);
+            text.push('\n');
+        }
+        std::fs::write(path, text)
+    }
+}
+
+impl dialoguer::History<String> for InputHistory {
+    fn read(&self, pos: usize) -> Option<String> {
+        self.entries.get(pos).cloned()
+    }
+
+    fn write(&mut self, val: &String) {
+        let line = val.trim();
+        if line.is_empty() || self.entries.front().is_some_and(|last| last == line) {
+            return;
+        }
+        self.entries.push_front(line.to_string());
+        self.entries.truncate(MAX_HISTORY_ENTRIES);
+        if let Some(path) = &self.path {
+            if let Err(e) = self.save(path) {
+                tracing::debug!("Failed to save CLI history to {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+/// Joins input lines into one message: a line ending in `\` continues on
+/// the next one, and lines between two `"""` lines are kept as typed.
+#[derive(Debug, Default)]
+struct MultiLineInput {
+    lines: Vec<String>,
+    in_block: bool,
+}
+
+impl MultiLineInput {
+    /// Whether the next line continues the current message.
+    fn is_continuing(&self) -> bool {
+        self.in_block || !self.lines.is_empty()
+    }
+
+    /// Add a line; returns the message once it is complete.
+    fn push(&mut self, line: &str) -> Option<String> {
+        if line.trim() == BLOCK_DELIMITER {
+            self.in_block = !self.in_block;
+            return if self.in_block { None } else { self.take() };
+        }
+        if self.in_block {
+            self.lines.push(line.to_string());
+            return None;
+        }
+        if let Some(continued) = line.strip_suffix('\\') {
+            self.lines.push(continued.to_string());
+            return None;
+        }
+        self.lines.push(line.to_string());
+        self.take()
+    }
+
+    /// The lines collected so far, if any.
+    fn take(&mut self) -> Option<String> {
+        self.in_block = false;
+        if self.lines.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut self.lines).join("\n"))
+    }
+}
+
 /// CLI channel — stdin/stdout, always available, zero deps
-pub struct CliChannel;
+#[derive(Clone)]
+pub struct CliChannel {
+    history: Arc<Mutex<InputHistory>>,
+}
 
 impl CliChannel {
     pub fn new() -> Self {
-        Self
+        Self {
+            history: Arc::new(Mutex::new(InputHistory::load(None))),
+        }
+    }
+
+    /// Load input history from `path` and save new entries to it.
+    pub fn with_history_file(self, path: PathBuf) -> Self {
+        Self {
+            history: Arc::new(Mutex::new(InputHistory::load(Some(path)))),
+        }
+    }
+
+    /// Read the next message from stdin, blocking. Returns `None` at the
+    /// end of input.
+    pub fn read_message(&self, prompt: &str) -> Option<String> {
+        let interactive = std::io::stdin().is_terminal();
+        let mut input = MultiLineInput::default();
+        loop {
+            let line = if interactive {
+                let prompt = if input.is_continuing() { "..." } else { prompt };
+                self.read_line_interactive(prompt)
+            } else {
+                let mut line = String::new();
+                match std::io::stdin().lock().read_line(&mut line) {
+                    Ok(0) | Err(_) => None,
+                    Ok(_) => Some(line.trim_end_matches(['\n', '\r']).to_string()),
+                }
+            };
+            let Some(line) = line else {
+                return input.take();
+            };
+            if let Some(message) = input.push(&line) {
+                return Some(message);
+            }
+        }
+    }
+
+    fn read_line_interactive(&self, prompt: &str) -> Option<String> {
+        let mut history = self.history.lock();
+        dialoguer::Input::<String>::new()
+            .with_prompt(prompt)
+            .allow_empty(true)
+            .history_with(&mut *history)
+            .interact_text()
+            .ok()
     }
 }
 
@@ -19,41 +305,48 @@ impl Channel for CliChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        println!("{}", message.content);
+        let mut stdout = std::io::stdout().lock();
+        if !stdout.is_terminal() {
+            writeln!(stdout, "{}", message.content)?;
+            return Ok(());
+        }
+        let graphics = detect_graphics(|name| std::env::var(name).ok());
+        writeln!(stdout, "{}", render_reply(&message.content, graphics))?;
+        stdout.flush()?;
         Ok(())
     }
 
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
-        let stdin = io::stdin();
-        let reader = BufReader::new(stdin);
-        let mut lines = reader.lines();
-
-        while let Ok(Some(line)) = lines.next_line().await {
-            let line = line.trim().to_string();
-            if line.is_empty() {
-                continue;
-            }
-            if line == "/quit" || line == "/exit" {
-                break;
-            }
+        let cli = self.clone();
+        tokio::task::spawn_blocking(move || {
+            while let Some(line) = cli.read_message("You") {
+                let line = line.trim().to_string();
+                if line.is_empty() {
+                    continue;
+                }
+                if line == "/quit" || line == "/exit" {
+                    break;
+                }
 
-            let msg = ChannelMessage {
-                id: Uuid::new_v4().to_string(),
-                sender: "user".to_string(),
-                reply_target: "user".to_string(),
-                content: line,
-                channel: "cli".to_string(),
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs(),
-                thread_ts: None,
-            };
+                let msg = ChannelMessage {
+                    id: Uuid::new_v4().to_string(),
+                    sender: "user".to_string(),
+                    reply_target: "user".to_string(),
+                    content: line,
+                    channel: "cli".to_string(),
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    thread_ts: None,
+                };
 
-            if tx.send(msg).await.is_err() {
-                break;
+                if tx.blocking_send(msg).is_err() {
+                    break;
+                }
             }
-        }
+        })
+        .await?;
         Ok(())
     }
 }
@@ -62,6 +355,90 @@ impl Channel for CliChannel {
 mod tests {
     use super::*;
 
+    #[test]
+    fn multi_line_input_joins_continuations_and_blocks() {
+        let mut input = MultiLineInput::default();
+        assert_eq!(input.push("hello").as_deref(), Some("hello"));
+
+        assert_eq!(input.push("first \\"), None);
+        assert!(input.is_continuing());
+        assert_eq!(input.push("second").as_deref(), Some("first \nsecond"));
+
+        assert_eq!(input.push("\"\"\""), None);
+        assert_eq!(input.push("fn main() {"), None);
+        assert_eq!(input.push(""), None);
+        assert_eq!(input.push("}"), None);
+        assert_eq!(input.push("\"\"\"").as_deref(), Some("fn main() {\n\n}"));
+        assert!(!input.is_continuing());
+    }
+
+    #[test]
+    fn history_is_most_recent_first_and_persisted() {
+        use dialoguer::History;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("state").join("cli_history");
+        let mut history = InputHistory::load(Some(path.clone()));
+        history.write(&"first".to_string());
+        history.write(&"second".to_string());
+        history.write(&"second".to_string());
+        history.write(&"  ".to_string());
+        assert_eq!(history.read(0).as_deref(), Some("second"));
+        assert_eq!(history.read(1).as_deref(), Some("first"));
+        assert_eq!(history.read(2), None);
+
+        let reloaded = InputHistory::load(Some(path));
+        assert_eq!(reloaded.entries, ["second", "first"]);
+    }
+
+    #[test]
+    fn detects_terminal_graphics_protocols() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| (*value).to_string())
+            }
+        };
+        assert_eq!(
+            detect_graphics(env(&[("TERM_PROGRAM", "iTerm.app")])),
+            Some(Graphics::Iterm2)
+        );
+        assert_eq!(
+            detect_graphics(env(&[("TERM", "xterm-kitty")])),
+            Some(Graphics::Kitty)
+        );
+        assert_eq!(detect_graphics(env(&[("TERM", "xterm-256color")])), None);
+    }
+
+    #[test]
+    fn reply_renders_markdown_and_attachment_paths() {
+        let rendered = render_reply(
+            "\n**Done**: see [IMAGE:/nonexistent/chart.png|Sales chart]\n",
+            Some(Graphics::Kitty),
+        );
+        assert_eq!(
+            rendered,
+            "\n\x1b[1mDone\x1b[22m: see\n📎 IMAGE: /nonexistent/chart.png (Sales chart)\n"
+        );
+    }
+
+    #[test]
+    fn images_use_the_terminal_protocol() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("dot.png");
+        image::RgbImage::new(1, 1).save(&path).unwrap();
+        let target = path.to_string_lossy();
+
+        let iterm = inline_image(&target, Graphics::Iterm2).unwrap();
+        assert!(iterm.starts_with("\x1b]1337;File=name="));
+        assert!(iterm.ends_with('\x07'));
+
+        let kitty = inline_image(&target, Graphics::Kitty).unwrap();
+        assert!(kitty.starts_with("\x1b_Ga=T,f=100,m=0;"));
+        assert!(inline_image("https://x.dev/a.png", Graphics::Kitty).is_none());
+    }
+
     #[test]
     fn cli_channel_name() {
         assert_eq!(CliChannel::new().name(), "cli");
@@ -0,0 +1,121 @@
+//! Inbound message deduplication.
+//!
+//! Webhook and event-stream platforms (DingTalk, Lark, WhatsApp Cloud,
+//! Twilio, ...) redeliver an event when it is not acknowledged in time,
+//! which would otherwise produce a second reply. Channels that put the
+//! platform message ID into [`ChannelMessage::id`] are deduplicated by it.
+//! The cache is process-wide so the channel dispatcher and the gateway
+//! webhook handlers share it.
+
+use super::traits::ChannelMessage;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// How long a message ID is remembered. Platforms stop retrying well before.
+const DEDUP_TTL: Duration = Duration::from_secs(30 * 60);
+/// Message IDs remembered at once; the oldest are dropped first.
+const MAX_TRACKED_MESSAGES: usize = 10_000;
+
+type SeenMessages = HashMap<(String, String), Instant>;
+
+fn registry() -> &'static Mutex<SeenMessages> {
+    static SEEN: OnceLock<Mutex<SeenMessages>> = OnceLock::new();
+    SEEN.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn check_and_record(seen: &mut SeenMessages, channel: &str, id: &str, now: Instant) -> bool {
+    let key = (channel.to_string(), id.to_string());
+    if seen
+        .get(&key)
+        .is_some_and(|at| now.duration_since(*at) < DEDUP_TTL)
+    {
+        return true;
+    }
+
+    if seen.len() >= MAX_TRACKED_MESSAGES {
+        seen.retain(|_, at| now.duration_since(*at) < DEDUP_TTL);
+    }
+    while seen.len() >= MAX_TRACKED_MESSAGES {
+        let Some(oldest) = seen
+            .iter()
+            .min_by_key(|(_, at)| **at)
+            .map(|(key, _)| key.clone())
+        else {
+            break;
+        };
+        seen.remove(&oldest);
+    }
+    seen.insert(key, now);
+    false
+}
+
+/// Record `msg` and return whether the same message was already received
+/// on its channel recently. Messages without an ID are never duplicates.
+pub fn is_duplicate(msg: &ChannelMessage) -> bool {
+    if msg.id.is_empty() {
+        return false;
+    }
+    check_and_record(
+        &mut registry().lock(),
+        &msg.channel,
+        &msg.id,
+        Instant::now(),
+    )
+}
+
+/// Drop `msg` from the cache so it is processed again when it comes back
+/// through the dispatcher, as when the outage backlog is replayed.
+pub fn forget(msg: &ChannelMessage) {
+    registry()
+        .lock()
+        .remove(&(msg.channel.clone(), msg.id.clone()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(channel: &str, id: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.into(),
+            sender: "alice".into(),
+            reply_target: "alice".into(),
+            content: "hello".into(),
+            channel: channel.into(),
+            timestamp: 0,
+            thread_ts: None,
+        }
+    }
+
+    #[test]
+    fn redelivered_messages_are_duplicates_per_channel() {
+        assert!(!is_duplicate(&message("dedup_test_a", "m1")));
+        assert!(is_duplicate(&message("dedup_test_a", "m1")));
+        assert!(!is_duplicate(&message("dedup_test_b", "m1")));
+        assert!(!is_duplicate(&message("dedup_test_a", "")));
+        assert!(!is_duplicate(&message("dedup_test_a", "")));
+
+        forget(&message("dedup_test_a", "m1"));
+        assert!(!is_duplicate(&message("dedup_test_a", "m1")));
+    }
+
+    #[test]
+    fn expired_and_evicted_ids_are_forgotten() {
+        let mut seen = SeenMessages::new();
+        let start = Instant::now();
+        assert!(!check_and_record(&mut seen, "c", "m1", start));
+        assert!(!check_and_record(
+            &mut seen,
+            "c",
+            "m1",
+            start + DEDUP_TTL + Duration::from_secs(1)
+        ));
+
+        for i in 0..MAX_TRACKED_MESSAGES {
+            check_and_record(&mut seen, "c", &format!("x{i}"), start);
+        }
+        assert!(seen.len() <= MAX_TRACKED_MESSAGES);
+    }
+}
@@ -0,0 +1,358 @@
+//! Degraded mode for provider outages.
+//!
+//! When every configured provider fails (the resilient provider gives up with
+//! "All providers/models failed"), channels stop calling the model. Inbound
+//! messages are acknowledged, persisted to `state/channel_backlog.db`, and
+//! replayed through the normal dispatch path once a periodic probe shows the
+//! provider answering again. A backlog left over from a previous run keeps the
+//! runtime in degraded mode on startup so nothing queued is lost.
+
+use super::traits::ChannelMessage;
+use crate::providers::Provider;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+
+const DEGRADED_COMPONENT: &str = "provider_outage";
+const PROBE_PROMPT: &str = "Reply with OK.";
+
+/// Reply sent for every message queued during an outage.
+pub const OUTAGE_ACK: &str = "⚠️ The AI provider is currently unavailable. Your message is queued and will be answered automatically once service recovers.";
+
+/// Returns true when `err` means every provider in the fallback chain failed.
+pub fn is_provider_outage(err: &anyhow::Error) -> bool {
+    format!("{err:#}").contains("All providers/models failed")
+}
+
+/// Durable FIFO of messages received while degraded.
+pub struct OutageBacklog {
+    db_path: PathBuf,
+}
+
+impl OutageBacklog {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            db_path: workspace_dir.join("state").join("channel_backlog.db"),
+        }
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create state directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(&self.db_path).with_context(|| {
+            format!("Failed to open channel backlog: {}", self.db_path.display())
+        })?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS backlog (
+                seq          INTEGER PRIMARY KEY AUTOINCREMENT,
+                id           TEXT NOT NULL,
+                sender       TEXT NOT NULL,
+                reply_target TEXT NOT NULL,
+                content      TEXT NOT NULL,
+                channel      TEXT NOT NULL,
+                timestamp    INTEGER NOT NULL,
+                thread_ts    TEXT
+            );",
+        )
+        .context("Failed to initialize channel backlog schema")?;
+
+        f(&conn)
+    }
+
+    pub fn push(&self, msg: &ChannelMessage) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO backlog (id, sender, reply_target, content, channel, timestamp, thread_ts)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    msg.id,
+                    msg.sender,
+                    msg.reply_target,
+                    msg.content,
+                    msg.channel,
+                    i64::try_from(msg.timestamp).unwrap_or(i64::MAX),
+                    msg.thread_ts
+                ],
+            )
+            .context("Failed to queue message in channel backlog")?;
+            Ok(())
+        })
+    }
+
+    /// All queued messages with their sequence numbers, oldest first. Rows
+    /// stay queued until [`Self::remove`] is called for them.
+    pub fn pending(&self) -> Result<Vec<(i64, ChannelMessage)>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT seq, id, sender, reply_target, content, channel, timestamp, thread_ts
+                 FROM backlog ORDER BY seq ASC",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                let timestamp: i64 = row.get(6)?;
+                Ok((
+                    row.get(0)?,
+                    ChannelMessage {
+                        id: row.get(1)?,
+                        sender: row.get(2)?,
+                        reply_target: row.get(3)?,
+                        content: row.get(4)?,
+                        channel: row.get(5)?,
+                        timestamp: u64::try_from(timestamp).unwrap_or_default(),
+                        thread_ts: row.get(7)?,
+                    },
+                ))
+            })?;
+            Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+        })
+    }
+
+    /// Delete a message once it has been handed off.
+    pub fn remove(&self, seq: i64) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute("DELETE FROM backlog WHERE seq = ?1", params![seq])
+                .context("Failed to remove message from channel backlog")?;
+            Ok(())
+        })
+    }
+
+    pub fn pending_count(&self) -> Result<usize> {
+        self.with_connection(|conn| {
+            let count: i64 =
+                conn.query_row("SELECT COUNT(*) FROM backlog", [], |row| row.get(0))?;
+            Ok(usize::try_from(count).unwrap_or_default())
+        })
+    }
+}
+
+/// Shared outage state for the channel runtime.
+pub struct DegradedMode {
+    active: AtomicBool,
+    backlog: OutageBacklog,
+    probe_interval: Duration,
+}
+
+impl DegradedMode {
+    pub fn new(workspace_dir: &Path, probe_interval_secs: u64) -> Self {
+        let backlog = OutageBacklog::new(workspace_dir);
+        let pending = backlog.pending_count().unwrap_or_else(|e| {
+            tracing::warn!("Failed to read channel backlog: {e}");
+            0
+        });
+        if pending > 0 {
+            tracing::warn!("Resuming in degraded mode with {pending} queued message(s)");
+        }
+        Self {
+            active: AtomicBool::new(pending > 0),
+            backlog,
+            probe_interval: Duration::from_secs(probe_interval_secs.max(1)),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Switch into degraded mode. Returns true if this call started the outage.
+    pub fn enter(&self) -> bool {
+        let started = !self.active.swap(true, Ordering::AcqRel);
+        if started {
+            tracing::warn!("All providers failed; channels entering degraded mode");
+            crate::health::mark_component_error(DEGRADED_COMPONENT, "all providers failed");
+        }
+        started
+    }
+
+    /// Queue a message for replay after recovery.
+    pub fn queue(&self, msg: &ChannelMessage) -> Result<()> {
+        self.backlog.push(msg)
+    }
+
+    /// Leave degraded mode and hand back the queued messages. They stay in
+    /// the backlog until each one is removed after its handoff.
+    fn recover(&self) -> Result<Vec<(i64, ChannelMessage)>> {
+        self.active.store(false, Ordering::Release);
+        crate::health::mark_component_ok(DEGRADED_COMPONENT);
+        self.backlog.pending()
+    }
+
+    /// Probe the provider while degraded and replay the backlog into the
+    /// dispatch bus once it answers. Exits when the bus closes.
+    pub async fn run_recovery(
+        self: Arc<Self>,
+        provider: Arc<dyn Provider>,
+        model: String,
+        bus: mpsc::WeakSender<ChannelMessage>,
+    ) {
+        let mut interval = tokio::time::interval(self.probe_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+            if !self.is_active() {
+                if bus.strong_count() == 0 {
+                    return;
+                }
+                continue;
+            }
+
+            if let Err(e) = provider
+                .chat_with_system(None, PROBE_PROMPT, &model, 0.0)
+                .await
+            {
+                tracing::debug!("Provider still unavailable: {e}");
+                continue;
+            }
+
+            let Some(tx) = bus.upgrade() else {
+                return;
+            };
+            let messages = match self.recover() {
+                Ok(messages) => messages,
+                Err(e) => {
+                    tracing::warn!("Failed to read channel backlog: {e}");
+                    continue;
+                }
+            };
+            tracing::info!(
+                "Provider recovered; replaying {} queued message(s)",
+                messages.len()
+            );
+
+            for (seq, msg) in messages {
+                // The ID was recorded when the message first arrived.
+                super::dedup::forget(&msg);
+                if tx.send(msg).await.is_err() {
+                    return;
+                }
+                if let Err(e) = self.backlog.remove(seq) {
+                    tracing::warn!("Failed to remove replayed message from backlog: {e}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use tempfile::TempDir;
+
+    fn message(id: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.into(),
+            sender: "alice".into(),
+            reply_target: "chat-1".into(),
+            content: content.into(),
+            channel: "telegram".into(),
+            timestamp: 7,
+            thread_ts: Some("42".into()),
+        }
+    }
+
+    struct OkProvider;
+
+    #[async_trait]
+    impl Provider for OkProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("OK".into())
+        }
+    }
+
+    #[test]
+    fn detects_exhausted_fallback_chain() {
+        let err = anyhow::anyhow!("All providers/models failed. Attempts:\nopenai: 503");
+        assert!(is_provider_outage(&err));
+        assert!(is_provider_outage(&err.context("tool loop failed")));
+        assert!(!is_provider_outage(&anyhow::anyhow!("400 bad request")));
+    }
+
+    #[test]
+    fn backlog_round_trips_messages_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let backlog = OutageBacklog::new(tmp.path());
+        backlog.push(&message("m1", "first")).unwrap();
+        backlog.push(&message("m2", "second")).unwrap();
+        assert_eq!(backlog.pending_count().unwrap(), 2);
+
+        let pending = backlog.pending().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].1.content, "first");
+        assert_eq!(pending[1].1.thread_ts.as_deref(), Some("42"));
+        assert_eq!(backlog.pending_count().unwrap(), 2);
+
+        backlog.remove(pending[0].0).unwrap();
+        let pending = backlog.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].1.content, "second");
+    }
+
+    #[test]
+    fn leftover_backlog_resumes_degraded() {
+        let tmp = TempDir::new().unwrap();
+        assert!(!DegradedMode::new(tmp.path(), 60).is_active());
+
+        OutageBacklog::new(tmp.path())
+            .push(&message("m1", "queued"))
+            .unwrap();
+        assert!(DegradedMode::new(tmp.path(), 60).is_active());
+    }
+
+    #[test]
+    fn enter_reports_only_first_transition() {
+        let tmp = TempDir::new().unwrap();
+        let mode = DegradedMode::new(tmp.path(), 60);
+        assert!(mode.enter());
+        assert!(!mode.enter());
+        assert!(mode.is_active());
+    }
+
+    #[tokio::test]
+    async fn recovery_replays_backlog_into_bus() {
+        let tmp = TempDir::new().unwrap();
+        let mode = Arc::new(DegradedMode::new(tmp.path(), 1));
+        mode.enter();
+        mode.queue(&message("m1", "while down")).unwrap();
+
+        let (tx, mut rx) = mpsc::channel(4);
+        let task = tokio::spawn(Arc::clone(&mode).run_recovery(
+            Arc::new(OkProvider),
+            "model".into(),
+            tx.downgrade(),
+        ));
+
+        let replayed = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(replayed.content, "while down");
+        assert!(!mode.is_active());
+        for _ in 0..50 {
+            if mode.backlog.pending_count().unwrap() == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(mode.backlog.pending_count().unwrap(), 0);
+
+        drop(tx);
+        tokio::time::timeout(Duration::from_secs(5), task)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -0,0 +1,356 @@
+//! Delivery status of outbound channel replies.
+//!
+//! Every agent reply is recorded in `state/channel_deliveries.db` in the
+//! workspace as `accepted` when the platform took it, `retrying` while the
+//! outbound queue retries it, and `failed` when it was dead-lettered.
+//! `zeroclaw channel deliveries` lists the records, so failed sends can be
+//! found without searching the logs.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Records older than this are pruned when new ones are written.
+const RETENTION_DAYS: i64 = 30;
+/// Characters of the reply kept with each record.
+const PREVIEW_CHARS: usize = 80;
+
+/// Where a reply stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// The platform accepted the send.
+    Accepted,
+    /// The first send failed; the outbound queue is retrying it.
+    Retrying,
+    /// The send failed for good and was dead-lettered.
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Accepted => "accepted",
+            Self::Retrying => "retrying",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "accepted" => Some(Self::Accepted),
+            "retrying" => Some(Self::Retrying),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded reply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryRecord {
+    pub id: i64,
+    pub channel: String,
+    pub recipient: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub error: Option<String>,
+    pub preview: String,
+    pub updated_at: String,
+}
+
+/// SQLite-backed delivery status log.
+pub struct DeliveryLog {
+    db_path: PathBuf,
+}
+
+impl DeliveryLog {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            db_path: workspace_dir.join("state").join("channel_deliveries.db"),
+        }
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create state directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(&self.db_path).with_context(|| {
+            format!(
+                "Failed to open delivery status log: {}",
+                self.db_path.display()
+            )
+        })?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS deliveries (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel    TEXT NOT NULL,
+                recipient  TEXT NOT NULL,
+                status     TEXT NOT NULL,
+                attempts   INTEGER NOT NULL,
+                error      TEXT,
+                preview    TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_deliveries_status ON deliveries(status, updated_at);",
+        )
+        .context("Failed to initialize delivery status schema")?;
+
+        f(&conn)
+    }
+
+    /// Record a reply's first send attempt and return the record ID.
+    pub fn record(
+        &self,
+        channel: &str,
+        recipient: &str,
+        content: &str,
+        status: DeliveryStatus,
+        error: Option<&str>,
+    ) -> Result<i64> {
+        self.record_at(channel, recipient, content, status, error, Utc::now())
+    }
+
+    fn record_at(
+        &self,
+        channel: &str,
+        recipient: &str,
+        content: &str,
+        status: DeliveryStatus,
+        error: Option<&str>,
+        at: DateTime<Utc>,
+    ) -> Result<i64> {
+        let preview: String = content
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(PREVIEW_CHARS)
+            .collect();
+        self.with_connection(|conn| {
+            conn.execute(
+                "DELETE FROM deliveries WHERE updated_at < ?1",
+                params![(at - Duration::days(RETENTION_DAYS)).to_rfc3339()],
+            )
+            .context("Failed to prune delivery status log")?;
+            conn.execute(
+                "INSERT INTO deliveries (channel, recipient, status, attempts, error, preview, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, 1, ?4, ?5, ?6, ?6)",
+                params![
+                    channel,
+                    recipient,
+                    status.as_str(),
+                    error,
+                    preview,
+                    at.to_rfc3339()
+                ],
+            )
+            .context("Failed to record delivery status")?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    /// Update a record after a retry.
+    pub fn update(
+        &self,
+        id: i64,
+        status: DeliveryStatus,
+        attempts: u32,
+        error: Option<&str>,
+    ) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "UPDATE deliveries SET status = ?1, attempts = ?2, error = COALESCE(?3, error), updated_at = ?4
+                 WHERE id = ?5",
+                params![
+                    status.as_str(),
+                    attempts,
+                    error,
+                    Utc::now().to_rfc3339(),
+                    id
+                ],
+            )
+            .context("Failed to update delivery status")?;
+            Ok(())
+        })
+    }
+
+    /// The latest records, newest first, optionally only those in `status`.
+    pub fn recent(
+        &self,
+        status: Option<DeliveryStatus>,
+        limit: usize,
+    ) -> Result<Vec<DeliveryRecord>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, channel, recipient, status, attempts, error, preview, updated_at
+                 FROM deliveries WHERE ?1 IS NULL OR status = ?1
+                 ORDER BY updated_at DESC, id DESC LIMIT ?2",
+            )?;
+            let rows = stmt.query_map(
+                params![
+                    status.map(DeliveryStatus::as_str),
+                    i64::try_from(limit).unwrap_or(i64::MAX)
+                ],
+                |row| {
+                    Ok(DeliveryRecord {
+                        id: row.get(0)?,
+                        channel: row.get(1)?,
+                        recipient: row.get(2)?,
+                        status: DeliveryStatus::parse(&row.get::<_, String>(3)?)
+                            .unwrap_or(DeliveryStatus::Failed),
+                        attempts: row.get(4)?,
+                        error: row.get(5)?,
+                        preview: row.get(6)?,
+                        updated_at: row.get(7)?,
+                    })
+                },
+            )?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()
+                .context("Failed to read delivery status log")
+        })
+    }
+
+    /// Number of records per status, in `accepted`, `retrying`, `failed` order.
+    pub fn counts(&self) -> Result<[(DeliveryStatus, u64); 3]> {
+        self.with_connection(|conn| {
+            let mut counts = [
+                (DeliveryStatus::Accepted, 0),
+                (DeliveryStatus::Retrying, 0),
+                (DeliveryStatus::Failed, 0),
+            ];
+            for (status, count) in &mut counts {
+                let n: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM deliveries WHERE status = ?1",
+                    params![status.as_str()],
+                    |row| row.get(0),
+                )?;
+                *count = u64::try_from(n).unwrap_or_default();
+            }
+            Ok(counts)
+        })
+    }
+}
+
+/// Text for `zeroclaw channel deliveries`.
+pub fn render_deliveries(counts: &[(DeliveryStatus, u64)], records: &[DeliveryRecord]) -> String {
+    let mut out = format!(
+        "Deliveries (last {RETENTION_DAYS} days): {}\n",
+        counts
+            .iter()
+            .map(|(status, count)| format!("{count} {}", status.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if records.is_empty() {
+        out.push_str("\nNo matching deliveries.");
+        return out;
+    }
+    for record in records {
+        let _ = write!(
+            out,
+            "\n#{} {} {} → {} ({} attempt{}) {}\n    {}",
+            record.id,
+            record.updated_at,
+            record.channel,
+            record.recipient,
+            record.attempts,
+            if record.attempts == 1 { "" } else { "s" },
+            record.status.as_str(),
+            record.preview
+        );
+        if let Some(error) = &record.error {
+            let _ = write!(out, "\n    error: {error}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn records_are_updated_and_filtered_by_status() {
+        let tmp = TempDir::new().unwrap();
+        let log = DeliveryLog::new(tmp.path());
+
+        log.record(
+            "telegram",
+            "42",
+            "hello\nthere",
+            DeliveryStatus::Accepted,
+            None,
+        )
+        .unwrap();
+        let id = log
+            .record(
+                "discord",
+                "C1",
+                "retry me",
+                DeliveryStatus::Retrying,
+                Some("503 Service Unavailable"),
+            )
+            .unwrap();
+        log.update(id, DeliveryStatus::Failed, 4, Some("400 Bad Request"))
+            .unwrap();
+
+        let all = log.recent(None, 10).unwrap();
+        assert_eq!(all.len(), 2);
+        let failed = log.recent(Some(DeliveryStatus::Failed), 10).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts, 4);
+        assert_eq!(failed[0].error.as_deref(), Some("400 Bad Request"));
+        assert_eq!(
+            log.recent(Some(DeliveryStatus::Accepted), 10).unwrap()[0].preview,
+            "hello there"
+        );
+        assert_eq!(
+            log.counts().unwrap(),
+            [
+                (DeliveryStatus::Accepted, 1),
+                (DeliveryStatus::Retrying, 0),
+                (DeliveryStatus::Failed, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn old_records_are_pruned() {
+        let tmp = TempDir::new().unwrap();
+        let log = DeliveryLog::new(tmp.path());
+        let old = Utc::now() - Duration::days(RETENTION_DAYS + 1);
+        log.record_at("slack", "C1", "old", DeliveryStatus::Failed, None, old)
+            .unwrap();
+        log.record("slack", "C1", "new", DeliveryStatus::Accepted, None)
+            .unwrap();
+        let records = log.recent(None, 10).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].preview, "new");
+    }
+
+    #[test]
+    fn render_lists_records_with_errors() {
+        let record = DeliveryRecord {
+            id: 7,
+            channel: "telegram".into(),
+            recipient: "42".into(),
+            status: DeliveryStatus::Failed,
+            attempts: 3,
+            error: Some("chat not found".into()),
+            preview: "hi".into(),
+            updated_at: "2024-01-01T00:00:00+00:00".into(),
+        };
+        let text = render_deliveries(&[(DeliveryStatus::Failed, 1)], &[record]);
+        assert!(text.starts_with("Deliveries (last 30 days): 1 failed"));
+        assert!(text.contains("#7 2024-01-01T00:00:00+00:00 telegram → 42 (3 attempts) failed"));
+        assert!(text.contains("error: chat not found"));
+        assert!(render_deliveries(&[], &[]).ends_with("No matching deliveries."));
+    }
+}
@@ -1,25 +1,56 @@
 #[cfg(test)]
 mod channel_delivery_instructions_tests {
-    use crate::channels::channel_delivery_instructions;
+    use crate::channels::attachment::AttachmentKind;
+    use crate::channels::traits::ChannelCapabilities;
+
+    fn channel_delivery_instructions(channel: &str) -> Option<String> {
+        crate::channels::channel_delivery_instructions(channel, &ChannelCapabilities::default())
+    }
+
+    fn instructions_with(channel: &str, capabilities: ChannelCapabilities) -> String {
+        crate::channels::channel_delivery_instructions(channel, &capabilities).unwrap()
+    }
+
+    fn button_capabilities() -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_buttons: true,
+            ..ChannelCapabilities::default()
+        }
+    }
 
     #[test]
     fn telegram_has_specific_instructions() {
-        let instructions = channel_delivery_instructions("telegram");
-        assert!(instructions.is_some());
-        let text = instructions.unwrap();
+        let text = instructions_with(
+            "telegram",
+            ChannelCapabilities {
+                attachment_kinds: ChannelCapabilities::rich_attachments(),
+                supports_polls: true,
+                ..button_capabilities()
+            },
+        );
         assert!(text.contains("Telegram"));
         assert!(text.contains("[IMAGE:<path-or-url>]"));
         assert!(text.contains("**bold**"));
+        assert!(text.contains("[BUTTONS:Yes|No|Cancel]"));
+        assert!(text.contains("[ANIMATION:<path-or-url>]"));
+        assert!(text.contains("[POLL:Question?|Option 1|Option 2]"));
     }
 
     #[test]
     fn discord_has_default_instructions() {
-        let instructions = channel_delivery_instructions("discord");
-        assert!(instructions.is_some());
-        let text = instructions.unwrap();
+        let text = instructions_with(
+            "discord",
+            ChannelCapabilities {
+                attachment_kinds: ChannelCapabilities::rich_attachments(),
+                ..ChannelCapabilities::default()
+            },
+        );
         assert!(text.contains("[IMAGE:<path-or-url>]"));
         assert!(text.contains("Be concise and direct"));
         assert!(text.contains("Use tool results silently"));
+        assert!(text.contains("[STICKER:<path-or-url>] or [ANIMATION:<path-or-url>]"));
+        assert!(!text.contains("[BUTTONS:"));
+        assert!(!text.contains("[POLL:"));
     }
 
     #[test]
@@ -44,11 +75,10 @@ mod channel_delivery_instructions_tests {
 
     #[test]
     fn dingtalk_has_default_instructions() {
-        let instructions = channel_delivery_instructions("dingtalk");
-        assert!(instructions.is_some());
-        let text = instructions.unwrap();
+        let text = instructions_with("dingtalk", button_capabilities());
         assert!(text.contains("[IMAGE:<path-or-url>]"));
         assert!(text.contains("Be concise and direct"));
+        assert!(text.contains("[BUTTONS:Yes|No|Cancel]"));
     }
 
     #[test]
@@ -57,6 +87,15 @@ mod channel_delivery_instructions_tests {
         assert!(instructions.is_some());
     }
 
+    #[test]
+    fn lark_and_feishu_describe_cards_and_buttons() {
+        for channel in ["lark", "feishu"] {
+            let text = instructions_with(channel, button_capabilities());
+            assert!(text.contains("[CARD:<title>|<color>]"));
+            assert!(text.contains("[BUTTONS:Yes|No|Cancel]"));
+        }
+    }
+
     #[test]
     fn feishu_has_default_instructions() {
         let instructions = channel_delivery_instructions("feishu");
@@ -109,6 +148,34 @@ mod channel_delivery_instructions_tests {
         assert!(text.contains("[VIDEO:<path-or-url>]"));
         assert!(text.contains("[AUDIO:<path-or-url>]"));
         assert!(text.contains("[VOICE:<path-or-url>]"));
+        assert!(!text.contains("[STICKER:"));
+    }
+
+    #[test]
+    fn instructions_follow_channel_capabilities() {
+        let text = instructions_with(
+            "twilio",
+            ChannelCapabilities {
+                supports_markdown: false,
+                max_message_len: Some(1600),
+                attachment_kinds: vec![AttachmentKind::Image, AttachmentKind::Video],
+                ..ChannelCapabilities::default()
+            },
+        );
+        assert!(text.starts_with("When responding:"));
+        assert!(text.contains("write plain text without Markdown"));
+        assert!(text.contains("Messages over 1600 characters"));
+        assert!(text.contains("[IMAGE:<path-or-url>] or [VIDEO:<path-or-url>]"));
+        assert!(!text.contains("[DOCUMENT:"));
+
+        let text = instructions_with(
+            "irc",
+            ChannelCapabilities {
+                attachment_kinds: Vec::new(),
+                ..ChannelCapabilities::default()
+            },
+        );
+        assert!(text.contains("cannot send attachments"));
     }
 
     #[test]
@@ -1,4 +1,7 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::buttons::parse_button_markers;
+use super::markdown::{self, Dialect};
+use super::split::split_message_bytes;
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use std::collections::HashMap;
@@ -8,6 +11,10 @@ use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 const DINGTALK_BOT_CALLBACK_TOPIC: &str = "/v1.0/im/bot/messages/get";
+/// DingTalk rejects robot message text longer than this.
+const DINGTALK_MAX_MESSAGE_BYTES: usize = 20_000;
+/// Characters of the reply used as the notification title.
+const DINGTALK_MAX_TITLE_CHARS: usize = 40;
 
 /// DingTalk channel — connects via Stream Mode WebSocket for real-time messages.
 /// Replies are sent through per-message session webhook URLs.
@@ -103,6 +110,105 @@ impl DingTalkChannel {
         let gw: GatewayResponse = resp.json().await?;
         Ok(gw)
     }
+
+    async fn post_webhook(
+        &self,
+        webhook_url: &str,
+        body: &serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let resp = self
+            .http_client()
+            .post(webhook_url)
+            .json(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("DingTalk webhook reply failed ({status}): {err}");
+        }
+        Ok(())
+    }
+}
+
+/// Notification title for a reply: its first line without markup, or
+/// "ZeroClaw" for an empty reply. DingTalk shows it in the chat list and
+/// push notifications instead of the message body.
+fn preview_title(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(|line| line.trim().trim_start_matches(['#', '>']).trim_start())
+        .map(|line| line.strip_prefix("- ").unwrap_or(line))
+        .map(|line| markdown::render(line, Dialect::Plain).trim().to_string())
+        .find(|line| !line.is_empty());
+    match line {
+        Some(line) if line.chars().count() > DINGTALK_MAX_TITLE_CHARS => {
+            let cut: String = line.chars().take(DINGTALK_MAX_TITLE_CHARS - 1).collect();
+            format!("{}…", cut.trim_end())
+        }
+        Some(line) => line,
+        None => "ZeroClaw".to_string(),
+    }
+}
+
+/// DingTalk markdown ignores single newlines, so every line of the reply
+/// becomes its own paragraph.
+fn dingtalk_markdown(text: &str) -> String {
+    markdown::render(text, Dialect::DingTalk)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn markdown_body(title: &str, text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "msgtype": "markdown",
+        "markdown": {
+            "title": title,
+            "text": dingtalk_markdown(text),
+        }
+    })
+}
+
+/// An ActionCard with one button per label. DingTalk buttons can only open
+/// links, so each one uses the client's `sendMessage` link: tapping it posts
+/// the label into the chat, where it arrives as the user's next message.
+fn action_card_body(title: &str, text: &str, rows: &[Vec<String>]) -> serde_json::Value {
+    let text = if text.trim().is_empty() {
+        format!("**{title}**")
+    } else {
+        dingtalk_markdown(text)
+    };
+    let btns: Vec<serde_json::Value> = rows
+        .iter()
+        .flatten()
+        .map(|label| {
+            serde_json::json!({
+                "title": label,
+                "actionURL": format!(
+                    "dtmd://dingtalkclient/sendMessage?content={}",
+                    urlencoding::encode(label)
+                ),
+            })
+        })
+        .collect();
+    // Side by side for a single short row, stacked otherwise.
+    let orientation = if rows.len() == 1 && btns.len() <= 3 {
+        "1"
+    } else {
+        "0"
+    };
+    serde_json::json!({
+        "msgtype": "actionCard",
+        "actionCard": {
+            "title": title,
+            "text": text,
+            "btnOrientation": orientation,
+            "btns": btns,
+        }
+    })
 }
 
 #[async_trait]
@@ -111,6 +217,13 @@ impl Channel for DingTalkChannel {
         "dingtalk"
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_buttons: true,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         use super::attachment::{parse_attachment_markers, is_local_path};
 
@@ -126,28 +239,23 @@ impl Channel for DingTalkChannel {
             )
         })?;
 
-        // Send text message
-        if !text.is_empty() || attachments.is_empty() {
-            let title = message.subject.as_deref().unwrap_or("ZeroClaw");
-            let body = serde_json::json!({
-                "msgtype": "markdown",
-                "markdown": {
-                    "title": title,
-                    "text": text,
-                }
-            });
-
-            let resp = self
-                .http_client()
-                .post(webhook_url)
-                .json(&body)
-                .send()
-                .await?;
-
-            if !resp.status().is_success() {
-                let status = resp.status();
-                let err = resp.text().await.unwrap_or_default();
-                anyhow::bail!("DingTalk webhook reply failed ({status}): {err}");
+        // Send text message, split to fit the robot message size limit. The
+        // last chunk carries the buttons as an ActionCard.
+        let (text, buttons) = parse_button_markers(&text);
+        if !text.is_empty() || !buttons.is_empty() || attachments.is_empty() {
+            let title = message
+                .subject
+                .clone()
+                .unwrap_or_else(|| preview_title(&text));
+            let chunks = split_message_bytes(&text, DINGTALK_MAX_MESSAGE_BYTES);
+            let last = chunks.len().saturating_sub(1);
+            for (i, chunk) in chunks.iter().enumerate() {
+                let body = if i == last && !buttons.is_empty() {
+                    action_card_body(&title, chunk, &buttons)
+                } else {
+                    markdown_body(&title, chunk)
+                };
+                self.post_webhook(webhook_url, &body).await?;
             }
         }
 
@@ -157,6 +265,9 @@ impl Channel for DingTalkChannel {
                 // For local files, send file path info (DingTalk Stream API doesn't support direct file upload via webhook)
                 format!("📎 {}: `{}`\n\n*Note: File upload requires DingTalk Open API integration*", 
                     attachment.kind.marker_name(), attachment.target)
+            } else if attachment.kind.is_image_like() {
+                // Markdown images render inline in robot messages
+                format!("![{}]({})", attachment.alt.as_deref().unwrap_or("image"), attachment.target)
             } else {
                 // For URLs, send as markdown link
                 format!("[{}]({})", attachment.kind.marker_name(), attachment.target)
@@ -303,8 +414,15 @@ impl Channel for DingTalkChannel {
                     });
                     let _ = write.send(Message::Text(ack.to_string().into())).await;
 
+                    // `msgId` is stable across redeliveries of the callback.
+                    let id = data
+                        .get("msgId")
+                        .and_then(|m| m.as_str())
+                        .filter(|m| !m.is_empty())
+                        .map_or_else(|| Uuid::new_v4().to_string(), |m| format!("dingtalk_{m}"));
+
                     let channel_msg = ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
+                        id,
                         sender: sender_id.to_string(),
                         reply_target: chat_id,
                         content: content.to_string(),
@@ -418,4 +536,45 @@ client_secret = "secret"
         let chat_id = DingTalkChannel::resolve_chat_id(&data, "staff-1");
         assert_eq!(chat_id, "cid-group");
     }
+
+    #[test]
+    fn preview_title_uses_first_line_without_markup() {
+        assert_eq!(
+            preview_title("## **Build** passed\nDetails"),
+            "Build passed"
+        );
+        assert_eq!(preview_title("\n  "), "ZeroClaw");
+        let title = preview_title(&"a".repeat(100));
+        assert_eq!(title.chars().count(), DINGTALK_MAX_TITLE_CHARS);
+        assert!(title.ends_with('…'));
+    }
+
+    #[test]
+    fn markdown_lines_become_paragraphs() {
+        let body = markdown_body("t", "line one\nline two\n\n- item");
+        assert_eq!(body["msgtype"], "markdown");
+        assert_eq!(body["markdown"]["text"], "line one\n\nline two\n\n- item");
+    }
+
+    #[test]
+    fn action_card_buttons_send_their_label() {
+        let rows = vec![vec!["Yes".to_string(), "No way".to_string()]];
+        let body = action_card_body("Deploy?", "Ship it?", &rows);
+        assert_eq!(body["msgtype"], "actionCard");
+        let card = &body["actionCard"];
+        assert_eq!(card["title"], "Deploy?");
+        assert_eq!(card["text"], "Ship it?");
+        assert_eq!(card["btnOrientation"], "1");
+        assert_eq!(card["btns"][1]["title"], "No way");
+        assert_eq!(
+            card["btns"][1]["actionURL"],
+            "dtmd://dingtalkclient/sendMessage?content=No%20way"
+        );
+
+        let rows = vec![vec!["A".to_string()], vec!["B".to_string()]];
+        let body = action_card_body("Pick", "", &rows);
+        assert_eq!(body["actionCard"]["text"], "**Pick**");
+        assert_eq!(body["actionCard"]["btnOrientation"], "0");
+        assert_eq!(body["actionCard"]["btns"].as_array().unwrap().len(), 2);
+    }
 }
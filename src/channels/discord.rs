@@ -1,4 +1,9 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::discord_voice::{self, VoiceCommand, VoiceManager};
+use super::draft::{truncate_chars, DraftThrottle};
+use super::polls::{self, parse_poll_markers, Poll};
+use super::split::split_message;
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
+use crate::config::StreamMode;
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use parking_lot::Mutex;
@@ -6,17 +11,28 @@ use reqwest::multipart::{Form, Part};
 use serde_json::json;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 /// Discord channel — connects via Gateway WebSocket for real-time messages
+#[allow(clippy::struct_excessive_bools)]
 pub struct DiscordChannel {
     bot_token: String,
     guild_id: Option<String>,
     allowed_users: Vec<String>,
     listen_to_bots: bool,
     mention_only: bool,
+    trigger_prefix: Option<String>,
+    thread_replies: bool,
+    slash_commands: bool,
+    stream_mode: StreamMode,
+    draft_throttle: DraftThrottle,
     typing_handles: Mutex<HashMap<String, tokio::task::JoinHandle<()>>>,
+    /// Channel ID -> whether it is a thread, filled lazily from the REST API.
+    thread_channels: Mutex<HashMap<String, bool>>,
+    /// Set when voice channel mode is enabled (see [`super::discord_voice`]).
+    voice: Option<Arc<VoiceManager>>,
 }
 
 impl DiscordChannel {
@@ -33,14 +49,119 @@ impl DiscordChannel {
             allowed_users,
             listen_to_bots,
             mention_only,
+            trigger_prefix: None,
+            thread_replies: false,
+            slash_commands: false,
+            stream_mode: StreamMode::Off,
+            draft_throttle: DraftThrottle::new(1000),
             typing_handles: Mutex::new(HashMap::new()),
+            thread_channels: Mutex::new(HashMap::new()),
+            voice: None,
         }
     }
 
+    /// Answer guild messages that start with `prefix` (see
+    /// [`super::trigger`]).
+    pub fn with_trigger_prefix(mut self, prefix: Option<String>) -> Self {
+        self.trigger_prefix = super::trigger::normalize_prefix(prefix);
+        self
+    }
+
+    /// Start a thread on guild messages that are not already in one and
+    /// answer there.
+    pub fn with_thread_replies(mut self, thread_replies: bool) -> Self {
+        self.thread_replies = thread_replies;
+        self
+    }
+
+    /// Register `/ask`, `/reset` and `/model` on connect and answer them.
+    pub fn with_slash_commands(mut self, slash_commands: bool) -> Self {
+        self.slash_commands = slash_commands;
+        self
+    }
+
+    /// Join voice channels on `/voice join`, transcribe what allowed users
+    /// say with `transcription` and speak replies with `tts`.
+    pub fn with_voice(
+        mut self,
+        voice: bool,
+        transcription: crate::config::TranscriptionConfig,
+        tts: crate::config::TtsConfig,
+    ) -> Self {
+        if !voice {
+            return self;
+        }
+        if !cfg!(feature = "channel-discord-voice") {
+            tracing::warn!(
+                "Discord voice mode is disabled in this build (enable `channel-discord-voice`)"
+            );
+            return self;
+        }
+        if !transcription.enabled {
+            tracing::warn!("Discord voice mode needs [transcription] enabled; voice stays off");
+            return self;
+        }
+        self.voice = Some(Arc::new(VoiceManager::new(
+            self.allowed_users.clone(),
+            transcription,
+            tts,
+        )));
+        self
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.discord")
     }
 
+    /// Act on `/voice join|leave` and tell the caller in `text_channel_id`.
+    /// Returns the gateway payload (opcode 4) that moves the bot.
+    async fn handle_voice_command(
+        &self,
+        voice: &VoiceManager,
+        command: VoiceCommand,
+        guild_id: Option<&str>,
+        user_id: &str,
+        text_channel_id: &str,
+    ) -> Option<serde_json::Value> {
+        let (payload, reply) = match (command, guild_id) {
+            (_, None) => (None, "Voice works in servers only.".to_string()),
+            (VoiceCommand::Join, Some(guild_id)) => {
+                let channel_id = voice
+                    .states()
+                    .channel_of(guild_id, user_id)
+                    .map(str::to_string);
+                match channel_id {
+                    Some(channel_id) => {
+                        voice.begin_join(guild_id, &channel_id, text_channel_id);
+                        (
+                            Some(discord_voice::voice_state_payload(
+                                guild_id,
+                                Some(&channel_id),
+                            )),
+                            format!("Joining <#{channel_id}>. I'll answer out loud and here."),
+                        )
+                    }
+                    None => (
+                        None,
+                        "Join a voice channel first, then try again.".to_string(),
+                    ),
+                }
+            }
+            (VoiceCommand::Leave, Some(guild_id)) => {
+                voice.leave(guild_id);
+                (
+                    Some(discord_voice::voice_state_payload(guild_id, None)),
+                    "Left the voice channel.".to_string(),
+                )
+            }
+        };
+
+        if let Err(e) = self.send(&SendMessage::new(reply, text_channel_id)).await {
+            tracing::debug!("Discord: failed to answer voice command: {e}");
+        }
+        payload
+    }
+
     /// Check if a Discord user ID is in the allowlist.
     /// Empty list means deny everyone until explicitly configured.
     /// `"*"` means allow everyone.
@@ -53,6 +174,443 @@ impl DiscordChannel {
         let part = token.split('.').next()?;
         base64_decode(part)
     }
+
+    /// Configure streaming mode for progressive draft updates.
+    pub fn with_streaming(
+        mut self,
+        stream_mode: StreamMode,
+        draft_update_interval_ms: u64,
+    ) -> Self {
+        self.stream_mode = stream_mode;
+        self.draft_throttle = DraftThrottle::new(draft_update_interval_ms);
+        self
+    }
+
+    fn message_url(channel_id: &str, message_id: &str) -> String {
+        format!("https://discord.com/api/v10/channels/{channel_id}/messages/{message_id}")
+    }
+
+    /// Replace the content of a message the bot posted earlier.
+    async fn edit_message(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        let resp = self
+            .http_client()
+            .patch(Self::message_url(channel_id, message_id))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&json!({ "content": content }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord edit message failed ({status}): {err}");
+        }
+        Ok(())
+    }
+
+    async fn delete_message(&self, channel_id: &str, message_id: &str) -> anyhow::Result<()> {
+        let resp = self
+            .http_client()
+            .delete(Self::message_url(channel_id, message_id))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord delete message failed ({status}): {err}");
+        }
+        Ok(())
+    }
+
+    /// Whether `channel_id` is a thread. Looked up once per channel and cached;
+    /// lookup failures are treated as "not a thread" and retried next time.
+    async fn is_thread_channel(&self, channel_id: &str) -> bool {
+        if let Some(known) = self.thread_channels.lock().get(channel_id) {
+            return *known;
+        }
+
+        let url = format!("https://discord.com/api/v10/channels/{channel_id}");
+        let resp = match self
+            .http_client()
+            .get(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => resp,
+            Ok(resp) => {
+                tracing::debug!(
+                    "Discord: channel lookup for {channel_id} failed ({})",
+                    resp.status()
+                );
+                return false;
+            }
+            Err(err) => {
+                tracing::debug!("Discord: channel lookup for {channel_id} failed: {err}");
+                return false;
+            }
+        };
+
+        let Ok(channel) = resp.json::<serde_json::Value>().await else {
+            return false;
+        };
+        let is_thread = channel
+            .get("type")
+            .and_then(serde_json::Value::as_u64)
+            .is_some_and(is_thread_channel_type);
+        self.thread_channels
+            .lock()
+            .insert(channel_id.to_string(), is_thread);
+        is_thread
+    }
+
+    /// The channel a thread was started in.
+    async fn parent_channel_id(&self, thread_id: &str) -> anyhow::Result<String> {
+        let url = format!("https://discord.com/api/v10/channels/{thread_id}");
+        let channel: serde_json::Value = self
+            .http_client()
+            .get(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        channel
+            .get("parent_id")
+            .and_then(|p| p.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Discord channel {thread_id} has no parent"))
+    }
+
+    /// Results of a poll this bot sent, read from the poll message that a
+    /// poll result message references.
+    async fn poll_results_message(
+        &self,
+        d: &serde_json::Value,
+        bot_user_id: &str,
+    ) -> Option<ChannelMessage> {
+        let channel_id = d.get("channel_id").and_then(|c| c.as_str())?;
+        let result_id = d.get("id").and_then(|i| i.as_str())?;
+        let poll_message_id = d
+            .get("message_reference")
+            .and_then(|r| r.get("message_id"))
+            .and_then(|i| i.as_str())?;
+
+        let url =
+            format!("https://discord.com/api/v10/channels/{channel_id}/messages/{poll_message_id}");
+        let response = self
+            .http_client()
+            .get(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+        let poll_message: serde_json::Value = match response {
+            Ok(resp) => resp.json().await.ok()?,
+            Err(e) => {
+                tracing::warn!("Discord: failed to fetch poll {poll_message_id}: {e}");
+                return None;
+            }
+        };
+
+        let author_id = poll_message
+            .get("author")
+            .and_then(|a| a.get("id"))
+            .and_then(|i| i.as_str());
+        if author_id != Some(bot_user_id) {
+            return None;
+        }
+        let (question, counts) = poll_counts(&poll_message)?;
+        Some(polls::results_message(
+            "discord",
+            format!("discord_{result_id}"),
+            channel_id,
+            None,
+            polls::format_results(&question, &counts),
+        ))
+    }
+
+    /// Start a public thread on `message_id` and return its ID.
+    async fn start_thread(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        content: &str,
+    ) -> anyhow::Result<String> {
+        let url = format!(
+            "https://discord.com/api/v10/channels/{channel_id}/messages/{message_id}/threads"
+        );
+        let resp = self
+            .http_client()
+            .post(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&json!({
+                "name": thread_name_from_content(content),
+                "auto_archive_duration": THREAD_AUTO_ARCHIVE_MINUTES,
+            }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord start thread failed ({status}): {err}");
+        }
+
+        let thread: serde_json::Value = resp.json().await?;
+        let thread_id = thread
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Discord start thread response has no id"))?
+            .to_string();
+        self.thread_channels.lock().insert(thread_id.clone(), true);
+        Ok(thread_id)
+    }
+
+    /// Where to answer a message and which thread it belongs to. Messages in
+    /// a thread stay there; with `thread_replies` a new thread is started on
+    /// guild messages. DMs are never threaded.
+    async fn resolve_reply_thread(
+        &self,
+        channel_id: &str,
+        message_id: &str,
+        content: &str,
+        in_guild: bool,
+    ) -> (String, Option<String>) {
+        if !in_guild || channel_id.is_empty() {
+            return (channel_id.to_string(), None);
+        }
+        if self.is_thread_channel(channel_id).await {
+            return (channel_id.to_string(), Some(channel_id.to_string()));
+        }
+        if self.thread_replies && !message_id.is_empty() {
+            match self.start_thread(channel_id, message_id, content).await {
+                Ok(thread_id) => return (thread_id.clone(), Some(thread_id)),
+                Err(err) => {
+                    tracing::warn!(
+                        "Discord: could not start reply thread, answering in channel: {err}"
+                    );
+                }
+            }
+        }
+        (channel_id.to_string(), None)
+    }
+
+    /// Answer a slash command interaction and turn it into a channel message.
+    async fn handle_interaction(&self, interaction: &serde_json::Value) -> Option<ChannelMessage> {
+        // Type 2 is APPLICATION_COMMAND; pings and component clicks are ignored.
+        if !self.slash_commands
+            || interaction.get("type").and_then(serde_json::Value::as_u64) != Some(2)
+        {
+            return None;
+        }
+
+        let interaction_id = interaction.get("id")?.as_str()?;
+        let token = interaction.get("token")?.as_str()?;
+        let channel_id = interaction.get("channel_id")?.as_str()?;
+        // Guild interactions carry `member.user`, DMs carry `user`.
+        let user_id = interaction
+            .get("member")
+            .and_then(|member| member.get("user"))
+            .or_else(|| interaction.get("user"))
+            .and_then(|user| user.get("id"))
+            .and_then(serde_json::Value::as_str)?;
+        let client = self.http_client();
+
+        if !self.is_user_allowed(user_id) {
+            tracing::warn!("Discord: ignoring slash command from unauthorized user: {user_id}");
+            let _ = respond_to_interaction(
+                &client,
+                interaction_id,
+                token,
+                "You are not allowed to use this bot.",
+                true,
+            )
+            .await;
+            return None;
+        }
+
+        let interaction_guild = interaction
+            .get("guild_id")
+            .and_then(serde_json::Value::as_str);
+        if let (Some(gid), Some(guild)) = (self.guild_id.as_deref(), interaction_guild) {
+            if guild != gid {
+                return None;
+            }
+        }
+
+        let (content, echo) = slash_command_request(interaction.get("data")?)?;
+        if let Err(e) = respond_to_interaction(&client, interaction_id, token, &echo, false).await {
+            tracing::debug!("{e}");
+        }
+
+        let thread_ts = if interaction_guild.is_some() && self.is_thread_channel(channel_id).await {
+            Some(channel_id.to_string())
+        } else {
+            None
+        };
+
+        Some(ChannelMessage {
+            id: format!("discord_{interaction_id}"),
+            sender: user_id.to_string(),
+            reply_target: channel_id.to_string(),
+            content,
+            channel: "discord".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts,
+        })
+    }
+}
+
+/// Minutes of inactivity before a thread started by the bot is archived.
+const THREAD_AUTO_ARCHIVE_MINUTES: u32 = 1440;
+/// Discord caps thread names at 100 characters.
+const MAX_THREAD_NAME_CHARS: usize = 100;
+
+/// Public, private and announcement threads (forum posts are public threads).
+fn is_thread_channel_type(kind: u64) -> bool {
+    matches!(kind, 10..=12)
+}
+
+/// Most messages one `GET /channels/{id}/messages` call returns.
+const DISCORD_MAX_HISTORY_MESSAGES: usize = 100;
+
+/// Messages of a `GET /channels/{id}/messages` response (newest first),
+/// oldest first and named by their author's display name.
+fn history_messages(channel_id: &str, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+    let mut messages: Vec<ChannelMessage> = payload
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|msg| {
+            let id = msg.get("id").and_then(|i| i.as_str())?;
+            let author = msg.get("author");
+            let sender = ["global_name", "username", "id"]
+                .iter()
+                .find_map(|key| author.and_then(|a| a.get(*key)).and_then(|v| v.as_str()))
+                .unwrap_or("unknown");
+            Some(ChannelMessage {
+                id: format!("discord_{id}"),
+                sender: sender.to_string(),
+                reply_target: channel_id.to_string(),
+                content: msg
+                    .get("content")
+                    .and_then(|c| c.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                channel: "discord".to_string(),
+                timestamp: chrono::DateTime::parse_from_rfc3339(
+                    msg.get("timestamp").and_then(|t| t.as_str()).unwrap_or(""),
+                )
+                .map_or(0, |t| u64::try_from(t.timestamp()).unwrap_or(0)),
+                thread_ts: None,
+            })
+        })
+        .collect();
+    messages.reverse();
+    messages
+}
+
+/// Message type Discord posts in the channel when a poll ends.
+const DISCORD_POLL_RESULT_MESSAGE_TYPE: u64 = 46;
+/// How long polls stay open, in hours.
+const DISCORD_POLL_DURATION_HOURS: u32 = 24;
+/// Discord's limits for poll questions and answers.
+const DISCORD_MAX_POLL_QUESTION_CHARS: usize = 300;
+const DISCORD_MAX_POLL_ANSWER_CHARS: usize = 55;
+
+/// `poll` request object for a `[POLL:...]` marker.
+fn poll_payload(poll: &Poll) -> serde_json::Value {
+    let answers: Vec<serde_json::Value> = poll
+        .options
+        .iter()
+        .map(|option| {
+            let text = truncate_chars(option, DISCORD_MAX_POLL_ANSWER_CHARS);
+            json!({ "poll_media": { "text": text } })
+        })
+        .collect();
+    json!({
+        "question": { "text": truncate_chars(&poll.question, DISCORD_MAX_POLL_QUESTION_CHARS) },
+        "answers": answers,
+        "duration": DISCORD_POLL_DURATION_HOURS,
+        "allow_multiselect": false,
+    })
+}
+
+/// Question and per-answer votes of a message carrying a finished poll.
+fn poll_counts(message: &serde_json::Value) -> Option<(String, Vec<(String, u64)>)> {
+    let poll = message.get("poll")?;
+    let question = poll
+        .get("question")
+        .and_then(|q| q.get("text"))
+        .and_then(|t| t.as_str())
+        .unwrap_or("")
+        .to_string();
+    let answer_counts = poll
+        .get("results")
+        .and_then(|r| r.get("answer_counts"))
+        .and_then(|c| c.as_array());
+    let counts = poll
+        .get("answers")?
+        .as_array()?
+        .iter()
+        .map(|answer| {
+            let text = answer
+                .get("poll_media")
+                .and_then(|m| m.get("text"))
+                .and_then(|t| t.as_str())
+                .unwrap_or("")
+                .to_string();
+            let id = answer.get("answer_id").and_then(serde_json::Value::as_u64);
+            let votes = answer_counts
+                .into_iter()
+                .flatten()
+                .find(|count| count.get("id").and_then(serde_json::Value::as_u64) == id)
+                .and_then(|count| count.get("count"))
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(0);
+            (text, votes)
+        })
+        .collect();
+    Some((question, counts))
+}
+
+/// Thread name from the first non-empty line of the message.
+fn thread_name_from_content(content: &str) -> String {
+    let first_line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("");
+    if first_line.is_empty() {
+        return "Conversation".to_string();
+    }
+    if first_line.chars().count() <= MAX_THREAD_NAME_CHARS {
+        return first_line.to_string();
+    }
+    let mut name: String = first_line.chars().take(MAX_THREAD_NAME_CHARS - 1).collect();
+    name.push('…');
+    name
 }
 
 /// Process Discord message attachments and return a string to append to the
@@ -110,6 +668,8 @@ enum DiscordAttachmentKind {
     Video,
     Audio,
     Voice,
+    Sticker,
+    Animation,
 }
 
 impl DiscordAttachmentKind {
@@ -120,6 +680,8 @@ impl DiscordAttachmentKind {
             "VIDEO" => Some(Self::Video),
             "AUDIO" => Some(Self::Audio),
             "VOICE" => Some(Self::Voice),
+            "STICKER" => Some(Self::Sticker),
+            "ANIMATION" | "GIF" => Some(Self::Animation),
             _ => None,
         }
     }
@@ -131,6 +693,8 @@ impl DiscordAttachmentKind {
             Self::Video => "VIDEO",
             Self::Audio => "AUDIO",
             Self::Voice => "VOICE",
+            Self::Sticker => "STICKER",
+            Self::Animation => "ANIMATION",
         }
     }
 }
@@ -186,6 +750,33 @@ fn parse_attachment_markers(message: &str) -> (String, Vec<DiscordAttachment>) {
     (cleaned.trim().to_string(), attachments)
 }
 
+/// Move remote stickers and animations into image embeds so they play
+/// inline instead of showing as bare links. Returns the other attachments;
+/// local files are uploaded as usual.
+fn take_animated_embeds(
+    attachments: Vec<DiscordAttachment>,
+    embeds: &mut Vec<serde_json::Value>,
+) -> Vec<DiscordAttachment> {
+    attachments
+        .into_iter()
+        .filter(|attachment| {
+            let target = attachment.target.trim();
+            let animated = matches!(
+                attachment.kind,
+                DiscordAttachmentKind::Sticker | DiscordAttachmentKind::Animation
+            );
+            if animated
+                && (target.starts_with("https://") || target.starts_with("http://"))
+                && embeds.len() < DISCORD_MAX_EMBEDS
+            {
+                embeds.push(json!({ "image": { "url": target } }));
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
 fn classify_outgoing_attachments(
     attachments: &[DiscordAttachment],
 ) -> (Vec<PathBuf>, Vec<String>, Vec<String>) {
@@ -230,19 +821,335 @@ fn with_inline_attachment_urls(
     lines.join("\n")
 }
 
-async fn send_discord_message_json(
+/// Prompt addition describing the `[EMBED:...]` marker to the agent.
+pub const EMBED_INSTRUCTIONS: &str = "To show structured results on Discord (a status card, a summary with key values, a preview image), add a marker like [EMBED:title=Build #42|description=All checks passed|field:Duration=3m 12s|field:Branch=main|image=https://example.com/chart.png|color=#2ecc71]. Supported keys: title, description, url, color, image, thumbnail, footer, and any number of field:Name=Value entries. Values cannot contain | or ].";
+
+/// Discord accepts up to 10 embeds per message and 25 fields per embed.
+const DISCORD_MAX_EMBEDS: usize = 10;
+const DISCORD_MAX_EMBED_FIELDS: usize = 25;
+
+/// Parse `[EMBED:key=value|...]` markers into Discord embed objects.
+///
+/// Markers that do not yield a title, description, field or image are
+/// left in the text unchanged.
+fn parse_embed_markers(message: &str) -> (String, Vec<serde_json::Value>) {
+    let mut cleaned = String::with_capacity(message.len());
+    let mut embeds = Vec::new();
+    let mut cursor = 0usize;
+
+    while let Some(rel_start) = message[cursor..].find('[') {
+        let start = cursor + rel_start;
+        let Some(rel_end) = message[start..].find(']') else {
+            break;
+        };
+        let end = start + rel_end;
+        cleaned.push_str(&message[cursor..start]);
+
+        let embed = message[start + 1..end]
+            .split_once(':')
+            .filter(|(kind, _)| kind.trim().eq_ignore_ascii_case("EMBED"))
+            .and_then(|(_, spec)| build_embed(spec));
+
+        match embed {
+            Some(embed) if embeds.len() < DISCORD_MAX_EMBEDS => embeds.push(embed),
+            _ => cleaned.push_str(&message[start..=end]),
+        }
+        cursor = end + 1;
+    }
+    cleaned.push_str(&message[cursor..]);
+
+    if embeds.is_empty() {
+        return (message.to_string(), embeds);
+    }
+    (cleaned.trim().to_string(), embeds)
+}
+
+fn build_embed(spec: &str) -> Option<serde_json::Value> {
+    let mut embed = serde_json::Map::new();
+    let mut fields = Vec::new();
+
+    for entry in spec.split('|') {
+        let Some((key, value)) = entry.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if value.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = key.strip_prefix("field:") {
+            let name = name.trim();
+            if !name.is_empty() && fields.len() < DISCORD_MAX_EMBED_FIELDS {
+                fields.push(json!({
+                    "name": truncate_chars(name, 256),
+                    "value": truncate_chars(value, 1024),
+                    "inline": true,
+                }));
+            }
+            continue;
+        }
+
+        let is_url = value.starts_with("https://") || value.starts_with("http://");
+        match key.to_ascii_lowercase().as_str() {
+            "title" => {
+                embed.insert("title".into(), json!(truncate_chars(value, 256)));
+            }
+            "description" => {
+                embed.insert("description".into(), json!(truncate_chars(value, 4096)));
+            }
+            "url" if is_url => {
+                embed.insert("url".into(), json!(value));
+            }
+            "image" if is_url => {
+                embed.insert("image".into(), json!({ "url": value }));
+            }
+            "thumbnail" if is_url => {
+                embed.insert("thumbnail".into(), json!({ "url": value }));
+            }
+            "footer" => {
+                embed.insert(
+                    "footer".into(),
+                    json!({ "text": truncate_chars(value, 2048) }),
+                );
+            }
+            "color" | "colour" => {
+                if let Some(color) = parse_embed_color(value) {
+                    embed.insert("color".into(), json!(color));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !fields.is_empty() {
+        embed.insert("fields".into(), serde_json::Value::Array(fields));
+    }
+
+    let has_body = ["title", "description", "fields", "image"]
+        .iter()
+        .any(|key| embed.contains_key(*key));
+    has_body.then_some(serde_json::Value::Object(embed))
+}
+
+/// `#2ecc71`, `0x2ecc71` or a decimal RGB integer.
+fn parse_embed_color(value: &str) -> Option<u32> {
+    let hex = value.strip_prefix('#').or_else(|| value.strip_prefix("0x"));
+    let color = match hex {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => value.parse().ok()?,
+    };
+    (color <= 0x00FF_FFFF).then_some(color)
+}
+
+/// Slash commands registered when `slash_commands` is enabled.
+fn slash_command_definitions(voice: bool) -> serde_json::Value {
+    let mut definitions = json!([
+        {
+            "name": "ask",
+            "type": 1,
+            "description": "Ask the assistant something",
+            "options": [{
+                "type": 3,
+                "name": "prompt",
+                "description": "Your question or request",
+                "required": true
+            }]
+        },
+        {
+            "name": "reset",
+            "type": 1,
+            "description": "Start a new conversation"
+        },
+        {
+            "name": "model",
+            "type": 1,
+            "description": "Show the current model or switch to another one",
+            "options": [{
+                "type": 3,
+                "name": "id",
+                "description": "Model ID to switch to",
+                "required": false
+            }]
+        }
+    ]);
+    if voice {
+        if let Some(definitions) = definitions.as_array_mut() {
+            definitions.push(json!({
+                "name": "voice",
+                "type": 1,
+                "description": "Join or leave your voice channel",
+                "options": [{
+                    "type": 3,
+                    "name": "action",
+                    "description": "join or leave",
+                    "required": true,
+                    "choices": [
+                        { "name": "join", "value": "join" },
+                        { "name": "leave", "value": "leave" }
+                    ]
+                }]
+            }));
+        }
+    }
+    definitions
+}
+
+/// Map a slash command interaction to the message the runtime handles and
+/// the text echoed back to the channel: `/ask` becomes its prompt, `/reset`
+/// the `/new` runtime command, and `/model` stays `/model [id]`.
+fn slash_command_request(data: &serde_json::Value) -> Option<(String, String)> {
+    let option = |name: &str| {
+        data.get("options")?
+            .as_array()?
+            .iter()
+            .find(|option| option.get("name").and_then(serde_json::Value::as_str) == Some(name))?
+            .get("value")?
+            .as_str()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+
+    match data.get("name")?.as_str()? {
+        "ask" => {
+            let prompt = option("prompt")?;
+            let echo = prompt
+                .lines()
+                .map(|line| format!("> {line}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some((
+                prompt,
+                truncate_chars(&echo, DISCORD_MAX_MESSAGE_LENGTH).to_string(),
+            ))
+        }
+        "reset" => Some(("/new".to_string(), "`/reset`".to_string())),
+        "model" => {
+            let command = match option("id") {
+                Some(id) => format!("/model {id}"),
+                None => "/model".to_string(),
+            };
+            let echo = format!("`{command}`");
+            Some((command, echo))
+        }
+        "voice" => {
+            let command = format!("/voice {}", option("action")?);
+            let echo = format!("`{command}`");
+            Some((command, echo))
+        }
+        _ => None,
+    }
+}
+
+/// Register the slash commands, replacing any previously registered set.
+async fn register_slash_commands(
+    client: &reqwest::Client,
+    bot_token: &str,
+    application_id: &str,
+    guild_id: Option<&str>,
+    voice: bool,
+) -> anyhow::Result<()> {
+    let url = match guild_id {
+        Some(guild_id) => format!(
+            "https://discord.com/api/v10/applications/{application_id}/guilds/{guild_id}/commands"
+        ),
+        None => format!("https://discord.com/api/v10/applications/{application_id}/commands"),
+    };
+
+    let resp = client
+        .put(&url)
+        .header("Authorization", format!("Bot {bot_token}"))
+        .json(&slash_command_definitions(voice))
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let err = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        anyhow::bail!("Discord slash command registration failed ({status}): {err}");
+    }
+
+    Ok(())
+}
+
+/// Answer an interaction with a channel message (ephemeral when only the
+/// invoking user should see it).
+async fn respond_to_interaction(
+    client: &reqwest::Client,
+    interaction_id: &str,
+    interaction_token: &str,
+    content: &str,
+    ephemeral: bool,
+) -> anyhow::Result<()> {
+    let url = format!(
+        "https://discord.com/api/v10/interactions/{interaction_id}/{interaction_token}/callback"
+    );
+    let mut data = json!({ "content": content });
+    if ephemeral {
+        data["flags"] = json!(64);
+    }
+
+    let resp = client
+        .post(&url)
+        .json(&json!({ "type": 4, "data": data }))
+        .send()
+        .await?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let err = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        anyhow::bail!("Discord interaction response failed ({status}): {err}");
+    }
+
+    Ok(())
+}
+
+async fn send_discord_message_json(
+    client: &reqwest::Client,
+    bot_token: &str,
+    recipient: &str,
+    content: &str,
+    embeds: &[serde_json::Value],
+) -> anyhow::Result<Option<String>> {
+    let mut body = json!({ "content": content });
+    if !embeds.is_empty() {
+        body["embeds"] = json!(embeds);
+    }
+    post_discord_message(client, bot_token, recipient, &body).await
+}
+
+async fn send_discord_poll(
     client: &reqwest::Client,
     bot_token: &str,
     recipient: &str,
-    content: &str,
+    poll: &Poll,
 ) -> anyhow::Result<()> {
+    let body = json!({ "poll": poll_payload(poll) });
+    post_discord_message(client, bot_token, recipient, &body)
+        .await
+        .map(|_| ())
+}
+
+async fn post_discord_message(
+    client: &reqwest::Client,
+    bot_token: &str,
+    recipient: &str,
+    body: &serde_json::Value,
+) -> anyhow::Result<Option<String>> {
     let url = format!("https://discord.com/api/v10/channels/{recipient}/messages");
-    let body = json!({ "content": content });
+
+    super::rate_limit::acquire("discord", recipient).await;
 
     let resp = client
         .post(&url)
         .header("Authorization", format!("Bot {bot_token}"))
-        .json(&body)
+        .json(body)
         .send()
         .await?;
 
@@ -255,7 +1162,14 @@ async fn send_discord_message_json(
         anyhow::bail!("Discord send message failed ({status}): {err}");
     }
 
-    Ok(())
+    Ok(created_message_id(resp).await)
+}
+
+/// Read the id of the message Discord created, as `discord_{id}`.
+async fn created_message_id(resp: reqwest::Response) -> Option<String> {
+    let data: serde_json::Value = resp.json().await.ok()?;
+    let id = data.get("id")?.as_str()?;
+    Some(format!("discord_{id}"))
 }
 
 async fn send_discord_message_with_files(
@@ -263,11 +1177,16 @@ async fn send_discord_message_with_files(
     bot_token: &str,
     recipient: &str,
     content: &str,
+    embeds: &[serde_json::Value],
     files: &[PathBuf],
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<String>> {
     let url = format!("https://discord.com/api/v10/channels/{recipient}/messages");
 
-    let mut form = Form::new().text("payload_json", json!({ "content": content }).to_string());
+    let mut payload = json!({ "content": content });
+    if !embeds.is_empty() {
+        payload["embeds"] = json!(embeds);
+    }
+    let mut form = Form::new().text("payload_json", payload.to_string());
 
     for (idx, path) in files.iter().enumerate() {
         let bytes = tokio::fs::read(path).await.map_err(|error| {
@@ -287,6 +1206,8 @@ async fn send_discord_message_with_files(
         );
     }
 
+    super::rate_limit::acquire("discord", recipient).await;
+
     let resp = client
         .post(&url)
         .header("Authorization", format!("Bot {bot_token}"))
@@ -303,7 +1224,7 @@ async fn send_discord_message_with_files(
         anyhow::bail!("Discord send message with files failed ({status}): {err}");
     }
 
-    Ok(())
+    Ok(created_message_id(resp).await)
 }
 
 const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
@@ -315,51 +1236,8 @@ const DISCORD_MAX_MESSAGE_LENGTH: usize = 2000;
 const DISCORD_ACK_REACTIONS: &[&str] = &["⚡️", "🦀", "🙌", "💪", "👌", "👀", "👣"];
 
 /// Split a message into chunks that respect Discord's 2000-character limit.
-/// Tries to split at word boundaries when possible.
 fn split_message_for_discord(message: &str) -> Vec<String> {
-    if message.chars().count() <= DISCORD_MAX_MESSAGE_LENGTH {
-        return vec![message.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = message;
-
-    while !remaining.is_empty() {
-        // Find the byte offset for the 2000th character boundary.
-        // If there are fewer than 2000 chars left, we can emit the tail directly.
-        let hard_split = remaining
-            .char_indices()
-            .nth(DISCORD_MAX_MESSAGE_LENGTH)
-            .map_or(remaining.len(), |(idx, _)| idx);
-
-        let chunk_end = if hard_split == remaining.len() {
-            hard_split
-        } else {
-            // Try to find a good break point (newline, then space)
-            let search_area = &remaining[..hard_split];
-
-            // Prefer splitting at newline
-            if let Some(pos) = search_area.rfind('\n') {
-                // Don't split if the newline is too close to the end
-                if search_area[..pos].chars().count() >= DISCORD_MAX_MESSAGE_LENGTH / 2 {
-                    pos + 1
-                } else {
-                    // Try space as fallback
-                    search_area.rfind(' ').map_or(hard_split, |space| space + 1)
-                }
-            } else if let Some(pos) = search_area.rfind(' ') {
-                pos + 1
-            } else {
-                // Hard split at the limit
-                hard_split
-            }
-        };
-
-        chunks.push(remaining[..chunk_end].to_string());
-        remaining = &remaining[chunk_end..];
-    }
-
-    chunks
+    split_message(message, DISCORD_MAX_MESSAGE_LENGTH)
 }
 
 fn pick_uniform_index(len: usize) -> usize {
@@ -404,6 +1282,11 @@ fn discord_reaction_url(channel_id: &str, message_id: &str, emoji: &str) -> Stri
     )
 }
 
+fn discord_pin_url(channel_id: &str, message_id: &str) -> String {
+    let raw_id = message_id.strip_prefix("discord_").unwrap_or(message_id);
+    format!("https://discord.com/api/v10/channels/{channel_id}/pins/{raw_id}")
+}
+
 fn mention_tags(bot_user_id: &str) -> [String; 2] {
     [format!("<@{bot_user_id}>"), format!("<@!{bot_user_id}>")]
 }
@@ -486,8 +1369,26 @@ impl Channel for DiscordChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        self.send_with_id(message).await.map(|_| ())
+    }
+
+    async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        // Voice replies are spoken, then posted as text too.
+        let recipient = discord_voice::text_channel_of(&message.recipient);
+        if let Some((guild_id, _)) = discord_voice::parse_voice_reply_target(&message.recipient) {
+            if let Some(voice) = &self.voice {
+                let spoken = super::strip_tool_call_tags(&message.content);
+                if let Err(e) = voice.speak(guild_id, &spoken).await {
+                    tracing::warn!("Discord voice: failed to speak reply: {e}");
+                }
+            }
+        }
+
         let raw_content = super::strip_tool_call_tags(&message.content);
+        let (raw_content, polls) = parse_poll_markers(&raw_content);
+        let (raw_content, mut embeds) = parse_embed_markers(&raw_content);
         let (cleaned_content, parsed_attachments) = parse_attachment_markers(&raw_content);
+        let parsed_attachments = take_animated_embeds(parsed_attachments, &mut embeds);
         let (mut local_files, remote_urls, unresolved_markers) =
             classify_outgoing_attachments(&parsed_attachments);
 
@@ -509,22 +1410,39 @@ impl Channel for DiscordChannel {
 
         let content =
             with_inline_attachment_urls(&cleaned_content, &remote_urls, &unresolved_markers);
-        let chunks = split_message_for_discord(&content);
+        // A reply that is only polls has no text message.
+        let chunks = if content.trim().is_empty()
+            && embeds.is_empty()
+            && local_files.is_empty()
+            && !polls.is_empty()
+        {
+            Vec::new()
+        } else {
+            split_message_for_discord(&content)
+        };
         let client = self.http_client();
+        let mut first_id = None;
 
         for (i, chunk) in chunks.iter().enumerate() {
-            if i == 0 && !local_files.is_empty() {
+            // Embeds go under the last chunk, after the text they illustrate.
+            let chunk_embeds: &[serde_json::Value] =
+                if i == chunks.len() - 1 { &embeds } else { &[] };
+            let sent_id = if i == 0 && !local_files.is_empty() {
                 send_discord_message_with_files(
                     &client,
                     &self.bot_token,
-                    &message.recipient,
+                    recipient,
                     chunk,
+                    chunk_embeds,
                     &local_files,
                 )
-                .await?;
+                .await?
             } else {
-                send_discord_message_json(&client, &self.bot_token, &message.recipient, chunk)
-                    .await?;
+                send_discord_message_json(&client, &self.bot_token, recipient, chunk, chunk_embeds)
+                    .await?
+            };
+            if i == 0 {
+                first_id = sent_id;
             }
 
             if i < chunks.len() - 1 {
@@ -532,9 +1450,121 @@ impl Channel for DiscordChannel {
             }
         }
 
+        for poll in &polls {
+            send_discord_poll(&client, &self.bot_token, recipient, poll).await?;
+        }
+
+        Ok(first_id)
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_edit: self.supports_draft_updates(),
+            max_message_len: Some(DISCORD_MAX_MESSAGE_LENGTH),
+            attachment_kinds: ChannelCapabilities::rich_attachments(),
+            supports_polls: true,
+            ..ChannelCapabilities::default()
+        }
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        // Voice replies are spoken once complete, so they are not streamed.
+        if self.stream_mode == StreamMode::Off
+            || discord_voice::parse_voice_reply_target(&message.recipient).is_some()
+        {
+            return Ok(None);
+        }
+
+        let initial_text = if message.content.is_empty() {
+            "..."
+        } else {
+            truncate_chars(&message.content, DISCORD_MAX_MESSAGE_LENGTH)
+        };
+        let resp = self
+            .http_client()
+            .post(format!(
+                "https://discord.com/api/v10/channels/{}/messages",
+                message.recipient
+            ))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&json!({ "content": initial_text }))
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord send message (draft) failed ({status}): {err}");
+        }
+
+        let posted: serde_json::Value = resp.json().await?;
+        let message_id = posted
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string);
+        if let Some(ref id) = message_id {
+            self.draft_throttle.mark(id);
+        }
+        Ok(message_id)
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        if !self.draft_throttle.ready(message_id) {
+            return Ok(());
+        }
+
+        let (text, _) = parse_embed_markers(text);
+        let display_text = truncate_chars(&text, DISCORD_MAX_MESSAGE_LENGTH);
+        match self.edit_message(recipient, message_id, display_text).await {
+            Ok(()) => self.draft_throttle.mark(message_id),
+            Err(e) => tracing::debug!("{e}"),
+        }
         Ok(())
     }
 
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.draft_throttle.clear(message_id);
+        let text = super::strip_tool_call_tags(text);
+        let (without_embeds, embeds) = parse_embed_markers(&text);
+        let (cleaned, attachments) = parse_attachment_markers(&without_embeds);
+
+        // Edits here only carry text, and long replies need several
+        // messages: replace the draft with a regular send.
+        if !attachments.is_empty()
+            || !embeds.is_empty()
+            || split_message_for_discord(&cleaned).len() > 1
+        {
+            if let Err(e) = self.delete_message(recipient, message_id).await {
+                tracing::debug!("{e}");
+            }
+            return self.send(&SendMessage::new(text, recipient)).await;
+        }
+
+        self.edit_message(recipient, message_id, &cleaned).await
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.draft_throttle.clear(message_id);
+        self.delete_message(recipient, message_id).await
+    }
+
     #[allow(clippy::too_many_lines)]
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let bot_user_id = Self::bot_user_id_from_token(&self.bot_token).unwrap_or_default();
@@ -569,12 +1599,20 @@ impl Channel for DiscordChannel {
             .and_then(serde_json::Value::as_u64)
             .unwrap_or(41250);
 
+        // GUILDS | GUILD_MESSAGES | MESSAGE_CONTENT | DIRECT_MESSAGES,
+        // plus GUILD_VOICE_STATES in voice mode
+        let intents: u64 = if self.voice.is_some() {
+            0x9201 | 0x80
+        } else {
+            0x9201
+        };
+
         // Send Identify (opcode 2)
         let identify = json!({
             "op": 2,
             "d": {
                 "token": self.bot_token,
-                "intents": 37377, // GUILDS | GUILD_MESSAGES | MESSAGE_CONTENT | DIRECT_MESSAGES
+                "intents": intents,
                 "properties": {
                     "os": "linux",
                     "browser": "zeroclaw",
@@ -659,8 +1697,104 @@ impl Channel for DiscordChannel {
                         _ => {}
                     }
 
-                    // Only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     let event_type = event.get("t").and_then(|t| t.as_str()).unwrap_or("");
+
+                    if event_type == "READY" && self.slash_commands {
+                        // Bot tokens belong to an application with the same ID as the bot user.
+                        let application_id = event
+                            .get("d")
+                            .and_then(|d| d.get("application"))
+                            .and_then(|a| a.get("id"))
+                            .and_then(serde_json::Value::as_str)
+                            .map_or_else(|| bot_user_id.clone(), str::to_string);
+                        let client = self.http_client();
+                        let bot_token = self.bot_token.clone();
+                        let guild_id = self.guild_id.clone();
+                        let voice = self.voice.is_some();
+                        tokio::spawn(async move {
+                            let registered = register_slash_commands(
+                                &client,
+                                &bot_token,
+                                &application_id,
+                                guild_id.as_deref(),
+                                voice,
+                            )
+                            .await;
+                            match registered {
+                                Ok(()) => tracing::info!("Discord: registered slash commands"),
+                                Err(e) => tracing::warn!("{e}"),
+                            }
+                        });
+                        continue;
+                    }
+
+                    if let (Some(voice), Some(d)) = (self.voice.as_ref(), event.get("d")) {
+                        let str_field = |name: &str| d.get(name).and_then(serde_json::Value::as_str);
+                        let ready = match event_type {
+                            "GUILD_CREATE" => {
+                                voice.states().apply_guild_create(d);
+                                None
+                            }
+                            "VOICE_STATE_UPDATE" => {
+                                voice.states().apply(d, None);
+                                match (str_field("guild_id"), str_field("session_id")) {
+                                    (Some(guild_id), Some(session_id))
+                                        if str_field("user_id") == Some(bot_user_id.as_str())
+                                            && str_field("channel_id").is_some() =>
+                                    {
+                                        voice.session_update(guild_id, session_id)
+                                    }
+                                    _ => None,
+                                }
+                            }
+                            // A null endpoint means the voice server is being reallocated.
+                            "VOICE_SERVER_UPDATE" => match (
+                                str_field("guild_id"),
+                                str_field("endpoint"),
+                                str_field("token"),
+                            ) {
+                                (Some(guild_id), Some(endpoint), Some(token)) => {
+                                    voice.server_update(guild_id, endpoint, token)
+                                }
+                                _ => None,
+                            },
+                            _ => None,
+                        };
+                        if let Some(request) = ready {
+                            Arc::clone(voice).spawn_connect(request, bot_user_id.clone(), tx.clone());
+                        }
+                    }
+
+                    if event_type == "INTERACTION_CREATE" {
+                        let Some(d) = event.get("d") else {
+                            continue;
+                        };
+                        if let Some(channel_msg) = self.handle_interaction(d).await {
+                            let voice_command = self
+                                .voice
+                                .as_ref()
+                                .zip(discord_voice::parse_voice_command(&channel_msg.content));
+                            if let Some((voice, command)) = voice_command {
+                                let guild_id = d.get("guild_id").and_then(serde_json::Value::as_str);
+                                let text_channel_id = channel_msg.thread_ts.as_deref().unwrap_or(&channel_msg.reply_target);
+                                if let Some(payload) = self
+                                    .handle_voice_command(voice, command, guild_id, &channel_msg.sender, text_channel_id)
+                                    .await
+                                {
+                                    if write.send(Message::Text(payload.to_string().into())).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                continue;
+                            }
+                            if tx.send(channel_msg).await.is_err() {
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+
+                    // Otherwise only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     if event_type != "MESSAGE_CREATE" {
                         continue;
                     }
@@ -669,6 +1803,16 @@ impl Channel for DiscordChannel {
                         continue;
                     };
 
+                    // Poll results are posted in the bot's name; handle them before the self-check
+                    if d.get("type").and_then(serde_json::Value::as_u64) == Some(DISCORD_POLL_RESULT_MESSAGE_TYPE) {
+                        if let Some(channel_msg) = self.poll_results_message(d, &bot_user_id).await {
+                            if tx.send(channel_msg).await.is_err() {
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+
                     // Skip messages from the bot itself
                     let author_id = d.get("author").and_then(|a| a.get("id")).and_then(|i| i.as_str()).unwrap_or("");
                     if author_id == bot_user_id {
@@ -698,12 +1842,46 @@ impl Channel for DiscordChannel {
                     }
 
                     let content = d.get("content").and_then(|c| c.as_str()).unwrap_or("");
-                    let Some(clean_content) =
-                        normalize_incoming_content(content, self.mention_only, &bot_user_id)
-                    else {
-                        continue;
+                    let in_guild = d.get("guild_id").and_then(serde_json::Value::as_str).is_some();
+                    let prefixed = self
+                        .trigger_prefix
+                        .as_deref()
+                        .filter(|_| in_guild)
+                        .and_then(|prefix| super::trigger::strip_trigger_prefix(content, prefix));
+                    let clean_content = match prefixed {
+                        Some(prefixed) => prefixed,
+                        // With only a prefix configured, unprefixed guild messages are ignored.
+                        None if in_guild && self.trigger_prefix.is_some() && !self.mention_only => {
+                            continue;
+                        }
+                        None => match normalize_incoming_content(
+                            content,
+                            self.mention_only,
+                            &bot_user_id,
+                        ) {
+                            Some(clean_content) => clean_content,
+                            None => continue,
+                        },
                     };
 
+                    let voice_command = self
+                        .voice
+                        .as_ref()
+                        .zip(discord_voice::parse_voice_command(&clean_content));
+                    if let Some((voice, command)) = voice_command {
+                        let guild_id = d.get("guild_id").and_then(serde_json::Value::as_str);
+                        let text_channel_id = d.get("channel_id").and_then(serde_json::Value::as_str).unwrap_or("");
+                        if let Some(payload) = self
+                            .handle_voice_command(voice, command, guild_id, author_id, text_channel_id)
+                            .await
+                        {
+                            if write.send(Message::Text(payload.to_string().into())).await.is_err() {
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+
                     let attachment_text = {
                         let atts = d
                             .get("attachments")
@@ -752,6 +1930,10 @@ impl Channel for DiscordChannel {
                         });
                     }
 
+                    let (thread_target, thread_ts) = self
+                        .resolve_reply_thread(&channel_id, message_id, &final_content, in_guild)
+                        .await;
+
                     let channel_msg = ChannelMessage {
                         id: if message_id.is_empty() {
                             Uuid::new_v4().to_string()
@@ -759,10 +1941,10 @@ impl Channel for DiscordChannel {
                             format!("discord_{message_id}")
                         },
                         sender: author_id.to_string(),
-                        reply_target: if channel_id.is_empty() {
+                        reply_target: if thread_target.is_empty() {
                             author_id.to_string()
                         } else {
-                            channel_id.clone()
+                            thread_target
                         },
                         content: final_content,
                         channel: "discord".to_string(),
@@ -770,7 +1952,7 @@ impl Channel for DiscordChannel {
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
                             .as_secs(),
-                        thread_ts: None,
+                        thread_ts,
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -783,6 +1965,52 @@ impl Channel for DiscordChannel {
         Ok(())
     }
 
+    async fn recent_messages(
+        &self,
+        message: &ChannelMessage,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ChannelMessage>> {
+        let Some(message_id) = message.id.strip_prefix("discord_") else {
+            return Ok(Vec::new());
+        };
+        // Spoken messages have no message to read history before.
+        if discord_voice::parse_voice_reply_target(&message.reply_target).is_some() {
+            return Ok(Vec::new());
+        }
+        // DMs are answered to the author.
+        if message.reply_target == message.sender {
+            return Ok(Vec::new());
+        }
+        // A reply thread started on this message is empty; read the channel
+        // the message was posted in.
+        let channel_id = if message.reply_target == message_id {
+            self.parent_channel_id(message_id).await?
+        } else {
+            message.reply_target.clone()
+        };
+
+        let url = format!("https://discord.com/api/v10/channels/{channel_id}/messages");
+        let resp = self
+            .http_client()
+            .get(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .query(&[
+                ("before", message_id.to_string()),
+                (
+                    "limit",
+                    limit.clamp(1, DISCORD_MAX_HISTORY_MESSAGES).to_string(),
+                ),
+            ])
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Discord message history failed ({status}): {err}");
+        }
+        Ok(history_messages(&channel_id, &resp.json().await?))
+    }
+
     async fn health_check(&self) -> bool {
         self.http_client()
             .get("https://discord.com/api/v10/users/@me")
@@ -798,7 +2026,7 @@ impl Channel for DiscordChannel {
 
         let client = self.http_client();
         let token = self.bot_token.clone();
-        let channel_id = recipient.to_string();
+        let channel_id = discord_voice::text_channel_of(recipient).to_string();
 
         let handle = tokio::spawn(async move {
             let url = format!("https://discord.com/api/v10/channels/{channel_id}/typing");
@@ -880,6 +2108,29 @@ impl Channel for DiscordChannel {
 
         Ok(())
     }
+
+    async fn pin_message(&self, channel_id: &str, message_id: &str) -> anyhow::Result<()> {
+        let url = discord_pin_url(channel_id, message_id);
+
+        let resp = self
+            .http_client()
+            .put(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord pin message failed ({status}): {err}");
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -996,6 +2247,64 @@ mod tests {
         assert!(!contains_bot_mention("hi <@99999>", "12345"));
     }
 
+    #[test]
+    fn history_messages_are_oldest_first_with_display_names() {
+        let payload = serde_json::json!([
+            {
+                "id": "3",
+                "content": "latest",
+                "timestamp": "2024-01-01T00:00:10+00:00",
+                "author": { "id": "11", "username": "bob", "global_name": "Bob" }
+            },
+            {
+                "id": "2",
+                "content": "earlier",
+                "timestamp": "2024-01-01T00:00:00+00:00",
+                "author": { "id": "12", "username": "carol" }
+            }
+        ]);
+        let messages = history_messages("C1", &payload);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].id, "discord_2");
+        assert_eq!(messages[0].sender, "carol");
+        assert_eq!(messages[0].timestamp, 1_704_067_200);
+        assert_eq!(messages[1].sender, "Bob");
+        assert_eq!(messages[1].reply_target, "C1");
+    }
+
+    #[test]
+    fn poll_payload_and_counts_round_trip() {
+        let poll = Poll {
+            question: "Lunch?".into(),
+            options: vec!["Pizza".into(), "Sushi".into()],
+        };
+        let payload = poll_payload(&poll);
+        assert_eq!(payload["question"]["text"], "Lunch?");
+        assert_eq!(payload["answers"][1]["poll_media"]["text"], "Sushi");
+        assert_eq!(payload["duration"], DISCORD_POLL_DURATION_HOURS);
+
+        let message = serde_json::json!({
+            "poll": {
+                "question": { "text": "Lunch?" },
+                "answers": [
+                    { "answer_id": 1, "poll_media": { "text": "Pizza" } },
+                    { "answer_id": 2, "poll_media": { "text": "Sushi" } }
+                ],
+                "results": {
+                    "is_finalized": true,
+                    "answer_counts": [{ "id": 2, "count": 3, "me_voted": false }]
+                }
+            }
+        });
+        let (question, counts) = poll_counts(&message).unwrap();
+        assert_eq!(question, "Lunch?");
+        assert_eq!(
+            counts,
+            vec![("Pizza".to_string(), 0), ("Sushi".to_string(), 3)]
+        );
+        assert!(poll_counts(&serde_json::json!({ "content": "hi" })).is_none());
+    }
+
     #[test]
     fn normalize_incoming_content_requires_mention_when_enabled() {
         let cleaned = normalize_incoming_content("hello there", true, "12345");
@@ -1163,6 +2472,65 @@ mod tests {
         assert_eq!(reconstructed, msg);
     }
 
+    #[test]
+    fn thread_name_uses_first_non_empty_line() {
+        assert_eq!(
+            thread_name_from_content("\n  deploy failed again  \nlogs below"),
+            "deploy failed again"
+        );
+        assert_eq!(thread_name_from_content("   "), "Conversation");
+
+        let long = "x".repeat(150);
+        let name = thread_name_from_content(&long);
+        assert_eq!(name.chars().count(), MAX_THREAD_NAME_CHARS);
+        assert!(name.ends_with('…'));
+    }
+
+    #[test]
+    fn thread_channel_types() {
+        assert!(is_thread_channel_type(11));
+        assert!(is_thread_channel_type(12));
+        assert!(!is_thread_channel_type(0));
+        assert!(!is_thread_channel_type(15));
+    }
+
+    #[tokio::test]
+    async fn dm_messages_are_never_threaded() {
+        let ch = DiscordChannel::new("fake".into(), None, vec![], false, false)
+            .with_thread_replies(true);
+        let (target, thread) = ch.resolve_reply_thread("123", "456", "hi", false).await;
+        assert_eq!(target, "123");
+        assert!(thread.is_none());
+    }
+
+    #[tokio::test]
+    async fn messages_in_known_threads_stay_in_thread() {
+        let ch = DiscordChannel::new("fake".into(), None, vec![], false, false);
+        ch.thread_channels.lock().insert("789".into(), true);
+        let (target, thread) = ch.resolve_reply_thread("789", "456", "hi", true).await;
+        assert_eq!(target, "789");
+        assert_eq!(thread.as_deref(), Some("789"));
+    }
+
+    #[tokio::test]
+    async fn drafts_follow_stream_mode_and_throttle() {
+        let off = DiscordChannel::new("fake".into(), None, vec![], false, false);
+        assert!(!off.supports_draft_updates());
+        assert_eq!(
+            off.send_draft(&SendMessage::new("draft", "123"))
+                .await
+                .unwrap(),
+            None
+        );
+
+        let partial = DiscordChannel::new("fake".into(), None, vec![], false, false)
+            .with_streaming(StreamMode::Partial, 60_000);
+        assert!(partial.supports_draft_updates());
+        // A recent edit short-circuits before any network call.
+        partial.draft_throttle.mark("42");
+        assert!(partial.update_draft("123", "42", "more text").await.is_ok());
+    }
+
     #[test]
     fn typing_handles_start_empty() {
         let ch = DiscordChannel::new("fake".into(), None, vec![], false, false);
@@ -1255,6 +2623,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn discord_pin_url_strips_prefix() {
+        assert_eq!(
+            discord_pin_url("123", "discord_456"),
+            "https://discord.com/api/v10/channels/123/pins/456"
+        );
+        assert_eq!(
+            discord_pin_url("123", "456"),
+            "https://discord.com/api/v10/channels/123/pins/456"
+        );
+    }
+
     // ── Message ID edge cases ─────────────────────────────────────
 
     #[test]
@@ -1458,6 +2838,23 @@ mod tests {
         assert!(attachments.is_empty());
     }
 
+    #[test]
+    fn remote_gifs_and_stickers_become_image_embeds() {
+        let (_, attachments) = parse_attachment_markers(
+            "Nice!\n[GIF:https://example.com/party.gif]\n[STICKER:/tmp/wave.webp]\n[IMAGE:https://example.com/a.png]",
+        );
+        let mut embeds = Vec::new();
+        let rest = take_animated_embeds(attachments, &mut embeds);
+
+        assert_eq!(
+            embeds,
+            vec![json!({ "image": { "url": "https://example.com/party.gif" } })]
+        );
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[0].kind, DiscordAttachmentKind::Sticker);
+        assert_eq!(rest[1].kind, DiscordAttachmentKind::Image);
+    }
+
     #[test]
     fn classify_outgoing_attachments_splits_local_remote_and_unresolved() {
         let temp = tempfile::tempdir().expect("tempdir");
@@ -1501,4 +2898,101 @@ mod tests {
             "Done\nhttps://example.com/a.png\n[IMAGE:/tmp/missing.png]"
         );
     }
+
+    #[test]
+    fn parse_embed_markers_builds_embed_and_strips_marker() {
+        let message = "Build finished.\n[EMBED:title=Build #42|description=All checks passed|field:Duration=3m 12s|field:Branch=main|image=https://example.com/chart.png|color=#2ecc71]";
+        let (cleaned, embeds) = parse_embed_markers(message);
+
+        assert_eq!(cleaned, "Build finished.");
+        assert_eq!(embeds.len(), 1);
+        let embed = &embeds[0];
+        assert_eq!(embed["title"], "Build #42");
+        assert_eq!(embed["description"], "All checks passed");
+        assert_eq!(embed["fields"][0]["name"], "Duration");
+        assert_eq!(embed["fields"][1]["value"], "main");
+        assert_eq!(embed["image"]["url"], "https://example.com/chart.png");
+        assert_eq!(embed["color"], 0x2e_cc71);
+    }
+
+    #[test]
+    fn parse_embed_markers_keeps_markers_without_content() {
+        let message = "See [EMBED:color=#ffffff|image=/tmp/local.png] and [IMAGE:/tmp/a.png]";
+        let (cleaned, embeds) = parse_embed_markers(message);
+
+        assert_eq!(cleaned, message);
+        assert!(embeds.is_empty());
+    }
+
+    #[test]
+    fn parse_embed_color_accepts_hex_and_decimal() {
+        assert_eq!(parse_embed_color("#FF0000"), Some(0xFF_0000));
+        assert_eq!(parse_embed_color("0x00ff00"), Some(0x00_FF00));
+        assert_eq!(parse_embed_color("255"), Some(255));
+        assert_eq!(parse_embed_color("#1000000"), None);
+        assert_eq!(parse_embed_color("red"), None);
+    }
+
+    #[test]
+    fn slash_command_request_maps_commands_to_runtime_messages() {
+        let ask = serde_json::json!({
+            "name": "ask",
+            "options": [{ "name": "prompt", "type": 3, "value": " What is Rust? " }]
+        });
+        assert_eq!(
+            slash_command_request(&ask),
+            Some(("What is Rust?".to_string(), "> What is Rust?".to_string()))
+        );
+
+        let reset = serde_json::json!({ "name": "reset" });
+        assert_eq!(
+            slash_command_request(&reset).map(|(content, _)| content),
+            Some("/new".to_string())
+        );
+
+        let model = serde_json::json!({
+            "name": "model",
+            "options": [{ "name": "id", "type": 3, "value": "gpt-4o" }]
+        });
+        assert_eq!(
+            slash_command_request(&model).map(|(content, _)| content),
+            Some("/model gpt-4o".to_string())
+        );
+
+        let show_model = serde_json::json!({ "name": "model" });
+        assert_eq!(
+            slash_command_request(&show_model).map(|(content, _)| content),
+            Some("/model".to_string())
+        );
+
+        let voice = serde_json::json!({
+            "name": "voice",
+            "options": [{ "name": "action", "type": 3, "value": "join" }]
+        });
+        assert_eq!(
+            slash_command_request(&voice).map(|(content, _)| content),
+            Some("/voice join".to_string())
+        );
+
+        let unknown = serde_json::json!({ "name": "other" });
+        assert!(slash_command_request(&unknown).is_none());
+    }
+
+    #[test]
+    fn slash_command_definitions_cover_ask_reset_and_model() {
+        let definitions = slash_command_definitions(false);
+        let names: Vec<&str> = definitions
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|command| command["name"].as_str())
+            .collect();
+        assert_eq!(names, vec!["ask", "reset", "model"]);
+
+        let with_voice = slash_command_definitions(true);
+        assert_eq!(
+            with_voice.as_array().unwrap().last().unwrap()["name"],
+            "voice"
+        );
+    }
 }
@@ -0,0 +1,685 @@
+//! Voice channel mode for Discord.
+//!
+//! With `voice = true` in `[channels_config.discord]`, `/voice join` (as a
+//! slash command or a message) makes the bot join the voice channel the
+//! caller is in. Whenever an allowed user stops talking, what they said is
+//! transcribed with the `[transcription]` backend and handed to the agent as a
+//! `[Voice]` message. Replies are read aloud with the `[tts]` engine and also
+//! posted in the text channel where the join was requested. `/voice leave`
+//! disconnects.
+//!
+//! The voice connection itself (UDP, encryption, Opus) is handled by songbird
+//! and needs the `channel-discord-voice` feature; the gateway side (voice
+//! state tracking, opcode 4) lives in the Discord channel's `listen` loop.
+
+use super::traits::ChannelMessage;
+use crate::config::{TranscriptionConfig, TtsConfig};
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Sample rate received audio is decoded to; what Whisper-style backends use.
+pub const VOICE_SAMPLE_RATE: u32 = 16_000;
+/// Songbird hands over one 20 ms frame per speaker per tick.
+const FRAME_MS: u32 = 20;
+/// A pause this long ends an utterance.
+const END_OF_UTTERANCE_MS: u32 = 800;
+/// Shorter utterances (coughs, clicks, "uh") are dropped.
+const MIN_UTTERANCE_MS: u32 = 500;
+/// Longer monologues are cut and transcribed in pieces.
+const MAX_UTTERANCE_MS: u32 = 30_000;
+
+/// Reply targets of voice conversations start with this.
+const VOICE_TARGET_PREFIX: &str = "voice:";
+
+/// `/voice` subcommands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceCommand {
+    Join,
+    Leave,
+}
+
+/// Parse `/voice join` or `/voice leave` (case-insensitive) from message
+/// content that already had any mention or trigger prefix removed.
+pub fn parse_voice_command(content: &str) -> Option<VoiceCommand> {
+    let mut words = content.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("/voice") {
+        return None;
+    }
+    let command = match words.next()?.to_ascii_lowercase().as_str() {
+        "join" => VoiceCommand::Join,
+        "leave" => VoiceCommand::Leave,
+        _ => return None,
+    };
+    words.next().is_none().then_some(command)
+}
+
+/// Reply target of a voice conversation: spoken in the guild's voice
+/// channel, with a text copy in `text_channel_id`.
+pub fn voice_reply_target(guild_id: &str, text_channel_id: &str) -> String {
+    format!("{VOICE_TARGET_PREFIX}{guild_id}:{text_channel_id}")
+}
+
+/// `(guild_id, text_channel_id)` of a voice reply target.
+pub fn parse_voice_reply_target(recipient: &str) -> Option<(&str, &str)> {
+    let (guild_id, text_channel_id) = recipient
+        .strip_prefix(VOICE_TARGET_PREFIX)?
+        .split_once(':')?;
+    (!guild_id.is_empty() && !text_channel_id.is_empty()).then_some((guild_id, text_channel_id))
+}
+
+/// Channel ID to post text to: the text channel of a voice reply target,
+/// otherwise the recipient itself.
+pub fn text_channel_of(recipient: &str) -> &str {
+    parse_voice_reply_target(recipient).map_or(recipient, |(_, text_channel_id)| text_channel_id)
+}
+
+/// Gateway opcode 4 payload moving the bot into `channel_id`, or out of
+/// voice in the guild when `None`.
+pub fn voice_state_payload(guild_id: &str, channel_id: Option<&str>) -> serde_json::Value {
+    json!({
+        "op": 4,
+        "d": {
+            "guild_id": guild_id,
+            "channel_id": channel_id,
+            "self_mute": false,
+            "self_deaf": false
+        }
+    })
+}
+
+/// Voice channel each user is in, per guild, from `GUILD_CREATE` and
+/// `VOICE_STATE_UPDATE` events.
+#[derive(Debug, Default)]
+pub struct VoiceStates {
+    channels: HashMap<String, HashMap<String, String>>,
+}
+
+impl VoiceStates {
+    /// Record a `VOICE_STATE_UPDATE` (or a `GUILD_CREATE` voice state, which
+    /// lacks `guild_id`, with the guild passed in).
+    pub fn apply(&mut self, state: &serde_json::Value, guild_id: Option<&str>) {
+        let str_field = |name: &str| state.get(name).and_then(serde_json::Value::as_str);
+        let Some(guild_id) = str_field("guild_id").or(guild_id) else {
+            return;
+        };
+        let Some(user_id) = str_field("user_id") else {
+            return;
+        };
+        let guild = self.channels.entry(guild_id.to_string()).or_default();
+        match str_field("channel_id") {
+            Some(channel_id) => {
+                guild.insert(user_id.to_string(), channel_id.to_string());
+            }
+            None => {
+                guild.remove(user_id);
+            }
+        }
+    }
+
+    /// Record the voice states a `GUILD_CREATE` event carries.
+    pub fn apply_guild_create(&mut self, guild: &serde_json::Value) {
+        let Some(guild_id) = guild.get("id").and_then(serde_json::Value::as_str) else {
+            return;
+        };
+        self.channels.remove(guild_id);
+        for state in guild
+            .get("voice_states")
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            self.apply(state, Some(guild_id));
+        }
+    }
+
+    pub fn channel_of(&self, guild_id: &str, user_id: &str) -> Option<&str> {
+        self.channels
+            .get(guild_id)?
+            .get(user_id)
+            .map(String::as_str)
+    }
+}
+
+/// Collects one speaker's audio until they pause.
+#[derive(Debug, Default)]
+pub struct UtteranceBuffer {
+    samples: Vec<i16>,
+    silent_ms: u32,
+}
+
+impl UtteranceBuffer {
+    fn duration_ms(&self) -> u32 {
+        u32::try_from(self.samples.len()).unwrap_or(u32::MAX) / (VOICE_SAMPLE_RATE / 1000)
+    }
+
+    /// Add a decoded frame. Returns the utterance when it reached the
+    /// maximum length.
+    pub fn push(&mut self, frame: &[i16]) -> Option<Vec<i16>> {
+        self.samples.extend_from_slice(frame);
+        self.silent_ms = 0;
+        (self.duration_ms() >= MAX_UTTERANCE_MS).then(|| std::mem::take(&mut self.samples))
+    }
+
+    /// Note a tick without audio from this speaker. Returns the utterance
+    /// once the pause is long enough, unless it was too short to keep.
+    pub fn silence(&mut self) -> Option<Vec<i16>> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        self.silent_ms += FRAME_MS;
+        if self.silent_ms < END_OF_UTTERANCE_MS {
+            return None;
+        }
+        self.silent_ms = 0;
+        let samples = std::mem::take(&mut self.samples);
+        let long_enough = u32::try_from(samples.len()).unwrap_or(u32::MAX)
+            >= MIN_UTTERANCE_MS * (VOICE_SAMPLE_RATE / 1000);
+        long_enough.then_some(samples)
+    }
+}
+
+/// Mono 16-bit PCM as a WAV file, accepted by every transcription backend.
+pub fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let data_len = u32::try_from(samples.len() * 2).unwrap_or(u32::MAX);
+    let mut wav = Vec::with_capacity(44 + samples.len() * 2);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// Transcribe an utterance into the message handed to the agent.
+async fn utterance_message(
+    samples: Vec<i16>,
+    user_id: String,
+    reply_target: String,
+    config: &TranscriptionConfig,
+) -> Option<ChannelMessage> {
+    let wav = encode_wav(&samples, VOICE_SAMPLE_RATE);
+    let transcript = match super::transcription::transcribe_audio(wav, "voice.wav", config).await {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            tracing::warn!("Discord voice: transcription failed: {e}");
+            return None;
+        }
+    };
+    if transcript.trim().is_empty() {
+        return None;
+    }
+
+    Some(ChannelMessage {
+        id: format!("discord_voice_{}", uuid::Uuid::new_v4()),
+        sender: user_id,
+        reply_target,
+        content: super::transcription::voice_message_content(&transcript, None),
+        channel: "discord".to_string(),
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        thread_ts: None,
+    })
+}
+
+/// Join requested with opcode 4, waiting for the gateway's session and
+/// server details.
+#[derive(Debug, Default)]
+struct PendingJoin {
+    channel_id: String,
+    text_channel_id: String,
+    session_id: Option<String>,
+    server: Option<(String, String)>,
+}
+
+/// Everything needed to open the voice connection of a guild.
+#[derive(Debug, Clone)]
+pub struct VoiceConnectRequest {
+    pub guild_id: String,
+    pub channel_id: String,
+    pub text_channel_id: String,
+    pub session_id: String,
+    pub endpoint: String,
+    pub token: String,
+}
+
+/// Voice state and connections of one Discord bot.
+pub struct VoiceManager {
+    allowed_users: Vec<String>,
+    transcription: TranscriptionConfig,
+    tts: TtsConfig,
+    states: Mutex<VoiceStates>,
+    pending: Mutex<HashMap<String, PendingJoin>>,
+    #[cfg(feature = "channel-discord-voice")]
+    sessions: Mutex<HashMap<String, songbird::Driver>>,
+}
+
+impl VoiceManager {
+    pub fn new(
+        allowed_users: Vec<String>,
+        transcription: TranscriptionConfig,
+        tts: TtsConfig,
+    ) -> Self {
+        Self {
+            allowed_users,
+            transcription,
+            tts,
+            states: Mutex::new(VoiceStates::default()),
+            pending: Mutex::new(HashMap::new()),
+            #[cfg(feature = "channel-discord-voice")]
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn is_user_allowed(&self, user_id: &str) -> bool {
+        self.allowed_users.iter().any(|u| u == "*" || u == user_id)
+    }
+
+    pub fn states(&self) -> parking_lot::MutexGuard<'_, VoiceStates> {
+        self.states.lock()
+    }
+
+    /// Remember a join until the gateway confirms it.
+    pub fn begin_join(&self, guild_id: &str, channel_id: &str, text_channel_id: &str) {
+        self.pending.lock().insert(
+            guild_id.to_string(),
+            PendingJoin {
+                channel_id: channel_id.to_string(),
+                text_channel_id: text_channel_id.to_string(),
+                ..PendingJoin::default()
+            },
+        );
+    }
+
+    /// The bot's own `VOICE_STATE_UPDATE`. Returns the connection details
+    /// once the server update has arrived too.
+    pub fn session_update(&self, guild_id: &str, session_id: &str) -> Option<VoiceConnectRequest> {
+        let mut pending = self.pending.lock();
+        pending.get_mut(guild_id)?.session_id = Some(session_id.to_string());
+        Self::take_ready(&mut pending, guild_id)
+    }
+
+    /// A `VOICE_SERVER_UPDATE`. Returns the connection details once the
+    /// bot's voice state has arrived too.
+    pub fn server_update(
+        &self,
+        guild_id: &str,
+        endpoint: &str,
+        token: &str,
+    ) -> Option<VoiceConnectRequest> {
+        let mut pending = self.pending.lock();
+        pending.get_mut(guild_id)?.server = Some((endpoint.to_string(), token.to_string()));
+        Self::take_ready(&mut pending, guild_id)
+    }
+
+    fn take_ready(
+        pending: &mut HashMap<String, PendingJoin>,
+        guild_id: &str,
+    ) -> Option<VoiceConnectRequest> {
+        let join = pending.get(guild_id)?;
+        if join.session_id.is_none() || join.server.is_none() {
+            return None;
+        }
+        let join = pending.remove(guild_id)?;
+        let (endpoint, token) = join.server?;
+        Some(VoiceConnectRequest {
+            guild_id: guild_id.to_string(),
+            channel_id: join.channel_id,
+            text_channel_id: join.text_channel_id,
+            session_id: join.session_id?,
+            endpoint,
+            token,
+        })
+    }
+
+    /// Drop the guild's connection and any join in progress.
+    pub fn leave(&self, guild_id: &str) {
+        self.pending.lock().remove(guild_id);
+        #[cfg(feature = "channel-discord-voice")]
+        if let Some(mut driver) = self.sessions.lock().remove(guild_id) {
+            driver.leave();
+        }
+    }
+
+    /// Speak `content` in the guild's voice channel.
+    pub async fn speak(&self, guild_id: &str, content: &str) -> anyhow::Result<()> {
+        if !self.tts.enabled {
+            return Ok(());
+        }
+        let text = super::tts::speech_text(content, self.tts.max_chars);
+        if text.is_empty() {
+            return Ok(());
+        }
+        let audio = super::tts::synthesize(&text, &self.tts).await?;
+        self.play(guild_id, audio).await
+    }
+
+    #[cfg(feature = "channel-discord-voice")]
+    async fn play(&self, guild_id: &str, ogg_opus: Vec<u8>) -> anyhow::Result<()> {
+        // Songbird mixes 48 kHz stereo; raw f32 needs no extra demuxer.
+        let pcm = super::audio_tools::ffmpeg_convert(
+            &ogg_opus,
+            "ogg",
+            "raw",
+            &["-f", "f32le", "-ac", "2", "-ar", "48000"],
+        )
+        .await?;
+        let input = songbird::input::RawAdapter::new(std::io::Cursor::new(pcm), 48_000, 2);
+
+        let mut sessions = self.sessions.lock();
+        let Some(driver) = sessions.get_mut(guild_id) else {
+            anyhow::bail!("Discord voice: not connected in guild {guild_id}");
+        };
+        driver.play_input(input.into());
+        Ok(())
+    }
+
+    #[cfg(not(feature = "channel-discord-voice"))]
+    fn play(&self, _guild_id: &str, _ogg_opus: Vec<u8>) -> std::future::Ready<anyhow::Result<()>> {
+        std::future::ready(Err(anyhow::anyhow!(
+            "Discord voice support is disabled in this build (enable `channel-discord-voice`)"
+        )))
+    }
+
+    /// Connect in the background once the gateway confirmed a join.
+    pub fn spawn_connect(
+        self: std::sync::Arc<Self>,
+        request: VoiceConnectRequest,
+        bot_user_id: String,
+        tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) {
+        #[cfg(feature = "channel-discord-voice")]
+        tokio::spawn(async move {
+            if let Err(e) = self.connect(request, &bot_user_id, tx).await {
+                tracing::warn!("{e}");
+            }
+        });
+        #[cfg(not(feature = "channel-discord-voice"))]
+        {
+            let _ = (request, bot_user_id, tx);
+            tracing::warn!(
+                "Discord voice support is disabled in this build (enable `channel-discord-voice`)"
+            );
+        }
+    }
+
+    /// Open the voice connection and feed what allowed users say to `tx`.
+    #[cfg(feature = "channel-discord-voice")]
+    async fn connect(
+        self: std::sync::Arc<Self>,
+        request: VoiceConnectRequest,
+        bot_user_id: &str,
+        tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        use songbird::driver::{Channels, DecodeMode, SampleRate};
+        use songbird::id::{ChannelId, GuildId, UserId};
+        use songbird::{CoreEvent, Event};
+
+        let id = |value: &str| -> anyhow::Result<std::num::NonZeroU64> {
+            value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Discord voice: invalid snowflake '{value}'"))
+        };
+        let info = songbird::ConnectionInfo {
+            channel_id: Some(ChannelId::from(id(&request.channel_id)?)),
+            endpoint: request.endpoint.clone(),
+            guild_id: GuildId::from(id(&request.guild_id)?),
+            session_id: request.session_id.clone(),
+            token: request.token.clone(),
+            user_id: UserId::from(id(bot_user_id)?),
+        };
+
+        let config = songbird::Config::default()
+            .decode_mode(DecodeMode::Decode)
+            .decode_channels(Channels::Mono)
+            .decode_sample_rate(SampleRate::Hz16000);
+        let mut driver = songbird::Driver::new(config);
+        let receiver = VoiceReceiver::new(
+            std::sync::Arc::clone(&self),
+            voice_reply_target(&request.guild_id, &request.text_channel_id),
+            tx,
+        );
+        driver.add_global_event(
+            Event::Core(CoreEvent::SpeakingStateUpdate),
+            receiver.clone(),
+        );
+        driver.add_global_event(Event::Core(CoreEvent::VoiceTick), receiver);
+        let connecting = driver.connect(info);
+
+        if let Some(mut previous) = self
+            .sessions
+            .lock()
+            .insert(request.guild_id.clone(), driver)
+        {
+            previous.leave();
+        }
+        if let Err(e) = connecting.await {
+            self.sessions.lock().remove(&request.guild_id);
+            anyhow::bail!("Discord voice: connection failed: {e}");
+        }
+        tracing::info!(
+            "Discord voice: joined channel {} in guild {}",
+            request.channel_id,
+            request.guild_id
+        );
+        Ok(())
+    }
+}
+
+/// Songbird event handler buffering each speaker until they pause.
+#[cfg(feature = "channel-discord-voice")]
+#[derive(Clone)]
+struct VoiceReceiver {
+    inner: std::sync::Arc<VoiceReceiverInner>,
+}
+
+#[cfg(feature = "channel-discord-voice")]
+struct VoiceReceiverInner {
+    manager: std::sync::Arc<VoiceManager>,
+    reply_target: String,
+    tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+    /// RTP SSRC -> Discord user ID.
+    speakers: Mutex<HashMap<u32, String>>,
+    buffers: Mutex<HashMap<u32, UtteranceBuffer>>,
+}
+
+#[cfg(feature = "channel-discord-voice")]
+impl VoiceReceiver {
+    fn new(
+        manager: std::sync::Arc<VoiceManager>,
+        reply_target: String,
+        tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> Self {
+        Self {
+            inner: std::sync::Arc::new(VoiceReceiverInner {
+                manager,
+                reply_target,
+                tx,
+                speakers: Mutex::new(HashMap::new()),
+                buffers: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    fn finish(&self, ssrc: u32, samples: Vec<i16>) {
+        let Some(user_id) = self.inner.speakers.lock().get(&ssrc).cloned() else {
+            return;
+        };
+        let inner = std::sync::Arc::clone(&self.inner);
+        tokio::spawn(async move {
+            let message = utterance_message(
+                samples,
+                user_id,
+                inner.reply_target.clone(),
+                &inner.manager.transcription,
+            )
+            .await;
+            if let Some(message) = message {
+                let _ = inner.tx.send(message).await;
+            }
+        });
+    }
+}
+
+#[cfg(feature = "channel-discord-voice")]
+#[async_trait::async_trait]
+impl songbird::EventHandler for VoiceReceiver {
+    async fn act(&self, ctx: &songbird::EventContext<'_>) -> Option<songbird::Event> {
+        match ctx {
+            songbird::EventContext::SpeakingStateUpdate(speaking) => {
+                if let Some(user_id) = speaking.user_id {
+                    let user_id = user_id.0.to_string();
+                    if self.inner.manager.is_user_allowed(&user_id) {
+                        self.inner.speakers.lock().insert(speaking.ssrc, user_id);
+                    } else {
+                        tracing::debug!("Discord voice: ignoring unauthorized speaker {user_id}");
+                    }
+                }
+            }
+            songbird::EventContext::VoiceTick(tick) => {
+                let mut finished = Vec::new();
+                {
+                    let speakers = self.inner.speakers.lock();
+                    let mut buffers = self.inner.buffers.lock();
+                    for (ssrc, data) in &tick.speaking {
+                        let Some(frame) = data.decoded_voice.as_deref() else {
+                            continue;
+                        };
+                        if !speakers.contains_key(ssrc) {
+                            continue;
+                        }
+                        if let Some(samples) = buffers.entry(*ssrc).or_default().push(frame) {
+                            finished.push((*ssrc, samples));
+                        }
+                    }
+                    for ssrc in &tick.silent {
+                        if let Some(samples) =
+                            buffers.get_mut(ssrc).and_then(UtteranceBuffer::silence)
+                        {
+                            finished.push((*ssrc, samples));
+                        }
+                    }
+                }
+                for (ssrc, samples) in finished {
+                    self.finish(ssrc, samples);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_voice_commands() {
+        assert_eq!(parse_voice_command("/voice join"), Some(VoiceCommand::Join));
+        assert_eq!(
+            parse_voice_command("  /Voice LEAVE "),
+            Some(VoiceCommand::Leave)
+        );
+        assert_eq!(parse_voice_command("/voice"), None);
+        assert_eq!(parse_voice_command("/voice join now"), None);
+        assert_eq!(parse_voice_command("please /voice join"), None);
+    }
+
+    #[test]
+    fn voice_reply_target_round_trips() {
+        let target = voice_reply_target("111", "222");
+        assert_eq!(parse_voice_reply_target(&target), Some(("111", "222")));
+        assert_eq!(text_channel_of(&target), "222");
+        assert_eq!(text_channel_of("333"), "333");
+        assert_eq!(parse_voice_reply_target("voice:111"), None);
+    }
+
+    #[test]
+    fn tracks_voice_states() {
+        let mut states = VoiceStates::default();
+        states.apply_guild_create(&json!({
+            "id": "g1",
+            "voice_states": [{ "user_id": "u1", "channel_id": "v1" }]
+        }));
+        assert_eq!(states.channel_of("g1", "u1"), Some("v1"));
+
+        states.apply(
+            &json!({ "guild_id": "g1", "user_id": "u1", "channel_id": "v2" }),
+            None,
+        );
+        assert_eq!(states.channel_of("g1", "u1"), Some("v2"));
+
+        states.apply(
+            &json!({ "guild_id": "g1", "user_id": "u1", "channel_id": null }),
+            None,
+        );
+        assert_eq!(states.channel_of("g1", "u1"), None);
+    }
+
+    #[test]
+    fn utterance_ends_after_pause_and_drops_short_sounds() {
+        let frame = vec![100i16; (VOICE_SAMPLE_RATE / 1000 * FRAME_MS) as usize];
+        let pause_ticks = END_OF_UTTERANCE_MS / FRAME_MS;
+
+        let mut buffer = UtteranceBuffer::default();
+        for _ in 0..50 {
+            assert!(buffer.push(&frame).is_none());
+        }
+        for _ in 1..pause_ticks {
+            assert!(buffer.silence().is_none());
+        }
+        assert_eq!(buffer.silence().map(|s| s.len()), Some(frame.len() * 50));
+
+        buffer.push(&frame);
+        let short = (0..pause_ticks).find_map(|_| buffer.silence());
+        assert!(short.is_none());
+        assert!(buffer.silence().is_none());
+    }
+
+    #[test]
+    fn wav_header_describes_mono_pcm() {
+        let wav = encode_wav(&[1, -1], VOICE_SAMPLE_RATE);
+        assert_eq!(wav.len(), 48);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(
+            u32::from_le_bytes(wav[24..28].try_into().unwrap()),
+            VOICE_SAMPLE_RATE
+        );
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 4);
+        assert_eq!(&wav[44..], &[1, 0, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn join_is_ready_after_session_and_server_updates() {
+        let manager = VoiceManager::new(
+            vec!["*".into()],
+            TranscriptionConfig::default(),
+            TtsConfig::default(),
+        );
+        assert!(manager.session_update("g1", "s1").is_none());
+
+        manager.begin_join("g1", "v1", "t1");
+        assert!(manager
+            .server_update("g1", "voice.discord.gg", "tok")
+            .is_none());
+        let request = manager.session_update("g1", "s1").unwrap();
+        assert_eq!(request.channel_id, "v1");
+        assert_eq!(request.text_channel_id, "t1");
+        assert_eq!(request.endpoint, "voice.discord.gg");
+        assert!(manager.session_update("g1", "s1").is_none());
+    }
+}
@@ -0,0 +1,78 @@
+//! Shared pieces for channels that stream replies by editing a placeholder
+//! message in place (see [`Channel::update_draft`](super::traits::Channel::update_draft)).
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Rate limit for draft edits, tracked per draft message.
+pub struct DraftThrottle {
+    interval: Duration,
+    last_edit: Mutex<HashMap<String, Instant>>,
+}
+
+impl DraftThrottle {
+    pub fn new(interval_ms: u64) -> Self {
+        Self {
+            interval: Duration::from_millis(interval_ms),
+            last_edit: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `draft_id` may be edited now.
+    pub fn ready(&self, draft_id: &str) -> bool {
+        self.last_edit
+            .lock()
+            .get(draft_id)
+            .is_none_or(|last| last.elapsed() >= self.interval)
+    }
+
+    /// Record that `draft_id` was just posted or edited.
+    pub fn mark(&self, draft_id: &str) {
+        self.last_edit
+            .lock()
+            .insert(draft_id.to_string(), Instant::now());
+    }
+
+    /// Forget `draft_id` once it is finalized or cancelled.
+    pub fn clear(&self, draft_id: &str) {
+        self.last_edit.lock().remove(draft_id);
+    }
+}
+
+/// Longest prefix of `text` with at most `max_chars` characters, for
+/// mid-stream edits on platforms with a message length limit.
+pub fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_waits_for_interval_per_draft() {
+        let throttle = DraftThrottle::new(60_000);
+        assert!(throttle.ready("a"));
+        throttle.mark("a");
+        assert!(!throttle.ready("a"));
+        assert!(throttle.ready("b"));
+        throttle.clear("a");
+        assert!(throttle.ready("a"));
+
+        let immediate = DraftThrottle::new(0);
+        immediate.mark("a");
+        assert!(immediate.ready("a"));
+    }
+
+    #[test]
+    fn truncate_chars_respects_char_boundaries() {
+        assert_eq!(truncate_chars("hello", 10), "hello");
+        assert_eq!(truncate_chars("hello", 3), "hel");
+        assert_eq!(truncate_chars("héllo😀", 5), "héllo");
+        assert_eq!(truncate_chars("", 0), "");
+    }
+}
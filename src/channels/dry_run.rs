@@ -0,0 +1,283 @@
+//! Dry-run preview mode for channel replies.
+//!
+//! In a previewed chat the agent runs its normal turn, but every tool it
+//! calls is swapped for a stand-in that records the call instead of running
+//! it. Nothing is sent back to the original chat: the composed reply and the
+//! planned tool calls go to the configured review channel, or to the console
+//! when none is set. Chats are selected by `[agent.dry_run]` and can be
+//! toggled at runtime with `/dryrun on|off`.
+
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::DryRunConfig;
+use crate::tools::{Tool, ToolResult};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+const MAX_ARGS_PREVIEW_CHARS: usize = 300;
+const MAX_INBOUND_PREVIEW_CHARS: usize = 200;
+
+/// A tool call the agent asked for while previewing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedCall {
+    pub tool: String,
+    pub args: serde_json::Value,
+}
+
+/// Tool calls collected during one previewed turn.
+#[derive(Clone, Default)]
+pub struct PlanLog(Arc<Mutex<Vec<PlannedCall>>>);
+
+impl PlanLog {
+    fn record(&self, tool: &str, args: serde_json::Value) {
+        self.0.lock().push(PlannedCall {
+            tool: tool.to_string(),
+            args,
+        });
+    }
+
+    /// Drain the recorded calls in the order they were made.
+    pub fn take(&self) -> Vec<PlannedCall> {
+        std::mem::take(&mut *self.0.lock())
+    }
+}
+
+/// Stand-in that advertises a real tool's spec but only records calls.
+struct PlanOnlyTool {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+    plans: PlanLog,
+}
+
+#[async_trait]
+impl Tool for PlanOnlyTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.parameters.clone()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.plans.record(&self.name, args);
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "[dry-run] `{}` was not executed. Assume it would succeed and continue without its output.",
+                self.name
+            ),
+            error: None,
+        })
+    }
+}
+
+/// Wrap every tool in `tools` so calls are recorded into `plans` instead of run.
+pub fn plan_only_tools(tools: &[Box<dyn Tool>], plans: &PlanLog) -> Vec<Box<dyn Tool>> {
+    tools
+        .iter()
+        .map(|tool| {
+            Box::new(PlanOnlyTool {
+                name: tool.name().to_string(),
+                description: tool.description().to_string(),
+                parameters: tool.parameters_schema(),
+                plans: plans.clone(),
+            }) as Box<dyn Tool>
+        })
+        .collect()
+}
+
+/// Which chats are previewed, plus per-chat `/dryrun` overrides.
+pub struct DryRunMode {
+    config: DryRunConfig,
+    overrides: Mutex<HashMap<String, bool>>,
+}
+
+fn chat_key(channel: &str, reply_target: &str) -> String {
+    format!("{channel}:{reply_target}")
+}
+
+impl DryRunMode {
+    pub fn new(config: DryRunConfig) -> Self {
+        Self {
+            config,
+            overrides: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether replies to `reply_target` on `channel` are previewed.
+    pub fn is_active(&self, channel: &str, reply_target: &str) -> bool {
+        let key = chat_key(channel, reply_target);
+        if let Some(on) = self.overrides.lock().get(&key) {
+            return *on;
+        }
+        self.config.enabled
+            || self
+                .config
+                .chats
+                .iter()
+                .any(|chat| chat == channel || *chat == key)
+    }
+
+    /// Turn previewing on or off for one chat, overriding the config.
+    pub fn set(&self, channel: &str, reply_target: &str, on: bool) {
+        self.overrides
+            .lock()
+            .insert(chat_key(channel, reply_target), on);
+    }
+
+    /// Human-readable description of where previews go.
+    pub fn review_target(&self) -> String {
+        match (&self.config.review_channel, &self.config.review_to) {
+            (Some(channel), Some(to)) => format!("{channel}:{to}"),
+            _ => "the console".to_string(),
+        }
+    }
+
+    /// Send `preview` to the review channel, falling back to the console.
+    pub async fn deliver(&self, channels: &HashMap<String, Arc<dyn Channel>>, preview: &str) {
+        if let (Some(name), Some(to)) = (&self.config.review_channel, &self.config.review_to) {
+            match channels.get(name) {
+                Some(channel) => match channel.send(&SendMessage::new(preview, to)).await {
+                    Ok(()) => return,
+                    Err(e) => tracing::warn!("Failed to deliver dry-run preview to {name}: {e}"),
+                },
+                None => tracing::warn!("Dry-run review channel '{name}' is not running"),
+            }
+        }
+        println!("{preview}");
+    }
+}
+
+/// Render what would have been sent for `msg`.
+pub fn render_preview(msg: &ChannelMessage, reply: &str, plans: &[PlannedCall]) -> String {
+    let mut out = format!(
+        "🧪 Dry run — reply to {} on {} (from {})\n> {}",
+        msg.reply_target,
+        msg.channel,
+        msg.sender,
+        crate::util::truncate_with_ellipsis(msg.content.trim(), MAX_INBOUND_PREVIEW_CHARS)
+    );
+    if plans.is_empty() {
+        out.push_str("\n\nNo tool calls planned.");
+    } else {
+        out.push_str("\n\nPlanned tool calls (not executed):");
+        for (i, plan) in plans.iter().enumerate() {
+            let args =
+                crate::util::truncate_with_ellipsis(&plan.args.to_string(), MAX_ARGS_PREVIEW_CHARS);
+            let _ = write!(out, "\n{}. {} {args}", i + 1, plan.tool);
+        }
+    }
+    let _ = write!(out, "\n\nReply:\n{reply}");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct CountingTool(Arc<std::sync::atomic::AtomicUsize>);
+
+    #[async_trait]
+    impl Tool for CountingTool {
+        fn name(&self) -> &str {
+            "shell"
+        }
+
+        fn description(&self) -> &str {
+            "Run a command"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(ToolResult {
+                success: true,
+                output: "ran".into(),
+                error: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_only_tools_record_without_executing() {
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(CountingTool(Arc::clone(&runs)))];
+        let plans = PlanLog::default();
+        let wrapped = plan_only_tools(&tools, &plans);
+
+        assert_eq!(wrapped[0].spec().name, "shell");
+        assert_eq!(wrapped[0].parameters_schema(), json!({"type": "object"}));
+        let result = wrapped[0].execute(json!({"command": "ls"})).await.unwrap();
+        assert!(result.output.contains("not executed"));
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(
+            plans.take(),
+            vec![PlannedCall {
+                tool: "shell".into(),
+                args: json!({"command": "ls"}),
+            }]
+        );
+        assert!(plans.take().is_empty());
+    }
+
+    #[test]
+    fn chat_selection_and_overrides() {
+        let mode = DryRunMode::new(DryRunConfig {
+            chats: vec!["discord".into(), "telegram:42".into()],
+            ..DryRunConfig::default()
+        });
+        assert!(mode.is_active("discord", "any"));
+        assert!(mode.is_active("telegram", "42"));
+        assert!(!mode.is_active("telegram", "7"));
+
+        mode.set("telegram", "7", true);
+        mode.set("discord", "general", false);
+        assert!(mode.is_active("telegram", "7"));
+        assert!(!mode.is_active("discord", "general"));
+        assert!(mode.is_active("discord", "other"));
+
+        let global = DryRunMode::new(DryRunConfig {
+            enabled: true,
+            ..DryRunConfig::default()
+        });
+        assert!(global.is_active("slack", "C1"));
+        assert_eq!(global.review_target(), "the console");
+    }
+
+    #[test]
+    fn preview_lists_plans_and_reply() {
+        let msg = ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            reply_target: "42".into(),
+            content: "clean up /tmp".into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+        };
+        let preview = render_preview(
+            &msg,
+            "Done, removed 3 files.",
+            &[PlannedCall {
+                tool: "shell".into(),
+                args: json!({"command": "rm -rf /tmp/x"}),
+            }],
+        );
+        assert!(preview.contains("reply to 42 on telegram (from alice)"));
+        assert!(preview.contains("1. shell {\"command\":\"rm -rf /tmp/x\"}"));
+        assert!(preview.ends_with("Reply:\nDone, removed 3 files."));
+        assert!(render_preview(&msg, "ok", &[]).contains("No tool calls planned."));
+    }
+}
@@ -14,7 +14,8 @@ use async_imap::types::Fetch;
 use async_imap::Session;
 use async_trait::async_trait;
 use futures_util::TryStreamExt;
-use lettre::message::SinglePart;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment as MailAttachment, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 use mail_parser::{MessageParser, MimeHeaders};
@@ -22,7 +23,9 @@ use rustls::{ClientConfig, RootCertStore};
 use rustls_pki_types::DnsName;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::TcpStream;
@@ -33,6 +36,7 @@ use tokio_rustls::TlsConnector;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use super::attachment::{is_local_path, parse_attachment_markers};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 
 /// Email channel configuration
@@ -114,10 +118,20 @@ impl Default for EmailConfig {
 
 type ImapSession = Session<TlsStream<TcpStream>>;
 
+/// Reply headers for an email thread, taken from its latest inbound message.
+#[derive(Debug, Clone, PartialEq)]
+struct ThreadContext {
+    subject: String,
+    last_message_id: String,
+    references: Vec<String>,
+}
+
 /// Email channel — IMAP IDLE for instant push notifications, SMTP for outbound
 pub struct EmailChannel {
     pub config: EmailConfig,
     seen_messages: Arc<Mutex<HashSet<String>>>,
+    /// Thread root Message-ID → reply headers, so replies land in the same thread.
+    threads: Arc<Mutex<HashMap<String, ThreadContext>>>,
 }
 
 impl EmailChannel {
@@ -125,6 +139,7 @@ impl EmailChannel {
         Self {
             config,
             seen_messages: Arc::new(Mutex::new(HashSet::new())),
+            threads: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -270,6 +285,13 @@ impl EmailChannel {
                         .message_id()
                         .map(|s| s.to_string())
                         .unwrap_or_else(|| format!("gen-{}", Uuid::new_v4()));
+                    let references: Vec<String> = parsed
+                        .references()
+                        .as_text_list()
+                        .map(|refs| refs.iter().map(|r| r.to_string()).collect())
+                        .unwrap_or_default();
+                    let in_reply_to = parsed.in_reply_to().as_text().map(|s| s.to_string());
+                    let thread_id = thread_root_id(&references, in_reply_to.as_deref(), &msg_id);
 
                     #[allow(clippy::cast_sign_loss)]
                     let ts = parsed
@@ -300,8 +322,11 @@ impl EmailChannel {
                         _uid: uid,
                         msg_id,
                         sender,
+                        subject,
                         content,
                         timestamp: ts,
+                        thread_id,
+                        references,
                     });
                 }
             }
@@ -452,6 +477,19 @@ impl EmailChannel {
                 continue;
             }
 
+            let mut references = email.references;
+            if !email.msg_id.starts_with("gen-") {
+                references.push(email.msg_id.clone());
+            }
+            self.threads.lock().await.insert(
+                email.thread_id.clone(),
+                ThreadContext {
+                    subject: email.subject,
+                    last_message_id: email.msg_id.clone(),
+                    references,
+                },
+            );
+
             let msg = ChannelMessage {
                 id: email.msg_id,
                 reply_target: email.sender.clone(),
@@ -459,7 +497,7 @@ impl EmailChannel {
                 content: email.content,
                 channel: "email".to_string(),
                 timestamp: email.timestamp,
-                thread_ts: None,
+                thread_ts: Some(email.thread_id),
             };
 
             if tx.send(msg).await.is_err() {
@@ -472,29 +510,93 @@ impl EmailChannel {
     }
 
     fn create_smtp_transport(&self) -> Result<SmtpTransport> {
-        let creds = Credentials::new(self.config.username.clone(), self.config.password.clone());
-        let transport = if self.config.smtp_tls {
-            SmtpTransport::relay(&self.config.smtp_host)?
-                .port(self.config.smtp_port)
-                .credentials(creds)
-                .build()
-        } else {
-            SmtpTransport::builder_dangerous(&self.config.smtp_host)
-                .port(self.config.smtp_port)
-                .credentials(creds)
-                .build()
-        };
-        Ok(transport)
+        smtp_transport(&self.config)
     }
 }
 
+/// SMTP transport for the server settings in `config`.
+pub(crate) fn smtp_transport(config: &EmailConfig) -> Result<SmtpTransport> {
+    let creds = Credentials::new(config.username.clone(), config.password.clone());
+    let transport = if config.smtp_tls {
+        SmtpTransport::relay(&config.smtp_host)?
+            .port(config.smtp_port)
+            .credentials(creds)
+            .build()
+    } else {
+        SmtpTransport::builder_dangerous(&config.smtp_host)
+            .port(config.smtp_port)
+            .credentials(creds)
+            .build()
+    };
+    Ok(transport)
+}
+
 /// Internal struct for parsed email data
 struct ParsedEmail {
     _uid: u32,
     msg_id: String,
     sender: String,
+    subject: String,
     content: String,
     timestamp: u64,
+    /// Root Message-ID of the thread this email belongs to.
+    thread_id: String,
+    /// Message-IDs from the `References` header, oldest first.
+    references: Vec<String>,
+}
+
+/// Pick the conversation key for an email: the oldest `References` entry,
+/// then `In-Reply-To`, then the message's own ID for a new thread.
+fn thread_root_id(references: &[String], parent_id: Option<&str>, message_id: &str) -> String {
+    references
+        .first()
+        .map(String::as_str)
+        .or(parent_id)
+        .unwrap_or(message_id)
+        .to_string()
+}
+
+/// Prefix a subject with `Re: ` unless it already is a reply subject.
+fn reply_subject(subject: &str) -> String {
+    let trimmed = subject.trim();
+    if trimmed
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("re:"))
+    {
+        trimmed.to_string()
+    } else {
+        format!("Re: {}", trimmed)
+    }
+}
+
+/// Wrap a bare Message-ID in angle brackets for outbound headers.
+fn angle_id(id: &str) -> String {
+    format!("<{}>", id.trim_matches(|c| c == '<' || c == '>'))
+}
+
+/// Content type for an outbound attachment, based on its file extension.
+pub(crate) fn attachment_content_type(path: &Path) -> ContentType {
+    let mime = match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        Some("txt" | "log" | "md") => "text/plain",
+        Some("csv") => "text/csv",
+        Some("json") => "application/json",
+        Some("zip") => "application/zip",
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") => "audio/ogg",
+        Some("mp4") => "video/mp4",
+        _ => "application/octet-stream",
+    };
+    ContentType::parse(mime).unwrap_or(ContentType::TEXT_PLAIN)
 }
 
 /// Result from waiting on IDLE
@@ -511,24 +613,82 @@ impl Channel for EmailChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> Result<()> {
-        // Use explicit subject if provided, otherwise fall back to legacy parsing or default
+        let thread = match message.thread_ts.as_deref() {
+            Some(thread_id) => self.threads.lock().await.get(thread_id).cloned(),
+            None => None,
+        };
+
+        // Use explicit subject if provided, otherwise fall back to legacy parsing,
+        // the thread's subject, or a default
         let (subject, body) = if let Some(ref subj) = message.subject {
-            (subj.as_str(), message.content.as_str())
+            (subj.clone(), message.content.as_str())
         } else if message.content.starts_with("Subject: ") {
             if let Some(pos) = message.content.find('\n') {
-                (&message.content[9..pos], message.content[pos + 1..].trim())
+                (
+                    message.content[9..pos].to_string(),
+                    message.content[pos + 1..].trim(),
+                )
             } else {
-                ("ZeroClaw Message", message.content.as_str())
+                ("ZeroClaw Message".to_string(), message.content.as_str())
             }
+        } else if let Some(ref ctx) = thread {
+            (reply_subject(&ctx.subject), message.content.as_str())
         } else {
-            ("ZeroClaw Message", message.content.as_str())
+            ("ZeroClaw Message".to_string(), message.content.as_str())
         };
 
-        let email = Message::builder()
+        let (mut text, markers) = parse_attachment_markers(body);
+        let mut files = Vec::new();
+        for attachment in markers {
+            if is_local_path(&attachment.target) {
+                let path = Path::new(&attachment.target);
+                let bytes = tokio::fs::read(path).await.map_err(|e| {
+                    anyhow!("Failed to read attachment {}: {}", attachment.target, e)
+                })?;
+                let filename = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("attachment")
+                    .to_string();
+                files
+                    .push(MailAttachment::new(filename).body(bytes, attachment_content_type(path)));
+            } else {
+                // Remote targets are linked rather than fetched.
+                let _ = write!(
+                    text,
+                    "\n\n{}: {}",
+                    attachment.kind.marker_name(),
+                    attachment.target
+                );
+            }
+        }
+
+        let mut builder = Message::builder()
             .from(self.config.from_address.parse()?)
             .to(message.recipient.parse()?)
-            .subject(subject)
-            .singlepart(SinglePart::plain(body.to_string()))?;
+            .subject(subject);
+        if let Some(ref ctx) = thread {
+            builder = builder.in_reply_to(angle_id(&ctx.last_message_id));
+            if !ctx.references.is_empty() {
+                let references = ctx
+                    .references
+                    .iter()
+                    .map(|r| angle_id(r))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                builder = builder.references(references);
+            }
+        }
+
+        let email = if files.is_empty() {
+            builder.singlepart(SinglePart::plain(text))?
+        } else {
+            let mut multipart = MultiPart::mixed().singlepart(SinglePart::plain(text));
+            for file in files {
+                multipart = multipart.singlepart(file);
+            }
+            builder.multipart(multipart)?
+        };
 
         let transport = self.create_smtp_transport()?;
         transport.send(&email)?;
@@ -568,6 +728,70 @@ impl Channel for EmailChannel {
 mod tests {
     use super::*;
 
+    #[test]
+    fn thread_root_prefers_first_reference() {
+        let refs = vec!["root@x".to_string(), "mid@x".to_string()];
+        assert_eq!(thread_root_id(&refs, Some("mid@x"), "new@x"), "root@x");
+    }
+
+    #[test]
+    fn thread_root_falls_back_to_in_reply_to_then_own_id() {
+        assert_eq!(thread_root_id(&[], Some("parent@x"), "new@x"), "parent@x");
+        assert_eq!(thread_root_id(&[], None, "new@x"), "new@x");
+    }
+
+    #[test]
+    fn reply_subject_adds_prefix_once() {
+        assert_eq!(reply_subject("Status"), "Re: Status");
+        assert_eq!(reply_subject("RE: Status"), "RE: Status");
+        assert_eq!(reply_subject("re: status"), "re: status");
+    }
+
+    #[test]
+    fn reply_subject_handles_non_ascii_subjects() {
+        assert_eq!(reply_subject("Ré: café"), "Re: Ré: café");
+        assert_eq!(reply_subject("日本語"), "Re: 日本語");
+        assert_eq!(reply_subject("a日本"), "Re: a日本");
+    }
+
+    #[test]
+    fn angle_id_wraps_bare_ids_only_once() {
+        assert_eq!(angle_id("abc@host"), "<abc@host>");
+        assert_eq!(angle_id("<abc@host>"), "<abc@host>");
+    }
+
+    #[test]
+    fn attachment_content_type_maps_extensions() {
+        assert_eq!(
+            attachment_content_type(Path::new("/tmp/a.PDF")),
+            ContentType::parse("application/pdf").unwrap()
+        );
+        assert_eq!(
+            attachment_content_type(Path::new("/tmp/photo.jpeg")),
+            ContentType::parse("image/jpeg").unwrap()
+        );
+        assert_eq!(
+            attachment_content_type(Path::new("/tmp/blob")),
+            ContentType::parse("application/octet-stream").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn send_fails_fast_on_missing_attachment() {
+        let channel = EmailChannel::new(EmailConfig {
+            from_address: "bot@example.com".into(),
+            ..EmailConfig::default()
+        });
+        let err = channel
+            .send(&SendMessage::new(
+                "Report [DOCUMENT:/nonexistent/zeroclaw-report.pdf]",
+                "user@example.com",
+            ))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to read attachment"));
+    }
+
     #[test]
     fn default_smtp_port_uses_tls_port() {
         assert_eq!(default_smtp_port(), 465);
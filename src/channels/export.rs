@@ -0,0 +1,319 @@
+//! Conversation export for `/export` and the admin API.
+//!
+//! A conversation's in-memory history is rendered as Markdown or JSON.
+//! Attachments are listed as references, never inlined: `[IMAGE:…]`-style
+//! markers and Telegram's `[Document: name] /path` lines are collected per
+//! message, with local files under the workspace given as workspace-relative
+//! paths so an archive stays meaningful next to a copy of the workspace.
+
+use super::attachment::{self, parse_attachment_markers};
+use crate::providers::ChatMessage;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Directory under the workspace that `/export` writes to.
+pub const EXPORTS_DIR: &str = "exports";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    /// `markdown`/`md` or `json`, case-insensitively.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+}
+
+/// A file or URL a message referred to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedAttachment {
+    /// Lowercase marker kind: `image`, `document`, `voice`, …
+    pub kind: String,
+    /// Path or URL as it appeared in the message.
+    pub target: String,
+    /// `target` relative to the workspace, for local files inside it.
+    pub workspace_path: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedMessage {
+    pub role: String,
+    pub content: String,
+    pub attachments: Vec<ExportedAttachment>,
+}
+
+/// One conversation, ready to render.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConversationExport {
+    /// History key of the conversation.
+    pub conversation: String,
+    pub channel: String,
+    /// RFC 3339 time of the export.
+    pub exported_at: String,
+    pub messages: Vec<ExportedMessage>,
+}
+
+impl ConversationExport {
+    /// Collect the user and assistant turns of `history`.
+    pub fn new(
+        conversation: &str,
+        channel: &str,
+        history: &[ChatMessage],
+        workspace_dir: &Path,
+    ) -> Self {
+        let messages = history
+            .iter()
+            .filter(|turn| turn.role == "user" || turn.role == "assistant")
+            .map(|turn| ExportedMessage {
+                role: turn.role.clone(),
+                content: turn.content.trim().to_string(),
+                attachments: attachments_in(&turn.content, workspace_dir),
+            })
+            .collect();
+        Self {
+            conversation: conversation.to_string(),
+            channel: channel.to_string(),
+            exported_at: Utc::now().to_rfc3339(),
+            messages,
+        }
+    }
+
+    pub fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Markdown => self.to_markdown(),
+            ExportFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = String::from("# Conversation export\n\n");
+        let _ = writeln!(out, "- Conversation: `{}`", self.conversation);
+        let _ = writeln!(out, "- Channel: {}", self.channel);
+        let _ = writeln!(out, "- Exported: {}", self.exported_at);
+        let _ = writeln!(out, "- Messages: {}", self.messages.len());
+
+        for message in &self.messages {
+            let speaker = if message.role == "user" {
+                "User"
+            } else {
+                "Assistant"
+            };
+            let _ = write!(out, "\n## {speaker}\n\n{}\n", message.content);
+            if message.attachments.is_empty() {
+                continue;
+            }
+            out.push_str("\nAttachments:\n");
+            for attachment in &message.attachments {
+                match &attachment.workspace_path {
+                    Some(path) => {
+                        let _ = writeln!(out, "- {}: `{path}` (workspace)", attachment.kind);
+                    }
+                    None => {
+                        let _ = writeln!(out, "- {}: {}", attachment.kind, attachment.target);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Write the export to `{workspace}/exports/` and return its path.
+    pub fn write_to_workspace(
+        &self,
+        workspace_dir: &Path,
+        format: ExportFormat,
+    ) -> Result<PathBuf> {
+        let dir = workspace_dir.join(EXPORTS_DIR);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(format!(
+            "{}-{}.{}",
+            file_stem(&self.conversation),
+            Utc::now().format("%Y%m%d-%H%M%S"),
+            format.extension()
+        ));
+        std::fs::write(&path, self.render(format))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+fn attachments_in(content: &str, workspace_dir: &Path) -> Vec<ExportedAttachment> {
+    let mut found = Vec::new();
+    let mut text = String::with_capacity(content.len());
+    for line in content.lines() {
+        // Telegram stores incoming files as `[Document: name] /path`, which
+        // would otherwise parse as a marker targeting `name`.
+        match line
+            .trim()
+            .strip_prefix("[Document: ")
+            .and_then(|rest| rest.split_once("] "))
+        {
+            Some((_, path)) if !path.trim().is_empty() => {
+                found.push(("document".to_string(), path.trim().to_string()));
+            }
+            _ => {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+    }
+    let (_, markers) = parse_attachment_markers(&text);
+    found.extend(markers.into_iter().map(|marker| {
+        (
+            marker.kind.marker_name().to_ascii_lowercase(),
+            marker.target,
+        )
+    }));
+
+    found
+        .into_iter()
+        .map(|(kind, target)| {
+            let workspace_path = if attachment::is_local_path(&target) {
+                Path::new(&target)
+                    .strip_prefix(workspace_dir)
+                    .ok()
+                    .map(|relative| relative.to_string_lossy().into_owned())
+            } else {
+                None
+            };
+            ExportedAttachment {
+                kind,
+                target,
+                workspace_path,
+            }
+        })
+        .collect()
+}
+
+/// A history key made safe to use in a file name.
+fn file_stem(conversation: &str) -> String {
+    let stem: String = conversation
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(80)
+        .collect();
+    if stem.is_empty() {
+        "conversation".to_string()
+    } else {
+        stem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(workspace: &Path) -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system("You are helpful."),
+            ChatMessage::user(format!(
+                "What is this? [IMAGE:{}]",
+                workspace.join("attachments/cat.png").display()
+            )),
+            ChatMessage::assistant("A cat."),
+            ChatMessage::user(format!(
+                "[Document: report.pdf] {}",
+                workspace.join("attachments/report.pdf").display()
+            )),
+            ChatMessage::assistant("See [IMAGE:https://example.com/chart.png]"),
+        ]
+    }
+
+    #[test]
+    fn export_lists_turns_and_workspace_attachments() {
+        let workspace = Path::new("/srv/zeroclaw/workspace");
+        let export =
+            ConversationExport::new("telegram_alice", "telegram", &history(workspace), workspace);
+
+        assert_eq!(export.messages.len(), 4);
+        assert_eq!(
+            export.messages[0].attachments,
+            vec![ExportedAttachment {
+                kind: "image".into(),
+                target: "/srv/zeroclaw/workspace/attachments/cat.png".into(),
+                workspace_path: Some("attachments/cat.png".into()),
+            }]
+        );
+        assert_eq!(
+            export.messages[2].attachments[0].workspace_path.as_deref(),
+            Some("attachments/report.pdf")
+        );
+        assert_eq!(export.messages[3].attachments[0].workspace_path, None);
+
+        let markdown = export.render(ExportFormat::Markdown);
+        assert!(markdown.starts_with("# Conversation export\n"));
+        assert!(markdown.contains("- Conversation: `telegram_alice`\n"));
+        assert!(markdown.contains("\n## Assistant\n\nA cat.\n"));
+        assert!(markdown.contains("- image: `attachments/cat.png` (workspace)\n"));
+        assert!(markdown.contains("- image: https://example.com/chart.png\n"));
+        assert!(!markdown.contains("You are helpful."));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&export.render(ExportFormat::Json)).unwrap();
+        assert_eq!(json["channel"], "telegram");
+        assert_eq!(json["messages"][1]["role"], "assistant");
+        assert_eq!(
+            json["messages"][2]["attachments"][0]["kind"],
+            serde_json::json!("document")
+        );
+    }
+
+    #[test]
+    fn export_is_written_under_the_workspace() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let export = ConversationExport::new(
+            "discord_42/../x",
+            "discord",
+            &history(tmp.path()),
+            tmp.path(),
+        );
+
+        let path = export
+            .write_to_workspace(tmp.path(), ExportFormat::Json)
+            .unwrap();
+        assert_eq!(path.parent(), Some(tmp.path().join(EXPORTS_DIR).as_path()));
+        let name = path.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("discord_42____x-") && name.ends_with(".json"));
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("\"conversation\": \"discord_42/../x\""));
+    }
+
+    #[test]
+    fn format_parses_aliases() {
+        assert_eq!(ExportFormat::parse("MD"), Some(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::parse("json"), Some(ExportFormat::Json));
+        assert_eq!(ExportFormat::parse("pdf"), None);
+    }
+}
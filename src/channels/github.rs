@@ -0,0 +1,495 @@
+use super::attachment::{is_local_path, parse_attachment_markers};
+use super::split::{non_empty_chunks, split_message};
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt::Write;
+
+/// GitHub rejects comment bodies longer than this many characters.
+const GITHUB_MAX_COMMENT_CHARS: usize = 65_536;
+/// Characters of a review comment's diff hunk passed to the agent.
+const MAX_DIFF_HUNK_CHARS: usize = 2_000;
+const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// GitHub channel in webhook mode — answers @-mentions in issue and pull
+/// request comments.
+///
+/// Incoming events are received by the gateway endpoint `/github`
+/// (`issue_comment`, `pull_request_review_comment`, and `issues` /
+/// `pull_request` when opened). Replies are posted as comments through the
+/// REST API.
+///
+/// Reply targets:
+/// - `owner/repo#<number>` — comment on the issue or pull request
+/// - `owner/repo#<number>:<comment_id>` — reply in a pull request review
+///   comment thread
+pub struct GithubChannel {
+    token: String,
+    bot_login: String,
+    allowed_users: Vec<String>,
+    allowed_repos: Vec<String>,
+    api_url: String,
+}
+
+/// Where a GitHub reply should be posted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GithubTarget {
+    repo: String,
+    number: u64,
+    review_comment_id: Option<u64>,
+}
+
+impl GithubChannel {
+    pub fn new(
+        token: String,
+        bot_login: String,
+        allowed_users: Vec<String>,
+        allowed_repos: Vec<String>,
+        api_url: Option<String>,
+    ) -> Self {
+        Self {
+            token,
+            bot_login: bot_login.trim().trim_start_matches('@').to_string(),
+            allowed_users,
+            allowed_repos,
+            api_url: api_url
+                .map(|url| url.trim().trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty())
+                .unwrap_or_else(|| DEFAULT_API_URL.to_string()),
+        }
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.github")
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.http_client()
+            .request(method, format!("{}/{path}", self.api_url))
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            .header("User-Agent", "zeroclaw")
+    }
+
+    /// Check if a GitHub login is in the allowlist.
+    /// Empty list means deny everyone. "*" means allow everyone.
+    fn is_user_allowed(&self, login: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(login))
+    }
+
+    /// Empty list means every repository that delivers webhooks.
+    fn is_repo_allowed(&self, repo: &str) -> bool {
+        self.allowed_repos.is_empty()
+            || self
+                .allowed_repos
+                .iter()
+                .any(|r| r == "*" || r.eq_ignore_ascii_case(repo))
+    }
+
+    fn parse_target(recipient: &str) -> Result<GithubTarget> {
+        let recipient = recipient.trim();
+        let parsed = recipient.split_once('#').and_then(|(repo, rest)| {
+            let (number, comment) = match rest.split_once(':') {
+                Some((number, comment)) => (number, Some(comment.parse().ok()?)),
+                None => (rest, None),
+            };
+            let (owner, name) = repo.split_once('/')?;
+            if owner.is_empty() || name.is_empty() || name.contains('/') {
+                return None;
+            }
+            Some(GithubTarget {
+                repo: repo.to_string(),
+                number: number.parse().ok()?,
+                review_comment_id: comment,
+            })
+        });
+        match parsed {
+            Some(target) => Ok(target),
+            None => bail!("Unsupported GitHub recipient '{recipient}'"),
+        }
+    }
+
+    /// Convert a webhook delivery into a channel message when it @-mentions
+    /// the bot, applying the self-filter and allowlists.
+    pub fn parse_webhook_payload(
+        &self,
+        event: &str,
+        payload: &serde_json::Value,
+    ) -> Option<ChannelMessage> {
+        let action = payload.get("action").and_then(|a| a.as_str());
+        let (item, body_source, kind) = match (event, action) {
+            ("issue_comment", Some("created")) => {
+                let issue = payload.get("issue")?;
+                let kind = if issue.get("pull_request").is_some() {
+                    "pull request"
+                } else {
+                    "issue"
+                };
+                (issue, payload.get("comment")?, kind)
+            }
+            ("pull_request_review_comment", Some("created")) => {
+                let pr = payload.get("pull_request")?;
+                (pr, payload.get("comment")?, "pull request")
+            }
+            ("issues", Some("opened")) => {
+                let issue = payload.get("issue")?;
+                (issue, issue, "issue")
+            }
+            ("pull_request", Some("opened")) => {
+                let pr = payload.get("pull_request")?;
+                (pr, pr, "pull request")
+            }
+            _ => return None,
+        };
+
+        let user = body_source.get("user")?;
+        let login = user.get("login")?.as_str()?;
+        if login.eq_ignore_ascii_case(&self.bot_login)
+            || user.get("type").and_then(|t| t.as_str()) == Some("Bot")
+        {
+            return None;
+        }
+        let body = body_source
+            .get("body")
+            .and_then(|b| b.as_str())
+            .unwrap_or("");
+        if !mentions(body, &self.bot_login) {
+            return None;
+        }
+        let repo = payload.get("repository")?.get("full_name")?.as_str()?;
+        if !self.is_repo_allowed(repo) {
+            tracing::debug!("GitHub: ignoring mention in repository not in allowed_repos: {repo}");
+            return None;
+        }
+        if !self.is_user_allowed(login) {
+            tracing::warn!("GitHub: ignoring mention from unauthorized user: {login}");
+            return None;
+        }
+
+        let number = item.get("number")?.as_u64()?;
+        let title = item.get("title").and_then(|t| t.as_str()).unwrap_or("");
+        let text = strip_mention(body, &self.bot_login);
+        let mut content = format!("[GitHub {kind} {repo}#{number}: {title}]\n");
+        let mut reply_target = format!("{repo}#{number}");
+        let source_id = body_source.get("id").and_then(serde_json::Value::as_u64)?;
+
+        if event == "pull_request_review_comment" {
+            // Replies go to the top of the review thread.
+            let thread_id = body_source
+                .get("in_reply_to_id")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(source_id);
+            reply_target = format!("{reply_target}:{thread_id}");
+            if let Some(path) = body_source.get("path").and_then(|p| p.as_str()) {
+                let _ = write!(content, "Comment on `{path}`");
+                if let Some(hunk) = body_source.get("diff_hunk").and_then(|h| h.as_str()) {
+                    let hunk: String = hunk.chars().take(MAX_DIFF_HUNK_CHARS).collect();
+                    let _ = write!(content, ":\n```diff\n{hunk}\n```");
+                }
+                content.push('\n');
+            }
+        }
+        content.push_str(&text);
+
+        Some(ChannelMessage {
+            id: format!("github_{event}_{source_id}"),
+            sender: login.to_string(),
+            reply_target,
+            content,
+            channel: "github".to_string(),
+            timestamp: body_source
+                .get("created_at")
+                .and_then(|t| t.as_str())
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .and_then(|t| u64::try_from(t.timestamp()).ok())
+                .unwrap_or_else(|| {
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs()
+                }),
+            thread_ts: None,
+        })
+    }
+
+    async fn post_comment(&self, target: &GithubTarget, body: &str) -> Result<()> {
+        let path = match target.review_comment_id {
+            Some(comment_id) => format!(
+                "repos/{}/pulls/{}/comments/{comment_id}/replies",
+                target.repo, target.number
+            ),
+            None => format!("repos/{}/issues/{}/comments", target.repo, target.number),
+        };
+        let resp = self
+            .request(reqwest::Method::POST, &path)
+            .json(&serde_json::json!({ "body": body }))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            bail!("GitHub send failed ({status}): {body}");
+        }
+        Ok(())
+    }
+}
+
+/// Whether `body` @-mentions `login` (case-insensitive, whole login only).
+fn mentions(body: &str, login: &str) -> bool {
+    !login.is_empty() && !mention_ranges(body, login).is_empty()
+}
+
+/// Byte ranges of `@login` mentions in `body`.
+fn mention_ranges(body: &str, login: &str) -> Vec<std::ops::Range<usize>> {
+    let needle = format!("@{}", login.to_ascii_lowercase());
+    let lower = body.to_ascii_lowercase();
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    while let Some(rel) = lower[from..].find(&needle) {
+        let start = from + rel;
+        let end = start + needle.len();
+        let boundary_before = lower[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_ascii_alphanumeric() && c != '-' && c != '/');
+        let boundary_after = lower[end..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_ascii_alphanumeric() && c != '-');
+        if boundary_before && boundary_after {
+            ranges.push(start..end);
+        }
+        from = end;
+    }
+    ranges
+}
+
+/// Remove the bot's mentions from a comment.
+fn strip_mention(body: &str, login: &str) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut cursor = 0;
+    for range in mention_ranges(body, login) {
+        text.push_str(&body[cursor..range.start]);
+        cursor = range.end;
+    }
+    text.push_str(&body[cursor..]);
+    text.trim().to_string()
+}
+
+/// Verify a webhook `X-Hub-Signature-256` header (`sha256=<hex>`).
+pub fn verify_github_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(hex_sig) = signature.trim().strip_prefix("sha256=") else {
+        return false;
+    };
+    let Ok(expected) = hex::decode(hex_sig) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+#[async_trait]
+impl Channel for GithubChannel {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        let target = Self::parse_target(&message.recipient)?;
+        let content = super::strip_tool_call_tags(&message.content);
+        let (text, attachments) = parse_attachment_markers(&content);
+
+        // Comments have no upload API; remote files are linked.
+        let mut links = Vec::new();
+        for attachment in &attachments {
+            let source = attachment.target.trim();
+            if is_local_path(source) {
+                links.push(format!("_(attachment not sent: {source})_"));
+            } else if attachment.kind.is_image_like() {
+                let alt = attachment.alt.as_deref().unwrap_or("image");
+                links.push(format!("![{alt}]({source})"));
+            } else {
+                links.push(source.to_string());
+            }
+        }
+
+        let mut body = text;
+        if !links.is_empty() {
+            if !body.is_empty() {
+                body.push_str("\n\n");
+            }
+            body.push_str(&links.join("\n"));
+        }
+
+        for chunk in non_empty_chunks(split_message(body.trim(), GITHUB_MAX_COMMENT_CHARS)) {
+            self.post_comment(&target, &chunk).await?;
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        tracing::info!(
+            "GitHub channel active (webhook mode). \
+            Configure a repository or organization webhook to POST to your gateway's /github endpoint."
+        );
+
+        // Keep task alive; incoming events are handled by the gateway webhook handler.
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        self.request(reqwest::Method::GET, "rate_limit")
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_channel() -> GithubChannel {
+        GithubChannel::new(
+            "token".into(),
+            "@ZeroClaw-Bot".into(),
+            vec!["alice".into()],
+            vec!["acme/widgets".into()],
+            None,
+        )
+    }
+
+    fn issue_comment(login: &str, body: &str) -> serde_json::Value {
+        json!({
+            "action": "created",
+            "issue": {
+                "number": 12,
+                "title": "Crash on start",
+                "pull_request": {"url": "https://api.github.com/repos/acme/widgets/pulls/12"}
+            },
+            "comment": {
+                "id": 555,
+                "body": body,
+                "user": {"login": login, "type": "User"},
+                "created_at": "2024-05-01T12:00:00Z"
+            },
+            "repository": {"full_name": "acme/widgets"}
+        })
+    }
+
+    #[test]
+    fn mentions_in_comments_become_messages() {
+        let ch = make_channel();
+        let msg = ch
+            .parse_webhook_payload(
+                "issue_comment",
+                &issue_comment("alice", "@zeroclaw-bot why does this fail?"),
+            )
+            .unwrap();
+        assert_eq!(msg.id, "github_issue_comment_555");
+        assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.reply_target, "acme/widgets#12");
+        assert_eq!(
+            msg.content,
+            "[GitHub pull request acme/widgets#12: Crash on start]\nwhy does this fail?"
+        );
+        assert_eq!(msg.timestamp, 1_714_564_800);
+    }
+
+    #[test]
+    fn ignores_unmentioned_self_and_unauthorized_comments() {
+        let ch = make_channel();
+        for payload in [
+            issue_comment("alice", "no mention here"),
+            issue_comment("alice", "cc @zeroclaw-bot-two"),
+            issue_comment("zeroclaw-bot", "@zeroclaw-bot loop"),
+            issue_comment("mallory", "@zeroclaw-bot hi"),
+        ] {
+            assert!(ch
+                .parse_webhook_payload("issue_comment", &payload)
+                .is_none());
+        }
+        let mut other_repo = issue_comment("alice", "@zeroclaw-bot hi");
+        other_repo["repository"]["full_name"] = json!("acme/secret");
+        assert!(ch
+            .parse_webhook_payload("issue_comment", &other_repo)
+            .is_none());
+        let mut edited = issue_comment("alice", "@zeroclaw-bot hi");
+        edited["action"] = json!("edited");
+        assert!(ch.parse_webhook_payload("issue_comment", &edited).is_none());
+    }
+
+    #[test]
+    fn review_comments_reply_in_their_thread() {
+        let ch = make_channel();
+        let payload = json!({
+            "action": "created",
+            "pull_request": {"number": 7, "title": "Add cache"},
+            "comment": {
+                "id": 901,
+                "in_reply_to_id": 900,
+                "path": "src/cache.rs",
+                "diff_hunk": "@@ -1 +1 @@\n-old\n+new",
+                "body": "@ZeroClaw-Bot is this right?",
+                "user": {"login": "alice", "type": "User"}
+            },
+            "repository": {"full_name": "acme/widgets"}
+        });
+        let msg = ch
+            .parse_webhook_payload("pull_request_review_comment", &payload)
+            .unwrap();
+        assert_eq!(msg.reply_target, "acme/widgets#7:900");
+        assert!(msg
+            .content
+            .contains("Comment on `src/cache.rs`:\n```diff\n@@ -1 +1 @@"));
+        assert!(msg.content.ends_with("\nis this right?"));
+    }
+
+    #[test]
+    fn parses_reply_targets() {
+        assert_eq!(
+            GithubChannel::parse_target("acme/widgets#12").unwrap(),
+            GithubTarget {
+                repo: "acme/widgets".into(),
+                number: 12,
+                review_comment_id: None
+            }
+        );
+        assert_eq!(
+            GithubChannel::parse_target("acme/widgets#7:900")
+                .unwrap()
+                .review_comment_id,
+            Some(900)
+        );
+        assert!(GithubChannel::parse_target("acme#1").is_err());
+        assert!(GithubChannel::parse_target("acme/widgets#x").is_err());
+    }
+
+    #[test]
+    fn verifies_webhook_signatures() {
+        let secret = "test-secret";
+        let body = br#"{"action":"created"}"#;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert!(verify_github_signature(secret, body, &signature));
+        assert!(!verify_github_signature("other", body, &signature));
+        assert!(!verify_github_signature(secret, body, "sha1=abc"));
+    }
+}
@@ -0,0 +1,508 @@
+//! Generic HTTP API channel.
+//!
+//! Runs a small HTTP server so scripts and services can talk to the agent
+//! without a chat platform:
+//!
+//! - `POST /v1/message` with `{"content": "...", "sender": "...", "session": "..."}`
+//!   waits for the agent's reply and returns it as JSON. Attachment markers in
+//!   the reply are resolved to URLs; local files the security policy allows
+//!   are served from `GET /v1/files/<token>/<name>` under an unguessable
+//!   token.
+//! - `GET /health` answers `ok`.
+//!
+//! Requests must carry `Authorization: Bearer <token>` when a token is
+//! configured. Binding to a non-loopback address without a token is refused.
+
+use super::attachment::{is_local_path, parse_attachment_markers};
+use super::media_host::allowed_local_file;
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use axum::extract::{Path as UrlPath, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+const DEFAULT_SENDER: &str = "api";
+/// Served files kept addressable; older tokens expire first.
+const MAX_SERVED_FILES: usize = 512;
+const MAX_REQUEST_BYTES: usize = 1024 * 1024;
+
+/// Body of `POST /v1/message`.
+#[derive(Debug, Deserialize)]
+struct MessageRequest {
+    content: String,
+    /// Caller identity; conversation history is kept per sender.
+    #[serde(default)]
+    sender: Option<String>,
+    /// Optional session id to keep separate histories for one sender.
+    #[serde(default)]
+    session: Option<String>,
+}
+
+/// Attachment in a reply, resolved to a fetchable URL.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ReplyAttachment {
+    pub kind: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+}
+
+/// Body returned by `POST /v1/message`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MessageReply {
+    pub id: String,
+    pub reply: String,
+    pub attachments: Vec<ReplyAttachment>,
+}
+
+/// Local files exposed under unguessable tokens, oldest evicted first.
+#[derive(Default)]
+pub(super) struct ServedFiles {
+    paths: HashMap<String, PathBuf>,
+    order: VecDeque<String>,
+}
+
+impl ServedFiles {
+    pub(super) fn insert(&mut self, path: PathBuf) -> String {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        self.paths.insert(token.clone(), path);
+        self.order.push_back(token.clone());
+        while self.order.len() > MAX_SERVED_FILES {
+            if let Some(old) = self.order.pop_front() {
+                self.paths.remove(&old);
+            }
+        }
+        token
+    }
+
+    pub(super) fn get(&self, token: &str) -> Option<PathBuf> {
+        self.paths.get(token).cloned()
+    }
+}
+
+struct Shared {
+    bearer_token: Option<String>,
+    reply_timeout: Duration,
+    pending: Mutex<HashMap<String, oneshot::Sender<String>>>,
+    files: Mutex<ServedFiles>,
+}
+
+#[derive(Clone)]
+struct ApiState {
+    shared: Arc<Shared>,
+    tx: mpsc::Sender<ChannelMessage>,
+}
+
+/// HTTP API channel — request/response JSON over a local HTTP server.
+pub struct HttpChannel {
+    host: String,
+    port: u16,
+    public_url: Option<String>,
+    security: Arc<SecurityPolicy>,
+    shared: Arc<Shared>,
+}
+
+impl HttpChannel {
+    pub fn new(
+        host: String,
+        port: u16,
+        bearer_token: Option<String>,
+        public_url: Option<String>,
+        reply_timeout_secs: u64,
+        security: Arc<SecurityPolicy>,
+    ) -> Self {
+        Self {
+            host,
+            port,
+            public_url: public_url.map(|url| url.trim_end_matches('/').to_string()),
+            security,
+            shared: Arc::new(Shared {
+                bearer_token: bearer_token.filter(|t| !t.trim().is_empty()),
+                reply_timeout: Duration::from_secs(reply_timeout_secs.max(1)),
+                pending: Mutex::new(HashMap::new()),
+                files: Mutex::new(ServedFiles::default()),
+            }),
+        }
+    }
+
+    fn base_url(&self) -> String {
+        self.public_url
+            .clone()
+            .unwrap_or_else(|| format!("http://{}:{}", self.host, self.port))
+    }
+
+    /// Split `content` into reply text and attachments with fetchable URLs.
+    fn resolve_reply(&self, id: &str, content: &str) -> MessageReply {
+        let (text, attachments) = parse_attachment_markers(content);
+        let base = self.base_url();
+        let attachments = attachments
+            .into_iter()
+            .filter_map(|attachment| {
+                let url = if is_local_path(&attachment.target) {
+                    let Some(path) = allowed_local_file(&self.security, &attachment.target) else {
+                        tracing::warn!("HTTP API: attachment not served: {}", attachment.target);
+                        return None;
+                    };
+                    let name = path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .map(urlencoding::encode)
+                        .map_or_else(|| "file".to_string(), |n| n.into_owned());
+                    let token = self.shared.files.lock().insert(path);
+                    format!("{base}/v1/files/{token}/{name}")
+                } else {
+                    attachment.target
+                };
+                Some(ReplyAttachment {
+                    kind: attachment.kind.marker_name().to_ascii_lowercase(),
+                    url,
+                    alt: attachment.alt,
+                })
+            })
+            .collect();
+        MessageReply {
+            id: id.to_string(),
+            reply: text,
+            attachments,
+        }
+    }
+
+    fn router(&self, tx: mpsc::Sender<ChannelMessage>) -> Router {
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/v1/message", post(handle_message))
+            .route("/v1/files/{token}/{name}", get(handle_file))
+            .layer(tower_http::limit::RequestBodyLimitLayer::new(
+                MAX_REQUEST_BYTES,
+            ))
+            .with_state(ApiState {
+                shared: Arc::clone(&self.shared),
+                tx,
+            })
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+fn is_authorized(shared: &Shared, headers: &HeaderMap) -> bool {
+    let Some(expected) = shared.bearer_token.as_deref() else {
+        return true;
+    };
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| crate::security::pairing::constant_time_eq(token.trim(), expected))
+}
+
+async fn handle_message(
+    State(state): State<ApiState>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Response {
+    if !is_authorized(&state.shared, &headers) {
+        return error_response(StatusCode::UNAUTHORIZED, "missing or invalid bearer token");
+    }
+    let Ok(request) = serde_json::from_slice::<MessageRequest>(&body) else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "expected JSON body with a `content` string",
+        );
+    };
+    let content = request.content.trim();
+    if content.is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "`content` must not be empty");
+    }
+
+    let id = format!("http_{}", uuid::Uuid::new_v4().simple());
+    let (reply_tx, reply_rx) = oneshot::channel();
+    state.shared.pending.lock().insert(id.clone(), reply_tx);
+
+    let msg = ChannelMessage {
+        id: id.clone(),
+        sender: request
+            .sender
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| DEFAULT_SENDER.to_string()),
+        reply_target: id.clone(),
+        content: content.to_string(),
+        channel: "http".to_string(),
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        thread_ts: request
+            .session
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()),
+    };
+    if state.tx.send(msg).await.is_err() {
+        state.shared.pending.lock().remove(&id);
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "agent is shutting down");
+    }
+
+    match tokio::time::timeout(state.shared.reply_timeout, reply_rx).await {
+        Ok(Ok(reply)) => ([(header::CONTENT_TYPE, "application/json")], reply).into_response(),
+        Ok(Err(_)) => error_response(StatusCode::INTERNAL_SERVER_ERROR, "reply was dropped"),
+        Err(_) => {
+            state.shared.pending.lock().remove(&id);
+            error_response(
+                StatusCode::GATEWAY_TIMEOUT,
+                "timed out waiting for the agent",
+            )
+        }
+    }
+}
+
+async fn handle_file(
+    State(state): State<ApiState>,
+    UrlPath((token, _name)): UrlPath<(String, String)>,
+) -> Response {
+    let path = state.shared.files.lock().get(&token);
+    let Some(path) = path else {
+        return error_response(StatusCode::NOT_FOUND, "unknown or expired file");
+    };
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => {
+            let mime = mime_guess::from_path(&path).first_or_octet_stream();
+            ([(header::CONTENT_TYPE, mime.to_string())], bytes).into_response()
+        }
+        Err(_) => error_response(StatusCode::NOT_FOUND, "file no longer available"),
+    }
+}
+
+#[async_trait]
+impl Channel for HttpChannel {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let Some(reply_tx) = self.shared.pending.lock().remove(&message.recipient) else {
+            // The caller timed out or already received its reply.
+            tracing::debug!(
+                "HTTP API: no pending request {}; dropping message",
+                message.recipient
+            );
+            return Ok(());
+        };
+        let content = super::strip_tool_call_tags(&message.content);
+        let reply = self.resolve_reply(&message.recipient, &content);
+        let _ = reply_tx.send(serde_json::to_string(&reply)?);
+        Ok(())
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        if self.shared.bearer_token.is_none()
+            && crate::security::pairing::is_public_bind(&self.host)
+        {
+            anyhow::bail!(
+                "HTTP API channel refuses to bind {} without a bearer_token",
+                self.host
+            );
+        }
+        let listener = tokio::net::TcpListener::bind((self.host.as_str(), self.port)).await?;
+        tracing::info!("HTTP API: listening on {}", listener.local_addr()?);
+        axum::serve(listener, self.router(tx)).await?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        tokio::net::TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    fn channel(token: Option<&str>, workspace: &std::path::Path) -> HttpChannel {
+        HttpChannel::new(
+            "127.0.0.1".into(),
+            8090,
+            token.map(str::to_string),
+            Some("https://agent.example.com/".into()),
+            5,
+            Arc::new(SecurityPolicy {
+                workspace_dir: workspace.to_path_buf(),
+                ..SecurityPolicy::default()
+            }),
+        )
+    }
+
+    fn post_message(body: &str, token: Option<&str>) -> axum::http::Request<axum::body::Body> {
+        let mut builder = axum::http::Request::post("/v1/message")
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        builder
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn message_round_trip_returns_json_reply() {
+        let ch = Arc::new(channel(Some("secret"), &std::env::temp_dir()));
+        let (tx, mut rx) = mpsc::channel(4);
+        let app = ch.router(tx);
+
+        let responder = {
+            let ch = Arc::clone(&ch);
+            tokio::spawn(async move {
+                let msg = rx.recv().await.unwrap();
+                assert_eq!(msg.channel, "http");
+                assert_eq!(msg.sender, "ci-bot");
+                assert_eq!(msg.thread_ts.as_deref(), Some("build-7"));
+                ch.send(&SendMessage::new(
+                    "All green [IMAGE:https://cdn.example.com/c.png|Pass rate chart]",
+                    &msg.reply_target,
+                ))
+                .await
+                .unwrap();
+            })
+        };
+
+        let response = app
+            .oneshot(post_message(
+                r#"{"content":"status?","sender":"ci-bot","session":"build-7"}"#,
+                Some("secret"),
+            ))
+            .await
+            .unwrap();
+        responder.await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["reply"], "All green");
+        assert_eq!(json["attachments"][0]["kind"], "image");
+        assert_eq!(
+            json["attachments"][0]["url"],
+            "https://cdn.example.com/c.png"
+        );
+        assert_eq!(json["attachments"][0]["alt"], "Pass rate chart");
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_token_and_empty_content() {
+        let ch = channel(Some("secret"), &std::env::temp_dir());
+        let (tx, _rx) = mpsc::channel(4);
+
+        let response = ch
+            .router(tx.clone())
+            .oneshot(post_message(r#"{"content":"hi"}"#, Some("wrong")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = ch
+            .router(tx)
+            .oneshot(post_message(r#"{"content":"  "}"#, Some("secret")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn local_attachments_are_served_by_token() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("report 1.txt");
+        std::fs::write(&file, "quarterly numbers").unwrap();
+        let ch = channel(None, tmp.path());
+
+        let reply = ch.resolve_reply(
+            "req-1",
+            &format!(
+                "Attached [DOCUMENT:{}] [IMAGE:/missing.png]",
+                file.display()
+            ),
+        );
+        assert_eq!(reply.reply, "Attached");
+        assert_eq!(reply.attachments.len(), 1);
+        let url = &reply.attachments[0].url;
+        assert!(url.starts_with("https://agent.example.com/v1/files/"));
+        assert!(url.ends_with("/report%201.txt"));
+
+        let (tx, _rx) = mpsc::channel(1);
+        let path = url.trim_start_matches("https://agent.example.com");
+        let response = ch
+            .router(tx.clone())
+            .oneshot(
+                axum::http::Request::get(path)
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"quarterly numbers");
+
+        let response = ch
+            .router(tx)
+            .oneshot(
+                axum::http::Request::get("/v1/files/nope/x.txt")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn files_outside_the_allowed_paths_are_not_served() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let secret = outside.path().join("id_rsa");
+        std::fs::write(&secret, "key").unwrap();
+        let ch = channel(None, workspace.path());
+
+        let reply = ch.resolve_reply(
+            "req-2",
+            &format!(
+                "Here [DOCUMENT:{}] [DOCUMENT:/etc/passwd]",
+                secret.display()
+            ),
+        );
+        assert_eq!(reply.reply, "Here");
+        assert!(reply.attachments.is_empty());
+        assert!(ch.shared.files.lock().paths.is_empty());
+    }
+
+    #[tokio::test]
+    async fn public_bind_requires_token() {
+        let ch = HttpChannel::new(
+            "0.0.0.0".into(),
+            0,
+            None,
+            None,
+            5,
+            Arc::new(SecurityPolicy::default()),
+        );
+        let (tx, _rx) = mpsc::channel(1);
+        let err = ch.listen(tx).await.unwrap_err();
+        assert!(err.to_string().contains("bearer_token"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Cross-channel user identities.
+//!
+//! The same person usually has a different user ID on every platform.
+//! `[[channels_config.identities]]` links those accounts under one name, so
+//! what is tied to a person rather than to an account follows them across
+//! channels: remembered facts, `/stats` and `/usage` totals, and access
+//! list entries.
+
+use crate::config::UserIdentityConfig;
+use std::collections::HashMap;
+
+/// A platform account: `(channel, user id)`.
+pub type Account = (String, String);
+
+/// Compiled `[[channels_config.identities]]`.
+pub struct IdentityMap {
+    by_account: HashMap<Account, String>,
+    accounts: HashMap<String, Vec<Account>>,
+}
+
+/// `"<channel>:<user id>"`; user IDs may contain `:` themselves (Matrix).
+fn parse_account(entry: &str) -> Option<Account> {
+    let (channel, user) = entry.trim().split_once(':')?;
+    let (channel, user) = (channel.trim(), user.trim());
+    (!channel.is_empty() && !user.is_empty()).then(|| (channel.to_string(), user.to_string()))
+}
+
+impl IdentityMap {
+    /// Returns `None` when no identity links any account.
+    pub fn from_config(identities: &[UserIdentityConfig]) -> Option<Self> {
+        let mut map = Self {
+            by_account: HashMap::new(),
+            accounts: HashMap::new(),
+        };
+        for identity in identities {
+            let name = identity.name.trim();
+            if name.is_empty() {
+                tracing::warn!("channels_config.identities: skipping an identity without a name");
+                continue;
+            }
+            for entry in &identity.accounts {
+                let Some(account) = parse_account(entry) else {
+                    tracing::warn!(
+                        "channels_config.identities: `{entry}` of `{name}` is not `<channel>:<user id>`"
+                    );
+                    continue;
+                };
+                if let Some(owner) = map.by_account.get(&account) {
+                    if owner != name {
+                        tracing::warn!(
+                            "channels_config.identities: `{entry}` is linked to both `{owner}` and `{name}`; keeping `{owner}`"
+                        );
+                    }
+                    continue;
+                }
+                map.by_account.insert(account.clone(), name.to_string());
+                map.accounts
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(account);
+            }
+        }
+        (!map.by_account.is_empty()).then_some(map)
+    }
+
+    /// Identity name of `sender` on `channel`, if linked.
+    pub fn resolve(&self, channel: &str, sender: &str) -> Option<&str> {
+        self.by_account
+            .get(&(channel.to_string(), sender.to_string()))
+            .map(String::as_str)
+    }
+
+    /// Every account of `sender`'s identity, or only their own when the
+    /// account is not linked.
+    pub fn accounts_of(&self, channel: &str, sender: &str) -> Vec<Account> {
+        self.resolve(channel, sender)
+            .and_then(|name| self.accounts.get(name))
+            .cloned()
+            .unwrap_or_else(|| vec![(channel.to_string(), sender.to_string())])
+    }
+
+    /// Accounts linked under `name`.
+    pub fn accounts_named(&self, name: &str) -> &[Account] {
+        self.accounts.get(name).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(name: &str, accounts: &[&str]) -> UserIdentityConfig {
+        UserIdentityConfig {
+            name: name.into(),
+            accounts: accounts.iter().map(|a| (*a).to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn links_accounts_across_channels() {
+        let map = IdentityMap::from_config(&[
+            identity(
+                "alice",
+                &["telegram:123", "matrix:@alice:example.org", "bogus"],
+            ),
+            identity("bob", &["slack:U02", "telegram:123"]),
+        ])
+        .unwrap();
+
+        assert_eq!(map.resolve("telegram", "123"), Some("alice"));
+        assert_eq!(map.resolve("matrix", "@alice:example.org"), Some("alice"));
+        assert_eq!(map.resolve("slack", "U02"), Some("bob"));
+        assert_eq!(map.resolve("slack", "U99"), None);
+        assert_eq!(
+            map.accounts_of("telegram", "123"),
+            vec![
+                ("telegram".to_string(), "123".to_string()),
+                ("matrix".to_string(), "@alice:example.org".to_string()),
+            ]
+        );
+        // An account claimed twice stays with the first identity.
+        assert_eq!(map.accounts_named("bob").len(), 1);
+        assert_eq!(
+            map.accounts_of("slack", "U99"),
+            vec![("slack".to_string(), "U99".to_string())]
+        );
+        assert!(IdentityMap::from_config(&[identity("carol", &[])]).is_none());
+    }
+}
@@ -0,0 +1,151 @@
+//! Per-channel and per-chat overrides of the delivery instructions.
+//!
+//! The built-in instructions are generated from each channel's capabilities.
+//! `[[channels_config.instructions]]` entries extend or replace them for a
+//! whole channel or a single chat, e.g. to always answer in German in one
+//! Slack channel. Channel-wide entries apply first, then chat entries, so
+//! the more specific entry wins when both replace the text.
+
+use crate::config::ChannelInstructionsConfig;
+
+/// A validated override.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Override {
+    channel: String,
+    chat: Option<String>,
+    replace: Option<String>,
+    append: Option<String>,
+}
+
+impl Override {
+    fn from_config(config: &ChannelInstructionsConfig) -> Option<Self> {
+        let (channel, chat) = match config.channel.trim().split_once(':') {
+            Some((channel, chat)) => (channel.trim(), Some(chat.trim().to_string())),
+            None => (config.channel.trim(), None),
+        };
+        let text = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string)
+        };
+        let replace = text(&config.replace);
+        let append = text(&config.append);
+        if channel.is_empty() || (replace.is_none() && append.is_none()) {
+            return None;
+        }
+        Some(Self {
+            channel: channel.to_string(),
+            chat: chat.filter(|chat| !chat.is_empty()),
+            replace,
+            append,
+        })
+    }
+
+    fn matches(&self, channel: &str, reply_target: &str) -> bool {
+        channel == self.channel
+            && self.chat.as_deref().is_none_or(|chat| {
+                reply_target == chat
+                    || reply_target
+                        .strip_prefix(chat)
+                        .is_some_and(|rest| rest.starts_with(':'))
+            })
+    }
+}
+
+/// All configured overrides.
+pub struct InstructionOverrides {
+    overrides: Vec<Override>,
+}
+
+impl InstructionOverrides {
+    /// Build the overrides, skipping entries without a channel or text.
+    /// Returns `None` when nothing is configured.
+    pub fn from_config(config: &[ChannelInstructionsConfig]) -> Option<Self> {
+        let overrides: Vec<Override> = config.iter().filter_map(Override::from_config).collect();
+        if overrides.is_empty() {
+            None
+        } else {
+            Some(Self { overrides })
+        }
+    }
+
+    /// Apply the overrides matching a chat to the built-in `instructions`.
+    pub fn apply(
+        &self,
+        channel: &str,
+        reply_target: &str,
+        instructions: Option<String>,
+    ) -> Option<String> {
+        let mut matching: Vec<&Override> = self
+            .overrides
+            .iter()
+            .filter(|o| o.matches(channel, reply_target))
+            .collect();
+        matching.sort_by_key(|o| o.chat.is_some());
+
+        let mut result = instructions;
+        for o in matching {
+            if let Some(replace) = &o.replace {
+                result = Some(replace.clone());
+            }
+            if let Some(append) = &o.append {
+                result = Some(match result {
+                    Some(text) => format!("{text}\n{append}"),
+                    None => append.clone(),
+                });
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        channel: &str,
+        replace: Option<&str>,
+        append: Option<&str>,
+    ) -> ChannelInstructionsConfig {
+        ChannelInstructionsConfig {
+            channel: channel.into(),
+            replace: replace.map(str::to_string),
+            append: append.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn from_config_skips_empty_entries() {
+        assert!(InstructionOverrides::from_config(&[entry("slack", None, Some("  "))]).is_none());
+        assert!(InstructionOverrides::from_config(&[entry("", Some("x"), None)]).is_none());
+    }
+
+    #[test]
+    fn chat_entries_apply_after_channel_entries() {
+        let overrides = InstructionOverrides::from_config(&[
+            entry("slack:C42", None, Some("Always reply in German.")),
+            entry("slack", Some("Reply in one short paragraph."), None),
+        ])
+        .unwrap();
+        let builtin = Some("When responding:".to_string());
+
+        assert_eq!(
+            overrides
+                .apply("slack", "C42:1700.1", builtin.clone())
+                .as_deref(),
+            Some("Reply in one short paragraph.\nAlways reply in German.")
+        );
+        assert_eq!(
+            overrides.apply("slack", "C7", builtin.clone()).as_deref(),
+            Some("Reply in one short paragraph.")
+        );
+        assert_eq!(overrides.apply("discord", "C42", builtin.clone()), builtin);
+        assert_eq!(
+            overrides.apply("slack", "C420", None).as_deref(),
+            Some("Reply in one short paragraph.")
+        );
+    }
+}
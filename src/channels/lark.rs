@@ -1,4 +1,7 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::buttons::parse_button_markers;
+use super::markdown::{self, Dialect};
+use super::split::split_message_bytes;
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use prost::Message as ProstMessage;
@@ -227,9 +230,124 @@ const WS_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(300);
 const LARK_TOKEN_REFRESH_SKEW: Duration = Duration::from_secs(120);
 /// Fallback tenant token TTL when `expire`/`expires_in` is absent.
 const LARK_DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(7200);
+/// Feishu/Lark reject text message requests over 150 KB; leave room for the
+/// JSON envelope around the text.
+const LARK_MAX_TEXT_BYTES: usize = 100_000;
+/// Feishu/Lark reject cards over 30 KB of JSON; larger replies are sent as
+/// text instead.
+const LARK_MAX_CARD_BYTES: usize = 28_000;
+/// Card header colors; anything else falls back to `blue`.
+const LARK_CARD_TEMPLATES: &[&str] = &[
+    "blue",
+    "wathet",
+    "turquoise",
+    "green",
+    "yellow",
+    "orange",
+    "red",
+    "carmine",
+    "violet",
+    "purple",
+    "indigo",
+    "grey",
+    "default",
+];
+/// Key of the card button callback value that carries the button label.
+const LARK_CARD_BUTTON_KEY: &str = "zeroclaw_button";
 /// Feishu/Lark API business code for expired/invalid tenant access token.
 const LARK_INVALID_ACCESS_TOKEN_CODE: i64 = 99_991_663;
 
+/// A `[CARD:Title|color]` marker asking for the reply to be sent as a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CardHeader {
+    title: String,
+    template: &'static str,
+}
+
+/// Extract the first `[CARD:Title]` or `[CARD:Title|green]` marker. The
+/// color names a header template (`green` for done, `red` for failures,
+/// `orange` for in progress, ...); unknown colors become `blue`.
+fn parse_card_marker(text: &str) -> (String, Option<CardHeader>) {
+    let mut cursor = 0;
+    while let Some(open_rel) = text[cursor..].find('[') {
+        let open = cursor + open_rel;
+        let Some(close_rel) = text[open..].find(']') else {
+            break;
+        };
+        let close = open + close_rel;
+        if let Some((kind, spec)) = text[open + 1..close].split_once(':') {
+            if kind.trim().eq_ignore_ascii_case("CARD") {
+                let (title, color) = spec.split_once('|').unwrap_or((spec, ""));
+                let color = color.trim().to_ascii_lowercase();
+                let template = LARK_CARD_TEMPLATES
+                    .iter()
+                    .find(|template| **template == color)
+                    .copied()
+                    .unwrap_or("blue");
+                let cleaned = format!("{}{}", &text[..open], &text[close + 1..]);
+                let header = CardHeader {
+                    title: title.trim().to_string(),
+                    template,
+                };
+                return (cleaned.trim().to_string(), Some(header));
+            }
+        }
+        cursor = close + 1;
+    }
+    (text.to_string(), None)
+}
+
+/// Build an interactive card (card JSON 2.0) from a reply: an optional
+/// header, the text as a markdown element (tables, lists and links render
+/// natively) and one row of callback buttons per `[BUTTONS:...]` marker.
+/// Returns `None` when the reply asks for neither a card nor buttons.
+fn build_card(text: &str) -> Option<serde_json::Value> {
+    let (text, header) = parse_card_marker(text);
+    let (text, rows) = parse_button_markers(&text);
+    if header.is_none() && rows.is_empty() {
+        return None;
+    }
+
+    let mut elements = Vec::new();
+    if !text.is_empty() {
+        elements.push(serde_json::json!({ "tag": "markdown", "content": text }));
+    }
+    for row in &rows {
+        let columns: Vec<serde_json::Value> = row
+            .iter()
+            .map(|label| {
+                serde_json::json!({
+                    "tag": "column",
+                    "width": "auto",
+                    "elements": [{
+                        "tag": "button",
+                        "text": { "tag": "plain_text", "content": label },
+                        "type": "default",
+                        "behaviors": [{
+                            "type": "callback",
+                            "value": { LARK_CARD_BUTTON_KEY: label },
+                        }],
+                    }],
+                })
+            })
+            .collect();
+        elements.push(serde_json::json!({ "tag": "column_set", "columns": columns }));
+    }
+
+    let mut card = serde_json::json!({
+        "schema": "2.0",
+        "config": { "update_multi": true },
+        "body": { "elements": elements },
+    });
+    if let Some(header) = header.filter(|header| !header.title.is_empty()) {
+        card["header"] = serde_json::json!({
+            "title": { "tag": "plain_text", "content": header.title },
+            "template": header.template,
+        });
+    }
+    Some(card)
+}
+
 /// Returns true when the WebSocket frame indicates live traffic that should
 /// refresh the heartbeat watchdog.
 fn should_refresh_last_recv(msg: &WsMsg) -> bool {
@@ -733,12 +851,18 @@ impl LarkChannel {
                         } else { continue; }
                     };
 
-                    if msg_type != "event" { continue; }
+                    if msg_type != "event" && msg_type != "card" { continue; }
 
                     let event: LarkEvent = match serde_json::from_slice(&payload) {
                         Ok(e) => e,
                         Err(e) => { tracing::error!("Lark: event JSON: {e}"); continue; }
                     };
+                    if event.header.event_type == "card.action.trigger" {
+                        if let Some(channel_msg) = self.parse_card_action(&event.event) {
+                            if tx.send(channel_msg).await.is_err() { break; }
+                        }
+                        continue;
+                    }
                     if event.header.event_type != "im.message.receive_v1" { continue; }
 
                     let event_payload = event.event;
@@ -856,7 +980,7 @@ impl LarkChannel {
                     });
 
                     let channel_msg = ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
+                        id: format!("lark_{}", lark_msg.message_id),
                         sender: lark_msg.chat_id.clone(),
                         reply_target: lark_msg.chat_id.clone(),
                         content: text,
@@ -1163,6 +1287,68 @@ impl LarkChannel {
         Ok((status, parsed))
     }
 
+    /// Send a message body, refreshing the tenant token once when it has
+    /// expired.
+    async fn send_with_token_refresh(
+        &self,
+        url: &str,
+        token: &mut String,
+        body: &serde_json::Value,
+        context: &str,
+    ) -> anyhow::Result<()> {
+        let (status, response) = self.send_text_once(url, token, body).await?;
+
+        if should_refresh_lark_tenant_token(status, &response) {
+            self.invalidate_token().await;
+            *token = self.get_tenant_access_token().await?;
+            let (retry_status, retry_response) = self.send_text_once(url, token, body).await?;
+
+            if should_refresh_lark_tenant_token(retry_status, &retry_response) {
+                anyhow::bail!(
+                    "Lark send failed after token refresh: status={retry_status}, body={retry_response}"
+                );
+            }
+
+            ensure_lark_send_success(retry_status, &retry_response, "after token refresh")
+        } else {
+            ensure_lark_send_success(status, &response, context)
+        }
+    }
+
+    /// Turn a `card.action.trigger` callback for one of our card buttons into
+    /// a message carrying the button label, like a typed reply.
+    fn parse_card_action(&self, event: &serde_json::Value) -> Option<ChannelMessage> {
+        let label = event
+            .pointer(&format!("/action/value/{LARK_CARD_BUTTON_KEY}"))
+            .and_then(|l| l.as_str())
+            .filter(|l| !l.is_empty())?;
+        let open_id = event
+            .pointer("/operator/open_id")
+            .and_then(|o| o.as_str())
+            .unwrap_or("");
+        if open_id.is_empty() || !self.is_user_allowed(open_id) {
+            tracing::warn!("Lark: ignoring card action from unauthorized user: {open_id}");
+            return None;
+        }
+        let chat_id = event
+            .pointer("/context/open_chat_id")
+            .and_then(|c| c.as_str())
+            .unwrap_or(open_id);
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: chat_id.to_string(),
+            reply_target: chat_id.to_string(),
+            content: label.to_string(),
+            channel: self.channel_name().to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+        })
+    }
+
     /// Parse an event callback payload and extract text messages
     pub fn parse_event_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
         let mut messages = Vec::new();
@@ -1174,6 +1360,14 @@ impl LarkChannel {
             .and_then(|e| e.as_str())
             .unwrap_or("");
 
+        if event_type == "card.action.trigger" {
+            return payload
+                .get("event")
+                .and_then(|event| self.parse_card_action(event))
+                .into_iter()
+                .collect();
+        }
+
         if event_type != "im.message.receive_v1" {
             return messages;
         }
@@ -1276,8 +1470,16 @@ impl LarkChannel {
             .and_then(|c| c.as_str())
             .unwrap_or(open_id);
 
+        // Lark retries event deliveries that are not acknowledged in time;
+        // the message ID stays the same.
+        let id = event
+            .pointer("/message/message_id")
+            .and_then(|m| m.as_str())
+            .filter(|m| !m.is_empty())
+            .map_or_else(|| Uuid::new_v4().to_string(), |m| format!("lark_{m}"));
+
         messages.push(ChannelMessage {
-            id: Uuid::new_v4().to_string(),
+            id,
             sender: chat_id.to_string(),
             reply_target: chat_id.to_string(),
             content: text,
@@ -1296,41 +1498,52 @@ impl Channel for LarkChannel {
         self.channel_name()
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_buttons: true,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         use super::attachment::{parse_attachment_markers, is_local_path, AttachmentKind};
 
-        let token = self.get_tenant_access_token().await?;
+        let mut token = self.get_tenant_access_token().await?;
         let url = self.send_message_url();
 
         let content = super::strip_tool_call_tags(&message.content);
         let (text, attachments) = parse_attachment_markers(&content);
 
-        // Send text message if present
-        if !text.is_empty() || attachments.is_empty() {
-            let text_content = serde_json::json!({ "text": text }).to_string();
+        // Replies with a [CARD:...] or [BUTTONS:...] marker go out as one
+        // interactive card when it fits.
+        let card = build_card(&text).map(|card| card.to_string());
+        if let Some(card) = card.filter(|card| card.len() <= LARK_MAX_CARD_BYTES) {
             let body = serde_json::json!({
                 "receive_id": message.recipient,
-                "msg_type": "text",
-                "content": text_content,
+                "msg_type": "interactive",
+                "content": card,
             });
-
-            let (status, response) = self.send_text_once(&url, &token, &body).await?;
-
-            if should_refresh_lark_tenant_token(status, &response) {
-                self.invalidate_token().await;
-                let new_token = self.get_tenant_access_token().await?;
-                let (retry_status, retry_response) =
-                    self.send_text_once(&url, &new_token, &body).await?;
-
-                if should_refresh_lark_tenant_token(retry_status, &retry_response) {
-                    anyhow::bail!(
-                        "Lark send failed after token refresh: status={retry_status}, body={retry_response}"
-                    );
-                }
-
-                ensure_lark_send_success(retry_status, &retry_response, "after token refresh")?;
-            } else {
-                ensure_lark_send_success(status, &response, "text send")?;
+            self.send_with_token_refresh(&url, &mut token, &body, "card send")
+                .await?;
+        } else if !text.is_empty() || attachments.is_empty() {
+            // Send text message if present, split to fit the message size limit
+            let (text, header) = parse_card_marker(&text);
+            let (text, _) = parse_button_markers(&text);
+            let text = match header {
+                Some(header) if !header.title.is_empty() => format!("{}\n\n{text}", header.title),
+                _ => text,
+            };
+            for chunk in split_message_bytes(&text, LARK_MAX_TEXT_BYTES) {
+                let text_content =
+                    serde_json::json!({ "text": markdown::render(&chunk, Dialect::Plain) })
+                        .to_string();
+                let body = serde_json::json!({
+                    "receive_id": message.recipient,
+                    "msg_type": "text",
+                    "content": text_content,
+                });
+                self.send_with_token_refresh(&url, &mut token, &body, "text send")
+                    .await?;
             }
         }
 
@@ -1352,7 +1565,7 @@ impl Channel for LarkChannel {
 
             // Handle local files
             match attachment.kind {
-                AttachmentKind::Image => {
+                AttachmentKind::Image | AttachmentKind::Sticker | AttachmentKind::Animation => {
                     match self.upload_image(&token, &attachment.target).await {
                         Ok(image_key) => {
                             let img_content = serde_json::json!({ "image_key": image_key }).to_string();
@@ -2060,6 +2273,7 @@ mod tests {
                     }
                 },
                 "message": {
+                    "message_id": "om_msg123",
                     "message_type": "text",
                     "content": "{\"text\":\"Hello ZeroClaw!\"}",
                     "chat_id": "oc_chat123",
@@ -2070,6 +2284,7 @@ mod tests {
 
         let msgs = ch.parse_event_payload(&payload);
         assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].id, "lark_om_msg123");
         assert_eq!(msgs[0].content, "Hello ZeroClaw!");
         assert_eq!(msgs[0].sender, "oc_chat123");
         assert_eq!(msgs[0].channel, "lark");
@@ -2160,6 +2375,66 @@ mod tests {
         assert!(msgs.is_empty());
     }
 
+    #[test]
+    fn lark_card_marker_sets_header_and_buttons_become_rows() {
+        let (text, header) = parse_card_marker("[CARD: Deploy | GREEN ]\n| a | b |");
+        assert_eq!(text, "| a | b |");
+        assert_eq!(
+            header,
+            Some(CardHeader {
+                title: "Deploy".into(),
+                template: "green",
+            })
+        );
+        assert_eq!(
+            parse_card_marker("[CARD:Report|plaid]").1.unwrap().template,
+            "blue"
+        );
+        assert_eq!(parse_card_marker("no card [here]").1, None);
+        assert_eq!(build_card("plain **text**"), None);
+
+        let card = build_card("[CARD:Deploy|red]\nFailed.\n[BUTTONS:Retry|Cancel]").unwrap();
+        assert_eq!(card["schema"], "2.0");
+        assert_eq!(card["header"]["template"], "red");
+        assert_eq!(card["body"]["elements"][0]["content"], "Failed.");
+        let button = &card["body"]["elements"][1]["columns"][1]["elements"][0];
+        assert_eq!(button["text"]["content"], "Cancel");
+        assert_eq!(
+            button["behaviors"][0]["value"][LARK_CARD_BUTTON_KEY],
+            "Cancel"
+        );
+
+        let card = build_card("[BUTTONS:Yes]").unwrap();
+        assert!(card.get("header").is_none());
+        assert_eq!(card["body"]["elements"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn lark_card_button_callback_becomes_message() {
+        let ch = make_channel();
+        let payload = serde_json::json!({
+            "schema": "2.0",
+            "header": { "event_type": "card.action.trigger", "event_id": "e1" },
+            "event": {
+                "operator": { "open_id": "ou_testuser123" },
+                "action": { "tag": "button", "value": { LARK_CARD_BUTTON_KEY: "Retry" } },
+                "context": { "open_message_id": "om_1", "open_chat_id": "oc_chat" }
+            }
+        });
+        let msgs = ch.parse_event_payload(&payload);
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].content, "Retry");
+        assert_eq!(msgs[0].reply_target, "oc_chat");
+
+        let mut stranger = payload.clone();
+        stranger["event"]["operator"]["open_id"] = serde_json::json!("ou_other");
+        assert!(ch.parse_event_payload(&stranger).is_empty());
+
+        let mut foreign = payload;
+        foreign["event"]["action"]["value"] = serde_json::json!({ "key": "x" });
+        assert!(ch.parse_event_payload(&foreign).is_empty());
+    }
+
     #[test]
     fn lark_parse_missing_sender() {
         let ch = LarkChannel::new(
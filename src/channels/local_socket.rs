@@ -0,0 +1,487 @@
+//! Local Unix-socket input channel.
+//!
+//! Lets other processes on the same host inject events into the agent with
+//! minimal overhead, e.g. `echo "backup finished" | nc -U ~/.zeroclaw/inbox.sock`.
+//! Every non-empty line is one inbound message. A line may also be a JSON object
+//! `{"sender": "backup-job", "content": "backup finished"}` to name the source.
+//! Replies are written back to the originating connection, also after the
+//! client has shut down its sending side, until every line is answered or a
+//! write fails.
+
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::UnixListener;
+use tokio::sync::{mpsc, Mutex};
+
+/// Default sender name for plain-text lines.
+const DEFAULT_SENDER: &str = "local";
+/// Lines longer than this are rejected to keep a misbehaving writer from
+/// flooding the agent with a single oversized prompt.
+const MAX_LINE_BYTES: usize = 16 * 1024;
+
+type ConnectionWriters = Arc<Mutex<HashMap<String, Arc<Connection>>>>;
+
+/// Write side of an accepted connection and the replies it is still owed.
+struct Connection {
+    writer: Mutex<OwnedWriteHalf>,
+    /// Lines forwarded to the agent that have not been answered yet.
+    awaiting: AtomicU64,
+    /// Set once the client has stopped sending.
+    read_closed: AtomicBool,
+}
+
+impl Connection {
+    fn is_done(&self) -> bool {
+        self.read_closed.load(Ordering::SeqCst) && self.awaiting.load(Ordering::SeqCst) == 0
+    }
+}
+
+/// Structured line payload accepted in addition to plain text.
+#[derive(Debug, Deserialize)]
+struct SocketEvent {
+    #[serde(default)]
+    sender: Option<String>,
+    content: String,
+}
+
+/// Local socket channel — newline-delimited events over a Unix domain socket.
+pub struct LocalSocketChannel {
+    path: PathBuf,
+    writers: ConnectionWriters,
+    next_connection: AtomicU64,
+}
+
+impl LocalSocketChannel {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: PathBuf::from(shellexpand::tilde(path).into_owned()),
+            writers: Arc::new(Mutex::new(HashMap::new())),
+            next_connection: AtomicU64::new(1),
+        }
+    }
+
+    /// Remove a socket left behind by a previous run. Refuses to touch
+    /// anything that is not a socket so a misconfigured path cannot delete files.
+    fn remove_stale_socket(path: &Path) -> anyhow::Result<()> {
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_socket() => {
+                std::fs::remove_file(path)?;
+                Ok(())
+            }
+            Ok(_) => anyhow::bail!(
+                "Local socket path {} exists and is not a socket",
+                path.display()
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Bind the socket with owner-only permissions before it becomes
+    /// reachable: it is created inside a private `0700` directory, restricted
+    /// to `0600` and then moved to `path`.
+    fn bind_private(path: &Path) -> anyhow::Result<UnixListener> {
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let file_name = path.file_name().ok_or_else(|| {
+            anyhow::anyhow!("Local socket path {} has no file name", path.display())
+        })?;
+        let staging = parent.join(format!(
+            ".{}.bind-{}",
+            file_name.to_string_lossy(),
+            std::process::id()
+        ));
+        if std::fs::symlink_metadata(&staging).is_ok_and(|meta| meta.is_dir()) {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+
+        let staged = staging.join(file_name);
+        let bound = UnixListener::bind(&staged)
+            .map_err(anyhow::Error::from)
+            .and_then(|listener| {
+                std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))?;
+                std::fs::rename(&staged, path)?;
+                Ok(listener)
+            });
+        let _ = std::fs::remove_dir_all(&staging);
+        bound
+    }
+}
+
+/// Parse one inbound line into `(sender, content)`.
+///
+/// Returns `None` for blank lines and JSON events with empty content.
+fn parse_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    if line.starts_with('{') {
+        if let Ok(event) = serde_json::from_str::<SocketEvent>(line) {
+            let content = event.content.trim();
+            if content.is_empty() {
+                return None;
+            }
+            let sender = event
+                .sender
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| DEFAULT_SENDER.to_string());
+            return Some((sender, content.to_string()));
+        }
+    }
+
+    Some((DEFAULT_SENDER.to_string(), line.to_string()))
+}
+
+/// One line read by [`read_capped_line`].
+#[derive(Debug, PartialEq, Eq)]
+enum SocketLine {
+    Text(String),
+    /// A line over [`MAX_LINE_BYTES`], discarded without buffering it whole.
+    TooLong(usize),
+}
+
+/// Read the next newline-terminated line, holding at most
+/// `MAX_LINE_BYTES + 1` bytes in memory. Returns `None` at end of stream.
+async fn read_capped_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> std::io::Result<Option<SocketLine>> {
+    let limit = MAX_LINE_BYTES as u64 + 1;
+    let mut buf = Vec::new();
+    let read = (&mut *reader)
+        .take(limit)
+        .read_until(b'\n', &mut buf)
+        .await?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if buf.last() != Some(&b'\n') && buf.len() > MAX_LINE_BYTES {
+        let mut skipped = buf.len();
+        loop {
+            buf.clear();
+            let read = (&mut *reader)
+                .take(limit)
+                .read_until(b'\n', &mut buf)
+                .await?;
+            skipped += read;
+            if read == 0 || buf.last() == Some(&b'\n') {
+                return Ok(Some(SocketLine::TooLong(skipped)));
+            }
+        }
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+    }
+    Ok(Some(SocketLine::Text(
+        String::from_utf8_lossy(&buf).into_owned(),
+    )))
+}
+
+#[async_trait]
+impl Channel for LocalSocketChannel {
+    fn name(&self) -> &str {
+        "local_socket"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let connection = self.writers.lock().await.get(&message.recipient).cloned();
+        let Some(connection) = connection else {
+            // Fire-and-forget writers usually disconnect before the reply is ready.
+            tracing::debug!(
+                "Local socket: connection {} closed before reply; dropping response",
+                message.recipient
+            );
+            return Ok(());
+        };
+
+        let mut payload = message.content.clone();
+        payload.push('\n');
+        let result = connection
+            .writer
+            .lock()
+            .await
+            .write_all(payload.as_bytes())
+            .await;
+        let mut writers = self.writers.lock().await;
+        match result {
+            Ok(()) => {
+                let _ = connection
+                    .awaiting
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+                // Dropping the writer closes the connection once the client
+                // has nothing more to send and every line is answered.
+                if connection.is_done() {
+                    writers.remove(&message.recipient);
+                }
+            }
+            Err(e) => {
+                writers.remove(&message.recipient);
+                tracing::debug!(
+                    "Local socket: failed to write reply to {}: {e}",
+                    message.recipient
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        Self::remove_stale_socket(&self.path)?;
+
+        // Owner-only access: the socket is an unauthenticated prompt input.
+        let listener = Self::bind_private(&self.path)?;
+        tracing::info!("Local socket: listening on {}", self.path.display());
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let connection_id = format!(
+                "conn-{}",
+                self.next_connection.fetch_add(1, Ordering::Relaxed)
+            );
+            let (read_half, write_half) = stream.into_split();
+            let connection = Arc::new(Connection {
+                writer: Mutex::new(write_half),
+                awaiting: AtomicU64::new(0),
+                read_closed: AtomicBool::new(false),
+            });
+            self.writers
+                .lock()
+                .await
+                .insert(connection_id.clone(), Arc::clone(&connection));
+
+            let tx = tx.clone();
+            let writers = Arc::clone(&self.writers);
+            tokio::spawn(async move {
+                let mut reader = BufReader::new(read_half);
+                let mut seq = 0_u64;
+
+                while let Ok(Some(line)) = read_capped_line(&mut reader).await {
+                    let line = match line {
+                        SocketLine::Text(line) => line,
+                        SocketLine::TooLong(len) => {
+                            tracing::warn!(
+                                "Local socket: dropping {len}-byte line from {connection_id} (limit {MAX_LINE_BYTES})"
+                            );
+                            continue;
+                        }
+                    };
+                    let Some((sender, content)) = parse_line(&line) else {
+                        continue;
+                    };
+                    seq += 1;
+
+                    let msg = ChannelMessage {
+                        id: format!("local_socket_{connection_id}_{seq}"),
+                        sender,
+                        reply_target: connection_id.clone(),
+                        content,
+                        channel: "local_socket".to_string(),
+                        timestamp: std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                        thread_ts: None,
+                    };
+                    connection.awaiting.fetch_add(1, Ordering::SeqCst);
+                    if tx.send(msg).await.is_err() {
+                        break;
+                    }
+                }
+
+                // A client that only shut down its sending side still waits
+                // for replies; keep the writer until they are written.
+                let mut writers = writers.lock().await;
+                connection.read_closed.store(true, Ordering::SeqCst);
+                if connection.is_done() {
+                    writers.remove(&connection_id);
+                }
+            });
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        std::fs::symlink_metadata(&self.path)
+            .map(|meta| meta.file_type().is_socket())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::UnixStream;
+
+    #[test]
+    fn parse_line_plain_text_uses_default_sender() {
+        let (sender, content) = parse_line("  backup finished \n").unwrap();
+        assert_eq!(sender, "local");
+        assert_eq!(content, "backup finished");
+    }
+
+    #[test]
+    fn parse_line_json_event_sets_sender() {
+        let (sender, content) =
+            parse_line(r#"{"sender":"sensor-7","content":"door opened"}"#).unwrap();
+        assert_eq!(sender, "sensor-7");
+        assert_eq!(content, "door opened");
+    }
+
+    #[test]
+    fn parse_line_json_without_sender_falls_back() {
+        let (sender, _) = parse_line(r#"{"content":"ping"}"#).unwrap();
+        assert_eq!(sender, "local");
+    }
+
+    #[test]
+    fn parse_line_skips_blank_and_empty_content() {
+        assert!(parse_line("   ").is_none());
+        assert!(parse_line(r#"{"content":"  "}"#).is_none());
+    }
+
+    #[test]
+    fn parse_line_invalid_json_is_plain_text() {
+        let (_, content) = parse_line("{not json").unwrap();
+        assert_eq!(content, "{not json");
+    }
+
+    #[test]
+    fn new_expands_tilde() {
+        let ch = LocalSocketChannel::new("~/inbox.sock");
+        assert!(!ch.path.to_string_lossy().starts_with('~'));
+    }
+
+    #[test]
+    fn remove_stale_socket_refuses_regular_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("not-a-socket");
+        std::fs::write(&path, "data").unwrap();
+        let err = LocalSocketChannel::remove_stale_socket(&path).unwrap_err();
+        assert!(err.to_string().contains("not a socket"));
+        assert!(path.exists());
+    }
+
+    #[tokio::test]
+    async fn read_capped_line_discards_oversized_lines() {
+        let mut input = vec![b'x'; MAX_LINE_BYTES * 3];
+        input.extend_from_slice(b"\nnext\nlast");
+        let mut reader = BufReader::new(input.as_slice());
+
+        assert_eq!(
+            read_capped_line(&mut reader).await.unwrap(),
+            Some(SocketLine::TooLong(MAX_LINE_BYTES * 3 + 1))
+        );
+        assert_eq!(
+            read_capped_line(&mut reader).await.unwrap(),
+            Some(SocketLine::Text("next".into()))
+        );
+        assert_eq!(
+            read_capped_line(&mut reader).await.unwrap(),
+            Some(SocketLine::Text("last".into()))
+        );
+        assert_eq!(read_capped_line(&mut reader).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn send_to_unknown_connection_is_noop() {
+        let ch = LocalSocketChannel::new("/tmp/unused.sock");
+        assert!(ch.send(&SendMessage::new("hi", "conn-99")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn listen_routes_lines_and_replies_on_connection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("inbox.sock");
+        let ch = Arc::new(LocalSocketChannel::new(path.to_str().unwrap()));
+        let (tx, mut rx) = mpsc::channel(4);
+
+        let listener = Arc::clone(&ch);
+        tokio::spawn(async move { listener.listen(tx).await });
+        for _ in 0..50 {
+            if ch.health_check().await {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // The private directory the socket was bound in is gone.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let stream = UnixStream::connect(&path).await.unwrap();
+        let (read_half, mut write_half) = stream.into_split();
+        write_half.write_all(b"backup finished\n").await.unwrap();
+
+        let msg = rx.recv().await.unwrap();
+        assert_eq!(msg.channel, "local_socket");
+        assert_eq!(msg.sender, "local");
+        assert_eq!(msg.content, "backup finished");
+
+        ch.send(&SendMessage::new("noted", &msg.reply_target))
+            .await
+            .unwrap();
+        let mut lines = BufReader::new(read_half).lines();
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("noted"));
+    }
+
+    #[tokio::test]
+    async fn half_closed_client_gets_reply_then_eof() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("inbox.sock");
+        let ch = Arc::new(LocalSocketChannel::new(path.to_str().unwrap()));
+        let (tx, mut rx) = mpsc::channel(4);
+
+        let listener = Arc::clone(&ch);
+        tokio::spawn(async move { listener.listen(tx).await });
+        for _ in 0..50 {
+            if ch.health_check().await {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream.write_all(b"first\nsecond\n").await.unwrap();
+        stream.shutdown().await.unwrap();
+
+        let first = rx.recv().await.unwrap();
+        let second = rx.recv().await.unwrap();
+        // Let the reader see end of stream before the replies go out.
+        for _ in 0..50 {
+            let closed = ch.writers.lock().await[&first.reply_target]
+                .read_closed
+                .load(Ordering::SeqCst);
+            if closed {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        ch.send(&SendMessage::new("one", &first.reply_target))
+            .await
+            .unwrap();
+        ch.send(&SendMessage::new("two", &second.reply_target))
+            .await
+            .unwrap();
+        assert!(ch.writers.lock().await.is_empty());
+
+        let mut replies = String::new();
+        stream.read_to_string(&mut replies).await.unwrap();
+        assert_eq!(replies, "one\ntwo\n");
+    }
+}
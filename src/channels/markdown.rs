@@ -0,0 +1,392 @@
+//! Rendering the generic Markdown that providers emit into each channel's
+//! own formatting dialect.
+//!
+//! Replies are parsed line by line: fenced code blocks, `#` headings, and
+//! inline bold, italic, strikethrough, code and links. Everything else
+//! passes through as text, escaped where the dialect needs it. Code block
+//! contents are never reformatted. Channels whose clients render Markdown
+//! natively (Discord, Mattermost, Zulip) send replies unchanged; Matrix
+//! replies are rendered to HTML by the Matrix SDK. The CLI renders to ANSI
+//! terminal styles.
+
+/// Target formatting for [`render`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// Telegram `parse_mode = "HTML"`: `<b>`, `<i>`, `<s>`, `<code>`,
+    /// `<pre>` and `<a href>`, with all other text entity-escaped.
+    TelegramHtml,
+    /// Slack mrkdwn: `*bold*`, `_italic_`, `~strike~`, `<url|label>`.
+    SlackMrkdwn,
+    /// Google Chat text: like mrkdwn, but without entity escaping.
+    GoogleChat,
+    /// WhatsApp: `*bold*`, `_italic_`, `~strike~`; links as `label (url)`.
+    WhatsApp,
+    /// DingTalk markdown messages: bold, italic, links and headings only.
+    DingTalk,
+    /// No markup at all, for channels that show text verbatim.
+    Plain,
+    /// ANSI escape sequences for terminals: bold, italic, strikethrough,
+    /// colored code and underlined links followed by their URL.
+    Ansi,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Inline<'a> {
+    Text(&'a str),
+    Bold(&'a str),
+    Italic(&'a str),
+    Strike(&'a str),
+    Code(&'a str),
+    Link { label: &'a str, url: &'a str },
+}
+
+/// Render `markdown` for `dialect`.
+pub fn render(markdown: &str, dialect: Dialect) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut code_block: Option<Vec<&str>> = None;
+
+    for line in markdown.split('\n') {
+        if line.trim_start().starts_with("```") {
+            match code_block.take() {
+                Some(lines) => out.push(render_code_block(&lines, dialect)),
+                None => code_block = Some(Vec::new()),
+            }
+            continue;
+        }
+        if let Some(lines) = code_block.as_mut() {
+            lines.push(line);
+            continue;
+        }
+        if let Some(title) = heading(line) {
+            out.push(render_heading(line, title, dialect));
+            continue;
+        }
+        out.push(
+            parse_inline(line)
+                .into_iter()
+                .map(|span| render_inline(&span, dialect))
+                .collect(),
+        );
+    }
+    // An unterminated fence still renders as code.
+    if let Some(lines) = code_block.filter(|lines| !lines.is_empty()) {
+        out.push(render_code_block(&lines, dialect));
+    }
+
+    out.join("\n").trim_end_matches('\n').to_string()
+}
+
+/// Heading text of an ATX heading line (`# Title` through `###### Title`).
+pub fn heading(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let rest = trimmed.trim_start_matches('#');
+    let level = trimmed.len() - rest.len();
+    ((1..=6).contains(&level) && rest.starts_with(' ')).then(|| rest.trim())
+}
+
+fn parse_inline(line: &str) -> Vec<Inline<'_>> {
+    let mut spans = Vec::new();
+    let bytes = line.as_bytes();
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let parsed = match bytes[i] {
+            b'*' if line[i..].starts_with("**") => {
+                delimited(line, i, "**").map(|(inner, end)| (Inline::Bold(inner), end))
+            }
+            b'_' if line[i..].starts_with("__") => {
+                delimited(line, i, "__").map(|(inner, end)| (Inline::Bold(inner), end))
+            }
+            b'~' if line[i..].starts_with("~~") => {
+                delimited(line, i, "~~").map(|(inner, end)| (Inline::Strike(inner), end))
+            }
+            b'*' => emphasis(line, i, '*'),
+            // `snake_case` words are not emphasis: `_` must open at a word start.
+            b'_' if i == 0 || !bytes[i - 1].is_ascii_alphanumeric() => emphasis(line, i, '_'),
+            b'`' => delimited(line, i, "`").map(|(inner, end)| (Inline::Code(inner), end)),
+            b'[' => link(line, i),
+            _ => None,
+        };
+
+        match parsed {
+            Some((span, end)) => {
+                if text_start < i {
+                    spans.push(Inline::Text(&line[text_start..i]));
+                }
+                spans.push(span);
+                i = end;
+                text_start = end;
+            }
+            None => i += line[i..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    if text_start < line.len() {
+        spans.push(Inline::Text(&line[text_start..]));
+    }
+    spans
+}
+
+/// Non-empty text between `marker` at `start` and the next `marker`, and the
+/// byte offset just past the closing marker.
+fn delimited<'a>(line: &'a str, start: usize, marker: &str) -> Option<(&'a str, usize)> {
+    let open = start + marker.len();
+    let len = line[open..].find(marker)?;
+    (len > 0).then(|| (&line[open..open + len], open + len + marker.len()))
+}
+
+/// Single-character emphasis; `* item` bullets and `2 * 3` are left alone.
+fn emphasis(line: &str, start: usize, marker: char) -> Option<(Inline<'_>, usize)> {
+    let next = line[start + 1..].chars().next()?;
+    if next.is_whitespace() {
+        return None;
+    }
+    let (inner, end) = delimited(line, start, marker.encode_utf8(&mut [0; 4]))?;
+    if inner.ends_with(char::is_whitespace) {
+        return None;
+    }
+    Some((Inline::Italic(inner), end))
+}
+
+/// `[label](http...)`; other bracketed text stays literal.
+fn link(line: &str, start: usize) -> Option<(Inline<'_>, usize)> {
+    let label_end = start + 1 + line[start + 1..].find("](")?;
+    let url_start = label_end + 2;
+    let url_end = url_start + line[url_start..].find(')')?;
+    let url = &line[url_start..url_end];
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return None;
+    }
+    let label = &line[start + 1..label_end];
+    Some((Inline::Link { label, url }, url_end + 1))
+}
+
+fn escape(text: &str, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::TelegramHtml => text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;"),
+        Dialect::SlackMrkdwn => text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;"),
+        Dialect::GoogleChat | Dialect::WhatsApp | Dialect::DingTalk | Dialect::Plain => {
+            text.to_string()
+        }
+        // Escape sequences from the model must not drive the terminal.
+        Dialect::Ansi => text.replace('\x1b', ""),
+    }
+}
+
+fn render_inline(span: &Inline<'_>, dialect: Dialect) -> String {
+    use Dialect::{Ansi, DingTalk, GoogleChat, Plain, SlackMrkdwn, TelegramHtml, WhatsApp};
+
+    match (span, dialect) {
+        (Inline::Text(text), _) => escape(text, dialect),
+
+        (Inline::Bold(inner), TelegramHtml) => format!("<b>{}</b>", escape(inner, dialect)),
+        (Inline::Bold(inner), SlackMrkdwn | GoogleChat | WhatsApp) => {
+            format!("*{}*", escape(inner, dialect))
+        }
+        (Inline::Bold(inner), DingTalk) => format!("**{inner}**"),
+        (Inline::Bold(inner), Ansi) => format!("\x1b[1m{}\x1b[22m", escape(inner, dialect)),
+
+        (Inline::Italic(inner), TelegramHtml) => format!("<i>{}</i>", escape(inner, dialect)),
+        (Inline::Italic(inner), SlackMrkdwn | GoogleChat | WhatsApp) => {
+            format!("_{}_", escape(inner, dialect))
+        }
+        (Inline::Italic(inner), DingTalk) => format!("*{inner}*"),
+        (Inline::Italic(inner), Ansi) => format!("\x1b[3m{}\x1b[23m", escape(inner, dialect)),
+
+        (Inline::Strike(inner), TelegramHtml) => format!("<s>{}</s>", escape(inner, dialect)),
+        (Inline::Strike(inner), SlackMrkdwn | GoogleChat | WhatsApp) => {
+            format!("~{}~", escape(inner, dialect))
+        }
+        (Inline::Strike(inner), Ansi) => format!("\x1b[9m{}\x1b[29m", escape(inner, dialect)),
+
+        (Inline::Code(inner), TelegramHtml) => {
+            format!("<code>{}</code>", escape(inner, dialect))
+        }
+        (Inline::Code(inner), SlackMrkdwn | GoogleChat | WhatsApp) => {
+            format!("`{}`", escape(inner, dialect))
+        }
+        (Inline::Code(inner), Ansi) => format!("\x1b[36m{}\x1b[39m", escape(inner, dialect)),
+
+        (Inline::Bold(inner) | Inline::Italic(inner), Plain)
+        | (Inline::Strike(inner) | Inline::Code(inner), DingTalk | Plain) => (*inner).to_string(),
+
+        (Inline::Link { label, url }, TelegramHtml) => format!(
+            "<a href=\"{}\">{}</a>",
+            escape(url, dialect),
+            escape(label, dialect)
+        ),
+        (Inline::Link { label, url }, SlackMrkdwn | GoogleChat) => {
+            format!("<{url}|{}>", escape(label, dialect))
+        }
+        (Inline::Link { label, url }, DingTalk) => format!("[{label}]({url})"),
+        (Inline::Link { label, url }, Ansi) => {
+            let label = escape(label, dialect);
+            let url = escape(url, dialect);
+            if label == url {
+                format!("\x1b[4m{url}\x1b[24m")
+            } else {
+                format!("\x1b[4m{label}\x1b[24m ({url})")
+            }
+        }
+        (Inline::Link { label, url }, WhatsApp | Plain) => {
+            if label == url {
+                (*url).to_string()
+            } else {
+                format!("{label} ({url})")
+            }
+        }
+    }
+}
+
+fn render_heading(line: &str, title: &str, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::TelegramHtml => format!("<b>{}</b>", escape(title, dialect)),
+        Dialect::SlackMrkdwn | Dialect::GoogleChat | Dialect::WhatsApp => {
+            format!("*{}*", escape(title, dialect))
+        }
+        Dialect::DingTalk => line.trim().to_string(),
+        Dialect::Plain => title.to_string(),
+        Dialect::Ansi => format!("\x1b[1;4m{}\x1b[0m", escape(title, dialect)),
+    }
+}
+
+/// The fence's info string is dropped: none of these dialects use it, and
+/// Telegram would reject it as an attribute.
+fn render_code_block(lines: &[&str], dialect: Dialect) -> String {
+    let code = lines.join("\n");
+    match dialect {
+        Dialect::TelegramHtml => format!(
+            "<pre><code>{}</code></pre>",
+            escape(code.trim_end_matches('\n'), dialect)
+        ),
+        Dialect::SlackMrkdwn | Dialect::GoogleChat | Dialect::WhatsApp => {
+            format!("```\n{}\n```", escape(&code, dialect))
+        }
+        // DingTalk has no code formatting; keep the lines as they are.
+        Dialect::DingTalk | Dialect::Plain => code,
+        Dialect::Ansi => format!("\x1b[36m{}\x1b[39m", escape(&code, dialect)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telegram_html_escapes_quotes_in_link_href() {
+        assert_eq!(
+            render(
+                "[click](https://example.com?q=\"x\"&a='b')",
+                Dialect::TelegramHtml
+            ),
+            "<a href=\"https://example.com?q=&quot;x&quot;&amp;a=&#39;b&#39;\">click</a>"
+        );
+    }
+
+    #[test]
+    fn telegram_html_escapes_plain_text() {
+        assert_eq!(
+            render("say \"hi\" & <tag> 'ok'", Dialect::TelegramHtml),
+            "say &quot;hi&quot; &amp; &lt;tag&gt; &#39;ok&#39;"
+        );
+    }
+
+    #[test]
+    fn telegram_html_code_block_drops_language_attribute() {
+        let rendered = render(
+            "```rust\" onclick=\"alert(1)\nlet x = 1;\n```",
+            Dialect::TelegramHtml,
+        );
+        assert_eq!(rendered, "<pre><code>let x = 1;</code></pre>");
+    }
+
+    #[test]
+    fn code_blocks_are_not_reformatted() {
+        let markdown = "Run **this**:\n```\nlet **x** = a_b_c < 1;\n```";
+        assert_eq!(
+            render(markdown, Dialect::TelegramHtml),
+            "Run <b>this</b>:\n<pre><code>let **x** = a_b_c &lt; 1;</code></pre>"
+        );
+        assert_eq!(
+            render(markdown, Dialect::SlackMrkdwn),
+            "Run *this*:\n```\nlet **x** = a_b_c &lt; 1;\n```"
+        );
+    }
+
+    #[test]
+    fn slack_mrkdwn_converts_emphasis_links_and_headings() {
+        assert_eq!(
+            render(
+                "## Status\n**Done** *now* ~~old~~ `cmd` see [docs](https://x.dev/a) & more",
+                Dialect::SlackMrkdwn
+            ),
+            "*Status*\n*Done* _now_ ~old~ `cmd` see <https://x.dev/a|docs> &amp; more"
+        );
+    }
+
+    #[test]
+    fn google_chat_formatting() {
+        assert_eq!(
+            render(
+                "**Done** ~~old~~ see [docs](https://x.dev/a)\n```\n**raw**\n```",
+                Dialect::GoogleChat
+            ),
+            "*Done* ~old~ see <https://x.dev/a|docs>\n```\n**raw**\n```"
+        );
+    }
+
+    #[test]
+    fn bullets_and_identifiers_are_not_emphasis() {
+        let markdown = "* first item\n* second\nuse snake_case_name and 2 * 3 * 4";
+        assert_eq!(render(markdown, Dialect::WhatsApp), markdown);
+        assert_eq!(render(markdown, Dialect::Plain), markdown);
+    }
+
+    #[test]
+    fn plain_and_whatsapp_links_show_the_url() {
+        assert_eq!(
+            render(
+                "**Read** [the docs](https://x.dev) or https://y.dev",
+                Dialect::Plain
+            ),
+            "Read the docs (https://x.dev) or https://y.dev"
+        );
+        assert_eq!(
+            render(
+                "**Read** _this_ [https://x.dev](https://x.dev)",
+                Dialect::WhatsApp
+            ),
+            "*Read* _this_ https://x.dev"
+        );
+    }
+
+    #[test]
+    fn dingtalk_keeps_supported_markdown_only() {
+        assert_eq!(
+            render(
+                "# Report\n**ok** ~~gone~~ `x`\n```\ncode\n```",
+                Dialect::DingTalk
+            ),
+            "# Report\n**ok** gone x\ncode"
+        );
+    }
+
+    #[test]
+    fn ansi_styles_text_and_strips_escapes() {
+        assert_eq!(
+            render(
+                "# Hi\n**b** *i* `c` [docs](https://x.dev) \x1b[31mred",
+                Dialect::Ansi
+            ),
+            "\x1b[1;4mHi\x1b[0m\n\x1b[1mb\x1b[22m \x1b[3mi\x1b[23m \x1b[36mc\x1b[39m \x1b[4mdocs\x1b[24m (https://x.dev) [31mred"
+        );
+    }
+}
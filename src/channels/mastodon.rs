@@ -0,0 +1,607 @@
+use super::attachment::{
+    download_remote, is_local_path, parse_attachment_markers, Attachment, AttachmentKind,
+};
+use super::markdown::{self, Dialect};
+use super::split::{non_empty_chunks, split_message};
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Default status length limit of Mastodon instances.
+const DEFAULT_MAX_CHARS: usize = 500;
+/// Default seconds between notification polls.
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 15;
+/// Mastodon accepts at most this many media attachments per status.
+const MAX_MEDIA_PER_STATUS: usize = 4;
+/// Largest remote attachment downloaded for upload.
+const MAX_MEDIA_BYTES: u64 = 40 * 1024 * 1024;
+/// Checks of a media upload that is still being processed.
+const MEDIA_PROCESSING_CHECKS: u32 = 30;
+/// Fewest characters of text left per toot after the mention prefix.
+const MIN_TEXT_CHARS: usize = 100;
+
+/// Mastodon (or any Mastodon-API Fediverse server) channel — answers
+/// mentions and direct messages of the bot account.
+///
+/// Mentions are read by polling the notifications API. Replies are posted
+/// as replies to the mentioning status, with the same visibility (public
+/// mentions get unlisted replies so threads stay off the public timelines),
+/// and long replies continue as a thread of toots.
+///
+/// Reply targets:
+/// - `status:<id>` — reply to that status, mentioning its author
+/// - `user@instance[,user@instance...]` — new direct message
+pub struct MastodonChannel {
+    instance_url: String,
+    access_token: String,
+    allowed_users: Vec<String>,
+    max_chars: usize,
+    poll_interval: Duration,
+}
+
+/// Where a Mastodon reply should be posted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MastodonTarget {
+    Reply { status_id: String },
+    Direct { accounts: Vec<String> },
+}
+
+impl MastodonChannel {
+    pub fn new(
+        instance_url: String,
+        access_token: String,
+        allowed_users: Vec<String>,
+        max_chars: Option<usize>,
+        poll_interval_secs: Option<u64>,
+    ) -> Self {
+        Self {
+            instance_url: instance_url.trim_end_matches('/').to_string(),
+            access_token,
+            allowed_users,
+            max_chars: max_chars.filter(|n| *n > 0).unwrap_or(DEFAULT_MAX_CHARS),
+            poll_interval: Duration::from_secs(
+                poll_interval_secs
+                    .filter(|n| *n > 0)
+                    .unwrap_or(DEFAULT_POLL_INTERVAL_SECS),
+            ),
+        }
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.mastodon")
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/api/{path}", self.instance_url)
+    }
+
+    /// Check if an account is in the allowlist.
+    /// Empty list means deny everyone. "*" means allow everyone.
+    fn is_user_allowed(&self, acct: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.trim_start_matches('@').eq_ignore_ascii_case(acct))
+    }
+
+    fn parse_target(recipient: &str) -> Result<MastodonTarget> {
+        let recipient = recipient.trim();
+        if let Some(id) = recipient.strip_prefix("status:") {
+            if id.trim().is_empty() {
+                bail!("Mastodon recipient '{recipient}' is missing a status ID");
+            }
+            return Ok(MastodonTarget::Reply {
+                status_id: id.trim().to_string(),
+            });
+        }
+        let accounts: Vec<String> = recipient
+            .split(',')
+            .map(|a| a.trim().trim_start_matches('@'))
+            .filter(|a| !a.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        if accounts.is_empty() || accounts.iter().any(|a| a.contains(char::is_whitespace)) {
+            bail!("Unsupported Mastodon recipient '{recipient}'");
+        }
+        Ok(MastodonTarget::Direct { accounts })
+    }
+
+    /// Convert a `mention` notification into a channel message, applying
+    /// the allowlist.
+    fn parse_notification(&self, notification: &serde_json::Value) -> Option<ChannelMessage> {
+        if notification.get("type").and_then(|t| t.as_str()) != Some("mention") {
+            return None;
+        }
+        let status = notification.get("status")?;
+        let acct = status.get("account")?.get("acct")?.as_str()?;
+        if !self.is_user_allowed(acct) {
+            tracing::warn!("Mastodon: ignoring mention from unauthorized user: {acct}");
+            return None;
+        }
+        let status_id = status.get("id")?.as_str()?;
+
+        let mut content = strip_leading_mentions(&html_to_text(
+            status.get("content").and_then(|c| c.as_str()).unwrap_or(""),
+        ));
+        let images = status
+            .get("media_attachments")
+            .and_then(|m| m.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|m| m.get("type").and_then(|t| t.as_str()) == Some("image"))
+            .filter_map(|m| m.get("url").and_then(|u| u.as_str()));
+        for url in images {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            let _ = write!(content, "[IMAGE:{url}]");
+        }
+        if content.is_empty() {
+            return None;
+        }
+
+        Some(ChannelMessage {
+            id: format!("mastodon_{status_id}"),
+            sender: acct.to_string(),
+            reply_target: format!("status:{status_id}"),
+            content,
+            channel: "mastodon".to_string(),
+            timestamp: status
+                .get("created_at")
+                .and_then(|t| t.as_str())
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .and_then(|t| u64::try_from(t.timestamp()).ok())
+                .unwrap_or_else(|| {
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs()
+                }),
+            thread_ts: None,
+        })
+    }
+
+    async fn get_json(&self, path: &str) -> Result<serde_json::Value> {
+        let resp = self
+            .http_client()
+            .get(self.api_url(path))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            bail!("Mastodon request {path} failed ({status}): {body}");
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Status ID to reply to, accounts to mention and visibility for a target.
+    async fn resolve_target(
+        &self,
+        target: &MastodonTarget,
+    ) -> Result<(Option<String>, Vec<String>, String)> {
+        match target {
+            MastodonTarget::Reply { status_id } => {
+                let status = self.get_json(&format!("v1/statuses/{status_id}")).await?;
+                let author = status
+                    .get("account")
+                    .and_then(|a| a.get("acct"))
+                    .and_then(|a| a.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Mastodon status {status_id} has no author"))?;
+                let visibility = reply_visibility(
+                    status
+                        .get("visibility")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unlisted"),
+                );
+                Ok((
+                    Some(status_id.clone()),
+                    vec![author.to_string()],
+                    visibility.to_string(),
+                ))
+            }
+            MastodonTarget::Direct { accounts } => {
+                Ok((None, accounts.clone(), "direct".to_string()))
+            }
+        }
+    }
+
+    /// Upload an attachment and return its media ID.
+    async fn upload_media(&self, attachment: &Attachment) -> Result<String> {
+        let target = attachment.target.trim();
+        let (bytes, file_name) = if is_local_path(target) {
+            let path = std::path::Path::new(target);
+            let bytes = tokio::fs::read(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Mastodon: cannot read attachment {target}: {e}"))?;
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("file")
+                .to_string();
+            (bytes, file_name)
+        } else {
+            let file = download_remote(
+                &self.http_client(),
+                target,
+                &attachment.kind,
+                MAX_MEDIA_BYTES,
+            )
+            .await?;
+            (file.bytes, file.file_name)
+        };
+
+        let mut form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(bytes).file_name(file_name),
+        );
+        if let Some(alt) = &attachment.alt {
+            form = form.text("description", alt.clone());
+        }
+        let resp = self
+            .http_client()
+            .post(self.api_url("v2/media"))
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body: serde_json::Value = resp.json().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!(
+                "Mastodon upload of {target} failed ({status}): {}",
+                body.get("error").and_then(|e| e.as_str()).unwrap_or("")
+            );
+        }
+        let id = body
+            .get("id")
+            .and_then(|i| i.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Mastodon upload response missing id"))?
+            .to_string();
+
+        // 202 Accepted: the server is still processing the file (videos,
+        // large images) and rejects statuses referencing it until it is done.
+        if status == reqwest::StatusCode::ACCEPTED {
+            for _ in 0..MEDIA_PROCESSING_CHECKS {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                let resp = self
+                    .http_client()
+                    .get(self.api_url(&format!("v1/media/{id}")))
+                    .bearer_auth(&self.access_token)
+                    .send()
+                    .await?;
+                if resp.status() == reqwest::StatusCode::OK {
+                    return Ok(id);
+                }
+            }
+            bail!("Mastodon is still processing {target}");
+        }
+        Ok(id)
+    }
+
+    /// Post one status and return its ID.
+    async fn post_status(
+        &self,
+        text: &str,
+        in_reply_to_id: Option<&str>,
+        visibility: &str,
+        media_ids: &[String],
+    ) -> Result<String> {
+        let mut body = serde_json::json!({
+            "status": text,
+            "visibility": visibility,
+        });
+        if let Some(id) = in_reply_to_id {
+            body["in_reply_to_id"] = serde_json::json!(id);
+        }
+        if !media_ids.is_empty() {
+            body["media_ids"] = serde_json::json!(media_ids);
+        }
+
+        let resp = self
+            .http_client()
+            .post(self.api_url("v1/statuses"))
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            bail!("Mastodon send failed ({status}): {body}");
+        }
+        let posted: serde_json::Value = resp.json().await?;
+        posted
+            .get("id")
+            .and_then(|i| i.as_str())
+            .map(ToString::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Mastodon status response missing id"))
+    }
+
+    /// ID of the newest mention notification, so polling starts after it.
+    async fn latest_notification_id(&self) -> Result<Option<String>> {
+        let notifications = self
+            .get_json("v1/notifications?types[]=mention&limit=1")
+            .await?;
+        Ok(notifications
+            .as_array()
+            .and_then(|n| n.first())
+            .and_then(|n| n.get("id"))
+            .and_then(|i| i.as_str())
+            .map(ToString::to_string))
+    }
+}
+
+/// Visibility of a reply to a status with `visibility`.
+fn reply_visibility(visibility: &str) -> &'static str {
+    match visibility {
+        "direct" => "direct",
+        "private" => "private",
+        _ => "unlisted",
+    }
+}
+
+/// Plain text of a status' HTML content.
+fn html_to_text(html: &str) -> String {
+    let html = html
+        .replace("<br />", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br>", "\n")
+        .replace("</p><p>", "\n\n");
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+/// Drop the `@user` mentions a status starts with (the bot and others in
+/// the thread), keeping mentions inside the text.
+fn strip_leading_mentions(text: &str) -> String {
+    let mut rest = text.trim_start();
+    while rest.starts_with('@') {
+        rest = rest
+            .find(char::is_whitespace)
+            .map_or("", |end| &rest[end..])
+            .trim_start();
+    }
+    rest.to_string()
+}
+
+/// Split a reply into toots of at most `max_chars`, each starting with
+/// `prefix` (the mentions that keep the thread addressed to the user).
+fn split_toots(text: &str, prefix: &str, max_chars: usize) -> Vec<String> {
+    let budget = max_chars
+        .saturating_sub(prefix.chars().count())
+        .max(MIN_TEXT_CHARS);
+    non_empty_chunks(split_message(text.trim(), budget))
+        .into_iter()
+        .map(|chunk| format!("{prefix}{chunk}"))
+        .collect()
+}
+
+#[async_trait]
+impl Channel for MastodonChannel {
+    fn name(&self) -> &str {
+        "mastodon"
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_markdown: false,
+            max_message_len: Some(self.max_chars),
+            attachment_kinds: vec![
+                AttachmentKind::Image,
+                AttachmentKind::Video,
+                AttachmentKind::Audio,
+                AttachmentKind::Animation,
+            ],
+            ..ChannelCapabilities::default()
+        }
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        let target = Self::parse_target(&message.recipient)?;
+        let content = super::strip_tool_call_tags(&message.content);
+        let (text, attachments) = parse_attachment_markers(&content);
+        let mut text = markdown::render(&text, Dialect::Plain);
+
+        let mut media_ids = Vec::new();
+        for attachment in &attachments {
+            match self.upload_media(attachment).await {
+                Ok(id) => media_ids.push(id),
+                Err(e) => {
+                    tracing::warn!("{e}");
+                    if !is_local_path(&attachment.target) {
+                        let _ = write!(text, "\n{}", attachment.target.trim());
+                    }
+                }
+            }
+        }
+
+        let (mut in_reply_to, mentions, visibility) = self.resolve_target(&target).await?;
+        let prefix = mentions.iter().fold(String::new(), |mut prefix, acct| {
+            let _ = write!(prefix, "@{acct} ");
+            prefix
+        });
+        let mut toots = split_toots(&text, &prefix, self.max_chars);
+        let media_groups: Vec<&[String]> = media_ids.chunks(MAX_MEDIA_PER_STATUS).collect();
+        // Media-only replies and leftover media still need a status each.
+        while toots.len() < media_groups.len() {
+            toots.push(prefix.trim_end().to_string());
+        }
+
+        for (i, toot) in toots.iter().enumerate() {
+            let media = media_groups.get(i).copied().unwrap_or_default();
+            let id = self
+                .post_status(toot, in_reply_to.as_deref(), &visibility, media)
+                .await?;
+            in_reply_to = Some(id);
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        let mut since_id = self.latest_notification_id().await?;
+        tracing::info!("Mastodon channel listening on {}", self.instance_url);
+
+        loop {
+            tokio::time::sleep(self.poll_interval).await;
+
+            let path = match &since_id {
+                Some(id) => format!("v1/notifications?types[]=mention&limit=40&since_id={id}"),
+                None => "v1/notifications?types[]=mention&limit=40".to_string(),
+            };
+            let notifications = match self.get_json(&path).await {
+                Ok(n) => n.as_array().cloned().unwrap_or_default(),
+                Err(e) => {
+                    tracing::warn!("Mastodon poll error: {e}");
+                    continue;
+                }
+            };
+
+            // Newest first; process in the order they arrived.
+            if let Some(newest) = notifications
+                .first()
+                .and_then(|n| n.get("id"))
+                .and_then(|i| i.as_str())
+            {
+                since_id = Some(newest.to_string());
+            }
+            for notification in notifications.iter().rev() {
+                if let Some(msg) = self.parse_notification(notification) {
+                    if tx.send(msg).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        self.get_json("v1/accounts/verify_credentials")
+            .await
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_channel() -> MastodonChannel {
+        MastodonChannel::new(
+            "https://social.example/".into(),
+            "token".into(),
+            vec!["alice@other.example".into(), "@bob".into()],
+            None,
+            None,
+        )
+    }
+
+    fn mention(acct: &str, content: &str) -> serde_json::Value {
+        json!({
+            "id": "900",
+            "type": "mention",
+            "account": {"acct": acct},
+            "status": {
+                "id": "1100",
+                "created_at": "2024-05-01T12:00:00.000Z",
+                "visibility": "direct",
+                "account": {"acct": acct},
+                "content": content,
+                "media_attachments": [
+                    {"type": "image", "url": "https://files.example/a.png"},
+                    {"type": "video", "url": "https://files.example/b.mp4"}
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn mentions_become_messages_replying_to_the_status() {
+        let ch = make_channel();
+        let msg = ch
+            .parse_notification(&mention(
+                "alice@other.example",
+                "<p><span class=\"h-card\"><a href=\"https://social.example/@bot\" class=\"u-url mention\">@<span>bot</span></a></span> What&#39;s 2 &amp; 2?</p><p>Thanks<br />— A</p>",
+            ))
+            .unwrap();
+        assert_eq!(msg.id, "mastodon_1100");
+        assert_eq!(msg.sender, "alice@other.example");
+        assert_eq!(msg.reply_target, "status:1100");
+        assert_eq!(msg.timestamp, 1_714_564_800);
+        assert_eq!(
+            msg.content,
+            "What's 2 & 2?\n\nThanks\n— A\n[IMAGE:https://files.example/a.png]"
+        );
+    }
+
+    #[test]
+    fn ignores_unauthorized_accounts_and_other_notifications() {
+        let ch = make_channel();
+        assert!(ch
+            .parse_notification(&mention("bob", "<p>hi</p>"))
+            .is_some());
+        assert!(ch
+            .parse_notification(&mention("mallory@evil.example", "<p>hi</p>"))
+            .is_none());
+        let mut favourite = mention("bob", "<p>hi</p>");
+        favourite["type"] = json!("favourite");
+        assert!(ch.parse_notification(&favourite).is_none());
+    }
+
+    #[test]
+    fn parses_reply_targets() {
+        assert_eq!(
+            MastodonChannel::parse_target("status:1100").unwrap(),
+            MastodonTarget::Reply {
+                status_id: "1100".into()
+            }
+        );
+        assert_eq!(
+            MastodonChannel::parse_target("@alice@other.example, bob").unwrap(),
+            MastodonTarget::Direct {
+                accounts: vec!["alice@other.example".into(), "bob".into()]
+            }
+        );
+        assert!(MastodonChannel::parse_target("status:").is_err());
+        assert!(MastodonChannel::parse_target("").is_err());
+    }
+
+    #[test]
+    fn long_replies_split_into_addressed_toots() {
+        let text = format!("{}\n\n{}", "a".repeat(450), "b".repeat(300));
+        let toots = split_toots(&text, "@alice@other.example ", 500);
+        assert_eq!(toots.len(), 2);
+        assert!(toots
+            .iter()
+            .all(|t| t.starts_with("@alice@other.example ") && t.chars().count() <= 500));
+        assert_eq!(reply_visibility("public"), "unlisted");
+        assert_eq!(reply_visibility("direct"), "direct");
+    }
+}
@@ -1,13 +1,18 @@
+use crate::channels::draft::DraftThrottle;
+use crate::channels::split::split_message_bytes;
 use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::StreamMode;
 use async_trait::async_trait;
 use matrix_sdk::{
+    attachment::AttachmentConfig,
     authentication::matrix::MatrixSession,
     config::SyncSettings,
     ruma::{
         events::room::message::{
-            MessageType, OriginalSyncRoomMessageEvent, RoomMessageEventContent,
+            MessageType, OriginalSyncRoomMessageEvent, ReplacementMetadata,
+            RoomMessageEventContent, TextMessageEventContent,
         },
-        OwnedRoomId, OwnedUserId,
+        OwnedEventId, OwnedRoomId, OwnedUserId,
     },
     Client as MatrixSdkClient, LoopCtrl, Room, RoomState, SessionMeta, SessionTokens,
 };
@@ -17,6 +22,10 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{mpsc, Mutex, OnceCell, RwLock};
 
+/// Matrix caps events at 64 KiB; markdown replies carry both the source and
+/// the rendered HTML, so keep the source well under half of that.
+const MATRIX_MAX_MESSAGE_BYTES: usize = 24_000;
+
 /// Matrix channel for Matrix Client-Server API.
 /// Uses matrix-sdk for reliable sync and encrypted-room decryption.
 #[derive(Clone)]
@@ -31,6 +40,8 @@ pub struct MatrixChannel {
     resolved_room_id_cache: Arc<RwLock<Option<String>>>,
     sdk_client: Arc<OnceCell<MatrixSdkClient>>,
     http_client: Client,
+    stream_mode: StreamMode,
+    draft_throttle: Arc<DraftThrottle>,
 }
 
 impl std::fmt::Debug for MatrixChannel {
@@ -163,7 +174,63 @@ impl MatrixChannel {
             resolved_room_id_cache: Arc::new(RwLock::new(None)),
             sdk_client: Arc::new(OnceCell::new()),
             http_client: Client::new(),
+            stream_mode: StreamMode::Off,
+            draft_throttle: Arc::new(DraftThrottle::new(1000)),
+        }
+    }
+
+    /// Configure streaming mode for progressive draft updates.
+    pub fn with_streaming(
+        mut self,
+        stream_mode: StreamMode,
+        draft_update_interval_ms: u64,
+    ) -> Self {
+        self.stream_mode = stream_mode;
+        self.draft_throttle = Arc::new(DraftThrottle::new(draft_update_interval_ms));
+        self
+    }
+
+    /// The configured room, which the bot must have joined.
+    async fn joined_room(&self) -> anyhow::Result<Room> {
+        let client = self.matrix_client().await?;
+        let target_room_id = self.target_room_id().await?;
+        let target_room: OwnedRoomId = target_room_id.parse()?;
+
+        let mut room = client.get_room(&target_room);
+        if room.is_none() {
+            let _ = client.sync_once(SyncSettings::new()).await;
+            room = client.get_room(&target_room);
+        }
+
+        let Some(room) = room else {
+            anyhow::bail!("Matrix room '{}' not found in joined rooms", target_room_id);
+        };
+
+        if room.state() != RoomState::Joined {
+            anyhow::bail!("Matrix room '{}' is not in joined state", target_room_id);
         }
+
+        Ok(room)
+    }
+
+    /// Replace an event the bot sent earlier with `content` (an `m.replace` edit).
+    async fn edit_event(
+        &self,
+        event_id: &str,
+        content: RoomMessageEventContent,
+    ) -> anyhow::Result<()> {
+        let event_id: OwnedEventId = event_id.parse()?;
+        let room = self.joined_room().await?;
+        room.send(content.make_replacement(ReplacementMetadata::new(event_id, None)))
+            .await?;
+        Ok(())
+    }
+
+    async fn redact_event(&self, event_id: &str) -> anyhow::Result<()> {
+        let event_id: OwnedEventId = event_id.parse()?;
+        let room = self.joined_room().await?;
+        room.redact(&event_id, None, None).await?;
+        Ok(())
     }
 
     fn encode_path_segment(value: &str) -> String {
@@ -529,33 +596,26 @@ impl Channel for MatrixChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        use super::attachment::{parse_attachment_markers, is_local_path};
-
-        let client = self.matrix_client().await?;
-        let target_room_id = self.target_room_id().await?;
-        let target_room: OwnedRoomId = target_room_id.parse()?;
-
-        let mut room = client.get_room(&target_room);
-        if room.is_none() {
-            let _ = client.sync_once(SyncSettings::new()).await;
-            room = client.get_room(&target_room);
-        }
+        self.send_with_id(message).await.map(|_| ())
+    }
 
-        let Some(room) = room else {
-            anyhow::bail!("Matrix room '{}' not found in joined rooms", target_room_id);
-        };
+    async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        use super::attachment::{parse_attachment_markers, is_local_path};
 
-        if room.state() != RoomState::Joined {
-            anyhow::bail!("Matrix room '{}' is not in joined state", target_room_id);
-        }
+        let room = self.joined_room().await?;
 
         let content = super::strip_tool_call_tags(&message.content);
         let (text, attachments) = parse_attachment_markers(&content);
+        let mut first_event_id = None;
 
-        // Send text message if present
+        // Send text message if present, split to fit the event size limit
         if !text.is_empty() || attachments.is_empty() {
-            room.send(RoomMessageEventContent::text_markdown(&text))
-                .await?;
+            for chunk in split_message_bytes(&text, MATRIX_MAX_MESSAGE_BYTES) {
+                let response = room
+                    .send(RoomMessageEventContent::text_markdown(&chunk))
+                    .await?;
+                first_event_id.get_or_insert_with(|| response.event_id.to_string());
+            }
         }
 
         // Send attachments
@@ -566,29 +626,136 @@ impl Channel for MatrixChannel {
                     let mime = mime_guess::from_path(path).first_or_octet_stream();
                     let data = tokio::fs::read(path).await?;
                     
-                    room.send_attachment(
-                        path.file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or("file"),
-                        &mime,
-                        data,
-                        Default::default(),
-                    )
-                    .await?;
+                    // Matrix clients read the caption (event body) as the
+                    // image description.
+                    let config = AttachmentConfig::new().caption(
+                        attachment
+                            .alt
+                            .as_ref()
+                            .map(|alt| TextMessageEventContent::plain(alt.clone())),
+                    );
+                    let response = room
+                        .send_attachment(
+                            path.file_name()
+                                .and_then(|n| n.to_str())
+                                .unwrap_or("file"),
+                            &mime,
+                            data,
+                            config,
+                        )
+                        .await?;
+                    first_event_id.get_or_insert_with(|| response.event_id.to_string());
                 } else {
                     tracing::warn!("Matrix: file not found: {}", attachment.target);
                 }
             } else {
                 // For URLs, send as text with link
                 let link_msg = format!("{}: {}", attachment.kind.marker_name(), attachment.target);
-                room.send(RoomMessageEventContent::text_plain(&link_msg))
+                let response = room
+                    .send(RoomMessageEventContent::text_plain(&link_msg))
                     .await?;
+                first_event_id.get_or_insert_with(|| response.event_id.to_string());
             }
         }
 
+        Ok(first_event_id)
+    }
+
+    async fn pin_message(&self, _channel_id: &str, message_id: &str) -> anyhow::Result<()> {
+        // Pinning rewrites the room's `m.room.pinned_events` state, which
+        // needs the matching power level.
+        let event_id: OwnedEventId = message_id.parse()?;
+        self.joined_room().await?.pin_event(&event_id).await?;
         Ok(())
     }
 
+    async fn start_typing(&self, _recipient: &str) -> anyhow::Result<()> {
+        // The SDK only re-sends the notice when the previous one is about to
+        // expire, so calling this on every refresh tick is cheap.
+        self.joined_room().await?.typing_notice(true).await?;
+        Ok(())
+    }
+
+    async fn stop_typing(&self, _recipient: &str) -> anyhow::Result<()> {
+        self.joined_room().await?.typing_notice(false).await?;
+        Ok(())
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        if self.stream_mode == StreamMode::Off {
+            return Ok(None);
+        }
+
+        let room = self.joined_room().await?;
+        let initial_text = if message.content.is_empty() {
+            "..."
+        } else {
+            message.content.as_str()
+        };
+        let response = room
+            .send(RoomMessageEventContent::text_plain(initial_text))
+            .await?;
+        let event_id = response.event_id.to_string();
+        self.draft_throttle.mark(&event_id);
+        Ok(Some(event_id))
+    }
+
+    async fn update_draft(
+        &self,
+        _recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        if !self.draft_throttle.ready(message_id) {
+            return Ok(());
+        }
+
+        match self
+            .edit_event(message_id, RoomMessageEventContent::text_plain(text))
+            .await
+        {
+            Ok(()) => self.draft_throttle.mark(message_id),
+            Err(e) => tracing::debug!("Matrix draft edit failed: {e}"),
+        }
+        Ok(())
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        use super::attachment::parse_attachment_markers;
+
+        self.draft_throttle.clear(message_id);
+        let content = super::strip_tool_call_tags(text);
+        let (cleaned, attachments) = parse_attachment_markers(&content);
+
+        // Edits cannot carry uploads, and long replies need several events:
+        // replace the draft with a regular send.
+        if !attachments.is_empty()
+            || split_message_bytes(&cleaned, MATRIX_MAX_MESSAGE_BYTES).len() > 1
+        {
+            if let Err(e) = self.redact_event(message_id).await {
+                tracing::debug!("Matrix draft redaction failed: {e}");
+            }
+            return self.send(&SendMessage::new(content, recipient)).await;
+        }
+
+        self.edit_event(message_id, RoomMessageEventContent::text_markdown(&cleaned))
+            .await
+    }
+
+    async fn cancel_draft(&self, _recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.draft_throttle.clear(message_id);
+        self.redact_event(message_id).await
+    }
+
     async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let target_room_id = self.target_room_id().await?;
         self.ensure_room_supported(&target_room_id).await?;
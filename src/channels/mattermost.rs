@@ -1,7 +1,12 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::attachment::{is_local_path, parse_attachment_markers, Attachment};
+use super::draft::{truncate_chars, DraftThrottle};
+use super::split::split_message;
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
+use crate::config::StreamMode;
 use anyhow::{bail, Result};
 use async_trait::async_trait;
 use parking_lot::Mutex;
+use std::collections::BTreeMap;
 
 /// Mattermost channel — polls channel posts via REST API v4.
 /// Mattermost is API-compatible with many Slack patterns but uses a dedicated v4 structure.
@@ -15,8 +20,35 @@ pub struct MattermostChannel {
     thread_replies: bool,
     /// When true, only respond to messages that @-mention the bot.
     mention_only: bool,
+    /// Command prefix that triggers a reply (see [`super::trigger`]).
+    trigger_prefix: Option<String>,
+    /// Token Mattermost sends with custom slash command requests.
+    command_token: Option<String>,
     /// Handle for the background typing-indicator loop (aborted on stop_typing).
     typing_handle: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    stream_mode: StreamMode,
+    draft_throttle: DraftThrottle,
+}
+
+/// Mattermost's default maximum post length.
+const MATTERMOST_MAX_POST_LENGTH: usize = 16383;
+/// Mattermost attaches at most this many files to one post.
+const MATTERMOST_MAX_FILES_PER_POST: usize = 10;
+/// Files larger than this are not uploaded (half the server default).
+const MATTERMOST_MAX_UPLOAD_BYTES: u64 = 50 * 1024 * 1024;
+
+/// A custom slash command invocation posted to the gateway's
+/// `/mattermost/commands`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MattermostSlashCommand {
+    /// The command as typed, e.g. `/ask`.
+    pub command: String,
+    /// Everything after the command.
+    pub text: String,
+    pub user_id: String,
+    pub channel_id: String,
+    /// Unique per invocation.
+    pub trigger_id: String,
 }
 
 impl MattermostChannel {
@@ -37,8 +69,133 @@ impl MattermostChannel {
             allowed_users,
             thread_replies,
             mention_only,
+            trigger_prefix: None,
+            command_token: None,
             typing_handle: Mutex::new(None),
+            stream_mode: StreamMode::Off,
+            draft_throttle: DraftThrottle::new(1000),
+        }
+    }
+
+    /// Answer posts that start with `prefix`. Combined with `mention_only`,
+    /// either a mention or the prefix triggers a reply.
+    pub fn with_trigger_prefix(mut self, prefix: Option<String>) -> Self {
+        self.trigger_prefix = super::trigger::normalize_prefix(prefix);
+        self
+    }
+
+    /// Accept slash commands that carry the command's token.
+    pub fn with_command_token(mut self, token: Option<String>) -> Self {
+        self.command_token = token
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty());
+        self
+    }
+
+    /// Check a slash command request's token. Without a configured token
+    /// every request is rejected.
+    pub fn verify_command_token(&self, token: &str) -> bool {
+        self.command_token
+            .as_deref()
+            .is_some_and(|expected| crate::security::pairing::constant_time_eq(expected, token))
+    }
+
+    /// Parse a slash command form body. Returns `None` for malformed
+    /// requests, for users outside `allowed_users` and for channels other
+    /// than the configured one.
+    pub fn slash_command_from_form(
+        &self,
+        form: &BTreeMap<String, String>,
+    ) -> Option<MattermostSlashCommand> {
+        let field = |name: &str| {
+            form.get(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let command = MattermostSlashCommand {
+            command: field("command")?,
+            text: field("text").unwrap_or_default(),
+            user_id: field("user_id")?,
+            channel_id: field("channel_id")?,
+            trigger_id: field("trigger_id").unwrap_or_default(),
+        };
+
+        if !self.is_user_allowed(&command.user_id) {
+            tracing::warn!(
+                "Mattermost: ignoring slash command from unauthorized user: {}",
+                command.user_id
+            );
+            return None;
+        }
+        if self
+            .channel_id
+            .as_deref()
+            .is_some_and(|scoped| scoped != command.channel_id)
+        {
+            return None;
+        }
+        Some(command)
+    }
+
+    /// Post the reply to a slash command in the channel it was used in.
+    pub async fn respond_to_command(
+        &self,
+        command: &MattermostSlashCommand,
+        reply: &str,
+    ) -> Result<()> {
+        self.send(&SendMessage::new(reply, &command.channel_id))
+            .await
+    }
+
+    /// Configure streaming mode for progressive draft updates.
+    pub fn with_streaming(
+        mut self,
+        stream_mode: StreamMode,
+        draft_update_interval_ms: u64,
+    ) -> Self {
+        self.stream_mode = stream_mode;
+        self.draft_throttle = DraftThrottle::new(draft_update_interval_ms);
+        self
+    }
+
+    /// Replace the message of a post the bot created earlier.
+    async fn patch_post(&self, post_id: &str, message: &str) -> Result<()> {
+        let resp = self
+            .http_client()
+            .put(format!("{}/api/v4/posts/{post_id}/patch", self.base_url))
+            .bearer_auth(&self.bot_token)
+            .json(&serde_json::json!({ "message": message }))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            bail!("Mattermost post patch failed ({status}): {body}");
+        }
+        Ok(())
+    }
+
+    async fn delete_post(&self, post_id: &str) -> Result<()> {
+        let resp = self
+            .http_client()
+            .delete(format!("{}/api/v4/posts/{post_id}", self.base_url))
+            .bearer_auth(&self.bot_token)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            bail!("Mattermost post delete failed ({status}): {body}");
         }
+        Ok(())
     }
 
     fn http_client(&self) -> reqwest::Client {
@@ -82,29 +239,84 @@ impl MattermostChannel {
         (id, username)
     }
 
-    async fn upload_file(
+    async fn create_post(
         &self,
-        file_path: &str,
         channel_id: &str,
         root_id: Option<&str>,
+        message: &str,
+        file_ids: &[String],
     ) -> Result<()> {
-        let path = std::path::Path::new(file_path);
-        if !path.exists() {
-            tracing::warn!("Mattermost: file not found: {}", file_path);
-            return Ok(());
+        let mut body = serde_json::json!({
+            "channel_id": channel_id,
+            "message": message,
+        });
+        if let Some(root) = root_id {
+            body["root_id"] = serde_json::json!(root);
+        }
+        if !file_ids.is_empty() {
+            body["file_ids"] = serde_json::json!(file_ids);
+        }
+
+        let resp = self
+            .http_client()
+            .post(format!("{}/api/v4/posts", self.base_url))
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            bail!("Mattermost post failed ({status}): {body}");
         }
+        Ok(())
+    }
+
+    /// Read a local attachment or download a remote one.
+    async fn attachment_bytes(&self, attachment: &Attachment) -> Result<(Vec<u8>, String)> {
+        let target = attachment.target.as_str();
+        if is_local_path(target) {
+            let path = std::path::Path::new(target);
+            let size = tokio::fs::metadata(path).await?.len();
+            if size > MATTERMOST_MAX_UPLOAD_BYTES {
+                bail!("{target} exceeds {MATTERMOST_MAX_UPLOAD_BYTES} bytes");
+            }
+            let filename = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("file")
+                .to_string();
+            return Ok((tokio::fs::read(path).await?, filename));
+        }
+
+        let resp = self.http_client().get(target).send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            bail!("download of {target} failed ({status})");
+        }
+        if resp
+            .content_length()
+            .is_some_and(|len| len > MATTERMOST_MAX_UPLOAD_BYTES)
+        {
+            bail!("{target} exceeds {MATTERMOST_MAX_UPLOAD_BYTES} bytes");
+        }
+        let bytes = resp.bytes().await?;
+        Ok((bytes.to_vec(), remote_file_name(target)))
+    }
 
-        let file_bytes = tokio::fs::read(path).await?;
-        let filename = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("file");
+    /// Upload an attachment to `channel_id` and return its file ID.
+    async fn upload_file(&self, attachment: &Attachment, channel_id: &str) -> Result<String> {
+        let (file_bytes, filename) = self.attachment_bytes(attachment).await?;
 
         let form = reqwest::multipart::Form::new()
             .text("channel_id", channel_id.to_string())
             .part(
                 "files",
-                reqwest::multipart::Part::bytes(file_bytes).file_name(filename.to_string()),
+                reqwest::multipart::Part::bytes(file_bytes).file_name(filename),
             );
 
         let resp = self
@@ -118,36 +330,50 @@ impl MattermostChannel {
         let status = resp.status();
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
-            tracing::warn!("Mattermost file upload failed ({status}): {body}");
-            return Ok(());
+            bail!("Mattermost file upload failed ({status}): {body}");
         }
 
-        // Get file_id from response and attach to a post
         let upload_resp: serde_json::Value = resp.json().await?;
-        if let Some(file_infos) = upload_resp.get("file_infos").and_then(|v| v.as_array()) {
-            if let Some(file_id) = file_infos.first().and_then(|f| f.get("id")).and_then(|id| id.as_str()) {
-                let mut post_body = serde_json::json!({
-                    "channel_id": channel_id,
-                    "message": "",
-                    "file_ids": [file_id]
-                });
-                if let Some(root) = root_id {
-                    post_body.as_object_mut().unwrap().insert(
-                        "root_id".to_string(),
-                        serde_json::Value::String(root.to_string()),
-                    );
-                }
-                self.http_client()
-                    .post(format!("{}/api/v4/posts", self.base_url))
-                    .bearer_auth(&self.bot_token)
-                    .json(&post_body)
-                    .send()
-                    .await?;
-            }
-        }
+        upload_resp
+            .get("file_infos")
+            .and_then(|v| v.as_array())
+            .and_then(|infos| infos.first())
+            .and_then(|info| info.get("id"))
+            .and_then(|id| id.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Mattermost file upload returned no file ID"))
+    }
+}
 
-        Ok(())
+/// File name for a downloaded attachment: the last URL path segment.
+fn remote_file_name(url: &str) -> String {
+    let url = url.split(['?', '#']).next().unwrap_or(url);
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme
+        .split_once('/')
+        .and_then(|(_, path)| path.trim_end_matches('/').rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("file")
+        .to_string()
+}
+
+/// Split a reply into posts: the text in chunks, with the uploaded files on
+/// the last chunk and any beyond the per-post limit in file-only posts.
+fn plan_posts(text: &str, file_ids: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut posts: Vec<(String, Vec<String>)> = if text.is_empty() {
+        Vec::new()
+    } else {
+        split_message(text, MATTERMOST_MAX_POST_LENGTH)
+            .into_iter()
+            .map(|chunk| (chunk, Vec::new()))
+            .collect()
+    };
+    let mut batches = file_ids.chunks(MATTERMOST_MAX_FILES_PER_POST);
+    if let Some(last) = posts.last_mut() {
+        last.1 = batches.next().map(<[String]>::to_vec).unwrap_or_default();
     }
+    posts.extend(batches.map(|batch| (String::new(), batch.to_vec())));
+    posts
 }
 
 #[async_trait]
@@ -157,8 +383,6 @@ impl Channel for MattermostChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> Result<()> {
-        use super::attachment::{parse_attachment_markers, is_local_path};
-
         let content = super::strip_tool_call_tags(&message.content);
         let (text, attachments) = parse_attachment_markers(&content);
 
@@ -168,67 +392,137 @@ impl Channel for MattermostChannel {
             (message.recipient.as_str(), None)
         };
 
-        // Send text message if present
-        if !text.is_empty() || attachments.is_empty() {
-            let mut body_map = serde_json::json!({
-                "channel_id": channel_id,
-                "message": if text.is_empty() { format!("{} attachment(s)", attachments.len()) } else { text }
-            });
-
-            if let Some(root) = root_id {
-                body_map.as_object_mut().unwrap().insert(
-                    "root_id".to_string(),
-                    serde_json::Value::String(root.to_string()),
-                );
+        // Upload attachments first so they can ride on the reply post.
+        // Remote files that cannot be fetched are linked instead.
+        let mut file_ids = Vec::new();
+        let mut lines = vec![text];
+        for attachment in &attachments {
+            match self.upload_file(attachment, channel_id).await {
+                Ok(file_id) => file_ids.push(file_id),
+                Err(e) if is_local_path(&attachment.target) => {
+                    tracing::warn!("Mattermost: could not upload {}: {e}", attachment.target);
+                }
+                Err(e) => {
+                    tracing::warn!("Mattermost: could not attach {}: {e}", attachment.target);
+                    lines.push(format!(
+                        "{}: {}",
+                        attachment.kind.marker_name(),
+                        attachment.target
+                    ));
+                }
             }
+        }
+        let text = lines
+            .into_iter()
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if text.is_empty() && file_ids.is_empty() && !attachments.is_empty() {
+            return Ok(());
+        }
 
-            let resp = self
-                .http_client()
-                .post(format!("{}/api/v4/posts", self.base_url))
-                .bearer_auth(&self.bot_token)
-                .json(&body_map)
-                .send()
+        let mut posts = plan_posts(&text, &file_ids);
+        if posts.is_empty() {
+            posts.push((String::new(), Vec::new()));
+        }
+        for (message, files) in posts {
+            self.create_post(channel_id, root_id, &message, &files)
                 .await?;
+        }
 
-            let status = resp.status();
-            if !status.is_success() {
-                let body = resp
-                    .text()
-                    .await
-                    .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
-                bail!("Mattermost post failed ({status}): {body}");
-            }
+        Ok(())
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_edit: self.supports_draft_updates(),
+            max_message_len: Some(MATTERMOST_MAX_POST_LENGTH),
+            ..ChannelCapabilities::default()
         }
+    }
 
-        // Upload attachments
-        for attachment in &attachments {
-            if is_local_path(&attachment.target) {
-                self.upload_file(&attachment.target, channel_id, root_id).await?;
-            } else {
-                // For URLs, send as text with link
-                let link_msg = format!("{}: {}", attachment.kind.marker_name(), attachment.target);
-                let mut link_body = serde_json::json!({
-                    "channel_id": channel_id,
-                    "message": link_msg
-                });
-                if let Some(root) = root_id {
-                    link_body.as_object_mut().unwrap().insert(
-                        "root_id".to_string(),
-                        serde_json::Value::String(root.to_string()),
-                    );
-                }
-                self.http_client()
-                    .post(format!("{}/api/v4/posts", self.base_url))
-                    .bearer_auth(&self.bot_token)
-                    .json(&link_body)
-                    .send()
-                    .await?;
-            }
+    async fn send_draft(&self, message: &SendMessage) -> Result<Option<String>> {
+        if self.stream_mode == StreamMode::Off {
+            return Ok(None);
+        }
+
+        let (channel_id, root_id) = match message.recipient.split_once(':') {
+            Some((c, r)) => (c, Some(r)),
+            None => (message.recipient.as_str(), None),
+        };
+        let mut body = serde_json::json!({
+            "channel_id": channel_id,
+            "message": if message.content.is_empty() { "..." } else { message.content.as_str() },
+        });
+        if let Some(root) = root_id {
+            body["root_id"] = serde_json::Value::String(root.to_string());
         }
 
+        let resp = self
+            .http_client()
+            .post(format!("{}/api/v4/posts", self.base_url))
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            bail!("Mattermost post (draft) failed ({status}): {body}");
+        }
+
+        let post: serde_json::Value = resp.json().await?;
+        let post_id = post.get("id").and_then(|i| i.as_str()).map(str::to_string);
+        if let Some(ref id) = post_id {
+            self.draft_throttle.mark(id);
+        }
+        Ok(post_id)
+    }
+
+    async fn update_draft(&self, _recipient: &str, message_id: &str, text: &str) -> Result<()> {
+        if !self.draft_throttle.ready(message_id) {
+            return Ok(());
+        }
+
+        let display_text = truncate_chars(text, MATTERMOST_MAX_POST_LENGTH);
+        match self.patch_post(message_id, display_text).await {
+            Ok(()) => self.draft_throttle.mark(message_id),
+            Err(e) => tracing::debug!("{e}"),
+        }
         Ok(())
     }
 
+    async fn finalize_draft(&self, recipient: &str, message_id: &str, text: &str) -> Result<()> {
+        self.draft_throttle.clear(message_id);
+        let content = super::strip_tool_call_tags(text);
+        let (cleaned, attachments) = parse_attachment_markers(&content);
+
+        // Patches cannot add files, and long replies need several posts:
+        // replace the draft with a regular send.
+        if !attachments.is_empty() || split_message(&cleaned, MATTERMOST_MAX_POST_LENGTH).len() > 1
+        {
+            if let Err(e) = self.delete_post(message_id).await {
+                tracing::debug!("{e}");
+            }
+            return self.send(&SendMessage::new(content, recipient)).await;
+        }
+
+        self.patch_post(message_id, &cleaned).await
+    }
+
+    async fn cancel_draft(&self, _recipient: &str, message_id: &str) -> Result<()> {
+        self.draft_throttle.clear(message_id);
+        self.delete_post(message_id).await
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
         let channel_id = self
             .channel_id
@@ -392,10 +686,19 @@ impl MattermostChannel {
             return None;
         }
 
-        // mention_only filtering: skip messages that don't @-mention the bot.
-        let content = if self.mention_only {
+        // Trigger filtering: with a prefix or mention_only configured, skip
+        // posts that neither start with the prefix nor @-mention the bot.
+        let prefixed = self
+            .trigger_prefix
+            .as_deref()
+            .and_then(|prefix| super::trigger::strip_trigger_prefix(text, prefix));
+        let content = if let Some(prefixed) = prefixed {
+            prefixed
+        } else if self.mention_only {
             let normalized = normalize_mattermost_content(text, bot_user_id, bot_username, post);
             normalized?
+        } else if self.trigger_prefix.is_some() {
+            return None;
         } else {
             text.to_string()
         };
@@ -563,6 +866,92 @@ mod tests {
             false,
         )
     }
+    #[tokio::test]
+    async fn drafts_follow_stream_mode_and_throttle() {
+        let off = make_channel(vec![], true);
+        assert!(!off.supports_draft_updates());
+        assert_eq!(
+            off.send_draft(&SendMessage::new("draft", "chan"))
+                .await
+                .unwrap(),
+            None
+        );
+
+        let partial = make_channel(vec![], true).with_streaming(StreamMode::Partial, 60_000);
+        assert!(partial.supports_draft_updates());
+        partial.draft_throttle.mark("post1");
+        assert!(partial
+            .update_draft("chan", "post1", "more text")
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn uploaded_files_ride_on_the_last_post() {
+        let ids: Vec<String> = (0..12).map(|i| format!("f{i}")).collect();
+
+        let posts = plan_posts("hello", &ids[..2]);
+        assert_eq!(posts, vec![("hello".to_string(), ids[..2].to_vec())]);
+
+        let posts = plan_posts("", &ids);
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0], (String::new(), ids[..10].to_vec()));
+        assert_eq!(posts[1], (String::new(), ids[10..].to_vec()));
+
+        let long = "word ".repeat(MATTERMOST_MAX_POST_LENGTH / 4);
+        let posts = plan_posts(long.trim(), &ids[..1]);
+        assert_eq!(posts.len(), 2);
+        assert!(posts[0].1.is_empty());
+        assert_eq!(posts[1].1, ids[..1].to_vec());
+
+        assert!(plan_posts("", &[]).is_empty());
+    }
+
+    #[test]
+    fn remote_file_name_uses_last_path_segment() {
+        assert_eq!(
+            remote_file_name("https://example.com/files/report.pdf?sig=abc"),
+            "report.pdf"
+        );
+        assert_eq!(remote_file_name("https://example.com/"), "file");
+    }
+
+    #[test]
+    fn slash_commands_need_token_and_allowed_user() {
+        let form: BTreeMap<String, String> = [
+            ("command", "/ask"),
+            ("text", " status? "),
+            ("user_id", "u1"),
+            ("channel_id", "c1"),
+            ("trigger_id", "t1"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let ch = make_channel(vec!["u1".into()], true).with_command_token(Some(" tok ".into()));
+        assert!(ch.verify_command_token("tok"));
+        assert!(!ch.verify_command_token("other"));
+        assert!(!make_channel(vec![], true).verify_command_token(""));
+
+        let command = ch.slash_command_from_form(&form).unwrap();
+        assert_eq!(command.command, "/ask");
+        assert_eq!(command.text, "status?");
+        assert_eq!(command.channel_id, "c1");
+
+        assert!(make_channel(vec!["u2".into()], true)
+            .slash_command_from_form(&form)
+            .is_none());
+        let scoped = MattermostChannel::new(
+            "url".into(),
+            "token".into(),
+            Some("c2".into()),
+            vec!["*".into()],
+            true,
+            false,
+        );
+        assert!(scoped.slash_command_from_form(&form).is_none());
+    }
 
     // Helper: create a channel with mention_only=true.
     fn make_mention_only_channel() -> MattermostChannel {
@@ -713,6 +1102,39 @@ mod tests {
         assert_eq!(msg.reply_target, "chan789:root789"); // Stays in existing thread
     }
 
+    #[test]
+    fn trigger_prefix_or_mention_triggers_reply() {
+        let ch = make_mention_only_channel().with_trigger_prefix(Some("!claw".into()));
+        let post = |message: &str| {
+            json!({
+                "id": "post1",
+                "user_id": "user1",
+                "message": message,
+                "create_at": 1_600_000_000_000_i64,
+                "root_id": ""
+            })
+        };
+        let parse = |message: &str| {
+            ch.parse_mattermost_post(
+                &post(message),
+                "bot123",
+                "mybot",
+                1_500_000_000_000_i64,
+                "chan1",
+            )
+        };
+
+        assert_eq!(
+            parse("!claw deploy status").unwrap().content,
+            "deploy status"
+        );
+        assert_eq!(
+            parse("@mybot deploy status").unwrap().content,
+            "deploy status"
+        );
+        assert!(parse("deploy status").is_none());
+    }
+
     // ── mention_only tests ────────────────────────────────────────
 
     #[test]
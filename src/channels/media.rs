@@ -0,0 +1,715 @@
+//! Outbound media preprocessing.
+//!
+//! Channels that can only upload files (Signal, Matrix, email) first get
+//! remote attachment targets downloaded to temporary files, checked for
+//! size and content type. Then every local file named by an attachment
+//! marker is checked against the target channel's upload limits. Images that
+//! are too large (in bytes or pixels) are scaled down and re-encoded as JPEG,
+//! WebP images go out as PNG where the channel does not display WebP, and
+//! oversized videos are re-encoded with ffmpeg. WhatsApp additionally needs
+//! 512×512 WebP stickers and MP4 instead of GIF. Markers are rewritten to
+//! point at the processed copy; a file that still cannot fit is replaced by a
+//! short note so the rest of the reply is delivered instead of failing the
+//! whole send.
+
+use super::attachment::{download_remote, is_local_path, AttachmentKind};
+use super::audio_tools::ffmpeg_convert;
+use anyhow::{bail, Context, Result};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const MB: u64 = 1024 * 1024;
+/// Processed copies older than this are removed on the next reply.
+const PREPARED_MEDIA_RETENTION: Duration = Duration::from_secs(3600);
+/// JPEG qualities tried in order before an image is scaled down further.
+const JPEG_QUALITIES: [u8; 3] = [85, 70, 55];
+/// Images are never scaled below this side while trying to fit a limit.
+const MIN_IMAGE_SIDE: u32 = 64;
+/// WhatsApp stickers are 512×512 WebP images of at most 100 KB.
+const STICKER_SIDE: u32 = 512;
+const STICKER_MAX_BYTES: u64 = 100 * 1024;
+/// Largest remote attachment downloaded for upload.
+const MAX_REMOTE_BYTES: u64 = 25 * MB;
+
+/// Upload limits of a delivery channel, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaLimits {
+    /// Largest file the channel accepts.
+    pub file: u64,
+    /// Largest image sent as a photo.
+    pub image: u64,
+    /// Largest video or audio file.
+    pub video: u64,
+    /// Longest image side the channel accepts; larger images are scaled down.
+    pub image_side: Option<u32>,
+    /// Whether WebP images display inline; otherwise they are sent as PNG.
+    pub webp_images: bool,
+    /// Whether stickers must be 512×512 WebP and animations MP4 (WhatsApp).
+    pub strict_formats: bool,
+}
+
+/// Documented limits of channels with known media restrictions.
+///
+/// - Telegram: 10 MB photos (width plus height at most 10000 px), 50 MB
+///   other uploads through the Bot API.
+/// - Discord: 10 MB per file without a boosted server.
+/// - WhatsApp (Cloud API): 5 MB JPEG/PNG images, 16 MB video and audio,
+///   100 MB documents.
+/// - Slack and Mattermost: 1 GB and the Mattermost server default of 100 MB.
+pub fn limits(channel: &str) -> Option<MediaLimits> {
+    match channel {
+        "telegram" => Some(MediaLimits {
+            file: 50 * MB,
+            image: 10 * MB,
+            video: 50 * MB,
+            image_side: Some(5000),
+            webp_images: false,
+            strict_formats: false,
+        }),
+        "discord" => Some(MediaLimits {
+            file: 10 * MB,
+            image: 10 * MB,
+            video: 10 * MB,
+            image_side: None,
+            webp_images: true,
+            strict_formats: false,
+        }),
+        "whatsapp" => Some(MediaLimits {
+            file: 100 * MB,
+            image: 5 * MB,
+            video: 16 * MB,
+            image_side: None,
+            webp_images: false,
+            strict_formats: true,
+        }),
+        "slack" => Some(MediaLimits {
+            file: 1024 * MB,
+            image: 1024 * MB,
+            video: 1024 * MB,
+            image_side: None,
+            webp_images: true,
+            strict_formats: false,
+        }),
+        "mattermost" => Some(MediaLimits {
+            file: 100 * MB,
+            image: 100 * MB,
+            video: 100 * MB,
+            image_side: None,
+            webp_images: true,
+            strict_formats: false,
+        }),
+        _ => None,
+    }
+}
+
+/// Channels that can only attach files they upload themselves. Remote
+/// attachment targets are downloaded for them first.
+pub fn requires_local_files(channel: &str) -> bool {
+    matches!(channel, "signal" | "matrix" | "email")
+}
+
+/// An attachment marker and its place in the reply.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Marker {
+    pub(super) range: Range<usize>,
+    pub(super) kind: AttachmentKind,
+    pub(super) target: String,
+    pub(super) alt: Option<String>,
+}
+
+impl Marker {
+    fn render(&self, kind: &AttachmentKind, path: &Path) -> String {
+        match &self.alt {
+            Some(alt) => format!("[{}:{}|{alt}]", kind.marker_name(), path.display()),
+            None => format!("[{}:{}]", kind.marker_name(), path.display()),
+        }
+    }
+}
+
+/// Attachment markers in `content` whose target is a local path (`local`)
+/// or a URL.
+pub(super) fn markers(content: &str, local: bool) -> Vec<Marker> {
+    let mut found = Vec::new();
+    let mut cursor = 0;
+    while let Some(open_rel) = content[cursor..].find('[') {
+        let open = cursor + open_rel;
+        let Some(close_rel) = content[open..].find(']') else {
+            break;
+        };
+        let close = open + close_rel;
+        let marker = &content[open + 1..close];
+        if let Some((kind, target)) = marker.split_once(':') {
+            let (target, alt) = match target.split_once('|') {
+                Some((target, alt)) => (target.trim(), Some(alt.trim())),
+                None => (target.trim(), None),
+            };
+            if let Some(kind) = AttachmentKind::from_marker(kind) {
+                if !target.is_empty() && is_local_path(target) == local {
+                    found.push(Marker {
+                        range: open..close + 1,
+                        kind,
+                        target: target.to_string(),
+                        alt: alt.filter(|alt| !alt.is_empty()).map(str::to_string),
+                    });
+                }
+            }
+        }
+        cursor = close + 1;
+    }
+    found
+}
+
+/// What has to happen to a file before the channel accepts it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    /// Scale down and re-encode as JPEG until it fits the image limit.
+    ShrinkImage,
+    /// Re-encode a WebP image as PNG.
+    WebpToPng,
+    /// Fit into a 512×512 WebP sticker.
+    Sticker,
+    /// Re-encode a GIF as a silent MP4.
+    GifToMp4,
+    /// Re-encode a video at a lower resolution and bitrate.
+    ShrinkVideo,
+    /// Too large and not compressible: drop from the reply.
+    Skip,
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+fn is_raster_image(extension: &str) -> bool {
+    matches!(extension, "png" | "jpg" | "jpeg" | "webp" | "bmp")
+}
+
+/// Decide how to adapt a file of `size` bytes (and image `dimensions`, when
+/// known) to `limits`. `None` means it can be sent as is.
+fn plan(
+    limits: &MediaLimits,
+    kind: &AttachmentKind,
+    path: &Path,
+    size: u64,
+    dimensions: Option<(u32, u32)>,
+) -> Option<Step> {
+    let ext = extension(path);
+    match kind {
+        AttachmentKind::Sticker if limits.strict_formats => (ext != "webp"
+            || dimensions != Some((STICKER_SIDE, STICKER_SIDE))
+            || size > STICKER_MAX_BYTES)
+            .then_some(Step::Sticker),
+        AttachmentKind::Animation if limits.strict_formats && ext == "gif" => Some(Step::GifToMp4),
+        kind if kind.is_image_like() && is_raster_image(&ext) => {
+            let too_large = size > limits.image
+                || dimensions
+                    .is_some_and(|(w, h)| limits.image_side.is_some_and(|side| w.max(h) > side));
+            if too_large {
+                Some(Step::ShrinkImage)
+            } else if ext == "webp" && *kind == AttachmentKind::Image && !limits.webp_images {
+                Some(Step::WebpToPng)
+            } else {
+                None
+            }
+        }
+        AttachmentKind::Video | AttachmentKind::Animation if size > limits.video => {
+            Some(Step::ShrinkVideo)
+        }
+        AttachmentKind::Audio | AttachmentKind::Voice if size > limits.video => Some(Step::Skip),
+        _ if size > limits.file => Some(Step::Skip),
+        _ => None,
+    }
+}
+
+/// Encode `image` as JPEG, lowering quality and then size until it fits in
+/// `max_bytes`.
+fn shrink_image(image: &DynamicImage, max_bytes: u64, max_side: Option<u32>) -> Result<Vec<u8>> {
+    let mut image = match max_side {
+        Some(side) if image.width().max(image.height()) > side => image.thumbnail(side, side),
+        _ => image.clone(),
+    };
+    // JPEG has no alpha channel.
+    image = DynamicImage::ImageRgb8(image.to_rgb8());
+    loop {
+        for quality in JPEG_QUALITIES {
+            let mut buf = Vec::new();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buf, quality)
+                .encode_image(&image)?;
+            if buf.len() as u64 <= max_bytes {
+                return Ok(buf);
+            }
+        }
+        let (w, h) = (image.width() * 3 / 4, image.height() * 3 / 4);
+        if w.min(h) < MIN_IMAGE_SIDE {
+            bail!("image does not fit in {max_bytes} bytes");
+        }
+        image = image.thumbnail(w, h);
+    }
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>> {
+    let mut buf = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut buf, format)?;
+    Ok(buf.into_inner())
+}
+
+/// Center `image` on a transparent 512×512 canvas and encode it as WebP.
+fn to_sticker(image: &DynamicImage) -> Result<Vec<u8>> {
+    let scaled = image.thumbnail(STICKER_SIDE, STICKER_SIDE).to_rgba8();
+    let mut canvas = RgbaImage::new(STICKER_SIDE, STICKER_SIDE);
+    let x = i64::from((STICKER_SIDE - scaled.width()) / 2);
+    let y = i64::from((STICKER_SIDE - scaled.height()) / 2);
+    image::imageops::overlay(&mut canvas, &scaled, x, y);
+    let webp = encode(&DynamicImage::ImageRgba8(canvas), ImageFormat::WebP)?;
+    if webp.len() as u64 > STICKER_MAX_BYTES {
+        bail!("sticker is {} KB, over the 100 KB limit", webp.len() / 1024);
+    }
+    Ok(webp)
+}
+
+/// Run an image transformation off the async runtime.
+async fn transform_image(
+    bytes: Vec<u8>,
+    transform: impl FnOnce(&DynamicImage) -> Result<Vec<u8>> + Send + 'static,
+) -> Result<Vec<u8>> {
+    tokio::task::spawn_blocking(move || {
+        let image = image::load_from_memory(&bytes).context("Failed to decode image")?;
+        transform(&image)
+    })
+    .await?
+}
+
+/// Re-encode a video to H.264 at most 1280 px wide, then 854 px with a
+/// lower quality if the first pass is still too large.
+async fn shrink_video(bytes: &[u8], ext: &str, max_bytes: u64) -> Result<Vec<u8>> {
+    for (width, crf) in [(1280, "28"), (854, "34")] {
+        let scale = format!("scale='min({width},iw)':-2");
+        let video = ffmpeg_convert(
+            bytes,
+            ext,
+            "mp4",
+            &[
+                "-vf",
+                &scale,
+                "-c:v",
+                "libx264",
+                "-preset",
+                "veryfast",
+                "-crf",
+                crf,
+                "-c:a",
+                "aac",
+                "-b:a",
+                "96k",
+                "-movflags",
+                "+faststart",
+            ],
+        )
+        .await?;
+        if video.len() as u64 <= max_bytes {
+            return Ok(video);
+        }
+    }
+    bail!("video does not fit in {} MB", max_bytes / MB)
+}
+
+async fn gif_to_mp4(bytes: &[u8]) -> Result<Vec<u8>> {
+    ffmpeg_convert(
+        bytes,
+        "gif",
+        "mp4",
+        &[
+            "-movflags",
+            "+faststart",
+            "-pix_fmt",
+            "yuv420p",
+            "-vf",
+            "scale=trunc(iw/2)*2:trunc(ih/2)*2",
+            "-an",
+        ],
+    )
+    .await
+}
+
+/// Where [`prepare_attachments`] writes processed copies.
+pub(super) fn prepared_media_dir() -> PathBuf {
+    std::env::temp_dir().join("zeroclaw-media")
+}
+
+/// Write a processed copy where the channel can pick it up, removing copies
+/// left over from earlier replies.
+async fn save_prepared(data: &[u8], original: &Path, ext: &str) -> Result<PathBuf> {
+    let dir = prepared_media_dir();
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .context("Failed to create media directory")?;
+
+    if let Ok(mut entries) = tokio::fs::read_dir(&dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let expired = entry
+                .metadata()
+                .await
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > PREPARED_MEDIA_RETENTION);
+            if expired {
+                let _ = tokio::fs::remove_file(entry.path()).await;
+            }
+        }
+    }
+
+    // Keep the original name so the recipient sees a sensible file name.
+    let stem = original
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("attachment");
+    let sub_dir = dir.join(uuid::Uuid::new_v4().to_string());
+    tokio::fs::create_dir_all(&sub_dir)
+        .await
+        .context("Failed to create media directory")?;
+    let path = sub_dir.join(format!("{stem}.{ext}"));
+    tokio::fs::write(&path, data)
+        .await
+        .context("Failed to write processed media")?;
+    Ok(path)
+}
+
+/// Note left in the reply in place of a file the channel cannot take.
+fn skipped_note(path: &Path, size: u64, limit: u64, channel: &str) -> String {
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("attachment");
+    format!(
+        "({name} was not sent: {:.1} MB is over the {} MB {channel} limit)",
+        size as f64 / MB as f64,
+        limit / MB
+    )
+}
+
+/// Apply `step` to the marker's file and return the replacement marker text.
+async fn apply(
+    step: &Step,
+    marker: &Marker,
+    limits: &MediaLimits,
+    channel: &str,
+    size: u64,
+) -> Result<String> {
+    let path = Path::new(&marker.target);
+    let ext = extension(path);
+    if *step == Step::Skip {
+        let limit = match marker.kind {
+            AttachmentKind::Audio | AttachmentKind::Voice => limits.video,
+            _ => limits.file,
+        };
+        return Ok(skipped_note(path, size, limit, channel));
+    }
+
+    let bytes = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let (kind, data, new_ext) = match step {
+        Step::ShrinkImage => {
+            let (max_bytes, max_side) = (limits.image, limits.image_side);
+            let jpeg =
+                transform_image(bytes, move |image| shrink_image(image, max_bytes, max_side))
+                    .await?;
+            (marker.kind.clone(), jpeg, "jpg")
+        }
+        Step::WebpToPng => {
+            let max_bytes = limits.image;
+            let png = transform_image(bytes, move |image| {
+                let png = encode(image, ImageFormat::Png)?;
+                if png.len() as u64 <= max_bytes {
+                    Ok(png)
+                } else {
+                    shrink_image(image, max_bytes, None)
+                }
+            })
+            .await?;
+            let ext = if png.starts_with(b"\x89PNG") {
+                "png"
+            } else {
+                "jpg"
+            };
+            (AttachmentKind::Image, png, ext)
+        }
+        Step::Sticker => match transform_image(bytes, to_sticker).await {
+            Ok(webp) => (AttachmentKind::Sticker, webp, "webp"),
+            Err(e) => {
+                // Still deliver the picture, as a regular image.
+                tracing::debug!("Sending {} as an image: {e}", path.display());
+                return Ok(marker.render(&AttachmentKind::Image, path));
+            }
+        },
+        Step::GifToMp4 => (AttachmentKind::Animation, gif_to_mp4(&bytes).await?, "mp4"),
+        Step::ShrinkVideo => (
+            marker.kind.clone(),
+            shrink_video(&bytes, &ext, limits.video).await?,
+            "mp4",
+        ),
+        Step::Skip => unreachable!("handled above"),
+    };
+
+    let prepared = save_prepared(&data, path, new_ext).await?;
+    tracing::info!(
+        "Prepared {} for {channel}: {:?}, {} -> {} bytes",
+        path.display(),
+        step,
+        size,
+        data.len()
+    );
+    Ok(marker.render(&kind, &prepared))
+}
+
+/// Download remote attachments so the channel can upload them as files.
+/// Markers whose download fails are left as they are, and the channel sends
+/// them as links.
+async fn fetch_remote_attachments(channel: &str, content: &str) -> String {
+    let markers = markers(content, false);
+    if markers.is_empty() {
+        return content.to_string();
+    }
+
+    let client = crate::config::build_runtime_proxy_client("channel.attachments");
+    let mut result = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for marker in markers {
+        let fetched = async {
+            let remote =
+                download_remote(&client, &marker.target, &marker.kind, MAX_REMOTE_BYTES).await?;
+            let name = Path::new(&remote.file_name);
+            let ext = extension(name);
+            let ext = if ext.is_empty() { "bin" } else { ext.as_str() };
+            save_prepared(&remote.bytes, name, ext).await
+        };
+        match fetched.await {
+            Ok(path) => {
+                result.push_str(&content[cursor..marker.range.start]);
+                result.push_str(&marker.render(&marker.kind, &path));
+                cursor = marker.range.end;
+            }
+            Err(e) => tracing::warn!(
+                "Failed to download {} for {channel}; sending it as a link: {e:#}",
+                marker.target
+            ),
+        }
+    }
+    result.push_str(&content[cursor..]);
+    result
+}
+
+/// Prepare the attachments in `content` for delivery on `channel`: download
+/// remote files for channels that cannot send URLs, then adapt local files to
+/// the channel's media limits. Other channels get `content` unchanged.
+pub async fn prepare_attachments(channel: &str, content: &str) -> String {
+    let content = if requires_local_files(channel) {
+        fetch_remote_attachments(channel, content).await
+    } else {
+        content.to_string()
+    };
+    let Some(limits) = limits(channel) else {
+        return content;
+    };
+    let markers = markers(&content, true);
+    if markers.is_empty() {
+        return content;
+    }
+
+    let mut result = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for marker in markers {
+        let path = Path::new(&marker.target);
+        let Ok(meta) = tokio::fs::metadata(path).await else {
+            continue;
+        };
+        if !meta.is_file() {
+            continue;
+        }
+        let size = meta.len();
+        let dimensions = if marker.kind.is_image_like() {
+            image::image_dimensions(path).ok()
+        } else {
+            None
+        };
+        let Some(step) = plan(&limits, &marker.kind, path, size, dimensions) else {
+            continue;
+        };
+
+        let replacement = match apply(&step, &marker, &limits, channel, size).await {
+            Ok(replacement) => replacement,
+            Err(e) if size > limits.file => {
+                tracing::warn!("Failed to prepare {} for {channel}: {e:#}", marker.target);
+                skipped_note(path, size, limits.file, channel)
+            }
+            Err(e) => {
+                // Within the hard limit: let the channel try the original.
+                tracing::warn!("Failed to prepare {} for {channel}: {e:#}", marker.target);
+                continue;
+            }
+        };
+        result.push_str(&content[cursor..marker.range.start]);
+        result.push_str(&replacement);
+        cursor = marker.range.end;
+    }
+    result.push_str(&content[cursor..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise_image(width: u32, height: u32) -> DynamicImage {
+        let mut seed = 0x2545_f491_u32;
+        DynamicImage::ImageRgb8(image::RgbImage::from_fn(width, height, |_, _| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let [r, g, b, _] = seed.to_le_bytes();
+            image::Rgb([r, g, b])
+        }))
+    }
+
+    #[test]
+    fn markers_split_local_and_remote_targets() {
+        let content = "See [IMAGE:/tmp/a.webp|A chart] and [VIDEO:https://x.test/v.mp4] [note]";
+        let remote = markers(content, false);
+        assert_eq!(remote.len(), 1);
+        assert_eq!(remote[0].target, "https://x.test/v.mp4");
+
+        let markers = markers(content, true);
+
+        assert_eq!(markers.len(), 1);
+        assert_eq!(
+            &content[markers[0].range.clone()],
+            "[IMAGE:/tmp/a.webp|A chart]"
+        );
+        assert_eq!(
+            markers[0].render(&AttachmentKind::Image, Path::new("/tmp/a.png")),
+            "[IMAGE:/tmp/a.png|A chart]"
+        );
+    }
+
+    #[test]
+    fn plan_follows_channel_limits() {
+        let step = |channel: &str, kind: AttachmentKind, path: &str, size, dimensions| {
+            plan(
+                &limits(channel).unwrap(),
+                &kind,
+                Path::new(path),
+                size,
+                dimensions,
+            )
+        };
+        let image = AttachmentKind::Image;
+
+        assert_eq!(
+            step(
+                "telegram",
+                image.clone(),
+                "/tmp/a.webp",
+                MB,
+                Some((800, 600))
+            ),
+            Some(Step::WebpToPng)
+        );
+        assert_eq!(
+            step("discord", image.clone(), "/tmp/a.webp", MB, None),
+            None
+        );
+        assert_eq!(
+            step("telegram", image.clone(), "/tmp/a.png", 12 * MB, None),
+            Some(Step::ShrinkImage)
+        );
+        assert_eq!(
+            step("telegram", image, "/tmp/a.png", MB, Some((8000, 100))),
+            Some(Step::ShrinkImage)
+        );
+
+        let sticker = AttachmentKind::Sticker;
+        assert_eq!(
+            step("whatsapp", sticker.clone(), "/tmp/s.png", 1024, None),
+            Some(Step::Sticker)
+        );
+        assert_eq!(
+            step("whatsapp", sticker, "/tmp/s.webp", 1024, Some((512, 512))),
+            None
+        );
+        assert_eq!(
+            step(
+                "whatsapp",
+                AttachmentKind::Animation,
+                "/tmp/a.gif",
+                1024,
+                None
+            ),
+            Some(Step::GifToMp4)
+        );
+        assert_eq!(
+            step(
+                "whatsapp",
+                AttachmentKind::Video,
+                "/tmp/v.mp4",
+                20 * MB,
+                None
+            ),
+            Some(Step::ShrinkVideo)
+        );
+        assert_eq!(
+            step(
+                "telegram",
+                AttachmentKind::Document,
+                "/tmp/a.zip",
+                60 * MB,
+                None
+            ),
+            Some(Step::Skip)
+        );
+    }
+
+    #[test]
+    fn shrink_image_fits_size_and_side_limits() {
+        let jpeg = shrink_image(&noise_image(1200, 800), 200 * 1024, Some(1000)).unwrap();
+        assert!(jpeg.len() <= 200 * 1024);
+
+        let decoded = image::load_from_memory(&jpeg).unwrap();
+        assert!(decoded.width() <= 1000 && decoded.height() <= 1000);
+        assert!(shrink_image(&noise_image(100, 100), 10, None).is_err());
+    }
+
+    #[test]
+    fn stickers_are_512_square_webp() {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            300,
+            150,
+            image::Rgb([200, 40, 40]),
+        ));
+        let webp = to_sticker(&image).unwrap();
+
+        let decoded = image::load_from_memory_with_format(&webp, ImageFormat::WebP).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (512, 512));
+    }
+
+    #[tokio::test]
+    async fn oversized_documents_become_a_note() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.pdf");
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_len(11 * MB).unwrap();
+
+        let content = format!("Here you go [DOCUMENT:{}]", path.display());
+        assert_eq!(
+            prepare_attachments("discord", &content).await,
+            "Here you go (report.pdf was not sent: 11.0 MB is over the 10 MB discord limit)"
+        );
+        assert_eq!(prepare_attachments("slack", &content).await, content);
+        assert_eq!(prepare_attachments("irc", &content).await, content);
+    }
+}
@@ -0,0 +1,263 @@
+//! Short-lived links for local attachments.
+//!
+//! Some channels can only show media by URL: Google Chat cards, DingTalk
+//! robot markdown and Twilio MMS `MediaUrl`s. With
+//! `[channels_config.media_host]` configured, local attachment markers in
+//! replies to those channels are rewritten to gateway URLs of the form
+//! `<public_url>/media/<token>/<name>`. Tokens are random and expire after
+//! `ttl_secs`; the registry is process-wide so the gateway serving the
+//! files and the channel sending the reply share it.
+
+use super::attachment::AttachmentKind;
+use super::media::{markers, prepared_media_dir};
+use crate::config::MediaHostConfig;
+use crate::security::SecurityPolicy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Hosted files kept addressable; the oldest are dropped first.
+const MAX_HOSTED_FILES: usize = 1024;
+
+#[derive(Debug, Clone)]
+struct HostedFile {
+    path: PathBuf,
+    expires_at: Instant,
+}
+
+fn registry() -> &'static Mutex<HashMap<String, HostedFile>> {
+    static HOSTED: OnceLock<Mutex<HashMap<String, HostedFile>>> = OnceLock::new();
+    HOSTED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn insert(hosted: &mut HashMap<String, HostedFile>, path: PathBuf, ttl: Duration) -> String {
+    let now = Instant::now();
+    hosted.retain(|_, file| file.expires_at > now);
+    while hosted.len() >= MAX_HOSTED_FILES {
+        let Some(oldest) = hosted
+            .iter()
+            .min_by_key(|(_, file)| file.expires_at)
+            .map(|(token, _)| token.clone())
+        else {
+            break;
+        };
+        hosted.remove(&oldest);
+    }
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    hosted.insert(
+        token.clone(),
+        HostedFile {
+            path,
+            expires_at: now + ttl,
+        },
+    );
+    token
+}
+
+/// The file behind an unexpired `token`.
+pub fn lookup(token: &str) -> Option<PathBuf> {
+    let hosted = registry().lock();
+    hosted
+        .get(token)
+        .filter(|file| file.expires_at > Instant::now())
+        .map(|file| file.path.clone())
+}
+
+/// Canonical path of the local attachment `target` when it is a file the
+/// security policy lets the agent read. Relative targets are resolved
+/// against the workspace.
+pub(super) fn allowed_local_file(security: &SecurityPolicy, target: &str) -> Option<PathBuf> {
+    let workspace = &security.workspace_dir;
+    let resolved = std::fs::canonicalize(workspace.join(target)).ok()?;
+    let canonical_workspace = workspace
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.clone());
+    // The raw target is checked for traversal, and workspace files
+    // relative to the workspace so `workspace_only` does not reject the
+    // absolute paths tools put in markers.
+    let policy_path = resolved
+        .strip_prefix(&canonical_workspace)
+        .unwrap_or(&resolved)
+        .to_string_lossy()
+        .into_owned();
+    // Copies made by `media::prepare_attachments` live in the temp dir.
+    let prepared_copy = prepared_media_dir()
+        .canonicalize()
+        .is_ok_and(|dir| resolved.starts_with(dir));
+    let allowed = prepared_copy
+        || (security.is_path_allowed(target.trim_start_matches('/'))
+            && security.is_path_allowed(&policy_path)
+            && security.is_resolved_path_allowed(&resolved));
+    if !allowed {
+        tracing::warn!("Refusing to share attachment {target} outside the allowed paths");
+        return None;
+    }
+    resolved.is_file().then_some(resolved)
+}
+
+/// Publishes local attachments of URL-only channels.
+#[derive(Debug, Clone)]
+pub struct MediaHost {
+    public_url: String,
+    ttl: Duration,
+    channels: Vec<String>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl MediaHost {
+    /// Returns `None` unless a `public_url` is configured.
+    pub fn from_config(config: &MediaHostConfig, security: Arc<SecurityPolicy>) -> Option<Self> {
+        let public_url = config
+            .public_url
+            .as_deref()
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())?;
+        Some(Self {
+            public_url: public_url.to_string(),
+            ttl: Duration::from_secs(config.ttl_secs.max(1)),
+            channels: config.channels.clone(),
+            security,
+        })
+    }
+
+    fn url_for(&self, token: &str, path: &Path) -> String {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(urlencoding::encode)
+            .map_or_else(|| "file".to_string(), |n| n.into_owned());
+        format!("{}/media/{token}/{name}", self.public_url)
+    }
+
+    /// Rewrite local attachment markers in a reply to `channel` to hosted
+    /// URLs. Markers of files that do not exist or that the security policy
+    /// does not allow are left for the channel to report.
+    pub fn publish_local_attachments(&self, channel: &str, content: &str) -> String {
+        if !self.channels.iter().any(|c| c == channel) {
+            return content.to_string();
+        }
+        let markers = markers(content, true);
+        if markers.is_empty() {
+            return content.to_string();
+        }
+
+        let mut result = String::with_capacity(content.len());
+        let mut cursor = 0;
+        for marker in markers {
+            let Some(path) = allowed_local_file(&self.security, &marker.target) else {
+                continue;
+            };
+            let url = self.url_for(
+                &insert(&mut registry().lock(), path.clone(), self.ttl),
+                &path,
+            );
+            result.push_str(&content[cursor..marker.range.start]);
+            result.push_str(&render(&marker.kind, &url, marker.alt.as_deref()));
+            cursor = marker.range.end;
+        }
+        result.push_str(&content[cursor..]);
+        result
+    }
+}
+
+fn render(kind: &AttachmentKind, url: &str, alt: Option<&str>) -> String {
+    match alt {
+        Some(alt) => format!("[{}:{url}|{alt}]", kind.marker_name()),
+        None => format!("[{}:{url}]", kind.marker_name()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(channels: &[&str], workspace: &Path) -> MediaHost {
+        MediaHost::from_config(
+            &MediaHostConfig {
+                public_url: Some("https://agent.example.com/".into()),
+                ttl_secs: 60,
+                channels: channels.iter().map(|c| (*c).to_string()).collect(),
+            },
+            Arc::new(SecurityPolicy {
+                workspace_dir: workspace.to_path_buf(),
+                ..SecurityPolicy::default()
+            }),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn from_config_requires_public_url() {
+        assert!(MediaHost::from_config(
+            &MediaHostConfig::default(),
+            Arc::new(SecurityPolicy::default())
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn publishes_existing_local_files_for_listed_channels() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("chart 1.png");
+        std::fs::write(&file, b"png").unwrap();
+        let content = format!(
+            "Here [IMAGE:{}|Sales] and [DOCUMENT:/nonexistent/x.pdf] [IMAGE:https://x.test/a.png]",
+            file.display()
+        );
+
+        let published =
+            host(&["google_chat"], dir.path()).publish_local_attachments("google_chat", &content);
+        let (_, rest) = published
+            .split_once("[IMAGE:https://agent.example.com/media/")
+            .unwrap();
+        let (token, rest) = rest.split_once('/').unwrap();
+        assert!(rest.starts_with("chart%201.png|Sales]"));
+        assert!(rest.contains("[DOCUMENT:/nonexistent/x.pdf]"));
+        assert!(rest.contains("[IMAGE:https://x.test/a.png]"));
+        assert_eq!(lookup(token), Some(file.canonicalize().unwrap()));
+
+        assert_eq!(
+            host(&["google_chat"], dir.path()).publish_local_attachments("telegram", &content),
+            content
+        );
+    }
+
+    #[test]
+    fn files_outside_the_allowed_paths_are_not_published() {
+        let workspace = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("id_rsa");
+        std::fs::write(&secret, b"key").unwrap();
+        std::os::unix::fs::symlink(&secret, workspace.path().join("link.png")).unwrap();
+        std::fs::write(workspace.path().join("ok.png"), b"png").unwrap();
+        let host = host(&["google_chat"], workspace.path());
+
+        for target in [
+            secret.display().to_string(),
+            workspace.path().join("link.png").display().to_string(),
+            "/etc/passwd".to_string(),
+            "../id_rsa".to_string(),
+        ] {
+            let content = format!("[DOCUMENT:{target}]");
+            assert_eq!(
+                host.publish_local_attachments("google_chat", &content),
+                content
+            );
+        }
+        assert!(host
+            .publish_local_attachments("google_chat", "[IMAGE:ok.png]")
+            .starts_with("[IMAGE:https://agent.example.com/media/"));
+    }
+
+    #[test]
+    fn expired_tokens_are_not_served() {
+        let token = insert(
+            &mut registry().lock(),
+            PathBuf::from("/tmp/a.png"),
+            Duration::ZERO,
+        );
+        assert_eq!(lookup(&token), None);
+    }
+}
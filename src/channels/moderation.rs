@@ -0,0 +1,336 @@
+//! Content moderation for inbound messages and outbound replies.
+//!
+//! Text is checked against the keyword rules of `[channels_config.moderation]`
+//! first and, when nothing matched and an API key is set, against an
+//! OpenAI-compatible `/moderations` endpoint. What happens to flagged text is
+//! decided per channel and direction: `block`, `warn`, `log` or `off`. The
+//! API failing or timing out never holds a message back.
+
+use crate::config::{ModerationAction, ModerationConfig};
+use regex::Regex;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Which way a message is travelling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl Direction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Inbound => "inbound",
+            Self::Outbound => "outbound",
+        }
+    }
+}
+
+/// A message that matched a rule, and what to do about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flagged {
+    pub action: ModerationAction,
+    /// `keyword:<word>`, `pattern:<regex>` or `api:<category>` per match.
+    pub reasons: Vec<String>,
+}
+
+struct ModerationApi {
+    url: String,
+    api_key: String,
+    model: String,
+    timeout: Duration,
+}
+
+/// Compiled `[channels_config.moderation]`.
+pub struct Moderation {
+    keywords: Vec<(String, Regex)>,
+    patterns: Vec<(String, Regex)>,
+    api: Option<ModerationApi>,
+    inbound: ModerationAction,
+    outbound: ModerationAction,
+    channels: HashMap<String, (Option<ModerationAction>, Option<ModerationAction>)>,
+    block_message: String,
+    warn_message: String,
+}
+
+impl Moderation {
+    /// `None` when no keyword, pattern or API key is configured.
+    pub fn from_config(config: &ModerationConfig) -> Option<Self> {
+        if !config.is_active() {
+            return None;
+        }
+        let keywords = config
+            .keywords
+            .iter()
+            .map(|keyword| keyword.trim())
+            .filter(|keyword| !keyword.is_empty())
+            .filter_map(|keyword| keyword_regex(keyword).map(|regex| (keyword.to_string(), regex)))
+            .collect();
+        // Patterns are checked by `Config::validate`; skip any that slipped through.
+        let patterns = config
+            .patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some((pattern.clone(), regex)),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid moderation pattern {pattern:?}: {e}");
+                    None
+                }
+            })
+            .collect();
+        let api = config
+            .api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| ModerationApi {
+                url: config.api_url.clone(),
+                api_key: key.to_string(),
+                model: config.model.clone(),
+                timeout: Duration::from_secs(config.timeout_secs.max(1)),
+            });
+        Some(Self {
+            keywords,
+            patterns,
+            api,
+            inbound: config.inbound,
+            outbound: config.outbound,
+            channels: config
+                .channels
+                .iter()
+                .map(|(name, policy)| (name.clone(), (policy.inbound, policy.outbound)))
+                .collect(),
+            block_message: config.block_message.clone(),
+            warn_message: config.warn_message.clone(),
+        })
+    }
+
+    /// Action for flagged text on `channel` in `direction`.
+    pub fn action_for(&self, channel: &str, direction: Direction) -> ModerationAction {
+        let (inbound, outbound) = self.channels.get(channel).copied().unwrap_or_default();
+        match direction {
+            Direction::Inbound => inbound.unwrap_or(self.inbound),
+            Direction::Outbound => outbound.unwrap_or(self.outbound),
+        }
+    }
+
+    /// Whether flagged replies on `channel` are withheld. Draft streaming
+    /// would show a reply before it is checked, so such channels answer in
+    /// one message.
+    pub fn blocks_outbound(&self, channel: &str) -> bool {
+        self.action_for(channel, Direction::Outbound) == ModerationAction::Block
+    }
+
+    pub fn block_message(&self) -> &str {
+        &self.block_message
+    }
+
+    pub fn warn_message(&self) -> &str {
+        &self.warn_message
+    }
+
+    /// Check `text`; `None` when it passes or moderation is off there.
+    pub async fn review(&self, channel: &str, direction: Direction, text: &str) -> Option<Flagged> {
+        let action = self.action_for(channel, direction);
+        if action == ModerationAction::Off || text.trim().is_empty() {
+            return None;
+        }
+        let mut reasons = self.rule_matches(text);
+        if reasons.is_empty() {
+            if let Some(api) = &self.api {
+                reasons = match api.check(text).await {
+                    Ok(categories) => categories,
+                    Err(e) => {
+                        tracing::warn!(
+                            channel,
+                            direction = direction.as_str(),
+                            "Moderation API unavailable; letting the message through: {e:#}"
+                        );
+                        Vec::new()
+                    }
+                };
+            }
+        }
+        (!reasons.is_empty()).then_some(Flagged { action, reasons })
+    }
+
+    fn rule_matches(&self, text: &str) -> Vec<String> {
+        let keywords = self
+            .keywords
+            .iter()
+            .filter(|(_, regex)| regex.is_match(text))
+            .map(|(keyword, _)| format!("keyword:{keyword}"));
+        let patterns = self
+            .patterns
+            .iter()
+            .filter(|(_, regex)| regex.is_match(text))
+            .map(|(pattern, _)| format!("pattern:{pattern}"));
+        keywords.chain(patterns).collect()
+    }
+}
+
+/// Case-insensitive match of `keyword` that does not start or end inside a
+/// longer word.
+fn keyword_regex(keyword: &str) -> Option<Regex> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let start = if keyword.starts_with(is_word) {
+        r"\b"
+    } else {
+        ""
+    };
+    let end = if keyword.ends_with(is_word) {
+        r"\b"
+    } else {
+        ""
+    };
+    Regex::new(&format!("(?i){start}{}{end}", regex::escape(keyword))).ok()
+}
+
+impl ModerationApi {
+    /// Flagged categories, as `api:<category>`.
+    async fn check(&self, text: &str) -> anyhow::Result<Vec<String>> {
+        let client = crate::config::build_runtime_proxy_client("channel.moderation");
+        let response = client
+            .post(&self.url)
+            .bearer_auth(&self.api_key)
+            .timeout(self.timeout)
+            .json(&serde_json::json!({ "model": self.model, "input": text }))
+            .send()
+            .await?
+            .error_for_status()?;
+        let body: serde_json::Value = response.json().await?;
+        Ok(flagged_categories(&body))
+    }
+}
+
+/// Categories of every flagged result in a `/moderations` response.
+fn flagged_categories(body: &serde_json::Value) -> Vec<String> {
+    let mut categories = Vec::new();
+    let results = body["results"].as_array().map_or(&[][..], Vec::as_slice);
+    for result in results {
+        if !result["flagged"].as_bool().unwrap_or(false) {
+            continue;
+        }
+        let before = categories.len();
+        if let Some(map) = result["categories"].as_object() {
+            for (category, hit) in map {
+                let reason = format!("api:{category}");
+                if hit.as_bool() == Some(true) && !categories.contains(&reason) {
+                    categories.push(reason);
+                }
+            }
+        }
+        if categories.len() == before {
+            categories.push("api:flagged".to_string());
+        }
+    }
+    categories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChannelModerationConfig;
+
+    fn moderation(config: ModerationConfig) -> Moderation {
+        Moderation::from_config(&config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn keywords_match_whole_words_case_insensitively() {
+        let moderation = moderation(ModerationConfig {
+            keywords: vec!["casino".into(), "free money".into()],
+            patterns: vec![r"\b\d{4}-\d{4}-\d{4}-\d{4}\b".into()],
+            ..ModerationConfig::default()
+        });
+
+        let flagged = moderation
+            .review(
+                "telegram",
+                Direction::Inbound,
+                "Best CASINO bonus, FREE money!",
+            )
+            .await
+            .unwrap();
+        assert_eq!(flagged.action, ModerationAction::Block);
+        assert_eq!(
+            flagged.reasons,
+            vec!["keyword:casino", "keyword:free money"]
+        );
+
+        assert!(moderation
+            .review("telegram", Direction::Inbound, "Casinos of Monaco")
+            .await
+            .is_none());
+        assert_eq!(
+            moderation
+                .review("telegram", Direction::Outbound, "Card 1234-5678-9012-3456")
+                .await
+                .unwrap()
+                .reasons,
+            vec![r"pattern:\b\d{4}-\d{4}-\d{4}-\d{4}\b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn channel_overrides_pick_the_action() {
+        let moderation = moderation(ModerationConfig {
+            keywords: vec!["casino".into()],
+            outbound: ModerationAction::Warn,
+            channels: HashMap::from([
+                (
+                    "discord".to_string(),
+                    ChannelModerationConfig {
+                        inbound: Some(ModerationAction::Log),
+                        outbound: None,
+                    },
+                ),
+                (
+                    "cli".to_string(),
+                    ChannelModerationConfig {
+                        inbound: Some(ModerationAction::Off),
+                        outbound: Some(ModerationAction::Off),
+                    },
+                ),
+            ]),
+            ..ModerationConfig::default()
+        });
+
+        assert_eq!(
+            moderation.action_for("discord", Direction::Inbound),
+            ModerationAction::Log
+        );
+        assert_eq!(
+            moderation.action_for("discord", Direction::Outbound),
+            ModerationAction::Warn
+        );
+        assert_eq!(
+            moderation.action_for("slack", Direction::Inbound),
+            ModerationAction::Block
+        );
+        assert!(!moderation.blocks_outbound("discord"));
+        assert!(!moderation.blocks_outbound("cli"));
+        assert!(moderation
+            .review("cli", Direction::Inbound, "casino")
+            .await
+            .is_none());
+        assert!(Moderation::from_config(&ModerationConfig::default()).is_none());
+    }
+
+    #[test]
+    fn api_response_categories_are_collected() {
+        let body = serde_json::json!({
+            "results": [
+                {"flagged": true, "categories": {"harassment": true, "violence": false, "hate": true}},
+                {"flagged": false, "categories": {"sexual": true}},
+                {"flagged": true, "categories": {}}
+            ]
+        });
+        assert_eq!(
+            flagged_categories(&body),
+            vec!["api:harassment", "api:hate", "api:flagged"]
+        );
+        assert!(flagged_categories(&serde_json::json!({"results": []})).is_empty());
+    }
+}
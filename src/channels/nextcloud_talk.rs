@@ -1,9 +1,13 @@
+use super::split::split_message;
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 use uuid::Uuid;
 
+/// Talk rejects chat messages longer than this.
+const NEXTCLOUD_TALK_MAX_MESSAGE_CHARS: usize = 32_000;
+
 /// Nextcloud Talk channel in webhook mode.
 ///
 /// Incoming messages are received by the gateway endpoint `/nextcloud-talk`.
@@ -208,8 +212,10 @@ impl Channel for NextcloudTalkChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        self.send_to_room(&message.recipient, &message.content)
-            .await
+        for chunk in split_message(&message.content, NEXTCLOUD_TALK_MAX_MESSAGE_CHARS) {
+            self.send_to_room(&message.recipient, &chunk).await?;
+        }
+        Ok(())
     }
 
     async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
@@ -0,0 +1,496 @@
+//! Background retries for channel replies that failed to send.
+//!
+//! A reply is only handed to the queue after its first send failed, so the
+//! common path stays synchronous. Each channel gets one worker that retries
+//! its queued replies in order with exponential backoff (honoring a
+//! `retry_after` hint from Telegram or Discord). Replies that fail with a
+//! non-transient error, run out of retries, or find the queue full are
+//! appended to `state/dead_letters.jsonl` in the workspace and logged, so a
+//! reply is never dropped silently after the provider call succeeded. With a
+//! [`DeliveryLog`] attached, each queued reply's status is kept up to date.
+//! On shutdown, queued replies get one immediate last attempt before they
+//! are dead-lettered.
+
+use super::delivery::{DeliveryLog, DeliveryStatus};
+use super::traits::{Channel, SendMessage};
+use crate::config::ReliabilityConfig;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Replies waiting per channel before new failures go straight to the
+/// dead-letter log.
+const QUEUE_CAPACITY: usize = 100;
+/// Upper bound for one backoff delay, including `retry_after` hints.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+struct QueuedSend {
+    channel: Arc<dyn Channel>,
+    message: SendMessage,
+    last_error: String,
+    delivery_id: Option<i64>,
+}
+
+/// Per-channel retry queues sharing one dead-letter log.
+pub struct OutboundQueue {
+    workers: Mutex<HashMap<String, mpsc::Sender<QueuedSend>>>,
+    retries: u32,
+    backoff: Duration,
+    dead_letter_path: PathBuf,
+    deliveries: Option<Arc<DeliveryLog>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+    stopping: CancellationToken,
+}
+
+impl OutboundQueue {
+    pub fn new(reliability: &ReliabilityConfig, workspace_dir: &Path) -> Self {
+        Self {
+            workers: Mutex::new(HashMap::new()),
+            retries: reliability.channel_send_retries,
+            backoff: Duration::from_millis(reliability.channel_send_backoff_ms.max(1)),
+            dead_letter_path: workspace_dir.join("state").join("dead_letters.jsonl"),
+            deliveries: None,
+            tasks: Mutex::new(Vec::new()),
+            stopping: CancellationToken::new(),
+        }
+    }
+
+    /// Record the status of queued replies in `deliveries`.
+    pub fn with_delivery_log(mut self, deliveries: Arc<DeliveryLog>) -> Self {
+        self.deliveries = Some(deliveries);
+        self
+    }
+
+    /// Queue `message` for retry after its first send failed with `error`.
+    pub fn enqueue(
+        self: &Arc<Self>,
+        channel: Arc<dyn Channel>,
+        message: SendMessage,
+        error: &anyhow::Error,
+    ) {
+        let last_error = format!("{error:#}");
+        let retry = self.retries > 0 && is_transient(&last_error);
+        let delivery_id = self.deliveries.as_ref().and_then(|deliveries| {
+            let status = if retry {
+                DeliveryStatus::Retrying
+            } else {
+                DeliveryStatus::Failed
+            };
+            deliveries
+                .record(
+                    channel.name(),
+                    &message.recipient,
+                    &message.content,
+                    status,
+                    Some(&last_error),
+                )
+                .map_err(|e| tracing::warn!("Failed to record delivery status: {e}"))
+                .ok()
+        });
+        let queued = QueuedSend {
+            channel,
+            message,
+            last_error,
+            delivery_id,
+        };
+        if !retry {
+            self.spawn_dead_letter(queued);
+            return;
+        }
+
+        let name = queued.channel.name().to_string();
+        let sender = {
+            let mut workers = self.workers.lock();
+            workers
+                .entry(name.clone())
+                .or_insert_with(|| self.spawn_worker())
+                .clone()
+        };
+        if let Err(
+            mpsc::error::TrySendError::Full(queued) | mpsc::error::TrySendError::Closed(queued),
+        ) = sender.try_send(queued)
+        {
+            tracing::warn!("Outbound retry queue for {name} rejected a reply");
+            self.spawn_dead_letter(queued);
+        }
+    }
+
+    /// Dead-letter a reply whose only send attempt failed.
+    fn spawn_dead_letter(self: &Arc<Self>, queued: QueuedSend) {
+        let queue = Arc::clone(self);
+        self.track(tokio::spawn(async move {
+            queue
+                .dead_letter(
+                    queued.channel.name(),
+                    &queued.message,
+                    1,
+                    &queued.last_error,
+                )
+                .await;
+        }));
+    }
+
+    fn spawn_worker(self: &Arc<Self>) -> mpsc::Sender<QueuedSend> {
+        let (tx, mut rx) = mpsc::channel::<QueuedSend>(QUEUE_CAPACITY);
+        let queue = Arc::clone(self);
+        self.track(tokio::spawn(async move {
+            while let Some(queued) = rx.recv().await {
+                queue.retry(queued).await;
+            }
+        }));
+        tx
+    }
+
+    fn track(&self, task: JoinHandle<()>) {
+        let mut tasks = self.tasks.lock();
+        tasks.retain(|task| !task.is_finished());
+        tasks.push(task);
+    }
+
+    /// Retry every queued reply once more without waiting for its backoff,
+    /// dead-letter those that still fail, and wait until that is done.
+    pub async fn shutdown(&self) {
+        self.stopping.cancel();
+        // Dropping the senders lets each worker exit once its queue is empty.
+        self.workers.lock().clear();
+        let tasks = std::mem::take(&mut *self.tasks.lock());
+        for task in tasks {
+            let _ = task.await;
+        }
+    }
+
+    async fn retry(&self, queued: QueuedSend) {
+        let QueuedSend {
+            channel,
+            message,
+            mut last_error,
+            delivery_id,
+        } = queued;
+
+        for attempt in 1..=self.retries {
+            tokio::select! {
+                () = tokio::time::sleep(backoff_delay(self.backoff, attempt, &last_error)) => {}
+                () = self.stopping.cancelled() => {}
+            }
+            match channel.send(&message).await {
+                Ok(()) => {
+                    tracing::info!(
+                        "Delivered reply to {} on {} after {} retries",
+                        message.recipient,
+                        channel.name(),
+                        attempt
+                    );
+                    self.update_delivery(delivery_id, DeliveryStatus::Accepted, attempt + 1, None);
+                    return;
+                }
+                Err(e) => last_error = format!("{e:#}"),
+            }
+            if !is_transient(&last_error) || self.stopping.is_cancelled() {
+                self.update_delivery(
+                    delivery_id,
+                    DeliveryStatus::Failed,
+                    attempt + 1,
+                    Some(&last_error),
+                );
+                self.dead_letter(channel.name(), &message, attempt + 1, &last_error)
+                    .await;
+                return;
+            }
+        }
+        self.update_delivery(
+            delivery_id,
+            DeliveryStatus::Failed,
+            self.retries + 1,
+            Some(&last_error),
+        );
+        self.dead_letter(channel.name(), &message, self.retries + 1, &last_error)
+            .await;
+    }
+
+    fn update_delivery(
+        &self,
+        delivery_id: Option<i64>,
+        status: DeliveryStatus,
+        attempts: u32,
+        error: Option<&str>,
+    ) {
+        let (Some(deliveries), Some(id)) = (self.deliveries.as_ref(), delivery_id) else {
+            return;
+        };
+        if let Err(e) = deliveries.update(id, status, attempts, error) {
+            tracing::warn!("Failed to update delivery status: {e}");
+        }
+    }
+
+    async fn dead_letter(&self, channel: &str, message: &SendMessage, attempts: u32, error: &str) {
+        tracing::error!(
+            channel,
+            recipient = %message.recipient,
+            attempts,
+            "Dropping undeliverable reply: {error}"
+        );
+
+        let record = serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "channel": channel,
+            "recipient": message.recipient,
+            "thread_ts": message.thread_ts,
+            "attempts": attempts,
+            "error": error,
+            "content": message.content,
+        });
+        if let Err(e) = append_line(&self.dead_letter_path, &record.to_string()).await {
+            tracing::warn!(
+                "Failed to write dead letter to {}: {e}",
+                self.dead_letter_path.display()
+            );
+        }
+    }
+}
+
+async fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(format!("{line}\n").as_bytes()).await
+}
+
+/// Whether a send error looks temporary: rate limits, server errors,
+/// timeouts and connection failures.
+fn is_transient(error: &str) -> bool {
+    let error = error.to_ascii_lowercase();
+    [
+        "429",
+        "too many requests",
+        "rate limit",
+        "ratelimit",
+        "retry_after",
+        "500",
+        "502",
+        "503",
+        "504",
+        "internal server error",
+        "bad gateway",
+        "service unavailable",
+        "timed out",
+        "timeout",
+        "connection",
+        "temporarily",
+    ]
+    .iter()
+    .any(|needle| error.contains(needle))
+}
+
+/// Seconds from a `retry_after` field in an API error body.
+fn retry_after_hint(error: &str) -> Option<Duration> {
+    let rest = &error[error.find("retry_after")? + "retry_after".len()..];
+    let rest = rest.trim_start_matches(|c: char| c == '"' || c == ':' || c.is_whitespace());
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(rest.len());
+    let secs: f64 = rest[..end].parse().ok()?;
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+/// Delay before retry `attempt` (1-based): `base * 2^(attempt-1)`, or the
+/// server's `retry_after` hint when it asks for longer.
+fn backoff_delay(base: Duration, attempt: u32, last_error: &str) -> Duration {
+    let exponential = base.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+    let delay = retry_after_hint(last_error).map_or(exponential, |hint| hint.max(exponential));
+    delay.min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    struct FlakyChannel {
+        failures_left: AtomicUsize,
+        error: &'static str,
+        sent: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for FlakyChannel {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn send(&self, _message: &SendMessage) -> anyhow::Result<()> {
+            if self
+                .failures_left
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                anyhow::bail!("{}", self.error);
+            }
+            self.sent.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn queue(tmp: &TempDir, retries: u32) -> Arc<OutboundQueue> {
+        let reliability = ReliabilityConfig {
+            channel_send_retries: retries,
+            channel_send_backoff_ms: 1,
+            ..ReliabilityConfig::default()
+        };
+        Arc::new(
+            OutboundQueue::new(&reliability, tmp.path())
+                .with_delivery_log(Arc::new(DeliveryLog::new(tmp.path()))),
+        )
+    }
+
+    async fn wait_for(condition: impl Fn() -> bool) {
+        for _ in 0..200 {
+            if condition() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("condition not reached");
+    }
+
+    #[test]
+    fn classifies_transient_errors_and_reads_retry_after() {
+        assert!(is_transient(
+            "Telegram sendMessage failed (429 Too Many Requests)"
+        ));
+        assert!(is_transient(
+            "Slack chat.postMessage failed (503): upstream"
+        ));
+        assert!(!is_transient(
+            "Telegram sendMessage failed (400): chat not found"
+        ));
+
+        let error = r#"{"ok":false,"error_code":429,"parameters":{"retry_after":7}}"#;
+        assert_eq!(retry_after_hint(error), Some(Duration::from_secs(7)));
+        assert_eq!(
+            backoff_delay(Duration::from_secs(1), 1, error),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            backoff_delay(Duration::from_secs(1), 3, "timeout"),
+            Duration::from_secs(4)
+        );
+        assert_eq!(backoff_delay(Duration::from_secs(100), 9, ""), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried_until_delivered() {
+        let tmp = TempDir::new().unwrap();
+        let queue = queue(&tmp, 3);
+        let channel = Arc::new(FlakyChannel {
+            failures_left: AtomicUsize::new(2),
+            error: "429 Too Many Requests",
+            sent: AtomicUsize::new(0),
+        });
+
+        queue.enqueue(
+            channel.clone(),
+            SendMessage::new("hello", "chat"),
+            &anyhow::anyhow!("503 Service Unavailable"),
+        );
+        wait_for(|| channel.sent.load(Ordering::SeqCst) == 1).await;
+        assert!(!tmp.path().join("state/dead_letters.jsonl").exists());
+
+        let deliveries = DeliveryLog::new(tmp.path());
+        wait_for(|| {
+            deliveries
+                .recent(Some(DeliveryStatus::Accepted), 1)
+                .is_ok_and(|records| records.len() == 1)
+        })
+        .await;
+        assert_eq!(deliveries.recent(None, 10).unwrap()[0].attempts, 4);
+    }
+
+    #[tokio::test]
+    async fn shutdown_retries_queued_replies_without_waiting() {
+        let tmp = TempDir::new().unwrap();
+        let reliability = ReliabilityConfig {
+            channel_send_retries: 3,
+            channel_send_backoff_ms: 60_000,
+            ..ReliabilityConfig::default()
+        };
+        let queue = Arc::new(OutboundQueue::new(&reliability, tmp.path()));
+        let recovering = Arc::new(FlakyChannel {
+            failures_left: AtomicUsize::new(0),
+            error: "",
+            sent: AtomicUsize::new(0),
+        });
+        let down = Arc::new(FlakyChannel {
+            failures_left: AtomicUsize::new(usize::MAX),
+            error: "503 Service Unavailable",
+            sent: AtomicUsize::new(0),
+        });
+        for channel in [recovering.clone(), down.clone()] {
+            queue.enqueue(
+                channel,
+                SendMessage::new("hello", "chat"),
+                &anyhow::anyhow!("429 Too Many Requests"),
+            );
+        }
+
+        tokio::time::timeout(Duration::from_secs(5), queue.shutdown())
+            .await
+            .expect("shutdown should not wait for the backoff");
+        assert_eq!(recovering.sent.load(Ordering::SeqCst), 1);
+        let log = std::fs::read_to_string(tmp.path().join("state/dead_letters.jsonl")).unwrap();
+        assert_eq!(log.lines().count(), 1);
+        assert!(log.contains("503 Service Unavailable"));
+    }
+
+    #[tokio::test]
+    async fn permanent_failures_are_dead_lettered() {
+        let tmp = TempDir::new().unwrap();
+        let queue = queue(&tmp, 3);
+        let channel = Arc::new(FlakyChannel {
+            failures_left: AtomicUsize::new(usize::MAX),
+            error: "400 Bad Request: chat not found",
+            sent: AtomicUsize::new(0),
+        });
+
+        queue.enqueue(
+            channel.clone(),
+            SendMessage::new("hello", "chat"),
+            &anyhow::anyhow!("502 Bad Gateway"),
+        );
+        let path = tmp.path().join("state/dead_letters.jsonl");
+        wait_for(|| std::fs::read_to_string(&path).is_ok_and(|log| log.ends_with('\n'))).await;
+
+        let record: serde_json::Value =
+            serde_json::from_str(std::fs::read_to_string(&path).unwrap().trim()).unwrap();
+        assert_eq!(record["channel"], "flaky");
+        assert_eq!(record["recipient"], "chat");
+        assert_eq!(record["attempts"], 2);
+        assert_eq!(record["content"], "hello");
+
+        let failed = DeliveryLog::new(tmp.path())
+            .recent(Some(DeliveryStatus::Failed), 10)
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts, 2);
+        assert!(failed[0]
+            .error
+            .as_deref()
+            .is_some_and(|e| e.contains("chat not found")));
+    }
+}
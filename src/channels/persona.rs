@@ -0,0 +1,266 @@
+//! Named persona profiles for channel conversations.
+//!
+//! A persona bundles instructions, a tool allowlist, a model, a temperature
+//! and a reply language under a name (`[channels_config.personas.<name>]`).
+//! It applies to the chats listed in its `channels`, or to one conversation
+//! after `/persona <name>`; that choice is kept in `state/personas.db` so it
+//! survives restarts. The conversation's choice wins over a chat assignment,
+//! and a chat assignment over a channel-wide one.
+
+use crate::config::PersonaConfig;
+use crate::tools::Tool;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// The persona answering one message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivePersona {
+    pub name: String,
+    pub profile: PersonaConfig,
+}
+
+impl ActivePersona {
+    /// System prompt section describing the persona, or an empty string when
+    /// it sets neither instructions nor a language.
+    pub fn prompt_section(&self) -> String {
+        let mut section = String::new();
+        if let Some(prompt) = non_empty(self.profile.system_prompt.as_deref()) {
+            let _ = write!(section, "## Persona: {}\n\n{prompt}", self.name);
+        }
+        if let Some(language) = non_empty(self.profile.language.as_deref()) {
+            if !section.is_empty() {
+                section.push_str("\n\n");
+            }
+            let _ = write!(
+                section,
+                "Always reply in {language}, whatever language the user writes in."
+            );
+        }
+        section
+    }
+
+    /// Names of the `tools` the persona may not use.
+    pub fn excluded_tools(&self, tools: &[Box<dyn Tool>]) -> Vec<String> {
+        let Some(allowed) = self.profile.tools.as_ref() else {
+            return Vec::new();
+        };
+        tools
+            .iter()
+            .map(|tool| tool.name())
+            .filter(|name| !allowed.iter().any(|allowed| allowed == name))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// `"<channel>"` or `"<channel>:<chat>"`; a chat entry also covers the
+/// chat's threads and topics. Returns how specific the match is.
+fn assignment_rank(entry: &str, channel: &str, reply_target: &str) -> Option<u8> {
+    match entry.trim().split_once(':') {
+        None => (entry.trim() == channel).then_some(1),
+        Some((entry_channel, chat)) => {
+            let in_chat = reply_target == chat
+                || reply_target
+                    .strip_prefix(chat)
+                    .is_some_and(|rest| rest.starts_with(':'));
+            (entry_channel == channel && in_chat).then_some(2)
+        }
+    }
+}
+
+/// Configured personas and the per-conversation choices.
+pub struct Personas {
+    profiles: BTreeMap<String, PersonaConfig>,
+    db_path: PathBuf,
+}
+
+impl Personas {
+    /// Returns `None` when no persona is configured.
+    pub fn from_config(
+        personas: &std::collections::HashMap<String, PersonaConfig>,
+        workspace_dir: &Path,
+    ) -> Option<Self> {
+        let profiles: BTreeMap<String, PersonaConfig> = personas
+            .iter()
+            .filter(|(name, _)| !name.trim().is_empty())
+            .map(|(name, profile)| (name.trim().to_string(), profile.clone()))
+            .collect();
+        (!profiles.is_empty()).then(|| Self {
+            profiles,
+            db_path: workspace_dir.join("state").join("personas.db"),
+        })
+    }
+
+    /// Configured personas, sorted by name.
+    pub fn profiles(&self) -> impl Iterator<Item = (&str, &PersonaConfig)> {
+        self.profiles
+            .iter()
+            .map(|(name, profile)| (name.as_str(), profile))
+    }
+
+    /// Configured name matching `name` case-insensitively.
+    pub fn resolve(&self, name: &str) -> Option<&str> {
+        self.profiles
+            .keys()
+            .find(|candidate| candidate.eq_ignore_ascii_case(name.trim()))
+            .map(String::as_str)
+    }
+
+    /// Persona for the conversation `session` in a chat, if any.
+    pub fn active(
+        &self,
+        session: &str,
+        channel: &str,
+        reply_target: &str,
+    ) -> Option<ActivePersona> {
+        let chosen = match self.session_choice(session) {
+            Ok(chosen) => chosen.filter(|name| self.profiles.contains_key(name)),
+            Err(e) => {
+                tracing::warn!("Failed to load persona choice: {e}");
+                None
+            }
+        };
+        let name = chosen.or_else(|| {
+            self.profiles
+                .iter()
+                .filter_map(|(name, profile)| {
+                    profile
+                        .channels
+                        .iter()
+                        .filter_map(|entry| assignment_rank(entry, channel, reply_target))
+                        .max()
+                        .map(|rank| (rank, name))
+                })
+                .max_by_key(|(rank, _)| *rank)
+                .map(|(_, name)| name.clone())
+        })?;
+        let profile = self.profiles.get(&name)?.clone();
+        Some(ActivePersona { name, profile })
+    }
+
+    /// Use persona `name` for `session`, or go back to the chat's default
+    /// with `None`.
+    pub fn select(&self, session: &str, name: Option<&str>) -> Result<()> {
+        self.with_connection(|conn| {
+            match name {
+                Some(name) => conn.execute(
+                    "INSERT INTO persona_sessions (session, persona, updated_at) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(session) DO UPDATE SET persona = ?2, updated_at = ?3",
+                    params![session, name, chrono::Utc::now().to_rfc3339()],
+                )?,
+                None => conn.execute(
+                    "DELETE FROM persona_sessions WHERE session = ?1",
+                    params![session],
+                )?,
+            };
+            Ok(())
+        })
+    }
+
+    fn session_choice(&self, session: &str) -> Result<Option<String>> {
+        if !self.db_path.exists() {
+            return Ok(None);
+        }
+        self.with_connection(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT persona FROM persona_sessions WHERE session = ?1",
+                    params![session],
+                    |row| row.get(0),
+                )
+                .optional()?)
+        })
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create state directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(&self.db_path)
+            .with_context(|| format!("Failed to open persona DB: {}", self.db_path.display()))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS persona_sessions (
+                session    TEXT PRIMARY KEY,
+                persona    TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );",
+        )
+        .context("Failed to initialize persona schema")?;
+
+        f(&conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn personas(tmp: &TempDir) -> Personas {
+        let config = HashMap::from([
+            (
+                "support".to_string(),
+                PersonaConfig {
+                    system_prompt: Some("You are Sam.".into()),
+                    language: Some("German".into()),
+                    tools: Some(vec!["memory_recall".into()]),
+                    channels: vec!["slack".into()],
+                    ..PersonaConfig::default()
+                },
+            ),
+            (
+                "pirate".to_string(),
+                PersonaConfig {
+                    system_prompt: Some("Talk like a pirate.".into()),
+                    channels: vec!["slack:C0123".into()],
+                    ..PersonaConfig::default()
+                },
+            ),
+        ]);
+        Personas::from_config(&config, tmp.path()).unwrap()
+    }
+
+    #[test]
+    fn assignments_and_session_choice_pick_the_persona() {
+        let tmp = TempDir::new().unwrap();
+        let personas = personas(&tmp);
+
+        let active = |session: &str, channel: &str, chat: &str| {
+            personas
+                .active(session, channel, chat)
+                .map(|persona| persona.name)
+        };
+        assert_eq!(active("s1", "slack", "C999"), Some("support".into()));
+        assert_eq!(active("s1", "slack", "C0123:1700.1"), Some("pirate".into()));
+        assert_eq!(active("s1", "telegram", "42"), None);
+
+        personas.select("s1", Some("pirate")).unwrap();
+        assert_eq!(active("s1", "telegram", "42"), Some("pirate".into()));
+        assert_eq!(active("s2", "telegram", "42"), None);
+        personas.select("s1", None).unwrap();
+        assert_eq!(active("s1", "telegram", "42"), None);
+        assert_eq!(personas.resolve("PIRATE"), Some("pirate"));
+    }
+
+    #[test]
+    fn prompt_section_includes_instructions_and_language() {
+        let tmp = TempDir::new().unwrap();
+        let persona = personas(&tmp).active("s", "slack", "C1").unwrap();
+        let section = persona.prompt_section();
+        assert!(section.starts_with("## Persona: support\n\nYou are Sam."));
+        assert!(section.ends_with("Always reply in German, whatever language the user writes in."));
+        assert!(Personas::from_config(&HashMap::new(), tmp.path()).is_none());
+    }
+}
@@ -0,0 +1,668 @@
+//! Plugin channels — external processes bridged in over JSON lines.
+//!
+//! A plugin is any program that speaks the bridge protocol on stdin/stdout
+//! (spawned from `command`) or on a Unix socket it already listens on
+//! (`socket`). Every line is one JSON object with a `type` field.
+//!
+//! Host → plugin:
+//! - `{"type":"init","channel":"<name>","protocol":1}` once per connection
+//! - `{"type":"send","request_id":"…","recipient":"…","content":"…","thread_ts":null,"attachments":[{"kind":"image","target":"/path/or/url"}]}`
+//! - `{"type":"typing","recipient":"…","active":true}`
+//! - `{"type":"ping","request_id":"…"}`
+//!
+//! Plugin → host:
+//! - `{"type":"message","id":"…","sender":"…","reply_target":"…","content":"…","thread_ts":null,"attachments":[…]}`
+//! - `{"type":"result","request_id":"…","ok":true,"error":null}` for `send` and `ping`
+//! - `{"type":"hello","name":"…","version":"…"}` and `{"type":"log","level":"info","message":"…"}` (optional)
+//!
+//! Outbound attachment markers are parsed out of the reply and passed as
+//! `attachments`; inbound attachments become `[IMAGE:…]`-style markers.
+
+use super::attachment::{parse_attachment_markers, AttachmentKind};
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::schema::PluginChannelConfig;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+/// Bridge protocol version announced in `init`.
+pub const PLUGIN_PROTOCOL_VERSION: u32 = 1;
+/// Lines longer than this are dropped so a broken plugin cannot exhaust memory.
+const MAX_LINE_BYTES: usize = 1024 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+type PluginWriter = Box<dyn AsyncWrite + Send + Unpin>;
+type PendingRequests =
+    Arc<parking_lot::Mutex<HashMap<String, oneshot::Sender<Result<(), String>>>>>;
+
+/// How the host reaches the plugin process.
+#[derive(Debug, Clone)]
+pub enum PluginTransport {
+    /// Spawn `command` and speak the protocol over its stdin/stdout.
+    Command {
+        command: String,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+    },
+    /// Connect to a plugin already listening on a Unix socket.
+    Socket(PathBuf),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginAttachment {
+    /// `image`, `document`, `video`, `audio`, or `voice`.
+    pub kind: String,
+    /// Local path or URL.
+    #[serde(alias = "url", alias = "path")]
+    pub target: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PluginEvent {
+    Hello {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        version: Option<String>,
+    },
+    Message {
+        #[serde(default)]
+        id: Option<String>,
+        sender: String,
+        #[serde(default)]
+        reply_target: Option<String>,
+        #[serde(default)]
+        content: String,
+        #[serde(default)]
+        thread_ts: Option<String>,
+        #[serde(default)]
+        attachments: Vec<PluginAttachment>,
+    },
+    Result {
+        request_id: String,
+        ok: bool,
+        #[serde(default)]
+        error: Option<String>,
+    },
+    Log {
+        #[serde(default)]
+        level: Option<String>,
+        message: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum HostCommand<'a> {
+    Init {
+        channel: &'a str,
+        protocol: u32,
+    },
+    Send {
+        request_id: String,
+        recipient: &'a str,
+        content: &'a str,
+        thread_ts: Option<&'a str>,
+        attachments: Vec<PluginAttachment>,
+    },
+    Typing {
+        recipient: &'a str,
+        active: bool,
+    },
+    Ping {
+        request_id: String,
+    },
+}
+
+/// Channel backed by an external plugin process.
+pub struct PluginChannel {
+    name: String,
+    transport: PluginTransport,
+    allowed_users: Vec<String>,
+    writer: Arc<Mutex<Option<PluginWriter>>>,
+    pending: PendingRequests,
+    next_id: AtomicU64,
+}
+
+impl PluginChannel {
+    pub fn new(name: String, transport: PluginTransport, allowed_users: Vec<String>) -> Self {
+        Self {
+            name,
+            transport,
+            allowed_users,
+            writer: Arc::new(Mutex::new(None)),
+            pending: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Build a plugin channel from its `[[channels_config.plugins]]` entry.
+    pub fn from_config(config: &PluginChannelConfig) -> anyhow::Result<Self> {
+        if !Self::is_valid_name(&config.name) {
+            anyhow::bail!(
+                "invalid plugin channel name '{}' (use lowercase letters, digits, '-' or '_')",
+                config.name
+            );
+        }
+        let transport = match (&config.command, &config.socket) {
+            (Some(command), None) => PluginTransport::Command {
+                command: command.clone(),
+                args: config.args.clone(),
+                env: config.env.clone(),
+            },
+            (None, Some(socket)) => {
+                PluginTransport::Socket(PathBuf::from(shellexpand::tilde(socket).into_owned()))
+            }
+            _ => anyhow::bail!("set exactly one of `command` or `socket`"),
+        };
+        Ok(Self::new(
+            config.name.clone(),
+            transport,
+            config.allowed_users.clone(),
+        ))
+    }
+
+    /// Plugin channel names become the `channel` of every message, so keep
+    /// them to a predictable identifier alphabet.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= 32
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    }
+
+    fn is_user_allowed(&self, sender: &str) -> bool {
+        self.allowed_users.iter().any(|u| u == "*" || u == sender)
+    }
+
+    async fn write_command(&self, command: &HostCommand<'_>) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(command)?;
+        line.push('\n');
+        let mut guard = self.writer.lock().await;
+        let Some(writer) = guard.as_mut() else {
+            anyhow::bail!("plugin channel '{}' is not connected", self.name);
+        };
+        writer.write_all(line.as_bytes()).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// Send a command carrying `request_id` and wait for the plugin's `result`.
+    async fn request(
+        &self,
+        request_id: String,
+        command: &HostCommand<'_>,
+        timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(request_id.clone(), tx);
+
+        if let Err(e) = self.write_command(command).await {
+            self.pending.lock().remove(&request_id);
+            return Err(e);
+        }
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(error))) => anyhow::bail!("plugin '{}' reported: {error}", self.name),
+            Ok(Err(_)) => anyhow::bail!("plugin channel '{}' disconnected", self.name),
+            Err(_) => {
+                self.pending.lock().remove(&request_id);
+                anyhow::bail!("plugin '{}' did not answer within {timeout:?}", self.name)
+            }
+        }
+    }
+
+    fn next_request_id(&self) -> String {
+        format!("req-{}", self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Turn an inbound plugin event into a channel message, if it is one.
+    fn handle_event(&self, event: PluginEvent) -> Option<ChannelMessage> {
+        match event {
+            PluginEvent::Hello { name, version } => {
+                tracing::info!(
+                    "Plugin channel '{}' connected: {} {}",
+                    self.name,
+                    name.as_deref().unwrap_or("unnamed plugin"),
+                    version.as_deref().unwrap_or("")
+                );
+                None
+            }
+            PluginEvent::Log { level, message } => {
+                match level.as_deref() {
+                    Some("error") => tracing::error!("Plugin {}: {message}", self.name),
+                    Some("warn") => tracing::warn!("Plugin {}: {message}", self.name),
+                    Some("debug") => tracing::debug!("Plugin {}: {message}", self.name),
+                    _ => tracing::info!("Plugin {}: {message}", self.name),
+                }
+                None
+            }
+            PluginEvent::Result {
+                request_id,
+                ok,
+                error,
+            } => {
+                if let Some(waiter) = self.pending.lock().remove(&request_id) {
+                    let outcome = if ok {
+                        Ok(())
+                    } else {
+                        Err(error.unwrap_or_else(|| "unknown error".to_string()))
+                    };
+                    let _ = waiter.send(outcome);
+                }
+                None
+            }
+            PluginEvent::Message {
+                id,
+                sender,
+                reply_target,
+                content,
+                thread_ts,
+                attachments,
+            } => {
+                if !self.is_user_allowed(&sender) {
+                    tracing::warn!(
+                        "Plugin {}: ignoring message from unauthorized user: {sender}",
+                        self.name
+                    );
+                    return None;
+                }
+
+                let mut parts = Vec::new();
+                if !content.trim().is_empty() {
+                    parts.push(content.trim().to_string());
+                }
+                for attachment in attachments {
+                    let Some(kind) = AttachmentKind::from_marker(&attachment.kind) else {
+                        tracing::debug!(
+                            "Plugin {}: skipping attachment of unknown kind '{}'",
+                            self.name,
+                            attachment.kind
+                        );
+                        continue;
+                    };
+                    parts.push(format!("[{}:{}]", kind.marker_name(), attachment.target));
+                }
+                if parts.is_empty() {
+                    return None;
+                }
+
+                Some(ChannelMessage {
+                    id: id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                    reply_target: reply_target.unwrap_or_else(|| sender.clone()),
+                    sender,
+                    content: parts.join("\n"),
+                    channel: self.name.clone(),
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    thread_ts,
+                })
+            }
+        }
+    }
+
+    /// Run one bridge session until the plugin disconnects.
+    async fn serve<R, W>(
+        &self,
+        reader: R,
+        writer: W,
+        tx: &mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        *self.writer.lock().await = Some(Box::new(writer));
+        let result = self.read_loop(reader, tx).await;
+        *self.writer.lock().await = None;
+        // Dropping the waiters fails any in-flight send with "disconnected".
+        self.pending.lock().clear();
+        result
+    }
+
+    async fn read_loop<R: AsyncRead + Unpin>(
+        &self,
+        reader: R,
+        tx: &mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        self.write_command(&HostCommand::Init {
+            channel: &self.name,
+            protocol: PLUGIN_PROTOCOL_VERSION,
+        })
+        .await?;
+
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.len() > MAX_LINE_BYTES {
+                tracing::warn!(
+                    "Plugin {}: dropping oversized line ({} bytes)",
+                    self.name,
+                    line.len()
+                );
+                continue;
+            }
+
+            let event = match serde_json::from_str::<PluginEvent>(line) {
+                Ok(event) => event,
+                Err(e) => {
+                    tracing::warn!("Plugin {}: invalid protocol line: {e}", self.name);
+                    continue;
+                }
+            };
+            if let Some(msg) = self.handle_event(event) {
+                if tx.send(msg).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+
+        anyhow::bail!("plugin channel '{}' disconnected", self.name)
+    }
+}
+
+#[async_trait]
+impl Channel for PluginChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (text, attachments) = parse_attachment_markers(&message.content);
+        let attachments = attachments
+            .into_iter()
+            .map(|a| PluginAttachment {
+                kind: a.kind.marker_name().to_ascii_lowercase(),
+                target: a.target,
+            })
+            .collect();
+
+        let request_id = self.next_request_id();
+        let command = HostCommand::Send {
+            request_id: request_id.clone(),
+            recipient: &message.recipient,
+            content: &text,
+            thread_ts: message.thread_ts.as_deref(),
+            attachments,
+        };
+        self.request(request_id, &command, REQUEST_TIMEOUT).await
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        match &self.transport {
+            PluginTransport::Command { command, args, env } => {
+                tracing::info!("Plugin channel '{}' starting `{command}`", self.name);
+                let mut child = tokio::process::Command::new(command)
+                    .args(args)
+                    .envs(env)
+                    .stdin(std::process::Stdio::piped())
+                    .stdout(std::process::Stdio::piped())
+                    .stderr(std::process::Stdio::piped())
+                    .kill_on_drop(true)
+                    .spawn()
+                    .map_err(|e| {
+                        anyhow::anyhow!("failed to start plugin '{}' ({command}): {e}", self.name)
+                    })?;
+
+                let stdin = child.stdin.take().expect("plugin stdin is piped");
+                let stdout = child.stdout.take().expect("plugin stdout is piped");
+                if let Some(stderr) = child.stderr.take() {
+                    let name = self.name.clone();
+                    tokio::spawn(async move {
+                        let mut lines = BufReader::new(stderr).lines();
+                        while let Ok(Some(line)) = lines.next_line().await {
+                            tracing::debug!("Plugin {name} stderr: {line}");
+                        }
+                    });
+                }
+
+                let result = self.serve(stdout, stdin, &tx).await;
+                if let Ok(Some(status)) = child.try_wait() {
+                    tracing::warn!("Plugin channel '{}' exited with {status}", self.name);
+                }
+                result
+            }
+            #[cfg(unix)]
+            PluginTransport::Socket(path) => {
+                tracing::info!(
+                    "Plugin channel '{}' connecting to {}",
+                    self.name,
+                    path.display()
+                );
+                let stream = tokio::net::UnixStream::connect(path).await.map_err(|e| {
+                    anyhow::anyhow!(
+                        "failed to connect plugin '{}' at {}: {e}",
+                        self.name,
+                        path.display()
+                    )
+                })?;
+                let (reader, writer) = stream.into_split();
+                self.serve(reader, writer, &tx).await
+            }
+            #[cfg(not(unix))]
+            PluginTransport::Socket(_) => {
+                anyhow::bail!("plugin socket transport is only supported on Unix platforms")
+            }
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        let request_id = self.next_request_id();
+        let command = HostCommand::Ping {
+            request_id: request_id.clone(),
+        };
+        self.request(request_id, &command, HEALTH_TIMEOUT)
+            .await
+            .is_ok()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.write_command(&HostCommand::Typing {
+            recipient,
+            active: true,
+        })
+        .await
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.write_command(&HostCommand::Typing {
+            recipient,
+            active: false,
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{duplex, DuplexStream, Lines, ReadHalf};
+
+    fn make_channel(allowed: &[&str]) -> Arc<PluginChannel> {
+        Arc::new(PluginChannel::new(
+            "myplatform".into(),
+            PluginTransport::Socket(PathBuf::from("/nonexistent")),
+            allowed.iter().map(ToString::to_string).collect(),
+        ))
+    }
+
+    async fn next_json(lines: &mut Lines<BufReader<ReadHalf<DuplexStream>>>) -> serde_json::Value {
+        let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[test]
+    fn validates_plugin_names() {
+        assert!(PluginChannel::is_valid_name("my_platform-2"));
+        assert!(!PluginChannel::is_valid_name(""));
+        assert!(!PluginChannel::is_valid_name("Bad Name"));
+        assert!(!PluginChannel::is_valid_name(&"x".repeat(33)));
+    }
+
+    #[test]
+    fn from_config_requires_exactly_one_transport() {
+        let mut cfg = PluginChannelConfig {
+            name: "myplatform".into(),
+            command: Some("my-bridge".into()),
+            args: Vec::new(),
+            env: HashMap::new(),
+            socket: Some("/tmp/p.sock".into()),
+            allowed_users: vec!["*".into()],
+        };
+        assert!(PluginChannel::from_config(&cfg).is_err());
+        cfg.socket = None;
+        assert!(PluginChannel::from_config(&cfg).is_ok());
+        cfg.command = None;
+        assert!(PluginChannel::from_config(&cfg).is_err());
+    }
+
+    #[test]
+    fn message_event_maps_attachments_to_markers() {
+        let ch = make_channel(&["alice"]);
+        let event = serde_json::from_str(
+            r#"{"type":"message","sender":"alice","content":"see this","attachments":[{"kind":"image","url":"https://x/y.png"},{"kind":"hologram","target":"h"}]}"#,
+        )
+        .unwrap();
+        let msg = ch.handle_event(event).unwrap();
+        assert_eq!(msg.channel, "myplatform");
+        assert_eq!(msg.reply_target, "alice");
+        assert_eq!(msg.content, "see this\n[IMAGE:https://x/y.png]");
+    }
+
+    #[test]
+    fn message_event_enforces_allowlist() {
+        let ch = make_channel(&["alice"]);
+        let event = serde_json::from_str(r#"{"type":"message","sender":"mallory","content":"hi"}"#)
+            .unwrap();
+        assert!(ch.handle_event(event).is_none());
+    }
+
+    #[tokio::test]
+    async fn bridge_session_routes_messages_and_sends() {
+        let ch = make_channel(&["*"]);
+        let (host_side, plugin_side) = duplex(64 * 1024);
+        let (host_read, host_write) = tokio::io::split(host_side);
+        let (plugin_read, mut plugin_write) = tokio::io::split(plugin_side);
+        let (tx, mut rx) = mpsc::channel(4);
+
+        let serve_ch = Arc::clone(&ch);
+        let session = tokio::spawn(async move { serve_ch.serve(host_read, host_write, &tx).await });
+
+        let mut plugin_lines = BufReader::new(plugin_read).lines();
+        let init = next_json(&mut plugin_lines).await;
+        assert_eq!(init["type"], "init");
+        assert_eq!(init["channel"], "myplatform");
+
+        plugin_write
+            .write_all(b"{\"type\":\"message\",\"id\":\"m1\",\"sender\":\"bob\",\"reply_target\":\"room-1\",\"content\":\"hello\"}\n")
+            .await
+            .unwrap();
+        let inbound = rx.recv().await.unwrap();
+        assert_eq!(inbound.id, "m1");
+        assert_eq!(inbound.reply_target, "room-1");
+
+        let send_ch = Arc::clone(&ch);
+        let send = tokio::spawn(async move {
+            send_ch
+                .send(&SendMessage::new("done [IMAGE:/tmp/a.png]", "room-1"))
+                .await
+        });
+        let cmd = next_json(&mut plugin_lines).await;
+        assert_eq!(cmd["type"], "send");
+        assert_eq!(cmd["recipient"], "room-1");
+        assert_eq!(cmd["content"], "done");
+        assert_eq!(cmd["attachments"][0]["kind"], "image");
+        assert_eq!(cmd["attachments"][0]["target"], "/tmp/a.png");
+
+        let result = format!(
+            "{{\"type\":\"result\",\"request_id\":\"{}\",\"ok\":true}}\n",
+            cmd["request_id"].as_str().unwrap()
+        );
+        plugin_write.write_all(result.as_bytes()).await.unwrap();
+        send.await.unwrap().unwrap();
+
+        drop(plugin_write);
+        drop(plugin_lines);
+        let ended = tokio::time::timeout(Duration::from_secs(5), session)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            ended.is_err(),
+            "disconnect should surface for supervisor restart"
+        );
+        assert!(ch.send(&SendMessage::new("late", "room-1")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_result_surfaces_plugin_error() {
+        let ch = make_channel(&["*"]);
+        let (host_side, plugin_side) = duplex(64 * 1024);
+        let (host_read, host_write) = tokio::io::split(host_side);
+        let (plugin_read, mut plugin_write) = tokio::io::split(plugin_side);
+        let (tx, _rx) = mpsc::channel(4);
+
+        let serve_ch = Arc::clone(&ch);
+        tokio::spawn(async move { serve_ch.serve(host_read, host_write, &tx).await });
+        let mut plugin_lines = BufReader::new(plugin_read).lines();
+        let _init = next_json(&mut plugin_lines).await;
+
+        let send_ch = Arc::clone(&ch);
+        let send =
+            tokio::spawn(async move { send_ch.send(&SendMessage::new("hi", "nobody")).await });
+        let cmd = next_json(&mut plugin_lines).await;
+        let result = format!(
+            "{{\"type\":\"result\",\"request_id\":\"{}\",\"ok\":false,\"error\":\"unknown recipient\"}}\n",
+            cmd["request_id"].as_str().unwrap()
+        );
+        plugin_write.write_all(result.as_bytes()).await.unwrap();
+
+        let err = send.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("unknown recipient"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_transport_spawns_plugin_process() {
+        let ch = PluginChannel::new(
+            "shellplug".into(),
+            PluginTransport::Command {
+                command: "sh".into(),
+                args: vec![
+                    "-c".into(),
+                    r#"read init; printf '%s\n' '{"type":"message","sender":"bob","content":"from sh"}'; read rest"#.into(),
+                ],
+                env: HashMap::new(),
+            },
+            vec!["bob".into()],
+        );
+        let (tx, mut rx) = mpsc::channel(4);
+        let listener = async move { ch.listen(tx).await };
+        let received = tokio::select! {
+            msg = rx.recv() => msg,
+            _ = listener => None,
+        };
+        let msg = received.expect("message from plugin process");
+        assert_eq!(msg.channel, "shellplug");
+        assert_eq!(msg.content, "from sh");
+    }
+}
@@ -0,0 +1,198 @@
+//! `[POLL:...]` reply markers.
+//!
+//! The agent asks a group to vote by adding `[POLL:Where to eat?|Pizza|Sushi]`
+//! to a reply. Channels with native polls (Telegram, Discord) send each
+//! marker as a poll and, when it closes, feed the tally back as a message
+//! from the person the poll was created for, so the conversation continues
+//! with the results.
+
+use super::traits::ChannelMessage;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Most options either platform accepts.
+pub const MAX_POLL_OPTIONS: usize = 10;
+/// Chats whose poll requester is remembered at once.
+const MAX_TRACKED_CHATS: usize = 1_000;
+
+/// One `[POLL:...]` marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poll {
+    pub question: String,
+    pub options: Vec<String>,
+}
+
+/// Extract `[POLL:question|opt1|opt2|...]` markers from a reply.
+///
+/// Blank options are dropped and at most [`MAX_POLL_OPTIONS`] are kept.
+/// Markers without a question or with fewer than two options are left in
+/// the text.
+pub fn parse_poll_markers(message: &str) -> (String, Vec<Poll>) {
+    let mut cleaned = String::with_capacity(message.len());
+    let mut polls = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(open_rel) = message[cursor..].find('[') {
+        let open = cursor + open_rel;
+        let Some(close_rel) = message[open..].find(']') else {
+            break;
+        };
+        let close = open + close_rel;
+        cleaned.push_str(&message[cursor..open]);
+
+        let poll = message[open + 1..close]
+            .split_once(':')
+            .filter(|(kind, _)| kind.trim().eq_ignore_ascii_case("POLL"))
+            .and_then(|(_, body)| {
+                let mut parts = body.split('|').map(str::trim);
+                let question = parts.next().filter(|q| !q.is_empty())?.to_string();
+                let options: Vec<String> = parts
+                    .filter(|option| !option.is_empty())
+                    .take(MAX_POLL_OPTIONS)
+                    .map(str::to_string)
+                    .collect();
+                (options.len() >= 2).then_some(Poll { question, options })
+            });
+
+        match poll {
+            Some(poll) => polls.push(poll),
+            None => cleaned.push_str(&message[open..=close]),
+        }
+        cursor = close + 1;
+    }
+    cleaned.push_str(&message[cursor..]);
+
+    if polls.is_empty() {
+        return (message.to_string(), polls);
+    }
+    (cleaned.trim().to_string(), polls)
+}
+
+/// Text of the message reporting a closed poll. `counts` pairs each option
+/// with its votes, in poll order.
+pub fn format_results(question: &str, counts: &[(String, u64)]) -> String {
+    let total: u64 = counts.iter().map(|(_, votes)| votes).sum();
+    let mut lines = vec![format!("[Poll closed] {question}")];
+    lines.extend(counts.iter().map(|(option, votes)| {
+        let unit = if *votes == 1 { "vote" } else { "votes" };
+        format!("- {option}: {votes} {unit}")
+    }));
+    lines.push(format!("Total votes: {total}"));
+    lines.join("\n")
+}
+
+type Requesters = HashMap<(String, String), String>;
+
+fn registry() -> &'static Mutex<Requesters> {
+    static REQUESTERS: OnceLock<Mutex<Requesters>> = OnceLock::new();
+    REQUESTERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Remember who a reply with polls answers, so the results reach the same
+/// conversation.
+pub fn record_requester(msg: &ChannelMessage) {
+    let mut requesters = registry().lock();
+    let key = (msg.channel.clone(), msg.reply_target.clone());
+    if !requesters.contains_key(&key) && requesters.len() >= MAX_TRACKED_CHATS {
+        if let Some(evicted) = requesters.keys().next().cloned() {
+            requesters.remove(&evicted);
+        }
+    }
+    requesters.insert(key, msg.sender.clone());
+}
+
+/// Message carrying the results of a poll sent to `reply_target`. The
+/// sender is the user the poll was created for, or the chat itself when
+/// unknown (e.g. after a restart).
+pub fn results_message(
+    channel: &str,
+    id: String,
+    reply_target: &str,
+    thread_ts: Option<String>,
+    content: String,
+) -> ChannelMessage {
+    let sender = registry()
+        .lock()
+        .get(&(channel.to_string(), reply_target.to_string()))
+        .cloned()
+        .unwrap_or_else(|| reply_target.to_string());
+    ChannelMessage {
+        id,
+        sender,
+        reply_target: reply_target.to_string(),
+        content,
+        channel: channel.to_string(),
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        thread_ts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_become_polls() {
+        let (cleaned, polls) =
+            parse_poll_markers("Let's decide. [POLL: Lunch? | Pizza | | Sushi ] [POLL:Only|one]");
+        assert_eq!(cleaned, "Let's decide.  [POLL:Only|one]");
+        assert_eq!(
+            polls,
+            vec![Poll {
+                question: "Lunch?".into(),
+                options: vec!["Pizza".into(), "Sushi".into()],
+            }]
+        );
+
+        let (cleaned, polls) = parse_poll_markers("no [BUTTONS:A|B] polls");
+        assert_eq!(cleaned, "no [BUTTONS:A|B] polls");
+        assert!(polls.is_empty());
+    }
+
+    #[test]
+    fn options_are_capped() {
+        let marker = format!(
+            "[POLL:Pick|{}]",
+            (1..=12)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join("|")
+        );
+        let (_, polls) = parse_poll_markers(&marker);
+        assert_eq!(polls[0].options.len(), MAX_POLL_OPTIONS);
+    }
+
+    #[test]
+    fn results_reach_the_requesting_conversation() {
+        let request = ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            reply_target: "polls_test_chat".into(),
+            content: "make a poll".into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+        };
+        record_requester(&request);
+
+        let content = format_results("Lunch?", &[("Pizza".into(), 1), ("Sushi".into(), 2)]);
+        assert_eq!(
+            content,
+            "[Poll closed] Lunch?\n- Pizza: 1 vote\n- Sushi: 2 votes\nTotal votes: 3"
+        );
+        let msg = results_message("telegram", "p1".into(), "polls_test_chat", None, content);
+        assert_eq!(msg.sender, "alice");
+        let msg = results_message(
+            "telegram",
+            "p2".into(),
+            "polls_test_other",
+            None,
+            String::new(),
+        );
+        assert_eq!(msg.sender, "polls_test_other");
+    }
+}
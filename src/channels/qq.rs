@@ -1,15 +1,27 @@
+use super::attachment::{is_local_path, parse_attachment_markers, Attachment, AttachmentKind};
+use super::split::split_message;
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use base64::Engine;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
 const QQ_API_BASE: &str = "https://api.sgroup.qq.com";
 const QQ_AUTH_URL: &str = "https://bots.qq.com/app/getAppAccessToken";
+/// Longer text messages are rejected by the QQ bot API.
+const QQ_MAX_MESSAGE_CHARS: usize = 2000;
+/// Local files larger than this are not uploaded.
+const QQ_MAX_UPLOAD_BYTES: u64 = 20 * 1024 * 1024;
+/// Replies within this window of the user's message are sent as passive
+/// replies (with `msg_id`), which QQ does not count against the much
+/// smaller active message quota.
+const QQ_PASSIVE_REPLY_WINDOW: Duration = Duration::from_secs(5 * 60);
 
 fn ensure_https(url: &str) -> anyhow::Result<()> {
     if !url.starts_with("https://") {
@@ -54,15 +66,43 @@ fn extract_image_marker_from_attachment(attachment: &serde_json::Value) -> Optio
         return None;
     }
 
-    Some(format!("[IMAGE:{url}]"))
+    // Guild attachments come without a scheme.
+    if url.starts_with("http://") || url.starts_with("https://") {
+        Some(format!("[IMAGE:{url}]"))
+    } else {
+        Some(format!("[IMAGE:https://{url}]"))
+    }
+}
+
+/// Remove `<@!id>` and `<@id>` mention tags from guild message text.
+fn strip_mentions(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("<@") {
+        out.push_str(&rest[..start]);
+        let tag = &rest[start + 2..];
+        let id = tag.strip_prefix('!').unwrap_or(tag);
+        match id.find('>') {
+            Some(end) if end > 0 && id[..end].chars().all(|c| c.is_ascii_digit()) => {
+                rest = &id[end + 1..];
+            }
+            _ => {
+                out.push_str("<@");
+                rest = tag;
+            }
+        }
+    }
+    out.push_str(rest);
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 fn compose_message_content(payload: &serde_json::Value) -> Option<String> {
-    let text = payload
-        .get("content")
-        .and_then(|c| c.as_str())
-        .unwrap_or("")
-        .trim();
+    let text = strip_mentions(
+        payload
+            .get("content")
+            .and_then(|c| c.as_str())
+            .unwrap_or(""),
+    );
 
     let image_markers: Vec<String> = payload
         .get("attachments")
@@ -84,12 +124,149 @@ fn compose_message_content(payload: &serde_json::Value) -> Option<String> {
     }
 
     if image_markers.is_empty() {
-        return Some(text.to_string());
+        return Some(text);
     }
 
     Some(format!("{text}\n\n{}", image_markers.join("\n")))
 }
 
+/// Where a reply goes, from the `reply_target` prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    /// Private chat with a user: `user:{openid}`.
+    User(String),
+    /// Group chat: `group:{group_openid}`.
+    Group(String),
+    /// Guild text channel: `channel:{channel_id}`.
+    Channel(String),
+    /// Guild direct message session: `dm:{guild_id}`.
+    Dm(String),
+}
+
+impl Target {
+    fn parse(recipient: &str) -> Self {
+        let id = |raw: &str| -> String {
+            raw.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+                .collect()
+        };
+        if let Some(group) = recipient.strip_prefix("group:") {
+            Self::Group(id(group))
+        } else if let Some(channel) = recipient.strip_prefix("channel:") {
+            Self::Channel(id(channel))
+        } else if let Some(guild) = recipient.strip_prefix("dm:") {
+            Self::Dm(id(guild))
+        } else {
+            Self::User(id(recipient.strip_prefix("user:").unwrap_or(recipient)))
+        }
+    }
+
+    /// Users and groups use the v2 API; guild channels and DMs the guild API.
+    fn is_guild(&self) -> bool {
+        matches!(self, Self::Channel(_) | Self::Dm(_))
+    }
+
+    fn messages_url(&self) -> String {
+        match self {
+            Self::User(id) => format!("{QQ_API_BASE}/v2/users/{id}/messages"),
+            Self::Group(id) => format!("{QQ_API_BASE}/v2/groups/{id}/messages"),
+            Self::Channel(id) => format!("{QQ_API_BASE}/channels/{id}/messages"),
+            Self::Dm(id) => format!("{QQ_API_BASE}/dms/{id}/messages"),
+        }
+    }
+
+    /// Rich media upload endpoint; guild messages attach images directly.
+    fn files_url(&self) -> Option<String> {
+        match self {
+            Self::User(id) => Some(format!("{QQ_API_BASE}/v2/users/{id}/files")),
+            Self::Group(id) => Some(format!("{QQ_API_BASE}/v2/groups/{id}/files")),
+            Self::Channel(_) | Self::Dm(_) => None,
+        }
+    }
+}
+
+/// `file_type` of the v2 rich media upload. QQ cannot send other files.
+fn media_file_type(kind: &AttachmentKind) -> Option<u8> {
+    match kind {
+        AttachmentKind::Image | AttachmentKind::Sticker | AttachmentKind::Animation => Some(1),
+        AttachmentKind::Video => Some(2),
+        AttachmentKind::Audio | AttachmentKind::Voice => Some(3),
+        AttachmentKind::Document => None,
+    }
+}
+
+/// A user message from a dispatch event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Inbound {
+    msg_id: String,
+    sender: String,
+    reply_target: String,
+    content: String,
+    /// Guild the message came from, so each guild gets its own session.
+    guild_id: Option<String>,
+}
+
+fn parse_dispatch(event_type: &str, d: &serde_json::Value) -> Option<Inbound> {
+    let str_field = |value: &serde_json::Value, key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let author = d.get("author").unwrap_or(&serde_json::Value::Null);
+
+    let (sender, reply_target, guild_id) = match event_type {
+        "C2C_MESSAGE_CREATE" => {
+            let openid = str_field(author, "user_openid")
+                .or_else(|| str_field(author, "id"))
+                .unwrap_or_else(|| "unknown".to_string());
+            let target = format!("user:{openid}");
+            (openid, target, None)
+        }
+        "GROUP_AT_MESSAGE_CREATE" => {
+            let member = str_field(author, "member_openid").unwrap_or_else(|| "unknown".into());
+            let group = str_field(d, "group_openid").unwrap_or_else(|| "unknown".into());
+            (member, format!("group:{group}"), None)
+        }
+        "AT_MESSAGE_CREATE" => {
+            let channel = str_field(d, "channel_id")?;
+            (
+                str_field(author, "id")?,
+                format!("channel:{channel}"),
+                str_field(d, "guild_id"),
+            )
+        }
+        "DIRECT_MESSAGE_CREATE" => {
+            // DMs live in a separate DM guild; `src_guild_id` is the guild
+            // the user started the conversation from.
+            let dm_guild = str_field(d, "guild_id")?;
+            (
+                str_field(author, "id")?,
+                format!("dm:{dm_guild}"),
+                str_field(d, "src_guild_id").or(Some(dm_guild)),
+            )
+        }
+        _ => return None,
+    };
+
+    Some(Inbound {
+        msg_id: str_field(d, "id").unwrap_or_default(),
+        sender,
+        reply_target,
+        content: compose_message_content(d)?,
+        guild_id,
+    })
+}
+
+/// The user message a reply answers, for passive replies.
+struct ReplyRef {
+    msg_id: String,
+    received: Instant,
+    /// Next `msg_seq`; QQ rejects a second reply with the same sequence.
+    seq: u32,
+}
+
 /// Deduplication set capacity — evict half of entries when full.
 const DEDUP_CAPACITY: usize = 10_000;
 
@@ -103,6 +280,8 @@ pub struct QQChannel {
     token_cache: Arc<RwLock<Option<(String, u64)>>>,
     /// Message deduplication set.
     dedup: Arc<RwLock<HashSet<String>>>,
+    /// Latest user message per reply target.
+    reply_refs: Arc<RwLock<HashMap<String, ReplyRef>>>,
 }
 
 impl QQChannel {
@@ -113,6 +292,7 @@ impl QQChannel {
             allowed_users,
             token_cache: Arc::new(RwLock::new(None)),
             dedup: Arc::new(RwLock::new(HashSet::new())),
+            reply_refs: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -240,47 +420,52 @@ impl QQChannel {
         dedup.insert(msg_id.to_string());
         false
     }
-}
 
-#[async_trait]
-impl Channel for QQChannel {
-    fn name(&self) -> &str {
-        "qq"
+    async fn remember_reply_ref(&self, reply_target: &str, msg_id: &str) {
+        if msg_id.is_empty() {
+            return;
+        }
+        let mut refs = self.reply_refs.write().await;
+        refs.retain(|_, r| r.received.elapsed() < QQ_PASSIVE_REPLY_WINDOW);
+        refs.insert(
+            reply_target.to_string(),
+            ReplyRef {
+                msg_id: msg_id.to_string(),
+                received: Instant::now(),
+                seq: 1,
+            },
+        );
     }
 
-    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        let token = self.get_token().await?;
+    /// `msg_id` and `msg_seq` for a passive reply to `reply_target`, or
+    /// `None` once the window has passed and the reply must be active.
+    async fn next_reply_ref(&self, reply_target: &str) -> Option<(String, u32)> {
+        let mut refs = self.reply_refs.write().await;
+        let reply = refs
+            .get_mut(reply_target)
+            .filter(|r| r.received.elapsed() < QQ_PASSIVE_REPLY_WINDOW)?;
+        let seq = reply.seq;
+        reply.seq += 1;
+        Some((reply.msg_id.clone(), seq))
+    }
 
-        // Determine if this is a group or private message based on recipient format
-        // Format: "user:{openid}" or "group:{group_openid}"
-        let (url, body) = if let Some(group_id) = message.recipient.strip_prefix("group:") {
-            (
-                format!("{QQ_API_BASE}/v2/groups/{group_id}/messages"),
-                json!({
-                    "content": &message.content,
-                    "msg_type": 0,
-                }),
-            )
-        } else {
-            let raw_uid = message
-                .recipient
-                .strip_prefix("user:")
-                .unwrap_or(&message.recipient);
-            let user_id: String = raw_uid
-                .chars()
-                .filter(|c| c.is_alphanumeric() || *c == '_')
-                .collect();
-            (
-                format!("{QQ_API_BASE}/v2/users/{user_id}/messages"),
-                json!({
-                    "content": &message.content,
-                    "msg_type": 0,
-                }),
-            )
-        };
+    /// Post one message, as a passive reply when possible.
+    async fn post_message(
+        &self,
+        token: &str,
+        target: &Target,
+        recipient: &str,
+        mut body: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        if let Some((msg_id, seq)) = self.next_reply_ref(recipient).await {
+            body["msg_id"] = json!(msg_id);
+            if !target.is_guild() {
+                body["msg_seq"] = json!(seq);
+            }
+        }
 
+        let url = target.messages_url();
         ensure_https(&url)?;
-
         let resp = self
             .http_client()
             .post(&url)
@@ -294,6 +479,171 @@ impl Channel for QQChannel {
             let err = resp.text().await.unwrap_or_default();
             anyhow::bail!("QQ send message failed ({status}): {err}");
         }
+        Ok(())
+    }
+
+    /// Upload a file for a user or group message and return its `file_info`.
+    async fn upload_media(
+        &self,
+        token: &str,
+        files_url: &str,
+        file_type: u8,
+        attachment: &Attachment,
+    ) -> anyhow::Result<String> {
+        let mut body = json!({ "file_type": file_type, "srv_send_msg": false });
+        if is_local_path(&attachment.target) {
+            body["file_data"] = json!(base64::engine::general_purpose::STANDARD
+                .encode(read_local_file(&attachment.target).await?));
+        } else {
+            body["url"] = json!(attachment.target);
+        }
+
+        ensure_https(files_url)?;
+        let resp = self
+            .http_client()
+            .post(files_url)
+            .header("Authorization", format!("QQBot {token}"))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("QQ media upload failed ({status}): {err}");
+        }
+
+        let data: serde_json::Value = resp.json().await?;
+        data.get("file_info")
+            .and_then(|f| f.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Missing file_info in QQ upload response"))
+    }
+
+    /// Post a local image to a guild channel or DM as multipart form data.
+    async fn post_guild_image_file(
+        &self,
+        token: &str,
+        target: &Target,
+        recipient: &str,
+        attachment: &Attachment,
+    ) -> anyhow::Result<()> {
+        let bytes = read_local_file(&attachment.target).await?;
+        let file_name = std::path::Path::new(&attachment.target)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("image.png")
+            .to_string();
+        let mut form = reqwest::multipart::Form::new().part(
+            "file_image",
+            reqwest::multipart::Part::bytes(bytes).file_name(file_name),
+        );
+        if let Some(caption) = attachment.alt.as_deref() {
+            form = form.text("content", caption.to_string());
+        }
+        if let Some((msg_id, _)) = self.next_reply_ref(recipient).await {
+            form = form.text("msg_id", msg_id);
+        }
+
+        let url = target.messages_url();
+        ensure_https(&url)?;
+        let resp = self
+            .http_client()
+            .post(&url)
+            .header("Authorization", format!("QQBot {token}"))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("QQ image upload failed ({status}): {err}");
+        }
+        Ok(())
+    }
+
+    async fn send_attachment(
+        &self,
+        token: &str,
+        target: &Target,
+        recipient: &str,
+        attachment: &Attachment,
+    ) -> anyhow::Result<()> {
+        let file_type = media_file_type(&attachment.kind);
+        match (target.files_url(), file_type) {
+            (Some(files_url), Some(file_type)) => {
+                let file_info = self
+                    .upload_media(token, &files_url, file_type, attachment)
+                    .await?;
+                let mut body = json!({ "msg_type": 7, "media": { "file_info": file_info } });
+                if let Some(caption) = attachment.alt.as_deref() {
+                    body["content"] = json!(caption);
+                }
+                self.post_message(token, target, recipient, body).await
+            }
+            (None, Some(1)) if is_local_path(&attachment.target) => {
+                self.post_guild_image_file(token, target, recipient, attachment)
+                    .await
+            }
+            (None, Some(1)) => {
+                let mut body = json!({ "image": attachment.target });
+                if let Some(caption) = attachment.alt.as_deref() {
+                    body["content"] = json!(caption);
+                }
+                self.post_message(token, target, recipient, body).await
+            }
+            _ if is_local_path(&attachment.target) => {
+                tracing::warn!(
+                    "QQ: cannot send {} attachment {}",
+                    attachment.kind.marker_name(),
+                    attachment.target
+                );
+                Ok(())
+            }
+            // Remote files QQ cannot attach are sent as a link.
+            _ => {
+                let body = json!({ "content": attachment.target, "msg_type": 0 });
+                self.post_message(token, target, recipient, body).await
+            }
+        }
+    }
+}
+
+async fn read_local_file(path: &str) -> anyhow::Result<Vec<u8>> {
+    let size = tokio::fs::metadata(path).await?.len();
+    if size > QQ_MAX_UPLOAD_BYTES {
+        anyhow::bail!("QQ attachment {path} exceeds {QQ_MAX_UPLOAD_BYTES} bytes");
+    }
+    Ok(tokio::fs::read(path).await?)
+}
+
+#[async_trait]
+impl Channel for QQChannel {
+    fn name(&self) -> &str {
+        "qq"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let token = self.get_token().await?;
+        let target = Target::parse(&message.recipient);
+        let (text, attachments) = parse_attachment_markers(&message.content);
+
+        if !text.is_empty() || attachments.is_empty() {
+            for chunk in split_message(&text, QQ_MAX_MESSAGE_CHARS) {
+                let body = json!({
+                    "content": chunk,
+                    "msg_type": 0,
+                });
+                self.post_message(&token, &target, &message.recipient, body)
+                    .await?;
+            }
+        }
+
+        for attachment in &attachments {
+            self.send_attachment(&token, &target, &message.recipient, attachment)
+                .await?;
+        }
 
         Ok(())
     }
@@ -324,7 +674,8 @@ impl Channel for QQChannel {
 
         // Send Identify (opcode 2)
         // Intents: PUBLIC_GUILD_MESSAGES (1<<30) | C2C_MESSAGE_CREATE & GROUP_AT_MESSAGE_CREATE (1<<25)
-        let intents: u64 = (1 << 25) | (1 << 30);
+        // | DIRECT_MESSAGE (1<<12)
+        let intents: u64 = (1 << 12) | (1 << 25) | (1 << 30);
         let identify = json!({
             "op": 2,
             "d": {
@@ -428,85 +779,39 @@ impl Channel for QQChannel {
                         None => continue,
                     };
 
-                    match event_type {
-                        "C2C_MESSAGE_CREATE" => {
-                            let msg_id = d.get("id").and_then(|i| i.as_str()).unwrap_or("");
-                            if self.is_duplicate(msg_id).await {
-                                continue;
-                            }
-
-                            let Some(content) = compose_message_content(d) else {
-                                continue;
-                            };
-
-                            let author_id = d.get("author").and_then(|a| a.get("id")).and_then(|i| i.as_str()).unwrap_or("unknown");
-                            // For QQ, user_openid is the identifier
-                            let user_openid = d.get("author").and_then(|a| a.get("user_openid")).and_then(|u| u.as_str()).unwrap_or(author_id);
-
-                            if !self.is_user_allowed(user_openid) {
-                                tracing::warn!("QQ: ignoring C2C message from unauthorized user: {user_openid}");
-                                continue;
-                            }
-
-                            let chat_id = format!("user:{user_openid}");
-
-                            let channel_msg = ChannelMessage {
-                                id: Uuid::new_v4().to_string(),
-                                sender: user_openid.to_string(),
-                                reply_target: chat_id,
-                                content,
-                                channel: "qq".to_string(),
-                                timestamp: std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap_or_default()
-                                    .as_secs(),
-                                thread_ts: None,
-                            };
-
-                            if tx.send(channel_msg).await.is_err() {
-                                tracing::warn!("QQ: message channel closed");
-                                break;
-                            }
-                        }
-                        "GROUP_AT_MESSAGE_CREATE" => {
-                            let msg_id = d.get("id").and_then(|i| i.as_str()).unwrap_or("");
-                            if self.is_duplicate(msg_id).await {
-                                continue;
-                            }
-
-                            let Some(content) = compose_message_content(d) else {
-                                continue;
-                            };
-
-                            let author_id = d.get("author").and_then(|a| a.get("member_openid")).and_then(|m| m.as_str()).unwrap_or("unknown");
-
-                            if !self.is_user_allowed(author_id) {
-                                tracing::warn!("QQ: ignoring group message from unauthorized user: {author_id}");
-                                continue;
-                            }
+                    let Some(inbound) = parse_dispatch(event_type, d) else {
+                        continue;
+                    };
+                    if self.is_duplicate(&inbound.msg_id).await {
+                        continue;
+                    }
+                    if !self.is_user_allowed(&inbound.sender) {
+                        tracing::warn!(
+                            "QQ: ignoring {event_type} from unauthorized user: {}",
+                            inbound.sender
+                        );
+                        continue;
+                    }
+                    self.remember_reply_ref(&inbound.reply_target, &inbound.msg_id)
+                        .await;
+
+                    let channel_msg = ChannelMessage {
+                        id: Uuid::new_v4().to_string(),
+                        sender: inbound.sender,
+                        reply_target: inbound.reply_target,
+                        content: inbound.content,
+                        channel: "qq".to_string(),
+                        timestamp: std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                        // Guild messages get one conversation per guild.
+                        thread_ts: inbound.guild_id,
+                    };
 
-                            let group_openid = d.get("group_openid").and_then(|g| g.as_str()).unwrap_or("unknown");
-                            let chat_id = format!("group:{group_openid}");
-
-                            let channel_msg = ChannelMessage {
-                                id: Uuid::new_v4().to_string(),
-                                sender: author_id.to_string(),
-                                reply_target: chat_id,
-                                content,
-                                channel: "qq".to_string(),
-                                timestamp: std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap_or_default()
-                                    .as_secs(),
-                                thread_ts: None,
-                            };
-
-                            if tx.send(channel_msg).await.is_err() {
-                                tracing::warn!("QQ: message channel closed");
-                                break;
-                            }
-                        }
-                        _ => {}
+                    if tx.send(channel_msg).await.is_err() {
+                        tracing::warn!("QQ: message channel closed");
+                        break;
                     }
                 }
             }
@@ -667,4 +972,92 @@ allowed_users = ["user1"]
 
         assert_eq!(compose_message_content(&payload), None);
     }
+
+    #[test]
+    fn strip_mentions_removes_mention_tags() {
+        assert_eq!(strip_mentions("<@!1234> hello  there"), "hello there");
+        assert_eq!(strip_mentions("hi <@56>!"), "hi !");
+        assert_eq!(strip_mentions("a <@name> b"), "a <@name> b");
+    }
+
+    #[test]
+    fn target_parse_and_urls() {
+        assert_eq!(Target::parse("user:ABC"), Target::User("ABC".into()));
+        assert_eq!(Target::parse("ABC/../x"), Target::User("ABCx".into()));
+        assert_eq!(Target::parse("group:G1"), Target::Group("G1".into()));
+        assert_eq!(
+            Target::parse("channel:42").messages_url(),
+            format!("{QQ_API_BASE}/channels/42/messages")
+        );
+        let dm = Target::parse("dm:7");
+        assert_eq!(dm.messages_url(), format!("{QQ_API_BASE}/dms/7/messages"));
+        assert!(dm.is_guild());
+        assert_eq!(dm.files_url(), None);
+        assert_eq!(
+            Target::parse("group:G1").files_url(),
+            Some(format!("{QQ_API_BASE}/v2/groups/G1/files"))
+        );
+    }
+
+    #[test]
+    fn parse_dispatch_handles_guild_channels_and_dms() {
+        let at = json!({
+            "id": "m1",
+            "channel_id": "42",
+            "guild_id": "g1",
+            "author": {"id": "u1"},
+            "content": "<@!999> status?",
+            "attachments": [{"content_type": "image/png", "url": "gchat.qpic.cn/a.png"}]
+        });
+        assert_eq!(
+            parse_dispatch("AT_MESSAGE_CREATE", &at),
+            Some(Inbound {
+                msg_id: "m1".into(),
+                sender: "u1".into(),
+                reply_target: "channel:42".into(),
+                content: "status?\n\n[IMAGE:https://gchat.qpic.cn/a.png]".into(),
+                guild_id: Some("g1".into()),
+            })
+        );
+
+        let dm = json!({
+            "id": "m2",
+            "guild_id": "dm-guild",
+            "src_guild_id": "g1",
+            "author": {"id": "u1"},
+            "content": "hi"
+        });
+        let inbound = parse_dispatch("DIRECT_MESSAGE_CREATE", &dm).unwrap();
+        assert_eq!(inbound.reply_target, "dm:dm-guild");
+        assert_eq!(inbound.guild_id.as_deref(), Some("g1"));
+
+        let group = json!({
+            "id": "m3",
+            "group_openid": "G1",
+            "author": {"member_openid": "M1"},
+            "content": "hello"
+        });
+        let inbound = parse_dispatch("GROUP_AT_MESSAGE_CREATE", &group).unwrap();
+        assert_eq!(inbound.reply_target, "group:G1");
+        assert_eq!(inbound.guild_id, None);
+
+        assert_eq!(parse_dispatch("READY", &at), None);
+    }
+
+    #[tokio::test]
+    async fn passive_replies_use_increasing_sequence() {
+        let ch = QQChannel::new("id".into(), "secret".into(), vec![]);
+        assert_eq!(ch.next_reply_ref("group:G1").await, None);
+
+        ch.remember_reply_ref("group:G1", "m1").await;
+        assert_eq!(
+            ch.next_reply_ref("group:G1").await,
+            Some(("m1".to_string(), 1))
+        );
+        assert_eq!(
+            ch.next_reply_ref("group:G1").await,
+            Some(("m1".to_string(), 2))
+        );
+        assert_eq!(ch.next_reply_ref("group:G2").await, None);
+    }
 }
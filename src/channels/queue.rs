@@ -0,0 +1,151 @@
+//! Per-conversation message queue for the channel dispatcher.
+//!
+//! A message arriving while its conversation is still being answered waits
+//! in that conversation's lane instead of starting a second, racing turn on
+//! the same history. The worker answering the conversation drains its lane
+//! in order and exits once it is empty; other conversations keep their own
+//! workers and run concurrently. With a merge window, the worker waits
+//! briefly before each turn and answers everything queued by then as one
+//! prompt, so "hi" / "quick question" / "what's the weather?" gets a single
+//! reply.
+
+use super::traits::ChannelMessage;
+use crate::config::ChannelQueueConfig;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Queued messages per conversation, keyed by conversation history key.
+pub struct ConversationQueues {
+    lanes: Mutex<HashMap<String, VecDeque<ChannelMessage>>>,
+    merge_window: Option<Duration>,
+}
+
+impl ConversationQueues {
+    /// Returns `None` when `sequential` is off.
+    pub fn from_config(config: &ChannelQueueConfig) -> Option<Self> {
+        config.sequential.then(|| Self {
+            lanes: Mutex::new(HashMap::new()),
+            merge_window: (config.merge_window_ms > 0)
+                .then(|| Duration::from_millis(config.merge_window_ms)),
+        })
+    }
+
+    /// Queue `msg` under `key`. Returns `true` when the lane was idle and
+    /// the caller must start a worker that drains it with [`Self::next`].
+    pub fn push(&self, key: &str, msg: ChannelMessage) -> bool {
+        let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+        match lanes.get_mut(key) {
+            Some(lane) => {
+                lane.push_back(msg);
+                false
+            }
+            None => {
+                lanes.insert(key.to_string(), VecDeque::from([msg]));
+                true
+            }
+        }
+    }
+
+    /// Next message to answer for `key`, or `None` once the lane is empty,
+    /// in which case the lane is closed and the worker must exit.
+    ///
+    /// In merge mode, waits for the merge window first and joins all queued
+    /// messages up to the next one `is_command` matches; commands are never
+    /// merged so they still reach the command handler verbatim.
+    pub async fn next(
+        &self,
+        key: &str,
+        is_command: impl Fn(&ChannelMessage) -> bool,
+    ) -> Option<ChannelMessage> {
+        if let Some(window) = self.merge_window {
+            tokio::time::sleep(window).await;
+        }
+
+        let mut lanes = self.lanes.lock().unwrap_or_else(|e| e.into_inner());
+        let lane = lanes.get_mut(key)?;
+        let Some(mut msg) = lane.pop_front() else {
+            lanes.remove(key);
+            return None;
+        };
+        if self.merge_window.is_some() && !is_command(&msg) {
+            while lane.front().is_some_and(|queued| !is_command(queued)) {
+                let Some(queued) = lane.pop_front() else {
+                    break;
+                };
+                msg = merge(msg, queued);
+            }
+        }
+        Some(msg)
+    }
+}
+
+/// Join two messages of one conversation; the later one supplies the ID,
+/// timestamp and thread the reply refers to.
+fn merge(earlier: ChannelMessage, later: ChannelMessage) -> ChannelMessage {
+    ChannelMessage {
+        content: format!("{}\n\n{}", earlier.content.trim_end(), later.content.trim()),
+        ..later
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.into(),
+            sender: "alice".into(),
+            reply_target: "alice".into(),
+            content: content.into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+        }
+    }
+
+    fn queues(merge_window_ms: u64) -> ConversationQueues {
+        ConversationQueues::from_config(&ChannelQueueConfig {
+            sequential: true,
+            merge_window_ms,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn lane_drains_in_order_then_closes() {
+        let queues = queues(0);
+        assert!(queues.push("a", message("1", "first")));
+        assert!(!queues.push("a", message("2", "second")));
+        assert!(queues.push("b", message("3", "other")));
+
+        assert_eq!(queues.next("a", |_| false).await.unwrap().id, "1");
+        assert_eq!(queues.next("a", |_| false).await.unwrap().id, "2");
+        assert!(queues.next("a", |_| false).await.is_none());
+        // Closed lanes start a new worker on the next message.
+        assert!(queues.push("a", message("4", "again")));
+        assert!(ConversationQueues::from_config(&ChannelQueueConfig {
+            sequential: false,
+            merge_window_ms: 0,
+        })
+        .is_none());
+    }
+
+    #[tokio::test]
+    async fn merge_mode_joins_rapid_messages_but_not_commands() {
+        let queues = queues(1);
+        queues.push("a", message("1", "hi"));
+        queues.push("a", message("2", "what's the weather?"));
+        queues.push("a", message("3", "/new"));
+        queues.push("a", message("4", "in Paris"));
+        let is_command = |msg: &ChannelMessage| msg.content.starts_with('/');
+
+        let merged = queues.next("a", is_command).await.unwrap();
+        assert_eq!(merged.id, "2");
+        assert_eq!(merged.content, "hi\n\nwhat's the weather?");
+        assert_eq!(queues.next("a", is_command).await.unwrap().content, "/new");
+        assert_eq!(queues.next("a", is_command).await.unwrap().id, "4");
+        assert!(queues.next("a", is_command).await.is_none());
+    }
+}
@@ -0,0 +1,176 @@
+//! Outbound rate limiting shared by all channel instances.
+//!
+//! Platforms ban or throttle bots that post faster than their documented
+//! limits, which a long reply split into chunks or a batch of attachments
+//! can easily do. Channels call [`acquire`] before every request that posts
+//! a message; it waits until both the platform-wide and the per-chat token
+//! bucket have a token. Buckets live in one process-wide registry so the
+//! gateway, cron delivery and the channel listeners share the same budget.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Per-chat buckets kept before idle, refilled ones are pruned.
+const MAX_BUCKETS: usize = 1024;
+
+/// A token bucket: up to `burst` messages at once, refilled at `per_sec`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rule {
+    burst: f64,
+    per_sec: f64,
+}
+
+impl Rule {
+    const fn new(burst: f64, per_sec: f64) -> Self {
+        Self { burst, per_sec }
+    }
+}
+
+/// Documented limits: `(platform-wide, per-chat)`.
+///
+/// - Telegram: 30 messages/s per bot, about 1/s in one chat and 20/min in a
+///   group (negative chat IDs).
+/// - Discord: 50 requests/s per bot, 5 messages per 5 s in one channel.
+/// - Slack: `chat.postMessage` allows 1 message/s per channel.
+fn rules(platform: &str, chat: &str) -> (Option<Rule>, Option<Rule>) {
+    match platform {
+        "telegram" if chat.starts_with('-') => (
+            Some(Rule::new(30.0, 30.0)),
+            Some(Rule::new(3.0, 20.0 / 60.0)),
+        ),
+        "telegram" => (Some(Rule::new(30.0, 30.0)), Some(Rule::new(3.0, 1.0))),
+        "discord" => (Some(Rule::new(50.0, 50.0)), Some(Rule::new(5.0, 1.0))),
+        "slack" => (None, Some(Rule::new(1.0, 1.0))),
+        _ => (None, None),
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    rule: Rule,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(rule: Rule, now: Instant) -> Self {
+        Self {
+            rule,
+            tokens: rule.burst,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rule.per_sec).min(self.rule.burst);
+        self.updated = now;
+    }
+
+    /// Take a token and return how long the caller must wait before using
+    /// it. The balance goes negative while callers are waiting, so later
+    /// callers queue behind earlier ones.
+    fn reserve(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rule.per_sec)
+        }
+    }
+
+    fn is_idle(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.rule.burst
+    }
+}
+
+/// Buckets keyed by `(platform, chat)`; the platform-wide bucket uses an
+/// empty chat.
+type Buckets = HashMap<(String, String), Bucket>;
+
+fn registry() -> &'static Mutex<Buckets> {
+    static BUCKETS: OnceLock<Mutex<Buckets>> = OnceLock::new();
+    BUCKETS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn reserve(buckets: &mut Buckets, platform: &str, chat: &str, now: Instant) -> Duration {
+    let (global, per_chat) = rules(platform, chat);
+    let mut wait = Duration::ZERO;
+    for (rule, key) in [(global, ""), (per_chat, chat)] {
+        if let Some(rule) = rule {
+            let bucket = buckets
+                .entry((platform.to_string(), key.to_string()))
+                .or_insert_with(|| Bucket::new(rule, now));
+            wait = wait.max(bucket.reserve(now));
+        }
+    }
+    if buckets.len() > MAX_BUCKETS {
+        buckets.retain(|_, bucket| !bucket.is_idle(now));
+    }
+    wait
+}
+
+/// Wait until `platform` allows another message to `chat`. Platforms
+/// without known limits return immediately.
+pub async fn acquire(platform: &str, chat: &str) {
+    let wait = reserve(&mut registry().lock(), platform, chat, Instant::now());
+    if !wait.is_zero() {
+        tracing::debug!("Rate limiting {platform} message to {chat} for {wait:?}");
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_then_spaces_messages() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(Rule::new(2.0, 1.0), now);
+
+        assert_eq!(bucket.reserve(now), Duration::ZERO);
+        assert_eq!(bucket.reserve(now), Duration::ZERO);
+        assert_eq!(bucket.reserve(now), Duration::from_secs(1));
+        assert_eq!(bucket.reserve(now), Duration::from_secs(2));
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert!(!bucket.is_idle(later));
+        assert!(bucket.is_idle(later + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn chats_are_limited_independently_under_the_platform_limit() {
+        let now = Instant::now();
+        let mut buckets = Buckets::new();
+
+        assert_eq!(reserve(&mut buckets, "slack", "C1", now), Duration::ZERO);
+        assert_eq!(
+            reserve(&mut buckets, "slack", "C1", now),
+            Duration::from_secs(1)
+        );
+        assert_eq!(reserve(&mut buckets, "slack", "C2", now), Duration::ZERO);
+        assert_eq!(
+            reserve(&mut buckets, "matrix", "!room", now),
+            Duration::ZERO
+        );
+        assert_eq!(buckets.len(), 2);
+
+        // Telegram groups get the slower per-chat rule.
+        for _ in 0..3 {
+            assert_eq!(
+                reserve(&mut buckets, "telegram", "-100", now),
+                Duration::ZERO
+            );
+        }
+        assert_eq!(
+            reserve(&mut buckets, "telegram", "-100", now),
+            Duration::from_secs(3)
+        );
+    }
+}
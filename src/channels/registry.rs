@@ -0,0 +1,145 @@
+//! Channels registered by downstream crates.
+//!
+//! A crate embedding zeroclaw can add its own messaging platform without
+//! forking: implement [`Channel`], wrap its construction in a
+//! [`ChannelFactory`] and call [`register_channel`] before
+//! [`start_channels`](super::start_channels) or the daemon starts. The
+//! factory receives the channel's `[channels_config.custom.<name>]` table,
+//! so custom channels are configured in `config.toml` like built-in ones:
+//!
+//! ```toml
+//! [channels_config.custom.intranet]
+//! url = "https://chat.intranet.example"
+//! token = "..."
+//! ```
+//!
+//! Registered channels go through the same dispatcher, ACLs, history and
+//! tool loop as built-in channels.
+
+use super::traits::Channel;
+use crate::config::Config;
+use parking_lot::Mutex;
+use std::sync::{Arc, OnceLock};
+
+/// Builds an out-of-tree channel from configuration.
+pub trait ChannelFactory: Send + Sync {
+    /// Channel name, used as the `[channels_config.custom.<name>]` key and
+    /// to route replies. Must match the built channel's [`Channel::name`].
+    fn name(&self) -> &str;
+
+    /// Build the channel from its `[channels_config.custom.<name>]` table
+    /// (`None` when absent). Return `Ok(None)` to stay disabled.
+    fn build(
+        &self,
+        settings: Option<&serde_json::Value>,
+        config: &Config,
+    ) -> anyhow::Result<Option<Arc<dyn Channel>>>;
+}
+
+fn registry() -> &'static Mutex<Vec<Arc<dyn ChannelFactory>>> {
+    static FACTORIES: OnceLock<Mutex<Vec<Arc<dyn ChannelFactory>>>> = OnceLock::new();
+    FACTORIES.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Register a channel factory. Returns `false` when a factory with the same
+/// name is already registered.
+pub fn register_channel(factory: impl ChannelFactory + 'static) -> bool {
+    let mut factories = registry().lock();
+    if factories.iter().any(|f| f.name() == factory.name()) {
+        return false;
+    }
+    factories.push(Arc::new(factory));
+    true
+}
+
+/// Names of all registered factories.
+pub fn registered_channels() -> Vec<String> {
+    registry()
+        .lock()
+        .iter()
+        .map(|f| f.name().to_string())
+        .collect()
+}
+
+/// Build every registered channel that is enabled for `config`. Factories
+/// that fail are logged and skipped.
+pub(super) fn build_registered_channels(config: &Config) -> Vec<Arc<dyn Channel>> {
+    let factories = registry().lock().clone();
+    factories
+        .iter()
+        .filter_map(|factory| {
+            let name = factory.name();
+            let settings = config.channels_config.custom.get(name);
+            match factory.build(settings, config) {
+                Ok(channel) => channel,
+                Err(e) => {
+                    tracing::warn!("Skipping custom channel '{name}': {e}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::{ChannelMessage, SendMessage};
+    use async_trait::async_trait;
+
+    struct EchoChannel;
+
+    #[async_trait]
+    impl Channel for EchoChannel {
+        fn name(&self) -> &str {
+            "registry_test_echo"
+        }
+
+        async fn send(&self, _message: &SendMessage) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct EchoFactory;
+
+    impl ChannelFactory for EchoFactory {
+        fn name(&self) -> &str {
+            "registry_test_echo"
+        }
+
+        fn build(
+            &self,
+            settings: Option<&serde_json::Value>,
+            _config: &Config,
+        ) -> anyhow::Result<Option<Arc<dyn Channel>>> {
+            Ok(settings.map(|_| Arc::new(EchoChannel) as Arc<dyn Channel>))
+        }
+    }
+
+    #[test]
+    fn registered_factory_builds_only_when_configured() {
+        assert!(register_channel(EchoFactory));
+        assert!(!register_channel(EchoFactory));
+        assert!(registered_channels().contains(&"registry_test_echo".to_string()));
+
+        let mut config = Config::default();
+        assert!(build_registered_channels(&config)
+            .iter()
+            .all(|c| c.name() != "registry_test_echo"));
+
+        config
+            .channels_config
+            .custom
+            .insert("registry_test_echo".into(), serde_json::json!({}));
+        assert!(build_registered_channels(&config)
+            .iter()
+            .any(|c| c.name() == "registry_test_echo"));
+    }
+}
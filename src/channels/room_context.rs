@@ -0,0 +1,144 @@
+//! Recent group chat messages as context for a reply.
+//!
+//! The agent only sees the messages addressed to it. With
+//! `[channels_config.room_context]` enabled, the messages posted in the chat
+//! before the one being answered are fetched through the channel's history
+//! API ([`Channel::recent_messages`]) and prepended to the user turn, so
+//! "summarize the discussion above" works without having observed it.
+
+use super::draft::truncate_chars;
+use super::traits::{Channel, ChannelMessage};
+use crate::config::RoomContextConfig;
+use std::time::Duration;
+
+/// Fetches and formats recent chat messages.
+#[derive(Debug, Clone)]
+pub struct RoomContext {
+    messages: usize,
+    max_chars: usize,
+    channels: Vec<String>,
+}
+
+impl RoomContext {
+    /// Returns `None` while `messages` is `0`.
+    pub fn from_config(config: &RoomContextConfig) -> Option<Self> {
+        (config.messages > 0).then(|| Self {
+            messages: config.messages,
+            max_chars: config.max_chars_per_message.max(1),
+            channels: config.channels.clone(),
+        })
+    }
+
+    fn applies_to(&self, channel: &str) -> bool {
+        self.channels.is_empty() || self.channels.iter().any(|c| c == channel)
+    }
+
+    /// Context block for `msg`, or an empty string when the channel has no
+    /// history, the fetch fails or it takes longer than `timeout`.
+    pub async fn fetch(
+        &self,
+        channel: &dyn Channel,
+        msg: &ChannelMessage,
+        timeout: Duration,
+    ) -> String {
+        if !self.applies_to(&msg.channel) {
+            return String::new();
+        }
+        match tokio::time::timeout(timeout, channel.recent_messages(msg, self.messages)).await {
+            Ok(Ok(messages)) => self.format(&messages, msg),
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to fetch recent {} messages: {e}", msg.channel);
+                String::new()
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "Fetching recent {} messages exceeded {}ms; continuing without them",
+                    msg.channel,
+                    timeout.as_millis()
+                );
+                String::new()
+            }
+        }
+    }
+
+    fn format(&self, messages: &[ChannelMessage], current: &ChannelMessage) -> String {
+        let lines: Vec<String> = messages
+            .iter()
+            .filter(|m| m.id != current.id && !m.content.trim().is_empty())
+            .map(|m| {
+                let content = m.content.trim().replace('\n', " ");
+                format!(
+                    "- {}: {}",
+                    m.sender,
+                    truncate_chars(&content, self.max_chars)
+                )
+            })
+            .collect();
+        let skip = lines.len().saturating_sub(self.messages);
+        if lines.len() == skip {
+            return String::new();
+        }
+        format!(
+            "[Recent messages in this chat, oldest first]\n{}\n\n",
+            lines[skip..].join("\n")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, sender: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.into(),
+            sender: sender.into(),
+            reply_target: "C1".into(),
+            content: content.into(),
+            channel: "slack".into(),
+            timestamp: 0,
+            thread_ts: None,
+        }
+    }
+
+    fn context(messages: usize, channels: &[&str]) -> Option<RoomContext> {
+        RoomContext::from_config(&RoomContextConfig {
+            messages,
+            max_chars_per_message: 10,
+            channels: channels.iter().map(|c| (*c).to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn from_config_is_disabled_without_messages() {
+        assert!(context(0, &[]).is_none());
+        assert!(context(5, &[]).is_some());
+    }
+
+    #[test]
+    fn format_keeps_the_latest_messages_and_truncates() {
+        let room = context(2, &[]).unwrap();
+        let current = message("m4", "alice", "summarize");
+        let formatted = room.format(
+            &[
+                message("m1", "bob", "first"),
+                message("m2", "carol", "second\nline that is long"),
+                message("m3", "dave", "  "),
+                message("m5", "erin", "third"),
+                current.clone(),
+            ],
+            &current,
+        );
+        assert!(!formatted.contains("- bob:"));
+        assert!(formatted.contains("- carol: second lin"));
+        assert!(formatted.ends_with("- erin: third\n\n"));
+        assert_eq!(room.format(std::slice::from_ref(&current), &current), "");
+    }
+
+    #[test]
+    fn applies_only_to_listed_channels() {
+        let room = context(5, &["discord"]).unwrap();
+        assert!(room.applies_to("discord"));
+        assert!(!room.applies_to("slack"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Messages the agent schedules for later delivery.
+//!
+//! A reply may promise a follow-up ("I'll remind you tomorrow at 9") by
+//! including `[SCHEDULE:<time>|<message>]`. The marker is removed from the
+//! reply and the message is stored as a one-shot cron job of type
+//! `message`, delivered to the same chat at that time. Jobs live in the
+//! cron database, so they survive restarts; they are sent by the cron
+//! scheduler of the running daemon.
+
+use super::traits::ChannelMessage;
+use crate::config::Config;
+use crate::cron::{self, DeliveryConfig, Schedule};
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+const SCHEDULE_MARKER_PREFIX: &str = "[SCHEDULE:";
+
+/// Added to the system prompt on channels that support scheduled messages.
+pub const SCHEDULE_INSTRUCTIONS: &str =
+    "To send a message to this chat later (a reminder or follow-up you promised), \
+     add [SCHEDULE:<time>|<message>] to your reply. <time> is an RFC3339 time with offset \
+     (e.g. 2024-07-01T09:00:00+02:00) or a delay such as 30m, 2h or 1d. The message is sent \
+     as written at that time, even after a restart. Tell the user what you scheduled.";
+
+/// One `[SCHEDULE:...]` marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledMessage {
+    pub at: DateTime<Utc>,
+    pub text: String,
+}
+
+/// Parse `<time>`: RFC3339, a UTC date-time without offset, or a delay.
+fn parse_time(raw: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(raw) {
+        return Some(at.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(at) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(at.and_utc());
+        }
+    }
+    cron::parse_delay(raw).ok().map(|delay| now + delay)
+}
+
+/// Remove `[SCHEDULE:...]` markers from a reply. Returns the cleaned text
+/// and, per marker, the message or why it could not be scheduled.
+pub fn extract_schedule_markers(
+    response: &str,
+    now: DateTime<Utc>,
+) -> (String, Vec<Result<ScheduledMessage, String>>) {
+    let mut cleaned = String::with_capacity(response.len());
+    let mut scheduled = Vec::new();
+    let mut rest = response;
+
+    while let Some(start) = rest.find(SCHEDULE_MARKER_PREFIX) {
+        let body_start = start + SCHEDULE_MARKER_PREFIX.len();
+        let Some(len) = rest[body_start..].find(']') else {
+            break;
+        };
+        cleaned.push_str(&rest[..start]);
+        let body = &rest[body_start..body_start + len];
+        rest = &rest[body_start + len + 1..];
+
+        let Some((time, text)) = body.split_once('|') else {
+            scheduled.push(Err(format!("missing message in [SCHEDULE:{body}]")));
+            continue;
+        };
+        let text = text.trim();
+        scheduled.push(match parse_time(time, now) {
+            _ if text.is_empty() => Err(format!("missing message in [SCHEDULE:{body}]")),
+            Some(at) if at > now => Ok(ScheduledMessage {
+                at,
+                text: text.to_string(),
+            }),
+            Some(_) => Err(format!("{} is in the past", time.trim())),
+            None => Err(format!("unrecognized time '{}'", time.trim())),
+        });
+    }
+    cleaned.push_str(rest);
+
+    if scheduled.is_empty() {
+        return (response.to_string(), scheduled);
+    }
+    (cleaned.trim().to_string(), scheduled)
+}
+
+/// Stores scheduled messages as cron jobs.
+pub struct MessageScheduler {
+    config: Config,
+}
+
+impl MessageScheduler {
+    /// Returns `None` when cron is disabled.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config.cron.enabled.then(|| Self {
+            config: config.clone(),
+        })
+    }
+
+    /// Whether scheduled messages can be delivered on `channel`.
+    pub fn supports(&self, channel: &str) -> bool {
+        cron::scheduler::supports_announcement(channel)
+    }
+
+    /// Schedule the markers in a reply to `msg` and return the reply without
+    /// them. Markers that fail are reported in the reply so the user is not
+    /// promised a message that will never come; in `preview` mode nothing
+    /// is stored.
+    pub fn apply(&self, msg: &ChannelMessage, response: &str, preview: bool) -> String {
+        let (mut cleaned, scheduled) = extract_schedule_markers(response, Utc::now());
+        for result in scheduled {
+            let note = match result {
+                Ok(message) if preview => format!(
+                    "🗓 Would schedule for {}: {}",
+                    message.at.to_rfc3339(),
+                    message.text
+                ),
+                Ok(message) => match self.schedule(msg, &message) {
+                    Ok(()) => continue,
+                    Err(e) => {
+                        tracing::warn!("Failed to schedule message for {}: {e}", msg.channel);
+                        format!("⚠️ Could not schedule \"{}\": {e}", message.text)
+                    }
+                },
+                Err(reason) => format!("⚠️ Could not schedule a message: {reason}"),
+            };
+            if !cleaned.is_empty() {
+                cleaned.push_str("\n\n");
+            }
+            cleaned.push_str(&note);
+        }
+        cleaned
+    }
+
+    fn schedule(&self, msg: &ChannelMessage, message: &ScheduledMessage) -> anyhow::Result<()> {
+        let delivery = DeliveryConfig {
+            mode: "announce".to_string(),
+            channel: Some(msg.channel.clone()),
+            to: Some(msg.reply_target.clone()),
+            best_effort: false,
+        };
+        let job = cron::add_message_job(
+            &self.config,
+            Some(format!("scheduled message for {}", msg.sender)),
+            Schedule::At { at: message.at },
+            &message.text,
+            delivery,
+        )?;
+        tracing::info!(
+            job_id = %job.id,
+            channel = %msg.channel,
+            at = %message.at.to_rfc3339(),
+            "Scheduled channel message"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 30, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn extracts_markers_with_absolute_and_relative_times() {
+        let (cleaned, scheduled) = extract_schedule_markers(
+            "Sure, I'll remind you.\n[SCHEDULE:2024-07-01T09:00:00+02:00|Stand-up in 5 minutes]\n[SCHEDULE:2h|Check the oven]",
+            now(),
+        );
+        assert_eq!(cleaned, "Sure, I'll remind you.");
+        assert_eq!(
+            scheduled,
+            vec![
+                Ok(ScheduledMessage {
+                    at: Utc.with_ymd_and_hms(2024, 7, 1, 7, 0, 0).unwrap(),
+                    text: "Stand-up in 5 minutes".into(),
+                }),
+                Ok(ScheduledMessage {
+                    at: now() + chrono::Duration::hours(2),
+                    text: "Check the oven".into(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_and_past_markers() {
+        let (cleaned, scheduled) = extract_schedule_markers(
+            "ok [SCHEDULE:2024-07-01T09:00] [SCHEDULE:2020-01-01T00:00|late] [SCHEDULE:soon|x]",
+            now(),
+        );
+        assert_eq!(cleaned, "ok");
+        assert!(scheduled.iter().all(Result::is_err));
+        assert_eq!(scheduled.len(), 3);
+    }
+
+    #[test]
+    fn replies_without_markers_are_unchanged() {
+        let (cleaned, scheduled) = extract_schedule_markers("  plain [reply]  ", now());
+        assert_eq!(cleaned, "  plain [reply]  ");
+        assert!(scheduled.is_empty());
+    }
+
+    #[test]
+    fn apply_stores_a_one_shot_message_job() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        let scheduler = MessageScheduler::from_config(&config).unwrap();
+        let msg = ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            reply_target: "12345".into(),
+            content: "remind me".into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+        };
+
+        let reply = scheduler.apply(&msg, "Will do. [SCHEDULE:1d|Call the bank]", false);
+        assert_eq!(reply, "Will do.");
+        let jobs = cron::list_jobs(&config).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].prompt.as_deref(), Some("Call the bank"));
+        assert_eq!(jobs[0].delivery.channel.as_deref(), Some("telegram"));
+        assert_eq!(jobs[0].delivery.to.as_deref(), Some("12345"));
+
+        let preview = scheduler.apply(&msg, "[SCHEDULE:1d|Call the bank]", true);
+        assert!(preview.starts_with("🗓 Would schedule for "));
+        assert_eq!(cron::list_jobs(&config).unwrap().len(), 1);
+    }
+}
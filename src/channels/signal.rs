@@ -1,5 +1,10 @@
+use crate::channels::attachment::{
+    is_local_path, parse_attachment_markers, sanitize_file_name, AttachmentKind,
+};
+use crate::channels::markdown::{self, Dialect};
 use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use base64::Engine;
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde::Deserialize;
@@ -8,6 +13,10 @@ use tokio::sync::mpsc;
 use uuid::Uuid;
 
 const GROUP_TARGET_PREFIX: &str = "group:";
+/// Placeholder signal-cli puts in the message text where a mention sits.
+const MENTION_PLACEHOLDER: char = '\u{FFFC}';
+/// Largest inbound attachment saved to the workspace.
+const SIGNAL_MAX_DOWNLOAD_BYTES: usize = 20 * 1024 * 1024;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum RecipientTarget {
@@ -28,6 +37,10 @@ pub struct SignalChannel {
     allowed_from: Vec<String>,
     ignore_attachments: bool,
     ignore_stories: bool,
+    mention_only: bool,
+    trigger_prefix: Option<String>,
+    transcription: Option<crate::config::TranscriptionConfig>,
+    workspace_dir: Option<std::path::PathBuf>,
 }
 
 // ── signal-cli SSE event JSON shapes ────────────────────────────
@@ -62,6 +75,21 @@ struct DataMessage {
     group_info: Option<GroupInfo>,
     #[serde(default)]
     attachments: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
+    mentions: Vec<Mention>,
+}
+
+/// A mention in a data message; `start` is a UTF-16 offset into the text.
+#[derive(Debug, Deserialize)]
+struct Mention {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    number: Option<String>,
+    #[serde(default)]
+    uuid: Option<String>,
+    #[serde(default)]
+    start: usize,
 }
 
 #[derive(Debug, Deserialize)]
@@ -87,7 +115,35 @@ impl SignalChannel {
             allowed_from,
             ignore_attachments,
             ignore_stories,
+            mention_only: false,
+            trigger_prefix: None,
+            transcription: None,
+            workspace_dir: None,
+        }
+    }
+
+    /// Configure the group triggers (see [`crate::channels::trigger`]):
+    /// `mention_only` answers group messages that mention the account,
+    /// `trigger_prefix` those that start with the prefix.
+    pub fn with_triggers(mut self, mention_only: bool, trigger_prefix: Option<String>) -> Self {
+        self.mention_only = mention_only;
+        self.trigger_prefix = crate::channels::trigger::normalize_prefix(trigger_prefix);
+        self
+    }
+
+    /// Configure workspace directory for saving inbound attachments.
+    pub fn with_workspace_dir(mut self, dir: std::path::PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
+    /// Configure voice transcription for inbound voice notes and audio
+    /// attachments. Has no effect when `ignore_attachments` is set.
+    pub fn with_transcription(mut self, config: crate::config::TranscriptionConfig) -> Self {
+        if config.enabled {
+            self.transcription = Some(config);
         }
+        self
     }
 
     fn http_client(&self) -> Client {
@@ -167,6 +223,253 @@ impl SignalChannel {
         }
     }
 
+    /// Replace the placeholders signal-cli leaves for mentions with `@name`.
+    /// Mentions of `account` (the bot) are removed. Returns the text and
+    /// whether the bot was mentioned.
+    fn resolve_mentions(text: &str, mentions: &[Mention], account: &str) -> (String, bool) {
+        let mut resolved = String::with_capacity(text.len());
+        let mut mentions_bot = false;
+        let mut offset = 0;
+        for c in text.chars() {
+            let mention = if c == MENTION_PLACEHOLDER {
+                mentions.iter().find(|m| m.start == offset)
+            } else {
+                None
+            };
+            offset += c.len_utf16();
+            match mention {
+                Some(m) if m.number.as_deref() == Some(account) => mentions_bot = true,
+                Some(m) => {
+                    let name = m
+                        .name
+                        .as_deref()
+                        .or(m.number.as_deref())
+                        .or(m.uuid.as_deref())
+                        .unwrap_or("unknown");
+                    resolved.push('@');
+                    resolved.push_str(name);
+                }
+                None => resolved.push(c),
+            }
+        }
+        if mentions_bot {
+            resolved = resolved.trim().to_string();
+        }
+        (resolved, mentions_bot)
+    }
+
+    /// Message text with mentions resolved, or `None` when a group message
+    /// does not match the configured triggers. Direct messages always pass.
+    fn triggered_text(&self, data_msg: &DataMessage) -> Option<String> {
+        let (text, mentions_bot) = Self::resolve_mentions(
+            data_msg.message.as_deref().unwrap_or_default(),
+            &data_msg.mentions,
+            &self.account,
+        );
+        if data_msg.group_info.is_none() || (!self.mention_only && self.trigger_prefix.is_none()) {
+            return Some(text);
+        }
+        if let Some(prefixed) = self
+            .trigger_prefix
+            .as_deref()
+            .and_then(|prefix| crate::channels::trigger::strip_trigger_prefix(&text, prefix))
+        {
+            return Some(prefixed);
+        }
+        (self.mention_only && mentions_bot).then_some(text)
+    }
+
+    /// Whether the channel handles `envelope` at all: not a skipped story,
+    /// from an allowed sender, in the configured group and, in groups,
+    /// matching the triggers. Returns the data message and sender.
+    fn accepted<'a>(&self, envelope: &'a Envelope) -> Option<(&'a DataMessage, String)> {
+        if self.ignore_stories && envelope.story_message.is_some() {
+            return None;
+        }
+        let data_msg = envelope.data_message.as_ref()?;
+        let sender = Self::sender(envelope)?;
+        if !self.is_sender_allowed(&sender)
+            || !self.matches_group(data_msg)
+            || self.triggered_text(data_msg).is_none()
+        {
+            return None;
+        }
+        Some((data_msg, sender))
+    }
+
+    /// Marker kind for an inbound attachment, from its content type.
+    fn attachment_kind(attachment: &serde_json::Value) -> AttachmentKind {
+        let content_type = attachment
+            .get("contentType")
+            .and_then(|c| c.as_str())
+            .unwrap_or_default();
+        if content_type.starts_with("image/") {
+            AttachmentKind::Image
+        } else if content_type.starts_with("video/") {
+            AttachmentKind::Video
+        } else if content_type.starts_with("audio/") {
+            if attachment
+                .get("voiceNote")
+                .and_then(serde_json::Value::as_bool)
+                == Some(true)
+            {
+                AttachmentKind::Voice
+            } else {
+                AttachmentKind::Audio
+            }
+        } else {
+            AttachmentKind::Document
+        }
+    }
+
+    /// Local file name for an inbound attachment: its id, then the sender's
+    /// file name when present, with unsafe characters replaced. Without an
+    /// extension, one is guessed from the content type.
+    fn attachment_file_name(attachment: &serde_json::Value) -> Option<String> {
+        let id = attachment.get("id").and_then(|id| id.as_str())?;
+        let name = match attachment.get("filename").and_then(|f| f.as_str()) {
+            Some(file_name) if !file_name.trim().is_empty() => format!("{id}_{}", file_name.trim()),
+            _ => id.to_string(),
+        };
+        let mut name = sanitize_file_name(&name);
+        if std::path::Path::new(&name).extension().is_none() {
+            let ext = attachment
+                .get("contentType")
+                .and_then(|c| c.as_str())
+                .and_then(mime_guess::get_mime_extensions_str)
+                .and_then(|exts| exts.first())
+                .copied()
+                .unwrap_or("bin");
+            name = format!("{name}.{ext}");
+        }
+        Some(name)
+    }
+
+    /// Fetch an attachment's bytes from signal-cli (`getAttachment`).
+    async fn fetch_attachment(
+        &self,
+        data_msg: &DataMessage,
+        sender: &str,
+        attachment_id: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        let mut params = serde_json::json!({
+            "id": attachment_id,
+            "account": &self.account,
+        });
+        match data_msg
+            .group_info
+            .as_ref()
+            .and_then(|g| g.group_id.as_deref())
+        {
+            Some(group_id) => params["groupId"] = serde_json::json!(group_id),
+            None => params["recipient"] = serde_json::json!(sender),
+        }
+
+        let result = self
+            .rpc_request("getAttachment", params)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("getAttachment returned no result"))?;
+        let encoded = result
+            .get("data")
+            .and_then(|d| d.as_str())
+            .ok_or_else(|| anyhow::anyhow!("getAttachment returned no data"))?;
+        Ok(base64::engine::general_purpose::STANDARD.decode(encoded)?)
+    }
+
+    /// Download the attachments of an accepted message into
+    /// `{workspace_dir}/signal_files/` and return their markers
+    /// (`[IMAGE:...]`, `[DOCUMENT:...]`, ...). Audio that was transcribed is
+    /// skipped. Attachments that cannot be fetched are logged and dropped.
+    async fn save_attachments(&self, envelope: &Envelope, transcribed: bool) -> Vec<String> {
+        let Some(workspace) = self.workspace_dir.as_ref() else {
+            return Vec::new();
+        };
+        if self.ignore_attachments {
+            return Vec::new();
+        }
+        let Some((data_msg, sender)) = self.accepted(envelope) else {
+            return Vec::new();
+        };
+
+        let dir = workspace.join("signal_files");
+        let mut markers = Vec::new();
+        for attachment in data_msg.attachments.iter().flatten() {
+            let kind = Self::attachment_kind(attachment);
+            if transcribed && matches!(kind, AttachmentKind::Audio | AttachmentKind::Voice) {
+                continue;
+            }
+            let (Some(attachment_id), Some(file_name)) = (
+                attachment.get("id").and_then(|id| id.as_str()),
+                Self::attachment_file_name(attachment),
+            ) else {
+                continue;
+            };
+            let size = attachment
+                .get("size")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(0);
+            if !usize::try_from(size).is_ok_and(|size| size <= SIGNAL_MAX_DOWNLOAD_BYTES) {
+                tracing::info!("Signal: skipping attachment {attachment_id} of {size} bytes");
+                continue;
+            }
+
+            let path = dir.join(file_name);
+            let saved = async {
+                let data = self
+                    .fetch_attachment(data_msg, &sender, attachment_id)
+                    .await?;
+                if data.len() > SIGNAL_MAX_DOWNLOAD_BYTES {
+                    anyhow::bail!("exceeds {SIGNAL_MAX_DOWNLOAD_BYTES} bytes");
+                }
+                tokio::fs::create_dir_all(&dir).await?;
+                tokio::fs::write(&path, data).await?;
+                anyhow::Ok(())
+            }
+            .await;
+            match saved {
+                Ok(()) => markers.push(format!("[{}:{}]", kind.marker_name(), path.display())),
+                Err(e) => tracing::warn!("Signal: failed to save attachment {attachment_id}: {e}"),
+            }
+        }
+        markers
+    }
+
+    /// Split outbound content into message text and attachments. Local files
+    /// from attachment markers are inlined as data URIs so signal-cli need not
+    /// share a filesystem with us; remote URLs stay in the text as links.
+    async fn outbound_content(content: &str) -> anyhow::Result<(String, Vec<String>)> {
+        let (text, markers) = parse_attachment_markers(content);
+        if markers.is_empty() {
+            return Ok((content.to_string(), Vec::new()));
+        }
+
+        let mut text = text;
+        let mut attachments = Vec::new();
+        for marker in markers {
+            if !is_local_path(&marker.target) {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&marker.target);
+                continue;
+            }
+            let path = std::path::Path::new(&marker.target);
+            let data = tokio::fs::read(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Signal attachment {}: {e}", path.display()))?;
+            let mime = mime_guess::from_path(path).first_or_octet_stream();
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("attachment");
+            attachments.push(format!(
+                "data:{mime};filename={file_name};base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(data)
+            ));
+        }
+        Ok((text, attachments))
+    }
+
     /// Send a JSON-RPC request to signal-cli daemon.
     async fn rpc_request(
         &self,
@@ -215,8 +518,84 @@ impl SignalChannel {
         Ok(parsed.get("result").cloned())
     }
 
-    /// Process a single SSE envelope, returning a ChannelMessage if valid.
+    /// First audio attachment (voice note or audio file) of a data message.
+    fn audio_attachment(data_msg: &DataMessage) -> Option<&serde_json::Value> {
+        data_msg.attachments.as_ref()?.iter().find(|attachment| {
+            attachment
+                .get("contentType")
+                .and_then(|c| c.as_str())
+                .is_some_and(|c| c.starts_with("audio/"))
+        })
+    }
+
+    /// Download and transcribe the audio attachment of an accepted message.
+    ///
+    /// Returns `None` when transcription is off, the message would be dropped
+    /// anyway, there is no audio attachment, or transcription fails.
+    async fn transcribe_voice_attachment(&self, envelope: &Envelope) -> Option<String> {
+        let config = self.transcription.as_ref()?;
+        if self.ignore_attachments {
+            return None;
+        }
+
+        let (data_msg, sender) = self.accepted(envelope)?;
+        let attachment = Self::audio_attachment(data_msg)?;
+        let attachment_id = attachment.get("id").and_then(|id| id.as_str())?;
+        let content_type = attachment
+            .get("contentType")
+            .and_then(|c| c.as_str())
+            .unwrap_or("audio/aac");
+
+        let audio_data = match self
+            .fetch_attachment(data_msg, &sender, attachment_id)
+            .await
+        {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Signal: failed to fetch voice attachment: {e}");
+                return None;
+            }
+        };
+
+        let file_name = format!(
+            "voice.{}",
+            crate::channels::transcription::extension_for_mime(content_type)
+        );
+        match crate::channels::transcription::transcribe_audio(audio_data, &file_name, config).await
+        {
+            Ok(text) if !text.trim().is_empty() => Some(text),
+            Ok(_) => {
+                tracing::info!("Signal: voice transcription returned empty text, skipping");
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Signal: voice transcription failed: {e}");
+                None
+            }
+        }
+    }
+
+    /// Process a single SSE envelope, transcribing its voice attachment and
+    /// downloading its other attachments first.
+    async fn handle_envelope(&self, envelope: &Envelope) -> Option<ChannelMessage> {
+        let transcript = self.transcribe_voice_attachment(envelope).await;
+        let attachments = self.save_attachments(envelope, transcript.is_some()).await;
+        self.message_from_envelope(envelope, transcript.as_deref(), &attachments)
+    }
+
+    /// Process a single SSE envelope without fetching attachments,
+    /// returning a ChannelMessage if valid.
+    #[cfg(test)]
     fn process_envelope(&self, envelope: &Envelope) -> Option<ChannelMessage> {
+        self.message_from_envelope(envelope, None, &[])
+    }
+
+    fn message_from_envelope(
+        &self,
+        envelope: &Envelope,
+        transcript: Option<&str>,
+        attachments: &[String],
+    ) -> Option<ChannelMessage> {
         // Skip story messages when configured
         if self.ignore_stories && envelope.story_message.is_some() {
             return None;
@@ -232,7 +611,6 @@ impl SignalChannel {
             }
         }
 
-        let text = data_msg.message.as_deref().filter(|t| !t.is_empty())?;
         let sender = Self::sender(envelope)?;
 
         if !self.is_sender_allowed(&sender) {
@@ -243,6 +621,24 @@ impl SignalChannel {
             return None;
         }
 
+        let caption = self.triggered_text(data_msg)?;
+        let caption = Some(caption.as_str()).filter(|t| !t.is_empty());
+        let mut text = match transcript {
+            Some(transcript) => {
+                crate::channels::transcription::voice_message_content(transcript, caption)
+            }
+            None => caption.unwrap_or_default().to_string(),
+        };
+        for marker in attachments {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(marker);
+        }
+        if text.is_empty() {
+            return None;
+        }
+
         let target = self.reply_target(data_msg, &sender);
 
         let timestamp = data_msg
@@ -262,7 +658,7 @@ impl SignalChannel {
             id: format!("sig_{timestamp}"),
             sender: sender.clone(),
             reply_target: target,
-            content: text.to_string(),
+            content: text,
             channel: "signal".to_string(),
             timestamp: timestamp / 1000, // millis → secs
             thread_ts: None,
@@ -277,18 +673,23 @@ impl Channel for SignalChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        let params = match Self::parse_recipient_target(&message.recipient) {
+        let (text, attachments) = Self::outbound_content(&message.content).await?;
+        let text = markdown::render(&text, Dialect::Plain);
+        let mut params = match Self::parse_recipient_target(&message.recipient) {
             RecipientTarget::Direct(number) => serde_json::json!({
                 "recipient": [number],
-                "message": &message.content,
+                "message": text,
                 "account": &self.account,
             }),
             RecipientTarget::Group(group_id) => serde_json::json!({
                 "groupId": group_id,
-                "message": &message.content,
+                "message": text,
                 "account": &self.account,
             }),
         };
+        if !attachments.is_empty() {
+            params["attachments"] = serde_json::json!(attachments);
+        }
 
         self.rpc_request("send", params).await?;
         Ok(())
@@ -369,7 +770,7 @@ impl Channel for SignalChannel {
                             match serde_json::from_str::<SseEnvelope>(&current_data) {
                                 Ok(sse) => {
                                     if let Some(ref envelope) = sse.envelope {
-                                        if let Some(msg) = self.process_envelope(envelope) {
+                                        if let Some(msg) = self.handle_envelope(envelope).await {
                                             if tx.send(msg).await.is_err() {
                                                 return Ok(());
                                             }
@@ -396,7 +797,7 @@ impl Channel for SignalChannel {
                 match serde_json::from_str::<SseEnvelope>(&current_data) {
                     Ok(sse) => {
                         if let Some(ref envelope) = sse.envelope {
-                            if let Some(msg) = self.process_envelope(envelope) {
+                            if let Some(msg) = self.handle_envelope(envelope).await {
                                 let _ = tx.send(msg).await;
                             }
                         }
@@ -483,6 +884,7 @@ mod tests {
                 timestamp: Some(1_700_000_000_000),
                 group_info: None,
                 attachments: None,
+                mentions: Vec::new(),
             }),
             story_message: None,
             timestamp: Some(1_700_000_000_000),
@@ -558,6 +960,7 @@ mod tests {
             timestamp: Some(1000),
             group_info: None,
             attachments: None,
+            mentions: Vec::new(),
         };
         assert!(ch.matches_group(&dm));
 
@@ -568,6 +971,7 @@ mod tests {
                 group_id: Some("group123".to_string()),
             }),
             attachments: None,
+            mentions: Vec::new(),
         };
         assert!(ch.matches_group(&group));
     }
@@ -582,6 +986,7 @@ mod tests {
                 group_id: Some("group123".to_string()),
             }),
             attachments: None,
+            mentions: Vec::new(),
         };
         assert!(ch.matches_group(&matching));
 
@@ -592,6 +997,7 @@ mod tests {
                 group_id: Some("other_group".to_string()),
             }),
             attachments: None,
+            mentions: Vec::new(),
         };
         assert!(!ch.matches_group(&non_matching));
     }
@@ -604,6 +1010,7 @@ mod tests {
             timestamp: Some(1000),
             group_info: None,
             attachments: None,
+            mentions: Vec::new(),
         };
         assert!(ch.matches_group(&dm));
 
@@ -614,6 +1021,7 @@ mod tests {
                 group_id: Some("group123".to_string()),
             }),
             attachments: None,
+            mentions: Vec::new(),
         };
         assert!(!ch.matches_group(&group));
     }
@@ -626,6 +1034,7 @@ mod tests {
             timestamp: Some(1000),
             group_info: None,
             attachments: None,
+            mentions: Vec::new(),
         };
         assert_eq!(ch.reply_target(&dm, "+1111111111"), "+1111111111");
     }
@@ -640,6 +1049,7 @@ mod tests {
                 group_id: Some("group123".to_string()),
             }),
             attachments: None,
+            mentions: Vec::new(),
         };
         assert_eq!(ch.reply_target(&group, "+1111111111"), "group:group123");
     }
@@ -738,6 +1148,7 @@ mod tests {
                 timestamp: Some(1_700_000_000_000),
                 group_info: None,
                 attachments: None,
+                mentions: Vec::new(),
             }),
             story_message: None,
             timestamp: Some(1_700_000_000_000),
@@ -773,6 +1184,7 @@ mod tests {
                     group_id: Some("testgroup".to_string()),
                 }),
                 attachments: None,
+                mentions: Vec::new(),
             }),
             story_message: None,
             timestamp: Some(1_700_000_000_000),
@@ -848,6 +1260,7 @@ mod tests {
                 timestamp: Some(1_700_000_000_000),
                 group_info: None,
                 attachments: Some(vec![serde_json::json!({"contentType": "image/png"})]),
+                mentions: Vec::new(),
             }),
             story_message: None,
             timestamp: Some(1_700_000_000_000),
@@ -855,6 +1268,129 @@ mod tests {
         assert!(ch.process_envelope(&env).is_none());
     }
 
+    #[test]
+    fn process_envelope_prefixes_voice_transcript() {
+        let ch = make_channel();
+        let mut env = make_envelope(Some("+1111111111"), Some("fyi"));
+        let data_msg = env.data_message.as_mut().unwrap();
+        data_msg.message = None;
+        data_msg.attachments = Some(vec![
+            serde_json::json!({"contentType": "audio/aac", "id": "a.aac"}),
+        ]);
+        assert!(SignalChannel::audio_attachment(data_msg).is_some());
+        assert!(ch.process_envelope(&env).is_none());
+
+        let msg = ch
+            .message_from_envelope(&env, Some("hello there"), &[])
+            .unwrap();
+        assert_eq!(msg.content, "[Voice] hello there");
+
+        env.data_message.as_mut().unwrap().message = Some("fyi".to_string());
+        let msg = ch
+            .message_from_envelope(&env, Some("hello there"), &[])
+            .unwrap();
+        assert_eq!(msg.content, "[Voice] hello there\n\nfyi");
+    }
+
+    #[test]
+    fn mentions_resolve_and_trigger_group_replies() {
+        let ch = make_channel().with_triggers(true, Some("!claw".into()));
+        let mut env = make_envelope(Some("+1111111111"), Some("\u{FFFC} ask \u{FFFC}"));
+        let data_msg = env.data_message.as_mut().unwrap();
+        data_msg.group_info = Some(GroupInfo {
+            group_id: Some("group123".to_string()),
+        });
+        data_msg.mentions = serde_json::from_value(serde_json::json!([
+            { "number": "+1234567890", "start": 0, "length": 1 },
+            { "name": "Bob", "number": "+2222222222", "start": 6, "length": 1 },
+        ]))
+        .unwrap();
+        let msg = ch.process_envelope(&env).unwrap();
+        assert_eq!(msg.content, "ask @Bob");
+        assert_eq!(msg.reply_target, "group:group123");
+
+        let data_msg = env.data_message.as_mut().unwrap();
+        data_msg.mentions.remove(0);
+        data_msg.message = Some("ask around".to_string());
+        assert!(ch.process_envelope(&env).is_none());
+
+        env.data_message.as_mut().unwrap().message = Some("!claw: ask around".to_string());
+        assert_eq!(ch.process_envelope(&env).unwrap().content, "ask around");
+
+        // Direct messages need no trigger.
+        let env = make_envelope(Some("+1111111111"), Some("ask around"));
+        assert_eq!(ch.process_envelope(&env).unwrap().content, "ask around");
+    }
+
+    #[test]
+    fn inbound_attachments_map_to_markers() {
+        let voice = serde_json::json!({"contentType": "audio/aac", "voiceNote": true, "id": "v1"});
+        assert_eq!(
+            SignalChannel::attachment_kind(&voice),
+            AttachmentKind::Voice
+        );
+        let pdf = serde_json::json!({
+            "contentType": "application/pdf",
+            "id": "Xy9",
+            "filename": "Q3 report.pdf",
+        });
+        assert_eq!(
+            SignalChannel::attachment_kind(&pdf),
+            AttachmentKind::Document
+        );
+        assert_eq!(
+            SignalChannel::attachment_file_name(&pdf).as_deref(),
+            Some("Xy9_Q3_report.pdf")
+        );
+        let photo = serde_json::json!({"contentType": "image/png", "id": "p/1"});
+        assert_eq!(
+            SignalChannel::attachment_kind(&photo),
+            AttachmentKind::Image
+        );
+        assert_eq!(
+            SignalChannel::attachment_file_name(&photo).as_deref(),
+            Some("p_1.png")
+        );
+
+        let ch = make_channel();
+        let mut env = make_envelope(Some("+1111111111"), Some("look"));
+        env.data_message.as_mut().unwrap().attachments = Some(vec![photo]);
+        let msg = ch
+            .message_from_envelope(&env, None, &["[IMAGE:/tmp/p1.png]".to_string()])
+            .unwrap();
+        assert_eq!(msg.content, "look\n[IMAGE:/tmp/p1.png]");
+
+        env.data_message.as_mut().unwrap().message = None;
+        let msg = ch
+            .message_from_envelope(&env, None, &["[IMAGE:/tmp/p1.png]".to_string()])
+            .unwrap();
+        assert_eq!(msg.content, "[IMAGE:/tmp/p1.png]");
+    }
+
+    #[tokio::test]
+    async fn outbound_content_inlines_local_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reply.ogg");
+        std::fs::write(&path, b"abc").unwrap();
+
+        let content = format!(
+            "Here you go [VOICE:{}] [IMAGE:https://example.com/a.png]",
+            path.display()
+        );
+        let (text, attachments) = SignalChannel::outbound_content(&content).await.unwrap();
+        assert_eq!(text, "Here you go\nhttps://example.com/a.png");
+        assert_eq!(
+            attachments,
+            vec!["data:audio/ogg;filename=reply.ogg;base64,YWJj".to_string()]
+        );
+
+        let (text, attachments) = SignalChannel::outbound_content("plain [text]")
+            .await
+            .unwrap();
+        assert_eq!(text, "plain [text]");
+        assert!(attachments.is_empty());
+    }
+
     #[test]
     fn sse_envelope_deserializes() {
         let json = r#"{
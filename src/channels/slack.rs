@@ -1,13 +1,225 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::draft::{truncate_chars, DraftThrottle};
+use super::markdown::{self, Dialect};
+use super::split::split_message;
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
+use crate::config::StreamMode;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use hmac::{Hmac, Mac};
+use parking_lot::Mutex;
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+/// How long a thread keeps being polled for replies after its last message.
+const THREAD_FOLLOW_TTL: Duration = Duration::from_secs(3600);
+/// Minimum time between `conversations.replies` polls of one thread.
+const THREAD_POLL_INTERVAL: Duration = Duration::from_secs(15);
+/// Threads followed at once; the least recently active one is dropped first.
+const MAX_FOLLOWED_THREADS: usize = 20;
+/// Slack truncates `text` longer than this; longer replies are split.
+const SLACK_MAX_MESSAGE_CHARS: usize = 40_000;
+/// Status shown under the thread while a reply is being generated.
+const TYPING_STATUS: &str = "is typing...";
+/// Slack keeps a thread status for about two minutes; refresh well before.
+const TYPING_STATUS_REFRESH: Duration = Duration::from_secs(60);
+
+/// Slack caps `section` text at 3000 characters, `header` text at 150 and a
+/// message at 50 blocks.
+const SLACK_MAX_SECTION_CHARS: usize = 3000;
+const SLACK_MAX_HEADER_CHARS: usize = 150;
+const SLACK_MAX_BLOCKS: usize = 50;
+/// `conversations.replies` page read for thread context; the latest
+/// messages are kept.
+const SLACK_THREAD_HISTORY_LIMIT: usize = 200;
+/// Slack rejects slash command requests older than this.
+const SLACK_SIGNATURE_MAX_AGE_SECS: u64 = 60 * 5;
+
+/// Block Kit layout for one reply message.
+///
+/// Headings become `header` blocks, fenced code a preformatted `rich_text`
+/// block, `---` a divider and other text mrkdwn `section`s; `images` are
+/// appended as `image` blocks. Replies without any of that structure
+/// render the same as plain `text`, so they (and replies over the block
+/// limit) get `None`.
+fn build_blocks(text: &str, images: &[(&str, &str)]) -> Option<Vec<serde_json::Value>> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+    let mut structured = !images.is_empty();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(lines) = code.as_mut() {
+            if trimmed.starts_with("```") {
+                push_code_block(&mut blocks, lines);
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("```") {
+            push_section_blocks(&mut blocks, &paragraph);
+            paragraph.clear();
+            code = Some(Vec::new());
+        } else if let Some(title) = markdown::heading(line) {
+            push_section_blocks(&mut blocks, &paragraph);
+            paragraph.clear();
+            let title = markdown::render(title, Dialect::Plain);
+            blocks.push(serde_json::json!({
+                "type": "header",
+                "text": {
+                    "type": "plain_text",
+                    "text": truncate_chars(&title, SLACK_MAX_HEADER_CHARS),
+                },
+            }));
+        } else if is_divider(trimmed) {
+            push_section_blocks(&mut blocks, &paragraph);
+            paragraph.clear();
+            blocks.push(serde_json::json!({ "type": "divider" }));
+        } else {
+            paragraph.push(line);
+            continue;
+        }
+        structured = true;
+    }
+    // An unterminated fence still renders as code.
+    if let Some(lines) = code {
+        push_code_block(&mut blocks, &lines);
+    }
+    push_section_blocks(&mut blocks, &paragraph);
+
+    for (url, alt) in images {
+        blocks.push(serde_json::json!({
+            "type": "image",
+            "image_url": url,
+            "alt_text": alt,
+        }));
+    }
+
+    (structured && !blocks.is_empty() && blocks.len() <= SLACK_MAX_BLOCKS).then_some(blocks)
+}
+
+/// A thematic break: three or more of the same `-`, `*` or `_`.
+fn is_divider(line: &str) -> bool {
+    let mut chars = line.chars();
+    matches!(
+        chars.next(),
+        Some(first @ ('-' | '*' | '_')) if line.len() >= 3 && chars.all(|c| c == first)
+    )
+}
+
+fn push_section_blocks(blocks: &mut Vec<serde_json::Value>, lines: &[&str]) {
+    let text = lines.join("\n");
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    let rendered = markdown::render(text, Dialect::SlackMrkdwn);
+    for chunk in split_message(&rendered, SLACK_MAX_SECTION_CHARS) {
+        blocks.push(serde_json::json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": chunk },
+        }));
+    }
+}
+
+fn push_code_block(blocks: &mut Vec<serde_json::Value>, lines: &[&str]) {
+    let code = lines.join("\n");
+    if code.trim().is_empty() {
+        return;
+    }
+    for chunk in split_message(&code, SLACK_MAX_SECTION_CHARS) {
+        blocks.push(serde_json::json!({
+            "type": "rich_text",
+            "elements": [{
+                "type": "rich_text_preformatted",
+                "elements": [{ "type": "text", "text": chunk }],
+            }],
+        }));
+    }
+}
+
+/// A slash command invocation posted to the gateway's `/slack/commands`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlackSlashCommand {
+    /// The command as typed, e.g. `/ask`.
+    pub command: String,
+    /// Everything after the command.
+    pub text: String,
+    pub user_id: String,
+    pub channel_id: String,
+    /// Where replies to this invocation are posted.
+    pub response_url: String,
+    /// Unique per invocation.
+    pub trigger_id: String,
+}
+
+/// Verify a Slack request signature.
+///
+/// Slack signs `v0:{timestamp}:{body}` with HMAC-SHA256 keyed by the app's
+/// signing secret and sends `v0=<hex>` in `X-Slack-Signature`. Requests
+/// whose `X-Slack-Request-Timestamp` is more than five minutes away from
+/// `now_secs` are rejected to prevent replays.
+pub fn verify_slack_signature(
+    signing_secret: &str,
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+    now_secs: u64,
+) -> bool {
+    let Ok(sent_at) = timestamp.trim().parse::<u64>() else {
+        return false;
+    };
+    if now_secs.abs_diff(sent_at) > SLACK_SIGNATURE_MAX_AGE_SECS {
+        return false;
+    }
+
+    let Some(provided) = signature
+        .trim()
+        .strip_prefix("v0=")
+        .and_then(|hex_digest| hex::decode(hex_digest).ok())
+    else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(signing_secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("v0:{}:", timestamp.trim()).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&provided).is_ok()
+}
+
+/// A thread the bot answered in, polled for follow-up replies.
+/// `conversations.history` only returns top-level messages.
+struct FollowedThread {
+    last_ts: String,
+    last_activity: Instant,
+    last_polled: Option<Instant>,
+}
+
+/// Followed threads keyed by `(channel_id, thread_ts)`.
+type FollowedThreads = HashMap<(String, String), FollowedThread>;
+
 /// Slack channel — polls conversations.history via Web API
 pub struct SlackChannel {
     bot_token: String,
     channel_id: Option<String>,
     allowed_users: Vec<String>,
+    thread_replies: bool,
+    mention_only: bool,
+    trigger_prefix: Option<String>,
+    stream_mode: StreamMode,
+    draft_throttle: DraftThrottle,
+    /// Draft `ts` -> thread it was posted in, for the final send when the
+    /// draft has to be replaced.
+    draft_threads: Mutex<HashMap<String, Option<String>>>,
+    /// `(channel, thread_ts)` -> when the typing status was last set.
+    typing_status: Mutex<HashMap<(String, String), Instant>>,
+    /// App signing secret for verifying slash command requests.
+    signing_secret: Option<String>,
 }
 
 impl SlackChannel {
@@ -16,13 +228,77 @@ impl SlackChannel {
             bot_token,
             channel_id,
             allowed_users,
+            thread_replies: true,
+            mention_only: false,
+            trigger_prefix: None,
+            stream_mode: StreamMode::Off,
+            draft_throttle: DraftThrottle::new(1000),
+            draft_threads: Mutex::new(HashMap::new()),
+            typing_status: Mutex::new(HashMap::new()),
+            signing_secret: None,
         }
     }
 
+    /// Accept slash commands signed with the app's signing secret.
+    pub fn with_signing_secret(mut self, signing_secret: Option<String>) -> Self {
+        self.signing_secret = signing_secret.filter(|secret| !secret.trim().is_empty());
+        self
+    }
+
+    /// Configure streaming mode for progressive draft updates.
+    pub fn with_streaming(
+        mut self,
+        stream_mode: StreamMode,
+        draft_update_interval_ms: u64,
+    ) -> Self {
+        self.stream_mode = stream_mode;
+        self.draft_throttle = DraftThrottle::new(draft_update_interval_ms);
+        self
+    }
+
+    /// Answer top-level messages in a thread on the original message (default)
+    /// or in the channel itself.
+    pub fn with_thread_replies(mut self, thread_replies: bool) -> Self {
+        self.thread_replies = thread_replies;
+        self
+    }
+
+    /// Only answer channel messages that @-mention the bot or start with
+    /// `trigger_prefix` (see [`super::trigger`]).
+    pub fn with_triggers(mut self, mention_only: bool, trigger_prefix: Option<String>) -> Self {
+        self.mention_only = mention_only;
+        self.trigger_prefix = super::trigger::normalize_prefix(trigger_prefix);
+        self
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.slack")
     }
 
+    /// The text to hand to the agent for a top-level message in
+    /// `channel_id`, or `None` when the configured triggers do not match.
+    /// Direct messages (`D…` conversations) always pass.
+    fn triggered_content(&self, channel_id: &str, text: &str, bot_user_id: &str) -> Option<String> {
+        if channel_id.starts_with('D') || (!self.mention_only && self.trigger_prefix.is_none()) {
+            return Some(text.to_string());
+        }
+        if let Some(prefixed) = self
+            .trigger_prefix
+            .as_deref()
+            .and_then(|prefix| super::trigger::strip_trigger_prefix(text, prefix))
+        {
+            return Some(prefixed);
+        }
+
+        let mention = format!("<@{bot_user_id}>");
+        if !self.mention_only || bot_user_id.is_empty() || !text.contains(&mention) {
+            return None;
+        }
+        let stripped = text.replace(&mention, " ");
+        let stripped = stripped.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!stripped.is_empty()).then_some(stripped)
+    }
+
     /// Check if a Slack user ID is in the allowlist.
     /// Empty list means deny everyone until explicitly configured.
     /// `"*"` means allow everyone.
@@ -57,6 +333,268 @@ impl SlackChannel {
             .map(str::to_string)
     }
 
+    /// Call a Slack Web API method that takes a JSON body, failing on
+    /// `"ok": false`.
+    async fn post_api(
+        &self,
+        method: &str,
+        body: &serde_json::Value,
+    ) -> anyhow::Result<serde_json::Value> {
+        let resp = self
+            .http_client()
+            .post(format!("https://slack.com/api/{method}"))
+            .bearer_auth(&self.bot_token)
+            .json(body)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body_text = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        if !status.is_success() {
+            anyhow::bail!("Slack {method} failed ({status}): {body_text}");
+        }
+
+        let parsed: serde_json::Value = serde_json::from_str(&body_text).unwrap_or_default();
+        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = parsed
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack {method} failed: {err}");
+        }
+        Ok(parsed)
+    }
+
+    /// Set or clear (empty `status`) the assistant status shown under a thread.
+    async fn set_thread_status(
+        &self,
+        channel_id: &str,
+        thread_ts: &str,
+        status: &str,
+    ) -> anyhow::Result<()> {
+        let body = serde_json::json!({
+            "channel_id": channel_id,
+            "thread_ts": thread_ts,
+            "status": status,
+        });
+        self.post_api("assistant.threads.setStatus", &body)
+            .await
+            .map(|_| ())
+    }
+
+    /// Thread to answer an inbound message in. Replies always stay in their
+    /// thread; top-level messages start one only when `thread_replies` is on.
+    fn reply_thread_ts(&self, msg: &serde_json::Value, ts: &str) -> Option<String> {
+        if self.thread_replies {
+            Self::inbound_thread_ts(msg, ts)
+        } else {
+            msg.get("thread_ts")
+                .and_then(|t| t.as_str())
+                .map(str::to_string)
+        }
+    }
+
+    /// Start (or refresh) following a thread, dropping expired threads and
+    /// the least recently active ones beyond the limit.
+    fn follow_thread(threads: &mut FollowedThreads, channel_id: &str, thread_ts: &str, ts: &str) {
+        let now = Instant::now();
+        let entry = threads
+            .entry((channel_id.to_string(), thread_ts.to_string()))
+            .or_insert_with(|| FollowedThread {
+                last_ts: ts.to_string(),
+                last_activity: now,
+                last_polled: None,
+            });
+        if ts > entry.last_ts.as_str() {
+            entry.last_ts = ts.to_string();
+        }
+        entry.last_activity = now;
+
+        threads.retain(|_, thread| now.duration_since(thread.last_activity) < THREAD_FOLLOW_TTL);
+        while threads.len() > MAX_FOLLOWED_THREADS {
+            let Some(oldest) = threads
+                .iter()
+                .min_by_key(|(_, thread)| thread.last_activity)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            threads.remove(&oldest);
+        }
+    }
+
+    /// Fetch replies newer than `oldest` in a thread, oldest first.
+    async fn fetch_thread_replies(
+        &self,
+        channel_id: &str,
+        thread_ts: &str,
+        oldest: &str,
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        let data: serde_json::Value = self
+            .http_client()
+            .get("https://slack.com/api/conversations.replies")
+            .bearer_auth(&self.bot_token)
+            .query(&[
+                ("channel", channel_id),
+                ("ts", thread_ts),
+                ("oldest", oldest),
+                ("limit", "20"),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if data.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = data
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack conversations.replies failed: {err}");
+        }
+
+        Ok(data
+            .get("messages")
+            .and_then(|m| m.as_array())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    /// Forward new replies in followed threads. Returns `false` once the
+    /// receiver is gone.
+    async fn poll_followed_threads(
+        &self,
+        threads: &mut FollowedThreads,
+        bot_user_id: &str,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+    ) -> bool {
+        let due: Vec<(String, String)> = threads
+            .iter()
+            .filter(|(_, thread)| {
+                thread
+                    .last_polled
+                    .is_none_or(|polled| polled.elapsed() >= THREAD_POLL_INTERVAL)
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for (channel_id, thread_ts) in due {
+            let Some(oldest) = threads
+                .get_mut(&(channel_id.clone(), thread_ts.clone()))
+                .map(|thread| {
+                    thread.last_polled = Some(Instant::now());
+                    thread.last_ts.clone()
+                })
+            else {
+                continue;
+            };
+
+            let replies = match self
+                .fetch_thread_replies(&channel_id, &thread_ts, &oldest)
+                .await
+            {
+                Ok(replies) => replies,
+                Err(e) => {
+                    tracing::warn!("Slack thread poll error for {channel_id}/{thread_ts}: {e}");
+                    continue;
+                }
+            };
+
+            for reply in &replies {
+                let ts = reply.get("ts").and_then(|t| t.as_str()).unwrap_or("");
+                let user = reply
+                    .get("user")
+                    .and_then(|u| u.as_str())
+                    .unwrap_or("unknown");
+                let text = reply.get("text").and_then(|t| t.as_str()).unwrap_or("");
+
+                // The parent is always returned; broadcasts also show up in
+                // conversations.history and are handled there.
+                if ts <= oldest.as_str()
+                    || reply.get("subtype").and_then(|s| s.as_str()) == Some("thread_broadcast")
+                {
+                    continue;
+                }
+                Self::follow_thread(threads, &channel_id, &thread_ts, ts);
+
+                if user == bot_user_id || text.is_empty() {
+                    continue;
+                }
+                if !self.is_user_allowed(user) {
+                    tracing::warn!("Slack: ignoring message from unauthorized user: {user}");
+                    continue;
+                }
+
+                let channel_msg = ChannelMessage {
+                    id: format!("slack_{channel_id}_{ts}"),
+                    sender: user.to_string(),
+                    reply_target: channel_id.clone(),
+                    content: text.to_string(),
+                    channel: "slack".to_string(),
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    thread_ts: Some(thread_ts.clone()),
+                };
+
+                if tx.send(channel_msg).await.is_err() {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Messages of a `conversations.history` or `conversations.replies`
+    /// payload, oldest first. Join and leave notices are skipped.
+    fn history_messages(
+        channel_id: &str,
+        thread_ts: Option<&str>,
+        data: &serde_json::Value,
+    ) -> Vec<ChannelMessage> {
+        let mut messages: Vec<ChannelMessage> = data
+            .get("messages")
+            .and_then(|m| m.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|msg| {
+                !matches!(
+                    msg.get("subtype").and_then(|s| s.as_str()),
+                    Some("channel_join" | "channel_leave")
+                )
+            })
+            .filter_map(|msg| {
+                let ts = msg.get("ts").and_then(|t| t.as_str())?;
+                let text = msg.get("text").and_then(|t| t.as_str())?;
+                let sender = msg
+                    .get("user")
+                    .or_else(|| msg.get("bot_id"))
+                    .and_then(|u| u.as_str())
+                    .unwrap_or("unknown");
+                Some(ChannelMessage {
+                    id: format!("slack_{channel_id}_{ts}"),
+                    sender: sender.to_string(),
+                    reply_target: channel_id.to_string(),
+                    content: text.to_string(),
+                    channel: "slack".to_string(),
+                    timestamp: ts
+                        .split('.')
+                        .next()
+                        .and_then(|secs| secs.parse().ok())
+                        .unwrap_or_default(),
+                    thread_ts: thread_ts.map(str::to_string),
+                })
+            })
+            .collect();
+        messages.sort_by(|a, b| a.id.cmp(&b.id));
+        messages
+    }
+
     fn normalized_channel_id(input: Option<&str>) -> Option<String> {
         input
             .map(str::trim)
@@ -177,11 +715,136 @@ impl SlackChannel {
             .clone()
     }
 
+    /// Check a slash command request against the signing secret. Without a
+    /// configured secret every request is rejected.
+    pub fn verify_request(&self, timestamp: &str, body: &[u8], signature: &str) -> bool {
+        let Some(ref secret) = self.signing_secret else {
+            return false;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        verify_slack_signature(secret, timestamp, body, signature, now)
+    }
+
+    /// Parse a slash command form body. Returns `None` for malformed
+    /// requests and for users outside `allowed_users`.
+    pub fn slash_command_from_form(
+        &self,
+        form: &BTreeMap<String, String>,
+    ) -> Option<SlackSlashCommand> {
+        let field = |name: &str| {
+            form.get(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let command = SlackSlashCommand {
+            command: field("command")?,
+            text: field("text").unwrap_or_default(),
+            user_id: field("user_id")?,
+            channel_id: field("channel_id")?,
+            response_url: field("response_url")?,
+            trigger_id: field("trigger_id").unwrap_or_default(),
+        };
+
+        if !self.is_user_allowed(&command.user_id) {
+            tracing::warn!(
+                "Slack: ignoring slash command from unauthorized user: {}",
+                command.user_id
+            );
+            return None;
+        }
+        if let Some(ref scoped) = self.configured_channel_id() {
+            if *scoped != command.channel_id {
+                return None;
+            }
+        }
+        Some(command)
+    }
+
+    /// Post the reply to a slash command through its `response_url`, visible
+    /// to the whole channel. Local attachments are uploaded to the channel.
+    pub async fn respond_to_command(
+        &self,
+        command: &SlackSlashCommand,
+        reply: &str,
+    ) -> anyhow::Result<()> {
+        use super::attachment::{is_local_path, parse_attachment_markers};
+
+        let content = super::strip_tool_call_tags(reply);
+        let (text, attachments) = parse_attachment_markers(&content);
+        let (local, remote): (Vec<_>, Vec<_>) = attachments
+            .iter()
+            .partition(|attachment| is_local_path(&attachment.target));
+        let images: Vec<(&str, &str)> = remote
+            .iter()
+            .filter(|attachment| attachment.kind.is_image_like())
+            .map(|attachment| {
+                (
+                    attachment.target.as_str(),
+                    attachment.alt.as_deref().unwrap_or("image"),
+                )
+            })
+            .collect();
+        let links: Vec<String> = remote
+            .iter()
+            .filter(|attachment| !attachment.kind.is_image_like())
+            .map(|attachment| format!("{}: {}", attachment.kind.marker_name(), attachment.target))
+            .collect();
+        let text = [text]
+            .into_iter()
+            .chain(links)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let chunks = split_message(&text, SLACK_MAX_MESSAGE_CHARS);
+        for (index, chunk) in chunks.iter().enumerate() {
+            let chunk_images = if index == chunks.len() - 1 {
+                images.as_slice()
+            } else {
+                &[]
+            };
+            let mut body = serde_json::json!({
+                "response_type": "in_channel",
+                "text": markdown::render(chunk, Dialect::SlackMrkdwn),
+            });
+            if let Some(blocks) = build_blocks(chunk, chunk_images) {
+                body["blocks"] = serde_json::json!(blocks);
+            }
+
+            let resp = self
+                .http_client()
+                .post(&command.response_url)
+                .json(&body)
+                .send()
+                .await?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let err = resp.text().await.unwrap_or_default();
+                anyhow::bail!("Slack slash command response failed ({status}): {err}");
+            }
+        }
+
+        for attachment in local {
+            self.upload_file(
+                &attachment.target,
+                &command.channel_id,
+                None,
+                attachment.alt.as_deref(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
     async fn upload_file(
         &self,
         file_path: &str,
         channel: &str,
         thread_ts: Option<&str>,
+        alt_text: Option<&str>,
     ) -> anyhow::Result<()> {
         let path = std::path::Path::new(file_path);
         if !path.exists() {
@@ -190,10 +853,7 @@ impl SlackChannel {
         }
 
         let file_bytes = tokio::fs::read(path).await?;
-        let filename = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("file");
+        let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("file");
 
         let form = reqwest::multipart::Form::new()
             .text("channels", channel.to_string())
@@ -206,6 +866,9 @@ impl SlackChannel {
         if let Some(ts) = thread_ts {
             form = form.text("thread_ts", ts.to_string());
         }
+        if let Some(alt) = alt_text {
+            form = form.text("alt_txt", alt.to_string());
+        }
 
         let resp = self
             .http_client()
@@ -232,64 +895,88 @@ impl Channel for SlackChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        use super::attachment::{parse_attachment_markers, is_local_path};
+        use super::attachment::{is_local_path, parse_attachment_markers};
 
         let content = super::strip_tool_call_tags(&message.content);
         let (text, attachments) = parse_attachment_markers(&content);
 
-        // Send text message
-        let mut body = serde_json::json!({
-            "channel": message.recipient,
-            "text": if text.is_empty() && !attachments.is_empty() {
-                format!("{} attachment(s)", attachments.len())
+        // Send text message, split to fit Slack's per-message limit
+        let text = if text.is_empty() && !attachments.is_empty() {
+            format!("{} attachment(s)", attachments.len())
+        } else {
+            text
+        };
+        // Remote images are shown in the reply itself as image blocks.
+        let images: Vec<(&str, &str)> = attachments
+            .iter()
+            .filter(|attachment| {
+                attachment.kind.is_image_like() && !is_local_path(&attachment.target)
+            })
+            .map(|attachment| {
+                (
+                    attachment.target.as_str(),
+                    attachment.alt.as_deref().unwrap_or("image"),
+                )
+            })
+            .collect();
+        let mut images_sent = false;
+
+        let chunks = split_message(&text, SLACK_MAX_MESSAGE_CHARS);
+        for (index, chunk) in chunks.iter().enumerate() {
+            let chunk_images = if index == chunks.len() - 1 {
+                images.as_slice()
             } else {
-                text
+                &[]
+            };
+            let mut body = serde_json::json!({
+                "channel": message.recipient,
+                "text": markdown::render(chunk, Dialect::SlackMrkdwn),
+            });
+            if let Some(blocks) = build_blocks(chunk, chunk_images) {
+                body["blocks"] = serde_json::json!(blocks);
+                images_sent |= !chunk_images.is_empty();
             }
-        });
-
-        if let Some(ref ts) = message.thread_ts {
-            body["thread_ts"] = serde_json::json!(ts);
-        }
-
-        let resp = self
-            .http_client()
-            .post("https://slack.com/api/chat.postMessage")
-            .bearer_auth(&self.bot_token)
-            .json(&body)
-            .send()
-            .await?;
-
-        let status = resp.status();
-        let body_text = resp
-            .text()
-            .await
-            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
-
-        if !status.is_success() {
-            anyhow::bail!("Slack chat.postMessage failed ({status}): {body_text}");
-        }
-
-        let parsed: serde_json::Value = serde_json::from_str(&body_text).unwrap_or_default();
-        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
-            let err = parsed
-                .get("error")
-                .and_then(|e| e.as_str())
-                .unwrap_or("unknown");
-            anyhow::bail!("Slack chat.postMessage failed: {err}");
+            if let Some(ref ts) = message.thread_ts {
+                body["thread_ts"] = serde_json::json!(ts);
+            }
+            super::rate_limit::acquire("slack", &message.recipient).await;
+            self.post_api("chat.postMessage", &body).await?;
         }
 
         // Upload attachments if any
         for attachment in &attachments {
+            if images_sent && attachment.kind.is_image_like() && !is_local_path(&attachment.target)
+            {
+                continue;
+            }
             if is_local_path(&attachment.target) {
-                self.upload_file(&attachment.target, &message.recipient, message.thread_ts.as_deref())
-                    .await?;
+                self.upload_file(
+                    &attachment.target,
+                    &message.recipient,
+                    message.thread_ts.as_deref(),
+                    attachment.alt.as_deref(),
+                )
+                .await?;
             } else {
-                // For URLs, send as text with link
+                // For URLs, send as text with link; described images become
+                // an image block so the alt text reaches screen readers.
                 let link_msg = format!("{}: {}", attachment.kind.marker_name(), attachment.target);
-                let link_body = serde_json::json!({
+                let mut link_body = serde_json::json!({
                     "channel": message.recipient,
                     "text": link_msg
                 });
+                if let Some(alt) = attachment
+                    .alt
+                    .as_ref()
+                    .filter(|_| attachment.kind.is_image_like())
+                {
+                    link_body["blocks"] = serde_json::json!([{
+                        "type": "image",
+                        "image_url": attachment.target,
+                        "alt_text": alt,
+                    }]);
+                }
+                super::rate_limit::acquire("slack", &message.recipient).await;
                 self.http_client()
                     .post("https://slack.com/api/chat.postMessage")
                     .bearer_auth(&self.bot_token)
@@ -302,12 +989,163 @@ impl Channel for SlackChannel {
         Ok(())
     }
 
+    // Slack has no typing indicator for bot tokens outside the legacy RTM
+    // API; threaded replies show an assistant status instead.
+    async fn start_typing_in_thread(
+        &self,
+        recipient: &str,
+        thread_ts: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let Some(thread_ts) = thread_ts else {
+            return Ok(());
+        };
+        let key = (recipient.to_string(), thread_ts.to_string());
+        if self
+            .typing_status
+            .lock()
+            .get(&key)
+            .is_some_and(|set| set.elapsed() < TYPING_STATUS_REFRESH)
+        {
+            return Ok(());
+        }
+
+        self.set_thread_status(recipient, thread_ts, TYPING_STATUS)
+            .await?;
+        self.typing_status.lock().insert(key, Instant::now());
+        Ok(())
+    }
+
+    async fn stop_typing_in_thread(
+        &self,
+        recipient: &str,
+        thread_ts: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let Some(thread_ts) = thread_ts else {
+            return Ok(());
+        };
+        let was_set = self
+            .typing_status
+            .lock()
+            .remove(&(recipient.to_string(), thread_ts.to_string()))
+            .is_some();
+        if was_set {
+            self.set_thread_status(recipient, thread_ts, "").await?;
+        }
+        Ok(())
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        self.stream_mode != StreamMode::Off
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_edit: self.supports_draft_updates(),
+            max_message_len: Some(SLACK_MAX_MESSAGE_CHARS),
+            ..ChannelCapabilities::default()
+        }
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        if self.stream_mode == StreamMode::Off {
+            return Ok(None);
+        }
+
+        let mut body = serde_json::json!({
+            "channel": message.recipient,
+            "text": if message.content.is_empty() { "..." } else { message.content.as_str() },
+        });
+        if let Some(ref ts) = message.thread_ts {
+            body["thread_ts"] = serde_json::json!(ts);
+        }
+
+        super::rate_limit::acquire("slack", &message.recipient).await;
+        let posted = self.post_api("chat.postMessage", &body).await?;
+        let ts = posted
+            .get("ts")
+            .and_then(|t| t.as_str())
+            .map(str::to_string);
+        if let Some(ref ts) = ts {
+            self.draft_throttle.mark(ts);
+            self.draft_threads
+                .lock()
+                .insert(ts.clone(), message.thread_ts.clone());
+        }
+        Ok(ts)
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        if !self.draft_throttle.ready(message_id) {
+            return Ok(());
+        }
+
+        let body = serde_json::json!({
+            "channel": recipient,
+            "ts": message_id,
+            "text": markdown::render(text, Dialect::SlackMrkdwn),
+        });
+        match self.post_api("chat.update", &body).await {
+            Ok(_) => self.draft_throttle.mark(message_id),
+            Err(e) => tracing::debug!("{e}"),
+        }
+        Ok(())
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        use super::attachment::parse_attachment_markers;
+
+        self.draft_throttle.clear(message_id);
+        let thread_ts = self.draft_threads.lock().remove(message_id).flatten();
+        let content = super::strip_tool_call_tags(text);
+        let (cleaned, attachments) = parse_attachment_markers(&content);
+
+        // Edits cannot carry uploads, and long replies need several
+        // messages: replace the draft with a regular send.
+        if !attachments.is_empty() || split_message(&cleaned, SLACK_MAX_MESSAGE_CHARS).len() > 1 {
+            let body = serde_json::json!({ "channel": recipient, "ts": message_id });
+            if let Err(e) = self.post_api("chat.delete", &body).await {
+                tracing::debug!("{e}");
+            }
+            return self
+                .send(&SendMessage::new(content, recipient).in_thread(thread_ts))
+                .await;
+        }
+
+        let mut body = serde_json::json!({
+            "channel": recipient,
+            "ts": message_id,
+            "text": markdown::render(&cleaned, Dialect::SlackMrkdwn),
+        });
+        if let Some(blocks) = build_blocks(&cleaned, &[]) {
+            body["blocks"] = serde_json::json!(blocks);
+        }
+        self.post_api("chat.update", &body).await.map(|_| ())
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.draft_throttle.clear(message_id);
+        self.draft_threads.lock().remove(message_id);
+        let body = serde_json::json!({ "channel": recipient, "ts": message_id });
+        self.post_api("chat.delete", &body).await.map(|_| ())
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let bot_user_id = self.get_bot_user_id().await.unwrap_or_default();
         let scoped_channel = self.configured_channel_id();
         let mut discovered_channels: Vec<String> = Vec::new();
         let mut last_discovery = Instant::now();
         let mut last_ts_by_channel: HashMap<String, String> = HashMap::new();
+        let mut followed_threads = FollowedThreads::new();
 
         if let Some(ref channel_id) = scoped_channel {
             tracing::info!("Slack channel listening on #{channel_id}...");
@@ -435,26 +1273,99 @@ impl Channel for SlackChannel {
 
                         last_ts_by_channel.insert(channel_id.clone(), ts.to_string());
 
+                        let Some(content) = self.triggered_content(&channel_id, text, &bot_user_id)
+                        else {
+                            continue;
+                        };
+
                         let channel_msg = ChannelMessage {
                             id: format!("slack_{channel_id}_{ts}"),
                             sender: user.to_string(),
                             reply_target: channel_id.clone(),
-                            content: text.to_string(),
+                            content,
                             channel: "slack".to_string(),
                             timestamp: std::time::SystemTime::now()
                                 .duration_since(std::time::UNIX_EPOCH)
                                 .unwrap_or_default()
                                 .as_secs(),
-                            thread_ts: Self::inbound_thread_ts(msg, ts),
+                            thread_ts: self.reply_thread_ts(msg, ts),
                         };
 
+                        if let Some(ref thread_ts) = channel_msg.thread_ts {
+                            Self::follow_thread(&mut followed_threads, &channel_id, thread_ts, ts);
+                        }
+
                         if tx.send(channel_msg).await.is_err() {
                             return Ok(());
                         }
                     }
                 }
             }
+
+            if !self
+                .poll_followed_threads(&mut followed_threads, &bot_user_id, &tx)
+                .await
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn recent_messages(
+        &self,
+        message: &ChannelMessage,
+        limit: usize,
+    ) -> anyhow::Result<Vec<ChannelMessage>> {
+        let channel_id = message.reply_target.as_str();
+        // Direct message conversation IDs start with `D`.
+        if channel_id.starts_with('D') {
+            return Ok(Vec::new());
         }
+        let Some(ts) = message.id.strip_prefix(&format!("slack_{channel_id}_")) else {
+            return Ok(Vec::new());
+        };
+
+        // Top-level messages carry their own `ts` as thread; only replies in
+        // an existing thread are answered from the thread's history.
+        let thread_ts = message.thread_ts.as_deref().filter(|thread| *thread != ts);
+        let (url, mut query) = match thread_ts {
+            Some(thread_ts) => (
+                "https://slack.com/api/conversations.replies",
+                vec![
+                    ("ts", thread_ts.to_string()),
+                    ("limit", SLACK_THREAD_HISTORY_LIMIT.to_string()),
+                ],
+            ),
+            None => (
+                "https://slack.com/api/conversations.history",
+                vec![("limit", limit.to_string())],
+            ),
+        };
+        query.extend([
+            ("channel", channel_id.to_string()),
+            ("latest", ts.to_string()),
+        ]);
+
+        let data: serde_json::Value = self
+            .http_client()
+            .get(url)
+            .bearer_auth(&self.bot_token)
+            .query(&query)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if data.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = data
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack history lookup failed: {err}");
+        }
+
+        let mut messages = Self::history_messages(channel_id, thread_ts, &data);
+        let skip = messages.len().saturating_sub(limit);
+        Ok(messages.split_off(skip))
     }
 
     async fn health_check(&self) -> bool {
@@ -630,6 +1541,113 @@ mod tests {
         assert_eq!(thread_ts.as_deref(), Some("123.001"));
     }
 
+    #[test]
+    fn reply_thread_ts_keeps_top_level_messages_in_channel_when_disabled() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]).with_thread_replies(false);
+        let top_level = serde_json::json!({ "ts": "123.001" });
+        assert_eq!(ch.reply_thread_ts(&top_level, "123.001"), None);
+
+        let reply = serde_json::json!({ "ts": "123.002", "thread_ts": "123.001" });
+        assert_eq!(
+            ch.reply_thread_ts(&reply, "123.002").as_deref(),
+            Some("123.001")
+        );
+    }
+
+    #[test]
+    fn triggered_content_requires_mention_or_prefix_outside_dms() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![])
+            .with_triggers(true, Some("!claw".into()));
+
+        assert_eq!(
+            ch.triggered_content("C1", "<@UBOT> status please", "UBOT")
+                .as_deref(),
+            Some("status please")
+        );
+        assert_eq!(
+            ch.triggered_content("C1", "!claw status", "UBOT")
+                .as_deref(),
+            Some("status")
+        );
+        assert_eq!(ch.triggered_content("C1", "status please", "UBOT"), None);
+        assert_eq!(
+            ch.triggered_content("D1", "status please", "UBOT")
+                .as_deref(),
+            Some("status please")
+        );
+    }
+
+    #[tokio::test]
+    async fn typing_status_needs_a_thread_and_is_throttled() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        assert!(ch.start_typing_in_thread("C1", None).await.is_ok());
+        assert!(ch.stop_typing_in_thread("C1", None).await.is_ok());
+
+        // A recently set status is not sent again.
+        ch.typing_status
+            .lock()
+            .insert(("C1".into(), "1.000".into()), Instant::now());
+        assert!(ch.start_typing_in_thread("C1", Some("1.000")).await.is_ok());
+        // Nothing was set for this thread, so there is nothing to clear.
+        assert!(ch.stop_typing_in_thread("C1", Some("2.000")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn drafts_follow_stream_mode_and_throttle() {
+        let off = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        assert!(!off.supports_draft_updates());
+        assert_eq!(
+            off.send_draft(&SendMessage::new("draft", "C1"))
+                .await
+                .unwrap(),
+            None
+        );
+
+        let partial = SlackChannel::new("xoxb-fake".into(), None, vec![])
+            .with_streaming(StreamMode::Partial, 60_000);
+        assert!(partial.supports_draft_updates());
+        partial.draft_throttle.mark("1.000");
+        assert!(partial
+            .update_draft("C1", "1.000", "more text")
+            .await
+            .is_ok());
+    }
+
+    #[test]
+    fn follow_thread_tracks_latest_ts_and_caps_count() {
+        let mut threads = FollowedThreads::new();
+        SlackChannel::follow_thread(&mut threads, "C1", "1.000", "1.000");
+        SlackChannel::follow_thread(&mut threads, "C1", "1.000", "1.500");
+        SlackChannel::follow_thread(&mut threads, "C1", "1.000", "1.200");
+        let key = ("C1".to_string(), "1.000".to_string());
+        assert_eq!(threads[&key].last_ts, "1.500");
+        threads.get_mut(&key).unwrap().last_activity -= Duration::from_secs(60);
+
+        for i in 0..MAX_FOLLOWED_THREADS + 5 {
+            SlackChannel::follow_thread(&mut threads, "C2", &format!("{i}.0"), &format!("{i}.0"));
+        }
+        assert_eq!(threads.len(), MAX_FOLLOWED_THREADS);
+        assert!(!threads.contains_key(&key));
+    }
+
+    #[test]
+    fn history_messages_are_oldest_first_without_join_notices() {
+        let data = serde_json::json!({
+            "ok": true,
+            "messages": [
+                { "ts": "1700000003.000200", "user": "U2", "text": "second" },
+                { "ts": "1700000002.000100", "user": "U3", "text": "joined", "subtype": "channel_join" },
+                { "ts": "1700000001.000100", "bot_id": "B1", "text": "first" },
+            ]
+        });
+        let messages = SlackChannel::history_messages("C1", None, &data);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].sender, "B1");
+        assert_eq!(messages[0].timestamp, 1_700_000_001);
+        assert_eq!(messages[1].id, "slack_C1_1700000003.000200");
+        assert_eq!(messages[1].content, "second");
+    }
+
     #[test]
     fn inbound_thread_ts_none_when_ts_missing() {
         let msg = serde_json::json!({});
@@ -659,4 +1677,108 @@ mod tests {
             Some("1700000000.000001")
         );
     }
+
+    #[test]
+    fn build_blocks_skips_plain_replies() {
+        assert!(build_blocks("Just a *short* answer.\nWith two lines.", &[]).is_none());
+    }
+
+    #[test]
+    fn build_blocks_maps_headings_code_dividers_and_images() {
+        let text = "# Result\nAll **good**.\n---\n```rust\nfn main() {}\n```\nDone";
+        let blocks = build_blocks(text, &[("https://example.com/a.png", "chart")]).unwrap();
+        let kinds: Vec<&str> = blocks
+            .iter()
+            .filter_map(|block| block["type"].as_str())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "header",
+                "section",
+                "divider",
+                "rich_text",
+                "section",
+                "image"
+            ]
+        );
+        assert_eq!(blocks[0]["text"]["text"], "Result");
+        assert_eq!(blocks[1]["text"]["text"], "All *good*.");
+        assert_eq!(
+            blocks[3]["elements"][0]["elements"][0]["text"],
+            "fn main() {}"
+        );
+        assert_eq!(blocks[5]["alt_text"], "chart");
+    }
+
+    #[test]
+    fn verify_slack_signature_checks_digest_and_age() {
+        let body = b"command=%2Fask&text=hi";
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+        mac.update(b"v0:1700000000:");
+        mac.update(body);
+        let signature = format!("v0={}", hex::encode(mac.finalize().into_bytes()));
+
+        assert!(verify_slack_signature(
+            "secret",
+            "1700000000",
+            body,
+            &signature,
+            1_700_000_010
+        ));
+        assert!(!verify_slack_signature(
+            "other",
+            "1700000000",
+            body,
+            &signature,
+            1_700_000_010
+        ));
+        assert!(!verify_slack_signature(
+            "secret",
+            "1700000000",
+            body,
+            &signature,
+            1_700_001_000
+        ));
+        assert!(!verify_slack_signature(
+            "secret",
+            "1700000000",
+            body,
+            "deadbeef",
+            1_700_000_010
+        ));
+    }
+
+    #[test]
+    fn slash_command_from_form_requires_allowed_user() {
+        let form: BTreeMap<String, String> = [
+            ("command", "/ask"),
+            ("text", " what changed? "),
+            ("user_id", "U123"),
+            ("channel_id", "C456"),
+            ("response_url", "https://hooks.slack.com/commands/x"),
+            ("trigger_id", "t-1"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["U123".into()]);
+        let command = ch.slash_command_from_form(&form).unwrap();
+        assert_eq!(command.command, "/ask");
+        assert_eq!(command.text, "what changed?");
+        assert_eq!(command.channel_id, "C456");
+
+        let other = SlackChannel::new("xoxb-fake".into(), None, vec!["U999".into()]);
+        assert!(other.slash_command_from_form(&form).is_none());
+
+        let scoped = SlackChannel::new("xoxb-fake".into(), Some("C000".into()), vec!["*".into()]);
+        assert!(scoped.slash_command_from_form(&form).is_none());
+    }
+
+    #[test]
+    fn verify_request_rejects_without_signing_secret() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["*".into()]);
+        assert!(!ch.verify_request("1700000000", b"", "v0=00"));
+    }
 }
@@ -0,0 +1,214 @@
+//! Splitting long replies into messages that fit a channel's length limit.
+//!
+//! Chunks break at paragraph boundaries first, then at line ends, then at
+//! spaces, and only hard-split a run of text that has none of those. A code
+//! fence cut by a split is closed at the end of its chunk and reopened, with
+//! the same info string, at the start of the next, so every chunk renders as
+//! valid markdown on its own. Text without code fences is split losslessly:
+//! concatenating the chunks gives back the input.
+
+const FENCE: &str = "```";
+
+/// Room kept at the end of a chunk for the `\n```` that closes a cut fence.
+const FENCE_CLOSE_RESERVE: usize = FENCE.len() + 1;
+
+#[derive(Debug, Clone, Copy)]
+enum Unit {
+    Chars,
+    Bytes,
+}
+
+impl Unit {
+    fn len(self, text: &str) -> usize {
+        match self {
+            Self::Chars => text.chars().count(),
+            Self::Bytes => text.len(),
+        }
+    }
+
+    /// Byte offset of the end of the longest prefix of `text` that is at most
+    /// `limit` units long, always on a char boundary.
+    fn prefix_end(self, text: &str, limit: usize) -> usize {
+        match self {
+            Self::Chars => text
+                .char_indices()
+                .nth(limit)
+                .map_or(text.len(), |(idx, _)| idx),
+            Self::Bytes => {
+                let mut end = limit.min(text.len());
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                end
+            }
+        }
+    }
+}
+
+/// Split `text` into chunks of at most `max_chars` characters.
+///
+/// Text that already fits is returned as a single chunk, even when empty.
+pub fn split_message(text: &str, max_chars: usize) -> Vec<String> {
+    split_with(text, max_chars, Unit::Chars)
+}
+
+/// Split `text` into chunks of at most `max_bytes` UTF-8 bytes, for
+/// platforms that measure messages in bytes (WeCom, IRC payloads).
+pub fn split_message_bytes(text: &str, max_bytes: usize) -> Vec<String> {
+    split_with(text, max_bytes, Unit::Bytes)
+}
+
+/// Trim each chunk and drop the blank ones, for APIs that reject empty or
+/// whitespace-only messages.
+pub fn non_empty_chunks(chunks: Vec<String>) -> Vec<String> {
+    chunks
+        .into_iter()
+        .map(|chunk| chunk.trim().to_string())
+        .filter(|chunk| !chunk.is_empty())
+        .collect()
+}
+
+fn split_with(text: &str, limit: usize, unit: Unit) -> Vec<String> {
+    let limit = limit.max(1);
+    if unit.len(text) <= limit {
+        return vec![text.to_string()];
+    }
+
+    let has_fences = text.contains(FENCE);
+    let mut chunks = Vec::new();
+    let mut remaining = text;
+    let mut reopen: Option<String> = None;
+
+    while !remaining.is_empty() {
+        let prefix = reopen
+            .take()
+            .map(|fence| format!("{fence}\n"))
+            .unwrap_or_default();
+        let available = limit.saturating_sub(unit.len(&prefix)).max(1);
+        if unit.len(remaining) <= available {
+            chunks.push(format!("{prefix}{remaining}"));
+            break;
+        }
+
+        let budget = if has_fences {
+            available.saturating_sub(FENCE_CLOSE_RESERVE).max(1)
+        } else {
+            available
+        };
+        let mut hard_split = unit.prefix_end(remaining, budget);
+        if hard_split == 0 {
+            // A single character wider than the budget: emit it anyway.
+            hard_split = remaining.chars().next().map_or(0, char::len_utf8);
+        }
+        let end = break_point(&remaining[..hard_split], budget, unit).unwrap_or(hard_split);
+
+        let mut chunk = prefix;
+        chunk.push_str(&remaining[..end]);
+        if let Some(fence) = open_fence(&chunk) {
+            if !chunk.ends_with('\n') {
+                chunk.push('\n');
+            }
+            chunk.push_str(FENCE);
+            reopen = Some(fence);
+        }
+        chunks.push(chunk);
+        remaining = &remaining[end..];
+    }
+
+    chunks
+}
+
+/// Where to end a chunk within `window`: after a blank line or a newline in
+/// the second half of the window, else after the last space.
+fn break_point(window: &str, budget: usize, unit: Unit) -> Option<usize> {
+    let late_enough = |pos: usize| unit.len(&window[..pos]) >= budget / 2;
+
+    if let Some(pos) = window.rfind("\n\n").filter(|&pos| late_enough(pos)) {
+        return Some(pos + 2);
+    }
+    if let Some(pos) = window.rfind('\n').filter(|&pos| late_enough(pos)) {
+        return Some(pos + 1);
+    }
+    window.rfind(' ').filter(|&pos| pos > 0).map(|pos| pos + 1)
+}
+
+/// The opening line of a code fence left open at the end of `chunk`.
+fn open_fence(chunk: &str) -> Option<String> {
+    let mut open: Option<&str> = None;
+    for line in chunk.lines() {
+        let line = line.trim();
+        if line.starts_with(FENCE) {
+            open = match open {
+                Some(_) => None,
+                None => Some(line),
+            };
+        }
+    }
+    open.map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_text_is_one_chunk() {
+        assert_eq!(split_message("hello", 10), vec!["hello"]);
+        assert_eq!(split_message("", 10), vec![""]);
+    }
+
+    #[test]
+    fn prefers_paragraph_breaks() {
+        let text = format!(
+            "{}\n\n{}\n{}",
+            "a".repeat(60),
+            "b".repeat(30),
+            "c".repeat(30)
+        );
+        let chunks = split_message(&text, 100);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], format!("{}\n\n", "a".repeat(60)));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn falls_back_to_spaces_then_hard_split() {
+        let chunks = split_message("one two three four", 9);
+        assert_eq!(chunks, vec!["one two ", "three ", "four"]);
+
+        let chunks = split_message(&"x".repeat(25), 10);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 10));
+    }
+
+    #[test]
+    fn cut_code_fence_is_closed_and_reopened() {
+        let code = (0..40).fold(String::new(), |mut code, i| {
+            use std::fmt::Write;
+            let _ = writeln!(code, "let x{i} = {i};");
+            code
+        });
+        let text = format!("Here:\n```rust\n{code}```\nDone.");
+        let chunks = split_message(&text, 200);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 200, "chunk too long: {chunk:?}");
+            assert_eq!(chunk.matches(FENCE).count() % 2, 0, "unbalanced: {chunk:?}");
+        }
+        assert!(chunks[1].starts_with("```rust\n"));
+        assert!(chunks.last().unwrap().ends_with("Done."));
+    }
+
+    #[test]
+    fn byte_limit_respects_char_boundaries() {
+        let chunks = split_message_bytes(&"你".repeat(10), 7);
+        assert!(chunks.iter().all(|c| c.len() <= 7));
+        assert_eq!(chunks.concat(), "你".repeat(10));
+    }
+
+    #[test]
+    fn non_empty_chunks_trims_and_drops_blanks() {
+        let chunks = non_empty_chunks(vec![" a \n".into(), "  ".into(), "b".into()]);
+        assert_eq!(chunks, vec!["a", "b"]);
+    }
+}
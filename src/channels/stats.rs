@@ -0,0 +1,666 @@
+//! Per-user usage analytics for the `/stats` and `/usage` commands.
+
+use super::identity::Account;
+use crate::config::schema::ModelPricing;
+use crate::memory::MemoryEntry;
+use crate::observability::traits::ObserverMetric;
+use crate::observability::{Observer, ObserverEvent};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Window covered by `/stats`.
+pub const STATS_WINDOW_DAYS: i64 = 7;
+const TOP_TOPICS_LIMIT: usize = 5;
+const TOPIC_MIN_CHARS: usize = 4;
+
+/// Words too common to describe what a conversation was about.
+const TOPIC_STOPWORDS: &[&str] = &[
+    "about",
+    "after",
+    "again",
+    "also",
+    "been",
+    "before",
+    "being",
+    "could",
+    "does",
+    "doing",
+    "done",
+    "from",
+    "have",
+    "here",
+    "just",
+    "know",
+    "like",
+    "make",
+    "more",
+    "need",
+    "please",
+    "should",
+    "some",
+    "something",
+    "tell",
+    "than",
+    "thank",
+    "thanks",
+    "that",
+    "their",
+    "them",
+    "then",
+    "there",
+    "these",
+    "they",
+    "thing",
+    "this",
+    "want",
+    "what",
+    "when",
+    "where",
+    "which",
+    "while",
+    "will",
+    "with",
+    "would",
+    "your",
+];
+
+/// Observer wrapper that sums token usage for one channel turn.
+pub struct UsageCountingObserver {
+    inner: Arc<dyn Observer>,
+    input_tokens: AtomicU64,
+    output_tokens: AtomicU64,
+}
+
+impl UsageCountingObserver {
+    pub fn new(inner: Arc<dyn Observer>) -> Self {
+        Self {
+            inner,
+            input_tokens: AtomicU64::new(0),
+            output_tokens: AtomicU64::new(0),
+        }
+    }
+
+    /// Total `(input, output)` tokens reported so far.
+    pub fn tokens(&self) -> (u64, u64) {
+        (
+            self.input_tokens.load(Ordering::Relaxed),
+            self.output_tokens.load(Ordering::Relaxed),
+        )
+    }
+}
+
+impl Observer for UsageCountingObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if let ObserverEvent::LlmResponse {
+            input_tokens,
+            output_tokens,
+            ..
+        } = event
+        {
+            self.input_tokens
+                .fetch_add(input_tokens.unwrap_or(0), Ordering::Relaxed);
+            self.output_tokens
+                .fetch_add(output_tokens.unwrap_or(0), Ordering::Relaxed);
+        }
+        self.inner.record_event(event);
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        self.inner.record_metric(metric);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// One answered message.
+#[derive(Debug, Clone)]
+pub struct TurnRecord {
+    pub channel: String,
+    pub sender: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub latency_ms: u64,
+}
+
+/// Aggregated usage for one sender (or all accounts of one identity).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageSummary {
+    pub messages: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// `None` when no configured price matched any model used.
+    pub cost_usd: Option<f64>,
+    pub avg_latency_ms: u64,
+}
+
+/// SQLite-backed usage log for channel turns.
+pub struct UsageStats {
+    db_path: PathBuf,
+    prices: HashMap<String, ModelPricing>,
+}
+
+impl UsageStats {
+    pub fn new(workspace_dir: &Path, prices: HashMap<String, ModelPricing>) -> Self {
+        Self {
+            db_path: workspace_dir.join("state").join("channel_stats.db"),
+            prices,
+        }
+    }
+
+    fn with_connection<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        if let Some(parent) = self.db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create state directory: {}", parent.display())
+            })?;
+        }
+
+        let conn = Connection::open(&self.db_path)
+            .with_context(|| format!("Failed to open channel stats: {}", self.db_path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS turns (
+                id            INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel       TEXT NOT NULL,
+                sender        TEXT NOT NULL,
+                model         TEXT NOT NULL,
+                input_tokens  INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cost_usd      REAL,
+                latency_ms    INTEGER NOT NULL,
+                created_at    TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_turns_sender ON turns(channel, sender, created_at);",
+        )
+        .context("Failed to initialize channel stats schema")?;
+
+        f(&conn)
+    }
+
+    /// Estimated cost in USD, or `None` when the model has no configured price.
+    fn estimate_cost(&self, model: &str, input_tokens: u64, output_tokens: u64) -> Option<f64> {
+        let pricing = self.prices.get(model).or_else(|| {
+            model
+                .rsplit_once('/')
+                .and_then(|(_, bare)| self.prices.get(bare))
+        })?;
+        let usage = crate::cost::TokenUsage::new(
+            model,
+            input_tokens,
+            output_tokens,
+            pricing.input,
+            pricing.output,
+        );
+        Some(usage.cost())
+    }
+
+    pub fn record(&self, turn: &TurnRecord) -> Result<()> {
+        self.record_at(turn, Utc::now())
+    }
+
+    fn record_at(&self, turn: &TurnRecord, at: DateTime<Utc>) -> Result<()> {
+        let cost = self.estimate_cost(&turn.model, turn.input_tokens, turn.output_tokens);
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO turns (channel, sender, model, input_tokens, output_tokens, cost_usd, latency_ms, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    turn.channel,
+                    turn.sender,
+                    turn.model,
+                    i64::try_from(turn.input_tokens).unwrap_or(i64::MAX),
+                    i64::try_from(turn.output_tokens).unwrap_or(i64::MAX),
+                    cost,
+                    i64::try_from(turn.latency_ms).unwrap_or(i64::MAX),
+                    at.to_rfc3339()
+                ],
+            )
+            .context("Failed to record channel usage")?;
+            Ok(())
+        })
+    }
+
+    /// Usage of `accounts` since `since`, summed.
+    pub fn summarize(&self, accounts: &[Account], since: DateTime<Utc>) -> Result<UsageSummary> {
+        let mut total = UsageSummary::default();
+        let mut latency_total_ms = 0u64;
+        for (channel, sender) in accounts {
+            let summary = self.summarize_account(channel, sender, since)?;
+            total.messages += summary.messages;
+            total.input_tokens += summary.input_tokens;
+            total.output_tokens += summary.output_tokens;
+            total.cost_usd = match (total.cost_usd, summary.cost_usd) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            };
+            latency_total_ms += summary.avg_latency_ms * summary.messages;
+        }
+        total.avg_latency_ms = latency_total_ms.checked_div(total.messages).unwrap_or(0);
+        Ok(total)
+    }
+
+    /// Usage of every sender since `since`, per channel, sorted by channel.
+    pub fn summarize_by_channel(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(String, UsageSummary)>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT channel, COUNT(*), COALESCE(SUM(input_tokens), 0),
+                        COALESCE(SUM(output_tokens), 0), SUM(cost_usd),
+                        COALESCE(AVG(latency_ms), 0)
+                 FROM turns WHERE created_at >= ?1
+                 GROUP BY channel ORDER BY channel",
+            )?;
+            let rows = stmt.query_map(params![since.to_rfc3339()], |row| {
+                Ok((row.get::<_, String>(0)?, summary_from_row(row, 1)?))
+            })?;
+            Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+        })
+    }
+
+    fn summarize_account(
+        &self,
+        channel: &str,
+        sender: &str,
+        since: DateTime<Utc>,
+    ) -> Result<UsageSummary> {
+        self.with_connection(|conn| {
+            Ok(conn.query_row(
+                "SELECT COUNT(*), COALESCE(SUM(input_tokens), 0), COALESCE(SUM(output_tokens), 0),
+                        SUM(cost_usd), COALESCE(AVG(latency_ms), 0)
+                 FROM turns WHERE channel = ?1 AND sender = ?2 AND created_at >= ?3",
+                params![channel, sender, since.to_rfc3339()],
+                |row| summary_from_row(row, 0),
+            )?)
+        })
+    }
+}
+
+/// Summary from the count, token sums, cost sum and average latency columns
+/// starting at index `first`.
+fn summary_from_row(row: &rusqlite::Row<'_>, first: usize) -> rusqlite::Result<UsageSummary> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let avg_latency_ms = row.get::<_, f64>(first + 4)?.max(0.0).round() as u64;
+    Ok(UsageSummary {
+        messages: u64::try_from(row.get::<_, i64>(first)?).unwrap_or_default(),
+        input_tokens: u64::try_from(row.get::<_, i64>(first + 1)?).unwrap_or_default(),
+        output_tokens: u64::try_from(row.get::<_, i64>(first + 2)?).unwrap_or_default(),
+        cost_usd: row.get(first + 3)?,
+        avg_latency_ms,
+    })
+}
+
+/// Whether an auto-saved conversation entry belongs to `sender` on `channel`
+/// and falls inside the stats window.
+fn is_recent_entry_from(
+    entry: &MemoryEntry,
+    channel: &str,
+    sender: &str,
+    since: DateTime<Utc>,
+) -> bool {
+    let Some(rest) = entry.key.strip_prefix(&format!("{channel}_")) else {
+        return false;
+    };
+    if !rest.starts_with(&format!("{sender}_")) && !rest.contains(&format!("_{sender}_")) {
+        return false;
+    }
+    DateTime::parse_from_rfc3339(&entry.timestamp)
+        .map_or(true, |ts| ts.with_timezone(&Utc) >= since)
+}
+
+/// Group messages by their recurring keywords and return the largest
+/// clusters as `(keyword, message_count)`.
+pub fn cluster_topics(messages: &[&str], limit: usize) -> Vec<(String, usize)> {
+    let mut clusters: HashMap<String, usize> = HashMap::new();
+    for message in messages {
+        let keywords: HashSet<String> = message
+            .split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .filter(|w| {
+                w.chars().count() >= TOPIC_MIN_CHARS
+                    && !w.chars().all(|c| c.is_ascii_digit())
+                    && !TOPIC_STOPWORDS.contains(&w.as_str())
+            })
+            .collect();
+        for keyword in keywords {
+            *clusters.entry(keyword).or_default() += 1;
+        }
+    }
+
+    let mut ranked: Vec<(String, usize)> = clusters
+        .into_iter()
+        .filter(|(_, count)| *count >= 2)
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Top topics from the auto-saved messages of `accounts` in the stats window.
+pub fn top_topics(
+    entries: &[MemoryEntry],
+    accounts: &[Account],
+    since: DateTime<Utc>,
+) -> Vec<(String, usize)> {
+    let messages: Vec<&str> = entries
+        .iter()
+        .filter(|entry| {
+            accounts
+                .iter()
+                .any(|(channel, sender)| is_recent_entry_from(entry, channel, sender, since))
+        })
+        .map(|entry| entry.content.as_str())
+        .collect();
+    cluster_topics(&messages, TOP_TOPICS_LIMIT)
+}
+
+/// Start of the `/stats` window.
+pub fn window_start() -> DateTime<Utc> {
+    Utc::now() - Duration::days(STATS_WINDOW_DAYS)
+}
+
+/// Midnight UTC today, the start of the `/usage` totals.
+pub fn today_start() -> DateTime<Utc> {
+    Utc::now().date_naive().and_time(NaiveTime::MIN).and_utc()
+}
+
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Render the `/stats` reply.
+pub fn render_stats(summary: &UsageSummary, topics: &[(String, usize)]) -> String {
+    let mut out = format!("📊 Your usage (last {STATS_WINDOW_DAYS} days)\n");
+    let _ = write!(out, "\n• Messages: {}", summary.messages);
+    if summary.messages == 0 {
+        out.push_str("\n\nNo answered messages yet in this period.");
+        return out;
+    }
+
+    let total = summary.input_tokens.saturating_add(summary.output_tokens);
+    let _ = write!(
+        out,
+        "\n• Tokens: {} ({} in / {} out)",
+        group_thousands(total),
+        group_thousands(summary.input_tokens),
+        group_thousands(summary.output_tokens)
+    );
+    match summary.cost_usd {
+        Some(cost) => {
+            let _ = write!(out, "\n• Estimated cost: ${cost:.2}");
+        }
+        None => out.push_str("\n• Estimated cost: n/a (no pricing in [cost.prices])"),
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let avg_secs = summary.avg_latency_ms as f64 / 1000.0;
+    let _ = write!(out, "\n• Avg response time: {avg_secs:.1}s");
+
+    if !topics.is_empty() {
+        let list = topics
+            .iter()
+            .map(|(topic, count)| format!("{topic} ({count})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = write!(out, "\n• Top topics: {list}");
+    }
+    out
+}
+
+/// The conversation a `/usage` reply describes.
+pub struct SessionUsage<'a> {
+    pub provider: &'a str,
+    pub model: &'a str,
+    /// Messages kept in the session history.
+    pub history_messages: usize,
+    /// Characters in those messages.
+    pub history_chars: usize,
+}
+
+/// Render the `/usage` reply. `today` is `None` when usage stats are off.
+pub fn render_usage(session: &SessionUsage<'_>, today: Option<&UsageSummary>) -> String {
+    let mut out = String::from("📈 This session\n");
+    let _ = write!(
+        out,
+        "\n• Model: `{}` ({})\n• History: {} messages (~{} tokens of context)",
+        session.model,
+        session.provider,
+        session.history_messages,
+        group_thousands(u64::try_from(session.history_chars / 4).unwrap_or(u64::MAX))
+    );
+    let Some(today) = today else {
+        return out;
+    };
+
+    let total = today.input_tokens.saturating_add(today.output_tokens);
+    let _ = write!(
+        out,
+        "\n\nToday\n\n• Messages: {}\n• Tokens: {} ({} in / {} out)",
+        today.messages,
+        group_thousands(total),
+        group_thousands(today.input_tokens),
+        group_thousands(today.output_tokens)
+    );
+    if let Some(cost) = today.cost_usd {
+        let _ = write!(out, "\n• Estimated cost: ${cost:.2}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryCategory;
+    use crate::observability::NoopObserver;
+    use tempfile::TempDir;
+
+    fn turn(sender: &str, model: &str, input: u64, output: u64, latency_ms: u64) -> TurnRecord {
+        TurnRecord {
+            channel: "telegram".into(),
+            sender: sender.into(),
+            model: model.into(),
+            input_tokens: input,
+            output_tokens: output,
+            latency_ms,
+        }
+    }
+
+    fn alice() -> Vec<Account> {
+        vec![("telegram".to_string(), "alice".to_string())]
+    }
+
+    fn entry(key: &str, content: &str, timestamp: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: content.into(),
+            category: MemoryCategory::Conversation,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn counting_observer_sums_llm_usage() {
+        let observer = UsageCountingObserver::new(Arc::new(NoopObserver));
+        for _ in 0..2 {
+            observer.record_event(&ObserverEvent::LlmResponse {
+                provider: "openrouter".into(),
+                model: "m".into(),
+                duration: std::time::Duration::from_millis(5),
+                success: true,
+                error_message: None,
+                input_tokens: Some(100),
+                output_tokens: Some(20),
+            });
+        }
+        assert_eq!(observer.tokens(), (200, 40));
+    }
+
+    #[test]
+    fn summarize_aggregates_sender_window() {
+        let tmp = TempDir::new().unwrap();
+        let mut prices = HashMap::new();
+        prices.insert(
+            "gpt-4o".to_string(),
+            ModelPricing {
+                input: 2.0,
+                output: 10.0,
+            },
+        );
+        let stats = UsageStats::new(tmp.path(), prices);
+        stats
+            .record(&turn("alice", "openai/gpt-4o", 1_000_000, 100_000, 1000))
+            .unwrap();
+        stats.record(&turn("alice", "gpt-4o", 0, 0, 3000)).unwrap();
+        stats.record(&turn("bob", "gpt-4o", 5, 5, 10)).unwrap();
+        stats
+            .record_at(
+                &turn("alice", "gpt-4o", 9, 9, 9),
+                Utc::now() - Duration::days(30),
+            )
+            .unwrap();
+
+        let summary = stats.summarize(&alice(), window_start()).unwrap();
+        assert_eq!(summary.messages, 2);
+        assert_eq!(summary.input_tokens, 1_000_000);
+        assert_eq!(summary.output_tokens, 100_000);
+        assert!((summary.cost_usd.unwrap() - 3.0).abs() < 1e-9);
+        assert_eq!(summary.avg_latency_ms, 2000);
+    }
+
+    #[test]
+    fn summarize_sums_linked_accounts() {
+        let tmp = TempDir::new().unwrap();
+        let stats = UsageStats::new(tmp.path(), HashMap::new());
+        stats
+            .record(&turn("alice", "gpt-4o", 100, 10, 1000))
+            .unwrap();
+        stats
+            .record(&TurnRecord {
+                channel: "slack".into(),
+                ..turn("U024BE7LH", "gpt-4o", 50, 5, 4000)
+            })
+            .unwrap();
+
+        let mut accounts = alice();
+        accounts.push(("slack".to_string(), "U024BE7LH".to_string()));
+        let summary = stats.summarize(&accounts, window_start()).unwrap();
+        assert_eq!(summary.messages, 2);
+        assert_eq!(summary.input_tokens, 150);
+        assert_eq!(summary.avg_latency_ms, 2500);
+        assert_eq!(
+            stats.summarize(&alice(), window_start()).unwrap().messages,
+            1
+        );
+
+        let by_channel = stats.summarize_by_channel(window_start()).unwrap();
+        let channels: Vec<(&str, u64)> = by_channel
+            .iter()
+            .map(|(channel, summary)| (channel.as_str(), summary.input_tokens))
+            .collect();
+        assert_eq!(channels, vec![("slack", 50), ("telegram", 100)]);
+    }
+
+    #[test]
+    fn cost_is_unknown_without_pricing() {
+        let tmp = TempDir::new().unwrap();
+        let stats = UsageStats::new(tmp.path(), HashMap::new());
+        stats.record(&turn("alice", "local", 10, 10, 10)).unwrap();
+        let summary = stats.summarize(&alice(), window_start()).unwrap();
+        assert_eq!(summary.cost_usd, None);
+        assert!(render_stats(&summary, &[]).contains("n/a"));
+    }
+
+    #[test]
+    fn top_topics_clusters_sender_messages() {
+        let now = Utc::now().to_rfc3339();
+        let old = (Utc::now() - Duration::days(40)).to_rfc3339();
+        let entries = vec![
+            entry("telegram_alice_1", "Deploy the invoices service", &now),
+            entry(
+                "telegram_alice_2",
+                "Why did the invoices deploy fail?",
+                &now,
+            ),
+            entry("telegram_alice_3", "Summarize invoices for March", &now),
+            entry("telegram_bob_1", "invoices invoices", &now),
+            entry("telegram_alice_4", "kubernetes kubernetes", &old),
+            entry("telegram_42_alice_5", "kubernetes rollout", &now),
+        ];
+        let topics = top_topics(&entries, &alice(), window_start());
+        assert_eq!(topics[0], ("invoices".to_string(), 3));
+        assert_eq!(topics[1], ("deploy".to_string(), 2));
+        assert!(topics.iter().all(|(topic, _)| topic != "kubernetes"));
+    }
+
+    #[test]
+    fn render_stats_formats_summary() {
+        let text = render_stats(
+            &UsageSummary {
+                messages: 12,
+                input_tokens: 30_000,
+                output_tokens: 4_210,
+                cost_usd: Some(0.4213),
+                avg_latency_ms: 3200,
+            },
+            &[("invoices".into(), 3)],
+        );
+        assert!(text.contains("Messages: 12"));
+        assert!(text.contains("Tokens: 34,210 (30,000 in / 4,210 out)"));
+        assert!(text.contains("Estimated cost: $0.42"));
+        assert!(text.contains("Avg response time: 3.2s"));
+        assert!(text.contains("Top topics: invoices (3)"));
+    }
+
+    #[test]
+    fn render_usage_formats_session_and_today() {
+        let session = SessionUsage {
+            provider: "openrouter",
+            model: "gpt-4o",
+            history_messages: 6,
+            history_chars: 8000,
+        };
+        let text = render_usage(
+            &session,
+            Some(&UsageSummary {
+                messages: 3,
+                input_tokens: 1200,
+                output_tokens: 300,
+                cost_usd: None,
+                avg_latency_ms: 900,
+            }),
+        );
+        assert!(text.contains("Model: `gpt-4o` (openrouter)"));
+        assert!(text.contains("History: 6 messages (~2,000 tokens of context)"));
+        assert!(text.contains("Tokens: 1,500 (1,200 in / 300 out)"));
+        assert!(!text.contains("Estimated cost"));
+        assert!(!render_usage(&session, None).contains("Today"));
+        assert!(today_start() <= Utc::now());
+    }
+}
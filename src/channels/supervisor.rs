@@ -0,0 +1,359 @@
+//! Supervision of channel listeners and their connection state.
+//!
+//! Each channel's `listen` runs under a supervisor that restarts it with
+//! exponential backoff when it returns or fails. While it runs, the channel's
+//! `health_check` is probed periodically; after
+//! [`MAX_FAILED_HEALTH_CHECKS`] failures in a row the listener is torn down
+//! and reconnected, so a connection that hangs without erroring (a dead long
+//! poll or WebSocket) does not silently stop the bot. Connection state is kept
+//! per channel for the admin API and the Prometheus endpoint.
+
+use super::traits::{self, Channel};
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// How often a running listener's connection is probed.
+pub const CHANNEL_HEALTH_CHECK_SECS: u64 = 30;
+/// Consecutive failed probes before the listener is reconnected.
+pub const MAX_FAILED_HEALTH_CHECKS: u32 = 3;
+/// Upper bound for one `health_check` call.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// `listen` started; no successful health check yet.
+    Connecting,
+    Connected,
+    /// Health checks are failing; the listener is still running.
+    Degraded,
+    /// Waiting out the backoff before the next `listen`.
+    Reconnecting,
+    Stopped,
+}
+
+/// Connection state of one channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChannelConnection {
+    pub state: ConnectionState,
+    /// RFC 3339 time the current state was entered.
+    pub since: String,
+    pub last_connected_at: Option<String>,
+    pub last_error: Option<String>,
+    /// Listener restarts since the runtime started.
+    pub reconnects: u64,
+    pub last_message_at: Option<String>,
+}
+
+fn registry() -> &'static Mutex<BTreeMap<String, ChannelConnection>> {
+    static CONNECTIONS: OnceLock<Mutex<BTreeMap<String, ChannelConnection>>> = OnceLock::new();
+    CONNECTIONS.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+fn update(channel: &str, f: impl FnOnce(&mut ChannelConnection)) {
+    let now = Utc::now().to_rfc3339();
+    let mut connections = registry().lock().unwrap_or_else(|e| e.into_inner());
+    let entry = connections
+        .entry(channel.to_string())
+        .or_insert_with(|| ChannelConnection {
+            state: ConnectionState::Connecting,
+            since: now.clone(),
+            last_connected_at: None,
+            last_error: None,
+            reconnects: 0,
+            last_message_at: None,
+        });
+    let previous = entry.state;
+    f(entry);
+    if entry.state != previous {
+        entry.since = now;
+    }
+}
+
+fn set_state(channel: &str, state: ConnectionState, error: Option<String>) {
+    update(channel, |entry| {
+        entry.state = state;
+        if state == ConnectionState::Connected {
+            entry.last_connected_at = Some(Utc::now().to_rfc3339());
+        }
+        if error.is_some() {
+            entry.last_error = error;
+        }
+    });
+}
+
+/// Note an inbound message on `channel`.
+pub fn record_message(channel: &str) {
+    update(channel, |entry| {
+        entry.last_message_at = Some(Utc::now().to_rfc3339());
+    });
+}
+
+/// Connection state of every supervised channel, by name.
+pub fn connections() -> BTreeMap<String, ChannelConnection> {
+    registry().lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Connection gauges and reconnect counters in Prometheus text format.
+pub fn prometheus_metrics() -> String {
+    render_metrics(&connections())
+}
+
+fn render_metrics(connections: &BTreeMap<String, ChannelConnection>) -> String {
+    if connections.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from(
+        "# HELP zeroclaw_channel_connected Whether the channel listener is connected (1) or not (0).\n\
+         # TYPE zeroclaw_channel_connected gauge\n",
+    );
+    for (name, connection) in connections {
+        let connected = u8::from(connection.state == ConnectionState::Connected);
+        let _ = writeln!(
+            out,
+            "zeroclaw_channel_connected{{channel=\"{}\"}} {connected}",
+            escape_label(name)
+        );
+    }
+    out.push_str(
+        "# HELP zeroclaw_channel_reconnects_total Channel listener restarts.\n\
+         # TYPE zeroclaw_channel_reconnects_total counter\n",
+    );
+    for (name, connection) in connections {
+        let _ = writeln!(
+            out,
+            "zeroclaw_channel_reconnects_total{{channel=\"{}\"}} {}",
+            escape_label(name),
+            connection.reconnects
+        );
+    }
+    out
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub(super) fn spawn_supervised_listener(
+    ch: Arc<dyn Channel>,
+    tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+    initial_backoff_secs: u64,
+    max_backoff_secs: u64,
+) -> tokio::task::JoinHandle<()> {
+    spawn_supervised_listener_with_health_interval(
+        ch,
+        tx,
+        initial_backoff_secs,
+        max_backoff_secs,
+        Duration::from_secs(CHANNEL_HEALTH_CHECK_SECS),
+    )
+}
+
+pub(super) fn spawn_supervised_listener_with_health_interval(
+    ch: Arc<dyn Channel>,
+    tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+    initial_backoff_secs: u64,
+    max_backoff_secs: u64,
+    health_interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    let health_interval = if health_interval.is_zero() {
+        Duration::from_secs(1)
+    } else {
+        health_interval
+    };
+
+    tokio::spawn(async move {
+        let name = ch.name().to_string();
+        let component = format!("channel:{name}");
+        let mut backoff = initial_backoff_secs.max(1);
+        let max_backoff = max_backoff_secs.max(backoff);
+
+        loop {
+            crate::health::mark_component_ok(&component);
+            set_state(&name, ConnectionState::Connecting, None);
+            let mut health = tokio::time::interval(health_interval);
+            health.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            let mut probe: Option<tokio::task::JoinHandle<bool>> = None;
+            let mut failed_checks = 0;
+            let result = {
+                let listen_future = ch.listen(tx.clone());
+                tokio::pin!(listen_future);
+
+                loop {
+                    tokio::select! {
+                        _ = health.tick(), if probe.is_none() => {
+                            let ch = Arc::clone(&ch);
+                            probe = Some(tokio::spawn(async move {
+                                tokio::time::timeout(HEALTH_CHECK_TIMEOUT, ch.health_check())
+                                    .await
+                                    .unwrap_or(false)
+                            }));
+                        }
+                        healthy = async { probe.as_mut().expect("probe is running").await }, if probe.is_some() => {
+                            probe = None;
+                            if healthy.unwrap_or(false) {
+                                failed_checks = 0;
+                                crate::health::mark_component_ok(&component);
+                                set_state(&name, ConnectionState::Connected, None);
+                                continue;
+                            }
+                            failed_checks += 1;
+                            let error = format!("health check failed ({failed_checks} in a row)");
+                            tracing::warn!("Channel {name}: {error}");
+                            crate::health::mark_component_error(&component, &error);
+                            set_state(&name, ConnectionState::Degraded, Some(error));
+                            if failed_checks >= MAX_FAILED_HEALTH_CHECKS {
+                                break Err(anyhow::anyhow!(
+                                    "connection unhealthy after {failed_checks} failed health checks"
+                                ));
+                            }
+                        }
+                        result = &mut listen_future => break result,
+                    }
+                }
+            };
+            if let Some(probe) = probe {
+                probe.abort();
+            }
+
+            if tx.is_closed() {
+                set_state(&name, ConnectionState::Stopped, None);
+                break;
+            }
+
+            match result {
+                Ok(()) => {
+                    tracing::warn!("Channel {name} exited unexpectedly; restarting");
+                    crate::health::mark_component_error(&component, "listener exited unexpectedly");
+                    set_state(
+                        &name,
+                        ConnectionState::Reconnecting,
+                        Some("listener exited unexpectedly".into()),
+                    );
+                    // Clean exit — reset backoff since the listener ran successfully
+                    backoff = initial_backoff_secs.max(1);
+                }
+                Err(e) => {
+                    tracing::error!("Channel {name} error: {e}; restarting");
+                    crate::health::mark_component_error(&component, e.to_string());
+                    set_state(&name, ConnectionState::Reconnecting, Some(e.to_string()));
+                }
+            }
+
+            crate::health::bump_component_restart(&component);
+            update(&name, |entry| entry.reconnects += 1);
+            tokio::time::sleep(Duration::from_secs(backoff)).await;
+            // Double backoff AFTER sleeping so first error uses initial_backoff
+            backoff = backoff.saturating_mul(2).min(max_backoff);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::{ChannelMessage, SendMessage};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Listens forever; healthy until `healthy` is cleared.
+    struct HangingChannel {
+        name: String,
+        healthy: AtomicBool,
+        listens: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for HangingChannel {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn send(&self, _message: &SendMessage) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            self.listens.fetch_add(1, Ordering::SeqCst);
+            std::future::pending().await
+        }
+
+        async fn health_check(&self) -> bool {
+            self.healthy.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_health_checks_reconnect_a_hung_listener() {
+        let name = format!("test-supervisor-{}", uuid::Uuid::new_v4());
+        let channel = Arc::new(HangingChannel {
+            name: name.clone(),
+            healthy: AtomicBool::new(true),
+            listens: AtomicUsize::new(0),
+        });
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let handle = spawn_supervised_listener_with_health_interval(
+            channel.clone(),
+            tx,
+            1,
+            1,
+            Duration::from_millis(20),
+        );
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(connections()[&name].state, ConnectionState::Connected);
+        assert!(connections()[&name].last_connected_at.is_some());
+
+        channel.healthy.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let connection = connections()[&name].clone();
+        assert_eq!(connection.state, ConnectionState::Reconnecting);
+        assert_eq!(connection.reconnects, 1);
+        assert!(connection
+            .last_error
+            .as_deref()
+            .is_some_and(|e| e.contains("3 failed health checks")));
+        assert!(prometheus_metrics().contains(&format!(
+            "zeroclaw_channel_reconnects_total{{channel=\"{name}\"}} 1"
+        )));
+
+        drop(rx);
+        handle.abort();
+        assert_eq!(channel.listens.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn metrics_report_connected_channels() {
+        let connection = |state| ChannelConnection {
+            state,
+            since: String::new(),
+            last_connected_at: None,
+            last_error: None,
+            reconnects: 2,
+            last_message_at: None,
+        };
+        let metrics = render_metrics(&BTreeMap::from([
+            (
+                "discord".to_string(),
+                connection(ConnectionState::Reconnecting),
+            ),
+            (
+                "telegram".to_string(),
+                connection(ConnectionState::Connected),
+            ),
+        ]));
+        assert!(metrics.contains("zeroclaw_channel_connected{channel=\"discord\"} 0\n"));
+        assert!(metrics.contains("zeroclaw_channel_connected{channel=\"telegram\"} 1\n"));
+        assert!(metrics.contains("zeroclaw_channel_reconnects_total{channel=\"telegram\"} 2\n"));
+        assert!(render_metrics(&BTreeMap::new()).is_empty());
+    }
+}
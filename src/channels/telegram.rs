@@ -1,4 +1,9 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::buttons::parse_button_markers;
+use super::draft::truncate_chars;
+use super::markdown::{self, Dialect};
+use super::polls::{self, parse_poll_markers, Poll};
+use super::split::split_message;
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
 use crate::config::{Config, StreamMode};
 use crate::security::pairing::PairingGuard;
 use anyhow::Context;
@@ -16,6 +21,13 @@ const TELEGRAM_MAX_MESSAGE_LENGTH: usize = 4096;
 /// Reserve space for continuation markers added by send_text_chunks:
 /// worst case is "(continued)\n\n" + chunk + "\n\n(continues...)" = 30 extra chars
 const TELEGRAM_CONTINUATION_OVERHEAD: usize = 30;
+/// Telegram's maximum length for a media caption
+const TELEGRAM_MAX_CAPTION_LENGTH: usize = 1024;
+/// Server-side wait of one `getUpdates` long poll, in seconds.
+const TELEGRAM_LONG_POLL_SECS: u64 = 30;
+/// Client-side bound on a long poll beyond the server-side wait, so a
+/// half-open connection is dropped instead of blocking the listener forever.
+const TELEGRAM_LONG_POLL_GRACE: Duration = Duration::from_secs(15);
 const TELEGRAM_ACK_REACTIONS: &[&str] = &["⚡️", "👌", "👀", "🔥", "👍"];
 
 /// Metadata for an incoming document or photo attachment.
@@ -37,59 +49,15 @@ enum IncomingAttachmentKind {
 const TELEGRAM_BIND_COMMAND: &str = "/bind";
 
 /// Split a message into chunks that respect Telegram's 4096 character limit.
-/// Tries to split at word boundaries when possible, and handles continuation.
 /// The effective per-chunk limit is reduced to leave room for continuation markers.
 fn split_message_for_telegram(message: &str) -> Vec<String> {
     if message.chars().count() <= TELEGRAM_MAX_MESSAGE_LENGTH {
         return vec![message.to_string()];
     }
-
-    let mut chunks = Vec::new();
-    let mut remaining = message;
-    let chunk_limit = TELEGRAM_MAX_MESSAGE_LENGTH - TELEGRAM_CONTINUATION_OVERHEAD;
-
-    while !remaining.is_empty() {
-        // If the remainder fits within the full limit, take it all (last chunk
-        // or single chunk — continuation overhead is at most 14 chars).
-        if remaining.chars().count() <= TELEGRAM_MAX_MESSAGE_LENGTH {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        // Find the byte offset for the Nth character boundary.
-        let hard_split = remaining
-            .char_indices()
-            .nth(chunk_limit)
-            .map_or(remaining.len(), |(idx, _)| idx);
-
-        let chunk_end = if hard_split == remaining.len() {
-            hard_split
-        } else {
-            // Try to find a good break point (newline, then space)
-            let search_area = &remaining[..hard_split];
-
-            // Prefer splitting at newline
-            if let Some(pos) = search_area.rfind('\n') {
-                // Don't split if the newline is too close to the start
-                if search_area[..pos].chars().count() >= chunk_limit / 2 {
-                    pos + 1
-                } else {
-                    // Try space as fallback
-                    search_area.rfind(' ').unwrap_or(hard_split) + 1
-                }
-            } else if let Some(pos) = search_area.rfind(' ') {
-                pos + 1
-            } else {
-                // Hard split at character boundary
-                hard_split
-            }
-        };
-
-        chunks.push(remaining[..chunk_end].to_string());
-        remaining = &remaining[chunk_end..];
-    }
-
-    chunks
+    split_message(
+        message,
+        TELEGRAM_MAX_MESSAGE_LENGTH - TELEGRAM_CONTINUATION_OVERHEAD,
+    )
 }
 
 fn pick_uniform_index(len: usize) -> usize {
@@ -131,12 +99,16 @@ enum TelegramAttachmentKind {
     Video,
     Audio,
     Voice,
+    Sticker,
+    Animation,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct TelegramAttachment {
     kind: TelegramAttachmentKind,
     target: String,
+    /// Text sent as the media caption.
+    caption: Option<String>,
 }
 
 impl TelegramAttachmentKind {
@@ -147,9 +119,27 @@ impl TelegramAttachmentKind {
             "VIDEO" => Some(Self::Video),
             "AUDIO" => Some(Self::Audio),
             "VOICE" => Some(Self::Voice),
+            "STICKER" => Some(Self::Sticker),
+            "ANIMATION" | "GIF" => Some(Self::Animation),
             _ => None,
         }
     }
+
+    /// `sendPhoto` turns a GIF into a still image, so GIF images are sent
+    /// as animations.
+    fn for_target(self, target: &str) -> Self {
+        let is_gif = target
+            .split(['?', '#'])
+            .next()
+            .unwrap_or(target)
+            .to_ascii_lowercase()
+            .ends_with(".gif");
+        if self == Self::Image && is_gif {
+            Self::Animation
+        } else {
+            self
+        }
+    }
 }
 
 /// Check whether a file path has a recognized image extension.
@@ -205,7 +195,9 @@ fn infer_attachment_kind_from_target(target: &str) -> Option<TelegramAttachmentK
         .to_ascii_lowercase();
 
     match extension.as_str() {
-        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => Some(TelegramAttachmentKind::Image),
+        "png" | "jpg" | "jpeg" | "webp" | "bmp" => Some(TelegramAttachmentKind::Image),
+        "gif" => Some(TelegramAttachmentKind::Animation),
+        "tgs" => Some(TelegramAttachmentKind::Sticker),
         "mp4" | "mov" | "mkv" | "avi" | "webm" => Some(TelegramAttachmentKind::Video),
         "mp3" | "m4a" | "wav" | "flac" => Some(TelegramAttachmentKind::Audio),
         "ogg" | "oga" | "opus" => Some(TelegramAttachmentKind::Voice),
@@ -236,6 +228,7 @@ fn parse_path_only_attachment(message: &str) -> Option<TelegramAttachment> {
     Some(TelegramAttachment {
         kind,
         target: candidate.to_string(),
+        caption: None,
     })
 }
 
@@ -244,50 +237,106 @@ fn strip_tool_call_tags(message: &str) -> String {
     super::strip_tool_call_tags(message)
 }
 
+/// Parse attachment markers, keeping the text on each marker's line as the
+/// caption of the media it describes.
 fn parse_attachment_markers(message: &str) -> (String, Vec<TelegramAttachment>) {
-    let mut cleaned = String::with_capacity(message.len());
-    let mut attachments = Vec::new();
-    let mut cursor = 0;
-
-    while cursor < message.len() {
-        let Some(open_rel) = message[cursor..].find('[') else {
-            cleaned.push_str(&message[cursor..]);
-            break;
-        };
+    let (cleaned, attachments) =
+        super::attachment::parse_captioned_attachments(message, TELEGRAM_MAX_CAPTION_LENGTH);
+    let attachments = attachments
+        .into_iter()
+        .filter_map(|attachment| {
+            Some(TelegramAttachment {
+                kind: TelegramAttachmentKind::from_marker(attachment.kind.marker_name())?,
+                target: attachment.target,
+                caption: attachment.caption,
+            })
+        })
+        .collect();
+    (cleaned, attachments)
+}
 
-        let open = cursor + open_rel;
-        cleaned.push_str(&message[cursor..open]);
+/// Text sent with an inline keyboard when the reply has no other text;
+/// Telegram rejects `sendMessage` with an empty body.
+const TELEGRAM_BUTTONS_DEFAULT_TEXT: &str = "Choose an option:";
 
-        let Some(close_rel) = message[open..].find(']') else {
-            cleaned.push_str(&message[open..]);
-            break;
-        };
+/// Prefix of the `callback_data` carried by inline keyboard buttons.
+const TELEGRAM_BUTTON_CALLBACK_PREFIX: &str = "btn:";
 
-        let close = open + close_rel;
-        let marker = &message[open + 1..close];
+/// How long polls stay open. Telegram closes them itself after at most
+/// 600 seconds and then reports the final tally.
+const TELEGRAM_POLL_OPEN_PERIOD_SECS: u64 = 600;
+/// Telegram's limits for poll questions and options.
+const TELEGRAM_MAX_POLL_QUESTION_CHARS: usize = 300;
+const TELEGRAM_MAX_POLL_OPTION_CHARS: usize = 100;
 
-        let parsed = marker.split_once(':').and_then(|(kind, target)| {
-            let kind = TelegramAttachmentKind::from_marker(kind)?;
-            let target = target.trim();
-            if target.is_empty() {
-                return None;
-            }
-            Some(TelegramAttachment {
-                kind,
-                target: target.to_string(),
-            })
-        });
+/// Build a `reply_markup` inline keyboard from parsed button rows.
+///
+/// Buttons carry their position as `callback_data` (Telegram caps it at 64
+/// bytes); the label is looked up again from the message when tapped.
+fn build_inline_keyboard(rows: &[Vec<String>]) -> Option<serde_json::Value> {
+    if rows.is_empty() {
+        return None;
+    }
 
-        if let Some(attachment) = parsed {
-            attachments.push(attachment);
-        } else {
-            cleaned.push_str(&message[open..=close]);
-        }
+    let keyboard: Vec<Vec<serde_json::Value>> = rows
+        .iter()
+        .enumerate()
+        .map(|(row_index, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(col_index, label)| {
+                    serde_json::json!({
+                        "text": label,
+                        "callback_data": format!(
+                            "{TELEGRAM_BUTTON_CALLBACK_PREFIX}{row_index}:{col_index}"
+                        ),
+                    })
+                })
+                .collect()
+        })
+        .collect();
 
-        cursor = close + 1;
-    }
+    Some(serde_json::json!({ "inline_keyboard": keyboard }))
+}
 
-    (cleaned.trim().to_string(), attachments)
+/// Read `result.message_id` from a successful `sendMessage` response.
+async fn sent_message_id(response: reqwest::Response) -> Option<i64> {
+    let data: serde_json::Value = response.json().await.ok()?;
+    data.get("result")?
+        .get("message_id")?
+        .as_i64()
+}
+
+/// Resolve the label of the button a `callback_query` was sent for, falling
+/// back to the raw callback data for buttons this channel did not create.
+fn callback_button_label(callback: &serde_json::Value) -> Option<String> {
+    let data = callback.get("data").and_then(serde_json::Value::as_str)?;
+
+    let label = callback
+        .get("message")
+        .and_then(|message| message.get("reply_markup"))
+        .and_then(|markup| markup.get("inline_keyboard"))
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_array)
+        .flatten()
+        .find(|button| {
+            button
+                .get("callback_data")
+                .and_then(serde_json::Value::as_str)
+                == Some(data)
+        })
+        .and_then(|button| button.get("text"))
+        .and_then(serde_json::Value::as_str);
+
+    let label = match label {
+        Some(label) => label,
+        // Our own button on a message Telegram no longer includes.
+        None if data.starts_with(TELEGRAM_BUTTON_CALLBACK_PREFIX) => return None,
+        None => data,
+    };
+    (!label.trim().is_empty()).then(|| label.to_string())
 }
 
 /// Telegram Bot API maximum file download size (20 MB).
@@ -304,6 +353,7 @@ pub struct TelegramChannel {
     draft_update_interval_ms: u64,
     last_draft_edit: Mutex<std::collections::HashMap<String, std::time::Instant>>,
     mention_only: bool,
+    trigger_prefix: Option<String>,
     bot_username: Mutex<Option<String>>,
     /// Base URL for the Telegram Bot API. Defaults to `https://api.telegram.org`.
     /// Override for local Bot API servers or testing.
@@ -311,6 +361,9 @@ pub struct TelegramChannel {
     transcription: Option<crate::config::TranscriptionConfig>,
     voice_transcriptions: Mutex<std::collections::HashMap<String, String>>,
     workspace_dir: Option<std::path::PathBuf>,
+    /// Chat (`reply_target`, thread) of each poll sent, by poll ID, until
+    /// it closes.
+    open_polls: Mutex<std::collections::HashMap<String, (String, Option<String>)>>,
 }
 
 impl TelegramChannel {
@@ -337,14 +390,23 @@ impl TelegramChannel {
             last_draft_edit: Mutex::new(std::collections::HashMap::new()),
             typing_handle: Mutex::new(None),
             mention_only,
+            trigger_prefix: None,
             bot_username: Mutex::new(None),
             api_base: "https://api.telegram.org".to_string(),
             transcription: None,
             voice_transcriptions: Mutex::new(std::collections::HashMap::new()),
             workspace_dir: None,
+            open_polls: Mutex::new(std::collections::HashMap::new()),
         }
     }
 
+    /// Answer group messages that start with `prefix` (see
+    /// [`super::trigger`]).
+    pub fn with_trigger_prefix(mut self, prefix: Option<String>) -> Self {
+        self.trigger_prefix = super::trigger::normalize_prefix(prefix);
+        self
+    }
+
     /// Configure workspace directory for saving downloaded attachments.
     pub fn with_workspace_dir(mut self, dir: std::path::PathBuf) -> Self {
         self.workspace_dir = Some(dir);
@@ -386,6 +448,13 @@ impl TelegramChannel {
         }
     }
 
+    /// Accept either a raw Telegram message id (draft ids) or the
+    /// `telegram_<chat_id>_<message_id>` form used for inbound messages.
+    fn parse_message_id(message_id: &str) -> Option<i64> {
+        let raw = message_id.rsplit_once('_').map_or(message_id, |(_, id)| id);
+        raw.parse::<i64>().ok()
+    }
+
     fn extract_update_message_target(update: &serde_json::Value) -> Option<(String, i64)> {
         let message = update.get("message")?;
         let chat_id = message
@@ -1132,10 +1201,12 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             cache.insert(format!("{chat_id}:{message_id}"), text.clone());
         }
 
+        let caption = message.get("caption").and_then(serde_json::Value::as_str);
+        let voice = super::transcription::voice_message_content(&text, caption);
         let content = if let Some(quote) = self.extract_reply_context(message) {
-            format!("{quote}\n\n[Voice] {text}")
+            format!("{quote}\n\n{voice}")
         } else {
-            format!("[Voice] {text}")
+            voice
         };
 
         Some(ChannelMessage {
@@ -1245,13 +1316,22 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         }
 
         let is_group = Self::is_group_message(message);
-        if self.mention_only && is_group {
-            let bot_username = self.bot_username.lock();
-            if let Some(ref bot_username) = *bot_username {
-                if !Self::contains_bot_mention(&text, bot_username) {
+        let prefixed = self
+            .trigger_prefix
+            .as_deref()
+            .filter(|_| is_group)
+            .and_then(|prefix| super::trigger::strip_trigger_prefix(text, prefix));
+        if is_group && prefixed.is_none() {
+            if self.mention_only {
+                let bot_username = self.bot_username.lock();
+                if let Some(ref bot_username) = *bot_username {
+                    if !Self::contains_bot_mention(text, bot_username) {
+                        return None;
+                    }
+                } else {
                     return None;
                 }
-            } else {
+            } else if self.trigger_prefix.is_some() {
                 return None;
             }
         }
@@ -1280,7 +1360,9 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             chat_id.clone()
         };
 
-        let content = if self.mention_only && is_group {
+        let content = if let Some(prefixed) = prefixed {
+            prefixed
+        } else if self.mention_only && is_group {
             let bot_username = self.bot_username.lock();
             let bot_username = bot_username.as_ref()?;
             Self::normalize_incoming_content(&text, bot_username)?
@@ -1308,6 +1390,248 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         })
     }
 
+    /// Turn an inline keyboard tap into a user message carrying the button
+    /// label, replying in the chat and topic the keyboard was posted in.
+    fn parse_callback_query(&self, callback: &serde_json::Value) -> Option<ChannelMessage> {
+        let (username, sender_id, sender_identity) = Self::extract_sender_info(callback);
+
+        let mut identities = vec![username.as_str()];
+        if let Some(id) = sender_id.as_deref() {
+            identities.push(id);
+        }
+
+        if !self.is_any_user_allowed(identities.iter().copied()) {
+            return None;
+        }
+
+        let content = callback_button_label(callback)?;
+        let message = callback.get("message")?;
+        let chat_id = message
+            .get("chat")
+            .and_then(|chat| chat.get("id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string())?;
+        let callback_id = callback
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+
+        let thread_id = message
+            .get("message_thread_id")
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string());
+        let reply_target = if let Some(ref tid) = thread_id {
+            format!("{}:{}", chat_id, tid)
+        } else {
+            chat_id.clone()
+        };
+
+        Some(ChannelMessage {
+            id: format!("telegram_{chat_id}_callback_{callback_id}"),
+            sender: sender_identity,
+            reply_target,
+            content,
+            channel: "telegram".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: thread_id,
+        })
+    }
+
+    /// Acknowledge a button tap and, for allowed users, remove the keyboard
+    /// so each choice is answered once.
+    async fn handle_callback_query(&self, callback: &serde_json::Value) -> Option<ChannelMessage> {
+        let msg = self.parse_callback_query(callback);
+
+        // Telegram shows a spinner on the button until the query is answered.
+        if let Some(callback_id) = callback.get("id").and_then(serde_json::Value::as_str) {
+            let _ = self
+                .http_client()
+                .post(self.api_url("answerCallbackQuery"))
+                .json(&serde_json::json!({ "callback_query_id": callback_id }))
+                .send()
+                .await;
+        }
+
+        if msg.is_some() {
+            let message = callback.get("message");
+            let chat_id = message
+                .and_then(|m| m.get("chat"))
+                .and_then(|chat| chat.get("id"))
+                .and_then(serde_json::Value::as_i64);
+            let message_id = message
+                .and_then(|m| m.get("message_id"))
+                .and_then(serde_json::Value::as_i64);
+            if let (Some(chat_id), Some(message_id)) = (chat_id, message_id) {
+                let _ = self
+                    .http_client()
+                    .post(self.api_url("editMessageReplyMarkup"))
+                    .json(&serde_json::json!({
+                        "chat_id": chat_id,
+                        "message_id": message_id,
+                        "reply_markup": { "inline_keyboard": [] },
+                    }))
+                    .send()
+                    .await;
+            }
+        }
+
+        msg
+    }
+
+    /// Send reply text with its attachment and button markers, returning
+    /// the id of the first text message sent.
+    async fn send_content(
+        &self,
+        content: &str,
+        chat_id: &str,
+        thread_id: Option<&str>,
+    ) -> anyhow::Result<Option<i64>> {
+        let (content, button_rows) = parse_button_markers(content);
+        let keyboard = build_inline_keyboard(&button_rows);
+        let (text_without_markers, attachments) = parse_attachment_markers(&content);
+
+        if !attachments.is_empty() {
+            let mut sent_id = None;
+            if !text_without_markers.is_empty() {
+                sent_id = self
+                    .send_text_chunks(&text_without_markers, chat_id, thread_id)
+                    .await?;
+            }
+
+            for attachment in &attachments {
+                self.send_attachment(chat_id, thread_id, attachment).await?;
+            }
+
+            // Send the keyboard last so it sits under the reply it belongs to.
+            if keyboard.is_some() {
+                self.send_text_chunks_with_keyboard("", chat_id, thread_id, keyboard.as_ref())
+                    .await?;
+            }
+
+            return Ok(sent_id);
+        }
+
+        if keyboard.is_none() {
+            if let Some(attachment) = parse_path_only_attachment(&content) {
+                self.send_attachment(chat_id, thread_id, &attachment)
+                    .await?;
+                return Ok(None);
+            }
+        }
+
+        self.send_text_chunks_with_keyboard(&content, chat_id, thread_id, keyboard.as_ref())
+            .await
+    }
+
+    /// Send a native poll and remember its chat so the results can be
+    /// reported when it closes.
+    async fn send_poll(
+        &self,
+        reply_target: &str,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        poll: &Poll,
+    ) -> anyhow::Result<()> {
+        let options: Vec<serde_json::Value> = poll
+            .options
+            .iter()
+            .map(|option| {
+                serde_json::json!({
+                    "text": truncate_chars(option, TELEGRAM_MAX_POLL_OPTION_CHARS)
+                })
+            })
+            .collect();
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "question": truncate_chars(&poll.question, TELEGRAM_MAX_POLL_QUESTION_CHARS),
+            "options": options,
+            "is_anonymous": false,
+            "open_period": TELEGRAM_POLL_OPEN_PERIOD_SECS,
+        });
+        if let Some(tid) = thread_id {
+            body["message_thread_id"] = serde_json::Value::String(tid.to_string());
+        }
+
+        super::rate_limit::acquire("telegram", chat_id).await;
+
+        let resp = self
+            .http_client()
+            .post(self.api_url("sendPoll"))
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram sendPoll failed: {err}");
+        }
+
+        let data: serde_json::Value = resp.json().await?;
+        if let Some(poll_id) = data
+            .get("result")
+            .and_then(|r| r.get("poll"))
+            .and_then(|p| p.get("id"))
+            .and_then(serde_json::Value::as_str)
+        {
+            self.open_polls.lock().insert(
+                poll_id.to_string(),
+                (reply_target.to_string(), thread_id.map(str::to_string)),
+            );
+        }
+        Ok(())
+    }
+
+    /// Turn a `poll` update for a closed poll sent by this bot into a
+    /// message with the results.
+    fn handle_poll_update(&self, poll: &serde_json::Value) -> Option<ChannelMessage> {
+        if !poll
+            .get("is_closed")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+        {
+            return None;
+        }
+        let poll_id = poll.get("id").and_then(serde_json::Value::as_str)?;
+        let (reply_target, thread_id) = self.open_polls.lock().remove(poll_id)?;
+
+        let question = poll
+            .get("question")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+        let counts: Vec<(String, u64)> = poll
+            .get("options")
+            .and_then(serde_json::Value::as_array)
+            .map(|options| {
+                options
+                    .iter()
+                    .map(|option| {
+                        (
+                            option
+                                .get("text")
+                                .and_then(serde_json::Value::as_str)
+                                .unwrap_or_default()
+                                .to_string(),
+                            option
+                                .get("voter_count")
+                                .and_then(serde_json::Value::as_u64)
+                                .unwrap_or(0),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(polls::results_message(
+            "telegram",
+            format!("telegram_poll_{poll_id}"),
+            &reply_target,
+            thread_id,
+            polls::format_results(question, &counts),
+        ))
+    }
+
     /// Download a Telegram photo by file_id, resize to fit within 1024px, and return as base64 data URI.
     async fn resolve_photo_data_uri(&self, file_id: &str) -> anyhow::Result<String> {
         use base64::Engine as _;
@@ -1354,173 +1678,32 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         Ok(format!("data:image/jpeg;base64,{}", b64))
     }
 
-    /// Convert Markdown to Telegram HTML format.
-    /// Telegram HTML supports: <b>, <i>, <u>, <s>, <code>, <pre>, <a href="...">
-    /// This mirrors OpenClaw's markdownToTelegramHtml approach.
-    fn markdown_to_telegram_html(text: &str) -> String {
-        let lines: Vec<&str> = text.split('\n').collect();
-        let mut result_lines: Vec<String> = Vec::new();
-
-        for line in &lines {
-            let trimmed_line = line.trim_start();
-            if trimmed_line.starts_with("```") {
-                // Preserve fence lines so the second-pass block parser can consume them
-                // without interference from inline backtick handling.
-                result_lines.push(trimmed_line.to_string());
-                continue;
-            }
-
-            let mut line_out = String::new();
-
-            // Handle code blocks (``` ... ```) - handled at text level below
-            // Handle headers: ## Title → <b>Title</b>
-            let stripped = line.trim_start_matches('#');
-            let header_level = line.len() - stripped.len();
-            if header_level > 0 && line.starts_with('#') && stripped.starts_with(' ') {
-                let title = Self::escape_html(stripped.trim());
-                result_lines.push(format!("<b>{title}</b>"));
-                continue;
-            }
-
-            // Inline formatting
-            let mut i = 0;
-            let bytes = line.as_bytes();
-            let len = bytes.len();
-            while i < len {
-                // Bold: **text** or __text__
-                if i + 1 < len && bytes[i] == b'*' && bytes[i + 1] == b'*' {
-                    if let Some(end) = line[i + 2..].find("**") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        line_out.push_str(&format!("<b>{inner}</b>"));
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                if i + 1 < len && bytes[i] == b'_' && bytes[i + 1] == b'_' {
-                    if let Some(end) = line[i + 2..].find("__") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        line_out.push_str(&format!("<b>{inner}</b>"));
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                // Italic: *text* or _text_ (single)
-                if bytes[i] == b'*' && (i == 0 || bytes[i - 1] != b'*') {
-                    if let Some(end) = line[i + 1..].find('*') {
-                        if end > 0 {
-                            let inner = Self::escape_html(&line[i + 1..i + 1 + end]);
-                            line_out.push_str(&format!("<i>{inner}</i>"));
-                            i += 2 + end;
-                            continue;
-                        }
-                    }
-                }
-                // Inline code: `code`
-                if bytes[i] == b'`' && (i == 0 || bytes[i - 1] != b'`') {
-                    if let Some(end) = line[i + 1..].find('`') {
-                        let inner = Self::escape_html(&line[i + 1..i + 1 + end]);
-                        line_out.push_str(&format!("<code>{inner}</code>"));
-                        i += 2 + end;
-                        continue;
-                    }
-                }
-                // Markdown link: [text](url)
-                if bytes[i] == b'[' {
-                    if let Some(bracket_end) = line[i + 1..].find(']') {
-                        let text_part = &line[i + 1..i + 1 + bracket_end];
-                        let after_bracket = i + 1 + bracket_end + 1; // position after ']'
-                        if after_bracket < len && bytes[after_bracket] == b'(' {
-                            if let Some(paren_end) = line[after_bracket + 1..].find(')') {
-                                let url = &line[after_bracket + 1..after_bracket + 1 + paren_end];
-                                if url.starts_with("http://") || url.starts_with("https://") {
-                                    let text_html = Self::escape_html(text_part);
-                                    let url_html = Self::escape_html(url);
-                                    line_out.push_str(&format!(
-                                        "<a href=\"{url_html}\">{text_html}</a>"
-                                    ));
-                                    i = after_bracket + 1 + paren_end + 1;
-                                    continue;
-                                }
-                            }
-                        }
-                    }
-                }
-                // Strikethrough: ~~text~~
-                if i + 1 < len && bytes[i] == b'~' && bytes[i + 1] == b'~' {
-                    if let Some(end) = line[i + 2..].find("~~") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        line_out.push_str(&format!("<s>{inner}</s>"));
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                // Default: escape HTML entities
-                let ch = line[i..].chars().next().unwrap();
-                match ch {
-                    '<' => line_out.push_str("&lt;"),
-                    '>' => line_out.push_str("&gt;"),
-                    '&' => line_out.push_str("&amp;"),
-                    '"' => line_out.push_str("&quot;"),
-                    '\'' => line_out.push_str("&#39;"),
-                    _ => line_out.push(ch),
-                }
-                i += ch.len_utf8();
-            }
-            result_lines.push(line_out);
-        }
-
-        // Second pass: handle ``` code blocks across lines
-        let joined = result_lines.join("\n");
-        let mut final_out = String::with_capacity(joined.len());
-        let mut in_code_block = false;
-        let mut code_buf = String::new();
-
-        for line in joined.split('\n') {
-            let trimmed = line.trim();
-            if trimmed.starts_with("```") {
-                if !in_code_block {
-                    in_code_block = true;
-                    code_buf.clear();
-                } else {
-                    in_code_block = false;
-                    let escaped = code_buf.trim_end_matches('\n');
-                    // Telegram HTML parse mode supports <pre> and <code>, but not class attributes.
-                    final_out.push_str(&format!("<pre><code>{escaped}</code></pre>\n"));
-                    code_buf.clear();
-                }
-            } else if in_code_block {
-                code_buf.push_str(line);
-                code_buf.push('\n');
-            } else {
-                final_out.push_str(line);
-                final_out.push('\n');
-            }
-        }
-        if in_code_block && !code_buf.is_empty() {
-            final_out.push_str(&format!(
-                "<pre><code>{}</code></pre>\n",
-                code_buf.trim_end()
-            ));
-        }
-
-        final_out.trim_end_matches('\n').to_string()
-    }
-
-    fn escape_html(s: &str) -> String {
-        s.replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;")
-            .replace('"', "&quot;")
-            .replace('\'', "&#39;")
+    async fn send_text_chunks(
+        &self,
+        message: &str,
+        chat_id: &str,
+        thread_id: Option<&str>,
+    ) -> anyhow::Result<Option<i64>> {
+        self.send_text_chunks_with_keyboard(message, chat_id, thread_id, None)
+            .await
     }
 
-    async fn send_text_chunks(
+    /// Send `message` in chunks, attaching `reply_markup` to the last one,
+    /// and return the id of the first chunk.
+    async fn send_text_chunks_with_keyboard(
         &self,
         message: &str,
         chat_id: &str,
         thread_id: Option<&str>,
-    ) -> anyhow::Result<()> {
+        reply_markup: Option<&serde_json::Value>,
+    ) -> anyhow::Result<Option<i64>> {
+        let message = if message.trim().is_empty() && reply_markup.is_some() {
+            TELEGRAM_BUTTONS_DEFAULT_TEXT
+        } else {
+            message
+        };
         let chunks = split_message_for_telegram(message);
+        let mut first_id = None;
 
         for (index, chunk) in chunks.iter().enumerate() {
             let text = if chunks.len() > 1 {
@@ -1535,9 +1718,11 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 chunk.to_string()
             };
 
+            let keyboard = reply_markup.filter(|_| index == chunks.len() - 1);
+
             let mut markdown_body = serde_json::json!({
                 "chat_id": chat_id,
-                "text": Self::markdown_to_telegram_html(&text),
+                "text": markdown::render(&text, Dialect::TelegramHtml),
                 "parse_mode": "HTML"
             });
 
@@ -1545,6 +1730,11 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             if let Some(tid) = thread_id {
                 markdown_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
             }
+            if let Some(keyboard) = keyboard {
+                markdown_body["reply_markup"] = keyboard.clone();
+            }
+
+            super::rate_limit::acquire("telegram", chat_id).await;
 
             let markdown_resp = self
                 .http_client()
@@ -1554,8 +1744,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .await?;
 
             if markdown_resp.status().is_success() {
-                if index < chunks.len() - 1 {
-                    tokio::time::sleep(Duration::from_millis(100)).await;
+                if index == 0 {
+                    first_id = sent_message_id(markdown_resp).await;
                 }
                 continue;
             }
@@ -1569,13 +1759,16 @@ Allowlist Telegram username (without '@') or numeric user ID.",
 
             let mut plain_body = serde_json::json!({
                 "chat_id": chat_id,
-                "text": text,
+                "text": markdown::render(&text, Dialect::Plain),
             });
 
             // Add message_thread_id for forum topic support
             if let Some(tid) = thread_id {
                 plain_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
             }
+            if let Some(keyboard) = keyboard {
+                plain_body["reply_markup"] = keyboard.clone();
+            }
             let plain_resp = self
                 .http_client()
                 .post(self.api_url("sendMessage"))
@@ -1594,13 +1787,12 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                     plain_err
                 );
             }
-
-            if index < chunks.len() - 1 {
-                tokio::time::sleep(Duration::from_millis(100)).await;
+            if index == 0 {
+                first_id = sent_message_id(plain_resp).await;
             }
         }
 
-        Ok(())
+        Ok(first_id)
     }
 
     async fn send_media_by_url(
@@ -1625,6 +1817,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             body["caption"] = serde_json::Value::String(cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url(method))
@@ -1648,27 +1842,43 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         attachment: &TelegramAttachment,
     ) -> anyhow::Result<()> {
         let target = attachment.target.trim();
+        let kind = attachment.kind.for_target(target);
+        let caption = attachment.caption.as_deref();
+
+        // Stickers take no caption, so it goes out as a message first.
+        if kind == TelegramAttachmentKind::Sticker {
+            if let Some(caption) = caption {
+                self.send_text_chunks(caption, chat_id, thread_id).await?;
+            }
+        }
 
         if is_http_url(target) {
-            let result = match attachment.kind {
+            let result = match kind {
                 TelegramAttachmentKind::Image => {
-                    self.send_photo_by_url(chat_id, thread_id, target, None)
+                    self.send_photo_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
                 TelegramAttachmentKind::Document => {
-                    self.send_document_by_url(chat_id, thread_id, target, None)
+                    self.send_document_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
                 TelegramAttachmentKind::Video => {
-                    self.send_video_by_url(chat_id, thread_id, target, None)
+                    self.send_video_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
                 TelegramAttachmentKind::Audio => {
-                    self.send_audio_by_url(chat_id, thread_id, target, None)
+                    self.send_audio_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
                 TelegramAttachmentKind::Voice => {
-                    self.send_voice_by_url(chat_id, thread_id, target, None)
+                    self.send_voice_by_url(chat_id, thread_id, target, caption)
+                        .await
+                }
+                TelegramAttachmentKind::Sticker => {
+                    self.send_sticker_by_url(chat_id, thread_id, target).await
+                }
+                TelegramAttachmentKind::Animation => {
+                    self.send_animation_by_url(chat_id, thread_id, target, caption)
                         .await
                 }
             };
@@ -1682,14 +1892,21 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                     error = %e,
                     "Telegram send media by URL failed; falling back to text link"
                 );
-                let kind_label = match attachment.kind {
+                let kind_label = match kind {
                     TelegramAttachmentKind::Image => "Image",
                     TelegramAttachmentKind::Document => "Document",
                     TelegramAttachmentKind::Video => "Video",
                     TelegramAttachmentKind::Audio => "Audio",
                     TelegramAttachmentKind::Voice => "Voice",
+                    TelegramAttachmentKind::Sticker => "Sticker",
+                    TelegramAttachmentKind::Animation => "Animation",
+                };
+                let fallback_text = match caption {
+                    Some(caption) if kind != TelegramAttachmentKind::Sticker => {
+                        format!("{caption}\n{kind_label}: {target}")
+                    }
+                    _ => format!("{kind_label}: {target}"),
                 };
-                let fallback_text = format!("{kind_label}: {target}");
                 self.send_text_chunks(&fallback_text, chat_id, thread_id)
                     .await?;
             }
@@ -1717,14 +1934,26 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             anyhow::bail!("Telegram attachment path not found: {target}");
         }
 
-        match attachment.kind {
-            TelegramAttachmentKind::Image => self.send_photo(chat_id, thread_id, path, None).await,
+        match kind {
+            TelegramAttachmentKind::Image => {
+                self.send_photo(chat_id, thread_id, path, caption).await
+            }
             TelegramAttachmentKind::Document => {
-                self.send_document(chat_id, thread_id, path, None).await
+                self.send_document(chat_id, thread_id, path, caption).await
+            }
+            TelegramAttachmentKind::Video => {
+                self.send_video(chat_id, thread_id, path, caption).await
+            }
+            TelegramAttachmentKind::Audio => {
+                self.send_audio(chat_id, thread_id, path, caption).await
+            }
+            TelegramAttachmentKind::Voice => {
+                self.send_voice(chat_id, thread_id, path, caption).await
+            }
+            TelegramAttachmentKind::Sticker => self.send_sticker(chat_id, thread_id, path).await,
+            TelegramAttachmentKind::Animation => {
+                self.send_animation(chat_id, thread_id, path, caption).await
             }
-            TelegramAttachmentKind::Video => self.send_video(chat_id, thread_id, path, None).await,
-            TelegramAttachmentKind::Audio => self.send_audio(chat_id, thread_id, path, None).await,
-            TelegramAttachmentKind::Voice => self.send_voice(chat_id, thread_id, path, None).await,
         }
     }
 
@@ -1756,6 +1985,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             form = form.text("caption", cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendDocument"))
@@ -1795,6 +2026,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             form = form.text("caption", cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendDocument"))
@@ -1839,6 +2072,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             form = form.text("caption", cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendPhoto"))
@@ -1878,6 +2113,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             form = form.text("caption", cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendPhoto"))
@@ -1922,6 +2159,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             form = form.text("caption", cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendVideo"))
@@ -1966,6 +2205,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             form = form.text("caption", cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendAudio"))
@@ -2010,6 +2251,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             form = form.text("caption", cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendVoice"))
@@ -2026,6 +2269,95 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         Ok(())
     }
 
+    /// Upload a local file with `method`, the file going in `media_field`.
+    #[allow(clippy::too_many_arguments)]
+    async fn send_media_file(
+        &self,
+        method: &str,
+        media_field: &str,
+        default_name: &str,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        file_path: &Path,
+        caption: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let file_name = file_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(default_name);
+
+        let file_bytes = tokio::fs::read(file_path).await?;
+        let part = Part::bytes(file_bytes).file_name(file_name.to_string());
+
+        let mut form = Form::new()
+            .text("chat_id", chat_id.to_string())
+            .part(media_field.to_string(), part);
+
+        if let Some(tid) = thread_id {
+            form = form.text("message_thread_id", tid.to_string());
+        }
+
+        if let Some(cap) = caption {
+            form = form.text("caption", cap.to_string());
+        }
+
+        super::rate_limit::acquire("telegram", chat_id).await;
+
+        let resp = self
+            .http_client()
+            .post(self.api_url(method))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Telegram {method} failed: {err}");
+        }
+
+        tracing::info!("Telegram {method} sent to {chat_id}: {file_name}");
+        Ok(())
+    }
+
+    /// Send a sticker (`.webp`, `.tgs` or `.webm`) to a Telegram chat
+    pub async fn send_sticker(
+        &self,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        file_path: &Path,
+    ) -> anyhow::Result<()> {
+        self.send_media_file(
+            "sendSticker",
+            "sticker",
+            "sticker.webp",
+            chat_id,
+            thread_id,
+            file_path,
+            None,
+        )
+        .await
+    }
+
+    /// Send an animation (GIF or silent MP4) to a Telegram chat
+    pub async fn send_animation(
+        &self,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        file_path: &Path,
+        caption: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.send_media_file(
+            "sendAnimation",
+            "animation",
+            "animation.gif",
+            chat_id,
+            thread_id,
+            file_path,
+            caption,
+        )
+        .await
+    }
+
     /// Send a file by URL (Telegram will download it)
     pub async fn send_document_by_url(
         &self,
@@ -2047,6 +2379,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             body["caption"] = serde_json::Value::String(cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendDocument"))
@@ -2084,6 +2418,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             body["caption"] = serde_json::Value::String(cap.to_string());
         }
 
+        super::rate_limit::acquire("telegram", chat_id).await;
+
         let resp = self
             .http_client()
             .post(self.api_url("sendPhoto"))
@@ -2135,6 +2471,36 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         self.send_media_by_url("sendVoice", "voice", chat_id, thread_id, url, caption)
             .await
     }
+
+    /// Send a sticker by URL (Telegram will download it)
+    pub async fn send_sticker_by_url(
+        &self,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        url: &str,
+    ) -> anyhow::Result<()> {
+        self.send_media_by_url("sendSticker", "sticker", chat_id, thread_id, url, None)
+            .await
+    }
+
+    /// Send an animation by URL (Telegram will download it)
+    pub async fn send_animation_by_url(
+        &self,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        url: &str,
+        caption: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.send_media_by_url(
+            "sendAnimation",
+            "animation",
+            chat_id,
+            thread_id,
+            url,
+            caption,
+        )
+        .await
+    }
 }
 
 #[async_trait]
@@ -2147,6 +2513,17 @@ impl Channel for TelegramChannel {
         self.stream_mode != StreamMode::Off
     }
 
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_edit: self.supports_draft_updates(),
+            max_message_len: Some(TELEGRAM_MAX_MESSAGE_LENGTH),
+            attachment_kinds: ChannelCapabilities::rich_attachments(),
+            supports_buttons: true,
+            supports_polls: true,
+            ..ChannelCapabilities::default()
+        }
+    }
+
     async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
         if self.stream_mode == StreamMode::Off {
             return Ok(None);
@@ -2167,6 +2544,8 @@ impl Channel for TelegramChannel {
             body["message_thread_id"] = serde_json::Value::String(tid.to_string());
         }
 
+        super::rate_limit::acquire("telegram", &chat_id).await;
+
         let resp = self
             .client
             .post(self.api_url("sendMessage"))
@@ -2200,6 +2579,8 @@ impl Channel for TelegramChannel {
         text: &str,
     ) -> anyhow::Result<()> {
         let (chat_id, _) = Self::parse_reply_target(recipient);
+        let (text, _) = parse_button_markers(text);
+        let text = text.as_str();
 
         // Rate-limit edits per chat
         {
@@ -2267,7 +2648,9 @@ impl Channel for TelegramChannel {
         message_id: &str,
         text: &str,
     ) -> anyhow::Result<()> {
-        let text = &strip_tool_call_tags(text);
+        let (text, button_rows) = parse_button_markers(&strip_tool_call_tags(text));
+        let text = text.as_str();
+        let keyboard = build_inline_keyboard(&button_rows);
         let (chat_id, thread_id) = Self::parse_reply_target(recipient);
 
         // Clean up rate-limit tracking for this chat
@@ -2313,6 +2696,16 @@ impl Channel for TelegramChannel {
                     .await?;
             }
 
+            if keyboard.is_some() {
+                self.send_text_chunks_with_keyboard(
+                    "",
+                    &chat_id,
+                    thread_id.as_deref(),
+                    keyboard.as_ref(),
+                )
+                .await?;
+            }
+
             return Ok(());
         }
 
@@ -2332,23 +2725,38 @@ impl Channel for TelegramChannel {
 
             // Fall back to chunked send
             return self
-                .send_text_chunks(text, &chat_id, thread_id.as_deref())
-                .await;
+                .send_text_chunks_with_keyboard(
+                    text,
+                    &chat_id,
+                    thread_id.as_deref(),
+                    keyboard.as_ref(),
+                )
+                .await
+                .map(|_| ());
         }
 
         let Some(id) = msg_id else {
             return self
-                .send_text_chunks(text, &chat_id, thread_id.as_deref())
-                .await;
+                .send_text_chunks_with_keyboard(
+                    text,
+                    &chat_id,
+                    thread_id.as_deref(),
+                    keyboard.as_ref(),
+                )
+                .await
+                .map(|_| ());
         };
 
         // Try editing with HTML formatting
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "chat_id": chat_id,
             "message_id": id,
-            "text": Self::markdown_to_telegram_html(text),
+            "text": markdown::render(text, Dialect::TelegramHtml),
             "parse_mode": "HTML",
         });
+        if let Some(keyboard) = &keyboard {
+            body["reply_markup"] = keyboard.clone();
+        }
 
         let resp = self
             .client
@@ -2362,11 +2770,14 @@ impl Channel for TelegramChannel {
         }
 
         // Markdown failed — retry without parse_mode
-        let plain_body = serde_json::json!({
+        let mut plain_body = serde_json::json!({
             "chat_id": chat_id,
             "message_id": id,
-            "text": text,
+            "text": markdown::render(text, Dialect::Plain),
         });
+        if let Some(keyboard) = &keyboard {
+            plain_body["reply_markup"] = keyboard.clone();
+        }
 
         let resp = self
             .client
@@ -2381,8 +2792,9 @@ impl Channel for TelegramChannel {
 
         // Edit failed entirely — fall back to new message
         tracing::warn!("Telegram finalize_draft edit failed; falling back to sendMessage");
-        self.send_text_chunks(text, &chat_id, thread_id.as_deref())
+        self.send_text_chunks_with_keyboard(text, &chat_id, thread_id.as_deref(), keyboard.as_ref())
             .await
+            .map(|_| ())
     }
 
     async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
@@ -2416,7 +2828,37 @@ impl Channel for TelegramChannel {
         Ok(())
     }
 
+    async fn pin_message(&self, channel_id: &str, message_id: &str) -> anyhow::Result<()> {
+        let (chat_id, _) = Self::parse_reply_target(channel_id);
+        let Some(message_id) = Self::parse_message_id(message_id) else {
+            anyhow::bail!("Invalid Telegram message_id '{message_id}'");
+        };
+
+        let response = self
+            .client
+            .post(self.api_url("pinChatMessage"))
+            .json(&serde_json::json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+                "disable_notification": true,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram pinChatMessage failed ({status}): {body}");
+        }
+
+        Ok(())
+    }
+
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        self.send_with_id(message).await.map(|_| ())
+    }
+
+    async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
         // Strip tool_call tags before processing to prevent Markdown parsing failures
         let content = strip_tool_call_tags(&message.content);
 
@@ -2426,28 +2868,16 @@ impl Channel for TelegramChannel {
             None => (message.recipient.as_str(), None),
         };
 
-        let (text_without_markers, attachments) = parse_attachment_markers(&content);
-
-        if !attachments.is_empty() {
-            if !text_without_markers.is_empty() {
-                self.send_text_chunks(&text_without_markers, chat_id, thread_id)
-                    .await?;
-            }
-
-            for attachment in &attachments {
-                self.send_attachment(chat_id, thread_id, attachment).await?;
-            }
-
-            return Ok(());
+        let (content, polls) = parse_poll_markers(&content);
+        let mut sent_id = None;
+        if !content.is_empty() || polls.is_empty() {
+            sent_id = self.send_content(&content, chat_id, thread_id).await?;
         }
-
-        if let Some(attachment) = parse_path_only_attachment(&content) {
-            self.send_attachment(chat_id, thread_id, &attachment)
+        for poll in &polls {
+            self.send_poll(&message.recipient, chat_id, thread_id, poll)
                 .await?;
-            return Ok(());
         }
-
-        self.send_text_chunks(&content, chat_id, thread_id).await
+        Ok(sent_id.map(|id| id.to_string()))
     }
 
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
@@ -2469,9 +2899,16 @@ impl Channel for TelegramChannel {
             let probe = serde_json::json!({
                 "offset": offset,
                 "timeout": 0,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query", "poll"]
             });
-            match self.http_client().post(&url).json(&probe).send().await {
+            match self
+                .http_client()
+                .post(&url)
+                .json(&probe)
+                .timeout(TELEGRAM_LONG_POLL_GRACE)
+                .send()
+                .await
+            {
                 Err(e) => {
                     tracing::warn!("Telegram startup probe error: {e}; retrying in 5s");
                     tokio::time::sleep(std::time::Duration::from_secs(5)).await;
@@ -2541,11 +2978,18 @@ impl Channel for TelegramChannel {
             let url = self.api_url("getUpdates");
             let body = serde_json::json!({
                 "offset": offset,
-                "timeout": 30,
-                "allowed_updates": ["message"]
+                "timeout": TELEGRAM_LONG_POLL_SECS,
+                "allowed_updates": ["message", "callback_query", "poll"]
             });
 
-            let resp = match self.http_client().post(&url).json(&body).send().await {
+            let resp = match self
+                .http_client()
+                .post(&url)
+                .json(&body)
+                .timeout(Duration::from_secs(TELEGRAM_LONG_POLL_SECS) + TELEGRAM_LONG_POLL_GRACE)
+                .send()
+                .await
+            {
                 Ok(r) => r,
                 Err(e) => {
                     tracing::warn!("Telegram poll error: {e}");
@@ -2603,6 +3047,24 @@ Ensure only one `zeroclaw` process is using this bot token."
                         offset = uid + 1;
                     }
 
+                    if let Some(poll) = update.get("poll") {
+                        if let Some(msg) = self.handle_poll_update(poll) {
+                            if tx.send(msg).await.is_err() {
+                                return Ok(());
+                            }
+                        }
+                        continue;
+                    }
+
+                    if let Some(callback) = update.get("callback_query") {
+                        if let Some(msg) = self.handle_callback_query(callback).await {
+                            if tx.send(msg).await.is_err() {
+                                return Ok(());
+                            }
+                        }
+                        continue;
+                    }
+
                     let msg = if let Some(m) = self.parse_update_message(update) {
                         m
                     } else if let Some(m) = self.try_parse_voice_message(update).await {
@@ -2795,6 +3257,23 @@ mod tests {
         assert_eq!(partial.draft_update_interval_ms, 750);
     }
 
+    #[test]
+    fn parse_message_id_accepts_raw_and_prefixed_ids() {
+        assert_eq!(TelegramChannel::parse_message_id("42"), Some(42));
+        assert_eq!(
+            TelegramChannel::parse_message_id("telegram_-100123_42"),
+            Some(42)
+        );
+        assert_eq!(TelegramChannel::parse_message_id("telegram_abc"), None);
+    }
+
+    #[tokio::test]
+    async fn pin_message_rejects_invalid_message_id() {
+        let ch = TelegramChannel::new("fake-token".into(), vec!["*".into()], false);
+        let err = ch.pin_message("123", "not-a-number").await.unwrap_err();
+        assert!(err.to_string().contains("Invalid Telegram message_id"));
+    }
+
     #[tokio::test]
     async fn send_draft_returns_none_when_stream_mode_off() {
         let ch = TelegramChannel::new("fake-token".into(), vec!["*".into()], false);
@@ -2852,36 +3331,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn telegram_markdown_to_html_escapes_quotes_in_link_href() {
-        let rendered = TelegramChannel::markdown_to_telegram_html(
-            "[click](https://example.com?q=\"x\"&a='b')",
-        );
-        assert_eq!(
-            rendered,
-            "<a href=\"https://example.com?q=&quot;x&quot;&amp;a=&#39;b&#39;\">click</a>"
-        );
-    }
-
-    #[test]
-    fn telegram_markdown_to_html_escapes_quotes_in_plain_text() {
-        let rendered = TelegramChannel::markdown_to_telegram_html("say \"hi\" & <tag> 'ok'");
-        assert_eq!(
-            rendered,
-            "say &quot;hi&quot; &amp; &lt;tag&gt; &#39;ok&#39;"
-        );
-    }
-
-    #[test]
-    fn telegram_markdown_to_html_code_block_drops_language_attribute() {
-        let rendered = TelegramChannel::markdown_to_telegram_html(
-            "```rust\" onclick=\"alert(1)\nlet x = 1;\n```",
-        );
-        assert_eq!(rendered, "<pre><code>let x = 1;</code></pre>");
-        assert!(!rendered.contains("language-"));
-        assert!(!rendered.contains("onclick"));
-    }
-
     #[test]
     fn telegram_user_allowed_wildcard() {
         let ch = TelegramChannel::new("t".into(), vec!["*".into()], false);
@@ -2988,10 +3437,15 @@ mod tests {
         let message = "Here are files [IMAGE:/tmp/a.png] and [DOCUMENT:https://example.com/a.pdf]";
         let (cleaned, attachments) = parse_attachment_markers(message);
 
-        assert_eq!(cleaned, "Here are files  and");
+        assert_eq!(cleaned, "");
         assert_eq!(attachments.len(), 2);
         assert_eq!(attachments[0].kind, TelegramAttachmentKind::Image);
         assert_eq!(attachments[0].target, "/tmp/a.png");
+        assert_eq!(
+            attachments[0].caption.as_deref(),
+            Some("Here are files and")
+        );
+        assert_eq!(attachments[1].caption, None);
         assert_eq!(attachments[1].kind, TelegramAttachmentKind::Document);
         assert_eq!(attachments[1].target, "https://example.com/a.pdf");
     }
@@ -3031,6 +3485,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gif_images_are_sent_as_animations() {
+        assert_eq!(
+            TelegramAttachmentKind::from_marker("gif"),
+            Some(TelegramAttachmentKind::Animation)
+        );
+        assert_eq!(
+            TelegramAttachmentKind::from_marker("STICKER"),
+            Some(TelegramAttachmentKind::Sticker)
+        );
+        assert_eq!(
+            TelegramAttachmentKind::Image.for_target("https://example.com/party.GIF?x=1"),
+            TelegramAttachmentKind::Animation
+        );
+        assert_eq!(
+            TelegramAttachmentKind::Image.for_target("/tmp/chart.png"),
+            TelegramAttachmentKind::Image
+        );
+        assert_eq!(
+            infer_attachment_kind_from_target("/tmp/wave.tgs"),
+            Some(TelegramAttachmentKind::Sticker)
+        );
+    }
+
     #[test]
     fn parse_update_message_uses_chat_id_as_reply_target() {
         let ch = TelegramChannel::new("token".into(), vec!["*".into()], false);
@@ -3113,6 +3591,106 @@ mod tests {
         assert_eq!(msg.id, "telegram_-100200300_42");
     }
 
+    // ── Inline keyboard tests ───────────────────────────────────────
+
+    #[test]
+    fn parse_button_markers_builds_one_row_per_marker() {
+        let message = "Deploy now?\n[BUTTONS:Yes|No| Cancel ]\n[BUTTONS:Later]";
+        let (cleaned, rows) = parse_button_markers(message);
+
+        assert_eq!(cleaned, "Deploy now?");
+        assert_eq!(rows, vec![vec!["Yes", "No", "Cancel"], vec!["Later"]]);
+
+        let keyboard = build_inline_keyboard(&rows).unwrap();
+        assert_eq!(keyboard["inline_keyboard"][0][2]["text"], "Cancel");
+        assert_eq!(
+            keyboard["inline_keyboard"][0][2]["callback_data"],
+            "btn:0:2"
+        );
+        assert_eq!(
+            keyboard["inline_keyboard"][1][0]["callback_data"],
+            "btn:1:0"
+        );
+    }
+
+    #[test]
+    fn parse_button_markers_leaves_other_text_untouched() {
+        let message = "  See [IMAGE:/tmp/a.png] and [BUTTONS:| ]  ";
+        let (cleaned, rows) = parse_button_markers(message);
+
+        assert_eq!(cleaned, message);
+        assert!(rows.is_empty());
+        assert!(build_inline_keyboard(&rows).is_none());
+    }
+
+    #[test]
+    fn parse_callback_query_returns_button_label_as_message() {
+        let ch = TelegramChannel::new("token".into(), vec!["alice".into()], false);
+        let callback = serde_json::json!({
+            "id": "cb-1",
+            "from": { "id": 555, "username": "alice" },
+            "data": "btn:0:1",
+            "message": {
+                "message_id": 42,
+                "chat": { "id": -100_200_300 },
+                "message_thread_id": 789,
+                "reply_markup": {
+                    "inline_keyboard": [[
+                        { "text": "Approve", "callback_data": "btn:0:0" },
+                        { "text": "Reject", "callback_data": "btn:0:1" }
+                    ]]
+                }
+            }
+        });
+
+        let msg = ch
+            .parse_callback_query(&callback)
+            .expect("callback from allowed user should parse");
+        assert_eq!(msg.content, "Reject");
+        assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.reply_target, "-100200300:789");
+        assert_eq!(msg.id, "telegram_-100200300_callback_cb-1");
+
+        let stranger = TelegramChannel::new("token".into(), vec!["bob".into()], false);
+        assert!(stranger.parse_callback_query(&callback).is_none());
+    }
+
+    #[test]
+    fn callback_button_label_ignores_own_buttons_without_message() {
+        let callback = serde_json::json!({ "id": "cb-2", "data": "btn:0:0" });
+        assert!(callback_button_label(&callback).is_none());
+
+        let foreign = serde_json::json!({ "id": "cb-3", "data": "yes" });
+        assert_eq!(callback_button_label(&foreign).as_deref(), Some("yes"));
+    }
+
+    #[test]
+    fn closed_poll_update_reports_results_to_its_chat() {
+        let ch = TelegramChannel::new("token".into(), vec!["alice".into()], false);
+        ch.open_polls
+            .lock()
+            .insert("p-1".into(), ("-100:7".into(), Some("7".into())));
+        let mut poll = serde_json::json!({
+            "id": "p-1",
+            "question": "Lunch?",
+            "options": [
+                { "text": "Pizza", "voter_count": 2 },
+                { "text": "Sushi", "voter_count": 1 }
+            ],
+            "is_closed": false
+        });
+        assert!(ch.handle_poll_update(&poll).is_none());
+
+        poll["is_closed"] = serde_json::json!(true);
+        let msg = ch
+            .handle_poll_update(&poll)
+            .expect("closed poll should be reported");
+        assert_eq!(msg.reply_target, "-100:7");
+        assert_eq!(msg.thread_ts.as_deref(), Some("7"));
+        assert!(msg.content.starts_with("[Poll closed] Lunch?\n- Pizza: 2 votes"));
+        assert!(ch.handle_poll_update(&poll).is_none());
+    }
+
     // ── File sending API URL tests ──────────────────────────────────
 
     #[test]
@@ -3740,6 +4318,36 @@ mod tests {
         assert!(ch.parse_update_message(&empty_update).is_none());
     }
 
+    #[test]
+    fn parse_update_message_trigger_prefix_applies_to_groups_only() {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()], false)
+            .with_trigger_prefix(Some("!claw".into()));
+        let update = |text: &str, chat_type: &str| {
+            serde_json::json!({
+                "update_id": 13,
+                "message": {
+                    "message_id": 47,
+                    "text": text,
+                    "from": { "id": 555, "username": "alice" },
+                    "chat": { "id": -100_200_300, "type": chat_type }
+                }
+            })
+        };
+
+        assert!(ch
+            .parse_update_message(&update("status please", "group"))
+            .is_none());
+        let parsed = ch
+            .parse_update_message(&update("!claw status please", "group"))
+            .expect("prefixed message should parse");
+        assert_eq!(parsed.content, "status please");
+
+        let direct = ch
+            .parse_update_message(&update("status please", "private"))
+            .expect("direct messages need no prefix");
+        assert_eq!(direct.content, "status please");
+    }
+
     #[test]
     fn telegram_is_group_message_detects_groups() {
         let group_msg = serde_json::json!({
@@ -0,0 +1,449 @@
+//! Chat confirmation for dangerous tool calls.
+//!
+//! With `[autonomy] channel_approval = true`, a channel turn that calls one
+//! of `channel_approval_tools` (shell, file writes, browser, email and
+//! desktop input by default) first posts "Run `rm -rf build/`? ✅/❌" to the
+//! chat the message came from and only runs the tool once the sender answers
+//! ✅. Channels with tappable buttons get them; elsewhere a ✅/❌ (or yes/no)
+//! reply works. Unanswered prompts are denied after
+//! `channel_approval_timeout_secs`.
+
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::AutonomyConfig;
+use crate::security::AutonomyLevel;
+use crate::tools::{Tool, ToolResult};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+const MAX_SUMMARY_CHARS: usize = 200;
+
+/// Which tools need confirmation, plus the prompts waiting for an answer.
+pub struct ToolApprovals {
+    tools: HashSet<String>,
+    timeout: Duration,
+    pending: Mutex<HashMap<String, PendingApproval>>,
+    /// One prompt per chat at a time; parallel calls wait their turn.
+    prompt_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+struct PendingApproval {
+    requester: String,
+    decision: oneshot::Sender<bool>,
+}
+
+fn chat_key(channel: &str, reply_target: &str) -> String {
+    format!("{channel}:{reply_target}")
+}
+
+impl ToolApprovals {
+    /// `None` when `channel_approval` is off or autonomy is `full`.
+    pub fn from_config(config: &AutonomyConfig) -> Option<Self> {
+        if !config.channel_approval || config.level == AutonomyLevel::Full {
+            return None;
+        }
+        let mut tools: HashSet<String> = config
+            .channel_approval_tools
+            .iter()
+            .filter(|tool| !config.auto_approve.contains(tool))
+            .cloned()
+            .collect();
+        tools.extend(config.always_ask.iter().cloned());
+        Some(Self {
+            tools,
+            timeout: Duration::from_secs(config.channel_approval_timeout_secs.max(1)),
+            pending: Mutex::new(HashMap::new()),
+            prompt_locks: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn needs_approval(&self, tool: &str) -> bool {
+        self.tools.contains(tool)
+    }
+
+    /// Wrap `tools` for one turn so gated calls ask in `msg`'s chat first.
+    pub fn gate_tools(
+        self: &Arc<Self>,
+        tools: &Arc<Vec<Box<dyn Tool>>>,
+        channel: &Arc<dyn Channel>,
+        msg: &ChannelMessage,
+    ) -> Vec<Box<dyn Tool>> {
+        (0..tools.len())
+            .map(|index| {
+                let gate = self.needs_approval(tools[index].name()).then(|| Gate {
+                    approvals: Arc::clone(self),
+                    channel: Arc::clone(channel),
+                    channel_name: msg.channel.clone(),
+                    reply_target: msg.reply_target.clone(),
+                    thread_ts: msg.thread_ts.clone(),
+                    requester: msg.sender.clone(),
+                });
+                Box::new(GatedTool {
+                    tools: Arc::clone(tools),
+                    index,
+                    gate,
+                }) as Box<dyn Tool>
+            })
+            .collect()
+    }
+
+    /// Treat `msg` as the answer to a waiting prompt in its chat.
+    ///
+    /// Returns `true` when the message was consumed as an answer; anything
+    /// else (other senders, unrelated text) goes through as usual.
+    pub fn resolve(&self, msg: &ChannelMessage) -> bool {
+        let Some(approved) = parse_decision(&msg.content) else {
+            return false;
+        };
+        let key = chat_key(&msg.channel, &msg.reply_target);
+        let mut pending = self.pending.lock();
+        if pending
+            .get(&key)
+            .is_none_or(|waiting| waiting.requester != msg.sender)
+        {
+            return false;
+        }
+        if let Some(waiting) = pending.remove(&key) {
+            let _ = waiting.decision.send(approved);
+        }
+        true
+    }
+
+    fn prompt_lock(&self, key: &str) -> Arc<tokio::sync::Mutex<()>> {
+        Arc::clone(self.prompt_locks.lock().entry(key.to_string()).or_default())
+    }
+
+    async fn ask(&self, gate: &Gate, tool: &str, args: &serde_json::Value) -> Result<(), String> {
+        let key = chat_key(&gate.channel_name, &gate.reply_target);
+        let lock = self.prompt_lock(&key);
+        let _turn = lock.lock().await;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(
+            key.clone(),
+            PendingApproval {
+                requester: gate.requester.clone(),
+                decision: tx,
+            },
+        );
+
+        let prompt = render_prompt(tool, args, gate.channel.capabilities().supports_buttons);
+        let sent = gate
+            .channel
+            .send(&SendMessage::new(prompt, &gate.reply_target).in_thread(gate.thread_ts.clone()))
+            .await;
+        if let Err(e) = sent {
+            self.pending.lock().remove(&key);
+            tracing::warn!(tool, "Failed to post approval prompt: {e}");
+            return Err(format!("Could not ask for approval to run `{tool}`."));
+        }
+
+        let decision = tokio::time::timeout(self.timeout, rx).await;
+        self.pending.lock().remove(&key);
+        match decision {
+            Ok(Ok(true)) => {
+                tracing::info!(tool, chat = %key, "Tool call approved in chat");
+                Ok(())
+            }
+            Ok(Ok(false) | Err(_)) => {
+                tracing::info!(tool, chat = %key, "Tool call denied in chat");
+                Err("Denied by user.".to_string())
+            }
+            Err(_) => {
+                tracing::info!(tool, chat = %key, "Tool approval timed out");
+                Err(format!(
+                    "Not approved within {}s; `{tool}` was not run.",
+                    self.timeout.as_secs()
+                ))
+            }
+        }
+    }
+}
+
+/// Where a gated call asks for confirmation.
+struct Gate {
+    approvals: Arc<ToolApprovals>,
+    channel: Arc<dyn Channel>,
+    channel_name: String,
+    reply_target: String,
+    thread_ts: Option<String>,
+    requester: String,
+}
+
+/// A registry tool that asks in chat before running when gated.
+struct GatedTool {
+    tools: Arc<Vec<Box<dyn Tool>>>,
+    index: usize,
+    gate: Option<Gate>,
+}
+
+impl GatedTool {
+    fn inner(&self) -> &dyn Tool {
+        self.tools[self.index].as_ref()
+    }
+}
+
+#[async_trait]
+impl Tool for GatedTool {
+    fn name(&self) -> &str {
+        self.inner().name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner().description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner().parameters_schema()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if let Some(gate) = &self.gate {
+            if let Err(reason) = gate.approvals.ask(gate, self.name(), &args).await {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(reason),
+                });
+            }
+        }
+        self.inner().execute(args).await
+    }
+}
+
+/// Read a ✅/❌ style answer. `None` for anything else.
+pub fn parse_decision(text: &str) -> Option<bool> {
+    let text = text.trim();
+    if text.starts_with(['✅', '👍', '✔']) {
+        return Some(true);
+    }
+    if text.starts_with(['❌', '👎', '✖', '🚫']) {
+        return Some(false);
+    }
+    match text
+        .trim_end_matches(['.', '!'])
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "y" | "yes" | "ok" | "approve" | "approved" | "run" => Some(true),
+        "n" | "no" | "deny" | "denied" | "cancel" | "stop" => Some(false),
+        _ => None,
+    }
+}
+
+/// What the call would do, in one line.
+fn summarize_call(tool: &str, args: &serde_json::Value) -> String {
+    let field = |key: &str| args.get(key).and_then(serde_json::Value::as_str);
+    let summary = match tool {
+        "shell" => field("command").map(str::to_string),
+        "file_write" | "file_edit" => field("path").map(|path| format!("{tool} {path}")),
+        "browser" => field("action").map(|action| match field("url") {
+            Some(url) => format!("browser {action} {url}"),
+            None => format!("browser {action}"),
+        }),
+        _ => None,
+    }
+    .unwrap_or_else(|| format!("{tool} {args}"));
+    crate::util::truncate_with_ellipsis(summary.trim(), MAX_SUMMARY_CHARS)
+}
+
+fn render_prompt(tool: &str, args: &serde_json::Value, buttons: bool) -> String {
+    let question = format!("Run `{}`?", summarize_call(tool, args).replace('`', "'"));
+    if buttons {
+        format!("{question}\n[BUTTONS:✅ Approve|❌ Deny]")
+    } else {
+        format!("{question} ✅/❌")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingTool(Arc<AtomicUsize>, &'static str);
+
+    #[async_trait]
+    impl Tool for CountingTool {
+        fn name(&self) -> &str {
+            self.1
+        }
+
+        fn description(&self) -> &str {
+            "Counts calls"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(ToolResult {
+                success: true,
+                output: "ran".into(),
+                error: None,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingChannel(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            self.0.lock().push(message.content.clone());
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn message(sender: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "1".into(),
+            sender: sender.into(),
+            reply_target: "chat-1".into(),
+            content: content.into(),
+            channel: "test".into(),
+            timestamp: 0,
+            thread_ts: None,
+        }
+    }
+
+    fn approvals(timeout_secs: u64) -> Arc<ToolApprovals> {
+        Arc::new(
+            ToolApprovals::from_config(&AutonomyConfig {
+                channel_approval: true,
+                channel_approval_timeout_secs: timeout_secs,
+                ..AutonomyConfig::default()
+            })
+            .unwrap(),
+        )
+    }
+
+    async fn wait_for_prompt(channel: &RecordingChannel) {
+        for _ in 0..200 {
+            if !channel.0.lock().is_empty() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("no approval prompt sent");
+    }
+
+    #[test]
+    fn from_config_applies_auto_approve_and_always_ask() {
+        assert!(ToolApprovals::from_config(&AutonomyConfig::default()).is_none());
+
+        let approvals = ToolApprovals::from_config(&AutonomyConfig {
+            channel_approval: true,
+            auto_approve: vec!["browser".into()],
+            always_ask: vec!["http_request".into()],
+            ..AutonomyConfig::default()
+        })
+        .unwrap();
+        assert!(approvals.needs_approval("shell"));
+        assert!(approvals.needs_approval("http_request"));
+        assert!(!approvals.needs_approval("browser"));
+        assert!(!approvals.needs_approval("file_read"));
+
+        assert!(ToolApprovals::from_config(&AutonomyConfig {
+            channel_approval: true,
+            level: AutonomyLevel::Full,
+            ..AutonomyConfig::default()
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn parse_decision_accepts_emoji_buttons_and_words() {
+        assert_eq!(parse_decision("✅ Approve"), Some(true));
+        assert_eq!(parse_decision(" yes! "), Some(true));
+        assert_eq!(parse_decision("❌ Deny"), Some(false));
+        assert_eq!(parse_decision("No"), Some(false));
+        assert_eq!(parse_decision("yes but use -i"), None);
+    }
+
+    #[test]
+    fn render_prompt_summarizes_shell_commands() {
+        let args = json!({"command": "rm -rf build/"});
+        assert_eq!(
+            render_prompt("shell", &args, false),
+            "Run `rm -rf build/`? ✅/❌"
+        );
+        assert!(render_prompt("shell", &args, true).ends_with("[BUTTONS:✅ Approve|❌ Deny]"));
+        assert_eq!(
+            summarize_call("file_write", &json!({"path": "a.txt", "content": "x"})),
+            "file_write a.txt"
+        );
+    }
+
+    #[tokio::test]
+    async fn gated_tool_runs_only_after_approval() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let tools: Arc<Vec<Box<dyn Tool>>> = Arc::new(vec![
+            Box::new(CountingTool(Arc::clone(&calls), "shell")),
+            Box::new(CountingTool(Arc::clone(&calls), "file_read")),
+        ]);
+        let recording = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = recording.clone();
+        let approvals = approvals(5);
+        let gated = approvals.gate_tools(&tools, &channel, &message("alice", "clean up"));
+
+        // Ungated tools run straight away.
+        assert!(gated[1].execute(json!({})).await.unwrap().success);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let shell = gated.into_iter().next().unwrap();
+        let run = tokio::spawn(async move { shell.execute(json!({"command": "ls"})).await });
+        wait_for_prompt(&recording).await;
+        assert_eq!(recording.0.lock()[0], "Run `ls`? ✅/❌");
+
+        // Other senders and unrelated text do not answer the prompt.
+        assert!(!approvals.resolve(&message("bob", "✅")));
+        assert!(!approvals.resolve(&message("alice", "what?")));
+        assert!(approvals.resolve(&message("alice", "✅")));
+
+        let result = run.await.unwrap().unwrap();
+        assert!(result.success);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn gated_tool_is_skipped_when_denied() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let tools: Arc<Vec<Box<dyn Tool>>> =
+            Arc::new(vec![Box::new(CountingTool(Arc::clone(&calls), "shell"))]);
+        let recording = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = recording.clone();
+        let approvals = approvals(5);
+        let shell = approvals
+            .gate_tools(&tools, &channel, &message("alice", "clean up"))
+            .pop()
+            .unwrap();
+
+        let run = tokio::spawn(async move { shell.execute(json!({"command": "ls"})).await });
+        wait_for_prompt(&recording).await;
+        assert!(approvals.resolve(&message("alice", "❌")));
+
+        let result = run.await.unwrap().unwrap();
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("Denied by user."));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -1,3 +1,4 @@
+use super::attachment::AttachmentKind;
 use async_trait::async_trait;
 
 /// A message received from or sent to a channel
@@ -9,8 +10,9 @@ pub struct ChannelMessage {
     pub content: String,
     pub channel: String,
     pub timestamp: u64,
-    /// Platform thread identifier (e.g. Slack `ts`, Discord thread ID).
-    /// When set, replies should be posted as threaded responses.
+    /// Platform thread identifier (e.g. Slack `thread_ts`, Discord thread
+    /// channel ID). When set, replies should be posted in that thread, and the
+    /// thread gets its own conversation history.
     pub thread_ts: Option<String>,
 }
 
@@ -56,6 +58,62 @@ impl SendMessage {
     }
 }
 
+/// What a channel can deliver. The dispatcher queries it to generate the
+/// model's delivery instructions.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ChannelCapabilities {
+    /// Whether Markdown in replies is rendered (natively or converted).
+    pub supports_markdown: bool,
+    /// Whether sent messages can be edited (draft streaming).
+    pub supports_edit: bool,
+    /// Longest message sent in one piece; longer replies are split.
+    pub max_message_len: Option<usize>,
+    /// Attachment marker kinds the channel delivers.
+    pub attachment_kinds: Vec<AttachmentKind>,
+    /// Whether `[BUTTONS:...]` markers become tappable buttons.
+    pub supports_buttons: bool,
+    /// Whether `[POLL:...]` markers become native polls.
+    pub supports_polls: bool,
+}
+
+impl ChannelCapabilities {
+    /// Images, documents, video, audio and voice notes.
+    pub fn standard_attachments() -> Vec<AttachmentKind> {
+        vec![
+            AttachmentKind::Image,
+            AttachmentKind::Document,
+            AttachmentKind::Video,
+            AttachmentKind::Audio,
+            AttachmentKind::Voice,
+        ]
+    }
+
+    /// Standard attachments plus stickers and animations.
+    pub fn rich_attachments() -> Vec<AttachmentKind> {
+        let mut kinds = Self::standard_attachments();
+        kinds.extend([AttachmentKind::Sticker, AttachmentKind::Animation]);
+        kinds
+    }
+
+    pub fn supports_attachment(&self, kind: &AttachmentKind) -> bool {
+        self.attachment_kinds.contains(kind)
+    }
+}
+
+impl Default for ChannelCapabilities {
+    fn default() -> Self {
+        Self {
+            supports_markdown: true,
+            supports_edit: false,
+            max_message_len: None,
+            attachment_kinds: Self::standard_attachments(),
+            supports_buttons: false,
+            supports_polls: false,
+        }
+    }
+}
+
 /// Core channel trait — implement for any messaging platform
 #[async_trait]
 pub trait Channel: Send + Sync {
@@ -65,6 +123,13 @@ pub trait Channel: Send + Sync {
     /// Send a message through this channel
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()>;
 
+    /// Send a message and return the id of what was posted, in the form
+    /// [`Channel::pin_message`] accepts. Channels that cannot report it
+    /// return `None`.
+    async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        self.send(message).await.map(|()| None)
+    }
+
     /// Start listening for incoming messages (long-running)
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()>;
 
@@ -84,11 +149,40 @@ pub trait Channel: Send + Sync {
         Ok(())
     }
 
+    /// Typing indicator for a reply posted in `thread_ts`. Defaults to
+    /// [`Channel::start_typing`]; platforms whose indicator is tied to a
+    /// thread (e.g. Slack) override this instead.
+    async fn start_typing_in_thread(
+        &self,
+        recipient: &str,
+        _thread_ts: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.start_typing(recipient).await
+    }
+
+    /// Counterpart of [`Channel::start_typing_in_thread`].
+    async fn stop_typing_in_thread(
+        &self,
+        recipient: &str,
+        _thread_ts: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.stop_typing(recipient).await
+    }
+
     /// Whether this channel supports progressive message updates via draft edits.
     fn supports_draft_updates(&self) -> bool {
         false
     }
 
+    /// What this channel can deliver. The default assumes Markdown and the
+    /// standard attachment kinds, without buttons or a length limit.
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_edit: self.supports_draft_updates(),
+            ..ChannelCapabilities::default()
+        }
+    }
+
     /// Send an initial draft message. Returns a platform-specific message ID for later edits.
     async fn send_draft(&self, _message: &SendMessage) -> anyhow::Result<Option<String>> {
         Ok(None)
@@ -142,6 +236,25 @@ pub trait Channel: Send + Sync {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Pin a message in a conversation so it stays easy to find.
+    ///
+    /// Uses the same identifiers as [`Channel::add_reaction`]. Channels without
+    /// pinning support return an error so callers can report it.
+    async fn pin_message(&self, _channel_id: &str, _message_id: &str) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support pinning messages", self.name())
+    }
+
+    /// Up to `limit` messages posted in the group chat of `message` before
+    /// it, oldest first. Channels without a history API, and direct
+    /// messages, return nothing.
+    async fn recent_messages(
+        &self,
+        _message: &ChannelMessage,
+        _limit: usize,
+    ) -> anyhow::Result<Vec<ChannelMessage>> {
+        Ok(Vec::new())
+    }
 }
 
 #[cfg(test)]
@@ -206,6 +319,11 @@ mod tests {
         assert!(channel.health_check().await);
         assert!(channel.start_typing("bob").await.is_ok());
         assert!(channel.stop_typing("bob").await.is_ok());
+        assert!(channel
+            .start_typing_in_thread("bob", Some("1.0"))
+            .await
+            .is_ok());
+        assert!(channel.stop_typing_in_thread("bob", None).await.is_ok());
         assert!(channel
             .send(&SendMessage::new("hello", "bob"))
             .await
@@ -226,11 +344,20 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn default_pin_message_is_unsupported() {
+        let channel = DummyChannel;
+
+        let err = channel.pin_message("chan_1", "msg_1").await.unwrap_err();
+        assert!(err.to_string().contains("does not support pinning"));
+    }
+
     #[tokio::test]
     async fn default_draft_methods_return_success() {
         let channel = DummyChannel;
 
         assert!(!channel.supports_draft_updates());
+        assert!(!channel.capabilities().supports_edit);
         assert!(channel
             .send_draft(&SendMessage::new("draft", "bob"))
             .await
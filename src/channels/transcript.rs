@@ -0,0 +1,658 @@
+//! Conversation transcripts streamed to external sinks.
+//!
+//! Channel turns are queued as [`TranscriptRecord`]s and written in batches
+//! (every `batch_size` records or `flush_interval_secs`) to each configured
+//! sink: rotating local JSONL files, S3-compatible object storage (one object
+//! per batch), or syslog. Sinks run on a background task, so a slow or
+//! failing sink never delays replies; failed batches are logged and dropped.
+//! [`TranscriptLog::close`] writes out whatever is still queued on shutdown.
+
+use crate::config::{TranscriptSinkConfig, TranscriptsConfig};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Records waiting for the writer before new ones are dropped.
+const QUEUE_CAPACITY: usize = 4096;
+const SYSLOG_SEVERITY_INFO: u8 = 6;
+/// Keep single syslog datagrams under common relay limits.
+const SYSLOG_MAX_CONTENT_CHARS: usize = 7000;
+
+/// One transcript line.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptRecord {
+    /// RFC 3339 time the turn was recorded.
+    pub timestamp: String,
+    pub channel: String,
+    /// Conversation session key (channel, thread and sender).
+    pub session: String,
+    pub sender: String,
+    /// `"user"` or `"assistant"`.
+    pub role: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Destination for transcript batches.
+#[async_trait]
+pub trait TranscriptSink: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn write_batch(&self, records: &[TranscriptRecord]) -> Result<()>;
+}
+
+/// Handle used by the channel runtime to queue transcript records.
+#[derive(Clone)]
+pub struct TranscriptLog {
+    tx: mpsc::Sender<TranscriptRecord>,
+    closing: CancellationToken,
+    writer: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl TranscriptLog {
+    /// Start the batching writer. Returns `None` when export is disabled or
+    /// no sink could be created.
+    pub fn start(config: &TranscriptsConfig, workspace_dir: &Path) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let sinks = create_sinks(config, workspace_dir);
+        if sinks.is_empty() {
+            tracing::warn!("Transcripts enabled but no sink is configured");
+            return None;
+        }
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        let closing = CancellationToken::new();
+        let writer = tokio::spawn(run_writer(
+            rx,
+            sinks,
+            config.batch_size.max(1),
+            Duration::from_secs(config.flush_interval_secs.max(1)),
+            closing.clone(),
+        ));
+        Some(Self {
+            tx,
+            closing,
+            writer: Arc::new(Mutex::new(Some(writer))),
+        })
+    }
+
+    /// Queue a record. Drops it (with a warning) if the writer is backed up.
+    pub fn record(&self, record: TranscriptRecord) {
+        if let Err(e) = self.tx.try_send(record) {
+            tracing::warn!("Dropping transcript record: {e}");
+        }
+    }
+
+    /// Stop accepting records and wait until the queued ones are written.
+    pub async fn close(&self) {
+        self.closing.cancel();
+        let writer = self.writer.lock().take();
+        if let Some(writer) = writer {
+            let _ = writer.await;
+        }
+    }
+}
+
+fn create_sinks(config: &TranscriptsConfig, workspace_dir: &Path) -> Vec<Box<dyn TranscriptSink>> {
+    let mut sinks: Vec<Box<dyn TranscriptSink>> = Vec::new();
+    for sink in &config.sinks {
+        match sink {
+            TranscriptSinkConfig::Jsonl { dir, max_file_mb } => {
+                let dir = PathBuf::from(shellexpand::tilde(dir).into_owned());
+                let dir = if dir.is_absolute() {
+                    dir
+                } else {
+                    workspace_dir.join(dir)
+                };
+                sinks.push(Box::new(JsonlSink::new(
+                    dir,
+                    max_file_mb.saturating_mul(1024 * 1024),
+                )));
+            }
+            TranscriptSinkConfig::S3 {
+                endpoint,
+                bucket,
+                region,
+                prefix,
+                access_key_id,
+                secret_access_key,
+            } => sinks.push(Box::new(S3Sink {
+                endpoint: endpoint.trim_end_matches('/').to_string(),
+                bucket: bucket.clone(),
+                region: region.clone(),
+                prefix: prefix.clone(),
+                access_key_id: access_key_id.clone(),
+                secret_access_key: secret_access_key.clone(),
+            })),
+            TranscriptSinkConfig::Syslog {
+                address,
+                facility,
+                app_name,
+            } => match syslog_facility_code(facility) {
+                Some(facility) => sinks.push(Box::new(SyslogSink {
+                    address: address.clone(),
+                    facility,
+                    app_name: app_name.clone(),
+                })),
+                None => tracing::warn!("Unknown syslog facility '{facility}'; sink skipped"),
+            },
+        }
+    }
+    sinks
+}
+
+async fn run_writer(
+    mut rx: mpsc::Receiver<TranscriptRecord>,
+    sinks: Vec<Box<dyn TranscriptSink>>,
+    batch_size: usize,
+    flush_interval: Duration,
+    closing: CancellationToken,
+) {
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut ticker = tokio::time::interval(flush_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            record = rx.recv() => match record {
+                Some(record) => {
+                    batch.push(record);
+                    if batch.len() >= batch_size {
+                        flush(&sinks, &mut batch).await;
+                    }
+                }
+                None => {
+                    flush(&sinks, &mut batch).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => flush(&sinks, &mut batch).await,
+            () = closing.cancelled() => {
+                rx.close();
+                while let Some(record) = rx.recv().await {
+                    batch.push(record);
+                }
+                flush(&sinks, &mut batch).await;
+                return;
+            }
+        }
+    }
+}
+
+async fn flush(sinks: &[Box<dyn TranscriptSink>], batch: &mut Vec<TranscriptRecord>) {
+    if batch.is_empty() {
+        return;
+    }
+    for sink in sinks {
+        if let Err(e) = sink.write_batch(batch).await {
+            tracing::warn!(
+                "Transcript sink {} failed to write {} records: {e:#}",
+                sink.name(),
+                batch.len()
+            );
+        }
+    }
+    batch.clear();
+}
+
+fn to_jsonl(records: &[TranscriptRecord]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut out, record)?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+// ── JSONL files ─────────────────────────────────────────────────
+
+/// Appends to `transcript-<date>.jsonl`; a full file is renamed to
+/// `transcript-<date>.<n>.jsonl` before writing continues.
+pub struct JsonlSink {
+    dir: PathBuf,
+    max_bytes: u64,
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+impl JsonlSink {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            dir,
+            max_bytes: max_bytes.max(1),
+            write_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn rotate(&self, current: &Path, date: &str) -> Result<()> {
+        let mut n = 1;
+        loop {
+            let rotated = self.dir.join(format!("transcript-{date}.{n}.jsonl"));
+            if !rotated.exists() {
+                fs::rename(current, &rotated)
+                    .with_context(|| format!("Failed to rotate {}", current.display()))?;
+                return Ok(());
+            }
+            n += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl TranscriptSink for JsonlSink {
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    async fn write_batch(&self, records: &[TranscriptRecord]) -> Result<()> {
+        let _guard = self.write_lock.lock().await;
+        let payload = to_jsonl(records)?;
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        let date = Utc::now().format("%Y-%m-%d").to_string();
+        let path = self.dir.join(format!("transcript-{date}.jsonl"));
+        let size = fs::metadata(&path).map_or(0, |m| m.len());
+        if size > 0 && size + payload.len() as u64 > self.max_bytes {
+            self.rotate(&path, &date)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        file.write_all(&payload)?;
+        Ok(())
+    }
+}
+
+// ── S3-compatible object storage ────────────────────────────────
+
+struct S3Sink {
+    endpoint: String,
+    bucket: String,
+    region: String,
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take key of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+impl S3Sink {
+    fn object_key(&self, now: &DateTime<Utc>) -> String {
+        format!(
+            "{}{}/{}-{}.jsonl",
+            self.prefix,
+            now.format("%Y/%m/%d"),
+            now.format("%H%M%S"),
+            uuid::Uuid::new_v4().simple()
+        )
+    }
+
+    /// SigV4 `Authorization` header for a path-style PUT.
+    fn authorization(
+        &self,
+        host: &str,
+        canonical_uri: &str,
+        payload_hash: &str,
+        now: &DateTime<Utc>,
+    ) -> String {
+        let date_stamp = now.format("%Y%m%d").to_string();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "PUT\n{canonical_uri}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}"
+        );
+        let scope = format!("{date_stamp}/{}/s3/aws4_request", self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            sha256_hex(canonical_request.as_bytes())
+        );
+
+        let k_date = hmac_sha256(
+            format!("AWS4{}", self.secret_access_key).as_bytes(),
+            date_stamp.as_bytes(),
+        );
+        let k_region = hmac_sha256(&k_date, self.region.as_bytes());
+        let k_service = hmac_sha256(&k_region, b"s3");
+        let k_signing = hmac_sha256(&k_service, b"aws4_request");
+        let signature = hex::encode(hmac_sha256(&k_signing, string_to_sign.as_bytes()));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.access_key_id
+        )
+    }
+}
+
+#[async_trait]
+impl TranscriptSink for S3Sink {
+    fn name(&self) -> &str {
+        "s3"
+    }
+
+    async fn write_batch(&self, records: &[TranscriptRecord]) -> Result<()> {
+        let payload = to_jsonl(records)?;
+        let now = Utc::now();
+        let key = self.object_key(&now);
+        let canonical_uri = format!(
+            "/{}/{}",
+            urlencoding::encode(&self.bucket),
+            key.split('/')
+                .map(|segment| urlencoding::encode(segment).into_owned())
+                .collect::<Vec<_>>()
+                .join("/")
+        );
+        let url = reqwest::Url::parse(&format!("{}{canonical_uri}", self.endpoint))
+            .context("Invalid S3 endpoint")?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => anyhow::bail!("S3 endpoint has no host"),
+        };
+        let payload_hash = sha256_hex(&payload);
+
+        let response = crate::config::build_runtime_proxy_client("memory.transcripts")
+            .put(url)
+            .header("x-amz-date", now.format("%Y%m%dT%H%M%SZ").to_string())
+            .header("x-amz-content-sha256", &payload_hash)
+            .header(
+                "authorization",
+                self.authorization(&host, &canonical_uri, &payload_hash, &now),
+            )
+            .header("content-type", "application/x-ndjson")
+            .body(payload)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("S3 PUT {key} failed ({status}): {body}");
+        }
+        Ok(())
+    }
+}
+
+// ── Syslog ──────────────────────────────────────────────────────
+
+struct SyslogSink {
+    address: String,
+    facility: u8,
+    app_name: String,
+}
+
+fn syslog_facility_code(name: &str) -> Option<u8> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "user" => Some(1),
+        "daemon" => Some(3),
+        "local0" => Some(16),
+        "local1" => Some(17),
+        "local2" => Some(18),
+        "local3" => Some(19),
+        "local4" => Some(20),
+        "local5" => Some(21),
+        "local6" => Some(22),
+        "local7" => Some(23),
+        _ => None,
+    }
+}
+
+/// RFC 5424 message carrying one record as JSON.
+fn syslog_line(facility: u8, app_name: &str, hostname: &str, record: &TranscriptRecord) -> String {
+    let mut record = record.clone();
+    record.content = crate::util::truncate_with_ellipsis(&record.content, SYSLOG_MAX_CONTENT_CHARS);
+    let pri = u16::from(facility) * 8 + u16::from(SYSLOG_SEVERITY_INFO);
+    format!(
+        "<{pri}>1 {} {hostname} {app_name} {} transcript - {}",
+        Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        std::process::id(),
+        serde_json::to_string(&record).unwrap_or_default()
+    )
+}
+
+impl SyslogSink {
+    fn hostname() -> String {
+        std::env::var("HOSTNAME")
+            .ok()
+            .filter(|h| !h.is_empty() && !h.contains(' '))
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    async fn send_lines(&self, lines: Vec<String>) -> Result<()> {
+        if let Some(addr) = self.address.strip_prefix("udp://") {
+            let socket = tokio::net::UdpSocket::bind("0.0.0.0:0").await?;
+            socket.connect(addr).await?;
+            for line in lines {
+                socket.send(line.as_bytes()).await?;
+            }
+            return Ok(());
+        }
+        if let Some(addr) = self.address.strip_prefix("tcp://") {
+            use tokio::io::AsyncWriteExt;
+            let mut stream = tokio::net::TcpStream::connect(addr).await?;
+            for line in lines {
+                // RFC 6587 octet-counting framing.
+                stream
+                    .write_all(format!("{} {line}", line.len()).as_bytes())
+                    .await?;
+            }
+            stream.flush().await?;
+            return Ok(());
+        }
+        self.send_unix(lines).await
+    }
+
+    #[cfg(unix)]
+    async fn send_unix(&self, lines: Vec<String>) -> Result<()> {
+        let socket = tokio::net::UnixDatagram::unbound()?;
+        for line in lines {
+            socket
+                .send_to(line.as_bytes(), &self.address)
+                .await
+                .with_context(|| format!("Failed to write to {}", self.address))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    async fn send_unix(&self, _lines: Vec<String>) -> Result<()> {
+        anyhow::bail!(
+            "Unix syslog sockets are not supported on this platform; use udp:// or tcp://"
+        )
+    }
+}
+
+#[async_trait]
+impl TranscriptSink for SyslogSink {
+    fn name(&self) -> &str {
+        "syslog"
+    }
+
+    async fn write_batch(&self, records: &[TranscriptRecord]) -> Result<()> {
+        let hostname = Self::hostname();
+        let lines = records
+            .iter()
+            .map(|record| syslog_line(self.facility, &self.app_name, &hostname, record))
+            .collect();
+        self.send_lines(lines).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(content: &str) -> TranscriptRecord {
+        TranscriptRecord {
+            timestamp: "2026-01-02T03:04:05Z".into(),
+            channel: "telegram".into(),
+            session: "telegram_alice".into(),
+            sender: "alice".into(),
+            role: "user".into(),
+            content: content.into(),
+            message_id: Some("m1".into()),
+            model: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn jsonl_sink_appends_and_rotates() {
+        let tmp = TempDir::new().unwrap();
+        let sink = JsonlSink::new(tmp.path().to_path_buf(), 150);
+
+        sink.write_batch(&[record("first")]).await.unwrap();
+        sink.write_batch(&[record("second")]).await.unwrap();
+
+        let date = Utc::now().format("%Y-%m-%d").to_string();
+        let current =
+            fs::read_to_string(tmp.path().join(format!("transcript-{date}.jsonl"))).unwrap();
+        let rotated =
+            fs::read_to_string(tmp.path().join(format!("transcript-{date}.1.jsonl"))).unwrap();
+        let parsed: TranscriptRecord = serde_json::from_str(rotated.trim()).unwrap();
+        assert_eq!(parsed, record("first"));
+        assert!(current.contains("\"content\":\"second\""));
+        assert!(!current.contains("\"model\""));
+    }
+
+    #[tokio::test]
+    async fn writer_batches_and_flushes_on_close() {
+        let tmp = TempDir::new().unwrap();
+        let (tx, rx) = mpsc::channel(16);
+        let sinks: Vec<Box<dyn TranscriptSink>> =
+            vec![Box::new(JsonlSink::new(tmp.path().to_path_buf(), 1 << 20))];
+        let writer = tokio::spawn(run_writer(
+            rx,
+            sinks,
+            2,
+            Duration::from_secs(3600),
+            CancellationToken::new(),
+        ));
+
+        for content in ["a", "b", "c"] {
+            tx.send(record(content)).await.unwrap();
+        }
+        drop(tx);
+        writer.await.unwrap();
+
+        let date = Utc::now().format("%Y-%m-%d").to_string();
+        let text = fs::read_to_string(tmp.path().join(format!("transcript-{date}.jsonl"))).unwrap();
+        assert_eq!(text.lines().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn writer_flushes_queued_records_when_closed() {
+        let tmp = TempDir::new().unwrap();
+        let (tx, rx) = mpsc::channel(16);
+        let sinks: Vec<Box<dyn TranscriptSink>> =
+            vec![Box::new(JsonlSink::new(tmp.path().to_path_buf(), 1 << 20))];
+        let closing = CancellationToken::new();
+        let writer = tokio::spawn(run_writer(
+            rx,
+            sinks,
+            100,
+            Duration::from_secs(3600),
+            closing.clone(),
+        ));
+
+        tx.send(record("pending")).await.unwrap();
+        closing.cancel();
+        writer.await.unwrap();
+        assert!(tx.send(record("late")).await.is_err());
+
+        let date = Utc::now().format("%Y-%m-%d").to_string();
+        let text = fs::read_to_string(tmp.path().join(format!("transcript-{date}.jsonl"))).unwrap();
+        assert_eq!(text.lines().count(), 1);
+    }
+
+    #[test]
+    fn syslog_line_is_rfc5424() {
+        let line = syslog_line(16, "zeroclaw", "host1", &record("hello"));
+        assert!(line.starts_with("<134>1 "));
+        assert!(line.contains(" host1 zeroclaw "));
+        assert!(line.contains(" transcript - {"));
+        assert!(line.ends_with('}'));
+        assert_eq!(syslog_facility_code("LOCAL7"), Some(23));
+        assert_eq!(syslog_facility_code("kern"), None);
+    }
+
+    #[tokio::test]
+    async fn syslog_sink_sends_udp_datagrams() {
+        let server = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = SyslogSink {
+            address: format!("udp://{}", server.local_addr().unwrap()),
+            facility: 1,
+            app_name: "zc".into(),
+        };
+        sink.write_batch(&[record("over udp")]).await.unwrap();
+
+        let mut buf = vec![0u8; 4096];
+        let n = server.recv(&mut buf).await.unwrap();
+        let datagram = String::from_utf8_lossy(&buf[..n]);
+        assert!(datagram.starts_with("<14>1 "));
+        assert!(datagram.contains("over udp"));
+    }
+
+    #[test]
+    fn s3_signature_matches_reference_shape() {
+        let sink = S3Sink {
+            endpoint: "https://s3.example.com".into(),
+            bucket: "archive".into(),
+            region: "us-east-1".into(),
+            prefix: "zc/".into(),
+            access_key_id: "AKIDEXAMPLE".into(),
+            secret_access_key: "secret".into(),
+        };
+        let now = DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let key = sink.object_key(&now);
+        assert!(key.starts_with("zc/2026/01/02/030405-"));
+        assert!(key.ends_with(".jsonl"));
+
+        let auth = sink.authorization(
+            "s3.example.com",
+            "/archive/zc/x.jsonl",
+            &sha256_hex(b""),
+            &now,
+        );
+        assert!(auth.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20260102/us-east-1/s3/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+        let signature = auth.rsplit('=').next().unwrap();
+        assert_eq!(signature.len(), 64);
+        assert_eq!(
+            auth,
+            sink.authorization(
+                "s3.example.com",
+                "/archive/zc/x.jsonl",
+                &sha256_hex(b""),
+                &now
+            )
+        );
+    }
+}
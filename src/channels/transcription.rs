@@ -1,7 +1,8 @@
 use anyhow::{bail, Context, Result};
 use reqwest::multipart::{Form, Part};
 
-use crate::config::TranscriptionConfig;
+use super::audio_tools::{ffmpeg_convert, run_local_tool, TempAudioFile};
+use crate::config::{TranscriptionBackend, TranscriptionConfig};
 
 /// Maximum upload size accepted by the Groq Whisper API (25 MB).
 const MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
@@ -31,11 +32,40 @@ fn normalize_audio_filename(file_name: &str) -> String {
     }
 }
 
-/// Transcribe audio bytes via a Whisper-compatible transcription API.
+/// Map an attachment MIME type (as reported by WhatsApp or Signal) to a file
+/// extension, so the audio can be named for the transcription backend.
+pub fn extension_for_mime(mime: &str) -> &'static str {
+    let base = mime.split(';').next().unwrap_or("").trim();
+    match base.to_ascii_lowercase().as_str() {
+        "audio/ogg" | "audio/opus" => "ogg",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => "m4a",
+        "audio/aac" | "audio/x-aac" => "aac",
+        "audio/amr" => "amr",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/webm" => "webm",
+        "audio/flac" | "audio/x-flac" => "flac",
+        _ => "bin",
+    }
+}
+
+/// User message content for a transcribed voice note: the transcript is
+/// prefixed with `[Voice]`, followed by any caption sent with the audio.
+pub fn voice_message_content(transcript: &str, caption: Option<&str>) -> String {
+    let transcript = transcript.trim();
+    match caption.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(caption) => format!("[Voice] {transcript}\n\n{caption}"),
+        None => format!("[Voice] {transcript}"),
+    }
+}
+
+/// Transcribe audio bytes with the configured backend.
 ///
-/// Returns the transcribed text on success.  Requires `GROQ_API_KEY` in the
-/// environment.  The caller is responsible for enforcing duration limits
-/// *before* downloading the file; this function enforces the byte-size cap.
+/// Returns the transcribed text on success.  The API backend needs an API key
+/// (`transcription.api_key` or `GROQ_API_KEY`); the whisper.cpp backend needs
+/// `transcription.whisper_cpp_model` and `ffmpeg` on `PATH`.  The caller is
+/// responsible for enforcing duration limits *before* downloading the file;
+/// this function enforces the byte-size cap.
 pub async fn transcribe_audio(
     audio_data: Vec<u8>,
     file_name: &str,
@@ -48,20 +78,43 @@ pub async fn transcribe_audio(
         );
     }
 
+    match config.backend {
+        TranscriptionBackend::Api => transcribe_via_api(audio_data, file_name, config).await,
+        TranscriptionBackend::WhisperCpp => {
+            transcribe_via_whisper_cpp(audio_data, file_name, config).await
+        }
+    }
+}
+
+async fn transcribe_via_api(
+    audio_data: Vec<u8>,
+    file_name: &str,
+    config: &TranscriptionConfig,
+) -> Result<String> {
     let normalized_name = normalize_audio_filename(file_name);
     let extension = normalized_name
         .rsplit_once('.')
         .map(|(_, e)| e)
         .unwrap_or("");
-    let mime = mime_for_audio(extension).ok_or_else(|| {
-        anyhow::anyhow!(
-            "Unsupported audio format '.{extension}' — accepted: flac, mp3, mp4, mpeg, mpga, m4a, ogg, opus, wav, webm"
-        )
-    })?;
 
-    let api_key = std::env::var("GROQ_API_KEY").context(
-        "GROQ_API_KEY environment variable is not set — required for voice transcription",
-    )?;
+    // Formats the API rejects (e.g. Signal's AAC voice notes) are converted
+    // to WAV locally when ffmpeg is available.
+    let (audio_data, normalized_name, mime) = match mime_for_audio(extension) {
+        Some(mime) => (audio_data, normalized_name, mime),
+        None => match convert_to_wav(&audio_data, extension).await {
+            Ok(wav) => (wav, "voice.wav".to_string(), "audio/wav"),
+            Err(e) => bail!(
+                "Unsupported audio format '.{extension}' — accepted: flac, mp3, mp4, mpeg, mpga, m4a, ogg, opus, wav, webm (ffmpeg conversion failed: {e})"
+            ),
+        },
+    };
+
+    let api_key = match config.api_key.as_deref().map(str::trim) {
+        Some(key) if !key.is_empty() => key.to_string(),
+        _ => std::env::var("GROQ_API_KEY").context(
+            "GROQ_API_KEY environment variable is not set — required for voice transcription",
+        )?,
+    };
 
     let client = crate::config::build_runtime_proxy_client("transcription.groq");
 
@@ -105,6 +158,55 @@ pub async fn transcribe_audio(
     Ok(text)
 }
 
+async fn transcribe_via_whisper_cpp(
+    audio_data: Vec<u8>,
+    file_name: &str,
+    config: &TranscriptionConfig,
+) -> Result<String> {
+    let model = config
+        .whisper_cpp_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .context("transcription.whisper_cpp_model must be set for the whisper_cpp backend")?;
+
+    let extension = file_name.rsplit_once('.').map(|(_, e)| e).unwrap_or("");
+    let wav = convert_to_wav(&audio_data, extension).await?;
+    let wav_file = TempAudioFile::write("wav", &wav).await?;
+
+    let mut command = tokio::process::Command::new(&config.whisper_cpp_command);
+    command
+        .arg("-m")
+        .arg(model)
+        .arg("-f")
+        .arg(&wav_file.path)
+        .args(["-nt", "-np"]);
+    if let Some(ref lang) = config.language {
+        command.arg("-l").arg(lang);
+    }
+
+    let output = run_local_tool(command, &config.whisper_cpp_command).await?;
+    let text = String::from_utf8_lossy(&output)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Ok(text)
+}
+
+/// Convert audio to 16 kHz mono PCM WAV with ffmpeg — the input format
+/// whisper.cpp expects, and one every Whisper API accepts.
+async fn convert_to_wav(audio_data: &[u8], extension: &str) -> Result<Vec<u8>> {
+    ffmpeg_convert(
+        audio_data,
+        extension,
+        "wav",
+        &["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"],
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,6 +299,41 @@ mod tests {
         assert_eq!(normalize_audio_filename("voice"), "voice");
     }
 
+    #[test]
+    fn extension_for_mime_maps_voice_note_types() {
+        assert_eq!(extension_for_mime("audio/ogg; codecs=opus"), "ogg");
+        assert_eq!(extension_for_mime("audio/aac"), "aac");
+        assert_eq!(extension_for_mime("audio/mp4"), "m4a");
+        assert_eq!(extension_for_mime("AUDIO/MPEG"), "mp3");
+        assert_eq!(extension_for_mime("application/octet-stream"), "bin");
+    }
+
+    #[test]
+    fn voice_message_content_prefixes_transcript() {
+        assert_eq!(voice_message_content(" hello ", None), "[Voice] hello");
+        assert_eq!(
+            voice_message_content("hello", Some("see attached")),
+            "[Voice] hello\n\nsee attached"
+        );
+        assert_eq!(voice_message_content("hello", Some("  ")), "[Voice] hello");
+    }
+
+    #[tokio::test]
+    async fn whisper_cpp_backend_requires_model() {
+        let config = TranscriptionConfig {
+            backend: TranscriptionBackend::WhisperCpp,
+            ..TranscriptionConfig::default()
+        };
+
+        let err = transcribe_audio(vec![0u8; 100], "voice.ogg", &config)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("whisper_cpp_model"),
+            "expected missing-model error, got: {err}"
+        );
+    }
+
     #[tokio::test]
     async fn rejects_unsupported_audio_format() {
         let data = vec![0u8; 100];
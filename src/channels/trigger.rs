@@ -0,0 +1,71 @@
+//! Command-prefix triggers for group chats.
+//!
+//! By default a channel answers every message it admits. In group chats,
+//! `mention_only` narrows that to messages that @-mention the bot and
+//! `trigger_prefix` (e.g. `"!claw"`) to messages that start with a command
+//! prefix; with both set, either one triggers a reply. Direct messages are
+//! always answered.
+
+/// Trim a configured prefix; a blank one means no prefix.
+pub fn normalize_prefix(prefix: Option<String>) -> Option<String> {
+    prefix
+        .map(|prefix| prefix.trim().to_string())
+        .filter(|prefix| !prefix.is_empty())
+}
+
+/// The text after `prefix` when `text` starts with it as a whole word
+/// (ASCII case-insensitive, optionally followed by `:` or `,`). `None` when
+/// the prefix is missing or nothing follows it.
+pub fn strip_trigger_prefix(text: &str, prefix: &str) -> Option<String> {
+    let text = text.trim_start();
+    let head = text.get(..prefix.len())?;
+    if !head.eq_ignore_ascii_case(prefix) {
+        return None;
+    }
+
+    let rest = &text[prefix.len()..];
+    let rest = match rest.chars().next() {
+        None => rest,
+        Some(':' | ',') => &rest[1..],
+        Some(c) if c.is_whitespace() => rest,
+        Some(_) => return None,
+    };
+    let rest = rest.trim();
+    (!rest.is_empty()).then(|| rest.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_prefix_is_no_prefix() {
+        assert_eq!(normalize_prefix(Some("  ".into())), None);
+        assert_eq!(
+            normalize_prefix(Some(" !claw ".into())),
+            Some("!claw".into())
+        );
+        assert_eq!(normalize_prefix(None), None);
+    }
+
+    #[test]
+    fn strips_prefix_as_a_whole_word() {
+        assert_eq!(
+            strip_trigger_prefix("  !claw what time is it", "!claw").as_deref(),
+            Some("what time is it")
+        );
+        assert_eq!(
+            strip_trigger_prefix("!CLAW: hello", "!claw").as_deref(),
+            Some("hello")
+        );
+        assert_eq!(strip_trigger_prefix("!clawed hello", "!claw"), None);
+        assert_eq!(strip_trigger_prefix("hello !claw", "!claw"), None);
+    }
+
+    #[test]
+    fn bare_prefix_has_nothing_to_answer() {
+        assert_eq!(strip_trigger_prefix("!claw", "!claw"), None);
+        assert_eq!(strip_trigger_prefix("!claw:   ", "!claw"), None);
+        assert_eq!(strip_trigger_prefix("日本語", "!claw"), None);
+    }
+}
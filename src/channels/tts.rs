@@ -0,0 +1,310 @@
+//! Text-to-speech for outbound voice replies.
+//!
+//! The agent asks for a voice reply with a `[VOICE:synthesize]` marker (read
+//! the whole reply aloud) or `[VOICE:synthesize|text]` (speak `text` only).
+//! Before delivery to a channel that can send voice notes, each marker is
+//! synthesized with the configured engine and rewritten as
+//! `[VOICE:<path>]`, which the channel sends as an audio message. Elsewhere,
+//! or when synthesis fails, the marker is reduced to its text.
+
+use super::audio_tools::{ffmpeg_convert, run_local_tool, run_local_tool_with_input};
+use crate::config::{TtsConfig, TtsEngine};
+use anyhow::{bail, Context, Result};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Marker target that requests synthesis instead of naming a file.
+const SYNTHESIZE_TARGET: &str = "synthesize";
+
+const DEFAULT_OPENAI_VOICE: &str = "alloy";
+const DEFAULT_EDGE_VOICE: &str = "en-US-AriaNeural";
+
+/// Synthesized voice notes older than this are removed on the next synthesis.
+const VOICE_NOTE_RETENTION: Duration = Duration::from_secs(3600);
+
+/// Added to the system prompt on channels that can send voice notes.
+pub const VOICE_REPLY_INSTRUCTIONS: &str = "To reply with a voice message, include the marker [VOICE:synthesize] to have your reply read aloud, or [VOICE:synthesize|text to speak] to speak only that text. Use voice when the user asks for it or talks to you with voice messages.";
+
+/// Channels whose `send` delivers `[VOICE:<path>]` markers as voice notes.
+pub fn supports_voice_notes(channel_name: &str) -> bool {
+    matches!(channel_name, "telegram" | "whatsapp" | "signal")
+}
+
+/// Byte range and explicit text of each synthesis marker in `content`.
+fn synthesis_markers(content: &str) -> Vec<(std::ops::Range<usize>, Option<String>)> {
+    let mut found = Vec::new();
+    let mut cursor = 0;
+    while let Some(open_rel) = content[cursor..].find('[') {
+        let open = cursor + open_rel;
+        let Some(close_rel) = content[open..].find(']') else {
+            break;
+        };
+        let close = open + close_rel;
+        let marker = &content[open + 1..close];
+        if let Some((kind, target)) = marker.split_once(':') {
+            let (target, text) = match target.split_once('|') {
+                Some((target, text)) => (target, Some(text.trim()).filter(|t| !t.is_empty())),
+                None => (target, None),
+            };
+            if kind.trim().eq_ignore_ascii_case("VOICE")
+                && target.trim().eq_ignore_ascii_case(SYNTHESIZE_TARGET)
+            {
+                found.push((open..close + 1, text.map(str::to_string)));
+            }
+        }
+        cursor = close + 1;
+    }
+    found
+}
+
+pub fn has_synthesis_markers(content: &str) -> bool {
+    !synthesis_markers(content).is_empty()
+}
+
+/// Replace each synthesis marker in `content` with `replace(explicit_text)`.
+fn rewrite_markers(content: &str, mut replace: impl FnMut(Option<&str>) -> String) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut cursor = 0;
+    for (range, text) in synthesis_markers(content) {
+        out.push_str(&content[cursor..range.start]);
+        out.push_str(&replace(text.as_deref()));
+        cursor = range.end;
+    }
+    out.push_str(&content[cursor..]);
+    out.trim().to_string()
+}
+
+/// Reduce synthesis markers to plain text: `[VOICE:synthesize|text]` becomes
+/// `text`, a bare `[VOICE:synthesize]` is dropped.
+pub fn strip_synthesis_markers(content: &str) -> String {
+    rewrite_markers(content, |text| text.unwrap_or_default().to_string())
+}
+
+/// The reply as it should be read aloud: no markers, no markdown emphasis.
+pub fn speech_text(content: &str, max_chars: usize) -> String {
+    let (text, _) = super::attachment::parse_attachment_markers(&strip_synthesis_markers(content));
+    let plain: String = text
+        .chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '#'))
+        .collect();
+    let plain = plain.split_whitespace().collect::<Vec<_>>().join(" ");
+    super::draft::truncate_chars(&plain, max_chars.max(1)).to_string()
+}
+
+/// Synthesize every marker in `content` and point it at the resulting voice
+/// note. Markers that fail fall back to their text.
+pub async fn synthesize_voice_markers(content: &str, config: &TtsConfig) -> String {
+    let markers = synthesis_markers(content);
+    if markers.is_empty() {
+        return content.to_string();
+    }
+
+    let mut voice_notes = Vec::with_capacity(markers.len());
+    for (_, text) in &markers {
+        let spoken = match text {
+            Some(text) => super::draft::truncate_chars(text, config.max_chars.max(1)).to_string(),
+            None => speech_text(content, config.max_chars),
+        };
+        let note = if spoken.is_empty() {
+            None
+        } else {
+            match synthesize(&spoken, config).await {
+                Ok(audio) => match save_voice_note(&audio).await {
+                    Ok(path) => Some(path),
+                    Err(e) => {
+                        tracing::warn!("Failed to store synthesized voice note: {e}");
+                        None
+                    }
+                },
+                Err(e) => {
+                    tracing::warn!("Speech synthesis failed: {e}");
+                    None
+                }
+            }
+        };
+        voice_notes.push(note);
+    }
+
+    let mut notes = voice_notes.into_iter();
+    rewrite_markers(content, |text| match notes.next().flatten() {
+        Some(path) => format!("[VOICE:{}]", path.display()),
+        None => text.unwrap_or_default().to_string(),
+    })
+}
+
+/// Synthesize `text` as Ogg/Opus, the format voice notes use on Telegram,
+/// WhatsApp and Signal.
+pub async fn synthesize(text: &str, config: &TtsConfig) -> Result<Vec<u8>> {
+    match config.engine {
+        TtsEngine::OpenAi => synthesize_via_api(text, config).await,
+        TtsEngine::Piper => synthesize_via_piper(text, config).await,
+        TtsEngine::EdgeTts => synthesize_via_edge_tts(text, config).await,
+    }
+}
+
+async fn synthesize_via_api(text: &str, config: &TtsConfig) -> Result<Vec<u8>> {
+    let api_key = match config.api_key.as_deref().map(str::trim) {
+        Some(key) if !key.is_empty() => key.to_string(),
+        _ => std::env::var("OPENAI_API_KEY").context(
+            "OPENAI_API_KEY environment variable is not set — required for tts.engine = \"open_ai\"",
+        )?,
+    };
+
+    let body = serde_json::json!({
+        "model": config.model,
+        "input": text,
+        "voice": config.voice.as_deref().unwrap_or(DEFAULT_OPENAI_VOICE),
+        "response_format": "opus",
+    });
+
+    let resp = crate::config::build_runtime_proxy_client("tts.openai")
+        .post(&config.api_url)
+        .bearer_auth(&api_key)
+        .json(&body)
+        .send()
+        .await
+        .context("Failed to send speech request")?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        bail!("Speech API error ({status}): {body}");
+    }
+    Ok(resp.bytes().await?.to_vec())
+}
+
+async fn synthesize_via_piper(text: &str, config: &TtsConfig) -> Result<Vec<u8>> {
+    let model = config
+        .piper_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .context("tts.piper_model must be set for the piper engine")?;
+
+    let wav = super::audio_tools::TempAudioFile::reserve("wav");
+    let mut command = tokio::process::Command::new(&config.piper_command);
+    command
+        .arg("--model")
+        .arg(model)
+        .arg("--output_file")
+        .arg(&wav.path);
+    run_local_tool_with_input(command, &config.piper_command, Some(text.as_bytes())).await?;
+
+    let audio = tokio::fs::read(&wav.path)
+        .await
+        .context("Failed to read Piper output")?;
+    to_ogg_opus(&audio, "wav").await
+}
+
+async fn synthesize_via_edge_tts(text: &str, config: &TtsConfig) -> Result<Vec<u8>> {
+    let mp3 = super::audio_tools::TempAudioFile::reserve("mp3");
+    let mut command = tokio::process::Command::new(&config.edge_tts_command);
+    command
+        .arg("--voice")
+        .arg(config.voice.as_deref().unwrap_or(DEFAULT_EDGE_VOICE))
+        .arg(format!("--text={text}"))
+        .arg("--write-media")
+        .arg(&mp3.path);
+    run_local_tool(command, &config.edge_tts_command).await?;
+
+    let audio = tokio::fs::read(&mp3.path)
+        .await
+        .context("Failed to read edge-tts output")?;
+    to_ogg_opus(&audio, "mp3").await
+}
+
+async fn to_ogg_opus(audio: &[u8], extension: &str) -> Result<Vec<u8>> {
+    ffmpeg_convert(
+        audio,
+        extension,
+        "ogg",
+        &["-ac", "1", "-c:a", "libopus", "-b:a", "32k"],
+    )
+    .await
+}
+
+fn voice_note_dir() -> PathBuf {
+    std::env::temp_dir().join("zeroclaw-tts")
+}
+
+/// Write a voice note where the channel can pick it up, removing notes left
+/// over from earlier replies.
+async fn save_voice_note(audio: &[u8]) -> Result<PathBuf> {
+    let dir = voice_note_dir();
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .context("Failed to create voice note directory")?;
+
+    if let Ok(mut entries) = tokio::fs::read_dir(&dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let expired = entry
+                .metadata()
+                .await
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > VOICE_NOTE_RETENTION);
+            if expired {
+                let _ = tokio::fs::remove_file(entry.path()).await;
+            }
+        }
+    }
+
+    let path = dir.join(format!("reply-{}.ogg", uuid::Uuid::new_v4()));
+    tokio::fs::write(&path, audio)
+        .await
+        .context("Failed to write voice note")?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_synthesis_markers_only() {
+        let content =
+            "Hi [VOICE:synthesize] and [voice: Synthesize | Say this ] [VOICE:/tmp/a.ogg]";
+        let markers = synthesis_markers(content);
+        assert_eq!(markers.len(), 2);
+        assert_eq!(&content[markers[0].0.clone()], "[VOICE:synthesize]");
+        assert_eq!(markers[0].1, None);
+        assert_eq!(markers[1].1.as_deref(), Some("Say this"));
+        assert!(!has_synthesis_markers("[VOICE:/tmp/a.ogg]"));
+    }
+
+    #[test]
+    fn strip_keeps_explicit_text() {
+        assert_eq!(strip_synthesis_markers("Done. [VOICE:synthesize]"), "Done.");
+        assert_eq!(
+            strip_synthesis_markers("Done. [VOICE:synthesize|All set]"),
+            "Done. All set"
+        );
+    }
+
+    #[test]
+    fn speech_text_drops_markers_and_markdown() {
+        let content = "**Weather** is `sunny` [IMAGE:/tmp/a.png]\n[VOICE:synthesize]";
+        assert_eq!(speech_text(content, 100), "Weather is sunny");
+        assert_eq!(speech_text(content, 7), "Weather");
+    }
+
+    #[tokio::test]
+    async fn failed_synthesis_falls_back_to_text() {
+        let config = TtsConfig {
+            enabled: true,
+            engine: TtsEngine::Piper,
+            ..TtsConfig::default()
+        };
+        let out = synthesize_voice_markers("Reply [VOICE:synthesize|spoken]", &config).await;
+        assert_eq!(out, "Reply spoken");
+    }
+
+    #[test]
+    fn voice_notes_supported_on_expected_channels() {
+        assert!(supports_voice_notes("telegram"));
+        assert!(supports_voice_notes("whatsapp"));
+        assert!(supports_voice_notes("signal"));
+        assert!(!supports_voice_notes("slack"));
+    }
+}
@@ -0,0 +1,473 @@
+use super::attachment::{is_local_path, parse_attachment_markers, AttachmentKind};
+use super::markdown::{self, Dialect};
+use super::split::{non_empty_chunks, split_message};
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
+use async_trait::async_trait;
+use base64::Engine;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Twilio channel — SMS, MMS and WhatsApp through the Twilio Messaging API.
+///
+/// This channel operates in webhook mode (push-based). Twilio posts inbound
+/// messages as form data to the gateway's `/twilio` endpoint; the `listen`
+/// method here is a keepalive placeholder. Replies go out through the
+/// Messages REST API, from the SMS number or the WhatsApp sender depending
+/// on which transport the conversation arrived on.
+pub struct TwilioChannel {
+    account_sid: String,
+    auth_token: String,
+    from_number: String,
+    whatsapp_from: Option<String>,
+    allowed_numbers: Vec<String>,
+    client: reqwest::Client,
+}
+
+const TWILIO_API_BASE: &str = "https://api.twilio.com/2010-04-01";
+/// Twilio rejects message bodies longer than 1600 characters.
+const TWILIO_MAX_BODY_CHARS: usize = 1600;
+/// Twilio accepts at most 10 `MediaUrl` parameters per message.
+const TWILIO_MAX_MEDIA: usize = 10;
+const WHATSAPP_PREFIX: &str = "whatsapp:";
+
+impl TwilioChannel {
+    pub fn new(
+        account_sid: String,
+        auth_token: String,
+        from_number: String,
+        whatsapp_from: Option<String>,
+        allowed_numbers: Vec<String>,
+    ) -> Self {
+        Self {
+            account_sid,
+            auth_token,
+            from_number,
+            whatsapp_from,
+            allowed_numbers,
+            client: crate::config::build_runtime_proxy_client("channel.twilio"),
+        }
+    }
+
+    /// Check if a phone number is allowed (E.164 format: +1234567890)
+    fn is_number_allowed(&self, phone: &str) -> bool {
+        self.allowed_numbers.iter().any(|n| n == "*" || n == phone)
+    }
+
+    /// Sender address for a reply to `recipient`.
+    ///
+    /// WhatsApp recipients carry the `whatsapp:` prefix from the inbound
+    /// webhook and must be answered from the WhatsApp-enabled sender.
+    fn sender_for(&self, recipient: &str) -> String {
+        if recipient.starts_with(WHATSAPP_PREFIX) {
+            let from = self.whatsapp_from.as_deref().unwrap_or(&self.from_number);
+            if from.starts_with(WHATSAPP_PREFIX) {
+                from.to_string()
+            } else {
+                format!("{WHATSAPP_PREFIX}{from}")
+            }
+        } else {
+            self.from_number.clone()
+        }
+    }
+
+    /// Verify `X-Twilio-Signature` for a webhook delivered to `url`.
+    pub fn verify_webhook_signature(
+        &self,
+        url: &str,
+        form: &BTreeMap<String, String>,
+        signature: &str,
+    ) -> bool {
+        verify_twilio_signature(&self.auth_token, url, form, signature)
+    }
+
+    /// Map an inbound media item to an attachment marker.
+    fn media_marker(url: &str, content_type: &str) -> String {
+        let content_type = content_type.to_ascii_lowercase();
+        let kind = if content_type.starts_with("image/") {
+            AttachmentKind::Image
+        } else if content_type.starts_with("video/") {
+            AttachmentKind::Video
+        } else if content_type.starts_with("audio/") {
+            AttachmentKind::Audio
+        } else {
+            AttachmentKind::Document
+        };
+        format!("[{}:{url}]", kind.marker_name())
+    }
+
+    /// Parse an incoming Twilio messaging webhook (form fields) into messages.
+    ///
+    /// Relevant fields: `MessageSid`, `From`, `To`, `Body`, `NumMedia`,
+    /// `MediaUrl{N}` and `MediaContentType{N}`. WhatsApp senders arrive as
+    /// `whatsapp:+1...`.
+    pub fn parse_webhook_form(&self, form: &BTreeMap<String, String>) -> Vec<ChannelMessage> {
+        let mut messages = Vec::new();
+
+        let Some(from) = form.get("From").map(|f| f.trim()).filter(|f| !f.is_empty()) else {
+            return messages;
+        };
+        let phone = from.strip_prefix(WHATSAPP_PREFIX).unwrap_or(from);
+
+        if !self.is_number_allowed(phone) {
+            tracing::warn!(
+                "Twilio: ignoring message from unauthorized number: {phone}. \
+                Add to channels.twilio.allowed_numbers in config.toml, \
+                or run `zeroclaw onboard --channels-only` to configure interactively."
+            );
+            return messages;
+        }
+
+        let mut parts = Vec::new();
+        if let Some(body) = form.get("Body").map(|b| b.trim()).filter(|b| !b.is_empty()) {
+            parts.push(body.to_string());
+        }
+
+        let num_media = form
+            .get("NumMedia")
+            .and_then(|n| n.parse::<usize>().ok())
+            .unwrap_or(0);
+        for i in 0..num_media {
+            let Some(url) = form.get(&format!("MediaUrl{i}")) else {
+                continue;
+            };
+            let content_type = form
+                .get(&format!("MediaContentType{i}"))
+                .map_or("", String::as_str);
+            parts.push(Self::media_marker(url, content_type));
+        }
+
+        let content = parts.join("\n");
+        if content.is_empty() {
+            return messages;
+        }
+
+        let id = form
+            .get("MessageSid")
+            .or_else(|| form.get("SmsMessageSid"))
+            .cloned()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+        messages.push(ChannelMessage {
+            id,
+            sender: phone.to_string(),
+            reply_target: from.to_string(),
+            content,
+            channel: "twilio".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+        });
+
+        messages
+    }
+
+    async fn send_one(
+        &self,
+        to: &str,
+        from: &str,
+        body: &str,
+        media_urls: &[String],
+    ) -> anyhow::Result<()> {
+        let mut form: Vec<(&str, &str)> = vec![("To", to), ("From", from)];
+        if !body.is_empty() {
+            form.push(("Body", body));
+        }
+        for url in media_urls {
+            form.push(("MediaUrl", url));
+        }
+
+        let url = format!(
+            "{TWILIO_API_BASE}/Accounts/{}/Messages.json",
+            self.account_sid
+        );
+        let resp = self
+            .client
+            .post(&url)
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .form(&form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("Twilio send failed: {status} — {error_body}");
+            anyhow::bail!("Twilio API error: {status}");
+        }
+        Ok(())
+    }
+}
+
+/// Split a message body into segments Twilio accepts, preferring to break
+/// at newlines and then spaces.
+fn split_body(text: &str) -> Vec<String> {
+    non_empty_chunks(split_message(text.trim(), TWILIO_MAX_BODY_CHARS))
+}
+
+/// Decode an `application/x-www-form-urlencoded` webhook body.
+pub fn parse_form_body(body: &str) -> BTreeMap<String, String> {
+    body.split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |s: &str| {
+                urlencoding::decode(&s.replace('+', " "))
+                    .map(std::borrow::Cow::into_owned)
+                    .ok()
+            };
+            Some((decode(key)?, decode(value)?))
+        })
+        .collect()
+}
+
+/// Verify a Twilio webhook signature.
+///
+/// Twilio signs the full callback URL followed by every POST field sorted by
+/// name and concatenated as `{name}{value}`, using HMAC-SHA1 keyed with the
+/// account auth token. The base64 digest arrives in `X-Twilio-Signature`.
+pub fn verify_twilio_signature(
+    auth_token: &str,
+    url: &str,
+    form: &BTreeMap<String, String>,
+    signature: &str,
+) -> bool {
+    use ring::hmac;
+
+    let Ok(provided) = base64::engine::general_purpose::STANDARD.decode(signature.trim()) else {
+        tracing::warn!("Twilio: invalid webhook signature format");
+        return false;
+    };
+
+    let mut signed = url.to_string();
+    for (key, value) in form {
+        signed.push_str(key);
+        signed.push_str(value);
+    }
+
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, auth_token.as_bytes());
+    hmac::verify(&key, signed.as_bytes(), &provided).is_ok()
+}
+
+#[async_trait]
+impl Channel for TwilioChannel {
+    fn name(&self) -> &str {
+        "twilio"
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            supports_markdown: false,
+            max_message_len: Some(TWILIO_MAX_BODY_CHARS),
+            ..ChannelCapabilities::default()
+        }
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (text, attachments) = parse_attachment_markers(&message.content);
+
+        let dialect = if message.recipient.starts_with(WHATSAPP_PREFIX) {
+            Dialect::WhatsApp
+        } else {
+            Dialect::Plain
+        };
+
+        // Twilio fetches media from public URLs; local files cannot be attached.
+        let mut body = markdown::render(&text, dialect);
+        let mut media_urls = Vec::new();
+        for attachment in attachments {
+            if is_local_path(&attachment.target) {
+                tracing::warn!(
+                    "Twilio: cannot attach local file {}; only public URLs are supported",
+                    attachment.target
+                );
+                let _ = write!(body, "\n[attachment not sent: {}]", attachment.target);
+            } else if media_urls.len() < TWILIO_MAX_MEDIA {
+                media_urls.push(attachment.target);
+            } else {
+                let _ = write!(body, "\n{}", attachment.target);
+            }
+        }
+
+        let from = self.sender_for(&message.recipient);
+        let segments = split_body(&body);
+        if segments.is_empty() {
+            if media_urls.is_empty() {
+                return Ok(());
+            }
+            return self
+                .send_one(&message.recipient, &from, "", &media_urls)
+                .await;
+        }
+
+        // Media rides on the first segment so it arrives with the text it belongs to.
+        for (i, segment) in segments.iter().enumerate() {
+            let media: &[String] = if i == 0 { &media_urls } else { &[] };
+            self.send_one(&message.recipient, &from, segment, media)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        // Twilio uses webhooks (push-based), not polling.
+        // Messages are received via the gateway's /twilio endpoint.
+        tracing::info!(
+            "Twilio channel active (webhook mode). \
+            Point the number's messaging webhook to your gateway's /twilio endpoint."
+        );
+
+        // Keep the task alive — it will be cancelled when the channel shuts down
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        let url = format!("{TWILIO_API_BASE}/Accounts/{}.json", self.account_sid);
+
+        self.client
+            .get(&url)
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_channel() -> TwilioChannel {
+        TwilioChannel::new(
+            "AC123".into(),
+            "token".into(),
+            "+15550001111".into(),
+            Some("+15550002222".into()),
+            vec!["+1234567890".into()],
+        )
+    }
+
+    fn form(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn twilio_channel_name() {
+        assert_eq!(make_channel().name(), "twilio");
+    }
+
+    #[test]
+    fn twilio_parse_sms_message() {
+        let ch = make_channel();
+        let msgs = ch.parse_webhook_form(&form(&[
+            ("MessageSid", "SM1"),
+            ("From", "+1234567890"),
+            ("To", "+15550001111"),
+            ("Body", "Hello agent"),
+            ("NumMedia", "0"),
+        ]));
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].id, "SM1");
+        assert_eq!(msgs[0].sender, "+1234567890");
+        assert_eq!(msgs[0].reply_target, "+1234567890");
+        assert_eq!(msgs[0].content, "Hello agent");
+        assert_eq!(msgs[0].channel, "twilio");
+    }
+
+    #[test]
+    fn twilio_parse_whatsapp_keeps_transport_in_reply_target() {
+        let ch = make_channel();
+        let msgs =
+            ch.parse_webhook_form(&form(&[("From", "whatsapp:+1234567890"), ("Body", "hi")]));
+        assert_eq!(msgs[0].sender, "+1234567890");
+        assert_eq!(msgs[0].reply_target, "whatsapp:+1234567890");
+        assert_eq!(
+            ch.sender_for(&msgs[0].reply_target),
+            "whatsapp:+15550002222"
+        );
+        assert_eq!(ch.sender_for("+1234567890"), "+15550001111");
+    }
+
+    #[test]
+    fn twilio_parse_mms_maps_media_to_markers() {
+        let ch = make_channel();
+        let msgs = ch.parse_webhook_form(&form(&[
+            ("From", "+1234567890"),
+            ("Body", "look"),
+            ("NumMedia", "2"),
+            ("MediaUrl0", "https://api.twilio.com/media/ME1"),
+            ("MediaContentType0", "image/jpeg"),
+            ("MediaUrl1", "https://api.twilio.com/media/ME2"),
+            ("MediaContentType1", "application/pdf"),
+        ]));
+        assert_eq!(
+            msgs[0].content,
+            "look\n[IMAGE:https://api.twilio.com/media/ME1]\n[DOCUMENT:https://api.twilio.com/media/ME2]"
+        );
+    }
+
+    #[test]
+    fn twilio_parse_rejects_unknown_number() {
+        let ch = make_channel();
+        let msgs = ch.parse_webhook_form(&form(&[("From", "+19999999999"), ("Body", "hi")]));
+        assert!(msgs.is_empty());
+    }
+
+    #[test]
+    fn split_body_respects_twilio_limit() {
+        let text = "word ".repeat(700);
+        let segments = split_body(&text);
+        assert!(segments.len() > 1);
+        assert!(segments
+            .iter()
+            .all(|s| s.chars().count() <= TWILIO_MAX_BODY_CHARS));
+        assert_eq!(segments.join(" "), text.trim());
+        assert_eq!(split_body("short"), vec!["short".to_string()]);
+    }
+
+    #[test]
+    fn parse_form_body_decodes_fields() {
+        let parsed = parse_form_body("Body=Hello+there%21&From=%2B1234567890&Empty=");
+        assert_eq!(parsed["Body"], "Hello there!");
+        assert_eq!(parsed["From"], "+1234567890");
+        assert_eq!(parsed["Empty"], "");
+    }
+
+    #[test]
+    fn twilio_signature_matches_reference_example() {
+        // Example from Twilio's webhook security documentation.
+        let params = form(&[
+            ("CallSid", "CA1234567890ABCDE"),
+            ("Caller", "+12349013030"),
+            ("Digits", "1234"),
+            ("From", "+12349013030"),
+            ("To", "+18005551212"),
+        ]);
+        let url = "https://mycompany.com/myapp.php?foo=1&bar=2";
+        assert!(verify_twilio_signature(
+            "12345",
+            url,
+            &params,
+            "0/KCTR6DLpKmkAf8muzZqo1nDgQ="
+        ));
+        assert!(!verify_twilio_signature(
+            "wrong",
+            url,
+            &params,
+            "0/KCTR6DLpKmkAf8muzZqo1nDgQ="
+        ));
+        assert!(!verify_twilio_signature(
+            "12345",
+            url,
+            &params,
+            "not base64!"
+        ));
+    }
+}
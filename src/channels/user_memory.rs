@@ -0,0 +1,654 @@
+//! Long-term facts about the people the agent talks to.
+//!
+//! With `[channels_config.user_memory]` enabled, each turn gets `remember`,
+//! `recall` and `forget` tools bound to the sender: facts are stored under
+//! the sender (`scope = "user"`) or the whole chat (`scope = "chat"`) in
+//! `state/user_memory.db`, apart from the shared memory backend so one
+//! user's facts never reach another user's prompt. The facts most relevant
+//! to each message are added to the system prompt, so names, preferences and
+//! standing instructions survive restarts and history compaction. Facts
+//! about a sender linked in `[[channels_config.identities]]` are stored under
+//! the identity and follow the person to their other accounts.
+
+use super::identity::IdentityMap;
+use super::traits::ChannelMessage;
+use crate::config::UserMemoryConfig;
+use crate::tools::traits::{Tool, ToolResult};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+use serde_json::json;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Longest fact key after normalization.
+const MAX_KEY_CHARS: usize = 64;
+
+/// Appended to the channel system prompt when user memory is enabled.
+pub const USER_MEMORY_INSTRUCTIONS: &str = "\
+## Remembering users
+
+When someone tells you a lasting fact about themselves (their name, a \
+preference, a standing instruction such as \"always answer in French\") or \
+asks you to remember something, save it with the `remember` tool. Use \
+`scope: \"chat\"` for things that apply to everyone in this chat. Use \
+`forget` when asked to forget. Do not save passing details or secrets.";
+
+/// A remembered fact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fact {
+    pub key: String,
+    pub content: String,
+    pub updated_at: String,
+}
+
+/// Who a fact belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// The sender of the current message, on this channel.
+    User,
+    /// Everyone in the current chat.
+    Chat,
+}
+
+impl Scope {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(str::trim) {
+            None | Some("" | "user") => Ok(Self::User),
+            Some("chat") => Ok(Self::Chat),
+            Some(other) => Err(format!("Unknown scope '{other}'; use 'user' or 'chat'")),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Chat => "chat",
+        }
+    }
+}
+
+/// Storage keys for the sender and chat of one message.
+#[derive(Debug, Clone)]
+struct Owner {
+    user: String,
+    chat: String,
+}
+
+impl Owner {
+    fn of(msg: &ChannelMessage, identities: Option<&IdentityMap>) -> Self {
+        let user = match identities.and_then(|map| map.resolve(&msg.channel, &msg.sender)) {
+            Some(name) => format!("identity:{name}"),
+            None => format!("{}:user:{}", msg.channel, msg.sender),
+        };
+        Self {
+            user,
+            chat: format!("{}:chat:{}", msg.channel, msg.reply_target),
+        }
+    }
+
+    fn scope_key(&self, scope: Scope) -> &str {
+        match scope {
+            Scope::User => &self.user,
+            Scope::Chat => &self.chat,
+        }
+    }
+}
+
+/// `"Favorite Color"` → `"favorite_color"`.
+fn normalize_key(key: &str) -> String {
+    let mut normalized = String::new();
+    for word in key
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        if !normalized.is_empty() {
+            normalized.push('_');
+        }
+        normalized.push_str(&word.to_lowercase());
+    }
+    normalized.chars().take(MAX_KEY_CHARS).collect()
+}
+
+/// Lowercase words of three or more characters, for relevance ranking.
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Facts sorted by how many words they share with `query`, most recently
+/// updated first among equals.
+fn rank(mut facts: Vec<Fact>, query: &str) -> Vec<Fact> {
+    let query = words(query);
+    let overlap = |fact: &Fact| {
+        words(&format!("{} {}", fact.key.replace('_', " "), fact.content))
+            .intersection(&query)
+            .count()
+    };
+    facts.sort_by(|a, b| {
+        overlap(b)
+            .cmp(&overlap(a))
+            .then_with(|| b.updated_at.cmp(&a.updated_at))
+    });
+    facts
+}
+
+/// Per-user and per-chat fact store.
+pub struct UserMemory {
+    db_path: PathBuf,
+    max_facts: usize,
+    max_context_facts: usize,
+    max_fact_chars: usize,
+    identities: Option<Arc<IdentityMap>>,
+}
+
+impl UserMemory {
+    /// Returns `None` unless `enabled` is set.
+    pub fn from_config(
+        config: &UserMemoryConfig,
+        workspace_dir: &Path,
+        identities: Option<Arc<IdentityMap>>,
+    ) -> Option<Self> {
+        config.enabled.then(|| Self {
+            db_path: workspace_dir.join("state").join("user_memory.db"),
+            max_facts: config.max_facts.max(1),
+            max_context_facts: config.max_context_facts,
+            max_fact_chars: config.max_fact_chars.max(1),
+            identities,
+        })
+    }
+
+    fn owner(&self, msg: &ChannelMessage) -> Owner {
+        Owner::of(msg, self.identities.as_deref())
+    }
+
+    fn remember(&self, scope: &str, key: &str, content: &str) -> Result<bool> {
+        let content = content.trim();
+        if content.is_empty() {
+            bail!("Nothing to remember: 'content' is empty");
+        }
+        if content.chars().count() > self.max_fact_chars {
+            bail!(
+                "Fact is too long ({} characters, limit {})",
+                content.chars().count(),
+                self.max_fact_chars
+            );
+        }
+        with_connection(&self.db_path, |conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM user_facts WHERE scope = ?1 AND key = ?2)",
+                params![scope, key],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                let count: usize = conn.query_row(
+                    "SELECT COUNT(*) FROM user_facts WHERE scope = ?1",
+                    params![scope],
+                    |row| row.get(0),
+                )?;
+                if count >= self.max_facts {
+                    bail!("Already remembering {count} facts here; forget one before adding more");
+                }
+            }
+            conn.execute(
+                "INSERT INTO user_facts (scope, key, content, updated_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(scope, key) DO UPDATE SET content = ?3, updated_at = ?4",
+                params![scope, key, content, chrono::Utc::now().to_rfc3339()],
+            )?;
+            Ok(exists)
+        })
+    }
+
+    fn facts(&self, scope: &str) -> Result<Vec<Fact>> {
+        with_connection(&self.db_path, |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT key, content, updated_at FROM user_facts WHERE scope = ?1 ORDER BY key",
+            )?;
+            let facts = stmt
+                .query_map(params![scope], |row| {
+                    Ok(Fact {
+                        key: row.get(0)?,
+                        content: row.get(1)?,
+                        updated_at: row.get(2)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(facts)
+        })
+    }
+
+    fn forget(&self, scope: &str, key: &str) -> Result<bool> {
+        with_connection(&self.db_path, |conn| {
+            let removed = conn.execute(
+                "DELETE FROM user_facts WHERE scope = ?1 AND key = ?2",
+                params![scope, key],
+            )?;
+            Ok(removed > 0)
+        })
+    }
+
+    /// System prompt section with the facts about `msg`'s sender and chat
+    /// that are most relevant to it, or an empty string when there are none.
+    pub fn context_for(&self, msg: &ChannelMessage) -> String {
+        if self.max_context_facts == 0 {
+            return String::new();
+        }
+        let owner = self.owner(msg);
+        let mut facts: Vec<(Scope, Fact)> = Vec::new();
+        for scope in [Scope::User, Scope::Chat] {
+            if scope == Scope::Chat && msg.reply_target == msg.sender {
+                // Direct chats: the chat is the user.
+                continue;
+            }
+            match self.facts(owner.scope_key(scope)) {
+                Ok(found) => facts.extend(found.into_iter().map(|fact| (scope, fact))),
+                Err(e) => tracing::warn!("Failed to load remembered facts: {e}"),
+            }
+        }
+        if facts.is_empty() {
+            return String::new();
+        }
+
+        let ranked = rank(
+            facts.iter().map(|(_, fact)| fact.clone()).collect(),
+            &msg.content,
+        );
+        let mut context = format!("## Remembered about {}\n", msg.sender);
+        for fact in ranked.iter().take(self.max_context_facts) {
+            let scope = facts
+                .iter()
+                .find(|(_, f)| f == fact)
+                .map_or(Scope::User, |(scope, _)| *scope);
+            let suffix = if scope == Scope::Chat {
+                " (everyone in this chat)"
+            } else {
+                ""
+            };
+            let _ = writeln!(context, "- {}: {}{suffix}", fact.key, fact.content);
+        }
+        context.trim_end().to_string()
+    }
+
+    /// `tools` plus `remember`, `recall` and `forget` bound to `msg`'s
+    /// sender and chat, for one turn.
+    pub fn with_scoped_tools(
+        self: &Arc<Self>,
+        tools: &Arc<Vec<Box<dyn Tool>>>,
+        msg: &ChannelMessage,
+    ) -> Vec<Box<dyn Tool>> {
+        let owner = self.owner(msg);
+        let mut turn_tools: Vec<Box<dyn Tool>> = (0..tools.len())
+            .map(|index| {
+                Box::new(RegistryTool {
+                    tools: Arc::clone(tools),
+                    index,
+                }) as Box<dyn Tool>
+            })
+            .collect();
+        for action in [Action::Remember, Action::Recall, Action::Forget] {
+            turn_tools.push(Box::new(UserMemoryTool {
+                store: Arc::clone(self),
+                owner: owner.clone(),
+                action,
+            }));
+        }
+        turn_tools
+    }
+}
+
+fn with_connection<T>(db_path: &Path, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create state directory: {}", parent.display()))?;
+    }
+
+    let conn = Connection::open(db_path)
+        .with_context(|| format!("Failed to open user memory DB: {}", db_path.display()))?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS user_facts (
+            scope      TEXT NOT NULL,
+            key        TEXT NOT NULL,
+            content    TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (scope, key)
+        );",
+    )
+    .context("Failed to initialize user memory schema")?;
+
+    f(&conn)
+}
+
+/// A registry tool shared into a per-turn tool list.
+struct RegistryTool {
+    tools: Arc<Vec<Box<dyn Tool>>>,
+    index: usize,
+}
+
+impl RegistryTool {
+    fn inner(&self) -> &dyn Tool {
+        self.tools[self.index].as_ref()
+    }
+}
+
+#[async_trait]
+impl Tool for RegistryTool {
+    fn name(&self) -> &str {
+        self.inner().name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner().description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner().parameters_schema()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.inner().execute(args).await
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Remember,
+    Recall,
+    Forget,
+}
+
+/// `remember`, `recall` or `forget` for one sender and chat.
+struct UserMemoryTool {
+    store: Arc<UserMemory>,
+    owner: Owner,
+    action: Action,
+}
+
+impl UserMemoryTool {
+    fn execute_action(&self, args: &serde_json::Value) -> Result<String, String> {
+        let scope = Scope::parse(args.get("scope").and_then(|v| v.as_str()))?;
+        let scope_key = self.owner.scope_key(scope);
+        let key = args
+            .get("key")
+            .and_then(|v| v.as_str())
+            .map(normalize_key)
+            .filter(|key| !key.is_empty());
+
+        match self.action {
+            Action::Remember => {
+                let key = key.ok_or("Missing 'key' parameter")?;
+                let content = args
+                    .get("content")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing 'content' parameter")?;
+                let replaced = self
+                    .store
+                    .remember(scope_key, &key, content)
+                    .map_err(|e| e.to_string())?;
+                let verb = if replaced { "Updated" } else { "Remembered" };
+                Ok(format!("{verb} {key} ({} scope).", scope.label()))
+            }
+            Action::Recall => {
+                let facts = self.store.facts(scope_key).map_err(|e| e.to_string())?;
+                let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+                let facts = match key {
+                    Some(key) => facts.into_iter().filter(|f| f.key == key).collect(),
+                    None if query.trim().is_empty() => facts,
+                    None => rank(facts, query),
+                };
+                if facts.is_empty() {
+                    return Ok(format!("Nothing remembered ({} scope).", scope.label()));
+                }
+                let mut output = String::new();
+                for fact in facts {
+                    let _ = writeln!(output, "- {}: {}", fact.key, fact.content);
+                }
+                Ok(output.trim_end().to_string())
+            }
+            Action::Forget => {
+                let key = key.ok_or("Missing 'key' parameter")?;
+                if self
+                    .store
+                    .forget(scope_key, &key)
+                    .map_err(|e| e.to_string())?
+                {
+                    Ok(format!("Forgot {key} ({} scope).", scope.label()))
+                } else {
+                    Ok(format!("Nothing remembered as {key}."))
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for UserMemoryTool {
+    fn name(&self) -> &str {
+        match self.action {
+            Action::Remember => "remember",
+            Action::Recall => "recall",
+            Action::Forget => "forget",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self.action {
+            Action::Remember => {
+                "Remember a lasting fact about the user you are talking to (name, preference, \
+                 standing instruction), or with scope 'chat' about this whole chat. Saving an \
+                 existing key replaces it. Remembered facts are shown to you in later sessions."
+            }
+            Action::Recall => {
+                "List what you remember about the user you are talking to, or with scope \
+                 'chat' about this chat. Optionally filter by key or rank by a query."
+            }
+            Action::Forget => {
+                "Forget a remembered fact about the user you are talking to (or this chat) by key."
+            }
+        }
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let scope = json!({
+            "type": "string",
+            "enum": ["user", "chat"],
+            "description": "'user' (default): the person who sent this message. 'chat': everyone in this chat."
+        });
+        match self.action {
+            Action::Remember => json!({
+                "type": "object",
+                "properties": {
+                    "key": {
+                        "type": "string",
+                        "description": "Short name for the fact, e.g. 'name', 'preferred_language', 'reply_style'"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "The fact, e.g. 'Prefers metric units'"
+                    },
+                    "scope": scope
+                },
+                "required": ["key", "content"]
+            }),
+            Action::Recall => json!({
+                "type": "object",
+                "properties": {
+                    "key": {
+                        "type": "string",
+                        "description": "Return only this fact"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "List facts related to this first"
+                    },
+                    "scope": scope
+                }
+            }),
+            Action::Forget => json!({
+                "type": "object",
+                "properties": {
+                    "key": {
+                        "type": "string",
+                        "description": "Key of the fact to forget"
+                    },
+                    "scope": scope
+                },
+                "required": ["key"]
+            }),
+        }
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        Ok(match self.execute_action(&args) {
+            Ok(output) => ToolResult {
+                success: true,
+                output,
+                error: None,
+            },
+            Err(error) => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store(tmp: &TempDir) -> Arc<UserMemory> {
+        let config = UserMemoryConfig {
+            enabled: true,
+            max_facts: 3,
+            ..UserMemoryConfig::default()
+        };
+        Arc::new(UserMemory::from_config(&config, tmp.path(), None).unwrap())
+    }
+
+    fn message(sender: &str, reply_target: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "1".into(),
+            sender: sender.into(),
+            reply_target: reply_target.into(),
+            content: content.into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+        }
+    }
+
+    fn tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> &'a dyn Tool {
+        tools.iter().find(|t| t.name() == name).unwrap().as_ref()
+    }
+
+    #[test]
+    fn disabled_config_has_no_store() {
+        let tmp = TempDir::new().unwrap();
+        assert!(UserMemory::from_config(&UserMemoryConfig::default(), tmp.path(), None).is_none());
+        assert_eq!(normalize_key(" Favorite Color! "), "favorite_color");
+    }
+
+    #[tokio::test]
+    async fn facts_are_scoped_to_the_sender() {
+        let tmp = TempDir::new().unwrap();
+        let store = store(&tmp);
+        let registry: Arc<Vec<Box<dyn Tool>>> = Arc::new(Vec::new());
+        let alice = store.with_scoped_tools(&registry, &message("alice", "group", "hi"));
+        let bob = store.with_scoped_tools(&registry, &message("bob", "group", "hi"));
+
+        let result = tool(&alice, "remember")
+            .execute(json!({"key": "Name", "content": "Alice Liddell"}))
+            .await
+            .unwrap();
+        assert_eq!(result.output, "Remembered name (user scope).");
+        tool(&alice, "remember")
+            .execute(json!({"key": "language", "content": "Reply in French", "scope": "chat"}))
+            .await
+            .unwrap();
+
+        let recalled = tool(&bob, "recall").execute(json!({})).await.unwrap();
+        assert_eq!(recalled.output, "Nothing remembered (user scope).");
+        let recalled = tool(&bob, "recall")
+            .execute(json!({"scope": "chat"}))
+            .await
+            .unwrap();
+        assert!(recalled.output.contains("Reply in French"));
+
+        let context = store.context_for(&message("alice", "group", "what is my name?"));
+        assert!(context.starts_with("## Remembered about alice\n- name: Alice Liddell"));
+        assert!(context.contains("- language: Reply in French (everyone in this chat)"));
+        assert!(!store
+            .context_for(&message("bob", "group", "hi"))
+            .contains("Alice"));
+
+        let forgot = tool(&alice, "forget")
+            .execute(json!({"key": "name"}))
+            .await
+            .unwrap();
+        assert_eq!(forgot.output, "Forgot name (user scope).");
+    }
+
+    #[tokio::test]
+    async fn remember_enforces_limits_and_replaces_existing_keys() {
+        let tmp = TempDir::new().unwrap();
+        let store = store(&tmp);
+        let tools = store.with_scoped_tools(&Arc::new(Vec::new()), &message("u", "u", "hi"));
+        let remember = tool(&tools, "remember");
+        for key in ["a", "b", "c"] {
+            let result = remember
+                .execute(json!({"key": key, "content": "x"}))
+                .await
+                .unwrap();
+            assert!(result.success);
+        }
+        let full = remember
+            .execute(json!({"key": "d", "content": "x"}))
+            .await
+            .unwrap();
+        assert!(full.error.unwrap().contains("forget one"));
+        let updated = remember
+            .execute(json!({"key": "a", "content": "y"}))
+            .await
+            .unwrap();
+        assert_eq!(updated.output, "Updated a (user scope).");
+        let bad_scope = remember
+            .execute(json!({"key": "a", "content": "y", "scope": "world"}))
+            .await
+            .unwrap();
+        assert!(bad_scope.error.unwrap().contains("Unknown scope"));
+    }
+
+    #[tokio::test]
+    async fn facts_follow_linked_identities() {
+        let tmp = TempDir::new().unwrap();
+        let identities = IdentityMap::from_config(&[crate::config::UserIdentityConfig {
+            name: "alice".into(),
+            accounts: vec!["telegram:alice".into(), "slack:U024BE7LH".into()],
+        }])
+        .map(Arc::new);
+        let config = UserMemoryConfig {
+            enabled: true,
+            ..UserMemoryConfig::default()
+        };
+        let store = Arc::new(UserMemory::from_config(&config, tmp.path(), identities).unwrap());
+        let tools =
+            store.with_scoped_tools(&Arc::new(Vec::new()), &message("alice", "alice", "hi"));
+        tool(&tools, "remember")
+            .execute(json!({"key": "name", "content": "Alice Liddell"}))
+            .await
+            .unwrap();
+
+        let on_slack = ChannelMessage {
+            channel: "slack".into(),
+            ..message("U024BE7LH", "D1", "who am I?")
+        };
+        assert!(store.context_for(&on_slack).contains("Alice Liddell"));
+    }
+}
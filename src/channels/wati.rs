@@ -1,7 +1,12 @@
+use super::markdown::{self, Dialect};
+use super::split::split_message;
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use uuid::Uuid;
 
+/// WhatsApp's limit for a text message body.
+const WATI_MAX_TEXT_CHARS: usize = 4096;
+
 /// WATI WhatsApp Business API channel.
 ///
 /// This channel operates in webhook mode (push-based) rather than polling.
@@ -177,28 +182,29 @@ impl Channel for WatiChannel {
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         let target = self.build_target(&message.recipient);
-
-        let body = serde_json::json!({
-            "target": target,
-            "text": message.content
-        });
-
         let url = format!("{}/api/ext/v3/conversations/messages/text", self.api_url);
 
-        let resp = self
-            .client
-            .post(&url)
-            .bearer_auth(&self.api_token)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+        for chunk in split_message(&message.content, WATI_MAX_TEXT_CHARS) {
+            let body = serde_json::json!({
+                "target": target,
+                "text": markdown::render(&chunk, Dialect::WhatsApp)
+            });
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let error_body = resp.text().await.unwrap_or_default();
-            tracing::error!("WATI send failed: {status} — {error_body}");
-            anyhow::bail!("WATI API error: {status}");
+            let resp = self
+                .client
+                .post(&url)
+                .bearer_auth(&self.api_token)
+                .header("Content-Type", "application/json")
+                .json(&body)
+                .send()
+                .await?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let error_body = resp.text().await.unwrap_or_default();
+                tracing::error!("WATI send failed: {status} — {error_body}");
+                anyhow::bail!("WATI API error: {status}");
+            }
         }
 
         Ok(())
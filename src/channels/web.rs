@@ -0,0 +1,637 @@
+//! Browser chat channel.
+//!
+//! Serves a minimal chat page at `/` and a WebSocket at `/ws`, so the agent
+//! can be used from a browser without a third-party messenger. Replies stream
+//! token by token through draft updates.
+//!
+//! Protocol (JSON text frames):
+//! ```text
+//! Client -> Server: {"type":"message","content":"Hello"}
+//! Server -> Client: {"type":"session","session":"..."}           on connect
+//! Server -> Client: {"type":"typing","active":true}
+//! Server -> Client: {"type":"start","id":"web_draft_..."}
+//! Server -> Client: {"type":"chunk","id":"...","content":"Hi"}    appended text
+//! Server -> Client: {"type":"replace","id":"...","content":"..."} draft rewritten
+//! Server -> Client: {"type":"done","id":"...","content":"...","attachments":[...]}
+//! Server -> Client: {"type":"cancel","id":"..."}
+//! Server -> Client: {"type":"message","content":"...","attachments":[...]}
+//! ```
+//!
+//! Each browser keeps a session id (`?session=`) so reloads continue the same
+//! conversation; every tab on a session receives its replies. The socket
+//! requires `?token=<token>`, which the page forwards from its own URL; without
+//! a configured `auth_token` a random one is generated at startup and logged
+//! with the page link. Upgrades from another origin are rejected, and binding
+//! to a non-loopback address without a configured token is refused.
+
+use super::attachment::{is_local_path, parse_attachment_markers};
+use super::http_api::{ReplyAttachment, ServedFiles};
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use async_trait::async_trait;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path as UrlPath, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+const CHAT_PAGE: &str = include_str!("web_chat.html");
+const SENDER: &str = "web";
+const MAX_SESSION_ID_LEN: usize = 64;
+const MAX_INBOUND_CHARS: usize = 32_000;
+
+#[derive(Debug, Deserialize)]
+struct ConnectQuery {
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    session: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientFrame {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    content: String,
+}
+
+type ClientTx = mpsc::UnboundedSender<String>;
+
+struct Shared {
+    auth_token: String,
+    /// Open sockets per reply target (`web_<session>`), keyed by connection id.
+    clients: Mutex<HashMap<String, HashMap<String, ClientTx>>>,
+    /// Text already streamed for each open draft.
+    drafts: Mutex<HashMap<String, String>>,
+    files: Mutex<ServedFiles>,
+}
+
+impl Shared {
+    /// Send `frame` to every socket on `recipient`. Returns false when none is open.
+    fn broadcast(&self, recipient: &str, frame: &serde_json::Value) -> bool {
+        let text = frame.to_string();
+        let mut clients = self.clients.lock();
+        let Some(sockets) = clients.get_mut(recipient) else {
+            return false;
+        };
+        sockets.retain(|_, tx| tx.send(text.clone()).is_ok());
+        let delivered = !sockets.is_empty();
+        if !delivered {
+            clients.remove(recipient);
+        }
+        delivered
+    }
+
+    fn is_authorized(&self, token: Option<&str>) -> bool {
+        token.is_some_and(|token| {
+            crate::security::pairing::constant_time_eq(token.trim(), &self.auth_token)
+        })
+    }
+}
+
+#[derive(Clone)]
+struct WebState {
+    shared: Arc<Shared>,
+    tx: mpsc::Sender<ChannelMessage>,
+}
+
+/// Browser chat channel — chat page plus streaming WebSocket.
+pub struct WebChannel {
+    host: String,
+    port: u16,
+    /// True when no `auth_token` was configured and one was generated.
+    generated_token: bool,
+    shared: Arc<Shared>,
+}
+
+impl WebChannel {
+    pub fn new(host: String, port: u16, auth_token: Option<String>) -> Self {
+        let configured = auth_token
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+        let generated_token = configured.is_none();
+        Self {
+            host,
+            port,
+            generated_token,
+            shared: Arc::new(Shared {
+                auth_token: configured.unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()),
+                clients: Mutex::new(HashMap::new()),
+                drafts: Mutex::new(HashMap::new()),
+                files: Mutex::new(ServedFiles::default()),
+            }),
+        }
+    }
+
+    /// Split `content` into text and attachments with URLs the page can load.
+    fn resolve(&self, content: &str) -> (String, Vec<ReplyAttachment>) {
+        let content = super::strip_tool_call_tags(content);
+        let (text, attachments) = parse_attachment_markers(&content);
+        let attachments = attachments
+            .into_iter()
+            .filter_map(|attachment| {
+                let url = if is_local_path(&attachment.target) {
+                    let path = PathBuf::from(&attachment.target);
+                    if !path.is_file() {
+                        tracing::warn!("Web: attachment not found: {}", attachment.target);
+                        return None;
+                    }
+                    let name = path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .map(urlencoding::encode)
+                        .map_or_else(|| "file".to_string(), |n| n.into_owned());
+                    let token = self.shared.files.lock().insert(path);
+                    format!("/files/{token}/{name}")
+                } else {
+                    attachment.target
+                };
+                Some(ReplyAttachment {
+                    kind: attachment.kind.marker_name().to_ascii_lowercase(),
+                    url,
+                    alt: attachment.alt,
+                })
+            })
+            .collect();
+        (text, attachments)
+    }
+
+    fn router(&self, tx: mpsc::Sender<ChannelMessage>) -> Router {
+        Router::new()
+            .route("/", get(|| async { Html(CHAT_PAGE) }))
+            .route("/health", get(|| async { "ok" }))
+            .route("/ws", get(handle_upgrade))
+            .route("/files/{token}/{name}", get(handle_file))
+            .with_state(WebState {
+                shared: Arc::clone(&self.shared),
+                tx,
+            })
+    }
+}
+
+/// Accept client session ids that are safe to embed in keys; otherwise mint one.
+fn session_id(requested: Option<&str>) -> String {
+    requested
+        .map(str::trim)
+        .filter(|s| {
+            !s.is_empty()
+                && s.len() <= MAX_SESSION_ID_LEN
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+        .map_or_else(|| uuid::Uuid::new_v4().simple().to_string(), str::to_string)
+}
+
+/// Browsers always send `Origin` on WebSocket upgrades; it must name the host
+/// the page was served from so other sites cannot open the socket.
+fn is_same_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let host = headers.get(header::HOST).and_then(|h| h.to_str().ok());
+    let authority = origin
+        .to_str()
+        .ok()
+        .and_then(|origin| origin.split_once("://"))
+        .map(|(_, authority)| authority.trim_end_matches('/'));
+    matches!((authority, host), (Some(a), Some(h)) if a.eq_ignore_ascii_case(h.trim()))
+}
+
+async fn handle_upgrade(
+    State(state): State<WebState>,
+    Query(query): Query<ConnectQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if !is_same_origin(&headers) {
+        return (
+            StatusCode::FORBIDDEN,
+            "Cross-origin WebSocket upgrade refused",
+        )
+            .into_response();
+    }
+    if !state.shared.is_authorized(query.token.as_deref()) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            "Unauthorized — open the page with ?token=<token>",
+        )
+            .into_response();
+    }
+    let session = session_id(query.session.as_deref());
+    ws.on_upgrade(move |socket| handle_socket(socket, state, session))
+        .into_response()
+}
+
+async fn handle_socket(socket: WebSocket, state: WebState, session: String) {
+    let (mut sink, mut stream) = socket.split();
+    let reply_target = format!("web_{session}");
+    let connection_id = uuid::Uuid::new_v4().simple().to_string();
+    let (client_tx, mut client_rx) = mpsc::unbounded_channel::<String>();
+    let _ = client_tx.send(serde_json::json!({"type": "session", "session": session}).to_string());
+    state
+        .shared
+        .clients
+        .lock()
+        .entry(reply_target.clone())
+        .or_default()
+        .insert(connection_id.clone(), client_tx.clone());
+
+    let writer = tokio::spawn(async move {
+        while let Some(text) = client_rx.recv().await {
+            if sink.send(Message::Text(text.into())).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(Ok(frame)) = stream.next().await {
+        let text = match frame {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let Ok(frame) = serde_json::from_str::<ClientFrame>(&text) else {
+            let _ = client_tx
+                .send(serde_json::json!({"type": "error", "message": "Invalid JSON"}).to_string());
+            continue;
+        };
+        let content = frame.content.trim();
+        if frame.kind != "message" || content.is_empty() {
+            continue;
+        }
+        if content.chars().count() > MAX_INBOUND_CHARS {
+            let _ = client_tx.send(
+                serde_json::json!({"type": "error", "message": "Message is too long"}).to_string(),
+            );
+            continue;
+        }
+        let msg = ChannelMessage {
+            id: format!("web_{}", uuid::Uuid::new_v4().simple()),
+            sender: SENDER.to_string(),
+            reply_target: reply_target.clone(),
+            content: content.to_string(),
+            channel: "web".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: Some(session.clone()),
+        };
+        if state.tx.send(msg).await.is_err() {
+            break;
+        }
+    }
+
+    {
+        let mut clients = state.shared.clients.lock();
+        if let Some(sockets) = clients.get_mut(&reply_target) {
+            sockets.remove(&connection_id);
+            if sockets.is_empty() {
+                clients.remove(&reply_target);
+            }
+        }
+    }
+    drop(client_tx);
+    let _ = writer.await;
+}
+
+async fn handle_file(
+    State(state): State<WebState>,
+    UrlPath((token, _name)): UrlPath<(String, String)>,
+) -> Response {
+    let path = state.shared.files.lock().get(&token);
+    let Some(path) = path else {
+        return (StatusCode::NOT_FOUND, "unknown or expired file").into_response();
+    };
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => {
+            let mime = mime_guess::from_path(&path).first_or_octet_stream();
+            ([(header::CONTENT_TYPE, mime.to_string())], bytes).into_response()
+        }
+        Err(_) => (StatusCode::NOT_FOUND, "file no longer available").into_response(),
+    }
+}
+
+#[async_trait]
+impl Channel for WebChannel {
+    fn name(&self) -> &str {
+        "web"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (text, attachments) = self.resolve(&message.content);
+        let frame = serde_json::json!({
+            "type": "message",
+            "content": text,
+            "attachments": attachments,
+        });
+        if !self.shared.broadcast(&message.recipient, &frame) {
+            tracing::debug!(
+                "Web: no open socket for {}; dropping message",
+                message.recipient
+            );
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        if self.generated_token && crate::security::pairing::is_public_bind(&self.host) {
+            anyhow::bail!(
+                "Web channel refuses to bind {} without an auth_token",
+                self.host
+            );
+        }
+        let listener = tokio::net::TcpListener::bind((self.host.as_str(), self.port)).await?;
+        if self.generated_token {
+            tracing::info!(
+                "Web: chat page at http://{}/?token={} (generated; set auth_token to keep it across restarts)",
+                listener.local_addr()?,
+                self.shared.auth_token
+            );
+        } else {
+            tracing::info!(
+                "Web: chat page at http://{}/?token=<auth_token>",
+                listener.local_addr()?
+            );
+        }
+        axum::serve(listener, self.router(tx)).await?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        tokio::net::TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .is_ok()
+    }
+
+    async fn start_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.shared.broadcast(
+            recipient,
+            &serde_json::json!({"type": "typing", "active": true}),
+        );
+        Ok(())
+    }
+
+    async fn stop_typing(&self, recipient: &str) -> anyhow::Result<()> {
+        self.shared.broadcast(
+            recipient,
+            &serde_json::json!({"type": "typing", "active": false}),
+        );
+        Ok(())
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let id = format!("web_draft_{}", uuid::Uuid::new_v4().simple());
+        if !self.shared.broadcast(
+            &message.recipient,
+            &serde_json::json!({"type": "start", "id": id}),
+        ) {
+            return Ok(None);
+        }
+        self.shared.drafts.lock().insert(id.clone(), String::new());
+        Ok(Some(id))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let frame = {
+            let mut drafts = self.shared.drafts.lock();
+            let Some(sent) = drafts.get_mut(message_id) else {
+                return Ok(());
+            };
+            let frame = match text.strip_prefix(sent.as_str()) {
+                Some("") => return Ok(()),
+                Some(delta) => {
+                    serde_json::json!({"type": "chunk", "id": message_id, "content": delta})
+                }
+                None => serde_json::json!({"type": "replace", "id": message_id, "content": text}),
+            };
+            text.clone_into(sent);
+            frame
+        };
+        self.shared.broadcast(recipient, &frame);
+        Ok(())
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.shared.drafts.lock().remove(message_id);
+        let (text, attachments) = self.resolve(text);
+        self.shared.broadcast(
+            recipient,
+            &serde_json::json!({
+                "type": "done",
+                "id": message_id,
+                "content": text,
+                "attachments": attachments,
+            }),
+        );
+        Ok(())
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.shared.drafts.lock().remove(message_id);
+        self.shared.broadcast(
+            recipient,
+            &serde_json::json!({"type": "cancel", "id": message_id}),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+    use tower::ServiceExt;
+
+    fn frames(rx: &mut mpsc::UnboundedReceiver<String>) -> Vec<serde_json::Value> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|text| serde_json::from_str(&text).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn drafts_stream_as_chunks_then_done() {
+        let ch = WebChannel::new("127.0.0.1".into(), 0, None);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        ch.shared
+            .clients
+            .lock()
+            .entry("web_s1".into())
+            .or_default()
+            .insert("c1".into(), tx);
+
+        let id = ch
+            .send_draft(&SendMessage::new("...", "web_s1"))
+            .await
+            .unwrap()
+            .unwrap();
+        ch.update_draft("web_s1", &id, "Hel").await.unwrap();
+        ch.update_draft("web_s1", &id, "Hello").await.unwrap();
+        ch.update_draft("web_s1", &id, "Hello").await.unwrap();
+        ch.update_draft("web_s1", &id, "Bye").await.unwrap();
+        ch.finalize_draft(
+            "web_s1",
+            &id,
+            "Bye [IMAGE:https://cdn.example.com/a.png|A cat]",
+        )
+        .await
+        .unwrap();
+
+        let got = frames(&mut rx);
+        let kinds: Vec<_> = got.iter().map(|f| f["type"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["start", "chunk", "chunk", "replace", "done"]);
+        assert_eq!(got[1]["content"], "Hel");
+        assert_eq!(got[2]["content"], "lo");
+        assert_eq!(got[3]["content"], "Bye");
+        assert_eq!(got[4]["content"], "Bye");
+        assert_eq!(got[4]["attachments"][0]["alt"], "A cat");
+        assert!(ch.shared.drafts.lock().is_empty());
+
+        // No open socket: no draft, so the runtime falls back to `send`.
+        assert!(ch
+            .send_draft(&SendMessage::new("...", "web_other"))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn session_ids_are_sanitized() {
+        assert_eq!(session_id(Some("abc-123_x")), "abc-123_x");
+        assert_ne!(session_id(Some("../etc")), "../etc");
+        assert_eq!(session_id(None).len(), 32);
+    }
+
+    #[tokio::test]
+    async fn serves_chat_page() {
+        let ch = WebChannel::new("127.0.0.1".into(), 0, None);
+        let (tx, _rx) = mpsc::channel(1);
+        let response = ch
+            .router(tx)
+            .oneshot(
+                axum::http::Request::get("/")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("new WebSocket"));
+    }
+
+    #[tokio::test]
+    async fn websocket_round_trip() {
+        let ch = Arc::new(WebChannel::new(
+            "127.0.0.1".into(),
+            0,
+            Some("secret".into()),
+        ));
+        let (tx, mut rx) = mpsc::channel(4);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = ch.router(tx);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let rejected = tokio_tungstenite::connect_async(format!("ws://{addr}/ws?token=wrong"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            rejected,
+            tokio_tungstenite::tungstenite::Error::Http(ref response)
+                if response.status() == StatusCode::UNAUTHORIZED
+        ));
+
+        let mut cross_site = format!("ws://{addr}/ws?token=secret")
+            .into_client_request()
+            .unwrap();
+        cross_site
+            .headers_mut()
+            .insert(header::ORIGIN, "https://evil.example".parse().unwrap());
+        let rejected = tokio_tungstenite::connect_async(cross_site)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            rejected,
+            tokio_tungstenite::tungstenite::Error::Http(ref response)
+                if response.status() == StatusCode::FORBIDDEN
+        ));
+
+        let mut same_site = format!("ws://{addr}/ws?token=secret&session=s-9")
+            .into_client_request()
+            .unwrap();
+        same_site
+            .headers_mut()
+            .insert(header::ORIGIN, format!("http://{addr}").parse().unwrap());
+        let (mut socket, _) = tokio_tungstenite::connect_async(same_site).await.unwrap();
+        let hello = socket.next().await.unwrap().unwrap();
+        let hello: serde_json::Value =
+            serde_json::from_str(hello.into_text().unwrap().as_str()).unwrap();
+        assert_eq!(hello["type"], "session");
+        assert_eq!(hello["session"], "s-9");
+
+        socket
+            .send(WsMessage::Text(
+                r#"{"type":"message","content":" hi "}"#.into(),
+            ))
+            .await
+            .unwrap();
+        let msg = rx.recv().await.unwrap();
+        assert_eq!(msg.channel, "web");
+        assert_eq!(msg.content, "hi");
+        assert_eq!(msg.reply_target, "web_s-9");
+        assert_eq!(msg.thread_ts.as_deref(), Some("s-9"));
+
+        ch.send(&SendMessage::new("hello back", &msg.reply_target))
+            .await
+            .unwrap();
+        let reply = socket.next().await.unwrap().unwrap();
+        let reply: serde_json::Value =
+            serde_json::from_str(reply.into_text().unwrap().as_str()).unwrap();
+        assert_eq!(reply["type"], "message");
+        assert_eq!(reply["content"], "hello back");
+    }
+
+    #[test]
+    fn missing_auth_token_is_generated() {
+        let ch = WebChannel::new("127.0.0.1".into(), 0, None);
+        assert!(ch.generated_token);
+        assert_eq!(ch.shared.auth_token.len(), 32);
+        assert!(!ch.shared.is_authorized(None));
+        assert!(!ch.shared.is_authorized(Some("")));
+        assert!(ch.shared.is_authorized(Some(&ch.shared.auth_token.clone())));
+    }
+
+    #[tokio::test]
+    async fn public_bind_requires_token() {
+        let ch = WebChannel::new("0.0.0.0".into(), 0, None);
+        let (tx, _rx) = mpsc::channel(1);
+        let err = ch.listen(tx).await.unwrap_err();
+        assert!(err.to_string().contains("auth_token"));
+    }
+}
@@ -0,0 +1,744 @@
+use super::attachment::{is_local_path, parse_attachment_markers, Attachment, AttachmentKind};
+use super::split::{non_empty_chunks, split_message_bytes};
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use aes::cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit};
+use async_trait::async_trait;
+use base64::Engine;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+const WECOM_API_BASE: &str = "https://qyapi.weixin.qq.com/cgi-bin";
+/// WeCom rejects text messages longer than 2048 bytes.
+const WECOM_TEXT_MAX_BYTES: usize = 2000;
+/// Images above this size are sent as files (API limit for `image`).
+const WECOM_IMAGE_MAX_BYTES: usize = 10 * 1024 * 1024;
+/// Upper bound for remote attachments fetched before re-upload.
+const WECOM_MAX_ATTACHMENT_BYTES: usize = 20 * 1024 * 1024;
+/// Refresh the access token this long before WeCom expires it.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(300);
+/// Recipient that routes a message through the group robot webhook.
+const ROBOT_RECIPIENT: &str = "robot";
+
+// ── Callback message crypto (WXBizMsgCrypt) ─────────────────────
+
+/// SHA1 signature over the sorted callback parameters, as hex.
+fn callback_signature(token: &str, timestamp: &str, nonce: &str, encrypted: &str) -> String {
+    let mut parts = [token, timestamp, nonce, encrypted];
+    parts.sort_unstable();
+    let digest = ring::digest::digest(
+        &ring::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        parts.concat().as_bytes(),
+    );
+    hex::encode(digest.as_ref())
+}
+
+fn decode_aes_key(encoding_aes_key: &str) -> anyhow::Result<[u8; 32]> {
+    // Keys from the admin console often carry non-zero trailing bits.
+    const KEY_ENGINE: base64::engine::GeneralPurpose = base64::engine::GeneralPurpose::new(
+        &base64::alphabet::STANDARD,
+        base64::engine::GeneralPurposeConfig::new().with_decode_allow_trailing_bits(true),
+    );
+    let raw = KEY_ENGINE
+        .decode(format!("{}=", encoding_aes_key.trim()))
+        .map_err(|e| anyhow::anyhow!("WeCom encoding_aes_key is not valid base64: {e}"))?;
+    raw.try_into()
+        .map_err(|_| anyhow::anyhow!("WeCom encoding_aes_key must decode to 32 bytes"))
+}
+
+/// Decrypt a callback payload and return the inner message, checking that
+/// it was addressed to `receive_id` (the corp ID).
+fn decrypt_callback(
+    aes_key: &[u8; 32],
+    encrypted: &str,
+    receive_id: &str,
+) -> anyhow::Result<String> {
+    let mut data = base64::engine::general_purpose::STANDARD
+        .decode(encrypted.trim())
+        .map_err(|e| anyhow::anyhow!("WeCom payload is not valid base64: {e}"))?;
+    if data.is_empty() || data.len() % 16 != 0 {
+        anyhow::bail!("WeCom payload has invalid length");
+    }
+
+    // AES-256-CBC with the IV taken from the first 16 key bytes.
+    let cipher = aes::Aes256::new(GenericArray::from_slice(aes_key));
+    let mut prev = [0_u8; 16];
+    prev.copy_from_slice(&aes_key[..16]);
+    for block in data.chunks_exact_mut(16) {
+        let mut current = [0_u8; 16];
+        current.copy_from_slice(block);
+        cipher.decrypt_block(GenericArray::from_mut_slice(block));
+        for (byte, iv) in block.iter_mut().zip(prev) {
+            *byte ^= iv;
+        }
+        prev = current;
+    }
+
+    // PKCS#7 padding with a 32-byte block size.
+    let pad = usize::from(*data.last().unwrap_or(&0));
+    if pad == 0 || pad > 32 || pad > data.len() {
+        anyhow::bail!("WeCom payload has invalid padding");
+    }
+    data.truncate(data.len() - pad);
+
+    // 16 random bytes | 4-byte big-endian length | message | receive id
+    if data.len() < 20 {
+        anyhow::bail!("WeCom payload is truncated");
+    }
+    let msg_len = u32::from_be_bytes([data[16], data[17], data[18], data[19]]) as usize;
+    let msg_end = 20 + msg_len;
+    if msg_end > data.len() {
+        anyhow::bail!("WeCom payload length mismatch");
+    }
+    if &data[msg_end..] != receive_id.as_bytes() {
+        anyhow::bail!("WeCom payload was not addressed to this corp");
+    }
+    Ok(String::from_utf8(data[20..msg_end].to_vec())?)
+}
+
+/// Extract a flat field from a WeCom callback XML document.
+fn xml_field(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = xml.find(&open)? + open.len();
+    let end = start + xml[start..].find(&close)?;
+    let value = xml[start..end].trim();
+    let value = value
+        .strip_prefix("<![CDATA[")
+        .and_then(|v| v.strip_suffix("]]>"))
+        .unwrap_or(value);
+    Some(value.to_string())
+}
+
+/// Split text into chunks of at most `max_bytes`, on char boundaries.
+fn split_text(text: &str, max_bytes: usize) -> Vec<String> {
+    non_empty_chunks(split_message_bytes(text, max_bytes))
+}
+
+/// WeCom media type for an outgoing attachment.
+fn media_type(kind: &AttachmentKind, file_name: &str, size: usize) -> &'static str {
+    let lower = file_name.to_ascii_lowercase();
+    match kind {
+        AttachmentKind::Image if size <= WECOM_IMAGE_MAX_BYTES => "image",
+        // WeCom voice messages only accept AMR.
+        AttachmentKind::Voice if lower.ends_with(".amr") => "voice",
+        AttachmentKind::Video if lower.ends_with(".mp4") => "video",
+        _ => "file",
+    }
+}
+
+fn ensure_wecom_ok(body: &serde_json::Value, context: &str) -> anyhow::Result<()> {
+    let errcode = body
+        .get("errcode")
+        .and_then(serde_json::Value::as_i64)
+        .unwrap_or(0);
+    if errcode != 0 {
+        let errmsg = body
+            .get("errmsg")
+            .and_then(|m| m.as_str())
+            .unwrap_or("unknown error");
+        anyhow::bail!("WeCom {context} failed ({errcode}): {errmsg}");
+    }
+    Ok(())
+}
+
+// ── Channel ─────────────────────────────────────────────────────
+
+/// WeChat Work (WeCom) channel.
+///
+/// Receives direct messages to a self-built app through the encrypted
+/// callback API and replies with the app message API. Messages addressed to
+/// `robot` go to the group robot webhook instead. Image and file markers are
+/// uploaded as WeCom media.
+#[derive(Clone)]
+pub struct WecomChannel {
+    corp_id: String,
+    corp_secret: String,
+    agent_id: i64,
+    callback_token: String,
+    encoding_aes_key: String,
+    port: u16,
+    robot_key: Option<String>,
+    allowed_users: Vec<String>,
+    access_token: Arc<RwLock<Option<(String, Instant)>>>,
+}
+
+/// Configuration for constructing a `WecomChannel`.
+pub struct WecomChannelConfig {
+    pub corp_id: String,
+    pub corp_secret: String,
+    pub agent_id: i64,
+    pub callback_token: String,
+    pub encoding_aes_key: String,
+    pub port: u16,
+    pub robot_key: Option<String>,
+    pub allowed_users: Vec<String>,
+}
+
+impl WecomChannel {
+    pub fn new(cfg: WecomChannelConfig) -> Self {
+        Self {
+            corp_id: cfg.corp_id,
+            corp_secret: cfg.corp_secret,
+            agent_id: cfg.agent_id,
+            callback_token: cfg.callback_token,
+            encoding_aes_key: cfg.encoding_aes_key,
+            port: cfg.port,
+            robot_key: cfg.robot_key.filter(|k| !k.trim().is_empty()),
+            allowed_users: cfg.allowed_users,
+            access_token: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.wecom")
+    }
+
+    fn is_user_allowed(&self, user_id: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(user_id))
+    }
+
+    fn verify_signature(
+        &self,
+        signature: &str,
+        timestamp: &str,
+        nonce: &str,
+        encrypted: &str,
+    ) -> bool {
+        let expected = callback_signature(&self.callback_token, timestamp, nonce, encrypted);
+        crate::security::pairing::constant_time_eq(&expected, &signature.to_ascii_lowercase())
+    }
+
+    /// Verify and decrypt a callback payload (URL check `echostr` or message `Encrypt`).
+    fn open_callback(
+        &self,
+        signature: &str,
+        timestamp: &str,
+        nonce: &str,
+        encrypted: &str,
+    ) -> anyhow::Result<String> {
+        if !self.verify_signature(signature, timestamp, nonce, encrypted) {
+            anyhow::bail!("WeCom callback signature mismatch");
+        }
+        let key = decode_aes_key(&self.encoding_aes_key)?;
+        decrypt_callback(&key, encrypted, &self.corp_id)
+    }
+
+    /// Convert a decrypted callback message into a channel message.
+    fn parse_callback_message(&self, xml: &str) -> Option<ChannelMessage> {
+        let sender = xml_field(xml, "FromUserName")?;
+        let content = match xml_field(xml, "MsgType")?.as_str() {
+            "text" => xml_field(xml, "Content")?.trim().to_string(),
+            "image" => format!("[IMAGE:{}]", xml_field(xml, "PicUrl")?),
+            other => {
+                tracing::debug!("WeCom: skipping unsupported {other} message");
+                return None;
+            }
+        };
+        if content.is_empty() {
+            return None;
+        }
+        if !self.is_user_allowed(&sender) {
+            tracing::warn!("WeCom: ignoring message from unauthorized user: {sender}");
+            return None;
+        }
+
+        let msg_id = xml_field(xml, "MsgId").unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let timestamp = xml_field(xml, "CreateTime")
+            .and_then(|t| t.parse().ok())
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            });
+        Some(ChannelMessage {
+            id: format!("wecom_{msg_id}"),
+            sender: sender.clone(),
+            reply_target: sender,
+            content,
+            channel: "wecom".to_string(),
+            timestamp,
+            thread_ts: None,
+        })
+    }
+
+    async fn get_access_token(&self) -> anyhow::Result<String> {
+        if let Some((token, expires_at)) = self.access_token.read().await.as_ref() {
+            if Instant::now() < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let body: serde_json::Value = self
+            .http_client()
+            .get(format!("{WECOM_API_BASE}/gettoken"))
+            .query(&[("corpid", &self.corp_id), ("corpsecret", &self.corp_secret)])
+            .send()
+            .await?
+            .json()
+            .await?;
+        ensure_wecom_ok(&body, "gettoken")?;
+        let token = body
+            .get("access_token")
+            .and_then(|t| t.as_str())
+            .ok_or_else(|| anyhow::anyhow!("WeCom gettoken: missing access_token"))?
+            .to_string();
+        let expires_in = body
+            .get("expires_in")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(7200);
+        let expires_at =
+            Instant::now() + Duration::from_secs(expires_in).saturating_sub(TOKEN_REFRESH_MARGIN);
+        *self.access_token.write().await = Some((token.clone(), expires_at));
+        Ok(token)
+    }
+
+    async fn post_json(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+        context: &str,
+    ) -> anyhow::Result<serde_json::Value> {
+        let resp: serde_json::Value = self
+            .http_client()
+            .post(url)
+            .json(body)
+            .send()
+            .await?
+            .json()
+            .await?;
+        ensure_wecom_ok(&resp, context)?;
+        Ok(resp)
+    }
+
+    /// Send one app message (`text`, `image`, `file`, ...) to a user.
+    async fn send_app_message(
+        &self,
+        to_user: &str,
+        msgtype: &str,
+        payload: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let token = self.get_access_token().await?;
+        let mut body = serde_json::json!({
+            "touser": to_user,
+            "msgtype": msgtype,
+            "agentid": self.agent_id,
+        });
+        body[msgtype] = payload;
+        self.post_json(
+            &format!("{WECOM_API_BASE}/message/send?access_token={token}"),
+            &body,
+            "message/send",
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn send_robot_message(
+        &self,
+        msgtype: &str,
+        payload: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let key = self
+            .robot_key
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("WeCom robot_key is not configured"))?;
+        let mut body = serde_json::json!({ "msgtype": msgtype });
+        body[msgtype] = payload;
+        self.post_json(
+            &format!("{WECOM_API_BASE}/webhook/send?key={key}"),
+            &body,
+            "robot webhook send",
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Read an attachment from disk or fetch it from its URL.
+    async fn load_attachment(&self, target: &str) -> anyhow::Result<(String, Vec<u8>)> {
+        if is_local_path(target) {
+            let path = std::path::PathBuf::from(shellexpand::tilde(target).into_owned());
+            let bytes = tokio::fs::read(&path)
+                .await
+                .map_err(|e| anyhow::anyhow!("WeCom attachment {}: {e}", path.display()))?;
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("attachment")
+                .to_string();
+            return Ok((name, bytes));
+        }
+
+        let resp = self.http_client().get(target).send().await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("WeCom attachment download failed ({})", resp.status());
+        }
+        let name = reqwest::Url::parse(target)
+            .ok()
+            .and_then(|u| u.path_segments()?.next_back().map(str::to_string))
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| "attachment".to_string());
+        let bytes = resp.bytes().await?;
+        if bytes.len() > WECOM_MAX_ATTACHMENT_BYTES {
+            anyhow::bail!("WeCom attachment exceeds {WECOM_MAX_ATTACHMENT_BYTES} bytes");
+        }
+        Ok((name, bytes.to_vec()))
+    }
+
+    /// Upload media and return its `media_id`.
+    async fn upload_media(
+        &self,
+        url: &str,
+        file_name: &str,
+        bytes: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let part = reqwest::multipart::Part::bytes(bytes)
+            .file_name(file_name.to_string())
+            .mime_str(
+                mime_guess::from_path(file_name)
+                    .first_or_octet_stream()
+                    .as_ref(),
+            )?;
+        let form = reqwest::multipart::Form::new().part("media", part);
+        let resp: serde_json::Value = self
+            .http_client()
+            .post(url)
+            .multipart(form)
+            .send()
+            .await?
+            .json()
+            .await?;
+        ensure_wecom_ok(&resp, "media upload")?;
+        resp.get("media_id")
+            .and_then(|m| m.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("WeCom media upload: missing media_id"))
+    }
+
+    async fn send_attachment(
+        &self,
+        recipient: &str,
+        attachment: &Attachment,
+    ) -> anyhow::Result<()> {
+        let (name, bytes) = self.load_attachment(&attachment.target).await?;
+
+        if recipient == ROBOT_RECIPIENT {
+            // Robot webhooks accept uploaded media only as files.
+            let key = self
+                .robot_key
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("WeCom robot_key is not configured"))?;
+            let media_id = self
+                .upload_media(
+                    &format!("{WECOM_API_BASE}/webhook/upload_media?key={key}&type=file"),
+                    &name,
+                    bytes,
+                )
+                .await?;
+            return self
+                .send_robot_message("file", serde_json::json!({ "media_id": media_id }))
+                .await;
+        }
+
+        let kind = media_type(&attachment.kind, &name, bytes.len());
+        let token = self.get_access_token().await?;
+        let media_id = self
+            .upload_media(
+                &format!("{WECOM_API_BASE}/media/upload?access_token={token}&type={kind}"),
+                &name,
+                bytes,
+            )
+            .await?;
+        self.send_app_message(recipient, kind, serde_json::json!({ "media_id": media_id }))
+            .await
+    }
+}
+
+#[async_trait]
+impl Channel for WecomChannel {
+    fn name(&self) -> &str {
+        "wecom"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let (text, attachments) = parse_attachment_markers(&message.content);
+
+        for chunk in split_text(&text, WECOM_TEXT_MAX_BYTES) {
+            let payload = serde_json::json!({ "content": chunk });
+            if message.recipient == ROBOT_RECIPIENT {
+                self.send_robot_message("text", payload).await?;
+            } else {
+                self.send_app_message(&message.recipient, "text", payload)
+                    .await?;
+            }
+        }
+
+        for attachment in &attachments {
+            if let Err(e) = self.send_attachment(&message.recipient, attachment).await {
+                tracing::warn!("WeCom: attachment upload failed: {e}");
+                let fallback = serde_json::json!({ "content": attachment.target });
+                if message.recipient == ROBOT_RECIPIENT {
+                    self.send_robot_message("text", fallback).await?;
+                } else if !is_local_path(&attachment.target) {
+                    self.send_app_message(&message.recipient, "text", fallback)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        use axum::extract::{Query, State};
+        use axum::http::StatusCode;
+        use axum::response::IntoResponse;
+        use axum::routing::get;
+        use axum::Router;
+        use std::collections::HashMap;
+
+        #[derive(Clone)]
+        struct AppState {
+            channel: Arc<WecomChannel>,
+            tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+        }
+
+        fn param<'a>(params: &'a HashMap<String, String>, key: &str) -> &'a str {
+            params.get(key).map_or("", String::as_str)
+        }
+
+        // URL verification: decrypt `echostr` and echo it back.
+        async fn handle_verify(
+            State(state): State<AppState>,
+            Query(params): Query<HashMap<String, String>>,
+        ) -> axum::response::Response {
+            match state.channel.open_callback(
+                param(&params, "msg_signature"),
+                param(&params, "timestamp"),
+                param(&params, "nonce"),
+                param(&params, "echostr"),
+            ) {
+                Ok(echo) => (StatusCode::OK, echo).into_response(),
+                Err(e) => {
+                    tracing::warn!("WeCom: URL verification failed: {e}");
+                    (StatusCode::FORBIDDEN, "invalid signature").into_response()
+                }
+            }
+        }
+
+        async fn handle_message(
+            State(state): State<AppState>,
+            Query(params): Query<HashMap<String, String>>,
+            body: String,
+        ) -> axum::response::Response {
+            let Some(encrypted) = xml_field(&body, "Encrypt") else {
+                return (StatusCode::BAD_REQUEST, "missing Encrypt").into_response();
+            };
+            let xml = match state.channel.open_callback(
+                param(&params, "msg_signature"),
+                param(&params, "timestamp"),
+                param(&params, "nonce"),
+                &encrypted,
+            ) {
+                Ok(xml) => xml,
+                Err(e) => {
+                    tracing::warn!("WeCom: rejected callback: {e}");
+                    return (StatusCode::FORBIDDEN, "invalid signature").into_response();
+                }
+            };
+
+            if let Some(msg) = state.channel.parse_callback_message(&xml) {
+                if state.tx.send(msg).await.is_err() {
+                    tracing::warn!("WeCom: message channel closed");
+                }
+            }
+            // Replies go out through the message API, so acknowledge with an empty body.
+            (StatusCode::OK, "").into_response()
+        }
+
+        decode_aes_key(&self.encoding_aes_key)?;
+        let state = AppState {
+            channel: Arc::new(self.clone()),
+            tx,
+        };
+        let app = Router::new()
+            .route("/wecom", get(handle_verify).post(handle_message))
+            .with_state(state);
+
+        let addr = std::net::SocketAddr::from(([0, 0, 0, 0], self.port));
+        tracing::info!("WeCom callback server listening on {addr}");
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, app).await?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.get_access_token().await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::cipher::BlockEncrypt;
+
+    // 43-char EncodingAESKey as issued by the WeCom admin console.
+    const TEST_AES_KEY: &str = "jWmYm7qr5nMoAUwZRjGtBxmz3KA1tkAj3ykkR6q2B2C";
+
+    fn make_channel() -> WecomChannel {
+        WecomChannel::new(WecomChannelConfig {
+            corp_id: "ww1234567890".into(),
+            corp_secret: "secret".into(),
+            agent_id: 1_000_002,
+            callback_token: "QDG6eK".into(),
+            encoding_aes_key: TEST_AES_KEY.into(),
+            port: 8082,
+            robot_key: None,
+            allowed_users: vec!["zhangsan".into()],
+        })
+    }
+
+    /// Encrypt like the WeCom server does, for round-trip tests.
+    fn encrypt_callback(key: &[u8; 32], msg: &str, receive_id: &str) -> String {
+        let mut data = vec![7_u8; 16];
+        data.extend_from_slice(&u32::try_from(msg.len()).unwrap().to_be_bytes());
+        data.extend_from_slice(msg.as_bytes());
+        data.extend_from_slice(receive_id.as_bytes());
+        let pad = 32 - data.len() % 32;
+        data.extend(std::iter::repeat_n(u8::try_from(pad).unwrap(), pad));
+
+        let cipher = aes::Aes256::new(GenericArray::from_slice(key));
+        let mut prev = [0_u8; 16];
+        prev.copy_from_slice(&key[..16]);
+        for block in data.chunks_exact_mut(16) {
+            for (byte, iv) in block.iter_mut().zip(prev) {
+                *byte ^= iv;
+            }
+            cipher.encrypt_block(GenericArray::from_mut_slice(block));
+            prev.copy_from_slice(block);
+        }
+        base64::engine::general_purpose::STANDARD.encode(data)
+    }
+
+    #[test]
+    fn signature_sorts_parameters() {
+        let a = callback_signature("token", "1409659589", "263014780", "enc");
+        let b = callback_signature("token", "1409659589", "263014780", "enc");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 40);
+        assert_ne!(
+            a,
+            callback_signature("token", "1409659589", "263014781", "enc")
+        );
+    }
+
+    #[test]
+    fn decrypt_round_trips_and_checks_receive_id() {
+        let key = decode_aes_key(TEST_AES_KEY).unwrap();
+        let encrypted = encrypt_callback(&key, "<xml>hello 你好</xml>", "ww1234567890");
+        assert_eq!(
+            decrypt_callback(&key, &encrypted, "ww1234567890").unwrap(),
+            "<xml>hello 你好</xml>"
+        );
+        assert!(decrypt_callback(&key, &encrypted, "ww-other").is_err());
+    }
+
+    #[test]
+    fn decode_aes_key_rejects_wrong_length() {
+        assert!(decode_aes_key("short").is_err());
+        assert!(decode_aes_key(TEST_AES_KEY).is_ok());
+    }
+
+    #[test]
+    fn open_callback_rejects_bad_signature() {
+        let ch = make_channel();
+        let key = decode_aes_key(TEST_AES_KEY).unwrap();
+        let encrypted = encrypt_callback(&key, "echo-123", "ww1234567890");
+        let signature = callback_signature("QDG6eK", "1", "n", &encrypted);
+
+        assert_eq!(
+            ch.open_callback(&signature, "1", "n", &encrypted).unwrap(),
+            "echo-123"
+        );
+        assert!(ch.open_callback(&signature, "2", "n", &encrypted).is_err());
+    }
+
+    #[test]
+    fn xml_field_reads_cdata_and_plain_values() {
+        let xml = "<xml><FromUserName><![CDATA[zhangsan]]></FromUserName><CreateTime>1348831860</CreateTime></xml>";
+        assert_eq!(xml_field(xml, "FromUserName").as_deref(), Some("zhangsan"));
+        assert_eq!(xml_field(xml, "CreateTime").as_deref(), Some("1348831860"));
+        assert!(xml_field(xml, "Content").is_none());
+    }
+
+    #[test]
+    fn parse_text_and_image_messages() {
+        let ch = make_channel();
+        let text = ch
+            .parse_callback_message(
+                "<xml><FromUserName><![CDATA[zhangsan]]></FromUserName><CreateTime>1348831860</CreateTime><MsgType><![CDATA[text]]></MsgType><Content><![CDATA[你好]]></Content><MsgId>1234567890123456</MsgId></xml>",
+            )
+            .unwrap();
+        assert_eq!(text.id, "wecom_1234567890123456");
+        assert_eq!(text.reply_target, "zhangsan");
+        assert_eq!(text.content, "你好");
+        assert_eq!(text.timestamp, 1_348_831_860);
+
+        let image = ch
+            .parse_callback_message(
+                "<xml><FromUserName><![CDATA[zhangsan]]></FromUserName><MsgType><![CDATA[image]]></MsgType><PicUrl><![CDATA[https://wework.qpic.cn/a.png]]></PicUrl></xml>",
+            )
+            .unwrap();
+        assert_eq!(image.content, "[IMAGE:https://wework.qpic.cn/a.png]");
+    }
+
+    #[test]
+    fn parse_ignores_unauthorized_and_unsupported_messages() {
+        let ch = make_channel();
+        assert!(ch
+            .parse_callback_message(
+                "<xml><FromUserName>lisi</FromUserName><MsgType>text</MsgType><Content>hi</Content></xml>"
+            )
+            .is_none());
+        assert!(ch
+            .parse_callback_message(
+                "<xml><FromUserName>zhangsan</FromUserName><MsgType>event</MsgType></xml>"
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn media_type_adapts_to_wecom_limits() {
+        assert_eq!(media_type(&AttachmentKind::Image, "a.png", 1024), "image");
+        assert_eq!(
+            media_type(&AttachmentKind::Image, "a.png", WECOM_IMAGE_MAX_BYTES + 1),
+            "file"
+        );
+        assert_eq!(media_type(&AttachmentKind::Voice, "a.amr", 10), "voice");
+        assert_eq!(media_type(&AttachmentKind::Voice, "a.ogg", 10), "file");
+        assert_eq!(media_type(&AttachmentKind::Video, "a.mp4", 10), "video");
+        assert_eq!(media_type(&AttachmentKind::Document, "a.pdf", 10), "file");
+    }
+
+    #[test]
+    fn split_text_respects_byte_limit() {
+        let chunks = split_text(&"你".repeat(1000), WECOM_TEXT_MAX_BYTES);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|c| c.len() <= WECOM_TEXT_MAX_BYTES));
+        assert!(split_text("", WECOM_TEXT_MAX_BYTES).is_empty());
+    }
+
+    #[test]
+    fn wecom_errcode_is_surfaced() {
+        let err = ensure_wecom_ok(
+            &serde_json::json!({"errcode": 40014, "errmsg": "invalid access_token"}),
+            "message/send",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("40014"));
+        assert!(ensure_wecom_ok(&serde_json::json!({"errcode": 0}), "x").is_ok());
+    }
+}
@@ -1,7 +1,29 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::attachment::{
+    is_local_path, parse_captioned_attachments, sanitize_file_name, AttachmentKind,
+};
+use super::markdown::{self, Dialect};
+use super::split::split_message;
+use super::traits::{Channel, ChannelCapabilities, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use uuid::Uuid;
 
+/// Cloud API limit for a text message body.
+const WHATSAPP_MAX_TEXT_CHARS: usize = 4096;
+/// Maximum length of an image, video or document caption.
+const WHATSAPP_MAX_CAPTION_CHARS: usize = 1024;
+/// Largest inbound media file saved to the workspace.
+const WHATSAPP_MAX_DOWNLOAD_BYTES: usize = 20 * 1024 * 1024;
+
+/// A media object attached to an inbound message.
+#[derive(Debug, PartialEq)]
+struct InboundMedia<'a> {
+    kind: AttachmentKind,
+    id: &'a str,
+    mime: &'a str,
+    file_name: Option<&'a str>,
+    caption: Option<&'a str>,
+}
+
 /// `WhatsApp` channel — uses `WhatsApp` Business Cloud API
 ///
 /// This channel operates in webhook mode (push-based) rather than polling.
@@ -27,6 +49,8 @@ pub struct WhatsAppChannel {
     endpoint_id: String,
     verify_token: String,
     allowed_numbers: Vec<String>,
+    transcription: Option<crate::config::TranscriptionConfig>,
+    workspace_dir: Option<std::path::PathBuf>,
 }
 
 impl WhatsAppChannel {
@@ -41,9 +65,25 @@ impl WhatsAppChannel {
             endpoint_id,
             verify_token,
             allowed_numbers,
+            transcription: None,
+            workspace_dir: None,
         }
     }
 
+    /// Configure voice transcription for inbound audio messages.
+    pub fn with_transcription(mut self, config: crate::config::TranscriptionConfig) -> Self {
+        if config.enabled {
+            self.transcription = Some(config);
+        }
+        self
+    }
+
+    /// Configure workspace directory for saving inbound media.
+    pub fn with_workspace_dir(mut self, dir: std::path::PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.whatsapp")
     }
@@ -58,128 +98,305 @@ impl WhatsAppChannel {
         &self.verify_token
     }
 
+    /// Flatten the message objects out of a webhook payload.
+    ///
+    /// WhatsApp Cloud API webhook structure:
+    /// `{ "object": "whatsapp_business_account", "entry": [{ "changes": [{ "value": { "messages": [...] } }] }] }`
+    fn webhook_messages(payload: &serde_json::Value) -> Vec<&serde_json::Value> {
+        payload
+            .get("entry")
+            .and_then(|e| e.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.get("changes").and_then(|c| c.as_array()))
+            .flatten()
+            .filter_map(|change| change.get("value"))
+            .filter_map(|value| value.get("messages").and_then(|m| m.as_array()))
+            .flatten()
+            .collect()
+    }
+
+    /// Normalized sender number of `msg`, or `None` when it is missing or not
+    /// on the allowlist.
+    fn allowed_sender(&self, msg: &serde_json::Value) -> Option<String> {
+        let from = msg.get("from").and_then(|f| f.as_str())?;
+
+        let normalized_from = if from.starts_with('+') {
+            from.to_string()
+        } else {
+            format!("+{from}")
+        };
+
+        if !self.is_number_allowed(&normalized_from) {
+            tracing::warn!(
+                "WhatsApp: ignoring message from unauthorized number: {normalized_from}. \
+                Add to channels.whatsapp.allowed_numbers in config.toml, \
+                or run `zeroclaw onboard --channels-only` to configure interactively."
+            );
+            return None;
+        }
+
+        Some(normalized_from)
+    }
+
+    fn message_timestamp(msg: &serde_json::Value) -> u64 {
+        msg.get("timestamp")
+            .and_then(|t| t.as_str())
+            .and_then(|t| t.parse::<u64>().ok())
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            })
+    }
+
+    /// The `wamid` Meta assigns to a message, stable across redeliveries.
+    fn message_id(msg: &serde_json::Value) -> String {
+        msg.get("id")
+            .and_then(|id| id.as_str())
+            .filter(|id| !id.is_empty())
+            .map_or_else(|| Uuid::new_v4().to_string(), str::to_string)
+    }
+
     /// Parse an incoming webhook payload from Meta and extract messages
     pub fn parse_webhook_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
         let mut messages = Vec::new();
 
-        // WhatsApp Cloud API webhook structure:
-        // { "object": "whatsapp_business_account", "entry": [...] }
-        let Some(entries) = payload.get("entry").and_then(|e| e.as_array()) else {
-            return messages;
-        };
+        for msg in Self::webhook_messages(payload) {
+            let Some(sender) = self.allowed_sender(msg) else {
+                continue;
+            };
 
-        for entry in entries {
-            let Some(changes) = entry.get("changes").and_then(|c| c.as_array()) else {
+            // Extract text content (audio is handled by `parse_voice_messages`)
+            let content = if let Some(text_obj) = msg.get("text") {
+                text_obj
+                    .get("body")
+                    .and_then(|b| b.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            } else {
+                tracing::debug!("WhatsApp: skipping non-text message from {sender}");
                 continue;
             };
 
-            for change in changes {
-                let Some(value) = change.get("value") else {
+            if content.is_empty() {
+                continue;
+            }
+
+            messages.push(ChannelMessage {
+                id: Self::message_id(msg),
+                reply_target: sender.clone(),
+                sender,
+                content,
+                channel: "whatsapp".to_string(),
+                timestamp: Self::message_timestamp(msg),
+                thread_ts: None,
+            });
+        }
+
+        messages
+    }
+
+    /// Download and transcribe the audio messages (voice notes and audio
+    /// files) in a webhook payload.
+    ///
+    /// Returns nothing when transcription is disabled. Messages whose audio
+    /// cannot be fetched or transcribed are logged and dropped.
+    pub async fn parse_voice_messages(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        let Some(config) = self.transcription.as_ref() else {
+            return Vec::new();
+        };
+
+        let mut messages = Vec::new();
+        for msg in Self::webhook_messages(payload) {
+            let Some(audio) = msg.get("audio") else {
+                continue;
+            };
+            let Some(media_id) = audio.get("id").and_then(|id| id.as_str()) else {
+                continue;
+            };
+            let Some(sender) = self.allowed_sender(msg) else {
+                continue;
+            };
+            let mime = audio
+                .get("mime_type")
+                .and_then(|m| m.as_str())
+                .unwrap_or("audio/ogg");
+
+            let audio_data = match self.download_media(media_id).await {
+                Ok(data) => data,
+                Err(e) => {
+                    tracing::warn!("WhatsApp: failed to download voice message: {e}");
                     continue;
-                };
+                }
+            };
 
-                // Extract messages array
-                let Some(msgs) = value.get("messages").and_then(|m| m.as_array()) else {
+            let file_name = format!("voice.{}", super::transcription::extension_for_mime(mime));
+            let text = match super::transcription::transcribe_audio(audio_data, &file_name, config)
+                .await
+            {
+                Ok(text) => text,
+                Err(e) => {
+                    tracing::warn!("WhatsApp: voice transcription failed: {e}");
                     continue;
-                };
-
-                for msg in msgs {
-                    // Get sender phone number
-                    let Some(from) = msg.get("from").and_then(|f| f.as_str()) else {
-                        continue;
-                    };
-
-                    // Check allowlist
-                    let normalized_from = if from.starts_with('+') {
-                        from.to_string()
-                    } else {
-                        format!("+{from}")
-                    };
-
-                    if !self.is_number_allowed(&normalized_from) {
-                        tracing::warn!(
-                            "WhatsApp: ignoring message from unauthorized number: {normalized_from}. \
-                            Add to channels.whatsapp.allowed_numbers in config.toml, \
-                            or run `zeroclaw onboard --channels-only` to configure interactively."
-                        );
-                        continue;
-                    }
+                }
+            };
+            if text.trim().is_empty() {
+                tracing::info!("WhatsApp: voice transcription returned empty text, skipping");
+                continue;
+            }
 
-                    // Extract text content (support text messages only for now)
-                    let content = if let Some(text_obj) = msg.get("text") {
-                        text_obj
-                            .get("body")
-                            .and_then(|b| b.as_str())
-                            .unwrap_or("")
-                            .to_string()
-                    } else {
-                        // Could be image, audio, etc. — skip for now
-                        tracing::debug!("WhatsApp: skipping non-text message from {from}");
-                        continue;
-                    };
-
-                    if content.is_empty() {
-                        continue;
-                    }
+            messages.push(ChannelMessage {
+                id: Self::message_id(msg),
+                reply_target: sender.clone(),
+                sender,
+                content: super::transcription::voice_message_content(&text, None),
+                channel: "whatsapp".to_string(),
+                timestamp: Self::message_timestamp(msg),
+                thread_ts: None,
+            });
+        }
+
+        messages
+    }
+
+    /// The media object of an image, sticker, video, document or audio
+    /// message. Stickers are images; audio sent as a voice note is `Voice`.
+    fn inbound_media(msg: &serde_json::Value) -> Option<InboundMedia<'_>> {
+        let (field, media) = ["image", "sticker", "video", "document", "audio"]
+            .into_iter()
+            .find_map(|field| msg.get(field).map(|media| (field, media)))?;
+        let kind = match field {
+            "image" | "sticker" => AttachmentKind::Image,
+            "video" => AttachmentKind::Video,
+            "document" => AttachmentKind::Document,
+            _ if media.get("voice").and_then(serde_json::Value::as_bool) == Some(true) => {
+                AttachmentKind::Voice
+            }
+            _ => AttachmentKind::Audio,
+        };
+        Some(InboundMedia {
+            kind,
+            id: media.get("id").and_then(|id| id.as_str())?,
+            mime: media
+                .get("mime_type")
+                .and_then(|m| m.as_str())
+                .unwrap_or("application/octet-stream"),
+            file_name: media.get("filename").and_then(|f| f.as_str()),
+            caption: media
+                .get("caption")
+                .and_then(|c| c.as_str())
+                .filter(|c| !c.trim().is_empty()),
+        })
+    }
+
+    /// Download the media messages in a webhook payload into
+    /// `{workspace_dir}/whatsapp_files/` and turn each into an attachment
+    /// marker (`[IMAGE:...]`, `[DOCUMENT:...]`, ...) followed by its caption.
+    ///
+    /// Audio is left to `parse_voice_messages` when transcription is enabled.
+    /// Returns nothing when `workspace_dir` is not configured; media that
+    /// cannot be fetched is logged and dropped.
+    pub async fn parse_media_messages(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        let mut messages = Vec::new();
+        for msg in Self::webhook_messages(payload) {
+            let Some(media) = Self::inbound_media(msg) else {
+                continue;
+            };
+            if self.transcription.is_some()
+                && matches!(media.kind, AttachmentKind::Audio | AttachmentKind::Voice)
+            {
+                continue;
+            }
+            let Some(workspace) = self.workspace_dir.as_ref() else {
+                tracing::warn!("WhatsApp: cannot save media: workspace_dir not configured");
+                continue;
+            };
+            let Some(sender) = self.allowed_sender(msg) else {
+                continue;
+            };
 
-                    // Get timestamp
-                    let timestamp = msg
-                        .get("timestamp")
-                        .and_then(|t| t.as_str())
-                        .and_then(|t| t.parse::<u64>().ok())
-                        .unwrap_or_else(|| {
-                            std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs()
-                        });
-
-                    messages.push(ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
-                        reply_target: normalized_from.clone(),
-                        sender: normalized_from,
-                        content,
-                        channel: "whatsapp".to_string(),
-                        timestamp,
-                        thread_ts: None,
-                    });
+            let path = match self.save_media(workspace, &media).await {
+                Ok(path) => path,
+                Err(e) => {
+                    tracing::warn!("WhatsApp: failed to save inbound media: {e}");
+                    continue;
                 }
+            };
+            let mut content = format!("[{}:{}]", media.kind.marker_name(), path.display());
+            if let Some(caption) = media.caption {
+                content.push_str("\n\n");
+                content.push_str(caption.trim());
             }
+
+            messages.push(ChannelMessage {
+                id: Self::message_id(msg),
+                reply_target: sender.clone(),
+                sender,
+                content,
+                channel: "whatsapp".to_string(),
+                timestamp: Self::message_timestamp(msg),
+                thread_ts: None,
+            });
         }
 
         messages
     }
-}
 
-#[async_trait]
-impl Channel for WhatsAppChannel {
-    fn name(&self) -> &str {
-        "whatsapp"
+    async fn save_media(
+        &self,
+        workspace: &std::path::Path,
+        media: &InboundMedia<'_>,
+    ) -> anyhow::Result<std::path::PathBuf> {
+        let data = self.download_media(media.id).await?;
+        if data.len() > WHATSAPP_MAX_DOWNLOAD_BYTES {
+            anyhow::bail!(
+                "media {} exceeds {WHATSAPP_MAX_DOWNLOAD_BYTES} bytes",
+                media.id
+            );
+        }
+
+        let dir = workspace.join("whatsapp_files");
+        tokio::fs::create_dir_all(&dir).await?;
+        let path = dir.join(media_file_name(media));
+        tokio::fs::write(&path, &data).await?;
+        Ok(path)
     }
 
-    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
+    /// Cloud API message type for an attachment marker. Voice notes are
+    /// `audio` messages; Ogg/Opus audio is shown as a voice note. WhatsApp
+    /// plays GIFs as MP4 videos.
+    fn media_type(kind: &AttachmentKind) -> &'static str {
+        match kind {
+            AttachmentKind::Image => "image",
+            AttachmentKind::Document => "document",
+            AttachmentKind::Video | AttachmentKind::Animation => "video",
+            AttachmentKind::Audio | AttachmentKind::Voice => "audio",
+            AttachmentKind::Sticker => "sticker",
+        }
+    }
+
+    /// POST a message to /v18.0/{phone_number_id}/messages. `payload` holds
+    /// the `type` and the matching content object.
+    async fn send_message_payload(
+        &self,
+        to: &str,
+        payload: serde_json::Value,
+    ) -> anyhow::Result<()> {
         let url = format!(
             "https://graph.facebook.com/v18.0/{}/messages",
             self.endpoint_id
         );
 
-        // Normalize recipient (remove leading + if present for API)
-        let to = message
-            .recipient
-            .strip_prefix('+')
-            .unwrap_or(&message.recipient);
-
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "messaging_product": "whatsapp",
             "recipient_type": "individual",
             "to": to,
-            "type": "text",
-            "text": {
-                "preview_url": false,
-                "body": message.content
-            }
         });
+        if let (Some(body), Some(payload)) = (body.as_object_mut(), payload.as_object()) {
+            body.extend(payload.clone());
+        }
 
         ensure_https(&url)?;
 
@@ -202,6 +419,203 @@ impl Channel for WhatsAppChannel {
         Ok(())
     }
 
+    /// Upload a local file to /v18.0/{phone_number_id}/media and return the
+    /// media id to reference in a message.
+    async fn upload_media(&self, path: &std::path::Path) -> anyhow::Result<String> {
+        let url = format!(
+            "https://graph.facebook.com/v18.0/{}/media",
+            self.endpoint_id
+        );
+        ensure_https(&url)?;
+
+        let data = tokio::fs::read(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("WhatsApp attachment {}: {e}", path.display()))?;
+        let mime = mime_guess::from_path(path).first_or_octet_stream();
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("attachment")
+            .to_string();
+
+        let part = reqwest::multipart::Part::bytes(data)
+            .file_name(file_name)
+            .mime_str(mime.as_ref())?;
+        let form = reqwest::multipart::Form::new()
+            .text("messaging_product", "whatsapp")
+            .text("type", mime.to_string())
+            .part("file", part);
+
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp media upload failed: {status} — {error_body}");
+            anyhow::bail!("WhatsApp media upload error: {status}");
+        }
+
+        let uploaded: serde_json::Value = resp.json().await?;
+        uploaded
+            .get("id")
+            .and_then(|id| id.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("WhatsApp media upload returned no id"))
+    }
+
+    /// Fetch a media object: resolve its download URL, then download the
+    /// bytes. Both requests need the access token.
+    async fn download_media(&self, media_id: &str) -> anyhow::Result<Vec<u8>> {
+        let url = format!("https://graph.facebook.com/v18.0/{media_id}");
+        ensure_https(&url)?;
+
+        let client = self.http_client();
+        let resp = client
+            .get(&url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("WhatsApp media lookup failed: {}", resp.status());
+        }
+        let media: serde_json::Value = resp.json().await?;
+        let download_url = media
+            .get("url")
+            .and_then(|u| u.as_str())
+            .ok_or_else(|| anyhow::anyhow!("WhatsApp media lookup returned no url"))?;
+        ensure_https(download_url)?;
+
+        let resp = client
+            .get(download_url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("WhatsApp media download failed: {}", resp.status());
+        }
+        Ok(resp.bytes().await?.to_vec())
+    }
+}
+
+/// File name for saved media: the sender's file name when present, otherwise
+/// the media id with an extension guessed from the MIME type. Prefixed with
+/// the media id so repeated names do not overwrite each other.
+fn media_file_name(media: &InboundMedia<'_>) -> String {
+    match media.file_name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => format!(
+            "{}_{}",
+            sanitize_file_name(media.id),
+            sanitize_file_name(name)
+        ),
+        None => {
+            let essence = media.mime.split(';').next().unwrap_or_default().trim();
+            let ext = mime_guess::get_mime_extensions_str(essence)
+                .and_then(|exts| exts.first())
+                .copied()
+                .unwrap_or("bin");
+            format!("{}.{ext}", sanitize_file_name(media.id))
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for WhatsAppChannel {
+    fn name(&self) -> &str {
+        "whatsapp"
+    }
+
+    fn capabilities(&self) -> ChannelCapabilities {
+        ChannelCapabilities {
+            max_message_len: Some(WHATSAPP_MAX_TEXT_CHARS),
+            attachment_kinds: ChannelCapabilities::rich_attachments(),
+            ..ChannelCapabilities::default()
+        }
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        // Normalize recipient (remove leading + if present for API)
+        let to = message
+            .recipient
+            .strip_prefix('+')
+            .unwrap_or(&message.recipient);
+
+        let (text, attachments) =
+            parse_captioned_attachments(&message.content, WHATSAPP_MAX_CAPTION_CHARS);
+        let text = if attachments.is_empty() {
+            message.content.as_str()
+        } else {
+            text.as_str()
+        };
+
+        if !text.is_empty() {
+            for chunk in split_message(text, WHATSAPP_MAX_TEXT_CHARS) {
+                self.send_message_payload(
+                    to,
+                    serde_json::json!({
+                        "type": "text",
+                        "text": {
+                            "preview_url": false,
+                            "body": markdown::render(&chunk, Dialect::WhatsApp)
+                        }
+                    }),
+                )
+                .await?;
+            }
+        }
+
+        for attachment in &attachments {
+            let media_type = Self::media_type(&attachment.kind);
+            let path = std::path::Path::new(&attachment.target);
+            let mut media = if is_local_path(&attachment.target) {
+                let media_id = self.upload_media(path).await?;
+                serde_json::json!({ "id": media_id })
+            } else {
+                serde_json::json!({ "link": attachment.target })
+            };
+            // Audio and sticker messages take neither a caption nor a file name,
+            // so their caption goes out as a text message first.
+            if matches!(media_type, "audio" | "sticker") {
+                if let Some(caption) = attachment.caption.as_deref() {
+                    self.send_message_payload(
+                        to,
+                        serde_json::json!({
+                            "type": "text",
+                            "text": {
+                                "preview_url": false,
+                                "body": markdown::render(caption, Dialect::WhatsApp)
+                            }
+                        }),
+                    )
+                    .await?;
+                }
+            } else if let Some(caption) =
+                attachment.caption.as_deref().or(attachment.alt.as_deref())
+            {
+                media["caption"] = serde_json::json!(markdown::render(caption, Dialect::WhatsApp));
+            }
+            if media_type == "document" {
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    media["filename"] = serde_json::json!(name);
+                }
+            }
+            self.send_message_payload(
+                to,
+                serde_json::json!({
+                    "type": media_type,
+                    media_type: media,
+                }),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
     async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         // WhatsApp uses webhooks (push-based), not polling.
         // Messages are received via the gateway's /whatsapp endpoint.
@@ -323,6 +737,7 @@ mod tests {
         assert_eq!(msgs[0].content, "Hello ZeroClaw!");
         assert_eq!(msgs[0].channel, "whatsapp");
         assert_eq!(msgs[0].timestamp, 1_699_999_999);
+        assert_eq!(msgs[0].id, "wamid.xxx");
     }
 
     #[test]
@@ -756,6 +1171,117 @@ mod tests {
         assert!(msgs.is_empty());
     }
 
+    #[test]
+    fn whatsapp_voice_attachments_are_audio_messages() {
+        assert_eq!(WhatsAppChannel::media_type(&AttachmentKind::Voice), "audio");
+        assert_eq!(WhatsAppChannel::media_type(&AttachmentKind::Audio), "audio");
+        assert_eq!(WhatsAppChannel::media_type(&AttachmentKind::Image), "image");
+    }
+
+    #[test]
+    fn whatsapp_inbound_media_maps_to_attachment_kinds() {
+        let kind = |msg: serde_json::Value| WhatsAppChannel::inbound_media(&msg).map(|m| m.kind);
+        assert_eq!(
+            kind(serde_json::json!({ "sticker": { "id": "s1" } })),
+            Some(AttachmentKind::Image)
+        );
+        assert_eq!(
+            kind(serde_json::json!({ "video": { "id": "v1" } })),
+            Some(AttachmentKind::Video)
+        );
+        assert_eq!(
+            kind(serde_json::json!({ "audio": { "id": "a1", "voice": true } })),
+            Some(AttachmentKind::Voice)
+        );
+        assert_eq!(
+            kind(serde_json::json!({ "audio": { "id": "a1" } })),
+            Some(AttachmentKind::Audio)
+        );
+        assert_eq!(kind(serde_json::json!({ "text": { "body": "hi" } })), None);
+        assert_eq!(kind(serde_json::json!({ "image": {} })), None);
+
+        let msg = serde_json::json!({
+            "document": {
+                "id": "d1",
+                "mime_type": "application/pdf",
+                "filename": "Q3 report.pdf",
+                "caption": "numbers"
+            }
+        });
+        let media = WhatsAppChannel::inbound_media(&msg).unwrap();
+        assert_eq!(media.kind, AttachmentKind::Document);
+        assert_eq!(media.caption, Some("numbers"));
+        assert_eq!(media_file_name(&media), "d1_Q3_report.pdf");
+    }
+
+    #[test]
+    fn whatsapp_media_file_name_uses_mime_extension() {
+        let media = InboundMedia {
+            kind: AttachmentKind::Image,
+            id: "../img",
+            mime: "image/png",
+            file_name: None,
+            caption: None,
+        };
+        assert_eq!(media_file_name(&media), "_img.png");
+        let voice = InboundMedia {
+            mime: "audio/ogg; codecs=opus",
+            ..media
+        };
+        assert!(media_file_name(&voice).starts_with("_img.og"));
+    }
+
+    #[tokio::test]
+    async fn whatsapp_media_messages_need_workspace_dir() {
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "messages": [{
+                            "from": "1234567890",
+                            "timestamp": "1",
+                            "type": "image",
+                            "image": { "id": "img1", "mime_type": "image/jpeg" }
+                        }]
+                    }
+                }]
+            }]
+        });
+        assert!(make_channel()
+            .parse_media_messages(&payload)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn whatsapp_voice_messages_need_transcription_enabled() {
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "messages": [{
+                            "from": "111",
+                            "timestamp": "1",
+                            "type": "audio",
+                            "audio": { "id": "audio123", "mime_type": "audio/ogg; codecs=opus" }
+                        }]
+                    }
+                }]
+            }]
+        });
+
+        let disabled =
+            make_channel().with_transcription(crate::config::TranscriptionConfig::default());
+        assert!(disabled.transcription.is_none());
+        assert!(disabled.parse_voice_messages(&payload).await.is_empty());
+
+        let enabled = make_channel().with_transcription(crate::config::TranscriptionConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        assert!(enabled.transcription.is_some());
+    }
+
     #[test]
     fn whatsapp_parse_video_message_skipped() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
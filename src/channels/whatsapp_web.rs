@@ -472,8 +472,8 @@ impl Channel for WhatsAppWebChannel {
             _ = shutdown_rx.recv() => {
                 tracing::info!("WhatsApp Web channel shutting down");
             }
-            _ = tokio::signal::ctrl_c() => {
-                tracing::info!("WhatsApp Web channel received Ctrl+C");
+            () = crate::shutdown::requested() => {
+                tracing::info!("WhatsApp Web channel disconnecting for shutdown");
             }
         }
 
@@ -0,0 +1,1181 @@
+use crate::channels::attachment::{is_local_path, parse_attachment_markers};
+use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use async_trait::async_trait;
+use base64::Engine;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+// Use tokio_rustls's re-export of rustls types
+use tokio_rustls::rustls;
+
+/// Read timeout — the listener pings the server every `PING_INTERVAL`, so
+/// silence beyond this means the connection is dead.
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+const PING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+/// How long to wait for an IQ reply (upload slots, service discovery).
+const IQ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+/// Cap on disco#items entries probed for an HTTP upload service.
+const MAX_DISCO_ITEMS: usize = 10;
+const DEFAULT_RESOURCE: &str = "zeroclaw";
+
+const NS_TLS: &str = "urn:ietf:params:xml:ns:xmpp-tls";
+const NS_SASL: &str = "urn:ietf:params:xml:ns:xmpp-sasl";
+const NS_BIND: &str = "urn:ietf:params:xml:ns:xmpp-bind";
+const NS_MUC: &str = "http://jabber.org/protocol/muc";
+const NS_MUC_USER: &str = "http://jabber.org/protocol/muc#user";
+const NS_PING: &str = "urn:xmpp:ping";
+const NS_DISCO_ITEMS: &str = "http://jabber.org/protocol/disco#items";
+const NS_DISCO_INFO: &str = "http://jabber.org/protocol/disco#info";
+const NS_HTTP_UPLOAD: &str = "urn:xmpp:http:upload:0";
+const NS_OOB: &str = "jabber:x:oob";
+
+/// Headers a slot may ask us to send with the upload PUT (XEP-0363 §5).
+const ALLOWED_UPLOAD_HEADERS: [&str; 3] = ["authorization", "cookie", "expires"];
+
+type TlsStream = tokio_rustls::client::TlsStream<tokio::net::TcpStream>;
+type WriteHalf = tokio::io::WriteHalf<TlsStream>;
+type ReadHalf = tokio::io::ReadHalf<TlsStream>;
+type PendingIqs = Arc<parking_lot::Mutex<HashMap<String, oneshot::Sender<XmlElement>>>>;
+
+// ── Minimal XML stream parsing ──────────────────────────────────
+
+/// A parsed XML element. Namespaces are kept as plain `xmlns` attributes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct XmlElement {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum StreamEvent {
+    Open(XmlElement),
+    Close,
+    Stanza(XmlElement),
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn xml_unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp..];
+        let Some(semi) = after.find(';') else {
+            out.push_str(after);
+            return out;
+        };
+        let entity = &after[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => out.push(c),
+            None => out.push_str(&after[..=semi]),
+        }
+        rest = &after[semi + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn skip_whitespace(s: &str, pos: usize) -> usize {
+    s[pos..]
+        .find(|c: char| !c.is_whitespace())
+        .map_or(s.len(), |i| pos + i)
+}
+
+/// Parse an opening tag at the start of `s`.
+/// Returns `(element, consumed_bytes, self_closing)` or `None` if incomplete.
+fn parse_open_tag(s: &str) -> anyhow::Result<Option<(XmlElement, usize, bool)>> {
+    if !s.starts_with('<') {
+        anyhow::bail!("XMPP stream: expected '<'");
+    }
+    let Some(name_len) = s[1..].find(|c: char| c.is_whitespace() || c == '/' || c == '>') else {
+        return Ok(None);
+    };
+    let mut element = XmlElement {
+        name: s[1..=name_len].to_string(),
+        ..XmlElement::default()
+    };
+    let mut pos = 1 + name_len;
+
+    loop {
+        pos = skip_whitespace(s, pos);
+        let rest = &s[pos..];
+        if rest.is_empty() || rest == "/" {
+            return Ok(None);
+        }
+        if rest.starts_with("/>") {
+            return Ok(Some((element, pos + 2, true)));
+        }
+        if rest.starts_with('>') {
+            return Ok(Some((element, pos + 1, false)));
+        }
+        let Some(eq) = rest.find('=') else {
+            return Ok(None);
+        };
+        let key = rest[..eq].trim().to_string();
+        let value_start = skip_whitespace(rest, eq + 1);
+        let Some(quote) = rest[value_start..].chars().next() else {
+            return Ok(None);
+        };
+        if quote != '"' && quote != '\'' {
+            anyhow::bail!("XMPP stream: malformed attribute `{key}`");
+        }
+        let Some(value_len) = rest[value_start + 1..].find(quote) else {
+            return Ok(None);
+        };
+        let value = &rest[value_start + 1..value_start + 1 + value_len];
+        element.attrs.push((key, xml_unescape(value)));
+        pos += value_start + value_len + 2;
+    }
+}
+
+/// Parse a complete element at the start of `s`, or `None` if incomplete.
+fn parse_element(s: &str) -> anyhow::Result<Option<(XmlElement, usize)>> {
+    let Some((mut element, mut pos, self_closing)) = parse_open_tag(s)? else {
+        return Ok(None);
+    };
+    if self_closing {
+        return Ok(Some((element, pos)));
+    }
+
+    loop {
+        let rest = &s[pos..];
+        if rest.is_empty() {
+            return Ok(None);
+        }
+        if let Some(close) = rest.strip_prefix("</") {
+            let Some(end) = close.find('>') else {
+                return Ok(None);
+            };
+            let name = close[..end].trim();
+            if name != element.name {
+                anyhow::bail!(
+                    "XMPP stream: mismatched closing tag </{name}> for <{}>",
+                    element.name
+                );
+            }
+            return Ok(Some((element, pos + 2 + end + 1)));
+        }
+        if rest.starts_with("<!--") {
+            let Some(end) = rest.find("-->") else {
+                return Ok(None);
+            };
+            pos += end + 3;
+            continue;
+        }
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let Some(end) = cdata.find("]]>") else {
+                return Ok(None);
+            };
+            element.text.push_str(&cdata[..end]);
+            pos += 9 + end + 3;
+            continue;
+        }
+        if rest.starts_with('<') {
+            let Some((child, used)) = parse_element(rest)? else {
+                return Ok(None);
+            };
+            element.children.push(child);
+            pos += used;
+            continue;
+        }
+        let Some(end) = rest.find('<') else {
+            return Ok(None);
+        };
+        element.text.push_str(&xml_unescape(&rest[..end]));
+        pos += end;
+    }
+}
+
+/// Incremental parser splitting an XMPP stream into top-level stanzas.
+#[derive(Default)]
+struct XmlStreamParser {
+    buf: Vec<u8>,
+}
+
+impl XmlStreamParser {
+    fn feed(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Discard buffered data; used when the stream restarts after STARTTLS/SASL.
+    fn reset(&mut self) {
+        self.buf.clear();
+    }
+
+    fn next_event(&mut self) -> anyhow::Result<Option<StreamEvent>> {
+        let text = match std::str::from_utf8(&self.buf) {
+            Ok(text) => text,
+            // A multi-byte character split across reads: parse the valid prefix.
+            Err(e) if e.error_len().is_none() => {
+                std::str::from_utf8(&self.buf[..e.valid_up_to()]).unwrap_or_default()
+            }
+            Err(_) => anyhow::bail!("XMPP stream: invalid UTF-8"),
+        };
+
+        let mut pos = skip_whitespace(text, 0);
+        while text[pos..].starts_with("<?") {
+            let Some(end) = text[pos..].find("?>") else {
+                return Ok(None);
+            };
+            pos = skip_whitespace(text, pos + end + 2);
+        }
+
+        let rest = &text[pos..];
+        let (event, consumed) = if rest.is_empty() {
+            (None, pos)
+        } else if rest.starts_with("</stream:stream") {
+            match rest.find('>') {
+                Some(end) => (Some(StreamEvent::Close), pos + end + 1),
+                None => return Ok(None),
+            }
+        } else if rest.starts_with("<stream:stream") {
+            match parse_open_tag(rest)? {
+                Some((element, used, _)) => (Some(StreamEvent::Open(element)), pos + used),
+                None => return Ok(None),
+            }
+        } else {
+            match parse_element(rest)? {
+                Some((element, used)) => (Some(StreamEvent::Stanza(element)), pos + used),
+                None => return Ok(None),
+            }
+        };
+
+        self.buf.drain(..consumed);
+        Ok(event)
+    }
+}
+
+async fn read_event<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    parser: &mut XmlStreamParser,
+) -> anyhow::Result<StreamEvent> {
+    let mut chunk = [0_u8; 4096];
+    loop {
+        if let Some(event) = parser.next_event()? {
+            return Ok(event);
+        }
+        let n = tokio::time::timeout(READ_TIMEOUT, reader.read(&mut chunk))
+            .await
+            .map_err(|_| anyhow::anyhow!("XMPP read timed out (no data for {READ_TIMEOUT:?})"))??;
+        if n == 0 {
+            anyhow::bail!("XMPP connection closed by server");
+        }
+        parser.feed(&chunk[..n]);
+    }
+}
+
+/// Read until the next top-level stanza, skipping stream headers.
+async fn read_stanza<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    parser: &mut XmlStreamParser,
+) -> anyhow::Result<XmlElement> {
+    loop {
+        match read_event(reader, parser).await? {
+            StreamEvent::Open(_) => {}
+            StreamEvent::Close => anyhow::bail!("XMPP server closed the stream"),
+            StreamEvent::Stanza(stanza) => return Ok(stanza),
+        }
+    }
+}
+
+/// Read stanzas until the IQ reply with `id` arrives. Used during connection
+/// setup, before presence is sent, so nothing else of interest is pending.
+async fn await_iq<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    parser: &mut XmlStreamParser,
+    id: &str,
+) -> anyhow::Result<XmlElement> {
+    loop {
+        let stanza = read_stanza(reader, parser).await?;
+        if stanza.name == "iq" && stanza.attr("id") == Some(id) {
+            return Ok(stanza);
+        }
+    }
+}
+
+async fn write_all<W: AsyncWrite + Unpin>(writer: &mut W, data: &str) -> anyhow::Result<()> {
+    writer.write_all(data.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+fn stream_header(domain: &str) -> String {
+    format!(
+        "<?xml version='1.0'?><stream:stream to='{}' version='1.0' xmlns='jabber:client' xmlns:stream='http://etherx.jabber.org/streams'>",
+        xml_escape(domain)
+    )
+}
+
+fn encode_sasl_plain(username: &str, password: &str) -> String {
+    base64::engine::general_purpose::STANDARD.encode(format!("\0{username}\0{password}"))
+}
+
+fn bare_jid(jid: &str) -> &str {
+    jid.split_once('/').map_or(jid, |(bare, _)| bare)
+}
+
+fn build_message_stanza(to: &str, kind: &str, body: &str, oob_url: Option<&str>) -> String {
+    let oob = oob_url.map_or_else(String::new, |url| {
+        format!("<x xmlns='{NS_OOB}'><url>{}</url></x>", xml_escape(url))
+    });
+    format!(
+        "<message to='{}' type='{kind}'><body>{}</body>{oob}</message>",
+        xml_escape(to),
+        xml_escape(body)
+    )
+}
+
+// ── Channel ─────────────────────────────────────────────────────
+
+/// XMPP channel with multi-user chat (XEP-0045) and HTTP upload (XEP-0363).
+///
+/// Connects with STARTTLS and SASL PLAIN, joins configured rooms, and routes
+/// direct chats and room messages to the agent. Local attachment markers are
+/// uploaded through the server's HTTP upload service and shared as links.
+pub struct XmppChannel {
+    jid: String,
+    password: String,
+    server: Option<String>,
+    port: u16,
+    rooms: Vec<String>,
+    nickname: String,
+    allowed_users: Vec<String>,
+    upload_service: Option<String>,
+    verify_tls: bool,
+    writer: Arc<Mutex<Option<WriteHalf>>>,
+    pending_iqs: PendingIqs,
+    discovered_upload_service: Arc<parking_lot::Mutex<Option<String>>>,
+    /// Real bare JID of each room occupant (`room@service/nick`), as announced
+    /// in MUC presence. Anonymous rooms announce none.
+    occupants: parking_lot::Mutex<HashMap<String, String>>,
+    next_id: AtomicU64,
+}
+
+/// Configuration for constructing an `XmppChannel`.
+pub struct XmppChannelConfig {
+    pub jid: String,
+    pub password: String,
+    pub server: Option<String>,
+    pub port: u16,
+    pub rooms: Vec<String>,
+    pub nickname: Option<String>,
+    pub allowed_users: Vec<String>,
+    pub upload_service: Option<String>,
+    pub verify_tls: bool,
+}
+
+impl XmppChannel {
+    pub fn new(cfg: XmppChannelConfig) -> Self {
+        let jid = bare_jid(cfg.jid.trim()).to_string();
+        let nickname = cfg
+            .nickname
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| jid.split('@').next().unwrap_or("zeroclaw").to_string());
+        Self {
+            jid,
+            password: cfg.password,
+            server: cfg.server.filter(|s| !s.trim().is_empty()),
+            port: cfg.port,
+            rooms: cfg.rooms,
+            nickname,
+            allowed_users: cfg.allowed_users,
+            upload_service: cfg.upload_service.filter(|s| !s.trim().is_empty()),
+            verify_tls: cfg.verify_tls,
+            writer: Arc::new(Mutex::new(None)),
+            pending_iqs: Arc::new(parking_lot::Mutex::new(HashMap::new())),
+            discovered_upload_service: Arc::new(parking_lot::Mutex::new(None)),
+            occupants: parking_lot::Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn domain(&self) -> &str {
+        self.jid.split_once('@').map_or(&self.jid, |(_, d)| d)
+    }
+
+    fn username(&self) -> &str {
+        self.jid.split_once('@').map_or(&self.jid, |(u, _)| u)
+    }
+
+    fn next_stanza_id(&self) -> String {
+        format!("zc{}", self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn is_room(&self, jid: &str) -> bool {
+        self.rooms.iter().any(|r| r.eq_ignore_ascii_case(jid))
+    }
+
+    fn is_user_allowed(&self, identity: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(identity))
+    }
+
+    /// Remember or forget the real JID behind a room occupant.
+    fn track_presence(&self, stanza: &XmlElement) {
+        let Some(from) = stanza.attr("from") else {
+            return;
+        };
+        let Some((room, nick)) = from.split_once('/') else {
+            return;
+        };
+        if !self.is_room(room) || nick.is_empty() {
+            return;
+        }
+        let real_jid = stanza
+            .children_named("x")
+            .filter(|x| x.attr("xmlns") == Some(NS_MUC_USER))
+            .find_map(|x| x.child("item")?.attr("jid"))
+            .map(|jid| bare_jid(jid).to_string());
+        let mut occupants = self.occupants.lock();
+        match real_jid {
+            Some(jid) if stanza.attr("type") != Some("unavailable") => {
+                occupants.insert(from.to_string(), jid);
+            }
+            _ => {
+                occupants.remove(from);
+            }
+        }
+    }
+
+    /// Real bare JID of a room occupant, when the room discloses it.
+    fn occupant_jid(&self, occupant: &str) -> Option<String> {
+        self.occupants.lock().get(occupant).cloned()
+    }
+
+    /// Room JIDs without a resource get `groupchat`; everything else,
+    /// including private messages to a room occupant, is a `chat`.
+    fn message_type_for(&self, recipient: &str) -> &'static str {
+        if !recipient.contains('/') && self.is_room(recipient) {
+            "groupchat"
+        } else {
+            "chat"
+        }
+    }
+
+    /// Sender name and authorization for a room occupant. Nicknames can be
+    /// picked by anyone, so only the real JID from MUC presence is checked;
+    /// occupants of anonymous rooms pass only a `*` allowlist.
+    fn room_sender(&self, occupant: &str, nick: &str) -> (String, bool) {
+        match self.occupant_jid(occupant) {
+            Some(jid) => {
+                let allowed = self.is_user_allowed(&jid);
+                (jid, allowed)
+            }
+            None => (
+                nick.to_string(),
+                self.allowed_users.iter().any(|u| u == "*"),
+            ),
+        }
+    }
+
+    /// Convert an inbound `<message>` stanza into a channel message.
+    fn parse_inbound_message(&self, stanza: &XmlElement) -> Option<ChannelMessage> {
+        let body = stanza.child("body")?.text.trim();
+        if body.is_empty() {
+            return None;
+        }
+        let from = stanza.attr("from")?;
+        let (bare, resource) = from.split_once('/').unwrap_or((from, ""));
+        let in_room = self.is_room(bare);
+
+        let (sender, reply_target, allowed) = match stanza.attr("type") {
+            Some("groupchat") => {
+                // Skip our own echoes and room history replayed on join.
+                if !in_room
+                    || resource.is_empty()
+                    || resource == self.nickname
+                    || stanza.child("delay").is_some()
+                {
+                    return None;
+                }
+                let (sender, allowed) = self.room_sender(from, resource);
+                (sender, bare.to_string(), allowed)
+            }
+            Some("error") => return None,
+            // A private message from a room occupant: reply to the occupant.
+            _ if in_room => {
+                if resource.is_empty() {
+                    return None;
+                }
+                let (sender, allowed) = self.room_sender(from, resource);
+                (sender, from.to_string(), allowed)
+            }
+            _ => (
+                bare.to_string(),
+                bare.to_string(),
+                self.is_user_allowed(bare),
+            ),
+        };
+
+        if !allowed {
+            tracing::warn!("XMPP: ignoring message from unauthorized sender: {from}");
+            return None;
+        }
+
+        let id = stanza.attr("id").map_or_else(
+            || format!("xmpp_{}", self.next_stanza_id()),
+            |id| format!("xmpp_{id}"),
+        );
+        Some(ChannelMessage {
+            id,
+            sender,
+            reply_target,
+            content: body.to_string(),
+            channel: "xmpp".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+        })
+    }
+
+    async fn write_raw(&self, data: &str) -> anyhow::Result<()> {
+        let mut guard = self.writer.lock().await;
+        let writer = guard
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("XMPP not connected"))?;
+        write_all(writer, data).await
+    }
+
+    /// Send an IQ and wait for the reply routed back by the listener.
+    async fn request_iq(&self, id: &str, iq: &str) -> anyhow::Result<XmlElement> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.pending_iqs.lock().insert(id.to_string(), reply_tx);
+        if let Err(e) = self.write_raw(iq).await {
+            self.pending_iqs.lock().remove(id);
+            return Err(e);
+        }
+        let reply = tokio::time::timeout(IQ_TIMEOUT, reply_rx).await;
+        self.pending_iqs.lock().remove(id);
+        match reply {
+            Ok(Ok(stanza)) => Ok(stanza),
+            Ok(Err(_)) => anyhow::bail!("XMPP connection dropped while waiting for IQ {id}"),
+            Err(_) => anyhow::bail!("XMPP IQ {id} timed out"),
+        }
+    }
+
+    fn tls_connector(&self) -> tokio_rustls::TlsConnector {
+        let tls_config = if self.verify_tls {
+            let root_store: rustls::RootCertStore =
+                webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect();
+            rustls::ClientConfig::builder()
+                .with_root_certificates(root_store)
+                .with_no_client_auth()
+        } else {
+            rustls::ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(super::irc::NoVerify))
+                .with_no_client_auth()
+        };
+        tokio_rustls::TlsConnector::from(Arc::new(tls_config))
+    }
+
+    /// Connect, negotiate STARTTLS, authenticate, and bind a resource.
+    async fn connect(&self) -> anyhow::Result<(TlsStream, XmlStreamParser)> {
+        let domain = self.domain().to_string();
+        let host = self.server.clone().unwrap_or_else(|| domain.clone());
+        let mut tcp = tokio::net::TcpStream::connect((host.as_str(), self.port)).await?;
+        let mut parser = XmlStreamParser::default();
+
+        write_all(&mut tcp, &stream_header(&domain)).await?;
+        let features = read_stanza(&mut tcp, &mut parser).await?;
+        if features.child("starttls").is_none() {
+            anyhow::bail!("XMPP server {host} does not offer STARTTLS");
+        }
+        write_all(&mut tcp, &format!("<starttls xmlns='{NS_TLS}'/>")).await?;
+        let proceed = read_stanza(&mut tcp, &mut parser).await?;
+        if proceed.name != "proceed" {
+            anyhow::bail!("XMPP STARTTLS rejected by server");
+        }
+
+        let server_name = rustls::pki_types::ServerName::try_from(domain.clone())?;
+        let mut tls = self.tls_connector().connect(server_name, tcp).await?;
+
+        // SASL PLAIN over the encrypted stream.
+        parser.reset();
+        write_all(&mut tls, &stream_header(&domain)).await?;
+        let features = read_stanza(&mut tls, &mut parser).await?;
+        let supports_plain = features.child("mechanisms").is_some_and(|m| {
+            m.children_named("mechanism")
+                .any(|mech| mech.text.trim().eq_ignore_ascii_case("PLAIN"))
+        });
+        if !supports_plain {
+            anyhow::bail!("XMPP server does not offer SASL PLAIN");
+        }
+        write_all(
+            &mut tls,
+            &format!(
+                "<auth xmlns='{NS_SASL}' mechanism='PLAIN'>{}</auth>",
+                encode_sasl_plain(self.username(), &self.password)
+            ),
+        )
+        .await?;
+        let outcome = read_stanza(&mut tls, &mut parser).await?;
+        if outcome.name != "success" {
+            anyhow::bail!("XMPP authentication failed for {}", self.jid);
+        }
+
+        // Resource binding on the restarted stream.
+        parser.reset();
+        write_all(&mut tls, &stream_header(&domain)).await?;
+        read_stanza(&mut tls, &mut parser).await?;
+        let bind_id = self.next_stanza_id();
+        write_all(
+            &mut tls,
+            &format!(
+                "<iq type='set' id='{bind_id}'><bind xmlns='{NS_BIND}'><resource>{DEFAULT_RESOURCE}</resource></bind></iq>"
+            ),
+        )
+        .await?;
+        let bound = await_iq(&mut tls, &mut parser, &bind_id).await?;
+        if bound.attr("type") != Some("result") {
+            anyhow::bail!("XMPP resource binding failed");
+        }
+
+        if self.upload_service.is_none() {
+            match self.discover_upload_service(&mut tls, &mut parser).await {
+                Ok(service) => *self.discovered_upload_service.lock() = service,
+                Err(e) => tracing::debug!("XMPP: HTTP upload discovery failed: {e}"),
+            }
+        }
+
+        Ok((tls, parser))
+    }
+
+    /// Find an HTTP upload component via service discovery on the server.
+    async fn discover_upload_service(
+        &self,
+        tls: &mut TlsStream,
+        parser: &mut XmlStreamParser,
+    ) -> anyhow::Result<Option<String>> {
+        let domain = self.domain().to_string();
+        let items_id = self.next_stanza_id();
+        write_all(
+            tls,
+            &format!(
+                "<iq type='get' to='{}' id='{items_id}'><query xmlns='{NS_DISCO_ITEMS}'/></iq>",
+                xml_escape(&domain)
+            ),
+        )
+        .await?;
+        let items = await_iq(tls, parser, &items_id).await?;
+        let mut candidates: Vec<String> = items
+            .child("query")
+            .map(|q| {
+                q.children_named("item")
+                    .filter_map(|item| item.attr("jid").map(str::to_string))
+                    .take(MAX_DISCO_ITEMS)
+                    .collect()
+            })
+            .unwrap_or_default();
+        candidates.insert(0, domain);
+
+        for jid in candidates {
+            let info_id = self.next_stanza_id();
+            write_all(
+                tls,
+                &format!(
+                    "<iq type='get' to='{}' id='{info_id}'><query xmlns='{NS_DISCO_INFO}'/></iq>",
+                    xml_escape(&jid)
+                ),
+            )
+            .await?;
+            let info = await_iq(tls, parser, &info_id).await?;
+            let supports_upload = info.child("query").is_some_and(|q| {
+                q.children_named("feature")
+                    .any(|f| f.attr("var") == Some(NS_HTTP_UPLOAD))
+            });
+            if supports_upload {
+                tracing::info!("XMPP: using HTTP upload service {jid}");
+                return Ok(Some(jid));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Upload a local file via XEP-0363 and return its download URL.
+    async fn upload_file(&self, target: &str) -> anyhow::Result<String> {
+        let service = self
+            .upload_service
+            .clone()
+            .or_else(|| self.discovered_upload_service.lock().clone())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "XMPP server has no HTTP upload service; set `upload_service` in [channels_config.xmpp]"
+                )
+            })?;
+
+        let path = std::path::PathBuf::from(shellexpand::tilde(target).into_owned());
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|e| anyhow::anyhow!("XMPP attachment {}: {e}", path.display()))?;
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("attachment");
+        let content_type = mime_guess::from_path(&path)
+            .first_or_octet_stream()
+            .to_string();
+
+        let id = self.next_stanza_id();
+        let request = format!(
+            "<iq type='get' to='{}' id='{id}'><request xmlns='{NS_HTTP_UPLOAD}' filename='{}' size='{}' content-type='{}'/></iq>",
+            xml_escape(&service),
+            xml_escape(filename),
+            bytes.len(),
+            xml_escape(&content_type)
+        );
+        let reply = self.request_iq(&id, &request).await?;
+        let slot = reply
+            .child("slot")
+            .filter(|_| reply.attr("type") == Some("result"))
+            .ok_or_else(|| anyhow::anyhow!("XMPP upload slot request rejected"))?;
+        let put = slot
+            .child("put")
+            .and_then(|p| p.attr("url").map(|url| (url, p)))
+            .ok_or_else(|| anyhow::anyhow!("XMPP upload slot missing PUT URL"))?;
+        let get_url = slot
+            .child("get")
+            .and_then(|g| g.attr("url"))
+            .ok_or_else(|| anyhow::anyhow!("XMPP upload slot missing GET URL"))?
+            .to_string();
+
+        let mut req = crate::config::build_runtime_proxy_client("channel.xmpp")
+            .put(put.0)
+            .header("Content-Type", content_type)
+            .body(bytes);
+        for header in put.1.children_named("header") {
+            if let Some(name) = header.attr("name") {
+                if ALLOWED_UPLOAD_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+                    req = req.header(name, header.text.trim());
+                }
+            }
+        }
+        let resp = req.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("XMPP HTTP upload failed ({status}): {body}");
+        }
+
+        Ok(get_url)
+    }
+
+    /// Answer server IQs and route replies to waiting requests.
+    async fn handle_iq(&self, stanza: XmlElement) -> anyhow::Result<()> {
+        let id = stanza.attr("id").unwrap_or_default().to_string();
+        match stanza.attr("type") {
+            Some("result" | "error") => {
+                if let Some(waiter) = self.pending_iqs.lock().remove(&id) {
+                    let _ = waiter.send(stanza);
+                }
+            }
+            Some(kind @ ("get" | "set")) => {
+                let to = stanza.attr("from").unwrap_or_else(|| self.domain());
+                let reply = if kind == "get" && stanza.child("ping").is_some() {
+                    format!(
+                        "<iq type='result' to='{}' id='{}'/>",
+                        xml_escape(to),
+                        xml_escape(&id)
+                    )
+                } else {
+                    format!(
+                        "<iq type='error' to='{}' id='{}'><error type='cancel'><service-unavailable xmlns='urn:ietf:params:xml:ns:xmpp-stanzas'/></error></iq>",
+                        xml_escape(to),
+                        xml_escape(&id)
+                    )
+                };
+                self.write_raw(&reply).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn run_session(
+        &self,
+        mut reader: ReadHalf,
+        mut parser: XmlStreamParser,
+        tx: &mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<()> {
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        ping.tick().await;
+
+        loop {
+            tokio::select! {
+                event = read_event(&mut reader, &mut parser) => {
+                    match event? {
+                        StreamEvent::Close => anyhow::bail!("XMPP server closed the stream"),
+                        StreamEvent::Open(_) => {}
+                        StreamEvent::Stanza(stanza) => match stanza.name.as_str() {
+                            "message" => {
+                                if let Some(msg) = self.parse_inbound_message(&stanza) {
+                                    if tx.send(msg).await.is_err() {
+                                        return Ok(());
+                                    }
+                                }
+                            }
+                            "presence" => self.track_presence(&stanza),
+                            "iq" => self.handle_iq(stanza).await?,
+                            "stream:error" => anyhow::bail!("XMPP stream error: {stanza:?}"),
+                            _ => {}
+                        },
+                    }
+                }
+                _ = ping.tick() => {
+                    let id = self.next_stanza_id();
+                    self.write_raw(&format!(
+                        "<iq type='get' to='{}' id='{id}'><ping xmlns='{NS_PING}'/></iq>",
+                        xml_escape(self.domain())
+                    ))
+                    .await?;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Channel for XmppChannel {
+    fn name(&self) -> &str {
+        "xmpp"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let kind = self.message_type_for(&message.recipient);
+        let (text, attachments) = parse_attachment_markers(&message.content);
+
+        if !text.is_empty() {
+            self.write_raw(&build_message_stanza(&message.recipient, kind, &text, None))
+                .await?;
+        }
+
+        for attachment in &attachments {
+            let url = if is_local_path(&attachment.target) {
+                self.upload_file(&attachment.target).await?
+            } else {
+                attachment.target.clone()
+            };
+            self.write_raw(&build_message_stanza(
+                &message.recipient,
+                kind,
+                &url,
+                Some(&url),
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        tracing::info!("XMPP channel connecting as {}...", self.jid);
+        let (tls, parser) = self.connect().await?;
+        let (reader, mut writer) = tokio::io::split(tls);
+
+        write_all(&mut writer, "<presence/>").await?;
+        self.occupants.lock().clear();
+        for room in &self.rooms {
+            write_all(
+                &mut writer,
+                &format!(
+                    "<presence to='{}/{}'><x xmlns='{NS_MUC}'><history maxstanzas='0'/></x></presence>",
+                    xml_escape(room),
+                    xml_escape(&self.nickname)
+                ),
+            )
+            .await?;
+        }
+        *self.writer.lock().await = Some(writer);
+        tracing::info!(
+            "XMPP channel online as {} ({} room(s))",
+            self.jid,
+            self.rooms.len()
+        );
+
+        let result = self.run_session(reader, parser, &tx).await;
+
+        *self.writer.lock().await = None;
+        self.pending_iqs.lock().clear();
+        result
+    }
+
+    async fn health_check(&self) -> bool {
+        self.writer.lock().await.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_channel() -> XmppChannel {
+        XmppChannel::new(XmppChannelConfig {
+            jid: "bot@example.org/ignored".into(),
+            password: "secret".into(),
+            server: None,
+            port: 5222,
+            rooms: vec!["ops@conference.example.org".into()],
+            nickname: None,
+            allowed_users: vec!["alice@example.org".into(), "carol@example.org".into()],
+            upload_service: None,
+            verify_tls: true,
+        })
+    }
+
+    fn parse_one(xml: &str) -> XmlElement {
+        let mut parser = XmlStreamParser::default();
+        parser.feed(xml.as_bytes());
+        match parser.next_event().unwrap() {
+            Some(StreamEvent::Stanza(stanza)) => stanza,
+            other => panic!("expected stanza, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn new_strips_resource_and_defaults_nickname() {
+        let ch = make_channel();
+        assert_eq!(ch.jid, "bot@example.org");
+        assert_eq!(ch.nickname, "bot");
+        assert_eq!(ch.domain(), "example.org");
+        assert_eq!(ch.name(), "xmpp");
+    }
+
+    #[test]
+    fn parser_handles_stream_header_and_split_stanzas() {
+        let mut parser = XmlStreamParser::default();
+        parser.feed(b"<?xml version='1.0'?><stream:stream from='example.org' id='abc' xmlns:stream='http://etherx.jabber.org/streams'>");
+        match parser.next_event().unwrap() {
+            Some(StreamEvent::Open(open)) => assert_eq!(open.attr("id"), Some("abc")),
+            other => panic!("expected stream open, got {other:?}"),
+        }
+
+        parser.feed(b"<message from='a@b'><body>hel");
+        assert!(parser.next_event().unwrap().is_none());
+        parser.feed(b"lo &amp; bye</body></message></stream:stream>");
+        match parser.next_event().unwrap() {
+            Some(StreamEvent::Stanza(msg)) => {
+                assert_eq!(msg.child("body").unwrap().text, "hello & bye");
+            }
+            other => panic!("expected stanza, got {other:?}"),
+        }
+        assert_eq!(parser.next_event().unwrap(), Some(StreamEvent::Close));
+    }
+
+    #[test]
+    fn parser_waits_for_split_utf8() {
+        let mut parser = XmlStreamParser::default();
+        let xml = "<message><body>héllo</body></message>".as_bytes();
+        let split = xml.iter().position(|b| *b == 0xC3).unwrap() + 1;
+        parser.feed(&xml[..split]);
+        assert!(parser.next_event().unwrap().is_none());
+        parser.feed(&xml[split..]);
+        let Some(StreamEvent::Stanza(msg)) = parser.next_event().unwrap() else {
+            panic!("expected stanza");
+        };
+        assert_eq!(msg.child("body").unwrap().text, "héllo");
+    }
+
+    #[test]
+    fn parser_rejects_mismatched_tags() {
+        let mut parser = XmlStreamParser::default();
+        parser.feed(b"<message><body>x</message>");
+        assert!(parser.next_event().is_err());
+    }
+
+    #[test]
+    fn xml_escape_round_trips() {
+        let raw = r#"<a href="x">Tom & 'Jerry'</a>"#;
+        assert_eq!(xml_unescape(&xml_escape(raw)), raw);
+        assert_eq!(xml_unescape("&#65;&#x42;&unknown;"), "AB&unknown;");
+    }
+
+    #[test]
+    fn sasl_plain_encodes_username_and_password() {
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(encode_sasl_plain("bot", "pw"))
+            .unwrap();
+        assert_eq!(decoded, b"\0bot\0pw");
+    }
+
+    #[test]
+    fn direct_chat_from_allowed_user_is_routed() {
+        let ch = make_channel();
+        let msg = ch
+            .parse_inbound_message(&parse_one(
+                "<message from='alice@example.org/phone' type='chat' id='m1'><body>hi</body></message>",
+            ))
+            .unwrap();
+        assert_eq!(msg.sender, "alice@example.org");
+        assert_eq!(msg.reply_target, "alice@example.org");
+        assert_eq!(msg.id, "xmpp_m1");
+        assert_eq!(msg.channel, "xmpp");
+    }
+
+    #[test]
+    fn unauthorized_and_bodyless_messages_are_ignored() {
+        let ch = make_channel();
+        assert!(ch
+            .parse_inbound_message(&parse_one(
+                "<message from='mallory@example.org/x' type='chat'><body>hi</body></message>"
+            ))
+            .is_none());
+        assert!(ch
+            .parse_inbound_message(&parse_one(
+                "<message from='alice@example.org/x' type='chat'><composing xmlns='http://jabber.org/protocol/chatstates'/></message>"
+            ))
+            .is_none());
+    }
+
+    fn join(ch: &XmppChannel, nick: &str, real_jid: &str) {
+        ch.track_presence(&parse_one(&format!(
+            "<presence from='ops@conference.example.org/{nick}'><x xmlns='{NS_MUC_USER}'><item affiliation='member' role='participant' jid='{real_jid}'/></x></presence>"
+        )));
+    }
+
+    #[test]
+    fn room_message_replies_to_room_and_skips_echo_and_history() {
+        let ch = make_channel();
+        join(&ch, "carol", "carol@example.org/laptop");
+        let msg = ch
+            .parse_inbound_message(&parse_one(
+                "<message from='ops@conference.example.org/carol' type='groupchat'><body>deploy?</body></message>",
+            ))
+            .unwrap();
+        assert_eq!(msg.sender, "carol@example.org");
+        assert_eq!(msg.reply_target, "ops@conference.example.org");
+        assert_eq!(ch.message_type_for(&msg.reply_target), "groupchat");
+
+        assert!(ch
+            .parse_inbound_message(&parse_one(
+                "<message from='ops@conference.example.org/bot' type='groupchat'><body>echo</body></message>"
+            ))
+            .is_none());
+        assert!(ch
+            .parse_inbound_message(&parse_one(
+                "<message from='ops@conference.example.org/carol' type='groupchat'><body>old</body><delay xmlns='urn:xmpp:delay' stamp='2024-01-01T00:00:00Z'/></message>"
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn room_occupants_are_authorized_by_real_jid() {
+        let ch = make_channel();
+        let from_carol = "<message from='ops@conference.example.org/carol' type='groupchat'><body>hi</body></message>";
+
+        // Anonymous room: no real JID, so the nickname alone is not enough.
+        assert!(ch.parse_inbound_message(&parse_one(from_carol)).is_none());
+
+        // Mallory takes the nickname "carol".
+        join(&ch, "carol", "mallory@example.org/x");
+        assert!(ch.parse_inbound_message(&parse_one(from_carol)).is_none());
+
+        join(&ch, "carol", "carol@example.org/laptop");
+        assert!(ch.parse_inbound_message(&parse_one(from_carol)).is_some());
+
+        ch.track_presence(&parse_one(&format!(
+            "<presence from='ops@conference.example.org/carol' type='unavailable'><x xmlns='{NS_MUC_USER}'><item jid='carol@example.org/laptop'/></x></presence>"
+        )));
+        assert!(ch.parse_inbound_message(&parse_one(from_carol)).is_none());
+    }
+
+    #[test]
+    fn private_room_message_replies_to_occupant() {
+        let ch = make_channel();
+        join(&ch, "carol", "carol@example.org/laptop");
+        let msg = ch
+            .parse_inbound_message(&parse_one(
+                "<message from='ops@conference.example.org/carol' type='chat'><body>psst</body></message>",
+            ))
+            .unwrap();
+        assert_eq!(msg.reply_target, "ops@conference.example.org/carol");
+        assert_eq!(ch.message_type_for(&msg.reply_target), "chat");
+    }
+
+    #[test]
+    fn build_message_stanza_escapes_and_adds_oob() {
+        let stanza = build_message_stanza(
+            "alice@example.org",
+            "chat",
+            "a < b",
+            Some("https://up.example.org/f?a=1&b=2"),
+        );
+        assert!(stanza.contains("<body>a &lt; b</body>"));
+        assert!(stanza.contains("<url>https://up.example.org/f?a=1&amp;b=2</url>"));
+        assert!(stanza.contains("type='chat'"));
+    }
+
+    #[tokio::test]
+    async fn send_without_connection_fails() {
+        let ch = make_channel();
+        let err = ch
+            .send(&SendMessage::new("hi", "alice@example.org"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not connected"));
+        assert!(!ch.health_check().await);
+    }
+
+    #[tokio::test]
+    async fn handle_iq_routes_replies_to_waiters() {
+        let ch = make_channel();
+        let (reply_tx, reply_rx) = oneshot::channel();
+        ch.pending_iqs.lock().insert("zc9".into(), reply_tx);
+        ch.handle_iq(parse_one(
+            "<iq type='result' id='zc9'><slot xmlns='urn:xmpp:http:upload:0'/></iq>",
+        ))
+        .await
+        .unwrap();
+        assert!(reply_rx.await.unwrap().child("slot").is_some());
+    }
+}
@@ -0,0 +1,604 @@
+use super::attachment::{is_local_path, parse_attachment_markers};
+use super::split::{non_empty_chunks, split_message};
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Zulip rejects messages longer than this many characters.
+const ZULIP_MAX_MESSAGE_CHARS: usize = 10_000;
+/// Long-poll requests are held open by the server for up to ~90s.
+const EVENTS_TIMEOUT_SECS: u64 = 120;
+
+/// Zulip channel — receives messages through the real-time events API and
+/// replies in the same stream topic or direct-message conversation.
+///
+/// Reply targets:
+/// - `stream:<stream>/<topic>` — stream name or ID, split at the first `/`
+/// - `stream:<stream>` — posts to `default_topic`
+/// - `dm:<email>[,<email>...]` or a bare email — (group) direct message
+///
+/// Each stream topic carries `thread_ts = "<stream_id>/<topic>"`, so every
+/// topic gets its own conversation history.
+pub struct ZulipChannel {
+    site: String,
+    email: String,
+    api_key: String,
+    allowed_users: Vec<String>,
+    mention_only: bool,
+    default_topic: String,
+}
+
+/// Where a Zulip message should be posted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ZulipTarget {
+    Stream { stream: String, topic: String },
+    Direct { emails: Vec<String> },
+}
+
+impl ZulipChannel {
+    pub fn new(
+        site: String,
+        email: String,
+        api_key: String,
+        allowed_users: Vec<String>,
+        mention_only: bool,
+        default_topic: Option<String>,
+    ) -> Self {
+        Self {
+            site: site.trim_end_matches('/').to_string(),
+            email,
+            api_key,
+            allowed_users,
+            mention_only,
+            default_topic: default_topic
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or_else(|| "zeroclaw".to_string()),
+        }
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.zulip")
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("{}/api/v1/{path}", self.site)
+    }
+
+    /// Check if a sender email is in the allowlist.
+    /// Empty list means deny everyone. "*" means allow everyone.
+    fn is_user_allowed(&self, email: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(email))
+    }
+
+    fn parse_target(&self, recipient: &str, thread_ts: Option<&str>) -> Result<ZulipTarget> {
+        let recipient = recipient.trim();
+        if let Some(rest) = recipient.strip_prefix("stream:") {
+            let (stream, topic) = match rest.split_once('/') {
+                Some((stream, topic)) => (stream, topic.to_string()),
+                // Fall back to the thread carried on the message, e.g. "<id>/<topic>".
+                None => (
+                    rest,
+                    thread_ts
+                        .and_then(|t| t.split_once('/'))
+                        .map_or_else(|| self.default_topic.clone(), |(_, t)| t.to_string()),
+                ),
+            };
+            if stream.trim().is_empty() {
+                bail!("Zulip recipient '{recipient}' is missing a stream");
+            }
+            let topic = if topic.trim().is_empty() {
+                self.default_topic.clone()
+            } else {
+                topic
+            };
+            return Ok(ZulipTarget::Stream {
+                stream: stream.trim().to_string(),
+                topic,
+            });
+        }
+
+        let list = recipient.strip_prefix("dm:").unwrap_or(recipient);
+        let emails: Vec<String> = list
+            .split(',')
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        if emails.is_empty() || emails.iter().any(|e| !e.contains('@')) {
+            bail!("Unsupported Zulip recipient '{recipient}'");
+        }
+        Ok(ZulipTarget::Direct { emails })
+    }
+
+    /// Upload a local file and return the Markdown link Zulip renders inline.
+    async fn upload_file(&self, file_path: &str) -> Result<String> {
+        let path = std::path::Path::new(file_path);
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Zulip: cannot read attachment {file_path}: {e}"))?;
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("file")
+            .to_string();
+
+        let form = reqwest::multipart::Form::new().part(
+            "filename",
+            reqwest::multipart::Part::bytes(bytes).file_name(filename.clone()),
+        );
+        let resp = self
+            .http_client()
+            .post(self.api_url("user_uploads"))
+            .basic_auth(&self.email, Some(&self.api_key))
+            .multipart(form)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body: serde_json::Value = resp.json().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!(
+                "Zulip upload failed ({status}): {}",
+                body.get("msg").and_then(|m| m.as_str()).unwrap_or("")
+            );
+        }
+        // Zulip 9 renamed `uri` to `url`; accept either.
+        let uri = body
+            .get("url")
+            .or_else(|| body.get("uri"))
+            .and_then(|u| u.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Zulip upload response missing url"))?;
+        Ok(format!("[{filename}]({uri})"))
+    }
+
+    async fn post_message(&self, target: &ZulipTarget, content: &str) -> Result<()> {
+        let mut form: Vec<(&str, String)> = vec![("content", content.to_string())];
+        match target {
+            ZulipTarget::Stream { stream, topic } => {
+                form.push(("type", "stream".to_string()));
+                form.push(("to", stream.clone()));
+                form.push(("topic", topic.clone()));
+            }
+            ZulipTarget::Direct { emails } => {
+                form.push(("type", "private".to_string()));
+                form.push(("to", serde_json::to_string(emails)?));
+            }
+        }
+
+        let resp = self
+            .http_client()
+            .post(self.api_url("messages"))
+            .basic_auth(&self.email, Some(&self.api_key))
+            .form(&form)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response: {e}>"));
+            bail!("Zulip send failed ({status}): {body}");
+        }
+        Ok(())
+    }
+
+    /// Register an event queue for new messages. Returns `(queue_id, last_event_id)`.
+    async fn register_queue(&self) -> Result<(String, i64)> {
+        let resp = self
+            .http_client()
+            .post(self.api_url("register"))
+            .basic_auth(&self.email, Some(&self.api_key))
+            .form(&[
+                ("event_types", r#"["message"]"#),
+                ("apply_markdown", "false"),
+            ])
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body: serde_json::Value = resp.json().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!(
+                "Zulip register failed ({status}): {}",
+                body.get("msg").and_then(|m| m.as_str()).unwrap_or("")
+            );
+        }
+        let queue_id = body
+            .get("queue_id")
+            .and_then(|q| q.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Zulip register response missing queue_id"))?;
+        let last_event_id = body
+            .get("last_event_id")
+            .and_then(serde_json::Value::as_i64)
+            .unwrap_or(-1);
+        Ok((queue_id.to_string(), last_event_id))
+    }
+
+    /// Convert a `message` event into a channel message, applying the
+    /// self-filter, allowlist and mention policy.
+    fn parse_message_event(&self, event: &serde_json::Value) -> Option<ChannelMessage> {
+        let message = event.get("message")?;
+        let sender_email = message.get("sender_email")?.as_str()?;
+        if sender_email.eq_ignore_ascii_case(&self.email) {
+            return None;
+        }
+        if !self.is_user_allowed(sender_email) {
+            tracing::warn!("Zulip: ignoring message from unauthorized user: {sender_email}");
+            return None;
+        }
+
+        let id = message.get("id").and_then(serde_json::Value::as_i64)?;
+        let raw = message
+            .get("content")
+            .and_then(|c| c.as_str())
+            .unwrap_or("");
+        let flags: Vec<&str> = event
+            .get("flags")
+            .and_then(|f| f.as_array())
+            .map(|f| f.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        let mentioned = flags.contains(&"mentioned");
+
+        let (reply_target, thread_ts) = match message.get("type").and_then(|t| t.as_str()) {
+            Some("stream") => {
+                if self.mention_only && !mentioned {
+                    return None;
+                }
+                let stream_id = message
+                    .get("stream_id")
+                    .and_then(serde_json::Value::as_i64)?;
+                let topic = message
+                    .get("subject")
+                    .or_else(|| message.get("topic"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("");
+                let thread = format!("{stream_id}/{topic}");
+                (format!("stream:{thread}"), Some(thread))
+            }
+            _ => {
+                // Direct messages: reply to every participant except ourselves.
+                let others: Vec<&str> = message
+                    .get("display_recipient")
+                    .and_then(|r| r.as_array())
+                    .map(|r| {
+                        r.iter()
+                            .filter_map(|u| u.get("email").and_then(|e| e.as_str()))
+                            .filter(|e| !e.eq_ignore_ascii_case(&self.email))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let recipients = if others.is_empty() {
+                    sender_email.to_string()
+                } else {
+                    others.join(",")
+                };
+                (format!("dm:{recipients}"), None)
+            }
+        };
+
+        let content = self.normalize_content(raw);
+        if content.is_empty() {
+            return None;
+        }
+
+        Some(ChannelMessage {
+            id: format!("zulip_{id}"),
+            sender: sender_email.to_string(),
+            reply_target,
+            content,
+            channel: "zulip".to_string(),
+            timestamp: message
+                .get("timestamp")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or_else(|| {
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs()
+                }),
+            thread_ts,
+        })
+    }
+
+    /// Strip `@**Name**` mentions and make upload links absolute so the
+    /// agent sees fetchable URLs.
+    fn normalize_content(&self, raw: &str) -> String {
+        let mut text = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(start) = rest.find("@**") {
+            text.push_str(&rest[..start]);
+            match rest[start + 3..].find("**") {
+                Some(end) => rest = &rest[start + 3 + end + 2..],
+                None => {
+                    text.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        text.push_str(rest);
+        text.replace(
+            "](/user_uploads/",
+            &format!("]({}/user_uploads/", self.site),
+        )
+        .trim()
+        .to_string()
+    }
+}
+
+/// Split a reply into chunks Zulip accepts, preferring line boundaries.
+fn split_content(text: &str) -> Vec<String> {
+    non_empty_chunks(split_message(text.trim(), ZULIP_MAX_MESSAGE_CHARS))
+}
+
+#[async_trait]
+impl Channel for ZulipChannel {
+    fn name(&self) -> &str {
+        "zulip"
+    }
+
+    async fn send(&self, message: &SendMessage) -> Result<()> {
+        let target = self.parse_target(&message.recipient, message.thread_ts.as_deref())?;
+        let content = super::strip_tool_call_tags(&message.content);
+        let (text, attachments) = parse_attachment_markers(&content);
+
+        let mut links = Vec::new();
+        for attachment in &attachments {
+            if is_local_path(&attachment.target) {
+                match self.upload_file(&attachment.target).await {
+                    Ok(link) => links.push(link),
+                    Err(e) => {
+                        tracing::warn!("{e}");
+                        links.push(format!("[attachment not sent: {}]", attachment.target));
+                    }
+                }
+            } else if attachment.kind.is_image_like() {
+                // Zulip previews linked images inline.
+                links.push(format!("[image]({})", attachment.target));
+            } else {
+                links.push(attachment.target.clone());
+            }
+        }
+
+        let mut body = text;
+        if !links.is_empty() {
+            if !body.is_empty() {
+                body.push_str("\n\n");
+            }
+            body.push_str(&links.join("\n"));
+        }
+
+        for chunk in split_content(&body) {
+            self.post_message(&target, &chunk).await?;
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+        let client = crate::config::build_runtime_proxy_client_with_timeouts(
+            "channel.zulip",
+            EVENTS_TIMEOUT_SECS,
+            10,
+        );
+        let (mut queue_id, mut last_event_id) = self.register_queue().await?;
+        tracing::info!("Zulip channel listening as {} on {}", self.email, self.site);
+
+        loop {
+            let resp = match client
+                .get(self.api_url("events"))
+                .basic_auth(&self.email, Some(&self.api_key))
+                .query(&[
+                    ("queue_id", queue_id.clone()),
+                    ("last_event_id", last_event_id.to_string()),
+                ])
+                .send()
+                .await
+            {
+                Ok(r) => r,
+                Err(e) => {
+                    tracing::warn!("Zulip poll error: {e}");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            let data: serde_json::Value = match resp.json().await {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::warn!("Zulip parse error: {e}");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            if data.get("result").and_then(|r| r.as_str()) != Some("success") {
+                if data.get("code").and_then(|c| c.as_str()) == Some("BAD_EVENT_QUEUE_ID") {
+                    tracing::info!("Zulip event queue expired; re-registering");
+                    (queue_id, last_event_id) = self.register_queue().await?;
+                } else {
+                    tracing::warn!(
+                        "Zulip events error: {}",
+                        data.get("msg").and_then(|m| m.as_str()).unwrap_or("")
+                    );
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+                continue;
+            }
+
+            let events = data
+                .get("events")
+                .and_then(|e| e.as_array())
+                .cloned()
+                .unwrap_or_default();
+            for event in events {
+                if let Some(id) = event.get("id").and_then(serde_json::Value::as_i64) {
+                    last_event_id = last_event_id.max(id);
+                }
+                if event.get("type").and_then(|t| t.as_str()) != Some("message") {
+                    continue;
+                }
+                if let Some(msg) = self.parse_message_event(&event) {
+                    if tx.send(msg).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        self.http_client()
+            .get(self.api_url("users/me"))
+            .basic_auth(&self.email, Some(&self.api_key))
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_channel(mention_only: bool) -> ZulipChannel {
+        ZulipChannel::new(
+            "https://chat.example.com/".into(),
+            "bot@example.com".into(),
+            "key".into(),
+            vec!["alice@example.com".into()],
+            mention_only,
+            None,
+        )
+    }
+
+    fn stream_event(topic: &str, flags: &[&str]) -> serde_json::Value {
+        json!({
+            "type": "message",
+            "id": 7,
+            "flags": flags,
+            "message": {
+                "id": 42,
+                "type": "stream",
+                "stream_id": 5,
+                "display_recipient": "engineering",
+                "subject": topic,
+                "sender_email": "alice@example.com",
+                "content": "@**ZeroClaw** look at [shot.png](/user_uploads/2/ab/shot.png)",
+                "timestamp": 1_700_000_000
+            }
+        })
+    }
+
+    #[test]
+    fn stream_messages_map_topics_to_threads() {
+        let ch = make_channel(false);
+        let msg = ch
+            .parse_message_event(&stream_event("deploys", &[]))
+            .unwrap();
+        assert_eq!(msg.id, "zulip_42");
+        assert_eq!(msg.reply_target, "stream:5/deploys");
+        assert_eq!(msg.thread_ts.as_deref(), Some("5/deploys"));
+        assert_eq!(
+            msg.content,
+            "look at [shot.png](https://chat.example.com/user_uploads/2/ab/shot.png)"
+        );
+
+        let other = ch
+            .parse_message_event(&stream_event("incidents", &[]))
+            .unwrap();
+        assert_ne!(msg.thread_ts, other.thread_ts);
+    }
+
+    #[test]
+    fn mention_only_ignores_unmentioned_stream_messages() {
+        let ch = make_channel(true);
+        assert!(ch.parse_message_event(&stream_event("t", &[])).is_none());
+        assert!(ch
+            .parse_message_event(&stream_event("t", &["mentioned"]))
+            .is_some());
+    }
+
+    #[test]
+    fn direct_messages_reply_to_other_participants() {
+        let ch = make_channel(true);
+        let event = json!({
+            "type": "message",
+            "id": 8,
+            "message": {
+                "id": 43,
+                "type": "private",
+                "display_recipient": [
+                    {"email": "alice@example.com"},
+                    {"email": "bob@example.com"},
+                    {"email": "bot@example.com"}
+                ],
+                "sender_email": "alice@example.com",
+                "content": "hi"
+            }
+        });
+        let msg = ch.parse_message_event(&event).unwrap();
+        assert_eq!(msg.reply_target, "dm:alice@example.com,bob@example.com");
+        assert!(msg.thread_ts.is_none());
+    }
+
+    #[test]
+    fn ignores_own_and_unauthorized_senders() {
+        let ch = make_channel(false);
+        let mut event = stream_event("t", &[]);
+        event["message"]["sender_email"] = json!("bot@example.com");
+        assert!(ch.parse_message_event(&event).is_none());
+        event["message"]["sender_email"] = json!("mallory@example.com");
+        assert!(ch.parse_message_event(&event).is_none());
+    }
+
+    #[test]
+    fn parses_reply_targets() {
+        let ch = make_channel(false);
+        assert_eq!(
+            ch.parse_target("stream:5/deploys/prod", None).unwrap(),
+            ZulipTarget::Stream {
+                stream: "5".into(),
+                topic: "deploys/prod".into()
+            }
+        );
+        assert_eq!(
+            ch.parse_target("stream:general", None).unwrap(),
+            ZulipTarget::Stream {
+                stream: "general".into(),
+                topic: "zeroclaw".into()
+            }
+        );
+        assert_eq!(
+            ch.parse_target("stream:general", Some("9/standup"))
+                .unwrap(),
+            ZulipTarget::Stream {
+                stream: "general".into(),
+                topic: "standup".into()
+            }
+        );
+        assert_eq!(
+            ch.parse_target("bob@example.com", None).unwrap(),
+            ZulipTarget::Direct {
+                emails: vec!["bob@example.com".into()]
+            }
+        );
+        assert!(ch.parse_target("not-an-email", None).is_err());
+    }
+
+    #[test]
+    fn split_content_respects_limit() {
+        let text = format!("{}\n{}", "a".repeat(9_000), "b".repeat(3_000));
+        let chunks = split_content(&text);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks
+            .iter()
+            .all(|c| c.chars().count() <= ZULIP_MAX_MESSAGE_CHARS));
+        assert!(chunks[0].chars().all(|c| c == 'a'));
+    }
+}
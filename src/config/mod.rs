@@ -5,18 +5,29 @@ pub mod traits;
 pub use schema::{
     apply_runtime_proxy_to_builder, build_runtime_proxy_client,
     build_runtime_proxy_client_with_timeouts, runtime_proxy_config, set_runtime_proxy_config,
-    AgentConfig, AuditConfig, AutonomyConfig, BrowserComputerUseConfig, BrowserConfig,
-    BuiltinHooksConfig, ChannelsConfig, ClassificationRule, ComposioConfig, Config, CostConfig,
-    CronConfig, DelegateAgentConfig, DiscordConfig, DockerRuntimeConfig, EmbeddingRouteConfig,
-    EstopConfig, FeishuConfig, GatewayConfig, HardwareConfig, HardwareTransport, HeartbeatConfig,
-    HooksConfig, HttpRequestConfig, IMessageConfig, IdentityConfig, LarkConfig, MatrixConfig,
-    MemoryConfig, ModelRouteConfig, MultimodalConfig, NextcloudTalkConfig, ObservabilityConfig,
-    OtpConfig, OtpMethod, PeripheralBoardConfig, PeripheralsConfig, ProxyConfig, ProxyScope,
-    QdrantConfig, QueryClassificationConfig, ReliabilityConfig, ResourceLimitsConfig,
-    RuntimeConfig, SandboxBackend, SandboxConfig, SchedulerConfig, SecretsConfig, SecurityConfig,
-    SkillsConfig, SkillsPromptInjectionMode, SlackConfig, StorageConfig, StorageProviderConfig,
-    StorageProviderSection, StreamMode, TelegramConfig, TranscriptionConfig, TunnelConfig,
-    WebFetchConfig, WebSearchConfig, WebhookConfig,
+    AgentConfig, AltTextConfig, AuditConfig, AutonomyConfig, BridgeConfig,
+    BrowserComputerUseConfig, BrowserConfig, BuiltinHooksConfig, CalendarBackend, CalendarConfig,
+    ChannelAclConfig, ChannelCommandsConfig, ChannelDigestConfig, ChannelInstructionsConfig,
+    ChannelModerationConfig, ChannelQueueConfig, ChannelsConfig, CitationConfig,
+    ClassificationRule, ComposioConfig, Config, CostConfig, CronConfig, CronJobConfig,
+    DelegateAgentConfig, DesktopConfig, DigestConfig, DiscordConfig, DockerRuntimeConfig,
+    DryRunConfig, EmbeddingRouteConfig, EstopConfig, FeedSourceConfig, FeedsConfig, FeishuConfig,
+    GatewayConfig, GitConfig, HardwareConfig, HardwareTransport, HeartbeatConfig,
+    HomeAssistantConfig, HooksConfig, HttpChannelConfig, HttpRequestConfig, IMessageConfig,
+    IdentityConfig, ImageGenerationBackend, ImageGenerationConfig, InjectionAction,
+    KnowledgeConfig, LarkConfig, LocalSocketConfig, MatrixConfig, McpConfig, McpServerConfig,
+    McpTransport, MediaHostConfig, MemoryConfig, ModelRouteConfig, ModerationAction,
+    ModerationConfig, MultimodalConfig, NextcloudTalkConfig, ObservabilityConfig, OcrBackend,
+    OcrConfig, OtpConfig, OtpMethod, PeripheralBoardConfig, PeripheralsConfig, PersonaConfig,
+    PromptInjectionConfig, ProxyConfig, ProxyScope, QdrantConfig, QueryClassificationConfig,
+    RedactionConfig, ReliabilityConfig, ResourceLimitsConfig, RoomContextConfig, RunCodeBackend,
+    RunCodeConfig, RuntimeConfig, SandboxBackend, SandboxConfig, SchedulerConfig, SecretsConfig,
+    SecurityConfig, SendEmailConfig, SkillsConfig, SkillsPromptInjectionMode, SlackConfig,
+    SqlConnectionConfig, SqlConnectionKind, SqlQueryConfig, StorageConfig, StorageProviderConfig,
+    StorageProviderSection, StreamMode, TelegramConfig, TranscriptSinkConfig, TranscriptionBackend,
+    TranscriptionConfig, TranscriptsConfig, TriggerConfig, TtsConfig, TtsEngine, TunnelConfig,
+    UserIdentityConfig, UserMemoryConfig, WebChannelConfig, WebFetchConfig, WebSearchConfig,
+    WebhookConfig,
 };
 
 pub fn name_and_presence<T: traits::ChannelConfig>(channel: &Option<T>) -> (&'static str, bool) {
@@ -45,6 +56,7 @@ mod tests {
             draft_update_interval_ms: 1000,
             interrupt_on_new_message: false,
             mention_only: false,
+            trigger_prefix: None,
         };
 
         let discord = DiscordConfig {
@@ -53,6 +65,12 @@ mod tests {
             allowed_users: vec![],
             listen_to_bots: false,
             mention_only: false,
+            trigger_prefix: None,
+            thread_replies: false,
+            slash_commands: true,
+            stream_mode: StreamMode::default(),
+            draft_update_interval_ms: 1000,
+            voice: false,
         };
 
         let lark = LarkConfig {
@@ -24,22 +24,37 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "provider.openrouter",
     "channel.dingtalk",
     "channel.discord",
+    "channel.feeds",
     "channel.feishu",
+    "channel.github",
+    "channel.google_chat",
     "channel.lark",
+    "channel.mastodon",
     "channel.matrix",
     "channel.mattermost",
+    "channel.moderation",
     "channel.nextcloud_talk",
     "channel.qq",
     "channel.signal",
     "channel.slack",
     "channel.telegram",
     "channel.wati",
+    "channel.twilio",
+    "channel.wecom",
     "channel.whatsapp",
+    "channel.xmpp",
+    "channel.zulip",
     "tool.browser",
+    "tool.calendar",
+    "tool.channel_forward",
     "tool.composio",
+    "tool.generate_image",
+    "tool.home_assistant",
     "tool.http_request",
+    "tool.mcp",
     "tool.pushover",
     "memory.embeddings",
+    "memory.transcripts",
     "tunnel.custom",
     "transcription.groq",
 ];
@@ -138,6 +153,14 @@ pub struct Config {
     #[serde(default)]
     pub cron: CronConfig,
 
+    /// Digest batching for proactive channel output (`[digest]`).
+    #[serde(default)]
+    pub digest: DigestConfig,
+
+    /// Conversation transcript export to external sinks (`[transcripts]`).
+    #[serde(default)]
+    pub transcripts: TranscriptsConfig,
+
     /// Channel configurations: Telegram, Discord, Slack, etc. (`[channels_config]`).
     #[serde(default)]
     pub channels_config: ChannelsConfig,
@@ -162,6 +185,10 @@ pub struct Config {
     #[serde(default)]
     pub composio: ComposioConfig,
 
+    /// External MCP (Model Context Protocol) tool servers (`[mcp]`).
+    #[serde(default)]
+    pub mcp: McpConfig,
+
     /// Secrets encryption configuration (`[secrets]`).
     #[serde(default)]
     pub secrets: SecretsConfig,
@@ -214,9 +241,58 @@ pub struct Config {
     #[serde(default)]
     pub hardware: HardwareConfig,
 
-    /// Voice transcription configuration (Whisper API via Groq).
+    /// Voice transcription configuration (`[transcription]` section).
     #[serde(default)]
     pub transcription: TranscriptionConfig,
+
+    /// Text-to-speech for outbound voice replies (`[tts]` section).
+    #[serde(default)]
+    pub tts: TtsConfig,
+
+    /// Image generation tool configuration (`[image_generation]` section).
+    #[serde(default)]
+    pub image_generation: ImageGenerationConfig,
+
+    /// Sandboxed code interpreter tool configuration (`[run_code]` section).
+    #[serde(default)]
+    pub run_code: RunCodeConfig,
+
+    /// Read-only database query tool configuration (`[sql_query]` section).
+    #[serde(default)]
+    pub sql_query: SqlQueryConfig,
+
+    /// Read-only repository tool configuration (`[git]` section).
+    #[serde(default)]
+    pub git: GitConfig,
+
+    /// Calendar tool configuration (`[calendar]` section).
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+
+    /// Outbound email tool configuration (`[send_email]` section).
+    #[serde(default)]
+    pub send_email: SendEmailConfig,
+
+    /// Home Assistant tool configuration (`[home_assistant]` section).
+    #[serde(default)]
+    pub home_assistant: HomeAssistantConfig,
+
+    /// RSS/Atom feed watcher configuration (`[feeds]` section).
+    #[serde(default)]
+    pub feeds: FeedsConfig,
+
+    /// Clipboard and desktop input tools (`[desktop]` section).
+    #[serde(default)]
+    pub desktop: DesktopConfig,
+
+    /// Knowledge base over the user's documents (`[knowledge]` section).
+    #[serde(default)]
+    pub knowledge: KnowledgeConfig,
+
+    /// Incoming webhook triggers (`[triggers.<name>]`), served by the
+    /// gateway at `POST /trigger/<name>`.
+    #[serde(default)]
+    pub triggers: HashMap<String, TriggerConfig>,
 }
 
 /// Named provider profile definition compatible with Codex app-server style config.
@@ -360,12 +436,33 @@ fn default_transcription_max_duration_secs() -> u64 {
     120
 }
 
-/// Voice transcription configuration (Whisper API via Groq).
+fn default_whisper_cpp_command() -> String {
+    "whisper-cli".into()
+}
+
+/// Speech-to-text engine used for voice transcription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionBackend {
+    /// Whisper-compatible HTTP API (Groq, OpenAI, self-hosted servers).
+    #[default]
+    Api,
+    /// Local whisper.cpp binary; audio is converted with `ffmpeg` first.
+    WhisperCpp,
+}
+
+/// Voice transcription configuration (Whisper API or local whisper.cpp).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TranscriptionConfig {
     /// Enable voice transcription for channels that support it.
     #[serde(default)]
     pub enabled: bool,
+    /// Transcription engine: `api` (default) or `whisper_cpp`.
+    #[serde(default)]
+    pub backend: TranscriptionBackend,
+    /// API key for the transcription endpoint. Falls back to `GROQ_API_KEY`.
+    #[serde(default)]
+    pub api_key: Option<String>,
     /// Whisper API endpoint URL.
     #[serde(default = "default_transcription_api_url")]
     pub api_url: String,
@@ -378,235 +475,1117 @@ pub struct TranscriptionConfig {
     /// Maximum voice duration in seconds (messages longer than this are skipped).
     #[serde(default = "default_transcription_max_duration_secs")]
     pub max_duration_secs: u64,
+    /// whisper.cpp CLI binary (name on `PATH` or absolute path).
+    #[serde(default = "default_whisper_cpp_command")]
+    pub whisper_cpp_command: String,
+    /// Path to the ggml model file passed to whisper.cpp (`-m`).
+    #[serde(default)]
+    pub whisper_cpp_model: Option<String>,
 }
 
 impl Default for TranscriptionConfig {
     fn default() -> Self {
         Self {
             enabled: false,
+            backend: TranscriptionBackend::default(),
+            api_key: None,
             api_url: default_transcription_api_url(),
             model: default_transcription_model(),
             language: None,
             max_duration_secs: default_transcription_max_duration_secs(),
+            whisper_cpp_command: default_whisper_cpp_command(),
+            whisper_cpp_model: None,
         }
     }
 }
 
-/// Agent orchestration configuration (`[agent]` section).
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct AgentConfig {
-    /// When true: bootstrap_max_chars=6000, rag_chunk_limit=2. Use for 13B or smaller models.
-    #[serde(default)]
-    pub compact_context: bool,
-    /// Maximum tool-call loop turns per user message. Default: `10`.
-    /// Setting to `0` falls back to the safe default of `10`.
-    #[serde(default = "default_agent_max_tool_iterations")]
-    pub max_tool_iterations: usize,
-    /// Maximum conversation history messages retained per session. Default: `50`.
-    #[serde(default = "default_agent_max_history_messages")]
-    pub max_history_messages: usize,
-    /// Enable parallel tool execution within a single iteration. Default: `false`.
-    #[serde(default)]
-    pub parallel_tools: bool,
-    /// Tool dispatch strategy (e.g. `"auto"`). Default: `"auto"`.
-    #[serde(default = "default_agent_tool_dispatcher")]
-    pub tool_dispatcher: String,
+fn default_tts_api_url() -> String {
+    "https://api.openai.com/v1/audio/speech".into()
 }
 
-fn default_agent_max_tool_iterations() -> usize {
-    10
+fn default_tts_model() -> String {
+    "gpt-4o-mini-tts".into()
 }
 
-fn default_agent_max_history_messages() -> usize {
-    50
+fn default_piper_command() -> String {
+    "piper".into()
 }
 
-fn default_agent_tool_dispatcher() -> String {
-    "auto".into()
+fn default_edge_tts_command() -> String {
+    "edge-tts".into()
 }
 
-impl Default for AgentConfig {
-    fn default() -> Self {
-        Self {
-            compact_context: false,
-            max_tool_iterations: default_agent_max_tool_iterations(),
-            max_history_messages: default_agent_max_history_messages(),
-            parallel_tools: false,
-            tool_dispatcher: default_agent_tool_dispatcher(),
-        }
-    }
+fn default_tts_max_chars() -> usize {
+    2000
 }
 
-/// Skills loading configuration (`[skills]` section).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+/// Speech synthesis engine used for voice replies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum SkillsPromptInjectionMode {
-    /// Inline full skill instructions and tool metadata into the system prompt.
+pub enum TtsEngine {
+    /// OpenAI-compatible `/audio/speech` API.
     #[default]
-    Full,
-    /// Inline only compact skill metadata (name/description/location) and load details on demand.
-    Compact,
-}
-
-fn parse_skills_prompt_injection_mode(raw: &str) -> Option<SkillsPromptInjectionMode> {
-    match raw.trim().to_ascii_lowercase().as_str() {
-        "full" => Some(SkillsPromptInjectionMode::Full),
-        "compact" => Some(SkillsPromptInjectionMode::Compact),
-        _ => None,
-    }
+    OpenAi,
+    /// Local Piper binary; output is converted with `ffmpeg`.
+    Piper,
+    /// `edge-tts` CLI (Microsoft Edge online voices); output is converted with `ffmpeg`.
+    EdgeTts,
 }
 
-/// Skills loading configuration (`[skills]` section).
+/// Text-to-speech configuration (`[tts]` section).
+///
+/// Replies containing `[VOICE:synthesize]` (speak the reply) or
+/// `[VOICE:synthesize|text]` (speak `text`) are sent as voice notes on
+/// channels that support them (Telegram, WhatsApp, Signal).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct SkillsConfig {
-    /// Enable loading and syncing the community open-skills repository.
-    /// Default: `false` (opt-in).
+pub struct TtsConfig {
+    /// Enable voice replies. Default: `false`.
     #[serde(default)]
-    pub open_skills_enabled: bool,
-    /// Optional path to a local open-skills repository.
-    /// If unset, defaults to `$HOME/open-skills` when enabled.
+    pub enabled: bool,
+    /// Synthesis engine: `open_ai` (default), `piper` or `edge_tts`.
     #[serde(default)]
-    pub open_skills_dir: Option<String>,
-    /// Controls how skills are injected into the system prompt.
-    /// `full` preserves legacy behavior. `compact` keeps context small and loads skills on demand.
+    pub engine: TtsEngine,
+    /// API key for `api_url`. Falls back to `OPENAI_API_KEY`.
     #[serde(default)]
-    pub prompt_injection_mode: SkillsPromptInjectionMode,
+    pub api_key: Option<String>,
+    /// OpenAI-compatible speech endpoint URL.
+    #[serde(default = "default_tts_api_url")]
+    pub api_url: String,
+    /// Speech model name for the API engine.
+    #[serde(default = "default_tts_model")]
+    pub model: String,
+    /// Voice name (engine-specific). Defaults to `alloy` for the API and
+    /// `en-US-AriaNeural` for edge-tts; unused by Piper.
+    #[serde(default)]
+    pub voice: Option<String>,
+    /// Piper CLI binary (name on `PATH` or absolute path).
+    #[serde(default = "default_piper_command")]
+    pub piper_command: String,
+    /// Path to the Piper `.onnx` voice model.
+    #[serde(default)]
+    pub piper_model: Option<String>,
+    /// edge-tts CLI binary (name on `PATH` or absolute path).
+    #[serde(default = "default_edge_tts_command")]
+    pub edge_tts_command: String,
+    /// Maximum characters spoken per voice note; longer text is cut off.
+    #[serde(default = "default_tts_max_chars")]
+    pub max_chars: usize,
 }
 
-impl Default for SkillsConfig {
+impl Default for TtsConfig {
     fn default() -> Self {
         Self {
-            open_skills_enabled: false,
-            open_skills_dir: None,
-            prompt_injection_mode: SkillsPromptInjectionMode::default(),
+            enabled: false,
+            engine: TtsEngine::default(),
+            api_key: None,
+            api_url: default_tts_api_url(),
+            model: default_tts_model(),
+            voice: None,
+            piper_command: default_piper_command(),
+            piper_model: None,
+            edge_tts_command: default_edge_tts_command(),
+            max_chars: default_tts_max_chars(),
         }
     }
 }
 
-/// Multimodal (image) handling configuration (`[multimodal]` section).
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct MultimodalConfig {
-    /// Maximum number of image attachments accepted per request.
-    #[serde(default = "default_multimodal_max_images")]
-    pub max_images: usize,
-    /// Maximum image payload size in MiB before base64 encoding.
-    #[serde(default = "default_multimodal_max_image_size_mb")]
-    pub max_image_size_mb: usize,
-    /// Allow fetching remote image URLs (http/https). Disabled by default.
-    #[serde(default)]
-    pub allow_remote_fetch: bool,
+fn default_image_generation_model() -> String {
+    "gpt-image-1".into()
 }
 
-fn default_multimodal_max_images() -> usize {
-    4
+fn default_image_generation_size() -> String {
+    "1024x1024".into()
 }
 
-fn default_multimodal_max_image_size_mb() -> usize {
-    5
+fn default_image_generation_timeout_secs() -> u64 {
+    180
 }
 
-impl MultimodalConfig {
-    /// Clamp configured values to safe runtime bounds.
-    pub fn effective_limits(&self) -> (usize, usize) {
-        let max_images = self.max_images.clamp(1, 16);
-        let max_image_size_mb = self.max_image_size_mb.clamp(1, 20);
-        (max_images, max_image_size_mb)
-    }
+/// Image generation service used by the `generate_image` tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageGenerationBackend {
+    /// OpenAI Images API (`/v1/images/generations`).
+    #[default]
+    OpenAi,
+    /// Stability AI Stable Image API.
+    Stability,
+    /// Local AUTOMATIC1111 / Forge Stable Diffusion web UI (`/sdapi/v1/txt2img`).
+    SdWebui,
+    /// Local ComfyUI server running an API-format workflow.
+    Comfyui,
 }
 
-impl Default for MultimodalConfig {
+/// Image generation configuration (`[image_generation]` section).
+///
+/// When enabled, the `generate_image` tool saves pictures to the workspace
+/// and returns them as `[IMAGE:<path>]` markers for channels to deliver.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImageGenerationConfig {
+    /// Register the `generate_image` tool. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Service used to generate images: `open_ai` (default), `stability`,
+    /// `sd_webui` or `comfyui`.
+    #[serde(default)]
+    pub backend: ImageGenerationBackend,
+    /// API key for hosted backends. Falls back to `OPENAI_API_KEY` or
+    /// `STABILITY_API_KEY`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Endpoint override. Defaults to the backend's public API, or
+    /// `http://127.0.0.1:7860` (SD web UI) / `http://127.0.0.1:8188` (ComfyUI).
+    #[serde(default)]
+    pub api_url: Option<String>,
+    /// Model name for the OpenAI backend.
+    #[serde(default = "default_image_generation_model")]
+    pub model: String,
+    /// Default image size as `WIDTHxHEIGHT`.
+    #[serde(default = "default_image_generation_size")]
+    pub size: String,
+    /// ComfyUI workflow exported in API format. The strings `{{prompt}}`,
+    /// `{{negative_prompt}}`, `{{width}}`, `{{height}}` and `{{seed}}` are
+    /// substituted before queueing. Required for the `comfyui` backend.
+    #[serde(default)]
+    pub comfyui_workflow: Option<String>,
+    /// Maximum time to wait for one image, in seconds.
+    #[serde(default = "default_image_generation_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for ImageGenerationConfig {
     fn default() -> Self {
         Self {
-            max_images: default_multimodal_max_images(),
-            max_image_size_mb: default_multimodal_max_image_size_mb(),
-            allow_remote_fetch: false,
+            enabled: false,
+            backend: ImageGenerationBackend::default(),
+            api_key: None,
+            api_url: None,
+            model: default_image_generation_model(),
+            size: default_image_generation_size(),
+            comfyui_workflow: None,
+            timeout_secs: default_image_generation_timeout_secs(),
         }
     }
 }
 
-// ── Identity (AIEOS / OpenClaw format) ──────────────────────────
+fn default_run_code_docker_image() -> String {
+    "python:3.12-slim".into()
+}
 
-/// Identity format configuration (`[identity]` section).
-///
-/// Supports `"openclaw"` (default) or `"aieos"` identity documents.
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct IdentityConfig {
-    /// Identity format: "openclaw" (default) or "aieos"
-    #[serde(default = "default_identity_format")]
-    pub format: String,
-    /// Path to AIEOS JSON file (relative to workspace)
-    #[serde(default)]
-    pub aieos_path: Option<String>,
-    /// Inline AIEOS JSON (alternative to file path)
-    #[serde(default)]
-    pub aieos_inline: Option<String>,
+fn default_run_code_python_command() -> String {
+    "python3".into()
 }
 
-fn default_identity_format() -> String {
-    "openclaw".into()
+fn default_run_code_wasmtime_command() -> String {
+    "wasmtime".into()
 }
 
-impl Default for IdentityConfig {
-    fn default() -> Self {
-        Self {
-            format: default_identity_format(),
-            aieos_path: None,
-            aieos_inline: None,
-        }
-    }
+fn default_run_code_timeout_secs() -> u64 {
+    60
 }
 
-// ── Cost tracking and budget enforcement ───────────────────────────
+fn default_run_code_memory_limit_mb() -> u64 {
+    512
+}
 
-/// Cost tracking and budget enforcement configuration (`[cost]` section).
+/// Isolation used by the `run_code` tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunCodeBackend {
+    /// Throwaway Docker container with the working directory mounted.
+    #[default]
+    Docker,
+    /// Firejail with a private home directory.
+    Firejail,
+    /// CPython compiled to WASI, run with `wasmtime`.
+    Wasm,
+}
+
+/// Code interpreter configuration (`[run_code]` section).
+///
+/// Python and SQL snippets run in a fresh working directory inside the
+/// configured sandbox; files they produce are copied into the workspace.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct CostConfig {
-    /// Enable cost tracking (default: false)
+pub struct RunCodeConfig {
+    /// Register the `run_code` tool. Default: `false`.
     #[serde(default)]
     pub enabled: bool,
+    /// Sandbox: `docker` (default), `firejail` or `wasm`.
+    #[serde(default)]
+    pub backend: RunCodeBackend,
+    /// Container image for the Docker backend. Needs `python3`; install
+    /// matplotlib/pandas in a custom image to use them.
+    #[serde(default = "default_run_code_docker_image")]
+    pub docker_image: String,
+    /// Python interpreter for the Firejail backend.
+    #[serde(default = "default_run_code_python_command")]
+    pub python_command: String,
+    /// `wasmtime` CLI binary for the WASM backend.
+    #[serde(default = "default_run_code_wasmtime_command")]
+    pub wasmtime_command: String,
+    /// Path to a self-contained `python.wasm` (WASI build with its standard
+    /// library). Required for the WASM backend.
+    #[serde(default)]
+    pub python_wasm: Option<String>,
+    /// Allow network access from the sandbox. Default: `false`.
+    #[serde(default)]
+    pub allow_network: bool,
+    /// Maximum run time per snippet, in seconds.
+    #[serde(default = "default_run_code_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Memory limit per run in MB (Docker and Firejail).
+    #[serde(default = "default_run_code_memory_limit_mb")]
+    pub memory_limit_mb: u64,
+}
 
-    /// Daily spending limit in USD (default: 10.00)
-    #[serde(default = "default_daily_limit")]
-    pub daily_limit_usd: f64,
+impl Default for RunCodeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: RunCodeBackend::default(),
+            docker_image: default_run_code_docker_image(),
+            python_command: default_run_code_python_command(),
+            wasmtime_command: default_run_code_wasmtime_command(),
+            python_wasm: None,
+            allow_network: false,
+            timeout_secs: default_run_code_timeout_secs(),
+            memory_limit_mb: default_run_code_memory_limit_mb(),
+        }
+    }
+}
 
-    /// Monthly spending limit in USD (default: 100.00)
-    #[serde(default = "default_monthly_limit")]
-    pub monthly_limit_usd: f64,
+fn default_sql_query_max_rows() -> usize {
+    100
+}
 
-    /// Warn when spending reaches this percentage of limit (default: 80)
-    #[serde(default = "default_warn_percent")]
-    pub warn_at_percent: u8,
+fn default_sql_query_timeout_secs() -> u64 {
+    30
+}
 
-    /// Allow requests to exceed budget with --override flag (default: false)
-    #[serde(default)]
-    pub allow_override: bool,
+/// Database engine behind a `[sql_query.connections.<name>]` entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlConnectionKind {
+    /// PostgreSQL, queried through the `psql` client.
+    Postgres,
+    /// MySQL or MariaDB, queried through the `mysql` client.
+    Mysql,
+    /// SQLite database file, opened read-only in process.
+    Sqlite,
+}
 
-    /// Per-model pricing (USD per 1M tokens)
+/// One named database the `sql_query` tool may read.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SqlConnectionConfig {
+    /// Database engine.
+    pub kind: SqlConnectionKind,
+    /// Connection URL (`postgres://...`, `mysql://...`) or, for SQLite, the
+    /// database file path. Use a read-only database user where possible.
+    pub url: String,
+    /// What the database holds; shown to the model to pick a connection.
     #[serde(default)]
-    pub prices: std::collections::HashMap<String, ModelPricing>,
+    pub description: Option<String>,
 }
 
-/// Per-model pricing entry (USD per 1M tokens).
+/// Database query tool configuration (`[sql_query]` section).
+///
+/// Queries run in read-only sessions and only `SELECT`-style statements are
+/// accepted.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct ModelPricing {
-    /// Input price per 1M tokens
+pub struct SqlQueryConfig {
+    /// Register the `sql_query` tool. Default: `false`.
     #[serde(default)]
-    pub input: f64,
+    pub enabled: bool,
+    /// Named connections (`[sql_query.connections.<name>]`).
+    #[serde(default)]
+    pub connections: HashMap<String, SqlConnectionConfig>,
+    /// Maximum rows returned per query. Default: `100`.
+    #[serde(default = "default_sql_query_max_rows")]
+    pub max_rows: usize,
+    /// Query timeout in seconds. Default: `30`.
+    #[serde(default = "default_sql_query_timeout_secs")]
+    pub timeout_secs: u64,
+}
 
-    /// Output price per 1M tokens
-    #[serde(default)]
-    pub output: f64,
+impl Default for SqlQueryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            connections: HashMap::new(),
+            max_rows: default_sql_query_max_rows(),
+            timeout_secs: default_sql_query_timeout_secs(),
+        }
+    }
 }
 
-fn default_daily_limit() -> f64 {
-    10.0
+fn default_git_max_output_bytes() -> usize {
+    32_768
 }
 
-fn default_monthly_limit() -> f64 {
-    100.0
+fn default_git_timeout_secs() -> u64 {
+    30
 }
 
-fn default_warn_percent() -> u8 {
+/// Repository tool configuration (`[git]` section).
+///
+/// The `git` tool only reads: status, diff, log, blame and grep.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitConfig {
+    /// Register the `git` tool. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Repository to inspect, absolute or relative to the workspace.
+    /// Default: the workspace itself.
+    #[serde(default)]
+    pub repo_path: Option<String>,
+    /// Maximum bytes of command output returned. Default: `32768`.
+    #[serde(default = "default_git_max_output_bytes")]
+    pub max_output_bytes: usize,
+    /// Command timeout in seconds. Default: `30`.
+    #[serde(default = "default_git_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            repo_path: None,
+            max_output_bytes: default_git_max_output_bytes(),
+            timeout_secs: default_git_timeout_secs(),
+        }
+    }
+}
+
+fn default_calendar_google_calendar_id() -> String {
+    "primary".into()
+}
+
+fn default_calendar_event_duration_mins() -> u32 {
+    60
+}
+
+fn default_calendar_timeout_secs() -> u64 {
+    30
+}
+
+/// Calendar service used by the `calendar` tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarBackend {
+    /// Any CalDAV server (Nextcloud, Fastmail, iCloud, Radicale, ...).
+    #[default]
+    Caldav,
+    /// Google Calendar API v3.
+    Google,
+}
+
+/// Calendar configuration (`[calendar]` section).
+///
+/// When enabled, the `calendar` tool lists, creates, updates and deletes
+/// events in one calendar.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CalendarConfig {
+    /// Register the `calendar` tool. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Calendar service: `caldav` (default) or `google`.
+    #[serde(default)]
+    pub backend: CalendarBackend,
+    /// IANA timezone for times given without an offset, e.g.
+    /// `"Europe/Berlin"`. Defaults to the system timezone.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Length of events created without an end time, in minutes.
+    #[serde(default = "default_calendar_event_duration_mins")]
+    pub default_duration_mins: u32,
+    /// CalDAV calendar collection URL, e.g.
+    /// `https://cloud.example.com/remote.php/dav/calendars/alice/personal/`.
+    #[serde(default)]
+    pub caldav_url: Option<String>,
+    /// CalDAV username.
+    #[serde(default)]
+    pub caldav_username: Option<String>,
+    /// CalDAV password or app password.
+    #[serde(default)]
+    pub caldav_password: Option<String>,
+    /// Google calendar ID. Default: `"primary"`.
+    #[serde(default = "default_calendar_google_calendar_id")]
+    pub google_calendar_id: String,
+    /// OAuth client ID used to refresh the Google access token.
+    #[serde(default)]
+    pub google_client_id: Option<String>,
+    /// OAuth client secret.
+    #[serde(default)]
+    pub google_client_secret: Option<String>,
+    /// OAuth refresh token with the `https://www.googleapis.com/auth/calendar.events` scope.
+    #[serde(default)]
+    pub google_refresh_token: Option<String>,
+    /// Request timeout in seconds.
+    #[serde(default = "default_calendar_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: CalendarBackend::default(),
+            timezone: None,
+            default_duration_mins: default_calendar_event_duration_mins(),
+            caldav_url: None,
+            caldav_username: None,
+            caldav_password: None,
+            google_calendar_id: default_calendar_google_calendar_id(),
+            google_client_id: None,
+            google_client_secret: None,
+            google_refresh_token: None,
+            timeout_secs: default_calendar_timeout_secs(),
+        }
+    }
+}
+
+fn default_send_email_smtp_port() -> u16 {
+    465
+}
+
+fn default_send_email_max_attachment_mb() -> u64 {
+    20
+}
+
+/// Outbound email configuration (`[send_email]` section).
+///
+/// When enabled, the `send_email` tool mails messages with workspace files
+/// attached. Without `smtp_host`, the SMTP settings of
+/// `[channels_config.email]` are used.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SendEmailConfig {
+    /// Register the `send_email` tool. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// SMTP server hostname.
+    #[serde(default)]
+    pub smtp_host: Option<String>,
+    /// SMTP server port. Default: `465`.
+    #[serde(default = "default_send_email_smtp_port")]
+    pub smtp_port: u16,
+    /// Use implicit TLS for SMTP. Default: `true`.
+    #[serde(default = "default_true")]
+    pub smtp_tls: bool,
+    /// SMTP username.
+    #[serde(default)]
+    pub username: Option<String>,
+    /// SMTP password.
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Sender address; defaults to `username`.
+    #[serde(default)]
+    pub from_address: Option<String>,
+    /// Allowed recipient addresses or `@domain` suffixes. Empty = any.
+    #[serde(default)]
+    pub allowed_recipients: Vec<String>,
+    /// Combined size limit for attachments, in MB.
+    #[serde(default = "default_send_email_max_attachment_mb")]
+    pub max_attachment_mb: u64,
+}
+
+impl Default for SendEmailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            smtp_host: None,
+            smtp_port: default_send_email_smtp_port(),
+            smtp_tls: true,
+            username: None,
+            password: None,
+            from_address: None,
+            allowed_recipients: Vec::new(),
+            max_attachment_mb: default_send_email_max_attachment_mb(),
+        }
+    }
+}
+
+fn default_home_assistant_url() -> String {
+    "http://homeassistant.local:8123".into()
+}
+
+fn default_home_assistant_allowed_domains() -> Vec<String> {
+    [
+        "light",
+        "switch",
+        "fan",
+        "cover",
+        "climate",
+        "media_player",
+        "scene",
+        "script",
+        "input_boolean",
+        "vacuum",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_home_assistant_timeout_secs() -> u64 {
+    15
+}
+
+/// Home Assistant configuration (`[home_assistant]` section).
+///
+/// When enabled, the `home_assistant` tool reads entity states and calls
+/// services through the Home Assistant REST API.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HomeAssistantConfig {
+    /// Register the `home_assistant` tool. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL of the Home Assistant instance.
+    #[serde(default = "default_home_assistant_url")]
+    pub url: String,
+    /// Long-lived access token.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Domains whose services the tool may call, and whose entities it may
+    /// target. Locks and alarm panels are left out by default.
+    #[serde(default = "default_home_assistant_allowed_domains")]
+    pub allowed_domains: Vec<String>,
+    /// Request timeout in seconds.
+    #[serde(default = "default_home_assistant_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for HomeAssistantConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: default_home_assistant_url(),
+            token: None,
+            allowed_domains: default_home_assistant_allowed_domains(),
+            timeout_secs: default_home_assistant_timeout_secs(),
+        }
+    }
+}
+
+fn default_desktop_max_clipboard_chars() -> usize {
+    20_000
+}
+
+/// Desktop control configuration (`[desktop]` section).
+///
+/// For agents running on a workstation. Enables the clipboard tools and,
+/// with `allow_input`, mouse and keyboard automation through `xdotool`
+/// (Linux/X11) or `cliclick` (macOS).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DesktopConfig {
+    /// Register `clipboard_read` and `clipboard_write`. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Also register `desktop_input` (move mouse, click, type, key press).
+    /// Default: `false`.
+    #[serde(default)]
+    pub allow_input: bool,
+    /// Longest clipboard text returned by `clipboard_read` or accepted by
+    /// `clipboard_write`.
+    #[serde(default = "default_desktop_max_clipboard_chars")]
+    pub max_clipboard_chars: usize,
+}
+
+impl Default for DesktopConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allow_input: false,
+            max_clipboard_chars: default_desktop_max_clipboard_chars(),
+        }
+    }
+}
+
+fn default_knowledge_directories() -> Vec<String> {
+    vec!["knowledge".into()]
+}
+
+fn default_knowledge_extensions() -> Vec<String> {
+    ["md", "markdown", "txt", "pdf", "docx", "xlsx"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_knowledge_chunk_max_tokens() -> usize {
+    512
+}
+
+fn default_knowledge_rescan_interval_secs() -> u64 {
+    300
+}
+
+fn default_knowledge_max_results() -> usize {
+    5
+}
+
+/// Knowledge base configuration (`[knowledge]` section).
+///
+/// When enabled, documents under `directories` are chunked, embedded with
+/// the `[memory]` embedding settings and indexed in
+/// `{workspace}/state/knowledge.db`; the `search_knowledge` tool queries the
+/// index. The daemon rescans the directories every `rescan_interval_secs`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct KnowledgeConfig {
+    /// Index documents and register `search_knowledge`. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directories to index, recursively. Relative paths resolve from the
+    /// workspace. Default: `["knowledge"]`.
+    #[serde(default = "default_knowledge_directories")]
+    pub directories: Vec<String>,
+    /// File extensions to index. PDF, DOCX and XLSX are converted to text
+    /// first. Default: `md`, `markdown`, `txt`, `pdf`, `docx`, `xlsx`.
+    #[serde(default = "default_knowledge_extensions")]
+    pub extensions: Vec<String>,
+    /// Approximate maximum tokens per indexed chunk. Default: `512`.
+    #[serde(default = "default_knowledge_chunk_max_tokens")]
+    pub chunk_max_tokens: usize,
+    /// Seconds between directory rescans in the daemon. Default: `300`.
+    #[serde(default = "default_knowledge_rescan_interval_secs")]
+    pub rescan_interval_secs: u64,
+    /// Passages returned by `search_knowledge` when the model does not ask
+    /// for a count. Default: `5`.
+    #[serde(default = "default_knowledge_max_results")]
+    pub max_results: usize,
+}
+
+impl Default for KnowledgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directories: default_knowledge_directories(),
+            extensions: default_knowledge_extensions(),
+            chunk_max_tokens: default_knowledge_chunk_max_tokens(),
+            rescan_interval_secs: default_knowledge_rescan_interval_secs(),
+            max_results: default_knowledge_max_results(),
+        }
+    }
+}
+
+/// An incoming webhook that runs the agent (`[triggers.<name>]`).
+///
+/// External systems (CI, alerting) POST JSON to `/trigger/<name>`; the
+/// payload is rendered into `prompt`, the agent answers it unattended and
+/// the answer is delivered to `deliver_to`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct TriggerConfig {
+    /// Shared secret expected in `X-Trigger-Secret` or as
+    /// `Authorization: Bearer <secret>`. Unset: the gateway pairing token is
+    /// required instead, when pairing is enabled.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Prompt template. `{{path.to.field}}` is replaced with that payload
+    /// value (array elements by index, e.g. `{{alerts.0.status}}`) and
+    /// `{{.}}` with the whole payload.
+    pub prompt: String,
+    /// Destination as `"<channel>:<recipient>"`, e.g.
+    /// `"mattermost:town-square-id"` or `"broadcast:<group>"`.
+    pub deliver_to: String,
+}
+
+/// Agent orchestration configuration (`[agent]` section).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AgentConfig {
+    /// When true: bootstrap_max_chars=6000, rag_chunk_limit=2. Use for 13B or smaller models.
+    #[serde(default)]
+    pub compact_context: bool,
+    /// Maximum tool-call loop turns per user message. Default: `10`.
+    /// Setting to `0` falls back to the safe default of `10`.
+    #[serde(default = "default_agent_max_tool_iterations")]
+    pub max_tool_iterations: usize,
+    /// Maximum conversation history messages retained per session. Default: `50`.
+    #[serde(default = "default_agent_max_history_messages")]
+    pub max_history_messages: usize,
+    /// Enable parallel tool execution within a single iteration. Default: `false`.
+    #[serde(default)]
+    pub parallel_tools: bool,
+    /// Most tool calls from one model turn that run at the same time.
+    /// Dependent calls (same file, shell, browser) still run in order.
+    /// Default: `4`. Setting to `0` falls back to the default; `1` runs
+    /// calls one at a time.
+    #[serde(default = "default_agent_max_parallel_tools")]
+    pub max_parallel_tools: usize,
+    /// Estimated tokens of tool output passed to the model; larger outputs
+    /// are cut to a head/tail excerpt and saved in full under
+    /// `tool_output/` in the workspace. Default: `8000`. `0` disables.
+    #[serde(default = "default_agent_max_tool_output_tokens")]
+    pub max_tool_output_tokens: usize,
+    /// Tool dispatch strategy (e.g. `"auto"`). Default: `"auto"`.
+    #[serde(default = "default_agent_tool_dispatcher")]
+    pub tool_dispatcher: String,
+    /// Source citation mode for channel replies (`[agent.citations]`).
+    #[serde(default)]
+    pub citations: CitationConfig,
+    /// Preview mode that diverts channel replies and tool calls to a review
+    /// target (`[agent.dry_run]`).
+    #[serde(default)]
+    pub dry_run: DryRunConfig,
+}
+
+fn default_agent_max_tool_iterations() -> usize {
+    10
+}
+
+fn default_agent_max_history_messages() -> usize {
+    50
+}
+
+fn default_agent_max_parallel_tools() -> usize {
+    4
+}
+
+fn default_agent_max_tool_output_tokens() -> usize {
+    8000
+}
+
+fn default_agent_tool_dispatcher() -> String {
+    "auto".into()
+}
+
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            compact_context: false,
+            max_tool_iterations: default_agent_max_tool_iterations(),
+            max_history_messages: default_agent_max_history_messages(),
+            parallel_tools: false,
+            max_parallel_tools: default_agent_max_parallel_tools(),
+            max_tool_output_tokens: default_agent_max_tool_output_tokens(),
+            tool_dispatcher: default_agent_tool_dispatcher(),
+            citations: CitationConfig::default(),
+            dry_run: DryRunConfig::default(),
+        }
+    }
+}
+
+/// Source citation mode (`[agent.citations]` section).
+///
+/// When enabled, answers that relied on tool lookups carry a compact sources
+/// footer (URLs, file paths, queries used) and an optional confidence level,
+/// formatted for the delivering channel.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CitationConfig {
+    /// Enable citation mode. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Drop cited paths and attachment markers that point at missing local
+    /// files before delivery. Default: `true`.
+    #[serde(default = "default_true")]
+    pub verify_paths: bool,
+}
+
+impl Default for CitationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            verify_paths: true,
+        }
+    }
+}
+
+/// Dry-run preview mode (`[agent.dry_run]` section).
+///
+/// Replies in previewed chats are composed as usual, but tool calls are
+/// recorded instead of executed and the reply plus tool plan goes to the
+/// review target instead of the original sender.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DryRunConfig {
+    /// Preview every chat. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Chats previewed even when `enabled` is false, as `"<channel>"` or
+    /// `"<channel>:<reply_target>"`.
+    #[serde(default)]
+    pub chats: Vec<String>,
+    /// Channel that receives previews. When unset, previews are printed to
+    /// the console.
+    #[serde(default)]
+    pub review_channel: Option<String>,
+    /// Recipient on `review_channel` (chat, channel or user id).
+    #[serde(default)]
+    pub review_to: Option<String>,
+}
+
+/// Skills loading configuration (`[skills]` section).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillsPromptInjectionMode {
+    /// Inline full skill instructions and tool metadata into the system prompt.
+    #[default]
+    Full,
+    /// Inline only compact skill metadata (name/description/location) and load details on demand.
+    Compact,
+}
+
+fn parse_skills_prompt_injection_mode(raw: &str) -> Option<SkillsPromptInjectionMode> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "full" => Some(SkillsPromptInjectionMode::Full),
+        "compact" => Some(SkillsPromptInjectionMode::Compact),
+        _ => None,
+    }
+}
+
+/// Skills loading configuration (`[skills]` section).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SkillsConfig {
+    /// Enable loading and syncing the community open-skills repository.
+    /// Default: `false` (opt-in).
+    #[serde(default)]
+    pub open_skills_enabled: bool,
+    /// Optional path to a local open-skills repository.
+    /// If unset, defaults to `$HOME/open-skills` when enabled.
+    #[serde(default)]
+    pub open_skills_dir: Option<String>,
+    /// Controls how skills are injected into the system prompt.
+    /// `full` preserves legacy behavior. `compact` keeps context small and loads skills on demand.
+    #[serde(default)]
+    pub prompt_injection_mode: SkillsPromptInjectionMode,
+}
+
+impl Default for SkillsConfig {
+    fn default() -> Self {
+        Self {
+            open_skills_enabled: false,
+            open_skills_dir: None,
+            prompt_injection_mode: SkillsPromptInjectionMode::default(),
+        }
+    }
+}
+
+/// Multimodal (image) handling configuration (`[multimodal]` section).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MultimodalConfig {
+    /// Maximum number of image attachments accepted per request.
+    #[serde(default = "default_multimodal_max_images")]
+    pub max_images: usize,
+    /// Maximum image payload size in MiB before base64 encoding.
+    #[serde(default = "default_multimodal_max_image_size_mb")]
+    pub max_image_size_mb: usize,
+    /// Allow fetching remote image URLs (http/https). Disabled by default.
+    #[serde(default)]
+    pub allow_remote_fetch: bool,
+    /// Generated alt text for outbound images (`[multimodal.alt_text]`).
+    #[serde(default)]
+    pub alt_text: AltTextConfig,
+    /// Text extraction from images (`[multimodal.ocr]`).
+    #[serde(default)]
+    pub ocr: OcrConfig,
+}
+
+fn default_multimodal_max_images() -> usize {
+    4
+}
+
+fn default_multimodal_max_image_size_mb() -> usize {
+    5
+}
+
+impl MultimodalConfig {
+    /// Clamp configured values to safe runtime bounds.
+    pub fn effective_limits(&self) -> (usize, usize) {
+        let max_images = self.max_images.clamp(1, 16);
+        let max_image_size_mb = self.max_image_size_mb.clamp(1, 20);
+        (max_images, max_image_size_mb)
+    }
+}
+
+impl Default for MultimodalConfig {
+    fn default() -> Self {
+        Self {
+            max_images: default_multimodal_max_images(),
+            max_image_size_mb: default_multimodal_max_image_size_mb(),
+            allow_remote_fetch: false,
+            alt_text: AltTextConfig::default(),
+            ocr: OcrConfig::default(),
+        }
+    }
+}
+
+/// Alt text generation for outbound images (`[multimodal.alt_text]` section).
+///
+/// Images the agent sends to channels that carry alt text (Matrix, Slack)
+/// are described by a vision-capable model before delivery.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AltTextConfig {
+    /// Enable alt text generation. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Provider used to describe images. Defaults to the chat's active provider.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model used to describe images. Defaults to the chat's active model.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Maximum alt text length in characters. Default: `200`.
+    #[serde(default = "default_alt_text_max_chars")]
+    pub max_chars: usize,
+}
+
+fn default_alt_text_max_chars() -> usize {
+    200
+}
+
+impl Default for AltTextConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: None,
+            model: None,
+            max_chars: default_alt_text_max_chars(),
+        }
+    }
+}
+
+/// Engine used by the `ocr` tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrBackend {
+    /// Tesseract when it runs, otherwise the vision model.
+    #[default]
+    Auto,
+    /// Local `tesseract` binary only.
+    Tesseract,
+    /// Vision-capable model only.
+    Vision,
+}
+
+/// Image text extraction (`[multimodal.ocr]` section).
+///
+/// Registers the `ocr` tool. When the active provider has no vision support,
+/// `[IMAGE:]` markers in user messages are replaced with the extracted text
+/// instead of failing the request.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OcrConfig {
+    /// Enable the `ocr` tool. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// OCR engine: `auto` (default), `tesseract` or `vision`.
+    #[serde(default)]
+    pub backend: OcrBackend,
+    /// Tesseract CLI binary (name on `PATH` or absolute path).
+    #[serde(default = "default_tesseract_command")]
+    pub tesseract_command: String,
+    /// Tesseract language codes, joined with `+` (e.g. `"eng+deu"`).
+    #[serde(default = "default_ocr_languages")]
+    pub languages: String,
+    /// Vision provider for the `vision` backend. Defaults to `default_provider`.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Vision model for the `vision` backend. Defaults to `default_model`.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+fn default_tesseract_command() -> String {
+    "tesseract".into()
+}
+
+fn default_ocr_languages() -> String {
+    "eng".into()
+}
+
+impl Default for OcrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: OcrBackend::default(),
+            tesseract_command: default_tesseract_command(),
+            languages: default_ocr_languages(),
+            provider: None,
+            model: None,
+        }
+    }
+}
+
+// ── Identity (AIEOS / OpenClaw format) ──────────────────────────
+
+/// Identity format configuration (`[identity]` section).
+///
+/// Supports `"openclaw"` (default) or `"aieos"` identity documents.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IdentityConfig {
+    /// Identity format: "openclaw" (default) or "aieos"
+    #[serde(default = "default_identity_format")]
+    pub format: String,
+    /// Path to AIEOS JSON file (relative to workspace)
+    #[serde(default)]
+    pub aieos_path: Option<String>,
+    /// Inline AIEOS JSON (alternative to file path)
+    #[serde(default)]
+    pub aieos_inline: Option<String>,
+}
+
+fn default_identity_format() -> String {
+    "openclaw".into()
+}
+
+impl Default for IdentityConfig {
+    fn default() -> Self {
+        Self {
+            format: default_identity_format(),
+            aieos_path: None,
+            aieos_inline: None,
+        }
+    }
+}
+
+// ── Cost tracking and budget enforcement ───────────────────────────
+
+/// Cost tracking and budget enforcement configuration (`[cost]` section).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CostConfig {
+    /// Enable cost tracking (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Daily spending limit in USD (default: 10.00)
+    #[serde(default = "default_daily_limit")]
+    pub daily_limit_usd: f64,
+
+    /// Monthly spending limit in USD (default: 100.00)
+    #[serde(default = "default_monthly_limit")]
+    pub monthly_limit_usd: f64,
+
+    /// Warn when spending reaches this percentage of limit (default: 80)
+    #[serde(default = "default_warn_percent")]
+    pub warn_at_percent: u8,
+
+    /// Allow requests to exceed budget with --override flag (default: false)
+    #[serde(default)]
+    pub allow_override: bool,
+
+    /// Per-model pricing (USD per 1M tokens)
+    #[serde(default)]
+    pub prices: std::collections::HashMap<String, ModelPricing>,
+}
+
+/// Per-model pricing entry (USD per 1M tokens).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelPricing {
+    /// Input price per 1M tokens
+    #[serde(default)]
+    pub input: f64,
+
+    /// Output price per 1M tokens
+    #[serde(default)]
+    pub output: f64,
+}
+
+fn default_daily_limit() -> f64 {
+    10.0
+}
+
+fn default_monthly_limit() -> f64 {
+    100.0
+}
+
+fn default_warn_percent() -> u8 {
     80
 }
 
@@ -884,6 +1863,62 @@ impl Default for ComposioConfig {
     }
 }
 
+// ── MCP (external tool servers) ─────────────────────────────────
+
+/// MCP client configuration (`[mcp]` section).
+///
+/// Each `[[mcp.servers]]` entry is connected at startup; its tools are listed
+/// and registered as `<server>__<tool>` alongside the built-in tools.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct McpConfig {
+    /// MCP servers to connect to
+    #[serde(default)]
+    pub servers: Vec<McpServerConfig>,
+}
+
+/// Transport used to reach an MCP server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    /// Spawn a local process and exchange JSON-RPC over stdin/stdout
+    #[default]
+    Stdio,
+    /// Connect to a remote server over HTTP with Server-Sent Events
+    Sse,
+}
+
+/// A single MCP server entry (`[[mcp.servers]]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpServerConfig {
+    /// Server name, used as the tool name prefix
+    pub name: String,
+    /// Transport: `stdio` (default) or `sse`
+    #[serde(default)]
+    pub transport: McpTransport,
+    /// Command to spawn (stdio transport)
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Command arguments (stdio transport)
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Extra environment variables for the spawned process (stdio transport)
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// SSE endpoint URL (sse transport)
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Extra HTTP headers, e.g. `Authorization` (sse transport)
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Per-request timeout in seconds
+    #[serde(default = "default_mcp_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_mcp_timeout_secs() -> u64 {
+    30
+}
+
 // ── Secrets (encrypted credential store) ────────────────────────
 
 /// Secrets encryption configuration (`[secrets]` section).
@@ -1961,6 +2996,7 @@ impl Default for BuiltinHooksConfig {
 /// Controls what the agent is allowed to do: shell commands, filesystem access,
 /// risk approval gates, and per-policy budgets.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct AutonomyConfig {
     /// Autonomy level: `read_only`, `supervised` (default), or `full`.
     pub level: AutonomyLevel,
@@ -1991,6 +3027,20 @@ pub struct AutonomyConfig {
     #[serde(default)]
     pub shell_env_passthrough: Vec<String>,
 
+    /// Executable names or paths the shell tool never runs, even when
+    /// `allowed_commands` contains `"*"`.
+    #[serde(default)]
+    pub forbidden_commands: Vec<String>,
+
+    /// Seconds before a shell command is killed. Default: `60`.
+    #[serde(default = "default_shell_timeout_secs")]
+    pub shell_timeout_secs: u64,
+
+    /// Bytes of shell output (stdout and stderr together) returned to the
+    /// model; the rest is cut off. Default: `1048576` (1 MB).
+    #[serde(default = "default_shell_max_output_bytes")]
+    pub shell_max_output_bytes: usize,
+
     /// Tools that never require approval (e.g. read-only tools).
     #[serde(default = "default_auto_approve")]
     pub auto_approve: Vec<String>,
@@ -1999,6 +3049,21 @@ pub struct AutonomyConfig {
     #[serde(default = "default_always_ask")]
     pub always_ask: Vec<String>,
 
+    /// Ask in the originating chat before a channel message runs one of
+    /// `channel_approval_tools`; the call waits for a ✅/❌ answer.
+    #[serde(default)]
+    pub channel_approval: bool,
+
+    /// Tools that need chat confirmation when `channel_approval` is on.
+    /// `auto_approve` entries are exempt and `always_ask` entries are added.
+    #[serde(default = "default_channel_approval_tools")]
+    pub channel_approval_tools: Vec<String>,
+
+    /// Seconds to wait for a chat answer before the call is denied.
+    /// Default: `300`.
+    #[serde(default = "default_channel_approval_timeout_secs")]
+    pub channel_approval_timeout_secs: u64,
+
     /// Extra directory roots the agent may read/write outside the workspace.
     /// Supports absolute, `~/...`, and workspace-relative entries.
     /// Resolved paths under any of these roots pass `is_resolved_path_allowed`.
@@ -2013,6 +3078,14 @@ pub struct AutonomyConfig {
     pub non_cli_excluded_tools: Vec<String>,
 }
 
+fn default_shell_timeout_secs() -> u64 {
+    60
+}
+
+fn default_shell_max_output_bytes() -> usize {
+    1_048_576
+}
+
 fn default_auto_approve() -> Vec<String> {
     vec!["file_read".into(), "memory_recall".into()]
 }
@@ -2021,6 +3094,21 @@ fn default_always_ask() -> Vec<String> {
     vec![]
 }
 
+fn default_channel_approval_tools() -> Vec<String> {
+    vec![
+        "shell".into(),
+        "file_write".into(),
+        "file_edit".into(),
+        "browser".into(),
+        "send_email".into(),
+        "desktop_input".into(),
+    ]
+}
+
+fn default_channel_approval_timeout_secs() -> u64 {
+    300
+}
+
 fn is_valid_env_var_name(name: &str) -> bool {
     let mut chars = name.chars();
     match chars.next() {
@@ -2075,8 +3163,14 @@ impl Default for AutonomyConfig {
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
             shell_env_passthrough: vec![],
+            forbidden_commands: Vec::new(),
+            shell_timeout_secs: default_shell_timeout_secs(),
+            shell_max_output_bytes: default_shell_max_output_bytes(),
             auto_approve: default_auto_approve(),
             always_ask: default_always_ask(),
+            channel_approval: false,
+            channel_approval_tools: default_channel_approval_tools(),
+            channel_approval_timeout_secs: default_channel_approval_timeout_secs(),
             allowed_roots: Vec::new(),
             non_cli_excluded_tools: Vec::new(),
         }
@@ -2216,6 +3310,25 @@ pub struct ReliabilityConfig {
     /// Max retries for cron job execution attempts.
     #[serde(default = "default_scheduler_retries")]
     pub scheduler_retries: u32,
+    /// Queue channel messages and notify senders while every provider is
+    /// failing, replaying them once a provider recovers. Default: `false`.
+    #[serde(default)]
+    pub degraded_mode: bool,
+    /// Provider probe cadence in seconds while in degraded mode.
+    #[serde(default = "default_degraded_probe_interval_secs")]
+    pub degraded_probe_interval_secs: u64,
+    /// Background retries for a channel reply whose send failed transiently
+    /// (rate limits, 5xx, timeouts). `0` disables retries; failed replies
+    /// are still recorded in the dead-letter log.
+    #[serde(default = "default_channel_send_retries")]
+    pub channel_send_retries: u32,
+    /// Base backoff (ms) between channel send retries, doubled per attempt.
+    #[serde(default = "default_channel_send_backoff_ms")]
+    pub channel_send_backoff_ms: u64,
+    /// On SIGINT/SIGTERM, how long (seconds) in-flight channel messages may
+    /// keep running before they are cancelled. Default: `30`.
+    #[serde(default = "default_shutdown_drain_secs")]
+    pub shutdown_drain_secs: u64,
 }
 
 fn default_provider_retries() -> u32 {
@@ -2242,6 +3355,22 @@ fn default_scheduler_retries() -> u32 {
     2
 }
 
+fn default_degraded_probe_interval_secs() -> u64 {
+    60
+}
+
+fn default_channel_send_retries() -> u32 {
+    3
+}
+
+fn default_channel_send_backoff_ms() -> u64 {
+    2000
+}
+
+fn default_shutdown_drain_secs() -> u64 {
+    30
+}
+
 impl Default for ReliabilityConfig {
     fn default() -> Self {
         Self {
@@ -2254,6 +3383,11 @@ impl Default for ReliabilityConfig {
             channel_max_backoff_secs: default_channel_backoff_max_secs(),
             scheduler_poll_secs: default_scheduler_poll_secs(),
             scheduler_retries: default_scheduler_retries(),
+            degraded_mode: false,
+            degraded_probe_interval_secs: default_degraded_probe_interval_secs(),
+            channel_send_retries: default_channel_send_retries(),
+            channel_send_backoff_ms: default_channel_send_backoff_ms(),
+            shutdown_drain_secs: default_shutdown_drain_secs(),
         }
     }
 }
@@ -2424,32 +3558,319 @@ impl Default for HeartbeatConfig {
     }
 }
 
-// ── Cron ────────────────────────────────────────────────────────
+// ── Cron ────────────────────────────────────────────────────────
+
+/// Cron job configuration (`[cron]` section).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CronConfig {
+    /// Enable the cron subsystem. Default: `true`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Maximum number of historical cron run records to retain. Default: `50`.
+    #[serde(default = "default_max_run_history")]
+    pub max_run_history: u32,
+    /// Agent jobs declared in config (`[[cron.jobs]]`), kept in sync with
+    /// the job store when the scheduler starts.
+    #[serde(default)]
+    pub jobs: Vec<CronJobConfig>,
+}
+
+fn default_max_run_history() -> u32 {
+    50
+}
+
+impl Default for CronConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_run_history: default_max_run_history(),
+            jobs: Vec::new(),
+        }
+    }
+}
+
+/// A recurring agent task declared in config (`[[cron.jobs]]`).
+///
+/// Each run sends `prompt` to the agent unattended and delivers the answer
+/// to `deliver_to`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct CronJobConfig {
+    /// Unique job name, shown in `zeroclaw cron list`.
+    pub name: String,
+    /// Cron expression (5 fields, or 6-7 with seconds), e.g. `"0 9 * * MON"`.
+    pub cron: String,
+    /// IANA timezone the expression is evaluated in. Default: UTC.
+    #[serde(default)]
+    pub tz: Option<String>,
+    /// Task the agent runs, e.g. `"Summarize last week's GitHub issues"`.
+    pub prompt: String,
+    /// Destination as `"<channel>:<recipient>"` (e.g. `"slack:C0123456"`, or
+    /// `"broadcast:<group>"`). Unset: the result is only kept in run history.
+    #[serde(default)]
+    pub deliver_to: Option<String>,
+    /// Model override for this job.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Set to `false` to pause the job without removing it. Default: `true`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+// ── Digest ──────────────────────────────────────────────────────
+
+/// Digest batching for noisy channels (`[digest]` section).
+///
+/// When enabled, proactive output (cron announcements, heartbeat results)
+/// addressed to a listed channel is queued and delivered as one combined
+/// summary per recipient every `interval_minutes` of that channel.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DigestConfig {
+    /// Enable digest batching. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Maximum items rendered in a single digest; older extras are summarized
+    /// as a count. Default: `20`.
+    #[serde(default = "default_digest_max_items")]
+    pub max_items: usize,
+    /// Channels whose proactive output is batched, keyed by channel name
+    /// (`[digest.channels.<name>]`).
+    #[serde(default)]
+    pub channels: HashMap<String, ChannelDigestConfig>,
+}
+
+/// Digest schedule of one channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelDigestConfig {
+    /// Minutes between this channel's digest deliveries. Default: `60`.
+    #[serde(default = "default_digest_interval_minutes")]
+    pub interval_minutes: u32,
+}
+
+fn default_digest_interval_minutes() -> u32 {
+    60
+}
+
+impl Default for ChannelDigestConfig {
+    fn default() -> Self {
+        Self {
+            interval_minutes: default_digest_interval_minutes(),
+        }
+    }
+}
+
+fn default_digest_max_items() -> usize {
+    20
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_items: default_digest_max_items(),
+            channels: HashMap::new(),
+        }
+    }
+}
+
+/// Conversation transcript export (`[transcripts]` section).
+///
+/// Every user message and delivered reply on a channel is written, in
+/// batches, to each configured sink for archiving outside the SQLite store.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TranscriptsConfig {
+    /// Enable transcript export. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Records buffered before a batch is written. Default: `50`.
+    #[serde(default = "default_transcript_batch_size")]
+    pub batch_size: usize,
+    /// Seconds after which a partial batch is written anyway. Default: `10`.
+    #[serde(default = "default_transcript_flush_interval_secs")]
+    pub flush_interval_secs: u64,
+    /// Destinations (`[[transcripts.sinks]]`).
+    #[serde(default)]
+    pub sinks: Vec<TranscriptSinkConfig>,
+}
+
+fn default_transcript_batch_size() -> usize {
+    50
+}
+
+fn default_transcript_flush_interval_secs() -> u64 {
+    10
+}
+
+impl Default for TranscriptsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            batch_size: default_transcript_batch_size(),
+            flush_interval_secs: default_transcript_flush_interval_secs(),
+            sinks: Vec::new(),
+        }
+    }
+}
+
+/// One transcript destination, selected by `type`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptSinkConfig {
+    /// Daily JSON-lines files, rotated when they reach `max_file_mb`.
+    Jsonl {
+        /// Output directory. Relative paths resolve under the workspace.
+        /// Default: `"state/transcripts"`.
+        #[serde(default = "default_transcript_jsonl_dir")]
+        dir: String,
+        /// Size in MiB at which the current file is rotated. Default: `50`.
+        #[serde(default = "default_transcript_max_file_mb")]
+        max_file_mb: u64,
+    },
+    /// S3-compatible object storage; each batch is one JSONL object.
+    S3 {
+        /// Endpoint URL, e.g. `"https://s3.us-east-1.amazonaws.com"` or a
+        /// MinIO/R2 endpoint. Requests use path-style addressing.
+        endpoint: String,
+        bucket: String,
+        /// Signing region. Default: `"us-east-1"`.
+        #[serde(default = "default_transcript_s3_region")]
+        region: String,
+        /// Object key prefix. Default: `"zeroclaw/transcripts/"`.
+        #[serde(default = "default_transcript_s3_prefix")]
+        prefix: String,
+        access_key_id: String,
+        secret_access_key: String,
+    },
+    /// RFC 5424 syslog over a Unix socket, UDP or TCP.
+    Syslog {
+        /// `/dev/log`-style socket path, `udp://host:port` or `tcp://host:port`.
+        /// Default: `"/dev/log"`.
+        #[serde(default = "default_transcript_syslog_address")]
+        address: String,
+        /// Syslog facility (`user`, `local0`..`local7`). Default: `"local0"`.
+        #[serde(default = "default_transcript_syslog_facility")]
+        facility: String,
+        /// APP-NAME field. Default: `"zeroclaw"`.
+        #[serde(default = "default_transcript_syslog_app_name")]
+        app_name: String,
+    },
+}
+
+fn default_transcript_jsonl_dir() -> String {
+    "state/transcripts".into()
+}
+
+fn default_transcript_max_file_mb() -> u64 {
+    50
+}
+
+fn default_transcript_s3_region() -> String {
+    "us-east-1".into()
+}
+
+fn default_transcript_s3_prefix() -> String {
+    "zeroclaw/transcripts/".into()
+}
+
+fn default_transcript_syslog_address() -> String {
+    "/dev/log".into()
+}
+
+fn default_transcript_syslog_facility() -> String {
+    "local0".into()
+}
+
+fn default_transcript_syslog_app_name() -> String {
+    "zeroclaw".into()
+}
+
+impl DigestConfig {
+    /// Digest settings of `channel`, matched case-insensitively.
+    pub fn channel(&self, channel: &str) -> Option<&ChannelDigestConfig> {
+        self.channels
+            .iter()
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case(channel.trim()))
+            .map(|(_, config)| config)
+    }
+
+    /// Whether proactive output for `channel` should be queued for the digest.
+    pub fn applies_to(&self, channel: &str) -> bool {
+        self.enabled && self.channel(channel).is_some()
+    }
+}
+
+// ── Feeds ───────────────────────────────────────────────────────
 
-/// Cron job configuration (`[cron]` section).
+/// RSS/Atom feed watcher (`[feeds]` section).
+///
+/// When enabled, the daemon polls each source every `interval_minutes` and
+/// posts a digest of new items to the source's channel.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct CronConfig {
-    /// Enable the cron subsystem. Default: `true`.
-    #[serde(default = "default_true")]
+pub struct FeedsConfig {
+    /// Enable the feed watcher. Default: `false`.
+    #[serde(default)]
     pub enabled: bool,
-    /// Maximum number of historical cron run records to retain. Default: `50`.
-    #[serde(default = "default_max_run_history")]
-    pub max_run_history: u32,
+    /// Minutes between polls; each poll that finds new items posts a
+    /// digest. Default: `60`.
+    #[serde(default = "default_feeds_interval_minutes")]
+    pub interval_minutes: u32,
+    /// Have the default provider summarize each digest. Default: `false`.
+    #[serde(default)]
+    pub summarize: bool,
+    /// Maximum items listed in one digest; extras are summarized as a
+    /// count. Default: `20`.
+    #[serde(default = "default_feeds_max_items")]
+    pub max_items: usize,
+    /// Default delivery channel (e.g. `"telegram"`, or `"broadcast"` for a
+    /// broadcast group).
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Default recipient/chat identifier on `target`.
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Feeds to watch.
+    #[serde(default)]
+    pub sources: Vec<FeedSourceConfig>,
 }
 
-fn default_max_run_history() -> u32 {
-    50
+fn default_feeds_interval_minutes() -> u32 {
+    60
 }
 
-impl Default for CronConfig {
+fn default_feeds_max_items() -> usize {
+    20
+}
+
+impl Default for FeedsConfig {
     fn default() -> Self {
         Self {
-            enabled: true,
-            max_run_history: default_max_run_history(),
+            enabled: false,
+            interval_minutes: default_feeds_interval_minutes(),
+            summarize: false,
+            max_items: default_feeds_max_items(),
+            target: None,
+            to: None,
+            sources: Vec::new(),
         }
     }
 }
 
+/// One watched feed (`[[feeds.sources]]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeedSourceConfig {
+    /// RSS or Atom feed URL.
+    pub url: String,
+    /// Name shown in digests. Defaults to the feed's own title.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Delivery channel for this feed, overriding `[feeds].target`.
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Recipient for this feed, overriding `[feeds].to`.
+    #[serde(default)]
+    pub to: Option<String>,
+}
+
 // ── Tunnel ──────────────────────────────────────────────────────
 
 /// Tunnel configuration for exposing the gateway publicly (`[tunnel]` section).
@@ -2558,6 +3979,12 @@ pub struct ChannelsConfig {
     pub slack: Option<SlackConfig>,
     /// Mattermost bot channel configuration.
     pub mattermost: Option<MattermostConfig>,
+    /// Zulip bot channel configuration.
+    pub zulip: Option<ZulipConfig>,
+    /// Mastodon (Fediverse) bot account configuration.
+    pub mastodon: Option<MastodonConfig>,
+    /// GitHub issue / pull request comment channel configuration.
+    pub github: Option<GithubConfig>,
     /// Webhook channel configuration.
     pub webhook: Option<WebhookConfig>,
     /// iMessage channel configuration (macOS only).
@@ -2572,23 +3999,41 @@ pub struct ChannelsConfig {
     pub linq: Option<LinqConfig>,
     /// WATI WhatsApp Business API channel configuration.
     pub wati: Option<WatiConfig>,
+    /// Twilio SMS/MMS/WhatsApp channel configuration.
+    pub twilio: Option<TwilioConfig>,
+    /// Google Chat (Workspace) app channel configuration.
+    pub google_chat: Option<GoogleChatConfig>,
     /// Nextcloud Talk bot channel configuration.
     pub nextcloud_talk: Option<NextcloudTalkConfig>,
     /// Email channel configuration.
     pub email: Option<crate::channels::email_channel::EmailConfig>,
     /// IRC channel configuration.
     pub irc: Option<IrcConfig>,
+    /// XMPP channel configuration.
+    pub xmpp: Option<XmppConfig>,
     /// Lark channel configuration.
     pub lark: Option<LarkConfig>,
     /// Feishu channel configuration.
     pub feishu: Option<FeishuConfig>,
     /// DingTalk channel configuration.
     pub dingtalk: Option<DingTalkConfig>,
+    /// WeChat Work (WeCom) channel configuration.
+    pub wecom: Option<WecomConfig>,
     /// QQ Official Bot channel configuration.
     pub qq: Option<QQConfig>,
     pub nostr: Option<NostrConfig>,
     /// ClawdTalk voice channel configuration.
     pub clawdtalk: Option<crate::channels::clawdtalk::ClawdTalkConfig>,
+    /// Local Unix-socket input channel configuration (Unix only).
+    pub local_socket: Option<LocalSocketConfig>,
+    /// Generic HTTP API channel configuration (`POST /v1/message`).
+    pub http: Option<HttpChannelConfig>,
+    /// Browser chat channel configuration (web page + WebSocket).
+    pub web: Option<WebChannelConfig>,
+    /// External channels bridged in over the plugin protocol
+    /// (`[[channels_config.plugins]]`).
+    #[serde(default)]
+    pub plugins: Vec<PluginChannelConfig>,
     /// Base timeout in seconds for processing a single channel message (LLM + tools).
     /// Runtime uses this as a per-turn budget that scales with tool-loop depth
     /// (up to 4x, capped) so one slow/retried model call does not consume the
@@ -2596,6 +4041,491 @@ pub struct ChannelsConfig {
     /// Default: 300s for on-device LLMs (Ollama) which are slower than cloud APIs.
     #[serde(default = "default_channel_message_timeout_secs")]
     pub message_timeout_secs: u64,
+    /// Access control applied to every inbound channel message
+    /// (`[channels_config.acl]`).
+    #[serde(default)]
+    pub acl: ChannelAclConfig,
+    /// Relays from one chat to another (`[[channels_config.bridges]]`).
+    #[serde(default)]
+    pub bridges: Vec<BridgeConfig>,
+    /// Named destination sets for broadcast delivery
+    /// (`[channels_config.broadcast_groups]`). Each destination is
+    /// `"<channel>:<target>"`, e.g. `"telegram:-1001234567890"` or
+    /// `"email:ops@example.com"`. Deliver to a group with channel
+    /// `"broadcast"` and the group name as target.
+    #[serde(default)]
+    pub broadcast_groups: HashMap<String, Vec<String>>,
+    /// Gateway-hosted links for local attachments on channels that can only
+    /// show media by URL (`[channels_config.media_host]`).
+    #[serde(default)]
+    pub media_host: MediaHostConfig,
+    /// Settings of channels registered by downstream crates through
+    /// `channels::register_channel`, keyed by channel name
+    /// (`[channels_config.custom.<name>]`).
+    #[serde(default)]
+    pub custom: HashMap<String, serde_json::Value>,
+    /// Overrides of the delivery instructions for a channel or chat
+    /// (`[[channels_config.instructions]]`).
+    #[serde(default)]
+    pub instructions: Vec<ChannelInstructionsConfig>,
+    /// Recent chat messages fetched as context for group messages
+    /// (`[channels_config.room_context]`).
+    #[serde(default)]
+    pub room_context: RoomContextConfig,
+    /// Facts remembered per user and per chat
+    /// (`[channels_config.user_memory]`).
+    #[serde(default)]
+    pub user_memory: UserMemoryConfig,
+    /// Prefix of the built-in session commands (`/new`, `/model`, `/help`,
+    /// ...) per channel (`[channels_config.commands]`).
+    #[serde(default)]
+    pub commands: ChannelCommandsConfig,
+    /// Ordering of messages that arrive while the same conversation is
+    /// still being answered (`[channels_config.queue]`).
+    #[serde(default)]
+    pub queue: ChannelQueueConfig,
+    /// Accounts on different channels that belong to the same person
+    /// (`[[channels_config.identities]]`).
+    #[serde(default)]
+    pub identities: Vec<UserIdentityConfig>,
+    /// Named persona profiles, keyed by name
+    /// (`[channels_config.personas.<name>]`).
+    #[serde(default)]
+    pub personas: HashMap<String, PersonaConfig>,
+    /// Content rules for inbound messages and outbound replies
+    /// (`[channels_config.moderation]`).
+    #[serde(default)]
+    pub moderation: ModerationConfig,
+}
+
+/// Delivery instructions override (`[[channels_config.instructions]]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ChannelInstructionsConfig {
+    /// `"<channel>"` for every chat of a channel, or `"<channel>:<chat>"`
+    /// for one chat (its threads and topics included).
+    pub channel: String,
+    /// Text used instead of the built-in instructions.
+    #[serde(default)]
+    pub replace: Option<String>,
+    /// Text added after the built-in (or replaced) instructions.
+    #[serde(default)]
+    pub append: Option<String>,
+}
+
+/// Recent group chat messages included as context
+/// (`[channels_config.room_context]`).
+///
+/// Before answering a message in a group chat, the last `messages` messages
+/// posted there are fetched through the platform's history API, so the
+/// agent can summarize or refer to a discussion it was not part of.
+/// Disabled while `messages` is `0`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RoomContextConfig {
+    /// Messages fetched before the one being answered. Default: `0` (off).
+    #[serde(default)]
+    pub messages: usize,
+    /// Longer messages are truncated to this many characters. Default: `500`.
+    #[serde(default = "default_room_context_max_chars")]
+    pub max_chars_per_message: usize,
+    /// Channels that fetch context; empty means every channel with a
+    /// history API (`slack`, `discord`). Default: `[]`.
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+fn default_room_context_max_chars() -> usize {
+    500
+}
+
+impl Default for RoomContextConfig {
+    fn default() -> Self {
+        Self {
+            messages: 0,
+            max_chars_per_message: default_room_context_max_chars(),
+            channels: Vec::new(),
+        }
+    }
+}
+
+/// A persona the agent can take on (`[channels_config.personas.<name>]`).
+///
+/// A persona applies to the chats listed in `channels`, or to a conversation
+/// after `/persona <name>`. Unset fields keep the runtime defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PersonaConfig {
+    /// Instructions added to the system prompt, e.g. who the persona is and
+    /// how it talks.
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Tools the persona may use. Default: all tools.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+    /// Provider used while the persona is active.
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model used while the persona is active.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Sampling temperature used while the persona is active.
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// Language the persona always replies in, e.g. `"German"`.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Turn citation mode on or off while the persona is active, overriding
+    /// `[agent.citations].enabled`.
+    #[serde(default)]
+    pub citations: Option<bool>,
+    /// Chats that use this persona by default: `"<channel>"` for every chat
+    /// of a channel, or `"<channel>:<chat>"` for one chat.
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+/// Long-term facts about the people the agent talks to
+/// (`[channels_config.user_memory]`).
+///
+/// When enabled, the `remember`, `recall` and `forget` tools store facts
+/// (names, preferences, standing instructions) for the sender or the whole
+/// chat in `{workspace}/state/user_memory.db`, and the facts most relevant to
+/// each message are added to the system prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct UserMemoryConfig {
+    /// Register the per-user memory tools. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Most facts kept per user or chat. Default: `100`.
+    #[serde(default = "default_user_memory_max_facts")]
+    pub max_facts: usize,
+    /// Most facts added to the system prompt per message. Default: `10`.
+    #[serde(default = "default_user_memory_max_context_facts")]
+    pub max_context_facts: usize,
+    /// Longest fact accepted by `remember`, in characters. Default: `500`.
+    #[serde(default = "default_user_memory_max_fact_chars")]
+    pub max_fact_chars: usize,
+}
+
+fn default_user_memory_max_facts() -> usize {
+    100
+}
+
+fn default_user_memory_max_context_facts() -> usize {
+    10
+}
+
+fn default_user_memory_max_fact_chars() -> usize {
+    500
+}
+
+impl Default for UserMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_facts: default_user_memory_max_facts(),
+            max_context_facts: default_user_memory_max_context_facts(),
+            max_fact_chars: default_user_memory_max_fact_chars(),
+        }
+    }
+}
+
+/// Built-in session commands (`[channels_config.commands]`).
+///
+/// Messages starting with the prefix followed by a known command (`new`,
+/// `reset`, `history`, `model`, `models`, `usage`, `stats`, `help`, ...) are
+/// answered by the runtime instead of the model, on every channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelCommandsConfig {
+    /// Prefix used on channels without an entry in `prefixes`. Default: `"/"`.
+    #[serde(default = "default_channel_command_prefix")]
+    pub prefix: String,
+    /// Prefix per channel name, e.g. `slack = "!"` where the platform
+    /// claims `/` for its own slash commands. An empty prefix turns the
+    /// commands off for that channel.
+    #[serde(default)]
+    pub prefixes: HashMap<String, String>,
+}
+
+fn default_channel_command_prefix() -> String {
+    "/".into()
+}
+
+impl Default for ChannelCommandsConfig {
+    fn default() -> Self {
+        Self {
+            prefix: default_channel_command_prefix(),
+            prefixes: HashMap::new(),
+        }
+    }
+}
+
+impl ChannelCommandsConfig {
+    /// Prefix for `channel`, or `None` when commands are off there.
+    pub fn prefix_for(&self, channel: &str) -> Option<&str> {
+        let prefix = self
+            .prefixes
+            .get(channel)
+            .map_or(self.prefix.as_str(), String::as_str)
+            .trim();
+        (!prefix.is_empty()).then_some(prefix)
+    }
+}
+
+/// What happens to a message the moderation stage flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationAction {
+    /// Do not check messages in this direction.
+    Off,
+    /// Deliver unchanged and log the match.
+    Log,
+    /// Deliver, and tell the sender it was flagged.
+    Warn,
+    /// Refuse the message (inbound) or replace the reply (outbound).
+    Block,
+}
+
+/// Moderation actions for one channel; unset directions use the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelModerationConfig {
+    #[serde(default)]
+    pub inbound: Option<ModerationAction>,
+    #[serde(default)]
+    pub outbound: Option<ModerationAction>,
+}
+
+/// Content moderation (`[channels_config.moderation]`).
+///
+/// Inbound messages and outbound replies are checked against keyword rules
+/// and, when `api_key` is set, an OpenAI-compatible moderation endpoint.
+/// Nothing is checked until at least one rule or the API is configured.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ModerationConfig {
+    /// Words or phrases, matched case-insensitively on word boundaries.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Regular expressions, matched anywhere in the text.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// API key for the moderation endpoint. Unset: keyword rules only.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// OpenAI-compatible `/moderations` endpoint.
+    #[serde(default = "default_moderation_api_url")]
+    pub api_url: String,
+    /// Model sent to the moderation endpoint.
+    #[serde(default = "default_moderation_model")]
+    pub model: String,
+    /// Timeout of one moderation API call. Messages pass when it expires.
+    #[serde(default = "default_moderation_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Action for flagged inbound messages. Default: `block`.
+    #[serde(default = "default_moderation_action")]
+    pub inbound: ModerationAction,
+    /// Action for flagged outbound replies. Default: `block`.
+    #[serde(default = "default_moderation_action")]
+    pub outbound: ModerationAction,
+    /// Per-channel overrides, keyed by channel name
+    /// (`[channels_config.moderation.channels.<name>]`).
+    #[serde(default)]
+    pub channels: HashMap<String, ChannelModerationConfig>,
+    /// Sent instead of a blocked message or reply.
+    #[serde(default = "default_moderation_block_message")]
+    pub block_message: String,
+    /// Sent with a flagged message or reply under `warn`.
+    #[serde(default = "default_moderation_warn_message")]
+    pub warn_message: String,
+}
+
+fn default_moderation_api_url() -> String {
+    "https://api.openai.com/v1/moderations".into()
+}
+
+fn default_moderation_model() -> String {
+    "omni-moderation-latest".into()
+}
+
+fn default_moderation_timeout_secs() -> u64 {
+    10
+}
+
+fn default_moderation_action() -> ModerationAction {
+    ModerationAction::Block
+}
+
+fn default_moderation_block_message() -> String {
+    "⚠️ This message was blocked by the content policy.".into()
+}
+
+fn default_moderation_warn_message() -> String {
+    "⚠️ This message may violate the content policy.".into()
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            keywords: Vec::new(),
+            patterns: Vec::new(),
+            api_key: None,
+            api_url: default_moderation_api_url(),
+            model: default_moderation_model(),
+            timeout_secs: default_moderation_timeout_secs(),
+            inbound: default_moderation_action(),
+            outbound: default_moderation_action(),
+            channels: HashMap::new(),
+            block_message: default_moderation_block_message(),
+            warn_message: default_moderation_warn_message(),
+        }
+    }
+}
+
+impl ModerationConfig {
+    /// Whether any rule or the API is configured.
+    pub fn is_active(&self) -> bool {
+        !self.keywords.is_empty()
+            || !self.patterns.is_empty()
+            || self
+                .api_key
+                .as_deref()
+                .is_some_and(|key| !key.trim().is_empty())
+    }
+}
+
+/// Per-conversation message queue (`[channels_config.queue]`).
+///
+/// Messages of one conversation are answered one after another, so rapid
+/// follow-ups neither race nor interleave; different conversations still
+/// run concurrently. With `merge_window_ms`, messages sent in quick
+/// succession are answered together as one prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelQueueConfig {
+    /// Answer one message per conversation at a time. Default: `true`.
+    #[serde(default = "default_true")]
+    pub sequential: bool,
+    /// Wait this long for follow-up messages before answering, and merge
+    /// everything queued into one prompt. Default: `0` (no merging).
+    #[serde(default)]
+    pub merge_window_ms: u64,
+}
+
+impl Default for ChannelQueueConfig {
+    fn default() -> Self {
+        Self {
+            sequential: true,
+            merge_window_ms: 0,
+        }
+    }
+}
+
+/// Short-lived gateway links for local attachments
+/// (`[channels_config.media_host]`).
+///
+/// Local `[IMAGE:/path]`-style markers in replies to `channels` are served
+/// from the gateway at `<public_url>/media/<token>/<name>` until the token
+/// expires. Disabled while `public_url` is unset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MediaHostConfig {
+    /// Externally reachable gateway base URL, e.g. `"https://agent.example.com"`.
+    #[serde(default)]
+    pub public_url: Option<String>,
+    /// Seconds a hosted link stays valid. Default: `3600`.
+    #[serde(default = "default_media_host_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Channels whose local attachments are hosted.
+    /// Default: `["google_chat", "dingtalk", "twilio"]`.
+    #[serde(default = "default_media_host_channels")]
+    pub channels: Vec<String>,
+}
+
+fn default_media_host_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_media_host_channels() -> Vec<String> {
+    vec!["google_chat".into(), "dingtalk".into(), "twilio".into()]
+}
+
+impl Default for MediaHostConfig {
+    fn default() -> Self {
+        Self {
+            public_url: None,
+            ttl_secs: default_media_host_ttl_secs(),
+            channels: default_media_host_channels(),
+        }
+    }
+}
+
+/// Access control lists checked before a channel message reaches the agent
+/// (`[channels_config.acl]`).
+///
+/// User entries are `"<user>"` (any channel), `"<channel>:<user>"`,
+/// `"<channel>:*"` or `"role:<name>"`; chat entries are `"<reply_target>"`
+/// or `"<channel>:<reply_target>"`, and also cover threads and topics in
+/// that chat. Deny lists win over allow lists, and an empty allow list
+/// allows everyone. Each channel's own `allowed_users` still applies first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelAclConfig {
+    /// When non-empty, only these users are answered.
+    #[serde(default)]
+    pub allow_users: Vec<String>,
+    /// Users who are never answered.
+    #[serde(default)]
+    pub deny_users: Vec<String>,
+    /// When non-empty, only these chats are answered.
+    #[serde(default)]
+    pub allow_chats: Vec<String>,
+    /// Chats that are never answered.
+    #[serde(default)]
+    pub deny_chats: Vec<String>,
+    /// Named user groups usable as `"role:<name>"` in the user lists.
+    #[serde(default)]
+    pub roles: HashMap<String, Vec<String>>,
+}
+
+impl ChannelAclConfig {
+    /// Whether any list is configured.
+    pub fn is_active(&self) -> bool {
+        !(self.allow_users.is_empty()
+            && self.deny_users.is_empty()
+            && self.allow_chats.is_empty()
+            && self.deny_chats.is_empty())
+    }
+}
+
+/// One person's accounts across channels (`[[channels_config.identities]]`).
+///
+/// Linked accounts share remembered facts and usage totals, and an access
+/// list entry for one of them applies to all of them.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct UserIdentityConfig {
+    /// Name of the person, usable as `"identity:<name>"` in
+    /// `[channels_config.acl]` user lists.
+    pub name: String,
+    /// Accounts as `"<channel>:<user id>"`, e.g. `"telegram:123456789"` or
+    /// `"slack:U024BE7LH"`.
+    #[serde(default)]
+    pub accounts: Vec<String>,
+}
+
+/// Relay of inbound messages from one chat to another
+/// (`[[channels_config.bridges]]`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct BridgeConfig {
+    /// Source: `"<channel>"` for every chat of a channel, or
+    /// `"<channel>:<chat>"` for one chat (its threads and topics included).
+    pub from: String,
+    /// Target channel name, e.g. `"discord"`.
+    pub to_channel: String,
+    /// Target recipient on `to_channel` (chat, room or channel ID).
+    pub to: String,
+    /// Optional instructions for passing each message through the provider
+    /// before relaying it, e.g. `"Translate to English"`.
+    #[serde(default)]
+    pub transform: Option<String>,
+    /// Whether the agent still answers bridged messages in the source chat.
+    #[serde(default)]
+    pub answer: bool,
 }
 
 impl ChannelsConfig {
@@ -2619,6 +4549,18 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(&self.mattermost)),
                 self.mattermost.is_some(),
             ),
+            (
+                Box::new(ConfigWrapper::new(&self.zulip)),
+                self.zulip.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(&self.mastodon)),
+                self.mastodon.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(&self.github)),
+                self.github.is_some(),
+            ),
             (
                 Box::new(ConfigWrapper::new(&self.imessage)),
                 self.imessage.is_some(),
@@ -2643,6 +4585,14 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(&self.wati)),
                 self.wati.is_some(),
             ),
+            (
+                Box::new(ConfigWrapper::new(&self.twilio)),
+                self.twilio.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(&self.google_chat)),
+                self.google_chat.is_some(),
+            ),
             (
                 Box::new(ConfigWrapper::new(&self.nextcloud_talk)),
                 self.nextcloud_talk.is_some(),
@@ -2655,6 +4605,10 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(&self.irc)),
                 self.irc.is_some()
             ),
+            (
+                Box::new(ConfigWrapper::new(&self.xmpp)),
+                self.xmpp.is_some(),
+            ),
             (
                 Box::new(ConfigWrapper::new(&self.lark)),
                 self.lark.is_some(),
@@ -2667,6 +4621,10 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(&self.dingtalk)),
                 self.dingtalk.is_some(),
             ),
+            (
+                Box::new(ConfigWrapper::new(&self.wecom)),
+                self.wecom.is_some(),
+            ),
             (
                 Box::new(ConfigWrapper::new(&self.qq)),
                 self.qq.is_some()
@@ -2679,16 +4637,272 @@ impl ChannelsConfig {
                 Box::new(ConfigWrapper::new(&self.clawdtalk)),
                 self.clawdtalk.is_some(),
             ),
+            (
+                Box::new(ConfigWrapper::new(&self.local_socket)),
+                self.local_socket.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(&self.http)),
+                self.http.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::new(&self.web)),
+                self.web.is_some(),
+            ),
+            (
+                Box::new(ConfigWrapper::<PluginChannelConfig>(std::marker::PhantomData)),
+                !self.plugins.is_empty(),
+            ),
         ]
     }
 
-    pub fn channels(&self) -> Vec<(Box<dyn super::traits::ConfigHandle>, bool)> {
-        let mut ret = self.channels_except_webhook();
-        ret.push((
-            Box::new(ConfigWrapper::new(&self.webhook)),
-            self.webhook.is_some(),
-        ));
-        ret
+    pub fn channels(&self) -> Vec<(Box<dyn super::traits::ConfigHandle>, bool)> {
+        let mut ret = self.channels_except_webhook();
+        ret.push((
+            Box::new(ConfigWrapper::new(&self.webhook)),
+            self.webhook.is_some(),
+        ));
+        ret
+    }
+
+    /// Channel tokens that may hold a secret reference, by field name.
+    pub fn tokens_mut(&mut self) -> Vec<(&'static str, &mut String)> {
+        let mut tokens = Vec::new();
+        if let Some(tg) = self.telegram.as_mut() {
+            tokens.push((
+                "config.channels_config.telegram.bot_token",
+                &mut tg.bot_token,
+            ));
+        }
+        if let Some(dc) = self.discord.as_mut() {
+            tokens.push((
+                "config.channels_config.discord.bot_token",
+                &mut dc.bot_token,
+            ));
+        }
+        if let Some(sl) = self.slack.as_mut() {
+            tokens.push(("config.channels_config.slack.bot_token", &mut sl.bot_token));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.slack.app_token",
+                &mut sl.app_token,
+            );
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.slack.signing_secret",
+                &mut sl.signing_secret,
+            );
+        }
+        if let Some(mm) = self.mattermost.as_mut() {
+            tokens.push((
+                "config.channels_config.mattermost.bot_token",
+                &mut mm.bot_token,
+            ));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.mattermost.command_token",
+                &mut mm.command_token,
+            );
+        }
+        if let Some(zl) = self.zulip.as_mut() {
+            tokens.push(("config.channels_config.zulip.api_key", &mut zl.api_key));
+        }
+        if let Some(md) = self.mastodon.as_mut() {
+            tokens.push((
+                "config.channels_config.mastodon.access_token",
+                &mut md.access_token,
+            ));
+        }
+        if let Some(gh) = self.github.as_mut() {
+            tokens.push(("config.channels_config.github.token", &mut gh.token));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.github.webhook_secret",
+                &mut gh.webhook_secret,
+            );
+        }
+        if let Some(wh) = self.webhook.as_mut() {
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.webhook.secret",
+                &mut wh.secret,
+            );
+        }
+        if let Some(mx) = self.matrix.as_mut() {
+            tokens.push((
+                "config.channels_config.matrix.access_token",
+                &mut mx.access_token,
+            ));
+        }
+        if let Some(wa) = self.whatsapp.as_mut() {
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.whatsapp.access_token",
+                &mut wa.access_token,
+            );
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.whatsapp.verify_token",
+                &mut wa.verify_token,
+            );
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.whatsapp.app_secret",
+                &mut wa.app_secret,
+            );
+        }
+        if let Some(lq) = self.linq.as_mut() {
+            tokens.push(("config.channels_config.linq.api_token", &mut lq.api_token));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.linq.signing_secret",
+                &mut lq.signing_secret,
+            );
+        }
+        if let Some(wt) = self.wati.as_mut() {
+            tokens.push(("config.channels_config.wati.api_token", &mut wt.api_token));
+        }
+        if let Some(tw) = self.twilio.as_mut() {
+            tokens.push((
+                "config.channels_config.twilio.auth_token",
+                &mut tw.auth_token,
+            ));
+        }
+        if let Some(gc) = self.google_chat.as_mut() {
+            tokens.push((
+                "config.channels_config.google_chat.service_account_key",
+                &mut gc.service_account_key,
+            ));
+        }
+        if let Some(nc) = self.nextcloud_talk.as_mut() {
+            tokens.push((
+                "config.channels_config.nextcloud_talk.app_token",
+                &mut nc.app_token,
+            ));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.nextcloud_talk.webhook_secret",
+                &mut nc.webhook_secret,
+            );
+        }
+        if let Some(em) = self.email.as_mut() {
+            tokens.push(("config.channels_config.email.password", &mut em.password));
+        }
+        if let Some(irc) = self.irc.as_mut() {
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.irc.server_password",
+                &mut irc.server_password,
+            );
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.irc.nickserv_password",
+                &mut irc.nickserv_password,
+            );
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.irc.sasl_password",
+                &mut irc.sasl_password,
+            );
+        }
+        if let Some(xm) = self.xmpp.as_mut() {
+            tokens.push(("config.channels_config.xmpp.password", &mut xm.password));
+        }
+        if let Some(lk) = self.lark.as_mut() {
+            tokens.push(("config.channels_config.lark.app_secret", &mut lk.app_secret));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.lark.encrypt_key",
+                &mut lk.encrypt_key,
+            );
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.lark.verification_token",
+                &mut lk.verification_token,
+            );
+        }
+        if let Some(fs) = self.feishu.as_mut() {
+            tokens.push((
+                "config.channels_config.feishu.app_secret",
+                &mut fs.app_secret,
+            ));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.feishu.encrypt_key",
+                &mut fs.encrypt_key,
+            );
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.feishu.verification_token",
+                &mut fs.verification_token,
+            );
+        }
+        if let Some(dt) = self.dingtalk.as_mut() {
+            tokens.push((
+                "config.channels_config.dingtalk.client_secret",
+                &mut dt.client_secret,
+            ));
+        }
+        if let Some(wc) = self.wecom.as_mut() {
+            tokens.push((
+                "config.channels_config.wecom.corp_secret",
+                &mut wc.corp_secret,
+            ));
+            tokens.push(("config.channels_config.wecom.token", &mut wc.token));
+            tokens.push((
+                "config.channels_config.wecom.encoding_aes_key",
+                &mut wc.encoding_aes_key,
+            ));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.wecom.robot_key",
+                &mut wc.robot_key,
+            );
+        }
+        if let Some(qq) = self.qq.as_mut() {
+            tokens.push(("config.channels_config.qq.app_secret", &mut qq.app_secret));
+        }
+        if let Some(ct) = self.clawdtalk.as_mut() {
+            tokens.push(("config.channels_config.clawdtalk.api_key", &mut ct.api_key));
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.clawdtalk.webhook_secret",
+                &mut ct.webhook_secret,
+            );
+        }
+        if let Some(http) = self.http.as_mut() {
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.http.bearer_token",
+                &mut http.bearer_token,
+            );
+        }
+        if let Some(web) = self.web.as_mut() {
+            push_optional_token(
+                &mut tokens,
+                "config.channels_config.web.auth_token",
+                &mut web.auth_token,
+            );
+        }
+        for plugin in &mut self.plugins {
+            tokens.extend(
+                plugin
+                    .env
+                    .values_mut()
+                    .map(|value| ("config.channels_config.plugins.*.env", value)),
+            );
+        }
+        tokens
+    }
+}
+
+fn push_optional_token<'a>(
+    tokens: &mut Vec<(&'static str, &'a mut String)>,
+    field_name: &'static str,
+    value: &'a mut Option<String>,
+) {
+    if let Some(value) = value.as_mut() {
+        tokens.push((field_name, value));
     }
 }
 
@@ -2704,6 +4918,9 @@ impl Default for ChannelsConfig {
             discord: None,
             slack: None,
             mattermost: None,
+            zulip: None,
+            mastodon: None,
+            github: None,
             webhook: None,
             imessage: None,
             matrix: None,
@@ -2711,16 +4928,37 @@ impl Default for ChannelsConfig {
             whatsapp: None,
             linq: None,
             wati: None,
+            twilio: None,
+            google_chat: None,
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
             lark: None,
             feishu: None,
             dingtalk: None,
+            wecom: None,
             qq: None,
             nostr: None,
             clawdtalk: None,
+            local_socket: None,
+            http: None,
+            web: None,
+            plugins: Vec::new(),
             message_timeout_secs: default_channel_message_timeout_secs(),
+            acl: ChannelAclConfig::default(),
+            bridges: Vec::new(),
+            broadcast_groups: HashMap::new(),
+            media_host: MediaHostConfig::default(),
+            custom: HashMap::new(),
+            instructions: Vec::new(),
+            room_context: RoomContextConfig::default(),
+            user_memory: UserMemoryConfig::default(),
+            commands: ChannelCommandsConfig::default(),
+            queue: ChannelQueueConfig::default(),
+            identities: Vec::new(),
+            personas: HashMap::new(),
+            moderation: ModerationConfig::default(),
         }
     }
 }
@@ -2761,6 +4999,11 @@ pub struct TelegramConfig {
     /// Direct messages are always processed.
     #[serde(default)]
     pub mention_only: bool,
+    /// Command prefix (e.g. `"!claw"`) that triggers a reply in groups. When
+    /// set, group messages without it are ignored unless `mention_only`
+    /// accepts them. Direct messages are always processed.
+    #[serde(default)]
+    pub trigger_prefix: Option<String>,
 }
 
 impl ChannelConfig for TelegramConfig {
@@ -2774,6 +5017,7 @@ impl ChannelConfig for TelegramConfig {
 
 /// Discord bot channel configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct DiscordConfig {
     /// Discord bot token (from Discord Developer Portal).
     pub bot_token: String,
@@ -2790,6 +5034,32 @@ pub struct DiscordConfig {
     /// Other messages in the guild are silently ignored.
     #[serde(default)]
     pub mention_only: bool,
+    /// Command prefix (e.g. `"!claw"`) that triggers a reply in guild
+    /// channels. When set, guild messages without it are ignored unless
+    /// `mention_only` accepts them. Direct messages are always processed.
+    #[serde(default)]
+    pub trigger_prefix: Option<String>,
+    /// When true, guild messages outside a thread start a new thread on the
+    /// original message and the reply is posted there. Messages already in a
+    /// thread are always answered in that thread.
+    #[serde(default)]
+    pub thread_replies: bool,
+    /// When true, register the `/ask`, `/reset` and `/model` slash commands
+    /// (in `guild_id` when set, globally otherwise) and answer them.
+    #[serde(default = "default_true")]
+    pub slash_commands: bool,
+    /// Streaming mode for progressive response delivery via message edits.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft message edits to avoid rate limits.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
+    /// When true, `/voice join` makes the bot join the caller's voice
+    /// channel, transcribe what allowed users say and speak its replies.
+    /// Needs the `channel-discord-voice` build feature and `[transcription]`;
+    /// replies are spoken when `[tts]` is enabled.
+    #[serde(default)]
+    pub voice: bool,
 }
 
 impl ChannelConfig for DiscordConfig {
@@ -2814,6 +5084,29 @@ pub struct SlackConfig {
     /// Allowed Slack user IDs. Empty = deny all.
     #[serde(default)]
     pub allowed_users: Vec<String>,
+    /// When true, only respond to channel messages that @-mention the bot.
+    /// Direct messages are always processed.
+    #[serde(default)]
+    pub mention_only: bool,
+    /// Command prefix (e.g. `"!claw"`) that triggers a reply in channels.
+    /// When set, channel messages without it are ignored unless
+    /// `mention_only` accepts them. Direct messages are always processed.
+    #[serde(default)]
+    pub trigger_prefix: Option<String>,
+    /// When true (default), replies thread on the original message.
+    /// When false, top-level messages are answered in the channel.
+    #[serde(default)]
+    pub thread_replies: Option<bool>,
+    /// App signing secret. When set, the gateway answers slash commands
+    /// posted to `/slack/commands`.
+    #[serde(default)]
+    pub signing_secret: Option<String>,
+    /// Streaming mode for progressive response delivery via message edits.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft message edits to avoid rate limits.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
 }
 
 impl ChannelConfig for SlackConfig {
@@ -2845,6 +5138,21 @@ pub struct MattermostConfig {
     /// Other messages in the channel are silently ignored.
     #[serde(default)]
     pub mention_only: Option<bool>,
+    /// Command prefix (e.g. `"!claw"`) that triggers a reply in channels.
+    /// When set, channel messages without it are ignored unless
+    /// `mention_only` accepts them. Direct messages are always processed.
+    #[serde(default)]
+    pub trigger_prefix: Option<String>,
+    /// Token of a custom slash command. When set, the gateway answers
+    /// slash commands posted to `/mattermost/commands`.
+    #[serde(default)]
+    pub command_token: Option<String>,
+    /// Streaming mode for progressive response delivery via message edits.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft message edits to avoid rate limits.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
 }
 
 impl ChannelConfig for MattermostConfig {
@@ -2856,6 +5164,102 @@ impl ChannelConfig for MattermostConfig {
     }
 }
 
+/// Zulip bot configuration (real-time events API + REST send).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ZulipConfig {
+    /// Zulip organization URL (e.g. `"https://chat.example.com"`).
+    pub site: String,
+    /// Bot email address (API basic-auth username).
+    pub email: String,
+    /// Bot API key.
+    pub api_key: String,
+    /// Allowed sender emails. Empty = deny all, "*" = allow all.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// When true, stream messages are ignored unless they @-mention the bot.
+    /// Direct messages are always processed.
+    #[serde(default)]
+    pub mention_only: Option<bool>,
+    /// Topic used when a `stream:<name>` recipient names no topic.
+    /// Default: `"zeroclaw"`.
+    #[serde(default)]
+    pub default_topic: Option<String>,
+}
+
+impl ChannelConfig for ZulipConfig {
+    fn name() -> &'static str {
+        "Zulip"
+    }
+    fn desc() -> &'static str {
+        "streams, topics and DMs via bot API"
+    }
+}
+
+/// Mastodon bot account configuration (notifications polling + statuses API).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MastodonConfig {
+    /// Instance URL (e.g. `"https://mastodon.social"`).
+    pub instance_url: String,
+    /// Access token of the bot account (scopes `read` and `write`).
+    pub access_token: String,
+    /// Allowed accounts as `user@instance` (`user` for local accounts).
+    /// Empty = deny all, "*" = allow all.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Characters per toot; longer replies continue in a thread.
+    /// Default: `500`.
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+    /// Seconds between checks for new mentions. Default: `15`.
+    #[serde(default)]
+    pub poll_interval_secs: Option<u64>,
+}
+
+impl ChannelConfig for MastodonConfig {
+    fn name() -> &'static str {
+        "Mastodon"
+    }
+    fn desc() -> &'static str {
+        "mentions and DMs of a Fediverse account"
+    }
+}
+
+/// GitHub comment channel configuration (webhook receive + REST send).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GithubConfig {
+    /// Token used to post comments (fine-grained token or GitHub App
+    /// installation token with issues and pull requests write access).
+    pub token: String,
+    /// Login the agent answers to when @-mentioned (e.g. `"zeroclaw-bot"`).
+    pub bot_login: String,
+    /// Secret for webhook signature verification (`X-Hub-Signature-256`).
+    ///
+    /// Can also be set via `ZEROCLAW_GITHUB_WEBHOOK_SECRET`.
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+    /// Allowed GitHub logins (`[]` = deny all, `"*"` = allow all).
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Repositories (`owner/name`) to answer in. Empty = every repository
+    /// that delivers webhooks.
+    #[serde(default)]
+    pub allowed_repos: Vec<String>,
+    /// REST API base URL for GitHub Enterprise Server
+    /// (e.g. `"https://github.example.com/api/v3"`).
+    /// Default: `"https://api.github.com"`.
+    #[serde(default)]
+    pub api_url: Option<String>,
+}
+
+impl ChannelConfig for GithubConfig {
+    fn name() -> &'static str {
+        "GitHub"
+    }
+    fn desc() -> &'static str {
+        "issue and pull request comment mentions"
+    }
+}
+
 /// Webhook channel configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WebhookConfig {
@@ -2890,6 +5294,147 @@ impl ChannelConfig for IMessageConfig {
     }
 }
 
+fn default_local_socket_path() -> String {
+    "~/.zeroclaw/inbox.sock".into()
+}
+
+/// Local Unix-socket input channel configuration (Unix only).
+///
+/// Other processes on the host write newline-delimited events to the socket;
+/// each line is routed to the agent like any other inbound message.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LocalSocketConfig {
+    /// Filesystem path of the listening socket. `~` is expanded.
+    /// Default: `~/.zeroclaw/inbox.sock`.
+    #[serde(default = "default_local_socket_path")]
+    pub path: String,
+}
+
+impl ChannelConfig for LocalSocketConfig {
+    fn name() -> &'static str {
+        "Local Socket"
+    }
+    fn desc() -> &'static str {
+        "Unix socket for host-local events"
+    }
+}
+
+fn default_http_channel_host() -> String {
+    "127.0.0.1".into()
+}
+
+fn default_http_channel_port() -> u16 {
+    8090
+}
+
+fn default_http_channel_reply_timeout_secs() -> u64 {
+    600
+}
+
+/// Generic HTTP API channel configuration.
+///
+/// Runs its own HTTP server; `POST /v1/message` returns the agent's reply as
+/// JSON with attachments resolved to URLs.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HttpChannelConfig {
+    /// Bind address. Default: `"127.0.0.1"`. Non-loopback addresses require
+    /// `bearer_token`.
+    #[serde(default = "default_http_channel_host")]
+    pub host: String,
+    /// Listen port. Default: `8090`.
+    #[serde(default = "default_http_channel_port")]
+    pub port: u16,
+    /// Token required as `Authorization: Bearer <token>`. Unset = no auth.
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    /// Externally reachable base URL used for attachment links
+    /// (e.g. `"https://agent.example.com"`). Defaults to `http://host:port`.
+    #[serde(default)]
+    pub public_url: Option<String>,
+    /// Seconds a request waits for the agent's reply. Default: `600`.
+    #[serde(default = "default_http_channel_reply_timeout_secs")]
+    pub reply_timeout_secs: u64,
+}
+
+impl ChannelConfig for HttpChannelConfig {
+    fn name() -> &'static str {
+        "HTTP API"
+    }
+    fn desc() -> &'static str {
+        "JSON request/response over HTTP"
+    }
+}
+
+fn default_web_channel_host() -> String {
+    "127.0.0.1".into()
+}
+
+fn default_web_channel_port() -> u16 {
+    8091
+}
+
+/// Browser chat channel configuration.
+///
+/// Serves a minimal chat page at `/` and streams replies over a WebSocket at
+/// `/ws`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebChannelConfig {
+    /// Bind address. Default: `"127.0.0.1"`. Non-loopback addresses require
+    /// `auth_token`.
+    #[serde(default = "default_web_channel_host")]
+    pub host: String,
+    /// Listen port. Default: `8091`.
+    #[serde(default = "default_web_channel_port")]
+    pub port: u16,
+    /// Token the page must pass as `?token=<token>`. Unset = a random token
+    /// is generated at startup and logged with the page link.
+    #[serde(default)]
+    pub auth_token: Option<String>,
+}
+
+impl ChannelConfig for WebChannelConfig {
+    fn name() -> &'static str {
+        "Web"
+    }
+    fn desc() -> &'static str {
+        "Chat from a browser"
+    }
+}
+
+/// Plugin channel: an external process speaking the JSON-lines bridge
+/// protocol, either spawned by ZeroClaw or listening on a Unix socket.
+/// Set exactly one of `command` or `socket`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PluginChannelConfig {
+    /// Channel name used for routing and logs (lowercase letters, digits,
+    /// `-`, `_`; must not clash with a built-in channel).
+    pub name: String,
+    /// Executable to spawn; the protocol runs over its stdin/stdout.
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments passed to `command`.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Extra environment variables for `command` (e.g. platform tokens).
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Unix socket path of an already-running plugin. `~` is expanded.
+    #[serde(default)]
+    pub socket: Option<String>,
+    /// Allowed sender IDs as reported by the plugin, or "*" for all.
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+}
+
+impl ChannelConfig for PluginChannelConfig {
+    fn name() -> &'static str {
+        "Plugin"
+    }
+    fn desc() -> &'static str {
+        "External channel via subprocess bridge"
+    }
+}
+
 /// Matrix channel configuration.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct MatrixConfig {
@@ -2907,6 +5452,12 @@ pub struct MatrixConfig {
     pub room_id: String,
     /// Allowed Matrix user IDs. Empty = deny all.
     pub allowed_users: Vec<String>,
+    /// Streaming mode for progressive response delivery via message edits.
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Minimum interval (ms) between draft message edits to avoid rate limits.
+    #[serde(default = "default_draft_update_interval_ms")]
+    pub draft_update_interval_ms: u64,
 }
 
 impl ChannelConfig for MatrixConfig {
@@ -2939,6 +5490,15 @@ pub struct SignalConfig {
     /// Skip incoming story messages.
     #[serde(default)]
     pub ignore_stories: bool,
+    /// When true, only respond to group messages that mention the account.
+    /// Direct messages are always processed.
+    #[serde(default)]
+    pub mention_only: bool,
+    /// Command prefix (e.g. `"!claw"`) that triggers a reply in groups. When
+    /// set, group messages without it are ignored unless `mention_only`
+    /// accepts them. Direct messages are always processed.
+    #[serde(default)]
+    pub trigger_prefix: Option<String>,
 }
 
 impl ChannelConfig for SignalConfig {
@@ -3008,44 +5568,98 @@ pub struct LinqConfig {
     pub signing_secret: Option<String>,
     /// Allowed sender handles (phone numbers) or "*" for all
     #[serde(default)]
-    pub allowed_senders: Vec<String>,
+    pub allowed_senders: Vec<String>,
+}
+
+impl ChannelConfig for LinqConfig {
+    fn name() -> &'static str {
+        "Linq"
+    }
+    fn desc() -> &'static str {
+        "iMessage/RCS/SMS via Linq API"
+    }
+}
+
+/// WATI WhatsApp Business API channel configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WatiConfig {
+    /// WATI API token (Bearer auth).
+    pub api_token: String,
+    /// WATI API base URL (default: https://live-mt-server.wati.io).
+    #[serde(default = "default_wati_api_url")]
+    pub api_url: String,
+    /// Tenant ID for multi-channel setups (optional).
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// Allowed phone numbers (E.164 format) or "*" for all.
+    #[serde(default)]
+    pub allowed_numbers: Vec<String>,
+}
+
+fn default_wati_api_url() -> String {
+    "https://live-mt-server.wati.io".to_string()
+}
+
+impl ChannelConfig for WatiConfig {
+    fn name() -> &'static str {
+        "WATI"
+    }
+    fn desc() -> &'static str {
+        "WhatsApp via WATI Business API"
+    }
+}
+
+/// Twilio Messaging channel configuration (SMS, MMS and WhatsApp).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TwilioConfig {
+    /// Twilio Account SID (basic-auth username).
+    pub account_sid: String,
+    /// Twilio auth token (basic-auth password and webhook signing key).
+    pub auth_token: String,
+    /// Twilio phone number to send SMS/MMS from (E.164 format).
+    pub from_number: String,
+    /// WhatsApp-enabled sender (E.164 format). Defaults to `from_number`.
+    #[serde(default)]
+    pub whatsapp_from: Option<String>,
+    /// Public URL Twilio posts to (e.g. "https://bot.example.com/twilio"),
+    /// used for webhook signature verification. Derived from the request
+    /// `Host` header when unset.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Allowed phone numbers (E.164 format) or "*" for all.
+    #[serde(default)]
+    pub allowed_numbers: Vec<String>,
 }
 
-impl ChannelConfig for LinqConfig {
+impl ChannelConfig for TwilioConfig {
     fn name() -> &'static str {
-        "Linq"
+        "Twilio"
     }
     fn desc() -> &'static str {
-        "iMessage/RCS/SMS via Linq API"
+        "SMS/MMS/WhatsApp via Twilio"
     }
 }
 
-/// WATI WhatsApp Business API channel configuration.
+/// Google Chat app configuration (HTTP endpoint receive + Chat REST API send).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
-pub struct WatiConfig {
-    /// WATI API token (Bearer auth).
-    pub api_token: String,
-    /// WATI API base URL (default: https://live-mt-server.wati.io).
-    #[serde(default = "default_wati_api_url")]
-    pub api_url: String,
-    /// Tenant ID for multi-channel setups (optional).
-    #[serde(default)]
-    pub tenant_id: Option<String>,
-    /// Allowed phone numbers (E.164 format) or "*" for all.
+pub struct GoogleChatConfig {
+    /// Service account key used to post replies: a path to the JSON key
+    /// file (`~` is expanded) or the JSON itself.
+    pub service_account_key: String,
+    /// Expected `aud` of inbound request tokens: the Google Cloud project
+    /// number, or the endpoint URL when the app uses URL authentication.
+    pub audience: String,
+    /// Allowed sender emails or `users/{id}` names, or "*" for all.
     #[serde(default)]
-    pub allowed_numbers: Vec<String>,
-}
-
-fn default_wati_api_url() -> String {
-    "https://live-mt-server.wati.io".to_string()
+    pub allowed_users: Vec<String>,
 }
 
-impl ChannelConfig for WatiConfig {
+impl ChannelConfig for GoogleChatConfig {
     fn name() -> &'static str {
-        "WATI"
+        "Google Chat"
     }
     fn desc() -> &'static str {
-        "WhatsApp via WATI Business API"
+        "Workspace Chat app with cards"
     }
 }
 
@@ -3148,6 +5762,45 @@ fn default_irc_port() -> u16 {
     6697
 }
 
+/// XMPP channel configuration (XEP-0045 rooms, XEP-0363 uploads).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct XmppConfig {
+    /// Bot account JID (e.g. `zeroclaw@example.org`)
+    pub jid: String,
+    /// Account password (SASL PLAIN over STARTTLS)
+    pub password: String,
+    /// Server hostname to connect to (defaults to the JID domain)
+    pub server: Option<String>,
+    /// Client-to-server port (default: 5222)
+    #[serde(default = "default_xmpp_port")]
+    pub port: u16,
+    /// Multi-user chat rooms to join (e.g. `ops@conference.example.org`)
+    #[serde(default)]
+    pub rooms: Vec<String>,
+    /// Nickname used in rooms (defaults to the JID local part)
+    pub nickname: Option<String>,
+    /// Allowed bare JIDs or room nicknames (case-insensitive) or "*" for all
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// HTTP upload service JID (discovered from the server when unset)
+    pub upload_service: Option<String>,
+    /// Verify TLS certificate (default: true)
+    pub verify_tls: Option<bool>,
+}
+
+impl ChannelConfig for XmppConfig {
+    fn name() -> &'static str {
+        "XMPP"
+    }
+    fn desc() -> &'static str {
+        "XMPP (ejabberd/Prosody) with MUC rooms"
+    }
+}
+
+fn default_xmpp_port() -> u16 {
+    5222
+}
+
 /// How ZeroClaw receives events from Feishu / Lark.
 ///
 /// - `websocket` (default) — persistent WSS long-connection; no public URL required.
@@ -3260,6 +5913,14 @@ pub struct SecurityConfig {
     /// Emergency-stop state machine configuration.
     #[serde(default)]
     pub estop: EstopConfig,
+
+    /// PII masking before requests reach cloud providers.
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+
+    /// Untrusted-content handling for tool results.
+    #[serde(default)]
+    pub prompt_injection: PromptInjectionConfig,
 }
 
 /// OTP validation strategy.
@@ -3371,6 +6032,106 @@ impl Default for EstopConfig {
     }
 }
 
+/// PII redaction configuration (`[security.redaction]`).
+///
+/// Emails, phone numbers and API-key-looking strings in messages sent to a
+/// cloud provider are replaced with placeholders such as `[EMAIL_1]`; the
+/// placeholders in its reply and tool calls are swapped back locally.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RedactionConfig {
+    /// Mask PII before provider requests.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Mask email addresses.
+    #[serde(default = "default_true")]
+    pub emails: bool,
+
+    /// Mask phone numbers written with separators or a leading `+`.
+    #[serde(default = "default_true")]
+    pub phone_numbers: bool,
+
+    /// Mask well-known API key and token formats (`sk-…`, `ghp_…`, `AKIA…`).
+    #[serde(default = "default_true")]
+    pub api_keys: bool,
+
+    /// Also mask requests to local providers (Ollama, LM Studio, …).
+    #[serde(default)]
+    pub local_providers: bool,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            emails: true,
+            phone_numbers: true,
+            api_keys: true,
+            local_providers: false,
+        }
+    }
+}
+
+/// Action for tool output the injection classifier flags.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InjectionAction {
+    /// Pass the content on, marked as suspicious.
+    #[default]
+    Warn,
+    /// Replace the content with a notice.
+    Block,
+}
+
+/// Prompt-injection guard for tool results (`[security.prompt_injection]`).
+///
+/// Tool output — including pages fetched from the web — is wrapped in an
+/// `<untrusted_content>` block before it goes back to the model, with
+/// tool-call markup removed, and can optionally be scanned for injection
+/// attempts first.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PromptInjectionConfig {
+    /// Wrap tool results in `<untrusted_content>` blocks.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Remove `<tool_call>`, `<invoke>`, `<tool_result>` and chat-template
+    /// markup from tool results.
+    #[serde(default = "default_true")]
+    pub strip_markup: bool,
+
+    /// Scan tool results with the heuristic injection classifier.
+    #[serde(default)]
+    pub classify: bool,
+
+    /// What to do with content the classifier flags.
+    #[serde(default)]
+    pub action: InjectionAction,
+
+    /// Classifier score (0.0–1.0) above which content is flagged.
+    #[serde(default = "default_injection_sensitivity")]
+    pub sensitivity: f64,
+}
+
+fn default_injection_sensitivity() -> f64 {
+    0.7
+}
+
+impl Default for PromptInjectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strip_markup: true,
+            classify: false,
+            action: InjectionAction::Warn,
+            sensitivity: default_injection_sensitivity(),
+        }
+    }
+}
+
 /// Sandbox configuration for OS-level isolation
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SandboxConfig {
@@ -3527,6 +6288,43 @@ impl ChannelConfig for DingTalkConfig {
     }
 }
 
+/// WeChat Work (WeCom) channel configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WecomConfig {
+    /// Corp ID (`ww...`) of the WeCom organization
+    pub corp_id: String,
+    /// Secret of the self-built app
+    pub corp_secret: String,
+    /// Agent ID of the self-built app
+    pub agent_id: i64,
+    /// Callback `Token` from the app's message receiving settings
+    pub token: String,
+    /// Callback `EncodingAESKey` (43 characters)
+    pub encoding_aes_key: String,
+    /// Local port for the callback server (default: 8082)
+    #[serde(default = "default_wecom_port")]
+    pub port: u16,
+    /// Optional group robot webhook key; messages to `robot` are sent there
+    #[serde(default)]
+    pub robot_key: Option<String>,
+    /// Allowed WeCom user IDs (case-insensitive) or "*" for all
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+}
+
+impl ChannelConfig for WecomConfig {
+    fn name() -> &'static str {
+        "WeCom"
+    }
+    fn desc() -> &'static str {
+        "WeChat Work app callback + group robot"
+    }
+}
+
+fn default_wecom_port() -> u16 {
+    8082
+}
+
 /// QQ Official Bot configuration (Tencent QQ Bot SDK)
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct QQConfig {
@@ -3608,12 +6406,15 @@ impl Default for Config {
             embedding_routes: Vec::new(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
+            digest: DigestConfig::default(),
+            transcripts: TranscriptsConfig::default(),
             channels_config: ChannelsConfig::default(),
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            mcp: McpConfig::default(),
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
@@ -3629,6 +6430,18 @@ impl Default for Config {
             hardware: HardwareConfig::default(),
             query_classification: QueryClassificationConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
+            image_generation: ImageGenerationConfig::default(),
+            run_code: RunCodeConfig::default(),
+            sql_query: SqlQueryConfig::default(),
+            git: GitConfig::default(),
+            calendar: CalendarConfig::default(),
+            send_email: SendEmailConfig::default(),
+            home_assistant: HomeAssistantConfig::default(),
+            feeds: FeedsConfig::default(),
+            desktop: DesktopConfig::default(),
+            knowledge: KnowledgeConfig::default(),
+            triggers: HashMap::new(),
         }
     }
 }
@@ -3890,18 +6703,14 @@ fn decrypt_optional_secret(
     value: &mut Option<String>,
     field_name: &str,
 ) -> Result<()> {
-    if let Some(raw) = value.clone() {
-        if crate::security::SecretStore::is_encrypted(&raw) {
-            *value = Some(
-                store
-                    .decrypt(&raw)
-                    .with_context(|| format!("Failed to decrypt {field_name}"))?,
-            );
-        }
+    if let Some(raw) = value.as_mut() {
+        decrypt_secret(store, raw, field_name)?;
     }
     Ok(())
 }
 
+/// Decrypt an `enc2:` value, then resolve a secret reference (`env:`,
+/// `pass:`, `vault:`...) to the secret it points to.
 fn decrypt_secret(
     store: &crate::security::SecretStore,
     value: &mut String,
@@ -3912,7 +6721,7 @@ fn decrypt_secret(
             .decrypt(value)
             .with_context(|| format!("Failed to decrypt {field_name}"))?;
     }
-    Ok(())
+    crate::security::secret_ref::resolve_in_place(value, field_name, store.zeroclaw_dir())
 }
 
 fn encrypt_optional_secret(
@@ -3920,23 +6729,22 @@ fn encrypt_optional_secret(
     value: &mut Option<String>,
     field_name: &str,
 ) -> Result<()> {
-    if let Some(raw) = value.clone() {
-        if !crate::security::SecretStore::is_encrypted(&raw) {
-            *value = Some(
-                store
-                    .encrypt(&raw)
-                    .with_context(|| format!("Failed to encrypt {field_name}"))?,
-            );
-        }
+    if let Some(raw) = value.as_mut() {
+        encrypt_secret(store, raw, field_name)?;
     }
     Ok(())
 }
 
+/// Encrypt a plaintext secret. A value resolved from a secret reference is
+/// saved as the reference again, unencrypted.
 fn encrypt_secret(
     store: &crate::security::SecretStore,
     value: &mut String,
     field_name: &str,
 ) -> Result<()> {
+    if crate::security::secret_ref::restore_in_place(value, field_name) {
+        return Ok(());
+    }
     if !crate::security::SecretStore::is_encrypted(value) {
         *value = store
             .encrypt(value)
@@ -4022,88 +6830,7 @@ impl Config {
             .context("Failed to create workspace directory")?;
 
         if config_path.exists() {
-            // Warn if config file is world-readable (may contain API keys)
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                if let Ok(meta) = fs::metadata(&config_path).await {
-                    if meta.permissions().mode() & 0o004 != 0 {
-                        tracing::warn!(
-                            "Config file {:?} is world-readable (mode {:o}). \
-                             Consider restricting with: chmod 600 {:?}",
-                            config_path,
-                            meta.permissions().mode() & 0o777,
-                            config_path,
-                        );
-                    }
-                }
-            }
-
-            let contents = fs::read_to_string(&config_path)
-                .await
-                .context("Failed to read config file")?;
-
-            // Track ignored/unknown config keys to warn users about silent misconfigurations
-            // (e.g., using [providers.ollama] which doesn't exist instead of top-level api_url)
-            let mut ignored_paths: Vec<String> = Vec::new();
-            let mut config: Config = serde_ignored::deserialize(
-                toml::de::Deserializer::parse(&contents).context("Failed to parse config file")?,
-                |path| {
-                    ignored_paths.push(path.to_string());
-                },
-            )
-            .context("Failed to deserialize config file")?;
-
-            // Warn about each unknown config key
-            for path in ignored_paths {
-                tracing::warn!(
-                    "Unknown config key ignored: \"{}\". Check config.toml for typos or deprecated options.",
-                    path
-                );
-            }
-            // Set computed paths that are skipped during serialization
-            config.config_path = config_path.clone();
-            config.workspace_dir = workspace_dir;
-            let store = crate::security::SecretStore::new(&zeroclaw_dir, config.secrets.encrypt);
-            decrypt_optional_secret(&store, &mut config.api_key, "config.api_key")?;
-            decrypt_optional_secret(
-                &store,
-                &mut config.composio.api_key,
-                "config.composio.api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.browser.computer_use.api_key,
-                "config.browser.computer_use.api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.web_search.brave_api_key,
-                "config.web_search.brave_api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.storage.provider.config.db_url,
-                "config.storage.provider.config.db_url",
-            )?;
-
-            for agent in config.agents.values_mut() {
-                decrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
-            }
-
-            if let Some(ref mut ns) = config.channels_config.nostr {
-                decrypt_secret(
-                    &store,
-                    &mut ns.private_key,
-                    "config.channels_config.nostr.private_key",
-                )?;
-            }
-
-            config.apply_env_overrides();
-            config.validate()?;
+            let config = Self::load_existing(&config_path, &zeroclaw_dir, workspace_dir).await?;
             tracing::info!(
                 path = %config.config_path.display(),
                 workspace = %config.workspace_dir.display(),
@@ -4125,17 +6852,249 @@ impl Config {
                 let _ = fs::set_permissions(&config_path, Permissions::from_mode(0o600)).await;
             }
 
-            config.apply_env_overrides();
-            config.validate()?;
-            tracing::info!(
-                path = %config.config_path.display(),
-                workspace = %config.workspace_dir.display(),
-                source = resolution_source.as_str(),
-                initialized = true,
-                "Config loaded"
+            config.apply_env_overrides();
+            config.validate()?;
+            tracing::info!(
+                path = %config.config_path.display(),
+                workspace = %config.workspace_dir.display(),
+                source = resolution_source.as_str(),
+                initialized = true,
+                "Config loaded"
+            );
+            Ok(config)
+        }
+    }
+
+    /// Re-read the file this config was loaded from, for a hot reload.
+    /// Unlike [`Config::load_or_init`], the location is not resolved again
+    /// and a missing file is an error instead of a fresh default config.
+    pub async fn reload_from_disk(&self) -> Result<Self> {
+        if !self.config_path.is_file() {
+            anyhow::bail!(
+                "Config file {} no longer exists",
+                self.config_path.display()
+            );
+        }
+        let zeroclaw_dir = self
+            .config_path
+            .parent()
+            .context("Config path has no parent directory")?;
+        Self::load_existing(&self.config_path, zeroclaw_dir, self.workspace_dir.clone()).await
+    }
+
+    /// Parse `config_path`, decrypt its secrets and apply env overrides.
+    async fn load_existing(
+        config_path: &Path,
+        zeroclaw_dir: &Path,
+        workspace_dir: PathBuf,
+    ) -> Result<Self> {
+        // Warn if config file is world-readable (may contain API keys)
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Ok(meta) = fs::metadata(config_path).await {
+                if meta.permissions().mode() & 0o004 != 0 {
+                    tracing::warn!(
+                        "Config file {:?} is world-readable (mode {:o}). \
+                         Consider restricting with: chmod 600 {:?}",
+                        config_path,
+                        meta.permissions().mode() & 0o777,
+                        config_path,
+                    );
+                }
+            }
+        }
+
+        let contents = fs::read_to_string(config_path)
+            .await
+            .context("Failed to read config file")?;
+
+        // Track ignored/unknown config keys to warn users about silent misconfigurations
+        // (e.g., using [providers.ollama] which doesn't exist instead of top-level api_url)
+        let mut ignored_paths: Vec<String> = Vec::new();
+        let mut config: Config = serde_ignored::deserialize(
+            toml::de::Deserializer::parse(&contents).context("Failed to parse config file")?,
+            |path| {
+                ignored_paths.push(path.to_string());
+            },
+        )
+        .context("Failed to deserialize config file")?;
+
+        // Warn about each unknown config key
+        for path in ignored_paths {
+            tracing::warn!(
+                "Unknown config key ignored: \"{}\". Check config.toml for typos or deprecated options.",
+                path
+            );
+        }
+        // Set computed paths that are skipped during serialization
+        config.config_path = config_path.to_path_buf();
+        config.workspace_dir = workspace_dir;
+        config.resolve_secrets(zeroclaw_dir)?;
+
+        config.apply_env_overrides();
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Decrypt the stored secrets and resolve secret references in place.
+    fn resolve_secrets(&mut self, zeroclaw_dir: &Path) -> Result<()> {
+        let store = crate::security::SecretStore::new(zeroclaw_dir, self.secrets.encrypt);
+        decrypt_optional_secret(&store, &mut self.api_key, "config.api_key")?;
+        decrypt_optional_secret(
+            &store,
+            &mut self.composio.api_key,
+            "config.composio.api_key",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.browser.computer_use.api_key,
+            "config.browser.computer_use.api_key",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.web_search.brave_api_key,
+            "config.web_search.brave_api_key",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.transcription.api_key,
+            "config.transcription.api_key",
+        )?;
+
+        decrypt_optional_secret(&store, &mut self.tts.api_key, "config.tts.api_key")?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.image_generation.api_key,
+            "config.image_generation.api_key",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.calendar.caldav_password,
+            "config.calendar.caldav_password",
+        )?;
+        decrypt_optional_secret(
+            &store,
+            &mut self.calendar.google_client_secret,
+            "config.calendar.google_client_secret",
+        )?;
+        decrypt_optional_secret(
+            &store,
+            &mut self.calendar.google_refresh_token,
+            "config.calendar.google_refresh_token",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.send_email.password,
+            "config.send_email.password",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.home_assistant.token,
+            "config.home_assistant.token",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.channels_config.moderation.api_key,
+            "config.channels_config.moderation.api_key",
+        )?;
+
+        decrypt_optional_secret(
+            &store,
+            &mut self.storage.provider.config.db_url,
+            "config.storage.provider.config.db_url",
+        )?;
+
+        for agent in self.agents.values_mut() {
+            decrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
+        }
+
+        for trigger in self.triggers.values_mut() {
+            decrypt_optional_secret(&store, &mut trigger.secret, "config.triggers.*.secret")?;
+        }
+
+        for connection in self.sql_query.connections.values_mut() {
+            if connection.kind != SqlConnectionKind::Sqlite {
+                decrypt_secret(
+                    &store,
+                    &mut connection.url,
+                    "config.sql_query.connections.*.url",
+                )?;
+            }
+        }
+
+        if let Some(ref mut ns) = self.channels_config.nostr {
+            decrypt_secret(
+                &store,
+                &mut ns.private_key,
+                "config.channels_config.nostr.private_key",
+            )?;
+        }
+
+        for (field_name, token) in self.secret_refs_mut() {
+            crate::security::secret_ref::resolve_in_place(token, field_name, zeroclaw_dir)?;
+        }
+        Ok(())
+    }
+
+    /// Secrets that are never stored encrypted but may hold a secret
+    /// reference, by field name.
+    fn secret_refs_mut(&mut self) -> Vec<(&'static str, &mut String)> {
+        let mut secrets = self.channels_config.tokens_mut();
+        if let Some(cf) = self.tunnel.cloudflare.as_mut() {
+            secrets.push(("config.tunnel.cloudflare.token", &mut cf.token));
+        }
+        if let Some(ng) = self.tunnel.ngrok.as_mut() {
+            secrets.push(("config.tunnel.ngrok.auth_token", &mut ng.auth_token));
+        }
+        push_optional_token(
+            &mut secrets,
+            "config.memory.qdrant.api_key",
+            &mut self.memory.qdrant.api_key,
+        );
+        secrets.extend(
+            self.reliability
+                .api_keys
+                .iter_mut()
+                .map(|key| ("config.reliability.api_keys", key)),
+        );
+        for route in &mut self.model_routes {
+            push_optional_token(
+                &mut secrets,
+                "config.model_routes.*.api_key",
+                &mut route.api_key,
             );
-            Ok(config)
         }
+        for route in &mut self.embedding_routes {
+            push_optional_token(
+                &mut secrets,
+                "config.embedding_routes.*.api_key",
+                &mut route.api_key,
+            );
+        }
+        for server in &mut self.mcp.servers {
+            secrets.extend(
+                server
+                    .env
+                    .values_mut()
+                    .map(|value| ("config.mcp.servers.*.env", value)),
+            );
+            secrets.extend(
+                server
+                    .headers
+                    .values_mut()
+                    .map(|value| ("config.mcp.servers.*.headers", value)),
+            );
+        }
+        secrets
     }
 
     fn lookup_model_provider_profile(
@@ -4289,6 +7248,64 @@ impl Config {
             anyhow::bail!("scheduler.max_tasks must be greater than 0");
         }
 
+        // Cron jobs
+        let mut cron_job_names = std::collections::HashSet::new();
+        for (i, job) in self.cron.jobs.iter().enumerate() {
+            let name = job.name.trim();
+            if name.is_empty() {
+                anyhow::bail!("cron.jobs[{i}].name must not be empty");
+            }
+            if !cron_job_names.insert(name) {
+                anyhow::bail!("cron.jobs[{i}].name '{name}' is used by another job");
+            }
+            if job.prompt.trim().is_empty() {
+                anyhow::bail!("cron.jobs[{i}].prompt must not be empty");
+            }
+            if let Some(deliver_to) = job.deliver_to.as_deref() {
+                let valid = deliver_to.split_once(':').is_some_and(|(channel, to)| {
+                    !channel.trim().is_empty() && !to.trim().is_empty()
+                });
+                if !valid {
+                    anyhow::bail!(
+                        "cron.jobs[{i}].deliver_to must be '<channel>:<recipient>', got '{deliver_to}'"
+                    );
+                }
+            }
+        }
+
+        // Triggers
+        for (name, trigger) in &self.triggers {
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                anyhow::bail!(
+                    "triggers.{name}: name must only contain letters, digits, '-' and '_'"
+                );
+            }
+            if trigger.prompt.trim().is_empty() {
+                anyhow::bail!("triggers.{name}.prompt must not be empty");
+            }
+            let valid = trigger
+                .deliver_to
+                .split_once(':')
+                .is_some_and(|(channel, to)| !channel.trim().is_empty() && !to.trim().is_empty());
+            if !valid {
+                anyhow::bail!(
+                    "triggers.{name}.deliver_to must be '<channel>:<recipient>', got '{}'",
+                    trigger.deliver_to
+                );
+            }
+        }
+
+        // Channel moderation
+        for (i, pattern) in self.channels_config.moderation.patterns.iter().enumerate() {
+            if let Err(e) = regex::Regex::new(pattern) {
+                anyhow::bail!("channels_config.moderation.patterns[{i}] is not a valid regex: {e}");
+            }
+        }
+
         // Model routes
         for (i, route) in self.model_routes.iter().enumerate() {
             if route.hint.trim().is_empty() {
@@ -4679,53 +7696,7 @@ impl Config {
     }
 
     pub async fn save(&self) -> Result<()> {
-        // Encrypt secrets before serialization
-        let mut config_to_save = self.clone();
-        let zeroclaw_dir = self
-            .config_path
-            .parent()
-            .context("Config path must have a parent directory")?;
-        let store = crate::security::SecretStore::new(zeroclaw_dir, self.secrets.encrypt);
-
-        encrypt_optional_secret(&store, &mut config_to_save.api_key, "config.api_key")?;
-        encrypt_optional_secret(
-            &store,
-            &mut config_to_save.composio.api_key,
-            "config.composio.api_key",
-        )?;
-
-        encrypt_optional_secret(
-            &store,
-            &mut config_to_save.browser.computer_use.api_key,
-            "config.browser.computer_use.api_key",
-        )?;
-
-        encrypt_optional_secret(
-            &store,
-            &mut config_to_save.web_search.brave_api_key,
-            "config.web_search.brave_api_key",
-        )?;
-
-        encrypt_optional_secret(
-            &store,
-            &mut config_to_save.storage.provider.config.db_url,
-            "config.storage.provider.config.db_url",
-        )?;
-
-        for agent in config_to_save.agents.values_mut() {
-            encrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
-        }
-
-        if let Some(ref mut ns) = config_to_save.channels_config.nostr {
-            encrypt_secret(
-                &store,
-                &mut ns.private_key,
-                "config.channels_config.nostr.private_key",
-            )?;
-        }
-
-        let toml_str =
-            toml::to_string_pretty(&config_to_save).context("Failed to serialize config")?;
+        let toml_str = self.to_saved_toml()?;
 
         let parent_dir = self
             .config_path
@@ -4812,6 +7783,127 @@ impl Config {
 
         Ok(())
     }
+
+    /// The config as written to disk: secrets encrypted, references kept.
+    /// Kept out of [`Config::save`] so the copy is not held across its awaits.
+    fn to_saved_toml(&self) -> Result<String> {
+        // Encrypt secrets before serialization
+        let mut config_to_save = self.clone();
+        let zeroclaw_dir = self
+            .config_path
+            .parent()
+            .context("Config path must have a parent directory")?;
+        let store = crate::security::SecretStore::new(zeroclaw_dir, self.secrets.encrypt);
+
+        encrypt_optional_secret(&store, &mut config_to_save.api_key, "config.api_key")?;
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.composio.api_key,
+            "config.composio.api_key",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.browser.computer_use.api_key,
+            "config.browser.computer_use.api_key",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.web_search.brave_api_key,
+            "config.web_search.brave_api_key",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.transcription.api_key,
+            "config.transcription.api_key",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.tts.api_key,
+            "config.tts.api_key",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.image_generation.api_key,
+            "config.image_generation.api_key",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.calendar.caldav_password,
+            "config.calendar.caldav_password",
+        )?;
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.calendar.google_client_secret,
+            "config.calendar.google_client_secret",
+        )?;
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.calendar.google_refresh_token,
+            "config.calendar.google_refresh_token",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.send_email.password,
+            "config.send_email.password",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.home_assistant.token,
+            "config.home_assistant.token",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.channels_config.moderation.api_key,
+            "config.channels_config.moderation.api_key",
+        )?;
+
+        encrypt_optional_secret(
+            &store,
+            &mut config_to_save.storage.provider.config.db_url,
+            "config.storage.provider.config.db_url",
+        )?;
+
+        for agent in config_to_save.agents.values_mut() {
+            encrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
+        }
+
+        for trigger in config_to_save.triggers.values_mut() {
+            encrypt_optional_secret(&store, &mut trigger.secret, "config.triggers.*.secret")?;
+        }
+
+        for connection in config_to_save.sql_query.connections.values_mut() {
+            if connection.kind != SqlConnectionKind::Sqlite {
+                encrypt_secret(
+                    &store,
+                    &mut connection.url,
+                    "config.sql_query.connections.*.url",
+                )?;
+            }
+        }
+
+        if let Some(ref mut ns) = config_to_save.channels_config.nostr {
+            encrypt_secret(
+                &store,
+                &mut ns.private_key,
+                "config.channels_config.nostr.private_key",
+            )?;
+        }
+
+        for (field_name, token) in config_to_save.secret_refs_mut() {
+            crate::security::secret_ref::restore_in_place(token, field_name);
+        }
+
+        toml::to_string_pretty(&config_to_save).context("Failed to serialize config")
+    }
 }
 
 async fn sync_directory(path: &Path) -> Result<()> {
@@ -4934,6 +8026,22 @@ mod tests {
         assert_eq!(mode, 0o600);
     }
 
+    #[test]
+    async fn reload_from_disk_rereads_the_loaded_file() {
+        let temp = TempDir::new().expect("temp dir");
+        let mut config = Config::default();
+        config.config_path = temp.path().join("config.toml");
+        config.workspace_dir = temp.path().join("workspace");
+        assert!(config.reload_from_disk().await.is_err());
+
+        config.default_temperature = 0.3;
+        config.save().await.expect("save config");
+        let reloaded = config.reload_from_disk().await.expect("reload config");
+        assert_eq!(reloaded.config_path, config.config_path);
+        assert_eq!(reloaded.workspace_dir, config.workspace_dir);
+        assert!((reloaded.default_temperature - 0.3).abs() < f64::EPSILON);
+    }
+
     #[test]
     async fn observability_config_default() {
         let o = ObservabilityConfig::default();
@@ -5009,6 +8117,7 @@ recipient = "42"
         let c = CronConfig {
             enabled: false,
             max_run_history: 100,
+            jobs: Vec::new(),
         };
         let json = serde_json::to_string(&c).unwrap();
         let parsed: CronConfig = serde_json::from_str(&json).unwrap();
@@ -5029,6 +8138,71 @@ default_temperature = 0.7
         assert_eq!(parsed.cron.max_run_history, 50);
     }
 
+    #[test]
+    async fn triggers_parse_and_validate() {
+        let toml_str = r#"
+workspace_dir = "/tmp/workspace"
+config_path = "/tmp/config.toml"
+default_temperature = 0.7
+
+[triggers.grafana]
+secret = "s3cret"
+prompt = "Triage this alert: {{title}}"
+deliver_to = "mattermost:town-square"
+"#;
+
+        let mut parsed: Config = toml::from_str(toml_str).unwrap();
+        let trigger = &parsed.triggers["grafana"];
+        assert_eq!(trigger.secret.as_deref(), Some("s3cret"));
+        assert_eq!(trigger.deliver_to, "mattermost:town-square");
+        parsed.validate().unwrap();
+
+        let mut renamed = parsed.triggers["grafana"].clone();
+        renamed.deliver_to = "mattermost".into();
+        parsed.triggers.insert("ci".into(), renamed);
+        let err = parsed.validate().unwrap_err().to_string();
+        assert!(err.contains("triggers.ci.deliver_to"));
+
+        parsed.triggers.remove("ci");
+        let bad_name = parsed.triggers["grafana"].clone();
+        parsed.triggers.insert("ci/alerts".into(), bad_name);
+        assert!(parsed.validate().is_err());
+    }
+
+    #[test]
+    async fn cron_jobs_parse_and_validate() {
+        let toml_str = r#"
+workspace_dir = "/tmp/workspace"
+config_path = "/tmp/config.toml"
+default_temperature = 0.7
+
+[[cron.jobs]]
+name = "weekly-issues"
+cron = "0 9 * * MON"
+tz = "Europe/Berlin"
+prompt = "Summarize last week's GitHub issues"
+deliver_to = "slack:C0123456"
+"#;
+
+        let mut parsed: Config = toml::from_str(toml_str).unwrap();
+        let job = &parsed.cron.jobs[0];
+        assert_eq!(job.name, "weekly-issues");
+        assert_eq!(job.cron, "0 9 * * MON");
+        assert_eq!(job.deliver_to.as_deref(), Some("slack:C0123456"));
+        assert!(job.enabled);
+        assert!(job.model.is_none());
+        parsed.validate().unwrap();
+
+        parsed.cron.jobs[0].deliver_to = Some("slack".into());
+        let err = parsed.validate().unwrap_err().to_string();
+        assert!(err.contains("cron.jobs[0].deliver_to"));
+
+        parsed.cron.jobs[0].deliver_to = None;
+        parsed.cron.jobs.push(parsed.cron.jobs[0].clone());
+        let err = parsed.validate().unwrap_err().to_string();
+        assert!(err.contains("used by another job"));
+    }
+
     #[test]
     async fn memory_config_default_hygiene_settings() {
         let m = MemoryConfig::default();
@@ -5086,8 +8260,14 @@ default_temperature = 0.7
                 require_approval_for_medium_risk: false,
                 block_high_risk_commands: true,
                 shell_env_passthrough: vec!["DATABASE_URL".into()],
+                forbidden_commands: vec![],
+                shell_timeout_secs: 60,
+                shell_max_output_bytes: 1_048_576,
                 auto_approve: vec!["file_read".into()],
                 always_ask: vec![],
+                channel_approval: false,
+                channel_approval_tools: vec!["shell".into()],
+                channel_approval_timeout_secs: 300,
                 allowed_roots: vec![],
                 non_cli_excluded_tools: vec![],
             },
@@ -5110,6 +8290,8 @@ default_temperature = 0.7
                 to: Some("123456".into()),
             },
             cron: CronConfig::default(),
+            digest: DigestConfig::default(),
+            transcripts: TranscriptsConfig::default(),
             channels_config: ChannelsConfig {
                 cli: true,
                 telegram: Some(TelegramConfig {
@@ -5119,10 +8301,14 @@ default_temperature = 0.7
                     draft_update_interval_ms: default_draft_update_interval_ms(),
                     interrupt_on_new_message: false,
                     mention_only: false,
+                    trigger_prefix: None,
                 }),
                 discord: None,
                 slack: None,
                 mattermost: None,
+                zulip: None,
+                mastodon: None,
+                github: None,
                 webhook: None,
                 imessage: None,
                 matrix: None,
@@ -5130,22 +8316,44 @@ default_temperature = 0.7
                 whatsapp: None,
                 linq: None,
                 wati: None,
+                twilio: None,
+                google_chat: None,
                 nextcloud_talk: None,
                 email: None,
                 irc: None,
+                xmpp: None,
                 lark: None,
                 feishu: None,
                 dingtalk: None,
+                wecom: None,
                 qq: None,
                 nostr: None,
                 clawdtalk: None,
+                local_socket: None,
+                http: None,
+                web: None,
+                plugins: Vec::new(),
                 message_timeout_secs: 300,
+                acl: ChannelAclConfig::default(),
+                bridges: Vec::new(),
+                broadcast_groups: HashMap::new(),
+                media_host: MediaHostConfig::default(),
+                custom: HashMap::new(),
+                instructions: Vec::new(),
+                room_context: RoomContextConfig::default(),
+                user_memory: UserMemoryConfig::default(),
+                commands: ChannelCommandsConfig::default(),
+                queue: ChannelQueueConfig::default(),
+                identities: Vec::new(),
+                personas: HashMap::new(),
+                moderation: ModerationConfig::default(),
             },
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            mcp: McpConfig::default(),
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
@@ -5161,6 +8369,18 @@ default_temperature = 0.7
             hooks: HooksConfig::default(),
             hardware: HardwareConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
+            image_generation: ImageGenerationConfig::default(),
+            run_code: RunCodeConfig::default(),
+            sql_query: SqlQueryConfig::default(),
+            git: GitConfig::default(),
+            calendar: CalendarConfig::default(),
+            send_email: SendEmailConfig::default(),
+            home_assistant: HomeAssistantConfig::default(),
+            feeds: FeedsConfig::default(),
+            desktop: DesktopConfig::default(),
+            knowledge: KnowledgeConfig::default(),
+            triggers: HashMap::new(),
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -5259,6 +8479,8 @@ reasoning_enabled = false
         assert_eq!(cfg.max_tool_iterations, 10);
         assert_eq!(cfg.max_history_messages, 50);
         assert!(!cfg.parallel_tools);
+        assert_eq!(cfg.max_parallel_tools, 4);
+        assert_eq!(cfg.max_tool_output_tokens, 8000);
         assert_eq!(cfg.tool_dispatcher, "auto");
     }
 
@@ -5271,6 +8493,7 @@ compact_context = true
 max_tool_iterations = 20
 max_history_messages = 80
 parallel_tools = true
+max_parallel_tools = 2
 tool_dispatcher = "xml"
 "#;
         let parsed: Config = toml::from_str(raw).unwrap();
@@ -5278,6 +8501,7 @@ tool_dispatcher = "xml"
         assert_eq!(parsed.agent.max_tool_iterations, 20);
         assert_eq!(parsed.agent.max_history_messages, 80);
         assert!(parsed.agent.parallel_tools);
+        assert_eq!(parsed.agent.max_parallel_tools, 2);
         assert_eq!(parsed.agent.tool_dispatcher, "xml");
     }
 
@@ -5322,12 +8546,15 @@ tool_dispatcher = "xml"
             query_classification: QueryClassificationConfig::default(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
+            digest: DigestConfig::default(),
+            transcripts: TranscriptsConfig::default(),
             channels_config: ChannelsConfig::default(),
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            mcp: McpConfig::default(),
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
@@ -5343,6 +8570,18 @@ tool_dispatcher = "xml"
             hooks: HooksConfig::default(),
             hardware: HardwareConfig::default(),
             transcription: TranscriptionConfig::default(),
+            tts: TtsConfig::default(),
+            image_generation: ImageGenerationConfig::default(),
+            run_code: RunCodeConfig::default(),
+            sql_query: SqlQueryConfig::default(),
+            git: GitConfig::default(),
+            calendar: CalendarConfig::default(),
+            send_email: SendEmailConfig::default(),
+            home_assistant: HomeAssistantConfig::default(),
+            feeds: FeedsConfig::default(),
+            desktop: DesktopConfig::default(),
+            knowledge: KnowledgeConfig::default(),
+            triggers: HashMap::new(),
         };
 
         config.save().await.unwrap();
@@ -5421,32 +8660,214 @@ tool_dispatcher = "xml"
             browser_encrypted
         ));
         assert_eq!(
-            store.decrypt(browser_encrypted).unwrap(),
-            "browser-credential"
+            store.decrypt(browser_encrypted).unwrap(),
+            "browser-credential"
+        );
+
+        let web_search_encrypted = stored.web_search.brave_api_key.as_deref().unwrap();
+        assert!(crate::security::SecretStore::is_encrypted(
+            web_search_encrypted
+        ));
+        assert_eq!(
+            store.decrypt(web_search_encrypted).unwrap(),
+            "brave-credential"
+        );
+
+        let worker = stored.agents.get("worker").unwrap();
+        let worker_encrypted = worker.api_key.as_deref().unwrap();
+        assert!(crate::security::SecretStore::is_encrypted(worker_encrypted));
+        assert_eq!(store.decrypt(worker_encrypted).unwrap(), "agent-credential");
+
+        let storage_db_url = stored.storage.provider.config.db_url.as_deref().unwrap();
+        assert!(crate::security::SecretStore::is_encrypted(storage_db_url));
+        assert_eq!(
+            store.decrypt(storage_db_url).unwrap(),
+            "postgres://user:pw@host/db"
+        );
+
+        let _ = fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn config_secret_references_resolve_and_save_back() {
+        let dir = std::env::temp_dir().join(format!(
+            "zeroclaw_test_secret_refs_{}",
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).await.unwrap();
+        std::env::set_var("ZEROCLAW_TEST_SCHEMA_API_KEY", "sk-from-env");
+        let store = crate::security::SecretStore::new(&dir, true);
+
+        let mut config = Config::default();
+        config.workspace_dir = dir.join("workspace");
+        config.config_path = dir.join("config.toml");
+        config.api_key = Some("env:ZEROCLAW_TEST_SCHEMA_API_KEY".into());
+        decrypt_optional_secret(&store, &mut config.api_key, "config.api_key").unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("sk-from-env"));
+
+        config.save().await.unwrap();
+        let contents = tokio::fs::read_to_string(&config.config_path)
+            .await
+            .unwrap();
+        let stored: Config = toml::from_str(&contents).unwrap();
+        assert_eq!(
+            stored.api_key.as_deref(),
+            Some("env:ZEROCLAW_TEST_SCHEMA_API_KEY")
         );
+        assert!(!contents.contains("sk-from-env"));
 
-        let web_search_encrypted = stored.web_search.brave_api_key.as_deref().unwrap();
-        assert!(crate::security::SecretStore::is_encrypted(
-            web_search_encrypted
-        ));
-        assert_eq!(
-            store.decrypt(web_search_encrypted).unwrap(),
-            "brave-credential"
-        );
+        let mut broken = Some("vault:secret/zeroclaw".to_string());
+        let err = decrypt_optional_secret(&store, &mut broken, "config.api_key").unwrap_err();
+        assert!(err.to_string().contains("config.api_key"));
 
-        let worker = stored.agents.get("worker").unwrap();
-        let worker_encrypted = worker.api_key.as_deref().unwrap();
-        assert!(crate::security::SecretStore::is_encrypted(worker_encrypted));
-        assert_eq!(store.decrypt(worker_encrypted).unwrap(), "agent-credential");
+        let _ = fs::remove_dir_all(&dir).await;
+    }
 
-        let storage_db_url = stored.storage.provider.config.db_url.as_deref().unwrap();
-        assert!(crate::security::SecretStore::is_encrypted(storage_db_url));
-        assert_eq!(
-            store.decrypt(storage_db_url).unwrap(),
-            "postgres://user:pw@host/db"
+    /// Whether a string property named `name` holds a secret.
+    fn is_secret_property(name: &str) -> bool {
+        const MARKERS: &[&str] = &[
+            "token",
+            "password",
+            "secret",
+            "private_key",
+            "api_key",
+            "aes_key",
+            "encrypt_key",
+            "robot_key",
+            "account_key",
+        ];
+        // Paired tokens are written back as hashes, never user secrets.
+        if name == "paired_tokens" {
+            return false;
+        }
+        matches!(name, "env" | "headers" | "api_keys" | "db_url")
+            || MARKERS.iter().any(|marker| name.contains(marker))
+    }
+
+    /// A JSON value for `schema` that sets every property, with `secret` in
+    /// each string whose property name looks like a secret.
+    fn fill_schema(
+        schema: &serde_json::Value,
+        defs: &serde_json::Value,
+        name: &str,
+        secret: &str,
+    ) -> serde_json::Value {
+        use serde_json::{json, Value};
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let def = reference.trim_start_matches("#/$defs/");
+            return fill_schema(&defs[def], defs, name, secret);
+        }
+        for key in ["allOf", "anyOf", "oneOf"] {
+            if let Some(variants) = schema.get(key).and_then(Value::as_array) {
+                let variant = variants
+                    .iter()
+                    .find(|variant| variant.get("type") != Some(&json!("null")))
+                    .unwrap_or(&variants[0]);
+                return fill_schema(variant, defs, name, secret);
+            }
+        }
+        if let Some(value) = schema.get("const") {
+            return value.clone();
+        }
+        if let Some(value) = schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .and_then(|v| v.first())
+        {
+            return value.clone();
+        }
+        let kind = match schema.get("type") {
+            Some(Value::Array(kinds)) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|kind| *kind != "null")
+                .unwrap_or("null"),
+            Some(kind) => kind.as_str().unwrap_or("null"),
+            None => "null",
+        };
+        match kind {
+            "object" => {
+                let mut object = serde_json::Map::new();
+                if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                    for (property, property_schema) in properties {
+                        object.insert(
+                            property.clone(),
+                            fill_schema(property_schema, defs, property, secret),
+                        );
+                    }
+                } else if let Some(values) = schema
+                    .get("additionalProperties")
+                    .filter(|values| values.is_object())
+                {
+                    object.insert("entry".into(), fill_schema(values, defs, name, secret));
+                }
+                Value::Object(object)
+            }
+            "array" => schema.get("items").map_or_else(
+                || json!([]),
+                |items| json!([fill_schema(items, defs, name, secret)]),
+            ),
+            "string" if is_secret_property(name) => json!(secret),
+            "string" => json!("value"),
+            "integer" => json!(1),
+            "number" => json!(1.0),
+            "boolean" => json!(false),
+            _ => Value::Null,
+        }
+    }
+
+    /// JSON paths under `value` whose string equals `needle`.
+    fn paths_holding(value: &serde_json::Value, needle: &str, path: &str, out: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(text) if text == needle => out.push(path.to_string()),
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    paths_holding(item, needle, &format!("{path}.*"), out);
+                }
+            }
+            serde_json::Value::Object(map) => {
+                for (key, item) in map {
+                    paths_holding(item, needle, &format!("{path}.{key}"), out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[tokio::test]
+    async fn every_secret_field_accepts_a_secret_reference() {
+        let reference = "env:ZEROCLAW_TEST_SCHEMA_WALKED_SECRET";
+        std::env::set_var("ZEROCLAW_TEST_SCHEMA_WALKED_SECRET", "walked-secret");
+        let schema = serde_json::to_value(schemars::schema_for!(Config)).unwrap();
+        let filled = fill_schema(&schema, &schema["$defs"], "", reference);
+        let mut config: Config = serde_json::from_value(filled).unwrap();
+        let mut before = Vec::new();
+        paths_holding(
+            &serde_json::to_value(&config).unwrap(),
+            reference,
+            "config",
+            &mut before,
         );
+        assert!(before.len() > 50, "schema walk found only {before:?}");
+
+        let dir = TempDir::new().unwrap();
+        config.config_path = dir.path().join("config.toml");
+        config.workspace_dir = dir.path().join("workspace");
+        config.resolve_secrets(dir.path()).unwrap();
+        let mut unresolved = Vec::new();
+        paths_holding(
+            &serde_json::to_value(&config).unwrap(),
+            reference,
+            "config",
+            &mut unresolved,
+        );
+        assert!(unresolved.is_empty(), "not resolved: {unresolved:?}");
 
-        let _ = fs::remove_dir_all(&dir).await;
+        config.save().await.unwrap();
+        let saved = tokio::fs::read_to_string(&config.config_path)
+            .await
+            .unwrap();
+        assert!(!saved.contains("walked-secret"));
     }
 
     #[tokio::test]
@@ -5490,6 +8911,7 @@ tool_dispatcher = "xml"
             draft_update_interval_ms: 500,
             interrupt_on_new_message: true,
             mention_only: false,
+            trigger_prefix: None,
         };
         let json = serde_json::to_string(&tc).unwrap();
         let parsed: TelegramConfig = serde_json::from_str(&json).unwrap();
@@ -5517,6 +8939,12 @@ tool_dispatcher = "xml"
             allowed_users: vec![],
             listen_to_bots: false,
             mention_only: false,
+            trigger_prefix: None,
+            thread_replies: false,
+            slash_commands: true,
+            stream_mode: StreamMode::default(),
+            draft_update_interval_ms: 1000,
+            voice: false,
         };
         let json = serde_json::to_string(&dc).unwrap();
         let parsed: DiscordConfig = serde_json::from_str(&json).unwrap();
@@ -5532,6 +8960,12 @@ tool_dispatcher = "xml"
             allowed_users: vec![],
             listen_to_bots: false,
             mention_only: false,
+            trigger_prefix: None,
+            thread_replies: false,
+            slash_commands: true,
+            stream_mode: StreamMode::default(),
+            draft_update_interval_ms: 1000,
+            voice: false,
         };
         let json = serde_json::to_string(&dc).unwrap();
         let parsed: DiscordConfig = serde_json::from_str(&json).unwrap();
@@ -5580,6 +9014,8 @@ tool_dispatcher = "xml"
             device_id: Some("DEVICE123".into()),
             room_id: "!room123:matrix.org".into(),
             allowed_users: vec!["@user:matrix.org".into()],
+            stream_mode: StreamMode::default(),
+            draft_update_interval_ms: 1000,
         };
         let json = serde_json::to_string(&mc).unwrap();
         let parsed: MatrixConfig = serde_json::from_str(&json).unwrap();
@@ -5600,6 +9036,8 @@ tool_dispatcher = "xml"
             device_id: None,
             room_id: "!abc:synapse.local".into(),
             allowed_users: vec!["@admin:synapse.local".into(), "*".into()],
+            stream_mode: StreamMode::default(),
+            draft_update_interval_ms: 1000,
         };
         let toml_str = toml::to_string(&mc).unwrap();
         let parsed: MatrixConfig = toml::from_str(&toml_str).unwrap();
@@ -5631,6 +9069,8 @@ allowed_users = ["@ops:matrix.org"]
             allowed_from: vec!["+1111111111".into()],
             ignore_attachments: true,
             ignore_stories: false,
+            mention_only: false,
+            trigger_prefix: None,
         };
         let json = serde_json::to_string(&sc).unwrap();
         let parsed: SignalConfig = serde_json::from_str(&json).unwrap();
@@ -5651,6 +9091,8 @@ allowed_users = ["@ops:matrix.org"]
             allowed_from: vec!["*".into()],
             ignore_attachments: false,
             ignore_stories: true,
+            mention_only: false,
+            trigger_prefix: None,
         };
         let toml_str = toml::to_string(&sc).unwrap();
         let parsed: SignalConfig = toml::from_str(&toml_str).unwrap();
@@ -5678,6 +9120,9 @@ allowed_users = ["@ops:matrix.org"]
             discord: None,
             slack: None,
             mattermost: None,
+            zulip: None,
+            mastodon: None,
+            github: None,
             webhook: None,
             imessage: Some(IMessageConfig {
                 allowed_contacts: vec!["+1".into()],
@@ -5689,21 +9134,44 @@ allowed_users = ["@ops:matrix.org"]
                 device_id: None,
                 room_id: "!r:m".into(),
                 allowed_users: vec!["@u:m".into()],
+                stream_mode: StreamMode::default(),
+                draft_update_interval_ms: 1000,
             }),
             signal: None,
             whatsapp: None,
             linq: None,
             wati: None,
+            twilio: None,
+            google_chat: None,
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
             lark: None,
             feishu: None,
             dingtalk: None,
+            wecom: None,
             qq: None,
             nostr: None,
             clawdtalk: None,
+            local_socket: None,
+            http: None,
+            web: None,
+            plugins: Vec::new(),
             message_timeout_secs: 300,
+            acl: ChannelAclConfig::default(),
+            bridges: Vec::new(),
+            broadcast_groups: HashMap::new(),
+            media_host: MediaHostConfig::default(),
+            custom: HashMap::new(),
+            instructions: Vec::new(),
+            room_context: RoomContextConfig::default(),
+            user_memory: UserMemoryConfig::default(),
+            commands: ChannelCommandsConfig::default(),
+            queue: ChannelQueueConfig::default(),
+            identities: Vec::new(),
+            personas: HashMap::new(),
+            moderation: ModerationConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -5892,6 +9360,9 @@ channel_id = "C123"
             discord: None,
             slack: None,
             mattermost: None,
+            zulip: None,
+            mastodon: None,
+            github: None,
             webhook: None,
             imessage: None,
             matrix: None,
@@ -5908,16 +9379,37 @@ channel_id = "C123"
             }),
             linq: None,
             wati: None,
+            twilio: None,
+            google_chat: None,
             nextcloud_talk: None,
             email: None,
             irc: None,
+            xmpp: None,
             lark: None,
             feishu: None,
             dingtalk: None,
+            wecom: None,
             qq: None,
             nostr: None,
             clawdtalk: None,
+            local_socket: None,
+            http: None,
+            web: None,
+            plugins: Vec::new(),
             message_timeout_secs: 300,
+            acl: ChannelAclConfig::default(),
+            bridges: Vec::new(),
+            broadcast_groups: HashMap::new(),
+            media_host: MediaHostConfig::default(),
+            custom: HashMap::new(),
+            instructions: Vec::new(),
+            room_context: RoomContextConfig::default(),
+            user_memory: UserMemoryConfig::default(),
+            commands: ChannelCommandsConfig::default(),
+            queue: ChannelQueueConfig::default(),
+            identities: Vec::new(),
+            personas: HashMap::new(),
+            moderation: ModerationConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -6094,6 +9586,36 @@ default_temperature = 0.7
         assert!(parsed.composio.api_key.is_none());
     }
 
+    #[test]
+    async fn mcp_config_parses_stdio_and_sse_servers() {
+        let toml_str = r#"
+[[servers]]
+name = "fs"
+command = "npx"
+args = ["-y", "server-filesystem"]
+
+[[servers]]
+name = "remote"
+transport = "sse"
+url = "https://mcp.example.com/sse"
+headers = { Authorization = "Bearer t" }
+timeout_secs = 10
+"#;
+        let parsed: McpConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(parsed.servers.len(), 2);
+        assert_eq!(parsed.servers[0].transport, McpTransport::Stdio);
+        assert_eq!(parsed.servers[0].timeout_secs, 30);
+        assert_eq!(parsed.servers[1].transport, McpTransport::Sse);
+        assert_eq!(
+            parsed.servers[1]
+                .headers
+                .get("Authorization")
+                .map(String::as_str),
+            Some("Bearer t")
+        );
+        assert!(McpConfig::default().servers.is_empty());
+    }
+
     #[test]
     async fn composio_config_partial_toml() {
         let toml_str = r"
@@ -7565,6 +11087,411 @@ default_model = "legacy-model"
         assert_eq!(parsed.transcription.max_duration_secs, 120);
     }
 
+    #[test]
+    async fn image_generation_config_parses_backend_and_defaults() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[image_generation]
+enabled = true
+backend = "sd_webui"
+size = "768x512"
+"#,
+        )
+        .unwrap();
+        let image = &parsed.image_generation;
+        assert!(image.enabled);
+        assert_eq!(image.backend, ImageGenerationBackend::SdWebui);
+        assert_eq!(image.size, "768x512");
+        assert_eq!(image.model, "gpt-image-1");
+        assert_eq!(image.timeout_secs, 180);
+        assert!(image.api_url.is_none());
+        assert!(!Config::default().image_generation.enabled);
+    }
+
+    #[test]
+    async fn run_code_config_defaults_to_isolated_docker() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[run_code]
+enabled = true
+"#,
+        )
+        .unwrap();
+        let run_code = &parsed.run_code;
+        assert!(run_code.enabled);
+        assert_eq!(run_code.backend, RunCodeBackend::Docker);
+        assert_eq!(run_code.docker_image, "python:3.12-slim");
+        assert!(!run_code.allow_network);
+        assert_eq!(run_code.timeout_secs, 60);
+
+        let parsed: Config = toml::from_str(
+            "default_temperature = 0.7\n[run_code]\nbackend = \"wasm\"\npython_wasm = \"python.wasm\"\n",
+        )
+        .unwrap();
+        assert_eq!(parsed.run_code.backend, RunCodeBackend::Wasm);
+        assert!(!parsed.run_code.enabled);
+    }
+
+    #[test]
+    async fn sql_query_config_parses_named_connections() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[sql_query]
+enabled = true
+max_rows = 25
+
+[sql_query.connections.app]
+kind = "postgres"
+url = "postgres://readonly@db.internal/app"
+description = "Production app database (read replica)"
+
+[sql_query.connections.local]
+kind = "sqlite"
+url = "data/metrics.db"
+"#,
+        )
+        .unwrap();
+        let sql = &parsed.sql_query;
+        assert!(sql.enabled);
+        assert_eq!(sql.max_rows, 25);
+        assert_eq!(sql.timeout_secs, 30);
+        assert_eq!(sql.connections["app"].kind, SqlConnectionKind::Postgres);
+        assert_eq!(sql.connections["local"].kind, SqlConnectionKind::Sqlite);
+        assert!(sql.connections["local"].description.is_none());
+        assert!(Config::default().sql_query.connections.is_empty());
+    }
+
+    #[test]
+    async fn git_config_parses_repo_path() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[git]
+enabled = true
+repo_path = "/srv/repos/app"
+"#,
+        )
+        .unwrap();
+        assert!(parsed.git.enabled);
+        assert_eq!(parsed.git.repo_path.as_deref(), Some("/srv/repos/app"));
+        assert_eq!(parsed.git.max_output_bytes, 32_768);
+        assert_eq!(parsed.git.timeout_secs, 30);
+        assert!(Config::default().git.repo_path.is_none());
+    }
+
+    #[test]
+    async fn calendar_config_parses_backend_and_defaults() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[calendar]
+enabled = true
+backend = "google"
+timezone = "Europe/Berlin"
+google_refresh_token = "1//refresh"
+"#,
+        )
+        .unwrap();
+        let calendar = &parsed.calendar;
+        assert!(calendar.enabled);
+        assert_eq!(calendar.backend, CalendarBackend::Google);
+        assert_eq!(calendar.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(calendar.google_calendar_id, "primary");
+        assert_eq!(calendar.default_duration_mins, 60);
+        assert_eq!(Config::default().calendar.backend, CalendarBackend::Caldav);
+    }
+
+    #[test]
+    async fn send_email_config_parses_smtp_and_defaults() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[send_email]
+enabled = true
+smtp_host = "smtp.example.com"
+username = "bot@example.com"
+allowed_recipients = ["@example.com"]
+"#,
+        )
+        .unwrap();
+        let email = &parsed.send_email;
+        assert!(email.enabled);
+        assert_eq!(email.smtp_host.as_deref(), Some("smtp.example.com"));
+        assert_eq!(email.smtp_port, 465);
+        assert!(email.smtp_tls);
+        assert_eq!(email.max_attachment_mb, 20);
+        assert!(!Config::default().send_email.enabled);
+    }
+
+    #[test]
+    async fn home_assistant_config_parses_url_and_default_domains() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[home_assistant]
+enabled = true
+url = "https://ha.example.com"
+token = "eyJ0eXAi"
+"#,
+        )
+        .unwrap();
+        let ha = &parsed.home_assistant;
+        assert!(ha.enabled);
+        assert_eq!(ha.url, "https://ha.example.com");
+        assert!(ha.allowed_domains.iter().any(|domain| domain == "light"));
+        assert!(!ha.allowed_domains.iter().any(|domain| domain == "lock"));
+        assert_eq!(ha.timeout_secs, 15);
+    }
+
+    #[test]
+    async fn feeds_config_parses_sources_and_defaults() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[feeds]
+enabled = true
+target = "telegram"
+to = "123456"
+
+[[feeds.sources]]
+url = "https://blog.rust-lang.org/feed.xml"
+
+[[feeds.sources]]
+url = "https://github.com/org/repo/releases.atom"
+name = "Repo releases"
+target = "slack"
+to = "C0123"
+"#,
+        )
+        .unwrap();
+        let feeds = &parsed.feeds;
+        assert!(feeds.enabled);
+        assert_eq!(feeds.interval_minutes, 60);
+        assert!(!feeds.summarize);
+        assert_eq!(feeds.sources.len(), 2);
+        assert!(feeds.sources[0].name.is_none());
+        assert_eq!(feeds.sources[1].target.as_deref(), Some("slack"));
+    }
+
+    #[test]
+    async fn desktop_config_defaults_to_disabled_input() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[desktop]
+enabled = true
+"#,
+        )
+        .unwrap();
+        assert!(parsed.desktop.enabled);
+        assert!(!parsed.desktop.allow_input);
+        assert_eq!(parsed.desktop.max_clipboard_chars, 20_000);
+        assert!(!Config::default().desktop.enabled);
+    }
+
+    #[test]
+    async fn knowledge_config_defaults_and_overrides() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[knowledge]
+enabled = true
+directories = ["docs", "/srv/handbook"]
+"#,
+        )
+        .unwrap();
+        assert!(parsed.knowledge.enabled);
+        assert_eq!(parsed.knowledge.directories, vec!["docs", "/srv/handbook"]);
+        assert!(parsed.knowledge.extensions.iter().any(|e| e == "pdf"));
+        assert_eq!(parsed.knowledge.chunk_max_tokens, 512);
+        assert_eq!(parsed.knowledge.max_results, 5);
+        assert!(!Config::default().knowledge.enabled);
+    }
+
+    #[test]
+    async fn user_memory_config_parses_from_channels_section() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[channels_config]
+cli = true
+
+[channels_config.user_memory]
+enabled = true
+max_context_facts = 5
+"#,
+        )
+        .unwrap();
+        let user_memory = &parsed.channels_config.user_memory;
+        assert!(user_memory.enabled);
+        assert_eq!(user_memory.max_context_facts, 5);
+        assert_eq!(user_memory.max_facts, 100);
+        assert!(!ChannelsConfig::default().user_memory.enabled);
+    }
+
+    #[test]
+    async fn channel_commands_config_resolves_prefix_per_channel() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[channels_config]
+cli = true
+
+[channels_config.commands.prefixes]
+slack = "!"
+irc = ""
+"#,
+        )
+        .unwrap();
+        let commands = &parsed.channels_config.commands;
+        assert_eq!(commands.prefix_for("telegram"), Some("/"));
+        assert_eq!(commands.prefix_for("slack"), Some("!"));
+        assert_eq!(commands.prefix_for("irc"), None);
+    }
+
+    #[test]
+    async fn channel_queue_config_defaults_to_sequential() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[channels_config]
+cli = true
+
+[channels_config.queue]
+merge_window_ms = 1500
+"#,
+        )
+        .unwrap();
+        let queue = &parsed.channels_config.queue;
+        assert!(queue.sequential);
+        assert_eq!(queue.merge_window_ms, 1500);
+        assert_eq!(ChannelsConfig::default().queue.merge_window_ms, 0);
+    }
+
+    #[test]
+    async fn identities_parse_from_channels_section() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[channels_config]
+cli = true
+
+[[channels_config.identities]]
+name = "alice"
+accounts = ["telegram:123456789", "slack:U024BE7LH"]
+"#,
+        )
+        .unwrap();
+        let identities = &parsed.channels_config.identities;
+        assert_eq!(identities.len(), 1);
+        assert_eq!(identities[0].name, "alice");
+        assert_eq!(identities[0].accounts[1], "slack:U024BE7LH");
+        assert!(ChannelsConfig::default().identities.is_empty());
+    }
+
+    #[test]
+    async fn personas_parse_from_channels_section() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[channels_config]
+cli = true
+
+[channels_config.personas.support]
+system_prompt = "You are Sam, a patient support agent."
+tools = ["memory_recall", "web_search"]
+temperature = 0.2
+language = "German"
+channels = ["slack:C0123"]
+"#,
+        )
+        .unwrap();
+        let support = &parsed.channels_config.personas["support"];
+        assert_eq!(support.language.as_deref(), Some("German"));
+        assert_eq!(support.tools.as_ref().map(Vec::len), Some(2));
+        assert_eq!(support.temperature, Some(0.2));
+        assert_eq!(support.model, None);
+        assert_eq!(support.channels, vec!["slack:C0123".to_string()]);
+    }
+
+    #[test]
+    async fn moderation_parses_channel_overrides_and_validates_patterns() {
+        let mut parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[channels_config]
+cli = true
+
+[channels_config.moderation]
+keywords = ["casino"]
+outbound = "warn"
+
+[channels_config.moderation.channels.discord]
+inbound = "log"
+"#,
+        )
+        .unwrap();
+        let moderation = &parsed.channels_config.moderation;
+        assert!(moderation.is_active());
+        assert_eq!(moderation.inbound, ModerationAction::Block);
+        assert_eq!(moderation.outbound, ModerationAction::Warn);
+        assert_eq!(
+            moderation.channels["discord"],
+            ChannelModerationConfig {
+                inbound: Some(ModerationAction::Log),
+                outbound: None,
+            }
+        );
+        assert!(!ModerationConfig::default().is_active());
+        assert!(parsed.validate().is_ok());
+
+        parsed.channels_config.moderation.patterns = vec!["(unclosed".into()];
+        let err = parsed.validate().unwrap_err().to_string();
+        assert!(err.contains("channels_config.moderation.patterns[0]"));
+    }
+
+    #[test]
+    async fn ocr_config_parses_from_multimodal_section() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[multimodal.ocr]
+enabled = true
+backend = "vision"
+provider = "openrouter"
+model = "google/gemini-2.5-flash"
+"#,
+        )
+        .unwrap();
+        let ocr = &parsed.multimodal.ocr;
+        assert!(ocr.enabled);
+        assert_eq!(ocr.backend, OcrBackend::Vision);
+        assert_eq!(ocr.tesseract_command, "tesseract");
+        assert_eq!(ocr.languages, "eng");
+        assert_eq!(ocr.model.as_deref(), Some("google/gemini-2.5-flash"));
+        assert!(!Config::default().multimodal.ocr.enabled);
+    }
+
     #[test]
     async fn security_defaults_are_backward_compatible() {
         let parsed: Config = toml::from_str(
@@ -0,0 +1,414 @@
+//! Digest batching for proactive channel output.
+//!
+//! Cron announcements and heartbeat results addressed to a channel listed in
+//! `[digest.channels]` are queued in `cron/digest.db` instead of being sent
+//! immediately. Each channel has its own `interval_minutes` and next run time,
+//! stored next to the queue; when it comes due the worker delivers one
+//! combined summary per recipient on that channel.
+
+use crate::config::Config;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use tokio::time::{self, Duration};
+
+const DIGEST_COMPONENT: &str = "digest";
+/// How often the worker checks for channels whose digest is due.
+const DIGEST_POLL_SECS: u64 = 60;
+/// Per-item character cap inside a rendered digest.
+const DIGEST_ITEM_MAX_CHARS: usize = 400;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestItem {
+    pub id: i64,
+    pub channel: String,
+    pub target: String,
+    pub source: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Queue proactive output for the next digest delivery.
+pub fn enqueue(
+    config: &Config,
+    channel: &str,
+    target: &str,
+    source: &str,
+    content: &str,
+) -> Result<()> {
+    with_connection(config, |conn| {
+        conn.execute(
+            "INSERT INTO digest_items (channel, target, source, content, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                channel.to_ascii_lowercase(),
+                target,
+                source,
+                content,
+                Utc::now().to_rfc3339()
+            ],
+        )
+        .context("Failed to enqueue digest item")?;
+        Ok(())
+    })
+}
+
+/// List queued items, oldest first.
+pub fn pending_items(config: &Config) -> Result<Vec<DigestItem>> {
+    with_connection(config, |conn| {
+        let mut stmt = conn.prepare(
+            "SELECT id, channel, target, source, content, created_at
+             FROM digest_items ORDER BY id ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let created_raw: String = row.get(5)?;
+            Ok(DigestItem {
+                id: row.get(0)?,
+                channel: row.get(1)?,
+                target: row.get(2)?,
+                source: row.get(3)?,
+                content: row.get(4)?,
+                created_at: DateTime::parse_from_rfc3339(&created_raw)
+                    .map(|d| d.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to read digest queue")
+    })
+}
+
+fn remove_items(config: &Config, ids: &[i64]) -> Result<()> {
+    with_connection(config, |conn| {
+        let tx = conn.unchecked_transaction()?;
+        for id in ids {
+            tx.execute("DELETE FROM digest_items WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(())
+    })
+}
+
+/// Render queued items for one recipient into a single message.
+///
+/// Items beyond `max_items` are collapsed into a trailing count so a long
+/// outage of the digest worker cannot produce an unbounded message.
+pub fn render_digest(items: &[DigestItem], max_items: usize) -> String {
+    let max_items = max_items.max(1);
+    let mut out = format!(
+        "📋 Digest — {} update{}\n",
+        items.len(),
+        if items.len() == 1 { "" } else { "s" }
+    );
+
+    for item in items.iter().take(max_items) {
+        let content = truncate_with_ellipsis(item.content.trim(), DIGEST_ITEM_MAX_CHARS);
+        let _ = write!(
+            out,
+            "\n• [{}] {} ({})",
+            item.source,
+            content,
+            item.created_at.format("%H:%M")
+        );
+    }
+
+    if items.len() > max_items {
+        let _ = write!(out, "\n\n…and {} more not shown.", items.len() - max_items);
+    }
+
+    out
+}
+
+/// Deliver the pending digests of `channel`. Returns the number of digests
+/// sent.
+///
+/// Items are removed only after their recipient's digest is delivered, so a
+/// failed send is retried on the next flush.
+pub async fn flush_channel(config: &Config, channel: &str) -> Result<usize> {
+    let mut grouped: BTreeMap<String, Vec<DigestItem>> = BTreeMap::new();
+    for item in pending_items(config)? {
+        if item.channel == channel {
+            grouped.entry(item.target.clone()).or_default().push(item);
+        }
+    }
+
+    let mut sent = 0;
+    for (target, items) in grouped {
+        let message = render_digest(&items, config.digest.max_items);
+        match super::scheduler::deliver_announcement(config, channel, &target, &message).await {
+            Ok(()) => {
+                let ids: Vec<i64> = items.iter().map(|item| item.id).collect();
+                remove_items(config, &ids)?;
+                sent += 1;
+            }
+            Err(e) => {
+                tracing::warn!("Digest delivery to {channel}:{target} failed: {e}");
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+/// Deliver the digests of every channel whose next run is at or before `now`,
+/// then schedule its next run one interval later. Returns the number of
+/// digests sent.
+///
+/// A channel seen for the first time is scheduled without flushing, so a new
+/// or restarted worker does not send a partially filled digest. Items queued
+/// for a channel that is no longer listed are delivered right away.
+pub async fn flush_due(config: &Config, now: DateTime<Utc>) -> Result<usize> {
+    let mut sent = 0;
+    let mut listed = Vec::new();
+    for (name, channel_config) in &config.digest.channels {
+        let channel = name.trim().to_ascii_lowercase();
+        let interval = chrono::Duration::minutes(i64::from(channel_config.interval_minutes.max(1)));
+        match next_run(config, &channel)? {
+            Some(at) if at <= now => {
+                sent += flush_channel(config, &channel).await?;
+                set_next_run(config, &channel, now + interval)?;
+            }
+            Some(_) => {}
+            None => set_next_run(config, &channel, now + interval)?,
+        }
+        listed.push(channel);
+    }
+
+    let mut unlisted: Vec<String> = pending_items(config)?
+        .into_iter()
+        .map(|item| item.channel)
+        .filter(|channel| !listed.contains(channel))
+        .collect();
+    unlisted.sort();
+    unlisted.dedup();
+    for channel in unlisted {
+        sent += flush_channel(config, &channel).await?;
+    }
+
+    Ok(sent)
+}
+
+fn next_run(config: &Config, channel: &str) -> Result<Option<DateTime<Utc>>> {
+    with_connection(config, |conn| {
+        let raw: Option<String> = conn
+            .query_row(
+                "SELECT next_run FROM digest_schedule WHERE channel = ?1",
+                params![channel],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Failed to read digest schedule")?;
+        Ok(raw
+            .and_then(|raw| DateTime::parse_from_rfc3339(&raw).ok())
+            .map(|at| at.with_timezone(&Utc)))
+    })
+}
+
+fn set_next_run(config: &Config, channel: &str, at: DateTime<Utc>) -> Result<()> {
+    with_connection(config, |conn| {
+        conn.execute(
+            "INSERT INTO digest_schedule (channel, next_run) VALUES (?1, ?2)
+             ON CONFLICT(channel) DO UPDATE SET next_run = excluded.next_run",
+            params![channel, at.to_rfc3339()],
+        )
+        .context("Failed to update digest schedule")?;
+        Ok(())
+    })
+}
+
+/// Run the digest flush loop.
+pub async fn run(config: Config) -> Result<()> {
+    let mut interval = time::interval(Duration::from_secs(DIGEST_POLL_SECS));
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+
+    crate::health::mark_component_ok(DIGEST_COMPONENT);
+
+    loop {
+        interval.tick().await;
+        match flush_due(&config, Utc::now()).await {
+            Ok(_) => crate::health::mark_component_ok(DIGEST_COMPONENT),
+            Err(e) => {
+                crate::health::mark_component_error(DIGEST_COMPONENT, e.to_string());
+                tracing::warn!("Digest flush failed: {e}");
+            }
+        }
+    }
+}
+
+fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    let db_path = config.workspace_dir.join("cron").join("digest.db");
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create cron directory: {}", parent.display()))?;
+    }
+
+    let conn = Connection::open(&db_path)
+        .with_context(|| format!("Failed to open digest DB: {}", db_path.display()))?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS digest_items (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            channel    TEXT NOT NULL,
+            target     TEXT NOT NULL,
+            source     TEXT NOT NULL,
+            content    TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS digest_schedule (
+            channel  TEXT PRIMARY KEY,
+            next_run TEXT NOT NULL
+        );",
+    )
+    .context("Failed to initialize digest schema")?;
+
+    f(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChannelDigestConfig;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Config {
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        config
+    }
+
+    fn item(id: i64, source: &str, content: &str) -> DigestItem {
+        DigestItem {
+            id,
+            channel: "telegram".into(),
+            target: "123".into(),
+            source: source.into(),
+            content: content.into(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn enqueue_persists_items_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        enqueue(&config, "Telegram", "123", "cron:feeds", "first").unwrap();
+        enqueue(&config, "telegram", "123", "heartbeat", "second").unwrap();
+
+        let items = pending_items(&config).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].channel, "telegram");
+        assert_eq!(items[0].content, "first");
+        assert_eq!(items[1].source, "heartbeat");
+    }
+
+    #[test]
+    fn render_digest_lists_items_with_sources() {
+        let text = render_digest(
+            &[
+                item(1, "cron:feeds", "new post"),
+                item(2, "heartbeat", "ok"),
+            ],
+            10,
+        );
+        assert!(text.contains("2 updates"));
+        assert!(text.contains("[cron:feeds] new post"));
+        assert!(text.contains("[heartbeat] ok"));
+        assert!(!text.contains("more not shown"));
+    }
+
+    #[test]
+    fn render_digest_caps_items() {
+        let items: Vec<DigestItem> = (0..5).map(|i| item(i, "cron", "x")).collect();
+        let text = render_digest(&items, 2);
+        assert!(text.contains("5 updates"));
+        assert!(text.contains("…and 3 more not shown."));
+    }
+
+    #[test]
+    fn render_digest_truncates_long_items() {
+        let long = "a".repeat(DIGEST_ITEM_MAX_CHARS * 2);
+        let text = render_digest(&[item(1, "cron", &long)], 10);
+        assert!(text.len() < long.len());
+    }
+
+    #[tokio::test]
+    async fn flush_keeps_items_when_delivery_fails() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        // No telegram channel configured, so delivery fails.
+        enqueue(&config, "telegram", "123", "cron", "alert").unwrap();
+        let sent = flush_channel(&config, "telegram").await.unwrap();
+
+        assert_eq!(sent, 0);
+        assert_eq!(pending_items(&config).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn each_channel_runs_on_its_own_interval() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        config.digest.enabled = true;
+        config.digest.channels = HashMap::from([
+            (
+                "Telegram".into(),
+                ChannelDigestConfig {
+                    interval_minutes: 30,
+                },
+            ),
+            (
+                "discord".into(),
+                ChannelDigestConfig {
+                    interval_minutes: 120,
+                },
+            ),
+        ]);
+        let start = Utc::now();
+        let minutes = chrono::Duration::minutes;
+
+        // First sight only schedules each channel.
+        assert_eq!(flush_due(&config, start).await.unwrap(), 0);
+        assert_eq!(
+            next_run(&config, "telegram").unwrap(),
+            Some(start + minutes(30))
+        );
+        assert_eq!(
+            next_run(&config, "discord").unwrap(),
+            Some(start + minutes(120))
+        );
+
+        // Telegram comes due and is rescheduled; discord keeps its time.
+        let later = start + minutes(31);
+        flush_due(&config, later).await.unwrap();
+        assert_eq!(
+            next_run(&config, "telegram").unwrap(),
+            Some(later + minutes(30))
+        );
+        assert_eq!(
+            next_run(&config, "discord").unwrap(),
+            Some(start + minutes(120))
+        );
+    }
+
+    #[test]
+    fn digest_config_applies_only_when_enabled_and_listed() {
+        let mut digest = crate::config::DigestConfig {
+            channels: HashMap::from([("Telegram".into(), ChannelDigestConfig::default())]),
+            ..crate::config::DigestConfig::default()
+        };
+        assert!(!digest.applies_to("telegram"));
+
+        digest.enabled = true;
+        assert!(digest.applies_to("telegram"));
+        assert!(!digest.applies_to("discord"));
+    }
+}
@@ -1,10 +1,12 @@
 use crate::channels::{
-    Channel, DiscordChannel, MattermostChannel, SendMessage, SlackChannel, TelegramChannel,
+    Channel, DiscordChannel, EmailChannel, MattermostChannel, SendMessage, SlackChannel,
+    TelegramChannel,
 };
 use crate::config::Config;
 use crate::cron::{
     due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
-    update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
+    sync_config_jobs, update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule,
+    SessionTarget,
 };
 use crate::security::SecurityPolicy;
 use anyhow::Result;
@@ -18,6 +20,9 @@ use tokio::time::{self, Duration};
 const MIN_POLL_SECONDS: u64 = 5;
 const SHELL_JOB_TIMEOUT_SECS: u64 = 120;
 const SCHEDULER_COMPONENT: &str = "scheduler";
+/// Delivery channel name that targets a `[channels_config.broadcast_groups]`
+/// group instead of a single chat.
+pub(crate) const BROADCAST_CHANNEL: &str = "broadcast";
 
 pub async fn run(config: Config) -> Result<()> {
     let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
@@ -28,6 +33,10 @@ pub async fn run(config: Config) -> Result<()> {
         &config.workspace_dir,
     ));
 
+    if let Err(e) = sync_config_jobs(&config) {
+        tracing::warn!("Failed to sync [[cron.jobs]] into the job store: {e:#}");
+    }
+
     crate::health::mark_component_ok(SCHEDULER_COMPONENT);
 
     loop {
@@ -66,6 +75,7 @@ async fn execute_job_with_retry(
         let (success, output) = match job.job_type {
             JobType::Shell => run_job_command(config, security, job).await,
             JobType::Agent => run_agent_job(config, security, job).await,
+            JobType::Message => (true, job.prompt.clone().unwrap_or_default()),
         };
         last_output = output;
 
@@ -98,18 +108,21 @@ async fn process_due_jobs(
     crate::health::mark_component_ok(component);
 
     let max_concurrent = config.scheduler.max_concurrent.max(1);
-    let mut in_flight =
-        stream::iter(
-            jobs.into_iter().map(|job| {
-                let config = config.clone();
-                let security = Arc::clone(security);
-                let component = component.to_owned();
-                async move {
-                    execute_and_persist_job(&config, security.as_ref(), &job, &component).await
-                }
-            }),
-        )
-        .buffer_unordered(max_concurrent);
+    let mut in_flight = stream::iter(jobs.into_iter().map(|job| {
+        let config = config.clone();
+        let security = Arc::clone(security);
+        let component = component.to_owned();
+        async move {
+            Box::pin(execute_and_persist_job(
+                &config,
+                security.as_ref(),
+                &job,
+                &component,
+            ))
+            .await
+        }
+    }))
+    .buffer_unordered(max_concurrent);
 
     while let Some((job_id, success, output)) = in_flight.next().await {
         if !success {
@@ -167,7 +180,7 @@ async fn run_agent_job(
 
     let run_result = match job.session_target {
         SessionTarget::Main | SessionTarget::Isolated => {
-            crate::agent::run(
+            Box::pin(crate::agent::run(
                 config.clone(),
                 Some(prefixed_prompt),
                 None,
@@ -175,7 +188,7 @@ async fn run_agent_job(
                 config.default_temperature,
                 vec![],
                 false,
-            )
+            ))
             .await
         }
     };
@@ -296,9 +309,110 @@ async fn deliver_if_configured(config: &Config, job: &CronJob, output: &str) ->
         .as_deref()
         .ok_or_else(|| anyhow::anyhow!("delivery.to is required for announce mode"))?;
 
+    let source = format!("cron:{}", job.name.as_deref().unwrap_or(&job.id));
+    deliver_proactive(config, channel, target, &source, output).await
+}
+
+/// Deliver proactive output, queueing it for the digest when the target
+/// channel is in digest mode (`[digest].channels`). Channel `"broadcast"`
+/// delivers to every destination of the broadcast group named by `target`.
+pub(crate) async fn deliver_proactive(
+    config: &Config,
+    channel: &str,
+    target: &str,
+    source: &str,
+    output: &str,
+) -> Result<()> {
+    if channel.eq_ignore_ascii_case(BROADCAST_CHANNEL) {
+        return deliver_broadcast(config, target, source, output).await;
+    }
+    deliver_to_destination(config, channel, target, source, output).await
+}
+
+async fn deliver_to_destination(
+    config: &Config,
+    channel: &str,
+    target: &str,
+    source: &str,
+    output: &str,
+) -> Result<()> {
+    if config.digest.applies_to(channel) {
+        return super::digest::enqueue(config, channel, target, source, output);
+    }
     deliver_announcement(config, channel, target, output).await
 }
 
+/// Split a `"<channel>:<target>"` broadcast destination.
+fn parse_broadcast_destination(destination: &str) -> Option<(&str, &str)> {
+    let (channel, target) = destination.trim().split_once(':')?;
+    let (channel, target) = (channel.trim(), target.trim());
+    (!channel.is_empty() && !target.is_empty()).then_some((channel, target))
+}
+
+/// Deliver `output` to every destination of broadcast group `group`.
+///
+/// Each destination goes through its own channel's `send`, so formatting
+/// is applied per channel, and one failing destination does not stop the
+/// others. Fails when the group is unknown or empty, or when any
+/// destination failed (after all were attempted).
+pub(crate) async fn deliver_broadcast(
+    config: &Config,
+    group: &str,
+    source: &str,
+    output: &str,
+) -> Result<()> {
+    let destinations = config
+        .channels_config
+        .broadcast_groups
+        .get(group)
+        .filter(|destinations| !destinations.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("unknown or empty broadcast group: {group}"))?;
+
+    let mut deliveries = Vec::with_capacity(destinations.len());
+    for destination in destinations {
+        deliveries.push(async move {
+            let Some((channel, target)) = parse_broadcast_destination(destination) else {
+                return Some(format!("{destination}: expected <channel>:<target>"));
+            };
+            if channel.eq_ignore_ascii_case(BROADCAST_CHANNEL) {
+                return Some(format!(
+                    "{destination}: nested broadcast groups are not supported"
+                ));
+            }
+            deliver_to_destination(config, channel, target, source, output)
+                .await
+                .err()
+                .map(|e| format!("{destination}: {e}"))
+        });
+    }
+    let failures: Vec<String> = futures_util::future::join_all(deliveries)
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+    for failure in &failures {
+        tracing::warn!("Broadcast to group '{group}' failed for {failure}");
+    }
+    anyhow::bail!(
+        "broadcast to group '{group}' failed for {} of {} destinations: {}",
+        failures.len(),
+        destinations.len(),
+        failures.join("; ")
+    )
+}
+
+/// Channels [`deliver_announcement`] can send to.
+pub(crate) fn supports_announcement(channel: &str) -> bool {
+    matches!(
+        channel.to_ascii_lowercase().as_str(),
+        "telegram" | "discord" | "slack" | "mattermost" | "email"
+    )
+}
+
 pub(crate) async fn deliver_announcement(
     config: &Config,
     channel: &str,
@@ -363,6 +477,15 @@ pub(crate) async fn deliver_announcement(
             );
             channel.send(&SendMessage::new(output, target)).await?;
         }
+        "email" => {
+            let em = config
+                .channels_config
+                .email
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("email channel not configured"))?;
+            let channel = EmailChannel::new(em.clone());
+            channel.send(&SendMessage::new(output, target)).await?;
+        }
         other => anyhow::bail!("unsupported delivery channel: {other}"),
     }
 
@@ -1011,4 +1134,72 @@ mod tests {
         let err = deliver_if_configured(&config, &job, "x").await.unwrap_err();
         assert!(err.to_string().contains("unsupported delivery channel"));
     }
+
+    #[tokio::test]
+    async fn deliver_if_configured_queues_digest_channels() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.digest.enabled = true;
+        config.digest.channels.insert(
+            "telegram".into(),
+            crate::config::ChannelDigestConfig::default(),
+        );
+        let mut job = test_job("echo ok");
+        job.name = Some("feeds".into());
+        job.delivery = DeliveryConfig {
+            mode: "announce".into(),
+            channel: Some("telegram".into()),
+            to: Some("123".into()),
+            best_effort: true,
+        };
+
+        // Telegram is not configured, so only the digest path can succeed.
+        deliver_if_configured(&config, &job, "new item")
+            .await
+            .unwrap();
+
+        let items = crate::cron::digest::pending_items(&config).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].source, "cron:feeds");
+        assert_eq!(items[0].content, "new item");
+    }
+
+    #[tokio::test]
+    async fn deliver_broadcast_attempts_every_destination() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.digest.enabled = true;
+        config.digest.channels.insert(
+            "telegram".into(),
+            crate::config::ChannelDigestConfig::default(),
+        );
+        config.channels_config.broadcast_groups.insert(
+            "reports".into(),
+            vec![
+                "invalid:target".into(),
+                "telegram:123".into(),
+                "nocolon".into(),
+            ],
+        );
+
+        let err = deliver_proactive(&config, "broadcast", "reports", "cron:daily", "report")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("2 of 3 destinations"), "{err}");
+        assert!(
+            err.contains("unsupported delivery channel: invalid"),
+            "{err}"
+        );
+
+        // The telegram destination was still delivered (to the digest queue).
+        let items = crate::cron::digest::pending_items(&config).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].content, "report");
+
+        let err = deliver_proactive(&config, "broadcast", "missing", "cron:daily", "x")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown or empty broadcast group"));
+    }
 }
@@ -1,4 +1,5 @@
-use crate::config::Config;
+use crate::config::{Config, CronJobConfig};
+use crate::cron::scheduler::{supports_announcement, BROADCAST_CHANNEL};
 use crate::cron::{
     next_run_for_schedule, schedule_cron_expression, validate_schedule, CronJob, CronJobPatch,
     CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
@@ -11,6 +12,8 @@ use uuid::Uuid;
 
 const MAX_CRON_OUTPUT_BYTES: usize = 16 * 1024;
 const TRUNCATED_OUTPUT_MARKER: &str = "\n...[truncated]";
+/// ID prefix of the jobs declared in `[[cron.jobs]]`.
+const CONFIG_JOB_ID_PREFIX: &str = "config:";
 
 impl rusqlite::types::FromSql for JobType {
     fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
@@ -113,6 +116,154 @@ pub fn add_agent_job(
     get_job(config, &id)
 }
 
+/// Add a job that delivers `message` as-is, without running the agent.
+/// One-shot (`at`) jobs are deleted after they ran.
+pub fn add_message_job(
+    config: &Config,
+    name: Option<String>,
+    schedule: Schedule,
+    message: &str,
+    delivery: DeliveryConfig,
+) -> Result<CronJob> {
+    if !delivery.mode.eq_ignore_ascii_case("announce") {
+        anyhow::bail!("message jobs need delivery mode 'announce'");
+    }
+    let now = Utc::now();
+    validate_schedule(&schedule, now)?;
+    let next_run = next_run_for_schedule(&schedule, now)?;
+    let id = Uuid::new_v4().to_string();
+    let expression = schedule_cron_expression(&schedule).unwrap_or_default();
+    let schedule_json = serde_json::to_string(&schedule)?;
+    let delete_after_run = matches!(schedule, Schedule::At { .. });
+
+    with_connection(config, |conn| {
+        conn.execute(
+            "INSERT INTO cron_jobs (
+                id, expression, command, schedule, job_type, prompt, name, session_target, model,
+                enabled, delivery, delete_after_run, created_at, next_run
+             ) VALUES (?1, ?2, '', ?3, 'message', ?4, ?5, 'isolated', NULL, 1, ?6, ?7, ?8, ?9)",
+            params![
+                id,
+                expression,
+                schedule_json,
+                message,
+                name,
+                serde_json::to_string(&delivery)?,
+                if delete_after_run { 1 } else { 0 },
+                now.to_rfc3339(),
+                next_run.to_rfc3339(),
+            ],
+        )
+        .context("Failed to insert cron message job")?;
+        Ok(())
+    })?;
+
+    get_job(config, &id)
+}
+
+/// Make the stored `[[cron.jobs]]` match the config: declared jobs are
+/// added or updated in place and jobs no longer declared are removed. A job
+/// keeps its next run unless its schedule changed or it was paused. Invalid
+/// entries are skipped with a warning.
+pub fn sync_config_jobs(config: &Config) -> Result<()> {
+    let now = Utc::now();
+    with_connection(config, |conn| {
+        let tx = conn.unchecked_transaction()?;
+        let mut declared = Vec::new();
+
+        for job in &config.cron.jobs {
+            let name = job.name.trim();
+            let (schedule, delivery) = match config_job_parts(job, now) {
+                Ok(parts) => parts,
+                Err(e) => {
+                    tracing::warn!("Skipping cron job '{name}' from config: {e:#}");
+                    continue;
+                }
+            };
+            let id = format!("{CONFIG_JOB_ID_PREFIX}{name}");
+            tx.execute(
+                "INSERT INTO cron_jobs (
+                    id, expression, command, schedule, job_type, prompt, name, session_target, model,
+                    enabled, delivery, delete_after_run, created_at, next_run
+                 ) VALUES (?1, ?2, '', ?3, 'agent', ?4, ?5, 'isolated', ?6, ?7, ?8, 0, ?9, ?10)
+                 ON CONFLICT(id) DO UPDATE SET
+                    expression = excluded.expression, command = '', job_type = 'agent',
+                    prompt = excluded.prompt, name = excluded.name, session_target = 'isolated',
+                    model = excluded.model, enabled = excluded.enabled, delivery = excluded.delivery,
+                    delete_after_run = 0,
+                    next_run = CASE
+                        WHEN cron_jobs.schedule IS excluded.schedule AND cron_jobs.enabled = 1
+                        THEN cron_jobs.next_run
+                        ELSE excluded.next_run
+                    END,
+                    schedule = excluded.schedule",
+                params![
+                    id,
+                    schedule_cron_expression(&schedule).unwrap_or_default(),
+                    serde_json::to_string(&schedule)?,
+                    job.prompt.trim(),
+                    name,
+                    job.model,
+                    if job.enabled { 1 } else { 0 },
+                    serde_json::to_string(&delivery)?,
+                    now.to_rfc3339(),
+                    next_run_for_schedule(&schedule, now)?.to_rfc3339(),
+                ],
+            )
+            .with_context(|| format!("Failed to sync cron job '{name}'"))?;
+            declared.push(id);
+        }
+
+        let stored: Vec<String> = {
+            let mut stmt = tx.prepare("SELECT id FROM cron_jobs WHERE id LIKE ?1")?;
+            let rows = stmt.query_map(params![format!("{CONFIG_JOB_ID_PREFIX}%")], |row| {
+                row.get(0)
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+        for id in stored.iter().filter(|id| !declared.contains(id)) {
+            tx.execute("DELETE FROM cron_jobs WHERE id = ?1", params![id])
+                .context("Failed to remove cron job dropped from config")?;
+        }
+
+        tx.commit()
+            .context("Failed to commit cron config job sync")?;
+        Ok(())
+    })
+}
+
+/// Schedule and delivery of a `[[cron.jobs]]` entry.
+fn config_job_parts(job: &CronJobConfig, now: DateTime<Utc>) -> Result<(Schedule, DeliveryConfig)> {
+    let schedule = Schedule::Cron {
+        expr: job.cron.trim().to_string(),
+        tz: job
+            .tz
+            .as_deref()
+            .map(str::trim)
+            .filter(|tz| !tz.is_empty())
+            .map(str::to_string),
+    };
+    validate_schedule(&schedule, now)?;
+
+    let Some(deliver_to) = job.deliver_to.as_deref() else {
+        return Ok((schedule, DeliveryConfig::default()));
+    };
+    let (channel, to) = deliver_to
+        .split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("deliver_to must be '<channel>:<recipient>'"))?;
+    let channel = channel.trim().to_ascii_lowercase();
+    if channel != BROADCAST_CHANNEL && !supports_announcement(&channel) {
+        anyhow::bail!("unsupported delivery channel '{channel}'");
+    }
+    let delivery = DeliveryConfig {
+        mode: "announce".into(),
+        channel: Some(channel),
+        to: Some(to.trim().to_string()),
+        best_effort: true,
+    };
+    Ok((schedule, delivery))
+}
+
 pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
@@ -728,6 +879,91 @@ mod tests {
         assert_eq!(job.job_type, JobType::Agent);
     }
 
+    #[test]
+    fn add_message_job_stores_text_and_requires_announce_delivery() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let schedule = Schedule::At {
+            at: Utc::now() + ChronoDuration::hours(1),
+        };
+        let delivery = DeliveryConfig {
+            mode: "announce".into(),
+            channel: Some("telegram".into()),
+            to: Some("12345".into()),
+            best_effort: false,
+        };
+
+        let job = add_message_job(
+            &config,
+            None,
+            schedule.clone(),
+            "Stand-up in 5 minutes",
+            delivery,
+        )
+        .unwrap();
+        assert_eq!(job.job_type, JobType::Message);
+        assert_eq!(job.prompt.as_deref(), Some("Stand-up in 5 minutes"));
+        assert!(job.delete_after_run);
+        assert_eq!(job.delivery.to.as_deref(), Some("12345"));
+
+        assert!(add_message_job(&config, None, schedule, "hi", DeliveryConfig::default()).is_err());
+    }
+
+    #[test]
+    fn sync_config_jobs_mirrors_declared_jobs() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        let manual = add_job(&config, "*/5 * * * *", "echo manual").unwrap();
+        config.cron.jobs = vec![
+            CronJobConfig {
+                name: "weekly-issues".into(),
+                cron: "0 9 * * MON".into(),
+                tz: Some("Europe/Berlin".into()),
+                prompt: "Summarize last week's GitHub issues".into(),
+                deliver_to: Some("slack:C0123456".into()),
+                model: None,
+                enabled: true,
+            },
+            CronJobConfig {
+                name: "broken".into(),
+                cron: "not a cron".into(),
+                tz: None,
+                prompt: "never stored".into(),
+                deliver_to: None,
+                model: None,
+                enabled: true,
+            },
+        ];
+
+        sync_config_jobs(&config).unwrap();
+        let job = get_job(&config, "config:weekly-issues").unwrap();
+        assert_eq!(job.job_type, JobType::Agent);
+        assert_eq!(job.expression, "0 9 * * MON");
+        assert_eq!(job.delivery.mode, "announce");
+        assert_eq!(job.delivery.channel.as_deref(), Some("slack"));
+        assert_eq!(job.delivery.to.as_deref(), Some("C0123456"));
+        assert!(get_job(&config, "config:broken").is_err());
+
+        // Re-syncing keeps the next run; only the changed prompt is updated.
+        config.cron.jobs[0].prompt = "Summarize open pull requests".into();
+        sync_config_jobs(&config).unwrap();
+        let updated = get_job(&config, "config:weekly-issues").unwrap();
+        assert_eq!(
+            updated.prompt.as_deref(),
+            Some("Summarize open pull requests")
+        );
+        assert_eq!(updated.next_run, job.next_run);
+
+        config.cron.jobs.clear();
+        sync_config_jobs(&config).unwrap();
+        let ids: Vec<String> = list_jobs(&config)
+            .unwrap()
+            .into_iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(ids, vec![manual.id]);
+    }
+
     #[test]
     fn job_type_from_sql_rejects_invalid_value() {
         let tmp = TempDir::new().unwrap();
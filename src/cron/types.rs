@@ -7,6 +7,8 @@ pub enum JobType {
     #[default]
     Shell,
     Agent,
+    /// Sends the fixed text in `prompt` through the job's delivery.
+    Message,
 }
 
 impl From<JobType> for &'static str {
@@ -14,6 +16,7 @@ impl From<JobType> for &'static str {
         match value {
             JobType::Shell => "shell",
             JobType::Agent => "agent",
+            JobType::Message => "message",
         }
     }
 }
@@ -25,8 +28,9 @@ impl TryFrom<&str> for JobType {
         match value.to_lowercase().as_str() {
             "shell" => Ok(JobType::Shell),
             "agent" => Ok(JobType::Agent),
+            "message" => Ok(JobType::Message),
             _ => Err(format!(
-                "Invalid job type '{}'. Expected one of: 'shell', 'agent'",
+                "Invalid job type '{}'. Expected one of: 'shell', 'agent', 'message'",
                 value
             )),
         }
@@ -156,6 +160,7 @@ mod tests {
         assert_eq!(JobType::try_from("SHELL").unwrap(), JobType::Shell);
         assert_eq!(JobType::try_from("agent").unwrap(), JobType::Agent);
         assert_eq!(JobType::try_from("AgEnT").unwrap(), JobType::Agent);
+        assert_eq!(JobType::try_from("message").unwrap(), JobType::Message);
     }
 
     #[test]
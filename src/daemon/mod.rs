@@ -7,6 +7,9 @@ use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
 const STATUS_FLUSH_SECONDS: u64 = 5;
+/// Time on top of `shutdown_drain_secs` for flushing queues and closing
+/// connections before the remaining components are aborted.
+const SHUTDOWN_GRACE_SECONDS: u64 = 15;
 
 pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     let initial_backoff = config.reliability.channel_initial_backoff_secs.max(1);
@@ -16,6 +19,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         .max(initial_backoff);
 
     crate::health::mark_component_ok("daemon");
+    crate::shutdown::listen_for_signals();
 
     if config.heartbeat.enabled {
         let _ =
@@ -24,18 +28,20 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     }
 
     let mut handles: Vec<JoinHandle<()>> = vec![spawn_state_writer(config.clone())];
+    // Components that drain in-flight work themselves on shutdown.
+    let mut draining: Vec<JoinHandle<()>> = Vec::new();
 
     {
         let gateway_cfg = config.clone();
         let gateway_host = host.clone();
-        handles.push(spawn_component_supervisor(
+        draining.push(spawn_component_supervisor(
             "gateway",
             initial_backoff,
             max_backoff,
             move || {
                 let cfg = gateway_cfg.clone();
                 let host = gateway_host.clone();
-                async move { crate::gateway::run_gateway(&host, port, cfg).await }
+                async move { Box::pin(crate::gateway::run_gateway(&host, port, cfg)).await }
             },
         ));
     }
@@ -43,13 +49,13 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     {
         if has_supervised_channels(&config) {
             let channels_cfg = config.clone();
-            handles.push(spawn_component_supervisor(
+            draining.push(spawn_component_supervisor(
                 "channels",
                 initial_backoff,
                 max_backoff,
                 move || {
                     let cfg = channels_cfg.clone();
-                    async move { crate::channels::start_channels(cfg).await }
+                    async move { Box::pin(crate::channels::start_channels(cfg)).await }
                 },
             ));
         } else {
@@ -79,7 +85,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
             max_backoff,
             move || {
                 let cfg = scheduler_cfg.clone();
-                async move { crate::cron::scheduler::run(cfg).await }
+                async move { Box::pin(crate::cron::scheduler::run(cfg)).await }
             },
         ));
     } else {
@@ -87,20 +93,78 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         tracing::info!("Cron disabled; scheduler supervisor not started");
     }
 
+    if config.digest.enabled {
+        let digest_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "digest",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = digest_cfg.clone();
+                async move { Box::pin(crate::cron::digest::run(cfg)).await }
+            },
+        ));
+    }
+
+    if config.feeds.enabled {
+        let feeds_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "feeds",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = feeds_cfg.clone();
+                async move { Box::pin(crate::feeds::run(cfg)).await }
+            },
+        ));
+    }
+
+    if config.knowledge.enabled {
+        let knowledge_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "knowledge",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = knowledge_cfg.clone();
+                async move { Box::pin(crate::knowledge::run(cfg)).await }
+            },
+        ));
+    }
+
     println!("🧠 ZeroClaw daemon started");
     println!("   Gateway:  http://{host}:{port}");
     println!("   Components: gateway, channels, heartbeat, scheduler");
     println!("   Ctrl+C to stop");
 
-    tokio::signal::ctrl_c().await?;
+    crate::shutdown::requested().await;
     crate::health::mark_component_error("daemon", "shutdown requested");
+    let drain_secs = config.reliability.shutdown_drain_secs;
+    println!("🛑 Shutting down: finishing in-flight messages (up to {drain_secs}s)...");
+
+    let drained = tokio::time::timeout(
+        Duration::from_secs(drain_secs + SHUTDOWN_GRACE_SECONDS),
+        async {
+            while let Some(handle) = draining.last_mut() {
+                let _ = handle.await;
+                draining.pop();
+            }
+        },
+    )
+    .await;
+    if drained.is_err() {
+        tracing::warn!("Gateway or channels did not stop in time; aborting them");
+    }
 
-    for handle in &handles {
+    for handle in draining.iter().chain(&handles) {
         handle.abort();
     }
-    for handle in handles {
+    for handle in draining.into_iter().chain(handles) {
         let _ = handle.await;
     }
+    // Let the state file show the daemon as stopped.
+    crate::health::mark_component_error("daemon", "stopped");
+    let _ = write_state_file(&config).await;
 
     Ok(())
 }
@@ -123,19 +187,23 @@ fn spawn_state_writer(config: Config) -> JoinHandle<()> {
         let mut interval = tokio::time::interval(Duration::from_secs(STATUS_FLUSH_SECONDS));
         loop {
             interval.tick().await;
-            let mut json = crate::health::snapshot_json();
-            if let Some(obj) = json.as_object_mut() {
-                obj.insert(
-                    "written_at".into(),
-                    serde_json::json!(Utc::now().to_rfc3339()),
-                );
-            }
-            let data = serde_json::to_vec_pretty(&json).unwrap_or_else(|_| b"{}".to_vec());
-            let _ = tokio::fs::write(&path, data).await;
+            let _ = write_state_file(&config).await;
         }
     })
 }
 
+async fn write_state_file(config: &Config) -> std::io::Result<()> {
+    let mut json = crate::health::snapshot_json();
+    if let Some(obj) = json.as_object_mut() {
+        obj.insert(
+            "written_at".into(),
+            serde_json::json!(Utc::now().to_rfc3339()),
+        );
+    }
+    let data = serde_json::to_vec_pretty(&json).unwrap_or_else(|_| b"{}".to_vec());
+    tokio::fs::write(state_file_path(config), data).await
+}
+
 fn spawn_component_supervisor<F, Fut>(
     name: &'static str,
     initial_backoff_secs: u64,
@@ -152,7 +220,15 @@ where
 
         loop {
             crate::health::mark_component_ok(name);
-            match run_component().await {
+            let result = run_component().await;
+            if crate::shutdown::is_requested() {
+                if let Err(e) = result {
+                    tracing::warn!("Daemon component '{name}' failed while shutting down: {e}");
+                }
+                crate::health::mark_component_error(name, "stopped");
+                break;
+            }
+            match result {
                 Ok(()) => {
                     crate::health::mark_component_error(name, "component exited unexpectedly");
                     tracing::warn!("Daemon component '{name}' exited unexpectedly");
@@ -166,7 +242,10 @@ where
             }
 
             crate::health::bump_component_restart(name);
-            tokio::time::sleep(Duration::from_secs(backoff)).await;
+            tokio::select! {
+                () = tokio::time::sleep(Duration::from_secs(backoff)) => {}
+                () = crate::shutdown::requested() => break,
+            }
             // Double backoff AFTER sleeping so first error uses initial_backoff
             backoff = backoff.saturating_mul(2).min(max_backoff);
         }
@@ -198,7 +277,7 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
         for task in tasks {
             let prompt = format!("[Heartbeat Task] {task}");
             let temp = config.default_temperature;
-            match crate::agent::run(
+            match Box::pin(crate::agent::run(
                 config.clone(),
                 Some(prompt),
                 None,
@@ -206,7 +285,7 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
                 temp,
                 vec![],
                 false,
-            )
+            ))
             .await
             {
                 Ok(output) => {
@@ -217,10 +296,11 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
                         output
                     };
                     if let Some((channel, target)) = &delivery {
-                        if let Err(e) = crate::cron::scheduler::deliver_announcement(
+                        if let Err(e) = crate::cron::scheduler::deliver_proactive(
                             &config,
                             channel,
                             target,
+                            "heartbeat",
                             &announcement,
                         )
                         .await
@@ -312,6 +392,14 @@ fn validate_heartbeat_channel_config(config: &Config, channel: &str) -> Result<(
                 );
             }
         }
+        "email" => {
+            if config.channels_config.email.is_none() {
+                anyhow::bail!(
+                    "heartbeat.target is set to email but channels_config.email is not configured"
+                );
+            }
+        }
+        crate::cron::scheduler::BROADCAST_CHANNEL => {}
         other => anyhow::bail!("unsupported heartbeat.target channel: {other}"),
     }
 
@@ -404,6 +492,7 @@ mod tests {
             draft_update_interval_ms: 1000,
             interrupt_on_new_message: false,
             mention_only: false,
+            trigger_prefix: None,
         });
         assert!(has_supervised_channels(&config));
     }
@@ -429,6 +518,10 @@ mod tests {
             allowed_users: vec!["*".into()],
             thread_replies: Some(true),
             mention_only: Some(false),
+            trigger_prefix: None,
+            command_token: None,
+            stream_mode: crate::config::StreamMode::default(),
+            draft_update_interval_ms: 1000,
         });
         assert!(has_supervised_channels(&config));
     }
@@ -505,8 +598,8 @@ mod tests {
     #[test]
     fn heartbeat_delivery_target_rejects_unsupported_channel() {
         let mut config = Config::default();
-        config.heartbeat.target = Some("email".into());
-        config.heartbeat.to = Some("ops@example.com".into());
+        config.heartbeat.target = Some("irc".into());
+        config.heartbeat.to = Some("#ops".into());
         let err = heartbeat_delivery_target(&config).unwrap_err();
         assert!(err
             .to_string()
@@ -536,6 +629,7 @@ mod tests {
             draft_update_interval_ms: 1000,
             interrupt_on_new_message: false,
             mention_only: false,
+            trigger_prefix: None,
         });
 
         let target = heartbeat_delivery_target(&config).unwrap();
@@ -0,0 +1,466 @@
+//! RSS/Atom feed watcher.
+//!
+//! Every `[feeds].interval_minutes` the worker fetches each source, keeps the
+//! items it has not seen before (tracked in `state/feeds.db`) and posts one
+//! digest per destination, optionally summarized by the default provider.
+//! The first poll of a feed only records its current items, so adding a feed
+//! does not flood the chat with its backlog.
+
+pub mod parse;
+
+use crate::config::{Config, FeedSourceConfig, FeedsConfig};
+use crate::providers;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use parse::{Feed, FeedItem};
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use tokio::time::{self, Duration};
+
+const FEEDS_COMPONENT: &str = "feeds";
+const FETCH_TIMEOUT_SECS: u64 = 30;
+/// Larger responses are not feeds worth parsing.
+const MAX_FEED_BYTES: usize = 5 * 1024 * 1024;
+/// Per-item summary cap inside a rendered digest.
+const DIGEST_SUMMARY_MAX_CHARS: usize = 200;
+/// Seen IDs that dropped out of their feed are forgotten after this long.
+const SEEN_RETENTION_DAYS: i64 = 90;
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You write short news digests for a chat. \
+Summarize the new feed items below in at most one sentence each, grouped by feed. \
+Keep every link, merge duplicates, and do not add items or commentary.";
+
+/// A new item and the feed it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewItem {
+    pub url: String,
+    /// Display name of the feed.
+    pub source: String,
+    pub item: FeedItem,
+}
+
+/// Delivery `(channel, recipient)` for a source, falling back to the
+/// section defaults.
+fn destination(config: &FeedsConfig, source: &FeedSourceConfig) -> Result<(String, String)> {
+    let pick = |own: &Option<String>, default: &Option<String>| {
+        own.as_deref()
+            .or(default.as_deref())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    match (
+        pick(&source.target, &config.target),
+        pick(&source.to, &config.to),
+    ) {
+        (Some(channel), Some(to)) => {
+            if !channel.eq_ignore_ascii_case(crate::cron::scheduler::BROADCAST_CHANNEL)
+                && !crate::cron::scheduler::supports_announcement(&channel)
+            {
+                bail!(
+                    "feed {}: unsupported delivery channel '{channel}'",
+                    source.url
+                );
+            }
+            Ok((channel.to_ascii_lowercase(), to))
+        }
+        _ => bail!(
+            "feed {}: set target and to on the source or in [feeds]",
+            source.url
+        ),
+    }
+}
+
+/// Render new items for one destination into a single message.
+pub fn render_digest(items: &[NewItem], max_items: usize) -> String {
+    let max_items = max_items.max(1);
+    let mut out = format!(
+        "📰 {} new feed item{}\n",
+        items.len(),
+        if items.len() == 1 { "" } else { "s" }
+    );
+    let mut current_source = None;
+    for new in items.iter().take(max_items) {
+        if current_source != Some(&new.source) {
+            let _ = write!(out, "\n{}", new.source);
+            current_source = Some(&new.source);
+        }
+        let _ = write!(out, "\n• {}", new.item.title);
+        if let Some(link) = &new.item.link {
+            let _ = write!(out, " — {link}");
+        }
+        if !new.item.summary.is_empty() {
+            let _ = write!(
+                out,
+                "\n  {}",
+                truncate_with_ellipsis(&new.item.summary, DIGEST_SUMMARY_MAX_CHARS)
+            );
+        }
+    }
+    if items.len() > max_items {
+        let _ = write!(out, "\n\n…and {} more not shown.", items.len() - max_items);
+    }
+    out
+}
+
+async fn summarize(config: &Config, digest: &str) -> Result<String> {
+    let provider = providers::create_resilient_provider_with_options(
+        config.default_provider.as_deref().unwrap_or("openrouter"),
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &providers::ProviderRuntimeOptions {
+            auth_profile_override: None,
+            provider_api_url: config.api_url.clone(),
+            zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+            secrets_encrypt: config.secrets.encrypt,
+            reasoning_enabled: config.runtime.reasoning_enabled,
+            redaction: config.security.redaction.clone(),
+        },
+    )?;
+    let model = config
+        .default_model
+        .clone()
+        .unwrap_or_else(|| "anthropic/claude-sonnet-4".into());
+    let summary = provider
+        .chat_with_system(
+            Some(SUMMARY_SYSTEM_PROMPT),
+            digest,
+            &model,
+            config.default_temperature,
+        )
+        .await?;
+    if summary.trim().is_empty() {
+        bail!("provider returned an empty summary");
+    }
+    Ok(format!("📰 Feed digest\n\n{}", summary.trim()))
+}
+
+async fn fetch(client: &reqwest::Client, url: &str) -> Result<Feed> {
+    let response = client
+        .get(url)
+        .header(
+            reqwest::header::USER_AGENT,
+            concat!("zeroclaw/", env!("CARGO_PKG_VERSION"), " feed watcher"),
+        )
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {url}"))?;
+    if !response.status().is_success() {
+        bail!("{url} returned {}", response.status());
+    }
+    let body = response.bytes().await?;
+    if body.len() > MAX_FEED_BYTES {
+        bail!("{url} is larger than {} MB", MAX_FEED_BYTES / (1024 * 1024));
+    }
+    parse::parse_feed(&String::from_utf8_lossy(&body)).with_context(|| url.to_string())
+}
+
+/// Poll every source once and post digests. Returns the number of digests
+/// sent.
+///
+/// New items are marked seen only after their digest is delivered, so a
+/// failed send is retried on the next poll.
+pub async fn poll(config: &Config) -> Result<usize> {
+    let client = crate::config::build_runtime_proxy_client_with_timeouts(
+        "channel.feeds",
+        FETCH_TIMEOUT_SECS,
+        10,
+    );
+    let mut pending: BTreeMap<(String, String), Vec<NewItem>> = BTreeMap::new();
+    let mut failures = Vec::new();
+
+    for source in &config.feeds.sources {
+        let destination = destination(&config.feeds, source)?;
+        let feed = match fetch(&client, &source.url).await {
+            Ok(feed) => feed,
+            Err(e) => {
+                tracing::warn!("Feed poll failed: {e:#}");
+                failures.push(source.url.clone());
+                continue;
+            }
+        };
+        let name = source
+            .name
+            .clone()
+            .or(feed.title)
+            .unwrap_or_else(|| source.url.clone());
+        let current: HashSet<String> = feed.items.iter().map(|item| item.id.clone()).collect();
+        let Some(seen) = seen_ids(config, &source.url)? else {
+            // First poll: remember the backlog without posting it.
+            mark_seen(config, &source.url, current.iter())?;
+            continue;
+        };
+        forget_stale(config, &source.url, &current)?;
+        let mut fresh: Vec<FeedItem> = feed
+            .items
+            .into_iter()
+            .filter(|item| !seen.contains(&item.id))
+            .collect();
+        // Oldest first, so the digest reads chronologically.
+        fresh.reverse();
+        let mut ids = HashSet::new();
+        fresh.retain(|item| ids.insert(item.id.clone()));
+        pending
+            .entry(destination)
+            .or_default()
+            .extend(fresh.into_iter().map(|item| NewItem {
+                url: source.url.clone(),
+                source: name.clone(),
+                item,
+            }));
+    }
+
+    let mut sent = 0;
+    for ((channel, to), items) in pending {
+        if items.is_empty() {
+            continue;
+        }
+        let digest = render_digest(&items, config.feeds.max_items);
+        let message = if config.feeds.summarize {
+            match summarize(config, &digest).await {
+                Ok(summary) => summary,
+                Err(e) => {
+                    tracing::warn!("Feed digest summary failed, sending it as is: {e}");
+                    digest
+                }
+            }
+        } else {
+            digest
+        };
+        match crate::cron::scheduler::deliver_proactive(config, &channel, &to, "feeds", &message)
+            .await
+        {
+            Ok(()) => {
+                let mut by_url: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+                for new in &items {
+                    by_url.entry(&new.url).or_default().push(&new.item.id);
+                }
+                for (url, ids) in by_url {
+                    mark_seen(config, url, ids)?;
+                }
+                sent += 1;
+            }
+            Err(e) => tracing::warn!("Feed digest delivery to {channel}:{to} failed: {e}"),
+        }
+    }
+
+    if !failures.is_empty() && failures.len() == config.feeds.sources.len() {
+        bail!("all feeds failed to load: {}", failures.join(", "));
+    }
+    Ok(sent)
+}
+
+/// Run the feed poll loop.
+pub async fn run(config: Config) -> Result<()> {
+    for source in &config.feeds.sources {
+        destination(&config.feeds, source)?;
+    }
+    let interval_secs = u64::from(config.feeds.interval_minutes.max(5)) * 60;
+    let mut interval = time::interval(Duration::from_secs(interval_secs));
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        match poll(&config).await {
+            Ok(_) => crate::health::mark_component_ok(FEEDS_COMPONENT),
+            Err(e) => {
+                crate::health::mark_component_error(FEEDS_COMPONENT, e.to_string());
+                tracing::warn!("Feed poll failed: {e}");
+            }
+        }
+    }
+}
+
+// ── Seen items ──────────────────────────────────────────────────
+
+/// IDs already seen for `url`, or `None` if the feed was never polled.
+fn seen_ids(config: &Config, url: &str) -> Result<Option<HashSet<String>>> {
+    with_connection(config, |conn| {
+        let polled: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM feed_sources WHERE url = ?1)",
+            params![url],
+            |row| row.get(0),
+        )?;
+        if !polled {
+            return Ok(None);
+        }
+        let mut stmt = conn.prepare("SELECT item_id FROM feed_items WHERE url = ?1")?;
+        let ids = stmt
+            .query_map(params![url], |row| row.get(0))?
+            .collect::<rusqlite::Result<HashSet<String>>>()
+            .context("Failed to read seen feed items")?;
+        Ok(Some(ids))
+    })
+}
+
+fn mark_seen<'a>(
+    config: &Config,
+    url: &str,
+    ids: impl IntoIterator<Item = &'a String>,
+) -> Result<()> {
+    with_connection(config, |conn| {
+        let now = Utc::now().to_rfc3339();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO feed_sources (url, first_polled_at) VALUES (?1, ?2)",
+            params![url, now],
+        )?;
+        for id in ids {
+            tx.execute(
+                "INSERT OR REPLACE INTO feed_items (url, item_id, seen_at) VALUES (?1, ?2, ?3)",
+                params![url, id, now],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    })
+}
+
+/// Drop old IDs that are no longer in the feed, keeping the table bounded.
+fn forget_stale(config: &Config, url: &str, current: &HashSet<String>) -> Result<()> {
+    let cutoff = (Utc::now() - ChronoDuration::days(SEEN_RETENTION_DAYS)).to_rfc3339();
+    with_connection(config, |conn| {
+        let mut stmt =
+            conn.prepare("SELECT item_id FROM feed_items WHERE url = ?1 AND seen_at < ?2")?;
+        let stale = stmt
+            .query_map(params![url, cutoff], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        for id in stale.iter().filter(|id| !current.contains(*id)) {
+            conn.execute(
+                "DELETE FROM feed_items WHERE url = ?1 AND item_id = ?2",
+                params![url, id],
+            )?;
+        }
+        Ok(())
+    })
+}
+
+fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    let db_path = config.workspace_dir.join("state").join("feeds.db");
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create state directory: {}", parent.display()))?;
+    }
+
+    let conn = Connection::open(&db_path)
+        .with_context(|| format!("Failed to open feeds DB: {}", db_path.display()))?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS feed_sources (
+            url             TEXT PRIMARY KEY,
+            first_polled_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS feed_items (
+            url     TEXT NOT NULL,
+            item_id TEXT NOT NULL,
+            seen_at TEXT NOT NULL,
+            PRIMARY KEY (url, item_id)
+        );",
+    )
+    .context("Failed to initialize feeds schema")?;
+
+    f(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Config {
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        config
+    }
+
+    fn source(url: &str) -> FeedSourceConfig {
+        FeedSourceConfig {
+            url: url.into(),
+            name: None,
+            target: None,
+            to: None,
+        }
+    }
+
+    fn new_item(source: &str, title: &str) -> NewItem {
+        NewItem {
+            url: format!("https://example.com/{source}.xml"),
+            source: source.into(),
+            item: FeedItem {
+                id: title.into(),
+                title: title.into(),
+                link: Some(format!("https://example.com/{title}")),
+                summary: String::new(),
+                published: None,
+            },
+        }
+    }
+
+    #[test]
+    fn destination_prefers_source_overrides() {
+        let feeds = FeedsConfig {
+            target: Some("telegram".into()),
+            to: Some("123".into()),
+            ..FeedsConfig::default()
+        };
+        let mut own = source("https://example.com/a.xml");
+        assert_eq!(
+            destination(&feeds, &own).unwrap(),
+            ("telegram".into(), "123".into())
+        );
+
+        own.target = Some("Discord".into());
+        own.to = Some("456".into());
+        assert_eq!(
+            destination(&feeds, &own).unwrap(),
+            ("discord".into(), "456".into())
+        );
+
+        assert!(destination(&FeedsConfig::default(), &own).is_ok());
+        assert!(destination(&FeedsConfig::default(), &source("https://x")).is_err());
+
+        own.target = Some("nonexistent".into());
+        assert!(destination(&feeds, &own).is_err());
+    }
+
+    #[test]
+    fn render_digest_groups_by_feed_and_caps_items() {
+        let items = vec![
+            new_item("Rust Blog", "one"),
+            new_item("Rust Blog", "two"),
+            new_item("Releases", "three"),
+        ];
+        let rendered = render_digest(&items, 2);
+        assert!(rendered.starts_with("📰 3 new feed items"));
+        assert_eq!(rendered.matches("Rust Blog").count(), 1);
+        assert!(rendered.contains("• one — https://example.com/one"));
+        assert!(!rendered.contains("three"));
+        assert!(rendered.contains("…and 1 more not shown."));
+    }
+
+    #[test]
+    fn seen_items_track_first_poll() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let url = "https://example.com/feed.xml";
+        assert!(seen_ids(&config, url).unwrap().is_none());
+
+        let first = ["a".to_string(), "b".to_string()];
+        mark_seen(&config, url, first.iter()).unwrap();
+        let seen = seen_ids(&config, url).unwrap().unwrap();
+        assert!(seen.contains("a") && seen.contains("b"));
+
+        mark_seen(&config, url, ["c".to_string()].iter()).unwrap();
+        assert_eq!(seen_ids(&config, url).unwrap().unwrap().len(), 3);
+        assert!(seen_ids(&config, "https://other.example/feed")
+            .unwrap()
+            .is_none());
+    }
+}
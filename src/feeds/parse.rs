@@ -0,0 +1,201 @@
+//! Minimal RSS 2.0 / Atom parsing: just what a digest needs.
+
+use crate::util::{xml_attribute, xml_elements, xml_tagged_elements, xml_text};
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+
+/// Characters kept from an item's description.
+const SUMMARY_MAX_CHARS: usize = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedItem {
+    /// Stable key for seen-tracking: guid/id, else link, else title.
+    pub id: String,
+    pub title: String,
+    pub link: Option<String>,
+    /// Plain-text description, trimmed to a few sentences.
+    pub summary: String,
+    pub published: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Feed {
+    pub title: Option<String>,
+    pub items: Vec<FeedItem>,
+}
+
+/// Text of the first element named `name`, decoded and trimmed.
+fn first_text(xml: &str, name: &str) -> Option<String> {
+    xml_elements(xml, name)
+        .into_iter()
+        .map(xml_text)
+        .map(|text| text.trim().to_string())
+        .find(|text| !text.is_empty())
+}
+
+/// Atom `<link href=...>`: the `alternate` one, or the first without a `rel`.
+fn atom_link(entry: &str) -> Option<String> {
+    let links = xml_tagged_elements(entry, "link");
+    let href = |rel: Option<&str>| {
+        links
+            .iter()
+            .find(|(attributes, _)| xml_attribute(attributes, "rel") == rel)
+            .and_then(|(attributes, _)| xml_attribute(attributes, "href"))
+            .map(xml_text)
+    };
+    href(Some("alternate")).or_else(|| href(None))
+}
+
+/// Strip markup from an HTML description and collapse whitespace.
+fn plain_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for ch in html.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(ch),
+            _ => {}
+        }
+    }
+    // Descriptions are often entity-encoded HTML, so decode once more.
+    let text = xml_text(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+    crate::util::truncate_with_ellipsis(&text, SUMMARY_MAX_CHARS)
+}
+
+fn parse_date(raw: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(raw)
+        .or_else(|_| DateTime::parse_from_rfc2822(raw))
+        .ok()
+        .map(|date| date.to_utc())
+}
+
+fn parse_item(xml: &str) -> Option<FeedItem> {
+    let title = first_text(xml, "title").map(|title| plain_text(&title));
+    let link = first_text(xml, "link").or_else(|| atom_link(xml));
+    let summary = ["summary", "description", "content", "encoded"]
+        .iter()
+        .find_map(|name| first_text(xml, name))
+        .map(|html| plain_text(&html))
+        .unwrap_or_default();
+    let id = first_text(xml, "guid")
+        .or_else(|| first_text(xml, "id"))
+        .or_else(|| link.clone())
+        .or_else(|| title.clone())?;
+    let published = ["pubDate", "published", "updated", "date"]
+        .iter()
+        .find_map(|name| first_text(xml, name))
+        .and_then(|raw| parse_date(&raw));
+    Some(FeedItem {
+        id,
+        title: title.unwrap_or_else(|| "(untitled)".into()),
+        link,
+        summary,
+        published,
+    })
+}
+
+/// Parse an RSS 2.0, RSS 1.0 (RDF) or Atom document, newest items first
+/// as the feed lists them.
+pub fn parse_feed(xml: &str) -> Result<Feed> {
+    let is_atom = xml_elements(xml, "feed")
+        .first()
+        .is_some_and(|feed| !xml_elements(feed, "entry").is_empty());
+    let blocks = if is_atom {
+        xml_elements(xml, "entry")
+    } else {
+        xml_elements(xml, "item")
+    };
+    if blocks.is_empty() && !xml.contains("<rss") && !xml.contains("<feed") && !xml.contains(":RDF")
+    {
+        bail!("Not an RSS or Atom feed");
+    }
+    let head_end = ["<item", "<entry"]
+        .iter()
+        .filter_map(|tag| xml.find(tag))
+        .min()
+        .unwrap_or(xml.len());
+    Ok(Feed {
+        title: first_text(&xml[..head_end], "title").map(|title| plain_text(&title)),
+        items: blocks.into_iter().filter_map(parse_item).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rss_items() {
+        let xml = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+<channel>
+  <title>Rust Blog</title>
+  <atom:link href="https://blog.rust-lang.org/feed.xml" rel="self"/>
+  <item>
+    <title>Announcing Rust 1.90 &amp; more</title>
+    <link>https://blog.rust-lang.org/2026/09/18/Rust-1.90.0.html</link>
+    <guid isPermaLink="false">rust-1.90</guid>
+    <pubDate>Fri, 18 Sep 2026 00:00:00 +0000</pubDate>
+    <description><![CDATA[<p>The Rust team is happy to <b>announce</b> a new version.</p>]]></description>
+  </item>
+  <item>
+    <title>No guid here</title>
+    <link>https://example.com/post</link>
+    <description>&lt;p&gt;Escaped &amp;amp; html&lt;/p&gt;</description>
+  </item>
+</channel>
+</rss>"#;
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Rust Blog"));
+        assert_eq!(feed.items.len(), 2);
+        let first = &feed.items[0];
+        assert_eq!(first.id, "rust-1.90");
+        assert_eq!(first.title, "Announcing Rust 1.90 & more");
+        assert_eq!(
+            first.summary,
+            "The Rust team is happy to announce a new version."
+        );
+        assert_eq!(
+            first.published.unwrap().to_rfc3339(),
+            "2026-09-18T00:00:00+00:00"
+        );
+        assert_eq!(feed.items[1].id, "https://example.com/post");
+        assert_eq!(feed.items[1].summary, "Escaped & html");
+    }
+
+    #[test]
+    fn parses_atom_entries() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">Releases</title>
+  <link href="https://github.com/org/repo/releases" rel="alternate"/>
+  <entry>
+    <id>tag:github.com,2008:Repository/1/v2.0.0</id>
+    <updated>2026-10-01T12:30:00Z</updated>
+    <link rel="replies" href="https://example.com/comments"/>
+    <link rel="alternate" type="text/html" href="https://github.com/org/repo/releases/tag/v2.0.0"/>
+    <title>v2.0.0</title>
+    <content type="html">&lt;h1&gt;Breaking changes&lt;/h1&gt;</content>
+  </entry>
+</feed>"#;
+        let feed = parse_feed(xml).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Releases"));
+        let entry = &feed.items[0];
+        assert_eq!(entry.id, "tag:github.com,2008:Repository/1/v2.0.0");
+        assert_eq!(
+            entry.link.as_deref(),
+            Some("https://github.com/org/repo/releases/tag/v2.0.0")
+        );
+        assert_eq!(entry.summary, "Breaking changes");
+        assert!(entry.published.is_some());
+    }
+
+    #[test]
+    fn rejects_html_pages() {
+        assert!(parse_feed("<html><body>Not found</body></html>").is_err());
+    }
+}
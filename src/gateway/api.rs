@@ -66,6 +66,16 @@ pub struct CronAddBody {
     pub command: String,
 }
 
+#[derive(Deserialize)]
+pub struct AdminUsageQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct AdminExportQuery {
+    pub format: Option<String>,
+}
+
 // ── Handlers ────────────────────────────────────────────────────
 
 /// GET /api/status — system status overview
@@ -518,6 +528,255 @@ pub async fn handle_api_health(
     Json(serde_json::json!({"health": snapshot})).into_response()
 }
 
+// ── Admin: runtime control ──────────────────────────────────────
+
+fn channel_runtime_not_running() -> axum::response::Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(serde_json::json!({
+            "error": "Channels are not running in this process — run `zeroclaw daemon` to manage them here"
+        })),
+    )
+        .into_response()
+}
+
+/// Channels the runtime listens on plus the webhook channels the gateway
+/// answers, sorted.
+fn admin_channel_names(state: &AppState) -> Vec<String> {
+    let mut names = crate::channels::admin::channel_names().unwrap_or_default();
+    names.extend(
+        super::webhook_channel_names(state)
+            .into_iter()
+            .map(str::to_string),
+    );
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// GET /api/admin/sessions — conversations held in memory
+pub async fn handle_api_admin_sessions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    match crate::channels::admin::sessions() {
+        Some(sessions) => Json(serde_json::json!({"sessions": sessions})).into_response(),
+        None => channel_runtime_not_running(),
+    }
+}
+
+/// DELETE /api/admin/sessions/:key — forget a conversation's history
+pub async fn handle_api_admin_session_flush(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    match crate::channels::admin::flush_session(&key) {
+        Some(true) => Json(serde_json::json!({"status": "ok"})).into_response(),
+        Some(false) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Unknown session"})),
+        )
+            .into_response(),
+        None => channel_runtime_not_running(),
+    }
+}
+
+/// GET /api/admin/sessions/:key/export?format=markdown|json — download a
+/// conversation's history
+pub async fn handle_api_admin_session_export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+    Query(params): Query<AdminExportQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let format = match params.format.as_deref() {
+        None => crate::channels::export::ExportFormat::Markdown,
+        Some(raw) => match crate::channels::export::ExportFormat::parse(raw) {
+            Some(format) => format,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": "format must be `markdown` or `json`"})),
+                )
+                    .into_response()
+            }
+        },
+    };
+    match crate::channels::admin::export_session(&key, format) {
+        Some(Ok(body)) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, format.content_type())],
+            body,
+        )
+            .into_response(),
+        Some(Err(e)) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+        None => channel_runtime_not_running(),
+    }
+}
+
+/// POST /api/admin/reload — re-read config.toml without a restart
+pub async fn handle_api_admin_reload(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let config = match super::load_reloaded_config(&state).await {
+        Ok(config) => config,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": format!("Config not applied: {e:#}")})),
+            )
+                .into_response();
+        }
+    };
+    super::apply_reloaded_config(&state, config);
+
+    let channels = match crate::channels::admin::reload_config().await {
+        Some(Ok(())) => "reloaded",
+        Some(Err(e)) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Channel reload failed: {e:#}")})),
+            )
+                .into_response();
+        }
+        None => "not_running",
+    };
+    tracing::info!("Config reloaded through the admin API");
+
+    Json(serde_json::json!({"status": "ok", "channels": channels})).into_response()
+}
+
+/// GET /api/admin/channels — channels, whether they are answering, and
+/// their listener connection state
+pub async fn handle_api_admin_channels(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let connections = crate::channels::supervisor::connections();
+    let channels: Vec<serde_json::Value> = admin_channel_names(&state)
+        .into_iter()
+        .map(|name| {
+            let enabled = !crate::channels::admin::is_channel_disabled(&name);
+            let connection = connections.get(&name);
+            serde_json::json!({"name": name, "enabled": enabled, "connection": connection})
+        })
+        .collect();
+    Json(serde_json::json!({"channels": channels})).into_response()
+}
+
+/// POST /api/admin/channels/:name/disable — stop answering a channel
+pub async fn handle_api_admin_channel_disable(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    set_admin_channel_enabled(&state, &headers, &name, false)
+}
+
+/// POST /api/admin/channels/:name/enable — resume answering a channel
+pub async fn handle_api_admin_channel_enable(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    set_admin_channel_enabled(&state, &headers, &name, true)
+}
+
+fn set_admin_channel_enabled(
+    state: &AppState,
+    headers: &HeaderMap,
+    name: &str,
+    enabled: bool,
+) -> axum::response::Response {
+    if let Err(e) = require_auth(state, headers) {
+        return e.into_response();
+    }
+
+    if !admin_channel_names(state).iter().any(|known| known == name) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Unknown channel"})),
+        )
+            .into_response();
+    }
+    crate::channels::admin::set_channel_enabled(name, enabled);
+    tracing::info!(
+        channel = name,
+        "Channel {} through the admin API",
+        if enabled { "enabled" } else { "disabled" }
+    );
+
+    Json(serde_json::json!({"channel": name, "enabled": enabled})).into_response()
+}
+
+/// GET /api/admin/usage — channel usage per channel over the last `days`
+pub async fn handle_api_admin_usage(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<AdminUsageQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let days = params
+        .days
+        .unwrap_or(crate::channels::stats::STATS_WINDOW_DAYS)
+        .clamp(1, 365);
+    let since = chrono::Utc::now() - chrono::Duration::days(days);
+    let (workspace_dir, prices) = {
+        let config = state.config.lock();
+        (config.workspace_dir.clone(), config.cost.prices.clone())
+    };
+    let stats = crate::channels::stats::UsageStats::new(&workspace_dir, prices);
+    match stats.summarize_by_channel(since) {
+        Ok(summaries) => {
+            let channels: serde_json::Map<String, serde_json::Value> = summaries
+                .into_iter()
+                .map(|(channel, summary)| {
+                    (channel, serde_json::to_value(summary).unwrap_or_default())
+                })
+                .collect();
+            Json(serde_json::json!({
+                "since": since.to_rfc3339(),
+                "channels": channels,
+            }))
+            .into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Usage summary failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
 // ── Helpers ─────────────────────────────────────────────────────
 
 fn is_masked_secret(value: &str) -> bool {
@@ -701,6 +960,14 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     mask_optional_secret(&mut masked.composio.api_key);
     mask_optional_secret(&mut masked.browser.computer_use.api_key);
     mask_optional_secret(&mut masked.web_search.brave_api_key);
+    mask_optional_secret(&mut masked.transcription.api_key);
+    mask_optional_secret(&mut masked.tts.api_key);
+    mask_optional_secret(&mut masked.image_generation.api_key);
+    mask_optional_secret(&mut masked.calendar.caldav_password);
+    mask_optional_secret(&mut masked.calendar.google_client_secret);
+    mask_optional_secret(&mut masked.calendar.google_refresh_token);
+    mask_optional_secret(&mut masked.send_email.password);
+    mask_optional_secret(&mut masked.home_assistant.token);
     mask_optional_secret(&mut masked.storage.provider.config.db_url);
     mask_optional_secret(&mut masked.memory.qdrant.api_key);
     if let Some(cloudflare) = masked.tunnel.cloudflare.as_mut() {
@@ -713,12 +980,33 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     for agent in masked.agents.values_mut() {
         mask_optional_secret(&mut agent.api_key);
     }
+    for connection in masked.sql_query.connections.values_mut() {
+        if connection.kind != crate::config::SqlConnectionKind::Sqlite {
+            mask_required_secret(&mut connection.url);
+        }
+    }
     for route in &mut masked.model_routes {
         mask_optional_secret(&mut route.api_key);
     }
     for route in &mut masked.embedding_routes {
         mask_optional_secret(&mut route.api_key);
     }
+    for sink in &mut masked.transcripts.sinks {
+        if let crate::config::TranscriptSinkConfig::S3 {
+            secret_access_key, ..
+        } = sink
+        {
+            mask_required_secret(secret_access_key);
+        }
+    }
+    for trigger in masked.triggers.values_mut() {
+        mask_optional_secret(&mut trigger.secret);
+    }
+    for server in &mut masked.mcp.servers {
+        for value in server.env.values_mut().chain(server.headers.values_mut()) {
+            mask_required_secret(value);
+        }
+    }
 
     if let Some(telegram) = masked.channels_config.telegram.as_mut() {
         mask_required_secret(&mut telegram.bot_token);
@@ -729,9 +1017,11 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     if let Some(slack) = masked.channels_config.slack.as_mut() {
         mask_required_secret(&mut slack.bot_token);
         mask_optional_secret(&mut slack.app_token);
+        mask_optional_secret(&mut slack.signing_secret);
     }
     if let Some(mattermost) = masked.channels_config.mattermost.as_mut() {
         mask_required_secret(&mut mattermost.bot_token);
+        mask_optional_secret(&mut mattermost.command_token);
     }
     if let Some(webhook) = masked.channels_config.webhook.as_mut() {
         mask_optional_secret(&mut webhook.secret);
@@ -755,11 +1045,41 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     if let Some(wati) = masked.channels_config.wati.as_mut() {
         mask_required_secret(&mut wati.api_token);
     }
+    if let Some(zulip) = masked.channels_config.zulip.as_mut() {
+        mask_required_secret(&mut zulip.api_key);
+    }
+    if let Some(mastodon) = masked.channels_config.mastodon.as_mut() {
+        mask_required_secret(&mut mastodon.access_token);
+    }
+    if let Some(github) = masked.channels_config.github.as_mut() {
+        mask_required_secret(&mut github.token);
+        mask_optional_secret(&mut github.webhook_secret);
+    }
+    if let Some(google_chat) = masked.channels_config.google_chat.as_mut() {
+        mask_required_secret(&mut google_chat.service_account_key);
+    }
+    if let Some(twilio) = masked.channels_config.twilio.as_mut() {
+        mask_required_secret(&mut twilio.auth_token);
+    }
+    if let Some(http) = masked.channels_config.http.as_mut() {
+        mask_optional_secret(&mut http.bearer_token);
+    }
+    if let Some(web) = masked.channels_config.web.as_mut() {
+        mask_optional_secret(&mut web.auth_token);
+    }
+    for plugin in &mut masked.channels_config.plugins {
+        for value in plugin.env.values_mut() {
+            mask_required_secret(value);
+        }
+    }
     if let Some(irc) = masked.channels_config.irc.as_mut() {
         mask_optional_secret(&mut irc.server_password);
         mask_optional_secret(&mut irc.nickserv_password);
         mask_optional_secret(&mut irc.sasl_password);
     }
+    if let Some(xmpp) = masked.channels_config.xmpp.as_mut() {
+        mask_required_secret(&mut xmpp.password);
+    }
     if let Some(lark) = masked.channels_config.lark.as_mut() {
         mask_required_secret(&mut lark.app_secret);
         mask_optional_secret(&mut lark.encrypt_key);
@@ -773,6 +1093,12 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     if let Some(dingtalk) = masked.channels_config.dingtalk.as_mut() {
         mask_required_secret(&mut dingtalk.client_secret);
     }
+    if let Some(wecom) = masked.channels_config.wecom.as_mut() {
+        mask_required_secret(&mut wecom.corp_secret);
+        mask_required_secret(&mut wecom.token);
+        mask_required_secret(&mut wecom.encoding_aes_key);
+        mask_optional_secret(&mut wecom.robot_key);
+    }
     if let Some(qq) = masked.channels_config.qq.as_mut() {
         mask_required_secret(&mut qq.app_secret);
     }
@@ -786,6 +1112,7 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     if let Some(email) = masked.channels_config.email.as_mut() {
         mask_required_secret(&mut email.password);
     }
+    mask_optional_secret(&mut masked.channels_config.moderation.api_key);
     masked
 }
 
@@ -811,6 +1138,35 @@ fn restore_masked_sensitive_fields(
         &mut incoming.web_search.brave_api_key,
         &current.web_search.brave_api_key,
     );
+    restore_optional_secret(
+        &mut incoming.transcription.api_key,
+        &current.transcription.api_key,
+    );
+    restore_optional_secret(&mut incoming.tts.api_key, &current.tts.api_key);
+    restore_optional_secret(
+        &mut incoming.image_generation.api_key,
+        &current.image_generation.api_key,
+    );
+    restore_optional_secret(
+        &mut incoming.calendar.caldav_password,
+        &current.calendar.caldav_password,
+    );
+    restore_optional_secret(
+        &mut incoming.calendar.google_client_secret,
+        &current.calendar.google_client_secret,
+    );
+    restore_optional_secret(
+        &mut incoming.calendar.google_refresh_token,
+        &current.calendar.google_refresh_token,
+    );
+    restore_optional_secret(
+        &mut incoming.send_email.password,
+        &current.send_email.password,
+    );
+    restore_optional_secret(
+        &mut incoming.home_assistant.token,
+        &current.home_assistant.token,
+    );
     restore_optional_secret(
         &mut incoming.storage.provider.config.db_url,
         &current.storage.provider.config.db_url,
@@ -837,8 +1193,53 @@ fn restore_masked_sensitive_fields(
             restore_optional_secret(&mut agent.api_key, &current_agent.api_key);
         }
     }
+    for (name, connection) in &mut incoming.sql_query.connections {
+        if let Some(current_connection) = current.sql_query.connections.get(name) {
+            restore_required_secret(&mut connection.url, &current_connection.url);
+        }
+    }
     restore_model_route_api_keys(&mut incoming.model_routes, &current.model_routes);
     restore_embedding_route_api_keys(&mut incoming.embedding_routes, &current.embedding_routes);
+    for (incoming_sink, current_sink) in incoming
+        .transcripts
+        .sinks
+        .iter_mut()
+        .zip(&current.transcripts.sinks)
+    {
+        if let (
+            crate::config::TranscriptSinkConfig::S3 {
+                secret_access_key, ..
+            },
+            crate::config::TranscriptSinkConfig::S3 {
+                secret_access_key: current_secret,
+                ..
+            },
+        ) = (incoming_sink, current_sink)
+        {
+            restore_required_secret(secret_access_key, current_secret);
+        }
+    }
+    for (name, trigger) in &mut incoming.triggers {
+        if let Some(current_trigger) = current.triggers.get(name) {
+            restore_optional_secret(&mut trigger.secret, &current_trigger.secret);
+        }
+    }
+    for server in &mut incoming.mcp.servers {
+        let Some(current_server) = current.mcp.servers.iter().find(|s| s.name == server.name)
+        else {
+            continue;
+        };
+        for (key, value) in &mut server.env {
+            if let Some(existing) = current_server.env.get(key) {
+                restore_required_secret(value, existing);
+            }
+        }
+        for (key, value) in &mut server.headers {
+            if let Some(existing) = current_server.headers.get(key) {
+                restore_required_secret(value, existing);
+            }
+        }
+    }
 
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.telegram.as_mut(),
@@ -858,12 +1259,14 @@ fn restore_masked_sensitive_fields(
     ) {
         restore_required_secret(&mut incoming_ch.bot_token, &current_ch.bot_token);
         restore_optional_secret(&mut incoming_ch.app_token, &current_ch.app_token);
+        restore_optional_secret(&mut incoming_ch.signing_secret, &current_ch.signing_secret);
     }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.mattermost.as_mut(),
         current.channels_config.mattermost.as_ref(),
     ) {
         restore_required_secret(&mut incoming_ch.bot_token, &current_ch.bot_token);
+        restore_optional_secret(&mut incoming_ch.command_token, &current_ch.command_token);
     }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.webhook.as_mut(),
@@ -905,6 +1308,67 @@ fn restore_masked_sensitive_fields(
     ) {
         restore_required_secret(&mut incoming_ch.api_token, &current_ch.api_token);
     }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.http.as_mut(),
+        current.channels_config.http.as_ref(),
+    ) {
+        restore_optional_secret(&mut incoming_ch.bearer_token, &current_ch.bearer_token);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.web.as_mut(),
+        current.channels_config.web.as_ref(),
+    ) {
+        restore_optional_secret(&mut incoming_ch.auth_token, &current_ch.auth_token);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.zulip.as_mut(),
+        current.channels_config.zulip.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.api_key, &current_ch.api_key);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.mastodon.as_mut(),
+        current.channels_config.mastodon.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.access_token, &current_ch.access_token);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.github.as_mut(),
+        current.channels_config.github.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.token, &current_ch.token);
+        restore_optional_secret(&mut incoming_ch.webhook_secret, &current_ch.webhook_secret);
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.google_chat.as_mut(),
+        current.channels_config.google_chat.as_ref(),
+    ) {
+        restore_required_secret(
+            &mut incoming_ch.service_account_key,
+            &current_ch.service_account_key,
+        );
+    }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.twilio.as_mut(),
+        current.channels_config.twilio.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.auth_token, &current_ch.auth_token);
+    }
+    for incoming_ch in &mut incoming.channels_config.plugins {
+        let Some(current_ch) = current
+            .channels_config
+            .plugins
+            .iter()
+            .find(|p| p.name == incoming_ch.name)
+        else {
+            continue;
+        };
+        for (key, value) in &mut incoming_ch.env {
+            if let Some(existing) = current_ch.env.get(key) {
+                restore_required_secret(value, existing);
+            }
+        }
+    }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.irc.as_mut(),
         current.channels_config.irc.as_ref(),
@@ -919,6 +1383,12 @@ fn restore_masked_sensitive_fields(
         );
         restore_optional_secret(&mut incoming_ch.sasl_password, &current_ch.sasl_password);
     }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.xmpp.as_mut(),
+        current.channels_config.xmpp.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.password, &current_ch.password);
+    }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.lark.as_mut(),
         current.channels_config.lark.as_ref(),
@@ -947,6 +1417,18 @@ fn restore_masked_sensitive_fields(
     ) {
         restore_required_secret(&mut incoming_ch.client_secret, &current_ch.client_secret);
     }
+    if let (Some(incoming_ch), Some(current_ch)) = (
+        incoming.channels_config.wecom.as_mut(),
+        current.channels_config.wecom.as_ref(),
+    ) {
+        restore_required_secret(&mut incoming_ch.corp_secret, &current_ch.corp_secret);
+        restore_required_secret(&mut incoming_ch.token, &current_ch.token);
+        restore_required_secret(
+            &mut incoming_ch.encoding_aes_key,
+            &current_ch.encoding_aes_key,
+        );
+        restore_optional_secret(&mut incoming_ch.robot_key, &current_ch.robot_key);
+    }
     if let (Some(incoming_ch), Some(current_ch)) = (
         incoming.channels_config.qq.as_mut(),
         current.channels_config.qq.as_ref(),
@@ -972,6 +1454,10 @@ fn restore_masked_sensitive_fields(
     ) {
         restore_required_secret(&mut incoming_ch.password, &current_ch.password);
     }
+    restore_optional_secret(
+        &mut incoming.channels_config.moderation.api_key,
+        &current.channels_config.moderation.api_key,
+    );
 }
 
 fn hydrate_config_for_save(
@@ -1393,4 +1879,85 @@ mod tests {
             .iter()
             .all(|route| route.api_key.as_deref() != Some(MASKED_SECRET)));
     }
+
+    #[test]
+    fn trigger_secrets_are_masked_and_restored_by_name() {
+        let mut current = crate::config::Config::default();
+        current.triggers.insert(
+            "deploy".to_string(),
+            crate::config::TriggerConfig {
+                secret: Some("trigger-secret-real".to_string()),
+                prompt: "Deploy {{ref}}".to_string(),
+                deliver_to: "telegram:1".to_string(),
+            },
+        );
+
+        let masked = mask_sensitive_fields(&current);
+        assert_eq!(
+            masked.triggers["deploy"].secret.as_deref(),
+            Some(MASKED_SECRET)
+        );
+
+        let hydrated = hydrate_config_for_save(masked, &current);
+        assert_eq!(
+            hydrated.triggers["deploy"].secret.as_deref(),
+            Some("trigger-secret-real")
+        );
+    }
+
+    #[test]
+    fn mcp_server_env_and_headers_are_masked_and_restored() {
+        let mut current = crate::config::Config::default();
+        current
+            .mcp
+            .servers
+            .push(crate::config::schema::McpServerConfig {
+                name: "search".to_string(),
+                transport: crate::config::schema::McpTransport::Sse,
+                command: None,
+                args: vec![],
+                env: std::collections::HashMap::from([(
+                    "SEARCH_API_KEY".to_string(),
+                    "env-real".to_string(),
+                )]),
+                url: Some("https://mcp.example.com/sse".to_string()),
+                headers: std::collections::HashMap::from([(
+                    "Authorization".to_string(),
+                    "Bearer header-real".to_string(),
+                )]),
+                timeout_secs: 30,
+            });
+
+        let masked = mask_sensitive_fields(&current);
+        assert_eq!(masked.mcp.servers[0].env["SEARCH_API_KEY"], MASKED_SECRET);
+        assert_eq!(
+            masked.mcp.servers[0].headers["Authorization"],
+            MASKED_SECRET
+        );
+
+        let hydrated = hydrate_config_for_save(masked, &current);
+        assert_eq!(hydrated.mcp.servers[0].env["SEARCH_API_KEY"], "env-real");
+        assert_eq!(
+            hydrated.mcp.servers[0].headers["Authorization"],
+            "Bearer header-real"
+        );
+    }
+
+    #[test]
+    fn moderation_api_key_is_masked_and_restored() {
+        let mut current = crate::config::Config::default();
+        current.channels_config.moderation.api_key = Some("moderation-real".to_string());
+
+        let masked = mask_sensitive_fields(&current);
+        assert_eq!(
+            masked.channels_config.moderation.api_key.as_deref(),
+            Some(MASKED_SECRET)
+        );
+
+        let hydrated = hydrate_config_for_save(masked, &current);
+        assert_eq!(
+            hydrated.channels_config.moderation.api_key.as_deref(),
+            Some("moderation-real")
+        );
+    }
 }
@@ -10,10 +10,12 @@
 pub mod api;
 pub mod sse;
 pub mod static_files;
+pub mod triggers;
 pub mod ws;
 
 use crate::channels::{
-    Channel, LinqChannel, NextcloudTalkChannel, SendMessage, WatiChannel, WhatsAppChannel,
+    Channel, GithubChannel, GoogleChatChannel, LinqChannel, MattermostChannel,
+    NextcloudTalkChannel, SendMessage, SlackChannel, TwilioChannel, WatiChannel, WhatsAppChannel,
 };
 use crate::config::Config;
 use crate::cost::CostTracker;
@@ -37,10 +39,12 @@ use axum::{
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::timeout::TimeoutLayer;
+use tracing::Instrument;
 use uuid::Uuid;
 
 /// Maximum request body size (64KB) — prevents memory exhaustion
@@ -70,10 +74,57 @@ fn wati_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
     format!("wati_{}_{}", msg.sender, msg.id)
 }
 
+fn twilio_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
+    format!("twilio_{}_{}", msg.sender, msg.id)
+}
+
+fn google_chat_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
+    format!("google_chat_{}_{}", msg.sender, msg.id)
+}
+
+fn slack_command_memory_key(command: &crate::channels::slack::SlackSlashCommand) -> String {
+    format!("slack_{}_{}", command.user_id, command.trigger_id)
+}
+
+fn mattermost_command_memory_key(
+    command: &crate::channels::mattermost::MattermostSlashCommand,
+) -> String {
+    format!("mattermost_{}_{}", command.user_id, command.trigger_id)
+}
+
 fn nextcloud_talk_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
     format!("nextcloud_talk_{}_{}", msg.sender, msg.id)
 }
 
+fn github_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
+    format!("github_{}_{}", msg.sender, msg.id)
+}
+
+/// Whether the gateway should answer `msg`: skips provider redeliveries and
+/// channels disabled through the admin API.
+fn should_answer(msg: &crate::channels::traits::ChannelMessage) -> bool {
+    !crate::channels::admin::is_channel_disabled(&msg.channel)
+        && !crate::channels::dedup::is_duplicate(msg)
+}
+
+/// Names of the channels whose webhooks this gateway answers.
+fn webhook_channel_names(state: &AppState) -> Vec<&'static str> {
+    [
+        ("whatsapp", state.whatsapp.is_some()),
+        ("linq", state.linq.is_some()),
+        ("nextcloud_talk", state.nextcloud_talk.is_some()),
+        ("github", state.github.is_some()),
+        ("wati", state.wati.is_some()),
+        ("twilio", state.twilio.is_some()),
+        ("google_chat", state.google_chat.is_some()),
+        ("slack", state.slack.is_some()),
+        ("mattermost", state.mattermost.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, configured)| configured.then_some(name))
+    .collect()
+}
+
 fn hash_webhook_secret(value: &str) -> String {
     use sha2::{Digest, Sha256};
 
@@ -86,7 +137,8 @@ const RATE_LIMITER_SWEEP_INTERVAL_SECS: u64 = 300; // 5 minutes
 
 #[derive(Debug)]
 struct SlidingWindowRateLimiter {
-    limit_per_window: u32,
+    /// Changed in place when the config is hot-reloaded.
+    limit_per_window: AtomicU32,
     window: Duration,
     max_keys: usize,
     requests: Mutex<(HashMap<String, Vec<Instant>>, Instant)>,
@@ -95,7 +147,7 @@ struct SlidingWindowRateLimiter {
 impl SlidingWindowRateLimiter {
     fn new(limit_per_window: u32, window: Duration, max_keys: usize) -> Self {
         Self {
-            limit_per_window,
+            limit_per_window: AtomicU32::new(limit_per_window),
             window,
             max_keys: max_keys.max(1),
             requests: Mutex::new((HashMap::new(), Instant::now())),
@@ -109,8 +161,14 @@ impl SlidingWindowRateLimiter {
         });
     }
 
+    fn set_limit(&self, limit_per_window: u32) {
+        self.limit_per_window
+            .store(limit_per_window, Ordering::Relaxed);
+    }
+
     fn allow(&self, key: &str) -> bool {
-        if self.limit_per_window == 0 {
+        let limit_per_window = self.limit_per_window.load(Ordering::Relaxed);
+        if limit_per_window == 0 {
             return true;
         }
 
@@ -145,7 +203,7 @@ impl SlidingWindowRateLimiter {
         let entry = requests.entry(key.to_owned()).or_default();
         entry.retain(|instant| *instant > cutoff);
 
-        if entry.len() >= self.limit_per_window as usize {
+        if entry.len() >= limit_per_window as usize {
             return false;
         }
 
@@ -176,6 +234,11 @@ impl GatewayRateLimiter {
     fn allow_webhook(&self, key: &str) -> bool {
         self.webhook.allow(key)
     }
+
+    fn set_limits(&self, pair_per_minute: u32, webhook_per_minute: u32) {
+        self.pair.set_limit(pair_per_minute);
+        self.webhook.set_limit(webhook_per_minute);
+    }
 }
 
 #[derive(Debug)]
@@ -301,7 +364,18 @@ pub struct AppState {
     pub nextcloud_talk: Option<Arc<NextcloudTalkChannel>>,
     /// Nextcloud Talk webhook secret for signature verification
     pub nextcloud_talk_webhook_secret: Option<Arc<str>>,
+    pub github: Option<Arc<GithubChannel>>,
+    /// GitHub webhook secret for `X-Hub-Signature-256` verification
+    pub github_webhook_secret: Option<Arc<str>>,
     pub wati: Option<Arc<WatiChannel>>,
+    pub twilio: Option<Arc<TwilioChannel>>,
+    /// Public Twilio callback URL used for `X-Twilio-Signature` verification
+    pub twilio_webhook_url: Option<Arc<str>>,
+    pub google_chat: Option<Arc<GoogleChatChannel>>,
+    /// Slack channel answering slash commands (only when a signing secret is set)
+    pub slack: Option<Arc<SlackChannel>>,
+    /// Mattermost channel answering slash commands (only when a command token is set)
+    pub mattermost: Option<Arc<MattermostChannel>>,
     /// Observability backend for metrics scraping
     pub observer: Arc<dyn crate::observability::Observer>,
     /// Registered tool specs (for web dashboard tools page)
@@ -349,6 +423,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
             secrets_encrypt: config.secrets.encrypt,
             reasoning_enabled: config.runtime.reasoning_enabled,
+            redaction: config.security.redaction.clone(),
         },
     )?);
     let model = config
@@ -428,12 +503,16 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .as_ref()
         .filter(|wa| wa.is_cloud_config())
         .map(|wa| {
-            Arc::new(WhatsAppChannel::new(
-                wa.access_token.clone().unwrap_or_default(),
-                wa.phone_number_id.clone().unwrap_or_default(),
-                wa.verify_token.clone().unwrap_or_default(),
-                wa.allowed_numbers.clone(),
-            ))
+            Arc::new(
+                WhatsAppChannel::new(
+                    wa.access_token.clone().unwrap_or_default(),
+                    wa.phone_number_id.clone().unwrap_or_default(),
+                    wa.verify_token.clone().unwrap_or_default(),
+                    wa.allowed_numbers.clone(),
+                )
+                .with_transcription(config.transcription.clone())
+                .with_workspace_dir(config.workspace_dir.clone()),
+            )
         });
 
     // WhatsApp app secret for webhook signature verification
@@ -494,6 +573,84 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             ))
         });
 
+    // Twilio channel (if configured)
+    let twilio_channel: Option<Arc<TwilioChannel>> =
+        config.channels_config.twilio.as_ref().map(|tw| {
+            Arc::new(TwilioChannel::new(
+                tw.account_sid.clone(),
+                tw.auth_token.clone(),
+                tw.from_number.clone(),
+                tw.whatsapp_from.clone(),
+                tw.allowed_numbers.clone(),
+            ))
+        });
+    let twilio_webhook_url: Option<Arc<str>> = config
+        .channels_config
+        .twilio
+        .as_ref()
+        .and_then(|tw| tw.webhook_url.as_deref())
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(Arc::from);
+
+    // Google Chat channel (if configured)
+    let google_chat_channel: Option<Arc<GoogleChatChannel>> =
+        config.channels_config.google_chat.as_ref().map(|gc| {
+            Arc::new(
+                GoogleChatChannel::new(
+                    gc.service_account_key.clone(),
+                    gc.audience.clone(),
+                    gc.allowed_users.clone(),
+                )
+                .with_workspace_dir(config.workspace_dir.clone()),
+            )
+        });
+
+    // Slack slash commands (if a signing secret is configured)
+    let slack_channel: Option<Arc<SlackChannel>> = config
+        .channels_config
+        .slack
+        .as_ref()
+        .filter(|sl| {
+            sl.signing_secret
+                .as_deref()
+                .is_some_and(|secret| !secret.trim().is_empty())
+        })
+        .map(|sl| {
+            Arc::new(
+                SlackChannel::new(
+                    sl.bot_token.clone(),
+                    sl.channel_id.clone(),
+                    sl.allowed_users.clone(),
+                )
+                .with_signing_secret(sl.signing_secret.clone()),
+            )
+        });
+
+    // Mattermost slash commands (if a command token is configured)
+    let mattermost_channel: Option<Arc<MattermostChannel>> = config
+        .channels_config
+        .mattermost
+        .as_ref()
+        .filter(|mm| {
+            mm.command_token
+                .as_deref()
+                .is_some_and(|token| !token.trim().is_empty())
+        })
+        .map(|mm| {
+            Arc::new(
+                MattermostChannel::new(
+                    mm.url.clone(),
+                    mm.bot_token.clone(),
+                    mm.channel_id.clone(),
+                    mm.allowed_users.clone(),
+                    mm.thread_replies.unwrap_or(true),
+                    mm.mention_only.unwrap_or(false),
+                )
+                .with_command_token(mm.command_token.clone()),
+            )
+        });
+
     // Nextcloud Talk channel (if configured)
     let nextcloud_talk_channel: Option<Arc<NextcloudTalkChannel>> =
         config.channels_config.nextcloud_talk.as_ref().map(|nc| {
@@ -528,6 +685,37 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             })
             .map(Arc::from);
 
+    // GitHub comment channel (if configured)
+    let github_channel: Option<Arc<GithubChannel>> =
+        config.channels_config.github.as_ref().map(|gh| {
+            Arc::new(GithubChannel::new(
+                gh.token.clone(),
+                gh.bot_login.clone(),
+                gh.allowed_users.clone(),
+                gh.allowed_repos.clone(),
+                gh.api_url.clone(),
+            ))
+        });
+
+    // GitHub webhook secret for signature verification
+    // Priority: environment variable > config file
+    let github_webhook_secret: Option<Arc<str>> = std::env::var("ZEROCLAW_GITHUB_WEBHOOK_SECRET")
+        .ok()
+        .and_then(|secret| {
+            let secret = secret.trim();
+            (!secret.is_empty()).then(|| secret.to_owned())
+        })
+        .or_else(|| {
+            config.channels_config.github.as_ref().and_then(|gh| {
+                gh.webhook_secret
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|secret| !secret.is_empty())
+                    .map(ToOwned::to_owned)
+            })
+        })
+        .map(Arc::from);
+
     // ── Pairing guard ──────────────────────────────────────
     let pairing = Arc::new(PairingGuard::new(
         config.gateway.require_pairing,
@@ -587,9 +775,24 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         println!("  GET  /wati      — WATI webhook verification");
         println!("  POST /wati      — WATI message webhook");
     }
+    if twilio_channel.is_some() {
+        println!("  POST /twilio    — Twilio SMS/WhatsApp webhook");
+    }
+    if google_chat_channel.is_some() {
+        println!("  POST /google-chat — Google Chat app events");
+    }
+    if slack_channel.is_some() {
+        println!("  POST /slack/commands — Slack slash commands");
+    }
+    if mattermost_channel.is_some() {
+        println!("  POST /mattermost/commands — Mattermost slash commands");
+    }
     if nextcloud_talk_channel.is_some() {
         println!("  POST /nextcloud-talk — Nextcloud Talk bot webhook");
     }
+    if github_channel.is_some() {
+        println!("  POST /github    — GitHub issue/PR comment webhook");
+    }
     println!("  GET  /api/*     — REST API (bearer token required)");
     println!("  GET  /ws/chat   — WebSocket agent chat");
     println!("  GET  /health    — health check");
@@ -640,7 +843,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         linq_signing_secret,
         nextcloud_talk: nextcloud_talk_channel,
         nextcloud_talk_webhook_secret,
+        github: github_channel,
+        github_webhook_secret,
         wati: wati_channel,
+        twilio: twilio_channel,
+        twilio_webhook_url,
+        google_chat: google_chat_channel,
+        slack: slack_channel,
+        mattermost: mattermost_channel,
         observer: broadcast_observer,
         tools_registry,
         cost_tracker,
@@ -653,6 +863,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .layer(RequestBodyLimitLayer::new(1_048_576));
 
     // Build router with middleware
+    let config_watcher = tokio::spawn(watch_config(state.clone()));
+
     let app = Router::new()
         // ── Existing routes ──
         .route("/health", get(handle_health))
@@ -664,7 +876,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/linq", post(handle_linq_webhook))
         .route("/wati", get(handle_wati_verify))
         .route("/wati", post(handle_wati_webhook))
+        .route("/twilio", post(handle_twilio_webhook))
+        .route("/google-chat", post(handle_google_chat_event))
+        .route("/slack/commands", post(handle_slack_command))
+        .route("/mattermost/commands", post(handle_mattermost_command))
         .route("/nextcloud-talk", post(handle_nextcloud_talk_webhook))
+        .route("/github", post(handle_github_webhook))
+        .route("/trigger/{name}", post(triggers::handle_trigger))
+        .route("/media/{token}/{name}", get(handle_media))
         // ── Web Dashboard API routes ──
         .route("/api/status", get(api::handle_api_status))
         .route("/api/config", get(api::handle_api_config_get))
@@ -683,6 +902,27 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/api/cost", get(api::handle_api_cost))
         .route("/api/cli-tools", get(api::handle_api_cli_tools))
         .route("/api/health", get(api::handle_api_health))
+        // ── Admin: runtime control ──
+        .route("/api/admin/sessions", get(api::handle_api_admin_sessions))
+        .route(
+            "/api/admin/sessions/{key}",
+            delete(api::handle_api_admin_session_flush),
+        )
+        .route(
+            "/api/admin/sessions/{key}/export",
+            get(api::handle_api_admin_session_export),
+        )
+        .route("/api/admin/reload", post(api::handle_api_admin_reload))
+        .route("/api/admin/channels", get(api::handle_api_admin_channels))
+        .route(
+            "/api/admin/channels/{name}/disable",
+            post(api::handle_api_admin_channel_disable),
+        )
+        .route(
+            "/api/admin/channels/{name}/enable",
+            post(api::handle_api_admin_channel_enable),
+        )
+        .route("/api/admin/usage", get(api::handle_api_admin_usage))
         // ── SSE event stream ──
         .route("/api/events", get(sse::handle_sse_events))
         // ── WebSocket agent chat ──
@@ -697,19 +937,118 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             StatusCode::REQUEST_TIMEOUT,
             Duration::from_secs(REQUEST_TIMEOUT_SECS),
         ))
+        .layer(axum::middleware::from_fn(trace_request))
         // ── SPA fallback: non-API GET requests serve index.html ──
         .fallback(get(static_files::handle_spa_fallback));
 
     // Run the server
-    axum::serve(
+    // On shutdown, stop accepting connections and let in-flight requests finish.
+    crate::shutdown::listen_for_signals();
+    let served = axum::serve(
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
-    .await?;
+    .with_graceful_shutdown(crate::shutdown::requested())
+    .await;
+    config_watcher.abort();
+    served?;
 
     Ok(())
 }
 
+/// Re-read the `config.toml` the gateway started from, refusing a config
+/// that is missing or does not validate.
+async fn load_reloaded_config(state: &AppState) -> Result<Config> {
+    let current = state.config.lock().clone();
+    current.reload_from_disk().await.context("Invalid config")
+}
+
+/// Swap in a reloaded config. What handlers read per request, such as
+/// triggers and the dashboard APIs, and the rate limits follow at once;
+/// everything else keeps its startup value until a restart.
+fn apply_reloaded_config(state: &AppState, config: Config) {
+    state.rate_limiter.set_limits(
+        config.gateway.pair_rate_limit_per_minute,
+        config.gateway.webhook_rate_limit_per_minute,
+    );
+    *state.config.lock() = config;
+}
+
+fn config_file_stamp(path: &std::path::Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Hot-reload the config whenever the file changes on disk.
+async fn watch_config(state: AppState) {
+    let path = state.config.lock().config_path.clone();
+    let mut last_seen = config_file_stamp(&path);
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        crate::channels::CONFIG_WATCH_INTERVAL_SECS,
+    ));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let stamp = config_file_stamp(&path);
+        if stamp.is_none() || stamp == last_seen {
+            continue;
+        }
+        last_seen = stamp;
+        match load_reloaded_config(&state).await {
+            Ok(config) => {
+                apply_reloaded_config(&state, config);
+                crate::health::mark_component_ok("config_reload");
+                tracing::info!(path = %path.display(), "Gateway applied updated config from disk");
+            }
+            Err(e) => {
+                crate::health::mark_component_error("config_reload", format!("{e:#}"));
+                tracing::error!(
+                    "Config change rejected; the gateway keeps its running config: {e:#}"
+                );
+            }
+        }
+    }
+}
+
+/// Header carrying the correlation ID of a gateway request.
+const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Caller-supplied `X-Request-Id`, if it is a sane token, or a new ID.
+fn request_id_from_headers(headers: &HeaderMap) -> String {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= 128 && id.chars().all(|c| c.is_ascii_graphic()))
+        .map_or_else(crate::observability::new_request_id, str::to_string)
+}
+
+/// Run each request in a `gateway_request` span carrying its request ID, and
+/// echo the ID back so callers can match their request to the logs.
+async fn trace_request(
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let request_id = request_id_from_headers(request.headers());
+    // The route template, not the raw path: media URLs embed access tokens.
+    let route = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map_or("", axum::extract::MatchedPath::as_str)
+        .to_string();
+    let span = tracing::info_span!(
+        "gateway_request",
+        request_id = %request_id,
+        method = %request.method(),
+        route = %route,
+    );
+    let mut response = next.run(request).instrument(span).await;
+    if let Ok(value) = header::HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // AXUM HANDLERS
 // ══════════════════════════════════════════════════════════════════════════════
@@ -725,12 +1064,29 @@ async fn handle_health(State(state): State<AppState>) -> impl IntoResponse {
     Json(body)
 }
 
+/// GET /media/{token}/{name} — local attachment published for a URL-only
+/// channel (`[channels_config.media_host]`). The token is the credential.
+async fn handle_media(
+    axum::extract::Path((token, _name)): axum::extract::Path<(String, String)>,
+) -> axum::response::Response {
+    let Some(path) = crate::channels::media_host::lookup(&token) else {
+        return (StatusCode::NOT_FOUND, "unknown or expired link").into_response();
+    };
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => {
+            let mime = mime_guess::from_path(&path).first_or_octet_stream();
+            ([(header::CONTENT_TYPE, mime.to_string())], bytes).into_response()
+        }
+        Err(_) => (StatusCode::NOT_FOUND, "file no longer available").into_response(),
+    }
+}
+
 /// Prometheus content type for text exposition format.
 const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
 
 /// GET /metrics — Prometheus text exposition format
 async fn handle_metrics(State(state): State<AppState>) -> impl IntoResponse {
-    let body = if let Some(prom) = state
+    let mut body = if let Some(prom) = state
         .observer
         .as_ref()
         .as_any()
@@ -740,6 +1096,8 @@ async fn handle_metrics(State(state): State<AppState>) -> impl IntoResponse {
     } else {
         String::from("# Prometheus backend not enabled. Set [observability] backend = \"prometheus\" in config.\n")
     };
+    // Channel connection state is tracked by the channel supervisor, not the observer.
+    body.push_str(&crate::channels::supervisor::prometheus_metrics());
 
     (
         StatusCode::OK,
@@ -859,10 +1217,10 @@ async fn run_gateway_chat_simple(state: &AppState, message: &str) -> anyhow::Res
         .await
 }
 
-/// Full-featured chat with tools for channel handlers (WhatsApp, Linq, Nextcloud Talk).
+/// Full-featured chat with tools for channel handlers (WhatsApp, Linq, Nextcloud Talk, GitHub).
 async fn run_gateway_chat_with_tools(state: &AppState, message: &str) -> anyhow::Result<String> {
     let config = state.config.lock().clone();
-    crate::agent::process_message(config, message).await
+    Box::pin(crate::agent::process_message(config, message)).await
 }
 
 /// Webhook request body
@@ -1166,7 +1524,11 @@ async fn handle_whatsapp_message(
     };
 
     // Parse messages from the webhook payload
-    let messages = wa.parse_webhook_payload(&payload);
+    let mut messages = wa.parse_webhook_payload(&payload);
+    messages.extend(wa.parse_voice_messages(&payload).await);
+    messages.extend(wa.parse_media_messages(&payload).await);
+    // Meta redelivers webhooks that are not acknowledged in time.
+    messages.retain(should_answer);
 
     if messages.is_empty() {
         // Acknowledge the webhook even if no messages (could be status updates)
@@ -1273,7 +1635,8 @@ async fn handle_linq_webhook(
     };
 
     // Parse messages from the webhook payload
-    let messages = linq.parse_webhook_payload(&payload);
+    let mut messages = linq.parse_webhook_payload(&payload);
+    messages.retain(|msg| !crate::channels::admin::is_channel_disabled(&msg.channel));
 
     if messages.is_empty() {
         // Acknowledge the webhook even if no messages (could be status/delivery events)
@@ -1366,7 +1729,8 @@ async fn handle_wati_webhook(State(state): State<AppState>, body: Bytes) -> impl
     };
 
     // Parse messages from the webhook payload
-    let messages = wati.parse_webhook_payload(&payload);
+    let mut messages = wati.parse_webhook_payload(&payload);
+    messages.retain(|msg| !crate::channels::admin::is_channel_disabled(&msg.channel));
 
     if messages.is_empty() {
         return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
@@ -1416,138 +1780,651 @@ async fn handle_wati_webhook(State(state): State<AppState>, body: Bytes) -> impl
     (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
 }
 
-/// POST /nextcloud-talk — incoming message webhook (Nextcloud Talk bot API)
-async fn handle_nextcloud_talk_webhook(
+/// Empty TwiML document: acknowledges the webhook without an automatic reply.
+const TWIML_EMPTY_RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8"?><Response></Response>"#;
+
+/// POST /twilio — incoming Twilio SMS/MMS/WhatsApp webhook (form-encoded)
+async fn handle_twilio_webhook(
     State(state): State<AppState>,
     headers: HeaderMap,
     body: Bytes,
 ) -> impl IntoResponse {
-    let Some(ref nextcloud_talk) = state.nextcloud_talk else {
+    let Some(ref twilio) = state.twilio else {
         return (
             StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "Nextcloud Talk not configured"})),
+            [(header::CONTENT_TYPE, "text/plain")],
+            "Twilio not configured".to_string(),
         );
     };
+    let form = crate::channels::twilio::parse_form_body(&String::from_utf8_lossy(&body));
+
+    // ── Security: Verify X-Twilio-Signature against the public callback URL ──
+    let webhook_url = state.twilio_webhook_url.as_deref().map_or_else(
+        || {
+            let host = headers
+                .get(header::HOST)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("");
+            format!("https://{host}/twilio")
+        },
+        ToOwned::to_owned,
+    );
+    let signature = headers
+        .get("X-Twilio-Signature")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !twilio.verify_webhook_signature(&webhook_url, &form, signature) {
+        tracing::warn!(
+            "Twilio webhook signature verification failed (signature: {}, url: {webhook_url})",
+            if signature.is_empty() {
+                "missing"
+            } else {
+                "invalid"
+            }
+        );
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::CONTENT_TYPE, "text/plain")],
+            "Invalid signature".to_string(),
+        );
+    }
 
-    let body_str = String::from_utf8_lossy(&body);
+    let mut messages = twilio.parse_webhook_form(&form);
+    messages.retain(should_answer);
+
+    // Twilio gives up on webhooks after 15s, so answer from a background task.
+    if !messages.is_empty() {
+        let twilio = Arc::clone(twilio);
+        let state = state.clone();
+        tokio::spawn(async move {
+            for msg in &messages {
+                tracing::info!(
+                    "Twilio message from {}: {}",
+                    msg.sender,
+                    truncate_with_ellipsis(&msg.content, 50)
+                );
+
+                // Auto-save to memory
+                if state.auto_save {
+                    let key = twilio_memory_key(msg);
+                    let _ = state
+                        .mem
+                        .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                        .await;
+                }
 
-    // ── Security: Verify Nextcloud Talk HMAC signature if secret is configured ──
-    if let Some(ref webhook_secret) = state.nextcloud_talk_webhook_secret {
-        let random = headers
-            .get("X-Nextcloud-Talk-Random")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
+                match run_gateway_chat_with_tools(&state, &msg.content).await {
+                    Ok(response) => {
+                        if let Err(e) = twilio
+                            .send(&SendMessage::new(response, &msg.reply_target))
+                            .await
+                        {
+                            tracing::error!("Failed to send Twilio reply: {e}");
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("LLM error for Twilio message: {e:#}");
+                        let _ = twilio
+                            .send(&SendMessage::new(
+                                "Sorry, I couldn't process your message right now.",
+                                &msg.reply_target,
+                            ))
+                            .await;
+                    }
+                }
+            }
+        });
+    }
 
-        let signature = headers
-            .get("X-Nextcloud-Talk-Signature")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/xml")],
+        TWIML_EMPTY_RESPONSE.to_string(),
+    )
+}
 
-        if !crate::channels::nextcloud_talk::verify_nextcloud_talk_signature(
-            webhook_secret,
-            random,
-            &body_str,
-            signature,
-        ) {
-            tracing::warn!(
-                "Nextcloud Talk webhook signature verification failed (signature: {})",
-                if signature.is_empty() {
-                    "missing"
-                } else {
-                    "invalid"
-                }
-            );
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": "Invalid signature"})),
-            );
-        }
+/// POST /google-chat — Google Chat app interaction events
+async fn handle_google_chat_event(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref google_chat) = state.google_chat else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Google Chat not configured"})),
+        );
+    };
+
+    // ── Security: Verify the Google-signed bearer token ──
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    if !google_chat.verify_request(authorization).await {
+        tracing::warn!(
+            "Google Chat request verification failed (token: {})",
+            if authorization.is_some() {
+                "invalid"
+            } else {
+                "missing"
+            }
+        );
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Invalid request token"})),
+        );
     }
 
-    // Parse JSON body
-    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
+    let Ok(event) = serde_json::from_slice::<serde_json::Value>(&body) else {
         return (
             StatusCode::BAD_REQUEST,
             Json(serde_json::json!({"error": "Invalid JSON payload"})),
         );
     };
 
-    // Parse messages from webhook payload
-    let messages = nextcloud_talk.parse_webhook_payload(&payload);
-    if messages.is_empty() {
-        // Acknowledge webhook even if payload does not contain actionable user messages.
-        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
-    }
-
-    for msg in &messages {
+    // Chat expects an answer within 30s, so reply asynchronously via the API.
+    let google_chat = Arc::clone(google_chat);
+    tokio::spawn(async move {
+        let Some(msg) = google_chat.message_from_event(&event).await else {
+            return;
+        };
+        if !should_answer(&msg) {
+            return;
+        }
         tracing::info!(
-            "Nextcloud Talk message from {}: {}",
+            "Google Chat message from {}: {}",
             msg.sender,
             truncate_with_ellipsis(&msg.content, 50)
         );
 
+        // Auto-save to memory
         if state.auto_save {
-            let key = nextcloud_talk_memory_key(msg);
+            let key = google_chat_memory_key(&msg);
             let _ = state
                 .mem
                 .store(&key, &msg.content, MemoryCategory::Conversation, None)
                 .await;
         }
 
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
-            Ok(response) => {
-                if let Err(e) = nextcloud_talk
-                    .send(&SendMessage::new(response, &msg.reply_target))
-                    .await
-                {
-                    tracing::error!("Failed to send Nextcloud Talk reply: {e}");
-                }
-            }
+        let reply = match run_gateway_chat_with_tools(&state, &msg.content).await {
+            Ok(response) => response,
             Err(e) => {
-                tracing::error!("LLM error for Nextcloud Talk message: {e:#}");
-                let _ = nextcloud_talk
-                    .send(&SendMessage::new(
-                        "Sorry, I couldn't process your message right now.",
-                        &msg.reply_target,
-                    ))
-                    .await;
+                tracing::error!("LLM error for Google Chat message: {e:#}");
+                "Sorry, I couldn't process your message right now.".to_string()
             }
+        };
+        if let Err(e) = google_chat
+            .send(&SendMessage::new(reply, &msg.reply_target).in_thread(msg.thread_ts.clone()))
+            .await
+        {
+            tracing::error!("Failed to send Google Chat reply: {e}");
         }
-    }
+    });
 
-    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
+    // An empty message tells Chat no synchronous reply is coming.
+    (StatusCode::OK, Json(serde_json::json!({})))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::channels::traits::ChannelMessage;
-    use crate::memory::{Memory, MemoryCategory, MemoryEntry};
-    use crate::providers::Provider;
-    use async_trait::async_trait;
-    use axum::http::HeaderValue;
-    use axum::response::IntoResponse;
-    use http_body_util::BodyExt;
-    use parking_lot::Mutex;
-    use std::sync::atomic::{AtomicUsize, Ordering};
+/// POST /slack/commands — Slack slash command requests (form-encoded)
+async fn handle_slack_command(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref slack) = state.slack else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Slack slash commands not configured"})),
+        );
+    };
 
-    /// Generate a random hex secret at runtime to avoid hard-coded cryptographic values.
-    fn generate_test_secret() -> String {
-        let bytes: [u8; 32] = rand::random();
-        hex::encode(bytes)
+    // ── Security: Verify X-Slack-Signature over the raw body ──
+    let timestamp = headers
+        .get("X-Slack-Request-Timestamp")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let signature = headers
+        .get("X-Slack-Signature")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !slack.verify_request(timestamp, &body, signature) {
+        tracing::warn!(
+            "Slack slash command signature verification failed (signature: {})",
+            if signature.is_empty() {
+                "missing"
+            } else {
+                "invalid"
+            }
+        );
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Invalid signature"})),
+        );
     }
 
-    #[test]
-    fn security_body_limit_is_64kb() {
-        assert_eq!(MAX_BODY_SIZE, 65_536);
+    let form = crate::channels::twilio::parse_form_body(&String::from_utf8_lossy(&body));
+    let Some(command) = slack.slash_command_from_form(&form) else {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "response_type": "ephemeral",
+                "text": "This command is not available to you here.",
+            })),
+        );
+    };
+    if crate::channels::admin::is_channel_disabled("slack") {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "response_type": "ephemeral",
+                "text": "The assistant is paused here right now. Please try again later.",
+            })),
+        );
     }
-
-    #[test]
-    fn security_timeout_is_30_seconds() {
-        assert_eq!(REQUEST_TIMEOUT_SECS, 30);
+    if command.text.is_empty() {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "response_type": "ephemeral",
+                "text": format!("Usage: `{} <your question>`", command.command),
+            })),
+        );
     }
 
-    #[test]
+    let echo = command
+        .text
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Slack expects an acknowledgement within 3s; the reply follows through
+    // the command's response_url.
+    let slack = Arc::clone(slack);
+    tokio::spawn(async move {
+        tracing::info!(
+            "Slack slash command {} from {}: {}",
+            command.command,
+            command.user_id,
+            truncate_with_ellipsis(&command.text, 50)
+        );
+
+        // Auto-save to memory
+        if state.auto_save {
+            let key = slack_command_memory_key(&command);
+            let _ = state
+                .mem
+                .store(&key, &command.text, MemoryCategory::Conversation, None)
+                .await;
+        }
+
+        let reply = match run_gateway_chat_with_tools(&state, &command.text).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::error!("LLM error for Slack slash command: {e:#}");
+                "Sorry, I couldn't process your message right now.".to_string()
+            }
+        };
+        if let Err(e) = slack.respond_to_command(&command, &reply).await {
+            tracing::error!("Failed to send Slack slash command reply: {e}");
+        }
+    });
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "response_type": "in_channel",
+            "text": echo,
+        })),
+    )
+}
+
+/// POST /mattermost/commands — Mattermost custom slash command requests
+/// (form-encoded)
+async fn handle_mattermost_command(
+    State(state): State<AppState>,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref mattermost) = state.mattermost else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Mattermost slash commands not configured"})),
+        );
+    };
+
+    // ── Security: Verify the slash command token ──
+    let form = crate::channels::twilio::parse_form_body(&String::from_utf8_lossy(&body));
+    let token = form.get("token").map(String::as_str).unwrap_or("");
+    if !mattermost.verify_command_token(token) {
+        tracing::warn!(
+            "Mattermost slash command token verification failed (token: {})",
+            if token.is_empty() {
+                "missing"
+            } else {
+                "invalid"
+            }
+        );
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Invalid token"})),
+        );
+    }
+
+    let Some(command) = mattermost.slash_command_from_form(&form) else {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "response_type": "ephemeral",
+                "text": "This command is not available to you here.",
+            })),
+        );
+    };
+    if crate::channels::admin::is_channel_disabled("mattermost") {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "response_type": "ephemeral",
+                "text": "The assistant is paused here right now. Please try again later.",
+            })),
+        );
+    }
+    if command.text.is_empty() {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "response_type": "ephemeral",
+                "text": format!("Usage: `{} <your question>`", command.command),
+            })),
+        );
+    }
+
+    let echo = command
+        .text
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Mattermost expects a response within 3s; the reply follows as a
+    // regular bot post so attachments can be uploaded.
+    let mattermost = Arc::clone(mattermost);
+    tokio::spawn(async move {
+        tracing::info!(
+            "Mattermost slash command {} from {}: {}",
+            command.command,
+            command.user_id,
+            truncate_with_ellipsis(&command.text, 50)
+        );
+
+        // Auto-save to memory
+        if state.auto_save {
+            let key = mattermost_command_memory_key(&command);
+            let _ = state
+                .mem
+                .store(&key, &command.text, MemoryCategory::Conversation, None)
+                .await;
+        }
+
+        let reply = match run_gateway_chat_with_tools(&state, &command.text).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::error!("LLM error for Mattermost slash command: {e:#}");
+                "Sorry, I couldn't process your message right now.".to_string()
+            }
+        };
+        if let Err(e) = mattermost.respond_to_command(&command, &reply).await {
+            tracing::error!("Failed to send Mattermost slash command reply: {e}");
+        }
+    });
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "response_type": "in_channel",
+            "text": echo,
+        })),
+    )
+}
+
+/// POST /nextcloud-talk — incoming message webhook (Nextcloud Talk bot API)
+async fn handle_nextcloud_talk_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref nextcloud_talk) = state.nextcloud_talk else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Nextcloud Talk not configured"})),
+        );
+    };
+
+    let body_str = String::from_utf8_lossy(&body);
+
+    // ── Security: Verify Nextcloud Talk HMAC signature if secret is configured ──
+    if let Some(ref webhook_secret) = state.nextcloud_talk_webhook_secret {
+        let random = headers
+            .get("X-Nextcloud-Talk-Random")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        let signature = headers
+            .get("X-Nextcloud-Talk-Signature")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        if !crate::channels::nextcloud_talk::verify_nextcloud_talk_signature(
+            webhook_secret,
+            random,
+            &body_str,
+            signature,
+        ) {
+            tracing::warn!(
+                "Nextcloud Talk webhook signature verification failed (signature: {})",
+                if signature.is_empty() {
+                    "missing"
+                } else {
+                    "invalid"
+                }
+            );
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({"error": "Invalid signature"})),
+            );
+        }
+    }
+
+    // Parse JSON body
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Invalid JSON payload"})),
+        );
+    };
+
+    // Parse messages from webhook payload
+    let mut messages = nextcloud_talk.parse_webhook_payload(&payload);
+    messages.retain(should_answer);
+    if messages.is_empty() {
+        // Acknowledge webhook even if payload does not contain actionable user messages.
+        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
+    }
+
+    for msg in &messages {
+        tracing::info!(
+            "Nextcloud Talk message from {}: {}",
+            msg.sender,
+            truncate_with_ellipsis(&msg.content, 50)
+        );
+
+        if state.auto_save {
+            let key = nextcloud_talk_memory_key(msg);
+            let _ = state
+                .mem
+                .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                .await;
+        }
+
+        match run_gateway_chat_with_tools(&state, &msg.content).await {
+            Ok(response) => {
+                if let Err(e) = nextcloud_talk
+                    .send(&SendMessage::new(response, &msg.reply_target))
+                    .await
+                {
+                    tracing::error!("Failed to send Nextcloud Talk reply: {e}");
+                }
+            }
+            Err(e) => {
+                tracing::error!("LLM error for Nextcloud Talk message: {e:#}");
+                let _ = nextcloud_talk
+                    .send(&SendMessage::new(
+                        "Sorry, I couldn't process your message right now.",
+                        &msg.reply_target,
+                    ))
+                    .await;
+            }
+        }
+    }
+
+    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
+}
+
+/// POST /github — issue and pull request comment webhook
+async fn handle_github_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref github) = state.github else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "GitHub not configured"})),
+        );
+    };
+
+    // ── Security: Verify X-Hub-Signature-256 if secret is configured ──
+    if let Some(ref webhook_secret) = state.github_webhook_secret {
+        let signature = headers
+            .get("X-Hub-Signature-256")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        if !crate::channels::github::verify_github_signature(webhook_secret, &body, signature) {
+            tracing::warn!(
+                "GitHub webhook signature verification failed (signature: {})",
+                if signature.is_empty() {
+                    "missing"
+                } else {
+                    "invalid"
+                }
+            );
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({"error": "Invalid signature"})),
+            );
+        }
+    }
+
+    let event = headers
+        .get("X-GitHub-Event")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Invalid JSON payload"})),
+        );
+    };
+
+    let Some(msg) = github
+        .parse_webhook_payload(&event, &payload)
+        .filter(should_answer)
+    else {
+        // Acknowledge pings and events that do not mention the bot.
+        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
+    };
+
+    // GitHub times out deliveries after 10s; the reply follows as a comment.
+    let github = Arc::clone(github);
+    tokio::spawn(
+        async move {
+            tracing::info!(
+                "GitHub mention from {} on {}: {}",
+                msg.sender,
+                msg.reply_target,
+                truncate_with_ellipsis(&msg.content, 50)
+            );
+
+            if state.auto_save {
+                let key = github_memory_key(&msg);
+                let _ = state
+                    .mem
+                    .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                    .await;
+            }
+
+            let reply = match run_gateway_chat_with_tools(&state, &msg.content).await {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::error!("LLM error for GitHub mention: {e:#}");
+                    "Sorry, I couldn't process your message right now.".to_string()
+                }
+            };
+            if let Err(e) = github
+                .send(&SendMessage::new(reply, &msg.reply_target))
+                .await
+            {
+                tracing::error!("Failed to send GitHub reply: {e}");
+            }
+        }
+        .in_current_span(),
+    );
+
+    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+    use crate::providers::Provider;
+    use async_trait::async_trait;
+    use axum::http::HeaderValue;
+    use axum::response::IntoResponse;
+    use http_body_util::BodyExt;
+    use parking_lot::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Generate a random hex secret at runtime to avoid hard-coded cryptographic values.
+    fn generate_test_secret() -> String {
+        let bytes: [u8; 32] = rand::random();
+        hex::encode(bytes)
+    }
+
+    #[test]
+    fn request_id_prefers_sane_caller_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("ci-run-42"));
+        assert_eq!(request_id_from_headers(&headers), "ci-run-42");
+
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("has space"));
+        let generated = request_id_from_headers(&headers);
+        assert_eq!(generated.len(), 12);
+        assert_ne!(generated, request_id_from_headers(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn security_body_limit_is_64kb() {
+        assert_eq!(MAX_BODY_SIZE, 65_536);
+    }
+
+    #[test]
+    fn security_timeout_is_30_seconds() {
+        assert_eq!(REQUEST_TIMEOUT_SECS, 30);
+    }
+
+    #[test]
     fn webhook_body_requires_message_field() {
         let valid = r#"{"message": "hello"}"#;
         let parsed: Result<WebhookBody, _> = serde_json::from_str(valid);
@@ -1595,7 +2472,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -1644,7 +2528,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer,
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -2010,7 +2901,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -2074,7 +2972,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -2150,7 +3055,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -2198,7 +3110,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -2251,7 +3170,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -2309,7 +3235,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: None,
             nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -2363,7 +3296,14 @@ mod tests {
             linq_signing_secret: None,
             nextcloud_talk: Some(channel),
             nextcloud_talk_webhook_secret: Some(Arc::from(secret)),
+            github: None,
+            github_webhook_secret: None,
             wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
             observer: Arc::new(crate::observability::NoopObserver),
             tools_registry: Arc::new(Vec::new()),
             cost_tracker: None,
@@ -2387,6 +3327,303 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
     }
 
+    #[tokio::test]
+    async fn github_webhook_rejects_invalid_signature() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let channel = Arc::new(GithubChannel::new(
+            "github-token".into(),
+            "zeroclaw-bot".into(),
+            vec!["*".into()],
+            Vec::new(),
+            None,
+        ));
+        let secret = generate_test_secret();
+        let body = r#"{"action":"created","issue":{"number":1,"title":"t"},"comment":{"id":2,"body":"@zeroclaw-bot hi","user":{"login":"alice"}},"repository":{"full_name":"acme/widgets"}}"#;
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            github: Some(channel),
+            github_webhook_secret: Some(Arc::from(secret.as_str())),
+            wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-GitHub-Event", HeaderValue::from_static("issue_comment"));
+        headers.insert(
+            "X-Hub-Signature-256",
+            HeaderValue::from_static("sha256=deadbeef"),
+        );
+
+        let response = handle_github_webhook(State(state), headers, Bytes::from(body))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn twilio_webhook_rejects_invalid_signature() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let channel = Arc::new(TwilioChannel::new(
+            "AC123".into(),
+            "twilio-auth-token".into(),
+            "+15550001111".into(),
+            None,
+            vec!["*".into()],
+        ));
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
+            wati: None,
+            twilio: Some(channel),
+            twilio_webhook_url: Some(Arc::from("https://bot.example.com/twilio")),
+            google_chat: None,
+            slack: None,
+            mattermost: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Twilio-Signature",
+            HeaderValue::from_static("bm90LWEtc2lnbmF0dXJl"),
+        );
+
+        let response = handle_twilio_webhook(
+            State(state),
+            headers,
+            Bytes::from_static(b"From=%2B1234567890&Body=hello"),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn google_chat_event_rejects_missing_token() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let channel = Arc::new(GoogleChatChannel::new(
+            "{}".into(),
+            "123456789".into(),
+            vec!["*".into()],
+        ));
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
+            wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: Some(channel),
+            slack: None,
+            mattermost: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let response = handle_google_chat_event(
+            State(state),
+            HeaderMap::new(),
+            Bytes::from_static(br#"{"type":"MESSAGE","message":{"text":"hi"}}"#),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn slack_command_rejects_invalid_signature() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let channel = Arc::new(
+            SlackChannel::new("xoxb-test".into(), None, vec!["*".into()])
+                .with_signing_secret(Some("signing-secret".into())),
+        );
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
+            wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: Some(channel),
+            mattermost: None,
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Slack-Request-Timestamp", HeaderValue::from_static("1"));
+        headers.insert("X-Slack-Signature", HeaderValue::from_static("v0=deadbeef"));
+        let response = handle_slack_command(
+            State(state),
+            headers,
+            Bytes::from_static(b"command=%2Fask&text=hi&user_id=U1&channel_id=C1&response_url=https%3A%2F%2Fhooks.slack.com%2Fx"),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn mattermost_command_rejects_invalid_token() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+
+        let channel = Arc::new(
+            MattermostChannel::new(
+                "https://mm.example.com".into(),
+                "bot-token".into(),
+                None,
+                vec!["*".into()],
+                true,
+                false,
+            )
+            .with_command_token(Some("command-token".into())),
+        );
+
+        let state = AppState {
+            config: Arc::new(Mutex::new(Config::default())),
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            trust_forwarded_headers: false,
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            linq: None,
+            linq_signing_secret: None,
+            nextcloud_talk: None,
+            nextcloud_talk_webhook_secret: None,
+            github: None,
+            github_webhook_secret: None,
+            wati: None,
+            twilio: None,
+            twilio_webhook_url: None,
+            google_chat: None,
+            slack: None,
+            mattermost: Some(channel),
+            observer: Arc::new(crate::observability::NoopObserver),
+            tools_registry: Arc::new(Vec::new()),
+            cost_tracker: None,
+            event_tx: tokio::sync::broadcast::channel(16).0,
+        };
+
+        let response = handle_mattermost_command(
+            State(state),
+            Bytes::from_static(b"token=wrong&command=%2Fask&text=hi&user_id=u1&channel_id=c1"),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
     // ══════════════════════════════════════════════════════════
     // WhatsApp Signature Verification Tests (CWE-345 Prevention)
     // ══════════════════════════════════════════════════════════
@@ -2693,6 +3930,21 @@ mod tests {
         assert!(!limiter.allow_webhook("ip-1")); // webhook now blocked
     }
 
+    #[test]
+    fn gateway_rate_limiter_applies_reloaded_limits() {
+        let limiter = GatewayRateLimiter::new(1, 1, 100);
+        assert!(limiter.allow_webhook("ip-1"));
+        assert!(!limiter.allow_webhook("ip-1"));
+
+        limiter.set_limits(1, 3);
+        assert!(limiter.allow_webhook("ip-1"));
+        assert!(limiter.allow_webhook("ip-1"));
+        assert!(!limiter.allow_webhook("ip-1"));
+
+        limiter.set_limits(1, 0);
+        assert!(limiter.allow_webhook("ip-1"));
+    }
+
     #[test]
     fn rate_limiter_single_key_max_allows_one_request() {
         let limiter = SlidingWindowRateLimiter::new(5, Duration::from_secs(60), 1);
@@ -0,0 +1,219 @@
+//! Incoming webhook triggers (`POST /trigger/{name}`).
+//!
+//! An external system (CI, alerting) posts JSON to a trigger configured in
+//! `[triggers.<name>]`. The payload is rendered into the trigger's prompt
+//! template, the agent answers it in the background, and the answer is
+//! delivered to the trigger's `deliver_to` destination through the same
+//! path as cron announcements.
+
+use super::{client_key_from_request, hash_webhook_secret, AppState, RATE_LIMIT_WINDOW_SECS};
+use crate::config::TriggerConfig;
+use crate::security::pairing::constant_time_eq;
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
+};
+use serde_json::Value;
+use std::net::SocketAddr;
+use tracing::Instrument;
+
+/// Whether the request carries the trigger's secret, or the pairing token
+/// when the trigger has no secret of its own.
+fn is_authorized(state: &AppState, trigger: &TriggerConfig, headers: &HeaderMap) -> bool {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    let Some(secret) = trigger.secret.as_deref().filter(|s| !s.is_empty()) else {
+        return !state.pairing.require_pairing()
+            || state.pairing.is_authenticated(bearer.unwrap_or(""));
+    };
+    let expected = hash_webhook_secret(secret);
+    headers
+        .get("X-Trigger-Secret")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .into_iter()
+        .chain(bearer)
+        .any(|candidate| constant_time_eq(&hash_webhook_secret(candidate), &expected))
+}
+
+/// Value at a dot-separated `path` in `payload`; numeric segments index
+/// arrays. `"."` is the whole payload.
+fn lookup<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    if path == "." {
+        return Some(payload);
+    }
+    path.split('.')
+        .try_fold(payload, |value, segment| match value {
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => value.get(segment),
+        })
+}
+
+/// Replace each `{{path}}` in `template` with the payload value at `path`:
+/// strings as-is, other values as JSON (pretty-printed for `{{.}}`), and
+/// missing values as an empty string.
+pub fn render_prompt(template: &str, payload: &Value) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let path = rest[start + 2..start + 2 + len].trim();
+        match lookup(payload, path) {
+            Some(Value::String(text)) => rendered.push_str(text),
+            Some(Value::Null) | None => {}
+            Some(value) if path == "." => {
+                rendered.push_str(&serde_json::to_string_pretty(value).unwrap_or_default());
+            }
+            Some(value) => rendered.push_str(&value.to_string()),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// POST /trigger/{name} — run the agent on an external event
+pub async fn handle_trigger(
+    State(state): State<AppState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(trigger) = state.config.lock().triggers.get(&name).cloned() else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Unknown trigger"})),
+        );
+    };
+
+    let rate_key =
+        client_key_from_request(Some(peer_addr), &headers, state.trust_forwarded_headers);
+    if !state.rate_limiter.allow_webhook(&rate_key) {
+        tracing::warn!("/trigger/{name} rate limit exceeded");
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(serde_json::json!({
+                "error": "Too many trigger requests. Please retry later.",
+                "retry_after": RATE_LIMIT_WINDOW_SECS,
+            })),
+        );
+    }
+
+    if !is_authorized(&state, &trigger, &headers) {
+        tracing::warn!("Trigger {name}: rejected request — invalid or missing secret");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({
+                "error": "Unauthorized — send X-Trigger-Secret or Authorization: Bearer <secret>"
+            })),
+        );
+    }
+
+    let Ok(payload) = serde_json::from_slice::<Value>(&body) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Invalid JSON payload"})),
+        );
+    };
+
+    if let Some(idempotency_key) = headers
+        .get("X-Idempotency-Key")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        if !state
+            .idempotency_store
+            .record_if_new(&format!("trigger:{name}:{idempotency_key}"))
+        {
+            tracing::info!(
+                "Trigger {name}: duplicate ignored (idempotency key: {idempotency_key})"
+            );
+            return (
+                StatusCode::OK,
+                Json(serde_json::json!({"status": "duplicate", "idempotent": true})),
+            );
+        }
+    }
+
+    let prompt = render_prompt(&trigger.prompt, &payload);
+    // Senders time out quickly; the answer is delivered to the channel.
+    tokio::spawn(
+        async move {
+            tracing::info!("Trigger {name} fired; running agent");
+            let reply = match super::run_gateway_chat_with_tools(&state, &prompt).await {
+                Ok(reply) => reply,
+                Err(e) => {
+                    tracing::error!("Trigger {name}: agent failed: {e:#}");
+                    return;
+                }
+            };
+            let Some((channel, to)) = trigger.deliver_to.split_once(':') else {
+                tracing::error!(
+                    "Trigger {name}: invalid deliver_to '{}'",
+                    trigger.deliver_to
+                );
+                return;
+            };
+            let config = state.config.lock().clone();
+            if let Err(e) = crate::cron::scheduler::deliver_proactive(
+                &config,
+                &channel.trim().to_ascii_lowercase(),
+                to.trim(),
+                &format!("trigger:{name}"),
+                &reply,
+            )
+            .await
+            {
+                tracing::error!("Trigger {name}: delivery failed: {e:#}");
+            }
+        }
+        .in_current_span(),
+    );
+
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({"status": "accepted"})),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn render_prompt_substitutes_payload_fields() {
+        let payload = json!({
+            "title": "[FIRING:1] HighErrorRate",
+            "alerts": [{"status": "firing", "labels": {"service": "api"}, "value": 0.42}],
+            "state": null
+        });
+        let prompt = render_prompt(
+            "Triage {{ title }}: {{alerts.0.labels.service}} is {{alerts.0.status}} \
+             ({{alerts.0.value}}){{state}}{{missing.field}}",
+            &payload,
+        );
+        assert_eq!(
+            prompt,
+            "Triage [FIRING:1] HighErrorRate: api is firing (0.42)"
+        );
+
+        let whole = render_prompt("Payload:\n{{.}}", &json!({"ok": true}));
+        assert_eq!(whole, "Payload:\n{\n  \"ok\": true\n}");
+        assert_eq!(
+            render_prompt("unclosed {{ title", &payload),
+            "unclosed {{ title"
+        );
+    }
+}
@@ -0,0 +1,695 @@
+//! Knowledge base over the user's own documents.
+//!
+//! Files under `[knowledge].directories` are converted to text, split with
+//! the memory chunker, embedded with the `[memory]` embedding provider and
+//! stored in `state/knowledge.db` (chunk text, an FTS5 index and embedding
+//! BLOBs). [`KnowledgeBase::sync`] re-indexes only files whose size or
+//! modification time changed; the daemon runs it every
+//! `rescan_interval_secs` and `search_knowledge` runs it before each query.
+//! Search uses the same hybrid vector + BM25 ranking as SQLite memory and
+//! falls back to keywords when no embedding provider is configured.
+
+use crate::config::{Config, KnowledgeConfig};
+use crate::memory::embeddings::EmbeddingProvider;
+use crate::memory::{chunker, vector};
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tokio::time::{self, Duration};
+
+const KNOWLEDGE_COMPONENT: &str = "knowledge";
+/// Files larger than this are not indexed (50 MB).
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+/// Chunks embedded per provider request.
+const EMBED_BATCH_SIZE: usize = 32;
+/// Directory depth below each knowledge directory that is scanned.
+const MAX_SCAN_DEPTH: usize = 16;
+/// Shortest rescan interval the daemon accepts.
+const MIN_RESCAN_SECS: u64 = 30;
+
+/// A file found under one of the knowledge directories.
+#[derive(Debug, Clone)]
+struct SourceFile {
+    /// Index key and display name: workspace-relative when the file is
+    /// inside the workspace, absolute otherwise.
+    key: String,
+    path: PathBuf,
+    modified: i64,
+    size: i64,
+}
+
+/// Outcome of one [`KnowledgeBase::sync`] pass.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SyncStats {
+    pub indexed: usize,
+    pub removed: usize,
+    pub failed: usize,
+}
+
+/// A chunk of a document returned by [`KnowledgeBase::search`].
+#[derive(Debug, Clone)]
+pub struct Passage {
+    pub source: String,
+    pub heading: Option<String>,
+    pub content: String,
+    pub score: f32,
+}
+
+pub struct KnowledgeBase {
+    config: KnowledgeConfig,
+    workspace_dir: PathBuf,
+    embedder: Arc<dyn EmbeddingProvider>,
+    /// Identifies the embedding model; a change forces a full re-index.
+    fingerprint: String,
+    vector_weight: f32,
+    keyword_weight: f32,
+    /// Keeps concurrent `search_knowledge` calls from indexing the same
+    /// files twice.
+    sync_lock: tokio::sync::Mutex<()>,
+}
+
+impl KnowledgeBase {
+    pub fn from_config(config: &Config) -> Self {
+        let (embedder, fingerprint) = crate::memory::create_embedder(
+            &config.memory,
+            &config.embedding_routes,
+            config.api_key.as_deref(),
+        );
+        Self::new(
+            config.knowledge.clone(),
+            config.workspace_dir.clone(),
+            embedder,
+            fingerprint,
+        )
+        .with_weights(config.memory.vector_weight, config.memory.keyword_weight)
+    }
+
+    pub fn new(
+        config: KnowledgeConfig,
+        workspace_dir: PathBuf,
+        embedder: Arc<dyn EmbeddingProvider>,
+        fingerprint: String,
+    ) -> Self {
+        Self {
+            config,
+            workspace_dir,
+            embedder,
+            fingerprint,
+            vector_weight: 0.7,
+            keyword_weight: 0.3,
+            sync_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn with_weights(mut self, vector_weight: f64, keyword_weight: f64) -> Self {
+        self.vector_weight = vector_weight as f32;
+        self.keyword_weight = keyword_weight as f32;
+        self
+    }
+
+    fn db_path(&self) -> PathBuf {
+        self.workspace_dir.join("state").join("knowledge.db")
+    }
+
+    fn directories(&self) -> Vec<PathBuf> {
+        self.config
+            .directories
+            .iter()
+            .map(|dir| {
+                let dir = PathBuf::from(shellexpand::tilde(dir.trim()).into_owned());
+                if dir.is_absolute() {
+                    dir
+                } else {
+                    self.workspace_dir.join(dir)
+                }
+            })
+            .collect()
+    }
+
+    // ── Indexing ────────────────────────────────────────────────
+
+    /// Bring the index in line with the knowledge directories: index new and
+    /// changed files and drop deleted ones. Files that fail to convert or
+    /// embed are logged and retried on the next pass.
+    pub async fn sync(&self) -> Result<SyncStats> {
+        let _guard = self.sync_lock.lock().await;
+
+        let directories = self.directories();
+        let extensions: Vec<String> = self
+            .config
+            .extensions
+            .iter()
+            .map(|ext| ext.trim_start_matches('.').to_ascii_lowercase())
+            .collect();
+        let workspace_dir = self.workspace_dir.clone();
+        let files = tokio::task::spawn_blocking(move || {
+            scan_directories(&directories, &extensions, &workspace_dir)
+        })
+        .await?;
+
+        let indexed = with_connection(&self.db_path(), |conn| {
+            reset_if_model_changed(conn, &self.fingerprint)?;
+            indexed_files(conn)
+        })?;
+
+        let mut stats = SyncStats::default();
+        let stale: Vec<&String> = indexed
+            .keys()
+            .filter(|key| !files.contains_key(*key))
+            .collect();
+        if !stale.is_empty() {
+            with_connection(&self.db_path(), |conn| {
+                let tx = conn.unchecked_transaction()?;
+                for key in &stale {
+                    tx.execute("DELETE FROM kb_chunks WHERE source = ?1", params![key])?;
+                    tx.execute("DELETE FROM kb_files WHERE source = ?1", params![key])?;
+                }
+                tx.commit()?;
+                Ok(())
+            })?;
+            stats.removed = stale.len();
+        }
+
+        for file in files.values() {
+            if indexed.get(&file.key) == Some(&(file.modified, file.size)) {
+                continue;
+            }
+            match self.index_file(file).await {
+                Ok(()) => stats.indexed += 1,
+                Err(e) => {
+                    tracing::warn!("Knowledge base: failed to index {}: {e}", file.key);
+                    stats.failed += 1;
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    async fn index_file(&self, file: &SourceFile) -> Result<()> {
+        let text = document_text(&file.path).await?;
+        let chunks: Vec<(Option<String>, String)> =
+            chunker::chunk_markdown(&text, self.config.chunk_max_tokens.max(64))
+                .into_iter()
+                .filter(|chunk| !chunk.content.trim().is_empty())
+                .map(|chunk| {
+                    let heading = chunk
+                        .heading
+                        .map(|h| h.trim_start_matches('#').trim().to_string());
+                    (heading, chunk.content)
+                })
+                .collect();
+
+        let mut embeddings: Vec<Option<Vec<u8>>> = vec![None; chunks.len()];
+        if self.embedder.dimensions() > 0 {
+            let mut offset = 0;
+            for batch in chunks.chunks(EMBED_BATCH_SIZE) {
+                let texts: Vec<&str> = batch.iter().map(|(_, content)| content.as_str()).collect();
+                let vectors = self.embedder.embed(&texts).await?;
+                if vectors.len() != batch.len() {
+                    bail!(
+                        "embedding provider returned {} vectors for {} chunks",
+                        vectors.len(),
+                        batch.len()
+                    );
+                }
+                for vector in vectors {
+                    embeddings[offset] = Some(vector::vec_to_bytes(&vector));
+                    offset += 1;
+                }
+            }
+        }
+
+        with_connection(&self.db_path(), |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM kb_chunks WHERE source = ?1", params![file.key])?;
+            for (index, ((heading, content), embedding)) in
+                chunks.iter().zip(&embeddings).enumerate()
+            {
+                tx.execute(
+                    "INSERT INTO kb_chunks (source, chunk_index, heading, content, embedding)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![file.key, index, heading, content, embedding],
+                )?;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO kb_files (source, modified, size, chunks, indexed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    file.key,
+                    file.modified,
+                    file.size,
+                    chunks.len(),
+                    chrono::Utc::now().to_rfc3339()
+                ],
+            )?;
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    // ── Search ──────────────────────────────────────────────────
+
+    /// The `limit` best-matching passages for `query`.
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Passage>> {
+        if query.trim().is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let query_embedding = if self.embedder.dimensions() > 0 {
+            match self.embedder.embed_one(query).await {
+                Ok(embedding) => Some(embedding),
+                Err(e) => {
+                    tracing::warn!("Knowledge base: query embedding failed, using keywords: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let db_path = self.db_path();
+        let query = query.to_string();
+        let (vector_weight, keyword_weight) = (self.vector_weight, self.keyword_weight);
+        tokio::task::spawn_blocking(move || {
+            with_connection(&db_path, |conn| {
+                let keyword_results = keyword_search(conn, &query, limit * 2)?;
+                let vector_results = match query_embedding {
+                    Some(ref embedding) => vector_search(conn, embedding, limit * 2)?,
+                    None => Vec::new(),
+                };
+                let merged = if vector_results.is_empty() {
+                    keyword_results
+                        .into_iter()
+                        .take(limit)
+                        .map(|(id, score)| vector::ScoredResult {
+                            id,
+                            vector_score: None,
+                            keyword_score: Some(score),
+                            final_score: score,
+                        })
+                        .collect()
+                } else {
+                    vector::hybrid_merge(
+                        &vector_results,
+                        &keyword_results,
+                        vector_weight,
+                        keyword_weight,
+                        limit,
+                    )
+                };
+
+                let mut stmt =
+                    conn.prepare("SELECT source, heading, content FROM kb_chunks WHERE id = ?1")?;
+                let mut passages = Vec::with_capacity(merged.len());
+                for scored in merged {
+                    let passage = stmt
+                        .query_row(params![scored.id], |row| {
+                            Ok(Passage {
+                                source: row.get(0)?,
+                                heading: row.get(1)?,
+                                content: row.get(2)?,
+                                score: scored.final_score,
+                            })
+                        })
+                        .optional()?;
+                    passages.extend(passage);
+                }
+                Ok(passages)
+            })
+        })
+        .await?
+    }
+}
+
+/// Text of a knowledge file. PDF, DOCX and XLSX go through the
+/// `extract_document` converters; everything else is read as UTF-8.
+async fn document_text(path: &Path) -> Result<String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("pdf" | "docx" | "xlsx") => crate::tools::extract_document::extract_text(path).await,
+        _ => {
+            let bytes = tokio::fs::read(path).await?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        }
+    }
+}
+
+/// Files with one of `extensions` under `directories`, keyed by index key.
+/// Hidden files and directories are skipped.
+fn scan_directories(
+    directories: &[PathBuf],
+    extensions: &[String],
+    workspace_dir: &Path,
+) -> HashMap<String, SourceFile> {
+    let mut files = HashMap::new();
+    let mut pending: Vec<(PathBuf, usize)> = directories.iter().map(|d| (d.clone(), 0)).collect();
+    while let Some((dir, depth)) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            tracing::debug!("Knowledge base: cannot read {}", dir.display());
+            continue;
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(meta) = std::fs::metadata(&path) else {
+                continue;
+            };
+            if meta.is_dir() {
+                if depth < MAX_SCAN_DEPTH {
+                    pending.push((path, depth + 1));
+                }
+                continue;
+            }
+            let matches_extension = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)));
+            if !meta.is_file() || !matches_extension || meta.len() > MAX_FILE_BYTES {
+                continue;
+            }
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .and_then(|elapsed| i64::try_from(elapsed.as_millis()).ok())
+                .unwrap_or(0);
+            let key = path
+                .strip_prefix(workspace_dir)
+                .unwrap_or(&path)
+                .display()
+                .to_string();
+            files.insert(
+                key.clone(),
+                SourceFile {
+                    key,
+                    path,
+                    modified,
+                    size: i64::try_from(meta.len()).unwrap_or(i64::MAX),
+                },
+            );
+        }
+    }
+    files
+}
+
+/// Run the knowledge directory rescan loop.
+pub async fn run(config: Config) -> Result<()> {
+    let knowledge = KnowledgeBase::from_config(&config);
+    let interval_secs = config.knowledge.rescan_interval_secs.max(MIN_RESCAN_SECS);
+    let mut interval = time::interval(Duration::from_secs(interval_secs));
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        match knowledge.sync().await {
+            Ok(stats) => {
+                if stats.indexed > 0 || stats.removed > 0 {
+                    tracing::info!(
+                        "Knowledge base: indexed {} file(s), removed {}",
+                        stats.indexed,
+                        stats.removed
+                    );
+                }
+                crate::health::mark_component_ok(KNOWLEDGE_COMPONENT);
+            }
+            Err(e) => {
+                crate::health::mark_component_error(KNOWLEDGE_COMPONENT, e.to_string());
+                tracing::warn!("Knowledge base sync failed: {e}");
+            }
+        }
+    }
+}
+
+// ── Storage ─────────────────────────────────────────────────────
+
+/// Indexed files with their `(modified, size)` at indexing time.
+fn indexed_files(conn: &Connection) -> Result<HashMap<String, (i64, i64)>> {
+    let mut stmt = conn.prepare("SELECT source, modified, size FROM kb_files")?;
+    let files = stmt
+        .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?
+        .collect::<rusqlite::Result<_>>()
+        .context("Failed to read knowledge index")?;
+    Ok(files)
+}
+
+/// Clear the index when it was built with a different embedding model, whose
+/// vectors are not comparable with new query embeddings.
+fn reset_if_model_changed(conn: &Connection, fingerprint: &str) -> Result<()> {
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT value FROM kb_meta WHERE key = 'embedding_model'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if stored.as_deref() == Some(fingerprint) {
+        return Ok(());
+    }
+    if stored.is_some() {
+        tracing::info!("Knowledge base: embedding model changed, re-indexing all files");
+    }
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM kb_chunks", [])?;
+    tx.execute("DELETE FROM kb_files", [])?;
+    tx.execute(
+        "INSERT OR REPLACE INTO kb_meta (key, value) VALUES ('embedding_model', ?1)",
+        params![fingerprint],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// FTS5 BM25 keyword search, best first.
+fn keyword_search(conn: &Connection, query: &str, limit: usize) -> Result<Vec<(String, f32)>> {
+    let fts_query = query
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{word}\""))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    if fts_query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT c.id, bm25(kb_chunks_fts) AS score
+         FROM kb_chunks_fts f
+         JOIN kb_chunks c ON c.id = f.rowid
+         WHERE kb_chunks_fts MATCH ?1
+         ORDER BY score
+         LIMIT ?2",
+    )?;
+    let results = stmt
+        .query_map(params![fts_query, limit], |row| {
+            let id: i64 = row.get(0)?;
+            let score: f64 = row.get(1)?;
+            // BM25 scores are negative (lower = better); negate for ranking.
+            #[allow(clippy::cast_possible_truncation)]
+            Ok((id.to_string(), (-score) as f32))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(results)
+}
+
+/// Cosine similarity over every stored chunk embedding, best first.
+fn vector_search(conn: &Connection, query: &[f32], limit: usize) -> Result<Vec<(String, f32)>> {
+    let mut stmt =
+        conn.prepare("SELECT id, embedding FROM kb_chunks WHERE embedding IS NOT NULL")?;
+    let mut scored = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let blob: Vec<u8> = row.get(1)?;
+            Ok((id, blob))
+        })?
+        .filter_map(|row| {
+            let (id, blob) = row.ok()?;
+            let similarity = vector::cosine_similarity(query, &vector::bytes_to_vec(&blob));
+            (similarity > 0.0).then(|| (id.to_string(), similarity))
+        })
+        .collect::<Vec<_>>();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(limit);
+    Ok(scored)
+}
+
+fn with_connection<T>(db_path: &Path, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create state directory: {}", parent.display()))?;
+    }
+
+    let conn = Connection::open(db_path)
+        .with_context(|| format!("Failed to open knowledge DB: {}", db_path.display()))?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS kb_meta (
+            key   TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS kb_files (
+            source     TEXT PRIMARY KEY,
+            modified   INTEGER NOT NULL,
+            size       INTEGER NOT NULL,
+            chunks     INTEGER NOT NULL,
+            indexed_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS kb_chunks (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            source      TEXT NOT NULL,
+            chunk_index INTEGER NOT NULL,
+            heading     TEXT,
+            content     TEXT NOT NULL,
+            embedding   BLOB
+        );
+        CREATE INDEX IF NOT EXISTS idx_kb_chunks_source ON kb_chunks(source);
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS kb_chunks_fts USING fts5(
+            heading, content, content=kb_chunks, content_rowid=id
+        );
+        CREATE TRIGGER IF NOT EXISTS kb_chunks_ai AFTER INSERT ON kb_chunks BEGIN
+            INSERT INTO kb_chunks_fts(rowid, heading, content)
+            VALUES (new.id, new.heading, new.content);
+        END;
+        CREATE TRIGGER IF NOT EXISTS kb_chunks_ad AFTER DELETE ON kb_chunks BEGIN
+            INSERT INTO kb_chunks_fts(kb_chunks_fts, rowid, heading, content)
+            VALUES ('delete', old.id, old.heading, old.content);
+        END;",
+    )
+    .context("Failed to initialize knowledge schema")?;
+
+    f(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use tempfile::TempDir;
+
+    /// Embeds each text as counts of a few marker words.
+    struct KeywordEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedding {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        fn dimensions(&self) -> usize {
+            3
+        }
+
+        async fn embed(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    ["refund", "shipping", "warranty"]
+                        .iter()
+                        .map(|word| text.matches(word).count() as f32 + 0.01)
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    fn knowledge_base(tmp: &TempDir, embedder: Arc<dyn EmbeddingProvider>) -> KnowledgeBase {
+        KnowledgeBase::new(
+            KnowledgeConfig {
+                enabled: true,
+                ..KnowledgeConfig::default()
+            },
+            tmp.path().to_path_buf(),
+            embedder.clone(),
+            format!("{}:{}", embedder.name(), embedder.dimensions()),
+        )
+    }
+
+    fn write(tmp: &TempDir, name: &str, contents: &str) {
+        let path = tmp.path().join("knowledge").join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[tokio::test]
+    async fn sync_indexes_changes_and_removals() {
+        let tmp = TempDir::new().unwrap();
+        write(
+            &tmp,
+            "policies/refunds.md",
+            "# Refunds\n\nRefunds take 14 days.",
+        );
+        write(&tmp, "shipping.txt", "Shipping is free over 50 EUR.");
+        write(&tmp, ".drafts/secret.md", "hidden");
+        write(&tmp, "image.png", "not text");
+        let kb = knowledge_base(&tmp, Arc::new(KeywordEmbedding));
+
+        let stats = kb.sync().await.unwrap();
+        assert_eq!(
+            stats,
+            SyncStats {
+                indexed: 2,
+                removed: 0,
+                failed: 0
+            }
+        );
+        assert_eq!(kb.sync().await.unwrap(), SyncStats::default());
+
+        std::fs::remove_file(tmp.path().join("knowledge/shipping.txt")).unwrap();
+        write(
+            &tmp,
+            "policies/refunds.md",
+            "# Refunds\n\nRefunds take 30 days now.",
+        );
+        let stats = kb.sync().await.unwrap();
+        assert_eq!((stats.indexed, stats.removed), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn search_ranks_by_embedding_and_keywords() {
+        let tmp = TempDir::new().unwrap();
+        write(
+            &tmp,
+            "refunds.md",
+            "# Refunds\n\nA refund is issued within 14 days.",
+        );
+        write(
+            &tmp,
+            "shipping.md",
+            "# Shipping\n\nShipping takes three days.",
+        );
+        let kb = knowledge_base(&tmp, Arc::new(KeywordEmbedding));
+        kb.sync().await.unwrap();
+
+        let passages = kb.search("how long does shipping take", 1).await.unwrap();
+        assert_eq!(passages.len(), 1);
+        assert_eq!(passages[0].source, "knowledge/shipping.md");
+        assert_eq!(passages[0].heading.as_deref(), Some("Shipping"));
+    }
+
+    #[tokio::test]
+    async fn keyword_search_without_embeddings_and_model_change_reindexes() {
+        let tmp = TempDir::new().unwrap();
+        write(&tmp, "warranty.md", "The warranty covers two years.");
+        write(&tmp, "other.md", "Unrelated notes.");
+        let kb = knowledge_base(&tmp, Arc::new(crate::memory::embeddings::NoopEmbedding));
+        kb.sync().await.unwrap();
+
+        let passages = kb.search("warranty", 5).await.unwrap();
+        assert_eq!(passages.len(), 1);
+        assert!(passages[0].content.contains("two years"));
+
+        let kb = knowledge_base(&tmp, Arc::new(KeywordEmbedding));
+        assert_eq!(kb.sync().await.unwrap().indexed, 2);
+    }
+}
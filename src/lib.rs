@@ -47,6 +47,7 @@ pub(crate) mod cost;
 pub(crate) mod cron;
 pub(crate) mod daemon;
 pub(crate) mod doctor;
+pub(crate) mod feeds;
 pub mod gateway;
 pub(crate) mod hardware;
 pub(crate) mod health;
@@ -54,6 +55,7 @@ pub(crate) mod heartbeat;
 pub mod hooks;
 pub(crate) mod identity;
 pub(crate) mod integrations;
+pub(crate) mod knowledge;
 pub mod memory;
 pub(crate) mod migration;
 pub(crate) mod multimodal;
@@ -65,6 +67,7 @@ pub mod rag;
 pub mod runtime;
 pub(crate) mod security;
 pub(crate) mod service;
+pub(crate) mod shutdown;
 pub(crate) mod skills;
 pub mod tools;
 pub(crate) mod tunnel;
@@ -136,6 +139,25 @@ Examples:
         /// Telegram identity to allow (username without '@' or numeric user ID)
         identity: String,
     },
+    /// Show the delivery status of recent agent replies
+    #[command(long_about = "\
+Show the delivery status of recent agent replies.
+
+Each reply is recorded as accepted (the platform took it), retrying \
+(the outbound queue is retrying a failed send) or failed (the send \
+was given up and dead-lettered). Records are kept for 30 days.
+
+Examples:
+  zeroclaw channel deliveries
+  zeroclaw channel deliveries --status failed --limit 50")]
+    Deliveries {
+        /// Only show replies with this status (accepted, retrying, failed)
+        #[arg(long)]
+        status: Option<String>,
+        /// Maximum number of replies to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
 }
 
 /// Skills management subcommands
@@ -327,6 +349,21 @@ pub enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Take a named snapshot of local long-term memory
+    Snapshot {
+        /// Snapshot name (letters, digits, '-', '_', '.')
+        name: String,
+    },
+    /// List named memory snapshots
+    Snapshots,
+    /// Roll back local long-term memory to a named snapshot
+    Rollback {
+        /// Snapshot name to restore
+        name: String,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
 }
 
 /// Integration subcommands
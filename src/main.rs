@@ -60,6 +60,7 @@ mod cost;
 mod cron;
 mod daemon;
 mod doctor;
+mod feeds;
 mod gateway;
 mod hardware;
 mod health;
@@ -67,6 +68,7 @@ mod heartbeat;
 mod hooks;
 mod identity;
 mod integrations;
+mod knowledge;
 mod memory;
 mod migration;
 mod multimodal;
@@ -77,6 +79,7 @@ mod providers;
 mod runtime;
 mod security;
 mod service;
+mod shutdown;
 mod skillforge;
 mod skills;
 mod tools;
@@ -117,6 +120,14 @@ enum EstopLevelArg {
     ToolFreeze,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, including the enclosing spans' fields
+    Json,
+}
+
 /// `ZeroClaw` - Zero overhead. Zero compromise. 100% Rust.
 #[derive(Parser, Debug)]
 #[command(name = "zeroclaw")]
@@ -127,6 +138,10 @@ struct Cli {
     #[arg(long, global = true)]
     config_dir: Option<String>,
 
+    /// Log output format (default: `text`, or `ZEROCLAW_LOG_FORMAT`)
+    #[arg(long, global = true, value_enum)]
+    log_format: Option<LogFormat>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -418,7 +433,9 @@ Examples:
   zeroclaw memory list
   zeroclaw memory list --category core --limit 10
   zeroclaw memory get <key>
-  zeroclaw memory clear --category conversation --yes")]
+  zeroclaw memory clear --category conversation --yes
+  zeroclaw memory snapshot before-import
+  zeroclaw memory rollback before-import")]
     Memory {
         #[command(subcommand)]
         memory_command: MemoryCommands,
@@ -655,6 +672,17 @@ enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Take a named snapshot of local long-term memory
+    Snapshot { name: String },
+    /// List named memory snapshots
+    Snapshots,
+    /// Roll back local long-term memory to a named snapshot
+    Rollback {
+        name: String,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
 }
 
 #[tokio::main]
@@ -685,13 +713,28 @@ async fn main() -> Result<()> {
     }
 
     // Initialize logging - respects RUST_LOG env var, defaults to INFO
-    let subscriber = fmt::Subscriber::builder()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .finish();
-
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    let log_format = cli
+        .log_format
+        .unwrap_or_else(|| match std::env::var("ZEROCLAW_LOG_FORMAT") {
+            Ok(value) if value.trim().eq_ignore_ascii_case("json") => LogFormat::Json,
+            _ => LogFormat::Text,
+        });
+    let builder = fmt::Subscriber::builder().with_env_filter(
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    );
+    match log_format {
+        LogFormat::Text => tracing::subscriber::set_global_default(builder.finish()),
+        // Span fields (request_id, channel, ...) correlate the lines of one request.
+        LogFormat::Json => tracing::subscriber::set_global_default(
+            builder
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(true)
+                .finish(),
+        ),
+    }
+    .expect("setting default subscriber failed");
 
     // Onboard runs quick setup by default, or the interactive wizard with --interactive.
     // The onboard wizard uses reqwest::blocking internally, which creates its own
@@ -727,9 +770,9 @@ async fn main() -> Result<()> {
             bail!("--channels-only does not accept --force");
         }
         let config = if channels_only {
-            onboard::run_channels_repair_wizard().await
+            Box::pin(onboard::run_channels_repair_wizard()).await
         } else if interactive {
-            onboard::run_wizard(force).await
+            Box::pin(onboard::run_wizard(force)).await
         } else {
             onboard::run_quick_setup(
                 api_key.as_deref(),
@@ -742,7 +785,7 @@ async fn main() -> Result<()> {
         }?;
         // Auto-start channels if user said yes during wizard
         if std::env::var("ZEROCLAW_AUTOSTART_CHANNELS").as_deref() == Ok("1") {
-            channels::start_channels(config).await?;
+            Box::pin(channels::start_channels(config)).await?;
         }
         return Ok(());
     }
@@ -775,7 +818,7 @@ async fn main() -> Result<()> {
             model,
             temperature,
             peripheral,
-        } => agent::run(
+        } => Box::pin(agent::run(
             config,
             message,
             provider,
@@ -783,7 +826,7 @@ async fn main() -> Result<()> {
             temperature,
             peripheral,
             true,
-        )
+        ))
         .await
         .map(|_| ()),
 
@@ -795,7 +838,7 @@ async fn main() -> Result<()> {
             } else {
                 info!("🚀 Starting ZeroClaw Gateway on {host}:{port}");
             }
-            gateway::run_gateway(&host, port, config).await
+            Box::pin(gateway::run_gateway(&host, port, config)).await
         }
 
         Commands::Daemon { port, host } => {
@@ -806,7 +849,7 @@ async fn main() -> Result<()> {
             } else {
                 info!("🧠 Starting ZeroClaw Daemon on {host}:{port}");
             }
-            daemon::run(config, host, port).await
+            Box::pin(daemon::run(config, host, port)).await
         }
 
         Commands::Status => {
@@ -996,8 +1039,8 @@ async fn main() -> Result<()> {
         },
 
         Commands::Channel { channel_command } => match channel_command {
-            ChannelCommands::Start => channels::start_channels(config).await,
-            ChannelCommands::Doctor => channels::doctor_channels(config).await,
+            ChannelCommands::Start => Box::pin(channels::start_channels(config)).await,
+            ChannelCommands::Doctor => Box::pin(channels::doctor_channels(config)).await,
             other => channels::handle_command(other, &config).await,
         },
 
@@ -0,0 +1,362 @@
+//! Named memory checkpoints — snapshot and roll back long-term memory.
+//!
+//! A checkpoint copies the local memory stores (`memory/brain.db` with its
+//! FTS and embedding indices, the knowledge base index `state/knowledge.db`,
+//! per-user facts in `state/user_memory.db`, `MEMORY.md`, and the daily
+//! `memory/*.md` files) into `memory/archive/snapshots/<name>/`, next to the files memory
+//! hygiene archives. Rolling back first checkpoints the current state as
+//! `pre-rollback-<timestamp>`, so a rollback can itself be undone.
+//!
+//! Remote backends (Postgres, Qdrant) are not covered; use their own backup
+//! tooling.
+
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const BRAIN_DB: &str = "memory/brain.db";
+/// SQLite stores captured with `VACUUM INTO`, workspace-relative.
+const SQLITE_STORES: &[&str] = &[BRAIN_DB, "state/knowledge.db", "state/user_memory.db"];
+const CORE_FILE: &str = "MEMORY.md";
+const MAX_NAME_LEN: usize = 64;
+
+/// Metadata stored with every checkpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CheckpointManifest {
+    pub name: String,
+    /// RFC 3339 creation time.
+    pub created_at: String,
+    /// Workspace-relative paths captured by the checkpoint.
+    pub files: Vec<String>,
+    /// Rows in `brain.db` at checkpoint time, when it was captured.
+    #[serde(default)]
+    pub entries: Option<usize>,
+}
+
+/// Result of a rollback.
+#[derive(Debug, Clone)]
+pub struct RollbackReport {
+    pub restored: CheckpointManifest,
+    /// Checkpoint of the state that was replaced, if there was any.
+    pub safety_checkpoint: Option<String>,
+}
+
+/// Directory holding all named checkpoints.
+pub fn checkpoints_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir
+        .join("memory")
+        .join("archive")
+        .join("snapshots")
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        bail!("Snapshot name must be 1-{MAX_NAME_LEN} characters");
+    }
+    if name.starts_with('.')
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        bail!("Snapshot name may only contain letters, digits, '-', '_' and '.' (got '{name}')");
+    }
+    Ok(())
+}
+
+/// Workspace-relative paths of the daily markdown memory files.
+fn daily_files(workspace_dir: &Path) -> Result<Vec<String>> {
+    let memory_dir = workspace_dir.join("memory");
+    if !memory_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&memory_dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            files.push(format!("memory/{name}"));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn count_entries(db_path: &Path) -> Option<usize> {
+    let conn = Connection::open(db_path).ok()?;
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+        .ok()?;
+    usize::try_from(count).ok()
+}
+
+/// Snapshot the local memory stores under `name`.
+pub fn create_checkpoint(workspace_dir: &Path, name: &str) -> Result<CheckpointManifest> {
+    validate_name(name)?;
+    let target = checkpoints_dir(workspace_dir).join(name);
+    if target.exists() {
+        bail!("Snapshot '{name}' already exists");
+    }
+    fs::create_dir_all(target.join("memory"))
+        .with_context(|| format!("Failed to create snapshot directory {}", target.display()))?;
+
+    let result = write_checkpoint(workspace_dir, name, &target);
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&target);
+    }
+    result
+}
+
+fn write_checkpoint(workspace_dir: &Path, name: &str, target: &Path) -> Result<CheckpointManifest> {
+    let mut files = Vec::new();
+    let mut entries = None;
+
+    for rel in SQLITE_STORES {
+        let db = workspace_dir.join(rel);
+        if !db.exists() {
+            continue;
+        }
+        // VACUUM INTO yields a consistent copy even while the WAL is active.
+        let dest = target.join(rel);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn =
+            Connection::open(&db).with_context(|| format!("Failed to open {}", db.display()))?;
+        conn.execute("VACUUM INTO ?1", [dest.to_string_lossy().as_ref()])
+            .with_context(|| format!("Failed to copy {rel}"))?;
+        if *rel == BRAIN_DB {
+            entries = count_entries(&dest);
+        }
+        files.push((*rel).to_string());
+    }
+
+    let mut plain = daily_files(workspace_dir)?;
+    if workspace_dir.join(CORE_FILE).is_file() {
+        plain.insert(0, CORE_FILE.to_string());
+    }
+    for rel in plain {
+        fs::copy(workspace_dir.join(&rel), target.join(&rel))
+            .with_context(|| format!("Failed to copy {rel}"))?;
+        files.push(rel);
+    }
+
+    if files.is_empty() {
+        bail!("No local memory files to snapshot");
+    }
+
+    let manifest = CheckpointManifest {
+        name: name.to_string(),
+        created_at: Local::now().to_rfc3339(),
+        files,
+        entries,
+    };
+    fs::write(
+        target.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+fn read_manifest(dir: &Path) -> Result<CheckpointManifest> {
+    let raw = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .with_context(|| format!("Missing snapshot manifest in {}", dir.display()))?;
+    serde_json::from_str(&raw).context("Invalid snapshot manifest")
+}
+
+/// All checkpoints, newest first.
+pub fn list_checkpoints(workspace_dir: &Path) -> Result<Vec<CheckpointManifest>> {
+    let dir = checkpoints_dir(workspace_dir);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut manifests = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        match read_manifest(&path) {
+            Ok(manifest) => manifests.push(manifest),
+            Err(e) => tracing::warn!("Skipping snapshot {}: {e}", path.display()),
+        }
+    }
+    manifests.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(manifests)
+}
+
+/// Replace the local memory stores with checkpoint `name`.
+pub fn rollback_to(workspace_dir: &Path, name: &str) -> Result<RollbackReport> {
+    validate_name(name)?;
+    let source = checkpoints_dir(workspace_dir).join(name);
+    let manifest = read_manifest(&source)
+        .with_context(|| format!("Snapshot '{name}' not found or unreadable"))?;
+    for rel in &manifest.files {
+        if !source.join(rel).is_file() {
+            bail!("Snapshot '{name}' is incomplete: missing {rel}");
+        }
+    }
+
+    let safety_name = format!("pre-rollback-{}", Local::now().format("%Y%m%d-%H%M%S-%3f"));
+    let safety_checkpoint = match create_checkpoint(workspace_dir, &safety_name) {
+        Ok(_) => Some(safety_name),
+        Err(e) if e.to_string().contains("No local memory files") => None,
+        Err(e) => return Err(e.context("Failed to checkpoint current memory before rollback")),
+    };
+
+    // Clear the current stores so files absent from the snapshot do not linger.
+    for rel in SQLITE_STORES {
+        let db = workspace_dir.join(rel);
+        for suffix in ["", "-wal", "-shm"] {
+            let path = PathBuf::from(format!("{}{suffix}", db.display()));
+            if path.exists() {
+                fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+        }
+    }
+    let core = workspace_dir.join(CORE_FILE);
+    if core.exists() {
+        fs::remove_file(&core)?;
+    }
+    for rel in daily_files(workspace_dir)? {
+        fs::remove_file(workspace_dir.join(rel))?;
+    }
+
+    for rel in &manifest.files {
+        if let Some(parent) = workspace_dir.join(rel).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(source.join(rel), workspace_dir.join(rel))
+            .with_context(|| format!("Failed to restore {rel}"))?;
+    }
+
+    Ok(RollbackReport {
+        restored: manifest,
+        safety_checkpoint,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+    use tempfile::TempDir;
+
+    #[test]
+    fn rejects_unsafe_names() {
+        assert!(validate_name("before-import_2.0").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../escape").is_err());
+        assert!(validate_name(".hidden").is_err());
+        assert!(validate_name(&"x".repeat(65)).is_err());
+    }
+
+    #[tokio::test]
+    async fn rollback_restores_snapshot_state() {
+        let tmp = TempDir::new().unwrap();
+        let ws = tmp.path();
+        {
+            let mem = SqliteMemory::new(ws).unwrap();
+            mem.store("lang", "User prefers Rust", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        fs::write(ws.join("memory/2025-01-01.md"), "daily note").unwrap();
+
+        let manifest = create_checkpoint(ws, "clean").unwrap();
+        assert_eq!(manifest.entries, Some(1));
+        assert!(manifest.files.contains(&"memory/brain.db".to_string()));
+        assert!(manifest.files.contains(&"memory/2025-01-01.md".to_string()));
+
+        {
+            let mem = SqliteMemory::new(ws).unwrap();
+            mem.store("junk", "runaway save", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        fs::write(ws.join("memory/2025-01-02.md"), "polluted").unwrap();
+
+        let report = rollback_to(ws, "clean").unwrap();
+        assert!(report.safety_checkpoint.is_some());
+        assert!(!ws.join("memory/2025-01-02.md").exists());
+        assert_eq!(
+            fs::read_to_string(ws.join("memory/2025-01-01.md")).unwrap(),
+            "daily note"
+        );
+
+        let mem = SqliteMemory::new(ws).unwrap();
+        assert!(mem.get("lang").await.unwrap().is_some());
+        assert!(mem.get("junk").await.unwrap().is_none());
+
+        let names: Vec<_> = list_checkpoints(ws)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert!(names.contains(&"clean".to_string()));
+        assert!(names.contains(report.safety_checkpoint.as_ref().unwrap()));
+    }
+
+    #[test]
+    fn state_databases_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let ws = tmp.path();
+        fs::create_dir_all(ws.join("state")).unwrap();
+        let stores = ["state/knowledge.db", "state/user_memory.db"];
+        let insert = |text: &str| {
+            for rel in stores {
+                let conn = Connection::open(ws.join(rel)).unwrap();
+                conn.execute("CREATE TABLE IF NOT EXISTS rows (text TEXT)", [])
+                    .unwrap();
+                conn.execute("INSERT INTO rows (text) VALUES (?1)", [text])
+                    .unwrap();
+            }
+        };
+        let texts = |rel: &str| -> Vec<String> {
+            let conn = Connection::open(ws.join(rel)).unwrap();
+            let mut stmt = conn
+                .prepare("SELECT text FROM rows ORDER BY rowid")
+                .unwrap();
+            stmt.query_map([], |row| row.get(0))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect()
+        };
+
+        insert("kept");
+        let manifest = create_checkpoint(ws, "indexed").unwrap();
+        assert_eq!(manifest.files, stores);
+        assert_eq!(manifest.entries, None);
+
+        insert("stale");
+        rollback_to(ws, "indexed").unwrap();
+        for rel in stores {
+            assert_eq!(texts(rel), vec!["kept"]);
+        }
+    }
+
+    #[test]
+    fn duplicate_and_empty_snapshots_fail() {
+        let tmp = TempDir::new().unwrap();
+        assert!(create_checkpoint(tmp.path(), "empty").is_err());
+        assert!(!checkpoints_dir(tmp.path()).join("empty").exists());
+
+        fs::write(tmp.path().join(CORE_FILE), "# Core").unwrap();
+        create_checkpoint(tmp.path(), "first").unwrap();
+        assert!(create_checkpoint(tmp.path(), "first").is_err());
+    }
+
+    #[test]
+    fn rollback_to_unknown_snapshot_fails() {
+        let tmp = TempDir::new().unwrap();
+        assert!(rollback_to(tmp.path(), "missing").is_err());
+    }
+}
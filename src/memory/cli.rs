@@ -1,7 +1,7 @@
 use super::traits::{Memory, MemoryCategory};
 use super::{
-    classify_memory_backend, create_memory_for_migration, effective_memory_backend_name,
-    MemoryBackendKind,
+    checkpoint, classify_memory_backend, create_memory_for_migration,
+    effective_memory_backend_name, MemoryBackendKind,
 };
 use crate::config::Config;
 #[cfg(feature = "memory-postgres")]
@@ -23,6 +23,9 @@ pub async fn handle_command(command: crate::MemoryCommands, config: &Config) ->
         crate::MemoryCommands::Clear { key, category, yes } => {
             handle_clear(config, key, category, yes).await
         }
+        crate::MemoryCommands::Snapshot { name } => handle_snapshot(config, &name),
+        crate::MemoryCommands::Snapshots => handle_snapshots(config),
+        crate::MemoryCommands::Rollback { name, yes } => handle_rollback(config, &name, yes),
     }
 }
 
@@ -297,6 +300,96 @@ async fn handle_clear_key(mem: &dyn Memory, key: &str, yes: bool) -> Result<()>
     Ok(())
 }
 
+/// Snapshots cover the local stores only; remote backends need their own backups.
+fn ensure_local_backend(config: &Config) -> Result<()> {
+    let backend = effective_memory_backend_name(
+        &config.memory.backend,
+        Some(&config.storage.provider.config),
+    );
+    match classify_memory_backend(&backend) {
+        MemoryBackendKind::Postgres | MemoryBackendKind::Qdrant => bail!(
+            "Memory snapshots cover local stores only; back up the '{backend}' backend with its own tooling."
+        ),
+        MemoryBackendKind::None => {
+            bail!("Memory backend is 'none' (disabled). Nothing to snapshot.")
+        }
+        _ => Ok(()),
+    }
+}
+
+fn handle_snapshot(config: &Config, name: &str) -> Result<()> {
+    ensure_local_backend(config)?;
+    let manifest = checkpoint::create_checkpoint(&config.workspace_dir, name)?;
+    println!(
+        "{} Snapshot '{}' saved ({} files{}).",
+        style("✓").green().bold(),
+        manifest.name,
+        manifest.files.len(),
+        manifest
+            .entries
+            .map(|n| format!(", {n} entries"))
+            .unwrap_or_default(),
+    );
+    println!(
+        "  Location: {}",
+        checkpoint::checkpoints_dir(&config.workspace_dir)
+            .join(&manifest.name)
+            .display()
+    );
+    Ok(())
+}
+
+fn handle_snapshots(config: &Config) -> Result<()> {
+    let snapshots = checkpoint::list_checkpoints(&config.workspace_dir)?;
+    if snapshots.is_empty() {
+        println!("No memory snapshots. Create one with `zeroclaw memory snapshot <name>`.");
+        return Ok(());
+    }
+
+    println!("Memory snapshots ({}):\n", snapshots.len());
+    for manifest in &snapshots {
+        let entries = manifest
+            .entries
+            .map_or_else(String::new, |n| format!(", {n} entries"));
+        println!(
+            "- {}  {} ({} files{entries})",
+            style(&manifest.name).white().bold(),
+            manifest.created_at,
+            manifest.files.len(),
+        );
+    }
+    Ok(())
+}
+
+fn handle_rollback(config: &Config, name: &str, yes: bool) -> Result<()> {
+    ensure_local_backend(config)?;
+
+    if !yes {
+        println!("Rolling back replaces the current local memory with snapshot '{name}'.");
+        println!("Stop any running agent or daemon first so it does not write during the restore.");
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!("  Roll back memory to '{name}'?"))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    let report = checkpoint::rollback_to(&config.workspace_dir, name)?;
+    println!(
+        "{} Memory rolled back to '{}' (taken {}).",
+        style("✓").green().bold(),
+        report.restored.name,
+        report.restored.created_at,
+    );
+    if let Some(safety) = report.safety_checkpoint {
+        println!("  Previous state saved as snapshot '{safety}'.");
+    }
+    Ok(())
+}
+
 fn parse_category(s: &str) -> MemoryCategory {
     match s.trim().to_ascii_lowercase().as_str() {
         "core" => MemoryCategory::Core,
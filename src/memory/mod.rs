@@ -1,4 +1,5 @@
 pub mod backend;
+pub mod checkpoint;
 pub mod chunker;
 pub mod cli;
 pub mod embeddings;
@@ -169,6 +170,29 @@ fn resolve_embedding_config(
     }
 }
 
+/// Embedding provider from the `[memory]` settings (resolving a `hint:`
+/// model through `[[embedding_routes]]`), for subsystems that keep their own
+/// vector index. Also returns a `provider:model:dimensions` fingerprint so
+/// the index can tell when its stored vectors were made by another model.
+pub fn create_embedder(
+    config: &MemoryConfig,
+    embedding_routes: &[EmbeddingRouteConfig],
+    api_key: Option<&str>,
+) -> (Arc<dyn embeddings::EmbeddingProvider>, String) {
+    let resolved = resolve_embedding_config(config, embedding_routes, api_key);
+    let embedder = Arc::from(embeddings::create_embedding_provider(
+        &resolved.provider,
+        resolved.api_key.as_deref(),
+        &resolved.model,
+        resolved.dimensions,
+    ));
+    let fingerprint = format!(
+        "{}:{}:{}",
+        resolved.provider, resolved.model, resolved.dimensions
+    );
+    (embedder, fingerprint)
+}
+
 /// Factory: create the right memory backend from config
 pub fn create_memory(
     config: &MemoryConfig,
@@ -172,6 +172,18 @@ pub async fn prepare_messages_for_provider(
     })
 }
 
+/// Load one image reference (local path, data URI or allowed remote URL) as
+/// a data URI, applying the configured size limit.
+pub async fn load_image_data_uri(
+    source: &str,
+    config: &MultimodalConfig,
+) -> anyhow::Result<String> {
+    let (_, max_image_size_mb) = config.effective_limits();
+    let max_bytes = max_image_size_mb.saturating_mul(1024 * 1024);
+    let remote_client = build_runtime_proxy_client_with_timeouts("provider.ollama", 30, 10);
+    normalize_image_reference(source, config, max_bytes, &remote_client).await
+}
+
 fn compose_multimodal_message(text: &str, data_uris: &[String]) -> String {
     let mut content = String::new();
     let trimmed = text.trim();
@@ -509,6 +521,7 @@ mod tests {
             max_images: 1,
             max_image_size_mb: 5,
             allow_remote_fetch: false,
+            ..Default::default()
         };
 
         let error = prepare_messages_for_provider(&messages, &config)
@@ -551,6 +564,7 @@ mod tests {
             max_images: 4,
             max_image_size_mb: 1,
             allow_remote_fetch: false,
+            ..Default::default()
         };
 
         let error = prepare_messages_for_provider(&messages, &config)
@@ -22,6 +22,14 @@ pub use verbose::VerboseObserver;
 
 use crate::config::ObservabilityConfig;
 
+/// Random ID for the `request_id` span field, which ties together the log
+/// lines of one request.
+pub fn new_request_id() -> String {
+    let mut id = uuid::Uuid::new_v4().simple().to_string();
+    id.truncate(12);
+    id
+}
+
 /// Factory: create the right observer from config
 pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
     match config.backend.as_str() {
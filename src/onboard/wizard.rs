@@ -148,12 +148,15 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         embedding_routes: Vec::new(),
         heartbeat: HeartbeatConfig::default(),
         cron: crate::config::CronConfig::default(),
+        digest: crate::config::DigestConfig::default(),
+        transcripts: crate::config::TranscriptsConfig::default(),
         channels_config,
         memory: memory_config, // User-selected memory backend
         storage: StorageConfig::default(),
         tunnel: tunnel_config,
         gateway: crate::config::GatewayConfig::default(),
         composio: composio_config,
+        mcp: crate::config::McpConfig::default(),
         secrets: secrets_config,
         browser: BrowserConfig::default(),
         http_request: crate::config::HttpRequestConfig::default(),
@@ -169,6 +172,18 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         hardware: hardware_config,
         query_classification: crate::config::QueryClassificationConfig::default(),
         transcription: crate::config::TranscriptionConfig::default(),
+        tts: crate::config::TtsConfig::default(),
+        image_generation: crate::config::ImageGenerationConfig::default(),
+        run_code: crate::config::RunCodeConfig::default(),
+        sql_query: crate::config::SqlQueryConfig::default(),
+        git: crate::config::GitConfig::default(),
+        calendar: crate::config::CalendarConfig::default(),
+        send_email: crate::config::SendEmailConfig::default(),
+        home_assistant: crate::config::HomeAssistantConfig::default(),
+        feeds: crate::config::FeedsConfig::default(),
+        desktop: crate::config::DesktopConfig::default(),
+        knowledge: crate::config::KnowledgeConfig::default(),
+        triggers: std::collections::HashMap::new(),
     };
 
     println!(
@@ -499,12 +514,15 @@ async fn run_quick_setup_with_home(
         embedding_routes: Vec::new(),
         heartbeat: HeartbeatConfig::default(),
         cron: crate::config::CronConfig::default(),
+        digest: crate::config::DigestConfig::default(),
+        transcripts: crate::config::TranscriptsConfig::default(),
         channels_config: ChannelsConfig::default(),
         memory: memory_config,
         storage: StorageConfig::default(),
         tunnel: crate::config::TunnelConfig::default(),
         gateway: crate::config::GatewayConfig::default(),
         composio: ComposioConfig::default(),
+        mcp: crate::config::McpConfig::default(),
         secrets: SecretsConfig::default(),
         browser: BrowserConfig::default(),
         http_request: crate::config::HttpRequestConfig::default(),
@@ -520,6 +538,18 @@ async fn run_quick_setup_with_home(
         hardware: crate::config::HardwareConfig::default(),
         query_classification: crate::config::QueryClassificationConfig::default(),
         transcription: crate::config::TranscriptionConfig::default(),
+        tts: crate::config::TtsConfig::default(),
+        image_generation: crate::config::ImageGenerationConfig::default(),
+        run_code: crate::config::RunCodeConfig::default(),
+        sql_query: crate::config::SqlQueryConfig::default(),
+        git: crate::config::GitConfig::default(),
+        calendar: crate::config::CalendarConfig::default(),
+        send_email: crate::config::SendEmailConfig::default(),
+        home_assistant: crate::config::HomeAssistantConfig::default(),
+        feeds: crate::config::FeedsConfig::default(),
+        desktop: crate::config::DesktopConfig::default(),
+        knowledge: crate::config::KnowledgeConfig::default(),
+        triggers: std::collections::HashMap::new(),
     };
 
     config.save().await?;
@@ -3618,6 +3648,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     draft_update_interval_ms: 1000,
                     interrupt_on_new_message: false,
                     mention_only: false,
+                    trigger_prefix: None,
                 });
             }
             ChannelMenuChoice::Discord => {
@@ -3717,6 +3748,12 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     allowed_users,
                     listen_to_bots: false,
                     mention_only: false,
+                    trigger_prefix: None,
+                    thread_replies: false,
+                    slash_commands: true,
+                    stream_mode: StreamMode::default(),
+                    draft_update_interval_ms: 1000,
+                    voice: false,
                 });
             }
             ChannelMenuChoice::Slack => {
@@ -3844,6 +3881,12 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         Some(channel)
                     },
                     allowed_users,
+                    mention_only: false,
+                    trigger_prefix: None,
+                    thread_replies: None,
+                    signing_secret: None,
+                    stream_mode: StreamMode::default(),
+                    draft_update_interval_ms: 1000,
                 });
             }
             ChannelMenuChoice::IMessage => {
@@ -4000,6 +4043,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     device_id: detected_device_id,
                     room_id,
                     allowed_users,
+                    stream_mode: StreamMode::default(),
+                    draft_update_interval_ms: 1000,
                 });
             }
             ChannelMenuChoice::Signal => {
@@ -4094,6 +4139,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     allowed_from,
                     ignore_attachments,
                     ignore_stories,
+                    mention_only: false,
+                    trigger_prefix: None,
                 });
 
                 println!("  {} Signal configured", style("✅").green().bold());
@@ -7154,6 +7201,8 @@ mod tests {
             allowed_from: vec!["*".into()],
             ignore_attachments: false,
             ignore_stories: true,
+            mention_only: false,
+            trigger_prefix: None,
         });
         assert!(has_launchable_channels(&channels));
 
@@ -7165,6 +7214,10 @@ mod tests {
             allowed_users: vec!["*".into()],
             thread_replies: Some(true),
             mention_only: Some(false),
+            trigger_prefix: None,
+            command_token: None,
+            stream_mode: StreamMode::default(),
+            draft_update_interval_ms: 1000,
         });
         assert!(has_launchable_channels(&channels));
 
@@ -134,9 +134,11 @@ impl KiroProvider {
             .env("NO_COLOR", "1")
             .env("TERM", "dumb");
 
+        let started = std::time::Instant::now();
         let mut child = cmd
             .spawn()
             .context("Failed to spawn kiro-cli")?;
+        tracing::debug!(pid = child.id(), prompt_chars = prompt.len(), "kiro-cli started");
 
         // Write prompt via stdin to avoid ARG_MAX limits
         if let Some(mut stdin) = child.stdin.take() {
@@ -148,6 +150,7 @@ impl KiroProvider {
 
         let output = child.wait_with_output().await
             .context("Failed to wait for kiro-cli")?;
+        tracing::debug!(status = %output.status, elapsed = ?started.elapsed(), "kiro-cli finished");
         
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
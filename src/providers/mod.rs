@@ -26,6 +26,7 @@ pub mod ollama;
 pub mod openai;
 pub mod openai_codex;
 pub mod openrouter;
+pub mod redacting;
 pub mod reliable;
 pub mod router;
 pub mod telnyx;
@@ -677,6 +678,9 @@ pub struct ProviderRuntimeOptions {
     pub zeroclaw_dir: Option<PathBuf>,
     pub secrets_encrypt: bool,
     pub reasoning_enabled: Option<bool>,
+    /// `[security.redaction]`; applied to non-local providers by
+    /// [`create_resilient_provider_with_options`].
+    pub redaction: crate::config::RedactionConfig,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -687,6 +691,7 @@ impl Default for ProviderRuntimeOptions {
             zeroclaw_dir: None,
             secrets_encrypt: true,
             reasoning_enabled: None,
+            redaction: crate::config::RedactionConfig::default(),
         }
     }
 }
@@ -1308,7 +1313,10 @@ pub fn create_resilient_provider_with_options(
         }
         _ => create_provider_with_url_and_options(primary_name, api_key, api_url, options)?,
     };
-    providers.push((primary_name.to_string(), primary_provider));
+    providers.push((
+        primary_name.to_string(),
+        with_redaction(primary_name, primary_provider, options),
+    ));
 
     for fallback in &reliability.fallback_providers {
         if fallback == primary_name || providers.iter().any(|(name, _)| name == fallback) {
@@ -1336,7 +1344,10 @@ pub fn create_resilient_provider_with_options(
         };
 
         match create_provider_with_options(provider_name, None, &fallback_options) {
-            Ok(provider) => providers.push((fallback.clone(), provider)),
+            Ok(provider) => providers.push((
+                fallback.clone(),
+                with_redaction(provider_name, provider, options),
+            )),
             Err(_error) => {
                 tracing::warn!(
                     fallback_provider = fallback,
@@ -1357,6 +1368,49 @@ pub fn create_resilient_provider_with_options(
     Ok(Box::new(reliable))
 }
 
+/// Wrap `provider` in PII redaction when `[security.redaction]` applies to it.
+fn with_redaction(
+    name: &str,
+    provider: Box<dyn Provider>,
+    options: &ProviderRuntimeOptions,
+) -> Box<dyn Provider> {
+    let Some(redactor) = crate::security::redaction::Redactor::from_config(&options.redaction)
+    else {
+        return provider;
+    };
+    if !options.redaction.local_providers && is_local_provider(name) {
+        return provider;
+    }
+    Box::new(redacting::RedactingProvider::new(provider, redactor))
+}
+
+/// Whether `name` runs on this machine: a local provider from
+/// [`list_providers`], or a custom endpoint on a loopback address.
+fn is_local_provider(name: &str) -> bool {
+    if let Some(url) = name
+        .strip_prefix("custom:")
+        .or_else(|| name.strip_prefix("anthropic-custom:"))
+    {
+        return reqwest::Url::parse(url).is_ok_and(|url| {
+            url.host_str().is_some_and(|host| {
+                host.eq_ignore_ascii_case("localhost")
+                    || host
+                        .trim_matches(['[', ']'])
+                        .parse::<std::net::IpAddr>()
+                        .is_ok_and(|ip| ip.is_loopback())
+            })
+        });
+    }
+    list_providers().iter().any(|provider| {
+        provider.local
+            && (provider.name.eq_ignore_ascii_case(name)
+                || provider
+                    .aliases
+                    .iter()
+                    .any(|alias| alias.eq_ignore_ascii_case(name)))
+    })
+}
+
 /// Create a RouterProvider if model routes are configured, otherwise return a
 /// standard resilient provider. The router wraps individual providers per route,
 /// each with its own retry/fallback chain.
@@ -2467,6 +2521,11 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            degraded_mode: false,
+            degraded_probe_interval_secs: 60,
+            channel_send_retries: 3,
+            channel_send_backoff_ms: 2000,
+            shutdown_drain_secs: 30,
         };
 
         let provider = create_resilient_provider(
@@ -2506,6 +2565,11 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            degraded_mode: false,
+            degraded_probe_interval_secs: 60,
+            channel_send_retries: 3,
+            channel_send_backoff_ms: 2000,
+            shutdown_drain_secs: 30,
         };
 
         // Primary uses a ZAI key; fallbacks (lmstudio, ollama) should NOT
@@ -2528,6 +2592,11 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            degraded_mode: false,
+            degraded_probe_interval_secs: 60,
+            channel_send_retries: 3,
+            channel_send_backoff_ms: 2000,
+            shutdown_drain_secs: 30,
         };
 
         let provider =
@@ -2554,6 +2623,11 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            degraded_mode: false,
+            degraded_probe_interval_secs: 60,
+            channel_send_retries: 3,
+            channel_send_backoff_ms: 2000,
+            shutdown_drain_secs: 30,
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -2586,6 +2660,11 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            degraded_mode: false,
+            degraded_probe_interval_secs: 60,
+            channel_send_retries: 3,
+            channel_send_backoff_ms: 2000,
+            shutdown_drain_secs: 30,
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -2879,6 +2958,11 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            degraded_mode: false,
+            degraded_probe_interval_secs: 60,
+            channel_send_retries: 3,
+            channel_send_backoff_ms: 2000,
+            shutdown_drain_secs: 30,
         };
 
         // openai-codex resolves its own OAuth credential; it should not
@@ -2908,9 +2992,24 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            degraded_mode: false,
+            degraded_probe_interval_secs: 60,
+            channel_send_retries: 3,
+            channel_send_backoff_ms: 2000,
+            shutdown_drain_secs: 30,
         };
 
         let provider = create_resilient_provider("ollama", None, None, &reliability);
         assert!(provider.is_ok());
     }
+
+    #[test]
+    fn redaction_skips_local_providers() {
+        assert!(is_local_provider("ollama"));
+        assert!(is_local_provider("LMStudio"));
+        assert!(is_local_provider("custom:http://localhost:8080/v1"));
+        assert!(is_local_provider("custom:http://[::1]:8080/v1"));
+        assert!(!is_local_provider("custom:https://api.example.com/v1"));
+        assert!(!is_local_provider("openrouter"));
+    }
 }
@@ -0,0 +1,242 @@
+//! Provider wrapper that masks PII before requests leave the machine.
+//!
+//! Every message is passed through a [`Redactor`]; placeholders in the
+//! response text and tool-call arguments are restored from the request's
+//! [`PlaceholderMap`], so history, tools and users only ever see real values.
+
+use super::traits::{ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities, ToolsPayload};
+use super::Provider;
+use crate::security::redaction::{PlaceholderMap, Redactor};
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+
+pub struct RedactingProvider {
+    inner: Box<dyn Provider>,
+    redactor: Redactor,
+}
+
+impl RedactingProvider {
+    pub fn new(inner: Box<dyn Provider>, redactor: Redactor) -> Self {
+        Self { inner, redactor }
+    }
+
+    fn redact_messages(&self, messages: &[ChatMessage]) -> (Vec<ChatMessage>, PlaceholderMap) {
+        let mut map = PlaceholderMap::default();
+        let redacted = messages
+            .iter()
+            .map(|message| ChatMessage {
+                role: message.role.clone(),
+                content: self.redactor.redact(&message.content, &mut map),
+            })
+            .collect();
+        log_redaction(&map);
+        (redacted, map)
+    }
+}
+
+fn log_redaction(map: &PlaceholderMap) {
+    if !map.is_empty() {
+        tracing::debug!(
+            values = map.len(),
+            "Masked PII before sending the provider request"
+        );
+    }
+}
+
+fn restore_response(map: &PlaceholderMap, mut response: ChatResponse) -> ChatResponse {
+    if map.is_empty() {
+        return response;
+    }
+    response.text = response.text.map(|text| map.restore(&text));
+    for call in &mut response.tool_calls {
+        call.arguments = map.restore(&call.arguments);
+    }
+    response
+}
+
+#[async_trait]
+impl Provider for RedactingProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut map = PlaceholderMap::default();
+        let system_prompt = system_prompt.map(|prompt| self.redactor.redact(prompt, &mut map));
+        let message = self.redactor.redact(message, &mut map);
+        log_redaction(&map);
+        let reply = self
+            .inner
+            .chat_with_system(system_prompt.as_deref(), &message, model, temperature)
+            .await?;
+        Ok(map.restore(&reply))
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let (messages, map) = self.redact_messages(messages);
+        let reply = self
+            .inner
+            .chat_with_history(&messages, model, temperature)
+            .await?;
+        Ok(map.restore(&reply))
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let (messages, map) = self.redact_messages(request.messages);
+        let response = self
+            .inner
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: request.tools,
+                },
+                model,
+                temperature,
+            )
+            .await?;
+        Ok(restore_response(&map, response))
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let (messages, map) = self.redact_messages(messages);
+        let response = self
+            .inner
+            .chat_with_tools(&messages, tools, model, temperature)
+            .await?;
+        Ok(restore_response(&map, response))
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    fn supports_raw_image_markers(&self) -> bool {
+        self.inner.supports_raw_image_markers()
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedactionConfig;
+    use crate::providers::traits::ToolCall;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    /// Records what it was sent and answers with a fixed response.
+    struct EchoProvider {
+        seen: Arc<Mutex<Vec<ChatMessage>>>,
+        reply: ChatResponse,
+    }
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(String::new())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.seen.lock().extend_from_slice(request.messages);
+            Ok(self.reply.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn masks_request_and_restores_reply_and_tool_calls() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let provider = RedactingProvider::new(
+            Box::new(EchoProvider {
+                seen: Arc::clone(&seen),
+                reply: ChatResponse {
+                    text: Some("I'll email [EMAIL_1] now.".into()),
+                    tool_calls: vec![ToolCall {
+                        id: "1".into(),
+                        name: "send_email".into(),
+                        arguments: r#"{"to":"[EMAIL_1]"}"#.into(),
+                    }],
+                    usage: None,
+                    reasoning_content: None,
+                },
+            }),
+            Redactor::from_config(&RedactionConfig {
+                enabled: true,
+                ..RedactionConfig::default()
+            })
+            .unwrap(),
+        );
+
+        let messages = [
+            ChatMessage::system("You help Alice."),
+            ChatMessage::user("Write to carol@example.com about the invoice"),
+        ];
+        let response = provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: None,
+                },
+                "model",
+                0.0,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            seen.lock()[1].content,
+            "Write to [EMAIL_1] about the invoice"
+        );
+        assert_eq!(
+            response.text.as_deref(),
+            Some("I'll email carol@example.com now.")
+        );
+        assert_eq!(
+            response.tool_calls[0].arguments,
+            r#"{"to":"carol@example.com"}"#
+        );
+    }
+}
@@ -36,6 +36,8 @@ pub mod otp;
 pub mod pairing;
 pub mod policy;
 pub mod prompt_guard;
+pub mod redaction;
+pub mod secret_ref;
 pub mod secrets;
 pub mod traits;
 
@@ -52,6 +54,8 @@ pub use otp::OtpValidator;
 pub use pairing::PairingGuard;
 pub use policy::{AutonomyLevel, SecurityPolicy};
 #[allow(unused_imports)]
+pub use secret_ref::SecretRef;
+#[allow(unused_imports)]
 pub use secrets::SecretStore;
 #[allow(unused_imports)]
 pub use traits::{NoopSandbox, Sandbox};
@@ -91,6 +91,9 @@ pub struct SecurityPolicy {
     pub require_approval_for_medium_risk: bool,
     pub block_high_risk_commands: bool,
     pub shell_env_passthrough: Vec<String>,
+    pub forbidden_commands: Vec<String>,
+    pub shell_timeout_secs: u64,
+    pub shell_max_output_bytes: usize,
     pub tracker: ActionTracker,
 }
 
@@ -143,6 +146,9 @@ impl Default for SecurityPolicy {
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
             shell_env_passthrough: vec![],
+            forbidden_commands: Vec::new(),
+            shell_timeout_secs: 60,
+            shell_max_output_bytes: 1_048_576,
             tracker: ActionTracker::new(),
         }
     }
@@ -545,6 +551,49 @@ fn is_allowlist_entry_match(allowed: &str, executable: &str, executable_base: &s
     allowed == executable_base
 }
 
+/// Whether `executable` runs the program a `forbidden_commands` entry names.
+/// Unlike the allowlist this fails closed: a path entry also blocks the bare
+/// name and the reverse, and symlinks and `PATH` lookups are resolved before
+/// comparing.
+fn is_denylist_entry_match(
+    forbidden: &str,
+    executable: &str,
+    executable_base: &str,
+    workspace_dir: &Path,
+) -> bool {
+    let forbidden = strip_wrapping_quotes(forbidden).trim();
+    if forbidden.is_empty() {
+        return false;
+    }
+    if forbidden == "*" {
+        return true;
+    }
+
+    let forbidden_base = forbidden.rsplit('/').next().unwrap_or(forbidden);
+    if forbidden_base == executable_base {
+        return true;
+    }
+
+    let Some(resolved) = resolve_executable(executable, workspace_dir) else {
+        return false;
+    };
+    resolved
+        .file_name()
+        .is_some_and(|name| name == forbidden_base)
+        || resolve_executable(forbidden, workspace_dir).is_some_and(|entry| entry == resolved)
+}
+
+/// Canonical path of the program `executable` starts: a path is taken
+/// relative to the workspace, a bare name is looked up on `PATH`.
+fn resolve_executable(executable: &str, workspace_dir: &Path) -> Option<PathBuf> {
+    let path = if looks_like_path(executable) {
+        workspace_dir.join(expand_user_path(executable))
+    } else {
+        which::which(executable).ok()?
+    };
+    path.canonicalize().ok()
+}
+
 impl SecurityPolicy {
     // ── Risk Classification ──────────────────────────────────────────────
     // Risk is assessed per-segment (split on shell operators), and the
@@ -673,6 +722,11 @@ impl SecurityPolicy {
         command: &str,
         approved: bool,
     ) -> Result<CommandRiskLevel, String> {
+        if let Some(executable) = self.forbidden_command(command) {
+            return Err(format!(
+                "Command blocked: '{executable}' is in forbidden_commands"
+            ));
+        }
         if !self.is_command_allowed(command) {
             return Err(format!("Command not allowed by security policy: {command}"));
         }
@@ -709,6 +763,25 @@ impl SecurityPolicy {
     // per-segment allowlist check. Each gate targets a specific bypass
     // technique. If any gate rejects, the whole command is blocked.
 
+    /// First executable in `command` that matches `forbidden_commands`.
+    /// The denylist wins over the allowlist, including `"*"`.
+    pub fn forbidden_command(&self, command: &str) -> Option<String> {
+        split_unquoted_segments(command).iter().find_map(|segment| {
+            let cmd_part = skip_env_assignments(segment);
+            let executable = strip_wrapping_quotes(cmd_part.split_whitespace().next()?).trim();
+            let base_cmd = executable.rsplit('/').next().unwrap_or("");
+            if base_cmd.is_empty() {
+                return None;
+            }
+            self.forbidden_commands
+                .iter()
+                .any(|forbidden| {
+                    is_denylist_entry_match(forbidden, executable, base_cmd, &self.workspace_dir)
+                })
+                .then(|| executable.to_string())
+        })
+    }
+
     /// Check if a shell command is allowed.
     ///
     /// Validates the **entire** command string, not just the first word:
@@ -723,6 +796,10 @@ impl SecurityPolicy {
             return false;
         }
 
+        if self.forbidden_command(command).is_some() {
+            return false;
+        }
+
         // Block subshell/expansion operators — these allow hiding arbitrary
         // commands inside an allowed command (e.g. `echo $(rm -rf /)`) and
         // bypassing path checks through variable indirection. The helper below
@@ -1070,6 +1147,9 @@ impl SecurityPolicy {
             require_approval_for_medium_risk: autonomy_config.require_approval_for_medium_risk,
             block_high_risk_commands: autonomy_config.block_high_risk_commands,
             shell_env_passthrough: autonomy_config.shell_env_passthrough.clone(),
+            forbidden_commands: autonomy_config.forbidden_commands.clone(),
+            shell_timeout_secs: autonomy_config.shell_timeout_secs.max(1),
+            shell_max_output_bytes: autonomy_config.shell_max_output_bytes.max(1),
             tracker: ActionTracker::new(),
         }
     }
@@ -1433,6 +1513,9 @@ mod tests {
             require_approval_for_medium_risk: false,
             block_high_risk_commands: false,
             shell_env_passthrough: vec!["DATABASE_URL".into()],
+            forbidden_commands: vec!["curl".into()],
+            shell_timeout_secs: 5,
+            shell_max_output_bytes: 4096,
             ..crate::config::AutonomyConfig::default()
         };
         let workspace = PathBuf::from("/tmp/test-workspace");
@@ -1447,9 +1530,32 @@ mod tests {
         assert!(!policy.require_approval_for_medium_risk);
         assert!(!policy.block_high_risk_commands);
         assert_eq!(policy.shell_env_passthrough, vec!["DATABASE_URL"]);
+        assert_eq!(policy.forbidden_commands, vec!["curl"]);
+        assert_eq!(policy.shell_timeout_secs, 5);
+        assert_eq!(policy.shell_max_output_bytes, 4096);
         assert_eq!(policy.workspace_dir, PathBuf::from("/tmp/test-workspace"));
     }
 
+    #[test]
+    fn forbidden_commands_override_wildcard_allowlist() {
+        let p = SecurityPolicy {
+            allowed_commands: vec!["*".into()],
+            forbidden_commands: vec!["curl".into(), "/usr/bin/wget".into()],
+            ..SecurityPolicy::default()
+        };
+        assert!(p.is_command_allowed("ls -la"));
+        assert!(!p.is_command_allowed("curl https://example.com"));
+        assert!(!p.is_command_allowed("ls && /usr/local/bin/curl x"));
+        assert!(!p.is_command_allowed("FOO=1 /usr/bin/wget x"));
+        assert_eq!(p.forbidden_command("echo a | curl x"), Some("curl".into()));
+        assert_eq!(p.forbidden_command("wget x"), Some("wget".into()));
+        assert!(!p.is_command_allowed("/opt/bin/curl x"));
+        assert!(p
+            .validate_command_execution("curl x", true)
+            .unwrap_err()
+            .contains("forbidden_commands"));
+    }
+
     #[test]
     fn from_config_normalizes_allowed_roots() {
         let autonomy_config = crate::config::AutonomyConfig {
@@ -2228,6 +2334,32 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn forbidden_commands_match_through_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("wget");
+        std::fs::write(&target, "#!/bin/sh\n").unwrap();
+        symlink(&target, tmp.path().join("fetch")).unwrap();
+
+        let p = SecurityPolicy {
+            allowed_commands: vec!["*".into()],
+            forbidden_commands: vec!["wget".into()],
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        };
+        assert!(!p.is_command_allowed("./fetch https://example.com"));
+
+        let p = SecurityPolicy {
+            forbidden_commands: vec![target.to_string_lossy().into_owned()],
+            ..p
+        };
+        assert_eq!(p.forbidden_command("./fetch x"), Some("./fetch".into()));
+        assert_eq!(p.forbidden_command("ls"), None);
+    }
+
     #[cfg(unix)]
     #[test]
     fn resolved_path_blocks_symlink_escape() {
@@ -0,0 +1,238 @@
+//! Reversible PII redaction for provider requests.
+//!
+//! [`Redactor`] replaces emails, phone numbers and API-key-looking strings
+//! with numbered placeholders (`[EMAIL_1]`, `[PHONE_1]`, `[SECRET_1]`) and
+//! records each substitution in a [`PlaceholderMap`]. The same value gets the
+//! same placeholder everywhere in one request, so the model can still refer
+//! to it, and [`PlaceholderMap::restore`] puts the real values back into the
+//! reply before anyone local sees it.
+
+use crate::config::RedactionConfig;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Placeholder ↔ value pairs collected while redacting one request.
+#[derive(Debug, Clone, Default)]
+pub struct PlaceholderMap {
+    by_value: HashMap<String, String>,
+    /// `(placeholder, value)` in the order they were assigned.
+    entries: Vec<(String, String)>,
+    counts: HashMap<&'static str, usize>,
+}
+
+impl PlaceholderMap {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn placeholder_for(&mut self, kind: &'static str, value: &str) -> String {
+        if let Some(placeholder) = self.by_value.get(value) {
+            return placeholder.clone();
+        }
+        let count = self.counts.entry(kind).or_insert(0);
+        *count += 1;
+        let placeholder = format!("[{kind}_{count}]");
+        self.by_value.insert(value.to_string(), placeholder.clone());
+        self.entries.push((placeholder.clone(), value.to_string()));
+        placeholder
+    }
+
+    /// Swap placeholders in `text` back to the values they stand for.
+    pub fn restore(&self, text: &str) -> String {
+        if self.entries.is_empty() || !text.contains('[') {
+            return text.to_string();
+        }
+        let mut restored = text.to_string();
+        for (placeholder, value) in &self.entries {
+            if restored.contains(placeholder.as_str()) {
+                restored = restored.replace(placeholder.as_str(), value);
+            }
+        }
+        restored
+    }
+}
+
+/// Masks the PII kinds enabled in `[security.redaction]`.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    emails: bool,
+    phone_numbers: bool,
+    api_keys: bool,
+}
+
+impl Redactor {
+    /// `None` when redaction is off or masks nothing.
+    pub fn from_config(config: &RedactionConfig) -> Option<Self> {
+        if !config.enabled || !(config.emails || config.phone_numbers || config.api_keys) {
+            return None;
+        }
+        Some(Self {
+            emails: config.emails,
+            phone_numbers: config.phone_numbers,
+            api_keys: config.api_keys,
+        })
+    }
+
+    /// Mask `text`, adding new substitutions to `map`. Image markers
+    /// (`[IMAGE:…]`, which may hold inline base64 data) are left untouched.
+    pub fn redact(&self, text: &str, map: &mut PlaceholderMap) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("[IMAGE:") {
+            let end = rest[start..]
+                .find(']')
+                .map_or(rest.len(), |i| start + i + 1);
+            out.push_str(&self.redact_plain(&rest[..start], map));
+            out.push_str(&rest[start..end]);
+            rest = &rest[end..];
+        }
+        out.push_str(&self.redact_plain(rest, map));
+        out
+    }
+
+    fn redact_plain(&self, text: &str, map: &mut PlaceholderMap) -> String {
+        let mut text = text.to_string();
+        if self.api_keys {
+            for pattern in api_key_patterns() {
+                text = replace_matches(&text, pattern, "SECRET", map);
+            }
+        }
+        if self.emails {
+            text = replace_matches(&text, email_pattern(), "EMAIL", map);
+        }
+        if self.phone_numbers {
+            text = replace_matches(&text, phone_pattern(), "PHONE", map);
+        }
+        text
+    }
+}
+
+fn replace_matches(
+    text: &str,
+    pattern: &Regex,
+    kind: &'static str,
+    map: &mut PlaceholderMap,
+) -> String {
+    if !pattern.is_match(text) {
+        return text.to_string();
+    }
+    pattern
+        .replace_all(text, |caps: &regex::Captures<'_>| {
+            map.placeholder_for(kind, &caps[0])
+        })
+        .into_owned()
+}
+
+fn email_pattern() -> &'static Regex {
+    static EMAIL: OnceLock<Regex> = OnceLock::new();
+    EMAIL.get_or_init(|| {
+        Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b")
+            .unwrap()
+    })
+}
+
+/// International numbers with a leading `+`, and national numbers whose
+/// groups are separated by spaces, dots or dashes. Bare digit runs (ids,
+/// timestamps) and dates are not matched.
+fn phone_pattern() -> &'static Regex {
+    static PHONE: OnceLock<Regex> = OnceLock::new();
+    PHONE.get_or_init(|| {
+        Regex::new(
+            r"\+\d{1,3}(?:[ .-]?\(?\d{1,4}\)?){2,5}\d\b|(?:\(\d{3}\) ?|\b\d{3}[ .-])\d{3}[ .-]\d{4}\b",
+        )
+        .unwrap()
+    })
+}
+
+fn api_key_patterns() -> &'static [Regex] {
+    static API_KEYS: OnceLock<Vec<Regex>> = OnceLock::new();
+    API_KEYS.get_or_init(|| {
+        [
+            // OpenAI, Anthropic, OpenRouter and most `sk-` style keys
+            r"\bsk-[A-Za-z0-9_-]{20,}",
+            // Stripe
+            r"\b[sr]k_(?:live|test)_[A-Za-z0-9]{16,}",
+            // GitHub
+            r"\bgh[pousr]_[A-Za-z0-9]{30,}",
+            r"\bgithub_pat_[A-Za-z0-9_]{22,}",
+            // Slack
+            r"\bxox[abprs]-[A-Za-z0-9-]{10,}",
+            // AWS access key id
+            r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+            // Google
+            r"\bAIza[0-9A-Za-z_-]{35}",
+            // Telegram bot token
+            r"\b\d{8,10}:[A-Za-z0-9_-]{35}\b",
+            // JSON Web Token
+            r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+        ]
+        .into_iter()
+        .map(|pattern| Regex::new(pattern).unwrap())
+        .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor() -> Redactor {
+        Redactor::from_config(&RedactionConfig {
+            enabled: true,
+            ..RedactionConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn masks_pii_and_restores_it() {
+        let redactor = redactor();
+        let mut map = PlaceholderMap::default();
+        let text = "Mail alice@example.com or bob.smith+tag@mail.example.org, \
+                    call +44 20 7946 0958 or (555) 123-4567. Key: sk-or-v1-abcdefghijklmnopqrstuvwxyz012345";
+
+        let masked = redactor.redact(text, &mut map);
+        assert_eq!(
+            masked,
+            "Mail [EMAIL_1] or [EMAIL_2], call [PHONE_1] or [PHONE_2]. Key: [SECRET_1]"
+        );
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.restore(&masked), text);
+
+        let again = redactor.redact("Reply to alice@example.com", &mut map);
+        assert_eq!(again, "Reply to [EMAIL_1]");
+        assert_eq!(map.len(), 5);
+    }
+
+    #[test]
+    fn leaves_ids_dates_and_images_alone() {
+        let redactor = redactor();
+        let mut map = PlaceholderMap::default();
+        let text = "Order 1700000000 on 2024-01-15 10:30, chat telegram:123456789 \
+                    [IMAGE:data:image/png;base64,c2stYWJjZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXo=]";
+
+        assert_eq!(redactor.redact(text, &mut map), text);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn respects_disabled_kinds() {
+        assert!(Redactor::from_config(&RedactionConfig::default()).is_none());
+        let redactor = Redactor::from_config(&RedactionConfig {
+            enabled: true,
+            emails: false,
+            ..RedactionConfig::default()
+        })
+        .unwrap();
+        let mut map = PlaceholderMap::default();
+        assert_eq!(
+            redactor.redact("alice@example.com 555-123-4567", &mut map),
+            "alice@example.com [PHONE_1]"
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! References to secrets kept outside `config.toml`.
+//!
+//! A secret field may hold a reference instead of the value itself:
+//!
+//! | Reference | Resolved with |
+//! |---|---|
+//! | `env:NAME` | the environment variable `NAME` |
+//! | `pass:path/in/store` | `pass show path/in/store` (first line) |
+//! | `age:/path/to/secret.age` | `age --decrypt` with the identity in `ZEROCLAW_AGE_IDENTITY`, or `age-identity.txt` in the config directory |
+//! | `vault:secret/path#field` | `vault kv get -field=field secret/path` (uses `VAULT_ADDR` / `VAULT_TOKEN`) |
+//! | `keyring:service/account` | the OS keyring (`security` on macOS, `secret-tool` elsewhere) |
+//!
+//! References are resolved once when the config is loaded. Resolved values
+//! are remembered so that saving the config writes the reference back, never
+//! the secret, and references are never encrypted.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+/// Environment variable naming the age identity file for `age:` references.
+pub const AGE_IDENTITY_ENV: &str = "ZEROCLAW_AGE_IDENTITY";
+const DEFAULT_AGE_IDENTITY_FILE: &str = "age-identity.txt";
+
+/// Where a secret is kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretRef {
+    Env(String),
+    Pass(String),
+    Age(PathBuf),
+    Vault { path: String, field: String },
+    Keyring { service: String, account: String },
+}
+
+impl SecretRef {
+    /// Parse a config value. `Ok(None)` for a literal secret; an error for
+    /// a value that uses a reference scheme but is malformed.
+    pub fn parse(value: &str) -> Result<Option<Self>> {
+        let Some((scheme, target)) = value.split_once(':') else {
+            return Ok(None);
+        };
+        let target = target.trim();
+        let reference = match scheme {
+            "env" => {
+                if target.is_empty()
+                    || !target
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    bail!("`{value}` is not `env:<VARIABLE_NAME>`");
+                }
+                Self::Env(target.to_string())
+            }
+            "pass" => {
+                if target.is_empty() {
+                    bail!("`pass:` needs a password store path");
+                }
+                Self::Pass(target.to_string())
+            }
+            "age" => {
+                if target.is_empty() {
+                    bail!("`age:` needs the path of an age-encrypted file");
+                }
+                Self::Age(PathBuf::from(shellexpand::tilde(target).into_owned()))
+            }
+            "vault" => match target.split_once('#') {
+                Some((path, field)) if !path.trim().is_empty() && !field.trim().is_empty() => {
+                    Self::Vault {
+                        path: path.trim().to_string(),
+                        field: field.trim().to_string(),
+                    }
+                }
+                _ => bail!("`{value}` is not `vault:<path>#<field>`"),
+            },
+            "keyring" => match target.split_once('/') {
+                Some((service, account))
+                    if !service.trim().is_empty() && !account.trim().is_empty() =>
+                {
+                    Self::Keyring {
+                        service: service.trim().to_string(),
+                        account: account.trim().to_string(),
+                    }
+                }
+                _ => bail!("`{value}` is not `keyring:<service>/<account>`"),
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(reference))
+    }
+
+    /// Fetch the secret. `zeroclaw_dir` holds the default age identity.
+    pub fn resolve(&self, zeroclaw_dir: &Path) -> Result<String> {
+        let secret = match self {
+            Self::Env(name) => std::env::var(name)
+                .with_context(|| format!("environment variable {name} is not set"))?,
+            Self::Pass(path) => run("pass", &["show", path])?
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            Self::Age(file) => {
+                let identity = std::env::var_os(AGE_IDENTITY_ENV)
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| zeroclaw_dir.join(DEFAULT_AGE_IDENTITY_FILE));
+                run(
+                    "age",
+                    &[
+                        "--decrypt",
+                        "-i",
+                        &identity.to_string_lossy(),
+                        &file.to_string_lossy(),
+                    ],
+                )?
+            }
+            Self::Vault { path, field } => {
+                run("vault", &["kv", "get", &format!("-field={field}"), path])?
+            }
+            Self::Keyring { service, account } => keyring_lookup(service, account)?,
+        };
+        let secret = secret.trim_end_matches(['\r', '\n']).to_string();
+        if secret.is_empty() {
+            bail!("resolved to an empty value");
+        }
+        Ok(secret)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn keyring_lookup(service: &str, account: &str) -> Result<String> {
+    run(
+        "security",
+        &["find-generic-password", "-s", service, "-a", account, "-w"],
+    )
+}
+
+#[cfg(not(target_os = "macos"))]
+fn keyring_lookup(service: &str, account: &str) -> Result<String> {
+    run(
+        "secret-tool",
+        &["lookup", "service", service, "account", account],
+    )
+}
+
+/// Stdout of `program`; its stderr becomes the error when it fails.
+fn run(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("failed to run `{program}`"))?;
+    if !output.status.success() {
+        bail!(
+            "`{program}` failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout)
+        .with_context(|| format!("`{program}` printed non-UTF-8 output"))
+}
+
+/// References resolved so far, by `(field name, resolved value)`.
+fn resolved() -> &'static Mutex<HashMap<(String, String), String>> {
+    static RESOLVED: OnceLock<Mutex<HashMap<(String, String), String>>> = OnceLock::new();
+    RESOLVED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Replace a reference in `value` with the secret it points to.
+pub fn resolve_in_place(value: &mut String, field_name: &str, zeroclaw_dir: &Path) -> Result<()> {
+    let Some(reference) = SecretRef::parse(value)
+        .with_context(|| format!("Invalid secret reference in {field_name}"))?
+    else {
+        return Ok(());
+    };
+    let secret = reference
+        .resolve(zeroclaw_dir)
+        .with_context(|| format!("Failed to resolve {field_name} from `{value}`"))?;
+    resolved()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert((field_name.to_string(), secret.clone()), value.clone());
+    *value = secret;
+    Ok(())
+}
+
+/// Put back the reference `value` was resolved from. Returns `true` when
+/// `value` is a reference afterwards and must be saved as is.
+pub fn restore_in_place(value: &mut String, field_name: &str) -> bool {
+    if let Some(reference) = resolved()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&(field_name.to_string(), value.clone()))
+    {
+        value.clone_from(reference);
+        return true;
+    }
+    matches!(SecretRef::parse(value), Ok(Some(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_recognizes_each_backend() {
+        assert_eq!(
+            SecretRef::parse("env:OPENAI_API_KEY").unwrap(),
+            Some(SecretRef::Env("OPENAI_API_KEY".into()))
+        );
+        assert_eq!(
+            SecretRef::parse("pass:zeroclaw/openrouter").unwrap(),
+            Some(SecretRef::Pass("zeroclaw/openrouter".into()))
+        );
+        assert_eq!(
+            SecretRef::parse("age:/etc/zeroclaw/key.age").unwrap(),
+            Some(SecretRef::Age("/etc/zeroclaw/key.age".into()))
+        );
+        assert_eq!(
+            SecretRef::parse("vault:secret/zeroclaw#telegram").unwrap(),
+            Some(SecretRef::Vault {
+                path: "secret/zeroclaw".into(),
+                field: "telegram".into(),
+            })
+        );
+        assert_eq!(
+            SecretRef::parse("keyring:zeroclaw/slack").unwrap(),
+            Some(SecretRef::Keyring {
+                service: "zeroclaw".into(),
+                account: "slack".into(),
+            })
+        );
+
+        assert_eq!(SecretRef::parse("sk-or-v1-abc").unwrap(), None);
+        assert_eq!(SecretRef::parse("123456:ABC-DEF").unwrap(), None);
+        assert!(SecretRef::parse("env:").is_err());
+        assert!(SecretRef::parse("vault:secret/zeroclaw").is_err());
+        assert!(SecretRef::parse("keyring:zeroclaw").is_err());
+    }
+
+    #[test]
+    fn env_reference_resolves_and_is_restored_for_saving() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::env::set_var("ZEROCLAW_TEST_SECRET_REF", "s3cret\n");
+
+        let mut value = "env:ZEROCLAW_TEST_SECRET_REF".to_string();
+        resolve_in_place(&mut value, "config.test.secret", tmp.path()).unwrap();
+        assert_eq!(value, "s3cret");
+
+        assert!(restore_in_place(&mut value, "config.test.secret"));
+        assert_eq!(value, "env:ZEROCLAW_TEST_SECRET_REF");
+        let mut other = "s3cret".to_string();
+        assert!(!restore_in_place(&mut other, "config.test.other"));
+        assert_eq!(other, "s3cret");
+
+        let mut missing = "env:ZEROCLAW_TEST_SECRET_REF_UNSET".to_string();
+        let err = resolve_in_place(&mut missing, "config.test.secret", tmp.path()).unwrap_err();
+        assert!(format!("{err:#}").contains("ZEROCLAW_TEST_SECRET_REF_UNSET is not set"));
+    }
+}
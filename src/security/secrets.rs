@@ -50,6 +50,11 @@ impl SecretStore {
         }
     }
 
+    /// Directory holding the key file.
+    pub fn zeroclaw_dir(&self) -> &Path {
+        self.key_path.parent().unwrap_or_else(|| Path::new("."))
+    }
+
     /// Encrypt a plaintext secret. Returns hex-encoded ciphertext prefixed with `enc2:`.
     /// Format: `enc2:<hex(nonce ‖ ciphertext ‖ tag)>` (12 + N + 16 bytes).
     /// If encryption is disabled, returns the plaintext as-is.
@@ -0,0 +1,68 @@
+//! Process-wide shutdown signal.
+//!
+//! The first SIGINT or SIGTERM cancels [`token`]; the channel runtime and
+//! the gateway stop taking new work on it and drain what is in flight
+//! before they return. A second signal exits immediately.
+
+use std::sync::{Once, OnceLock};
+use tokio_util::sync::CancellationToken;
+
+fn shutdown_token() -> &'static CancellationToken {
+    static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+    TOKEN.get_or_init(CancellationToken::new)
+}
+
+/// Token cancelled once shutdown has been requested.
+pub fn token() -> CancellationToken {
+    shutdown_token().clone()
+}
+
+/// Whether shutdown has been requested.
+pub fn is_requested() -> bool {
+    shutdown_token().is_cancelled()
+}
+
+/// Resolves once shutdown has been requested.
+pub async fn requested() {
+    shutdown_token().cancelled().await;
+}
+
+/// Install the SIGINT/SIGTERM handler. Safe to call more than once.
+pub fn listen_for_signals() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        tokio::spawn(async {
+            let signal = wait_for_signal().await;
+            tracing::info!("Received {signal}; shutting down gracefully (send again to force)");
+            shutdown_token().cancel();
+
+            let signal = wait_for_signal().await;
+            tracing::warn!("Received {signal} again; exiting without draining");
+            std::process::exit(130);
+        });
+    });
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> &'static str {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            tracing::warn!("Failed to listen for SIGTERM: {e}");
+            let _ = tokio::signal::ctrl_c().await;
+            return "SIGINT";
+        }
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => "SIGINT",
+        _ = terminate.recv() => "SIGTERM",
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> &'static str {
+    let _ = tokio::signal::ctrl_c().await;
+    "Ctrl+C"
+}
@@ -12,6 +12,7 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::net::ToSocketAddrs;
+use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
@@ -865,6 +866,33 @@ impl BrowserTool {
         }
     }
 
+    /// File under `{workspace}/screenshots` a screenshot is written to. Only
+    /// the file name of a requested path is kept, so screenshots cannot be
+    /// written elsewhere, and existing files are never overwritten.
+    fn screenshot_path(&self, requested: Option<&str>) -> anyhow::Result<PathBuf> {
+        let file_name = requested
+            .and_then(|path| std::path::Path::new(path).file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| {
+                format!(
+                    "browser_{}.png",
+                    chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f")
+                )
+            });
+        let path = self
+            .security
+            .workspace_dir
+            .join("screenshots")
+            .join(file_name);
+        if path.symlink_metadata().is_ok() {
+            anyhow::bail!(
+                "Screenshot file {} already exists; choose another path",
+                path.display()
+            );
+        }
+        Ok(path)
+    }
+
     #[allow(clippy::unnecessary_wraps, clippy::unused_self)]
     fn to_result(&self, resp: AgentBrowserResponse) -> anyhow::Result<ToolResult> {
         if resp.success {
@@ -898,7 +926,8 @@ impl Tool for BrowserTool {
             "Web/browser automation with pluggable backends (agent-browser, rust-native, computer_use). ",
             "Supports DOM actions plus optional OS-level actions (mouse_move, mouse_click, mouse_drag, ",
             "key_type, key_press, screen_capture) through a computer-use sidecar. Use 'snapshot' to map ",
-            "interactive elements to refs (@e1, @e2). Enforces browser.allowed_domains for open actions."
+            "interactive elements to refs (@e1, @e2). 'screenshot' returns an [IMAGE:path] marker. ",
+            "Enforces browser.allowed_domains for open actions."
         )
     }
 
@@ -991,7 +1020,7 @@ impl Tool for BrowserTool {
                 },
                 "path": {
                     "type": "string",
-                    "description": "File path for screenshot"
+                    "description": "For screenshot: new file name under workspace/screenshots (default: browser_<timestamp>.png). The result includes an [IMAGE:path] marker to attach it to your reply."
                 },
                 "ms": {
                     "type": "integer",
@@ -1082,7 +1111,42 @@ impl Tool for BrowserTool {
             }
         };
 
-        self.execute_action(action, backend).await
+        // Screenshots land in `{workspace}/screenshots` and are handed back as image
+        // attachments the agent can include in its reply.
+        let (action, screenshot_path) = match action {
+            BrowserAction::Screenshot { path, full_page } => {
+                let path = match self.screenshot_path(path.as_deref()) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e.to_string()),
+                        });
+                    }
+                };
+                if let Some(dir) = path.parent() {
+                    tokio::fs::create_dir_all(dir).await?;
+                }
+                let path = path.to_string_lossy().to_string();
+                (
+                    BrowserAction::Screenshot {
+                        path: Some(path.clone()),
+                        full_page,
+                    },
+                    Some(path),
+                )
+            }
+            other => (other, None),
+        };
+
+        let mut result = self.execute_action(action, backend).await?;
+        if let Some(path) = screenshot_path {
+            if result.success && tokio::fs::metadata(&path).await.is_ok() {
+                result.output = format!("{}\n[IMAGE:{path}]", result.output.trim_end());
+            }
+        }
+        Ok(result)
     }
 }
 
@@ -2483,6 +2547,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn screenshot_path_stays_in_workspace() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let tool = BrowserTool::new(security, vec!["example.com".into()], None);
+        let screenshots = tmp.path().join("screenshots");
+
+        assert_eq!(
+            tool.screenshot_path(Some("../../etc/page.png")).unwrap(),
+            screenshots.join("page.png")
+        );
+        let default = tool.screenshot_path(None).unwrap();
+        assert_eq!(default.parent(), Some(screenshots.as_path()));
+        let default_name = default.file_name().unwrap().to_string_lossy();
+        assert!(default_name.starts_with("browser_"));
+        assert!(default_name.ends_with(".png"));
+
+        std::fs::create_dir_all(&screenshots).unwrap();
+        std::fs::write(screenshots.join("page.png"), b"png").unwrap();
+        assert!(tool.screenshot_path(Some("page.png")).is_err());
+        assert!(tool.screenshot_path(Some("/etc/page.png")).is_err());
+    }
+
     #[cfg(feature = "browser-native")]
     #[test]
     fn reset_session_is_idempotent_without_client() {
@@ -0,0 +1,1400 @@
+//! Calendar events over CalDAV or the Google Calendar API.
+//!
+//! The `calendar` tool lists, creates, updates and deletes events in the
+//! calendar configured in `[calendar]`. Times given without an offset are
+//! read in `[calendar] timezone` (default: the system timezone), and events
+//! are listed in that timezone too.
+//!
+//! CalDAV needs no extra dependencies: the multistatus XML and iCalendar
+//! data are handled by the small parsers below, which only understand the
+//! parts of RFC 4791 / RFC 5545 the tool uses.
+
+use super::traits::{Tool, ToolResult};
+use crate::config::{CalendarBackend, CalendarConfig};
+use crate::security::SecurityPolicy;
+use crate::util::{xml_elements, xml_text};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use reqwest::{header, Method, Url};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_CALENDAR_API: &str = "https://www.googleapis.com/calendar/v3";
+
+/// Properties rewritten in the main `VEVENT` when an event is updated.
+const REWRITTEN_PROPERTIES: &[&str] = &[
+    "SUMMARY",
+    "DTSTART",
+    "DTEND",
+    "DURATION",
+    "LOCATION",
+    "DESCRIPTION",
+    "DTSTAMP",
+    "SEQUENCE",
+];
+
+/// Longest description shown per event when listing, in characters.
+const MAX_LISTED_DESCRIPTION_CHARS: usize = 200;
+
+/// Timezone used to read and show wall-clock times.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Zone {
+    Named(chrono_tz::Tz),
+    Local,
+}
+
+impl Zone {
+    fn resolve(self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|t| t.to_utc()),
+            Zone::Local => chrono::Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|t| t.to_utc()),
+        }
+    }
+
+    fn local(self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Named(tz) => at.with_timezone(&tz).naive_local(),
+            Zone::Local => at.with_timezone(&chrono::Local).naive_local(),
+        }
+    }
+
+    fn name(self) -> String {
+        match self {
+            Zone::Named(tz) => tz.name().to_string(),
+            Zone::Local => format!("UTC{}", chrono::Local::now().format("%:z")),
+        }
+    }
+}
+
+/// Start or end of an event. All-day events use dates, with an exclusive
+/// end date as in iCalendar and the Google API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventTime {
+    Date(NaiveDate),
+    At(DateTime<Utc>),
+}
+
+impl EventTime {
+    /// The instant this time starts at, reading dates as midnight in `zone`.
+    fn instant(self, zone: Zone) -> DateTime<Utc> {
+        match self {
+            EventTime::At(at) => at,
+            EventTime::Date(day) => {
+                let midnight = day.and_hms_opt(0, 0, 0).expect("midnight is valid");
+                zone.resolve(midnight)
+                    .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Event {
+    /// CalDAV resource href or Google event ID.
+    id: String,
+    title: String,
+    start: EventTime,
+    end: EventTime,
+    location: Option<String>,
+    description: Option<String>,
+}
+
+/// Fields an `update` call changes. Empty `location` or `description`
+/// strings clear the field.
+#[derive(Debug, Default)]
+struct EventChanges {
+    title: Option<String>,
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    location: Option<String>,
+    description: Option<String>,
+}
+
+impl EventChanges {
+    fn from_args(args: &Value, zone: Zone) -> Result<Self> {
+        let text = |key: &str| {
+            args.get(key)
+                .and_then(Value::as_str)
+                .map(|value| value.trim().to_string())
+        };
+        let time = |key: &str| str_arg(args, key).map(|value| parse_time(value, zone));
+        Ok(Self {
+            title: text("title").filter(|title| !title.is_empty()),
+            start: time("start").transpose()?,
+            end: time("end").transpose()?,
+            location: text("location"),
+            description: text("description"),
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.start.is_none()
+            && self.end.is_none()
+            && self.location.is_none()
+            && self.description.is_none()
+    }
+
+    /// Apply the changes. Moving the start without a new end keeps the
+    /// event's length.
+    fn apply(self, event: &mut Event, default_duration: Duration) {
+        if let Some(title) = self.title {
+            event.title = title;
+        }
+        if let Some(location) = self.location {
+            event.location = Some(location).filter(|location| !location.is_empty());
+        }
+        if let Some(description) = self.description {
+            event.description = Some(description).filter(|description| !description.is_empty());
+        }
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => {
+                event.start = start;
+                event.end = end;
+            }
+            (Some(start), None) => {
+                event.end = match (event.start, event.end, start) {
+                    (EventTime::At(old), EventTime::At(end), EventTime::At(new)) => {
+                        EventTime::At(new + (end - old))
+                    }
+                    (EventTime::Date(old), EventTime::Date(end), EventTime::Date(new)) => {
+                        EventTime::Date(new + (end - old))
+                    }
+                    _ => default_end(start, default_duration),
+                };
+                event.start = start;
+            }
+            (None, Some(end)) => event.end = end,
+            (None, None) => {}
+        }
+    }
+}
+
+fn default_end(start: EventTime, duration: Duration) -> EventTime {
+    match start {
+        EventTime::Date(day) => EventTime::Date(day + Duration::days(1)),
+        EventTime::At(at) => EventTime::At(at + duration),
+    }
+}
+
+fn check_order(event: &Event, zone: Zone) -> Result<()> {
+    if matches!(event.start, EventTime::Date(_)) != matches!(event.end, EventTime::Date(_)) {
+        bail!("'start' and 'end' must both be dates or both be times");
+    }
+    if event.end.instant(zone) <= event.start.instant(zone) {
+        bail!("'end' must be after 'start'");
+    }
+    Ok(())
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Parse a tool argument: a date (`2026-10-17`), an RFC 3339 time, or a
+/// wall-clock time in `zone` (`2026-10-17T15:00`).
+fn parse_time(text: &str, zone: Zone) -> Result<EventTime> {
+    if let Ok(day) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Ok(EventTime::Date(day));
+    }
+    if let Ok(at) = DateTime::parse_from_rfc3339(text) {
+        return Ok(EventTime::At(at.to_utc()));
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(text, format) {
+            return zone
+                .resolve(naive)
+                .map(EventTime::At)
+                .with_context(|| format!("'{text}' does not exist in {}", zone.name()));
+        }
+    }
+    bail!("Invalid time '{text}': use YYYY-MM-DD, YYYY-MM-DDTHH:MM or RFC 3339")
+}
+
+/// One-line summary of an event for tool output.
+fn describe(event: &Event, zone: Zone) -> String {
+    let when = match (event.start, event.end) {
+        (EventTime::Date(start), EventTime::Date(end)) => {
+            let last = end.pred_opt().filter(|last| *last > start).unwrap_or(start);
+            if last == start {
+                format!("{} (all day)", start.format("%a %Y-%m-%d"))
+            } else {
+                format!(
+                    "{} to {} (all day)",
+                    start.format("%a %Y-%m-%d"),
+                    last.format("%a %Y-%m-%d")
+                )
+            }
+        }
+        (EventTime::At(start), EventTime::At(end)) => {
+            let (start, end) = (zone.local(start), zone.local(end));
+            if start.date() == end.date() {
+                format!(
+                    "{}-{}",
+                    start.format("%a %Y-%m-%d %H:%M"),
+                    end.format("%H:%M")
+                )
+            } else {
+                format!(
+                    "{} to {}",
+                    start.format("%a %Y-%m-%d %H:%M"),
+                    end.format("%a %Y-%m-%d %H:%M")
+                )
+            }
+        }
+        (start, _) => zone
+            .local(start.instant(zone))
+            .format("%a %Y-%m-%d %H:%M")
+            .to_string(),
+    };
+    let mut line = format!("{when}: {}", event.title);
+    if let Some(location) = &event.location {
+        let _ = write!(line, " @ {location}");
+    }
+    let _ = write!(line, " (id: {})", event.id);
+    line
+}
+
+// ── iCalendar ───────────────────────────────────────────────────
+
+/// Split iCalendar text into logical lines, joining folded continuations.
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(rest) = raw.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+    }
+    lines
+}
+
+/// Fold a content line at 75 octets.
+fn fold(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out
+}
+
+/// An iCalendar content line with its name and parameter keys upper-cased.
+struct Property<'a> {
+    name: String,
+    params: Vec<(String, String)>,
+    value: &'a str,
+}
+
+fn split_property(line: &str) -> Option<Property<'_>> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(idx, ch)| match ch {
+        '"' => {
+            in_quotes = !in_quotes;
+            None
+        }
+        ':' if !in_quotes => Some(idx),
+        _ => None,
+    })?;
+    let mut head = line[..colon].split(';');
+    let name = head.next()?.trim().to_ascii_uppercase();
+    let params = head
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.trim().to_ascii_uppercase(),
+                value.trim().trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: &line[colon + 1..],
+    })
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Parse an iCalendar duration such as `PT1H30M` or `P1D`.
+fn parse_ical_duration(value: &str) -> Option<Duration> {
+    let rest = value.trim().trim_start_matches('+').strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for ch in rest.chars() {
+        match ch {
+            'T' => in_time = true,
+            '0'..='9' => number.push(ch),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(total)
+}
+
+/// Parse a `DTSTART`/`DTEND` value. Unknown `TZID`s fall back to `zone`.
+fn parse_ical_time(params: &[(String, String)], value: &str, zone: Zone) -> Option<EventTime> {
+    let value = value.trim();
+    let param = |key: &str| {
+        params
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    };
+    if param("VALUE").is_some_and(|kind| kind.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(EventTime::Date);
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .ok()
+            .map(|naive| EventTime::At(naive.and_utc()));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    // Accept prefixed IDs such as `/mozilla.org/20050126_1/Europe/Berlin`.
+    let named = param("TZID").and_then(|tzid| {
+        std::iter::once(tzid)
+            .chain(tzid.match_indices('/').map(|(idx, _)| &tzid[idx + 1..]))
+            .find_map(|candidate| chrono_tz::Tz::from_str(candidate).ok())
+    });
+    named
+        .map_or(zone, Zone::Named)
+        .resolve(naive)
+        .map(EventTime::At)
+}
+
+/// The `VEVENT`s in an iCalendar object, each identified by `id`.
+fn parse_events(ics: &str, id: &str, zone: Zone) -> Vec<Event> {
+    #[derive(Default)]
+    struct Draft {
+        title: Option<String>,
+        start: Option<EventTime>,
+        end: Option<EventTime>,
+        duration: Option<Duration>,
+        location: Option<String>,
+        description: Option<String>,
+    }
+
+    let mut events = Vec::new();
+    let mut components: Vec<String> = Vec::new();
+    let mut draft = Draft::default();
+    for line in unfold(ics) {
+        let Some(Property {
+            name,
+            params,
+            value,
+        }) = split_property(&line)
+        else {
+            continue;
+        };
+        let in_event = components.last().is_some_and(|c| c == "VEVENT");
+        match name.as_str() {
+            "BEGIN" => {
+                if value.eq_ignore_ascii_case("VEVENT") {
+                    draft = Draft::default();
+                }
+                components.push(value.trim().to_ascii_uppercase());
+            }
+            "END" => {
+                components.pop();
+                if !in_event {
+                    continue;
+                }
+                let draft = std::mem::take(&mut draft);
+                let Some(start) = draft.start else {
+                    continue;
+                };
+                let end = match (draft.end, draft.duration, start) {
+                    (Some(end), _, _) => end,
+                    (None, Some(length), EventTime::Date(day)) => {
+                        EventTime::Date(day + Duration::days(length.num_days().max(1)))
+                    }
+                    (None, Some(length), EventTime::At(at)) => EventTime::At(at + length),
+                    (None, None, start) => default_end(start, Duration::zero()),
+                };
+                events.push(Event {
+                    id: id.to_string(),
+                    title: draft.title.unwrap_or_else(|| "(no title)".into()),
+                    start,
+                    end,
+                    location: draft.location,
+                    description: draft.description,
+                });
+            }
+            "SUMMARY" if in_event => draft.title = Some(unescape_text(value)),
+            "LOCATION" if in_event => {
+                draft.location = Some(unescape_text(value)).filter(|text| !text.is_empty());
+            }
+            "DESCRIPTION" if in_event => {
+                draft.description = Some(unescape_text(value)).filter(|text| !text.is_empty());
+            }
+            "DTSTART" if in_event => draft.start = parse_ical_time(&params, value, zone),
+            "DTEND" if in_event => draft.end = parse_ical_time(&params, value, zone),
+            "DURATION" if in_event => draft.duration = parse_ical_duration(value),
+            _ => {}
+        }
+    }
+    events
+}
+
+fn ical_time_property(name: &str, time: EventTime) -> String {
+    match time {
+        EventTime::Date(day) => format!("{name};VALUE=DATE:{}", day.format("%Y%m%d")),
+        EventTime::At(at) => format!("{name}:{}", at.format("%Y%m%dT%H%M%SZ")),
+    }
+}
+
+fn event_properties(event: &Event, now: DateTime<Utc>) -> Vec<String> {
+    let mut lines = vec![
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+        format!("SUMMARY:{}", escape_text(&event.title)),
+        ical_time_property("DTSTART", event.start),
+        ical_time_property("DTEND", event.end),
+    ];
+    if let Some(location) = &event.location {
+        lines.push(format!("LOCATION:{}", escape_text(location)));
+    }
+    if let Some(description) = &event.description {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    lines
+}
+
+fn join_lines(lines: impl IntoIterator<Item = String>) -> String {
+    lines.into_iter().map(|line| fold(&line) + "\r\n").collect()
+}
+
+fn new_calendar(uid: &str, event: &Event, now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".into(),
+        "PRODID:-//ZeroClaw//Calendar Tool//EN".into(),
+        "BEGIN:VEVENT".into(),
+        format!("UID:{uid}"),
+    ];
+    lines.extend(event_properties(event, now));
+    lines.extend(["END:VEVENT".to_string(), "END:VCALENDAR".into()]);
+    join_lines(lines)
+}
+
+/// Rewrite the first `VEVENT` in `ics` to match `event`, keeping its other
+/// properties (UID, RRULE, alarms, attendees) and bumping `SEQUENCE`.
+fn update_calendar(ics: &str, event: &Event, now: DateTime<Utc>) -> String {
+    let mut out = Vec::new();
+    let mut components: Vec<String> = Vec::new();
+    let mut sequence = 0u32;
+    let mut inserted = false;
+    let mut done = false;
+    for line in unfold(ics) {
+        let (name, value) = split_property(&line)
+            .map(|property| (property.name, property.value.trim().to_string()))
+            .unwrap_or_default();
+        let in_main_event = !done && components.last().is_some_and(|c| c == "VEVENT");
+        // Properties go before sub-components such as VALARM.
+        if in_main_event && !inserted && (name == "BEGIN" || name == "END") {
+            out.push(format!("SEQUENCE:{}", sequence + 1));
+            out.extend(event_properties(event, now));
+            inserted = true;
+        }
+        match name.as_str() {
+            "BEGIN" => components.push(value.to_ascii_uppercase()),
+            "END" => {
+                done |= in_main_event;
+                components.pop();
+            }
+            "SEQUENCE" if in_main_event => {
+                sequence = value.parse().unwrap_or(0);
+                continue;
+            }
+            property if in_main_event && REWRITTEN_PROPERTIES.contains(&property) => continue,
+            _ => {}
+        }
+        out.push(line);
+    }
+    join_lines(out)
+}
+
+// ── CalDAV XML ──────────────────────────────────────────────────
+
+/// Resolve an event ID (href) against the calendar collection, refusing
+/// anything outside it so credentials are never sent elsewhere.
+fn caldav_event_url(collection: &Url, id: &str) -> Result<Url> {
+    let url = collection
+        .join(id)
+        .with_context(|| format!("Invalid event id '{id}'"))?;
+    if url.origin() != collection.origin()
+        || !url.path().starts_with(collection.path())
+        || url.path() == collection.path()
+    {
+        bail!("Event id '{id}' is not in the configured calendar");
+    }
+    Ok(url)
+}
+
+// ── Google Calendar ─────────────────────────────────────────────
+
+fn google_time(time: EventTime) -> Value {
+    match time {
+        EventTime::Date(day) => {
+            json!({"date": day.format("%Y-%m-%d").to_string(), "dateTime": null})
+        }
+        EventTime::At(at) => {
+            json!({"dateTime": at.to_rfc3339_opts(SecondsFormat::Secs, true), "date": null})
+        }
+    }
+}
+
+fn google_event_body(event: &Event) -> Value {
+    json!({
+        "summary": event.title,
+        "start": google_time(event.start),
+        "end": google_time(event.end),
+        "location": event.location,
+        "description": event.description,
+    })
+}
+
+fn parse_google_event(item: &Value) -> Option<Event> {
+    let time = |key: &str| {
+        let value = item.get(key)?;
+        match value.get("dateTime").and_then(Value::as_str) {
+            Some(at) => DateTime::parse_from_rfc3339(at)
+                .ok()
+                .map(|at| EventTime::At(at.to_utc())),
+            None => value
+                .get("date")
+                .and_then(Value::as_str)
+                .and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+                .map(EventTime::Date),
+        }
+    };
+    let text = |key: &str| {
+        item.get(key)
+            .and_then(Value::as_str)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+    let start = time("start")?;
+    Some(Event {
+        id: item.get("id")?.as_str()?.to_string(),
+        title: text("summary").unwrap_or_else(|| "(no title)".into()),
+        start,
+        end: time("end").unwrap_or(start),
+        location: text("location"),
+        description: text("description"),
+    })
+}
+
+async fn checked(resp: reqwest::Response, service: &str) -> Result<reqwest::Response> {
+    let status = resp.status();
+    if !status.is_success() {
+        let body: String = resp
+            .text()
+            .await
+            .unwrap_or_default()
+            .chars()
+            .take(500)
+            .collect();
+        bail!("{service} error ({status}): {}", body.trim());
+    }
+    Ok(resp)
+}
+
+/// Tool that manages events in the configured calendar.
+pub struct CalendarTool {
+    security: Arc<SecurityPolicy>,
+    config: CalendarConfig,
+    google_token: Mutex<Option<(String, Instant)>>,
+}
+
+impl CalendarTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: CalendarConfig) -> Self {
+        Self {
+            security,
+            config,
+            google_token: Mutex::new(None),
+        }
+    }
+
+    fn client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client_with_timeouts(
+            "tool.calendar",
+            self.config.timeout_secs.max(1),
+            10,
+        )
+    }
+
+    fn zone(&self) -> Result<Zone> {
+        match self.config.timezone.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => chrono_tz::Tz::from_str(name)
+                .map(Zone::Named)
+                .map_err(|_| anyhow::anyhow!("Invalid [calendar] timezone '{name}'")),
+            _ => Ok(Zone::Local),
+        }
+    }
+
+    fn default_duration(&self) -> Duration {
+        Duration::minutes(i64::from(self.config.default_duration_mins.max(1)))
+    }
+
+    async fn list(&self, args: &Value) -> Result<String> {
+        let zone = self.zone()?;
+        let from = match str_arg(args, "start") {
+            Some(start) => parse_time(start, zone)?.instant(zone),
+            None => Utc::now(),
+        };
+        let to = match str_arg(args, "end") {
+            // An end date includes that whole day.
+            Some(end) => match parse_time(end, zone)? {
+                EventTime::Date(day) => EventTime::Date(day + Duration::days(1)).instant(zone),
+                EventTime::At(at) => at,
+            },
+            None => from + Duration::days(7),
+        };
+        if to <= from {
+            bail!("'end' must be after 'start'");
+        }
+        let limit = usize::try_from(
+            args.get("max_results")
+                .and_then(Value::as_u64)
+                .unwrap_or(20)
+                .clamp(1, 100),
+        )
+        .unwrap_or(20);
+        let query = str_arg(args, "query").map(str::to_lowercase);
+
+        let mut events = match self.config.backend {
+            CalendarBackend::Caldav => self.caldav_list(from, to, zone).await?,
+            CalendarBackend::Google => self.google_list(from, to, query.as_deref()).await?,
+        };
+        events.retain(|event| {
+            let start = event.start.instant(zone);
+            start < to && (start >= from || event.end.instant(zone) > from)
+        });
+        if let Some(query) = &query {
+            events.retain(|event| {
+                [
+                    Some(&event.title),
+                    event.location.as_ref(),
+                    event.description.as_ref(),
+                ]
+                .into_iter()
+                .flatten()
+                .any(|text| text.to_lowercase().contains(query.as_str()))
+            });
+        }
+        events.sort_by_key(|event| event.start.instant(zone));
+
+        let range = format!(
+            "{} to {} ({})",
+            zone.local(from).format("%a %Y-%m-%d %H:%M"),
+            zone.local(to).format("%a %Y-%m-%d %H:%M"),
+            zone.name()
+        );
+        if events.is_empty() {
+            return Ok(format!("No events from {range}."));
+        }
+        let mut output = format!("Events from {range}:");
+        for event in events.iter().take(limit) {
+            let _ = write!(output, "\n- {}", describe(event, zone));
+            if let Some(description) = &event.description {
+                let flat = description.split_whitespace().collect::<Vec<_>>().join(" ");
+                let mut short: String = flat.chars().take(MAX_LISTED_DESCRIPTION_CHARS).collect();
+                if short.len() < flat.len() {
+                    short.push_str("...");
+                }
+                let _ = write!(output, "\n  {short}");
+            }
+        }
+        if events.len() > limit {
+            let _ = write!(output, "\n(showing {limit} of {} events)", events.len());
+        }
+        Ok(output)
+    }
+
+    async fn create(&self, args: &Value) -> Result<String> {
+        let zone = self.zone()?;
+        let title = str_arg(args, "title").context("Missing 'title' parameter")?;
+        let start = parse_time(
+            str_arg(args, "start").context("Missing 'start' parameter")?,
+            zone,
+        )?;
+        let end = match str_arg(args, "end") {
+            Some(end) => parse_time(end, zone)?,
+            None => default_end(start, self.default_duration()),
+        };
+        let mut event = Event {
+            id: String::new(),
+            title: title.to_string(),
+            start,
+            end,
+            location: str_arg(args, "location").map(str::to_string),
+            description: str_arg(args, "description").map(str::to_string),
+        };
+        check_order(&event, zone)?;
+
+        event.id = match self.config.backend {
+            CalendarBackend::Caldav => self.caldav_create(&event).await?,
+            CalendarBackend::Google => self.google_create(&event).await?,
+        };
+        Ok(format!("Created event: {}", describe(&event, zone)))
+    }
+
+    async fn update(&self, args: &Value) -> Result<String> {
+        let zone = self.zone()?;
+        let id = str_arg(args, "event_id").context("Missing 'event_id' parameter")?;
+        let changes = EventChanges::from_args(args, zone)?;
+        if changes.is_empty() {
+            bail!("Nothing to update: pass title, start, end, location or description");
+        }
+        let event = match self.config.backend {
+            CalendarBackend::Caldav => self.caldav_update(id, changes, zone).await?,
+            CalendarBackend::Google => self.google_update(id, changes, zone).await?,
+        };
+        Ok(format!("Updated event: {}", describe(&event, zone)))
+    }
+
+    async fn delete(&self, args: &Value) -> Result<String> {
+        let id = str_arg(args, "event_id").context("Missing 'event_id' parameter")?;
+        match self.config.backend {
+            CalendarBackend::Caldav => {
+                let url = caldav_event_url(&self.caldav_collection()?, id)?;
+                let resp = self
+                    .caldav_request(Method::DELETE, url)
+                    .send()
+                    .await
+                    .context("CalDAV request failed")?;
+                checked(resp, "CalDAV").await?;
+            }
+            CalendarBackend::Google => {
+                let resp = self
+                    .client()
+                    .delete(self.google_event_url(id))
+                    .bearer_auth(self.google_access_token().await?)
+                    .send()
+                    .await
+                    .context("Google Calendar request failed")?;
+                checked(resp, "Google Calendar").await?;
+            }
+        }
+        Ok(format!("Deleted event {id}."))
+    }
+
+    // ── CalDAV backend ──────────────────────────────────────────
+
+    fn caldav_collection(&self) -> Result<Url> {
+        let raw = self
+            .config
+            .caldav_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .context("[calendar] caldav_url is not set")?;
+        let raw = if raw.ends_with('/') {
+            raw.to_string()
+        } else {
+            format!("{raw}/")
+        };
+        Url::parse(&raw).context("Invalid [calendar] caldav_url")
+    }
+
+    fn caldav_request(&self, method: Method, url: Url) -> reqwest::RequestBuilder {
+        let request = self.client().request(method, url);
+        match self.config.caldav_username.as_deref() {
+            Some(username) if !username.is_empty() => {
+                request.basic_auth(username, self.config.caldav_password.as_deref())
+            }
+            _ => request,
+        }
+    }
+
+    async fn caldav_list(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        zone: Zone,
+    ) -> Result<Vec<Event>> {
+        let start = from.format("%Y%m%dT%H%M%SZ");
+        let end = to.format("%Y%m%dT%H%M%SZ");
+        // `expand` asks the server to return recurring events as instances.
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <c:calendar-data><c:expand start="{start}" end="{end}"/></c:calendar-data>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VEVENT">
+        <c:time-range start="{start}" end="{end}"/>
+      </c:comp-filter>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#
+        );
+        let resp = self
+            .caldav_request(Method::from_bytes(b"REPORT")?, self.caldav_collection()?)
+            .header("Depth", "1")
+            .header(header::CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(body)
+            .send()
+            .await
+            .context("CalDAV request failed")?;
+        let xml = checked(resp, "CalDAV").await?.text().await?;
+
+        let mut events = Vec::new();
+        for response in xml_elements(&xml, "response") {
+            let Some(href) = xml_elements(response, "href")
+                .first()
+                .map(|href| xml_text(href))
+            else {
+                continue;
+            };
+            for data in xml_elements(response, "calendar-data") {
+                events.extend(parse_events(&xml_text(data), &href, zone));
+            }
+        }
+        Ok(events)
+    }
+
+    async fn caldav_create(&self, event: &Event) -> Result<String> {
+        let uid = uuid::Uuid::new_v4().to_string();
+        let url = self.caldav_collection()?.join(&format!("{uid}.ics"))?;
+        let resp = self
+            .caldav_request(Method::PUT, url.clone())
+            .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .header(header::IF_NONE_MATCH, "*")
+            .body(new_calendar(&uid, event, Utc::now()))
+            .send()
+            .await
+            .context("CalDAV request failed")?;
+        checked(resp, "CalDAV").await?;
+        Ok(url.path().to_string())
+    }
+
+    async fn caldav_update(&self, id: &str, changes: EventChanges, zone: Zone) -> Result<Event> {
+        let url = caldav_event_url(&self.caldav_collection()?, id)?;
+        let resp = self
+            .caldav_request(Method::GET, url.clone())
+            .send()
+            .await
+            .context("CalDAV request failed")?;
+        let resp = checked(resp, "CalDAV").await?;
+        let etag = resp.headers().get(header::ETAG).cloned();
+        let ics = resp.text().await?;
+
+        let mut event = parse_events(&ics, id, zone)
+            .into_iter()
+            .next()
+            .with_context(|| format!("No event found at '{id}'"))?;
+        changes.apply(&mut event, self.default_duration());
+        check_order(&event, zone)?;
+
+        let mut request = self
+            .caldav_request(Method::PUT, url)
+            .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+            .body(update_calendar(&ics, &event, Utc::now()));
+        // Fail rather than overwrite a concurrent change.
+        if let Some(etag) = etag {
+            request = request.header(header::IF_MATCH, etag);
+        }
+        let resp = request.send().await.context("CalDAV request failed")?;
+        checked(resp, "CalDAV").await?;
+        Ok(event)
+    }
+
+    // ── Google backend ──────────────────────────────────────────
+
+    async fn google_access_token(&self) -> Result<String> {
+        let mut cached = self.google_token.lock().await;
+        if let Some((token, expires_at)) = cached.as_ref() {
+            if Instant::now() < *expires_at {
+                return Ok(token.clone());
+            }
+        }
+
+        let setting = |value: &Option<String>, key: &str| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .with_context(|| format!("[calendar] {key} is not set"))
+        };
+        let form = [
+            (
+                "client_id",
+                setting(&self.config.google_client_id, "google_client_id")?,
+            ),
+            (
+                "client_secret",
+                setting(&self.config.google_client_secret, "google_client_secret")?,
+            ),
+            (
+                "refresh_token",
+                setting(&self.config.google_refresh_token, "google_refresh_token")?,
+            ),
+            ("grant_type", "refresh_token".to_string()),
+        ];
+        let resp = self
+            .client()
+            .post(GOOGLE_TOKEN_URL)
+            .form(&form)
+            .send()
+            .await
+            .context("Google token refresh failed")?;
+        let body: Value = checked(resp, "Google OAuth").await?.json().await?;
+        let token = body
+            .get("access_token")
+            .and_then(Value::as_str)
+            .context("Google OAuth response has no access_token")?
+            .to_string();
+        let lifetime = body
+            .get("expires_in")
+            .and_then(Value::as_u64)
+            .unwrap_or(3600)
+            .saturating_sub(60);
+        *cached = Some((
+            token.clone(),
+            Instant::now() + std::time::Duration::from_secs(lifetime),
+        ));
+        Ok(token)
+    }
+
+    fn google_events_url(&self) -> String {
+        format!(
+            "{GOOGLE_CALENDAR_API}/calendars/{}/events",
+            urlencoding::encode(&self.config.google_calendar_id)
+        )
+    }
+
+    fn google_event_url(&self, id: &str) -> String {
+        format!("{}/{}", self.google_events_url(), urlencoding::encode(id))
+    }
+
+    async fn google_list(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        query: Option<&str>,
+    ) -> Result<Vec<Event>> {
+        let mut params = vec![
+            ("timeMin", from.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            ("timeMax", to.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            ("singleEvents", "true".into()),
+            ("orderBy", "startTime".into()),
+            ("maxResults", "250".into()),
+        ];
+        if let Some(query) = query {
+            params.push(("q", query.to_string()));
+        }
+        let resp = self
+            .client()
+            .get(self.google_events_url())
+            .query(&params)
+            .bearer_auth(self.google_access_token().await?)
+            .send()
+            .await
+            .context("Google Calendar request failed")?;
+        let body: Value = checked(resp, "Google Calendar").await?.json().await?;
+        Ok(body
+            .get("items")
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(parse_google_event).collect())
+            .unwrap_or_default())
+    }
+
+    async fn google_create(&self, event: &Event) -> Result<String> {
+        let resp = self
+            .client()
+            .post(self.google_events_url())
+            .bearer_auth(self.google_access_token().await?)
+            .json(&google_event_body(event))
+            .send()
+            .await
+            .context("Google Calendar request failed")?;
+        let body: Value = checked(resp, "Google Calendar").await?.json().await?;
+        body.get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .context("Google Calendar response has no event id")
+    }
+
+    async fn google_update(&self, id: &str, changes: EventChanges, zone: Zone) -> Result<Event> {
+        let token = self.google_access_token().await?;
+        let resp = self
+            .client()
+            .get(self.google_event_url(id))
+            .bearer_auth(&token)
+            .send()
+            .await
+            .context("Google Calendar request failed")?;
+        let current: Value = checked(resp, "Google Calendar").await?.json().await?;
+        let mut event =
+            parse_google_event(&current).with_context(|| format!("Event '{id}' has no start"))?;
+        changes.apply(&mut event, self.default_duration());
+        check_order(&event, zone)?;
+
+        let resp = self
+            .client()
+            .patch(self.google_event_url(id))
+            .bearer_auth(&token)
+            .json(&google_event_body(&event))
+            .send()
+            .await
+            .context("Google Calendar request failed")?;
+        checked(resp, "Google Calendar").await?;
+        Ok(event)
+    }
+}
+
+#[async_trait]
+impl Tool for CalendarTool {
+    fn name(&self) -> &str {
+        "calendar"
+    }
+
+    fn description(&self) -> &str {
+        "Manage calendar events: list upcoming events, create events, update them (rename, \
+         move, change location) or delete them. Times without an offset use the calendar's \
+         timezone; dates without a time make all-day events. List first to find an event_id."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "create", "update", "delete"],
+                    "description": "What to do"
+                },
+                "start": {
+                    "type": "string",
+                    "description": "Start time: '2026-10-17T15:00' (calendar timezone), RFC 3339, or a date for all-day events. For 'list', the range start (default: now)"
+                },
+                "end": {
+                    "type": "string",
+                    "description": "End time in the same formats. For 'create', defaults to start plus the default duration; for 'list', the range end (default: 7 days after start)"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Event title (required for 'create')"
+                },
+                "location": {
+                    "type": "string",
+                    "description": "Event location; an empty string clears it on 'update'"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "Event notes; an empty string clears them on 'update'"
+                },
+                "event_id": {
+                    "type": "string",
+                    "description": "ID from 'list' output (required for 'update' and 'delete')"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Only list events whose title, location or notes contain this text"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum events to list (default: 20, max: 100)"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let action = str_arg(&args, "action")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+        let writes = match action {
+            "list" => false,
+            "create" | "update" | "delete" => true,
+            other => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!(
+                        "Unknown action '{other}'. Use: list, create, update, delete"
+                    )),
+                })
+            }
+        };
+
+        if writes && !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        let result = match action {
+            "list" => self.list(&args).await,
+            "create" => self.create(&args).await,
+            "update" => self.update(&args).await,
+            _ => self.delete(&args).await,
+        };
+        match result {
+            Ok(output) => Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    fn berlin() -> Zone {
+        Zone::Named(chrono_tz::Europe::Berlin)
+    }
+
+    fn utc(text: &str) -> EventTime {
+        EventTime::At(DateTime::parse_from_rfc3339(text).unwrap().to_utc())
+    }
+
+    const SERIES: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+UID:abc-123\r\n\
+SEQUENCE:2\r\n\
+SUMMARY:Planning\\, Q4 \r\n review\r\n\
+DTSTART;TZID=/mozilla.org/20050126_1/Europe/Berlin:20261017T150000\r\n\
+DURATION:PT1H30M\r\n\
+RRULE:FREQ=WEEKLY\r\n\
+BEGIN:VALARM\r\n\
+ACTION:DISPLAY\r\n\
+DESCRIPTION:Reminder\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Offsite\r\n\
+DTSTART;VALUE=DATE:20261020\r\n\
+DTEND;VALUE=DATE:20261022\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn parse_events_reads_timezones_durations_and_escapes() {
+        let events = parse_events(SERIES, "/cal/abc-123.ics", Zone::Local);
+        assert_eq!(events.len(), 2);
+
+        let planning = &events[0];
+        assert_eq!(planning.title, "Planning, Q4 review");
+        assert_eq!(planning.start, utc("2026-10-17T13:00:00Z"));
+        assert_eq!(planning.end, utc("2026-10-17T14:30:00Z"));
+        assert_eq!(planning.description, None, "alarm text is not the event's");
+        assert_eq!(
+            describe(planning, berlin()),
+            "Sat 2026-10-17 15:00-16:30: Planning, Q4 review (id: /cal/abc-123.ics)"
+        );
+
+        let offsite = &events[1];
+        assert_eq!(
+            describe(offsite, berlin()),
+            "Tue 2026-10-20 to Wed 2026-10-21 (all day): Offsite (id: /cal/abc-123.ics)"
+        );
+    }
+
+    #[test]
+    fn update_calendar_rewrites_only_the_main_event() {
+        let mut event = parse_events(SERIES, "x", berlin()).remove(0);
+        EventChanges {
+            start: Some(parse_time("2026-10-17T16:00", berlin()).unwrap()),
+            location: Some("Room 4; 2nd floor".into()),
+            ..EventChanges::default()
+        }
+        .apply(&mut event, Duration::minutes(60));
+        assert_eq!(event.end, utc("2026-10-17T15:30:00Z"), "length is kept");
+
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 9, 0, 0).unwrap();
+        let updated = update_calendar(SERIES, &event, now);
+        let lines = unfold(&updated);
+        assert!(lines.contains(&"UID:abc-123".to_string()));
+        assert!(lines.contains(&"RRULE:FREQ=WEEKLY".to_string()));
+        assert!(lines.contains(&"SEQUENCE:3".to_string()));
+        assert!(lines.contains(&"DTSTART:20261017T140000Z".to_string()));
+        assert!(lines.contains(&"DTEND:20261017T153000Z".to_string()));
+        assert!(lines.contains(&"LOCATION:Room 4\\; 2nd floor".to_string()));
+        assert!(lines.contains(&"DESCRIPTION:Reminder".to_string()));
+        assert!(!lines.iter().any(|line| line.starts_with("DURATION")));
+        // The second event is untouched.
+        assert!(lines.contains(&"DTEND;VALUE=DATE:20261022".to_string()));
+        assert_eq!(parse_events(&updated, "x", berlin())[0], event);
+    }
+
+    #[test]
+    fn parse_time_accepts_dates_offsets_and_wall_clock() {
+        assert_eq!(
+            parse_time("2026-10-17", berlin()).unwrap(),
+            EventTime::Date(NaiveDate::from_ymd_opt(2026, 10, 17).unwrap())
+        );
+        assert_eq!(
+            parse_time("2026-10-17T15:00:00-04:00", berlin()).unwrap(),
+            utc("2026-10-17T19:00:00Z")
+        );
+        assert_eq!(
+            parse_time("2026-12-01 09:30", berlin()).unwrap(),
+            utc("2026-12-01T08:30:00Z")
+        );
+        assert!(parse_time("tomorrow at 3", berlin()).is_err());
+
+        let event = Event {
+            id: String::new(),
+            title: "x".into(),
+            start: utc("2026-10-17T15:00:00Z"),
+            end: EventTime::Date(NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()),
+            location: None,
+            description: None,
+        };
+        assert!(check_order(&event, berlin()).is_err());
+    }
+
+    #[test]
+    fn multistatus_and_event_urls() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response>
+    <d:href>/dav/calendars/alice/work/a%20b.ics</d:href>
+    <d:propstat><d:prop>
+      <cal:calendar-data>BEGIN:VEVENT&#13;
+SUMMARY:Fish &amp; chips&#13;
+END:VEVENT</cal:calendar-data>
+    </d:prop></d:propstat>
+  </d:response>
+  <d:response><d:href>/dav/calendars/alice/work/</d:href><d:propstat/></d:response>
+</d:multistatus>"#;
+        let responses = xml_elements(xml, "response");
+        assert_eq!(responses.len(), 2);
+        assert_eq!(
+            xml_text(xml_elements(responses[0], "href")[0]),
+            "/dav/calendars/alice/work/a%20b.ics"
+        );
+        let data = xml_text(xml_elements(responses[0], "calendar-data")[0]);
+        assert_eq!(unfold(&data)[1], "SUMMARY:Fish & chips");
+        assert!(xml_elements(responses[1], "calendar-data").is_empty());
+
+        let collection = Url::parse("https://dav.example.com/dav/calendars/alice/work/").unwrap();
+        assert!(caldav_event_url(&collection, "/dav/calendars/alice/work/a%20b.ics").is_ok());
+        assert!(caldav_event_url(&collection, "../home/secret.ics").is_err());
+        assert!(caldav_event_url(&collection, "https://evil.example/x.ics").is_err());
+        assert!(caldav_event_url(&collection, "").is_err());
+    }
+
+    #[tokio::test]
+    async fn read_only_autonomy_can_list_but_not_create() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tool = CalendarTool::new(security, CalendarConfig::default());
+
+        let created = tool
+            .execute(json!({"action": "create", "title": "Sync", "start": "2026-10-17T15:00"}))
+            .await
+            .unwrap();
+        assert_eq!(
+            created.error.as_deref(),
+            Some("Action blocked: autonomy is read-only")
+        );
+
+        let listed = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert_eq!(
+            listed.error.as_deref(),
+            Some("[calendar] caldav_url is not set")
+        );
+    }
+}
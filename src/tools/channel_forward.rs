@@ -0,0 +1,580 @@
+use super::http_request::{
+    extract_host, host_matches_allowlist, is_private_or_local_host, normalize_allowed_domains,
+};
+use super::traits::{Tool, ToolResult};
+use crate::channels::attachment::{file_name_for_url, is_local_path, AttachmentKind};
+use crate::config::Config;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const FORWARD_DOWNLOAD_TIMEOUT_SECS: u64 = 120;
+/// Hard cap on files fetched from a source channel.
+const MAX_FORWARD_DOWNLOAD_BYTES: u64 = 100 * 1024 * 1024;
+const FORWARDED_FILES_DIR: &str = "forwarded_files";
+const SLACK_FILES_ORIGIN: &str = "https://files.slack.com";
+const MB: u64 = 1024 * 1024;
+
+/// Upload limits of a delivery channel, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct UploadLimits {
+    /// Largest file the channel accepts as a document.
+    file: u64,
+    /// Largest image sent as an inline photo; bigger images go as documents.
+    inline_image: u64,
+}
+
+fn upload_limits(channel: &str) -> Option<UploadLimits> {
+    match channel {
+        "telegram" => Some(UploadLimits {
+            file: 50 * MB,
+            inline_image: 10 * MB,
+        }),
+        "discord" => Some(UploadLimits {
+            file: 10 * MB,
+            inline_image: 10 * MB,
+        }),
+        "slack" => Some(UploadLimits {
+            file: 1024 * MB,
+            inline_image: 1024 * MB,
+        }),
+        "mattermost" => Some(UploadLimits {
+            file: 100 * MB,
+            inline_image: 100 * MB,
+        }),
+        _ => None,
+    }
+}
+
+/// Pick the attachment kind for a file from its MIME type. Image formats
+/// chat clients do not render inline (HEIC, TIFF, SVG, ...) are sent as
+/// documents so the recipient still gets the original file.
+fn classify_file(path: &Path) -> AttachmentKind {
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    match (mime.type_().as_str(), mime.subtype().as_str()) {
+        ("image", "jpeg" | "png" | "gif" | "webp") => AttachmentKind::Image,
+        ("audio", _) => AttachmentKind::Audio,
+        ("video", _) => AttachmentKind::Video,
+        _ => AttachmentKind::Document,
+    }
+}
+
+/// Adapt an attachment to the target channel's limits.
+fn plan_delivery(channel: &str, kind: AttachmentKind, size: u64) -> Result<AttachmentKind, String> {
+    let limits = upload_limits(channel)
+        .ok_or_else(|| format!("Forwarding files to '{channel}' is not supported"))?;
+    if size > limits.file {
+        return Err(format!(
+            "File is {:.1} MB; {channel} accepts at most {} MB",
+            size as f64 / MB as f64,
+            limits.file / MB
+        ));
+    }
+    Ok(match kind {
+        AttachmentKind::Image if size > limits.inline_image => AttachmentKind::Document,
+        AttachmentKind::Voice if channel != "telegram" => AttachmentKind::Audio,
+        other => other,
+    })
+}
+
+/// True when `url` has exactly the scheme, host and port of `origin`.
+fn same_origin(url: &str, origin: &str) -> bool {
+    let (Ok(url), Ok(origin)) = (reqwest::Url::parse(url), reqwest::Url::parse(origin)) else {
+        return false;
+    };
+    url.scheme() == origin.scheme()
+        && url.host_str().is_some()
+        && url.host_str() == origin.host_str()
+        && url.port_or_known_default() == origin.port_or_known_default()
+}
+
+/// File resolved from the forwarding source.
+struct SourceFile {
+    path: PathBuf,
+    size: u64,
+    /// Original URL when the file was downloaded, used as a link fallback.
+    url: Option<String>,
+}
+
+/// Forward a file received on one channel to a recipient on another.
+pub struct ChannelForwardTool {
+    config: Arc<Config>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl ChannelForwardTool {
+    pub fn new(config: Arc<Config>, security: Arc<SecurityPolicy>) -> Self {
+        Self { config, security }
+    }
+
+    /// Bearer token for file URLs that the source channel only serves to
+    /// authenticated bots.
+    fn source_auth_token(&self, source_channel: Option<&str>, url: &str) -> Option<String> {
+        let channels = &self.config.channels_config;
+        match source_channel? {
+            "slack" if same_origin(url, SLACK_FILES_ORIGIN) => {
+                channels.slack.as_ref().map(|sl| sl.bot_token.clone())
+            }
+            "mattermost" => channels
+                .mattermost
+                .as_ref()
+                .filter(|mm| same_origin(url, &mm.url))
+                .map(|mm| mm.bot_token.clone()),
+            _ => None,
+        }
+    }
+
+    /// Downloads are limited to the file hosts of configured source channels
+    /// and to `[http_request].allowed_domains`; private and local hosts are
+    /// refused unless they are a configured channel's own origin.
+    fn validate_download_url(&self, url: &str) -> Result<(), String> {
+        let channels = &self.config.channels_config;
+        let source_origins = channels
+            .slack
+            .as_ref()
+            .map(|_| SLACK_FILES_ORIGIN.to_string())
+            .into_iter()
+            .chain(channels.mattermost.as_ref().map(|mm| mm.url.clone()));
+        if source_origins
+            .into_iter()
+            .any(|origin| same_origin(url, &origin))
+        {
+            return Ok(());
+        }
+
+        let host = extract_host(url).map_err(|e| e.to_string())?;
+        if is_private_or_local_host(&host) {
+            return Err(format!("Blocked local/private host: {host}"));
+        }
+        let allowed = normalize_allowed_domains(self.config.http_request.allowed_domains.clone());
+        if !host_matches_allowlist(&host, &allowed) {
+            return Err(format!(
+                "Host '{host}' is not a configured channel file host or in http_request.allowed_domains"
+            ));
+        }
+        Ok(())
+    }
+
+    async fn resolve_local(&self, path: &str) -> Result<SourceFile, String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        let full_path = self.security.workspace_dir.join(path);
+        let resolved = tokio::fs::canonicalize(&full_path)
+            .await
+            .map_err(|e| format!("Failed to resolve file path: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(self.security.resolved_path_violation_message(&resolved));
+        }
+        let meta = tokio::fs::metadata(&resolved)
+            .await
+            .map_err(|e| format!("Failed to read file metadata: {e}"))?;
+        if !meta.is_file() {
+            return Err(format!("Not a file: {path}"));
+        }
+        Ok(SourceFile {
+            path: resolved,
+            size: meta.len(),
+            url: None,
+        })
+    }
+
+    async fn download(
+        &self,
+        url: &str,
+        source_channel: Option<&str>,
+    ) -> Result<SourceFile, String> {
+        self.validate_download_url(url)?;
+        // Redirects are not followed so an allowed host cannot bounce the
+        // download to an internal address.
+        let builder = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(
+                FORWARD_DOWNLOAD_TIMEOUT_SECS,
+            ))
+            .connect_timeout(std::time::Duration::from_secs(10))
+            .redirect(reqwest::redirect::Policy::none());
+        let client = crate::config::apply_runtime_proxy_to_builder(builder, "tool.channel_forward")
+            .build()
+            .map_err(|e| format!("Failed to build download client: {e}"))?;
+        let mut request = client.get(url);
+        if let Some(token) = self.source_auth_token(source_channel, url) {
+            request = request.bearer_auth(token);
+        }
+        let mut response = request
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download {url}: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "Download of {url} failed with status {}",
+                response.status()
+            ));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_FORWARD_DOWNLOAD_BYTES)
+        {
+            return Err(format!(
+                "File exceeds the {} MB forwarding limit",
+                MAX_FORWARD_DOWNLOAD_BYTES / MB
+            ));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to download {url}: {e}"))?
+        {
+            bytes.extend_from_slice(&chunk);
+            if bytes.len() as u64 > MAX_FORWARD_DOWNLOAD_BYTES {
+                return Err(format!(
+                    "File exceeds the {} MB forwarding limit",
+                    MAX_FORWARD_DOWNLOAD_BYTES / MB
+                ));
+            }
+        }
+
+        let dir = self.security.workspace_dir.join(FORWARDED_FILES_DIR);
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+        let path = dir.join(format!(
+            "{stamp}_{}",
+            file_name_for_url(url, content_type.as_deref())
+        ));
+        tokio::fs::write(&path, &bytes)
+            .await
+            .map_err(|e| format!("Failed to save {}: {e}", path.display()))?;
+
+        Ok(SourceFile {
+            path,
+            size: bytes.len() as u64,
+            url: Some(url.to_string()),
+        })
+    }
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+    }
+}
+
+#[async_trait]
+impl Tool for ChannelForwardTool {
+    fn name(&self) -> &str {
+        "channel_forward"
+    }
+
+    fn description(&self) -> &str {
+        "Forward a file to a recipient on another channel (e.g. a PDF received on Slack to a Telegram group). Accepts a workspace path or a URL from the source channel; adapts the upload to the target's size and format limits."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "source": {
+                    "type": "string",
+                    "description": "File to forward: a local path from an inbound attachment or an http(s) URL"
+                },
+                "source_channel": {
+                    "type": "string",
+                    "description": "Channel the file came from (slack, mattermost, ...); used to authenticate downloads of private file URLs"
+                },
+                "channel": {
+                    "type": "string",
+                    "enum": ["telegram", "discord", "slack", "mattermost"],
+                    "description": "Target channel"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Recipient on the target channel (chat ID, channel ID, ...)"
+                },
+                "caption": {
+                    "type": "string",
+                    "description": "Optional text sent with the file"
+                }
+            },
+            "required": ["source", "channel", "to"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let arg = |key: &str| {
+            args.get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        let source = arg("source").ok_or_else(|| anyhow::anyhow!("Missing 'source' parameter"))?;
+        let channel = arg("channel")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'channel' parameter"))?
+            .to_ascii_lowercase();
+        let to = arg("to").ok_or_else(|| anyhow::anyhow!("Missing 'to' parameter"))?;
+        let source_channel = arg("source_channel").map(str::to_ascii_lowercase);
+        let caption = arg("caption");
+
+        if !self.security.can_act() {
+            return Ok(failure("Action blocked: autonomy is read-only"));
+        }
+        if upload_limits(&channel).is_none() {
+            return Ok(failure(format!(
+                "Forwarding files to '{channel}' is not supported"
+            )));
+        }
+        if !self.security.record_action() {
+            return Ok(failure("Action blocked: rate limit exceeded"));
+        }
+
+        let file = if is_local_path(source) {
+            self.resolve_local(source.strip_prefix("file://").unwrap_or(source))
+                .await
+        } else {
+            self.download(source, source_channel.as_deref()).await
+        };
+        let file = match file {
+            Ok(file) => file,
+            Err(e) => return Ok(failure(e)),
+        };
+
+        let content = match plan_delivery(&channel, classify_file(&file.path), file.size) {
+            Ok(kind) => {
+                let marker = format!("[{}:{}]", kind.marker_name(), file.path.display());
+                caption.map_or(marker.clone(), |c| format!("{c}\n{marker}"))
+            }
+            // Too large to re-upload, but the recipient can still fetch the original.
+            Err(reason) => match &file.url {
+                Some(url) => {
+                    let note = format!("{reason}; sharing the link instead: {url}");
+                    caption.map_or(note.clone(), |c| format!("{c}\n{note}"))
+                }
+                None => return Ok(failure(reason)),
+            },
+        };
+
+        if let Err(e) =
+            crate::cron::scheduler::deliver_announcement(&self.config, &channel, to, &content).await
+        {
+            return Ok(failure(format!("Delivery to {channel}:{to} failed: {e}")));
+        }
+
+        let name = file
+            .path
+            .file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        Ok(ToolResult {
+            success: true,
+            output: format!("Forwarded {name} to {channel}:{to}"),
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn make_tool(tmp: &TempDir, autonomy: AutonomyLevel) -> ChannelForwardTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        ChannelForwardTool::new(Arc::new(config), security)
+    }
+
+    #[test]
+    fn classify_file_maps_mime_types() {
+        assert_eq!(classify_file(Path::new("a.png")), AttachmentKind::Image);
+        assert_eq!(classify_file(Path::new("a.heic")), AttachmentKind::Document);
+        assert_eq!(classify_file(Path::new("a.mp3")), AttachmentKind::Audio);
+        assert_eq!(classify_file(Path::new("a.mp4")), AttachmentKind::Video);
+        assert_eq!(classify_file(Path::new("a.pdf")), AttachmentKind::Document);
+    }
+
+    #[test]
+    fn plan_delivery_adapts_to_target_limits() {
+        assert_eq!(
+            plan_delivery("telegram", AttachmentKind::Image, 2 * MB),
+            Ok(AttachmentKind::Image)
+        );
+        assert_eq!(
+            plan_delivery("telegram", AttachmentKind::Image, 20 * MB),
+            Ok(AttachmentKind::Document)
+        );
+        assert_eq!(
+            plan_delivery("slack", AttachmentKind::Voice, MB),
+            Ok(AttachmentKind::Audio)
+        );
+        let err = plan_delivery("discord", AttachmentKind::Document, 30 * MB).unwrap_err();
+        assert!(err.contains("discord accepts at most 10 MB"));
+        assert!(plan_delivery("irc", AttachmentKind::Document, 1).is_err());
+    }
+
+    #[test]
+    fn source_auth_only_applies_to_source_channel_hosts() {
+        let tmp = TempDir::new().unwrap();
+        let mut tool = make_tool(&tmp, AutonomyLevel::Full);
+        let mut config = (*tool.config).clone();
+        config.channels_config.slack = Some(crate::config::schema::SlackConfig {
+            bot_token: "xoxb-test".into(),
+            app_token: None,
+            channel_id: None,
+            allowed_users: vec![],
+            mention_only: false,
+            trigger_prefix: None,
+            thread_replies: None,
+            signing_secret: None,
+            stream_mode: crate::config::StreamMode::default(),
+            draft_update_interval_ms: 1000,
+        });
+        tool.config = Arc::new(config);
+
+        assert_eq!(
+            tool.source_auth_token(Some("slack"), "https://files.slack.com/a.pdf"),
+            Some("xoxb-test".into())
+        );
+        assert_eq!(
+            tool.source_auth_token(Some("slack"), "https://evil.example.com/a.pdf"),
+            None
+        );
+        assert_eq!(
+            tool.source_auth_token(None, "https://files.slack.com/a.pdf"),
+            None
+        );
+        assert_eq!(
+            tool.source_auth_token(Some("slack"), "https://files.slack.com.evil.example/a.pdf"),
+            None
+        );
+        assert_eq!(
+            tool.source_auth_token(Some("slack"), "https://files.slack.com:8443/a.pdf"),
+            None
+        );
+    }
+
+    #[test]
+    fn same_origin_compares_scheme_host_and_port() {
+        assert!(same_origin(
+            "https://chat.example.com/api/v4/files/1",
+            "https://chat.example.com/"
+        ));
+        assert!(same_origin(
+            "https://chat.example.com:443/f",
+            "https://chat.example.com"
+        ));
+        assert!(!same_origin(
+            "https://chat.example.com.evil.io/f",
+            "https://chat.example.com"
+        ));
+        assert!(!same_origin(
+            "https://chat.example.com@evil.io/f",
+            "https://chat.example.com"
+        ));
+        assert!(!same_origin(
+            "http://chat.example.com/f",
+            "https://chat.example.com"
+        ));
+        assert!(!same_origin(
+            "https://chat.example.com:8065/f",
+            "https://chat.example.com"
+        ));
+    }
+
+    #[test]
+    fn downloads_are_limited_to_allowed_public_hosts() {
+        let tmp = TempDir::new().unwrap();
+        let mut tool = make_tool(&tmp, AutonomyLevel::Full);
+        let mut config = (*tool.config).clone();
+        config.http_request.allowed_domains = vec!["cdn.example.com".into()];
+        tool.config = Arc::new(config);
+
+        assert!(tool
+            .validate_download_url("https://cdn.example.com/a.pdf")
+            .is_ok());
+        assert!(tool
+            .validate_download_url("https://files.slack.com/a.pdf")
+            .unwrap_err()
+            .contains("allowed_domains"));
+        assert!(tool
+            .validate_download_url("http://169.254.169.254/latest/meta-data")
+            .unwrap_err()
+            .contains("local/private"));
+        assert!(tool
+            .validate_download_url("http://10.0.0.5/a.pdf")
+            .unwrap_err()
+            .contains("local/private"));
+        assert!(tool
+            .validate_download_url("http://localhost:8080/a.pdf")
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_target_channel() {
+        let tmp = TempDir::new().unwrap();
+        let tool = make_tool(&tmp, AutonomyLevel::Full);
+        let result = tool
+            .execute(json!({"source": "a.pdf", "channel": "irc", "to": "#ops"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not supported"));
+    }
+
+    #[tokio::test]
+    async fn blocks_in_read_only_mode() {
+        let tmp = TempDir::new().unwrap();
+        let tool = make_tool(&tmp, AutonomyLevel::ReadOnly);
+        let result = tool
+            .execute(json!({"source": "a.pdf", "channel": "telegram", "to": "123"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let tool = make_tool(&tmp, AutonomyLevel::Full);
+        let result = tool
+            .execute(json!({"source": "/etc/passwd", "channel": "telegram", "to": "123"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn reports_delivery_failure_for_unconfigured_channel() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("report.pdf"), b"%PDF-1.4").unwrap();
+        let tool = make_tool(&tmp, AutonomyLevel::Full);
+        let result = tool
+            .execute(json!({"source": "report.pdf", "channel": "telegram", "to": "123"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("telegram channel not configured"));
+    }
+}
@@ -0,0 +1,302 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::DesktopConfig;
+use crate::security::SecurityPolicy;
+use crate::util::truncate_with_ellipsis;
+use async_trait::async_trait;
+use serde_json::json;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Maximum time to wait for a clipboard command to complete.
+const CLIPBOARD_TIMEOUT_SECS: u64 = 10;
+
+/// A clipboard command: program and arguments.
+type ClipboardCommand = (&'static str, &'static [&'static str]);
+
+/// Commands that print the clipboard, in order of preference.
+///
+/// macOS: `pbpaste`
+/// Linux: `wl-paste` under Wayland, then `xclip`, then `xsel`.
+/// Windows: PowerShell `Get-Clipboard`.
+fn paste_commands() -> Vec<ClipboardCommand> {
+    if cfg!(target_os = "macos") {
+        vec![("pbpaste", &[])]
+    } else if cfg!(target_os = "windows") {
+        vec![(
+            "powershell",
+            &["-NoProfile", "-Command", "Get-Clipboard -Raw"],
+        )]
+    } else {
+        let mut commands: Vec<ClipboardCommand> = vec![
+            ("xclip", &["-selection", "clipboard", "-o"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ];
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.insert(0, ("wl-paste", &["--no-newline"]));
+        }
+        commands
+    }
+}
+
+/// Commands that replace the clipboard with their stdin, in order of
+/// preference.
+fn copy_commands() -> Vec<ClipboardCommand> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[])]
+    } else if cfg!(target_os = "windows") {
+        vec![("clip", &[])]
+    } else {
+        let mut commands: Vec<ClipboardCommand> = vec![
+            ("xclip", &["-selection", "clipboard", "-i"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ];
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.insert(0, ("wl-copy", &[]));
+        }
+        commands
+    }
+}
+
+fn no_clipboard_tool() -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(
+            "No clipboard tool found. Install wl-clipboard (Wayland), xclip or xsel.".into(),
+        ),
+    }
+}
+
+/// Run the first installed command of `commands`, feeding it `input` on
+/// stdin when given. Returns `Ok(None)` when none is installed.
+///
+/// When writing, stdout is discarded: `xclip` and `wl-copy` fork a process
+/// that keeps serving the selection and would hold a captured pipe open.
+async fn run_first(
+    commands: &[ClipboardCommand],
+    input: Option<&str>,
+) -> anyhow::Result<Option<std::process::Output>> {
+    for (program, args) in commands {
+        let mut command = tokio::process::Command::new(program);
+        command.args(*args).kill_on_drop(true);
+        if input.is_some() {
+            command
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+        } else {
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+        }
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => anyhow::bail!("Failed to run {program}: {e}"),
+        };
+        if let (Some(text), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(text.as_bytes()).await?;
+        }
+        let output = tokio::time::timeout(
+            Duration::from_secs(CLIPBOARD_TIMEOUT_SECS),
+            child.wait_with_output(),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("{program} timed out after {CLIPBOARD_TIMEOUT_SECS}s"))??;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{program} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        return Ok(Some(output));
+    }
+    Ok(None)
+}
+
+/// Tool for reading the system clipboard as text.
+pub struct ClipboardReadTool {
+    max_chars: usize,
+}
+
+impl ClipboardReadTool {
+    pub fn new(config: &DesktopConfig) -> Self {
+        Self {
+            max_chars: config.max_clipboard_chars,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ClipboardReadTool {
+    fn name(&self) -> &str {
+        "clipboard_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read the text currently on the system clipboard."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let output = match run_first(&paste_commands(), None).await {
+            Ok(Some(output)) => output,
+            Ok(None) => return Ok(no_clipboard_tool()),
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to read clipboard: {e}")),
+                })
+            }
+        };
+        let text = String::from_utf8_lossy(&output.stdout);
+        Ok(ToolResult {
+            success: true,
+            output: if text.is_empty() {
+                "Clipboard is empty.".into()
+            } else {
+                truncate_with_ellipsis(&text, self.max_chars)
+            },
+            error: None,
+        })
+    }
+}
+
+/// Tool for replacing the system clipboard with text.
+pub struct ClipboardWriteTool {
+    security: Arc<SecurityPolicy>,
+    max_chars: usize,
+}
+
+impl ClipboardWriteTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: &DesktopConfig) -> Self {
+        Self {
+            security,
+            max_chars: config.max_clipboard_chars,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ClipboardWriteTool {
+    fn name(&self) -> &str {
+        "clipboard_write"
+    }
+
+    fn description(&self) -> &str {
+        "Copy text to the system clipboard, replacing its contents."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "Text to place on the clipboard"
+                }
+            },
+            "required": ["text"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let text = args
+            .get("text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'text' parameter"))?;
+
+        let chars = text.chars().count();
+        if chars > self.max_chars {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Text is {chars} characters; the clipboard limit is {}",
+                    self.max_chars
+                )),
+            });
+        }
+
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        match run_first(&copy_commands(), Some(text)).await {
+            Ok(Some(_)) => Ok(ToolResult {
+                success: true,
+                output: format!("Copied {chars} characters to the clipboard."),
+                error: None,
+            }),
+            Ok(None) => Ok(no_clipboard_tool()),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to write clipboard: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    fn config(max_clipboard_chars: usize) -> DesktopConfig {
+        DesktopConfig {
+            enabled: true,
+            max_clipboard_chars,
+            ..DesktopConfig::default()
+        }
+    }
+
+    #[test]
+    fn clipboard_commands_exist_for_platform() {
+        assert!(!paste_commands().is_empty());
+        assert!(!copy_commands().is_empty());
+    }
+
+    #[tokio::test]
+    async fn clipboard_write_blocked_in_readonly_mode() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let tool = ClipboardWriteTool::new(security, &config(100));
+        let result = tool.execute(json!({"text": "hello"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn clipboard_write_rejects_oversized_text() {
+        let tool = ClipboardWriteTool::new(Arc::new(SecurityPolicy::default()), &config(4));
+        let result = tool.execute(json!({"text": "hello"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("limit is 4"));
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
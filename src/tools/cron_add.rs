@@ -55,7 +55,8 @@ impl Tool for CronAddTool {
 
     fn description(&self) -> &str {
         "Create a scheduled cron job (shell or agent) with cron/at/every schedules. \
-         Use job_type='agent' with a prompt to run the AI agent on schedule. \
+         Use job_type='agent' with a prompt to run the AI agent on schedule, or \
+         job_type='message' with the exact text as prompt to send a fixed reminder. \
          To deliver output to a channel (Discord, Telegram, Slack, Mattermost), set \
          delivery={\"mode\":\"announce\",\"channel\":\"discord\",\"to\":\"<channel_id_or_chat_id>\"}. \
          This is the preferred tool for sending scheduled/delayed messages to users via channels."
@@ -70,7 +71,7 @@ impl Tool for CronAddTool {
                     "type": "object",
                     "description": "Schedule object: {kind:'cron',expr,tz?} | {kind:'at',at} | {kind:'every',every_ms}"
                 },
-                "job_type": { "type": "string", "enum": ["shell", "agent"] },
+                "job_type": { "type": "string", "enum": ["shell", "agent", "message"] },
                 "command": { "type": "string" },
                 "prompt": { "type": "string" },
                 "session_target": { "type": "string", "enum": ["isolated", "main"] },
@@ -80,8 +81,8 @@ impl Tool for CronAddTool {
                     "description": "Delivery config to send job output to a channel. Example: {\"mode\":\"announce\",\"channel\":\"discord\",\"to\":\"<channel_id>\"}",
                     "properties": {
                         "mode": { "type": "string", "enum": ["none", "announce"], "description": "Set to 'announce' to deliver output to a channel" },
-                        "channel": { "type": "string", "enum": ["telegram", "discord", "slack", "mattermost"], "description": "Channel type to deliver to" },
-                        "to": { "type": "string", "description": "Target: Discord channel ID, Telegram chat ID, Slack channel, etc." },
+                        "channel": { "type": "string", "enum": ["telegram", "discord", "slack", "mattermost", "email", "broadcast"], "description": "Channel type to deliver to; 'broadcast' delivers to every destination of a configured broadcast group" },
+                        "to": { "type": "string", "description": "Target: Discord channel ID, Telegram chat ID, Slack channel, email address, or broadcast group name" },
                         "best_effort": { "type": "boolean", "description": "If true, delivery failure does not fail the job" }
                     }
                 },
@@ -133,6 +134,7 @@ impl Tool for CronAddTool {
         let job_type = match args.get("job_type").and_then(serde_json::Value::as_str) {
             Some("agent") => JobType::Agent,
             Some("shell") => JobType::Shell,
+            Some("message") => JobType::Message,
             Some(other) => {
                 return Ok(ToolResult {
                     success: false,
@@ -246,6 +248,46 @@ impl Tool for CronAddTool {
                     delete_after_run,
                 )
             }
+            JobType::Message => {
+                let text = match args.get("prompt").and_then(serde_json::Value::as_str) {
+                    Some(text) if !text.trim().is_empty() => text,
+                    _ => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(
+                                "Missing 'prompt' (the text to send) for message job".to_string(),
+                            ),
+                        });
+                    }
+                };
+
+                let delivery = match args.get("delivery") {
+                    Some(v) => match serde_json::from_value::<DeliveryConfig>(v.clone()) {
+                        Ok(cfg) => cfg,
+                        Err(e) => {
+                            return Ok(ToolResult {
+                                success: false,
+                                output: String::new(),
+                                error: Some(format!("Invalid delivery config: {e}")),
+                            });
+                        }
+                    },
+                    None => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("Missing 'delivery' for message job".to_string()),
+                        });
+                    }
+                };
+
+                if let Some(blocked) = self.enforce_mutation_allowed("cron_add") {
+                    return Ok(blocked);
+                }
+
+                cron::add_message_job(&self.config, name, schedule, text, delivery)
+            }
         };
 
         match result {
@@ -482,4 +524,37 @@ mod tests {
             .unwrap_or_default()
             .contains("Missing 'prompt'"));
     }
+
+    #[tokio::test]
+    async fn message_job_requires_delivery() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "every", "every_ms": 60000 },
+                "job_type": "message",
+                "prompt": "Drink water"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap_or_default()
+            .contains("Missing 'delivery'"));
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "every", "every_ms": 60000 },
+                "job_type": "message",
+                "prompt": "Drink water",
+                "delivery": { "mode": "announce", "channel": "telegram", "to": "123" }
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("\"message\""));
+    }
 }
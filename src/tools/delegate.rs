@@ -411,6 +411,8 @@ impl DelegateTool {
                 None,
                 None,
                 &[],
+                None,
+                0,
             ),
         )
         .await;
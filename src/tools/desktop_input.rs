@@ -0,0 +1,528 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum time to wait for an input command to complete.
+const INPUT_TIMEOUT_SECS: u64 = 15;
+/// Longest text accepted by the `type` action.
+const MAX_TYPE_CHARS: usize = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Modifier {
+    Ctrl,
+    Alt,
+    Shift,
+    /// Command on macOS, Super/Windows key on Linux.
+    Meta,
+}
+
+/// A key chord such as `ctrl+shift+t`: modifiers plus a canonical key name
+/// (`return`, `escape`, `page_up`, `f5`, ...) or a single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KeyChord {
+    modifiers: Vec<Modifier>,
+    key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum InputAction {
+    MouseMove {
+        x: u32,
+        y: u32,
+    },
+    Click {
+        button: MouseButton,
+        at: Option<(u32, u32)>,
+        double: bool,
+    },
+    Type(String),
+    Key(KeyChord),
+}
+
+const NAMED_KEYS: &[&str] = &[
+    "return",
+    "escape",
+    "tab",
+    "backspace",
+    "delete",
+    "space",
+    "up",
+    "down",
+    "left",
+    "right",
+    "home",
+    "end",
+    "page_up",
+    "page_down",
+];
+
+fn parse_chord(raw: &str) -> anyhow::Result<KeyChord> {
+    let parts: Vec<String> = raw
+        .split('+')
+        .map(|part| part.trim().to_ascii_lowercase())
+        .collect();
+    let Some((key, modifier_names)) = parts.split_last() else {
+        anyhow::bail!("Empty key");
+    };
+    let mut modifiers = Vec::new();
+    for name in modifier_names {
+        modifiers.push(match name.as_str() {
+            "ctrl" | "control" => Modifier::Ctrl,
+            "alt" | "option" => Modifier::Alt,
+            "shift" => Modifier::Shift,
+            "cmd" | "command" | "super" | "meta" | "win" => Modifier::Meta,
+            other => anyhow::bail!("Unknown modifier '{other}'"),
+        });
+    }
+    let key = match key.as_str() {
+        "enter" => "return",
+        "esc" => "escape",
+        "del" => "delete",
+        "pageup" | "pgup" => "page_up",
+        "pagedown" | "pgdn" => "page_down",
+        other => other,
+    };
+    let is_function_key = key
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=12).contains(&n));
+    // Single characters keep their original case.
+    let original = raw.rsplit('+').next().unwrap_or_default().trim();
+    if original.chars().count() == 1 {
+        return Ok(KeyChord {
+            modifiers,
+            key: original.to_string(),
+        });
+    }
+    if !NAMED_KEYS.contains(&key) && !is_function_key {
+        anyhow::bail!("Unknown key '{original}'");
+    }
+    Ok(KeyChord {
+        modifiers,
+        key: key.to_string(),
+    })
+}
+
+fn coordinate(args: &serde_json::Value, name: &str) -> anyhow::Result<Option<u32>> {
+    match args.get(name) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .map(Some)
+            .ok_or_else(|| anyhow::anyhow!("'{name}' must be a non-negative integer")),
+    }
+}
+
+fn parse_action(args: &serde_json::Value) -> anyhow::Result<InputAction> {
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+    let position = match (coordinate(args, "x")?, coordinate(args, "y")?) {
+        (Some(x), Some(y)) => Some((x, y)),
+        (None, None) => None,
+        _ => anyhow::bail!("Provide both 'x' and 'y'"),
+    };
+    match action {
+        "mouse_move" => {
+            let (x, y) = position.ok_or_else(|| anyhow::anyhow!("Missing 'x'/'y' parameters"))?;
+            Ok(InputAction::MouseMove { x, y })
+        }
+        "click" => {
+            let button = match args.get("button").and_then(|v| v.as_str()) {
+                None | Some("left") => MouseButton::Left,
+                Some("middle") => MouseButton::Middle,
+                Some("right") => MouseButton::Right,
+                Some(other) => anyhow::bail!("Unknown button '{other}'"),
+            };
+            Ok(InputAction::Click {
+                button,
+                at: position,
+                double: args
+                    .get("double")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            })
+        }
+        "type" => {
+            let text = args
+                .get("text")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing 'text' parameter"))?;
+            if text.is_empty() || text.chars().count() > MAX_TYPE_CHARS {
+                anyhow::bail!("'text' must be 1 to {MAX_TYPE_CHARS} characters");
+            }
+            Ok(InputAction::Type(text.to_string()))
+        }
+        "key" => {
+            let keys = args
+                .get("keys")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing 'keys' parameter"))?;
+            Ok(InputAction::Key(parse_chord(keys)?))
+        }
+        other => anyhow::bail!("Unknown action '{other}'. Use mouse_move, click, type or key"),
+    }
+}
+
+/// `xdotool` arguments (Linux/X11).
+fn xdotool_args(action: &InputAction) -> Vec<String> {
+    match action {
+        InputAction::MouseMove { x, y } => {
+            vec!["mousemove".into(), x.to_string(), y.to_string()]
+        }
+        InputAction::Click { button, at, double } => {
+            let mut args = Vec::new();
+            if let Some((x, y)) = at {
+                args.extend(["mousemove".into(), x.to_string(), y.to_string()]);
+            }
+            let button = match button {
+                MouseButton::Left => "1",
+                MouseButton::Middle => "2",
+                MouseButton::Right => "3",
+            };
+            let repeat = if *double { "2" } else { "1" };
+            args.extend(["click", "--repeat", repeat, button].map(String::from));
+            args
+        }
+        InputAction::Type(text) => ["type", "--delay", "12", "--", text]
+            .map(String::from)
+            .to_vec(),
+        InputAction::Key(chord) => {
+            let mut names: Vec<&str> = chord
+                .modifiers
+                .iter()
+                .map(|modifier| match modifier {
+                    Modifier::Ctrl => "ctrl",
+                    Modifier::Alt => "alt",
+                    Modifier::Shift => "shift",
+                    Modifier::Meta => "super",
+                })
+                .collect();
+            let key = match chord.key.as_str() {
+                "return" => "Return".to_string(),
+                "escape" => "Escape".to_string(),
+                "tab" => "Tab".to_string(),
+                "backspace" => "BackSpace".to_string(),
+                "delete" => "Delete".to_string(),
+                "space" => "space".to_string(),
+                "page_up" => "Page_Up".to_string(),
+                "page_down" => "Page_Down".to_string(),
+                "up" | "down" | "left" | "right" | "home" | "end" => {
+                    let mut chars = chord.key.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                        .unwrap_or_default()
+                }
+                function if function.len() > 1 => function.to_ascii_uppercase(),
+                other => other.to_string(),
+            };
+            names.push(&key);
+            vec!["key".into(), "--".into(), names.join("+")]
+        }
+    }
+}
+
+/// `cliclick` arguments (macOS).
+fn cliclick_args(action: &InputAction) -> anyhow::Result<Vec<String>> {
+    Ok(match action {
+        InputAction::MouseMove { x, y } => vec![format!("m:{x},{y}")],
+        InputAction::Click { button, at, double } => {
+            let position = at.map_or_else(|| ".".to_string(), |(x, y)| format!("{x},{y}"));
+            let command = match (button, double) {
+                (MouseButton::Left, false) => "c",
+                (MouseButton::Left, true) => "dc",
+                (MouseButton::Right, false) => "rc",
+                (MouseButton::Middle, _) | (MouseButton::Right, true) => {
+                    anyhow::bail!("cliclick supports left, double-left and right clicks only")
+                }
+            };
+            vec![format!("{command}:{position}")]
+        }
+        InputAction::Type(text) => vec![format!("t:{text}")],
+        InputAction::Key(chord) => {
+            let modifiers = chord
+                .modifiers
+                .iter()
+                .map(|modifier| match modifier {
+                    Modifier::Ctrl => "ctrl",
+                    Modifier::Alt => "alt",
+                    Modifier::Shift => "shift",
+                    Modifier::Meta => "cmd",
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            let key = match chord.key.as_str() {
+                "escape" => "kp:esc".to_string(),
+                "backspace" => "kp:delete".to_string(),
+                "delete" => "kp:fwd-delete".to_string(),
+                "up" | "down" | "left" | "right" => format!("kp:arrow-{}", chord.key),
+                "page_up" => "kp:page-up".to_string(),
+                "page_down" => "kp:page-down".to_string(),
+                single if single.chars().count() == 1 => format!("t:{single}"),
+                named => format!("kp:{named}"),
+            };
+            if modifiers.is_empty() {
+                vec![key]
+            } else {
+                vec![format!("kd:{modifiers}"), key, format!("ku:{modifiers}")]
+            }
+        }
+    })
+}
+
+fn describe(action: &InputAction) -> String {
+    match action {
+        InputAction::MouseMove { x, y } => format!("Moved mouse to ({x}, {y})."),
+        InputAction::Click { button, at, double } => {
+            let kind = if *double { "Double-clicked" } else { "Clicked" };
+            let button = format!("{button:?}").to_ascii_lowercase();
+            match at {
+                Some((x, y)) => format!("{kind} {button} button at ({x}, {y})."),
+                None => format!("{kind} {button} button."),
+            }
+        }
+        InputAction::Type(text) => format!("Typed {} characters.", text.chars().count()),
+        InputAction::Key(chord) => {
+            let mut names: Vec<String> = chord
+                .modifiers
+                .iter()
+                .map(|modifier| format!("{modifier:?}").to_ascii_lowercase())
+                .collect();
+            names.push(chord.key.clone());
+            format!("Pressed {}.", names.join("+"))
+        }
+    }
+}
+
+/// Tool for basic mouse and keyboard automation.
+///
+/// Linux (X11): `xdotool`
+/// macOS: `cliclick`
+pub struct DesktopInputTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl DesktopInputTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+
+    /// Program and arguments for the current platform.
+    fn input_command(action: &InputAction) -> anyhow::Result<Option<(&'static str, Vec<String>)>> {
+        if cfg!(target_os = "macos") {
+            Ok(Some(("cliclick", cliclick_args(action)?)))
+        } else if cfg!(target_os = "linux") {
+            Ok(Some(("xdotool", xdotool_args(action))))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for DesktopInputTool {
+    fn name(&self) -> &str {
+        "desktop_input"
+    }
+
+    fn description(&self) -> &str {
+        "Control the local mouse and keyboard: move the pointer, click, type text or press a key chord. Take a screenshot first to find coordinates."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["mouse_move", "click", "type", "key"],
+                    "description": "Input to send"
+                },
+                "x": {
+                    "type": "integer",
+                    "description": "Screen X coordinate in pixels (mouse_move, optional for click)"
+                },
+                "y": {
+                    "type": "integer",
+                    "description": "Screen Y coordinate in pixels (mouse_move, optional for click)"
+                },
+                "button": {
+                    "type": "string",
+                    "enum": ["left", "middle", "right"],
+                    "description": "Mouse button for click (default: left)"
+                },
+                "double": {
+                    "type": "boolean",
+                    "description": "Double-click (default: false)"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Text to type (type)"
+                },
+                "keys": {
+                    "type": "string",
+                    "description": "Key chord for key, e.g. 'enter', 'ctrl+c', 'cmd+shift+t', 'f5'"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = match parse_action(&args) {
+            Ok(action) => action,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                })
+            }
+        };
+
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        let (program, command_args) = match Self::input_command(&action) {
+            Ok(Some(command)) => command,
+            Ok(None) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some("Desktop input not supported on this platform".into()),
+                })
+            }
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                })
+            }
+        };
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(INPUT_TIMEOUT_SECS),
+            tokio::process::Command::new(program)
+                .args(&command_args)
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await;
+
+        match result {
+            Ok(Ok(output)) if output.status.success() => Ok(ToolResult {
+                success: true,
+                output: describe(&action),
+                error: None,
+            }),
+            Ok(Ok(output)) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "{program} failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )),
+            }),
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(if program == "cliclick" {
+                    "cliclick not found. Install it with `brew install cliclick`.".into()
+                } else {
+                    "xdotool not found. Install xdotool (X11 sessions only).".into()
+                }),
+            }),
+            Ok(Err(e)) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to execute {program}: {e}")),
+            }),
+            Err(_) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("{program} timed out after {INPUT_TIMEOUT_SECS}s")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    #[test]
+    fn parses_key_chords() {
+        let chord = parse_chord("Ctrl+Shift+T").unwrap();
+        assert_eq!(chord.modifiers, vec![Modifier::Ctrl, Modifier::Shift]);
+        assert_eq!(chord.key, "T");
+        assert_eq!(parse_chord("enter").unwrap().key, "return");
+        assert_eq!(parse_chord("cmd+F5").unwrap().key, "f5");
+        assert!(parse_chord("hyper+a").is_err());
+        assert!(parse_chord("ctrl+banana").is_err());
+    }
+
+    #[test]
+    fn builds_xdotool_and_cliclick_arguments() {
+        let click =
+            parse_action(&json!({"action": "click", "x": 10, "y": 20, "double": true})).unwrap();
+        assert_eq!(
+            xdotool_args(&click),
+            ["mousemove", "10", "20", "click", "--repeat", "2", "1"]
+        );
+        assert_eq!(cliclick_args(&click).unwrap(), ["dc:10,20"]);
+
+        let key = parse_action(&json!({"action": "key", "keys": "cmd+page_down"})).unwrap();
+        assert_eq!(xdotool_args(&key), ["key", "--", "super+Page_Down"]);
+        assert_eq!(
+            cliclick_args(&key).unwrap(),
+            ["kd:cmd", "kp:page-down", "ku:cmd"]
+        );
+
+        let middle = parse_action(&json!({"action": "click", "button": "middle"})).unwrap();
+        assert!(cliclick_args(&middle).is_err());
+        assert!(parse_action(&json!({"action": "mouse_move", "x": 5})).is_err());
+        assert!(parse_action(&json!({"action": "mouse_move", "x": -1, "y": 2})).is_err());
+    }
+
+    #[tokio::test]
+    async fn desktop_input_blocked_in_readonly_mode() {
+        let tool = DesktopInputTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        }));
+        let result = tool
+            .execute(json!({"action": "type", "text": "hello"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
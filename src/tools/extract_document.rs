@@ -0,0 +1,749 @@
+use super::pdf_read::extract_pdf_text;
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::json;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+/// Maximum document size (50 MB).
+const MAX_DOCUMENT_BYTES: u64 = 50 * 1024 * 1024;
+/// Largest decompressed archive member read from a DOCX/XLSX package.
+const MAX_ENTRY_BYTES: u64 = 100 * 1024 * 1024;
+/// Default character limit returned to the LLM.
+const DEFAULT_MAX_CHARS: usize = 50_000;
+/// Hard ceiling regardless of what the caller requests.
+const MAX_OUTPUT_CHARS: usize = 200_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocumentFormat {
+    Pdf,
+    Docx,
+    Xlsx,
+    Text,
+}
+
+impl DocumentFormat {
+    /// Format from the file extension, falling back to the content for
+    /// extensionless inbound files.
+    fn detect(path: &Path, bytes: &[u8]) -> Option<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("pdf") => return Some(Self::Pdf),
+            Some("docx") => return Some(Self::Docx),
+            Some("xlsx") => return Some(Self::Xlsx),
+            Some("txt" | "md" | "csv" | "tsv" | "json" | "log") => return Some(Self::Text),
+            _ => {}
+        }
+        if bytes.starts_with(b"%PDF") {
+            return Some(Self::Pdf);
+        }
+        let package = open_package(bytes).ok()?;
+        if package.index_for_name("word/document.xml").is_some() {
+            Some(Self::Docx)
+        } else if package.index_for_name("xl/workbook.xml").is_some() {
+            Some(Self::Xlsx)
+        } else {
+            None
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Pdf => "PDF",
+            Self::Docx => "Word document",
+            Self::Xlsx => "Excel workbook",
+            Self::Text => "text",
+        }
+    }
+}
+
+// ── Office packages ─────────────────────────────────────────────
+
+/// A DOCX or XLSX file: a ZIP archive of XML parts.
+type Package<'a> = ZipArchive<Cursor<&'a [u8]>>;
+
+fn open_package(bytes: &[u8]) -> anyhow::Result<Package<'_>> {
+    ZipArchive::new(Cursor::new(bytes)).map_err(|e| anyhow!("Not a valid Office document: {e}"))
+}
+
+/// Part `name` decoded as UTF-8, or `None` when the package lacks it.
+fn read_part(package: &mut Package, name: &str) -> anyhow::Result<Option<String>> {
+    let part = match package.by_name(name) {
+        Ok(part) => part,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => bail!("Failed to open {name}: {e}"),
+    };
+    let mut out = Vec::new();
+    part.take(MAX_ENTRY_BYTES).read_to_end(&mut out)?;
+    Ok(Some(String::from_utf8_lossy(&out).into_owned()))
+}
+
+/// Unescaped value of the attribute with local name `name`, so `w:val` is
+/// found as `val` whatever prefix the producer chose.
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(Cow::into_owned)
+}
+
+/// Every element of `xml` with local name `name`, empty or not.
+fn elements_named(xml: &str, name: &str) -> anyhow::Result<Vec<BytesStart<'static>>> {
+    let mut reader = Reader::from_str(xml);
+    let mut elements = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == name.as_bytes() =>
+            {
+                elements.push(element.into_owned());
+            }
+            Event::Eof => return Ok(elements),
+            _ => {}
+        }
+    }
+}
+
+fn markdown_table(rows: &[Vec<String>]) -> String {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let cell = |row: &Vec<String>, index: usize| {
+        row.get(index)
+            .map(|value| value.replace('|', "\\|").replace('\n', " "))
+            .unwrap_or_default()
+    };
+    let mut out = String::new();
+    for (index, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = (0..width).map(|column| cell(row, column)).collect();
+        let _ = writeln!(out, "| {} |", cells.join(" | "));
+        if index == 0 {
+            let _ = writeln!(out, "|{}", " --- |".repeat(width));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+// ── Word ────────────────────────────────────────────────────────
+
+/// A `<w:p>` paragraph being read.
+#[derive(Default)]
+struct DocxParagraph {
+    text: String,
+    style: String,
+    numbered: bool,
+}
+
+impl DocxParagraph {
+    /// Apply an element met inside the paragraph. Tabs and breaks inside
+    /// run or paragraph properties are tab stop definitions, not content.
+    fn apply(&mut self, element: &BytesStart, in_properties: bool) {
+        match element.local_name().as_ref() {
+            b"pStyle" => self.style = attribute(element, "val").unwrap_or_default(),
+            b"numPr" => self.numbered = true,
+            b"tab" if !in_properties => self.text.push('\t'),
+            b"br" | b"cr" if !in_properties => self.text.push('\n'),
+            _ => {}
+        }
+    }
+
+    /// Markdown for the paragraph: `Heading1`..`Heading6` and `Title` styles
+    /// become headings and numbered or bulleted paragraphs list items.
+    fn into_markdown(self) -> Option<String> {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let level = if self.style == "Title" {
+            Some(1)
+        } else {
+            self.style
+                .strip_prefix("Heading")
+                .and_then(|level| level.parse::<usize>().ok())
+                .filter(|level| (1..=6).contains(level))
+        };
+        Some(match level {
+            Some(level) => format!("{} {text}", "#".repeat(level)),
+            None if self.numbered => format!("- {text}"),
+            None => text.to_string(),
+        })
+    }
+}
+
+/// Markdown for `word/document.xml`: paragraphs and tables in document
+/// order. Tables nested in a cell are flattened into that cell's text.
+fn docx_to_markdown(document: &str) -> anyhow::Result<String> {
+    let mut reader = Reader::from_str(document);
+    let mut blocks = Vec::new();
+    let mut paragraph: Option<DocxParagraph> = None;
+    let mut properties = 0usize;
+    let mut in_text = false;
+    let mut table_depth = 0usize;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut cell: Vec<String> = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"p" => paragraph = Some(DocxParagraph::default()),
+                b"pPr" | b"rPr" => properties += 1,
+                b"t" => in_text = properties == 0,
+                b"tbl" => table_depth += 1,
+                b"tr" if table_depth == 1 => rows.push(Vec::new()),
+                b"tc" if table_depth == 1 => cell.clear(),
+                _ => {
+                    if let Some(paragraph) = paragraph.as_mut() {
+                        paragraph.apply(&element, properties > 0);
+                    }
+                }
+            },
+            Event::Empty(element) => {
+                if let Some(paragraph) = paragraph.as_mut() {
+                    paragraph.apply(&element, properties > 0);
+                }
+            }
+            Event::Text(text) if in_text => {
+                if let Some(paragraph) = paragraph.as_mut() {
+                    paragraph.text.push_str(&text.unescape()?);
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"p" => {
+                    let Some(paragraph) = paragraph.take() else {
+                        continue;
+                    };
+                    if table_depth > 0 {
+                        let text = paragraph.text.trim();
+                        if !text.is_empty() {
+                            cell.push(text.to_string());
+                        }
+                    } else if let Some(markdown) = paragraph.into_markdown() {
+                        blocks.push(markdown);
+                    }
+                }
+                b"pPr" | b"rPr" => properties = properties.saturating_sub(1),
+                b"t" => in_text = false,
+                b"tc" if table_depth == 1 => {
+                    if let Some(row) = rows.last_mut() {
+                        row.push(cell.join(" "));
+                    }
+                }
+                b"tbl" => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 && !rows.is_empty() {
+                        blocks.push(markdown_table(&std::mem::take(&mut rows)));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => return Ok(blocks.join("\n\n")),
+            _ => {}
+        }
+    }
+}
+
+// ── Excel ───────────────────────────────────────────────────────
+
+/// Zero-based column of a cell reference such as `C7`.
+fn column_index(reference: &str) -> Option<usize> {
+    let letters: String = reference
+        .chars()
+        .take_while(char::is_ascii_alphabetic)
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    letters
+        .bytes()
+        .try_fold(0usize, |acc, letter| {
+            Some(acc * 26 + usize::from(letter.to_ascii_uppercase() - b'A' + 1))
+        })
+        .map(|column| column - 1)
+}
+
+/// Entries of `xl/sharedStrings.xml`, each the concatenation of its runs.
+fn xlsx_shared_strings(xml: &str) -> anyhow::Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"t" => in_text = true,
+                _ => {}
+            },
+            Event::Empty(element) if element.local_name().as_ref() == b"si" => {
+                strings.push(String::new());
+            }
+            Event::Text(text) if in_text => current.push_str(&text.unescape()?),
+            Event::End(element) => match element.local_name().as_ref() {
+                b"si" => strings.push(std::mem::take(&mut current)),
+                b"t" => in_text = false,
+                _ => {}
+            },
+            Event::Eof => return Ok(strings),
+            _ => {}
+        }
+    }
+}
+
+/// Display value of a cell of type `kind` holding `value`.
+fn xlsx_value(kind: Option<&str>, value: String, shared: &[String]) -> String {
+    match kind {
+        Some("s") => value
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| shared.get(index).cloned())
+            .unwrap_or_default(),
+        Some("b") => match value.trim() {
+            "1" => "TRUE".into(),
+            _ => "FALSE".into(),
+        },
+        _ => value,
+    }
+}
+
+/// Rows of a worksheet, without trailing empty cells or empty rows.
+fn xlsx_sheet(sheet: &str, shared: &[String]) -> anyhow::Result<Vec<Vec<String>>> {
+    let mut reader = Reader::from_str(sheet);
+    let mut rows = Vec::new();
+    let mut cells: Vec<String> = Vec::new();
+    // Column and type of the open `<c>`, and its `<v>` or inline `<t>` text.
+    let mut cell: Option<(usize, Option<String>)> = None;
+    let mut value = String::new();
+    let mut in_value = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"row" => cells.clear(),
+                b"c" => {
+                    let column = attribute(&element, "r")
+                        .as_deref()
+                        .and_then(column_index)
+                        .unwrap_or(cells.len());
+                    cell = Some((column, attribute(&element, "t")));
+                    value.clear();
+                }
+                b"v" | b"t" => in_value = cell.is_some(),
+                _ => {}
+            },
+            Event::Text(text) if in_value => value.push_str(&text.unescape()?),
+            Event::End(element) => match element.local_name().as_ref() {
+                b"v" | b"t" => in_value = false,
+                b"c" => {
+                    if let Some((column, kind)) = cell.take() {
+                        if column >= cells.len() {
+                            cells.resize(column + 1, String::new());
+                        }
+                        cells[column] =
+                            xlsx_value(kind.as_deref(), std::mem::take(&mut value), shared);
+                    }
+                }
+                b"row" => {
+                    while cells.last().is_some_and(String::is_empty) {
+                        cells.pop();
+                    }
+                    if !cells.is_empty() {
+                        rows.push(std::mem::take(&mut cells));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => return Ok(rows),
+            _ => {}
+        }
+    }
+}
+
+/// Markdown for a workbook: one heading and table per sheet.
+fn xlsx_to_markdown(package: &mut Package) -> anyhow::Result<String> {
+    let shared = match read_part(package, "xl/sharedStrings.xml")? {
+        Some(xml) => xlsx_shared_strings(&xml)?,
+        None => Vec::new(),
+    };
+    let workbook = read_part(package, "xl/workbook.xml")?
+        .ok_or_else(|| anyhow!("Workbook has no xl/workbook.xml"))?;
+    let mut targets = HashMap::new();
+    if let Some(rels) = read_part(package, "xl/_rels/workbook.xml.rels")? {
+        for relationship in elements_named(&rels, "Relationship")? {
+            let (Some(id), Some(target)) = (
+                attribute(&relationship, "Id"),
+                attribute(&relationship, "Target"),
+            ) else {
+                continue;
+            };
+            let path = match target.strip_prefix('/') {
+                Some(absolute) => absolute.to_string(),
+                None => format!("xl/{target}"),
+            };
+            targets.insert(id, path);
+        }
+    }
+
+    let mut sections = Vec::new();
+    for (index, sheet) in elements_named(&workbook, "sheet")?.iter().enumerate() {
+        let name = attribute(sheet, "name").unwrap_or_else(|| format!("Sheet{}", index + 1));
+        let path = attribute(sheet, "id")
+            .and_then(|id| targets.get(&id).cloned())
+            .unwrap_or_else(|| format!("xl/worksheets/sheet{}.xml", index + 1));
+        let Some(xml) = read_part(package, &path)? else {
+            continue;
+        };
+        let rows = xlsx_sheet(&xml, &shared)?;
+        sections.push(if rows.is_empty() {
+            format!("## {name}\n\n(empty)")
+        } else {
+            format!("## {name}\n\n{}", markdown_table(&rows))
+        });
+    }
+    Ok(sections.join("\n\n"))
+}
+
+fn office_to_markdown(format: DocumentFormat, bytes: &[u8]) -> anyhow::Result<String> {
+    let mut package = open_package(bytes)?;
+    match format {
+        DocumentFormat::Docx => {
+            let document = read_part(&mut package, "word/document.xml")?
+                .ok_or_else(|| anyhow!("Document has no word/document.xml"))?;
+            docx_to_markdown(&document)
+        }
+        _ => xlsx_to_markdown(&mut package),
+    }
+}
+
+// ── Extraction ──────────────────────────────────────────────────
+
+async fn extract(path: &Path) -> anyhow::Result<(DocumentFormat, String)> {
+    let bytes = tokio::fs::read(path).await?;
+    let format = DocumentFormat::detect(path, &bytes)
+        .ok_or_else(|| anyhow!("Unsupported document type; expected PDF, DOCX or XLSX"))?;
+    let text = match format {
+        DocumentFormat::Pdf => extract_pdf_text(bytes).await?,
+        DocumentFormat::Text => String::from_utf8_lossy(&bytes).into_owned(),
+        DocumentFormat::Docx | DocumentFormat::Xlsx => {
+            tokio::task::spawn_blocking(move || office_to_markdown(format, &bytes)).await??
+        }
+    };
+    Ok((format, text))
+}
+
+/// Text of a PDF, DOCX, XLSX or plain-text file, converted as the tool does.
+/// Used by the knowledge-base indexer.
+pub async fn extract_text(path: &Path) -> anyhow::Result<String> {
+    extract(path).await.map(|(_, text)| text)
+}
+
+/// Convert a PDF, Word or Excel document in the workspace to text or
+/// markdown, for example a `[DOCUMENT:...]` attachment a user sent.
+pub struct ExtractDocumentTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl ExtractDocumentTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+
+    /// Accept a bare path or an attachment marker such as
+    /// `[DOCUMENT:/…/whatsapp_files/contract.pdf]`. Paths inside the
+    /// workspace are made relative so `workspace_only` policies accept them.
+    fn normalize_path(&self, raw: &str) -> String {
+        let raw = raw.trim();
+        let path = raw
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(kind, _)| !kind.is_empty() && kind.chars().all(|c| c.is_ascii_uppercase()))
+            .map_or(raw, |(_, target)| {
+                target.split('|').next().unwrap_or(target).trim()
+            });
+        Path::new(path)
+            .strip_prefix(&self.security.workspace_dir)
+            .map_or_else(
+                |_| path.to_string(),
+                |relative| relative.display().to_string(),
+            )
+    }
+
+    async fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        if path.starts_with("http://") || path.starts_with("https://") {
+            return Err("Only files in the workspace can be extracted, not URLs".into());
+        }
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        // Record action before canonicalization so path-probing still consumes budget.
+        if !self.security.record_action() {
+            return Err("Rate limit exceeded: action budget exhausted".into());
+        }
+        let resolved = tokio::fs::canonicalize(self.security.workspace_dir.join(path))
+            .await
+            .map_err(|e| format!("Failed to resolve file path: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(self.security.resolved_path_violation_message(&resolved));
+        }
+        let meta = tokio::fs::metadata(&resolved)
+            .await
+            .map_err(|e| format!("Failed to read file metadata: {e}"))?;
+        if !meta.is_file() {
+            return Err(format!("Not a file: {path}"));
+        }
+        if meta.len() > MAX_DOCUMENT_BYTES {
+            return Err(format!(
+                "Document too large: {} bytes (limit: {MAX_DOCUMENT_BYTES} bytes)",
+                meta.len()
+            ));
+        }
+        Ok(resolved)
+    }
+}
+
+#[async_trait]
+impl Tool for ExtractDocumentTool {
+    fn name(&self) -> &str {
+        "extract_document"
+    }
+
+    fn description(&self) -> &str {
+        "Extract the text of a PDF, Word (DOCX) or Excel (XLSX) document in the workspace, \
+         such as a [DOCUMENT:path] attachment from a chat. Word headings, lists and tables \
+         and Excel sheets are returned as markdown."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Document path or the attachment marker itself ([DOCUMENT:/path/file.pdf]). Relative paths resolve from workspace."
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Maximum characters to return (default: 50000, max: 200000)",
+                    "minimum": 1,
+                    "maximum": 200_000
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let raw_path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'path' parameter"))?;
+
+        let max_chars = args
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .map(|n| {
+                usize::try_from(n)
+                    .unwrap_or(MAX_OUTPUT_CHARS)
+                    .min(MAX_OUTPUT_CHARS)
+            })
+            .unwrap_or(DEFAULT_MAX_CHARS);
+
+        if self.security.is_rate_limited() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+            });
+        }
+
+        let path = self.normalize_path(raw_path);
+        let resolved = match self.resolve(&path).await {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                })
+            }
+        };
+
+        let (format, text) = match extract(&resolved).await {
+            Ok(extracted) => extracted,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                })
+            }
+        };
+
+        let file_name = resolved
+            .file_name()
+            .map_or_else(|| path.clone(), |name| name.to_string_lossy().to_string());
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "{file_name} contains no extractable text (may be image-only or encrypted)"
+                ),
+                error: None,
+            });
+        }
+
+        let total_chars = text.chars().count();
+        let mut output = format!(
+            "{file_name} ({}, {total_chars} characters)\n\n",
+            format.label()
+        );
+        if total_chars > max_chars {
+            output.extend(text.chars().take(max_chars));
+            let _ = write!(output, "\n\n... [truncated at {max_chars} chars]");
+        } else {
+            output.push_str(text);
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn test_security(workspace: PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    /// Build a ZIP archive; members whose name ends in `.xml` are deflated.
+    fn package(members: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in members {
+            let method = if name.ends_with(".xml") {
+                zip::CompressionMethod::Deflated
+            } else {
+                zip::CompressionMethod::Stored
+            };
+            let options = zip::write::SimpleFileOptions::default().compression_method(method);
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn docx_converts_headings_lists_and_tables() {
+        let document = r#"<?xml version="1.0"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>
+<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Lease</w:t></w:r><w:r><w:t xml:space="preserve"> Agreement</w:t></w:r></w:p>
+<w:p><w:pPr><w:numPr><w:ilvl w:val="0"/></w:numPr></w:pPr><w:r><w:t>Rent &amp; deposit</w:t></w:r></w:p>
+<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Term</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Amount</w:t></w:r></w:p></w:tc></w:tr>
+<w:tr><w:tc><w:p><w:r><w:t>Monthly</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>1|200</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+<w:p/><w:sectPr/></w:body></w:document>"#;
+        let archive = package(&[("word/document.xml", document)]);
+        let markdown = office_to_markdown(DocumentFormat::Docx, &archive).unwrap();
+        assert_eq!(
+            markdown,
+            "# Lease Agreement\n\n- Rent & deposit\n\n| Term | Amount |\n| --- | --- |\n| Monthly | 1\\|200 |"
+        );
+    }
+
+    #[test]
+    fn xlsx_converts_sheets_with_shared_strings() {
+        let archive = package(&[
+            (
+                "xl/workbook.xml",
+                r#"<workbook><sheets><sheet name="Budget" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="worksheets/data.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/sharedStrings.xml",
+                r#"<sst><si><t>Item</t></si><si><r><t>Cost</t></r><r><t xml:space="preserve"> (EUR)</t></r></si><si><t>Paint</t></si></sst>"#,
+            ),
+            (
+                "xl/worksheets/data.xml",
+                r#"<worksheet><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row><row r="2"><c r="A2" t="s"><v>2</v></c><c r="C2"><v>42.5</v></c></row></sheetData></worksheet>"#,
+            ),
+        ]);
+        assert_eq!(
+            DocumentFormat::detect(Path::new("upload.bin"), &archive),
+            Some(DocumentFormat::Xlsx)
+        );
+        let markdown = office_to_markdown(DocumentFormat::Xlsx, &archive).unwrap();
+        assert_eq!(
+            markdown,
+            "## Budget\n\n| Item | Cost (EUR) |  |\n| --- | --- | --- |\n| Paint |  | 42.5 |"
+        );
+    }
+
+    #[tokio::test]
+    async fn extracts_attachment_marker_inside_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path().to_path_buf();
+        std::fs::create_dir_all(workspace.join("whatsapp_files")).unwrap();
+        std::fs::write(
+            workspace.join("whatsapp_files/notes.docx"),
+            package(&[(
+                "word/document.xml",
+                "<w:document><w:body><w:p><w:r><w:t>Signed</w:t></w:r></w:p></w:body></w:document>",
+            )]),
+        )
+        .unwrap();
+        let tool = ExtractDocumentTool::new(test_security(workspace.clone()));
+        let marker = format!(
+            "[DOCUMENT:{}]",
+            workspace.join("whatsapp_files/notes.docx").display()
+        );
+        let result = tool.execute(json!({ "path": marker })).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            result.output,
+            "notes.docx (Word document, 6 characters)\n\nSigned"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_files_and_urls() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("photo.jpg"), [0xff, 0xd8, 0xff]).unwrap();
+        let tool = ExtractDocumentTool::new(test_security(tmp.path().to_path_buf()));
+
+        let result = tool.execute(json!({"path": "photo.jpg"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Unsupported document type"));
+
+        let result = tool
+            .execute(json!({"path": "[DOCUMENT:https://example.com/a.pdf]"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not URLs"));
+    }
+}
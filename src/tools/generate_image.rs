@@ -0,0 +1,606 @@
+//! Image generation tool.
+//!
+//! Sends a text prompt to the configured `[image_generation]` backend (OpenAI
+//! Images, Stability AI, a local Stable Diffusion web UI or ComfyUI), saves
+//! the picture under `generated_images/` in the workspace and returns it as an
+//! `[IMAGE:<path>]` marker, which every channel delivers as an attachment.
+
+use super::image_info::ImageInfoTool;
+use super::traits::{Tool, ToolResult};
+use crate::config::{ImageGenerationBackend, ImageGenerationConfig};
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+const OPENAI_IMAGES_URL: &str = "https://api.openai.com/v1/images/generations";
+const STABILITY_IMAGES_URL: &str = "https://api.stability.ai/v2beta/stable-image/generate/core";
+const SD_WEBUI_URL: &str = "http://127.0.0.1:7860";
+const COMFYUI_URL: &str = "http://127.0.0.1:8188";
+
+/// Aspect ratios accepted by the Stability API.
+const STABILITY_ASPECT_RATIOS: &[&str] = &[
+    "16:9", "1:1", "21:9", "2:3", "3:2", "4:5", "5:4", "9:16", "9:21",
+];
+
+/// How often ComfyUI is polled for a finished prompt.
+const COMFYUI_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What to draw, with the size already resolved.
+struct ImageRequest {
+    prompt: String,
+    negative_prompt: Option<String>,
+    width: u32,
+    height: u32,
+}
+
+/// Tool that generates an image from a text prompt.
+pub struct GenerateImageTool {
+    security: Arc<SecurityPolicy>,
+    config: ImageGenerationConfig,
+}
+
+impl GenerateImageTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: ImageGenerationConfig) -> Self {
+        Self { security, config }
+    }
+
+    fn client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client_with_timeouts(
+            "tool.generate_image",
+            self.config.timeout_secs.max(1),
+            10,
+        )
+    }
+
+    fn api_url<'a>(&'a self, default: &'a str) -> &'a str {
+        self.config
+            .api_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .unwrap_or(default)
+            .trim_end_matches('/')
+    }
+
+    fn api_key(&self, env_var: &str) -> Result<String> {
+        match self.config.api_key.as_deref().map(str::trim) {
+            Some(key) if !key.is_empty() => Ok(key.to_string()),
+            _ => std::env::var(env_var).with_context(|| {
+                format!("{env_var} is not set and image_generation.api_key is empty")
+            }),
+        }
+    }
+
+    /// Generate one image and return its encoded bytes.
+    async fn generate(&self, request: &ImageRequest) -> Result<Vec<u8>> {
+        match self.config.backend {
+            ImageGenerationBackend::OpenAi => self.generate_via_openai(request).await,
+            ImageGenerationBackend::Stability => self.generate_via_stability(request).await,
+            ImageGenerationBackend::SdWebui => self.generate_via_sd_webui(request).await,
+            ImageGenerationBackend::Comfyui => self.generate_via_comfyui(request).await,
+        }
+    }
+
+    async fn generate_via_openai(&self, request: &ImageRequest) -> Result<Vec<u8>> {
+        let api_key = self.api_key("OPENAI_API_KEY")?;
+        let body = json!({
+            "model": self.config.model,
+            "prompt": request.prompt,
+            "size": format!("{}x{}", request.width, request.height),
+            "n": 1,
+        });
+
+        let client = self.client();
+        let resp = client
+            .post(self.api_url(OPENAI_IMAGES_URL))
+            .bearer_auth(&api_key)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to send image generation request")?;
+        let payload = json_response(resp, "OpenAI Images").await?;
+
+        let image = payload
+            .pointer("/data/0")
+            .context("OpenAI Images response contained no image")?;
+        if let Some(b64) = image.get("b64_json").and_then(Value::as_str) {
+            return decode_base64(b64);
+        }
+        // DALL·E models answer with a short-lived URL by default.
+        let url = image
+            .get("url")
+            .and_then(Value::as_str)
+            .context("OpenAI Images response had neither b64_json nor url")?;
+        download(&client, url).await
+    }
+
+    async fn generate_via_stability(&self, request: &ImageRequest) -> Result<Vec<u8>> {
+        let api_key = self.api_key("STABILITY_API_KEY")?;
+        let mut form = reqwest::multipart::Form::new()
+            .text("prompt", request.prompt.clone())
+            .text("output_format", "png");
+        if let Some(negative) = &request.negative_prompt {
+            form = form.text("negative_prompt", negative.clone());
+        }
+        if let Some(ratio) = aspect_ratio(request.width, request.height) {
+            form = form.text("aspect_ratio", ratio);
+        }
+
+        let resp = self
+            .client()
+            .post(self.api_url(STABILITY_IMAGES_URL))
+            .bearer_auth(&api_key)
+            .header(reqwest::header::ACCEPT, "image/*")
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to send image generation request")?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            bail!("Stability API error ({status}): {body}");
+        }
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    async fn generate_via_sd_webui(&self, request: &ImageRequest) -> Result<Vec<u8>> {
+        let body = json!({
+            "prompt": request.prompt,
+            "negative_prompt": request.negative_prompt.as_deref().unwrap_or_default(),
+            "width": request.width,
+            "height": request.height,
+        });
+
+        let resp = self
+            .client()
+            .post(format!("{}/sdapi/v1/txt2img", self.api_url(SD_WEBUI_URL)))
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to reach the Stable Diffusion web UI")?;
+        let payload = json_response(resp, "Stable Diffusion web UI").await?;
+
+        let b64 = payload
+            .pointer("/images/0")
+            .and_then(Value::as_str)
+            .context("Stable Diffusion web UI returned no image")?;
+        decode_base64(b64)
+    }
+
+    async fn generate_via_comfyui(&self, request: &ImageRequest) -> Result<Vec<u8>> {
+        let workflow_path = self
+            .config
+            .comfyui_workflow
+            .as_deref()
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .context("image_generation.comfyui_workflow must be set for the comfyui backend")?;
+        let workflow_path = self.security.workspace_dir.join(workflow_path);
+        let raw = tokio::fs::read_to_string(&workflow_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to read ComfyUI workflow {}",
+                    workflow_path.display()
+                )
+            })?;
+        let mut workflow: Value =
+            serde_json::from_str(&raw).context("ComfyUI workflow is not valid JSON")?;
+        fill_workflow(&mut workflow, request, rand::random::<u32>());
+
+        let base = self.api_url(COMFYUI_URL);
+        let client = self.client();
+        let resp = client
+            .post(format!("{base}/prompt"))
+            .json(&json!({
+                "prompt": workflow,
+                "client_id": uuid::Uuid::new_v4().to_string(),
+            }))
+            .send()
+            .await
+            .context("Failed to reach ComfyUI")?;
+        let queued = json_response(resp, "ComfyUI").await?;
+        let prompt_id = queued
+            .get("prompt_id")
+            .and_then(Value::as_str)
+            .context("ComfyUI did not return a prompt_id")?
+            .to_string();
+
+        let deadline =
+            tokio::time::Instant::now() + Duration::from_secs(self.config.timeout_secs.max(1));
+        let (filename, subfolder, kind) = loop {
+            let resp = client
+                .get(format!("{base}/history/{prompt_id}"))
+                .send()
+                .await
+                .context("Failed to poll ComfyUI")?;
+            let history = json_response(resp, "ComfyUI").await?;
+            if let Some(entry) = history.get(&prompt_id) {
+                break comfyui_output_image(entry)
+                    .context("ComfyUI workflow finished without an image output")?;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                bail!(
+                    "ComfyUI did not finish within {}s",
+                    self.config.timeout_secs
+                );
+            }
+            tokio::time::sleep(COMFYUI_POLL_INTERVAL).await;
+        };
+
+        download(
+            &client,
+            &format!(
+                "{base}/view?filename={}&subfolder={}&type={}",
+                urlencoding::encode(&filename),
+                urlencoding::encode(&subfolder),
+                urlencoding::encode(&kind)
+            ),
+        )
+        .await
+    }
+
+    /// Write `bytes` to `generated_images/` in the workspace.
+    async fn save(&self, bytes: &[u8], filename: Option<&str>) -> Result<PathBuf> {
+        let extension = match ImageInfoTool::detect_format(bytes) {
+            "unknown" => bail!("Image backend returned data that is not an image"),
+            format => format,
+        };
+        let stem = filename
+            .map(sanitize_stem)
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or_else(|| {
+                let id = uuid::Uuid::new_v4().simple().to_string();
+                format!(
+                    "image_{}_{}",
+                    chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+                    &id[..8]
+                )
+            });
+
+        let dir = self.security.workspace_dir.join("generated_images");
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .context("Failed to create generated_images directory")?;
+        let path = dir.join(format!("{stem}.{extension}"));
+        tokio::fs::write(&path, bytes)
+            .await
+            .context("Failed to write generated image")?;
+        Ok(path)
+    }
+}
+
+async fn json_response(resp: reqwest::Response, service: &str) -> Result<Value> {
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        bail!("{service} error ({status}): {body}");
+    }
+    resp.json()
+        .await
+        .with_context(|| format!("{service} returned invalid JSON"))
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .context("Failed to download generated image")?;
+    let status = resp.status();
+    if !status.is_success() {
+        bail!("Downloading generated image failed ({status})");
+    }
+    Ok(resp.bytes().await?.to_vec())
+}
+
+fn decode_base64(data: &str) -> Result<Vec<u8>> {
+    // SD web UI may prefix the payload as a data URI.
+    let data = data.split_once(";base64,").map_or(data, |(_, b64)| b64);
+    base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .context("Generated image is not valid base64")
+}
+
+/// Parse a `WIDTHxHEIGHT` size such as `1024x768`.
+fn parse_size(size: &str) -> Option<(u32, u32)> {
+    let (width, height) = size.trim().split_once(['x', 'X'])?;
+    let width = width.trim().parse().ok().filter(|w| *w > 0)?;
+    let height = height.trim().parse().ok().filter(|h| *h > 0)?;
+    Some((width, height))
+}
+
+/// The Stability aspect ratio matching `width:height` exactly, if any.
+fn aspect_ratio(width: u32, height: u32) -> Option<String> {
+    fn gcd(a: u32, b: u32) -> u32 {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+    let divisor = gcd(width, height).max(1);
+    let ratio = format!("{}:{}", width / divisor, height / divisor);
+    STABILITY_ASPECT_RATIOS
+        .contains(&ratio.as_str())
+        .then_some(ratio)
+}
+
+/// Substitute the request into a ComfyUI workflow's placeholder strings.
+fn fill_workflow(value: &mut Value, request: &ImageRequest, seed: u32) {
+    match value {
+        Value::String(text) => match text.as_str() {
+            "{{width}}" => *value = json!(request.width),
+            "{{height}}" => *value = json!(request.height),
+            "{{seed}}" => *value = json!(seed),
+            _ if text.contains("{{") => {
+                *text = text.replace("{{prompt}}", &request.prompt).replace(
+                    "{{negative_prompt}}",
+                    request.negative_prompt.as_deref().unwrap_or_default(),
+                );
+            }
+            _ => {}
+        },
+        Value::Array(items) => {
+            for item in items {
+                fill_workflow(item, request, seed);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                fill_workflow(item, request, seed);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `(filename, subfolder, type)` of the first image in a ComfyUI history entry.
+fn comfyui_output_image(entry: &Value) -> Option<(String, String, String)> {
+    entry
+        .get("outputs")?
+        .as_object()?
+        .values()
+        .filter_map(|output| output.pointer("/images/0"))
+        .find_map(|image| {
+            let field = |key: &str| image.get(key).and_then(Value::as_str).map(str::to_string);
+            Some((
+                field("filename")?,
+                field("subfolder").unwrap_or_default(),
+                field("type").unwrap_or_else(|| "output".into()),
+            ))
+        })
+}
+
+fn sanitize_stem(name: &str) -> String {
+    let name = std::path::Path::new(name.trim())
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Tool for GenerateImageTool {
+    fn name(&self) -> &str {
+        "generate_image"
+    }
+
+    fn description(&self) -> &str {
+        "Generate an image from a text description. The picture is saved in the workspace and \
+         returned as an [IMAGE:path] marker; include that marker in your reply to send it."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "prompt": {
+                    "type": "string",
+                    "description": "Detailed description of the image to generate"
+                },
+                "negative_prompt": {
+                    "type": "string",
+                    "description": "Things the image should not contain (Stability, SD web UI and ComfyUI only)"
+                },
+                "size": {
+                    "type": "string",
+                    "description": "Image size as WIDTHxHEIGHT, e.g. 1024x1024 (default from config)"
+                },
+                "filename": {
+                    "type": "string",
+                    "description": "Optional file name (without directory) for the saved image"
+                }
+            },
+            "required": ["prompt"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let prompt = args
+            .get("prompt")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|prompt| !prompt.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'prompt' parameter"))?;
+
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        let size = args
+            .get("size")
+            .and_then(Value::as_str)
+            .unwrap_or(&self.config.size);
+        let Some((width, height)) = parse_size(size) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Invalid size '{size}': expected WIDTHxHEIGHT")),
+            });
+        };
+        let request = ImageRequest {
+            prompt: prompt.to_string(),
+            negative_prompt: args
+                .get("negative_prompt")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string),
+            width,
+            height,
+        };
+
+        let filename = args.get("filename").and_then(Value::as_str);
+        let saved = match self.generate(&request).await {
+            Ok(bytes) => self.save(&bytes, filename).await,
+            Err(e) => Err(e),
+        };
+        match saved {
+            Ok(path) => {
+                let relative = path
+                    .strip_prefix(&self.security.workspace_dir)
+                    .unwrap_or(&path);
+                Ok(ToolResult {
+                    success: true,
+                    output: format!(
+                        "Image saved to {}.\n[IMAGE:{}]",
+                        relative.display(),
+                        path.display()
+                    ),
+                    error: None,
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Image generation failed: {e:#}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn tool(tmp: &TempDir, config: ImageGenerationConfig) -> GenerateImageTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        GenerateImageTool::new(security, config)
+    }
+
+    fn request() -> ImageRequest {
+        ImageRequest {
+            prompt: "a red \"fox\"".into(),
+            negative_prompt: None,
+            width: 768,
+            height: 512,
+        }
+    }
+
+    #[test]
+    fn parses_sizes_and_stability_ratios() {
+        assert_eq!(parse_size("1024x768"), Some((1024, 768)));
+        assert_eq!(parse_size(" 512 X 512 "), Some((512, 512)));
+        assert_eq!(parse_size("0x512"), None);
+        assert_eq!(parse_size("large"), None);
+        assert_eq!(aspect_ratio(1024, 1024).as_deref(), Some("1:1"));
+        assert_eq!(aspect_ratio(1920, 1080).as_deref(), Some("16:9"));
+        assert_eq!(aspect_ratio(1000, 700), None);
+    }
+
+    #[test]
+    fn fills_comfyui_workflow_placeholders() {
+        let mut workflow = json!({
+            "3": {"inputs": {"seed": "{{seed}}", "steps": 20}},
+            "5": {"inputs": {"width": "{{width}}", "height": "{{height}}"}},
+            "6": {"inputs": {"text": "photo, {{prompt}}"}},
+            "7": {"inputs": {"text": "{{negative_prompt}}"}}
+        });
+        fill_workflow(&mut workflow, &request(), 42);
+        assert_eq!(workflow["3"]["inputs"]["seed"], json!(42));
+        assert_eq!(workflow["5"]["inputs"]["width"], json!(768));
+        assert_eq!(workflow["5"]["inputs"]["height"], json!(512));
+        assert_eq!(
+            workflow["6"]["inputs"]["text"],
+            json!("photo, a red \"fox\"")
+        );
+        assert_eq!(workflow["7"]["inputs"]["text"], json!(""));
+    }
+
+    #[test]
+    fn finds_first_comfyui_output_image() {
+        let entry = json!({
+            "outputs": {
+                "8": {"text": ["done"]},
+                "9": {"images": [{"filename": "fox_00001_.png", "subfolder": "", "type": "output"}]}
+            }
+        });
+        assert_eq!(
+            comfyui_output_image(&entry),
+            Some(("fox_00001_.png".into(), String::new(), "output".into()))
+        );
+        assert_eq!(comfyui_output_image(&json!({"outputs": {}})), None);
+    }
+
+    #[tokio::test]
+    async fn saves_images_in_workspace_with_sanitized_name() {
+        let tmp = TempDir::new().unwrap();
+        let tool = tool(&tmp, ImageGenerationConfig::default());
+        let png = b"\x89PNG\r\n\x1a\n0000";
+
+        let path = tool.save(png, Some("../my fox.jpg")).await.unwrap();
+        assert_eq!(path, tmp.path().join("generated_images/my_fox.png"));
+        assert_eq!(std::fs::read(&path).unwrap(), png);
+
+        let err = tool.save(b"<html>oops</html>", None).await.unwrap_err();
+        assert!(err.to_string().contains("not an image"));
+    }
+
+    #[tokio::test]
+    async fn comfyui_backend_requires_workflow() {
+        let tmp = TempDir::new().unwrap();
+        let tool = tool(
+            &tmp,
+            ImageGenerationConfig {
+                backend: ImageGenerationBackend::Comfyui,
+                ..ImageGenerationConfig::default()
+            },
+        );
+        let result = tool.execute(json!({"prompt": "a fox"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("comfyui_workflow"));
+        assert!(!tmp.path().join("generated_images").exists());
+    }
+}
@@ -0,0 +1,579 @@
+//! Read-only repository inspection.
+//!
+//! The `git` tool answers questions about a configured repository
+//! (`[git] repo_path`, default: the workspace) with `status`, `diff`, `log`,
+//! `blame` and `grep`. Nothing it runs changes the repository, so it is
+//! available at every autonomy level; use `git_operations` to commit.
+//! Repository config cannot make it run programs: fsmonitor, hooks, pagers,
+//! external diff tools, credential helpers, SSH commands, signature checks
+//! and every filter driver the effective config defines are overridden on
+//! every call.
+
+use super::shell::truncate_to_budget;
+use super::traits::{Tool, ToolResult};
+use crate::config::GitConfig;
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Most commits `log` returns.
+const MAX_LOG_LIMIT: u64 = 200;
+
+/// `-c` settings that take precedence over the repository's own config.
+const SAFE_CONFIG: &[&str] = &[
+    "color.ui=never",
+    "core.askPass=",
+    "core.fsmonitor=false",
+    "core.hooksPath=/dev/null",
+    "core.pager=cat",
+    "core.sshCommand=",
+    "credential.helper=",
+    "diff.external=",
+    "log.showSignature=false",
+    "protocol.allow=never",
+];
+
+/// Filter driver settings that name a program to run on file contents.
+const FILTER_PROGRAMS: &[&str] = &["clean", "smudge", "process"];
+
+/// Tool that runs read-only git commands in a configured repository.
+pub struct GitTool {
+    security: Arc<SecurityPolicy>,
+    config: GitConfig,
+}
+
+impl GitTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: GitConfig) -> Self {
+        Self { security, config }
+    }
+
+    fn repo_dir(&self) -> PathBuf {
+        match self.config.repo_path.as_deref().map(str::trim) {
+            Some(path) if !path.is_empty() => {
+                let expanded = PathBuf::from(shellexpand::tilde(path).into_owned());
+                self.security.workspace_dir.join(expanded)
+            }
+            _ => self.security.workspace_dir.clone(),
+        }
+    }
+
+    /// A `git` invocation in the repository with [`SAFE_CONFIG`] applied.
+    fn git_command(&self) -> tokio::process::Command {
+        let mut cmd = tokio::process::Command::new("git");
+        cmd.arg("--no-pager");
+        for setting in SAFE_CONFIG {
+            cmd.args(["-c", setting]);
+        }
+        cmd.arg("-C")
+            .arg(self.repo_dir())
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("GIT_OPTIONAL_LOCKS", "0")
+            .env("GIT_NO_LAZY_FETCH", "1")
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true);
+        cmd
+    }
+
+    /// `-c` settings that disable every filter driver in the effective
+    /// config. Driver names are free-form, so they are looked up per call.
+    async fn filter_overrides(&self, timeout: Duration) -> Result<Vec<String>> {
+        let mut cmd = self.git_command();
+        cmd.args(["config", "--name-only", "--get-regexp", r"^filter\."]);
+        let output = tokio::time::timeout(timeout, cmd.output())
+            .await
+            .with_context(|| format!("git config timed out after {}s", timeout.as_secs()))?
+            .context("Failed to run git; is it installed?")?;
+        match output.status.code() {
+            Some(0) => disable_filter_drivers(&String::from_utf8_lossy(&output.stdout)),
+            // No filter settings at all.
+            Some(1) => Ok(Vec::new()),
+            _ => bail!(
+                "git config failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        }
+    }
+
+    async fn run_git(&self, operation: &str, git_args: &[String]) -> Result<String> {
+        let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+        let overrides = self.filter_overrides(timeout).await?;
+        let mut cmd = self.git_command();
+        for setting in &overrides {
+            cmd.args(["-c", setting]);
+        }
+        cmd.args(git_args);
+
+        let output = tokio::time::timeout(timeout, cmd.output())
+            .await
+            .with_context(|| format!("git {operation} timed out after {}s", timeout.as_secs()))?
+            .context("Failed to run git; is it installed?")?;
+
+        let mut stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // `git grep` exits 1 when nothing matched.
+            if operation == "grep" && output.status.code() == Some(1) && stderr.trim().is_empty() {
+                return Ok("No matches.".into());
+            }
+            bail!("git {operation} failed: {}", stderr.trim());
+        }
+        if stdout.trim().is_empty() {
+            stdout = match operation {
+                "diff" => "No differences.",
+                "log" => "No commits.",
+                "blame" => "No lines.",
+                _ => "No output.",
+            }
+            .into();
+        }
+        truncate_to_budget(
+            &mut stdout,
+            &mut String::new(),
+            self.config.max_output_bytes.max(1024),
+        );
+        Ok(stdout)
+    }
+}
+
+/// Turn the `filter.<driver>.<key>` names printed by `git config --name-only`
+/// into settings that blank each driver's programs.
+fn disable_filter_drivers(config_names: &str) -> Result<Vec<String>> {
+    let mut drivers: Vec<&str> = Vec::new();
+    for name in config_names
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let Some((driver, _)) = name
+            .strip_prefix("filter.")
+            .and_then(|rest| rest.rsplit_once('.'))
+        else {
+            continue;
+        };
+        // `-c` splits at the first `=`; such a driver could not be blanked.
+        if driver.contains('=') {
+            bail!("Refusing to run git: filter driver '{driver}' cannot be disabled");
+        }
+        if !drivers.contains(&driver) {
+            drivers.push(driver);
+        }
+    }
+    Ok(drivers
+        .iter()
+        .flat_map(|driver| {
+            FILTER_PROGRAMS
+                .iter()
+                .map(move |key| format!("filter.{driver}.{key}="))
+        })
+        .collect())
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Accept a revision or range such as `HEAD~3`, `v1.2.0` or `main..feature`.
+fn check_rev(rev: &str) -> Result<(), String> {
+    let valid = !rev.starts_with('-')
+        && rev
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "._/~^@{}-".contains(ch));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid revision '{rev}'"))
+    }
+}
+
+/// Accept a repository-relative path or glob that stays inside the repository.
+fn check_path(path: &str) -> Result<(), String> {
+    let escapes = Path::new(path)
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+    if path.starts_with('-') || path.starts_with(':') || escapes {
+        Err(format!(
+            "Invalid path '{path}': use a path relative to the repository root"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Translate tool arguments into the git command line for `operation`.
+fn build_args(operation: &str, args: &Value) -> Result<Vec<String>, String> {
+    let rev = str_arg(args, "rev");
+    let path = str_arg(args, "path");
+    if let Some(rev) = rev {
+        check_rev(rev)?;
+    }
+    if let Some(path) = path {
+        check_path(path)?;
+    }
+    let flag = |key: &str| args.get(key).and_then(Value::as_bool).unwrap_or(false);
+
+    let mut git_args: Vec<String> = match operation {
+        "status" => return Ok(vec!["status".into(), "--short".into(), "--branch".into()]),
+        "diff" => {
+            let mut git_args = vec![
+                "diff".to_string(),
+                "--no-ext-diff".into(),
+                "--no-textconv".into(),
+            ];
+            if flag("cached") {
+                git_args.push("--cached".into());
+            }
+            if flag("stat") {
+                git_args.push("--stat".into());
+            }
+            git_args
+        }
+        "log" => {
+            let limit = args
+                .get("limit")
+                .and_then(Value::as_u64)
+                .unwrap_or(20)
+                .clamp(1, MAX_LOG_LIMIT);
+            let mut git_args = vec![
+                "log".to_string(),
+                format!("--max-count={limit}"),
+                "--date=short".into(),
+                "--pretty=format:%h %ad %an: %s".into(),
+            ];
+            if let Some(since) = str_arg(args, "since") {
+                git_args.push(format!("--since={since}"));
+            }
+            git_args
+        }
+        "blame" => {
+            if path.is_none() {
+                return Err("'blame' requires a 'path'".into());
+            }
+            let mut git_args = vec![
+                "blame".to_string(),
+                "--date=short".into(),
+                "--no-textconv".into(),
+            ];
+            let line = |key: &str| args.get(key).and_then(Value::as_u64).filter(|n| *n > 0);
+            match (line("start_line"), line("end_line")) {
+                (Some(start), Some(end)) if end >= start => {
+                    git_args.push(format!("-L{start},{end}"));
+                }
+                (Some(start), None) => git_args.push(format!("-L{start},")),
+                (None, Some(end)) => git_args.push(format!("-L1,{end}")),
+                (None, None) => {}
+                _ => return Err("'end_line' must not be before 'start_line'".into()),
+            }
+            git_args
+        }
+        "grep" => {
+            let pattern = args
+                .get("pattern")
+                .and_then(Value::as_str)
+                .filter(|pattern| !pattern.is_empty())
+                .ok_or("'grep' requires a 'pattern'")?;
+            let mut git_args = vec!["grep".to_string(), "-n".into(), "-I".into()];
+            if flag("ignore_case") {
+                git_args.push("-i".into());
+            }
+            git_args.push("-e".into());
+            git_args.push(pattern.into());
+            git_args
+        }
+        other => {
+            return Err(format!(
+                "Unknown operation '{other}'. Use: status, diff, log, blame, grep"
+            ))
+        }
+    };
+
+    if let Some(rev) = rev {
+        git_args.push(rev.into());
+    }
+    git_args.push("--".into());
+    if let Some(path) = path {
+        git_args.push(path.into());
+    }
+    Ok(git_args)
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn name(&self) -> &str {
+        "git"
+    }
+
+    fn description(&self) -> &str {
+        "Inspect the configured git repository without changing it: status, diff, log, blame \
+         (who last changed each line) and grep (search tracked files). Paths are relative to \
+         the repository root."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["status", "diff", "log", "blame", "grep"],
+                    "description": "Git command to run"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File, directory or glob relative to the repository root (required for 'blame')"
+                },
+                "rev": {
+                    "type": "string",
+                    "description": "Revision or range, e.g. 'HEAD~5', 'v1.2.0' or 'main..feature' (diff, log, blame, grep)"
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "Regular expression to search for (for 'grep')"
+                },
+                "ignore_case": {
+                    "type": "boolean",
+                    "description": "Case-insensitive search (for 'grep')"
+                },
+                "cached": {
+                    "type": "boolean",
+                    "description": "Show staged changes (for 'diff')"
+                },
+                "stat": {
+                    "type": "boolean",
+                    "description": "Only summarize changed files (for 'diff')"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Number of commits (for 'log', default: 20, max: 200)"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only commits after this date, e.g. '2 weeks ago' or '2026-01-01' (for 'log')"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to blame (for 'blame')"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "Last line to blame (for 'blame')"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let operation = str_arg(&args, "operation")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'operation' parameter"))?;
+
+        let git_args = match build_args(operation, &args) {
+            Ok(git_args) => git_args,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                })
+            }
+        };
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        match self.run_git(operation, &git_args).await {
+            Ok(output) => Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn repo_tool(tmp: &TempDir) -> GitTool {
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "--quiet"]);
+        std::fs::write(repo.join("lib.rs"), "fn answer() -> u32 {\n    42\n}\n").unwrap();
+        git(&repo, &["add", "lib.rs"]);
+        git(&repo, &["commit", "--quiet", "-m", "Add answer"]);
+        std::fs::write(repo.join("lib.rs"), "fn answer() -> u32 {\n    43\n}\n").unwrap();
+
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        GitTool::new(
+            security,
+            GitConfig {
+                enabled: true,
+                repo_path: Some("repo".into()),
+                ..GitConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn build_args_rejects_option_injection() {
+        assert!(build_args("log", &json!({"rev": "--output=/tmp/x"})).is_err());
+        assert!(build_args("diff", &json!({"rev": "HEAD; rm -rf /"})).is_err());
+        assert!(build_args("blame", &json!({"path": "../secrets.txt"})).is_err());
+        assert!(build_args("grep", &json!({"pattern": "x", "path": "/etc"})).is_err());
+        assert!(build_args("push", &json!({})).is_err());
+        assert!(build_args("blame", &json!({})).is_err());
+
+        let grep = build_args("grep", &json!({"pattern": "--exec", "rev": "main"})).unwrap();
+        assert_eq!(grep, ["grep", "-n", "-I", "-e", "--exec", "main", "--"]);
+        let blame = build_args(
+            "blame",
+            &json!({"path": "src/main.rs", "start_line": 10, "end_line": 20}),
+        )
+        .unwrap();
+        assert_eq!(
+            blame,
+            [
+                "blame",
+                "--date=short",
+                "--no-textconv",
+                "-L10,20",
+                "--",
+                "src/main.rs"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_configured_repository_in_read_only_mode() {
+        let tmp = TempDir::new().unwrap();
+        let tool = repo_tool(&tmp);
+
+        let log = tool
+            .execute(json!({"operation": "log", "limit": 5}))
+            .await
+            .unwrap();
+        assert!(log.success, "{:?}", log.error);
+        assert!(log.output.contains("Test: Add answer"));
+
+        let diff = tool.execute(json!({"operation": "diff"})).await.unwrap();
+        assert!(diff.output.contains("+    43"));
+
+        let blame = tool
+            .execute(json!({"operation": "blame", "path": "lib.rs", "rev": "HEAD", "start_line": 2, "end_line": 2}))
+            .await
+            .unwrap();
+        assert!(blame.output.contains("42"));
+        assert_eq!(blame.output.lines().count(), 1);
+
+        let grep = tool
+            .execute(json!({"operation": "grep", "pattern": "ANSWER", "ignore_case": true}))
+            .await
+            .unwrap();
+        assert!(grep.output.starts_with("lib.rs:1:"));
+
+        let none = tool
+            .execute(json!({"operation": "grep", "pattern": "missing_symbol"}))
+            .await
+            .unwrap();
+        assert!(none.success);
+        assert_eq!(none.output, "No matches.");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn repository_config_cannot_run_programs() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let tool = repo_tool(&tmp);
+        let repo = tmp.path().join("repo");
+        let marker = tmp.path().join("pwned");
+        let hook = tmp.path().join("hook.sh");
+        std::fs::write(&hook, format!("#!/bin/sh\ntouch {}\n", marker.display())).unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let hook = hook.to_string_lossy().to_string();
+        git(&repo, &["config", "core.fsmonitor", &hook]);
+        git(&repo, &["config", "diff.external", &hook]);
+        git(&repo, &["config", "filter.evil.clean", &hook]);
+        git(&repo, &["config", "filter.evil.smudge", &hook]);
+        git(&repo, &["config", "filter.evil.process", &hook]);
+        std::fs::write(repo.join(".git/info/attributes"), "* filter=evil\n").unwrap();
+
+        for operation in ["status", "diff"] {
+            let result = tool.execute(json!({"operation": operation})).await.unwrap();
+            assert!(result.success, "{:?}", result.error);
+        }
+        let blame = tool
+            .execute(json!({"operation": "blame", "path": "lib.rs"}))
+            .await
+            .unwrap();
+        assert!(blame.success, "{:?}", blame.error);
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn every_filter_driver_is_blanked() {
+        let overrides =
+            disable_filter_drivers("filter.lfs.clean\nfilter.lfs.smudge\nfilter.a.b.process\n")
+                .unwrap();
+        assert_eq!(
+            overrides,
+            [
+                "filter.lfs.clean=",
+                "filter.lfs.smudge=",
+                "filter.lfs.process=",
+                "filter.a.b.clean=",
+                "filter.a.b.smudge=",
+                "filter.a.b.process=",
+            ]
+        );
+        assert!(disable_filter_drivers("").unwrap().is_empty());
+        assert!(disable_filter_drivers("filter.x=y.clean\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn reports_git_errors() {
+        let tmp = TempDir::new().unwrap();
+        let tool = repo_tool(&tmp);
+
+        let result = tool
+            .execute(json!({"operation": "log", "rev": "no-such-branch"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("git log failed:"));
+    }
+}
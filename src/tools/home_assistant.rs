@@ -0,0 +1,509 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::HomeAssistantConfig;
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Map, Value};
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Entities listed by `states` before the output is cut short.
+const MAX_LISTED_STATES: usize = 100;
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Domains, services and object IDs are lowercase slugs in Home Assistant.
+fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Domain of a `domain.object_id` entity ID.
+fn entity_domain(entity_id: &str) -> Result<&str> {
+    match entity_id.split_once('.') {
+        Some((domain, object_id)) if is_slug(domain) && is_slug(object_id) => Ok(domain),
+        _ => bail!("Invalid entity_id '{entity_id}': expected 'domain.object_id'"),
+    }
+}
+
+/// `entity_id` as a string, comma-separated string or array.
+fn entity_ids(value: Option<&Value>) -> Result<Vec<String>> {
+    let raw: Vec<&str> = match value {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(list)) => list.split(',').collect(),
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+        Some(_) => bail!("'entity_id' must be a string or an array of strings"),
+    };
+    raw.into_iter()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            entity_domain(id)?;
+            Ok(id.to_string())
+        })
+        .collect()
+}
+
+fn friendly_name(entity: &Value) -> Option<&str> {
+    entity
+        .pointer("/attributes/friendly_name")
+        .and_then(Value::as_str)
+}
+
+/// One line per entity: `sensor.outside: 12.5 °C (Outside temperature)`.
+fn format_state(entity: &Value) -> String {
+    let id = entity
+        .get("entity_id")
+        .and_then(Value::as_str)
+        .unwrap_or("?");
+    let state = entity.get("state").and_then(Value::as_str).unwrap_or("?");
+    let mut line = format!("{id}: {state}");
+    if let Some(unit) = entity
+        .pointer("/attributes/unit_of_measurement")
+        .and_then(Value::as_str)
+    {
+        let _ = write!(line, " {unit}");
+    }
+    if let Some(name) = friendly_name(entity) {
+        let _ = write!(line, " ({name})");
+    }
+    line
+}
+
+fn format_detail(entity: &Value) -> String {
+    let mut output = format_state(entity);
+    if let Some(changed) = entity.get("last_changed").and_then(Value::as_str) {
+        let _ = write!(output, "\nLast changed: {changed}");
+    }
+    if let Some(attributes) = entity.get("attributes").and_then(Value::as_object) {
+        let mut keys: Vec<&String> = attributes.keys().collect();
+        keys.sort();
+        for key in keys {
+            if key == "friendly_name" {
+                continue;
+            }
+            let value = &attributes[key];
+            let rendered = value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string);
+            let _ = write!(output, "\n  {key}: {rendered}");
+        }
+    }
+    output
+}
+
+/// States whose entity ID is in `domain` and whose ID or friendly name
+/// contains `search`, sorted by entity ID.
+fn filter_states<'a>(
+    states: &'a [Value],
+    domain: Option<&str>,
+    search: Option<&str>,
+) -> Vec<&'a Value> {
+    let search = search.map(str::to_lowercase);
+    let mut matches: Vec<&Value> = states
+        .iter()
+        .filter(|entity| {
+            let id = entity
+                .get("entity_id")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let in_domain = domain.is_none_or(|domain| {
+                id.split_once('.')
+                    .is_some_and(|(entity_domain, _)| entity_domain == domain)
+            });
+            let matches_search = search.as_deref().is_none_or(|needle| {
+                id.to_lowercase().contains(needle)
+                    || friendly_name(entity)
+                        .is_some_and(|name| name.to_lowercase().contains(needle))
+            });
+            in_domain && matches_search
+        })
+        .collect();
+    matches.sort_by_key(|entity| entity.get("entity_id").and_then(Value::as_str));
+    matches
+}
+
+/// Read entity states and call services in Home Assistant.
+pub struct HomeAssistantTool {
+    security: Arc<SecurityPolicy>,
+    config: HomeAssistantConfig,
+}
+
+impl HomeAssistantTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: HomeAssistantConfig) -> Self {
+        Self { security, config }
+    }
+
+    fn is_domain_allowed(&self, domain: &str) -> bool {
+        self.config.allowed_domains.is_empty()
+            || self
+                .config
+                .allowed_domains
+                .iter()
+                .any(|allowed| allowed.trim() == domain)
+    }
+
+    /// The service domain and every targeted entity must be allowed, so
+    /// generic services like `homeassistant.turn_off` cannot reach a lock.
+    fn check_call(&self, domain: &str, service: &str, entities: &[String]) -> Result<()> {
+        if !is_slug(domain) || !is_slug(service) {
+            bail!("Invalid service '{domain}.{service}'");
+        }
+        if !self.is_domain_allowed(domain) {
+            bail!("Services in '{domain}' are not in [home_assistant] allowed_domains");
+        }
+        for entity in entities {
+            let entity_domain = entity_domain(entity)?;
+            if !self.is_domain_allowed(entity_domain) {
+                bail!("Entity {entity} is not in [home_assistant] allowed_domains");
+            }
+        }
+        Ok(())
+    }
+
+    async fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let token = self
+            .config
+            .token
+            .as_deref()
+            .filter(|token| !token.trim().is_empty())
+            .context("No Home Assistant token configured: set [home_assistant] token")?;
+        let url = format!("{}/api/{path}", self.config.url.trim_end_matches('/'));
+        let client = crate::config::build_runtime_proxy_client_with_timeouts(
+            "tool.home_assistant",
+            self.config.timeout_secs.max(1),
+            10,
+        );
+        let mut request = client.request(method, &url).bearer_auth(token.trim());
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to reach Home Assistant at {}", self.config.url))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            bail!("Home Assistant rejected the token (401)");
+        }
+        if status == reqwest::StatusCode::NOT_FOUND {
+            bail!("Not found in Home Assistant: {path}");
+        }
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            let detail: String = text.chars().take(300).collect();
+            bail!("Home Assistant returned {status}: {detail}");
+        }
+        serde_json::from_str(&text).context("Home Assistant returned invalid JSON")
+    }
+
+    async fn states(&self, args: &Value) -> Result<String> {
+        let states = self.request(reqwest::Method::GET, "states", None).await?;
+        let states = states.as_array().map(Vec::as_slice).unwrap_or_default();
+        let matches = filter_states(states, str_arg(args, "domain"), str_arg(args, "search"));
+        if matches.is_empty() {
+            return Ok("No matching entities.".into());
+        }
+        let mut output = matches
+            .iter()
+            .take(MAX_LISTED_STATES)
+            .map(|entity| format_state(entity))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if matches.len() > MAX_LISTED_STATES {
+            let _ = write!(
+                output,
+                "\n... and {} more; narrow with 'domain' or 'search'",
+                matches.len() - MAX_LISTED_STATES
+            );
+        }
+        Ok(output)
+    }
+
+    async fn get(&self, args: &Value) -> Result<String> {
+        let entity_id = str_arg(args, "entity_id")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'entity_id' parameter"))?;
+        entity_domain(entity_id)?;
+        let entity = self
+            .request(reqwest::Method::GET, &format!("states/{entity_id}"), None)
+            .await?;
+        Ok(format_detail(&entity))
+    }
+
+    async fn services(&self, args: &Value) -> Result<String> {
+        let domain = str_arg(args, "domain");
+        let services = self.request(reqwest::Method::GET, "services", None).await?;
+        let mut output = String::new();
+        for entry in services.as_array().into_iter().flatten() {
+            let Some(entry_domain) = entry.get("domain").and_then(Value::as_str) else {
+                continue;
+            };
+            if domain.is_some_and(|domain| domain != entry_domain)
+                || !self.is_domain_allowed(entry_domain)
+            {
+                continue;
+            }
+            let Some(services) = entry.get("services").and_then(Value::as_object) else {
+                continue;
+            };
+            for (service, info) in services {
+                let _ = write!(output, "\n{entry_domain}.{service}");
+                if domain.is_some() {
+                    if let Some(fields) = info.get("fields").and_then(Value::as_object) {
+                        if !fields.is_empty() {
+                            let names: Vec<&str> = fields.keys().map(String::as_str).collect();
+                            let _ = write!(output, " (fields: {})", names.join(", "));
+                        }
+                    }
+                }
+            }
+        }
+        if output.is_empty() {
+            return Ok("No allowed services found.".into());
+        }
+        Ok(output.trim_start().to_string())
+    }
+
+    async fn call_service(&self, args: &Value) -> Result<String> {
+        let domain =
+            str_arg(args, "domain").ok_or_else(|| anyhow::anyhow!("Missing 'domain' parameter"))?;
+        let service = str_arg(args, "service")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'service' parameter"))?;
+        let entities = entity_ids(args.get("entity_id"))?;
+        self.check_call(domain, service, &entities)?;
+
+        let mut body = match args.get("data") {
+            None | Some(Value::Null) => Map::new(),
+            Some(Value::Object(data)) => data.clone(),
+            Some(_) => bail!("'data' must be an object"),
+        };
+        if body.contains_key("entity_id") || body.contains_key("area_id") {
+            bail!("Pass targets with the 'entity_id' parameter, not inside 'data'");
+        }
+        if !entities.is_empty() {
+            body.insert("entity_id".into(), json!(entities));
+        }
+
+        let changed = self
+            .request(
+                reqwest::Method::POST,
+                &format!("services/{domain}/{service}"),
+                Some(Value::Object(body)),
+            )
+            .await?;
+        let mut output = format!("Called {domain}.{service}");
+        if !entities.is_empty() {
+            let _ = write!(output, " for {}", entities.join(", "));
+        }
+        let changed: Vec<String> = changed
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(format_state)
+            .collect();
+        if changed.is_empty() {
+            output.push_str(". No state changes reported yet.");
+        } else {
+            let _ = write!(output, ". Changed:\n{}", changed.join("\n"));
+        }
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl Tool for HomeAssistantTool {
+    fn name(&self) -> &str {
+        "home_assistant"
+    }
+
+    fn description(&self) -> &str {
+        "Read smart home state and control devices through Home Assistant: list entity states, \
+         get one entity, list services, or call a service like light.turn_off."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["states", "get", "services", "call_service"],
+                    "description": "What to do"
+                },
+                "entity_id": {
+                    "description": "Entity ID like 'cover.garage_door' ('get'), or one or more targets ('call_service')",
+                    "anyOf": [
+                        { "type": "string" },
+                        { "type": "array", "items": { "type": "string" } }
+                    ]
+                },
+                "domain": {
+                    "type": "string",
+                    "description": "Domain filter for 'states'/'services', or the service domain for 'call_service' (e.g. 'light')"
+                },
+                "search": {
+                    "type": "string",
+                    "description": "For 'states': match entity IDs and names containing this text, e.g. 'garage'"
+                },
+                "service": {
+                    "type": "string",
+                    "description": "Service to call, e.g. 'turn_off'"
+                },
+                "data": {
+                    "type": "object",
+                    "description": "Extra service data, e.g. {\"brightness_pct\": 30}"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let action = str_arg(&args, "action")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+        let writes = match action {
+            "states" | "get" | "services" => false,
+            "call_service" => true,
+            other => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!(
+                        "Unknown action '{other}'. Use: states, get, services, call_service"
+                    )),
+                })
+            }
+        };
+
+        if writes && !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        let result = match action {
+            "states" => self.states(&args).await,
+            "get" => self.get(&args).await,
+            "services" => self.services(&args).await,
+            _ => self.call_service(&args).await,
+        };
+        match result {
+            Ok(output) => Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    fn sample_states() -> Vec<Value> {
+        vec![
+            json!({
+                "entity_id": "light.kitchen",
+                "state": "on",
+                "attributes": {"friendly_name": "Kitchen", "brightness": 180}
+            }),
+            json!({
+                "entity_id": "cover.garage_door",
+                "state": "open",
+                "attributes": {"friendly_name": "Garage Door", "device_class": "garage"},
+                "last_changed": "2026-10-16T07:12:00+00:00"
+            }),
+            json!({
+                "entity_id": "sensor.outside_temperature",
+                "state": "12.5",
+                "attributes": {"friendly_name": "Outside", "unit_of_measurement": "°C"}
+            }),
+        ]
+    }
+
+    fn tool(level: AutonomyLevel) -> HomeAssistantTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: level,
+            ..SecurityPolicy::default()
+        });
+        HomeAssistantTool::new(security, HomeAssistantConfig::default())
+    }
+
+    #[test]
+    fn filters_and_formats_states() {
+        let states = sample_states();
+        let garage = filter_states(&states, None, Some("GARAGE"));
+        assert_eq!(garage.len(), 1);
+        assert_eq!(
+            format_state(garage[0]),
+            "cover.garage_door: open (Garage Door)"
+        );
+        assert_eq!(
+            format_state(&states[2]),
+            "sensor.outside_temperature: 12.5 °C (Outside)"
+        );
+        let lights = filter_states(&states, Some("light"), None);
+        assert_eq!(lights.len(), 1);
+
+        let detail = format_detail(&states[1]);
+        assert!(detail.contains("Last changed: 2026-10-16T07:12:00+00:00"));
+        assert!(detail.contains("\n  device_class: garage"));
+        assert!(!detail.contains("friendly_name"));
+    }
+
+    #[test]
+    fn service_calls_are_limited_to_allowed_domains() {
+        let ha = tool(AutonomyLevel::Supervised);
+        let lights = entity_ids(Some(&json!("light.kitchen, light.hall"))).unwrap();
+        assert!(ha.check_call("light", "turn_off", &lights).is_ok());
+        assert!(ha.check_call("lock", "unlock", &[]).is_err());
+        let lock = vec!["lock.front_door".to_string()];
+        assert!(ha.check_call("homeassistant", "turn_off", &lock).is_err());
+        assert!(entity_ids(Some(&json!(["Light.Kitchen"]))).is_err());
+        assert!(ha.check_call("light", "turn_off/../x", &[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn read_only_mode_blocks_service_calls() {
+        let ha = tool(AutonomyLevel::ReadOnly);
+        let result = ha
+            .execute(json!({
+                "action": "call_service",
+                "domain": "light",
+                "service": "turn_off",
+                "entity_id": "light.kitchen"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
@@ -24,7 +24,7 @@ impl ImageInfoTool {
     }
 
     /// Detect image format from first few bytes (magic numbers).
-    fn detect_format(bytes: &[u8]) -> &'static str {
+    pub(crate) fn detect_format(bytes: &[u8]) -> &'static str {
         if bytes.len() < 4 {
             return "unknown";
         }
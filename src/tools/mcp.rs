@@ -0,0 +1,805 @@
+// MCP client — exposes tools from external Model Context Protocol servers.
+//
+// Each configured `[[mcp.servers]]` entry is connected once per process over
+// stdio (spawned child process, newline-delimited JSON-RPC) or SSE (HTTP
+// event stream for responses, POST endpoint for requests). The server's tools
+// are listed once and registered as `<server>__<tool>` so they flow through
+// the normal tool protocol; invocations are proxied back with `tools/call`.
+// Every agent built later shares those connections. Stdio servers get the
+// same scrubbed environment as the shell tool plus their own `env`.
+
+use super::traits::{Tool, ToolResult};
+use crate::config::{McpConfig, McpServerConfig, McpTransport};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use anyhow::Context;
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::{oneshot, OnceCell};
+
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+/// Provider tool names are capped at 64 characters by most function-calling APIs.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Servers this process has connected, with the tools each one listed.
+static CONNECTED_SERVERS: OnceCell<Vec<(Arc<McpClient>, Vec<McpToolDef>)>> = OnceCell::const_new();
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>;
+
+enum McpConnection {
+    Stdio {
+        stdin: tokio::sync::Mutex<ChildStdin>,
+        // Held so the server lives as long as the client (`kill_on_drop`).
+        _child: Mutex<Child>,
+    },
+    Sse {
+        client: reqwest::Client,
+        endpoint: reqwest::Url,
+        headers: HashMap<String, String>,
+    },
+}
+
+/// JSON-RPC 2.0 session with a single MCP server.
+pub struct McpClient {
+    server: String,
+    connection: McpConnection,
+    pending: PendingMap,
+    next_id: AtomicU64,
+    timeout: Duration,
+}
+
+impl McpClient {
+    /// Connect to the server and complete the `initialize` handshake.
+    /// A stdio server inherits only the `inherited_env` variables.
+    pub async fn connect(
+        config: &McpServerConfig,
+        inherited_env: &[String],
+    ) -> anyhow::Result<Self> {
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let timeout = Duration::from_secs(config.timeout_secs.max(1));
+        let connection = match config.transport {
+            McpTransport::Stdio => Self::spawn_stdio(config, inherited_env, Arc::clone(&pending))?,
+            McpTransport::Sse => Self::open_sse(config, Arc::clone(&pending), timeout).await?,
+        };
+
+        let client = Self {
+            server: config.name.clone(),
+            connection,
+            pending,
+            next_id: AtomicU64::new(1),
+            timeout,
+        };
+
+        client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": MCP_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": "zeroclaw",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }),
+            )
+            .await
+            .with_context(|| format!("MCP server '{}' initialize failed", config.name))?;
+        client
+            .notify("notifications/initialized", json!({}))
+            .await?;
+
+        Ok(client)
+    }
+
+    fn spawn_stdio(
+        config: &McpServerConfig,
+        inherited_env: &[String],
+        pending: PendingMap,
+    ) -> anyhow::Result<McpConnection> {
+        let command = config
+            .command
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .with_context(|| format!("MCP server '{}' has no command", config.name))?;
+
+        let mut cmd = Command::new(command);
+        cmd.env_clear();
+        for var in inherited_env {
+            if let Ok(value) = std::env::var(var) {
+                cmd.env(var, value);
+            }
+        }
+        let mut child = cmd
+            .args(&config.args)
+            .envs(&config.env)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to spawn MCP server '{}' ({command})", config.name))?;
+
+        let stdin = child.stdin.take().context("MCP server stdin unavailable")?;
+        let stdout = child
+            .stdout
+            .take()
+            .context("MCP server stdout unavailable")?;
+
+        let server = config.name.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                dispatch_message(&server, &pending, &line);
+            }
+            tracing::debug!(server = %server, "MCP server closed stdout");
+        });
+
+        if let Some(stderr) = child.stderr.take() {
+            let server = config.name.clone();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    tracing::debug!(server = %server, "MCP stderr: {line}");
+                }
+            });
+        }
+
+        Ok(McpConnection::Stdio {
+            stdin: tokio::sync::Mutex::new(stdin),
+            _child: Mutex::new(child),
+        })
+    }
+
+    async fn open_sse(
+        config: &McpServerConfig,
+        pending: PendingMap,
+        timeout: Duration,
+    ) -> anyhow::Result<McpConnection> {
+        let url = config
+            .url
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .with_context(|| format!("MCP server '{}' has no url", config.name))?;
+        let base = reqwest::Url::parse(url)
+            .with_context(|| format!("Invalid MCP server url for '{}'", config.name))?;
+
+        let client = crate::config::build_runtime_proxy_client_with_timeouts(
+            "tool.mcp",
+            config.timeout_secs.max(1),
+            10,
+        );
+        // The event stream stays open for the lifetime of the session, so it
+        // must not inherit the per-request timeout.
+        let stream_client = crate::config::build_runtime_proxy_client("tool.mcp");
+
+        let mut request = stream_client
+            .get(base.clone())
+            .header("Accept", "text/event-stream");
+        for (key, value) in &config.headers {
+            request = request.header(key, value);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to open MCP event stream for '{}'", config.name))?;
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "MCP server '{}' event stream returned {}",
+                config.name,
+                response.status()
+            );
+        }
+
+        let (endpoint_tx, endpoint_rx) = oneshot::channel::<String>();
+        let server = config.name.clone();
+        tokio::spawn(async move {
+            let mut endpoint_tx = Some(endpoint_tx);
+            let mut parser = SseParser::default();
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                let Ok(chunk) = chunk else { break };
+                for event in parser.feed(&String::from_utf8_lossy(&chunk)) {
+                    match event.event.as_str() {
+                        "endpoint" => {
+                            if let Some(tx) = endpoint_tx.take() {
+                                let _ = tx.send(event.data);
+                            }
+                        }
+                        "message" => dispatch_message(&server, &pending, &event.data),
+                        _ => {}
+                    }
+                }
+            }
+            tracing::debug!(server = %server, "MCP event stream closed");
+        });
+
+        let endpoint = tokio::time::timeout(timeout, endpoint_rx)
+            .await
+            .with_context(|| format!("MCP server '{}' sent no endpoint event", config.name))?
+            .with_context(|| format!("MCP event stream for '{}' closed early", config.name))?;
+        let endpoint = base
+            .join(endpoint.trim())
+            .with_context(|| format!("Invalid MCP endpoint from '{}'", config.name))?;
+
+        Ok(McpConnection::Sse {
+            client,
+            endpoint,
+            headers: config.headers.clone(),
+        })
+    }
+
+    async fn send(&self, message: &Value) -> anyhow::Result<()> {
+        match &self.connection {
+            McpConnection::Stdio { stdin, .. } => {
+                let mut line = serde_json::to_string(message)?;
+                line.push('\n');
+                let mut stdin = stdin.lock().await;
+                stdin.write_all(line.as_bytes()).await?;
+                stdin.flush().await?;
+            }
+            McpConnection::Sse {
+                client,
+                endpoint,
+                headers,
+            } => {
+                let mut request = client.post(endpoint.clone()).json(message);
+                for (key, value) in headers {
+                    request = request.header(key, value);
+                }
+                let response = request.send().await?;
+                if !response.status().is_success() {
+                    anyhow::bail!("MCP endpoint returned {}", response.status());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Send a request and wait for the matching response's `result`.
+    pub async fn request(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(id, tx);
+
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        if let Err(e) = self.send(&message).await {
+            self.pending.lock().remove(&id);
+            return Err(e.context(format!("MCP server '{}' {method} send failed", self.server)));
+        }
+
+        let response = match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => anyhow::bail!("MCP server '{}' disconnected", self.server),
+            Err(_) => {
+                self.pending.lock().remove(&id);
+                anyhow::bail!(
+                    "MCP server '{}' {method} timed out after {}s",
+                    self.server,
+                    self.timeout.as_secs()
+                );
+            }
+        };
+
+        if let Some(error) = response.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error");
+            anyhow::bail!("MCP server '{}' {method} error: {message}", self.server);
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> anyhow::Result<()> {
+        self.send(&json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }))
+        .await
+    }
+
+    /// List all tools, following `nextCursor` pagination.
+    pub async fn list_tools(&self) -> anyhow::Result<Vec<McpToolDef>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(c) => json!({ "cursor": c }),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params).await?;
+            tools.extend(parse_tool_defs(&result));
+            cursor = result
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(tools)
+    }
+}
+
+/// Route a JSON-RPC response to its waiting request. Server-initiated
+/// requests and notifications are ignored.
+fn dispatch_message(server: &str, pending: &PendingMap, raw: &str) {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return;
+    }
+    let Ok(message) = serde_json::from_str::<Value>(raw) else {
+        tracing::debug!(server = %server, "Ignoring non-JSON MCP output");
+        return;
+    };
+    if message.get("method").is_some() {
+        return;
+    }
+    let Some(id) = message.get("id").and_then(Value::as_u64) else {
+        return;
+    };
+    if let Some(tx) = pending.lock().remove(&id) {
+        let _ = tx.send(message);
+    }
+}
+
+/// Tool definition reported by `tools/list`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpToolDef {
+    pub name: String,
+    pub description: String,
+    pub input_schema: Value,
+}
+
+fn parse_tool_defs(result: &Value) -> Vec<McpToolDef> {
+    result
+        .get("tools")
+        .and_then(Value::as_array)
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|tool| {
+                    let name = tool.get("name").and_then(Value::as_str)?.trim();
+                    if name.is_empty() {
+                        return None;
+                    }
+                    Some(McpToolDef {
+                        name: name.to_string(),
+                        description: tool
+                            .get("description")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .trim()
+                            .to_string(),
+                        input_schema: tool
+                            .get("inputSchema")
+                            .filter(|s| s.is_object())
+                            .cloned()
+                            .unwrap_or_else(|| json!({ "type": "object", "properties": {} })),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Build the registry name `<server>__<tool>`, restricted to the characters
+/// and length accepted by provider function-calling APIs.
+fn qualified_tool_name(server: &str, tool: &str) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    let mut name = format!("{}__{}", sanitize(server), sanitize(tool));
+    name.truncate(MAX_TOOL_NAME_LEN);
+    name
+}
+
+/// Flatten a `tools/call` result into text. Returns `(is_error, text)`.
+fn render_call_result(result: &Value) -> (bool, String) {
+    let is_error = result
+        .get("isError")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let mut text = String::new();
+    for item in result
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        match item.get("type").and_then(Value::as_str) {
+            Some("text") => {
+                text.push_str(item.get("text").and_then(Value::as_str).unwrap_or_default());
+            }
+            Some("resource") => {
+                let resource = item.get("resource").unwrap_or(&Value::Null);
+                match resource.get("text").and_then(Value::as_str) {
+                    Some(body) => text.push_str(body),
+                    None => {
+                        let uri = resource
+                            .get("uri")
+                            .and_then(Value::as_str)
+                            .unwrap_or("unknown");
+                        let _ = write!(text, "[resource: {uri}]");
+                    }
+                }
+            }
+            Some(kind) => {
+                let mime = item
+                    .get("mimeType")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown");
+                let _ = write!(text, "[{kind}: {mime}]");
+            }
+            None => text.push_str(&item.to_string()),
+        }
+    }
+    if text.is_empty() {
+        if let Some(structured) = result.get("structuredContent") {
+            text = structured.to_string();
+        }
+    }
+    (is_error, text)
+}
+
+/// Minimal incremental Server-Sent Events parser.
+#[derive(Default)]
+struct SseParser {
+    buffer: String,
+    event: String,
+    data: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+impl SseParser {
+    fn feed(&mut self, chunk: &str) -> Vec<SseEvent> {
+        self.buffer.push_str(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.find('\n') {
+            let line: String = self.buffer.drain(..=pos).collect();
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    let event = if self.event.is_empty() {
+                        "message".to_string()
+                    } else {
+                        std::mem::take(&mut self.event)
+                    };
+                    events.push(SseEvent {
+                        event,
+                        data: self.data.join("\n"),
+                    });
+                }
+                self.event.clear();
+                self.data.clear();
+            } else if let Some(value) = line.strip_prefix("event:") {
+                self.event = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+        }
+        events
+    }
+}
+
+/// A single remote tool proxied through an [`McpClient`].
+pub struct McpTool {
+    client: Arc<McpClient>,
+    name: String,
+    remote_name: String,
+    description: String,
+    input_schema: Value,
+    security: Arc<SecurityPolicy>,
+}
+
+impl McpTool {
+    pub fn new(client: Arc<McpClient>, def: McpToolDef, security: Arc<SecurityPolicy>) -> Self {
+        let description = if def.description.is_empty() {
+            format!("Tool '{}' from MCP server '{}'", def.name, client.server)
+        } else {
+            format!("[MCP {}] {}", client.server, def.description)
+        };
+        Self {
+            name: qualified_tool_name(&client.server, &def.name),
+            remote_name: def.name,
+            description,
+            input_schema: def.input_schema,
+            client,
+            security,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        self.input_schema.clone()
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if let Err(error) = self
+            .security
+            .enforce_tool_operation(ToolOperation::Act, &format!("mcp.{}", self.name))
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            });
+        }
+
+        let arguments = if args.is_object() { args } else { json!({}) };
+        let result = match self
+            .client
+            .request(
+                "tools/call",
+                json!({ "name": self.remote_name, "arguments": arguments }),
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                })
+            }
+        };
+
+        let (is_error, text) = render_call_result(&result);
+        if is_error {
+            Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(if text.is_empty() {
+                    format!("MCP tool '{}' reported an error", self.remote_name)
+                } else {
+                    text
+                }),
+            })
+        } else {
+            Ok(ToolResult {
+                success: true,
+                output: text,
+                error: None,
+            })
+        }
+    }
+}
+
+/// Connect to every configured MCP server and list its tools.
+///
+/// Servers that fail to connect are logged and skipped so one broken entry
+/// does not take down the agent.
+async fn connect_servers(
+    config: &McpConfig,
+    security: &SecurityPolicy,
+) -> Vec<(Arc<McpClient>, Vec<McpToolDef>)> {
+    let inherited_env = super::shell::collect_allowed_shell_env_vars(security);
+    let mut servers = Vec::new();
+    for server in &config.servers {
+        let client = match McpClient::connect(server, &inherited_env).await {
+            Ok(client) => Arc::new(client),
+            Err(e) => {
+                tracing::warn!(server = %server.name, "MCP server unavailable: {e:#}");
+                continue;
+            }
+        };
+        match client.list_tools().await {
+            Ok(defs) => {
+                tracing::info!(server = %server.name, count = defs.len(), "MCP tools loaded");
+                servers.push((client, defs));
+            }
+            Err(e) => {
+                tracing::warn!(server = %server.name, "MCP tools/list failed: {e:#}");
+            }
+        }
+    }
+    servers
+}
+
+/// Tools of every configured MCP server. The servers are connected on the
+/// first call; later calls reuse those connections, so config changes to
+/// `[mcp]` take effect after a restart.
+pub async fn create_mcp_tools(
+    config: &McpConfig,
+    security: Arc<SecurityPolicy>,
+) -> Vec<Box<dyn Tool>> {
+    let servers = CONNECTED_SERVERS
+        .get_or_init(|| connect_servers(config, &security))
+        .await;
+    servers
+        .iter()
+        .flat_map(|(client, defs)| {
+            defs.iter().map(|def| {
+                Box::new(McpTool::new(
+                    Arc::clone(client),
+                    def.clone(),
+                    Arc::clone(&security),
+                )) as Box<dyn Tool>
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qualified_tool_name_sanitizes_and_truncates() {
+        assert_eq!(
+            qualified_tool_name("git hub", "search.issues"),
+            "git_hub__search_issues"
+        );
+        let long = qualified_tool_name("server", &"x".repeat(100));
+        assert_eq!(long.len(), MAX_TOOL_NAME_LEN);
+        assert!(long.starts_with("server__"));
+    }
+
+    #[test]
+    fn parse_tool_defs_defaults_missing_schema() {
+        let result = json!({
+            "tools": [
+                { "name": "read_file", "description": "Read a file", "inputSchema": {
+                    "type": "object",
+                    "properties": { "path": { "type": "string" } },
+                    "required": ["path"]
+                }},
+                { "name": "ping" },
+                { "description": "nameless" }
+            ]
+        });
+        let defs = parse_tool_defs(&result);
+        assert_eq!(defs.len(), 2);
+        assert_eq!(defs[0].name, "read_file");
+        assert_eq!(defs[0].input_schema["required"][0], "path");
+        assert_eq!(defs[1].description, "");
+        assert_eq!(defs[1].input_schema["type"], "object");
+    }
+
+    #[test]
+    fn render_call_result_flattens_content() {
+        let result = json!({
+            "content": [
+                { "type": "text", "text": "first" },
+                { "type": "image", "data": "AAAA", "mimeType": "image/png" },
+                { "type": "resource", "resource": { "uri": "file:///a", "text": "body" } }
+            ]
+        });
+        let (is_error, text) = render_call_result(&result);
+        assert!(!is_error);
+        assert_eq!(text, "first\n[image: image/png]\nbody");
+
+        let (is_error, text) = render_call_result(&json!({
+            "isError": true,
+            "content": [{ "type": "text", "text": "boom" }]
+        }));
+        assert!(is_error);
+        assert_eq!(text, "boom");
+    }
+
+    #[test]
+    fn sse_parser_handles_split_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser
+            .feed("event: endpoint\r\ndata: /messages?s")
+            .is_empty());
+        let events = parser.feed("ession=1\r\n\r\ndata: {\"id\":1}\n\n");
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "endpoint".into(),
+                    data: "/messages?session=1".into(),
+                },
+                SseEvent {
+                    event: "message".into(),
+                    data: "{\"id\":1}".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn dispatch_message_routes_responses_only() {
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let (tx, mut rx) = oneshot::channel();
+        pending.lock().insert(7, tx);
+
+        dispatch_message("s", &pending, r#"{"jsonrpc":"2.0","id":7,"method":"ping"}"#);
+        assert!(rx.try_recv().is_err());
+
+        dispatch_message(
+            "s",
+            &pending,
+            r#"{"jsonrpc":"2.0","id":7,"result":{"ok":true}}"#,
+        );
+        assert_eq!(rx.try_recv().unwrap()["result"]["ok"], true);
+        assert!(pending.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stdio_connect_reports_missing_command() {
+        let config = McpServerConfig {
+            name: "broken".into(),
+            transport: McpTransport::Stdio,
+            command: None,
+            args: Vec::new(),
+            env: HashMap::new(),
+            url: None,
+            headers: HashMap::new(),
+            timeout_secs: 5,
+        };
+        let err = McpClient::connect(&config, &[]).await.err().unwrap();
+        assert!(err.to_string().contains("has no command"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_server_gets_a_scrubbed_environment() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dump = tmp.path().join("env.txt");
+        std::env::set_var("ZEROCLAW_MCP_TEST_SECRET", "hunter2");
+        let config = McpServerConfig {
+            name: "env".into(),
+            transport: McpTransport::Stdio,
+            command: Some("sh".into()),
+            args: vec!["-c".into(), format!("env > {}", dump.display())],
+            env: HashMap::from([("SERVER_TOKEN".to_string(), "abc".to_string())]),
+            url: None,
+            headers: HashMap::new(),
+            timeout_secs: 1,
+        };
+        let _ = McpClient::connect(&config, &["PATH".to_string()]).await;
+
+        let env = std::fs::read_to_string(&dump).unwrap();
+        assert!(env.contains("SERVER_TOKEN=abc"));
+        assert!(env.contains("PATH="));
+        assert!(!env.contains("ZEROCLAW_MCP_TEST_SECRET"));
+    }
+}
@@ -17,7 +17,10 @@
 
 pub mod browser;
 pub mod browser_open;
+pub mod calendar;
+pub mod channel_forward;
 pub mod cli_discovery;
+pub mod clipboard;
 pub mod composio;
 pub mod content_search;
 pub mod cron_add;
@@ -27,9 +30,13 @@ pub mod cron_run;
 pub mod cron_runs;
 pub mod cron_update;
 pub mod delegate;
+pub mod desktop_input;
+pub mod extract_document;
 pub mod file_edit;
 pub mod file_read;
 pub mod file_write;
+pub mod generate_image;
+pub mod git;
 pub mod git_operations;
 pub mod glob_search;
 #[cfg(feature = "hardware")]
@@ -38,25 +45,40 @@ pub mod hardware_board_info;
 pub mod hardware_memory_map;
 #[cfg(feature = "hardware")]
 pub mod hardware_memory_read;
+pub mod home_assistant;
 pub mod http_request;
 pub mod image_info;
+pub mod mcp;
 pub mod memory_forget;
 pub mod memory_recall;
 pub mod memory_store;
 pub mod model_routing_config;
+pub mod ocr;
+pub mod output_limit;
 pub mod pdf_read;
 pub mod proxy_config;
 pub mod pushover;
+pub mod record_screen;
+pub mod reminders;
+pub mod run_code;
 pub mod schedule;
 pub mod schema;
 pub mod screenshot;
+pub mod search_knowledge;
+pub mod send_email;
 pub mod shell;
+pub mod sql_query;
 pub mod traits;
+pub mod transcribe_media;
+pub mod untrusted;
 pub mod web_fetch;
 pub mod web_search_tool;
 
 pub use browser::{BrowserTool, ComputerUseConfig};
 pub use browser_open::BrowserOpenTool;
+pub use calendar::CalendarTool;
+pub use channel_forward::ChannelForwardTool;
+pub use clipboard::{ClipboardReadTool, ClipboardWriteTool};
 pub use composio::ComposioTool;
 pub use content_search::ContentSearchTool;
 pub use cron_add::CronAddTool;
@@ -66,9 +88,13 @@ pub use cron_run::CronRunTool;
 pub use cron_runs::CronRunsTool;
 pub use cron_update::CronUpdateTool;
 pub use delegate::DelegateTool;
+pub use desktop_input::DesktopInputTool;
+pub use extract_document::ExtractDocumentTool;
 pub use file_edit::FileEditTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
+pub use generate_image::GenerateImageTool;
+pub use git::GitTool;
 pub use git_operations::GitOperationsTool;
 pub use glob_search::GlobSearchTool;
 #[cfg(feature = "hardware")]
@@ -77,23 +103,32 @@ pub use hardware_board_info::HardwareBoardInfoTool;
 pub use hardware_memory_map::HardwareMemoryMapTool;
 #[cfg(feature = "hardware")]
 pub use hardware_memory_read::HardwareMemoryReadTool;
+pub use home_assistant::HomeAssistantTool;
 pub use http_request::HttpRequestTool;
 pub use image_info::ImageInfoTool;
 pub use memory_forget::MemoryForgetTool;
 pub use memory_recall::MemoryRecallTool;
 pub use memory_store::MemoryStoreTool;
 pub use model_routing_config::ModelRoutingConfigTool;
+pub use ocr::OcrTool;
 pub use pdf_read::PdfReadTool;
 pub use proxy_config::ProxyConfigTool;
 pub use pushover::PushoverTool;
+pub use record_screen::RecordScreenTool;
+pub use reminders::{CancelReminderTool, ListRemindersTool, SetReminderTool};
+pub use run_code::RunCodeTool;
 pub use schedule::ScheduleTool;
 #[allow(unused_imports)]
 pub use schema::{CleaningStrategy, SchemaCleanr};
 pub use screenshot::ScreenshotTool;
+pub use search_knowledge::SearchKnowledgeTool;
+pub use send_email::SendEmailTool;
 pub use shell::ShellTool;
+pub use sql_query::SqlQueryTool;
 pub use traits::Tool;
 #[allow(unused_imports)]
 pub use traits::{ToolResult, ToolSpec};
+pub use transcribe_media::TranscribeMediaTool;
 pub use web_fetch::WebFetchTool;
 pub use web_search_tool::WebSearchTool;
 
@@ -222,6 +257,9 @@ pub fn all_tools_with_runtime(
         Arc::new(CronUpdateTool::new(config.clone(), security.clone())),
         Arc::new(CronRunTool::new(config.clone(), security.clone())),
         Arc::new(CronRunsTool::new(config.clone())),
+        Arc::new(SetReminderTool::new(config.clone(), security.clone())),
+        Arc::new(ListRemindersTool::new(config.clone())),
+        Arc::new(CancelReminderTool::new(config.clone(), security.clone())),
         Arc::new(MemoryStoreTool::new(memory.clone(), security.clone())),
         Arc::new(MemoryRecallTool::new(memory.clone())),
         Arc::new(MemoryForgetTool::new(memory, security.clone())),
@@ -239,6 +277,7 @@ pub fn all_tools_with_runtime(
             security.clone(),
             workspace_dir.to_path_buf(),
         )),
+        Arc::new(ChannelForwardTool::new(config.clone(), security.clone())),
     ];
 
     if browser_config.enabled {
@@ -299,11 +338,90 @@ pub fn all_tools_with_runtime(
 
     // PDF extraction (feature-gated at compile time via rag-pdf)
     tool_arcs.push(Arc::new(PdfReadTool::new(security.clone())));
+    tool_arcs.push(Arc::new(ExtractDocumentTool::new(security.clone())));
+    tool_arcs.push(Arc::new(TranscribeMediaTool::new(
+        security.clone(),
+        root_config.transcription.clone(),
+    )));
 
     // Vision tools are always available
     tool_arcs.push(Arc::new(ScreenshotTool::new(security.clone())));
+    tool_arcs.push(Arc::new(RecordScreenTool::new(security.clone())));
     tool_arcs.push(Arc::new(ImageInfoTool::new(security.clone())));
 
+    if root_config.knowledge.enabled {
+        tool_arcs.push(Arc::new(SearchKnowledgeTool::new(root_config)));
+    }
+
+    if root_config.desktop.enabled {
+        tool_arcs.push(Arc::new(ClipboardReadTool::new(&root_config.desktop)));
+        tool_arcs.push(Arc::new(ClipboardWriteTool::new(
+            security.clone(),
+            &root_config.desktop,
+        )));
+        if root_config.desktop.allow_input {
+            tool_arcs.push(Arc::new(DesktopInputTool::new(security.clone())));
+        }
+    }
+
+    if root_config.multimodal.ocr.enabled {
+        tool_arcs.push(Arc::new(OcrTool::new(
+            security.clone(),
+            root_config.multimodal.clone(),
+            ocr::vision_ocr_from_config(root_config),
+        )));
+    }
+
+    if root_config.image_generation.enabled {
+        tool_arcs.push(Arc::new(GenerateImageTool::new(
+            security.clone(),
+            root_config.image_generation.clone(),
+        )));
+    }
+
+    if root_config.run_code.enabled {
+        tool_arcs.push(Arc::new(RunCodeTool::new(
+            security.clone(),
+            root_config.run_code.clone(),
+        )));
+    }
+
+    if root_config.sql_query.enabled {
+        tool_arcs.push(Arc::new(SqlQueryTool::new(
+            security.clone(),
+            root_config.sql_query.clone(),
+        )));
+    }
+
+    if root_config.git.enabled {
+        tool_arcs.push(Arc::new(GitTool::new(
+            security.clone(),
+            root_config.git.clone(),
+        )));
+    }
+
+    if root_config.calendar.enabled {
+        tool_arcs.push(Arc::new(CalendarTool::new(
+            security.clone(),
+            root_config.calendar.clone(),
+        )));
+    }
+
+    if root_config.send_email.enabled {
+        tool_arcs.push(Arc::new(SendEmailTool::new(
+            security.clone(),
+            root_config.send_email.clone(),
+            root_config.channels_config.email.clone(),
+        )));
+    }
+
+    if root_config.home_assistant.enabled {
+        tool_arcs.push(Arc::new(HomeAssistantTool::new(
+            security.clone(),
+            root_config.home_assistant.clone(),
+        )));
+    }
+
     if let Some(key) = composio_key {
         if !key.is_empty() {
             tool_arcs.push(Arc::new(ComposioTool::new(
@@ -338,6 +456,7 @@ pub fn all_tools_with_runtime(
                     .map(std::path::PathBuf::from),
                 secrets_encrypt: root_config.secrets.encrypt,
                 reasoning_enabled: root_config.runtime.reasoning_enabled,
+                redaction: root_config.security.redaction.clone(),
             },
         )
         .with_parent_tools(parent_tools)
@@ -345,7 +464,10 @@ pub fn all_tools_with_runtime(
         tool_arcs.push(Arc::new(delegate_tool));
     }
 
-    boxed_registry_from_arcs(tool_arcs)
+    untrusted::guard_tool_outputs(
+        output_limit::limit_tool_outputs(boxed_registry_from_arcs(tool_arcs), root_config),
+        root_config,
+    )
 }
 
 #[cfg(test)]
@@ -453,6 +575,43 @@ mod tests {
         assert!(names.contains(&"proxy_config"));
     }
 
+    #[test]
+    fn all_tools_includes_ocr_only_when_enabled() {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy::default());
+        let mem_cfg = MemoryConfig {
+            backend: "markdown".into(),
+            ..MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> =
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+        let mut cfg = test_config(&tmp);
+
+        let ocr_registered = |cfg: &Config| {
+            all_tools(
+                Arc::new(Config::default()),
+                &security,
+                mem.clone(),
+                None,
+                None,
+                &BrowserConfig::default(),
+                &crate::config::HttpRequestConfig::default(),
+                &crate::config::WebFetchConfig::default(),
+                tmp.path(),
+                &HashMap::new(),
+                None,
+                cfg,
+            )
+            .iter()
+            .any(|tool| tool.name() == "ocr")
+        };
+
+        assert!(!ocr_registered(&cfg));
+        cfg.multimodal.ocr.enabled = true;
+        cfg.multimodal.ocr.backend = crate::config::OcrBackend::Tesseract;
+        assert!(ocr_registered(&cfg));
+    }
+
     #[test]
     fn default_tools_names() {
         let security = Arc::new(SecurityPolicy::default());
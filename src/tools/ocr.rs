@@ -0,0 +1,419 @@
+//! Text extraction from images.
+//!
+//! The `ocr` tool reads the text in a screenshot or photo with a local
+//! `tesseract` binary or, as a fallback, a vision-capable model. Providers
+//! without vision support (e.g. Kiro in text mode) get the same text in place
+//! of `[IMAGE:]` markers through [`transcribe_image_markers`].
+
+use super::traits::{Tool, ToolResult};
+use crate::channels::audio_tools::run_local_tool_with_input;
+use crate::config::{Config, MultimodalConfig, OcrBackend, OcrConfig};
+use crate::multimodal;
+use crate::providers::{self, ChatMessage, Provider};
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+pub const OCR_TOOL_NAME: &str = "ocr";
+
+const OCR_PROMPT: &str = "You are an OCR engine. Transcribe all text visible in the image exactly as written, keeping line breaks and reading order. Do not describe the image or add commentary. If there is no text, reply with nothing.";
+
+/// Vision model used when tesseract is not available or not configured.
+pub struct VisionOcr {
+    provider: Box<dyn Provider>,
+    model: String,
+}
+
+/// Build the vision fallback from `[multimodal.ocr]`, falling back to the
+/// default provider and model. `None` for the `tesseract` backend.
+pub fn vision_ocr_from_config(config: &Config) -> Option<VisionOcr> {
+    let ocr = &config.multimodal.ocr;
+    if ocr.backend == OcrBackend::Tesseract {
+        return None;
+    }
+    let provider_name = ocr
+        .provider
+        .as_deref()
+        .or(config.default_provider.as_deref())?;
+    let model = ocr.model.clone().or_else(|| config.default_model.clone())?;
+
+    let options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        provider_api_url: config.api_url.clone(),
+        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        redaction: config.security.redaction.clone(),
+    };
+    match providers::create_provider_with_options(
+        provider_name,
+        config.api_key.as_deref(),
+        &options,
+    ) {
+        Ok(provider) => Some(VisionOcr { provider, model }),
+        Err(e) => {
+            tracing::warn!("OCR vision provider `{provider_name}` unavailable: {e}");
+            None
+        }
+    }
+}
+
+/// Extract text from images in the workspace.
+pub struct OcrTool {
+    security: Arc<SecurityPolicy>,
+    config: OcrConfig,
+    multimodal: MultimodalConfig,
+    vision: Option<VisionOcr>,
+}
+
+impl OcrTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        multimodal: MultimodalConfig,
+        vision: Option<VisionOcr>,
+    ) -> Self {
+        Self {
+            security,
+            config: multimodal.ocr.clone(),
+            multimodal,
+            vision,
+        }
+    }
+
+    /// Text in the image at `source` (a data URI or an allowed remote URL,
+    /// or a local path already checked against the security policy).
+    async fn extract_text(&self, source: &str) -> anyhow::Result<String> {
+        let data_uri = multimodal::load_image_data_uri(source, &self.multimodal).await?;
+        match self.config.backend {
+            OcrBackend::Tesseract => self.tesseract(&data_uri).await,
+            OcrBackend::Vision => self.vision(&data_uri).await,
+            OcrBackend::Auto => match self.tesseract(&data_uri).await {
+                Ok(text) => Ok(text),
+                Err(e) if self.vision.is_some() => {
+                    tracing::debug!("tesseract failed, using vision OCR: {e}");
+                    self.vision(&data_uri).await
+                }
+                Err(e) => Err(e),
+            },
+        }
+    }
+
+    async fn tesseract(&self, data_uri: &str) -> anyhow::Result<String> {
+        let (_, payload) = data_uri
+            .split_once(',')
+            .context("Image data URI has no payload")?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(payload.trim())
+            .context("Image data is not valid base64")?;
+
+        let mut command = tokio::process::Command::new(&self.config.tesseract_command);
+        command
+            .args(["stdin", "stdout", "-l"])
+            .arg(&self.config.languages);
+        let output =
+            run_local_tool_with_input(command, &self.config.tesseract_command, Some(&bytes))
+                .await?;
+        Ok(String::from_utf8_lossy(&output).trim().to_string())
+    }
+
+    async fn vision(&self, data_uri: &str) -> anyhow::Result<String> {
+        let Some(vision) = &self.vision else {
+            bail!("No vision model configured for OCR; set [multimodal.ocr] provider and model");
+        };
+        if !vision.provider.supports_vision() {
+            bail!(
+                "OCR model `{}` has no vision support; set [multimodal.ocr] provider and model",
+                vision.model
+            );
+        }
+        let messages = vec![
+            ChatMessage::system(OCR_PROMPT),
+            ChatMessage::user(format!("[IMAGE:{data_uri}]")),
+        ];
+        let text = vision
+            .provider
+            .chat_with_history(&messages, &vision.model, 0.0)
+            .await?;
+        Ok(text.trim().to_string())
+    }
+
+    /// Check a local path against the security policy and resolve it.
+    async fn resolve_local_path(&self, path: &str) -> Result<String, String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        let full_path = self.security.workspace_dir.join(path);
+        let resolved = tokio::fs::canonicalize(&full_path)
+            .await
+            .map_err(|e| format!("Failed to resolve file path: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(self.security.resolved_path_violation_message(&resolved));
+        }
+        Ok(resolved.display().to_string())
+    }
+}
+
+fn is_inline_or_remote(source: &str) -> bool {
+    source.starts_with("data:") || source.starts_with("http://") || source.starts_with("https://")
+}
+
+#[async_trait]
+impl Tool for OcrTool {
+    fn name(&self) -> &str {
+        OCR_TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "Extract the text from an image (screenshot, photo of a document or sign) using OCR. \
+         Use it to read images you cannot see directly."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Image file path (relative to the workspace), data URI, or image URL"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        let source = if is_inline_or_remote(path) {
+            path.to_string()
+        } else {
+            match self.resolve_local_path(path).await {
+                Ok(resolved) => resolved,
+                Err(error) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(error),
+                    })
+                }
+            }
+        };
+
+        match self.extract_text(&source).await {
+            Ok(text) if text.is_empty() => Ok(ToolResult {
+                success: true,
+                output: "No text found in the image.".into(),
+                error: None,
+            }),
+            Ok(text) => Ok(ToolResult {
+                success: true,
+                output: text,
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("OCR failed: {e}")),
+            }),
+        }
+    }
+}
+
+/// Short name for an image reference in transcribed messages.
+fn image_label(source: &str) -> &str {
+    if source.starts_with("data:") {
+        return "inline image";
+    }
+    source
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or(source)
+}
+
+/// Replace the `[IMAGE:]` markers in user messages with the text `ocr` reads
+/// from each image, for providers that cannot take images. Returns the number
+/// of images replaced.
+///
+/// Images are loaded like the vision pipeline loads them (same limits, no
+/// workspace path policy) and handed to the tool as data URIs.
+pub async fn transcribe_image_markers(
+    history: &mut [ChatMessage],
+    ocr: &dyn Tool,
+    config: &MultimodalConfig,
+) -> usize {
+    let mut transcribed = 0;
+    for message in history.iter_mut().filter(|m| m.role == "user") {
+        let (text, refs) = multimodal::parse_image_markers(&message.content);
+        if refs.is_empty() {
+            continue;
+        }
+
+        let mut content = text;
+        for source in &refs {
+            let label = image_label(source);
+            if !content.is_empty() {
+                content.push_str("\n\n");
+            }
+            let result = match multimodal::load_image_data_uri(source, config).await {
+                Ok(data_uri) => ocr.execute(json!({ "path": data_uri })).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(result) if result.success => {
+                    let _ = write!(
+                        content,
+                        "[Image {label} — text extracted by OCR]\n{}",
+                        result.output.trim()
+                    );
+                }
+                Ok(result) => {
+                    let error = result.error.unwrap_or_default();
+                    let _ = write!(content, "[Image {label} could not be read: {error}]");
+                }
+                Err(e) => {
+                    let _ = write!(content, "[Image {label} could not be read: {e}]");
+                }
+            }
+            transcribed += 1;
+        }
+        message.content = content;
+    }
+    transcribed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::ProviderCapabilities;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    const PNG_BYTES: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+    struct ReaderProvider;
+
+    #[async_trait]
+    impl Provider for ReaderProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                vision: true,
+                ..ProviderCapabilities::default()
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            assert!(message.contains("data:image/png;base64,"));
+            Ok("  TOTAL: 42.00 EUR \n".into())
+        }
+    }
+
+    fn tool(tmp: &TempDir, vision: Option<VisionOcr>) -> OcrTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let multimodal = MultimodalConfig {
+            ocr: OcrConfig {
+                enabled: true,
+                backend: OcrBackend::Vision,
+                ..OcrConfig::default()
+            },
+            ..MultimodalConfig::default()
+        };
+        OcrTool::new(security, multimodal, vision)
+    }
+
+    fn reader() -> Option<VisionOcr> {
+        Some(VisionOcr {
+            provider: Box::new(ReaderProvider),
+            model: "vision-model".into(),
+        })
+    }
+
+    #[tokio::test]
+    async fn vision_backend_reads_workspace_image() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("receipt.png"), PNG_BYTES).unwrap();
+
+        let result = tool(&tmp, reader())
+            .execute(json!({"path": "receipt.png"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "TOTAL: 42.00 EUR");
+    }
+
+    #[tokio::test]
+    async fn vision_backend_without_model_reports_error() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("receipt.png"), PNG_BYTES).unwrap();
+
+        let result = tool(&tmp, None)
+            .execute(json!({"path": "receipt.png"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("[multimodal.ocr]"));
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let result = tool(&tmp, reader())
+            .execute(json!({"path": "/etc/passwd"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn transcribes_image_markers_in_user_messages() {
+        let tmp = TempDir::new().unwrap();
+        let image = tmp.path().join("receipt.png");
+        std::fs::write(&image, PNG_BYTES).unwrap();
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user(format!("How much was this? [IMAGE:{}]", image.display())),
+        ];
+
+        let count = transcribe_image_markers(
+            &mut history,
+            &tool(&tmp, reader()),
+            &MultimodalConfig::default(),
+        )
+        .await;
+
+        assert_eq!(count, 1);
+        assert_eq!(
+            history[1].content,
+            "How much was this?\n\n[Image receipt.png — text extracted by OCR]\nTOTAL: 42.00 EUR"
+        );
+        assert_eq!(multimodal::count_image_markers(&history), 0);
+    }
+}
@@ -0,0 +1,232 @@
+//! Size limit for tool results.
+//!
+//! Outputs larger than `[agent] max_tool_output_tokens` are cut down to a
+//! head and tail excerpt before they reach the model. The complete output is
+//! written to `tool_output/` in the workspace and the excerpt ends with its
+//! path, so the model can page through it with `file_read` or hand it to the
+//! user as a `[DOCUMENT:path]` attachment.
+
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Rough characters-per-token ratio used to turn the budget into characters.
+const CHARS_PER_TOKEN: usize = 4;
+/// Share of the budget kept from the start of the output; the rest is the tail.
+const HEAD_SHARE_PERCENT: usize = 70;
+
+/// Token budget and spill-over directory for tool results.
+pub struct OutputLimit {
+    max_chars: usize,
+    workspace_dir: PathBuf,
+}
+
+impl OutputLimit {
+    /// `None` when `max_tool_output_tokens = 0` (no limit).
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let tokens = config.agent.max_tool_output_tokens;
+        (tokens > 0).then(|| Self {
+            max_chars: tokens.saturating_mul(CHARS_PER_TOKEN),
+            workspace_dir: config.workspace_dir.clone(),
+        })
+    }
+
+    fn spill_dir(&self) -> PathBuf {
+        self.workspace_dir.join("tool_output")
+    }
+
+    /// Truncate `output` to the budget, saving the full text when it is cut.
+    pub async fn apply(&self, tool: &str, output: String) -> String {
+        let total = output.chars().count();
+        if total <= self.max_chars {
+            return output;
+        }
+
+        // Reading a file back in full would just spill it again: point at
+        // paging instead.
+        let saved = if tool == "file_read" {
+            None
+        } else {
+            match self.spill(tool, &output).await {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    tracing::warn!(tool, "Failed to save full tool output: {e}");
+                    None
+                }
+            }
+        };
+
+        let mut limited = excerpt(&output, self.max_chars);
+        let _ = write!(
+            limited,
+            "\n\n[Output truncated: {total} characters, showing the first and last parts."
+        );
+        match saved {
+            Some(path) => {
+                let relative = path.strip_prefix(&self.workspace_dir).unwrap_or(&path);
+                let _ = write!(
+                    limited,
+                    " Full output saved to {} — read it in parts with file_read (offset/limit) \
+                     or send it to the user as [DOCUMENT:{}].]",
+                    relative.display(),
+                    path.display()
+                );
+            }
+            None if tool == "file_read" => {
+                limited.push_str(" Use offset/limit to read the rest.]");
+            }
+            None => limited.push(']'),
+        }
+        limited
+    }
+
+    async fn spill(&self, tool: &str, output: &str) -> anyhow::Result<PathBuf> {
+        let dir = self.spill_dir();
+        tokio::fs::create_dir_all(&dir).await?;
+        let path = dir.join(spill_file_name(tool));
+        tokio::fs::write(&path, output).await?;
+        Ok(path)
+    }
+}
+
+fn spill_file_name(tool: &str) -> String {
+    let tool: String = tool
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    format!(
+        "{tool}_{}_{}.txt",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+        &id[..8]
+    )
+}
+
+/// First and last parts of `text`, `max_chars` characters in total.
+fn excerpt(text: &str, max_chars: usize) -> String {
+    let head_chars = max_chars * HEAD_SHARE_PERCENT / 100;
+    let tail_chars = max_chars - head_chars;
+    let total = text.chars().count();
+
+    let head_end = text
+        .char_indices()
+        .nth(head_chars)
+        .map_or(text.len(), |(i, _)| i);
+    let tail_start = text
+        .char_indices()
+        .nth(total.saturating_sub(tail_chars))
+        .map_or(text.len(), |(i, _)| i)
+        .max(head_end);
+
+    format!("{}\n…\n{}", &text[..head_end], &text[tail_start..])
+}
+
+/// A tool whose successful output goes through an [`OutputLimit`].
+struct LimitedTool {
+    inner: Box<dyn Tool>,
+    limit: Arc<OutputLimit>,
+}
+
+#[async_trait]
+impl Tool for LimitedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner.parameters_schema()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let mut result = self.inner.execute(args).await?;
+        result.output = self.limit.apply(self.inner.name(), result.output).await;
+        Ok(result)
+    }
+}
+
+/// Apply the configured output limit to every tool in `tools`.
+pub fn limit_tool_outputs(tools: Vec<Box<dyn Tool>>, config: &Config) -> Vec<Box<dyn Tool>> {
+    let Some(limit) = OutputLimit::from_config(config).map(Arc::new) else {
+        return tools;
+    };
+    tools
+        .into_iter()
+        .map(|inner| {
+            Box::new(LimitedTool {
+                inner,
+                limit: Arc::clone(&limit),
+            }) as Box<dyn Tool>
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn limit(tmp: &TempDir, tokens: usize) -> OutputLimit {
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            agent: crate::config::AgentConfig {
+                max_tool_output_tokens: tokens,
+                ..crate::config::AgentConfig::default()
+            },
+            ..Config::default()
+        };
+        OutputLimit::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn excerpt_keeps_head_and_tail_on_char_boundaries() {
+        let text = "é".repeat(50) + &"x".repeat(50);
+        let cut = excerpt(&text, 10);
+        assert!(cut.starts_with("ééééééé\n…\n"));
+        assert!(cut.ends_with("xxx"));
+    }
+
+    #[tokio::test]
+    async fn small_output_is_untouched() {
+        let tmp = TempDir::new().unwrap();
+        let out = limit(&tmp, 100).apply("shell", "hello".into()).await;
+        assert_eq!(out, "hello");
+        assert!(!tmp.path().join("tool_output").exists());
+    }
+
+    #[tokio::test]
+    async fn large_output_spills_to_workspace_file() {
+        let tmp = TempDir::new().unwrap();
+        let full = (0..2000).fold(String::new(), |mut full, i| {
+            let _ = writeln!(full, "line {i}");
+            full
+        });
+        let out = limit(&tmp, 50).apply("shell", full.clone()).await;
+
+        assert!(out.starts_with("line 0\n"));
+        assert!(out.contains("line 1999"));
+        assert!(out.contains("Full output saved to tool_output"));
+
+        let saved: Vec<_> = std::fs::read_dir(tmp.path().join("tool_output"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(saved.len(), 1);
+        assert!(out.contains(&format!("[DOCUMENT:{}]", saved[0].display())));
+        assert_eq!(std::fs::read_to_string(&saved[0]).unwrap(), full);
+    }
+
+    #[tokio::test]
+    async fn file_read_is_truncated_without_spilling() {
+        let tmp = TempDir::new().unwrap();
+        let out = limit(&tmp, 10).apply("file_read", "x".repeat(500)).await;
+        assert!(out.ends_with("Use offset/limit to read the rest.]"));
+        assert!(!tmp.path().join("tool_output").exists());
+    }
+}
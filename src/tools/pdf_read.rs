@@ -159,75 +159,62 @@ impl Tool for PdfReadTool {
             }
         };
 
-        // pdf_extract is a blocking CPU-bound operation; keep it off the async executor.
-        #[cfg(feature = "rag-pdf")]
-        {
-            let text = match tokio::task::spawn_blocking(move || {
-                pdf_extract::extract_text_from_mem(&bytes)
-            })
-            .await
-            {
-                Ok(Ok(t)) => t,
-                Ok(Err(e)) => {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(format!("PDF extraction failed: {e}")),
-                    });
-                }
-                Err(e) => {
-                    return Ok(ToolResult {
-                        success: false,
-                        output: String::new(),
-                        error: Some(format!("PDF extraction task panicked: {e}")),
-                    });
-                }
-            };
-
-            if text.trim().is_empty() {
+        let text = match extract_pdf_text(bytes).await {
+            Ok(text) => text,
+            Err(e) => {
                 return Ok(ToolResult {
-                    success: true,
-                    // Agent dispatchers currently forward `error` only when `success=false`.
-                    // Keep this as successful execution and expose the warning in `output`.
-                    output: "PDF contains no extractable text (may be image-only or encrypted)"
-                        .into(),
-                    error: None,
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
                 });
             }
+        };
 
-            let output = if text.chars().count() > max_chars {
-                let mut truncated: String = text.chars().take(max_chars).collect();
-                use std::fmt::Write as _;
-                let _ = write!(truncated, "\n\n... [truncated at {max_chars} chars]");
-                truncated
-            } else {
-                text
-            };
-
+        if text.trim().is_empty() {
             return Ok(ToolResult {
                 success: true,
-                output,
+                // Agent dispatchers currently forward `error` only when `success=false`.
+                // Keep this as successful execution and expose the warning in `output`.
+                output: "PDF contains no extractable text (may be image-only or encrypted)".into(),
                 error: None,
             });
         }
 
-        #[cfg(not(feature = "rag-pdf"))]
-        {
-            let _ = bytes;
-            let _ = max_chars;
-            Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(
-                    "PDF extraction is not enabled. \
-                     Rebuild with: cargo build --features rag-pdf"
-                        .into(),
-                ),
-            })
-        }
+        let output = if text.chars().count() > max_chars {
+            let mut truncated: String = text.chars().take(max_chars).collect();
+            use std::fmt::Write as _;
+            let _ = write!(truncated, "\n\n... [truncated at {max_chars} chars]");
+            truncated
+        } else {
+            text
+        };
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+/// Plain text of a PDF. Shared with `extract_document`.
+#[cfg(feature = "rag-pdf")]
+pub(super) async fn extract_pdf_text(bytes: Vec<u8>) -> anyhow::Result<String> {
+    // pdf_extract is a blocking CPU-bound operation; keep it off the async executor.
+    match tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes)).await {
+        Ok(Ok(text)) => Ok(text),
+        Ok(Err(e)) => anyhow::bail!("PDF extraction failed: {e}"),
+        Err(e) => anyhow::bail!("PDF extraction task panicked: {e}"),
     }
 }
 
+#[cfg(not(feature = "rag-pdf"))]
+pub(super) fn extract_pdf_text(_bytes: Vec<u8>) -> std::future::Ready<anyhow::Result<String>> {
+    std::future::ready(Err(anyhow::anyhow!(
+        "PDF extraction is not enabled. Rebuild with: cargo build --features rag-pdf"
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,265 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Longest recording the tool will make.
+const MAX_DURATION_SECS: u64 = 60;
+const DEFAULT_DURATION_SECS: u64 = 10;
+const FRAMES_PER_SECOND: &str = "15";
+/// Time allowed on top of the recording for ffmpeg to start and finish
+/// encoding.
+const ENCODE_GRACE_SECS: u64 = 30;
+
+/// Tool for recording the screen to an MP4 with ffmpeg.
+///
+/// Linux: `x11grab` on `$DISPLAY` (X11 sessions only)
+/// macOS: `avfoundation` ("Capture screen 0")
+/// Windows: `gdigrab`
+pub struct RecordScreenTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl RecordScreenTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+
+    /// ffmpeg input arguments for the current platform.
+    fn capture_input() -> Option<Vec<String>> {
+        let (format, input) = if cfg!(target_os = "macos") {
+            ("avfoundation", "Capture screen 0:none".to_string())
+        } else if cfg!(target_os = "linux") {
+            (
+                "x11grab",
+                std::env::var("DISPLAY").unwrap_or_else(|_| ":0".into()),
+            )
+        } else if cfg!(target_os = "windows") {
+            ("gdigrab", "desktop".to_string())
+        } else {
+            return None;
+        };
+        let mut args = vec!["-f".to_string(), format.into()];
+        if format == "avfoundation" {
+            args.extend(["-capture_cursor".into(), "1".into()]);
+        }
+        args.extend(["-framerate".into(), FRAMES_PER_SECOND.into()]);
+        args.extend(["-i".into(), input]);
+        Some(args)
+    }
+
+    /// Full ffmpeg argument list recording `duration_secs` into `output`.
+    fn ffmpeg_args(input: Vec<String>, duration_secs: u64, output: &str) -> Vec<String> {
+        let mut args: Vec<String> = ["-nostdin", "-loglevel", "error", "-y"]
+            .map(String::from)
+            .to_vec();
+        args.extend(input);
+        args.extend(
+            [
+                "-t",
+                &duration_secs.to_string(),
+                // libx264 with yuv420p needs even dimensions.
+                "-vf",
+                "scale=trunc(iw/2)*2:trunc(ih/2)*2",
+                "-c:v",
+                "libx264",
+                "-preset",
+                "veryfast",
+                "-pix_fmt",
+                "yuv420p",
+                "-movflags",
+                "+faststart",
+                output,
+            ]
+            .map(String::from),
+        );
+        args
+    }
+
+    async fn record(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let duration_secs = args
+            .get("duration_secs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_DURATION_SECS);
+        if duration_secs == 0 || duration_secs > MAX_DURATION_SECS {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "duration_secs must be between 1 and {MAX_DURATION_SECS}"
+                )),
+            });
+        }
+
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let default_name = || format!("recording_{timestamp}.mp4");
+        // Keep only the file name so the recording stays in the workspace.
+        let safe_name = args
+            .get("filename")
+            .and_then(|v| v.as_str())
+            .and_then(|name| PathBuf::from(name).file_name().map(PathBuf::from))
+            .map_or_else(default_name, |name| {
+                name.with_extension("mp4").to_string_lossy().to_string()
+            });
+        let output_path = self.security.workspace_dir.join(&safe_name);
+
+        let Some(input) = Self::capture_input() else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Screen recording not supported on this platform".into()),
+            });
+        };
+        let ffmpeg_args = Self::ffmpeg_args(input, duration_secs, &output_path.to_string_lossy());
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(duration_secs + ENCODE_GRACE_SECS),
+            tokio::process::Command::new("ffmpeg")
+                .args(&ffmpeg_args)
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await;
+
+        match result {
+            Ok(Ok(output)) if output.status.success() => {
+                match tokio::fs::metadata(&output_path).await {
+                    Ok(meta) => Ok(ToolResult {
+                        success: true,
+                        output: format!(
+                            "Screen recorded for {duration_secs}s ({} bytes).\n[VIDEO:{}]",
+                            meta.len(),
+                            output_path.display(),
+                        ),
+                        error: None,
+                    }),
+                    Err(e) => Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Failed to read recording metadata: {e}")),
+                    }),
+                }
+            }
+            Ok(Ok(output)) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Screen recording failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )),
+            }),
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("ffmpeg not found. Install ffmpeg to record the screen.".into()),
+            }),
+            Ok(Err(e)) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to execute ffmpeg: {e}")),
+            }),
+            Err(_) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Screen recording timed out after {}s",
+                    duration_secs + ENCODE_GRACE_SECS
+                )),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for RecordScreenTool {
+    fn name(&self) -> &str {
+        "record_screen"
+    }
+
+    fn description(&self) -> &str {
+        "Record the screen for a few seconds to an MP4. Returns the file path as a [VIDEO:path] marker."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "duration_secs": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_DURATION_SECS,
+                    "description": "Recording length in seconds (default: 10)"
+                },
+                "filename": {
+                    "type": "string",
+                    "description": "Optional filename (default: recording_<timestamp>.mp4). Saved in workspace."
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+        self.record(args).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+
+    fn test_security() -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: std::env::temp_dir(),
+            ..SecurityPolicy::default()
+        })
+    }
+
+    #[test]
+    fn record_screen_tool_spec() {
+        let tool = RecordScreenTool::new(test_security());
+        assert_eq!(tool.name(), "record_screen");
+        assert!(tool.description().contains("[VIDEO:path]"));
+        let schema = tool.parameters_schema();
+        assert_eq!(schema["properties"]["duration_secs"]["maximum"], 60);
+    }
+
+    #[test]
+    fn ffmpeg_args_bound_duration_and_output() {
+        let input = vec!["-f".into(), "x11grab".into(), "-i".into(), ":0".into()];
+        let args = RecordScreenTool::ffmpeg_args(input, 5, "/tmp/out.mp4");
+        let joined = args.join(" ");
+        assert!(joined.contains("-f x11grab -i :0 -t 5"));
+        assert_eq!(args.last().map(String::as_str), Some("/tmp/out.mp4"));
+    }
+
+    #[tokio::test]
+    async fn record_screen_rejects_out_of_range_duration() {
+        let tool = RecordScreenTool::new(test_security());
+        let result = tool.execute(json!({"duration_secs": 600})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("between 1 and 60"));
+    }
+
+    #[tokio::test]
+    async fn record_screen_blocked_in_readonly_mode() {
+        let tool = RecordScreenTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        }));
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
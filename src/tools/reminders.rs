@@ -0,0 +1,560 @@
+//! Reminders and timers for the current conversation.
+//!
+//! `set_reminder` stores a `message` cron job that announces fixed text to
+//! the given channel and recipient, so reminders survive restarts and are
+//! sent by the daemon's cron scheduler. `list_reminders` and
+//! `cancel_reminder` operate on the message jobs of one conversation, which
+//! includes messages scheduled with `[SCHEDULE:...]` markers.
+
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::cron::{self, CronJob, DeliveryConfig, JobType, Schedule};
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Job name given to reminders, shown by `cron_list`.
+const REMINDER_JOB_NAME: &str = "reminder";
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+    }
+}
+
+fn cron_disabled(config: &Config) -> Option<ToolResult> {
+    (!config.cron.enabled).then(|| failure("cron is disabled by config (cron.enabled=false)"))
+}
+
+fn enforce_mutation_allowed(security: &SecurityPolicy, action: &str) -> Option<ToolResult> {
+    if !security.can_act() {
+        return Some(failure(format!(
+            "Security policy: read-only mode, cannot perform '{action}'"
+        )));
+    }
+    if security.is_rate_limited() {
+        return Some(failure(
+            "Rate limit exceeded: too many actions in the last hour",
+        ));
+    }
+    if !security.record_action() {
+        return Some(failure("Rate limit exceeded: action budget exhausted"));
+    }
+    None
+}
+
+fn parse_tz(name: Option<&str>) -> Result<Option<chrono_tz::Tz>> {
+    name.map(|name| {
+        chrono_tz::Tz::from_str(name).map_err(|_| anyhow::anyhow!("Invalid IANA timezone: {name}"))
+    })
+    .transpose()
+}
+
+/// Parse `at`: RFC 3339, or a wall-clock time in `tz` (default: the
+/// system timezone).
+fn parse_at(raw: &str, tz: Option<chrono_tz::Tz>) -> Result<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(at.to_utc());
+    }
+    let naive = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .into_iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+        .with_context(|| format!("Invalid time '{raw}': use RFC 3339 or YYYY-MM-DDTHH:MM"))?;
+    let resolved = match tz {
+        Some(tz) => tz
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|t| t.to_utc()),
+        None => chrono::Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|t| t.to_utc()),
+    };
+    resolved.with_context(|| format!("'{raw}' does not exist in that timezone"))
+}
+
+/// Build the schedule from exactly one of `at`, `in` or `cron`.
+fn reminder_schedule(args: &Value, now: DateTime<Utc>) -> Result<Schedule> {
+    let tz_name = str_arg(args, "tz");
+    let tz = parse_tz(tz_name)?;
+    match (
+        str_arg(args, "at"),
+        str_arg(args, "in"),
+        str_arg(args, "cron"),
+    ) {
+        (Some(at), None, None) => Ok(Schedule::At {
+            at: parse_at(at, tz)?,
+        }),
+        (None, Some(delay), None) => Ok(Schedule::At {
+            at: now + cron::parse_delay(delay)?,
+        }),
+        (None, None, Some(expr)) => Ok(Schedule::Cron {
+            expr: expr.to_string(),
+            tz: tz_name.map(str::to_string),
+        }),
+        (None, None, None) => bail!("Pass one of 'at', 'in' or 'cron'"),
+        _ => bail!("Pass only one of 'at', 'in' or 'cron'"),
+    }
+}
+
+/// The conversation a reminder belongs to.
+fn conversation(args: &Value) -> Option<(&str, &str)> {
+    Some((str_arg(args, "channel")?, str_arg(args, "to")?))
+}
+
+fn belongs_to(job: &CronJob, channel: &str, to: &str) -> bool {
+    job.delivery
+        .channel
+        .as_deref()
+        .is_some_and(|job_channel| job_channel.eq_ignore_ascii_case(channel))
+        && job.delivery.to.as_deref() == Some(to)
+}
+
+fn format_local(at: DateTime<Utc>) -> String {
+    at.with_timezone(&chrono::Local)
+        .format("%a %Y-%m-%d %H:%M %:z")
+        .to_string()
+}
+
+fn describe(job: &CronJob) -> String {
+    let when = match &job.schedule {
+        Schedule::At { .. } => format_local(job.next_run),
+        Schedule::Cron { expr, tz } => format!(
+            "'{expr}'{}, next {}",
+            tz.as_deref()
+                .map(|tz| format!(" ({tz})"))
+                .unwrap_or_default(),
+            format_local(job.next_run)
+        ),
+        Schedule::Every { every_ms } => format!(
+            "every {}s, next {}",
+            every_ms / 1000,
+            format_local(job.next_run)
+        ),
+    };
+    format!(
+        "{when}: {} (id: {})",
+        job.prompt.as_deref().unwrap_or_default(),
+        job.id
+    )
+}
+
+/// Tool that schedules a reminder message for a conversation.
+pub struct SetReminderTool {
+    config: Arc<Config>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl SetReminderTool {
+    pub fn new(config: Arc<Config>, security: Arc<SecurityPolicy>) -> Self {
+        Self { config, security }
+    }
+}
+
+#[async_trait]
+impl Tool for SetReminderTool {
+    fn name(&self) -> &str {
+        "set_reminder"
+    }
+
+    fn description(&self) -> &str {
+        "Send a reminder message to a chat at a later time, once (at a time or after a delay, \
+         like a timer) or repeatedly (cron expression). Pass the channel and reply target from \
+         the channel context. Reminders survive restarts."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "Text to send, written to the user (e.g. 'Reminder: call the dentist')"
+                },
+                "at": {
+                    "type": "string",
+                    "description": "When to send: RFC 3339, or 'YYYY-MM-DDTHH:MM' in 'tz' (default: the system timezone)"
+                },
+                "in": {
+                    "type": "string",
+                    "description": "Delay instead of 'at', e.g. '10m', '2h', '1d'"
+                },
+                "cron": {
+                    "type": "string",
+                    "description": "Cron expression for a repeating reminder, e.g. '0 9 * * 1-5'"
+                },
+                "tz": {
+                    "type": "string",
+                    "description": "IANA timezone for 'at' and 'cron', e.g. 'Europe/Berlin'"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Channel to deliver on (the current channel)"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Recipient (the current reply_target)"
+                }
+            },
+            "required": ["message", "channel", "to"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if let Some(disabled) = cron_disabled(&self.config) {
+            return Ok(disabled);
+        }
+        let message = str_arg(&args, "message")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'message' parameter"))?;
+        let Some((channel, to)) = conversation(&args) else {
+            return Ok(failure(
+                "Missing 'channel' or 'to': use the channel and reply_target of this conversation",
+            ));
+        };
+        if !cron::scheduler::supports_announcement(channel) {
+            return Ok(failure(format!(
+                "Reminders cannot be delivered on '{channel}'. Supported: telegram, discord, \
+                 slack, mattermost, email"
+            )));
+        }
+        let schedule = match reminder_schedule(&args, Utc::now()) {
+            Ok(schedule) => schedule,
+            Err(e) => return Ok(failure(e.to_string())),
+        };
+
+        if let Some(blocked) = enforce_mutation_allowed(&self.security, "set_reminder") {
+            return Ok(blocked);
+        }
+
+        let delivery = DeliveryConfig {
+            mode: "announce".to_string(),
+            channel: Some(channel.to_string()),
+            to: Some(to.to_string()),
+            best_effort: false,
+        };
+        match cron::add_message_job(
+            &self.config,
+            Some(REMINDER_JOB_NAME.to_string()),
+            schedule,
+            message,
+            delivery,
+        ) {
+            Ok(job) => Ok(ToolResult {
+                success: true,
+                output: format!("Reminder set for {}", describe(&job)),
+                error: None,
+            }),
+            Err(e) => Ok(failure(e.to_string())),
+        }
+    }
+}
+
+/// Tool that lists pending reminders.
+pub struct ListRemindersTool {
+    config: Arc<Config>,
+}
+
+impl ListRemindersTool {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Tool for ListRemindersTool {
+    fn name(&self) -> &str {
+        "list_reminders"
+    }
+
+    fn description(&self) -> &str {
+        "List pending reminders and scheduled messages for a chat, with their ids."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "channel": {
+                    "type": "string",
+                    "description": "Channel of the conversation (the current channel)"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Recipient (the current reply_target). Omit both to list reminders for every chat"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if let Some(disabled) = cron_disabled(&self.config) {
+            return Ok(disabled);
+        }
+        let jobs = match cron::list_jobs(&self.config) {
+            Ok(jobs) => jobs,
+            Err(e) => return Ok(failure(e.to_string())),
+        };
+        let target = conversation(&args);
+        let mut reminders: Vec<&CronJob> = jobs
+            .iter()
+            .filter(|job| job.job_type == JobType::Message && job.enabled)
+            .filter(|job| target.is_none_or(|(channel, to)| belongs_to(job, channel, to)))
+            .collect();
+        reminders.sort_by_key(|job| job.next_run);
+
+        if reminders.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No pending reminders.".into(),
+                error: None,
+            });
+        }
+        let mut output = format!("Pending reminders ({}):", reminders.len());
+        for job in reminders {
+            let _ = write!(output, "\n- {}", describe(job));
+            if target.is_none() {
+                let _ = write!(
+                    output,
+                    " [{}: {}]",
+                    job.delivery.channel.as_deref().unwrap_or("?"),
+                    job.delivery.to.as_deref().unwrap_or("?")
+                );
+            }
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+/// Tool that cancels a pending reminder.
+pub struct CancelReminderTool {
+    config: Arc<Config>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl CancelReminderTool {
+    pub fn new(config: Arc<Config>, security: Arc<SecurityPolicy>) -> Self {
+        Self { config, security }
+    }
+}
+
+#[async_trait]
+impl Tool for CancelReminderTool {
+    fn name(&self) -> &str {
+        "cancel_reminder"
+    }
+
+    fn description(&self) -> &str {
+        "Cancel a pending reminder by id (from list_reminders)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "reminder_id": {
+                    "type": "string",
+                    "description": "Id from list_reminders"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Channel of the conversation; limits cancelling to its reminders"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Recipient (the current reply_target)"
+                }
+            },
+            "required": ["reminder_id"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if let Some(disabled) = cron_disabled(&self.config) {
+            return Ok(disabled);
+        }
+        let id = str_arg(&args, "reminder_id")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'reminder_id' parameter"))?;
+
+        let job = match cron::get_job(&self.config, id) {
+            Ok(job) if job.job_type == JobType::Message => job,
+            Ok(_) => {
+                return Ok(failure(format!(
+                    "'{id}' is not a reminder; use cron_remove for other jobs"
+                )))
+            }
+            Err(e) => return Ok(failure(e.to_string())),
+        };
+        if let Some((channel, to)) = conversation(&args) {
+            if !belongs_to(&job, channel, to) {
+                return Ok(failure(format!(
+                    "Reminder '{id}' belongs to another conversation"
+                )));
+            }
+        }
+
+        if let Some(blocked) = enforce_mutation_allowed(&self.security, "cancel_reminder") {
+            return Ok(blocked);
+        }
+
+        match cron::remove_job(&self.config, id) {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Cancelled reminder: {}",
+                    job.prompt.as_deref().unwrap_or_default()
+                ),
+                error: None,
+            }),
+            Err(e) => Ok(failure(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Arc<Config> {
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        Arc::new(config)
+    }
+
+    fn test_security(cfg: &Config) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy::from_config(
+            &cfg.autonomy,
+            &cfg.workspace_dir,
+        ))
+    }
+
+    #[test]
+    fn reminder_schedule_accepts_exactly_one_time() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        assert_eq!(
+            reminder_schedule(&json!({"in": "90m"}), now).unwrap(),
+            Schedule::At {
+                at: Utc.with_ymd_and_hms(2026, 10, 16, 13, 30, 0).unwrap()
+            }
+        );
+        assert_eq!(
+            reminder_schedule(
+                &json!({"at": "2026-10-17T15:00", "tz": "Europe/Berlin"}),
+                now
+            )
+            .unwrap(),
+            Schedule::At {
+                at: Utc.with_ymd_and_hms(2026, 10, 17, 13, 0, 0).unwrap()
+            }
+        );
+        assert_eq!(
+            reminder_schedule(&json!({"cron": "0 9 * * 1-5", "tz": "Asia/Tokyo"}), now).unwrap(),
+            Schedule::Cron {
+                expr: "0 9 * * 1-5".into(),
+                tz: Some("Asia/Tokyo".into())
+            }
+        );
+        assert!(reminder_schedule(&json!({}), now).is_err());
+        assert!(reminder_schedule(&json!({"in": "5m", "cron": "* * * * *"}), now).is_err());
+        assert!(reminder_schedule(&json!({"at": "tomorrow"}), now).is_err());
+        assert!(
+            reminder_schedule(&json!({"at": "2026-10-17T15:00", "tz": "Mars/Base"}), now).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn set_list_and_cancel_are_scoped_to_the_conversation() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp);
+        let security = test_security(&cfg);
+        let set = SetReminderTool::new(cfg.clone(), security.clone());
+        let list = ListRemindersTool::new(cfg.clone());
+        let cancel = CancelReminderTool::new(cfg.clone(), security);
+
+        let result = set
+            .execute(json!({"message": "Stretch!", "in": "25m", "channel": "telegram", "to": "42"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        set.execute(
+            json!({"message": "Stand-up", "cron": "0 9 * * 1-5", "channel": "slack", "to": "C1"}),
+        )
+        .await
+        .unwrap();
+
+        let jobs = cron::list_jobs(&cfg).unwrap();
+        assert_eq!(jobs.len(), 2);
+        let stretch = jobs
+            .iter()
+            .find(|job| job.prompt.as_deref() == Some("Stretch!"))
+            .unwrap();
+        assert!(stretch.delete_after_run);
+        assert_eq!(stretch.delivery.to.as_deref(), Some("42"));
+
+        let mine = list
+            .execute(json!({"channel": "telegram", "to": "42"}))
+            .await
+            .unwrap();
+        assert!(mine.output.contains("Stretch!"));
+        assert!(!mine.output.contains("Stand-up"));
+
+        let foreign = cancel
+            .execute(json!({"reminder_id": stretch.id, "channel": "slack", "to": "C1"}))
+            .await
+            .unwrap();
+        assert!(!foreign.success);
+
+        let cancelled = cancel
+            .execute(json!({"reminder_id": stretch.id, "channel": "telegram", "to": "42"}))
+            .await
+            .unwrap();
+        assert!(cancelled.success, "{:?}", cancelled.error);
+        assert_eq!(cron::list_jobs(&cfg).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_channels_and_read_only_mode() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp);
+        let set = SetReminderTool::new(cfg.clone(), test_security(&cfg));
+        let result = set
+            .execute(json!({"message": "hi", "in": "5m", "channel": "cli", "to": "user"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("cannot be delivered"));
+
+        let mut config = (*cfg).clone();
+        config.autonomy.level = AutonomyLevel::ReadOnly;
+        let cfg = Arc::new(config);
+        let set = SetReminderTool::new(cfg.clone(), test_security(&cfg));
+        let result = set
+            .execute(json!({"message": "hi", "in": "5m", "channel": "telegram", "to": "42"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("read-only"));
+        assert!(cron::list_jobs(&cfg).unwrap().is_empty());
+    }
+}
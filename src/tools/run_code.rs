@@ -0,0 +1,677 @@
+//! Sandboxed code interpreter.
+//!
+//! Each call gets a fresh working directory holding the snippet as `main.py`
+//! plus any workspace files the model asked for. The directory is run inside
+//! the `[run_code]` sandbox (a Docker container, Firejail, or CPython on WASI
+//! under `wasmtime`) with no network unless allowed. Afterwards, files the
+//! snippet created or changed are copied to `code_output/<run>/` in the
+//! workspace; images among them are returned as `[IMAGE:<path>]` markers.
+//! Matplotlib figures are saved automatically, so `plt.show()` produces an
+//! image instead of trying to open a window.
+//!
+//! SQL snippets are wrapped in a small Python program that runs them with
+//! `sqlite3`, so every backend supports them without extra tooling.
+
+use super::shell::{collect_allowed_shell_env_vars, truncate_to_budget};
+use super::traits::{Tool, ToolResult};
+use crate::config::{RunCodeBackend, RunCodeConfig};
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Snippet file inside the working directory.
+const MAIN_FILE: &str = "main.py";
+
+/// Where the working directory is mounted in Docker and WASM sandboxes.
+const GUEST_WORKDIR: &str = "/work";
+
+/// Rows printed per SQL query.
+const SQL_MAX_ROWS: usize = 200;
+
+/// Files copied back to the workspace per run.
+const MAX_COLLECTED_FILES: usize = 20;
+
+/// Entry point passed to `python -c`: moves into the working directory,
+/// saves matplotlib figures instead of showing them, then runs `main.py`.
+const BOOTSTRAP: &str = r#"import os, runpy
+os.chdir(os.path.expanduser(os.environ.get("ZEROCLAW_WORKDIR", ".")))
+with open("main.py", encoding="utf-8") as source:
+    uses_matplotlib = "matplotlib" in source.read()
+if uses_matplotlib:
+    os.environ.setdefault("MPLBACKEND", "Agg")
+    try:
+        import atexit
+        import matplotlib.pyplot as plt
+        saved = [0]
+        def save_figures(*args, **kwargs):
+            for num in plt.get_fignums():
+                saved[0] += 1
+                plt.figure(num).savefig(f"figure_{saved[0]}.png", bbox_inches="tight")
+            plt.close("all")
+        plt.show = save_figures
+        atexit.register(save_figures)
+    except ImportError:
+        pass
+runpy.run_path("main.py", run_name="__main__")
+"#;
+
+/// Extensions delivered as `[IMAGE:]` markers.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+/// Size and modification time of each file in a working directory.
+type Snapshot = HashMap<PathBuf, (u64, Option<SystemTime>)>;
+
+/// Tool that runs Python or SQL snippets in a sandbox.
+pub struct RunCodeTool {
+    security: Arc<SecurityPolicy>,
+    config: RunCodeConfig,
+}
+
+impl RunCodeTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: RunCodeConfig) -> Self {
+        Self { security, config }
+    }
+
+    /// Command that runs `main.py` from `workdir` inside the sandbox.
+    fn build_command(&self, workdir: &Path, run_id: &str) -> Result<tokio::process::Command> {
+        let mut cmd = match self.config.backend {
+            RunCodeBackend::Docker => {
+                let mut cmd = tokio::process::Command::new("docker");
+                cmd.args(["run", "--rm", "--init", "--name"])
+                    .arg(container_name(run_id))
+                    .arg("--network")
+                    .arg(if self.config.allow_network {
+                        "bridge"
+                    } else {
+                        "none"
+                    })
+                    .arg("--memory")
+                    .arg(format!("{}m", self.config.memory_limit_mb.max(16)))
+                    .arg("--volume")
+                    .arg(format!("{}:{GUEST_WORKDIR}:rw", workdir.display()))
+                    .arg("--workdir")
+                    .arg(GUEST_WORKDIR)
+                    .arg("--env")
+                    .arg(format!("HOME={GUEST_WORKDIR}"))
+                    .arg("--env")
+                    .arg(format!("ZEROCLAW_WORKDIR={GUEST_WORKDIR}"));
+                // Keep produced files owned by the agent user, not root.
+                #[cfg(unix)]
+                {
+                    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+                    cmd.arg("--user").arg(format!("{uid}:{gid}"));
+                }
+                cmd.arg(self.config.docker_image.trim())
+                    .arg("python3")
+                    .arg("-c")
+                    .arg(BOOTSTRAP);
+                cmd
+            }
+            RunCodeBackend::Firejail => {
+                let mut cmd = tokio::process::Command::new("firejail");
+                cmd.args(["--quiet", "--noprofile", "--private-dev", "--nosound"])
+                    .arg(format!("--private={}", workdir.display()))
+                    .arg(format!(
+                        "--rlimit-as={}",
+                        self.config.memory_limit_mb.max(16) * 1024 * 1024
+                    ));
+                if !self.config.allow_network {
+                    cmd.arg("--net=none");
+                }
+                cmd.arg(self.config.python_command.trim())
+                    .arg("-c")
+                    .arg(BOOTSTRAP);
+                // Firejail passes the environment through: keep secrets out.
+                cmd.env_clear();
+                for var in collect_allowed_shell_env_vars(&self.security) {
+                    if let Ok(value) = std::env::var(&var) {
+                        cmd.env(&var, value);
+                    }
+                }
+                cmd.env("ZEROCLAW_WORKDIR", "~");
+                cmd
+            }
+            RunCodeBackend::Wasm => {
+                let python_wasm = self
+                    .config
+                    .python_wasm
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|path| !path.is_empty())
+                    .context("run_code.python_wasm must be set for the wasm backend")?;
+                let mut cmd = tokio::process::Command::new(self.config.wasmtime_command.trim());
+                cmd.arg("run")
+                    .arg("--dir")
+                    .arg(format!("{}::{GUEST_WORKDIR}", workdir.display()))
+                    .arg("--env")
+                    .arg(format!("ZEROCLAW_WORKDIR={GUEST_WORKDIR}"));
+                if self.config.allow_network {
+                    cmd.args(["-S", "inherit-network"]);
+                }
+                cmd.arg(python_wasm).arg("-c").arg(BOOTSTRAP);
+                cmd
+            }
+        };
+        cmd.current_dir(workdir).kill_on_drop(true);
+        Ok(cmd)
+    }
+
+    /// Resolve a workspace file the model wants available to the snippet.
+    async fn resolve_input(&self, path: &str) -> Result<PathBuf, String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        let resolved = tokio::fs::canonicalize(self.security.workspace_dir.join(path))
+            .await
+            .map_err(|e| format!("Failed to resolve input file {path}: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(self.security.resolved_path_violation_message(&resolved));
+        }
+        Ok(resolved)
+    }
+
+    /// Run `source` in a fresh working directory and describe the outcome.
+    async fn run(&self, source: &str, inputs: &[PathBuf]) -> Result<ToolResult> {
+        let run_id = new_run_id();
+        let workdir = std::env::temp_dir().join("zeroclaw-run-code").join(&run_id);
+        tokio::fs::create_dir_all(&workdir)
+            .await
+            .context("Failed to create working directory")?;
+
+        let result = self.run_in(&workdir, &run_id, source, inputs).await;
+        if let Err(e) = tokio::fs::remove_dir_all(&workdir).await {
+            tracing::warn!(
+                "Failed to remove run_code directory {}: {e}",
+                workdir.display()
+            );
+        }
+        result
+    }
+
+    async fn run_in(
+        &self,
+        workdir: &Path,
+        run_id: &str,
+        source: &str,
+        inputs: &[PathBuf],
+    ) -> Result<ToolResult> {
+        for input in inputs {
+            let name = input.file_name().context("Input file has no file name")?;
+            tokio::fs::copy(input, workdir.join(name))
+                .await
+                .with_context(|| format!("Failed to copy {}", input.display()))?;
+        }
+        tokio::fs::write(workdir.join(MAIN_FILE), source)
+            .await
+            .context("Failed to write snippet")?;
+        let before = snapshot(workdir).await;
+
+        let mut cmd = self.build_command(workdir, run_id)?;
+        let timeout_secs = self.config.timeout_secs.max(1);
+        let output =
+            match tokio::time::timeout(Duration::from_secs(timeout_secs), cmd.output()).await {
+                Ok(Ok(output)) => output,
+                Ok(Err(e)) => bail!("Failed to start the {} sandbox: {e}", self.backend_name()),
+                Err(_) => {
+                    if self.config.backend == RunCodeBackend::Docker {
+                        // Killing the client does not stop the container.
+                        let _ = tokio::process::Command::new("docker")
+                            .args(["rm", "--force", &container_name(run_id)])
+                            .output()
+                            .await;
+                    }
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!(
+                            "Code timed out after {timeout_secs}s and was stopped"
+                        )),
+                    });
+                }
+            };
+
+        let mut stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        truncate_to_budget(
+            &mut stdout,
+            &mut stderr,
+            self.security.shell_max_output_bytes,
+        );
+
+        let produced = changed_files(workdir, &before).await;
+        if !produced.is_empty() {
+            let saved = self.save_outputs(workdir, run_id, &produced).await?;
+            stdout.push_str(&describe_outputs(&self.security.workspace_dir, &saved));
+        }
+
+        Ok(ToolResult {
+            success: output.status.success(),
+            output: stdout,
+            error: (!stderr.is_empty()).then_some(stderr),
+        })
+    }
+
+    /// Copy produced files to `code_output/<run>/` in the workspace.
+    async fn save_outputs(
+        &self,
+        workdir: &Path,
+        run_id: &str,
+        produced: &[PathBuf],
+    ) -> Result<Vec<PathBuf>> {
+        let dest_root = self.security.workspace_dir.join("code_output").join(run_id);
+        let mut saved = Vec::with_capacity(produced.len());
+        for relative in produced.iter().take(MAX_COLLECTED_FILES) {
+            let dest = dest_root.join(relative);
+            if let Some(parent) = dest.parent() {
+                tokio::fs::create_dir_all(parent)
+                    .await
+                    .context("Failed to create code_output directory")?;
+            }
+            tokio::fs::copy(workdir.join(relative), &dest)
+                .await
+                .with_context(|| format!("Failed to save {}", relative.display()))?;
+            saved.push(dest);
+        }
+        if produced.len() > MAX_COLLECTED_FILES {
+            tracing::warn!(
+                "run_code produced {} files; kept the first {MAX_COLLECTED_FILES}",
+                produced.len()
+            );
+        }
+        Ok(saved)
+    }
+
+    fn backend_name(&self) -> &'static str {
+        match self.config.backend {
+            RunCodeBackend::Docker => "docker",
+            RunCodeBackend::Firejail => "firejail",
+            RunCodeBackend::Wasm => "wasm",
+        }
+    }
+}
+
+fn new_run_id() -> String {
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    format!(
+        "{}_{}",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+        &id[..8]
+    )
+}
+
+fn container_name(run_id: &str) -> String {
+    format!("zeroclaw-run-code-{run_id}")
+}
+
+/// Python program that runs `sql` against `database` with `sqlite3` and
+/// prints each result set as a table.
+fn sql_program(sql: &str, database: Option<&str>) -> String {
+    // JSON string literals are valid Python string literals.
+    let sql = serde_json::to_string(sql).unwrap_or_default();
+    let database = serde_json::to_string(database.unwrap_or(":memory:")).unwrap_or_default();
+    format!(
+        r#"import sqlite3
+
+SQL = {sql}
+MAX_ROWS = {SQL_MAX_ROWS}
+conn = sqlite3.connect({database})
+
+
+def run(statement):
+    if not statement.strip(" ;\r\n\t"):
+        return
+    cursor = conn.execute(statement)
+    if cursor.description is None:
+        if cursor.rowcount >= 0:
+            print(f"OK, {{cursor.rowcount}} row(s) affected")
+        return
+    print(" | ".join(column[0] for column in cursor.description))
+    rows = cursor.fetchmany(MAX_ROWS + 1)
+    for row in rows[:MAX_ROWS]:
+        print(" | ".join("NULL" if value is None else str(value) for value in row))
+    if len(rows) > MAX_ROWS:
+        print(f"... more than {{MAX_ROWS}} rows")
+    print()
+
+
+buffer = ""
+for part in SQL.split(";"):
+    buffer += part + ";"
+    if sqlite3.complete_statement(buffer):
+        run(buffer)
+        buffer = ""
+run(buffer)
+conn.commit()
+conn.close()
+"#
+    )
+}
+
+/// Skip the snippet itself, hidden files (shell/profile files a sandbox may
+/// create) and bytecode caches.
+fn is_collectable(relative: &Path) -> bool {
+    relative != Path::new(MAIN_FILE)
+        && relative.components().all(|part| {
+            let name = part.as_os_str().to_string_lossy();
+            !name.starts_with('.') && name != "__pycache__"
+        })
+}
+
+async fn snapshot(workdir: &Path) -> Snapshot {
+    let mut files = Snapshot::new();
+    let mut pending = vec![workdir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(meta) = entry.metadata().await else {
+                continue;
+            };
+            let path = entry.path();
+            let Ok(relative) = path.strip_prefix(workdir) else {
+                continue;
+            };
+            if !is_collectable(relative) {
+                continue;
+            }
+            if meta.is_dir() {
+                pending.push(path);
+            } else if meta.is_file() {
+                files.insert(relative.to_path_buf(), (meta.len(), meta.modified().ok()));
+            }
+        }
+    }
+    files
+}
+
+/// Files in `workdir` that are new or changed since `before`, sorted.
+async fn changed_files(workdir: &Path, before: &Snapshot) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = snapshot(workdir)
+        .await
+        .into_iter()
+        .filter(|(path, state)| before.get(path) != Some(state))
+        .map(|(path, _)| path)
+        .collect();
+    changed.sort();
+    changed
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// List saved files relative to the workspace and attach images.
+fn describe_outputs(workspace_dir: &Path, saved: &[PathBuf]) -> String {
+    let mut text = String::from("\n\nFiles produced:");
+    for path in saved {
+        let relative = path.strip_prefix(workspace_dir).unwrap_or(path);
+        let _ = write!(text, "\n- {}", relative.display());
+    }
+    for path in saved.iter().filter(|path| is_image(path)) {
+        let _ = write!(text, "\n[IMAGE:{}]", path.display());
+    }
+    text
+}
+
+#[async_trait]
+impl Tool for RunCodeTool {
+    fn name(&self) -> &str {
+        "run_code"
+    }
+
+    fn description(&self) -> &str {
+        "Run a Python or SQL (SQLite) snippet in an isolated sandbox and return its output. \
+         Files the code writes to its working directory are saved in the workspace; \
+         matplotlib plots are returned as [IMAGE:path] markers to include in your reply."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "Python source, or SQL statements when language is sql. Print results to stdout."
+                },
+                "language": {
+                    "type": "string",
+                    "enum": ["python", "sql"],
+                    "description": "Snippet language (default: python)"
+                },
+                "files": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Workspace files to copy into the working directory, available by file name"
+                },
+                "database": {
+                    "type": "string",
+                    "description": "SQLite database file name for SQL (pass an existing one via files); default in-memory"
+                }
+            },
+            "required": ["code"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let code = args
+            .get("code")
+            .and_then(Value::as_str)
+            .filter(|code| !code.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'code' parameter"))?;
+        let language = args
+            .get("language")
+            .and_then(Value::as_str)
+            .unwrap_or("python")
+            .trim()
+            .to_ascii_lowercase();
+
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+        if self.security.is_rate_limited() || !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        let source = match language.as_str() {
+            "python" | "py" => code.to_string(),
+            "sql" | "sqlite" => {
+                let database = args
+                    .get("database")
+                    .and_then(Value::as_str)
+                    .and_then(|name| Path::new(name.trim()).file_name())
+                    .map(|name| name.to_string_lossy().to_string());
+                sql_program(code, database.as_deref())
+            }
+            other => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Unsupported language '{other}': use python or sql")),
+                })
+            }
+        };
+
+        let mut inputs = Vec::new();
+        for path in args
+            .get("files")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            match self.resolve_input(path.trim()).await {
+                Ok(resolved) => inputs.push(resolved),
+                Err(error) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(error),
+                    })
+                }
+            }
+        }
+
+        match self.run(&source, &inputs).await {
+            Ok(result) => Ok(result),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("{e:#}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn tool(tmp: &TempDir, autonomy: AutonomyLevel, config: RunCodeConfig) -> RunCodeTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        RunCodeTool::new(security, config)
+    }
+
+    fn args_of(cmd: &tokio::process::Command) -> Vec<String> {
+        cmd.as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn docker_command_isolates_network_and_mounts_workdir() {
+        let tmp = TempDir::new().unwrap();
+        let tool = tool(&tmp, AutonomyLevel::Full, RunCodeConfig::default());
+        let cmd = tool.build_command(Path::new("/tmp/run"), "abc").unwrap();
+        assert_eq!(cmd.as_std().get_program(), "docker");
+        let args = args_of(&cmd);
+        assert!(args.windows(2).any(|w| w == ["--network", "none"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["--volume", "/tmp/run:/work:rw"]));
+        assert!(args.contains(&"python:3.12-slim".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some(BOOTSTRAP));
+    }
+
+    #[test]
+    fn firejail_and_wasm_commands_follow_config() {
+        let tmp = TempDir::new().unwrap();
+        let firejail = tool(
+            &tmp,
+            AutonomyLevel::Full,
+            RunCodeConfig {
+                backend: RunCodeBackend::Firejail,
+                ..RunCodeConfig::default()
+            },
+        );
+        let args = args_of(
+            &firejail
+                .build_command(Path::new("/tmp/run"), "abc")
+                .unwrap(),
+        );
+        assert!(args.contains(&"--private=/tmp/run".to_string()));
+        assert!(args.contains(&"--net=none".to_string()));
+        assert!(args.contains(&"python3".to_string()));
+
+        let mut config = RunCodeConfig {
+            backend: RunCodeBackend::Wasm,
+            ..RunCodeConfig::default()
+        };
+        let missing = tool(&tmp, AutonomyLevel::Full, config.clone());
+        let err = missing
+            .build_command(Path::new("/tmp/run"), "abc")
+            .unwrap_err();
+        assert!(err.to_string().contains("python_wasm"));
+
+        config.python_wasm = Some("/opt/python.wasm".into());
+        let wasm = tool(&tmp, AutonomyLevel::Full, config);
+        let cmd = wasm.build_command(Path::new("/tmp/run"), "abc").unwrap();
+        assert_eq!(cmd.as_std().get_program(), "wasmtime");
+        let args = args_of(&cmd);
+        assert!(args.windows(2).any(|w| w == ["--dir", "/tmp/run::/work"]));
+        assert!(args.contains(&"/opt/python.wasm".to_string()));
+    }
+
+    #[test]
+    fn sql_program_embeds_query_and_database() {
+        let program = sql_program("SELECT 'it''s'; -- \"quoted\"\n", Some("data.db"));
+        assert!(program.contains(r#"SQL = "SELECT 'it''s'; -- \"quoted\"\n""#));
+        assert!(program.contains(r#"sqlite3.connect("data.db")"#));
+        assert!(sql_program("SELECT 1", None).contains(r#"sqlite3.connect(":memory:")"#));
+    }
+
+    #[tokio::test]
+    async fn collects_new_and_changed_files_as_outputs() {
+        let tmp = TempDir::new().unwrap();
+        let workdir = tmp.path().join("run");
+        std::fs::create_dir_all(workdir.join("__pycache__")).unwrap();
+        std::fs::write(workdir.join(MAIN_FILE), "print(1)").unwrap();
+        std::fs::write(workdir.join("input.csv"), "a,b").unwrap();
+        let before = snapshot(&workdir).await;
+
+        std::fs::write(workdir.join("input.csv"), "a,b\n1,2").unwrap();
+        std::fs::write(workdir.join("figure_1.png"), b"\x89PNG").unwrap();
+        std::fs::write(workdir.join(".bashrc"), "").unwrap();
+        std::fs::write(workdir.join("__pycache__/x.pyc"), "").unwrap();
+
+        let changed = changed_files(&workdir, &before).await;
+        assert_eq!(
+            changed,
+            vec![PathBuf::from("figure_1.png"), PathBuf::from("input.csv")]
+        );
+
+        let saved = vec![tmp.path().join("code_output/r/figure_1.png")];
+        let text = describe_outputs(tmp.path(), &saved);
+        assert!(text.contains("- code_output/r/figure_1.png"));
+        assert!(text.contains(&format!("[IMAGE:{}]", saved[0].display())));
+    }
+
+    #[tokio::test]
+    async fn rejects_read_only_mode_and_outside_inputs() {
+        let tmp = TempDir::new().unwrap();
+        let read_only = tool(&tmp, AutonomyLevel::ReadOnly, RunCodeConfig::default());
+        let result = read_only
+            .execute(json!({"code": "print(1)"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+
+        let full = tool(&tmp, AutonomyLevel::Full, RunCodeConfig::default());
+        let result = full
+            .execute(json!({"code": "print(1)", "files": ["../../etc/passwd"]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+
+        let result = full
+            .execute(json!({"code": "1", "language": "ruby"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Unsupported language"));
+    }
+}
@@ -0,0 +1,164 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::knowledge::{KnowledgeBase, Passage};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+
+/// Most passages returned for one query.
+const MAX_RESULTS: usize = 20;
+
+/// Tool for answering from the user's documents in `[knowledge]` directories.
+pub struct SearchKnowledgeTool {
+    knowledge: KnowledgeBase,
+    default_results: usize,
+}
+
+impl SearchKnowledgeTool {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            knowledge: KnowledgeBase::from_config(config),
+            default_results: config.knowledge.max_results.clamp(1, MAX_RESULTS),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_knowledge_base(knowledge: KnowledgeBase, default_results: usize) -> Self {
+        Self {
+            knowledge,
+            default_results,
+        }
+    }
+}
+
+fn render_passages(passages: &[Passage]) -> String {
+    let mut output = format!("Found {} passage(s):\n", passages.len());
+    for (index, passage) in passages.iter().enumerate() {
+        let location = match passage.heading.as_deref() {
+            Some(heading) if !heading.is_empty() => format!("{} › {heading}", passage.source),
+            _ => passage.source.clone(),
+        };
+        let _ = write!(
+            output,
+            "\n{}. [{location}] (score {:.2})\n{}\n",
+            index + 1,
+            passage.score,
+            passage.content.trim()
+        );
+    }
+    output
+}
+
+#[async_trait]
+impl Tool for SearchKnowledgeTool {
+    fn name(&self) -> &str {
+        "search_knowledge"
+    }
+
+    fn description(&self) -> &str {
+        "Search the user's own documents (the knowledge base) and return the most relevant \
+         passages with their source file. Use it to answer questions from the user's notes, \
+         manuals and policies, and cite the source."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for, in natural language"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Number of passages to return (default: 5, max: 20)",
+                    "minimum": 1,
+                    "maximum": MAX_RESULTS
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map_or(self.default_results, |n| {
+                usize::try_from(n)
+                    .unwrap_or(MAX_RESULTS)
+                    .clamp(1, MAX_RESULTS)
+            });
+
+        // Pick up documents added since the last rescan; unchanged files cost
+        // only a stat.
+        if let Err(e) = self.knowledge.sync().await {
+            tracing::warn!("Knowledge base sync before search failed: {e}");
+        }
+
+        match self.knowledge.search(query, limit).await {
+            Ok(passages) if passages.is_empty() => Ok(ToolResult {
+                success: true,
+                output: "No matching passages in the knowledge base.".into(),
+                error: None,
+            }),
+            Ok(passages) => Ok(ToolResult {
+                success: true,
+                output: render_passages(&passages),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Knowledge base search failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KnowledgeConfig;
+    use crate::memory::embeddings::NoopEmbedding;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn search_knowledge_indexes_and_cites_sources() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("knowledge");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("handbook.md"),
+            "# Holidays\n\nEmployees get 28 days of annual leave.",
+        )
+        .unwrap();
+        let knowledge = KnowledgeBase::new(
+            KnowledgeConfig {
+                enabled: true,
+                ..KnowledgeConfig::default()
+            },
+            tmp.path().to_path_buf(),
+            Arc::new(NoopEmbedding),
+            "none::0".into(),
+        );
+        let tool = SearchKnowledgeTool::with_knowledge_base(knowledge, 5);
+
+        let result = tool
+            .execute(json!({"query": "annual leave"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("[knowledge/handbook.md › Holidays]"));
+        assert!(result.output.contains("28 days"));
+
+        let result = tool.execute(json!({"query": "parking"})).await.unwrap();
+        assert!(result.output.contains("No matching passages"));
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
@@ -0,0 +1,428 @@
+use super::traits::{Tool, ToolResult};
+use crate::channels::email_channel::{attachment_content_type, smtp_transport, EmailConfig};
+use crate::config::SendEmailConfig;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::{Message, Transport};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const MB: u64 = 1024 * 1024;
+
+/// Send email over SMTP, with workspace files as attachments.
+///
+/// Sending is an outward action: it needs a non-read-only autonomy level and
+/// goes through the approval workflow (`send_email` is in the default
+/// `channel_approval_tools`).
+pub struct SendEmailTool {
+    security: Arc<SecurityPolicy>,
+    config: SendEmailConfig,
+    /// `[channels_config.email]`, used when `[send_email]` has no SMTP host.
+    channel: Option<EmailConfig>,
+}
+
+impl SendEmailTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        config: SendEmailConfig,
+        channel: Option<EmailConfig>,
+    ) -> Self {
+        Self {
+            security,
+            config,
+            channel,
+        }
+    }
+
+    /// SMTP settings from `[send_email]`, falling back to the email channel.
+    fn smtp_settings(&self) -> Result<EmailConfig, String> {
+        let settings = match self.config.smtp_host.as_deref().map(str::trim) {
+            Some(host) if !host.is_empty() => {
+                let username = self.config.username.clone().unwrap_or_default();
+                EmailConfig {
+                    smtp_host: host.to_string(),
+                    smtp_port: self.config.smtp_port,
+                    smtp_tls: self.config.smtp_tls,
+                    from_address: self
+                        .config
+                        .from_address
+                        .clone()
+                        .unwrap_or_else(|| username.clone()),
+                    username,
+                    password: self.config.password.clone().unwrap_or_default(),
+                    ..EmailConfig::default()
+                }
+            }
+            _ => self.channel.clone().ok_or_else(|| {
+                "No SMTP server configured: set [send_email] smtp_host or [channels_config.email]"
+                    .to_string()
+            })?,
+        };
+        if settings.from_address.trim().is_empty() {
+            return Err("No sender address configured: set [send_email] from_address".into());
+        }
+        Ok(settings)
+    }
+
+    fn is_recipient_allowed(&self, address: &str) -> bool {
+        if self.config.allowed_recipients.is_empty() {
+            return true;
+        }
+        let address = address.to_ascii_lowercase();
+        self.config.allowed_recipients.iter().any(|allowed| {
+            let allowed = allowed.trim().to_ascii_lowercase();
+            allowed == "*"
+                || allowed == address
+                || (allowed.starts_with('@') && address.ends_with(&allowed))
+        })
+    }
+
+    /// Parse `to`/`cc` (a string, comma-separated string or array) and check
+    /// each address against `allowed_recipients`.
+    fn recipients(&self, value: Option<&Value>) -> Result<Vec<Mailbox>, String> {
+        let raw: Vec<&str> = match value {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::String(list)) => list.split(',').collect(),
+            Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+            Some(_) => return Err("Recipients must be a string or an array of strings".into()),
+        };
+        raw.into_iter()
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let mailbox: Mailbox = entry
+                    .parse()
+                    .map_err(|e| format!("Invalid email address '{entry}': {e}"))?;
+                if !self.is_recipient_allowed(mailbox.email.as_ref()) {
+                    return Err(format!(
+                        "Recipient {} is not in [send_email] allowed_recipients",
+                        mailbox.email
+                    ));
+                }
+                Ok(mailbox)
+            })
+            .collect()
+    }
+
+    async fn resolve_attachment(&self, path: &str) -> Result<(PathBuf, u64), String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        let full_path = self.security.workspace_dir.join(path);
+        let resolved = tokio::fs::canonicalize(&full_path)
+            .await
+            .map_err(|e| format!("Failed to resolve attachment {path}: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(self.security.resolved_path_violation_message(&resolved));
+        }
+        let meta = tokio::fs::metadata(&resolved)
+            .await
+            .map_err(|e| format!("Failed to read attachment {path}: {e}"))?;
+        if !meta.is_file() {
+            return Err(format!("Not a file: {path}"));
+        }
+        Ok((resolved, meta.len()))
+    }
+
+    async fn build_message(&self, args: &Value, from: &str) -> Result<(Message, String), String> {
+        let to = self.recipients(args.get("to"))?;
+        if to.is_empty() {
+            return Err("Missing 'to' parameter".into());
+        }
+        let cc = self.recipients(args.get("cc"))?;
+        let subject = args
+            .get("subject")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|subject| !subject.is_empty())
+            .ok_or("Missing 'subject' parameter")?;
+        let body = args
+            .get("body")
+            .and_then(Value::as_str)
+            .ok_or("Missing 'body' parameter")?;
+
+        let mut files = Vec::new();
+        let mut total = 0;
+        let paths = args
+            .get("attachments")
+            .and_then(Value::as_array)
+            .map(|paths| paths.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for path in paths {
+            let (resolved, size) = self.resolve_attachment(path).await?;
+            total += size;
+            if total > self.config.max_attachment_mb * MB {
+                return Err(format!(
+                    "Attachments exceed the {} MB limit",
+                    self.config.max_attachment_mb
+                ));
+            }
+            files.push(resolved);
+        }
+
+        let mut builder = Message::builder()
+            .from(
+                from.parse()
+                    .map_err(|e| format!("Invalid sender address '{from}': {e}"))?,
+            )
+            .subject(subject);
+        let mut summary = to
+            .iter()
+            .map(|mailbox| mailbox.email.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        for mailbox in to {
+            builder = builder.to(mailbox);
+        }
+        for mailbox in cc {
+            builder = builder.cc(mailbox);
+        }
+
+        let message = if files.is_empty() {
+            builder.singlepart(SinglePart::plain(body.to_string()))
+        } else {
+            let mut names = Vec::new();
+            let mut multipart = MultiPart::mixed().singlepart(SinglePart::plain(body.to_string()));
+            for path in files {
+                let bytes = tokio::fs::read(&path)
+                    .await
+                    .map_err(|e| format!("Failed to read attachment {}: {e}", path.display()))?;
+                let filename = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("attachment")
+                    .to_string();
+                multipart = multipart.singlepart(
+                    Attachment::new(filename.clone()).body(bytes, attachment_content_type(&path)),
+                );
+                names.push(filename);
+            }
+            let _ = write!(summary, " with attachments: {}", names.join(", "));
+            builder.multipart(multipart)
+        }
+        .map_err(|e| format!("Failed to build email: {e}"))?;
+        Ok((message, summary))
+    }
+}
+
+#[async_trait]
+impl Tool for SendEmailTool {
+    fn name(&self) -> &str {
+        "send_email"
+    }
+
+    fn description(&self) -> &str {
+        "Send an email with optional workspace files attached (reports, exports, images). \
+         Requires user approval before sending."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "to": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Recipient addresses"
+                },
+                "cc": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional CC addresses"
+                },
+                "subject": {
+                    "type": "string",
+                    "description": "Subject line"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Plain-text message body"
+                },
+                "attachments": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Workspace file paths to attach"
+                }
+            },
+            "required": ["to", "subject", "body"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+
+        let settings = match self.smtp_settings() {
+            Ok(settings) => settings,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                })
+            }
+        };
+        let (message, summary) = match self.build_message(&args, &settings.from_address).await {
+            Ok(built) => built,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                })
+            }
+        };
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        let sent = tokio::task::spawn_blocking(move || {
+            smtp_transport(&settings)?.send(&message)?;
+            anyhow::Ok(())
+        })
+        .await?;
+        match sent {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: format!("Email sent to {summary}"),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to send email: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn tool(tmp: &TempDir, config: SendEmailConfig, channel: Option<EmailConfig>) -> SendEmailTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        SendEmailTool::new(security, config, channel)
+    }
+
+    fn smtp_config() -> SendEmailConfig {
+        SendEmailConfig {
+            enabled: true,
+            smtp_host: Some("smtp.example.com".into()),
+            username: Some("bot@example.com".into()),
+            ..SendEmailConfig::default()
+        }
+    }
+
+    #[test]
+    fn smtp_settings_fall_back_to_the_email_channel() {
+        let tmp = TempDir::new().unwrap();
+        let own = tool(&tmp, smtp_config(), None).smtp_settings().unwrap();
+        assert_eq!(own.smtp_host, "smtp.example.com");
+        assert_eq!(own.from_address, "bot@example.com");
+
+        let channel = EmailConfig {
+            smtp_host: "mail.example.org".into(),
+            from_address: "agent@example.org".into(),
+            ..EmailConfig::default()
+        };
+        let shared = tool(&tmp, SendEmailConfig::default(), Some(channel))
+            .smtp_settings()
+            .unwrap();
+        assert_eq!(shared.smtp_host, "mail.example.org");
+
+        assert!(tool(&tmp, SendEmailConfig::default(), None)
+            .smtp_settings()
+            .is_err());
+    }
+
+    #[test]
+    fn recipients_are_checked_against_the_allowlist() {
+        let tmp = TempDir::new().unwrap();
+        let config = SendEmailConfig {
+            allowed_recipients: vec!["@example.com".into(), "boss@corp.io".into()],
+            ..smtp_config()
+        };
+        let tool = tool(&tmp, config, None);
+        let ok = tool
+            .recipients(Some(&json!("Ann <ann@Example.com>, boss@corp.io")))
+            .unwrap();
+        assert_eq!(ok.len(), 2);
+        assert!(tool.recipients(Some(&json!(["eve@evil.com"]))).is_err());
+        assert!(tool.recipients(Some(&json!(["not an address"]))).is_err());
+    }
+
+    #[tokio::test]
+    async fn builds_message_with_workspace_attachments_only() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("report.csv"), "a,b\n1,2\n").unwrap();
+        let tool = tool(&tmp, smtp_config(), None);
+
+        let (message, summary) = tool
+            .build_message(
+                &json!({
+                    "to": ["ann@example.com"],
+                    "subject": "Weekly report",
+                    "body": "Attached.",
+                    "attachments": ["report.csv"]
+                }),
+                "bot@example.com",
+            )
+            .await
+            .unwrap();
+        assert_eq!(summary, "ann@example.com with attachments: report.csv");
+        let raw = String::from_utf8(message.formatted()).unwrap();
+        assert!(raw.contains("filename=\"report.csv\""));
+        assert!(raw.contains("Content-Type: text/csv"));
+
+        let err = tool
+            .build_message(
+                &json!({
+                    "to": ["ann@example.com"],
+                    "subject": "Secrets",
+                    "body": "",
+                    "attachments": ["/etc/passwd"]
+                }),
+                "bot@example.com",
+            )
+            .await
+            .unwrap_err();
+        assert!(err.contains("not allowed"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn read_only_mode_blocks_sending() {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let tool = SendEmailTool::new(security, smtp_config(), None);
+        let result = tool
+            .execute(json!({"to": ["ann@example.com"], "subject": "Hi", "body": "Hi"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
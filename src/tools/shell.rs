@@ -4,13 +4,10 @@ use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
 use std::collections::HashSet;
+use std::fmt::Write;
 use std::sync::Arc;
 use std::time::Duration;
 
-/// Maximum shell command execution time before kill.
-const SHELL_TIMEOUT_SECS: u64 = 60;
-/// Maximum output size in bytes (1MB).
-const MAX_OUTPUT_BYTES: usize = 1_048_576;
 /// Environment variables safe to pass to shell commands.
 /// Only functional variables are included — never API keys or secrets.
 const SAFE_ENV_VARS: &[&str] = &[
@@ -38,7 +35,35 @@ fn is_valid_env_var_name(name: &str) -> bool {
     chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
 }
 
-fn collect_allowed_shell_env_vars(security: &SecurityPolicy) -> Vec<String> {
+/// Cut `text` to `limit` bytes on a character boundary, noting what was lost.
+fn truncate_stream(text: &mut String, limit: usize, label: &str) {
+    if text.len() > limit {
+        let mut end = limit;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let dropped = text.len() - end;
+        text.truncate(end);
+        let _ = write!(text, "\n... [{label} truncated, {dropped} more bytes]");
+    }
+}
+
+/// Fit stdout and stderr into one output budget. When both are too long,
+/// stderr keeps up to half the budget so errors are not lost behind a
+/// flood of output.
+pub(super) fn truncate_to_budget(stdout: &mut String, stderr: &mut String, budget: usize) {
+    if stdout.len() + stderr.len() <= budget {
+        return;
+    }
+    let stderr_budget = stderr
+        .len()
+        .min(budget / 2)
+        .max(budget.saturating_sub(stdout.len()));
+    truncate_stream(stderr, stderr_budget, "stderr");
+    truncate_stream(stdout, budget - stderr_budget, "output");
+}
+
+pub(super) fn collect_allowed_shell_env_vars(security: &SecurityPolicy) -> Vec<String> {
     let mut out = Vec::new();
     let mut seen = HashSet::new();
     for key in SAFE_ENV_VARS
@@ -154,23 +179,20 @@ impl Tool for ShellTool {
             }
         }
 
-        let result =
-            tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
+        let timeout_secs = self.security.shell_timeout_secs;
+        let result = tokio::time::timeout(Duration::from_secs(timeout_secs), cmd.output()).await;
 
         match result {
             Ok(Ok(output)) => {
                 let mut stdout = String::from_utf8_lossy(&output.stdout).to_string();
                 let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
-                // Truncate output to prevent OOM
-                if stdout.len() > MAX_OUTPUT_BYTES {
-                    stdout.truncate(stdout.floor_char_boundary(MAX_OUTPUT_BYTES));
-                    stdout.push_str("\n... [output truncated at 1MB]");
-                }
-                if stderr.len() > MAX_OUTPUT_BYTES {
-                    stderr.truncate(stderr.floor_char_boundary(MAX_OUTPUT_BYTES));
-                    stderr.push_str("\n... [stderr truncated at 1MB]");
-                }
+                // Keep the result within what the model is given to read.
+                truncate_to_budget(
+                    &mut stdout,
+                    &mut stderr,
+                    self.security.shell_max_output_bytes,
+                );
 
                 Ok(ToolResult {
                     success: output.status.success(),
@@ -191,7 +213,7 @@ impl Tool for ShellTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!(
-                    "Command timed out after {SHELL_TIMEOUT_SECS}s and was killed"
+                    "Command timed out after {timeout_secs}s and was killed"
                 )),
             }),
         }
@@ -550,18 +572,80 @@ mod tests {
     // ── §5.2 Shell timeout enforcement tests ─────────────────
 
     #[test]
-    fn shell_timeout_constant_is_reasonable() {
-        assert_eq!(SHELL_TIMEOUT_SECS, 60, "shell timeout must be 60 seconds");
+    fn shell_timeout_default_is_reasonable() {
+        assert_eq!(
+            SecurityPolicy::default().shell_timeout_secs,
+            60,
+            "shell timeout must default to 60 seconds"
+        );
     }
 
     #[test]
-    fn shell_output_limit_is_1mb() {
+    fn shell_output_limit_defaults_to_1mb() {
         assert_eq!(
-            MAX_OUTPUT_BYTES, 1_048_576,
-            "max output must be 1 MB to prevent OOM"
+            SecurityPolicy::default().shell_max_output_bytes,
+            1_048_576,
+            "max output must default to 1 MB to prevent OOM"
         );
     }
 
+    #[test]
+    fn output_budget_keeps_room_for_stderr() {
+        let mut stdout = "o".repeat(100);
+        let mut stderr = "e".repeat(100);
+        truncate_to_budget(&mut stdout, &mut stderr, 60);
+        assert!(stdout.starts_with(&"o".repeat(30)) && !stdout.starts_with(&"o".repeat(31)));
+        assert!(stderr.starts_with(&"e".repeat(30)) && !stderr.starts_with(&"e".repeat(31)));
+        assert!(stdout.contains("[output truncated, 70 more bytes]"));
+
+        let mut stdout = "o".repeat(100);
+        let mut stderr = "err".to_string();
+        truncate_to_budget(&mut stdout, &mut stderr, 60);
+        assert_eq!(stderr, "err");
+        assert!(stdout.starts_with(&"o".repeat(57)) && !stdout.starts_with(&"o".repeat(58)));
+
+        let mut stdout = "short".to_string();
+        let mut stderr = String::new();
+        truncate_to_budget(&mut stdout, &mut stderr, 60);
+        assert_eq!(stdout, "short");
+    }
+
+    #[tokio::test]
+    async fn shell_enforces_configured_timeout() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: std::env::temp_dir(),
+            allowed_commands: vec!["sleep".into()],
+            shell_timeout_secs: 1,
+            ..SecurityPolicy::default()
+        });
+        let tool = ShellTool::new(security, test_runtime());
+        let result = tool
+            .execute(json!({"command": "sleep 5"}))
+            .await
+            .expect("timed out command should return a result");
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("timed out after 1s"));
+    }
+
+    #[tokio::test]
+    async fn shell_blocks_forbidden_command_even_with_wildcard() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: std::env::temp_dir(),
+            allowed_commands: vec!["*".into()],
+            forbidden_commands: vec!["curl".into()],
+            ..SecurityPolicy::default()
+        });
+        let tool = ShellTool::new(security, test_runtime());
+        let result = tool
+            .execute(json!({"command": "echo hi && curl example.com"}))
+            .await
+            .expect("forbidden command should return a result");
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("forbidden_commands"));
+    }
+
     // ── §5.3 Non-UTF8 binary output tests ────────────────────
 
     #[test]
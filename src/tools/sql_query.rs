@@ -0,0 +1,752 @@
+//! Read-only database queries.
+//!
+//! The `sql_query` tool runs one statement against a connection named in
+//! `[sql_query.connections]` and returns the rows as a text table. SQLite
+//! files are opened read-only in process; Postgres and MySQL are queried
+//! through the `psql` and `mysql` clients in read-only sessions.
+//!
+//! Statements are screened before they are sent (a single `SELECT`, `WITH`,
+//! `EXPLAIN`, `SHOW`, ... without data-changing keywords), but the read-only
+//! session is the real guarantee; connect with a read-only database user.
+
+use super::traits::{Tool, ToolResult};
+use crate::config::{SqlConnectionConfig, SqlConnectionKind, SqlQueryConfig};
+use crate::security::SecurityPolicy;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Statements a query may start with.
+const READ_KEYWORDS: &[&str] = &[
+    "select", "with", "explain", "show", "describe", "desc", "values", "table", "pragma",
+];
+
+/// Keywords rejected anywhere in a query.
+const WRITE_KEYWORDS: &[&str] = &[
+    "insert", "update", "delete", "merge", "upsert", "drop", "alter", "create", "truncate",
+    "grant", "revoke", "attach", "detach", "into", "copy", "call", "lock", "vacuum", "reindex",
+];
+
+/// Longest cell shown in a result table, in characters.
+const MAX_CELL_CHARS: usize = 80;
+
+/// Column names and rows of a query result, as display strings.
+#[derive(Debug, Default, PartialEq)]
+struct QueryOutput {
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+    truncated: bool,
+}
+
+/// Tool that runs read-only SQL against configured databases.
+pub struct SqlQueryTool {
+    security: Arc<SecurityPolicy>,
+    config: SqlQueryConfig,
+    description: String,
+}
+
+impl SqlQueryTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: SqlQueryConfig) -> Self {
+        let mut names: Vec<&String> = config.connections.keys().collect();
+        names.sort();
+        let mut description = String::from(
+            "Run a read-only SQL query (SELECT, WITH, EXPLAIN, SHOW) against a configured \
+             database and get the rows as a table. Connections:",
+        );
+        for name in names {
+            let connection = &config.connections[name];
+            let _ = write!(description, "\n- {name} ({})", kind_name(connection.kind));
+            if let Some(about) = connection.description.as_deref() {
+                let _ = write!(description, ": {about}");
+            }
+        }
+        Self {
+            security,
+            config,
+            description,
+        }
+    }
+
+    fn connection(&self, name: Option<&str>) -> Result<&SqlConnectionConfig, String> {
+        let available = || {
+            let mut names: Vec<&str> = self.config.connections.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            names.join(", ")
+        };
+        match name {
+            Some(name) => {
+                self.config.connections.get(name).ok_or_else(|| {
+                    format!("Unknown connection '{name}'. Available: {}", available())
+                })
+            }
+            None if self.config.connections.len() == 1 => Ok(self
+                .config
+                .connections
+                .values()
+                .next()
+                .expect("one connection")),
+            None if self.config.connections.is_empty() => {
+                Err("No connections configured in [sql_query.connections]".into())
+            }
+            None => Err(format!(
+                "Missing 'connection' parameter. Available: {}",
+                available()
+            )),
+        }
+    }
+
+    async fn query(&self, connection: &SqlConnectionConfig, sql: &str) -> Result<QueryOutput> {
+        let max_rows = self.config.max_rows.max(1);
+        let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+        match connection.kind {
+            SqlConnectionKind::Sqlite => {
+                let path = self.security.workspace_dir.join(connection.url.trim());
+                query_sqlite(path, sql.to_string(), max_rows, timeout).await
+            }
+            SqlConnectionKind::Postgres => {
+                let stdout = run_client(
+                    postgres_command(connection, sql, timeout),
+                    None,
+                    timeout,
+                    "psql",
+                )
+                .await?;
+                Ok(into_output(parse_csv(&stdout), max_rows))
+            }
+            SqlConnectionKind::Mysql => {
+                let stdout =
+                    run_client(mysql_command(connection)?, Some(sql), timeout, "mysql").await?;
+                Ok(into_output(parse_mysql_batch(&stdout), max_rows))
+            }
+        }
+    }
+}
+
+fn kind_name(kind: SqlConnectionKind) -> &'static str {
+    match kind {
+        SqlConnectionKind::Postgres => "postgres",
+        SqlConnectionKind::Mysql => "mysql",
+        SqlConnectionKind::Sqlite => "sqlite",
+    }
+}
+
+/// Replace string literals, quoted identifiers and comments with spaces so
+/// keyword checks only see SQL structure.
+fn strip_literals(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' | '"' | '`' => {
+                while let Some(next) = chars.next() {
+                    if next == ch {
+                        // A doubled quote is an escaped quote.
+                        if chars.peek() == Some(&ch) {
+                            chars.next();
+                            continue;
+                        }
+                        break;
+                    }
+                }
+                out.push(' ');
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+                out.push('\n');
+            }
+            '#' => {
+                // MySQL line comment.
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+                out.push('\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+                out.push(' ');
+            }
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Check that `sql` is a single read-only statement.
+fn check_read_only(sql: &str) -> Result<(), String> {
+    let structure = strip_literals(sql).to_ascii_lowercase();
+    let structure = structure.trim().trim_end_matches(';').trim_end();
+    if structure.contains(';') {
+        return Err("Only one statement per query is allowed".into());
+    }
+    // psql and mysql run backslash meta-commands such as `\!` (shell).
+    if structure.contains('\\') {
+        return Err("Backslash commands are not allowed outside string literals".into());
+    }
+
+    let words: Vec<&str> = structure
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let Some(first) = words.first() else {
+        return Err("Query is empty".into());
+    };
+    if !READ_KEYWORDS.contains(first) {
+        return Err(format!(
+            "Only read-only queries are allowed ({}); got '{}'",
+            READ_KEYWORDS.join(", ").to_ascii_uppercase(),
+            first.to_ascii_uppercase()
+        ));
+    }
+    if let Some(word) = words.iter().find(|word| WRITE_KEYWORDS.contains(word)) {
+        return Err(format!(
+            "Query contains '{}', which is not allowed in read-only queries",
+            word.to_ascii_uppercase()
+        ));
+    }
+    if words.contains(&"delimiter") {
+        return Err("DELIMITER is not allowed in read-only queries".into());
+    }
+    if *first == "pragma" && structure.contains('=') {
+        return Err("PRAGMA assignments are not allowed".into());
+    }
+    Ok(())
+}
+
+async fn query_sqlite(
+    path: PathBuf,
+    sql: String,
+    max_rows: usize,
+    timeout: Duration,
+) -> Result<QueryOutput> {
+    use rusqlite::{types::ValueRef, Connection, OpenFlags};
+
+    let conn = Connection::open_with_flags(
+        &path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("Failed to open SQLite database {}", path.display()))?;
+    conn.pragma_update(None, "query_only", true)?;
+    let interrupt = conn.get_interrupt_handle();
+
+    let task = tokio::task::spawn_blocking(move || -> Result<QueryOutput> {
+        let mut stmt = conn.prepare(&sql)?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+        let mut output = QueryOutput {
+            columns,
+            ..QueryOutput::default()
+        };
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            if output.rows.len() == max_rows {
+                output.truncated = true;
+                break;
+            }
+            let mut cells = Vec::with_capacity(output.columns.len());
+            for idx in 0..output.columns.len() {
+                cells.push(match row.get_ref(idx)? {
+                    ValueRef::Null => "NULL".to_string(),
+                    ValueRef::Integer(value) => value.to_string(),
+                    ValueRef::Real(value) => value.to_string(),
+                    ValueRef::Text(text) => String::from_utf8_lossy(text).to_string(),
+                    ValueRef::Blob(blob) => format!("<{} bytes>", blob.len()),
+                });
+            }
+            output.rows.push(cells);
+        }
+        Ok(output)
+    });
+
+    match tokio::time::timeout(timeout, task).await {
+        Ok(joined) => joined.context("SQLite query task failed")?,
+        Err(_) => {
+            interrupt.interrupt();
+            bail!("Query timed out after {}s", timeout.as_secs())
+        }
+    }
+}
+
+/// `psql` sends a `--command` string to the server as is, without
+/// interpreting meta-commands.
+fn postgres_command(
+    connection: &SqlConnectionConfig,
+    sql: &str,
+    timeout: Duration,
+) -> tokio::process::Command {
+    let mut cmd = tokio::process::Command::new("psql");
+    cmd.args([
+        "--no-psqlrc",
+        "--csv",
+        "--quiet",
+        "--set",
+        "ON_ERROR_STOP=1",
+    ])
+    .arg("--dbname")
+    .arg(connection.url.trim())
+    .arg("--command")
+    .arg(sql)
+    .env(
+        "PGOPTIONS",
+        format!(
+            "-c default_transaction_read_only=on -c statement_timeout={}",
+            timeout.as_millis()
+        ),
+    )
+    .env("PGCONNECT_TIMEOUT", "10");
+    cmd
+}
+
+fn mysql_command(connection: &SqlConnectionConfig) -> Result<tokio::process::Command> {
+    let url = reqwest::Url::parse(connection.url.trim()).context("Invalid MySQL connection URL")?;
+    let mut cmd = tokio::process::Command::new("mysql");
+    // `--binary-mode` turns off client commands such as `system` in piped input.
+    cmd.args([
+        "--batch",
+        "--binary-mode",
+        "--connect-timeout=10",
+        "--init-command=SET SESSION TRANSACTION READ ONLY",
+    ]);
+    if let Some(host) = url.host_str() {
+        cmd.arg(format!("--host={host}"));
+    }
+    if let Some(port) = url.port() {
+        cmd.arg(format!("--port={port}"));
+    }
+    if !url.username().is_empty() {
+        let user = urlencoding::decode(url.username()).context("Invalid MySQL user")?;
+        cmd.arg(format!("--user={user}"));
+    }
+    let database = url.path().trim_start_matches('/');
+    if !database.is_empty() {
+        cmd.arg(format!("--database={database}"));
+    }
+    // Keep the password off the command line.
+    if let Some(password) = url.password() {
+        let password = urlencoding::decode(password).context("Invalid MySQL password")?;
+        cmd.env("MYSQL_PWD", &*password);
+    }
+    Ok(cmd)
+}
+
+/// Run a database client, writing `stdin` to it when given, and return its
+/// stdout.
+async fn run_client(
+    mut cmd: tokio::process::Command,
+    stdin: Option<&str>,
+    timeout: Duration,
+    name: &str,
+) -> Result<String> {
+    let stdin_mode = if stdin.is_some() {
+        std::process::Stdio::piped()
+    } else {
+        std::process::Stdio::null()
+    };
+    cmd.stdin(stdin_mode)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+
+    let run = async {
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to run {name}; is it installed?"))?;
+        if let (Some(mut pipe), Some(input)) = (child.stdin.take(), stdin) {
+            tokio::io::AsyncWriteExt::write_all(&mut pipe, input.as_bytes()).await?;
+        }
+        child.wait_with_output().await.map_err(anyhow::Error::from)
+    };
+    let output = tokio::time::timeout(timeout, run)
+        .await
+        .with_context(|| format!("Query timed out after {}s", timeout.as_secs()))??;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("{}", stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse `psql --csv` output (RFC 4180 quoting).
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            '\r' if !in_quotes => {}
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Parse `mysql --batch` output: tab-separated with backslash escapes.
+fn parse_mysql_batch(text: &str) -> Vec<Vec<String>> {
+    text.lines()
+        .map(|line| {
+            line.split('\t')
+                .map(|field| {
+                    let mut value = String::with_capacity(field.len());
+                    let mut chars = field.chars();
+                    while let Some(ch) = chars.next() {
+                        if ch != '\\' {
+                            value.push(ch);
+                            continue;
+                        }
+                        match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some('r') => value.push('\r'),
+                            Some('0') => value.push('\0'),
+                            Some(other) => value.push(other),
+                            None => value.push('\\'),
+                        }
+                    }
+                    value
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Split client output into header and at most `max_rows` rows.
+fn into_output(mut records: Vec<Vec<String>>, max_rows: usize) -> QueryOutput {
+    if records.is_empty() {
+        return QueryOutput::default();
+    }
+    let columns = records.remove(0);
+    let truncated = records.len() > max_rows;
+    records.truncate(max_rows);
+    QueryOutput {
+        columns,
+        rows: records,
+        truncated,
+    }
+}
+
+fn display_cell(value: &str) -> String {
+    let flat = value.replace(['\n', '\r', '\t'], " ");
+    match flat.char_indices().nth(MAX_CELL_CHARS) {
+        Some((cut, _)) => format!("{}…", &flat[..cut]),
+        None => flat,
+    }
+}
+
+/// Render a result as an aligned text table with a row count.
+fn format_table(output: &QueryOutput) -> String {
+    if output.columns.is_empty() {
+        return "Query returned no result set.".into();
+    }
+    let header: Vec<String> = output.columns.iter().map(|c| display_cell(c)).collect();
+    let rows: Vec<Vec<String>> = output
+        .rows
+        .iter()
+        .map(|row| row.iter().map(|cell| display_cell(cell)).collect())
+        .collect();
+    let mut widths: Vec<usize> = header.iter().map(|cell| cell.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let render = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell}{}", " ".repeat(width - cell.chars().count())))
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    let mut table = render(&header);
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    let _ = write!(table, "\n|-{}-|", separator.join("-|-"));
+    for row in &rows {
+        table.push('\n');
+        table.push_str(&render(row));
+    }
+    let count = rows.len();
+    if output.truncated {
+        let _ = write!(
+            table,
+            "\n(first {count} rows shown; add a LIMIT or aggregate to narrow the result)"
+        );
+    } else {
+        let _ = write!(
+            table,
+            "\n({count} row{})",
+            if count == 1 { "" } else { "s" }
+        );
+    }
+    table
+}
+
+#[async_trait]
+impl Tool for SqlQueryTool {
+    fn name(&self) -> &str {
+        "sql_query"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let mut names: Vec<&String> = self.config.connections.keys().collect();
+        names.sort();
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "connection": {
+                    "type": "string",
+                    "description": "Configured connection to query (optional when only one exists)"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "A single read-only SQL statement"
+                }
+            },
+            "required": ["query"]
+        });
+        if !names.is_empty() {
+            schema["properties"]["connection"]["enum"] = json!(names);
+        }
+        schema
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|query| !query.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+
+        let connection = match self.connection(args.get("connection").and_then(Value::as_str)) {
+            Ok(connection) => connection,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                })
+            }
+        };
+        if let Err(error) = check_read_only(query) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(error),
+            });
+        }
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        match self.query(connection, query).await {
+            Ok(output) => Ok(ToolResult {
+                success: true,
+                output: format_table(&output),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Query failed: {e:#}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn sqlite_tool(tmp: &TempDir, max_rows: usize) -> SqlQueryTool {
+        let db = tmp.path().join("app.db");
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE signups (id INTEGER, email TEXT, day TEXT);
+             INSERT INTO signups VALUES (1, 'a@example.com', '2026-10-15'),
+                                        (2, 'b@example.com', '2026-10-15'),
+                                        (3, NULL, '2026-10-14');",
+        )
+        .unwrap();
+        let config = SqlQueryConfig {
+            enabled: true,
+            connections: HashMap::from([(
+                "app".to_string(),
+                SqlConnectionConfig {
+                    kind: SqlConnectionKind::Sqlite,
+                    url: "app.db".into(),
+                    description: Some("Signups".into()),
+                },
+            )]),
+            max_rows,
+            ..SqlQueryConfig::default()
+        };
+        let security = Arc::new(SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        SqlQueryTool::new(security, config)
+    }
+
+    #[test]
+    fn read_only_check_accepts_queries_and_rejects_writes() {
+        assert!(check_read_only("SELECT count(*) FROM signups;").is_ok());
+        assert!(check_read_only("-- daily\nWITH d AS (SELECT 1) SELECT * FROM d").is_ok());
+        assert!(check_read_only("SELECT 'drop table; insert' AS note").is_ok());
+        assert!(check_read_only("select last_update from t").is_ok());
+
+        assert!(check_read_only("DELETE FROM signups").is_err());
+        assert!(check_read_only("SELECT 1; DROP TABLE signups").is_err());
+        assert!(check_read_only("SELECT * INTO backup FROM signups").is_err());
+        assert!(check_read_only("WITH x AS (DELETE FROM t RETURNING *) SELECT * FROM x").is_err());
+        assert!(check_read_only("PRAGMA query_only = 0").is_err());
+        assert!(check_read_only("  -- nothing").is_err());
+    }
+
+    #[test]
+    fn read_only_check_rejects_client_meta_commands() {
+        assert!(check_read_only("select 1 \\! rm -rf ~")
+            .unwrap_err()
+            .contains("Backslash"));
+        assert!(check_read_only("SELECT 1\n\\! id").is_err());
+        assert!(check_read_only("SELECT 1 \\g").is_err());
+        assert!(check_read_only("SELECT 'a\\' \\! id --'").is_err());
+        assert!(check_read_only("SELECT 1\ndelimiter //").is_err());
+        assert!(check_read_only("SELECT 'C:\\temp' AS path").is_ok());
+    }
+
+    #[test]
+    fn parses_client_output_formats() {
+        assert_eq!(
+            parse_csv("id,note\n1,\"a, \"\"quoted\"\"\nline\"\n2,\n"),
+            vec![
+                vec!["id".to_string(), "note".to_string()],
+                vec!["1".to_string(), "a, \"quoted\"\nline".to_string()],
+                vec!["2".to_string(), String::new()],
+            ]
+        );
+        assert_eq!(
+            parse_mysql_batch("id\tnote\n1\ttab\\there\n2\tNULL\n"),
+            vec![
+                vec!["id".to_string(), "note".to_string()],
+                vec!["1".to_string(), "tab\there".to_string()],
+                vec!["2".to_string(), "NULL".to_string()],
+            ]
+        );
+        let output = into_output(parse_mysql_batch("n\n1\n2\n3\n"), 2);
+        assert_eq!(output.rows.len(), 2);
+        assert!(output.truncated);
+    }
+
+    #[test]
+    fn formats_aligned_table() {
+        let table = format_table(&QueryOutput {
+            columns: vec!["day".into(), "signups".into()],
+            rows: vec![vec!["2026-10-15".into(), "2".into()]],
+            truncated: false,
+        });
+        assert_eq!(
+            table,
+            "| day        | signups |\n|------------|---------|\n| 2026-10-15 | 2       |\n(1 row)"
+        );
+    }
+
+    #[tokio::test]
+    async fn queries_sqlite_read_only_with_row_limit() {
+        let tmp = TempDir::new().unwrap();
+        let tool = sqlite_tool(&tmp, 2);
+        assert!(tool.description().contains("- app (sqlite): Signups"));
+
+        let result = tool
+            .execute(json!({"query": "SELECT id, email FROM signups ORDER BY id"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("| 1  | a@example.com |"));
+        assert!(result.output.contains("first 2 rows shown"));
+
+        let result = tool
+            .execute(
+                json!({"connection": "app", "query": "SELECT email FROM signups WHERE id = 3"}),
+            )
+            .await
+            .unwrap();
+        assert!(result.output.contains("| NULL  |"));
+
+        let result = tool
+            .execute(json!({"query": "DELETE FROM signups"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+
+        let result = tool
+            .execute(json!({"connection": "billing", "query": "SELECT 1"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Available: app"));
+    }
+
+    #[tokio::test]
+    async fn blocked_in_read_only_autonomy() {
+        let tmp = TempDir::new().unwrap();
+        let mut tool = sqlite_tool(&tmp, 10);
+        tool.security = Arc::new(SecurityPolicy {
+            autonomy: crate::security::AutonomyLevel::ReadOnly,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let result = tool.execute(json!({"query": "SELECT 1"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
@@ -0,0 +1,361 @@
+use super::traits::{Tool, ToolResult};
+use crate::channels::audio_tools::run_local_tool;
+use crate::channels::transcription::transcribe_audio;
+use crate::config::TranscriptionConfig;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Largest media file accepted (2 GB), enough for an hour of video.
+const MAX_MEDIA_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+/// Longest stretch of a recording that is transcribed (4 hours).
+const MAX_MEDIA_SECS: u64 = 4 * 60 * 60;
+/// Length of each chunk sent to the backend. Five minutes of 16 kHz mono
+/// PCM is about 9.6 MB, well under the 25 MB transcription upload limit.
+const CHUNK_SECS: u64 = 5 * 60;
+const WAV_BYTES_PER_SEC: u64 = 16_000 * 2;
+const WAV_HEADER_BYTES: u64 = 44;
+/// Default character limit returned to the LLM.
+const DEFAULT_MAX_CHARS: usize = 100_000;
+/// Hard ceiling regardless of what the caller requests.
+const MAX_OUTPUT_CHARS: usize = 400_000;
+
+/// Temporary directory holding the audio chunks, removed when dropped.
+struct ChunkDir {
+    path: PathBuf,
+}
+
+impl ChunkDir {
+    async fn create() -> anyhow::Result<Self> {
+        let path = std::env::temp_dir().join(format!("zeroclaw-media-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir(&path).await?;
+        Ok(Self { path })
+    }
+
+    /// Chunk files in playback order.
+    async fn chunks(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut chunks = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            chunks.push(entry.path());
+        }
+        chunks.sort();
+        Ok(chunks)
+    }
+}
+
+impl Drop for ChunkDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Playback length of a 16 kHz mono 16-bit WAV chunk of `len` bytes.
+fn wav_duration_secs(len: u64) -> u64 {
+    len.saturating_sub(WAV_HEADER_BYTES) / WAV_BYTES_PER_SEC
+}
+
+/// `HH:MM:SS` offset into the recording.
+fn format_offset(secs: u64) -> String {
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Tool for transcribing audio and video files on request.
+///
+/// Unlike the automatic voice-note pipeline, this handles long recordings:
+/// ffmpeg extracts the audio track as 16 kHz mono WAV in fixed-length
+/// chunks, and each chunk goes through the `[transcription]` backend.
+pub struct TranscribeMediaTool {
+    security: Arc<SecurityPolicy>,
+    config: TranscriptionConfig,
+}
+
+impl TranscribeMediaTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: TranscriptionConfig) -> Self {
+        Self { security, config }
+    }
+
+    /// Accept a bare path or an attachment marker such as
+    /// `[AUDIO:/…/signal_files/meeting.m4a]`. Paths inside the workspace are
+    /// made relative so `workspace_only` policies accept them.
+    fn normalize_path(&self, raw: &str) -> String {
+        let raw = raw.trim();
+        let path = raw
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(kind, _)| !kind.is_empty() && kind.chars().all(|c| c.is_ascii_uppercase()))
+            .map_or(raw, |(_, target)| {
+                target.split('|').next().unwrap_or(target).trim()
+            });
+        Path::new(path)
+            .strip_prefix(&self.security.workspace_dir)
+            .map_or_else(
+                |_| path.to_string(),
+                |relative| relative.display().to_string(),
+            )
+    }
+
+    async fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        if path.starts_with("http://") || path.starts_with("https://") {
+            return Err("Only files in the workspace can be transcribed, not URLs".into());
+        }
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        // Record action before canonicalization so path-probing still consumes budget.
+        if !self.security.record_action() {
+            return Err("Rate limit exceeded: action budget exhausted".into());
+        }
+        let resolved = tokio::fs::canonicalize(self.security.workspace_dir.join(path))
+            .await
+            .map_err(|e| format!("Failed to resolve file path: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(self.security.resolved_path_violation_message(&resolved));
+        }
+        let meta = tokio::fs::metadata(&resolved)
+            .await
+            .map_err(|e| format!("Failed to read file metadata: {e}"))?;
+        if !meta.is_file() {
+            return Err(format!("Not a file: {path}"));
+        }
+        if meta.len() > MAX_MEDIA_BYTES {
+            return Err(format!(
+                "Media file too large: {} bytes (limit: {MAX_MEDIA_BYTES} bytes)",
+                meta.len()
+            ));
+        }
+        Ok(resolved)
+    }
+
+    /// Extract the audio track of `input` into [`CHUNK_SECS`] WAV chunks.
+    async fn split_audio(input: &Path, dir: &ChunkDir) -> anyhow::Result<Vec<PathBuf>> {
+        let mut command = tokio::process::Command::new("ffmpeg");
+        command
+            .args(["-nostdin", "-loglevel", "error", "-y", "-i"])
+            .arg(input)
+            .args(["-t", &MAX_MEDIA_SECS.to_string()])
+            .args(["-vn", "-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+            .args(["-f", "segment", "-segment_time", &CHUNK_SECS.to_string()])
+            .arg(dir.path.join("chunk_%04d.wav"));
+        run_local_tool(command, "ffmpeg").await?;
+        dir.chunks().await
+    }
+
+    /// Transcribe every chunk, each prefixed with its offset. Chunks that
+    /// fail are noted inline; the run fails only when none succeed.
+    async fn transcribe_chunks(&self, chunks: &[PathBuf]) -> anyhow::Result<String> {
+        let mut transcript = String::new();
+        let mut first_error = None;
+        let mut transcribed = 0;
+        for (index, chunk) in chunks.iter().enumerate() {
+            let offset = format_offset(index as u64 * CHUNK_SECS);
+            let result = match tokio::fs::read(chunk).await {
+                Ok(audio) => transcribe_audio(audio, "chunk.wav", &self.config).await,
+                Err(e) => Err(e.into()),
+            };
+            match result {
+                Ok(text) => {
+                    transcribed += 1;
+                    let text = text.trim();
+                    if !text.is_empty() {
+                        let _ = write!(transcript, "[{offset}] {text}\n\n");
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("transcribe_media: chunk at {offset} failed: {e}");
+                    let _ = write!(transcript, "[{offset}] (transcription failed: {e})\n\n");
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if transcribed == 0 => Err(e),
+            _ => Ok(transcript.trim_end().to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for TranscribeMediaTool {
+    fn name(&self) -> &str {
+        "transcribe_media"
+    }
+
+    fn description(&self) -> &str {
+        "Transcribe the speech in an audio or video file in the workspace, such as an \
+         [AUDIO:path], [VOICE:path] or [VIDEO:path] attachment from a chat. Handles long \
+         recordings; the transcript is marked with [HH:MM:SS] offsets."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Media path or the attachment marker itself ([AUDIO:/path/meeting.m4a]). Relative paths resolve from workspace."
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "description": "Maximum characters to return (default: 100000, max: 400000)",
+                    "minimum": 1,
+                    "maximum": 400_000
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let raw_path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+
+        let max_chars = args
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .map(|n| {
+                usize::try_from(n)
+                    .unwrap_or(MAX_OUTPUT_CHARS)
+                    .min(MAX_OUTPUT_CHARS)
+            })
+            .unwrap_or(DEFAULT_MAX_CHARS);
+
+        if self.security.is_rate_limited() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+            });
+        }
+
+        let path = self.normalize_path(raw_path);
+        let resolved = match self.resolve(&path).await {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                })
+            }
+        };
+
+        let result = async {
+            let dir = ChunkDir::create().await?;
+            let chunks = Self::split_audio(&resolved, &dir).await?;
+            let mut duration_secs = 0;
+            for chunk in &chunks {
+                duration_secs += wav_duration_secs(tokio::fs::metadata(chunk).await?.len());
+            }
+            let transcript = self.transcribe_chunks(&chunks).await?;
+            anyhow::Ok((duration_secs, transcript))
+        }
+        .await;
+        let (duration_secs, transcript) = match result {
+            Ok(transcribed) => transcribed,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Transcription failed: {e}")),
+                })
+            }
+        };
+
+        let file_name = resolved
+            .file_name()
+            .map_or_else(|| path.clone(), |name| name.to_string_lossy().to_string());
+        if transcript.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("{file_name} contains no recognizable speech"),
+                error: None,
+            });
+        }
+
+        let mut output = format!(
+            "{file_name} ({}, {} characters)\n\n",
+            format_offset(duration_secs),
+            transcript.chars().count()
+        );
+        if transcript.chars().count() > max_chars {
+            output.extend(transcript.chars().take(max_chars));
+            let _ = write!(output, "\n\n... [truncated at {max_chars} chars]");
+        } else {
+            output.push_str(&transcript);
+        }
+        if duration_secs >= MAX_MEDIA_SECS {
+            let _ = write!(
+                output,
+                "\n\n(Only the first {} hours were transcribed.)",
+                MAX_MEDIA_SECS / 3600
+            );
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn test_tool(workspace: PathBuf) -> TranscribeMediaTool {
+        TranscribeMediaTool::new(
+            Arc::new(SecurityPolicy {
+                autonomy: AutonomyLevel::Supervised,
+                workspace_dir: workspace,
+                ..SecurityPolicy::default()
+            }),
+            TranscriptionConfig::default(),
+        )
+    }
+
+    #[test]
+    fn format_offset_and_wav_duration() {
+        assert_eq!(format_offset(0), "00:00:00");
+        assert_eq!(format_offset(300), "00:05:00");
+        assert_eq!(format_offset(3 * 3600 + 65), "03:01:05");
+        assert_eq!(wav_duration_secs(44 + 32_000 * 90), 90);
+    }
+
+    #[test]
+    fn normalize_path_accepts_markers_inside_workspace() {
+        let tool = test_tool(PathBuf::from("/srv/workspace"));
+        assert_eq!(
+            tool.normalize_path("[AUDIO:/srv/workspace/signal_files/meeting.m4a]"),
+            "signal_files/meeting.m4a"
+        );
+        assert_eq!(tool.normalize_path(" [VIDEO:talk.mp4] "), "talk.mp4");
+        assert_eq!(tool.normalize_path("notes/call.ogg"), "notes/call.ogg");
+    }
+
+    #[tokio::test]
+    async fn transcribe_media_rejects_urls_and_missing_files() {
+        let workspace = TempDir::new().unwrap();
+        let tool = test_tool(workspace.path().to_path_buf());
+
+        let result = tool
+            .execute(json!({"path": "[AUDIO:https://example.com/a.mp3]"}))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("not URLs"));
+
+        let result = tool.execute(json!({"path": "missing.mp3"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Failed to resolve"));
+
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
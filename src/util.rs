@@ -43,6 +43,120 @@ pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
     }
 }
 
+/// Contents of each element named `name` in `xml`, whatever its namespace
+/// prefix. Elements with the same name must not nest.
+pub fn xml_elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    xml_tagged_elements(xml, name)
+        .into_iter()
+        .map(|(_, contents)| contents)
+        .collect()
+}
+
+/// Like [`xml_elements`], pairing each element's contents with the
+/// attribute list of its start tag (see [`xml_attribute`]).
+pub fn xml_tagged_elements<'a>(xml: &'a str, name: &str) -> Vec<(&'a str, &'a str)> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        let after = &rest[open + 1..];
+        let Some(tag_end) = after.find('>') else {
+            break;
+        };
+        let tag = &after[..tag_end];
+        rest = &after[tag_end + 1..];
+        let tag_name = tag
+            .split(|ch: char| ch.is_whitespace() || ch == '/')
+            .next()
+            .unwrap_or_default();
+        if tag_name.rsplit(':').next() != Some(name) {
+            continue;
+        }
+        let attributes = tag[tag_name.len()..].trim_end_matches('/');
+        if tag.ends_with('/') {
+            found.push((attributes, ""));
+            continue;
+        }
+        let close = format!("</{tag_name}>");
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        found.push((attributes, &rest[..end]));
+        rest = &rest[end + close.len()..];
+    }
+    found
+}
+
+/// Raw value of attribute `name` (with its prefix, e.g. `r:id`) in a start
+/// tag's attribute list. Entities are not decoded.
+pub fn xml_attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes;
+    while let Some(at) = rest.find(name) {
+        let before = rest[..at].chars().last();
+        let after = rest[at + name.len()..].trim_start();
+        rest = &rest[at + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=').map(str::trim_start) else {
+            continue;
+        };
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            continue;
+        }
+        let value = &value[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+/// Decode XML character data (entities or a CDATA section).
+pub fn xml_text(raw: &str) -> String {
+    let raw = raw.trim();
+    if let Some(inner) = raw
+        .strip_prefix("<![CDATA[")
+        .and_then(|rest| rest.strip_suffix("]]>"))
+    {
+        return inner.to_string();
+    }
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').and_then(|semi| {
+            let ch = match &after[..semi] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                entity => {
+                    let code = entity.strip_prefix('#')?;
+                    let code = match code.strip_prefix('x') {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => code.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((ch, semi))
+        });
+        match decoded {
+            Some((ch, semi)) => {
+                out.push(ch);
+                rest = &after[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 /// Utility enum for handling optional values.
 pub enum MaybeSet<T> {
     Set(T),
@@ -142,4 +256,16 @@ mod tests {
         // Edge case: max_chars = 0
         assert_eq!(truncate_with_ellipsis("hello", 0), "...");
     }
+
+    #[test]
+    fn test_xml_tagged_elements_and_attributes() {
+        let xml = r#"<sheets><sheet name="Q1 &amp; Q2" r:id='rId1'/><x:sheet id="2">body</x:sheet></sheets>"#;
+        let sheets = xml_tagged_elements(xml, "sheet");
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(xml_attribute(sheets[0].0, "name"), Some("Q1 &amp; Q2"));
+        assert_eq!(xml_attribute(sheets[0].0, "r:id"), Some("rId1"));
+        assert_eq!(xml_attribute(sheets[0].0, "id"), None);
+        assert_eq!(sheets[1], (r#" id="2""#, "body"));
+        assert_eq!(xml_elements(xml, "sheet"), vec!["", "body"]);
+    }
 }
@@ -8,7 +8,7 @@
 //! `send_document_by_url()` immediately via `?`, causing the entire reply
 //! (including already-sent text) to fail with no fallback.
 
-use wiremock::matchers::{method, path_regex};
+use wiremock::matchers::{body_string_contains, method, path_regex};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 use zeroclaw::channels::telegram::TelegramChannel;
 use zeroclaw::channels::traits::{Channel, SendMessage};
@@ -103,7 +103,7 @@ async fn photo_url_failure_falls_back_to_text_link() {
 }
 
 /// Text portion of a message with attachments is still delivered even when
-/// the attachment fails.
+/// the attachment it captions fails.
 #[tokio::test]
 async fn text_portion_delivered_before_attachment_failure() {
     let server = MockServer::start().await;
@@ -120,11 +120,11 @@ async fn text_portion_delivered_before_attachment_failure() {
         .mount(&server)
         .await;
 
-    // sendMessage should be called at least twice:
-    // 1. for the text portion ("Here is the file")
-    // 2. for the fallback text link
+    // The text portion ("Here is the file") was the document's caption, so
+    // the fallback text link carries it.
     Mock::given(method("POST"))
         .and(path_regex(r"/botTEST_TOKEN/sendMessage$"))
+        .and(body_string_contains("Here is the file"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
             "ok": true,
             "result": {
@@ -133,7 +133,7 @@ async fn text_portion_delivered_before_attachment_failure() {
                 "text": "ok"
             }
         })))
-        .expect(2)
+        .expect(1)
         .mount(&server)
         .await;
 
@@ -177,7 +177,7 @@ async fn multiple_attachments_independent_fallback() {
         .mount(&server)
         .await;
 
-    // sendMessage succeeds (text + 2 fallback links)
+    // sendMessage succeeds (2 fallback links, the first with the text)
     Mock::given(method("POST"))
         .and(path_regex(r"/botTEST_TOKEN/sendMessage$"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
@@ -188,7 +188,7 @@ async fn multiple_attachments_independent_fallback() {
                 "text": "ok"
             }
         })))
-        .expect(3) // text + doc fallback + image fallback
+        .expect(2) // doc fallback with its caption + image fallback
         .mount(&server)
         .await;
 
@@ -205,7 +205,8 @@ async fn multiple_attachments_independent_fallback() {
     );
 }
 
-/// When attachment succeeds, no fallback text is sent.
+/// When attachment succeeds, no fallback text is sent and the text portion
+/// goes out as its caption.
 #[tokio::test]
 async fn successful_attachment_no_fallback() {
     let server = MockServer::start().await;
@@ -213,6 +214,7 @@ async fn successful_attachment_no_fallback() {
     // sendDocument succeeds
     Mock::given(method("POST"))
         .and(path_regex(r"/botTEST_TOKEN/sendDocument$"))
+        .and(body_string_contains("Report attached"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
             "ok": true,
             "result": {
@@ -225,8 +227,8 @@ async fn successful_attachment_no_fallback() {
         .mount(&server)
         .await;
 
-    // sendMessage should only be called once (for the text portion),
-    // NOT a second time for a fallback
+    // sendMessage should not be called at all: the text portion is the
+    // caption and there is no fallback
     Mock::given(method("POST"))
         .and(path_regex(r"/botTEST_TOKEN/sendMessage$"))
         .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
@@ -237,7 +239,7 @@ async fn successful_attachment_no_fallback() {
                 "text": "ok"
             }
         })))
-        .expect(1) // only the text portion, no fallback
+        .expect(0)
         .mount(&server)
         .await;
 